@@ -10,9 +10,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     // Compile the proto files
     // Generated code goes to $OUT_DIR which is then included via include_proto!
+    // The descriptor set is served by gRPC reflection (see proto/mod.rs)
+    let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR")?);
     tonic_build::configure()
         .build_server(true)
         .build_client(true)
+        .file_descriptor_set_path(out_dir.join("titan_sync_descriptor.bin"))
         .compile_protos(
             &["../../proto/titan_sync.proto"],
             &["../../proto"],
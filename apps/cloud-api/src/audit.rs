@@ -0,0 +1,328 @@
+//! Audit logging middleware.
+//!
+//! Every mutating RPC is recorded in the append-only `audit_log` table:
+//!
+//! ```text
+//! request ──► AuditMiddleware ──► service handler
+//!               │  who: bearer token (store / user / anonymous)
+//!               │  method: gRPC path
+//!               │                     │
+//!               │ ◄───────────────────┘ response
+//!               │  result: grpc-status header (absent = OK)
+//!               │  entities: AuditContext in the response extensions
+//!               ▼
+//!   tokio::spawn(insert audit_log row)   ← never delays the response
+//! ```
+//!
+//! The middleware cannot see message bodies, so handlers name the entities
+//! they touched by attaching an [`AuditContext`] to their response. Calls
+//! that fail are recorded without entities.
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use tonic::codegen::http::{HeaderMap, Request, Response};
+use tonic::codegen::{BoxFuture, Service};
+use tower::Layer;
+use tracing::warn;
+
+use crate::auth::{extract_bearer_token, JwtManager};
+use crate::db::Database;
+
+/// gRPC methods that change state and are therefore audited.
+///
+/// Acknowledgements and cursor reports are sync bookkeeping and are left
+/// out; sale uploads are recorded once per batch (or stream), not per sale.
+pub const AUDITED_METHODS: &[&str] = &[
+    "/titan.sync.v1.SyncService/UploadBatch",
+    "/titan.sync.v1.SyncService/StreamUpload",
+    "/titan.sync.v1.SyncService/RedeemLoyaltyReward",
+    "/titan.sync.v1.ConfigService/UpdateConfigValue",
+    "/titan.sync.v1.ConfigService/SetFeatureFlag",
+    "/titan.sync.v1.ConfigService/UpsertCoupon",
+    "/titan.sync.v1.ImportService/UploadCatalog",
+    "/titan.sync.v1.ImportService/PublishCatalogImport",
+    "/titan.sync.v1.UserService/InviteUser",
+    "/titan.sync.v1.UserService/AcceptInvite",
+    "/titan.sync.v1.UserService/DisableUser",
+    "/titan.sync.v1.AuthService/RevokeToken",
+];
+
+/// What a handler changed, attached to its response for the audit log.
+#[derive(Debug, Clone, Default)]
+pub struct AuditContext {
+    /// Tenant, when the caller's token does not carry one (e.g. AcceptInvite).
+    pub tenant_id: Option<String>,
+    /// Store the call acted on, when it is not the calling store.
+    pub store_id: Option<String>,
+    /// Entities touched, as `kind:id` (e.g. `user:…`, `config:currency`).
+    pub entity_ids: Vec<String>,
+}
+
+impl AuditContext {
+    /// Context naming the touched entities.
+    pub fn entities<I, S>(entity_ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        AuditContext {
+            entity_ids: entity_ids.into_iter().map(Into::into).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Attach this context to a response.
+    pub fn attach<T>(self, response: &mut tonic::Response<T>) {
+        response.extensions_mut().insert(self);
+    }
+}
+
+/// One audit log row.
+#[derive(Debug, Clone)]
+pub struct AuditEntry {
+    /// "STORE", "USER" or "ANONYMOUS".
+    pub actor_type: String,
+    pub actor_id: Option<String>,
+    pub tenant_id: Option<String>,
+    pub store_id: Option<String>,
+    pub method: String,
+    pub entity_ids: Vec<String>,
+    /// gRPC status code (0 = OK).
+    pub status_code: i32,
+    pub status_message: Option<String>,
+    pub duration_ms: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Caller identity taken from the bearer token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Actor {
+    actor_type: &'static str,
+    actor_id: Option<String>,
+    tenant_id: Option<String>,
+    store_id: Option<String>,
+}
+
+/// Identify the caller without a database lookup; an invalid or missing
+/// token is recorded as anonymous (the handler rejects it anyway).
+fn actor_from_headers(jwt_manager: &JwtManager, headers: &HeaderMap) -> Actor {
+    let claims = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(extract_bearer_token)
+        .and_then(|token| jwt_manager.validate_token(token).ok());
+
+    match claims {
+        Some(c) if c.token_type == "access" => Actor {
+            actor_type: "STORE",
+            actor_id: Some(c.sub.clone()),
+            tenant_id: Some(c.tenant_id),
+            store_id: Some(c.sub),
+        },
+        Some(c) if c.token_type == "user_access" => Actor {
+            actor_type: "USER",
+            actor_id: Some(c.sub),
+            tenant_id: Some(c.tenant_id),
+            store_id: None,
+        },
+        _ => Actor {
+            actor_type: "ANONYMOUS",
+            actor_id: None,
+            tenant_id: None,
+            store_id: None,
+        },
+    }
+}
+
+/// Result of the call as seen in the response headers. Errors returned by
+/// a handler are sent trailers-only, so `grpc-status` is in the headers;
+/// its absence means the call succeeded.
+fn status_from_headers(headers: &HeaderMap) -> (i32, Option<String>) {
+    let code = headers
+        .get("grpc-status")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    let message = headers
+        .get("grpc-message")
+        .and_then(|v| v.to_str().ok())
+        .map(|m| m.to_string());
+
+    (code, message)
+}
+
+/// Tower layer that wraps the gRPC router with [`AuditMiddleware`].
+#[derive(Clone)]
+pub struct AuditLayer {
+    db: Database,
+    jwt_manager: Arc<JwtManager>,
+}
+
+impl AuditLayer {
+    /// Create the layer.
+    pub fn new(db: Database, jwt_manager: JwtManager) -> Self {
+        AuditLayer {
+            db,
+            jwt_manager: Arc::new(jwt_manager),
+        }
+    }
+}
+
+impl<S> Layer<S> for AuditLayer {
+    type Service = AuditMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuditMiddleware {
+            inner,
+            db: self.db.clone(),
+            jwt_manager: self.jwt_manager.clone(),
+        }
+    }
+}
+
+/// Records audited calls after the inner service responds.
+#[derive(Clone)]
+pub struct AuditMiddleware<S> {
+    inner: S,
+    db: Database,
+    jwt_manager: Arc<JwtManager>,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for AuditMiddleware<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        // The clone is not ready; keep the one that was polled
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        let method = request.uri().path().to_string();
+        if !AUDITED_METHODS.contains(&method.as_str()) {
+            return Box::pin(inner.call(request));
+        }
+
+        let actor = actor_from_headers(&self.jwt_manager, request.headers());
+        let db = self.db.clone();
+
+        Box::pin(async move {
+            let started = Instant::now();
+            let response = inner.call(request).await?;
+
+            let context = response
+                .extensions()
+                .get::<AuditContext>()
+                .cloned()
+                .unwrap_or_default();
+            let (status_code, status_message) = status_from_headers(response.headers());
+
+            let entry = AuditEntry {
+                actor_type: actor.actor_type.to_string(),
+                actor_id: actor.actor_id,
+                tenant_id: actor.tenant_id.or(context.tenant_id),
+                store_id: context.store_id.or(actor.store_id),
+                method,
+                entity_ids: context.entity_ids,
+                status_code,
+                status_message,
+                duration_ms: started.elapsed().as_millis().min(i32::MAX as u128) as i32,
+                created_at: Utc::now(),
+            };
+
+            tokio::spawn(async move {
+                if let Err(e) = db.insert_audit_entry(&entry).await {
+                    warn!(error = %e, method = %entry.method, "Failed to write audit log entry");
+                }
+            });
+
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rbac::Role;
+
+    fn jwt() -> JwtManager {
+        JwtManager::new("test-secret".to_string(), 3600, 86400)
+    }
+
+    fn bearer(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "authorization",
+            format!("Bearer {}", token).parse().unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_actor_from_store_token() {
+        let jwt = jwt();
+        let token = jwt
+            .generate_access_token("store-001", "tenant-001", "device-001", "session-001")
+            .unwrap();
+
+        let actor = actor_from_headers(&jwt, &bearer(&token));
+        assert_eq!(actor.actor_type, "STORE");
+        assert_eq!(actor.store_id.as_deref(), Some("store-001"));
+        assert_eq!(actor.tenant_id.as_deref(), Some("tenant-001"));
+    }
+
+    #[test]
+    fn test_actor_from_user_token() {
+        let jwt = jwt();
+        let token = jwt
+            .generate_user_access_token("user-001", "tenant-001", Role::Owner, "session-001")
+            .unwrap();
+
+        let actor = actor_from_headers(&jwt, &bearer(&token));
+        assert_eq!(actor.actor_type, "USER");
+        assert_eq!(actor.actor_id.as_deref(), Some("user-001"));
+        assert_eq!(actor.store_id, None);
+    }
+
+    #[test]
+    fn test_invalid_token_is_anonymous() {
+        let actor = actor_from_headers(&jwt(), &bearer("not-a-jwt"));
+        assert_eq!(actor.actor_type, "ANONYMOUS");
+        assert_eq!(actor.actor_id, None);
+
+        let actor = actor_from_headers(&jwt(), &HeaderMap::new());
+        assert_eq!(actor.actor_type, "ANONYMOUS");
+    }
+
+    #[test]
+    fn test_status_from_headers() {
+        assert_eq!(status_from_headers(&HeaderMap::new()), (0, None));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("grpc-status", "7".parse().unwrap());
+        headers.insert(
+            "grpc-message",
+            "Role ANALYST cannot import the catalog".parse().unwrap(),
+        );
+        assert_eq!(
+            status_from_headers(&headers),
+            (
+                7,
+                Some("Role ANALYST cannot import the catalog".to_string())
+            )
+        );
+    }
+}
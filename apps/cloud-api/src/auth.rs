@@ -1,6 +1,14 @@
 //! JWT authentication module.
 //!
 //! Handles JWT token generation, validation, and refresh.
+//!
+//! ## Sessions
+//! Every token carries the ID of the session it was issued in (`sid`): one
+//! per API key exchange or login. Refresh tokens are single-use; the
+//! service records each one (`Database::rotate_refresh_token`) and revokes
+//! the whole session when one is presented twice. Access tokens are not
+//! checked against the session and stay valid until they expire, so their
+//! lifetime bounds how long a revoked session keeps working.
 
 use chrono::{Duration, Utc};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, TokenData, Validation};
@@ -8,6 +16,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::error::CloudError;
+use crate::rbac::Role;
 
 /// JWT claims structure.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,8 +39,18 @@ pub struct Claims {
     /// JWT ID (unique identifier for this token)
     pub jti: String,
     
-    /// Token type ("access" or "refresh")
+    /// Token type ("access" or "refresh"; "user_access" or "user_refresh"
+    /// for tenant back-office users)
     pub token_type: String,
+
+    /// Back-office role at the time of issue (user tokens only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+
+    /// Session the token belongs to (absent on tokens issued before
+    /// refresh rotation)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
 }
 
 /// JWT token manager.
@@ -57,54 +76,117 @@ impl JwtManager {
         store_id: &str,
         tenant_id: &str,
         device_id: &str,
+        session_id: &str,
     ) -> Result<String, CloudError> {
-        let now = Utc::now();
-        let exp = now + Duration::seconds(self.access_lifetime_secs);
-
-        let claims = Claims {
-            sub: store_id.to_string(),
-            tenant_id: tenant_id.to_string(),
-            device_id: device_id.to_string(),
-            iat: now.timestamp(),
-            exp: exp.timestamp(),
-            jti: Uuid::new_v4().to_string(),
-            token_type: "access".to_string(),
-        };
-
-        encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(self.secret.as_bytes()),
-        )
-        .map_err(|e| CloudError::Internal(format!("Failed to generate token: {}", e)))
+        let issued = self.issue(
+            store_id,
+            tenant_id,
+            device_id,
+            None,
+            session_id,
+            "access",
+            self.access_lifetime_secs,
+        );
+        issued
+            .map(|(token, _)| token)
+            .map_err(|e| CloudError::Internal(format!("Failed to generate token: {}", e)))
     }
 
-    /// Generate a refresh token.
+    /// Generate a refresh token, with its claims for recording.
     pub fn generate_refresh_token(
         &self,
         store_id: &str,
         tenant_id: &str,
         device_id: &str,
+        session_id: &str,
+    ) -> Result<(String, Claims), CloudError> {
+        self.issue(
+            store_id,
+            tenant_id,
+            device_id,
+            None,
+            session_id,
+            "refresh",
+            self.refresh_lifetime_secs,
+        )
+        .map_err(|e| CloudError::Internal(format!("Failed to generate refresh token: {}", e)))
+    }
+
+    /// Generate an access token for a tenant back-office user.
+    pub fn generate_user_access_token(
+        &self,
+        user_id: &str,
+        tenant_id: &str,
+        role: Role,
+        session_id: &str,
     ) -> Result<String, CloudError> {
+        let issued = self.issue(
+            user_id,
+            tenant_id,
+            "",
+            Some(role),
+            session_id,
+            "user_access",
+            self.access_lifetime_secs,
+        );
+        issued
+            .map(|(token, _)| token)
+            .map_err(|e| CloudError::Internal(format!("Failed to generate token: {}", e)))
+    }
+
+    /// Generate a refresh token for a tenant back-office user, with its
+    /// claims for recording.
+    pub fn generate_user_refresh_token(
+        &self,
+        user_id: &str,
+        tenant_id: &str,
+        role: Role,
+        session_id: &str,
+    ) -> Result<(String, Claims), CloudError> {
+        self.issue(
+            user_id,
+            tenant_id,
+            "",
+            Some(role),
+            session_id,
+            "user_refresh",
+            self.refresh_lifetime_secs,
+        )
+        .map_err(|e| CloudError::Internal(format!("Failed to generate refresh token: {}", e)))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn issue(
+        &self,
+        sub: &str,
+        tenant_id: &str,
+        device_id: &str,
+        role: Option<Role>,
+        session_id: &str,
+        token_type: &str,
+        lifetime_secs: i64,
+    ) -> Result<(String, Claims), jsonwebtoken::errors::Error> {
         let now = Utc::now();
-        let exp = now + Duration::seconds(self.refresh_lifetime_secs);
+        let exp = now + Duration::seconds(lifetime_secs);
 
         let claims = Claims {
-            sub: store_id.to_string(),
+            sub: sub.to_string(),
             tenant_id: tenant_id.to_string(),
             device_id: device_id.to_string(),
             iat: now.timestamp(),
             exp: exp.timestamp(),
             jti: Uuid::new_v4().to_string(),
-            token_type: "refresh".to_string(),
+            token_type: token_type.to_string(),
+            role: role.map(|r| r.as_str().to_string()),
+            sid: Some(session_id.to_string()),
         };
 
-        encode(
+        let token = encode(
             &Header::default(),
             &claims,
             &EncodingKey::from_secret(self.secret.as_bytes()),
-        )
-        .map_err(|e| CloudError::Internal(format!("Failed to generate refresh token: {}", e)))
+        )?;
+        Ok((token, claims))
     }
 
     /// Validate and decode a token.
@@ -143,6 +225,17 @@ impl JwtManager {
         Ok(claims)
     }
 
+    /// Validate that a token is a back-office user refresh token.
+    pub fn validate_user_refresh_token(&self, token: &str) -> Result<Claims, CloudError> {
+        let claims = self.validate_token(token)?;
+
+        if claims.token_type != "user_refresh" {
+            return Err(CloudError::AuthFailed("Expected refresh token".to_string()));
+        }
+
+        Ok(claims)
+    }
+
     /// Get remaining lifetime of a token in seconds.
     pub fn get_token_lifetime(&self, token: &str) -> Result<i64, CloudError> {
         let claims = self.validate_token(token)?;
@@ -153,11 +246,7 @@ impl JwtManager {
 
 /// Extract bearer token from authorization header.
 pub fn extract_bearer_token(auth_header: &str) -> Option<&str> {
-    if auth_header.starts_with("Bearer ") {
-        Some(&auth_header[7..])
-    } else {
-        None
-    }
+    auth_header.strip_prefix("Bearer ")
 }
 
 #[cfg(test)]
@@ -169,7 +258,7 @@ mod tests {
         let manager = JwtManager::new("test-secret".to_string(), 3600, 86400);
         
         let access_token = manager
-            .generate_access_token("store-001", "tenant-001", "device-001")
+            .generate_access_token("store-001", "tenant-001", "device-001", "session-001")
             .unwrap();
         
         let claims = manager.validate_access_token(&access_token).unwrap();
@@ -184,12 +273,14 @@ mod tests {
     fn test_refresh_token() {
         let manager = JwtManager::new("test-secret".to_string(), 3600, 86400);
         
-        let refresh_token = manager
-            .generate_refresh_token("store-001", "tenant-001", "device-001")
+        let (refresh_token, issued) = manager
+            .generate_refresh_token("store-001", "tenant-001", "device-001", "session-001")
             .unwrap();
         
         let claims = manager.validate_refresh_token(&refresh_token).unwrap();
         assert_eq!(claims.token_type, "refresh");
+        assert_eq!(claims.jti, issued.jti);
+        assert_eq!(claims.sid.as_deref(), Some("session-001"));
     }
 
     #[test]
@@ -197,11 +288,58 @@ mod tests {
         let manager = JwtManager::new("test-secret".to_string(), 3600, 86400);
         
         let access_token = manager
-            .generate_access_token("store-001", "tenant-001", "device-001")
+            .generate_access_token("store-001", "tenant-001", "device-001", "session-001")
             .unwrap();
         
         // Try to validate access token as refresh token
         let result = manager.validate_refresh_token(&access_token);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_user_tokens_are_not_store_tokens() {
+        let manager = JwtManager::new("test-secret".to_string(), 3600, 86400);
+
+        let access_token = manager
+            .generate_user_access_token("user-001", "tenant-001", Role::Manager, "session-001")
+            .unwrap();
+        let claims = manager.validate_token(&access_token).unwrap();
+        assert_eq!(claims.token_type, "user_access");
+        assert_eq!(claims.role.as_deref(), Some("MANAGER"));
+
+        // Store-only services must not accept it
+        assert!(manager.validate_access_token(&access_token).is_err());
+
+        let (refresh_token, _) = manager
+            .generate_user_refresh_token("user-001", "tenant-001", Role::Manager, "session-001")
+            .unwrap();
+        assert!(manager.validate_user_refresh_token(&refresh_token).is_ok());
+        assert!(manager.validate_refresh_token(&refresh_token).is_err());
+    }
+
+    #[test]
+    fn test_tokens_without_session_still_decode() {
+        let manager = JwtManager::new("test-secret".to_string(), 3600, 86400);
+
+        // A token issued before sessions existed
+        let now = Utc::now();
+        let legacy = serde_json::json!({
+            "sub": "store-001",
+            "tenant_id": "tenant-001",
+            "device_id": "device-001",
+            "iat": now.timestamp(),
+            "exp": (now + Duration::seconds(60)).timestamp(),
+            "jti": "jti-001",
+            "token_type": "refresh",
+        });
+        let token = encode(
+            &Header::default(),
+            &legacy,
+            &EncodingKey::from_secret("test-secret".as_bytes()),
+        )
+        .unwrap();
+
+        let claims = manager.validate_refresh_token(&token).unwrap();
+        assert!(claims.sid.is_none());
+    }
 }
@@ -0,0 +1,373 @@
+//! # Upload Batch Queue
+//!
+//! Processing a large upload inside the `UploadBatch` call holds the store's
+//! request open for as long as the database takes. Stores that set
+//! `allow_queued` get an answer as soon as the batch is stored; workers
+//! process it in the background.
+//!
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                          Upload Batch Queue                             │
+//! │                                                                         │
+//! │  UploadBatch ──► validate ──► upload_batches (QUEUED)                   │
+//! │      │                              │                                   │
+//! │      │ ACCEPTED + batch_status_id   └──LPUSH id──► Redis list           │
+//! │      ▼                                               │ BRPOP            │
+//! │  GetBatchStatus ◄── upload_batches ◄── result ── BatchWorkers (N)       │
+//! │                     (COMPLETED / FAILED)                                │
+//! │                                                                         │
+//! │  Sweeper (every minute):                                                │
+//! │    QUEUED / PROCESSING for STALE_AFTER ──► LPUSH again                  │
+//! │    PROCESSING for STALE_AFTER, MAX_ATTEMPTS used ──► FAILED             │
+//! │    finished for KEEP_FINISHED ──► deleted                               │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! The batch itself lives in PostgreSQL; Redis only carries IDs. An ID lost
+//! from Redis (a worker stopped mid-pop, Redis restarted) is pushed again
+//! by the sweeper, and an ID pushed twice is harmless: a worker must claim
+//! the batch before processing it. Entity processing is idempotent, so a
+//! batch taken over from a lost worker is safe to process again.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use prost::Message;
+use tracing::{error, info, warn};
+
+use crate::db::ClaimedUploadBatch;
+use crate::error::CloudError;
+use crate::processors::StoreContext;
+use crate::proto::UploadBatchRequest;
+use crate::services::sync_service::SyncServiceImpl;
+use crate::AppState;
+
+/// Redis list holding the IDs of queued batches.
+pub const QUEUE_KEY: &str = "titan:upload_batches";
+
+/// Name the workers report under in [`crate::Liveness`].
+pub const BATCH_WORKER: &str = "batch_queue";
+
+/// `UploadBatchResponse.status` of a batch processed in the call.
+pub const STATUS_COMPLETED: &str = "COMPLETED";
+
+/// `UploadBatchResponse.status` of a queued batch.
+pub const STATUS_ACCEPTED: &str = "ACCEPTED";
+
+/// Times a batch is claimed before it is given up on.
+pub const MAX_ATTEMPTS: i32 = 3;
+
+/// How long a batch may sit queued or processing before the sweeper acts.
+pub const STALE_AFTER: Duration = Duration::from_secs(600);
+
+/// How long finished batches are kept for `GetBatchStatus`.
+pub const KEEP_FINISHED: Duration = Duration::from_secs(7 * 24 * 3600);
+
+/// Longest a worker blocks on `BRPOP` before checking in.
+const POP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Pause before reconnecting after a Redis error.
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// Interval between sweeps.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Checks a batch before it is queued, so a malformed one is refused in
+/// the call rather than failing later where the store cannot see it.
+///
+/// Entity types are not checked: unknown ones are quarantined when the
+/// batch is processed (see [`crate::processors`]).
+pub fn validate_batch(req: &UploadBatchRequest) -> Result<(), String> {
+    if req.batch_id.is_empty() {
+        return Err("batch_id is required".to_string());
+    }
+    if req.entities.is_empty() {
+        return Err("Batch has no entities".to_string());
+    }
+
+    for entity in &req.entities {
+        if entity.entity_id.is_empty() {
+            return Err("Every entity needs an entity_id".to_string());
+        }
+        if entity.entity_type.is_empty() {
+            return Err(format!("Entity {} has no entity_type", entity.entity_id));
+        }
+        if entity.data.is_none() {
+            return Err(format!("Entity {} has no data", entity.entity_id));
+        }
+    }
+
+    Ok(())
+}
+
+// =============================================================================
+// Queue
+// =============================================================================
+
+/// Redis list of queued batch IDs. Cloning shares the client.
+#[derive(Clone)]
+pub struct BatchQueue {
+    redis: redis::Client,
+}
+
+impl BatchQueue {
+    /// Creates a queue on `redis`.
+    pub fn new(redis: redis::Client) -> Self {
+        BatchQueue { redis }
+    }
+
+    /// Hands a stored batch to the workers.
+    pub async fn push(&self, id: &str) -> Result<(), CloudError> {
+        let mut conn = self
+            .redis
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| CloudError::Unavailable(e.to_string()))?;
+
+        redis::cmd("LPUSH")
+            .arg(QUEUE_KEY)
+            .arg(id)
+            .query_async::<i64>(&mut conn)
+            .await
+            .map_err(|e| CloudError::Unavailable(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Workers
+// =============================================================================
+
+/// Background workers draining the [`BatchQueue`].
+pub struct BatchWorkers {
+    state: Arc<AppState>,
+    queue: BatchQueue,
+    service: SyncServiceImpl,
+}
+
+impl BatchWorkers {
+    /// Creates the workers for `queue`.
+    pub fn new(state: Arc<AppState>, queue: BatchQueue) -> Self {
+        BatchWorkers {
+            service: SyncServiceImpl::new(state.clone()),
+            state,
+            queue,
+        }
+    }
+
+    /// Starts `workers` workers and the sweeper.
+    ///
+    /// Workers stop taking batches once the server drains; the batch in
+    /// hand counts as in flight, so shutdown waits for it.
+    pub fn spawn(self, workers: usize) {
+        // A worker checks in at least every POP_TIMEOUT, unless it is busy
+        // with a batch, which may take up to STALE_AFTER
+        self.state
+            .liveness
+            .register(BATCH_WORKER, STALE_AFTER + POP_TIMEOUT);
+
+        let this = Arc::new(self);
+        for worker in 0..workers {
+            tokio::spawn(this.clone().run_worker(worker));
+        }
+        tokio::spawn(this.run_sweeper());
+
+        info!(workers, "Upload batch workers started");
+    }
+
+    async fn run_worker(self: Arc<Self>, worker: usize) {
+        let mut conn = None;
+
+        loop {
+            self.state.liveness.beat(BATCH_WORKER);
+            if self.state.drain.is_draining() {
+                break;
+            }
+
+            let c = match conn.as_mut() {
+                Some(c) => c,
+                None => match self.queue.redis.get_multiplexed_async_connection().await {
+                    Ok(c) => conn.insert(c),
+                    Err(e) => {
+                        warn!(worker, error = %e, "Batch worker cannot reach Redis");
+                        tokio::time::sleep(RECONNECT_DELAY).await;
+                        continue;
+                    }
+                },
+            };
+
+            let mut brpop = redis::cmd("BRPOP");
+            brpop.arg(QUEUE_KEY).arg(POP_TIMEOUT.as_secs_f64());
+            let popped = tokio::select! {
+                popped = brpop.query_async::<Option<(String, String)>>(c) => popped,
+                _ = self.state.drain.draining() => break,
+            };
+
+            let id = match popped {
+                Ok(Some((_, id))) => id,
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!(worker, error = %e, "Batch worker lost Redis");
+                    conn = None;
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                    continue;
+                }
+            };
+
+            let Ok(_in_flight) = self.state.drain.track() else {
+                // Still QUEUED: another instance picks it up
+                if let Err(e) = self.queue.push(&id).await {
+                    warn!(batch_status_id = %id, error = %e, "Could not requeue batch on shutdown");
+                }
+                break;
+            };
+
+            if let Err(e) = self.process(&id).await {
+                // Left PROCESSING: the sweeper retries it
+                error!(worker, batch_status_id = %id, error = %e, "Queued batch failed");
+            }
+        }
+
+        info!(worker, "Batch worker stopped");
+    }
+
+    /// Claims and processes one batch.
+    async fn process(&self, id: &str) -> Result<(), CloudError> {
+        let claimed = self
+            .state
+            .db
+            .claim_upload_batch(id, MAX_ATTEMPTS, STALE_AFTER.as_secs() as i64)
+            .await?;
+        let Some(ClaimedUploadBatch {
+            tenant_id,
+            store_id,
+            device_id,
+            payload,
+        }) = claimed
+        else {
+            return Ok(());
+        };
+
+        let req = match payload.as_deref().map(UploadBatchRequest::decode) {
+            Some(Ok(req)) => req,
+            _ => {
+                return self
+                    .state
+                    .db
+                    .fail_upload_batch(id, "Stored batch is unreadable")
+                    .await;
+            }
+        };
+
+        let auth = StoreContext {
+            store_id,
+            tenant_id,
+            device_id,
+        };
+        let mut response = self.service.process_batch(&auth, &req).await;
+        response.status = STATUS_COMPLETED.to_string();
+        response.batch_status_id = id.to_string();
+
+        self.state
+            .db
+            .complete_upload_batch(id, &response.encode_to_vec())
+            .await
+    }
+
+    async fn run_sweeper(self: Arc<Self>) {
+        let mut ticker = tokio::time::interval(SWEEP_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = self.state.drain.draining() => return,
+            }
+
+            let ids = match self
+                .state
+                .db
+                .sweep_upload_batches(
+                    STALE_AFTER.as_secs() as i64,
+                    MAX_ATTEMPTS,
+                    KEEP_FINISHED.as_secs() as i64,
+                )
+                .await
+            {
+                Ok(ids) => ids,
+                Err(e) => {
+                    error!(error = %e, "Upload batch sweep failed");
+                    continue;
+                }
+            };
+
+            if !ids.is_empty() {
+                warn!(count = ids.len(), "Requeueing stalled upload batches");
+            }
+            for id in ids {
+                if let Err(e) = self.queue.push(&id).await {
+                    warn!(batch_status_id = %id, error = %e, "Could not requeue batch");
+                    break;
+                }
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::{sync_entity::Data, Sale, SyncEntity};
+
+    fn batch(entities: Vec<SyncEntity>) -> UploadBatchRequest {
+        UploadBatchRequest {
+            batch_id: "batch-001".to_string(),
+            entities,
+            ..Default::default()
+        }
+    }
+
+    fn sale(id: &str) -> SyncEntity {
+        SyncEntity {
+            entity_id: id.to_string(),
+            entity_type: "SALE".to_string(),
+            data: Some(Data::Sale(Sale::default())),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_validate_batch_accepts_well_formed() {
+        assert!(validate_batch(&batch(vec![sale("sale-1"), sale("sale-2")])).is_ok());
+    }
+
+    #[test]
+    fn test_validate_batch_needs_ids_and_entities() {
+        let mut req = batch(vec![sale("sale-1")]);
+        req.batch_id.clear();
+        assert!(validate_batch(&req).is_err());
+
+        assert!(validate_batch(&batch(vec![])).is_err());
+        assert!(validate_batch(&batch(vec![sale("")])).is_err());
+    }
+
+    #[test]
+    fn test_validate_batch_rejects_untyped_or_empty_entities() {
+        let mut unknown = sale("x-1");
+        unknown.entity_type = "TIMECLOCK".to_string();
+        assert!(validate_batch(&batch(vec![sale("sale-1"), unknown])).is_ok());
+
+        let mut untyped = sale("x-2");
+        untyped.entity_type.clear();
+        let err = validate_batch(&batch(vec![untyped])).unwrap_err();
+        assert!(err.contains("x-2"));
+
+        let mut empty = sale("sale-2");
+        empty.data = None;
+        assert!(validate_batch(&batch(vec![empty])).is_err());
+    }
+}
@@ -0,0 +1,430 @@
+//! # Catalog Import Parsing
+//!
+//! Parses and validates a bulk catalog file (CSV or JSON) into product
+//! rows, collecting every row error instead of stopping at the first one.
+//!
+//! ## File Formats
+//! ```text
+//! CSV  (header row required, column order free, names case-insensitive)
+//!   sku,name,price_cents,barcode,tax_rate_bps,category
+//!   ESP-001,Espresso,350,1234567890123,800,Hot Drinks
+//!
+//! JSON (array of objects with the same field names)
+//!   [{"sku": "ESP-001", "name": "Espresso", "price_cents": 350}]
+//! ```
+//!
+//! ## Fields
+//! | Field             | Required | Rule                              |
+//! |-------------------|----------|-----------------------------------|
+//! | `sku`             | yes      | 1-64 chars, unique in the file    |
+//! | `name`            | yes      | 1-200 chars                       |
+//! | `price_cents`     | yes      | integer >= 0                      |
+//! | `cost_cents`      | no       | integer >= 0                      |
+//! | `barcode`         | no       | up to 64 chars                    |
+//! | `tax_rate_bps`    | no       | 0-10000 (default 0)               |
+//! | `track_inventory` | no       | true/false/yes/no/1/0 (default true) |
+//! | `is_active`       | no       | true/false/yes/no/1/0 (default true) |
+//! | `category`        | no       | up to 100 chars                   |
+//! | `department`      | no       | up to 100 chars                   |
+//!
+//! Rows are numbered from 1 (the CSV header is not counted).
+
+use std::collections::HashMap;
+
+use crate::error::CloudError;
+
+/// Most rows accepted in one file.
+pub const MAX_IMPORT_ROWS: usize = 100_000;
+
+/// Every column a catalog file may contain.
+const KNOWN_FIELDS: [&str; 10] = [
+    "sku",
+    "name",
+    "price_cents",
+    "cost_cents",
+    "barcode",
+    "tax_rate_bps",
+    "track_inventory",
+    "is_active",
+    "category",
+    "department",
+];
+
+/// Catalog file format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    Csv,
+    Json,
+}
+
+impl ImportFormat {
+    /// Name stored with the import.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ImportFormat::Csv => "csv",
+            ImportFormat::Json => "json",
+        }
+    }
+}
+
+/// A validated product row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogRow {
+    pub row: i32,
+    pub sku: String,
+    pub name: String,
+    pub barcode: Option<String>,
+    pub price_cents: i64,
+    pub cost_cents: Option<i64>,
+    pub tax_rate_bps: i32,
+    pub track_inventory: bool,
+    pub is_active: bool,
+    pub category: Option<String>,
+    pub department: Option<String>,
+}
+
+/// Why a row was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowError {
+    pub row: i32,
+    pub field: String,
+    pub message: String,
+}
+
+/// Result of parsing a catalog file.
+#[derive(Debug, Clone, Default)]
+pub struct ParsedCatalog {
+    pub total_rows: i32,
+    pub accepted: Vec<CatalogRow>,
+    pub errors: Vec<RowError>,
+}
+
+impl ParsedCatalog {
+    /// Number of rows with at least one error.
+    pub fn error_rows(&self) -> i32 {
+        self.total_rows - self.accepted.len() as i32
+    }
+}
+
+/// Parses and validates a catalog file.
+///
+/// ## Errors
+/// `CloudError::InvalidRequest` if the file as a whole is unusable: not
+/// UTF-8, malformed JSON, a missing or unknown CSV column, or more than
+/// [`MAX_IMPORT_ROWS`] rows. Problems with individual rows are reported in
+/// [`ParsedCatalog::errors`].
+pub fn parse_catalog(format: ImportFormat, data: &[u8]) -> Result<ParsedCatalog, CloudError> {
+    let raw_rows = match format {
+        ImportFormat::Csv => read_csv(data)?,
+        ImportFormat::Json => read_json(data)?,
+    };
+    if raw_rows.len() > MAX_IMPORT_ROWS {
+        return Err(CloudError::InvalidRequest(format!(
+            "Catalog has {} rows, the limit is {}",
+            raw_rows.len(),
+            MAX_IMPORT_ROWS
+        )));
+    }
+
+    let mut parsed = ParsedCatalog {
+        total_rows: raw_rows.len() as i32,
+        ..Default::default()
+    };
+    let mut first_seen: HashMap<String, i32> = HashMap::new();
+
+    for (index, raw) in raw_rows.into_iter().enumerate() {
+        let row = index as i32 + 1;
+        let fields = match raw {
+            Ok(fields) => fields,
+            Err(message) => {
+                parsed.errors.push(RowError {
+                    row,
+                    field: String::new(),
+                    message,
+                });
+                continue;
+            }
+        };
+
+        match validate_row(row, &fields) {
+            Ok(product) => match first_seen.get(&product.sku) {
+                Some(first) => parsed.errors.push(RowError {
+                    row,
+                    field: "sku".to_string(),
+                    message: format!("Duplicate SKU (first on row {})", first),
+                }),
+                None => {
+                    first_seen.insert(product.sku.clone(), row);
+                    parsed.accepted.push(product);
+                }
+            },
+            Err(errors) => parsed.errors.extend(errors),
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// One row's fields by lowercase name, or why the row could not be read.
+type RawRow = Result<HashMap<String, String>, String>;
+
+fn read_csv(data: &[u8]) -> Result<Vec<RawRow>, CloudError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(data);
+
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| CloudError::InvalidRequest(format!("Invalid CSV header: {}", e)))?
+        .iter()
+        .map(|h| h.to_ascii_lowercase())
+        .collect();
+    check_columns(&headers)?;
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let row = record.map_err(|e| e.to_string()).and_then(|record| {
+            if record.len() != headers.len() {
+                return Err(format!(
+                    "Expected {} columns, found {}",
+                    headers.len(),
+                    record.len()
+                ));
+            }
+            Ok(headers
+                .iter()
+                .cloned()
+                .zip(record.iter().map(str::to_string))
+                .filter(|(_, value)| !value.is_empty())
+                .collect())
+        });
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+fn read_json(data: &[u8]) -> Result<Vec<RawRow>, CloudError> {
+    let items: Vec<serde_json::Value> = serde_json::from_slice(data)
+        .map_err(|e| CloudError::InvalidRequest(format!("Invalid JSON catalog: {}", e)))?;
+
+    Ok(items
+        .into_iter()
+        .map(|item| {
+            let serde_json::Value::Object(object) = item else {
+                return Err("Expected an object".to_string());
+            };
+            let mut fields = HashMap::new();
+            for (key, value) in object {
+                let key = key.to_ascii_lowercase();
+                if !KNOWN_FIELDS.contains(&key.as_str()) {
+                    return Err(format!("Unknown field: {}", key));
+                }
+                let value = match value {
+                    serde_json::Value::Null => continue,
+                    serde_json::Value::String(s) => s.trim().to_string(),
+                    serde_json::Value::Number(n) => n.to_string(),
+                    serde_json::Value::Bool(b) => b.to_string(),
+                    _ => return Err(format!("{}: expected a scalar value", key)),
+                };
+                if !value.is_empty() {
+                    fields.insert(key, value);
+                }
+            }
+            Ok(fields)
+        })
+        .collect())
+}
+
+fn check_columns(headers: &[String]) -> Result<(), CloudError> {
+    if let Some(unknown) = headers.iter().find(|h| !KNOWN_FIELDS.contains(&h.as_str())) {
+        return Err(CloudError::InvalidRequest(format!(
+            "Unknown column: {}",
+            unknown
+        )));
+    }
+    for required in ["sku", "name", "price_cents"] {
+        if !headers.iter().any(|h| h == required) {
+            return Err(CloudError::InvalidRequest(format!(
+                "Missing column: {}",
+                required
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Validates one row, returning all of its errors.
+fn validate_row(row: i32, fields: &HashMap<String, String>) -> Result<CatalogRow, Vec<RowError>> {
+    let mut errors = Vec::new();
+    let mut error = |field: &str, message: String| {
+        errors.push(RowError {
+            row,
+            field: field.to_string(),
+            message,
+        })
+    };
+
+    let text =
+        |field: &str, required: bool, max: usize, error: &mut dyn FnMut(&str, String)| match fields
+            .get(field)
+        {
+            None if required => {
+                error(field, "Required".to_string());
+                None
+            }
+            Some(value) if value.chars().count() > max => {
+                error(field, format!("Longer than {} characters", max));
+                None
+            }
+            value => value.cloned(),
+        };
+    let sku = text("sku", true, 64, &mut error);
+    let name = text("name", true, 200, &mut error);
+    let barcode = text("barcode", false, 64, &mut error);
+    let category = text("category", false, 100, &mut error);
+    let department = text("department", false, 100, &mut error);
+
+    let integer = |field: &str, min: i64, max: i64, error: &mut dyn FnMut(&str, String)| {
+        let value = fields.get(field)?;
+        match value.parse::<i64>() {
+            Ok(n) if (min..=max).contains(&n) => Some(n),
+            Ok(_) => {
+                error(field, format!("Must be between {} and {}", min, max));
+                None
+            }
+            Err(_) => {
+                error(field, format!("Not a whole number: {}", value));
+                None
+            }
+        }
+    };
+    let price_cents = integer("price_cents", 0, i64::MAX, &mut error);
+    if !fields.contains_key("price_cents") {
+        error("price_cents", "Required".to_string());
+    }
+    let cost_cents = integer("cost_cents", 0, i64::MAX, &mut error);
+    let tax_rate_bps = integer("tax_rate_bps", 0, 10_000, &mut error);
+
+    let flag = |field: &str, error: &mut dyn FnMut(&str, String)| {
+        let value = fields.get(field)?;
+        match value.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "1" => Some(true),
+            "false" | "no" | "0" => Some(false),
+            _ => {
+                error(field, format!("Not a boolean: {}", value));
+                None
+            }
+        }
+    };
+    let track_inventory = flag("track_inventory", &mut error);
+    let is_active = flag("is_active", &mut error);
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    Ok(CatalogRow {
+        row,
+        sku: sku.unwrap_or_default(),
+        name: name.unwrap_or_default(),
+        barcode,
+        price_cents: price_cents.unwrap_or_default(),
+        cost_cents,
+        tax_rate_bps: tax_rate_bps.unwrap_or(0) as i32,
+        track_inventory: track_inventory.unwrap_or(true),
+        is_active: is_active.unwrap_or(true),
+        category,
+        department,
+    })
+}
+
+// =============================================================================
+// Unit Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_rows_are_validated_and_defaulted() {
+        let csv = "SKU,Name,price_cents,tax_rate_bps,is_active\n\
+                   ESP-001,Espresso,350,800,yes\n\
+                   LAT-001,\"Latte, large\",550,,\n";
+
+        let parsed = parse_catalog(ImportFormat::Csv, csv.as_bytes()).unwrap();
+        assert_eq!(parsed.total_rows, 2);
+        assert!(parsed.errors.is_empty());
+
+        let latte = &parsed.accepted[1];
+        assert_eq!(latte.row, 2);
+        assert_eq!(latte.name, "Latte, large");
+        assert_eq!(latte.tax_rate_bps, 0);
+        assert!(latte.is_active);
+        assert!(latte.track_inventory);
+    }
+
+    #[test]
+    fn test_row_errors_are_collected_per_field() {
+        let csv = "sku,name,price_cents,tax_rate_bps\n\
+                   ,Espresso,abc,20000\n\
+                   OK-1,Fine,100,0\n\
+                   OK-1,Duplicate,100,0\n\
+                   X,Too,few\n";
+
+        let parsed = parse_catalog(ImportFormat::Csv, csv.as_bytes()).unwrap();
+        assert_eq!(parsed.total_rows, 4);
+        assert_eq!(parsed.accepted.len(), 1);
+        assert_eq!(parsed.error_rows(), 3);
+
+        let fields: Vec<(i32, &str)> = parsed
+            .errors
+            .iter()
+            .map(|e| (e.row, e.field.as_str()))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                (1, "sku"),
+                (1, "price_cents"),
+                (1, "tax_rate_bps"),
+                (3, "sku"),
+                (4, ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_csv_columns_are_checked() {
+        let missing = parse_catalog(ImportFormat::Csv, b"sku,name\nA,B\n");
+        assert!(matches!(missing, Err(CloudError::InvalidRequest(_))));
+
+        let unknown = parse_catalog(ImportFormat::Csv, b"sku,name,price_cents,colour\n");
+        assert!(matches!(unknown, Err(CloudError::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_json_rows() {
+        let json = r#"[
+            {"sku": "ESP-001", "name": "Espresso", "price_cents": 350, "track_inventory": false},
+            {"sku": "BAD", "name": "Bad", "price_cents": -1},
+            "not an object",
+            {"sku": "X", "name": "X", "price_cents": 1, "colour": "red"}
+        ]"#;
+
+        let parsed = parse_catalog(ImportFormat::Json, json.as_bytes()).unwrap();
+        assert_eq!(parsed.total_rows, 4);
+        assert_eq!(parsed.accepted.len(), 1);
+        assert!(!parsed.accepted[0].track_inventory);
+        assert_eq!(parsed.errors.len(), 3);
+        assert_eq!(parsed.errors[0].field, "price_cents");
+    }
+
+    #[test]
+    fn test_invalid_json_is_rejected() {
+        let result = parse_catalog(ImportFormat::Json, b"{\"sku\": 1}");
+        assert!(matches!(result, Err(CloudError::InvalidRequest(_))));
+    }
+}
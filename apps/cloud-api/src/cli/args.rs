@@ -0,0 +1,296 @@
+//! Command-line parsing for `titan-cloud-cli`.
+
+use std::path::PathBuf;
+
+/// Server used when neither `--url` nor `TITAN_CLOUD_URL` is set.
+pub const DEFAULT_URL: &str = "http://localhost:50051";
+
+/// Usage text for `--help` and parse errors.
+pub const USAGE: &str = "\
+Titan Cloud CLI
+
+Usage: titan-cloud-cli [OPTIONS] <COMMAND>
+
+Commands:
+  token --api-key <KEY> --store <ID> --tenant <ID> [--device <ID>]
+                               Exchange a store API key for an access token
+  services                     List the server's gRPC services (reflection)
+  health [--service <NAME>] [--watch]
+                               Check health (database, redis, migrations, workers)
+  cursors                      Show the store's sync cursors
+  upload-test [--delta <PRODUCT_ID>:<QTY>]
+                               Upload a test batch (empty unless --delta is given)
+  tail [--topic <TOPIC>]...    Print notifications until Ctrl+C
+
+Options:
+  -u, --url <URL>          Server URL (default: $TITAN_CLOUD_URL or http://localhost:50051)
+  -t, --token <TOKEN>      Access token (default: $TITAN_CLOUD_TOKEN)
+      --ca-cert <PATH>     PEM CA certificate for https servers
+  -h, --help               Show this help message";
+
+/// What to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Token {
+        api_key: String,
+        store_id: String,
+        tenant_id: String,
+        device_id: String,
+    },
+    Services,
+    Health {
+        service: String,
+        watch: bool,
+    },
+    Cursors,
+    UploadTest {
+        delta: Option<(String, i32)>,
+    },
+    Tail {
+        topics: Vec<String>,
+    },
+    Help,
+}
+
+/// Parsed command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    pub url: String,
+    pub token: Option<String>,
+    pub ca_cert: Option<PathBuf>,
+    pub command: Command,
+}
+
+/// Parses the arguments after the program name. `env` looks up the
+/// `TITAN_CLOUD_URL` / `TITAN_CLOUD_TOKEN` defaults.
+pub fn parse(args: &[String], env: impl Fn(&str) -> Option<String>) -> Result<Options, String> {
+    let mut url = env("TITAN_CLOUD_URL").unwrap_or_else(|| DEFAULT_URL.to_string());
+    let mut token = env("TITAN_CLOUD_TOKEN").filter(|t| !t.is_empty());
+    let mut ca_cert = None;
+    let mut command: Option<String> = None;
+    let mut rest: Vec<String> = Vec::new();
+
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        match arg {
+            "--url" | "-u" => url = value(args, &mut i, arg)?,
+            "--token" | "-t" => token = Some(value(args, &mut i, arg)?),
+            "--ca-cert" => ca_cert = Some(PathBuf::from(value(args, &mut i, arg)?)),
+            "--help" | "-h" => {
+                command = Some("help".to_string());
+                rest.clear();
+                break;
+            }
+            _ if command.is_none() && !arg.starts_with('-') => command = Some(arg.to_string()),
+            _ => rest.push(arg.to_string()),
+        }
+        i += 1;
+    }
+
+    let command = match command.as_deref() {
+        None | Some("help") => Command::Help,
+        Some("token") => {
+            let mut api_key = None;
+            let mut store_id = None;
+            let mut tenant_id = None;
+            let mut device_id = "titan-cloud-cli".to_string();
+            let mut i = 0;
+            while i < rest.len() {
+                let arg = rest[i].as_str();
+                match arg {
+                    "--api-key" => api_key = Some(value(&rest, &mut i, arg)?),
+                    "--store" => store_id = Some(value(&rest, &mut i, arg)?),
+                    "--tenant" => tenant_id = Some(value(&rest, &mut i, arg)?),
+                    "--device" => device_id = value(&rest, &mut i, arg)?,
+                    _ => return Err(format!("Unexpected argument for token: {}", arg)),
+                }
+                i += 1;
+            }
+            Command::Token {
+                api_key: api_key.ok_or("token needs --api-key")?,
+                store_id: store_id.ok_or("token needs --store")?,
+                tenant_id: tenant_id.ok_or("token needs --tenant")?,
+                device_id,
+            }
+        }
+        Some("services") => {
+            no_arguments("services", &rest)?;
+            Command::Services
+        }
+        Some("health") => {
+            let mut service = String::new();
+            let mut watch = false;
+            let mut i = 0;
+            while i < rest.len() {
+                let arg = rest[i].as_str();
+                match arg {
+                    "--service" => service = value(&rest, &mut i, arg)?,
+                    "--watch" => watch = true,
+                    _ => return Err(format!("Unexpected argument for health: {}", arg)),
+                }
+                i += 1;
+            }
+            Command::Health { service, watch }
+        }
+        Some("cursors") => {
+            no_arguments("cursors", &rest)?;
+            Command::Cursors
+        }
+        Some("upload-test") => {
+            let mut delta = None;
+            let mut i = 0;
+            while i < rest.len() {
+                let arg = rest[i].as_str();
+                match arg {
+                    "--delta" => delta = Some(parse_delta(&value(&rest, &mut i, arg)?)?),
+                    _ => return Err(format!("Unexpected argument for upload-test: {}", arg)),
+                }
+                i += 1;
+            }
+            Command::UploadTest { delta }
+        }
+        Some("tail") => {
+            let mut topics = Vec::new();
+            let mut i = 0;
+            while i < rest.len() {
+                let arg = rest[i].as_str();
+                match arg {
+                    "--topic" => topics.push(value(&rest, &mut i, arg)?.to_uppercase()),
+                    _ => return Err(format!("Unexpected argument for tail: {}", arg)),
+                }
+                i += 1;
+            }
+            Command::Tail { topics }
+        }
+        Some(other) => return Err(format!("Unknown command: {}", other)),
+    };
+
+    Ok(Options {
+        url,
+        token,
+        ca_cert,
+        command,
+    })
+}
+
+/// The value after the option at `args[*i]`, advancing past it.
+fn value(args: &[String], i: &mut usize, option: &str) -> Result<String, String> {
+    *i += 1;
+    args.get(*i)
+        .cloned()
+        .ok_or_else(|| format!("{} needs a value", option))
+}
+
+fn no_arguments(command: &str, rest: &[String]) -> Result<(), String> {
+    match rest.first() {
+        Some(arg) => Err(format!("Unexpected argument for {}: {}", command, arg)),
+        None => Ok(()),
+    }
+}
+
+/// Parses `PRODUCT_ID:QTY`, e.g. `p-1:-2`.
+fn parse_delta(value: &str) -> Result<(String, i32), String> {
+    let (product_id, quantity) = value
+        .rsplit_once(':')
+        .ok_or_else(|| format!("--delta must be PRODUCT_ID:QTY, got {}", value))?;
+    let quantity: i32 = quantity
+        .parse()
+        .map_err(|_| format!("--delta quantity must be a whole number, got {}", quantity))?;
+    if product_id.is_empty() || quantity == 0 {
+        return Err(format!(
+            "--delta must be PRODUCT_ID:QTY with QTY not 0, got {}",
+            value
+        ));
+    }
+    Ok((product_id.to_string(), quantity))
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    fn no_env(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn test_global_options_and_env_defaults() {
+        let options = parse(&args("health"), no_env).unwrap();
+        assert_eq!(options.url, DEFAULT_URL);
+        assert_eq!(options.token, None);
+
+        let env = |key: &str| match key {
+            "TITAN_CLOUD_URL" => Some("http://cloud:50051".to_string()),
+            "TITAN_CLOUD_TOKEN" => Some("env-token".to_string()),
+            _ => None,
+        };
+        let options = parse(&args("cursors"), env).unwrap();
+        assert_eq!(options.url, "http://cloud:50051");
+        assert_eq!(options.token.as_deref(), Some("env-token"));
+
+        // Flags win over the environment, before or after the command
+        let options = parse(&args("cursors --token flag-token -u http://other:1"), env).unwrap();
+        assert_eq!(options.url, "http://other:1");
+        assert_eq!(options.token.as_deref(), Some("flag-token"));
+        assert_eq!(options.command, Command::Cursors);
+    }
+
+    #[test]
+    fn test_commands() {
+        let options = parse(&args("token --api-key k --store s-1 --tenant t-1"), no_env).unwrap();
+        assert_eq!(
+            options.command,
+            Command::Token {
+                api_key: "k".to_string(),
+                store_id: "s-1".to_string(),
+                tenant_id: "t-1".to_string(),
+                device_id: "titan-cloud-cli".to_string(),
+            }
+        );
+        assert!(parse(&args("token --api-key k --store s-1"), no_env).is_err());
+
+        let options = parse(&args("health --service database --watch"), no_env).unwrap();
+        assert_eq!(
+            options.command,
+            Command::Health {
+                service: "database".to_string(),
+                watch: true,
+            }
+        );
+
+        let options = parse(&args("upload-test --delta p-1:-2"), no_env).unwrap();
+        assert_eq!(
+            options.command,
+            Command::UploadTest {
+                delta: Some(("p-1".to_string(), -2)),
+            }
+        );
+        assert!(parse(&args("upload-test --delta p-1"), no_env).is_err());
+        assert!(parse(&args("upload-test --delta p-1:0"), no_env).is_err());
+
+        let options = parse(&args("tail --topic alert --topic CONFIG_UPDATE"), no_env).unwrap();
+        assert_eq!(
+            options.command,
+            Command::Tail {
+                topics: vec!["ALERT".to_string(), "CONFIG_UPDATE".to_string()],
+            }
+        );
+
+        assert_eq!(parse(&args(""), no_env).unwrap().command, Command::Help);
+        assert_eq!(
+            parse(&args("tail -h"), no_env).unwrap().command,
+            Command::Help
+        );
+        assert!(parse(&args("cursors extra"), no_env).is_err());
+        assert!(parse(&args("frobnicate"), no_env).is_err());
+    }
+}
@@ -0,0 +1,369 @@
+//! # Titan Cloud CLI
+//!
+//! Debug client for operators of the cloud API: the calls a store hub
+//! makes, one at a time, without hand-written `grpcurl` requests.
+//!
+//! ## Commands
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         titan-cloud-cli                                 │
+//! │                                                                         │
+//! │  token ────────► AuthService.ExchangeToken ──► export TITAN_CLOUD_TOKEN │
+//! │  services ─────► ServerReflection (list services)                       │
+//! │  health ───────► HealthService.Check / Watch                            │
+//! │  cursors ──────► SyncService.GetSyncStatus (token's store)              │
+//! │  upload-test ──► SyncService.UploadBatch (processed in the call)        │
+//! │  tail ─────────► NotificationService.Subscribe (acks heartbeats)        │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Every command but `token`, `services` and `health` needs a store access
+//! token, from `--token` or `TITAN_CLOUD_TOKEN`. Note that `tail` counts as
+//! the store's notification stream: the store shows online while it runs.
+//!
+//! ## Usage
+//! ```bash
+//! eval "$(titan-cloud-cli token --api-key $KEY --store $STORE --tenant $TENANT)"
+//! titan-cloud-cli --url http://localhost:50051 cursors
+//! ```
+
+mod args;
+
+use std::error::Error;
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use tonic::metadata::MetadataValue;
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint};
+use tonic::Request;
+use tonic_reflection::pb::v1::{
+    server_reflection_client::ServerReflectionClient, server_reflection_request::MessageRequest,
+    server_reflection_response::MessageResponse, ServerReflectionRequest,
+};
+
+use titan_cloud_api::proto::{
+    auth_service_client::AuthServiceClient, health_check_response::ServingStatus,
+    health_service_client::HealthServiceClient, notification,
+    notification_service_client::NotificationServiceClient, sync_entity,
+    sync_service_client::SyncServiceClient, ExchangeTokenRequest, GetSyncStatusRequest,
+    HealthCheckRequest, HealthCheckResponse, InventoryDelta, Notification, SubscriptionMessage,
+    SyncEntity, Timestamp, UploadBatchRequest,
+};
+
+use args::{Command, Options, USAGE};
+
+/// How long to wait for the server before giving up.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+type CliResult<T = ()> = Result<T, Box<dyn Error>>;
+
+#[tokio::main]
+async fn main() -> CliResult {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match args::parse(&args, |key| std::env::var(key).ok()) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            eprintln!();
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    if options.command == Command::Help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let channel = connect(&options).await?;
+    match &options.command {
+        Command::Token {
+            api_key,
+            store_id,
+            tenant_id,
+            device_id,
+        } => token(channel, api_key, store_id, tenant_id, device_id).await,
+        Command::Services => services(channel).await,
+        Command::Health { service, watch } => health(channel, &options, service, *watch).await,
+        Command::Cursors => cursors(channel, &options).await,
+        Command::UploadTest { delta } => upload_test(channel, &options, delta.as_ref()).await,
+        Command::Tail { topics } => tail(channel, &options, topics).await,
+        Command::Help => Ok(()),
+    }
+}
+
+/// Connects to the server, over TLS when a CA certificate is given.
+async fn connect(options: &Options) -> CliResult<Channel> {
+    let mut endpoint = Endpoint::from_shared(options.url.clone())?.connect_timeout(CONNECT_TIMEOUT);
+    if let Some(path) = &options.ca_cert {
+        let pem = std::fs::read(path)?;
+        endpoint = endpoint
+            .tls_config(ClientTlsConfig::new().ca_certificate(Certificate::from_pem(pem)))?;
+    }
+    endpoint
+        .connect()
+        .await
+        .map_err(|e| format!("Could not connect to {}: {}", options.url, e).into())
+}
+
+/// Wraps `message` in a request carrying the access token, if there is one.
+fn request<T>(message: T, token: Option<&str>) -> CliResult<Request<T>> {
+    let mut request = Request::new(message);
+    if let Some(token) = token {
+        let value: MetadataValue<_> = format!("Bearer {}", token).parse()?;
+        request.metadata_mut().insert("authorization", value);
+    }
+    Ok(request)
+}
+
+/// The access token, for commands the server only answers for a store.
+fn require_token(options: &Options) -> CliResult<&str> {
+    options.token.as_deref().ok_or_else(|| {
+        "This command needs an access token: pass --token or set TITAN_CLOUD_TOKEN \
+         (see `titan-cloud-cli token`)"
+            .into()
+    })
+}
+
+async fn token(
+    channel: Channel,
+    api_key: &str,
+    store_id: &str,
+    tenant_id: &str,
+    device_id: &str,
+) -> CliResult {
+    let response = AuthServiceClient::new(channel)
+        .exchange_token(ExchangeTokenRequest {
+            api_key: api_key.to_string(),
+            store_id: store_id.to_string(),
+            tenant_id: tenant_id.to_string(),
+            device_id: device_id.to_string(),
+            device_name: "titan-cloud-cli".to_string(),
+        })
+        .await?
+        .into_inner();
+
+    // Shell-friendly, so the output can be eval'd
+    eprintln!("# expires in {}s", response.expires_in);
+    println!("export TITAN_CLOUD_TOKEN={}", response.access_token);
+    println!(
+        "export TITAN_CLOUD_REFRESH_TOKEN={}",
+        response.refresh_token
+    );
+    Ok(())
+}
+
+async fn services(channel: Channel) -> CliResult {
+    let outbound = tokio_stream::iter([ServerReflectionRequest {
+        host: String::new(),
+        message_request: Some(MessageRequest::ListServices(String::new())),
+    }]);
+    let mut inbound = ServerReflectionClient::new(channel)
+        .server_reflection_info(outbound)
+        .await?
+        .into_inner();
+
+    match inbound.next().await.transpose()? {
+        Some(response) => match response.message_response {
+            Some(MessageResponse::ListServicesResponse(list)) => {
+                for service in list.service {
+                    println!("{}", service.name);
+                }
+                Ok(())
+            }
+            Some(MessageResponse::ErrorResponse(error)) => Err(error.error_message.into()),
+            _ => Err("Unexpected reflection response".into()),
+        },
+        None => Err("The server closed the reflection stream".into()),
+    }
+}
+
+async fn health(channel: Channel, options: &Options, service: &str, watch: bool) -> CliResult {
+    let mut client = HealthServiceClient::new(channel);
+    let message = HealthCheckRequest {
+        service: service.to_string(),
+    };
+
+    if !watch {
+        let response = client
+            .check(request(message, options.token.as_deref())?)
+            .await?
+            .into_inner();
+        println!("{}", describe_health(&response));
+        return Ok(());
+    }
+
+    let mut stream = client
+        .watch(request(message, options.token.as_deref())?)
+        .await?
+        .into_inner();
+    while let Some(response) = stream.next().await.transpose()? {
+        println!("{}", describe_health(&response));
+    }
+    Ok(())
+}
+
+fn describe_health(response: &HealthCheckResponse) -> String {
+    let status = ServingStatus::try_from(response.status).unwrap_or(ServingStatus::Unknown);
+    let at = response
+        .server_time
+        .as_ref()
+        .map(|t| t.value.as_str())
+        .unwrap_or("-");
+    format!("{}  {}  {}", at, status.as_str_name(), response.message)
+}
+
+async fn cursors(channel: Channel, options: &Options) -> CliResult {
+    let token = require_token(options)?;
+    let status = SyncServiceClient::new(channel)
+        .get_sync_status(request(GetSyncStatusRequest::default(), Some(token))?)
+        .await?
+        .into_inner();
+
+    println!("{:<10} {:>12}  UPDATED", "STREAM", "POSITION");
+    for cursor in &status.cursors {
+        let updated = cursor
+            .updated_at
+            .as_ref()
+            .map(|t| t.value.as_str())
+            .unwrap_or("-");
+        println!("{:<10} {:>12}  {}", cursor.stream, cursor.position, updated);
+    }
+    println!();
+    println!("health: {} {}", status.health_status, status.health_message);
+    Ok(())
+}
+
+async fn upload_test(
+    channel: Channel,
+    options: &Options,
+    delta: Option<&(String, i32)>,
+) -> CliResult {
+    let token = require_token(options)?;
+    let batch_id = format!("cli-{}", uuid::Uuid::new_v4());
+    let now = Timestamp {
+        value: Utc::now().to_rfc3339(),
+    };
+
+    let entities = delta
+        .map(|(product_id, quantity)| {
+            let id = uuid::Uuid::new_v4().to_string();
+            SyncEntity {
+                entity_id: id.clone(),
+                entity_type: "INVENTORY_DELTA".to_string(),
+                data: Some(sync_entity::Data::InventoryDelta(InventoryDelta {
+                    id,
+                    product_id: product_id.clone(),
+                    delta: *quantity,
+                    reason: "ADJUSTMENT".to_string(),
+                    reference_id: batch_id.clone(),
+                    created_at: Some(now.clone()),
+                    ..Default::default()
+                })),
+                created_at: Some(now.clone()),
+                correlation_id: batch_id.clone(),
+                ..Default::default()
+            }
+        })
+        .into_iter()
+        .collect();
+
+    let message = UploadBatchRequest {
+        batch_id: batch_id.clone(),
+        device_id: "titan-cloud-cli".to_string(),
+        entities,
+        ..Default::default()
+    };
+    let ack = SyncServiceClient::new(channel)
+        .upload_batch(request(message, Some(token))?)
+        .await?
+        .into_inner();
+
+    println!("batch:   {}", ack.batch_id);
+    println!("status:  {} (success: {})", ack.status, ack.success);
+    println!("synced:  {}", ack.synced_ids.join(", "));
+    for error in &ack.errors {
+        println!(
+            "error:   {} {} {}{}",
+            error.entity_id,
+            error.error_code,
+            error.error_message,
+            if error.retryable { " (retryable)" } else { "" }
+        );
+    }
+    if let Some(cursor) = &ack.new_cursor {
+        println!("cursor:  {} @ {}", cursor.stream, cursor.position);
+    }
+    Ok(())
+}
+
+async fn tail(channel: Channel, options: &Options, topics: &[String]) -> CliResult {
+    let token = require_token(options)?;
+    let (tx, rx) = mpsc::channel(8);
+    tx.send(SubscriptionMessage {
+        topics: topics.to_vec(),
+        ..Default::default()
+    })
+    .await?;
+
+    let mut stream = NotificationServiceClient::new(channel)
+        .subscribe(request(ReceiverStream::new(rx), Some(token))?)
+        .await?
+        .into_inner();
+    eprintln!("# subscribed, Ctrl+C to stop");
+
+    while let Some(notification) = stream.next().await.transpose()? {
+        println!("{}", describe_notification(&notification));
+        match &notification.payload {
+            Some(notification::Payload::Heartbeat(_)) => {
+                let ack = SubscriptionMessage {
+                    heartbeat_ack: true,
+                    ..Default::default()
+                };
+                if tx.send(ack).await.is_err() {
+                    break;
+                }
+            }
+            Some(notification::Payload::GoAway(_)) => break,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn describe_notification(notification: &Notification) -> String {
+    use notification::Payload;
+
+    let at = notification
+        .timestamp
+        .as_ref()
+        .map(|t| t.value.as_str())
+        .unwrap_or("-");
+    let detail = match &notification.payload {
+        Some(Payload::ProductUpdate(update)) => {
+            format!("{} {}", update.operation, update.product_id)
+        }
+        Some(Payload::PriceChange(change)) => format!(
+            "{} {} -> {}",
+            change.product_id,
+            change.old_price.as_ref().map_or(0, |m| m.cents),
+            change.new_price.as_ref().map_or(0, |m| m.cents)
+        ),
+        Some(Payload::ConfigUpdate(update)) => {
+            format!("{} = {}", update.config_key, update.config_value)
+        }
+        Some(Payload::Alert(alert)) => {
+            format!("[{}] {}: {}", alert.severity, alert.title, alert.message)
+        }
+        Some(Payload::Heartbeat(_)) => String::new(),
+        Some(Payload::GoAway(go_away)) => format!(
+            "{} (reconnect after {}ms)",
+            go_away.reason, go_away.reconnect_after_ms
+        ),
+        None => String::new(),
+    };
+    format!("{}  {:<16} {}", at, notification.topic, detail)
+}
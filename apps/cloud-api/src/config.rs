@@ -5,6 +5,9 @@
 use serde::{Deserialize, Serialize};
 use std::env;
 
+use crate::presence::MIN_STORE_OFFLINE_AFTER_SECS;
+use crate::storage::{ObjectStorageConfig, MAX_SIGNED_URL_SECS};
+
 /// Cloud API configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CloudConfig {
@@ -38,8 +41,39 @@ pub struct CloudConfig {
     /// Max message size in bytes (default: 16MB)
     pub max_message_size: usize,
 
+    /// Serve gRPC reflection, so operators can discover the API
+    pub grpc_reflection: bool,
+
     /// Sync batch size limit
     pub sync_batch_size_limit: usize,
+
+    /// Months of sales data kept for tenants without their own policy
+    pub sales_retention_months: u32,
+
+    /// Directory expired sales data is archived to (retention is off when unset)
+    pub archive_dir: Option<String>,
+
+    /// Seconds between partition maintenance / retention runs
+    pub retention_interval_secs: u64,
+
+    /// S3-compatible bucket for exports, imports and archives (optional)
+    pub object_storage: Option<ObjectStorageConfig>,
+
+    /// Default signed URL lifetime in seconds
+    pub signed_url_ttl_secs: u64,
+
+    /// Longest shutdown waits for in-flight upload batches to commit
+    pub shutdown_drain_secs: u64,
+
+    /// Master key (hex) wrapping the tenants' PII data keys (optional)
+    pub pii_master_key: Option<String>,
+
+    /// Upload batch queue workers (0 processes every batch in the call)
+    pub batch_workers: usize,
+
+    /// Seconds without a message on its notification stream before a store
+    /// is reported offline
+    pub store_offline_after_secs: u64,
 }
 
 impl CloudConfig {
@@ -89,17 +123,74 @@ impl CloudConfig {
                 .parse()
                 .map_err(|_| ConfigError::InvalidValue("MAX_MESSAGE_SIZE".to_string()))?,
 
+            grpc_reflection: env::var("GRPC_REFLECTION")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("GRPC_REFLECTION".to_string()))?,
+
             sync_batch_size_limit: env::var("SYNC_BATCH_SIZE_LIMIT")
                 .unwrap_or_else(|_| "1000".to_string())
                 .parse()
                 .map_err(|_| ConfigError::InvalidValue("SYNC_BATCH_SIZE_LIMIT".to_string()))?,
+
+            sales_retention_months: env::var("SALES_RETENTION_MONTHS")
+                .unwrap_or_else(|_| "84".to_string()) // 7 years
+                .parse()
+                .ok()
+                .filter(|months: &u32| *months >= 1)
+                .ok_or_else(|| ConfigError::InvalidValue("SALES_RETENTION_MONTHS".to_string()))?,
+
+            archive_dir: env::var("ARCHIVE_DIR").ok(),
+
+            retention_interval_secs: env::var("RETENTION_INTERVAL_SECS")
+                .unwrap_or_else(|_| "86400".to_string()) // daily
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("RETENTION_INTERVAL_SECS".to_string()))?,
+
+            object_storage: match env::var("S3_BUCKET") {
+                Ok(bucket) => Some(ObjectStorageConfig {
+                    endpoint: env::var("S3_ENDPOINT")
+                        .map_err(|_| ConfigError::MissingRequired("S3_ENDPOINT".to_string()))?,
+                    bucket,
+                    region: env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+                    access_key_id: env::var("S3_ACCESS_KEY_ID")
+                        .map_err(|_| ConfigError::MissingRequired("S3_ACCESS_KEY_ID".to_string()))?,
+                    secret_access_key: env::var("S3_SECRET_ACCESS_KEY")
+                        .map_err(|_| ConfigError::MissingRequired("S3_SECRET_ACCESS_KEY".to_string()))?,
+                }),
+                Err(_) => None,
+            },
+
+            signed_url_ttl_secs: env::var("SIGNED_URL_TTL_SECS")
+                .unwrap_or_else(|_| "900".to_string()) // 15 minutes
+                .parse()
+                .ok()
+                .filter(|secs: &u64| (1..=MAX_SIGNED_URL_SECS).contains(secs))
+                .ok_or_else(|| ConfigError::InvalidValue("SIGNED_URL_TTL_SECS".to_string()))?,
+
+            shutdown_drain_secs: env::var("SHUTDOWN_DRAIN_SECS")
+                .unwrap_or_else(|_| "25".to_string()) // under the usual 30s kill grace
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("SHUTDOWN_DRAIN_SECS".to_string()))?,
+
+            pii_master_key: env::var("PII_MASTER_KEY").ok().filter(|k| !k.is_empty()),
+
+            batch_workers: env::var("BATCH_WORKERS")
+                .unwrap_or_else(|_| "4".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidValue("BATCH_WORKERS".to_string()))?,
+
+            store_offline_after_secs: env::var("STORE_OFFLINE_AFTER_SECS")
+                .unwrap_or_else(|_| "300".to_string()) // 10 missed heartbeats
+                .parse()
+                .ok()
+                .filter(|secs: &u64| *secs >= MIN_STORE_OFFLINE_AFTER_SECS)
+                .ok_or_else(|| ConfigError::InvalidValue("STORE_OFFLINE_AFTER_SECS".to_string()))?,
         };
 
         // Validate TLS configuration
-        if config.tls_enabled {
-            if config.tls_cert_path.is_none() || config.tls_key_path.is_none() {
-                return Err(ConfigError::MissingTlsConfig);
-            }
+        if config.tls_enabled && (config.tls_cert_path.is_none() || config.tls_key_path.is_none()) {
+            return Err(ConfigError::MissingTlsConfig);
         }
 
         Ok(config)
@@ -2,18 +2,46 @@
 //!
 //! Provides PostgreSQL connectivity and repository methods.
 
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use sqlx::migrate::Migrator;
 use sqlx::postgres::{PgPool, PgPoolOptions};
-use sqlx::Row;
+use chrono::{DateTime, NaiveDate, Utc};
 use tracing::info;
-use uuid::Uuid;
-use chrono::{DateTime, Utc};
 
+use crate::audit::AuditEntry;
+use crate::auth::Claims;
+use crate::catalog_import::{CatalogRow, RowError};
 use crate::error::CloudError;
+use crate::pii::{random_key, MasterKey, PiiCipher, PiiField, CIPHERTEXT_PREFIX};
+use crate::retention::PartitionedTable;
+
+/// Name written over an erased customer's name (matches the terminals).
+pub const ERASED_CUSTOMER_NAME: &str = "Erased customer";
+
+/// Rows per statement when staging catalog imports.
+const STAGING_CHUNK_ROWS: usize = 5000;
+
+/// Migrations embedded at build time.
+static MIGRATOR: Migrator = sqlx::migrate!("../../migrations/postgres");
+
+/// Columns selected into `TenantUserRecord`.
+const TENANT_USER_COLUMNS: &str = "id, tenant_id, email, display_name, role, is_active, \
+     invite_token_hash IS NOT NULL AS invite_pending, last_login_at, created_at";
 
 /// Database connection pool.
 #[derive(Clone)]
 pub struct Database {
     pool: PgPool,
+    /// Set when a PII master key is configured.
+    pii: Option<Arc<PiiKeys>>,
+}
+
+/// The PII master key and the tenant ciphers opened with it.
+struct PiiKeys {
+    master: MasterKey,
+    ciphers: RwLock<HashMap<String, PiiCipher>>,
 }
 
 impl Database {
@@ -25,12 +53,131 @@ impl Database {
             .await
             .map_err(|e| CloudError::Database(e.to_string()))?;
 
-        Ok(Database { pool })
+        Ok(Database { pool, pii: None })
+    }
+
+    /// Encrypt customer phone numbers and emails with per-tenant data keys
+    /// wrapped by `master` (see [`crate::pii`]).
+    pub fn with_pii_master_key(mut self, master: MasterKey) -> Self {
+        self.pii = Some(Arc::new(PiiKeys {
+            master,
+            ciphers: RwLock::new(HashMap::new()),
+        }));
+        self
+    }
+
+    /// The tenant's PII cipher, creating its data key on first use.
+    ///
+    /// Without a master key this is a cipher that stores plain text.
+    pub async fn pii_cipher(&self, tenant_id: &str) -> Result<PiiCipher, CloudError> {
+        let Some(keys) = &self.pii else {
+            return Ok(PiiCipher::disabled());
+        };
+        if let Some(cipher) = keys.ciphers.read().expect("PII key cache poisoned").get(tenant_id) {
+            return Ok(cipher.clone());
+        }
+
+        sqlx::query(
+            "INSERT INTO tenant_data_keys (tenant_id, wrapped_key) VALUES ($1, $2) \
+             ON CONFLICT (tenant_id) DO NOTHING",
+        )
+        .bind(tenant_id)
+        .bind(keys.master.wrap_data_key(tenant_id, &random_key()?)?)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        // The stored key, whether just created or created earlier
+        let wrapped: String =
+            sqlx::query_scalar("SELECT wrapped_key FROM tenant_data_keys WHERE tenant_id = $1")
+                .bind(tenant_id)
+                .fetch_one(&self.pool)
+                .await
+                .map_err(|e| CloudError::Database(e.to_string()))?;
+        let cipher = PiiCipher::new(&keys.master.unwrap_data_key(tenant_id, &wrapped)?);
+
+        keys.ciphers
+            .write()
+            .expect("PII key cache poisoned")
+            .insert(tenant_id.to_string(), cipher.clone());
+        Ok(cipher)
+    }
+
+    /// Encrypt and hash customer data still stored in plain text.
+    ///
+    /// Without a master key values stay in plain text and only missing
+    /// lookup hashes are filled in. Returns the number of values updated.
+    pub async fn encrypt_existing_pii(&self) -> Result<u64, CloudError> {
+        let encrypting = self.pii.is_some();
+        let prefix = format!("{}%", CIPHERTEXT_PREFIX);
+        let mut updated = 0;
+
+        let accounts: Vec<(String, String, String)> = sqlx::query_as(
+            r#"
+            SELECT id, tenant_id, customer_phone
+            FROM store_credit_accounts
+            WHERE customer_phone IS NOT NULL
+              AND (CASE WHEN $1 THEN customer_phone NOT LIKE $2 ELSE customer_phone_hash IS NULL END)
+            "#
+        )
+        .bind(encrypting)
+        .bind(&prefix)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        for (id, tenant_id, phone) in accounts {
+            let cipher = self.pii_cipher(&tenant_id).await?;
+            sqlx::query(
+                "UPDATE store_credit_accounts SET customer_phone = $2, customer_phone_hash = $3 WHERE id = $1"
+            )
+            .bind(&id)
+            .bind(cipher.encrypt(PiiField::Phone, &phone)?)
+            .bind(cipher.blind_index(PiiField::Phone, &phone))
+            .execute(&self.pool)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+            updated += 1;
+        }
+
+        if encrypting {
+            let erasures: Vec<(String, String, Option<String>, Option<String>)> = sqlx::query_as(
+                r#"
+                SELECT request_id, tenant_id, phone, email
+                FROM customer_erasures
+                WHERE phone NOT LIKE $1 OR email NOT LIKE $1
+                "#
+            )
+            .bind(&prefix)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+            for (request_id, tenant_id, phone, email) in erasures {
+                let cipher = self.pii_cipher(&tenant_id).await?;
+                // Encrypting leaves values that already are encrypted alone
+                let phone = cipher.decrypt_opt(PiiField::Phone, phone)?;
+                let email = cipher.decrypt_opt(PiiField::Email, email)?;
+                sqlx::query("UPDATE customer_erasures SET phone = $2, email = $3 WHERE request_id = $1")
+                    .bind(&request_id)
+                    .bind(cipher.encrypt_opt(PiiField::Phone, phone.as_deref())?)
+                    .bind(cipher.encrypt_opt(PiiField::Email, email.as_deref())?)
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| CloudError::Database(e.to_string()))?;
+                updated += 1;
+            }
+        }
+
+        if updated > 0 {
+            info!(updated, encrypting, "Customer data brought up to date");
+        }
+        Ok(updated)
     }
 
     /// Run database migrations.
     pub async fn run_migrations(&self) -> Result<(), CloudError> {
-        sqlx::migrate!("../../migrations/postgres")
+        MIGRATOR
             .run(&self.pool)
             .await
             .map_err(|e| CloudError::Migration(e.to_string()))?;
@@ -42,6 +189,23 @@ impl Database {
         &self.pool
     }
 
+    /// Latest migration version built into this binary.
+    pub fn expected_migration_version() -> i64 {
+        MIGRATOR.iter().map(|m| m.version).max().unwrap_or(0)
+    }
+
+    /// Latest migration version successfully applied to the database.
+    pub async fn applied_migration_version(&self) -> Result<Option<i64>, CloudError> {
+        let version: Option<i64> = sqlx::query_scalar(
+            "SELECT MAX(version) FROM _sqlx_migrations WHERE success = true",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(version)
+    }
+
     // =========================================================================
     // Store Operations
     // =========================================================================
@@ -108,11 +272,12 @@ impl Database {
             INSERT INTO sales (
                 id, store_id, device_id, tenant_id, receipt_number,
                 subtotal_cents, tax_amount_cents, discount_amount_cents, total_cents,
-                status, created_at, completed_at
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
-            ON CONFLICT (id) DO UPDATE SET
+                status, created_at, completed_at, correlation_id, station_number
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            ON CONFLICT (id, created_at) DO UPDATE SET
                 status = EXCLUDED.status,
                 completed_at = EXCLUDED.completed_at,
+                correlation_id = COALESCE(EXCLUDED.correlation_id, sales.correlation_id),
                 updated_at = NOW()
             "#
         )
@@ -126,8 +291,10 @@ impl Database {
         .bind(sale.discount_amount_cents)
         .bind(sale.total_cents)
         .bind(&sale.status)
-        .bind(&sale.created_at)
-        .bind(&sale.completed_at)
+        .bind(sale.created_at)
+        .bind(sale.completed_at)
+        .bind(&sale.correlation_id)
+        .bind(sale.station_number)
         .execute(&self.pool)
         .await
         .map_err(|e| CloudError::Database(e.to_string()))?;
@@ -136,15 +303,23 @@ impl Database {
     }
 
     /// Insert a sale item.
+    ///
+    /// The item is stored in the month partition of its sale (created_at is
+    /// copied from the sale, or the upload time if the sale has not arrived
+    /// yet). Items already stored under any created_at are skipped.
     pub async fn insert_sale_item(&self, item: &SaleItemRecord) -> Result<(), CloudError> {
         sqlx::query(
             r#"
             INSERT INTO sale_items (
                 id, sale_id, product_id, sku, name,
                 quantity, unit_price_cents, line_total_cents,
-                tax_amount_cents, tax_rate_bps
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-            ON CONFLICT (id) DO NOTHING
+                tax_amount_cents, tax_rate_bps, tracking_kind, tracking_codes,
+                base_price_cents, created_at
+            )
+            SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13,
+                COALESCE((SELECT created_at FROM sales WHERE id = $2 LIMIT 1), NOW())
+            WHERE NOT EXISTS (SELECT 1 FROM sale_items WHERE id = $1)
+            ON CONFLICT (id, created_at) DO NOTHING
             "#
         )
         .bind(&item.id)
@@ -157,6 +332,9 @@ impl Database {
         .bind(item.line_total_cents)
         .bind(item.tax_amount_cents)
         .bind(item.tax_rate_bps)
+        .bind(&item.tracking_kind)
+        .bind(&item.tracking_codes)
+        .bind(item.base_price_cents)
         .execute(&self.pool)
         .await
         .map_err(|e| CloudError::Database(e.to_string()))?;
@@ -173,7 +351,7 @@ impl Database {
                 amount_cents, change_given_cents, reference, authorization_code,
                 created_at
             ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
-            ON CONFLICT (id) DO NOTHING
+            ON CONFLICT (id, created_at) DO NOTHING
             "#
         )
         .bind(&payment.id)
@@ -185,7 +363,7 @@ impl Database {
         .bind(payment.change_given_cents)
         .bind(&payment.reference)
         .bind(&payment.authorization_code)
-        .bind(&payment.created_at)
+        .bind(payment.created_at)
         .execute(&self.pool)
         .await
         .map_err(|e| CloudError::Database(e.to_string()))?;
@@ -193,6 +371,71 @@ impl Database {
         Ok(())
     }
 
+    /// A store's sale by receipt number (the latest, should a receipt
+    /// number repeat), with its lines and payments.
+    pub async fn get_sale_by_receipt(
+        &self,
+        store_id: &str,
+        receipt_number: &str,
+    ) -> Result<Option<(SaleRecord, Vec<SaleItemRecord>, Vec<PaymentRecord>)>, CloudError> {
+        let sale = sqlx::query_as::<_, SaleRecord>(
+            r#"
+            SELECT
+                id, store_id, device_id, tenant_id, receipt_number, station_number,
+                subtotal_cents, tax_amount_cents, discount_amount_cents, total_cents,
+                status, created_at, completed_at, correlation_id
+            FROM sales
+            WHERE store_id = $1 AND receipt_number = $2
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#
+        )
+        .bind(store_id)
+        .bind(receipt_number)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let Some(sale) = sale else {
+            return Ok(None);
+        };
+
+        let items = sqlx::query_as::<_, SaleItemRecord>(
+            r#"
+            SELECT
+                id, sale_id, product_id, sku, name,
+                quantity, unit_price_cents, line_total_cents,
+                tax_amount_cents, tax_rate_bps, tracking_kind, tracking_codes,
+                base_price_cents
+            FROM sale_items
+            WHERE sale_id = $1
+            ORDER BY id
+            "#
+        )
+        .bind(&sale.id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let payments = sqlx::query_as::<_, PaymentRecord>(
+            r#"
+            SELECT
+                id, sale_id, store_id, tenant_id, method,
+                amount_cents, change_given_cents, reference, authorization_code,
+                created_at
+            FROM payments
+            WHERE sale_id = $1
+            ORDER BY created_at
+            "#
+        )
+        .bind(&sale.id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(Some((sale, items, payments)))
+    }
+
     /// Apply an inventory delta (CRDT merge).
     pub async fn apply_inventory_delta(&self, delta: &InventoryDeltaRecord) -> Result<(), CloudError> {
         // Insert the delta record
@@ -200,8 +443,8 @@ impl Database {
             r#"
             INSERT INTO inventory_deltas (
                 id, store_id, device_id, tenant_id, product_id,
-                delta, reason, reference_id, created_at
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                delta, reason, reference_id, value_cents, created_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             ON CONFLICT (id) DO NOTHING
             "#
         )
@@ -213,7 +456,8 @@ impl Database {
         .bind(delta.delta)
         .bind(&delta.reason)
         .bind(&delta.reference_id)
-        .bind(&delta.created_at)
+        .bind(delta.value_cents)
+        .bind(delta.created_at)
         .execute(&self.pool)
         .await
         .map_err(|e| CloudError::Database(e.to_string()))?;
@@ -239,145 +483,2899 @@ impl Database {
         Ok(())
     }
 
-    /// Get pending product updates for a store.
-    pub async fn get_pending_product_updates(
+    /// Apply a store transfer uploaded by one of its two stores.
+    ///
+    /// New transfers are inserted. Existing transfers are only updated while
+    /// still `IN_TRANSIT` and when the incoming version is newer, so the
+    /// first store to close a transfer (receive or cancel) wins.
+    ///
+    /// Returns `true` if the transfer was stored (and will be routed).
+    pub async fn upsert_store_transfer(
+        &self,
+        transfer: &StoreTransferRecord,
+        items: &[StoreTransferItemRecord],
+    ) -> Result<bool, CloudError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO store_transfers (
+                id, tenant_id, transfer_number, from_store_id, to_store_id,
+                status, notes, created_by, received_by,
+                created_at, updated_at, received_at, cancelled_at, version
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            ON CONFLICT (id) DO UPDATE SET
+                status = EXCLUDED.status,
+                notes = EXCLUDED.notes,
+                received_by = EXCLUDED.received_by,
+                updated_at = EXCLUDED.updated_at,
+                received_at = EXCLUDED.received_at,
+                cancelled_at = EXCLUDED.cancelled_at,
+                version = EXCLUDED.version,
+                route_seq = nextval('store_transfer_route_seq')
+            WHERE store_transfers.status = 'IN_TRANSIT'
+              AND EXCLUDED.version > store_transfers.version
+            "#
+        )
+        .bind(&transfer.id)
+        .bind(&transfer.tenant_id)
+        .bind(&transfer.transfer_number)
+        .bind(&transfer.from_store_id)
+        .bind(&transfer.to_store_id)
+        .bind(&transfer.status)
+        .bind(&transfer.notes)
+        .bind(&transfer.created_by)
+        .bind(&transfer.received_by)
+        .bind(transfer.created_at)
+        .bind(transfer.updated_at)
+        .bind(transfer.received_at)
+        .bind(transfer.cancelled_at)
+        .bind(transfer.version)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query("DELETE FROM store_transfer_items WHERE transfer_id = $1")
+            .bind(&transfer.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        for item in items {
+            sqlx::query(
+                r#"
+                INSERT INTO store_transfer_items (
+                    id, transfer_id, product_id, sku, name,
+                    quantity_sent, quantity_received
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+                "#
+            )
+            .bind(&item.id)
+            .bind(&item.transfer_id)
+            .bind(&item.product_id)
+            .bind(&item.sku)
+            .bind(&item.name)
+            .bind(item.quantity_sent)
+            .bind(item.quantity_received)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(true)
+    }
+
+    /// Get transfers involving a store that changed after `since_seq`.
+    pub async fn get_pending_store_transfers(
         &self,
         store_id: &str,
-        since_version: i64,
+        since_seq: i64,
         limit: i32,
-    ) -> Result<Vec<ProductRecord>, CloudError> {
+    ) -> Result<Vec<(StoreTransferRecord, Vec<StoreTransferItemRecord>)>, CloudError> {
         let limit = if limit <= 0 { 100 } else { limit };
-        
-        let results = sqlx::query_as::<_, ProductRecord>(
+
+        let transfers = sqlx::query_as::<_, StoreTransferRecord>(
             r#"
-            SELECT 
-                id, tenant_id, sku, name, barcode,
-                price_cents, cost_cents, tax_rate_id, tax_rate_bps,
-                track_inventory, current_stock, low_stock_threshold,
-                is_active, category, department,
-                created_at, updated_at, version
-            FROM products
-            WHERE tenant_id = (SELECT tenant_id FROM stores WHERE id = $1)
-              AND version > $2
-            ORDER BY version ASC
+            SELECT
+                id, tenant_id, transfer_number, from_store_id, to_store_id,
+                status, notes, created_by, received_by,
+                created_at, updated_at, received_at, cancelled_at,
+                version, route_seq
+            FROM store_transfers
+            WHERE (from_store_id = $1 OR to_store_id = $1)
+              AND route_seq > $2
+            ORDER BY route_seq ASC
             LIMIT $3
             "#
         )
         .bind(store_id)
-        .bind(since_version)
+        .bind(since_seq)
         .bind(limit)
         .fetch_all(&self.pool)
         .await
         .map_err(|e| CloudError::Database(e.to_string()))?;
 
+        let mut results = Vec::with_capacity(transfers.len());
+        for transfer in transfers {
+            let items = sqlx::query_as::<_, StoreTransferItemRecord>(
+                r#"
+                SELECT id, transfer_id, product_id, sku, name, quantity_sent, quantity_received
+                FROM store_transfer_items
+                WHERE transfer_id = $1
+                ORDER BY sku, id
+                "#
+            )
+            .bind(&transfer.id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+            results.push((transfer, items));
+        }
+
         Ok(results)
     }
 
-    /// Update sync cursor for a store.
-    pub async fn update_sync_cursor(
+    /// Merge a store credit account uploaded by one of the tenant's stores.
+    ///
+    /// Customer details are replaced only by a newer version; entries are
+    /// appended if new and never changed. Any change moves the account to
+    /// the end of the download sequence so every store receives it.
+    ///
+    /// Returns `true` if anything changed.
+    pub async fn upsert_store_credit(
         &self,
-        store_id: &str,
-        stream: &str,
-        position: i64,
-    ) -> Result<(), CloudError> {
-        sqlx::query(
+        account: &StoreCreditRecord,
+        entries: &[StoreCreditEntryRecord],
+    ) -> Result<bool, CloudError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let owner: Option<String> =
+            sqlx::query_scalar("SELECT tenant_id FROM store_credit_accounts WHERE id = $1")
+                .bind(&account.id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        if owner.as_deref().is_some_and(|t| t != account.tenant_id) {
+            return Err(CloudError::Unauthorized(format!(
+                "Store credit {} belongs to another tenant",
+                account.id
+            )));
+        }
+
+        let cipher = self.pii_cipher(&account.tenant_id).await?;
+        let phone = account.customer_phone.as_deref();
+
+        let account_changed = sqlx::query(
             r#"
-            INSERT INTO sync_cursors (store_id, stream, position, updated_at)
-            VALUES ($1, $2, $3, NOW())
-            ON CONFLICT (store_id, stream) DO UPDATE SET
-                position = EXCLUDED.position,
-                updated_at = NOW()
+            INSERT INTO store_credit_accounts (
+                id, tenant_id, credit_number, customer_name, customer_phone,
+                customer_phone_hash, created_at, updated_at, version
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (id) DO UPDATE SET
+                customer_name = EXCLUDED.customer_name,
+                customer_phone = EXCLUDED.customer_phone,
+                customer_phone_hash = EXCLUDED.customer_phone_hash,
+                updated_at = EXCLUDED.updated_at,
+                version = EXCLUDED.version
+            WHERE EXCLUDED.version > store_credit_accounts.version
             "#
         )
-        .bind(store_id)
-        .bind(stream)
-        .bind(position)
-        .execute(&self.pool)
+        .bind(&account.id)
+        .bind(&account.tenant_id)
+        .bind(&account.credit_number)
+        .bind(&account.customer_name)
+        .bind(cipher.encrypt_opt(PiiField::Phone, phone)?)
+        .bind(cipher.blind_index_opt(PiiField::Phone, phone))
+        .bind(account.created_at)
+        .bind(account.updated_at)
+        .bind(account.version)
+        .execute(&mut *tx)
         .await
-        .map_err(|e| CloudError::Database(e.to_string()))?;
+        .map_err(|e| CloudError::Database(e.to_string()))?
+        .rows_affected()
+            > 0;
 
-        Ok(())
+        let mut new_entries = 0;
+        for entry in entries {
+            new_entries += sqlx::query(
+                r#"
+                INSERT INTO store_credit_entries (
+                    id, account_id, kind, amount_cents, sale_id, device_id, created_at
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT (id) DO NOTHING
+                "#
+            )
+            .bind(&entry.id)
+            .bind(&account.id)
+            .bind(&entry.kind)
+            .bind(entry.amount_cents)
+            .bind(&entry.sale_id)
+            .bind(&entry.device_id)
+            .bind(entry.created_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?
+            .rows_affected();
+        }
+
+        let changed = account_changed || new_entries > 0;
+        if changed {
+            sqlx::query(
+                "UPDATE store_credit_accounts SET route_seq = nextval('store_credit_route_seq') WHERE id = $1"
+            )
+            .bind(&account.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(changed)
     }
 
-    /// Get sync cursor for a store and stream.
-    pub async fn get_sync_cursor(
+    /// Upserts a supplier with its price list.
+    ///
+    /// The supplier and all of its items are replaced only by a newer
+    /// version.
+    ///
+    /// Returns `true` if the supplier changed.
+    pub async fn upsert_supplier(
         &self,
-        store_id: &str,
-        stream: &str,
-    ) -> Result<Option<i64>, CloudError> {
-        let result = sqlx::query_scalar::<_, i64>(
+        supplier: &SupplierRecord,
+        items: &[SupplierItemRecord],
+    ) -> Result<bool, CloudError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let owner: Option<String> =
+            sqlx::query_scalar("SELECT tenant_id FROM suppliers WHERE id = $1")
+                .bind(&supplier.id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        if owner.as_deref().is_some_and(|t| t != supplier.tenant_id) {
+            return Err(CloudError::Unauthorized(format!(
+                "Supplier {} belongs to another tenant",
+                supplier.id
+            )));
+        }
+
+        let changed = sqlx::query(
             r#"
-            SELECT position FROM sync_cursors
-            WHERE store_id = $1 AND stream = $2
+            INSERT INTO suppliers (
+                id, tenant_id, name, contact_name, phone, email, is_active,
+                created_at, updated_at, version
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (id) DO UPDATE SET
+                name = EXCLUDED.name,
+                contact_name = EXCLUDED.contact_name,
+                phone = EXCLUDED.phone,
+                email = EXCLUDED.email,
+                is_active = EXCLUDED.is_active,
+                updated_at = EXCLUDED.updated_at,
+                version = EXCLUDED.version
+            WHERE EXCLUDED.version > suppliers.version
             "#
         )
-        .bind(store_id)
-        .bind(stream)
-        .fetch_optional(&self.pool)
+        .bind(&supplier.id)
+        .bind(&supplier.tenant_id)
+        .bind(&supplier.name)
+        .bind(&supplier.contact_name)
+        .bind(&supplier.phone)
+        .bind(&supplier.email)
+        .bind(supplier.is_active)
+        .bind(supplier.created_at)
+        .bind(supplier.updated_at)
+        .bind(supplier.version)
+        .execute(&mut *tx)
         .await
-        .map_err(|e| CloudError::Database(e.to_string()))?;
+        .map_err(|e| CloudError::Database(e.to_string()))?
+        .rows_affected()
+            > 0;
 
-        Ok(result)
+        if changed {
+            sqlx::query("DELETE FROM supplier_items WHERE supplier_id = $1")
+                .bind(&supplier.id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CloudError::Database(e.to_string()))?;
+
+            for item in items {
+                sqlx::query(
+                    r#"
+                    INSERT INTO supplier_items (supplier_id, product_id, supplier_sku, cost_cents)
+                    VALUES ($1, $2, $3, $4)
+                    "#
+                )
+                .bind(&supplier.id)
+                .bind(&item.product_id)
+                .bind(&item.supplier_sku)
+                .bind(item.cost_cents)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CloudError::Database(e.to_string()))?;
+            }
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(changed)
     }
 
-    // =========================================================================
-    // Config Operations
-    // =========================================================================
+    /// Create or change a coupon from the back office.
+    ///
+    /// The stored version is bumped on every change and the coupon moves to
+    /// the end of the download sequence, so every store of the tenant
+    /// receives it. Redemptions are not touched.
+    ///
+    /// Returns the stored coupon.
+    pub async fn upsert_coupon(&self, coupon: &CouponRecord) -> Result<CouponRecord, CloudError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
 
-    /// Get store configuration.
-    pub async fn get_store_config(&self, store_id: &str) -> Result<Option<StoreConfigRecord>, CloudError> {
-        let result = sqlx::query_as::<_, StoreConfigRecord>(
+        let owner: Option<String> =
+            sqlx::query_scalar("SELECT tenant_id FROM coupons WHERE id = $1")
+                .bind(&coupon.id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        if owner.as_deref().is_some_and(|t| t != coupon.tenant_id) {
+            return Err(CloudError::Unauthorized(format!(
+                "Coupon {} belongs to another tenant",
+                coupon.id
+            )));
+        }
+
+        let taken: Option<String> = sqlx::query_scalar(
+            "SELECT id FROM coupons WHERE tenant_id = $1 AND code = $2 AND id <> $3"
+        )
+        .bind(&coupon.tenant_id)
+        .bind(&coupon.code)
+        .bind(&coupon.id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        if taken.is_some() {
+            return Err(CloudError::Conflict(format!(
+                "Coupon code {} is already in use",
+                coupon.code
+            )));
+        }
+
+        let stored = sqlx::query_as::<_, CouponRecord>(
             r#"
-            SELECT 
-                store_id, tenant_id, store_name, address, city, state,
-                postal_code, country, timezone, currency, tax_mode,
-                allow_negative_inventory, receipt_header, receipt_footer,
-                sync_batch_size, sync_interval_secs
-            FROM store_configs
-            WHERE store_id = $1
+            INSERT INTO coupons (
+                id, tenant_id, code, description, kind, value,
+                min_subtotal_cents, product_ids, starts_at, expires_at,
+                max_uses, active, created_at, updated_at, version
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, 1)
+            ON CONFLICT (id) DO UPDATE SET
+                code = EXCLUDED.code,
+                description = EXCLUDED.description,
+                kind = EXCLUDED.kind,
+                value = EXCLUDED.value,
+                min_subtotal_cents = EXCLUDED.min_subtotal_cents,
+                product_ids = EXCLUDED.product_ids,
+                starts_at = EXCLUDED.starts_at,
+                expires_at = EXCLUDED.expires_at,
+                max_uses = EXCLUDED.max_uses,
+                active = EXCLUDED.active,
+                updated_at = EXCLUDED.updated_at,
+                version = coupons.version + 1,
+                route_seq = nextval('coupon_route_seq')
+            RETURNING
+                id, tenant_id, code, description, kind, value,
+                min_subtotal_cents, product_ids, starts_at, expires_at,
+                max_uses, active, created_at, updated_at, version, route_seq
             "#
         )
-        .bind(store_id)
-        .fetch_optional(&self.pool)
+        .bind(&coupon.id)
+        .bind(&coupon.tenant_id)
+        .bind(&coupon.code)
+        .bind(&coupon.description)
+        .bind(&coupon.kind)
+        .bind(coupon.value)
+        .bind(coupon.min_subtotal_cents)
+        .bind(&coupon.product_ids)
+        .bind(coupon.starts_at)
+        .bind(coupon.expires_at)
+        .bind(coupon.max_uses)
+        .bind(coupon.active)
+        .bind(coupon.created_at)
+        .bind(coupon.updated_at)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| CloudError::Database(e.to_string()))?;
 
-        Ok(result)
+        tx.commit()
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(stored)
     }
-}
 
-// =============================================================================
-// Record Types
-// =============================================================================
+    /// Merge the redemptions of a coupon uploaded by one of the tenant's
+    /// stores.
+    ///
+    /// Only redemptions are taken from a store; the definition is the
+    /// back office's. New redemptions move the coupon to the end of the
+    /// download sequence so every store learns of the use.
+    ///
+    /// Returns the number of new redemptions and the coupon's total uses.
+    pub async fn add_coupon_redemptions(
+        &self,
+        tenant_id: &str,
+        coupon_id: &str,
+        redemptions: &[CouponRedemptionRecord],
+    ) -> Result<(u64, i64), CloudError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
 
-#[derive(Debug, Clone, sqlx::FromRow)]
-pub struct StoreRecord {
-    pub id: String,
-    pub tenant_id: String,
-    pub name: String,
-    pub api_key_hash: String,
-    pub is_active: bool,
-    pub created_at: DateTime<Utc>,
-    pub updated_at: DateTime<Utc>,
-}
+        // Lock the coupon so concurrent uploads count uses in turn
+        let owner: Option<String> =
+            sqlx::query_scalar("SELECT tenant_id FROM coupons WHERE id = $1 FOR UPDATE")
+                .bind(coupon_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| CloudError::Database(e.to_string()))?;
 
-#[derive(Debug, Clone)]
-pub struct SaleRecord {
-    pub id: String,
-    pub store_id: String,
-    pub device_id: String,
-    pub tenant_id: String,
-    pub receipt_number: String,
-    pub subtotal_cents: i64,
-    pub tax_amount_cents: i64,
-    pub discount_amount_cents: i64,
+        match owner {
+            None => return Err(CloudError::NotFound(format!("Coupon {}", coupon_id))),
+            Some(owner) if owner != tenant_id => {
+                return Err(CloudError::Unauthorized(format!(
+                    "Coupon {} belongs to another tenant",
+                    coupon_id
+                )))
+            }
+            Some(_) => {}
+        }
+
+        let mut new_redemptions = 0;
+        for redemption in redemptions {
+            new_redemptions += sqlx::query(
+                r#"
+                INSERT INTO coupon_redemptions (
+                    id, coupon_id, sale_id, store_id, device_id, discount_cents, redeemed_at
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT (id) DO NOTHING
+                "#
+            )
+            .bind(&redemption.id)
+            .bind(coupon_id)
+            .bind(&redemption.sale_id)
+            .bind(&redemption.store_id)
+            .bind(&redemption.device_id)
+            .bind(redemption.discount_cents)
+            .bind(redemption.redeemed_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?
+            .rows_affected();
+        }
+
+        if new_redemptions > 0 {
+            sqlx::query("UPDATE coupons SET route_seq = nextval('coupon_route_seq') WHERE id = $1")
+                .bind(coupon_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CloudError::Database(e.to_string()))?;
+        }
+
+        let uses: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM coupon_redemptions WHERE coupon_id = $1")
+                .bind(coupon_id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok((new_redemptions, uses))
+    }
+
+    /// Get the tenant's coupons that changed after `since_seq`, with their
+    /// redemptions.
+    pub async fn get_pending_coupons(
+        &self,
+        tenant_id: &str,
+        since_seq: i64,
+        limit: i32,
+    ) -> Result<Vec<(CouponRecord, Vec<CouponRedemptionRecord>)>, CloudError> {
+        let limit = if limit <= 0 { 100 } else { limit };
+
+        let coupons = sqlx::query_as::<_, CouponRecord>(
+            r#"
+            SELECT
+                id, tenant_id, code, description, kind, value,
+                min_subtotal_cents, product_ids, starts_at, expires_at,
+                max_uses, active, created_at, updated_at, version, route_seq
+            FROM coupons
+            WHERE tenant_id = $1
+              AND route_seq > $2
+            ORDER BY route_seq ASC
+            LIMIT $3
+            "#
+        )
+        .bind(tenant_id)
+        .bind(since_seq)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let mut results = Vec::with_capacity(coupons.len());
+        for coupon in coupons {
+            let redemptions = self.get_coupon_redemptions(&coupon.id).await?;
+            results.push((coupon, redemptions));
+        }
+
+        Ok(results)
+    }
+
+    /// Get the redemptions of a coupon, oldest first.
+    pub async fn get_coupon_redemptions(
+        &self,
+        coupon_id: &str,
+    ) -> Result<Vec<CouponRedemptionRecord>, CloudError> {
+        sqlx::query_as::<_, CouponRedemptionRecord>(
+            r#"
+            SELECT id, coupon_id, sale_id, store_id, device_id, discount_cents, redeemed_at
+            FROM coupon_redemptions
+            WHERE coupon_id = $1
+            ORDER BY redeemed_at, id
+            "#
+        )
+        .bind(coupon_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))
+    }
+
+    /// Merge a loyalty account uploaded by one of the tenant's stores.
+    ///
+    /// Entries are appended if new and never changed. Any change moves the
+    /// account to the end of the download sequence so every store receives
+    /// it.
+    ///
+    /// Returns `true` if anything changed.
+    pub async fn upsert_loyalty(
+        &self,
+        account: &LoyaltyRecord,
+        entries: &[LoyaltyEntryRecord],
+    ) -> Result<bool, CloudError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let changed = merge_loyalty(&mut tx, account, entries).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(changed)
+    }
+
+    /// Spend loyalty rewards if, and only if, the tenant's ledger covers
+    /// them.
+    ///
+    /// The store's copy of the account is merged first, then the account is
+    /// locked, so redemptions from different stores are checked in turn.
+    /// `redemption.reward_cents` is negative; replaying one already on the
+    /// ledger approves it again without spending twice.
+    ///
+    /// Returns whether the redemption is on the ledger and the rewards
+    /// balance after it (the current balance if rejected).
+    pub async fn redeem_loyalty_reward(
+        &self,
+        account: &LoyaltyRecord,
+        entries: &[LoyaltyEntryRecord],
+        redemption: &LoyaltyEntryRecord,
+    ) -> Result<(bool, i64), CloudError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        merge_loyalty(&mut tx, account, entries).await?;
+
+        sqlx::query("SELECT id FROM loyalty_accounts WHERE id = $1 FOR UPDATE")
+            .bind(&account.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let replayed: Option<String> =
+            sqlx::query_scalar("SELECT id FROM loyalty_entries WHERE id = $1")
+                .bind(&redemption.id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let balance: i64 = sqlx::query_scalar(
+            "SELECT COALESCE(SUM(reward_cents), 0)::BIGINT FROM loyalty_entries WHERE account_id = $1"
+        )
+        .bind(&account.id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let approved = replayed.is_some() || balance + redemption.reward_cents >= 0;
+        let balance = if replayed.is_none() && approved {
+            merge_loyalty(&mut tx, account, std::slice::from_ref(redemption)).await?;
+            balance + redemption.reward_cents
+        } else {
+            balance
+        };
+
+        // Commit even when rejected: the merged entries are kept
+        tx.commit()
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok((approved, balance))
+    }
+
+    /// Get the tenant's loyalty accounts that changed after `since_seq`,
+    /// with their ledgers.
+    pub async fn get_pending_loyalty(
+        &self,
+        tenant_id: &str,
+        since_seq: i64,
+        limit: i32,
+    ) -> Result<Vec<(LoyaltyRecord, Vec<LoyaltyEntryRecord>)>, CloudError> {
+        let limit = if limit <= 0 { 100 } else { limit };
+
+        let accounts = sqlx::query_as::<_, LoyaltyRecord>(
+            r#"
+            SELECT id, tenant_id, member_number, created_at, updated_at, version, route_seq
+            FROM loyalty_accounts
+            WHERE tenant_id = $1
+              AND route_seq > $2
+            ORDER BY route_seq ASC
+            LIMIT $3
+            "#
+        )
+        .bind(tenant_id)
+        .bind(since_seq)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let mut results = Vec::with_capacity(accounts.len());
+        for account in accounts {
+            let entries = sqlx::query_as::<_, LoyaltyEntryRecord>(
+                r#"
+                SELECT id, account_id, kind, points, reward_cents, sale_id, device_id, created_at
+                FROM loyalty_entries
+                WHERE account_id = $1
+                ORDER BY created_at, id
+                "#
+            )
+            .bind(&account.id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+            results.push((account, entries));
+        }
+
+        Ok(results)
+    }
+
+    /// Current balance of a store credit account.
+    pub async fn get_store_credit_balance(&self, account_id: &str) -> Result<i64, CloudError> {
+        let balance: i64 = sqlx::query_scalar(
+            "SELECT COALESCE(SUM(amount_cents), 0)::BIGINT FROM store_credit_entries WHERE account_id = $1"
+        )
+        .bind(account_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(balance)
+    }
+
+    /// Get the tenant's store credit accounts that changed after `since_seq`.
+    pub async fn get_pending_store_credits(
+        &self,
+        tenant_id: &str,
+        since_seq: i64,
+        limit: i32,
+    ) -> Result<Vec<(StoreCreditRecord, Vec<StoreCreditEntryRecord>)>, CloudError> {
+        let limit = if limit <= 0 { 100 } else { limit };
+
+        let accounts = sqlx::query_as::<_, StoreCreditRecord>(
+            r#"
+            SELECT
+                id, tenant_id, credit_number, customer_name, customer_phone,
+                created_at, updated_at, version, route_seq
+            FROM store_credit_accounts
+            WHERE tenant_id = $1
+              AND route_seq > $2
+            ORDER BY route_seq ASC
+            LIMIT $3
+            "#
+        )
+        .bind(tenant_id)
+        .bind(since_seq)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let cipher = self.pii_cipher(tenant_id).await?;
+        let mut results = Vec::with_capacity(accounts.len());
+        for mut account in accounts {
+            account.customer_phone = cipher.decrypt_opt(PiiField::Phone, account.customer_phone)?;
+            let entries = sqlx::query_as::<_, StoreCreditEntryRecord>(
+                r#"
+                SELECT id, account_id, kind, amount_cents, sale_id, device_id, created_at
+                FROM store_credit_entries
+                WHERE account_id = $1
+                ORDER BY created_at, id
+                "#
+            )
+            .bind(&account.id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+            results.push((account, entries));
+        }
+
+        Ok(results)
+    }
+
+    /// Record a customer erasure and anonymize the tenant's store credit
+    /// accounts held for the customer's phone.
+    ///
+    /// Ledger entries and balances are kept. Anonymized accounts get a new
+    /// version and move to the end of the download sequence, so stores
+    /// replace their copies. A request already recorded changes nothing.
+    ///
+    /// Returns `None` if the request was already recorded, otherwise the
+    /// number of accounts anonymized.
+    pub async fn forget_customer(
+        &self,
+        erasure: &CustomerErasureRecord,
+    ) -> Result<Option<i32>, CloudError> {
+        let cipher = self.pii_cipher(&erasure.tenant_id).await?;
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO customer_erasures (
+                request_id, tenant_id, business_customer_id, phone, email,
+                reason, requested_by, requested_at, origin_store_id
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (request_id) DO NOTHING
+            "#
+        )
+        .bind(&erasure.request_id)
+        .bind(&erasure.tenant_id)
+        .bind(&erasure.business_customer_id)
+        .bind(cipher.encrypt_opt(PiiField::Phone, erasure.phone.as_deref())?)
+        .bind(cipher.encrypt_opt(PiiField::Email, erasure.email.as_deref())?)
+        .bind(&erasure.reason)
+        .bind(&erasure.requested_by)
+        .bind(erasure.requested_at)
+        .bind(&erasure.origin_store_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?
+        .rows_affected()
+            > 0;
+
+        if !inserted {
+            return Ok(None);
+        }
+
+        let mut accounts = 0;
+        if let Some(phone) = &erasure.phone {
+            accounts = sqlx::query(
+                r#"
+                UPDATE store_credit_accounts SET
+                    customer_name = $3,
+                    customer_phone = NULL,
+                    customer_phone_hash = NULL,
+                    updated_at = NOW(),
+                    version = version + 1,
+                    route_seq = nextval('store_credit_route_seq')
+                WHERE tenant_id = $1 AND customer_phone_hash = $2
+                "#
+            )
+            .bind(&erasure.tenant_id)
+            .bind(cipher.blind_index(PiiField::Phone, phone))
+            .bind(ERASED_CUSTOMER_NAME)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?
+            .rows_affected() as i32;
+
+            sqlx::query("UPDATE customer_erasures SET store_credit_accounts = $2 WHERE request_id = $1")
+                .bind(&erasure.request_id)
+                .bind(accounts)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CloudError::Database(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(Some(accounts))
+    }
+
+    /// Get the tenant's customer erasures recorded after `since_seq`.
+    pub async fn get_pending_erasures(
+        &self,
+        tenant_id: &str,
+        since_seq: i64,
+        limit: i32,
+    ) -> Result<Vec<CustomerErasureRecord>, CloudError> {
+        let limit = if limit <= 0 { 100 } else { limit };
+
+        let records = sqlx::query_as::<_, CustomerErasureRecord>(
+            r#"
+            SELECT
+                request_id, tenant_id, business_customer_id, phone, email,
+                reason, requested_by, requested_at, origin_store_id,
+                store_credit_accounts, erased_at, route_seq
+            FROM customer_erasures
+            WHERE tenant_id = $1
+              AND route_seq > $2
+            ORDER BY route_seq ASC
+            LIMIT $3
+            "#
+        )
+        .bind(tenant_id)
+        .bind(since_seq)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let cipher = self.pii_cipher(tenant_id).await?;
+        records
+            .into_iter()
+            .map(|mut record| {
+                record.phone = cipher.decrypt_opt(PiiField::Phone, record.phone)?;
+                record.email = cipher.decrypt_opt(PiiField::Email, record.email)?;
+                Ok(record)
+            })
+            .collect()
+    }
+
+    /// Get pending product updates for a store.
+    ///
+    /// `price_cents` is the store's price: its override when it has one,
+    /// with the tenant price in `base_price_cents`.
+    pub async fn get_pending_product_updates(
+        &self,
+        store_id: &str,
+        since_version: i64,
+        limit: i32,
+    ) -> Result<Vec<ProductRecord>, CloudError> {
+        let limit = if limit <= 0 { 100 } else { limit };
+        
+        let results = sqlx::query_as::<_, ProductRecord>(
+            r#"
+            SELECT 
+                p.id, p.tenant_id, p.sku, p.name, p.barcode,
+                COALESCE(o.price_cents, p.price_cents) AS price_cents,
+                CASE WHEN o.price_cents IS NOT NULL THEN p.price_cents END AS base_price_cents,
+                p.cost_cents, p.tax_rate_id, p.tax_rate_bps,
+                p.track_inventory, p.current_stock, p.low_stock_threshold,
+                p.is_active, p.category, p.department,
+                p.created_at, p.updated_at, p.version
+            FROM products p
+            LEFT JOIN store_price_overrides o ON o.store_id = $1 AND o.product_id = p.id
+            WHERE p.tenant_id = (SELECT tenant_id FROM stores WHERE id = $1)
+              AND p.version > $2
+              AND (
+                  NOT (SELECT assortment_scoped FROM stores WHERE id = $1)
+                  OR p.id IN (SELECT product_id FROM store_assortments WHERE store_id = $1)
+              )
+            ORDER BY p.version ASC
+            LIMIT $3
+            "#
+        )
+        .bind(store_id)
+        .bind(since_version)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(results)
+    }
+
+    /// Attributes and tags of `product_ids`, keyed by product ID (products
+    /// without any are left out).
+    pub async fn get_product_attributes(
+        &self,
+        product_ids: &[String],
+    ) -> Result<HashMap<String, HashMap<String, String>>, CloudError> {
+        if product_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows = sqlx::query_as::<_, (String, String, String)>(
+            r#"
+            SELECT product_id, key, value
+            FROM product_attributes
+            WHERE product_id = ANY($1)
+            "#
+        )
+        .bind(product_ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let mut attributes: HashMap<String, HashMap<String, String>> = HashMap::new();
+        for (product_id, key, value) in rows {
+            attributes.entry(product_id).or_default().insert(key, value);
+        }
+        Ok(attributes)
+    }
+
+    /// Update sync cursor for a store.
+    pub async fn update_sync_cursor(
+        &self,
+        store_id: &str,
+        stream: &str,
+        position: i64,
+    ) -> Result<(), CloudError> {
+        sqlx::query(
+            r#"
+            INSERT INTO sync_cursors (store_id, stream, position, updated_at)
+            VALUES ($1, $2, $3, NOW())
+            ON CONFLICT (store_id, stream) DO UPDATE SET
+                position = EXCLUDED.position,
+                updated_at = NOW()
+            "#
+        )
+        .bind(store_id)
+        .bind(stream)
+        .bind(position)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Get sync cursor for a store and stream.
+    pub async fn get_sync_cursor(
+        &self,
+        store_id: &str,
+        stream: &str,
+    ) -> Result<Option<i64>, CloudError> {
+        let result = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT position FROM sync_cursors
+            WHERE store_id = $1 AND stream = $2
+            "#
+        )
+        .bind(store_id)
+        .bind(stream)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    // =========================================================================
+    // Config Operations
+    // =========================================================================
+
+    /// Get store configuration.
+    pub async fn get_store_config(&self, store_id: &str) -> Result<Option<StoreConfigRecord>, CloudError> {
+        let result = sqlx::query_as::<_, StoreConfigRecord>(
+            r#"
+            SELECT 
+                store_id, tenant_id, store_name, address, city, state,
+                postal_code, country, timezone, currency, tax_mode,
+                allow_negative_inventory, receipt_header, receipt_footer,
+                receipt_campaigns, sync_batch_size, sync_interval_secs
+            FROM store_configs
+            WHERE store_id = $1
+            "#
+        )
+        .bind(store_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    /// Update one store configuration value.
+    ///
+    /// `key` is a `store_configs` column from a fixed allow-list; the value
+    /// has already been validated and is cast to the column type here.
+    /// Returns false if the store has no configuration row.
+    pub async fn update_store_config_value(
+        &self,
+        store_id: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<bool, CloudError> {
+        let (column, cast) = match key {
+            "store_name" => ("store_name", "TEXT"),
+            "currency" => ("currency", "TEXT"),
+            "tax_mode" => ("tax_mode", "TEXT"),
+            "timezone" => ("timezone", "TEXT"),
+            "receipt_header" => ("receipt_header", "TEXT"),
+            "receipt_footer" => ("receipt_footer", "TEXT"),
+            "receipt_campaigns" => ("receipt_campaigns", "TEXT"),
+            "allow_negative_inventory" => ("allow_negative_inventory", "BOOLEAN"),
+            "sync_batch_size" => ("sync_batch_size", "INTEGER"),
+            "sync_interval_secs" => ("sync_interval_secs", "INTEGER"),
+            _ => {
+                return Err(CloudError::InvalidRequest(format!(
+                    "Config key not found: {}",
+                    key
+                )))
+            }
+        };
+
+        let result = sqlx::query(&format!(
+            "UPDATE store_configs SET {} = $2::{}, updated_at = NOW() WHERE store_id = $1",
+            column, cast
+        ))
+        .bind(store_id)
+        .bind(value)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Releases for a platform on the given channels, excluding withdrawn
+    /// ones, newest published first.
+    pub async fn list_releases(
+        &self,
+        channels: &[&str],
+        platform: &str,
+    ) -> Result<Vec<ReleaseRecord>, CloudError> {
+        let channels: Vec<String> = channels.iter().map(|c| c.to_string()).collect();
+        let result = sqlx::query_as::<_, ReleaseRecord>(
+            r#"
+            SELECT
+                id, version, channel, platform, download_url, sha256, signature,
+                notes, rollout_percent, mandatory, published_at
+            FROM releases
+            WHERE channel = ANY($1) AND platform = $2 AND withdrawn_at IS NULL
+            ORDER BY published_at DESC
+            LIMIT 50
+            "#
+        )
+        .bind(&channels)
+        .bind(platform)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    // =========================================================================
+    // Feature Flag Operations
+    // =========================================================================
+
+    /// Tenant-wide flags and the store's own flags (see `feature_flags`).
+    pub async fn list_feature_flags(
+        &self,
+        tenant_id: &str,
+        store_id: &str,
+    ) -> Result<Vec<FeatureFlagRecord>, CloudError> {
+        let result = sqlx::query_as::<_, FeatureFlagRecord>(
+            r#"
+            SELECT key, enabled, store_id, updated_at
+            FROM feature_flags
+            WHERE tenant_id = $1 AND (store_id IS NULL OR store_id = $2)
+            ORDER BY key
+            "#
+        )
+        .bind(tenant_id)
+        .bind(store_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    /// Sets a flag for one store, or for the whole tenant when `store_id`
+    /// is `None`.
+    pub async fn set_feature_flag(
+        &self,
+        tenant_id: &str,
+        store_id: Option<&str>,
+        key: &str,
+        enabled: bool,
+    ) -> Result<FeatureFlagRecord, CloudError> {
+        let conflict = if store_id.is_some() {
+            "(store_id, key) WHERE store_id IS NOT NULL"
+        } else {
+            "(tenant_id, key) WHERE store_id IS NULL"
+        };
+        let result = sqlx::query_as::<_, FeatureFlagRecord>(&format!(
+            r#"
+            INSERT INTO feature_flags (tenant_id, store_id, key, enabled, updated_at)
+            VALUES ($1, $2, $3, $4, NOW())
+            ON CONFLICT {}
+            DO UPDATE SET enabled = EXCLUDED.enabled, updated_at = NOW()
+            RETURNING key, enabled, store_id, updated_at
+            "#,
+            conflict
+        ))
+        .bind(tenant_id)
+        .bind(store_id)
+        .bind(key)
+        .bind(enabled)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    // =========================================================================
+    // Store Presence Operations
+    // =========================================================================
+
+    /// Records a message from `store_id` at `now`, marking it online.
+    ///
+    /// Returns the store as it was if it had been offline (and so just came
+    /// back); `None` if it was online already or is seen for the first time.
+    pub async fn touch_store_presence(
+        &self,
+        store_id: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<StorePresenceRecord>, CloudError> {
+        let result = sqlx::query_as::<_, StorePresenceRecord>(
+            r#"
+            WITH previous AS (
+                SELECT store_id, online, last_seen_at, status_since
+                FROM store_presence
+                WHERE store_id = $1
+                FOR UPDATE
+            ), seen AS (
+                INSERT INTO store_presence (store_id, last_seen_at, online, status_since)
+                VALUES ($1, $2, TRUE, $2)
+                ON CONFLICT (store_id) DO UPDATE SET
+                    last_seen_at = GREATEST(store_presence.last_seen_at, EXCLUDED.last_seen_at),
+                    online = TRUE,
+                    status_since = CASE WHEN store_presence.online
+                        THEN store_presence.status_since ELSE EXCLUDED.status_since END
+                RETURNING store_id
+            )
+            SELECT p.store_id, s.tenant_id, s.name AS store_name,
+                   p.online, p.last_seen_at, p.status_since
+            FROM seen
+            JOIN previous p ON p.store_id = seen.store_id
+            JOIN stores s ON s.id = p.store_id
+            WHERE NOT p.online
+            "#
+        )
+        .bind(store_id)
+        .bind(now)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    /// Marks the online stores last seen before `cutoff` offline as of
+    /// `now`, returning them.
+    pub async fn mark_stores_offline(
+        &self,
+        cutoff: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<StorePresenceRecord>, CloudError> {
+        let result = sqlx::query_as::<_, StorePresenceRecord>(
+            r#"
+            UPDATE store_presence p
+            SET online = FALSE, status_since = $2
+            FROM stores s
+            WHERE s.id = p.store_id
+              AND p.online
+              AND p.last_seen_at < $1
+            RETURNING p.store_id, s.tenant_id, s.name AS store_name,
+                      p.online, p.last_seen_at, p.status_since
+            "#
+        )
+        .bind(cutoff)
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    /// Presence of a tenant's active stores, or of one of them, by name.
+    /// Stores never seen are offline with no timestamps.
+    pub async fn list_store_presence(
+        &self,
+        tenant_id: &str,
+        store_id: Option<&str>,
+    ) -> Result<Vec<StorePresenceRecord>, CloudError> {
+        let result = sqlx::query_as::<_, StorePresenceRecord>(
+            r#"
+            SELECT s.id AS store_id, s.tenant_id, s.name AS store_name,
+                   COALESCE(p.online, FALSE) AS online, p.last_seen_at, p.status_since
+            FROM stores s
+            LEFT JOIN store_presence p ON p.store_id = s.id
+            WHERE s.tenant_id = $1
+              AND s.is_active
+              AND ($2::TEXT IS NULL OR s.id = $2)
+            ORDER BY s.name, s.id
+            "#
+        )
+        .bind(tenant_id)
+        .bind(store_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    // =========================================================================
+    // Webhook Operations
+    // =========================================================================
+
+    /// Queues `body` for every enabled webhook of the tenant subscribed to
+    /// `event`; returns how many deliveries were queued.
+    pub async fn enqueue_webhook_event(
+        &self,
+        tenant_id: &str,
+        event: &str,
+        body: &str,
+    ) -> Result<u64, CloudError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO webhook_deliveries (webhook_id, event, payload)
+            SELECT id, $2, $3
+            FROM tenant_webhooks
+            WHERE tenant_id = $1
+              AND enabled
+              AND (cardinality(events) = 0 OR $2 = ANY(events))
+            "#
+        )
+        .bind(tenant_id)
+        .bind(event)
+        .bind(body)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Claims up to `limit` deliveries due at `now`, counting the attempt
+    /// and hiding them from other dispatchers until `lease_until`.
+    pub async fn claim_webhook_deliveries(
+        &self,
+        now: DateTime<Utc>,
+        lease_until: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDeliveryRecord>, CloudError> {
+        let result = sqlx::query_as::<_, WebhookDeliveryRecord>(
+            r#"
+            UPDATE webhook_deliveries d
+            SET attempts = d.attempts + 1, next_attempt_at = $2
+            FROM tenant_webhooks w
+            WHERE w.id = d.webhook_id
+              AND d.id IN (
+                  SELECT id FROM webhook_deliveries
+                  WHERE delivered_at IS NULL
+                    AND failed_at IS NULL
+                    AND next_attempt_at <= $1
+                  ORDER BY next_attempt_at
+                  LIMIT $3
+                  FOR UPDATE SKIP LOCKED
+              )
+            RETURNING d.id, d.event, d.payload, d.attempts, w.url, w.secret
+            "#
+        )
+        .bind(now)
+        .bind(lease_until)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    /// Records a delivery the endpoint accepted.
+    pub async fn mark_webhook_delivered(&self, id: i64) -> Result<(), CloudError> {
+        sqlx::query(
+            "UPDATE webhook_deliveries SET delivered_at = NOW(), last_error = NULL WHERE id = $1",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Records a failed attempt: tried again at `retry_at`, or given up on
+    /// when `None`.
+    pub async fn fail_webhook_delivery(
+        &self,
+        id: i64,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), CloudError> {
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET last_error = $2,
+                next_attempt_at = COALESCE($3, next_attempt_at),
+                failed_at = CASE WHEN $3 IS NULL THEN NOW() END
+            WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .bind(error)
+        .bind(retry_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Deletes deliveries delivered or given up on before `before`.
+    pub async fn delete_finished_webhook_deliveries(
+        &self,
+        before: DateTime<Utc>,
+    ) -> Result<u64, CloudError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM webhook_deliveries
+            WHERE COALESCE(delivered_at, failed_at) < $1
+            "#
+        )
+        .bind(before)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected())
+    }
+
+    // =========================================================================
+    // Report Operations
+    // =========================================================================
+
+    /// Sales and tax per tax rate for a tenant's sales completed in
+    /// `[from, to)`, optionally for one store (see `tax_report`).
+    pub async fn tax_report(
+        &self,
+        tenant_id: &str,
+        store_id: Option<&str>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<TaxRateRecord>, CloudError> {
+        let result = sqlx::query_as::<_, TaxRateRecord>(
+            r#"
+            WITH by_rate AS (
+                SELECT
+                    si.tax_rate_bps,
+                    COALESCE(SUM(si.line_total_cents)
+                        FILTER (WHERE si.tax_rate_bps > 0 OR si.tax_amount_cents <> 0), 0)::bigint
+                        AS taxable_cents,
+                    COALESCE(SUM(si.line_total_cents)
+                        FILTER (WHERE si.tax_rate_bps = 0 AND si.tax_amount_cents = 0), 0)::bigint
+                        AS non_taxable_cents,
+                    COALESCE(SUM(si.tax_amount_cents), 0)::bigint AS tax_cents,
+                    COUNT(*) AS line_count
+                FROM sales s
+                JOIN sale_items si ON si.sale_id = s.id
+                WHERE s.tenant_id = $1
+                  AND ($2::text IS NULL OR s.store_id = $2)
+                  AND s.status = 'COMPLETED'
+                  AND s.completed_at >= $3
+                  AND s.completed_at < $4
+                GROUP BY si.tax_rate_bps
+            )
+            SELECT
+                r.tax_rate_bps,
+                (SELECT string_agg(t.name, ' / ' ORDER BY t.name)
+                 FROM tax_rates t
+                 WHERE t.tenant_id = $1 AND t.rate_bps = r.tax_rate_bps AND t.is_active)
+                    AS jurisdiction,
+                r.taxable_cents,
+                r.non_taxable_cents,
+                r.tax_cents,
+                r.line_count
+            FROM by_rate r
+            ORDER BY r.tax_rate_bps
+            "#
+        )
+        .bind(tenant_id)
+        .bind(store_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    /// Waste written off per store and reason for a tenant's inventory
+    /// deltas in `[from, to)`, optionally for one store.
+    pub async fn shrinkage_report(
+        &self,
+        tenant_id: &str,
+        store_id: Option<&str>,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<ShrinkageRecord>, CloudError> {
+        let result = sqlx::query_as::<_, ShrinkageRecord>(
+            r#"
+            SELECT
+                d.store_id,
+                s.name AS store_name,
+                d.reason,
+                COALESCE(-SUM(d.delta), 0)::bigint AS units,
+                COALESCE(SUM(d.value_cents), 0)::bigint AS value_cents,
+                COUNT(*) AS entries
+            FROM inventory_deltas d
+            JOIN stores s ON s.id = d.store_id
+            WHERE d.tenant_id = $1
+              AND ($2::text IS NULL OR d.store_id = $2)
+              AND d.reason IN ('DAMAGE', 'EXPIRY', 'THEFT')
+              AND d.created_at >= $3
+              AND d.created_at < $4
+            GROUP BY d.store_id, s.name, d.reason
+            ORDER BY s.name, d.store_id, d.reason
+            "#
+        )
+        .bind(tenant_id)
+        .bind(store_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    // =========================================================================
+    // Tenant User Operations
+    // =========================================================================
+
+    /// Get a tenant back-office user by ID.
+    pub async fn get_tenant_user(&self, user_id: &str) -> Result<Option<TenantUserRecord>, CloudError> {
+        let result = sqlx::query_as::<_, TenantUserRecord>(&format!(
+            "SELECT {} FROM tenant_users WHERE id = $1",
+            TENANT_USER_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    /// List a tenant's back-office users.
+    pub async fn list_tenant_users(&self, tenant_id: &str) -> Result<Vec<TenantUserRecord>, CloudError> {
+        let result = sqlx::query_as::<_, TenantUserRecord>(&format!(
+            "SELECT {} FROM tenant_users WHERE tenant_id = $1 ORDER BY created_at",
+            TENANT_USER_COLUMNS
+        ))
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    /// Create an invited user. `invite_token_hash` is the SHA-256 of the
+    /// token handed to the invitee.
+    ///
+    /// Returns `Conflict` if the email is already registered.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn invite_tenant_user(
+        &self,
+        user_id: &str,
+        tenant_id: &str,
+        email: &str,
+        display_name: &str,
+        role: &str,
+        invite_token_hash: &str,
+        invite_expires_at: DateTime<Utc>,
+        invited_by: &str,
+    ) -> Result<TenantUserRecord, CloudError> {
+        let result = sqlx::query_as::<_, TenantUserRecord>(&format!(
+            r#"
+            INSERT INTO tenant_users (
+                id, tenant_id, email, display_name, role,
+                invite_token_hash, invite_expires_at, invited_by
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (email) DO NOTHING
+            RETURNING {}
+            "#,
+            TENANT_USER_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(tenant_id)
+        .bind(email)
+        .bind(display_name)
+        .bind(role)
+        .bind(invite_token_hash)
+        .bind(invite_expires_at)
+        .bind(invited_by)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        result.ok_or_else(|| CloudError::Conflict(format!("User already exists: {}", email)))
+    }
+
+    /// Redeem an unexpired invite: set the password and clear the invite.
+    ///
+    /// Returns `None` if the token is unknown, used or expired.
+    pub async fn accept_tenant_invite(
+        &self,
+        invite_token_hash: &str,
+        password_hash: &str,
+    ) -> Result<Option<TenantUserRecord>, CloudError> {
+        let result = sqlx::query_as::<_, TenantUserRecord>(&format!(
+            r#"
+            UPDATE tenant_users
+            SET password_hash = $2,
+                invite_token_hash = NULL,
+                invite_expires_at = NULL,
+                last_login_at = NOW()
+            WHERE invite_token_hash = $1
+              AND invite_expires_at > NOW()
+              AND is_active = true
+            RETURNING {}
+            "#,
+            TENANT_USER_COLUMNS
+        ))
+        .bind(invite_token_hash)
+        .bind(password_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    /// Validate a user's email and password, recording the login.
+    pub async fn authenticate_tenant_user(
+        &self,
+        email: &str,
+        password: &str,
+    ) -> Result<Option<TenantUserRecord>, CloudError> {
+        let row: Option<(String, Option<String>)> = sqlx::query_as(
+            "SELECT id, password_hash FROM tenant_users WHERE email = $1 AND is_active = true",
+        )
+        .bind(email)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let user_id = match row {
+            Some((id, Some(hash))) if verify_password(password, &hash) => id,
+            _ => return Ok(None),
+        };
+
+        let result = sqlx::query_as::<_, TenantUserRecord>(&format!(
+            "UPDATE tenant_users SET last_login_at = NOW() WHERE id = $1 RETURNING {}",
+            TENANT_USER_COLUMNS
+        ))
+        .bind(&user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    /// Disable a user of the tenant. Returns false if there is no such
+    /// active user.
+    pub async fn disable_tenant_user(&self, user_id: &str, tenant_id: &str) -> Result<bool, CloudError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE tenant_users
+            SET is_active = false, invite_token_hash = NULL, invite_expires_at = NULL
+            WHERE id = $1 AND tenant_id = $2 AND is_active = true
+            "#,
+        )
+        .bind(user_id)
+        .bind(tenant_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // =========================================================================
+    // Session Operations
+    // =========================================================================
+
+    /// Start a session with its first refresh token.
+    pub async fn start_session(&self, first: &Claims) -> Result<(), CloudError> {
+        let session_id = session_of(first)?;
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        sqlx::query(
+            "INSERT INTO auth_sessions (id, tenant_id, subject, device_id) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(session_id)
+        .bind(&first.tenant_id)
+        .bind(&first.sub)
+        .bind(&first.device_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        insert_refresh_token(&mut tx, first).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))
+    }
+
+    /// Exchange a refresh token for the next one of its session.
+    ///
+    /// The presented token is marked used and `next` recorded, unless the
+    /// token was used before: then the session is revoked, since either
+    /// the thief or the owner now holds a token of the chain.
+    pub async fn rotate_refresh_token(
+        &self,
+        presented: &Claims,
+        next: &Claims,
+    ) -> Result<RefreshRotation, CloudError> {
+        let session_id = session_of(presented)?;
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        // Lock the token row so two concurrent refreshes cannot both win
+        let state: Option<(bool, bool)> = sqlx::query_as(
+            r#"
+            SELECT t.used_at IS NOT NULL, s.revoked_at IS NOT NULL
+            FROM refresh_tokens t
+            JOIN auth_sessions s ON s.id = t.session_id
+            WHERE t.jti = $1 AND t.session_id = $2
+            FOR UPDATE OF t
+            "#
+        )
+        .bind(&presented.jti)
+        .bind(session_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let rotation = match state {
+            None => RefreshRotation::Unknown,
+            Some((_, true)) => RefreshRotation::Revoked,
+            Some((true, false)) => {
+                sqlx::query(
+                    r#"
+                    UPDATE auth_sessions SET revoked_at = NOW(), revoked_reason = 'REUSE'
+                    WHERE id = $1 AND revoked_at IS NULL
+                    "#
+                )
+                .bind(session_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CloudError::Database(e.to_string()))?;
+                RefreshRotation::Reused
+            }
+            Some((false, false)) => {
+                sqlx::query("UPDATE refresh_tokens SET used_at = NOW() WHERE jti = $1")
+                    .bind(&presented.jti)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| CloudError::Database(e.to_string()))?;
+                insert_refresh_token(&mut tx, next).await?;
+
+                // Expired tokens can no longer be presented, used or not
+                sqlx::query("DELETE FROM refresh_tokens WHERE session_id = $1 AND expires_at < NOW()")
+                    .bind(session_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| CloudError::Database(e.to_string()))?;
+                RefreshRotation::Rotated
+            }
+        };
+
+        tx.commit()
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(rotation)
+    }
+
+    /// Revoke a session of the tenant, e.g. on logout. Returns false if it
+    /// was unknown or already revoked.
+    pub async fn revoke_session(
+        &self,
+        session_id: &str,
+        tenant_id: &str,
+        reason: &str,
+    ) -> Result<bool, CloudError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE auth_sessions SET revoked_at = NOW(), revoked_reason = $3
+            WHERE id = $1 AND tenant_id = $2 AND revoked_at IS NULL
+            "#
+        )
+        .bind(session_id)
+        .bind(tenant_id)
+        .bind(reason)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    // =========================================================================
+    // Upload Batch Queue Operations
+    // =========================================================================
+
+    /// Store a batch for the queue workers and return its status ID.
+    ///
+    /// A batch the store uploaded before keeps its first status ID; one that
+    /// failed is queued again with the new payload.
+    pub async fn enqueue_upload_batch(&self, batch: &QueuedUploadBatch) -> Result<String, CloudError> {
+        let id: String = sqlx::query_scalar(
+            r#"
+            INSERT INTO upload_batches (
+                id, batch_id, tenant_id, store_id, device_id, entity_count, payload
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (store_id, batch_id) DO UPDATE SET
+                status = CASE WHEN upload_batches.status = 'FAILED'
+                    THEN 'QUEUED' ELSE upload_batches.status END,
+                payload = CASE WHEN upload_batches.status = 'FAILED'
+                    THEN EXCLUDED.payload ELSE upload_batches.payload END,
+                entity_count = CASE WHEN upload_batches.status = 'FAILED'
+                    THEN EXCLUDED.entity_count ELSE upload_batches.entity_count END,
+                attempts = CASE WHEN upload_batches.status = 'FAILED'
+                    THEN 0 ELSE upload_batches.attempts END,
+                error_message = CASE WHEN upload_batches.status = 'FAILED'
+                    THEN NULL ELSE upload_batches.error_message END,
+                completed_at = CASE WHEN upload_batches.status = 'FAILED'
+                    THEN NULL ELSE upload_batches.completed_at END
+            RETURNING id
+            "#
+        )
+        .bind(&batch.id)
+        .bind(&batch.batch_id)
+        .bind(&batch.tenant_id)
+        .bind(&batch.store_id)
+        .bind(&batch.device_id)
+        .bind(batch.entity_count)
+        .bind(&batch.payload)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(id)
+    }
+
+    /// Claim a queued batch for processing.
+    ///
+    /// Returns `None` if another worker has it, it is finished, or it has
+    /// used up `max_attempts`. A batch left `PROCESSING` for `stale_after_secs`
+    /// is taken over: its worker is assumed lost.
+    pub async fn claim_upload_batch(
+        &self,
+        id: &str,
+        max_attempts: i32,
+        stale_after_secs: i64,
+    ) -> Result<Option<ClaimedUploadBatch>, CloudError> {
+        let result = sqlx::query_as::<_, ClaimedUploadBatch>(
+            r#"
+            UPDATE upload_batches
+            SET status = 'PROCESSING', started_at = NOW(), attempts = attempts + 1
+            WHERE id = $1
+              AND attempts < $2
+              AND (status = 'QUEUED'
+                   OR (status = 'PROCESSING'
+                       AND started_at < NOW() - $3 * INTERVAL '1 second'))
+            RETURNING tenant_id, store_id, device_id, payload
+            "#
+        )
+        .bind(id)
+        .bind(max_attempts)
+        .bind(stale_after_secs as f64)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    /// Record a processed batch's result and drop its payload.
+    pub async fn complete_upload_batch(&self, id: &str, result: &[u8]) -> Result<(), CloudError> {
+        sqlx::query(
+            r#"
+            UPDATE upload_batches
+            SET status = 'COMPLETED', result = $2, payload = NULL, completed_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .bind(result)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Give up on a batch that cannot be processed.
+    pub async fn fail_upload_batch(&self, id: &str, message: &str) -> Result<(), CloudError> {
+        sqlx::query(
+            r#"
+            UPDATE upload_batches
+            SET status = 'FAILED', error_message = $2, payload = NULL, completed_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .bind(message)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Get one of a store's queued batches.
+    pub async fn get_upload_batch(
+        &self,
+        id: &str,
+        store_id: &str,
+    ) -> Result<Option<UploadBatchRecord>, CloudError> {
+        let result = sqlx::query_as::<_, UploadBatchRecord>(
+            r#"
+            SELECT id, status, entity_count, result, error_message, created_at, completed_at
+            FROM upload_batches
+            WHERE id = $1 AND store_id = $2
+            "#
+        )
+        .bind(id)
+        .bind(store_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    /// Housekeeping for the batch queue; returns the IDs to push again.
+    ///
+    /// - `PROCESSING` for `stale_after_secs` with no attempts left ──► FAILED
+    /// - finished more than `keep_secs` ago ──► deleted
+    /// - `QUEUED` or `PROCESSING` for `stale_after_secs` ──► returned, as
+    ///   their push or their worker was lost
+    pub async fn sweep_upload_batches(
+        &self,
+        stale_after_secs: i64,
+        max_attempts: i32,
+        keep_secs: i64,
+    ) -> Result<Vec<String>, CloudError> {
+        let stale_after = stale_after_secs as f64;
+
+        sqlx::query(
+            r#"
+            UPDATE upload_batches
+            SET status = 'FAILED', error_message = 'Processing did not finish',
+                payload = NULL, completed_at = NOW()
+            WHERE status = 'PROCESSING'
+              AND attempts >= $1
+              AND started_at < NOW() - $2 * INTERVAL '1 second'
+            "#
+        )
+        .bind(max_attempts)
+        .bind(stale_after)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM upload_batches
+            WHERE status IN ('COMPLETED', 'FAILED')
+              AND completed_at < NOW() - $1 * INTERVAL '1 second'
+            "#
+        )
+        .bind(keep_secs as f64)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let ids: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT id FROM upload_batches
+            WHERE (status = 'QUEUED' AND created_at < NOW() - $1 * INTERVAL '1 second')
+               OR (status = 'PROCESSING' AND started_at < NOW() - $1 * INTERVAL '1 second')
+            ORDER BY created_at
+            "#
+        )
+        .bind(stale_after)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(ids)
+    }
+
+    // =========================================================================
+    // Entity Quarantine Operations
+    // =========================================================================
+
+    /// Keep an uploaded entity of a type this build cannot process.
+    ///
+    /// Uploading the same entity again replaces the payload and counts the
+    /// sighting.
+    pub async fn quarantine_entity(&self, record: &QuarantinedEntityRecord) -> Result<(), CloudError> {
+        sqlx::query(
+            r#"
+            INSERT INTO quarantined_entities (
+                tenant_id, store_id, device_id, entity_type, entity_id, payload,
+                seen_count, first_seen_at, last_seen_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, 1, $7, $7)
+            ON CONFLICT (store_id, entity_type, entity_id) DO UPDATE SET
+                device_id = EXCLUDED.device_id,
+                payload = EXCLUDED.payload,
+                seen_count = quarantined_entities.seen_count + 1,
+                last_seen_at = EXCLUDED.last_seen_at
+            "#
+        )
+        .bind(&record.tenant_id)
+        .bind(&record.store_id)
+        .bind(&record.device_id)
+        .bind(&record.entity_type)
+        .bind(&record.entity_id)
+        .bind(&record.payload)
+        .bind(record.last_seen_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Quarantined entities of the given types, oldest first.
+    pub async fn list_quarantined_entities(
+        &self,
+        entity_types: &[String],
+    ) -> Result<Vec<QuarantinedEntityRecord>, CloudError> {
+        let result = sqlx::query_as::<_, QuarantinedEntityRecord>(
+            r#"
+            SELECT
+                tenant_id, store_id, device_id, entity_type, entity_id, payload,
+                seen_count, first_seen_at, last_seen_at
+            FROM quarantined_entities
+            WHERE entity_type = ANY($1)
+            ORDER BY first_seen_at
+            "#
+        )
+        .bind(entity_types)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    /// Release a quarantined entity once it has been processed.
+    pub async fn delete_quarantined_entity(
+        &self,
+        store_id: &str,
+        entity_type: &str,
+        entity_id: &str,
+    ) -> Result<(), CloudError> {
+        sqlx::query(
+            r#"
+            DELETE FROM quarantined_entities
+            WHERE store_id = $1 AND entity_type = $2 AND entity_id = $3
+            "#
+        )
+        .bind(store_id)
+        .bind(entity_type)
+        .bind(entity_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    // =========================================================================
+    // Audit Log Operations
+    // =========================================================================
+
+    /// Append an audit log entry.
+    pub async fn insert_audit_entry(&self, entry: &AuditEntry) -> Result<(), CloudError> {
+        sqlx::query(
+            r#"
+            INSERT INTO audit_log (
+                tenant_id, actor_type, actor_id, store_id, method, entity_ids,
+                status_code, status_message, duration_ms, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#
+        )
+        .bind(&entry.tenant_id)
+        .bind(&entry.actor_type)
+        .bind(&entry.actor_id)
+        .bind(&entry.store_id)
+        .bind(&entry.method)
+        .bind(&entry.entity_ids)
+        .bind(entry.status_code)
+        .bind(&entry.status_message)
+        .bind(entry.duration_ms)
+        .bind(entry.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Query a tenant's audit log, newest first.
+    pub async fn query_audit_log(
+        &self,
+        tenant_id: &str,
+        filter: &AuditLogFilter,
+    ) -> Result<Vec<AuditLogRecord>, CloudError> {
+        let result = sqlx::query_as::<_, AuditLogRecord>(
+            r#"
+            SELECT
+                id, actor_type, actor_id, store_id, method, entity_ids,
+                status_code, status_message, duration_ms, created_at
+            FROM audit_log
+            WHERE tenant_id = $1
+              AND ($2::text IS NULL OR store_id = $2)
+              AND ($3::text IS NULL OR actor_id = $3)
+              AND ($4::text IS NULL OR method = $4)
+              AND ($5::text IS NULL OR $5 = ANY(entity_ids))
+              AND ($6::timestamptz IS NULL OR created_at >= $6)
+              AND ($7::timestamptz IS NULL OR created_at < $7)
+              AND ($8::bigint IS NULL OR id < $8)
+            ORDER BY id DESC
+            LIMIT $9
+            "#
+        )
+        .bind(tenant_id)
+        .bind(&filter.store_id)
+        .bind(&filter.actor_id)
+        .bind(&filter.method)
+        .bind(&filter.entity_id)
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(filter.before_id)
+        .bind(filter.limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    // =========================================================================
+    // Catalog Import Operations
+    // =========================================================================
+
+    /// Stage a validated catalog file: the import, its accepted rows and its
+    /// row errors, in one transaction.
+    pub async fn stage_catalog_import(
+        &self,
+        import: &CatalogImportRecord,
+        rows: &[CatalogRow],
+        errors: &[RowError],
+    ) -> Result<(), CloudError> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO catalog_imports (
+                id, tenant_id, uploaded_by, file_name, format, status,
+                total_rows, accepted_rows, error_rows, created_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#
+        )
+        .bind(&import.id)
+        .bind(&import.tenant_id)
+        .bind(&import.uploaded_by)
+        .bind(&import.file_name)
+        .bind(&import.format)
+        .bind(&import.status)
+        .bind(import.total_rows)
+        .bind(import.accepted_rows)
+        .bind(import.error_rows)
+        .bind(import.created_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        for chunk in rows.chunks(STAGING_CHUNK_ROWS) {
+            sqlx::query(
+                r#"
+                INSERT INTO catalog_import_rows (
+                    import_id, row_number, sku, name, barcode, price_cents, cost_cents,
+                    tax_rate_bps, track_inventory, is_active, category, department
+                )
+                SELECT $1, * FROM UNNEST(
+                    $2::int[], $3::text[], $4::text[], $5::text[], $6::bigint[], $7::bigint[],
+                    $8::int[], $9::bool[], $10::bool[], $11::text[], $12::text[]
+                )
+                "#
+            )
+            .bind(&import.id)
+            .bind(chunk.iter().map(|r| r.row).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|r| r.sku.clone()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|r| r.name.clone()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|r| r.barcode.clone()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|r| r.price_cents).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|r| r.cost_cents).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|r| r.tax_rate_bps).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|r| r.track_inventory).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|r| r.is_active).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|r| r.category.clone()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|r| r.department.clone()).collect::<Vec<_>>())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+        }
+
+        for chunk in errors.chunks(STAGING_CHUNK_ROWS) {
+            sqlx::query(
+                r#"
+                INSERT INTO catalog_import_errors (import_id, row_number, field, message)
+                SELECT $1, * FROM UNNEST($2::int[], $3::text[], $4::text[])
+                "#
+            )
+            .bind(&import.id)
+            .bind(chunk.iter().map(|e| e.row).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|e| e.field.clone()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|e| e.message.clone()).collect::<Vec<_>>())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+        }
+
+        tx.commit().await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Apply a staged import to the tenant's products, matched by SKU.
+    ///
+    /// Rows identical to the current product are left alone, so only real
+    /// changes bump the product version and get queued for the stores.
+    ///
+    /// ## Returns
+    /// `(inserted, updated, unchanged)`
+    ///
+    /// ## Errors
+    /// `CloudError::NotFound` for an unknown import (or another tenant's),
+    /// `CloudError::Conflict` if it was already published.
+    pub async fn publish_catalog_import(
+        &self,
+        import_id: &str,
+        tenant_id: &str,
+    ) -> Result<(i32, i32, i32), CloudError> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let import = sqlx::query_as::<_, (String, i32)>(
+            r#"
+            SELECT status, accepted_rows FROM catalog_imports
+            WHERE id = $1 AND tenant_id = $2
+            FOR UPDATE
+            "#
+        )
+        .bind(import_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let (status, accepted_rows) = import
+            .ok_or_else(|| CloudError::NotFound(format!("Catalog import {}", import_id)))?;
+        if status != "STAGED" {
+            return Err(CloudError::Conflict(format!(
+                "Catalog import {} is already {}",
+                import_id, status
+            )));
+        }
+
+        let (inserted, updated) = sqlx::query_as::<_, (i64, i64)>(
+            r#"
+            WITH upserted AS (
+                INSERT INTO products (
+                    id, tenant_id, sku, name, barcode, price_cents, cost_cents,
+                    tax_rate_bps, track_inventory, is_active, category, department
+                )
+                SELECT
+                    gen_random_uuid()::text, $2, r.sku, r.name, r.barcode, r.price_cents,
+                    r.cost_cents, r.tax_rate_bps, r.track_inventory, r.is_active,
+                    r.category, r.department
+                FROM catalog_import_rows r
+                WHERE r.import_id = $1
+                ORDER BY r.row_number
+                ON CONFLICT (tenant_id, sku) DO UPDATE SET
+                    name = EXCLUDED.name,
+                    barcode = EXCLUDED.barcode,
+                    price_cents = EXCLUDED.price_cents,
+                    cost_cents = EXCLUDED.cost_cents,
+                    tax_rate_bps = EXCLUDED.tax_rate_bps,
+                    track_inventory = EXCLUDED.track_inventory,
+                    is_active = EXCLUDED.is_active,
+                    category = EXCLUDED.category,
+                    department = EXCLUDED.department,
+                    updated_at = NOW()
+                WHERE (
+                    products.name, products.barcode, products.price_cents, products.cost_cents,
+                    products.tax_rate_bps, products.track_inventory, products.is_active,
+                    products.category, products.department
+                ) IS DISTINCT FROM (
+                    EXCLUDED.name, EXCLUDED.barcode, EXCLUDED.price_cents, EXCLUDED.cost_cents,
+                    EXCLUDED.tax_rate_bps, EXCLUDED.track_inventory, EXCLUDED.is_active,
+                    EXCLUDED.category, EXCLUDED.department
+                )
+                RETURNING (xmax = 0) AS inserted
+            )
+            SELECT
+                COUNT(*) FILTER (WHERE inserted),
+                COUNT(*) FILTER (WHERE NOT inserted)
+            FROM upserted
+            "#
+        )
+        .bind(import_id)
+        .bind(tenant_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let inserted = inserted as i32;
+        let updated = updated as i32;
+
+        sqlx::query(
+            r#"
+            UPDATE catalog_imports SET
+                status = 'PUBLISHED',
+                inserted_count = $2,
+                updated_count = $3,
+                published_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(import_id)
+        .bind(inserted)
+        .bind(updated)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        tx.commit().await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok((inserted, updated, accepted_rows - inserted - updated))
+    }
+
+    /// Change the products a store carries (see [`AssortmentChange`]).
+    ///
+    /// Products newly added to the assortment are queued for download to
+    /// the store. SKUs not in the tenant catalog are skipped and returned.
+    pub async fn set_store_assortment(
+        &self,
+        tenant_id: &str,
+        store_id: &str,
+        change: AssortmentChange,
+        skus: &[String],
+    ) -> Result<AssortmentUpdate, CloudError> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let scoped: Option<bool> = sqlx::query_scalar(
+            "SELECT assortment_scoped FROM stores WHERE id = $1 AND tenant_id = $2 FOR UPDATE",
+        )
+        .bind(store_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+        let scoped = scoped.ok_or_else(|| CloudError::NotFound(format!("Store {}", store_id)))?;
+
+        let found: Vec<(String, String)> = sqlx::query_as(
+            "SELECT id, sku FROM products WHERE tenant_id = $1 AND sku = ANY($2)",
+        )
+        .bind(tenant_id)
+        .bind(skus)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+        let product_ids: Vec<String> = found.iter().map(|(id, _)| id.clone()).collect();
+        let mut unknown_skus: Vec<String> = Vec::new();
+        for sku in skus {
+            if !found.iter().any(|(_, s)| s == sku) && !unknown_skus.contains(sku) {
+                unknown_skus.push(sku.clone());
+            }
+        }
+
+        if matches!(change, AssortmentChange::Replace | AssortmentChange::FullCatalog) {
+            // Replace keeps the products that stay, so they are not queued again
+            let keep: &[String] = if change == AssortmentChange::Replace {
+                &product_ids
+            } else {
+                &[]
+            };
+            sqlx::query(
+                "DELETE FROM store_assortments WHERE store_id = $1 AND NOT (product_id = ANY($2))",
+            )
+            .bind(store_id)
+            .bind(keep)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+        }
+
+        match change {
+            AssortmentChange::Replace | AssortmentChange::Add => {
+                // A store that was not scoped already has every product;
+                // otherwise queue the ones it did not carry before
+                sqlx::query(
+                    r#"
+                    WITH added AS (
+                        INSERT INTO store_assortments (store_id, product_id)
+                        SELECT $2, unnest($3::text[])
+                        ON CONFLICT DO NOTHING
+                        RETURNING product_id
+                    )
+                    SELECT queue_download_for_store(
+                        $1, $2, 'PRODUCT', p.id, 'UPDATE', row_to_json(p)::jsonb
+                    )
+                    FROM products p
+                    WHERE $4 AND p.id IN (SELECT product_id FROM added)
+                    "#
+                )
+                .bind(tenant_id)
+                .bind(store_id)
+                .bind(&product_ids)
+                .bind(scoped)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CloudError::Database(e.to_string()))?;
+            }
+            AssortmentChange::Remove => {
+                sqlx::query(
+                    "DELETE FROM store_assortments WHERE store_id = $1 AND product_id = ANY($2)",
+                )
+                .bind(store_id)
+                .bind(&product_ids)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| CloudError::Database(e.to_string()))?;
+            }
+            AssortmentChange::FullCatalog => {}
+        }
+
+        let now_scoped = match change {
+            AssortmentChange::Replace | AssortmentChange::Add => true,
+            AssortmentChange::Remove => scoped,
+            AssortmentChange::FullCatalog => false,
+        };
+        sqlx::query("UPDATE stores SET assortment_scoped = $2 WHERE id = $1")
+            .bind(store_id)
+            .bind(now_scoped)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let product_count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM store_assortments WHERE store_id = $1",
+        )
+        .bind(store_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        tx.commit().await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(AssortmentUpdate {
+            scoped: now_scoped,
+            product_count: product_count as i32,
+            unknown_skus,
+        })
+    }
+
+    /// Set or clear store price overrides, and queue the changed products
+    /// for download to the store.
+    ///
+    /// Returns `(set, cleared, unknown_skus)`; SKUs not in the tenant
+    /// catalog are skipped.
+    pub async fn set_store_prices(
+        &self,
+        tenant_id: &str,
+        store_id: &str,
+        set_by: &str,
+        prices: &[StorePriceChange],
+    ) -> Result<(i32, i32, Vec<String>), CloudError> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        let store: Option<String> = sqlx::query_scalar(
+            "SELECT id FROM stores WHERE id = $1 AND tenant_id = $2",
+        )
+        .bind(store_id)
+        .bind(tenant_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+        if store.is_none() {
+            return Err(CloudError::NotFound(format!("Store {}", store_id)));
+        }
+
+        let (mut set, mut cleared) = (0, 0);
+        let mut unknown_skus = Vec::new();
+
+        for change in prices {
+            let product_id: Option<String> = sqlx::query_scalar(
+                "SELECT id FROM products WHERE tenant_id = $1 AND sku = $2",
+            )
+            .bind(tenant_id)
+            .bind(&change.sku)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+            let Some(product_id) = product_id else {
+                unknown_skus.push(change.sku.clone());
+                continue;
+            };
+
+            let changed = match change.price_cents {
+                Some(price_cents) => sqlx::query(
+                    r#"
+                    INSERT INTO store_price_overrides (store_id, product_id, price_cents, set_by)
+                    VALUES ($1, $2, $3, $4)
+                    ON CONFLICT (store_id, product_id) DO UPDATE SET
+                        price_cents = EXCLUDED.price_cents,
+                        set_by = EXCLUDED.set_by,
+                        updated_at = NOW()
+                    "#
+                )
+                .bind(store_id)
+                .bind(&product_id)
+                .bind(price_cents)
+                .bind(set_by),
+                None => sqlx::query(
+                    "DELETE FROM store_price_overrides WHERE store_id = $1 AND product_id = $2",
+                )
+                .bind(store_id)
+                .bind(&product_id),
+            }
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?
+            .rows_affected();
+
+            if changed == 0 {
+                continue;
+            }
+            if change.price_cents.is_some() {
+                set += 1;
+            } else {
+                cleared += 1;
+            }
+
+            sqlx::query(
+                r#"
+                SELECT queue_download_for_store(
+                    $1, $2, 'PRODUCT', p.id, 'UPDATE', row_to_json(p)::jsonb
+                )
+                FROM products p
+                WHERE p.id = $3
+                "#
+            )
+            .bind(tenant_id)
+            .bind(store_id)
+            .bind(&product_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+        }
+
+        tx.commit().await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok((set, cleared, unknown_skus))
+    }
+
+    // =========================================================================
+    // Retention Operations
+    // =========================================================================
+
+    /// Create the monthly partitions of a table from `from_month` up to
+    /// `months_ahead` months after the current month.
+    ///
+    /// Returns the number of partitions created.
+    pub async fn ensure_monthly_partitions(
+        &self,
+        table: PartitionedTable,
+        from_month: NaiveDate,
+        months_ahead: i32,
+    ) -> Result<i32, CloudError> {
+        let created = sqlx::query_scalar::<_, i32>(
+            "SELECT titan_ensure_monthly_partitions($1, $2, $3)"
+        )
+        .bind(table.name())
+        .bind(from_month)
+        .bind(months_ahead)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(created)
+    }
+
+    /// List the partitions attached to a table (including the default one).
+    pub async fn list_partitions(&self, table: PartitionedTable) -> Result<Vec<String>, CloudError> {
+        let result = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT child.relname::text
+            FROM pg_inherits i
+            JOIN pg_class child ON child.oid = i.inhrelid
+            JOIN pg_class parent ON parent.oid = i.inhparent
+            WHERE parent.relname = $1
+            ORDER BY child.relname
+            "#
+        )
+        .bind(table.name())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    /// Get every tenant with its sales retention override, if any.
+    pub async fn get_tenant_retention_policies(
+        &self,
+    ) -> Result<Vec<(String, Option<i32>)>, CloudError> {
+        let result = sqlx::query_as::<_, (String, Option<i32>)>(
+            r#"
+            SELECT t.id, p.sales_retention_months
+            FROM tenants t
+            LEFT JOIN tenant_retention_policies p ON p.tenant_id = t.id
+            ORDER BY t.id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    /// Read one page of a partition as JSON rows, ordered by id.
+    ///
+    /// `partition` must be a partition name returned by
+    /// [`Database::list_partitions`].
+    pub async fn fetch_partition_rows(
+        &self,
+        partition: &str,
+        after_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<ArchivedRow>, CloudError> {
+        let sql = format!(
+            r#"
+            SELECT t.id, t.created_at, to_jsonb(t)::text AS json
+            FROM "{}" t
+            WHERE $1::text IS NULL OR t.id > $1
+            ORDER BY t.id
+            LIMIT $2
+            "#,
+            partition
+        );
+        let result = sqlx::query_as::<_, ArchivedRow>(&sql)
+            .bind(after_id)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    /// Detach a partition from its table and drop it.
+    pub async fn drop_partition(
+        &self,
+        table: PartitionedTable,
+        partition: &str,
+    ) -> Result<(), CloudError> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        sqlx::query(&format!(r#"ALTER TABLE {} DETACH PARTITION "{}""#, table.name(), partition))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+        sqlx::query(&format!(r#"DROP TABLE "{}""#, partition))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        tx.commit().await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Read one page of a tenant's rows created before `before`.
+    pub async fn fetch_expired_tenant_rows(
+        &self,
+        table: PartitionedTable,
+        tenant_id: &str,
+        before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<ArchivedRow>, CloudError> {
+        let sql = format!(
+            r#"
+            SELECT t.id, t.created_at, to_jsonb(t)::text AS json
+            FROM {} t
+            WHERE {} AND t.created_at < $2
+            ORDER BY t.id
+            LIMIT $3
+            "#,
+            table.name(),
+            table.tenant_filter()
+        );
+        let result = sqlx::query_as::<_, ArchivedRow>(&sql)
+            .bind(tenant_id)
+            .bind(before)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    /// Delete archived rows by primary key.
+    pub async fn delete_archived_rows(
+        &self,
+        table: PartitionedTable,
+        rows: &[ArchivedRow],
+    ) -> Result<u64, CloudError> {
+        let ids: Vec<&str> = rows.iter().map(|r| r.id.as_str()).collect();
+        let created: Vec<DateTime<Utc>> = rows.iter().map(|r| r.created_at).collect();
+
+        let result = sqlx::query(&format!(
+            r#"
+            DELETE FROM {} t
+            USING UNNEST($1::text[], $2::timestamptz[]) AS k(id, created_at)
+            WHERE t.id = k.id AND t.created_at = k.created_at
+            "#,
+            table.name()
+        ))
+        .bind(&ids)
+        .bind(&created)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+// =============================================================================
+// Record Types
+// =============================================================================
+
+/// Outcome of presenting a refresh token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshRotation {
+    /// Used for the first time; the next token is recorded.
+    Rotated,
+    /// Used before: replayed or leaked. The session is now revoked.
+    Reused,
+    /// The session was revoked earlier.
+    Revoked,
+    /// Not issued by this server (or already expired and removed).
+    Unknown,
+}
+
+/// How [`Database::set_store_assortment`] changes a store's products.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssortmentChange {
+    /// The store carries exactly the given products.
+    Replace,
+    /// Add the given products.
+    Add,
+    /// Remove the given products.
+    Remove,
+    /// Drop the assortment: the store gets the whole tenant catalog.
+    FullCatalog,
+}
+
+/// A store's assortment after a change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssortmentUpdate {
+    pub scoped: bool,
+    pub product_count: i32,
+    /// Requested SKUs missing from the tenant catalog.
+    pub unknown_skus: Vec<String>,
+}
+
+/// A store price to set (`Some`) or clear (`None`), see
+/// [`Database::set_store_prices`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorePriceChange {
+    pub sku: String,
+    pub price_cents: Option<i64>,
+}
+
+/// An uploaded entity of a type no processor handles.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct QuarantinedEntityRecord {
+    pub tenant_id: String,
+    pub store_id: String,
+    pub device_id: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    /// Encoded `SyncEntity`.
+    pub payload: Vec<u8>,
+    pub seen_count: i32,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+/// A batch handed to the queue workers.
+#[derive(Debug, Clone)]
+pub struct QueuedUploadBatch {
+    /// Batch status ID.
+    pub id: String,
+    /// The store's own batch ID.
+    pub batch_id: String,
+    pub tenant_id: String,
+    pub store_id: String,
+    pub device_id: String,
+    pub entity_count: i32,
+    /// Encoded `UploadBatchRequest`.
+    pub payload: Vec<u8>,
+}
+
+/// A batch claimed by a queue worker.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ClaimedUploadBatch {
+    pub tenant_id: String,
+    pub store_id: String,
+    pub device_id: String,
+    pub payload: Option<Vec<u8>>,
+}
+
+/// Status of a queued batch.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct UploadBatchRecord {
+    pub id: String,
+    pub status: String,
+    pub entity_count: i32,
+    /// Encoded `UploadBatchResponse`, once completed.
+    pub result: Option<Vec<u8>>,
+    pub error_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StoreRecord {
+    pub id: String,
+    pub tenant_id: String,
+    pub name: String,
+    pub api_key_hash: String,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SaleRecord {
+    pub id: String,
+    pub store_id: String,
+    pub device_id: String,
+    pub tenant_id: String,
+    pub receipt_number: String,
+    /// Register the sale was rung up on, if the terminal recorded it.
+    pub station_number: Option<i32>,
+    pub subtotal_cents: i64,
+    pub tax_amount_cents: i64,
+    pub discount_amount_cents: i64,
     pub total_cents: i64,
     pub status: String,
     pub created_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
+    /// Correlation ID of the terminal command that produced the sale.
+    pub correlation_id: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, sqlx::FromRow)]
 pub struct SaleItemRecord {
     pub id: String,
     pub sale_id: String,
@@ -389,9 +3387,14 @@ pub struct SaleItemRecord {
     pub line_total_cents: i64,
     pub tax_amount_cents: i64,
     pub tax_rate_bps: i32,
+    /// "serial" or "lot" when codes were captured at sale time.
+    pub tracking_kind: Option<String>,
+    pub tracking_codes: Vec<String>,
+    /// Tenant price when the item was sold at a store override.
+    pub base_price_cents: Option<i64>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, sqlx::FromRow)]
 pub struct PaymentRecord {
     pub id: String,
     pub sale_id: String,
@@ -415,6 +3418,8 @@ pub struct InventoryDeltaRecord {
     pub delta: i32,
     pub reason: String,
     pub reference_id: Option<String>,
+    /// Retail value of the goods (waste only; 0 otherwise).
+    pub value_cents: i64,
     pub created_at: DateTime<Utc>,
 }
 
@@ -425,7 +3430,10 @@ pub struct ProductRecord {
     pub sku: String,
     pub name: String,
     pub barcode: Option<String>,
+    /// The store's price (its override, if any).
     pub price_cents: i64,
+    /// Tenant price, when `price_cents` is a store override.
+    pub base_price_cents: Option<i64>,
     pub cost_cents: Option<i64>,
     pub tax_rate_id: Option<String>,
     pub tax_rate_bps: i32,
@@ -440,6 +3448,234 @@ pub struct ProductRecord {
     pub version: i64,
 }
 
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StoreTransferRecord {
+    pub id: String,
+    pub tenant_id: String,
+    pub transfer_number: String,
+    pub from_store_id: String,
+    pub to_store_id: String,
+    pub status: String,
+    pub notes: Option<String>,
+    pub created_by: String,
+    pub received_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub received_at: Option<DateTime<Utc>>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+    pub version: i64,
+    /// Download cursor position (assigned by the database).
+    pub route_seq: i64,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StoreTransferItemRecord {
+    pub id: String,
+    pub transfer_id: String,
+    pub product_id: String,
+    pub sku: String,
+    pub name: String,
+    pub quantity_sent: i32,
+    pub quantity_received: Option<i32>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SupplierRecord {
+    pub id: String,
+    pub tenant_id: String,
+    pub name: String,
+    pub contact_name: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub version: i64,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SupplierItemRecord {
+    pub supplier_id: String,
+    pub product_id: String,
+    pub supplier_sku: Option<String>,
+    pub cost_cents: i64,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StoreCreditRecord {
+    pub id: String,
+    pub tenant_id: String,
+    pub credit_number: String,
+    pub customer_name: String,
+    pub customer_phone: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub version: i64,
+    /// Download cursor position (assigned by the database).
+    pub route_seq: i64,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StoreCreditEntryRecord {
+    pub id: String,
+    pub account_id: String,
+    pub kind: String,
+    pub amount_cents: i64,
+    pub sale_id: Option<String>,
+    pub device_id: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A coupon definition; `kind` is "PERCENT" or "AMOUNT".
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct CouponRecord {
+    pub id: String,
+    pub tenant_id: String,
+    pub code: String,
+    pub description: Option<String>,
+    pub kind: String,
+    /// Basis points for PERCENT, cents for AMOUNT.
+    pub value: i64,
+    pub min_subtotal_cents: Option<i64>,
+    pub product_ids: Vec<String>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub max_uses: Option<i64>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub version: i64,
+    /// Download cursor position (assigned by the database).
+    pub route_seq: i64,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct CouponRedemptionRecord {
+    pub id: String,
+    pub coupon_id: String,
+    pub sale_id: String,
+    pub store_id: String,
+    pub device_id: String,
+    pub discount_cents: i64,
+    pub redeemed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct LoyaltyRecord {
+    pub id: String,
+    pub tenant_id: String,
+    pub member_number: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub version: i64,
+    /// Download cursor position (assigned by the database).
+    pub route_seq: i64,
+}
+
+/// A loyalty ledger entry; `kind` is "EARN", "REWARD" or "REDEEM".
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct LoyaltyEntryRecord {
+    pub id: String,
+    pub account_id: String,
+    pub kind: String,
+    pub points: i64,
+    pub reward_cents: i64,
+    pub sale_id: Option<String>,
+    pub device_id: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A customer erasure request and what the cloud anonymized for it.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct CustomerErasureRecord {
+    pub request_id: String,
+    pub tenant_id: String,
+    pub business_customer_id: Option<String>,
+    pub phone: Option<String>,
+    /// Lowercase.
+    pub email: Option<String>,
+    pub reason: Option<String>,
+    pub requested_by: String,
+    pub requested_at: DateTime<Utc>,
+    /// The uploading store; `None` for the ForgetCustomer RPC.
+    pub origin_store_id: Option<String>,
+    /// Filled in by `forget_customer`.
+    pub store_credit_accounts: i32,
+    /// Assigned by the database.
+    pub erased_at: DateTime<Utc>,
+    /// Download cursor position (assigned by the database).
+    pub route_seq: i64,
+}
+
+/// Filters for `query_audit_log`; `None` matches everything.
+#[derive(Debug, Clone, Default)]
+pub struct AuditLogFilter {
+    pub store_id: Option<String>,
+    pub actor_id: Option<String>,
+    pub method: Option<String>,
+    /// Entity in `kind:id` form.
+    pub entity_id: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    /// Page before this entry ID (keyset pagination).
+    pub before_id: Option<i64>,
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AuditLogRecord {
+    pub id: i64,
+    pub actor_type: String,
+    pub actor_id: Option<String>,
+    pub store_id: Option<String>,
+    pub method: String,
+    pub entity_ids: Vec<String>,
+    pub status_code: i32,
+    pub status_message: Option<String>,
+    pub duration_ms: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TenantUserRecord {
+    pub id: String,
+    pub tenant_id: String,
+    pub email: String,
+    pub display_name: String,
+    /// "OWNER", "MANAGER" or "ANALYST".
+    pub role: String,
+    pub is_active: bool,
+    pub invite_pending: bool,
+    pub last_login_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CatalogImportRecord {
+    pub id: String,
+    pub tenant_id: String,
+    /// Tenant user that uploaded the file.
+    pub uploaded_by: String,
+    pub file_name: String,
+    /// "csv" or "json".
+    pub format: String,
+    /// "STAGED" or "PUBLISHED".
+    pub status: String,
+    pub total_rows: i32,
+    pub accepted_rows: i32,
+    pub error_rows: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A row read for archival, with its primary key.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ArchivedRow {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    /// The whole row as a JSON object.
+    pub json: String,
+}
+
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct StoreConfigRecord {
     pub store_id: String,
@@ -456,14 +3692,200 @@ pub struct StoreConfigRecord {
     pub allow_negative_inventory: bool,
     pub receipt_header: Option<String>,
     pub receipt_footer: Option<String>,
+    /// JSON array of receipt footer campaigns.
+    pub receipt_campaigns: Option<String>,
     pub sync_batch_size: i32,
     pub sync_interval_secs: i32,
 }
 
+/// A published app release (see `releases`).
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ReleaseRecord {
+    pub id: String,
+    pub version: String,
+    pub channel: String,
+    pub platform: String,
+    pub download_url: String,
+    pub sha256: String,
+    pub signature: String,
+    pub notes: Option<String>,
+    pub rollout_percent: i32,
+    pub mandatory: bool,
+    pub published_at: DateTime<Utc>,
+}
+
+/// A feature flag row (see `feature_flags`).
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct FeatureFlagRecord {
+    pub key: String,
+    pub enabled: bool,
+    /// `None` for the tenant-wide default.
+    pub store_id: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A store's presence (see `presence`).
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StorePresenceRecord {
+    pub store_id: String,
+    pub tenant_id: String,
+    pub store_name: String,
+    pub online: bool,
+    /// `None` if the store never subscribed.
+    pub last_seen_at: Option<DateTime<Utc>>,
+    /// Last online/offline change.
+    pub status_since: Option<DateTime<Utc>>,
+}
+
+/// A webhook delivery claimed by the dispatcher (see `webhooks`).
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct WebhookDeliveryRecord {
+    pub id: i64,
+    pub event: String,
+    /// JSON body.
+    pub payload: String,
+    /// Attempts including this one.
+    pub attempts: i32,
+    pub url: String,
+    pub secret: String,
+}
+
+/// Sales and tax at one rate (see `tax_report`).
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TaxRateRecord {
+    pub tax_rate_bps: i32,
+    /// Names of the tenant's active tax rates at this rate.
+    pub jurisdiction: Option<String>,
+    pub taxable_cents: i64,
+    pub non_taxable_cents: i64,
+    pub tax_cents: i64,
+    pub line_count: i64,
+}
+
+/// Waste for one store and reason (see `ReportService.GetShrinkageReport`).
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ShrinkageRecord {
+    pub store_id: String,
+    pub store_name: String,
+    /// `DAMAGE`, `EXPIRY` or `THEFT`.
+    pub reason: String,
+    /// Units written off (positive).
+    pub units: i64,
+    pub value_cents: i64,
+    pub entries: i64,
+}
+
 // =============================================================================
 // Helper Functions
 // =============================================================================
 
+/// The session a token was issued in.
+fn session_of(claims: &Claims) -> Result<&str, CloudError> {
+    claims
+        .sid
+        .as_deref()
+        .ok_or_else(|| CloudError::AuthFailed("Token has no session".to_string()))
+}
+
+/// Record an issued refresh token inside an open transaction.
+async fn insert_refresh_token(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    claims: &Claims,
+) -> Result<(), CloudError> {
+    let expires_at = DateTime::from_timestamp(claims.exp, 0)
+        .ok_or_else(|| CloudError::Internal("Refresh token expiry out of range".to_string()))?;
+
+    sqlx::query("INSERT INTO refresh_tokens (jti, session_id, expires_at) VALUES ($1, $2, $3)")
+        .bind(&claims.jti)
+        .bind(session_of(claims)?)
+        .bind(expires_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Merge a loyalty account and entries inside an open transaction, moving
+/// the account to the end of the download sequence if anything changed.
+async fn merge_loyalty(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    account: &LoyaltyRecord,
+    entries: &[LoyaltyEntryRecord],
+) -> Result<bool, CloudError> {
+    let owner: Option<String> =
+        sqlx::query_scalar("SELECT tenant_id FROM loyalty_accounts WHERE id = $1")
+            .bind(&account.id)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+
+    if owner.as_deref().is_some_and(|t| t != account.tenant_id) {
+        return Err(CloudError::Unauthorized(format!(
+            "Loyalty account {} belongs to another tenant",
+            account.id
+        )));
+    }
+
+    let account_changed = sqlx::query(
+        r#"
+        INSERT INTO loyalty_accounts (
+            id, tenant_id, member_number, created_at, updated_at, version
+        ) VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (id) DO UPDATE SET
+            updated_at = EXCLUDED.updated_at,
+            version = EXCLUDED.version
+        WHERE EXCLUDED.version > loyalty_accounts.version
+        "#
+    )
+    .bind(&account.id)
+    .bind(&account.tenant_id)
+    .bind(&account.member_number)
+    .bind(account.created_at)
+    .bind(account.updated_at)
+    .bind(account.version)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| CloudError::Database(e.to_string()))?
+    .rows_affected()
+        > 0;
+
+    let mut new_entries = 0;
+    for entry in entries {
+        new_entries += sqlx::query(
+            r#"
+            INSERT INTO loyalty_entries (
+                id, account_id, kind, points, reward_cents, sale_id, device_id, created_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (id) DO NOTHING
+            "#
+        )
+        .bind(&entry.id)
+        .bind(&account.id)
+        .bind(&entry.kind)
+        .bind(entry.points)
+        .bind(entry.reward_cents)
+        .bind(&entry.sale_id)
+        .bind(&entry.device_id)
+        .bind(entry.created_at)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| CloudError::Database(e.to_string()))?
+        .rows_affected();
+    }
+
+    let changed = account_changed || new_entries > 0;
+    if changed {
+        sqlx::query("UPDATE loyalty_accounts SET route_seq = nextval('loyalty_route_seq') WHERE id = $1")
+            .bind(&account.id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| CloudError::Database(e.to_string()))?;
+    }
+
+    Ok(changed)
+}
+
 /// Verify an API key against its hash.
 fn verify_api_key(api_key: &str, hash: &str) -> bool {
     use argon2::{Argon2, PasswordHash, PasswordVerifier};
@@ -478,6 +3900,16 @@ fn verify_api_key(api_key: &str, hash: &str) -> bool {
         .is_ok()
 }
 
+/// Verify a tenant user's password against its hash (same scheme as API keys).
+fn verify_password(password: &str, hash: &str) -> bool {
+    verify_api_key(password, hash)
+}
+
+/// Hash a tenant user's password for storage.
+pub fn hash_password(password: &str) -> Result<String, CloudError> {
+    hash_api_key(password)
+}
+
 /// Hash an API key for storage.
 pub fn hash_api_key(api_key: &str) -> Result<String, CloudError> {
     use argon2::{
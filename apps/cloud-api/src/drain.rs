@@ -0,0 +1,195 @@
+//! Graceful shutdown draining.
+//!
+//! `serve_with_shutdown` stops accepting connections but would cut
+//! long-lived streams mid-batch. Before handing over to it, the server
+//! drains:
+//!
+//! ```text
+//! SIGTERM ──► Drain::begin()
+//!               │
+//!               ├─► Subscribe streams send GOAWAY and end cleanly
+//!               ├─► StreamUpload finishes the batch in hand, then ends
+//!               │   with UNAVAILABLE + retry pushback
+//!               ├─► new UploadBatch / StreamUpload ──► UNAVAILABLE + retry
+//!               │
+//!               └─► wait_idle(timeout): in-flight batches commit
+//!                     │
+//!                     ▼
+//!            serve_with_shutdown completes (HTTP/2 GOAWAY)
+//! ```
+//!
+//! Clients honour the standard `grpc-retry-pushback-ms` trailer and
+//! reconnect to another instance.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::{watch, Notify};
+use tonic::Status;
+
+/// Delay clients are asked to wait before retrying on another instance.
+pub const RETRY_PUSHBACK: Duration = Duration::from_secs(1);
+
+/// Shutdown drain coordinator. Cloning shares the state.
+#[derive(Debug, Clone)]
+pub struct Drain {
+    draining: watch::Sender<bool>,
+    in_flight: Arc<AtomicUsize>,
+    idle: Arc<Notify>,
+}
+
+impl Default for Drain {
+    fn default() -> Self {
+        Drain {
+            draining: watch::channel(false).0,
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            idle: Arc::new(Notify::new()),
+        }
+    }
+}
+
+impl Drain {
+    /// Creates a coordinator that is not draining.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts draining; idempotent.
+    pub fn begin(&self) {
+        self.draining.send_replace(true);
+    }
+
+    /// Whether draining has started.
+    pub fn is_draining(&self) -> bool {
+        *self.draining.borrow()
+    }
+
+    /// Resolves once draining has started (immediately if it has).
+    pub async fn draining(&self) {
+        let mut rx = self.draining.subscribe();
+        // The sender lives in `self`, so this cannot fail
+        let _ = rx.wait_for(|draining| *draining).await;
+    }
+
+    /// Registers an in-flight batch, or rejects it with UNAVAILABLE once
+    /// draining has started. The batch counts until the guard is dropped.
+    #[allow(clippy::result_large_err)] // tonic::Status is the service error type
+    pub fn track(&self) -> Result<InFlight, Status> {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        // Checked after counting so wait_idle cannot miss a batch that
+        // slipped in as draining began
+        if self.is_draining() {
+            self.release();
+            return Err(unavailable());
+        }
+
+        Ok(InFlight {
+            drain: self.clone(),
+        })
+    }
+
+    /// Number of batches currently in flight.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Waits until no batch is in flight, up to `timeout`. Returns whether
+    /// the server went idle.
+    pub async fn wait_idle(&self, timeout: Duration) -> bool {
+        tokio::time::timeout(timeout, async {
+            loop {
+                let notified = self.idle.notified();
+                if self.in_flight() == 0 {
+                    return;
+                }
+                notified.await;
+            }
+        })
+        .await
+        .is_ok()
+    }
+
+    fn release(&self) {
+        if self.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.idle.notify_waiters();
+        }
+    }
+}
+
+/// Guard for one in-flight batch.
+#[derive(Debug)]
+pub struct InFlight {
+    drain: Drain,
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.drain.release();
+    }
+}
+
+/// UNAVAILABLE with a retry pushback, returned while the server drains.
+pub fn unavailable() -> Status {
+    let mut status = Status::unavailable("Server is shutting down; retry on another instance");
+    if let Ok(value) = RETRY_PUSHBACK.as_millis().to_string().parse() {
+        status
+            .metadata_mut()
+            .insert("grpc-retry-pushback-ms", value);
+    }
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_track_rejects_while_draining() {
+        let drain = Drain::new();
+        let guard = drain.track().unwrap();
+        assert_eq!(drain.in_flight(), 1);
+
+        drain.begin();
+        let status = drain.track().unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unavailable);
+        assert_eq!(
+            status.metadata().get("grpc-retry-pushback-ms").unwrap(),
+            "1000"
+        );
+        assert_eq!(drain.in_flight(), 1);
+
+        drop(guard);
+        assert_eq!(drain.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_wait_idle() {
+        let drain = Drain::new();
+        let guard = drain.track().unwrap();
+        drain.begin();
+
+        assert!(!drain.wait_idle(Duration::from_millis(20)).await);
+
+        let waiter = {
+            let drain = drain.clone();
+            tokio::spawn(async move { drain.wait_idle(Duration::from_secs(5)).await })
+        };
+        drop(guard);
+        assert!(waiter.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_draining_resolves() {
+        let drain = Drain::new();
+        let waiter = {
+            let drain = drain.clone();
+            tokio::spawn(async move { drain.draining().await })
+        };
+
+        drain.begin();
+        waiter.await.unwrap();
+        // Already draining: resolves immediately
+        drain.draining().await;
+    }
+}
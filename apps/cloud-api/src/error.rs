@@ -36,6 +36,12 @@ pub enum CloudError {
     Unavailable(String),
 }
 
+impl From<titan_core::pii::PiiError> for CloudError {
+    fn from(error: titan_core::pii::PiiError) -> Self {
+        CloudError::Internal(error.to_string())
+    }
+}
+
 impl From<CloudError> for Status {
     fn from(error: CloudError) -> Self {
         match error {
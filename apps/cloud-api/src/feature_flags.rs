@@ -0,0 +1,135 @@
+//! # Feature Flags
+//!
+//! Per-tenant and per-store switches for rolling new subsystems out
+//! incrementally, served by `ConfigService.GetFeatureFlags`.
+//!
+//! ## Resolution
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Flag Resolution                                 │
+//! │                                                                         │
+//! │  feature_flags rows for the store's tenant                              │
+//! │       │                                                                 │
+//! │       ├── store row for the key ──► wins (store_override = true)       │
+//! │       ├── tenant row (store NULL) ─► default for the store             │
+//! │       └── no row ──────────────────► not listed = off                  │
+//! │                                                                         │
+//! │  terminals cache the result for ttl_secs, and keep using it while      │
+//! │  the cloud is out of reach                                              │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Keys are free-form so a flag can be created before the terminals that
+//! read it ship; the keys terminals know are listed in
+//! `titan_core::feature`.
+
+use std::collections::BTreeMap;
+
+use crate::db::FeatureFlagRecord;
+use crate::error::CloudError;
+use crate::proto::FeatureFlag;
+
+/// How long terminals may cache the flags before asking again.
+pub const FLAG_TTL_SECS: u32 = 15 * 60;
+
+/// Longest flag key accepted.
+pub const MAX_KEY_LEN: usize = 64;
+
+/// Checks a flag key: lowercase ASCII letters, digits and underscores.
+///
+/// ## Errors
+/// `CloudError::InvalidRequest` for an empty, too long or malformed key.
+pub fn validate_key(key: &str) -> Result<(), CloudError> {
+    let well_formed = !key.is_empty()
+        && key.len() <= MAX_KEY_LEN
+        && key.starts_with(|c: char| c.is_ascii_lowercase())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if well_formed {
+        Ok(())
+    } else {
+        Err(CloudError::InvalidRequest(format!(
+            "Invalid feature flag key {:?}: use lowercase letters, digits and \
+             underscores (at most {} characters)",
+            key, MAX_KEY_LEN
+        )))
+    }
+}
+
+/// The flags in effect for a store from its tenant's and its own rows,
+/// ordered by key.
+pub fn resolve(records: &[FeatureFlagRecord]) -> Vec<FeatureFlag> {
+    let mut flags: BTreeMap<&str, FeatureFlag> = BTreeMap::new();
+    for record in records {
+        let store_override = record.store_id.is_some();
+        let flag = FeatureFlag {
+            key: record.key.clone(),
+            enabled: record.enabled,
+            store_override,
+        };
+        match flags.get(record.key.as_str()) {
+            Some(existing) if existing.store_override && !store_override => {}
+            _ => {
+                flags.insert(&record.key, flag);
+            }
+        }
+    }
+    flags.into_values().collect()
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn record(key: &str, enabled: bool, store_id: Option<&str>) -> FeatureFlagRecord {
+        FeatureFlagRecord {
+            key: key.to_string(),
+            enabled,
+            store_id: store_id.map(str::to_string),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_store_row_overrides_tenant_row() {
+        // Store and tenant rows in either order
+        let records = vec![
+            record("loyalty", false, Some("store-1")),
+            record("loyalty", true, None),
+            record("gift_cards", true, None),
+            record("gift_cards", true, Some("store-1")),
+            record("training", true, None),
+        ];
+
+        let flags = resolve(&records);
+        let keys: Vec<&str> = flags.iter().map(|f| f.key.as_str()).collect();
+        assert_eq!(keys, ["gift_cards", "loyalty", "training"]);
+
+        assert!(flags[0].enabled && flags[0].store_override);
+        assert!(!flags[1].enabled && flags[1].store_override);
+        assert!(flags[2].enabled && !flags[2].store_override);
+    }
+
+    #[test]
+    fn test_no_rows_means_no_flags() {
+        assert!(resolve(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_validate_key() {
+        assert!(validate_key("loyalty").is_ok());
+        assert!(validate_key("gift_cards_v2").is_ok());
+
+        assert!(validate_key("").is_err());
+        assert!(validate_key("Loyalty").is_err());
+        assert!(validate_key("gift-cards").is_err());
+        assert!(validate_key("2fa").is_err());
+        assert!(validate_key(&"a".repeat(MAX_KEY_LEN + 1)).is_err());
+    }
+}
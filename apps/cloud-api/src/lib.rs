@@ -13,14 +13,40 @@
 //! │  │ • ExchangeToken│  │ • UploadBatch  │  │ • GetStoreConfig           ││
 //! │  │ • RefreshToken │  │ • StreamUpload │  │ • GetConfigValue           ││
 //! │  │ • RevokeToken  │  │ • GetPending   │  │ • UpdateConfigValue        ││
+//! │  │ • Login        │  │ • BatchStatus  │  │ • GetLatestRelease         ││
+//! │  │                │  │ • RedeemReward │  │ • Get/SetFeatureFlag(s)    ││
+//! │  │                │  │                │  │ • UpsertCoupon             ││
 //! │  └────────────────┘  └────────────────┘  └────────────────────────────┘│
 //! │                                                                         │
-//! │  ┌────────────────┐  ┌────────────────┐                                │
-//! │  │NotificationSvc │  │  HealthService │                                │
-//! │  │                │  │                │                                │
-//! │  │ • Subscribe    │  │ • Check        │                                │
-//! │  │ (bidirectional)│  │ • Watch        │                                │
-//! │  └────────────────┘  └────────────────┘                                │
+//! │  ┌────────────────┐  ┌────────────────┐  ┌────────────────────────────┐│
+//! │  │NotificationSvc │  │  HealthService │  │  StorageService            ││
+//! │  │                │  │                │  │                            ││
+//! │  │ • Subscribe    │  │ • Check        │  │ • CreateSignedUrl          ││
+//! │  │ (bidirectional)│  │ • Watch        │  │                            ││
+//! │  │ • StoreStatus  │  │                │  │                            ││
+//! │  └────────────────┘  └────────────────┘  └────────────────────────────┘│
+//! │                                                                         │
+//! │  ┌────────────────────────────┐  ┌────────────────────────────┐        │
+//! │  │  ImportService             │  │  UserService               │        │
+//! │  │                            │  │                            │        │
+//! │  │ • UploadCatalog (stream)   │  │ • InviteUser / AcceptInvite│        │
+//! │  │ • PublishCatalogImport     │  │ • DisableUser / ListUsers  │        │
+//! │  └────────────────────────────┘  └────────────────────────────┘        │
+//! │                                                                         │
+//! │  ┌────────────────────────────┐  ┌────────────────────────────┐        │
+//! │  │  AuditService              │  │  PrivacyService            │        │
+//! │  │                            │  │                            │        │
+//! │  │ • QueryAuditLog            │  │ • ForgetCustomer           │        │
+//! │  └────────────────────────────┘  └────────────────────────────┘        │
+//! │                                                                         │
+//! │  ┌────────────────────────────┐                                        │
+//! │  │  ReportService             │                                        │
+//! │  │                            │                                        │
+//! │  │ • GetTaxReport             │                                        │
+//! │  │ • GetShrinkageReport       │                                        │
+//! │  └────────────────────────────┘                                        │
+//! │   every mutating RPC passes through AuditLayer ──► audit_log           │
+//! │   (append-only)                                                        │
 //! │                                                                         │
 //! │  ┌──────────────────────────────────────────────────────────────────┐  │
 //! │  │                      Infrastructure                               │  │
@@ -29,7 +55,7 @@
 //! │  │  │  PostgreSQL  │  │    Redis     │  │    JWT Auth              ││  │
 //! │  │  │              │  │              │  │                          ││  │
 //! │  │  │ Primary data │  │ Caching      │  │ Token management         ││  │
-//! │  │  │ store        │  │ Pub/Sub      │  │ API key exchange         ││  │
+//! │  │  │ store        │  │ Batch queue  │  │ API key exchange         ││  │
 //! │  │  └──────────────┘  └──────────────┘  └──────────────────────────┘│  │
 //! │  └──────────────────────────────────────────────────────────────────┘  │
 //! └─────────────────────────────────────────────────────────────────────────┘
@@ -43,22 +69,62 @@
 //! - `JWT_SECRET` - Secret for JWT signing
 //! - `JWT_ACCESS_EXPIRY_SECS` - Access token lifetime (default: 3600)
 //! - `JWT_REFRESH_EXPIRY_SECS` - Refresh token lifetime (default: 604800)
+//! - `SALES_RETENTION_MONTHS` - Default sales data retention (default: 84)
+//! - `ARCHIVE_DIR` - Where expired sales data is archived before removal
+//! - `RETENTION_INTERVAL_SECS` - Retention run interval (default: 86400)
+//! - `S3_ENDPOINT`, `S3_BUCKET`, `S3_REGION`, `S3_ACCESS_KEY_ID`,
+//!   `S3_SECRET_ACCESS_KEY` - Object storage (enabled when `S3_BUCKET` is set)
+//! - `SIGNED_URL_TTL_SECS` - Default signed URL lifetime (default: 900)
+//! - `SHUTDOWN_DRAIN_SECS` - Max wait for in-flight uploads on shutdown (default: 25)
+//! - `PII_MASTER_KEY` - 64 hex characters; customer phone numbers and emails
+//!   are stored encrypted when set
+//! - `BATCH_WORKERS` - Upload batch queue workers (default: 4; 0 processes
+//!   every batch in the call). The queue needs Redis.
+//! - `STORE_OFFLINE_AFTER_SECS` - Silence after which a store is reported
+//!   offline to tenant webhooks (default: 300, at least 60)
+//! - `OTEL_EXPORTER_OTLP_ENDPOINT` - OTLP/gRPC collector spans are exported
+//!   to (off when unset); uploads join the sending terminal's trace
 
+pub mod audit;
 pub mod auth;
+pub mod batch_queue;
+pub mod catalog_import;
 pub mod config;
 pub mod db;
+pub mod drain;
 pub mod error;
+pub mod feature_flags;
+pub mod liveness;
+pub mod pii;
+pub mod presence;
+pub mod processors;
 pub mod proto;
+pub mod rbac;
+pub mod releases;
+pub mod retention;
 pub mod services;
+pub mod storage;
+pub mod tax_report;
+pub mod telemetry;
+pub mod webhooks;
 
 // Re-exports
+pub use batch_queue::BatchQueue;
 pub use config::CloudConfig;
 pub use db::Database;
+pub use drain::Drain;
 pub use error::CloudError;
+pub use liveness::Liveness;
+pub use storage::ObjectStore;
 
 /// Shared application state.
 pub struct AppState {
     pub db: Database,
     pub redis: Option<redis::Client>,
+    /// Set when uploads can be queued (Redis configured, workers enabled).
+    pub batch_queue: Option<BatchQueue>,
+    pub storage: Option<ObjectStore>,
+    pub liveness: Liveness,
+    pub drain: Drain,
     pub config: CloudConfig,
 }
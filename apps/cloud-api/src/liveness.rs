@@ -0,0 +1,119 @@
+//! Background worker liveness.
+//!
+//! Long-running tasks (the retention job, ...) register with a deadline and
+//! beat once per loop. The health service reports a worker that has gone
+//! quiet for longer than its deadline, which usually means the task
+//! panicked or is stuck.
+//!
+//! ```text
+//! RetentionJob loop ──beat()──► Liveness ◄──report()── HealthService
+//!                               name → (last beat, max silence)
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+/// Liveness of one worker at report time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerStatus {
+    pub name: &'static str,
+    /// Time since the last beat (since registration if it never beat).
+    pub silent_for: Duration,
+    pub max_silence: Duration,
+}
+
+impl WorkerStatus {
+    /// Whether the worker beat within its deadline.
+    pub fn is_alive(&self) -> bool {
+        self.silent_for <= self.max_silence
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Heartbeat {
+    last_beat: DateTime<Utc>,
+    max_silence: Duration,
+}
+
+/// Shared heartbeat registry. Cloning shares the registry.
+#[derive(Debug, Clone, Default)]
+pub struct Liveness {
+    workers: Arc<Mutex<BTreeMap<&'static str, Heartbeat>>>,
+}
+
+impl Liveness {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a worker that must beat at least every `max_silence`.
+    pub fn register(&self, name: &'static str, max_silence: Duration) {
+        self.workers.lock().expect("liveness lock poisoned").insert(
+            name,
+            Heartbeat {
+                last_beat: Utc::now(),
+                max_silence,
+            },
+        );
+    }
+
+    /// Records a beat from a registered worker.
+    pub fn beat(&self, name: &'static str) {
+        if let Some(heartbeat) = self
+            .workers
+            .lock()
+            .expect("liveness lock poisoned")
+            .get_mut(name)
+        {
+            heartbeat.last_beat = Utc::now();
+        }
+    }
+
+    /// Status of every registered worker as of `now`, by name.
+    pub fn report(&self, now: DateTime<Utc>) -> Vec<WorkerStatus> {
+        self.workers
+            .lock()
+            .expect("liveness lock poisoned")
+            .iter()
+            .map(|(name, heartbeat)| WorkerStatus {
+                name,
+                silent_for: (now - heartbeat.last_beat).to_std().unwrap_or_default(),
+                max_silence: heartbeat.max_silence,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worker_goes_stale() {
+        let liveness = Liveness::new();
+        liveness.register("retention", Duration::from_secs(60));
+
+        let report = liveness.report(Utc::now());
+        assert_eq!(report.len(), 1);
+        assert!(report[0].is_alive());
+
+        let later = Utc::now() + chrono::Duration::seconds(120);
+        assert!(!liveness.report(later)[0].is_alive());
+    }
+
+    #[test]
+    fn test_beat_resets_silence() {
+        let liveness = Liveness::new();
+        liveness.register("retention", Duration::from_secs(60));
+        liveness.beat("retention");
+        liveness.beat("unregistered");
+
+        let report = liveness.report(Utc::now() + chrono::Duration::seconds(30));
+        assert_eq!(report.len(), 1);
+        assert!(report[0].is_alive());
+    }
+}
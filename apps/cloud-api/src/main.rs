@@ -14,46 +14,73 @@
 //! │                                  (Pub/Sub)                              │
 //! └─────────────────────────────────────────────────────────────────────────┘
 //! ```
-
-mod proto;
-mod config;
-mod db;
-mod error;
-mod services;
-mod auth;
+//!
+//! Unless `GRPC_REFLECTION=false`, the server also serves gRPC reflection,
+//! so `grpcurl` and `titan-cloud-cli` can list and describe every service.
+//!
+//! With `OTEL_EXPORTER_OTLP_ENDPOINT` set, spans are exported over OTLP and
+//! uploads continue the trace the sending hub or terminal started.
 
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use tonic::transport::Server;
 use tracing::{info, Level};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::FmtSubscriber;
 
-use crate::config::CloudConfig;
-use crate::db::Database;
-use crate::services::{
+use titan_cloud_api::services::{
     auth_service::AuthServiceImpl,
     sync_service::SyncServiceImpl,
     config_service::ConfigServiceImpl,
     notification_service::NotificationServiceImpl,
     health_service::HealthServiceImpl,
+    storage_service::StorageServiceImpl,
+    import_service::ImportServiceImpl,
+    user_service::UserServiceImpl,
+    audit_service::AuditServiceImpl,
+    privacy_service::PrivacyServiceImpl,
+    report_service::ReportServiceImpl,
 };
-use crate::proto::{
+use titan_cloud_api::proto::{
+    FILE_DESCRIPTOR_SET,
     auth_service_server::AuthServiceServer,
     sync_service_server::SyncServiceServer,
     config_service_server::ConfigServiceServer,
     notification_service_server::NotificationServiceServer,
     health_service_server::HealthServiceServer,
+    storage_service_server::StorageServiceServer,
+    import_service_server::ImportServiceServer,
+    user_service_server::UserServiceServer,
+    audit_service_server::AuditServiceServer,
+    privacy_service_server::PrivacyServiceServer,
+    report_service_server::ReportServiceServer,
 };
+use titan_cloud_api::audit::AuditLayer;
+use titan_cloud_api::auth::JwtManager;
+use titan_cloud_api::batch_queue::BatchWorkers;
+use titan_cloud_api::config::ConfigError;
+use titan_cloud_api::pii::MasterKey;
+use titan_cloud_api::presence::{PresenceMonitor, PRESENCE_CHECK_INTERVAL};
+use titan_cloud_api::processors::ProcessorRegistry;
+use titan_cloud_api::retention::RetentionJob;
+use titan_cloud_api::telemetry;
+use titan_cloud_api::webhooks::{WebhookDispatcher, DISPATCH_INTERVAL};
+use titan_cloud_api::{AppState, BatchQueue, CloudConfig, Database, Drain, Liveness, ObjectStore};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize tracing
-    let subscriber = FmtSubscriber::builder()
+    // Initialize tracing, exporting spans when a collector is configured
+    let (otlp, _telemetry) = telemetry::otlp_layer()?.unzip();
+    FmtSubscriber::builder()
         .with_max_level(Level::INFO)
         .with_target(true)
         .with_thread_ids(true)
         .pretty()
+        .finish()
+        .with(otlp)
         .init();
 
     info!("Starting Titan Cloud API server...");
@@ -67,13 +94,30 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
 
     // Connect to database
-    let db = Database::connect(&config.database_url).await?;
+    let mut db = Database::connect(&config.database_url).await?;
     info!("Connected to PostgreSQL");
 
     // Run migrations
     db.run_migrations().await?;
     info!("Database migrations complete");
 
+    // Customer data encryption
+    match &config.pii_master_key {
+        Some(hex) => {
+            let master = MasterKey::from_hex(hex)
+                .map_err(|_| ConfigError::InvalidValue("PII_MASTER_KEY".to_string()))?;
+            db = db.with_pii_master_key(master);
+            info!("Customer data encryption enabled");
+        }
+        None => tracing::warn!("PII_MASTER_KEY not set, customer data is stored unencrypted"),
+    }
+    db.encrypt_existing_pii().await?;
+
+    // Entities quarantined by an older build may have a processor now
+    if let Err(e) = ProcessorRegistry::standard().replay_quarantined(&db).await {
+        tracing::warn!(error = %e, "Could not replay quarantined entities");
+    }
+
     // Connect to Redis (optional for now)
     let redis = if let Some(ref redis_url) = config.redis_url {
         match redis::Client::open(redis_url.as_str()) {
@@ -90,19 +134,94 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         None
     };
 
+    // Upload batch queue (needs Redis)
+    let batch_queue = match &redis {
+        Some(client) if config.batch_workers > 0 => Some(BatchQueue::new(client.clone())),
+        _ => {
+            info!("Upload batch queue disabled, batches are processed in the call");
+            None
+        }
+    };
+
+    // Object storage (optional)
+    let storage = match config.object_storage {
+        Some(ref storage_config) => {
+            let store = ObjectStore::new(storage_config.clone())?;
+            info!(bucket = %storage_config.bucket, "Object storage configured");
+            Some(store)
+        }
+        None => None,
+    };
+
     // Create shared state
     let state = Arc::new(AppState {
         db,
         redis,
+        batch_queue,
+        storage,
+        liveness: Liveness::new(),
+        drain: Drain::new(),
         config: config.clone(),
     });
 
+    // Keep sales partitions ahead and apply retention in the background
+    RetentionJob::from_config(state.db.clone(), &config, state.storage.clone())
+        .with_liveness(state.liveness.clone())
+        .spawn(Duration::from_secs(config.retention_interval_secs));
+
+    // Call stores offline when they go quiet, and tell tenant webhooks
+    PresenceMonitor::new(
+        state.db.clone(),
+        Duration::from_secs(config.store_offline_after_secs),
+    )
+    .with_liveness(state.liveness.clone())
+    .spawn(PRESENCE_CHECK_INTERVAL);
+    WebhookDispatcher::new(state.db.clone())
+        .with_liveness(state.liveness.clone())
+        .spawn(DISPATCH_INTERVAL);
+
+    // Process queued upload batches in the background
+    if let Some(queue) = state.batch_queue.clone() {
+        BatchWorkers::new(state.clone(), queue).spawn(config.batch_workers);
+    }
+
     // Build gRPC services
     let auth_service = AuthServiceServer::new(AuthServiceImpl::new(state.clone()));
     let sync_service = SyncServiceServer::new(SyncServiceImpl::new(state.clone()));
     let config_service = ConfigServiceServer::new(ConfigServiceImpl::new(state.clone()));
     let notification_service = NotificationServiceServer::new(NotificationServiceImpl::new(state.clone()));
     let health_service = HealthServiceServer::new(HealthServiceImpl::new(state.clone()));
+    let storage_service = StorageServiceServer::new(StorageServiceImpl::new(state.clone()));
+    let import_service = ImportServiceServer::new(ImportServiceImpl::new(state.clone()));
+    let user_service = UserServiceServer::new(UserServiceImpl::new(state.clone()));
+    let audit_service = AuditServiceServer::new(AuditServiceImpl::new(state.clone()));
+    let privacy_service = PrivacyServiceServer::new(PrivacyServiceImpl::new(state.clone()));
+    let report_service = ReportServiceServer::new(ReportServiceImpl::new(state.clone()));
+
+    // Record every mutating RPC in the audit log
+    let audit_layer = AuditLayer::new(
+        state.db.clone(),
+        JwtManager::new(
+            config.jwt_secret.clone(),
+            config.jwt_access_lifetime_secs,
+            config.jwt_refresh_lifetime_secs,
+        ),
+    );
+
+    // Service discovery for grpcurl and titan-cloud-cli (both versions of
+    // the reflection API, older clients only speak v1alpha)
+    let (reflection_v1, reflection_v1alpha) = if config.grpc_reflection {
+        let v1 = tonic_reflection::server::Builder::configure()
+            .register_encoded_file_descriptor_set(FILE_DESCRIPTOR_SET)
+            .build_v1()?;
+        let v1alpha = tonic_reflection::server::Builder::configure()
+            .register_encoded_file_descriptor_set(FILE_DESCRIPTOR_SET)
+            .build_v1alpha()?;
+        info!("gRPC reflection enabled");
+        (Some(v1), Some(v1alpha))
+    } else {
+        (None, None)
+    };
 
     // Build server address
     let addr: SocketAddr = format!("0.0.0.0:{}", config.grpc_port).parse()?;
@@ -110,23 +229,46 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Start server
     Server::builder()
+        .layer(audit_layer)
         .add_service(auth_service)
         .add_service(sync_service)
         .add_service(config_service)
         .add_service(notification_service)
         .add_service(health_service)
-        .serve_with_shutdown(addr, shutdown_signal())
+        .add_service(storage_service)
+        .add_service(import_service)
+        .add_service(user_service)
+        .add_service(audit_service)
+        .add_service(privacy_service)
+        .add_service(report_service)
+        .add_optional_service(reflection_v1)
+        .add_optional_service(reflection_v1alpha)
+        .serve_with_shutdown(addr, drain_on_shutdown(
+            state.drain.clone(),
+            Duration::from_secs(config.shutdown_drain_secs),
+        ))
         .await?;
 
     info!("Server shutdown complete");
     Ok(())
 }
 
-/// Shared application state.
-pub struct AppState {
-    pub db: Database,
-    pub redis: Option<redis::Client>,
-    pub config: CloudConfig,
+/// Waits for a shutdown signal, then drains streams and in-flight uploads
+/// (bounded by `timeout`) before letting the server stop.
+async fn drain_on_shutdown(drain: Drain, timeout: Duration) {
+    shutdown_signal().await;
+
+    drain.begin();
+    info!(in_flight = drain.in_flight(), "Draining streams and in-flight uploads...");
+
+    if drain.wait_idle(timeout).await {
+        info!("Drain complete");
+    } else {
+        tracing::warn!(
+            in_flight = drain.in_flight(),
+            "Drain timed out, stopping with uploads in flight"
+        );
+    }
 }
 
 /// Graceful shutdown signal handler.
@@ -0,0 +1,23 @@
+//! PII encryption for customer data stored in PostgreSQL.
+//!
+//! The scheme is `titan_core::pii`, shared with the terminals
+//! (`titan_db::pii`), so values move between them unchanged. Each tenant
+//! has a random data key, stored in `tenant_data_keys` wrapped with the
+//! master key (`PII_MASTER_KEY`, never in the database).
+//! The data key derives an AES-256-GCM key for values (`"pii1:…"`) and an
+//! HMAC-SHA256 key for the lookup hash stored beside each value.
+//!
+//! ```text
+//! PII_MASTER_KEY ──unwraps──► tenant_data_keys.wrapped_key ──► data key
+//!                                                   │ HKDF
+//!                            customer_phone = "pii1:…"   customer_phone_hash
+//! ```
+//!
+//! Hashes are of the normalized value (trimmed; emails lowercased).
+//! Without a master key values are stored in plain text and hashed with a
+//! fixed key. Plain text from before encryption is returned as it is and
+//! encrypted by `Database::encrypt_existing_pii` at startup.
+
+pub use titan_core::pii::{MasterKey, PiiCipher, PiiError, PiiField, CIPHERTEXT_PREFIX};
+
+pub(crate) use titan_core::pii::random_key;
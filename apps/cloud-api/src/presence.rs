@@ -0,0 +1,197 @@
+//! # Store Presence
+//!
+//! Whether each store hub is reachable, so a tenant learns a store went
+//! dark (power cut, lost uplink) without waiting for someone to call.
+//!
+//! ## Online / Offline
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Store Presence                                  │
+//! │                                                                         │
+//! │  Subscribe stream: connect, topics, heartbeat acks                      │
+//! │       │                                                                 │
+//! │       ▼ record_seen(store)                                              │
+//! │  store_presence.last_seen_at = now                                      │
+//! │       was offline ──► online ──► "store.online" webhook                 │
+//! │                                                                         │
+//! │  PresenceMonitor (every PRESENCE_CHECK_INTERVAL):                       │
+//! │       online, last seen before now - offline_after                      │
+//! │       ──► offline ──► "store.offline" webhook                           │
+//! │                                                                         │
+//! │  NotificationService.GetStoreStatus ◄── store_presence                  │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! The cloud sends a heartbeat every 30 seconds and the hub acknowledges
+//! it, so a connected store is seen at least that often. A store that has
+//! never subscribed is reported offline but raises no event.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tracing::{error, info, warn};
+
+use crate::db::{Database, StorePresenceRecord};
+use crate::error::CloudError;
+use crate::liveness::Liveness;
+use crate::webhooks::{event_body, EVENT_STORE_OFFLINE, EVENT_STORE_ONLINE};
+
+/// Shortest `STORE_OFFLINE_AFTER_SECS`: two missed heartbeats.
+pub const MIN_STORE_OFFLINE_AFTER_SECS: u64 = 60;
+
+/// Name the monitor reports under in [`Liveness`].
+pub const PRESENCE_WORKER: &str = "presence";
+
+/// Time between checks for stores gone quiet.
+pub const PRESENCE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The `data` of a store status webhook.
+///
+/// `offline_since` is when the store was found offline: now for
+/// `store.offline`, the start of the outage for `store.online`.
+pub fn status_event_data(
+    store: &StorePresenceRecord,
+    online: bool,
+    last_seen_at: Option<DateTime<Utc>>,
+    offline_since: Option<DateTime<Utc>>,
+) -> serde_json::Value {
+    serde_json::json!({
+        "tenant_id": store.tenant_id,
+        "store_id": store.store_id,
+        "store_name": store.store_name,
+        "online": online,
+        "last_seen_at": last_seen_at.map(|t| t.to_rfc3339()),
+        "offline_since": offline_since.map(|t| t.to_rfc3339()),
+    })
+}
+
+/// Marks `store_id` seen at `now`, queueing `store.online` if it was
+/// offline.
+pub async fn record_seen(
+    db: &Database,
+    store_id: &str,
+    now: DateTime<Utc>,
+) -> Result<(), CloudError> {
+    let Some(previous) = db.touch_store_presence(store_id, now).await? else {
+        return Ok(());
+    };
+
+    let data = status_event_data(&previous, true, Some(now), previous.status_since);
+    let queued = db
+        .enqueue_webhook_event(
+            &previous.tenant_id,
+            EVENT_STORE_ONLINE,
+            &event_body(EVENT_STORE_ONLINE, data, now),
+        )
+        .await?;
+    info!(store_id = %store_id, webhooks = queued, "Store back online");
+
+    Ok(())
+}
+
+/// Marks online stores offline once they have been silent too long.
+pub struct PresenceMonitor {
+    db: Database,
+    offline_after: Duration,
+    liveness: Option<Liveness>,
+}
+
+impl PresenceMonitor {
+    /// Creates a monitor calling a store offline after `offline_after`
+    /// without a message.
+    pub fn new(db: Database, offline_after: Duration) -> Self {
+        PresenceMonitor {
+            db,
+            offline_after,
+            liveness: None,
+        }
+    }
+
+    /// Reports the monitor's loop to `liveness` as [`PRESENCE_WORKER`].
+    pub fn with_liveness(mut self, liveness: Liveness) -> Self {
+        self.liveness = Some(liveness);
+        self
+    }
+
+    /// Runs the check every `interval`, starting now.
+    pub fn spawn(self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        if let Some(liveness) = &self.liveness {
+            liveness.register(PRESENCE_WORKER, interval * 4);
+        }
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+
+            loop {
+                ticker.tick().await;
+                if let Some(liveness) = &self.liveness {
+                    liveness.beat(PRESENCE_WORKER);
+                }
+
+                if let Err(e) = self.run_once(Utc::now()).await {
+                    error!(error = %e, "Store presence check failed");
+                }
+            }
+        })
+    }
+
+    /// Marks the stores silent since before `now - offline_after` offline
+    /// and queues their `store.offline` events. Returns how many went
+    /// offline.
+    pub async fn run_once(&self, now: DateTime<Utc>) -> Result<usize, CloudError> {
+        let cutoff = now - chrono::Duration::from_std(self.offline_after).unwrap_or_default();
+        let stores = self.db.mark_stores_offline(cutoff, now).await?;
+
+        for store in &stores {
+            warn!(
+                store_id = %store.store_id,
+                last_seen_at = ?store.last_seen_at,
+                "Store offline"
+            );
+            let data = status_event_data(store, false, store.last_seen_at, Some(now));
+            self.db
+                .enqueue_webhook_event(
+                    &store.tenant_id,
+                    EVENT_STORE_OFFLINE,
+                    &event_body(EVENT_STORE_OFFLINE, data, now),
+                )
+                .await?;
+        }
+
+        Ok(stores.len())
+    }
+}
+
+// =============================================================================
+// Unit Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_status_event_data() {
+        let went_offline = Utc.with_ymd_and_hms(2026, 3, 10, 21, 5, 0).unwrap();
+        let back = Utc.with_ymd_and_hms(2026, 3, 10, 22, 0, 0).unwrap();
+        let store = StorePresenceRecord {
+            store_id: "store-001".to_string(),
+            tenant_id: "tenant-001".to_string(),
+            store_name: "Main Street".to_string(),
+            online: false,
+            last_seen_at: Some(went_offline),
+            status_since: Some(went_offline),
+        };
+
+        let data = status_event_data(&store, true, Some(back), store.status_since);
+        assert_eq!(data["store_id"], "store-001");
+        assert_eq!(data["store_name"], "Main Street");
+        assert_eq!(data["online"], true);
+        assert_eq!(data["last_seen_at"], back.to_rfc3339());
+        assert_eq!(data["offline_since"], went_offline.to_rfc3339());
+
+        let never = status_event_data(&store, false, None, None);
+        assert!(never["last_seen_at"].is_null());
+    }
+}
@@ -0,0 +1,1105 @@
+//! # Entity Processors
+//!
+//! Each entity type a store uploads (`SyncEntity.entity_type`) has an
+//! [`EntityProcessor`] that applies it to the cloud database. Processors
+//! are looked up in a [`ProcessorRegistry`], so a new entity type is one
+//! more processor registered in [`ProcessorRegistry::standard`]; the
+//! dispatcher itself does not change.
+//!
+//! ```text
+//! SyncEntity ──► ProcessorRegistry ──► processor for entity_type
+//!                      │                   │ payload from `data`
+//!                      │                   ▼
+//!                      │               process(ctx, payload) ──► PostgreSQL
+//!                      │
+//!                      └── no processor ──► quarantined_entities
+//!                                            (replayed at startup once a
+//!                                             processor exists)
+//! ```
+//!
+//! A terminal newer than the cloud may upload types the cloud does not know
+//! yet. They are kept in quarantine rather than dropped, and reported to
+//! the store as `QUARANTINED`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use prost::Message;
+use tracing::{info, info_span, warn, Instrument};
+
+use crate::db::{
+    CouponRedemptionRecord, Database, InventoryDeltaRecord, LoyaltyEntryRecord, LoyaltyRecord, PaymentRecord, QuarantinedEntityRecord, SaleItemRecord,
+    SaleRecord, StoreCreditEntryRecord, StoreCreditRecord, StoreTransferItemRecord,
+    StoreTransferRecord, SupplierItemRecord, SupplierRecord,
+};
+use crate::error::CloudError;
+use crate::proto::{self, sync_entity::Data, SyncEntity, SyncError, Timestamp as ProtoTimestamp};
+use crate::services::sync_service::erasure_record;
+use crate::telemetry;
+
+/// The store a batch came from, as authenticated.
+#[derive(Debug, Clone)]
+pub struct StoreContext {
+    pub store_id: String,
+    pub tenant_id: String,
+    pub device_id: String,
+}
+
+/// What a processor gets besides its payload.
+pub struct ProcessContext<'a> {
+    pub db: &'a Database,
+    pub store: &'a StoreContext,
+    /// The entity being processed (IDs, correlation ID).
+    pub entity: &'a SyncEntity,
+}
+
+/// Applies one entity type.
+#[tonic::async_trait]
+pub trait EntityProcessor: Send + Sync + 'static {
+    /// `SyncEntity.entity_type` handled.
+    const ENTITY_TYPE: &'static str;
+
+    /// Message carried in `SyncEntity.data` for this type.
+    type Payload: Send + Sync;
+
+    /// The payload, if `data` holds this processor's message.
+    fn payload(data: &Data) -> Option<&Self::Payload>;
+
+    /// Applies one entity.
+    async fn process(
+        &self,
+        ctx: &ProcessContext<'_>,
+        payload: &Self::Payload,
+    ) -> Result<(), SyncError>;
+}
+
+/// Object-safe face of [`EntityProcessor`], as stored in the registry.
+#[tonic::async_trait]
+trait DynProcessor: Send + Sync {
+    async fn process_entity(&self, ctx: &ProcessContext<'_>) -> Result<(), SyncError>;
+}
+
+#[tonic::async_trait]
+impl<P: EntityProcessor> DynProcessor for P {
+    async fn process_entity(&self, ctx: &ProcessContext<'_>) -> Result<(), SyncError> {
+        let payload = payload_of::<P>(ctx.entity)?;
+        self.process(ctx, payload).await
+    }
+}
+
+/// `P`'s payload in `entity`, or an error if it carries another message.
+fn payload_of<P: EntityProcessor>(entity: &SyncEntity) -> Result<&P::Payload, SyncError> {
+    entity
+        .data
+        .as_ref()
+        .and_then(P::payload)
+        .ok_or_else(|| SyncError {
+            entity_id: entity.entity_id.clone(),
+            error_code: "INVALID_PAYLOAD".to_string(),
+            error_message: format!("{} entity carries no matching data", P::ENTITY_TYPE),
+            retryable: false,
+        })
+}
+
+// =============================================================================
+// Registry
+// =============================================================================
+
+/// Entity processors by entity type. Cloning shares the processors.
+#[derive(Clone, Default)]
+pub struct ProcessorRegistry {
+    processors: HashMap<&'static str, Arc<dyn DynProcessor>>,
+}
+
+impl ProcessorRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry with every entity type the cloud handles.
+    pub fn standard() -> Self {
+        ProcessorRegistry::new()
+            .register(SaleProcessor)
+            .register(SaleItemProcessor)
+            .register(PaymentProcessor)
+            .register(InventoryDeltaProcessor)
+            .register(StoreTransferProcessor)
+            .register(StoreCreditProcessor)
+            .register(CustomerErasureProcessor)
+            .register(SupplierProcessor)
+            .register(CouponProcessor)
+            .register(LoyaltyProcessor)
+    }
+
+    /// Adds `processor`, replacing any processor of the same type.
+    pub fn register<P: EntityProcessor>(mut self, processor: P) -> Self {
+        self.processors.insert(P::ENTITY_TYPE, Arc::new(processor));
+        self
+    }
+
+    /// Whether `entity_type` has a processor.
+    pub fn handles(&self, entity_type: &str) -> bool {
+        self.processors.contains_key(entity_type)
+    }
+
+    /// Entity types with a processor, sorted.
+    pub fn entity_types(&self) -> Vec<&'static str> {
+        let mut types: Vec<_> = self.processors.keys().copied().collect();
+        types.sort_unstable();
+        types
+    }
+
+    /// Processes one entity from `store`.
+    ///
+    /// An entity of an unknown type is quarantined and reported as
+    /// `QUARANTINED` (not retryable: the cloud keeps it).
+    pub async fn process(
+        &self,
+        db: &Database,
+        store: &StoreContext,
+        entity: &SyncEntity,
+    ) -> Result<(), SyncError> {
+        // Part of the trace of the terminal command that queued the entity
+        let span = info_span!(
+            "process_entity",
+            entity_type = %entity.entity_type,
+            entity_id = %entity.entity_id
+        );
+        telemetry::set_parent(&span, &entity.trace_context);
+
+        let ctx = ProcessContext { db, store, entity };
+        async {
+            match self.processors.get(entity.entity_type.as_str()) {
+                Some(processor) => processor.process_entity(&ctx).await,
+                None => Err(quarantine(&ctx).await),
+            }
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Processes quarantined entities whose type now has a processor.
+    ///
+    /// Entities that fail stay quarantined. Returns the number processed.
+    pub async fn replay_quarantined(&self, db: &Database) -> Result<usize, CloudError> {
+        let types: Vec<String> = self.entity_types().into_iter().map(String::from).collect();
+        let mut replayed = 0;
+
+        for record in db.list_quarantined_entities(&types).await? {
+            let entity = match SyncEntity::decode(record.payload.as_slice()) {
+                Ok(entity) => entity,
+                Err(e) => {
+                    warn!(
+                        entity_id = %record.entity_id,
+                        error = %e,
+                        "Unreadable quarantined entity"
+                    );
+                    continue;
+                }
+            };
+            let store = StoreContext {
+                store_id: record.store_id.clone(),
+                tenant_id: record.tenant_id.clone(),
+                device_id: record.device_id.clone(),
+            };
+
+            match self.process(db, &store, &entity).await {
+                Ok(()) => {
+                    db.delete_quarantined_entity(
+                        &record.store_id,
+                        &record.entity_type,
+                        &record.entity_id,
+                    )
+                    .await?;
+                    replayed += 1;
+                }
+                Err(e) => warn!(
+                    entity_type = %record.entity_type,
+                    entity_id = %record.entity_id,
+                    error = %e.error_message,
+                    "Quarantined entity still fails"
+                ),
+            }
+        }
+
+        if replayed > 0 {
+            info!(replayed, "Replayed quarantined entities");
+        }
+        Ok(replayed)
+    }
+}
+
+/// Stores an entity of unknown type and builds the error reported for it.
+async fn quarantine(ctx: &ProcessContext<'_>) -> SyncError {
+    let entity = ctx.entity;
+    let record = QuarantinedEntityRecord {
+        tenant_id: ctx.store.tenant_id.clone(),
+        store_id: ctx.store.store_id.clone(),
+        device_id: ctx.store.device_id.clone(),
+        entity_type: entity.entity_type.clone(),
+        entity_id: entity.entity_id.clone(),
+        payload: entity.encode_to_vec(),
+        seen_count: 1,
+        first_seen_at: Utc::now(),
+        last_seen_at: Utc::now(),
+    };
+
+    match ctx.db.quarantine_entity(&record).await {
+        Ok(()) => {
+            warn!(
+                store_id = %ctx.store.store_id,
+                entity_type = %entity.entity_type,
+                entity_id = %entity.entity_id,
+                "Quarantined entity of unknown type"
+            );
+            SyncError {
+                entity_id: entity.entity_id.clone(),
+                error_code: "QUARANTINED".to_string(),
+                error_message: format!(
+                    "Unknown entity type: {}; kept for a later cloud version",
+                    entity.entity_type
+                ),
+                retryable: false,
+            }
+        }
+        Err(e) => SyncError {
+            entity_id: entity.entity_id.clone(),
+            error_code: "DB_ERROR".to_string(),
+            error_message: e.to_string(),
+            retryable: true,
+        },
+    }
+}
+
+// =============================================================================
+// Processors
+// =============================================================================
+
+/// Processes a sale record.
+pub struct SaleProcessor;
+
+#[tonic::async_trait]
+impl EntityProcessor for SaleProcessor {
+    const ENTITY_TYPE: &'static str = "SALE";
+    type Payload = proto::Sale;
+
+    fn payload(data: &Data) -> Option<&Self::Payload> {
+        match data {
+            Data::Sale(sale) => Some(sale),
+            _ => None,
+        }
+    }
+
+    async fn process(
+        &self,
+        ctx: &ProcessContext<'_>,
+        sale: &Self::Payload,
+    ) -> Result<(), SyncError> {
+        let created_at = parse_timestamp(&sale.created_at)?;
+        let completed_at = if let Some(ref ts) = sale.completed_at {
+            Some(parse_timestamp(&Some(ts.clone()))?)
+        } else {
+            None
+        };
+
+        let record = SaleRecord {
+            id: sale.id.clone(),
+            store_id: ctx.store.store_id.clone(),
+            device_id: sale.device_id.clone(),
+            tenant_id: ctx.store.tenant_id.clone(),
+            receipt_number: sale.receipt_number.clone(),
+            station_number: (sale.station_number > 0).then_some(sale.station_number),
+            subtotal_cents: sale.subtotal.as_ref().map(|m| m.cents).unwrap_or(0),
+            tax_amount_cents: sale.tax_amount.as_ref().map(|m| m.cents).unwrap_or(0),
+            discount_amount_cents: sale.discount_amount.as_ref().map(|m| m.cents).unwrap_or(0),
+            total_cents: sale.total.as_ref().map(|m| m.cents).unwrap_or(0),
+            status: sale.status.clone(),
+            created_at,
+            completed_at,
+            correlation_id: (!ctx.entity.correlation_id.is_empty())
+                .then(|| ctx.entity.correlation_id.clone()),
+        };
+
+        ctx.db.insert_sale(&record).await.map_err(|e| SyncError {
+            entity_id: sale.id.clone(),
+            error_code: "DB_ERROR".to_string(),
+            error_message: e.to_string(),
+            retryable: true,
+        })?;
+
+        Ok(())
+    }
+}
+
+/// Processes a sale item record.
+pub struct SaleItemProcessor;
+
+#[tonic::async_trait]
+impl EntityProcessor for SaleItemProcessor {
+    const ENTITY_TYPE: &'static str = "SALE_ITEM";
+    type Payload = proto::SaleItem;
+
+    fn payload(data: &Data) -> Option<&Self::Payload> {
+        match data {
+            Data::SaleItem(item) => Some(item),
+            _ => None,
+        }
+    }
+
+    async fn process(
+        &self,
+        ctx: &ProcessContext<'_>,
+        item: &Self::Payload,
+    ) -> Result<(), SyncError> {
+        let record = SaleItemRecord {
+            id: item.id.clone(),
+            sale_id: item.sale_id.clone(),
+            product_id: item.product_id.clone(),
+            sku: item.sku.clone(),
+            name: item.name.clone(),
+            quantity: item.quantity,
+            unit_price_cents: item.unit_price.as_ref().map(|m| m.cents).unwrap_or(0),
+            line_total_cents: item.line_total.as_ref().map(|m| m.cents).unwrap_or(0),
+            tax_amount_cents: item.tax_amount.as_ref().map(|m| m.cents).unwrap_or(0),
+            tax_rate_bps: item.tax_rate_bps,
+            tracking_kind: Some(item.tracking_kind.clone()).filter(|k| !k.is_empty()),
+            tracking_codes: item.tracking_codes.clone(),
+            base_price_cents: item.base_price.as_ref().map(|m| m.cents),
+        };
+
+        ctx.db
+            .insert_sale_item(&record)
+            .await
+            .map_err(|e| SyncError {
+                entity_id: item.id.clone(),
+                error_code: "DB_ERROR".to_string(),
+                error_message: e.to_string(),
+                retryable: true,
+            })?;
+
+        Ok(())
+    }
+}
+
+/// Processes a payment record.
+pub struct PaymentProcessor;
+
+#[tonic::async_trait]
+impl EntityProcessor for PaymentProcessor {
+    const ENTITY_TYPE: &'static str = "PAYMENT";
+    type Payload = proto::Payment;
+
+    fn payload(data: &Data) -> Option<&Self::Payload> {
+        match data {
+            Data::Payment(payment) => Some(payment),
+            _ => None,
+        }
+    }
+
+    async fn process(
+        &self,
+        ctx: &ProcessContext<'_>,
+        payment: &Self::Payload,
+    ) -> Result<(), SyncError> {
+        let created_at = parse_timestamp(&payment.created_at)?;
+
+        let record = PaymentRecord {
+            id: payment.id.clone(),
+            sale_id: payment.sale_id.clone(),
+            store_id: ctx.store.store_id.clone(),
+            tenant_id: ctx.store.tenant_id.clone(),
+            method: payment.method.clone(),
+            amount_cents: payment.amount.as_ref().map(|m| m.cents).unwrap_or(0),
+            change_given_cents: payment.change_given.as_ref().map(|m| m.cents).unwrap_or(0),
+            reference: if payment.reference.is_empty() {
+                None
+            } else {
+                Some(payment.reference.clone())
+            },
+            authorization_code: if payment.authorization_code.is_empty() {
+                None
+            } else {
+                Some(payment.authorization_code.clone())
+            },
+            created_at,
+        };
+
+        ctx.db
+            .insert_payment(&record)
+            .await
+            .map_err(|e| SyncError {
+                entity_id: payment.id.clone(),
+                error_code: "DB_ERROR".to_string(),
+                error_message: e.to_string(),
+                retryable: true,
+            })?;
+
+        Ok(())
+    }
+}
+
+/// Processes an inventory delta (CRDT).
+pub struct InventoryDeltaProcessor;
+
+#[tonic::async_trait]
+impl EntityProcessor for InventoryDeltaProcessor {
+    const ENTITY_TYPE: &'static str = "INVENTORY_DELTA";
+    type Payload = proto::InventoryDelta;
+
+    fn payload(data: &Data) -> Option<&Self::Payload> {
+        match data {
+            Data::InventoryDelta(delta) => Some(delta),
+            _ => None,
+        }
+    }
+
+    async fn process(
+        &self,
+        ctx: &ProcessContext<'_>,
+        delta: &Self::Payload,
+    ) -> Result<(), SyncError> {
+        let created_at = parse_timestamp(&delta.created_at)?;
+
+        let record = InventoryDeltaRecord {
+            id: delta.id.clone(),
+            store_id: ctx.store.store_id.clone(),
+            device_id: delta.device_id.clone(),
+            tenant_id: ctx.store.tenant_id.clone(),
+            product_id: delta.product_id.clone(),
+            delta: delta.delta,
+            reason: delta.reason.clone(),
+            reference_id: if delta.reference_id.is_empty() {
+                None
+            } else {
+                Some(delta.reference_id.clone())
+            },
+            value_cents: delta.value_cents,
+            created_at,
+        };
+
+        ctx.db
+            .apply_inventory_delta(&record)
+            .await
+            .map_err(|e| SyncError {
+                entity_id: delta.id.clone(),
+                error_code: "DB_ERROR".to_string(),
+                error_message: e.to_string(),
+                retryable: true,
+            })?;
+
+        Ok(())
+    }
+}
+
+/// Processes a store transfer from either the sending or receiving store.
+///
+/// Stale or late uploads (transfer already closed, or an older version)
+/// are accepted but not applied; the uploading store gets the cloud copy
+/// on its next download.
+pub struct StoreTransferProcessor;
+
+#[tonic::async_trait]
+impl EntityProcessor for StoreTransferProcessor {
+    const ENTITY_TYPE: &'static str = "STORE_TRANSFER";
+    type Payload = proto::StoreTransfer;
+
+    fn payload(data: &Data) -> Option<&Self::Payload> {
+        match data {
+            Data::StoreTransfer(transfer) => Some(transfer),
+            _ => None,
+        }
+    }
+
+    async fn process(
+        &self,
+        ctx: &ProcessContext<'_>,
+        transfer: &Self::Payload,
+    ) -> Result<(), SyncError> {
+        if transfer.from_store_id != ctx.store.store_id
+            && transfer.to_store_id != ctx.store.store_id
+        {
+            return Err(SyncError {
+                entity_id: transfer.id.clone(),
+                error_code: "FORBIDDEN".to_string(),
+                error_message: "Store is not a party to this transfer".to_string(),
+                retryable: false,
+            });
+        }
+
+        let optional_timestamp = |ts: &Option<ProtoTimestamp>| match ts {
+            Some(_) => parse_timestamp(ts).map(Some),
+            None => Ok(None),
+        };
+        let non_empty = |s: &str| {
+            if s.is_empty() {
+                None
+            } else {
+                Some(s.to_string())
+            }
+        };
+
+        let record = StoreTransferRecord {
+            id: transfer.id.clone(),
+            tenant_id: ctx.store.tenant_id.clone(),
+            transfer_number: transfer.transfer_number.clone(),
+            from_store_id: transfer.from_store_id.clone(),
+            to_store_id: transfer.to_store_id.clone(),
+            status: transfer.status.clone(),
+            notes: non_empty(&transfer.notes),
+            created_by: transfer.created_by.clone(),
+            received_by: non_empty(&transfer.received_by),
+            created_at: parse_timestamp(&transfer.created_at)?,
+            updated_at: parse_timestamp(&transfer.updated_at)?,
+            received_at: optional_timestamp(&transfer.received_at)?,
+            cancelled_at: optional_timestamp(&transfer.cancelled_at)?,
+            version: transfer.version,
+            route_seq: 0,
+        };
+
+        let items: Vec<StoreTransferItemRecord> = transfer
+            .items
+            .iter()
+            .map(|item| StoreTransferItemRecord {
+                id: item.id.clone(),
+                transfer_id: transfer.id.clone(),
+                product_id: item.product_id.clone(),
+                sku: item.sku.clone(),
+                name: item.name.clone(),
+                quantity_sent: item.quantity_sent,
+                quantity_received: item.quantity_received,
+            })
+            .collect();
+
+        let applied = ctx
+            .db
+            .upsert_store_transfer(&record, &items)
+            .await
+            .map_err(|e| SyncError {
+                entity_id: transfer.id.clone(),
+                error_code: "DB_ERROR".to_string(),
+                error_message: e.to_string(),
+                retryable: true,
+            })?;
+
+        if !applied {
+            warn!(
+                store_id = %ctx.store.store_id,
+                transfer_id = %transfer.id,
+                status = %transfer.status,
+                "Store transfer upload not applied (already closed or stale)"
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Processes a store credit account from one of the tenant's stores.
+///
+/// Entries are merged by id, so uploading the same account from several
+/// stores is harmless. A negative merged balance means the credit was
+/// spent in two stores that were offline from the cloud; it is logged,
+/// not rejected, since both sales have already happened.
+pub struct StoreCreditProcessor;
+
+#[tonic::async_trait]
+impl EntityProcessor for StoreCreditProcessor {
+    const ENTITY_TYPE: &'static str = "STORE_CREDIT";
+    type Payload = proto::StoreCredit;
+
+    fn payload(data: &Data) -> Option<&Self::Payload> {
+        match data {
+            Data::StoreCredit(credit) => Some(credit),
+            _ => None,
+        }
+    }
+
+    async fn process(
+        &self,
+        ctx: &ProcessContext<'_>,
+        credit: &Self::Payload,
+    ) -> Result<(), SyncError> {
+        let db_error = |e: crate::error::CloudError| SyncError {
+            entity_id: credit.id.clone(),
+            error_code: "DB_ERROR".to_string(),
+            error_message: e.to_string(),
+            retryable: true,
+        };
+
+        let record = StoreCreditRecord {
+            id: credit.id.clone(),
+            tenant_id: ctx.store.tenant_id.clone(),
+            credit_number: credit.credit_number.clone(),
+            customer_name: credit.customer_name.clone(),
+            customer_phone: if credit.customer_phone.is_empty() {
+                None
+            } else {
+                Some(credit.customer_phone.clone())
+            },
+            created_at: parse_timestamp(&credit.created_at)?,
+            updated_at: parse_timestamp(&credit.updated_at)?,
+            version: credit.version,
+            route_seq: 0,
+        };
+
+        let mut entries = Vec::with_capacity(credit.entries.len());
+        for entry in &credit.entries {
+            entries.push(StoreCreditEntryRecord {
+                id: entry.id.clone(),
+                account_id: credit.id.clone(),
+                kind: entry.kind.clone(),
+                amount_cents: entry.amount.as_ref().map(|m| m.cents).unwrap_or(0),
+                sale_id: if entry.sale_id.is_empty() {
+                    None
+                } else {
+                    Some(entry.sale_id.clone())
+                },
+                device_id: entry.device_id.clone(),
+                created_at: parse_timestamp(&entry.created_at)?,
+            });
+        }
+
+        let changed = ctx
+            .db
+            .upsert_store_credit(&record, &entries)
+            .await
+            .map_err(|e| match e {
+                crate::error::CloudError::Unauthorized(message) => SyncError {
+                    entity_id: credit.id.clone(),
+                    error_code: "FORBIDDEN".to_string(),
+                    error_message: message,
+                    retryable: false,
+                },
+                other => db_error(other),
+            })?;
+
+        if changed {
+            let balance = ctx
+                .db
+                .get_store_credit_balance(&credit.id)
+                .await
+                .map_err(db_error)?;
+            if balance < 0 {
+                warn!(
+                    tenant_id = %ctx.store.tenant_id,
+                    store_id = %ctx.store.store_id,
+                    credit_number = %credit.credit_number,
+                    balance_cents = balance,
+                    "Store credit overdrawn across stores"
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Processes a customer erasure made at one of the tenant's stores.
+///
+/// The cloud anonymizes its store credit accounts for the customer and
+/// routes the request to the tenant's other stores. An upload of a
+/// request already recorded is accepted and ignored.
+pub struct CustomerErasureProcessor;
+
+#[tonic::async_trait]
+impl EntityProcessor for CustomerErasureProcessor {
+    const ENTITY_TYPE: &'static str = "CUSTOMER_ERASURE";
+    type Payload = proto::CustomerErasure;
+
+    fn payload(data: &Data) -> Option<&Self::Payload> {
+        match data {
+            Data::CustomerErasure(erasure) => Some(erasure),
+            _ => None,
+        }
+    }
+
+    async fn process(
+        &self,
+        ctx: &ProcessContext<'_>,
+        erasure: &Self::Payload,
+    ) -> Result<(), SyncError> {
+        let record = erasure_record(
+            erasure,
+            &ctx.store.tenant_id,
+            parse_timestamp(&erasure.requested_at)?,
+            Some(ctx.store.store_id.clone()),
+        )
+        .ok_or_else(|| SyncError {
+            entity_id: erasure.id.clone(),
+            error_code: "VALIDATION_ERROR".to_string(),
+            error_message: "Erasure names no customer".to_string(),
+            retryable: false,
+        })?;
+
+        let accounts = ctx
+            .db
+            .forget_customer(&record)
+            .await
+            .map_err(|e| SyncError {
+                entity_id: erasure.id.clone(),
+                error_code: "DB_ERROR".to_string(),
+                error_message: e.to_string(),
+                retryable: true,
+            })?;
+
+        if let Some(accounts) = accounts {
+            info!(
+                tenant_id = %ctx.store.tenant_id,
+                store_id = %ctx.store.store_id,
+                request_id = %erasure.id,
+                store_credit_accounts = accounts,
+                "Customer erased"
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Processes a supplier and its price list from one of the tenant's
+/// stores; the newer version wins.
+pub struct SupplierProcessor;
+
+#[tonic::async_trait]
+impl EntityProcessor for SupplierProcessor {
+    const ENTITY_TYPE: &'static str = "SUPPLIER";
+    type Payload = proto::Supplier;
+
+    fn payload(data: &Data) -> Option<&Self::Payload> {
+        match data {
+            Data::Supplier(supplier) => Some(supplier),
+            _ => None,
+        }
+    }
+
+    async fn process(
+        &self,
+        ctx: &ProcessContext<'_>,
+        supplier: &Self::Payload,
+    ) -> Result<(), SyncError> {
+        let optional = |value: &str| (!value.is_empty()).then(|| value.to_string());
+
+        let record = SupplierRecord {
+            id: supplier.id.clone(),
+            tenant_id: ctx.store.tenant_id.clone(),
+            name: supplier.name.clone(),
+            contact_name: optional(&supplier.contact_name),
+            phone: optional(&supplier.phone),
+            email: optional(&supplier.email),
+            is_active: supplier.is_active,
+            created_at: parse_timestamp(&supplier.created_at)?,
+            updated_at: parse_timestamp(&supplier.updated_at)?,
+            version: supplier.version,
+        };
+        let items: Vec<SupplierItemRecord> = supplier
+            .items
+            .iter()
+            .map(|item| SupplierItemRecord {
+                supplier_id: supplier.id.clone(),
+                product_id: item.product_id.clone(),
+                supplier_sku: optional(&item.supplier_sku),
+                cost_cents: item.cost.as_ref().map(|m| m.cents).unwrap_or(0),
+            })
+            .collect();
+
+        let changed = ctx
+            .db
+            .upsert_supplier(&record, &items)
+            .await
+            .map_err(|e| match e {
+                CloudError::Unauthorized(message) => SyncError {
+                    entity_id: supplier.id.clone(),
+                    error_code: "FORBIDDEN".to_string(),
+                    error_message: message,
+                    retryable: false,
+                },
+                other => SyncError {
+                    entity_id: supplier.id.clone(),
+                    error_code: "DB_ERROR".to_string(),
+                    error_message: other.to_string(),
+                    retryable: true,
+                },
+            })?;
+
+        if changed {
+            info!(
+                tenant_id = %ctx.store.tenant_id,
+                supplier_id = %supplier.id,
+                version = supplier.version,
+                items = items.len(),
+                "Supplier updated"
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Processes the redemptions of a coupon from one of the tenant's stores.
+///
+/// Only redemptions are taken, merged by id; the definition belongs to
+/// the back office. More uses than `max_uses` means a limited coupon was
+/// spent in stores that were offline from the cloud; it is logged, not
+/// rejected, since the sales have already happened.
+pub struct CouponProcessor;
+
+#[tonic::async_trait]
+impl EntityProcessor for CouponProcessor {
+    const ENTITY_TYPE: &'static str = "COUPON";
+    type Payload = proto::Coupon;
+
+    fn payload(data: &Data) -> Option<&Self::Payload> {
+        match data {
+            Data::Coupon(coupon) => Some(coupon),
+            _ => None,
+        }
+    }
+
+    async fn process(
+        &self,
+        ctx: &ProcessContext<'_>,
+        coupon: &Self::Payload,
+    ) -> Result<(), SyncError> {
+        let mut redemptions = Vec::with_capacity(coupon.redemptions.len());
+        for redemption in &coupon.redemptions {
+            redemptions.push(CouponRedemptionRecord {
+                id: redemption.id.clone(),
+                coupon_id: coupon.id.clone(),
+                sale_id: redemption.sale_id.clone(),
+                // Stamped with the uploading store when the terminal did not
+                store_id: if redemption.store_id.is_empty() {
+                    ctx.store.store_id.clone()
+                } else {
+                    redemption.store_id.clone()
+                },
+                device_id: redemption.device_id.clone(),
+                discount_cents: redemption.discount.as_ref().map(|m| m.cents).unwrap_or(0),
+                redeemed_at: parse_timestamp(&redemption.redeemed_at)?,
+            });
+        }
+
+        let (added, uses) = ctx
+            .db
+            .add_coupon_redemptions(&ctx.store.tenant_id, &coupon.id, &redemptions)
+            .await
+            .map_err(|e| match e {
+                CloudError::Unauthorized(message) => SyncError {
+                    entity_id: coupon.id.clone(),
+                    error_code: "FORBIDDEN".to_string(),
+                    error_message: message,
+                    retryable: false,
+                },
+                CloudError::NotFound(message) => SyncError {
+                    entity_id: coupon.id.clone(),
+                    error_code: "NOT_FOUND".to_string(),
+                    error_message: message,
+                    retryable: false,
+                },
+                other => SyncError {
+                    entity_id: coupon.id.clone(),
+                    error_code: "DB_ERROR".to_string(),
+                    error_message: other.to_string(),
+                    retryable: true,
+                },
+            })?;
+
+        if added > 0 {
+            info!(
+                tenant_id = %ctx.store.tenant_id,
+                store_id = %ctx.store.store_id,
+                code = %coupon.code,
+                added,
+                uses,
+                "Coupon redemptions merged"
+            );
+            if coupon.max_uses.is_some_and(|max| uses > max) {
+                warn!(
+                    tenant_id = %ctx.store.tenant_id,
+                    code = %coupon.code,
+                    uses,
+                    max_uses = coupon.max_uses,
+                    "Coupon over-used across stores"
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Processes a loyalty account from one of the tenant's stores.
+///
+/// Entries are merged by id, so uploading the same account from several
+/// stores is harmless. Redemptions were already checked here through
+/// RedeemLoyaltyReward; a negative merged balance means rewards were spent
+/// on a terminal without a hub, and is logged, not rejected.
+pub struct LoyaltyProcessor;
+
+#[tonic::async_trait]
+impl EntityProcessor for LoyaltyProcessor {
+    const ENTITY_TYPE: &'static str = "LOYALTY";
+    type Payload = proto::LoyaltyAccount;
+
+    fn payload(data: &Data) -> Option<&Self::Payload> {
+        match data {
+            Data::Loyalty(account) => Some(account),
+            _ => None,
+        }
+    }
+
+    async fn process(
+        &self,
+        ctx: &ProcessContext<'_>,
+        account: &Self::Payload,
+    ) -> Result<(), SyncError> {
+        let (record, entries) = loyalty_records(account, &ctx.store.tenant_id)?;
+        let changed = ctx
+            .db
+            .upsert_loyalty(&record, &entries)
+            .await
+            .map_err(|e| match e {
+                CloudError::Unauthorized(message) => SyncError {
+                    entity_id: account.id.clone(),
+                    error_code: "FORBIDDEN".to_string(),
+                    error_message: message,
+                    retryable: false,
+                },
+                other => SyncError {
+                    entity_id: account.id.clone(),
+                    error_code: "DB_ERROR".to_string(),
+                    error_message: other.to_string(),
+                    retryable: true,
+                },
+            })?;
+
+        let rewards: i64 = entries.iter().map(|e| e.reward_cents).sum();
+        if changed && rewards < 0 {
+            warn!(
+                tenant_id = %ctx.store.tenant_id,
+                store_id = %ctx.store.store_id,
+                member_number = %account.member_number,
+                balance_cents = rewards,
+                "Loyalty rewards overdrawn"
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Converts an uploaded loyalty account into its database records.
+pub(crate) fn loyalty_records(
+    account: &proto::LoyaltyAccount,
+    tenant_id: &str,
+) -> Result<(LoyaltyRecord, Vec<LoyaltyEntryRecord>), SyncError> {
+    let record = LoyaltyRecord {
+        id: account.id.clone(),
+        tenant_id: tenant_id.to_string(),
+        member_number: account.member_number.clone(),
+        created_at: parse_timestamp(&account.created_at)?,
+        updated_at: parse_timestamp(&account.updated_at)?,
+        version: account.version,
+        route_seq: 0,
+    };
+
+    let mut entries = Vec::with_capacity(account.entries.len());
+    for entry in &account.entries {
+        entries.push(loyalty_entry_record(entry, &account.id)?);
+    }
+
+    Ok((record, entries))
+}
+
+/// Converts an uploaded loyalty ledger entry into its database record.
+pub(crate) fn loyalty_entry_record(
+    entry: &proto::LoyaltyEntry,
+    account_id: &str,
+) -> Result<LoyaltyEntryRecord, SyncError> {
+    Ok(LoyaltyEntryRecord {
+        id: entry.id.clone(),
+        account_id: account_id.to_string(),
+        kind: entry.kind.clone(),
+        points: entry.points,
+        reward_cents: entry.reward.as_ref().map(|m| m.cents).unwrap_or(0),
+        sale_id: if entry.sale_id.is_empty() {
+            None
+        } else {
+            Some(entry.sale_id.clone())
+        },
+        device_id: entry.device_id.clone(),
+        created_at: parse_timestamp(&entry.created_at)?,
+    })
+}
+
+/// Parse a proto timestamp to DateTime<Utc>.
+fn parse_timestamp(ts: &Option<ProtoTimestamp>) -> Result<DateTime<Utc>, SyncError> {
+    let ts = ts.as_ref().ok_or_else(|| SyncError {
+        entity_id: String::new(),
+        error_code: "INVALID_TIMESTAMP".to_string(),
+        error_message: "Missing timestamp".to_string(),
+        retryable: false,
+    })?;
+
+    DateTime::parse_from_rfc3339(&ts.value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| SyncError {
+            entity_id: String::new(),
+            error_code: "INVALID_TIMESTAMP".to_string(),
+            error_message: format!("Invalid timestamp format: {}", e),
+            retryable: false,
+        })
+}
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(entity_type: &str, data: Option<Data>) -> SyncEntity {
+        SyncEntity {
+            entity_id: "entity-1".to_string(),
+            entity_type: entity_type.to_string(),
+            data,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_standard_registry_handles_uploaded_types() {
+        let registry = ProcessorRegistry::standard();
+        assert_eq!(
+            registry.entity_types(),
+            vec![
+                "COUPON",
+                "CUSTOMER_ERASURE",
+                "INVENTORY_DELTA",
+                "LOYALTY",
+                "PAYMENT",
+                "SALE",
+                "SALE_ITEM",
+                "STORE_CREDIT",
+                "STORE_TRANSFER",
+                "SUPPLIER",
+            ]
+        );
+        assert!(!registry.handles("TIMECLOCK"));
+        assert!(!ProcessorRegistry::new().handles("SALE"));
+    }
+
+    #[test]
+    fn test_payload_must_match_entity_type() {
+        let sale = entity("SALE", Some(Data::Sale(proto::Sale::default())));
+        assert!(payload_of::<SaleProcessor>(&sale).is_ok());
+
+        let err = payload_of::<PaymentProcessor>(&sale).unwrap_err();
+        assert_eq!(err.error_code, "INVALID_PAYLOAD");
+        assert_eq!(err.entity_id, "entity-1");
+
+        let empty = entity("SALE", None);
+        assert!(payload_of::<SaleProcessor>(&empty).is_err());
+    }
+}
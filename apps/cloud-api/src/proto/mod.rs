@@ -3,5 +3,12 @@
 //! This module contains the generated Rust code from the .proto files.
 //! The code is generated by tonic-build during the build process.
 
+// Generated oneof enums carry full entity messages by value.
+#![allow(clippy::large_enum_variant)]
+
 // Include the generated code from $OUT_DIR
 tonic::include_proto!("titan.sync.v1");
+
+/// Encoded descriptors of every service and message, served by gRPC
+/// reflection so `grpcurl` and the CLI can discover the API.
+pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("titan_sync_descriptor");
@@ -0,0 +1,253 @@
+//! Tenant roles and permission checks.
+//!
+//! Two kinds of callers hold cloud tokens:
+//!
+//! ```text
+//! ┌──────────────────────────────┐      ┌──────────────────────────────────┐
+//! │ Store hub (API key exchange) │      │ Back-office user (Login)         │
+//! │ token_type = "access"        │      │ token_type = "user_access"       │
+//! └──────────────┬───────────────┘      └────────────────┬─────────────────┘
+//!                ▼                                       ▼
+//!   Principal::Store { store_id }          Principal::User { user_id, role }
+//!   sync + its own store only              role re-read from tenant_users on
+//!                                          every request (disable = instant)
+//! ```
+//!
+//! ## Role Matrix
+//! | Permission          | OWNER | MANAGER | ANALYST |
+//! |---------------------|-------|---------|---------|
+//! | `ViewReports`       |   ✓   |    ✓    |    ✓    |
+//! | `ViewConfig`        |   ✓   |    ✓    |    ✓    |
+//! | `ManageConfig`      |   ✓   |    ✓    |         |
+//! | `ImportCatalog`     |   ✓   |    ✓    |         |
+//! | `ViewAuditLog`      |   ✓   |    ✓    |         |
+//! | `ManageUsers`       |   ✓   |         |         |
+//! | `EraseCustomerData` |   ✓   |         |         |
+
+use std::fmt;
+
+use crate::auth::{extract_bearer_token, JwtManager};
+use crate::db::Database;
+use crate::error::CloudError;
+
+/// Tenant-wide role of a back-office user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Owner,
+    Manager,
+    Analyst,
+}
+
+impl Role {
+    /// Database/claim representation.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::Owner => "OWNER",
+            Role::Manager => "MANAGER",
+            Role::Analyst => "ANALYST",
+        }
+    }
+
+    /// Parses the database/claim representation.
+    pub fn parse(value: &str) -> Option<Role> {
+        match value {
+            "OWNER" => Some(Role::Owner),
+            "MANAGER" => Some(Role::Manager),
+            "ANALYST" => Some(Role::Analyst),
+            _ => None,
+        }
+    }
+
+    /// Whether this role grants `permission`.
+    pub fn allows(self, permission: Permission) -> bool {
+        match permission {
+            Permission::ViewReports | Permission::ViewConfig => true,
+            Permission::ManageConfig | Permission::ImportCatalog | Permission::ViewAuditLog => {
+                matches!(self, Role::Owner | Role::Manager)
+            }
+            Permission::ManageUsers | Permission::EraseCustomerData => self == Role::Owner,
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Back-office actions guarded by role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    ViewReports,
+    ViewConfig,
+    ManageConfig,
+    ImportCatalog,
+    ViewAuditLog,
+    ManageUsers,
+    EraseCustomerData,
+}
+
+impl Permission {
+    fn describe(self) -> &'static str {
+        match self {
+            Permission::ViewReports => "view reports",
+            Permission::ViewConfig => "view store configuration",
+            Permission::ManageConfig => "change store configuration",
+            Permission::ImportCatalog => "import the catalog",
+            Permission::ViewAuditLog => "view the audit log",
+            Permission::ManageUsers => "manage users",
+            Permission::EraseCustomerData => "erase customer data",
+        }
+    }
+}
+
+/// The authenticated caller of a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Principal {
+    /// A store hub, limited to its own store.
+    Store { store_id: String, tenant_id: String },
+
+    /// A tenant back-office user.
+    User {
+        user_id: String,
+        tenant_id: String,
+        role: Role,
+    },
+}
+
+impl Principal {
+    /// Tenant the caller belongs to.
+    pub fn tenant_id(&self) -> &str {
+        match self {
+            Principal::Store { tenant_id, .. } | Principal::User { tenant_id, .. } => tenant_id,
+        }
+    }
+
+    /// Fails with `Unauthorized` unless the caller is a user whose role
+    /// grants `permission`. Store credentials never hold back-office
+    /// permissions.
+    pub fn require(&self, permission: Permission) -> Result<(), CloudError> {
+        match self {
+            Principal::User { role, .. } if role.allows(permission) => Ok(()),
+            Principal::User { role, .. } => Err(CloudError::Unauthorized(format!(
+                "Role {} cannot {}",
+                role,
+                permission.describe()
+            ))),
+            Principal::Store { .. } => Err(CloudError::Unauthorized(format!(
+                "Store credentials cannot {}",
+                permission.describe()
+            ))),
+        }
+    }
+
+    /// Authenticates an `authorization` header value.
+    ///
+    /// Store tokens are trusted as issued. User tokens are checked against
+    /// `tenant_users` so a disabled user or a changed role takes effect
+    /// without waiting for the token to expire.
+    pub async fn authenticate(
+        db: &Database,
+        jwt_manager: &JwtManager,
+        auth_header: Option<&str>,
+    ) -> Result<Principal, CloudError> {
+        let auth_header = auth_header
+            .ok_or_else(|| CloudError::AuthFailed("Missing authorization header".to_string()))?;
+        let token = extract_bearer_token(auth_header)
+            .ok_or_else(|| CloudError::AuthFailed("Invalid authorization header".to_string()))?;
+
+        let claims = jwt_manager.validate_token(token)?;
+        match claims.token_type.as_str() {
+            "access" => Ok(Principal::Store {
+                store_id: claims.sub,
+                tenant_id: claims.tenant_id,
+            }),
+            "user_access" => {
+                let user = db
+                    .get_tenant_user(&claims.sub)
+                    .await?
+                    .filter(|u| u.is_active && u.tenant_id == claims.tenant_id)
+                    .ok_or_else(|| CloudError::AuthFailed("User is disabled".to_string()))?;
+                let role = Role::parse(&user.role)
+                    .ok_or_else(|| CloudError::Internal(format!("Unknown role: {}", user.role)))?;
+
+                Ok(Principal::User {
+                    user_id: user.id,
+                    tenant_id: user.tenant_id,
+                    role,
+                })
+            }
+            _ => Err(CloudError::AuthFailed("Expected access token".to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(role: Role) -> Principal {
+        Principal::User {
+            user_id: "user-001".to_string(),
+            tenant_id: "tenant-001".to_string(),
+            role,
+        }
+    }
+
+    #[test]
+    fn test_role_round_trip() {
+        for role in [Role::Owner, Role::Manager, Role::Analyst] {
+            assert_eq!(Role::parse(role.as_str()), Some(role));
+        }
+        assert_eq!(Role::parse("CASHIER"), None);
+    }
+
+    #[test]
+    fn test_role_matrix() {
+        assert!(user(Role::Owner).require(Permission::ManageUsers).is_ok());
+        assert!(user(Role::Manager)
+            .require(Permission::ManageUsers)
+            .is_err());
+        assert!(user(Role::Owner)
+            .require(Permission::EraseCustomerData)
+            .is_ok());
+        assert!(user(Role::Manager)
+            .require(Permission::EraseCustomerData)
+            .is_err());
+
+        assert!(user(Role::Manager)
+            .require(Permission::ImportCatalog)
+            .is_ok());
+        assert!(user(Role::Manager)
+            .require(Permission::ManageConfig)
+            .is_ok());
+        assert!(user(Role::Analyst)
+            .require(Permission::ImportCatalog)
+            .is_err());
+        assert!(user(Role::Analyst)
+            .require(Permission::ManageConfig)
+            .is_err());
+        assert!(user(Role::Manager).require(Permission::ViewAuditLog).is_ok());
+        assert!(user(Role::Analyst)
+            .require(Permission::ViewAuditLog)
+            .is_err());
+
+        assert!(user(Role::Analyst).require(Permission::ViewReports).is_ok());
+        assert!(user(Role::Analyst).require(Permission::ViewConfig).is_ok());
+    }
+
+    #[test]
+    fn test_store_has_no_back_office_permissions() {
+        let store = Principal::Store {
+            store_id: "store-001".to_string(),
+            tenant_id: "tenant-001".to_string(),
+        };
+
+        assert_eq!(store.tenant_id(), "tenant-001");
+        assert!(matches!(
+            store.require(Permission::ViewReports),
+            Err(CloudError::Unauthorized(_))
+        ));
+    }
+}
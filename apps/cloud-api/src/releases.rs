@@ -0,0 +1,177 @@
+//! # App Releases
+//!
+//! Picks the desktop app release a store is offered by
+//! `ConfigService.GetLatestRelease`.
+//!
+//! ## Release Selection
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Release Selection                               │
+//! │                                                                         │
+//! │  releases on the store's channels (beta ⊇ stable), not withdrawn        │
+//! │       │                                                                 │
+//! │       ├── version <= current version ──► skipped                        │
+//! │       ├── mandatory ──────────────────► offered                         │
+//! │       └── rollout_bucket(store, version) < rollout_percent ──► offered  │
+//! │                                                                         │
+//! │  newest offered version wins                                            │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! A store's bucket is a hash of the store ID and the version, so each
+//! release reaches a different first 10% of stores, and a store in the
+//! rollout at 10% stays in it at 50%.
+//!
+//! Staggering the terminals *within* a store is the hub's job (see
+//! `titan_sync::update_rollout`).
+
+use semver::Version;
+use sha2::{Digest, Sha256};
+
+use crate::db::ReleaseRecord;
+use crate::error::CloudError;
+
+/// Release channel names, as stored in `releases.channel`.
+pub const STABLE_CHANNEL: &str = "stable";
+pub const BETA_CHANNEL: &str = "beta";
+
+/// Channels whose releases a device on `channel` may install.
+///
+/// ## Errors
+/// `CloudError::InvalidRequest` for an unknown channel.
+pub fn channels_for(channel: &str) -> Result<&'static [&'static str], CloudError> {
+    match channel {
+        "" | STABLE_CHANNEL => Ok(&[STABLE_CHANNEL]),
+        BETA_CHANNEL => Ok(&[STABLE_CHANNEL, BETA_CHANNEL]),
+        other => Err(CloudError::InvalidRequest(format!(
+            "Unknown release channel: {}",
+            other
+        ))),
+    }
+}
+
+/// The store's rollout bucket for a version, 0-99.
+pub fn rollout_bucket(store_id: &str, version: &str) -> u32 {
+    let digest = Sha256::digest(format!("{}:{}", store_id, version).as_bytes());
+    u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]) % 100
+}
+
+/// Whether `store_id` is offered `release` at its current rollout.
+pub fn is_offered(release: &ReleaseRecord, store_id: &str) -> bool {
+    release.mandatory
+        || rollout_bucket(store_id, &release.version) < release.rollout_percent.max(0) as u32
+}
+
+/// The newest release newer than `current` that `store_id` is offered.
+///
+/// Releases with a version that does not parse are skipped.
+///
+/// ## Errors
+/// `CloudError::InvalidRequest` if `current` is not a semantic version.
+pub fn select_release<'a>(
+    releases: &'a [ReleaseRecord],
+    store_id: &str,
+    current: &str,
+) -> Result<Option<&'a ReleaseRecord>, CloudError> {
+    let current = Version::parse(current.trim()).map_err(|e| {
+        CloudError::InvalidRequest(format!("Invalid current version {:?}: {}", current, e))
+    })?;
+
+    let newest = releases
+        .iter()
+        .filter_map(|r| Version::parse(&r.version).ok().map(|v| (v, r)))
+        .filter(|(version, release)| *version > current && is_offered(release, store_id))
+        .max_by(|(a, _), (b, _)| a.cmp(b));
+
+    Ok(newest.map(|(_, release)| release))
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn release(version: &str, channel: &str, rollout_percent: i32) -> ReleaseRecord {
+        ReleaseRecord {
+            id: format!("rel-{}", version),
+            version: version.to_string(),
+            channel: channel.to_string(),
+            platform: "windows-x86_64".to_string(),
+            download_url: format!("https://downloads.example.com/titan-{}.msi", version),
+            sha256: "00".repeat(32),
+            signature: "00".repeat(64),
+            notes: None,
+            rollout_percent,
+            mandatory: false,
+            published_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_channels() {
+        assert_eq!(channels_for("stable").unwrap(), &["stable"]);
+        assert_eq!(channels_for("").unwrap(), &["stable"]);
+        assert_eq!(channels_for("beta").unwrap(), &["stable", "beta"]);
+        assert!(channels_for("nightly").is_err());
+    }
+
+    #[test]
+    fn test_selects_newest_newer_release() {
+        let releases = vec![
+            release("1.2.0", STABLE_CHANNEL, 100),
+            release("1.10.0", STABLE_CHANNEL, 100),
+            release("1.3.0", STABLE_CHANNEL, 100),
+            release("not-a-version", STABLE_CHANNEL, 100),
+        ];
+
+        let picked = select_release(&releases, "store-1", "1.2.0")
+            .unwrap()
+            .unwrap();
+        assert_eq!(picked.version, "1.10.0");
+        assert!(select_release(&releases, "store-1", "1.10.0")
+            .unwrap()
+            .is_none());
+        assert!(select_release(&releases, "store-1", "latest").is_err());
+    }
+
+    #[test]
+    fn test_prerelease_orders_before_release() {
+        let releases = vec![release("2.0.0-beta.2", BETA_CHANNEL, 100)];
+        assert!(select_release(&releases, "store-1", "2.0.0-beta.1")
+            .unwrap()
+            .is_some());
+        assert!(select_release(&releases, "store-1", "2.0.0")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_rollout_is_stable_and_widens() {
+        let stores: Vec<String> = (0..1000).map(|i| format!("store-{}", i)).collect();
+        let offered = |percent: i32| -> Vec<&String> {
+            let r = release("1.5.0", STABLE_CHANNEL, percent);
+            stores.iter().filter(|s| is_offered(&r, s)).collect()
+        };
+
+        assert!(offered(0).is_empty());
+        assert_eq!(offered(100).len(), stores.len());
+
+        let ten = offered(10);
+        let fifty = offered(50);
+        assert!((50..150).contains(&ten.len()));
+        assert!((400..600).contains(&fifty.len()));
+        assert!(ten.iter().all(|s| fifty.contains(s)));
+    }
+
+    #[test]
+    fn test_mandatory_ignores_rollout() {
+        let mut r = release("1.5.0", STABLE_CHANNEL, 0);
+        assert!(!is_offered(&r, "store-1"));
+        r.mandatory = true;
+        assert!(is_offered(&r, "store-1"));
+    }
+}
@@ -0,0 +1,490 @@
+//! # Sales Data Retention
+//!
+//! Keeps the monthly partitions of `sales`, `sale_items` and `payments`
+//! ahead of time, and archives then removes data older than each tenant's
+//! retention period.
+//!
+//! ## Retention Run
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Retention Run (daily)                           │
+//! │                                                                         │
+//! │  1. ensure partitions: current month + PARTITION_MONTHS_AHEAD           │
+//! │                                                                         │
+//! │  2. per tenant with a retention shorter than the longest one:           │
+//! │       rows older than the tenant cutoff ──► archive ──► DELETE          │
+//! │       (sale_items, payments, then sales)                                │
+//! │                                                                         │
+//! │  3. partitions entirely older than the longest retention:               │
+//! │       all rows ──► archive ──► DETACH + DROP partition                  │
+//! │                                                                         │
+//! │  No archive sink configured ──► only step 1 runs; nothing is removed    │
+//! │  that has not been exported first.                                      │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Archive Layout
+//! Rows are written as JSON lines, one object per row:
+//!
+//! ```text
+//! partitions/<table>/<partition>/part-00001.jsonl
+//! tenants/<tenant>/<table>/<run timestamp>/part-00001.jsonl
+//! ```
+//!
+//! Rows left in a `_default` partition (created before their month had a
+//! partition) are not expired.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use tracing::{error, info, warn};
+
+use crate::config::CloudConfig;
+use crate::db::{ArchivedRow, Database};
+use crate::error::CloudError;
+use crate::liveness::Liveness;
+use crate::storage::ObjectStore;
+
+/// Months of partitions created ahead of the current month.
+pub const PARTITION_MONTHS_AHEAD: i32 = 3;
+
+/// Rows per archive part.
+const ARCHIVE_BATCH_ROWS: i64 = 5000;
+
+/// Name the retention loop reports under in [`Liveness`].
+pub const RETENTION_WORKER: &str = "retention";
+
+/// Extra silence allowed for a slow run on top of two intervals.
+const RETENTION_RUN_GRACE: Duration = Duration::from_secs(3600);
+
+// =============================================================================
+// Partitioned Tables
+// =============================================================================
+
+/// The tables partitioned by month on `created_at`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartitionedTable {
+    Sales,
+    SaleItems,
+    Payments,
+}
+
+impl PartitionedTable {
+    /// All partitioned tables, children before `sales` so a tenant purge
+    /// can still find item rows through their sale.
+    pub const ALL: [PartitionedTable; 3] = [
+        PartitionedTable::SaleItems,
+        PartitionedTable::Payments,
+        PartitionedTable::Sales,
+    ];
+
+    /// Table name in PostgreSQL.
+    pub fn name(self) -> &'static str {
+        match self {
+            PartitionedTable::Sales => "sales",
+            PartitionedTable::SaleItems => "sale_items",
+            PartitionedTable::Payments => "payments",
+        }
+    }
+
+    /// SQL condition (on alias `t`) selecting one tenant's rows; `$1` is
+    /// the tenant ID.
+    pub(crate) fn tenant_filter(self) -> &'static str {
+        match self {
+            PartitionedTable::Sales | PartitionedTable::Payments => "t.tenant_id = $1",
+            PartitionedTable::SaleItems => {
+                "t.sale_id IN (SELECT id FROM sales WHERE tenant_id = $1)"
+            }
+        }
+    }
+}
+
+/// The month a partition holds, from its name (`<table>_pYYYYMM`).
+///
+/// Returns `None` for the default partition and anything else that is not
+/// a monthly partition of `table`.
+pub fn partition_month(table: PartitionedTable, partition: &str) -> Option<NaiveDate> {
+    let suffix = partition.strip_prefix(table.name())?.strip_prefix("_p")?;
+    if suffix.len() != 6 || !suffix.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let year = suffix[..4].parse().ok()?;
+    let month = suffix[4..].parse().ok()?;
+
+    NaiveDate::from_ymd_opt(year, month, 1)
+}
+
+/// First day of the month `months` months before the month of `today`.
+///
+/// Data created before this date is older than a `months` retention.
+pub fn retention_cutoff(today: NaiveDate, months: u32) -> NaiveDate {
+    let month_start = today.with_day(1).expect("day 1 exists in every month");
+    month_start
+        .checked_sub_months(Months::new(months))
+        .unwrap_or(NaiveDate::MIN)
+}
+
+/// The monthly partitions of `table` whose whole month is before `cutoff`,
+/// oldest first.
+pub fn expired_partitions(
+    table: PartitionedTable,
+    partitions: &[String],
+    cutoff: NaiveDate,
+) -> Vec<(String, NaiveDate)> {
+    let mut expired: Vec<(String, NaiveDate)> = partitions
+        .iter()
+        .filter_map(|p| partition_month(table, p).map(|month| (p.clone(), month)))
+        .filter(|(_, month)| *month < cutoff)
+        .collect();
+    expired.sort_by_key(|(_, month)| *month);
+    expired
+}
+
+/// Joins archived rows into a JSON lines body.
+fn to_jsonl(rows: &[ArchivedRow]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for row in rows {
+        body.extend_from_slice(row.json.as_bytes());
+        body.push(b'\n');
+    }
+    body
+}
+
+// =============================================================================
+// Archive Sinks
+// =============================================================================
+
+/// Destination for archived rows.
+#[tonic::async_trait]
+pub trait ArchiveSink: Send + Sync {
+    /// Stores `body` under `key`, replacing anything already there.
+    async fn put(&self, key: &str, body: Vec<u8>) -> Result<(), CloudError>;
+}
+
+/// Archive sink writing files below a local (or mounted) directory.
+pub struct LocalArchiveSink {
+    root: PathBuf,
+}
+
+impl LocalArchiveSink {
+    /// Creates a sink writing into `root`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        LocalArchiveSink { root: root.into() }
+    }
+}
+
+#[tonic::async_trait]
+impl ArchiveSink for LocalArchiveSink {
+    async fn put(&self, key: &str, body: Vec<u8>) -> Result<(), CloudError> {
+        let path = self.root.join(key);
+
+        tokio::task::spawn_blocking(move || {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, body)
+        })
+        .await
+        .map_err(|e| CloudError::Internal(format!("Archive write task failed: {}", e)))?
+        .map_err(|e| CloudError::Internal(format!("Failed to write archive {}: {}", key, e)))
+    }
+}
+
+// =============================================================================
+// Retention Job
+// =============================================================================
+
+/// What a retention run did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetentionReport {
+    pub partitions_created: i32,
+    pub partitions_dropped: u32,
+    pub rows_purged: u64,
+}
+
+/// Partition maintenance and retention for sales data.
+pub struct RetentionJob {
+    db: Database,
+    sink: Option<Arc<dyn ArchiveSink>>,
+    default_months: u32,
+    liveness: Option<Liveness>,
+}
+
+impl RetentionJob {
+    /// Creates a job; without a sink, nothing is ever removed.
+    pub fn new(db: Database, sink: Option<Arc<dyn ArchiveSink>>, default_months: u32) -> Self {
+        RetentionJob {
+            db,
+            sink,
+            default_months,
+            liveness: None,
+        }
+    }
+
+    /// Creates a job from the server configuration.
+    ///
+    /// Archives go to `ARCHIVE_DIR` when set, otherwise to the `archive/`
+    /// prefix of object storage, if configured.
+    pub fn from_config(db: Database, config: &CloudConfig, storage: Option<ObjectStore>) -> Self {
+        let sink = match (&config.archive_dir, storage) {
+            (Some(dir), _) => Some(Arc::new(LocalArchiveSink::new(dir)) as Arc<dyn ArchiveSink>),
+            (None, Some(storage)) => Some(Arc::new(storage) as Arc<dyn ArchiveSink>),
+            (None, None) => None,
+        };
+
+        RetentionJob::new(db, sink, config.sales_retention_months)
+    }
+
+    /// Reports the job's loop to `liveness` as [`RETENTION_WORKER`].
+    pub fn with_liveness(mut self, liveness: Liveness) -> Self {
+        self.liveness = Some(liveness);
+        self
+    }
+
+    /// Runs the job every `interval`, starting now.
+    pub fn spawn(self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        if let Some(liveness) = &self.liveness {
+            // A run may take a while on large partitions; allow two missed
+            // ticks before calling the worker dead
+            liveness.register(RETENTION_WORKER, interval * 2 + RETENTION_RUN_GRACE);
+        }
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+
+            loop {
+                ticker.tick().await;
+                if let Some(liveness) = &self.liveness {
+                    liveness.beat(RETENTION_WORKER);
+                }
+
+                match self.run_once(Utc::now()).await {
+                    Ok(report) => info!(
+                        partitions_created = report.partitions_created,
+                        partitions_dropped = report.partitions_dropped,
+                        rows_purged = report.rows_purged,
+                        "Sales retention run complete"
+                    ),
+                    Err(e) => error!(error = %e, "Sales retention run failed"),
+                }
+            }
+        })
+    }
+
+    /// Runs partition maintenance and retention once.
+    pub async fn run_once(&self, now: DateTime<Utc>) -> Result<RetentionReport, CloudError> {
+        let today = now.date_naive();
+        let mut report = RetentionReport::default();
+
+        for table in PartitionedTable::ALL {
+            report.partitions_created += self
+                .db
+                .ensure_monthly_partitions(table, today, PARTITION_MONTHS_AHEAD)
+                .await?;
+        }
+
+        let Some(sink) = self.sink.as_deref() else {
+            warn!("No archive sink configured, skipping sales retention");
+            return Ok(report);
+        };
+
+        let tenants: Vec<(String, u32)> = self
+            .db
+            .get_tenant_retention_policies()
+            .await?
+            .into_iter()
+            .map(|(tenant_id, months)| {
+                let months = months
+                    .and_then(|m| u32::try_from(m).ok())
+                    .unwrap_or(self.default_months);
+                (tenant_id, months)
+            })
+            .collect();
+        let longest = tenants
+            .iter()
+            .map(|(_, months)| *months)
+            .max()
+            .unwrap_or(self.default_months);
+
+        // Tenants with a shorter retention lose rows inside live partitions
+        let run_stamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+        for (tenant_id, months) in tenants.iter().filter(|(_, m)| *m < longest) {
+            let cutoff = retention_cutoff(today, *months)
+                .and_time(chrono::NaiveTime::MIN)
+                .and_utc();
+            for table in PartitionedTable::ALL {
+                report.rows_purged += self
+                    .purge_tenant(sink, table, tenant_id, cutoff, &run_stamp)
+                    .await?;
+            }
+        }
+
+        // Whole months older than every tenant's retention
+        let cutoff = retention_cutoff(today, longest);
+        for table in PartitionedTable::ALL {
+            let partitions = self.db.list_partitions(table).await?;
+            for (partition, _) in expired_partitions(table, &partitions, cutoff) {
+                self.archive_partition(sink, table, &partition).await?;
+                self.db.drop_partition(table, &partition).await?;
+                report.partitions_dropped += 1;
+
+                info!(partition = %partition, "Sales partition archived and dropped");
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Archives and deletes one tenant's rows older than `before`.
+    async fn purge_tenant(
+        &self,
+        sink: &dyn ArchiveSink,
+        table: PartitionedTable,
+        tenant_id: &str,
+        before: DateTime<Utc>,
+        run_stamp: &str,
+    ) -> Result<u64, CloudError> {
+        let mut purged = 0;
+        let mut part = 1;
+
+        loop {
+            let rows = self
+                .db
+                .fetch_expired_tenant_rows(table, tenant_id, before, ARCHIVE_BATCH_ROWS)
+                .await?;
+            if rows.is_empty() {
+                break;
+            }
+
+            let key = format!(
+                "tenants/{}/{}/{}/part-{:05}.jsonl",
+                tenant_id,
+                table.name(),
+                run_stamp,
+                part
+            );
+            sink.put(&key, to_jsonl(&rows)).await?;
+            purged += self.db.delete_archived_rows(table, &rows).await?;
+            part += 1;
+        }
+
+        if purged > 0 {
+            info!(tenant_id = %tenant_id, table = table.name(), rows = purged, "Expired tenant rows archived and deleted");
+        }
+
+        Ok(purged)
+    }
+
+    /// Archives every row of a partition.
+    async fn archive_partition(
+        &self,
+        sink: &dyn ArchiveSink,
+        table: PartitionedTable,
+        partition: &str,
+    ) -> Result<(), CloudError> {
+        let mut after_id: Option<String> = None;
+        let mut part = 1;
+
+        loop {
+            let rows = self
+                .db
+                .fetch_partition_rows(partition, after_id.as_deref(), ARCHIVE_BATCH_ROWS)
+                .await?;
+            let Some(last) = rows.last() else {
+                break;
+            };
+            after_id = Some(last.id.clone());
+
+            let key = format!(
+                "partitions/{}/{}/part-{:05}.jsonl",
+                table.name(),
+                partition,
+                part
+            );
+            sink.put(&key, to_jsonl(&rows)).await?;
+            part += 1;
+        }
+
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Unit Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_partition_month_parses_only_monthly_partitions() {
+        assert_eq!(
+            partition_month(PartitionedTable::Sales, "sales_p202401"),
+            Some(date(2024, 1, 1))
+        );
+        assert_eq!(
+            partition_month(PartitionedTable::SaleItems, "sale_items_p202412"),
+            Some(date(2024, 12, 1))
+        );
+        assert_eq!(partition_month(PartitionedTable::Sales, "sales_default"), None);
+        assert_eq!(partition_month(PartitionedTable::Sales, "sale_items_p202401"), None);
+        assert_eq!(partition_month(PartitionedTable::Sales, "sales_p202413"), None);
+        assert_eq!(partition_month(PartitionedTable::Sales, "sales_p2024011"), None);
+    }
+
+    #[test]
+    fn test_retention_cutoff_counts_whole_months() {
+        assert_eq!(retention_cutoff(date(2024, 5, 17), 1), date(2024, 4, 1));
+        assert_eq!(retention_cutoff(date(2024, 5, 17), 12), date(2023, 5, 1));
+        assert_eq!(retention_cutoff(date(2024, 1, 31), 2), date(2023, 11, 1));
+    }
+
+    #[test]
+    fn test_expired_partitions_are_before_cutoff_and_sorted() {
+        let partitions = vec![
+            "payments_p202403".to_string(),
+            "payments_default".to_string(),
+            "payments_p202401".to_string(),
+            "payments_p202402".to_string(),
+        ];
+
+        let expired = expired_partitions(PartitionedTable::Payments, &partitions, date(2024, 3, 1));
+        let names: Vec<&str> = expired.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(names, vec!["payments_p202401", "payments_p202402"]);
+    }
+
+    #[test]
+    fn test_to_jsonl_writes_one_row_per_line() {
+        let row = |id: &str| ArchivedRow {
+            id: id.to_string(),
+            created_at: Utc::now(),
+            json: format!("{{\"id\":\"{}\"}}", id),
+        };
+
+        let body = to_jsonl(&[row("a"), row("b")]);
+        assert_eq!(body, b"{\"id\":\"a\"}\n{\"id\":\"b\"}\n");
+    }
+
+    #[tokio::test]
+    async fn test_local_sink_creates_directories() {
+        let root = std::env::temp_dir().join(format!("titan-archive-{}", uuid::Uuid::new_v4()));
+        let sink = LocalArchiveSink::new(&root);
+
+        sink.put("partitions/sales/sales_p202401/part-00001.jsonl", b"{}\n".to_vec())
+            .await
+            .unwrap();
+
+        let written =
+            std::fs::read(root.join("partitions/sales/sales_p202401/part-00001.jsonl")).unwrap();
+        assert_eq!(written, b"{}\n");
+
+        std::fs::remove_dir_all(root).ok();
+    }
+}
@@ -0,0 +1,121 @@
+//! Audit gRPC service implementation.
+//!
+//! Read access to the tenant's audit log (written by `crate::audit`).
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use tonic::{Request, Response, Status};
+
+use crate::auth::JwtManager;
+use crate::db::AuditLogFilter;
+use crate::proto::{
+    audit_service_server::AuditService, AuditEntry, QueryAuditLogRequest, QueryAuditLogResponse,
+    Timestamp,
+};
+use crate::rbac::{Permission, Principal};
+use crate::AppState;
+
+/// Entries per page when the request does not say.
+const DEFAULT_PAGE_SIZE: i32 = 100;
+
+/// Largest page a caller may ask for.
+const MAX_PAGE_SIZE: i32 = 1000;
+
+/// Audit service implementation.
+pub struct AuditServiceImpl {
+    state: Arc<AppState>,
+    jwt_manager: JwtManager,
+}
+
+impl AuditServiceImpl {
+    /// Create a new audit service.
+    pub fn new(state: Arc<AppState>) -> Self {
+        let jwt_manager = JwtManager::new(
+            state.config.jwt_secret.clone(),
+            state.config.jwt_access_lifetime_secs,
+            state.config.jwt_refresh_lifetime_secs,
+        );
+
+        AuditServiceImpl { state, jwt_manager }
+    }
+}
+
+#[tonic::async_trait]
+impl AuditService for AuditServiceImpl {
+    /// Query audit entries, newest first.
+    async fn query_audit_log(
+        &self,
+        request: Request<QueryAuditLogRequest>,
+    ) -> Result<Response<QueryAuditLogResponse>, Status> {
+        let auth_header = request
+            .metadata()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok());
+        let principal =
+            Principal::authenticate(&self.state.db, &self.jwt_manager, auth_header).await?;
+        principal.require(Permission::ViewAuditLog)?;
+        let req = request.into_inner();
+
+        let limit = match req.limit {
+            n if n <= 0 => DEFAULT_PAGE_SIZE,
+            n => n.min(MAX_PAGE_SIZE),
+        };
+        let filter = AuditLogFilter {
+            store_id: non_empty(req.store_id),
+            actor_id: non_empty(req.actor_id),
+            method: non_empty(req.method),
+            entity_id: non_empty(req.entity_id),
+            since: parse_timestamp(req.since, "since")?,
+            until: parse_timestamp(req.until, "until")?,
+            before_id: (req.before_id > 0).then_some(req.before_id),
+            limit: limit as i64,
+        };
+
+        let records = self
+            .state
+            .db
+            .query_audit_log(principal.tenant_id(), &filter)
+            .await?;
+
+        let next_before_id = match records.last() {
+            Some(last) if records.len() == limit as usize => last.id,
+            _ => 0,
+        };
+
+        Ok(Response::new(QueryAuditLogResponse {
+            entries: records
+                .into_iter()
+                .map(|r| AuditEntry {
+                    id: r.id,
+                    actor_type: r.actor_type,
+                    actor_id: r.actor_id.unwrap_or_default(),
+                    store_id: r.store_id.unwrap_or_default(),
+                    method: r.method,
+                    entity_ids: r.entity_ids,
+                    status_code: r.status_code,
+                    status_message: r.status_message.unwrap_or_default(),
+                    duration_ms: r.duration_ms,
+                    created_at: Some(Timestamp {
+                        value: r.created_at.to_rfc3339(),
+                    }),
+                })
+                .collect(),
+            next_before_id,
+        }))
+    }
+}
+
+fn non_empty(value: String) -> Option<String> {
+    (!value.is_empty()).then_some(value)
+}
+
+#[allow(clippy::result_large_err)] // tonic::Status is the service error type
+fn parse_timestamp(ts: Option<Timestamp>, field: &str) -> Result<Option<DateTime<Utc>>, Status> {
+    match ts {
+        Some(ts) if !ts.value.is_empty() => DateTime::parse_from_rfc3339(&ts.value)
+            .map(|t| Some(t.with_timezone(&Utc)))
+            .map_err(|e| Status::invalid_argument(format!("Invalid {}: {}", field, e))),
+        _ => Ok(None),
+    }
+}
@@ -1,19 +1,36 @@
 //! Authentication gRPC service implementation.
 //!
-//! Handles API key exchange for JWT tokens.
+//! Handles API key exchange for JWT tokens, and email/password sign-in
+//! for tenant back-office users.
+//!
+//! ## Refresh Rotation
+//! ```text
+//! ExchangeToken / Login ──► new session, refresh token R1
+//! RefreshToken(R1) ──► R1 marked used, R2 issued
+//! RefreshToken(R1) again ──► reuse: session revoked, R2 refused too
+//! RevokeToken(any token of the session) ──► session revoked
+//! ```
+//! A leaked refresh token is therefore good for one refresh at most, and
+//! only until its owner refreshes; after that either side presenting a
+//! used token ends the session for both.
 
 use std::sync::Arc;
 
 use tonic::{Request, Response, Status};
 use tracing::{info, warn};
+use uuid::Uuid;
 
-use crate::auth::JwtManager;
+use crate::auth::{Claims, JwtManager};
+use crate::db::RefreshRotation;
 use crate::proto::{
     auth_service_server::AuthService,
     ExchangeTokenRequest, ExchangeTokenResponse,
+    LoginRequest, LoginResponse,
     RefreshTokenRequest, RefreshTokenResponse,
     RevokeTokenRequest, RevokeTokenResponse,
 };
+use crate::rbac::Role;
+use crate::services::user_service::login_response;
 use crate::AppState;
 
 /// Authentication service implementation.
@@ -33,6 +50,35 @@ impl AuthServiceImpl {
         
         AuthServiceImpl { state, jwt_manager }
     }
+
+    /// Record `next` as the successor of the presented refresh token.
+    async fn rotate(&self, presented: &Claims, next: &Claims) -> Result<(), Status> {
+        if presented.sid.is_none() {
+            return Err(Status::unauthenticated(
+                "Refresh token predates session tracking; sign in again",
+            ));
+        }
+
+        match self.state.db.rotate_refresh_token(presented, next).await? {
+            RefreshRotation::Rotated => Ok(()),
+            RefreshRotation::Reused => {
+                warn!(
+                    tenant_id = %presented.tenant_id,
+                    subject = %presented.sub,
+                    session_id = presented.sid.as_deref().unwrap_or_default(),
+                    "Refresh token reused, session revoked"
+                );
+                Err(Status::unauthenticated("Refresh token reused; session revoked"))
+            }
+            RefreshRotation::Revoked => Err(Status::unauthenticated("Session revoked")),
+            RefreshRotation::Unknown => Err(Status::unauthenticated("Unknown refresh token")),
+        }
+    }
+}
+
+/// A new session ID.
+pub(crate) fn new_session_id() -> String {
+    Uuid::new_v4().to_string()
 }
 
 #[tonic::async_trait]
@@ -68,14 +114,16 @@ impl AuthService for AuthServiceImpl {
             }
         };
 
-        // Generate tokens
+        // Generate tokens for a new session
+        let session_id = new_session_id();
         let access_token = self.jwt_manager
-            .generate_access_token(&store.id, &store.tenant_id, &req.device_id)
+            .generate_access_token(&store.id, &store.tenant_id, &req.device_id, &session_id)
             .map_err(|e| Status::internal(e.to_string()))?;
 
-        let refresh_token = self.jwt_manager
-            .generate_refresh_token(&store.id, &store.tenant_id, &req.device_id)
+        let (refresh_token, refresh_claims) = self.jwt_manager
+            .generate_refresh_token(&store.id, &store.tenant_id, &req.device_id, &session_id)
             .map_err(|e| Status::internal(e.to_string()))?;
+        self.state.db.start_session(&refresh_claims).await?;
 
         info!(
             store_id = %store.id,
@@ -92,24 +140,54 @@ impl AuthService for AuthServiceImpl {
     }
 
     /// Refresh an expiring token.
+    ///
+    /// The refresh token is single-use; see the module docs.
     async fn refresh_token(
         &self,
         request: Request<RefreshTokenRequest>,
     ) -> Result<Response<RefreshTokenResponse>, Status> {
         let req = request.into_inner();
 
+        // Back-office users are re-issued tokens with their current role,
+        // and only while they are still active
+        if let Ok(claims) = self.jwt_manager.validate_user_refresh_token(&req.refresh_token) {
+            let user = self.state.db
+                .get_tenant_user(&claims.sub)
+                .await?
+                .filter(|u| u.is_active && u.tenant_id == claims.tenant_id)
+                .ok_or_else(|| Status::unauthenticated("User is disabled"))?;
+            let role = Role::parse(&user.role)
+                .ok_or_else(|| Status::internal(format!("Unknown role: {}", user.role)))?;
+            let session_id = claims.sid.clone().unwrap_or_default();
+
+            let (refresh_token, next) = self.jwt_manager
+                .generate_user_refresh_token(&user.id, &user.tenant_id, role, &session_id)?;
+            self.rotate(&claims, &next).await?;
+
+            info!(user_id = %user.id, "User token refreshed successfully");
+
+            return Ok(Response::new(RefreshTokenResponse {
+                access_token: self.jwt_manager
+                    .generate_user_access_token(&user.id, &user.tenant_id, role, &session_id)?,
+                refresh_token,
+                expires_in: self.state.config.jwt_access_lifetime_secs,
+            }));
+        }
+
         // Validate the refresh token
         let claims = self.jwt_manager
             .validate_refresh_token(&req.refresh_token)
             .map_err(|e| Status::unauthenticated(e.to_string()))?;
 
-        // Generate new tokens
-        let access_token = self.jwt_manager
-            .generate_access_token(&claims.sub, &claims.tenant_id, &claims.device_id)
+        // Generate new tokens in the same session
+        let session_id = claims.sid.clone().unwrap_or_default();
+        let (refresh_token, next) = self.jwt_manager
+            .generate_refresh_token(&claims.sub, &claims.tenant_id, &claims.device_id, &session_id)
             .map_err(|e| Status::internal(e.to_string()))?;
+        self.rotate(&claims, &next).await?;
 
-        let refresh_token = self.jwt_manager
-            .generate_refresh_token(&claims.sub, &claims.tenant_id, &claims.device_id)
+        let access_token = self.jwt_manager
+            .generate_access_token(&claims.sub, &claims.tenant_id, &claims.device_id, &session_id)
             .map_err(|e| Status::internal(e.to_string()))?;
 
         info!(
@@ -126,23 +204,56 @@ impl AuthService for AuthServiceImpl {
     }
 
     /// Revoke a token (logout).
+    ///
+    /// Ends the token's session: its refresh tokens are refused from now
+    /// on. Access tokens already issued run until they expire.
     async fn revoke_token(
         &self,
         request: Request<RevokeTokenRequest>,
     ) -> Result<Response<RevokeTokenResponse>, Status> {
         let req = request.into_inner();
 
-        // In a full implementation, we would add the token to a blacklist
-        // For now, we just acknowledge the request
         info!("Token revocation requested");
 
         // Validate the token exists and is valid
-        let _ = self.jwt_manager
+        let claims = self.jwt_manager
             .validate_token(&req.token)
             .map_err(|e| Status::invalid_argument(e.to_string()))?;
 
-        // TODO: Add to token blacklist (Redis or database)
+        if let Some(session_id) = &claims.sid {
+            let revoked = self.state.db
+                .revoke_session(session_id, &claims.tenant_id, "LOGOUT")
+                .await?;
+            info!(session_id = %session_id, revoked, "Session revoked");
+        }
 
         Ok(Response::new(RevokeTokenResponse { success: true }))
     }
+
+    /// Sign in a tenant back-office user.
+    async fn login(
+        &self,
+        request: Request<LoginRequest>,
+    ) -> Result<Response<LoginResponse>, Status> {
+        let req = request.into_inner();
+        let email = req.email.trim().to_lowercase();
+
+        let user = match self.state.db.authenticate_tenant_user(&email, &req.password).await? {
+            Some(u) => u,
+            None => {
+                warn!("Invalid email or password");
+                return Err(Status::unauthenticated("Invalid email or password"));
+            }
+        };
+
+        info!(tenant_id = %user.tenant_id, user_id = %user.id, "User signed in");
+
+        Ok(Response::new(login_response(
+            &self.state.db,
+            &self.jwt_manager,
+            self.state.config.jwt_access_lifetime_secs,
+            user,
+        )
+        .await?))
+    }
 }
@@ -4,18 +4,29 @@
 
 use std::sync::Arc;
 
+use chrono::{DateTime, Utc};
 use tonic::{Request, Response, Status};
 use tracing::info;
+use uuid::Uuid;
 
-use crate::auth::{extract_bearer_token, JwtManager};
+use crate::audit::AuditContext;
+use crate::auth::JwtManager;
+use crate::db::CouponRecord;
+use crate::feature_flags;
 use crate::proto::{
     config_service_server::ConfigService,
-    GetConfigValueRequest, GetConfigValueResponse,
+    FeatureFlag, GetConfigValueRequest, GetConfigValueResponse,
+    GetFeatureFlagsRequest, GetFeatureFlagsResponse,
+    GetLatestReleaseRequest, GetLatestReleaseResponse,
     GetStoreConfigRequest, GetStoreConfigResponse,
-    StoreConfig as ProtoStoreConfig,
+    ReleaseInfo, SetFeatureFlagRequest, SetFeatureFlagResponse,
+    StoreConfig as ProtoStoreConfig, Timestamp,
     UpdateConfigValueRequest, UpdateConfigValueResponse,
-    Timestamp as ProtoTimestamp,
+    UpsertCouponRequest, UpsertCouponResponse,
 };
+use crate::rbac::{Permission, Principal};
+use crate::releases;
+use crate::services::sync_service::coupon_proto;
 use crate::AppState;
 
 /// Config service implementation.
@@ -36,22 +47,46 @@ impl ConfigServiceImpl {
         ConfigServiceImpl { state, jwt_manager }
     }
 
-    /// Authenticate a request from metadata.
-    fn authenticate(&self, request: &Request<impl std::any::Any>) -> Result<(String, String), Status> {
+    /// Authenticate a request and check it may use `permission` on
+    /// `store_id`.
+    ///
+    /// A store may only read its own configuration. Back-office users need
+    /// the permission and act on any store of their tenant.
+    async fn authorize<T>(
+        &self,
+        request: &Request<T>,
+        store_id: &str,
+        permission: Permission,
+    ) -> Result<Principal, Status> {
         let auth_header = request
             .metadata()
             .get("authorization")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| Status::unauthenticated("Missing authorization header"))?;
+            .and_then(|v| v.to_str().ok());
+        let principal = Principal::authenticate(&self.state.db, &self.jwt_manager, auth_header).await?;
 
-        let token = extract_bearer_token(auth_header)
-            .ok_or_else(|| Status::unauthenticated("Invalid authorization header"))?;
+        match &principal {
+            Principal::Store { store_id: own, .. } => {
+                if own != store_id {
+                    return Err(Status::permission_denied("Cannot access other store's configuration"));
+                }
+                if permission == Permission::ManageConfig {
+                    return Err(Status::permission_denied("Store config updates are managed by tenant administrators"));
+                }
+            }
+            Principal::User { tenant_id, .. } => {
+                principal.require(permission)?;
 
-        let claims = self.jwt_manager
-            .validate_access_token(token)
-            .map_err(|e| Status::unauthenticated(e.to_string()))?;
+                let in_tenant = self.state.db
+                    .get_store(store_id)
+                    .await?
+                    .is_some_and(|s| &s.tenant_id == tenant_id);
+                if !in_tenant {
+                    return Err(Status::not_found("Store configuration not found"));
+                }
+            }
+        }
 
-        Ok((claims.sub, claims.tenant_id))
+        Ok(principal)
     }
 }
 
@@ -62,13 +97,9 @@ impl ConfigService for ConfigServiceImpl {
         &self,
         request: Request<GetStoreConfigRequest>,
     ) -> Result<Response<GetStoreConfigResponse>, Status> {
-        let (store_id, _tenant_id) = self.authenticate(&request)?;
+        self.authorize(&request, &request.get_ref().store_id, Permission::ViewConfig).await?;
         let req = request.into_inner();
-
-        // Verify the requested store matches the authenticated store
-        if req.store_id != store_id {
-            return Err(Status::permission_denied("Cannot access other store's configuration"));
-        }
+        let store_id = req.store_id.clone();
 
         info!(store_id = %store_id, "Fetching store configuration");
 
@@ -99,6 +130,7 @@ impl ConfigService for ConfigServiceImpl {
             allow_negative_inventory: config.allow_negative_inventory,
             receipt_header: config.receipt_header.unwrap_or_default(),
             receipt_footer: config.receipt_footer.unwrap_or_default(),
+            receipt_campaigns: config.receipt_campaigns.unwrap_or_default(),
             sync_batch_size: config.sync_batch_size,
             sync_interval_secs: config.sync_interval_secs,
         };
@@ -113,13 +145,9 @@ impl ConfigService for ConfigServiceImpl {
         &self,
         request: Request<GetConfigValueRequest>,
     ) -> Result<Response<GetConfigValueResponse>, Status> {
-        let (store_id, _tenant_id) = self.authenticate(&request)?;
+        self.authorize(&request, &request.get_ref().store_id, Permission::ViewConfig).await?;
         let req = request.into_inner();
-
-        // Verify the requested store matches the authenticated store
-        if req.store_id != store_id {
-            return Err(Status::permission_denied("Cannot access other store's configuration"));
-        }
+        let store_id = req.store_id.clone();
 
         info!(store_id = %store_id, key = %req.key, "Fetching config value");
 
@@ -143,6 +171,7 @@ impl ConfigService for ConfigServiceImpl {
             "tax_mode" => config.tax_mode,
             "timezone" => config.timezone.unwrap_or_else(|| "UTC".to_string()),
             "allow_negative_inventory" => config.allow_negative_inventory.to_string(),
+            "receipt_campaigns" => config.receipt_campaigns.unwrap_or_else(|| "[]".to_string()),
             "sync_batch_size" => config.sync_batch_size.to_string(),
             "sync_interval_secs" => config.sync_interval_secs.to_string(),
             _ => {
@@ -162,18 +191,325 @@ impl ConfigService for ConfigServiceImpl {
         &self,
         request: Request<UpdateConfigValueRequest>,
     ) -> Result<Response<UpdateConfigValueResponse>, Status> {
-        let (store_id, _tenant_id) = self.authenticate(&request)?;
+        let principal = self
+            .authorize(&request, &request.get_ref().store_id, Permission::ManageConfig)
+            .await?;
         let req = request.into_inner();
 
-        // Verify the requested store matches the authenticated store
-        if req.store_id != store_id {
-            return Err(Status::permission_denied("Cannot modify other store's configuration"));
+        if let Err(message) = validate_config_value(&req.key, &req.value) {
+            return Ok(Response::new(UpdateConfigValueResponse {
+                success: false,
+                error_message: message,
+            }));
+        }
+
+        let updated = self.state.db
+            .update_store_config_value(&req.store_id, &req.key, &req.value)
+            .await?;
+        if !updated {
+            return Err(Status::not_found("Store configuration not found"));
         }
 
-        info!(store_id = %store_id, key = %req.key, "Updating config value");
+        if let Principal::User { user_id, .. } = &principal {
+            info!(store_id = %req.store_id, key = %req.key, user_id = %user_id, "Config value updated");
+        }
+
+        let mut response = Response::new(UpdateConfigValueResponse {
+            success: true,
+            error_message: String::new(),
+        });
+        AuditContext {
+            store_id: Some(req.store_id),
+            entity_ids: vec![format!("config:{}", req.key)],
+            ..Default::default()
+        }
+        .attach(&mut response);
+
+        Ok(response)
+    }
+
+    /// Newest app release offered to the store (see `releases`).
+    async fn get_latest_release(
+        &self,
+        request: Request<GetLatestReleaseRequest>,
+    ) -> Result<Response<GetLatestReleaseResponse>, Status> {
+        self.authorize(&request, &request.get_ref().store_id, Permission::ViewConfig).await?;
+        let req = request.into_inner();
+
+        if req.platform.trim().is_empty() {
+            return Err(Status::invalid_argument("platform is required"));
+        }
+        let channels = releases::channels_for(&req.channel)?;
+        let candidates = self.state.db.list_releases(channels, &req.platform).await?;
+        let release = releases::select_release(&candidates, &req.store_id, &req.current_version)?;
 
-        // For now, config updates from stores are not allowed
-        // This would be implemented when we have admin functionality
-        Err(Status::permission_denied("Store config updates are managed by tenant administrators"))
+        info!(
+            store_id = %req.store_id,
+            channel = %req.channel,
+            current_version = %req.current_version,
+            offered = release.map(|r| r.version.as_str()),
+            "Release check"
+        );
+
+        Ok(Response::new(GetLatestReleaseResponse {
+            update_available: release.is_some(),
+            release: release.map(|r| ReleaseInfo {
+                version: r.version.clone(),
+                channel: r.channel.clone(),
+                platform: r.platform.clone(),
+                download_url: r.download_url.clone(),
+                sha256: r.sha256.clone(),
+                signature: r.signature.clone(),
+                notes: r.notes.clone().unwrap_or_default(),
+                mandatory: r.mandatory,
+                published_at: Some(Timestamp {
+                    value: r.published_at.to_rfc3339(),
+                }),
+            }),
+        }))
+    }
+
+    /// Feature flags in effect for the store (see `feature_flags`).
+    async fn get_feature_flags(
+        &self,
+        request: Request<GetFeatureFlagsRequest>,
+    ) -> Result<Response<GetFeatureFlagsResponse>, Status> {
+        self.authorize(&request, &request.get_ref().store_id, Permission::ViewConfig).await?;
+        let req = request.into_inner();
+
+        let store = self.state.db
+            .get_store(&req.store_id)
+            .await?
+            .ok_or_else(|| Status::not_found("Store configuration not found"))?;
+        let records = self.state.db
+            .list_feature_flags(&store.tenant_id, &req.store_id)
+            .await?;
+
+        Ok(Response::new(GetFeatureFlagsResponse {
+            flags: feature_flags::resolve(&records),
+            ttl_secs: feature_flags::FLAG_TTL_SECS,
+        }))
+    }
+
+    /// Turn a feature on or off for a store, or for its whole tenant.
+    async fn set_feature_flag(
+        &self,
+        request: Request<SetFeatureFlagRequest>,
+    ) -> Result<Response<SetFeatureFlagResponse>, Status> {
+        let principal = self
+            .authorize(&request, &request.get_ref().store_id, Permission::ManageConfig)
+            .await?;
+        let req = request.into_inner();
+        feature_flags::validate_key(&req.key)?;
+
+        let store = self.state.db
+            .get_store(&req.store_id)
+            .await?
+            .ok_or_else(|| Status::not_found("Store configuration not found"))?;
+        let scope = (!req.tenant_wide).then_some(req.store_id.as_str());
+        let record = self.state.db
+            .set_feature_flag(&store.tenant_id, scope, &req.key, req.enabled)
+            .await?;
+
+        if let Principal::User { user_id, .. } = &principal {
+            info!(
+                tenant_id = %store.tenant_id,
+                store_id = ?scope,
+                key = %req.key,
+                enabled = req.enabled,
+                user_id = %user_id,
+                "Feature flag set"
+            );
+        }
+
+        let mut response = Response::new(SetFeatureFlagResponse {
+            flag: Some(FeatureFlag {
+                key: record.key,
+                enabled: record.enabled,
+                store_override: record.store_id.is_some(),
+            }),
+        });
+        AuditContext {
+            store_id: scope.map(str::to_string),
+            entity_ids: vec![format!("feature_flag:{}", req.key)],
+            ..Default::default()
+        }
+        .attach(&mut response);
+
+        Ok(response)
+    }
+
+    /// Create or change a coupon for the tenant of `store_id`.
+    ///
+    /// The code is normalized (upper case, no spaces or dashes) and must be
+    /// unique in the tenant. Stores receive the coupon on their `coupons`
+    /// download stream.
+    async fn upsert_coupon(
+        &self,
+        request: Request<UpsertCouponRequest>,
+    ) -> Result<Response<UpsertCouponResponse>, Status> {
+        let principal = self
+            .authorize(&request, &request.get_ref().store_id, Permission::ManageConfig)
+            .await?;
+        let req = request.into_inner();
+        let coupon = req
+            .coupon
+            .ok_or_else(|| Status::invalid_argument("coupon is required"))?;
+
+        let store = self.state.db
+            .get_store(&req.store_id)
+            .await?
+            .ok_or_else(|| Status::not_found("Store configuration not found"))?;
+        let record = coupon_record(&coupon, &store.tenant_id).map_err(Status::invalid_argument)?;
+        let stored = self.state.db.upsert_coupon(&record).await?;
+
+        if let Principal::User { user_id, .. } = &principal {
+            info!(
+                tenant_id = %store.tenant_id,
+                code = %stored.code,
+                version = stored.version,
+                user_id = %user_id,
+                "Coupon saved"
+            );
+        }
+
+        let coupon_id = stored.id.clone();
+        let redemptions = self.state.db.get_coupon_redemptions(&coupon_id).await?;
+
+        let mut response = Response::new(UpsertCouponResponse {
+            coupon: Some(coupon_proto(stored, redemptions)),
+        });
+        AuditContext {
+            entity_ids: vec![format!("coupon:{}", coupon_id)],
+            ..Default::default()
+        }
+        .attach(&mut response);
+
+        Ok(response)
+    }
+}
+
+/// Check a coupon from the back office and build the record to store.
+///
+/// Timestamps are the cloud's; an empty id creates a new coupon.
+fn coupon_record(coupon: &crate::proto::Coupon, tenant_id: &str) -> Result<CouponRecord, String> {
+    let code: String = coupon
+        .code
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .flat_map(char::to_uppercase)
+        .collect();
+    if code.is_empty() || code.len() > 32 || !code.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err("code must be 1 to 32 letters and digits".to_string());
+    }
+    match coupon.kind.as_str() {
+        "PERCENT" if !(1..=10_000).contains(&coupon.value) => {
+            return Err("a PERCENT value must be 1 to 10000 basis points".to_string())
+        }
+        "AMOUNT" if coupon.value <= 0 => {
+            return Err("an AMOUNT value must be a positive number of cents".to_string())
+        }
+        "PERCENT" | "AMOUNT" => {}
+        _ => return Err("kind must be PERCENT or AMOUNT".to_string()),
+    }
+    if coupon.min_subtotal_cents.is_some_and(|m| m <= 0) {
+        return Err("min_subtotal_cents must be positive".to_string());
+    }
+    if coupon.max_uses.is_some_and(|m| m <= 0) {
+        return Err("max_uses must be positive".to_string());
+    }
+
+    let parse = |ts: &Option<Timestamp>, field: &str| -> Result<Option<DateTime<Utc>>, String> {
+        ts.as_ref()
+            .map(|ts| {
+                DateTime::parse_from_rfc3339(&ts.value)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .map_err(|e| format!("{} is not a valid timestamp: {}", field, e))
+            })
+            .transpose()
+    };
+    let starts_at = parse(&coupon.starts_at, "starts_at")?;
+    let expires_at = parse(&coupon.expires_at, "expires_at")?;
+    if let (Some(starts_at), Some(expires_at)) = (starts_at, expires_at) {
+        if expires_at <= starts_at {
+            return Err("expires_at must be after starts_at".to_string());
+        }
+    }
+
+    let now = Utc::now();
+    Ok(CouponRecord {
+        id: if coupon.id.is_empty() {
+            Uuid::new_v4().to_string()
+        } else {
+            coupon.id.clone()
+        },
+        tenant_id: tenant_id.to_string(),
+        code,
+        description: (!coupon.description.trim().is_empty()).then(|| coupon.description.trim().to_string()),
+        kind: coupon.kind.clone(),
+        value: coupon.value,
+        min_subtotal_cents: coupon.min_subtotal_cents,
+        product_ids: coupon.product_ids.clone(),
+        starts_at,
+        expires_at,
+        max_uses: coupon.max_uses,
+        active: coupon.active,
+        created_at: now,
+        updated_at: now,
+        version: 1,
+        route_seq: 0,
+    })
+}
+
+/// Check a new value for a config key before it is written.
+fn validate_config_value(key: &str, value: &str) -> Result<(), String> {
+    match key {
+        "store_name" if value.trim().is_empty() => Err("store_name cannot be empty".to_string()),
+        "currency" if value.len() != 3 || !value.chars().all(|c| c.is_ascii_uppercase()) => {
+            Err("currency must be a 3-letter ISO 4217 code".to_string())
+        }
+        "tax_mode" if value != "INCLUSIVE" && value != "EXCLUSIVE" => {
+            Err("tax_mode must be INCLUSIVE or EXCLUSIVE".to_string())
+        }
+        "allow_negative_inventory" if value.parse::<bool>().is_err() => {
+            Err("allow_negative_inventory must be true or false".to_string())
+        }
+        "sync_batch_size" | "sync_interval_secs" if !value.parse::<i32>().is_ok_and(|v| v > 0) => {
+            Err(format!("{} must be a positive integer", key))
+        }
+        "receipt_campaigns" => validate_receipt_campaigns(value),
+        "store_name" | "currency" | "tax_mode" | "timezone" | "receipt_header"
+        | "receipt_footer" | "allow_negative_inventory" | "sync_batch_size"
+        | "sync_interval_secs" => Ok(()),
+        _ => Err(format!("Config key not found: {}", key)),
+    }
+}
+
+/// Check the shape of a `receipt_campaigns` value: a JSON array of objects,
+/// each with a unique `id` and a known `type`. Terminals check the rest
+/// when they load the campaigns and skip the ones they cannot print.
+fn validate_receipt_campaigns(value: &str) -> Result<(), String> {
+    let campaigns: Vec<serde_json::Value> = serde_json::from_str(value)
+        .map_err(|e| format!("receipt_campaigns must be a JSON array: {}", e))?;
+    let mut ids = std::collections::HashSet::new();
+    for campaign in &campaigns {
+        let id = campaign
+            .get("id")
+            .and_then(|v| v.as_str())
+            .filter(|id| !id.trim().is_empty())
+            .ok_or("every receipt campaign needs an id")?;
+        if !ids.insert(id) {
+            return Err(format!("duplicate receipt campaign id: {}", id));
+        }
+        match campaign.get("type").and_then(|v| v.as_str()) {
+            Some("survey" | "coupon" | "message") => {}
+            _ => {
+                return Err(format!(
+                    "receipt campaign {} must have type survey, coupon or message",
+                    id
+                ))
+            }
+        }
     }
+    Ok(())
 }
@@ -1,16 +1,24 @@
 //! Health check gRPC service implementation.
 //!
-//! Provides health checks for monitoring and keepalive.
+//! Provides health checks for monitoring and load balancers.
+//!
+//! | Service      | Probe                                        |
+//! |--------------|----------------------------------------------|
+//! | `database`   | `SELECT 1`                                   |
+//! | `redis`      | `PING` round-trip                            |
+//! | `migrations` | applied schema version vs. this build        |
+//! | `workers`    | background worker heartbeats (`Liveness`)    |
+//! | `""`         | all of the above (Redis only degrades)       |
 
 use std::pin::Pin;
 use std::sync::Arc;
 
 use chrono::Utc;
 use tokio::sync::mpsc;
-use tokio::time::{interval, Duration};
+use tokio::time::{interval, timeout, Duration};
 use tokio_stream::{wrappers::ReceiverStream, Stream};
 use tonic::{Request, Response, Status};
-use tracing::info;
+use tracing::{info, warn};
 
 use crate::proto::{
     health_service_server::HealthService,
@@ -18,12 +26,17 @@ use crate::proto::{
     HealthCheckRequest, HealthCheckResponse,
     Timestamp as ProtoTimestamp,
 };
+use crate::db::Database;
 use crate::AppState;
 
-/// Health check interval for watch stream.
-const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// How often Watch re-checks health; only status changes are sent.
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Longest a single dependency probe may take before it counts as failed.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
 
 /// Health service implementation.
+#[derive(Clone)]
 pub struct HealthServiceImpl {
     state: Arc<AppState>,
 }
@@ -49,6 +62,14 @@ impl HealthServiceImpl {
                 // Check Redis connectivity
                 self.check_redis_health().await
             }
+            "migrations" => {
+                // Check the schema matches this build
+                self.check_migration_health().await
+            }
+            "workers" => {
+                // Check background workers are still looping
+                self.check_worker_health()
+            }
             _ => {
                 // Unknown service
                 (ServingStatus::Unknown, format!("Unknown service: {}", service))
@@ -65,6 +86,10 @@ impl HealthServiceImpl {
     }
 
     /// Check overall system health.
+    ///
+    /// NOT_SERVING when the database is unreachable, the schema is behind
+    /// this build, or a background worker has died. Redis is optional, so
+    /// a Redis failure only degrades.
     async fn check_overall_health(&self) -> (ServingStatus, String) {
         // Check database
         let db_health = self.check_database_health().await;
@@ -72,6 +97,18 @@ impl HealthServiceImpl {
             return (ServingStatus::NotServing, format!("Database unhealthy: {}", db_health.1));
         }
 
+        // Check schema version
+        let migration_health = self.check_migration_health().await;
+        if migration_health.0 != ServingStatus::Serving {
+            return (ServingStatus::NotServing, format!("Migrations unhealthy: {}", migration_health.1));
+        }
+
+        // Check background workers
+        let worker_health = self.check_worker_health();
+        if worker_health.0 != ServingStatus::Serving {
+            return (ServingStatus::NotServing, format!("Workers unhealthy: {}", worker_health.1));
+        }
+
         // Check Redis (optional)
         if self.state.redis.is_some() {
             let redis_health = self.check_redis_health().await;
@@ -86,30 +123,73 @@ impl HealthServiceImpl {
 
     /// Check database health.
     async fn check_database_health(&self) -> (ServingStatus, String) {
-        match sqlx::query("SELECT 1")
-            .fetch_one(self.state.db.pool())
-            .await
-        {
-            Ok(_) => (ServingStatus::Serving, "Database connected".to_string()),
-            Err(e) => (ServingStatus::NotServing, format!("Database error: {}", e)),
+        let probe = sqlx::query("SELECT 1").fetch_one(self.state.db.pool());
+
+        match timeout(PROBE_TIMEOUT, probe).await {
+            Ok(Ok(_)) => (ServingStatus::Serving, "Database connected".to_string()),
+            Ok(Err(e)) => (ServingStatus::NotServing, format!("Database error: {}", e)),
+            Err(_) => (ServingStatus::NotServing, "Database probe timed out".to_string()),
         }
     }
 
-    /// Check Redis health.
+    /// Check Redis health with a PING round-trip.
     async fn check_redis_health(&self) -> (ServingStatus, String) {
-        match &self.state.redis {
-            Some(client) => {
-                match client.get_connection() {
-                    Ok(mut conn) => {
-                        match redis::cmd("PING").query::<String>(&mut conn) {
-                            Ok(_) => (ServingStatus::Serving, "Redis connected".to_string()),
-                            Err(e) => (ServingStatus::NotServing, format!("Redis ping failed: {}", e)),
-                        }
-                    }
-                    Err(e) => (ServingStatus::NotServing, format!("Redis connection failed: {}", e)),
-                }
+        let client = match &self.state.redis {
+            Some(client) => client,
+            None => return (ServingStatus::Unknown, "Redis not configured".to_string()),
+        };
+
+        let probe = async {
+            let mut conn = client.get_multiplexed_async_connection().await?;
+            redis::cmd("PING").query_async::<String>(&mut conn).await
+        };
+
+        match timeout(PROBE_TIMEOUT, probe).await {
+            Ok(Ok(_)) => (ServingStatus::Serving, "Redis connected".to_string()),
+            Ok(Err(e)) => (ServingStatus::NotServing, format!("Redis ping failed: {}", e)),
+            Err(_) => (ServingStatus::NotServing, "Redis probe timed out".to_string()),
+        }
+    }
+
+    /// Check the database schema is at the version this build expects.
+    async fn check_migration_health(&self) -> (ServingStatus, String) {
+        let expected = Database::expected_migration_version();
+
+        match timeout(PROBE_TIMEOUT, self.state.db.applied_migration_version()).await {
+            Ok(Ok(Some(applied))) if applied == expected => {
+                (ServingStatus::Serving, format!("Schema at migration {}", applied))
             }
-            None => (ServingStatus::Unknown, "Redis not configured".to_string()),
+            Ok(Ok(Some(applied))) if applied > expected => (
+                // A newer instance migrated first during a rolling deploy
+                ServingStatus::Serving,
+                format!("Schema at migration {}, ahead of this build ({})", applied, expected),
+            ),
+            Ok(Ok(applied)) => (
+                ServingStatus::NotServing,
+                format!("Schema at migration {}, expected {}", applied.unwrap_or(0), expected),
+            ),
+            Ok(Err(e)) => (ServingStatus::NotServing, format!("Migration check failed: {}", e)),
+            Err(_) => (ServingStatus::NotServing, "Migration probe timed out".to_string()),
+        }
+    }
+
+    /// Check every registered background worker beat within its deadline.
+    fn check_worker_health(&self) -> (ServingStatus, String) {
+        let workers = self.state.liveness.report(Utc::now());
+        if workers.is_empty() {
+            return (ServingStatus::Serving, "No background workers".to_string());
+        }
+
+        let dead: Vec<String> = workers
+            .iter()
+            .filter(|w| !w.is_alive())
+            .map(|w| format!("{} silent for {}s", w.name, w.silent_for.as_secs()))
+            .collect();
+
+        if dead.is_empty() {
+            (ServingStatus::Serving, format!("{} workers alive", workers.len()))
+        } else {
+            (ServingStatus::NotServing, dead.join(", "))
         }
     }
 }
@@ -128,28 +208,42 @@ impl HealthService for HealthServiceImpl {
 
     type WatchStream = Pin<Box<dyn Stream<Item = Result<HealthCheckResponse, Status>> + Send>>;
 
-    /// Streaming health check (keepalive).
+    /// Streaming health check.
+    ///
+    /// Sends the current status, then one message per status transition.
     async fn watch(
         &self,
         request: Request<HealthCheckRequest>,
     ) -> Result<Response<Self::WatchStream>, Status> {
         let req = request.into_inner();
         let service = req.service;
-        let state = self.state.clone();
+        let health_service = self.clone();
 
         info!(service = %service, "Starting health watch stream");
 
         let (tx, rx) = mpsc::channel(16);
 
         tokio::spawn(async move {
-            let health_service = HealthServiceImpl { state };
             let mut check_interval = interval(HEALTH_CHECK_INTERVAL);
+            let mut last_status = None;
 
             loop {
-                check_interval.tick().await;
-                
+                tokio::select! {
+                    _ = check_interval.tick() => {}
+                    // Client disconnected
+                    _ = tx.closed() => break,
+                }
+
                 let response = health_service.check_health(&service).await;
-                
+                if last_status == Some(response.status) {
+                    continue;
+                }
+
+                if last_status.is_some() {
+                    warn!(service = %service, status = response.status, message = %response.message, "Health status changed");
+                }
+                last_status = Some(response.status);
+
                 if tx.send(Ok(response)).await.is_err() {
                     // Client disconnected
                     break;
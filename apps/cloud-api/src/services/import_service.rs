@@ -0,0 +1,282 @@
+//! Import gRPC service implementation.
+//!
+//! Bulk catalog uploads: validate and stage a file, then publish it to
+//! the tenant's products. Also sets which of those products each store
+//! carries (its assortment) and what it charges for them (price overrides).
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use tokio_stream::StreamExt;
+use tonic::metadata::MetadataMap;
+use tonic::{Request, Response, Status, Streaming};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::audit::AuditContext;
+use crate::auth::JwtManager;
+use crate::catalog_import::{parse_catalog, ImportFormat};
+use crate::db::{AssortmentChange, CatalogImportRecord, StorePriceChange};
+use crate::proto::{
+    catalog_upload_chunk::Format, import_service_server::ImportService,
+    set_store_assortment_request::Mode, CatalogImportReport, CatalogRowError, CatalogUploadChunk,
+    PublishCatalogImportRequest, PublishCatalogImportResponse, SetStoreAssortmentRequest,
+    SetStoreAssortmentResponse, SetStorePricesRequest, SetStorePricesResponse,
+};
+use crate::rbac::{Permission, Principal};
+use crate::AppState;
+
+/// Largest catalog file accepted (64 MiB).
+const MAX_UPLOAD_BYTES: usize = 64 * 1024 * 1024;
+
+/// Row errors returned in the report; all of them are stored.
+const MAX_REPORTED_ERRORS: usize = 1000;
+
+/// Import service implementation.
+pub struct ImportServiceImpl {
+    state: Arc<AppState>,
+    jwt_manager: JwtManager,
+}
+
+impl ImportServiceImpl {
+    /// Create a new import service.
+    pub fn new(state: Arc<AppState>) -> Self {
+        let jwt_manager = JwtManager::new(
+            state.config.jwt_secret.clone(),
+            state.config.jwt_access_lifetime_secs,
+            state.config.jwt_refresh_lifetime_secs,
+        );
+
+        ImportServiceImpl { state, jwt_manager }
+    }
+
+    /// Authenticate a request and require catalog import rights.
+    ///
+    /// Imports change the whole tenant's catalog, so they are reserved for
+    /// back-office users; store tokens are rejected.
+    ///
+    /// Takes the metadata rather than the request: a `Streaming` body is not
+    /// `Sync`, so the request cannot be held across the lookup.
+    async fn authorize(&self, metadata: &MetadataMap) -> Result<(String, String), Status> {
+        let auth_header = metadata
+            .get("authorization")
+            .and_then(|v| v.to_str().ok());
+        let principal = Principal::authenticate(&self.state.db, &self.jwt_manager, auth_header).await?;
+        principal.require(Permission::ImportCatalog)?;
+
+        match principal {
+            Principal::User { user_id, tenant_id, .. } => Ok((user_id, tenant_id)),
+            Principal::Store { .. } => Err(Status::permission_denied("User token required")),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl ImportService for ImportServiceImpl {
+    /// Upload, validate and stage a catalog file.
+    async fn upload_catalog(
+        &self,
+        request: Request<Streaming<CatalogUploadChunk>>,
+    ) -> Result<Response<CatalogImportReport>, Status> {
+        let (uploaded_by, tenant_id) = self.authorize(request.metadata()).await?;
+        let mut chunks = request.into_inner();
+
+        // First chunk carries the file metadata
+        let first = chunks
+            .next()
+            .await
+            .ok_or_else(|| Status::invalid_argument("Empty upload"))??;
+        let format = match Format::try_from(first.format) {
+            Ok(Format::Csv) => ImportFormat::Csv,
+            Ok(Format::Json) => ImportFormat::Json,
+            _ => return Err(Status::invalid_argument("Unknown catalog format")),
+        };
+        let file_name = first.file_name;
+        let mut data = first.data;
+
+        while let Some(chunk) = chunks.next().await {
+            data.extend_from_slice(&chunk?.data);
+            if data.len() > MAX_UPLOAD_BYTES {
+                return Err(Status::resource_exhausted(format!(
+                    "Catalog file exceeds {} bytes",
+                    MAX_UPLOAD_BYTES
+                )));
+            }
+        }
+
+        let parsed = tokio::task::spawn_blocking(move || parse_catalog(format, &data))
+            .await
+            .map_err(|e| Status::internal(e.to_string()))??;
+
+        let import = CatalogImportRecord {
+            id: Uuid::new_v4().to_string(),
+            tenant_id: tenant_id.clone(),
+            uploaded_by: uploaded_by.clone(),
+            file_name,
+            format: format.as_str().to_string(),
+            status: "STAGED".to_string(),
+            total_rows: parsed.total_rows,
+            accepted_rows: parsed.accepted.len() as i32,
+            error_rows: parsed.error_rows(),
+            created_at: Utc::now(),
+        };
+        self.state
+            .db
+            .stage_catalog_import(&import, &parsed.accepted, &parsed.errors)
+            .await?;
+
+        info!(
+            tenant_id = %tenant_id,
+            uploaded_by = %uploaded_by,
+            import_id = %import.id,
+            total = import.total_rows,
+            accepted = import.accepted_rows,
+            errors = import.error_rows,
+            "Catalog import staged"
+        );
+
+        let entity_id = format!("import:{}", import.id);
+        let mut response = Response::new(CatalogImportReport {
+            import_id: import.id,
+            total_rows: import.total_rows,
+            accepted_rows: import.accepted_rows,
+            error_rows: import.error_rows,
+            errors: parsed
+                .errors
+                .into_iter()
+                .take(MAX_REPORTED_ERRORS)
+                .map(|e| CatalogRowError {
+                    row: e.row,
+                    field: e.field,
+                    message: e.message,
+                })
+                .collect(),
+        });
+        AuditContext::entities([entity_id]).attach(&mut response);
+
+        Ok(response)
+    }
+
+    /// Apply a staged import to the tenant catalog.
+    async fn publish_catalog_import(
+        &self,
+        request: Request<PublishCatalogImportRequest>,
+    ) -> Result<Response<PublishCatalogImportResponse>, Status> {
+        let (_user_id, tenant_id) = self.authorize(request.metadata()).await?;
+        let req = request.into_inner();
+
+        let (inserted, updated, unchanged) = self
+            .state
+            .db
+            .publish_catalog_import(&req.import_id, &tenant_id)
+            .await?;
+
+        info!(
+            tenant_id = %tenant_id,
+            import_id = %req.import_id,
+            inserted,
+            updated,
+            unchanged,
+            "Catalog import published"
+        );
+
+        let mut response = Response::new(PublishCatalogImportResponse {
+            inserted,
+            updated,
+            unchanged,
+        });
+        AuditContext::entities([format!("import:{}", req.import_id)]).attach(&mut response);
+
+        Ok(response)
+    }
+
+    /// Change the products a store downloads.
+    async fn set_store_assortment(
+        &self,
+        request: Request<SetStoreAssortmentRequest>,
+    ) -> Result<Response<SetStoreAssortmentResponse>, Status> {
+        let (_user_id, tenant_id) = self.authorize(request.metadata()).await?;
+        let req = request.into_inner();
+
+        let change = match Mode::try_from(req.mode) {
+            Ok(Mode::Replace) => AssortmentChange::Replace,
+            Ok(Mode::Add) => AssortmentChange::Add,
+            Ok(Mode::Remove) => AssortmentChange::Remove,
+            Ok(Mode::FullCatalog) => AssortmentChange::FullCatalog,
+            _ => return Err(Status::invalid_argument("Unknown assortment mode")),
+        };
+
+        let update = self
+            .state
+            .db
+            .set_store_assortment(&tenant_id, &req.store_id, change, &req.skus)
+            .await?;
+
+        info!(
+            tenant_id = %tenant_id,
+            store_id = %req.store_id,
+            ?change,
+            scoped = update.scoped,
+            products = update.product_count,
+            unknown = update.unknown_skus.len(),
+            "Store assortment changed"
+        );
+
+        let mut response = Response::new(SetStoreAssortmentResponse {
+            scoped: update.scoped,
+            product_count: update.product_count,
+            unknown_skus: update.unknown_skus,
+        });
+        AuditContext::entities([format!("store:{}", req.store_id)]).attach(&mut response);
+
+        Ok(response)
+    }
+
+    /// Set or clear a store's price overrides.
+    async fn set_store_prices(
+        &self,
+        request: Request<SetStorePricesRequest>,
+    ) -> Result<Response<SetStorePricesResponse>, Status> {
+        let (user_id, tenant_id) = self.authorize(request.metadata()).await?;
+        let req = request.into_inner();
+
+        let mut changes = Vec::with_capacity(req.prices.len());
+        for price in req.prices {
+            let price_cents = price.price.map(|m| m.cents);
+            if price_cents.is_some_and(|cents| cents < 0) {
+                return Err(Status::invalid_argument(format!(
+                    "Negative price for {}",
+                    price.sku
+                )));
+            }
+            changes.push(StorePriceChange {
+                sku: price.sku,
+                price_cents,
+            });
+        }
+
+        let (set, cleared, unknown_skus) = self
+            .state
+            .db
+            .set_store_prices(&tenant_id, &req.store_id, &user_id, &changes)
+            .await?;
+
+        info!(
+            tenant_id = %tenant_id,
+            store_id = %req.store_id,
+            set,
+            cleared,
+            unknown = unknown_skus.len(),
+            "Store prices changed"
+        );
+
+        let mut response = Response::new(SetStorePricesResponse {
+            set,
+            cleared,
+            unknown_skus,
+        });
+        AuditContext::entities([format!("store:{}", req.store_id)]).attach(&mut response);
+
+        Ok(response)
+    }
+}
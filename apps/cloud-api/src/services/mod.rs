@@ -7,3 +7,9 @@ pub mod sync_service;
 pub mod config_service;
 pub mod notification_service;
 pub mod health_service;
+pub mod storage_service;
+pub mod import_service;
+pub mod user_service;
+pub mod audit_service;
+pub mod privacy_service;
+pub mod report_service;
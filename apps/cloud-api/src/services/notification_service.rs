@@ -1,6 +1,8 @@
 //! Notification gRPC service implementation.
 //!
-//! Provides server-push notifications via bidirectional streaming.
+//! Provides server-push notifications via bidirectional streaming. Every
+//! message a store sends on its stream marks it seen (see
+//! `crate::presence`), which backs `GetStoreStatus`.
 
 use std::pin::Pin;
 use std::sync::Arc;
@@ -13,11 +15,14 @@ use tonic::{Request, Response, Status, Streaming};
 use tracing::{debug, info, warn};
 
 use crate::auth::{extract_bearer_token, JwtManager};
+use crate::drain::RETRY_PUSHBACK;
+use crate::presence::record_seen;
 use crate::proto::{
     notification_service_server::NotificationService,
-    HeartbeatNotification, Notification, SubscriptionMessage,
-    Timestamp as ProtoTimestamp,
+    GetStoreStatusRequest, GetStoreStatusResponse, GoAwayNotification, HeartbeatNotification,
+    Notification, StoreStatus, SubscriptionMessage, Timestamp as ProtoTimestamp,
 };
+use crate::rbac::{Permission, Principal};
 use crate::AppState;
 
 /// Heartbeat interval for keeping connections alive.
@@ -42,6 +47,7 @@ impl NotificationServiceImpl {
     }
 
     /// Authenticate a subscription request.
+    #[allow(clippy::result_large_err)] // tonic::Status is the service error type
     fn authenticate_stream(&self, request: &Request<Streaming<SubscriptionMessage>>) -> Result<String, Status> {
         let auth_header = request
             .metadata()
@@ -75,10 +81,15 @@ impl NotificationService for NotificationServiceImpl {
         info!(store_id = %store_id, "New notification subscription");
 
         let (tx, rx) = mpsc::channel(64);
-        let state = self.state.clone();
+        let drain = self.state.drain.clone();
+        let db = self.state.db.clone();
 
         // Spawn task to handle the subscription
         tokio::spawn(async move {
+            if let Err(e) = record_seen(&db, &store_id, Utc::now()).await {
+                warn!(store_id = %store_id, error = %e, "Failed to record store presence");
+            }
+
             let mut heartbeat_interval = interval(HEARTBEAT_INTERVAL);
             let mut notification_counter: u64 = 0;
             let mut subscribed_topics: Vec<String> = Vec::new();
@@ -89,6 +100,10 @@ impl NotificationService for NotificationServiceImpl {
                     Some(result) = inbound.next() => {
                         match result {
                             Ok(msg) => {
+                                if let Err(e) = record_seen(&db, &store_id, Utc::now()).await {
+                                    warn!(store_id = %store_id, error = %e, "Failed to record store presence");
+                                }
+
                                 debug!(
                                     store_id = %store_id,
                                     topics = ?msg.topics,
@@ -135,13 +150,82 @@ impl NotificationService for NotificationServiceImpl {
                             break;
                         }
                     }
+
+                    // Server shutting down: tell the store to reconnect
+                    // elsewhere and end the stream cleanly
+                    _ = drain.draining() => {
+                        let notification = Notification {
+                            notification_id: format!("goaway-{}", store_id),
+                            topic: "GOAWAY".to_string(),
+                            timestamp: Some(ProtoTimestamp {
+                                value: Utc::now().to_rfc3339(),
+                            }),
+                            payload: Some(crate::proto::notification::Payload::GoAway(
+                                GoAwayNotification {
+                                    reason: "Server shutting down".to_string(),
+                                    reconnect_after_ms: RETRY_PUSHBACK.as_millis() as i32,
+                                },
+                            )),
+                        };
+
+                        let _ = tx.send(Ok(notification)).await;
+                        break;
+                    }
                 }
             }
 
-            info!(store_id = %store_id, "Notification subscription ended");
+            info!(
+                store_id = %store_id,
+                topics = ?subscribed_topics,
+                "Notification subscription ended"
+            );
         });
 
         let output_stream = ReceiverStream::new(rx);
         Ok(Response::new(Box::pin(output_stream)))
     }
+
+    /// Online/offline status of the caller's stores.
+    async fn get_store_status(
+        &self,
+        request: Request<GetStoreStatusRequest>,
+    ) -> Result<Response<GetStoreStatusResponse>, Status> {
+        let auth_header = request
+            .metadata()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok());
+        let principal =
+            Principal::authenticate(&self.state.db, &self.jwt_manager, auth_header).await?;
+        principal.require(Permission::ViewReports)?;
+        let req = request.into_inner();
+
+        let store_id = (!req.store_id.is_empty()).then_some(req.store_id.as_str());
+        let stores = self
+            .state
+            .db
+            .list_store_presence(principal.tenant_id(), store_id)
+            .await?;
+        if store_id.is_some() && stores.is_empty() {
+            return Err(Status::not_found("Store not found"));
+        }
+
+        let timestamp = |t: Option<chrono::DateTime<Utc>>| {
+            t.map(|t| ProtoTimestamp {
+                value: t.to_rfc3339(),
+            })
+        };
+        Ok(Response::new(GetStoreStatusResponse {
+            stores: stores
+                .into_iter()
+                .map(|s| StoreStatus {
+                    store_id: s.store_id,
+                    store_name: s.store_name,
+                    online: s.online,
+                    last_seen_at: timestamp(s.last_seen_at),
+                    status_since: timestamp(s.status_since),
+                })
+                .collect(),
+            offline_after_secs: self.state.config.store_offline_after_secs as u32,
+        }))
+    }
 }
@@ -0,0 +1,100 @@
+//! Privacy gRPC service implementation.
+//!
+//! Customer data erasure requested from the back office. The request is
+//! recorded and carried out the same way as one uploaded by a store (see
+//! `Database::forget_customer`), then reaches every store of the tenant
+//! through the `erasures` download stream.
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use tonic::{Request, Response, Status};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::audit::AuditContext;
+use crate::auth::JwtManager;
+use crate::proto::{
+    privacy_service_server::PrivacyService, CustomerErasure, ForgetCustomerRequest,
+    ForgetCustomerResponse,
+};
+use crate::rbac::{Permission, Principal};
+use crate::services::sync_service::{erasure_proto, erasure_record};
+use crate::AppState;
+
+/// Privacy service implementation.
+pub struct PrivacyServiceImpl {
+    state: Arc<AppState>,
+    jwt_manager: JwtManager,
+}
+
+impl PrivacyServiceImpl {
+    /// Create a new privacy service.
+    pub fn new(state: Arc<AppState>) -> Self {
+        let jwt_manager = JwtManager::new(
+            state.config.jwt_secret.clone(),
+            state.config.jwt_access_lifetime_secs,
+            state.config.jwt_refresh_lifetime_secs,
+        );
+
+        PrivacyServiceImpl { state, jwt_manager }
+    }
+}
+
+#[tonic::async_trait]
+impl PrivacyService for PrivacyServiceImpl {
+    /// Erase a customer's personal data in the cloud and at every store.
+    async fn forget_customer(
+        &self,
+        request: Request<ForgetCustomerRequest>,
+    ) -> Result<Response<ForgetCustomerResponse>, Status> {
+        let auth_header = request
+            .metadata()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok());
+        let principal =
+            Principal::authenticate(&self.state.db, &self.jwt_manager, auth_header).await?;
+        principal.require(Permission::EraseCustomerData)?;
+        let Principal::User { user_id, .. } = &principal else {
+            return Err(Status::permission_denied("Only users can erase customer data"));
+        };
+        let req = request.into_inner();
+
+        let request_id = Uuid::new_v4().to_string();
+        let erasure = CustomerErasure {
+            id: request_id.clone(),
+            business_customer_id: req.business_customer_id,
+            phone: req.phone,
+            email: req.email,
+            reason: req.reason,
+            requested_by: user_id.clone(),
+            requested_at: None,
+        };
+        let record = erasure_record(&erasure, principal.tenant_id(), Utc::now(), None)
+            .ok_or_else(|| {
+                Status::invalid_argument("business_customer_id, phone or email is required")
+            })?;
+
+        let accounts = self
+            .state
+            .db
+            .forget_customer(&record)
+            .await?
+            .unwrap_or(0);
+
+        info!(
+            tenant_id = %principal.tenant_id(),
+            request_id = %request_id,
+            store_credit_accounts = accounts,
+            "Customer erased"
+        );
+
+        let mut response = Response::new(ForgetCustomerResponse {
+            erasure: Some(erasure_proto(record)),
+            store_credit_accounts: accounts,
+        });
+        AuditContext::entities([format!("erasure:{}", request_id)]).attach(&mut response);
+
+        Ok(response)
+    }
+}
@@ -0,0 +1,171 @@
+//! Report gRPC service implementation.
+//!
+//! Back-office reports built from uploaded sales and stock movements: the
+//! sales tax report (see `crate::tax_report`) and shrinkage (waste written
+//! off at the terminals) per store.
+
+use std::sync::Arc;
+
+use tonic::{Request, Response, Status};
+use tracing::debug;
+
+use crate::auth::JwtManager;
+use crate::proto::{
+    report_service_server::ReportService, GetShrinkageReportRequest,
+    GetShrinkageReportResponse, GetTaxReportRequest, GetTaxReportResponse, Money, StoreShrinkage,
+    TaxRateSummary,
+};
+use crate::rbac::{Permission, Principal};
+use crate::tax_report;
+use crate::AppState;
+
+/// Report service implementation.
+pub struct ReportServiceImpl {
+    state: Arc<AppState>,
+    jwt_manager: JwtManager,
+}
+
+impl ReportServiceImpl {
+    /// Create a new report service.
+    pub fn new(state: Arc<AppState>) -> Self {
+        let jwt_manager = JwtManager::new(
+            state.config.jwt_secret.clone(),
+            state.config.jwt_access_lifetime_secs,
+            state.config.jwt_refresh_lifetime_secs,
+        );
+
+        ReportServiceImpl { state, jwt_manager }
+    }
+
+    /// Authenticates a back-office user allowed to view reports.
+    async fn authorize<T>(&self, request: &Request<T>) -> Result<Principal, Status> {
+        let auth_header = request
+            .metadata()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok());
+        let principal =
+            Principal::authenticate(&self.state.db, &self.jwt_manager, auth_header).await?;
+        principal.require(Permission::ViewReports)?;
+        Ok(principal)
+    }
+
+    /// The requested store (`None` for every store), checked to belong to
+    /// the principal's tenant.
+    async fn report_store<'a>(
+        &self,
+        principal: &Principal,
+        store_id: &'a str,
+    ) -> Result<Option<&'a str>, Status> {
+        let store_id = (!store_id.is_empty()).then_some(store_id);
+        if let Some(store_id) = store_id {
+            let in_tenant = self
+                .state
+                .db
+                .get_store(store_id)
+                .await?
+                .is_some_and(|s| s.tenant_id == principal.tenant_id());
+            if !in_tenant {
+                return Err(Status::not_found("Store not found"));
+            }
+        }
+        Ok(store_id)
+    }
+}
+
+#[tonic::async_trait]
+impl ReportService for ReportServiceImpl {
+    /// Sales tax per rate for a date range, with a CSV export.
+    async fn get_tax_report(
+        &self,
+        request: Request<GetTaxReportRequest>,
+    ) -> Result<Response<GetTaxReportResponse>, Status> {
+        let principal = self.authorize(&request).await?;
+        let req = request.into_inner();
+
+        let (from, to) = tax_report::report_window(&req.from, &req.to)?;
+        let store_id = self.report_store(&principal, &req.store_id).await?;
+
+        let rates = self
+            .state
+            .db
+            .tax_report(principal.tenant_id(), store_id, from, to)
+            .await?;
+        let csv = tax_report::to_csv(&rates)?;
+
+        debug!(
+            tenant_id = %principal.tenant_id(),
+            from = %req.from,
+            to = %req.to,
+            rates = rates.len(),
+            "Tax report built"
+        );
+
+        let total = |f: fn(&crate::db::TaxRateRecord) -> i64| money(rates.iter().map(f).sum());
+        Ok(Response::new(GetTaxReportResponse {
+            taxable: total(|r| r.taxable_cents),
+            non_taxable: total(|r| r.non_taxable_cents),
+            tax: total(|r| r.tax_cents),
+            rates: rates
+                .into_iter()
+                .map(|r| TaxRateSummary {
+                    tax_rate_bps: r.tax_rate_bps,
+                    jurisdiction: r.jurisdiction.unwrap_or_default(),
+                    taxable: money(r.taxable_cents),
+                    non_taxable: money(r.non_taxable_cents),
+                    tax: money(r.tax_cents),
+                    line_count: r.line_count,
+                })
+                .collect(),
+            csv,
+        }))
+    }
+
+    /// Waste written off per store and reason for a date range.
+    async fn get_shrinkage_report(
+        &self,
+        request: Request<GetShrinkageReportRequest>,
+    ) -> Result<Response<GetShrinkageReportResponse>, Status> {
+        let principal = self.authorize(&request).await?;
+        let req = request.into_inner();
+
+        let (from, to) = tax_report::report_window(&req.from, &req.to)?;
+        let store_id = self.report_store(&principal, &req.store_id).await?;
+
+        let rows = self
+            .state
+            .db
+            .shrinkage_report(principal.tenant_id(), store_id, from, to)
+            .await?;
+
+        debug!(
+            tenant_id = %principal.tenant_id(),
+            from = %req.from,
+            to = %req.to,
+            rows = rows.len(),
+            "Shrinkage report built"
+        );
+
+        Ok(Response::new(GetShrinkageReportResponse {
+            units: rows.iter().map(|r| r.units).sum(),
+            value: money(rows.iter().map(|r| r.value_cents).sum()),
+            rows: rows
+                .into_iter()
+                .map(|r| StoreShrinkage {
+                    store_id: r.store_id,
+                    store_name: r.store_name,
+                    reason: r.reason,
+                    units: r.units,
+                    value: money(r.value_cents),
+                    entries: r.entries,
+                })
+                .collect(),
+        }))
+    }
+}
+
+fn money(cents: i64) -> Option<Money> {
+    Some(Money {
+        cents,
+        currency: "USD".to_string(),
+    })
+}
@@ -0,0 +1,110 @@
+//! Storage gRPC service implementation.
+//!
+//! Hands out signed object storage URLs scoped to the authenticated store.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+use crate::auth::{extract_bearer_token, JwtManager};
+use crate::proto::{
+    create_signed_url_request::{Access, Kind},
+    storage_service_server::StorageService,
+    CreateSignedUrlRequest, CreateSignedUrlResponse, Timestamp,
+};
+use crate::storage::{store_object_key, ObjectKind, MAX_SIGNED_URL_SECS};
+use crate::AppState;
+
+/// Storage service implementation.
+pub struct StorageServiceImpl {
+    state: Arc<AppState>,
+    jwt_manager: JwtManager,
+}
+
+impl StorageServiceImpl {
+    /// Create a new storage service.
+    pub fn new(state: Arc<AppState>) -> Self {
+        let jwt_manager = JwtManager::new(
+            state.config.jwt_secret.clone(),
+            state.config.jwt_access_lifetime_secs,
+            state.config.jwt_refresh_lifetime_secs,
+        );
+
+        StorageServiceImpl { state, jwt_manager }
+    }
+
+    /// Authenticate a request from metadata.
+    #[allow(clippy::result_large_err)] // tonic::Status is the service error type
+    fn authenticate(&self, request: &Request<impl std::any::Any>) -> Result<(String, String), Status> {
+        let auth_header = request
+            .metadata()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| Status::unauthenticated("Missing authorization header"))?;
+
+        let token = extract_bearer_token(auth_header)
+            .ok_or_else(|| Status::unauthenticated("Invalid authorization header"))?;
+
+        let claims = self.jwt_manager
+            .validate_access_token(token)
+            .map_err(|e| Status::unauthenticated(e.to_string()))?;
+
+        Ok((claims.sub, claims.tenant_id))
+    }
+}
+
+#[tonic::async_trait]
+impl StorageService for StorageServiceImpl {
+    /// Create a signed URL for one object in the store's area.
+    async fn create_signed_url(
+        &self,
+        request: Request<CreateSignedUrlRequest>,
+    ) -> Result<Response<CreateSignedUrlResponse>, Status> {
+        let (store_id, tenant_id) = self.authenticate(&request)?;
+        let req = request.into_inner();
+
+        let storage = self.state.storage.as_ref()
+            .ok_or_else(|| Status::unavailable("Object storage is not configured"))?;
+
+        let kind = match Kind::try_from(req.kind) {
+            Ok(Kind::StoreExport) => ObjectKind::StoreExport,
+            Ok(Kind::CatalogImport) => ObjectKind::CatalogImport,
+            Ok(Kind::CrashReport) => ObjectKind::CrashReport,
+            _ => return Err(Status::invalid_argument("Unknown object kind")),
+        };
+        let method = match Access::try_from(req.access) {
+            Ok(Access::Read) => "GET",
+            Ok(Access::Write) => "PUT",
+            _ => return Err(Status::invalid_argument("Unknown access")),
+        };
+
+        let expires_in_secs = match req.expires_in_secs {
+            secs if secs <= 0 => self.state.config.signed_url_ttl_secs,
+            secs if secs as u64 > MAX_SIGNED_URL_SECS => {
+                return Err(Status::invalid_argument(format!(
+                    "expires_in_secs must not exceed {}",
+                    MAX_SIGNED_URL_SECS
+                )));
+            }
+            secs => secs as u64,
+        };
+
+        let key = store_object_key(kind, &tenant_id, &store_id, &req.name)?;
+        let now = Utc::now();
+        let url = storage.presign(method, &key, Duration::from_secs(expires_in_secs), now)?;
+
+        info!(store_id = %store_id, key = %key, method, expires_in_secs, "Signed storage URL issued");
+
+        Ok(Response::new(CreateSignedUrlResponse {
+            url,
+            method: method.to_string(),
+            key,
+            expires_at: Some(Timestamp {
+                value: (now + chrono::Duration::seconds(expires_in_secs as i64)).to_rfc3339(),
+            }),
+        }))
+    }
+}
@@ -1,34 +1,48 @@
 //! Sync gRPC service implementation.
 //!
 //! Handles bidirectional data synchronization between Store Hubs and Cloud.
+//!
+//! `UploadBatch` processes the batch in the call, or queues it when the
+//! store allows and Redis is available (see [`crate::batch_queue`]).
 
 use std::pin::Pin;
 use std::sync::Arc;
 
 use chrono::{DateTime, Utc};
+use prost::Message;
 use tokio::sync::mpsc;
 use tokio_stream::{wrappers::ReceiverStream, Stream, StreamExt};
 use tonic::{Request, Response, Status, Streaming};
-use tracing::{debug, error, info, warn};
+use tracing::{info, info_span, warn, Instrument};
 
+use crate::audit::AuditContext;
 use crate::auth::{extract_bearer_token, JwtManager};
-use crate::db::{InventoryDeltaRecord, PaymentRecord, SaleItemRecord, SaleRecord};
+use crate::batch_queue::{self, STATUS_ACCEPTED, STATUS_COMPLETED};
+use crate::db::{
+    CouponRecord, CouponRedemptionRecord, CustomerErasureRecord, LoyaltyEntryRecord, LoyaltyRecord, PaymentRecord, QueuedUploadBatch, SaleItemRecord, SaleRecord,
+    StoreCreditEntryRecord, StoreCreditRecord, StoreTransferItemRecord, StoreTransferRecord,
+};
+use crate::drain;
+use crate::processors::{loyalty_entry_record, loyalty_records, ProcessorRegistry, StoreContext};
 use crate::proto::{
     sync_service_server::SyncService,
     AcknowledgeUpdatesRequest, AcknowledgeUpdatesResponse,
-    EntityUpdate, GetPendingUpdatesRequest,
-    GetSyncStatusRequest, GetSyncStatusResponse,
+    EntityUpdate, GetBatchStatusRequest, GetBatchStatusResponse, GetPendingUpdatesRequest,
+    GetSaleRequest, GetSaleResponse, GetSyncStatusRequest, GetSyncStatusResponse,
+    RedeemLoyaltyRewardRequest, RedeemLoyaltyRewardResponse,
     ReportCursorRequest, ReportCursorResponse,
     SyncCursor, SyncEntity, SyncError,
     UploadBatchRequest, UploadBatchResponse,
     Timestamp as ProtoTimestamp,
 };
+use crate::telemetry;
 use crate::AppState;
 
 /// Sync service implementation.
 pub struct SyncServiceImpl {
     state: Arc<AppState>,
     jwt_manager: JwtManager,
+    processors: ProcessorRegistry,
 }
 
 impl SyncServiceImpl {
@@ -40,11 +54,16 @@ impl SyncServiceImpl {
             state.config.jwt_refresh_lifetime_secs,
         );
         
-        SyncServiceImpl { state, jwt_manager }
+        SyncServiceImpl {
+            state,
+            jwt_manager,
+            processors: ProcessorRegistry::standard(),
+        }
     }
 
     /// Authenticate a request from metadata.
-    fn authenticate(&self, request: &Request<impl std::any::Any>) -> Result<AuthContext, Status> {
+    #[allow(clippy::result_large_err)] // tonic::Status is the service error type
+    fn authenticate(&self, request: &Request<impl std::any::Any>) -> Result<StoreContext, Status> {
         let auth_header = request
             .metadata()
             .get("authorization")
@@ -58,194 +77,25 @@ impl SyncServiceImpl {
             .validate_access_token(token)
             .map_err(|e| Status::unauthenticated(e.to_string()))?;
 
-        Ok(AuthContext {
+        Ok(StoreContext {
             store_id: claims.sub,
             tenant_id: claims.tenant_id,
             device_id: claims.device_id,
         })
     }
 
-    /// Process a single sync entity.
-    async fn process_entity(
-        &self,
-        auth: &AuthContext,
-        entity: &SyncEntity,
-    ) -> Result<(), SyncError> {
-        match entity.entity_type.as_str() {
-            "SALE" => {
-                if let Some(crate::proto::sync_entity::Data::Sale(sale)) = &entity.data {
-                    self.process_sale(auth, sale).await?;
-                }
-            }
-            "SALE_ITEM" => {
-                if let Some(crate::proto::sync_entity::Data::SaleItem(item)) = &entity.data {
-                    self.process_sale_item(auth, item).await?;
-                }
-            }
-            "PAYMENT" => {
-                if let Some(crate::proto::sync_entity::Data::Payment(payment)) = &entity.data {
-                    self.process_payment(auth, payment).await?;
-                }
-            }
-            "INVENTORY_DELTA" => {
-                if let Some(crate::proto::sync_entity::Data::InventoryDelta(delta)) = &entity.data {
-                    self.process_inventory_delta(auth, delta).await?;
-                }
-            }
-            other => {
-                return Err(SyncError {
-                    entity_id: entity.entity_id.clone(),
-                    error_code: "UNKNOWN_ENTITY_TYPE".to_string(),
-                    error_message: format!("Unknown entity type: {}", other),
-                    retryable: false,
-                });
-            }
-        }
-        
-        Ok(())
-    }
-
-    /// Process a sale record.
-    async fn process_sale(
-        &self,
-        auth: &AuthContext,
-        sale: &crate::proto::Sale,
-    ) -> Result<(), SyncError> {
-        let created_at = parse_timestamp(&sale.created_at)?;
-        let completed_at = if let Some(ref ts) = sale.completed_at {
-            Some(parse_timestamp(&Some(ts.clone()))?)
-        } else {
-            None
-        };
-
-        let record = SaleRecord {
-            id: sale.id.clone(),
-            store_id: auth.store_id.clone(),
-            device_id: sale.device_id.clone(),
-            tenant_id: auth.tenant_id.clone(),
-            receipt_number: sale.receipt_number.clone(),
-            subtotal_cents: sale.subtotal.as_ref().map(|m| m.cents).unwrap_or(0),
-            tax_amount_cents: sale.tax_amount.as_ref().map(|m| m.cents).unwrap_or(0),
-            discount_amount_cents: sale.discount_amount.as_ref().map(|m| m.cents).unwrap_or(0),
-            total_cents: sale.total.as_ref().map(|m| m.cents).unwrap_or(0),
-            status: sale.status.clone(),
-            created_at,
-            completed_at,
-        };
-
-        self.state.db.insert_sale(&record).await.map_err(|e| SyncError {
-            entity_id: sale.id.clone(),
-            error_code: "DB_ERROR".to_string(),
-            error_message: e.to_string(),
-            retryable: true,
-        })?;
-
-        Ok(())
-    }
-
-    /// Process a sale item record.
-    async fn process_sale_item(
-        &self,
-        _auth: &AuthContext,
-        item: &crate::proto::SaleItem,
-    ) -> Result<(), SyncError> {
-        let record = SaleItemRecord {
-            id: item.id.clone(),
-            sale_id: item.sale_id.clone(),
-            product_id: item.product_id.clone(),
-            sku: item.sku.clone(),
-            name: item.name.clone(),
-            quantity: item.quantity,
-            unit_price_cents: item.unit_price.as_ref().map(|m| m.cents).unwrap_or(0),
-            line_total_cents: item.line_total.as_ref().map(|m| m.cents).unwrap_or(0),
-            tax_amount_cents: item.tax_amount.as_ref().map(|m| m.cents).unwrap_or(0),
-            tax_rate_bps: item.tax_rate_bps,
-        };
-
-        self.state.db.insert_sale_item(&record).await.map_err(|e| SyncError {
-            entity_id: item.id.clone(),
-            error_code: "DB_ERROR".to_string(),
-            error_message: e.to_string(),
-            retryable: true,
-        })?;
-
-        Ok(())
-    }
-
-    /// Process a payment record.
-    async fn process_payment(
-        &self,
-        auth: &AuthContext,
-        payment: &crate::proto::Payment,
-    ) -> Result<(), SyncError> {
-        let created_at = parse_timestamp(&payment.created_at)?;
-
-        let record = PaymentRecord {
-            id: payment.id.clone(),
-            sale_id: payment.sale_id.clone(),
-            store_id: auth.store_id.clone(),
-            tenant_id: auth.tenant_id.clone(),
-            method: payment.method.clone(),
-            amount_cents: payment.amount.as_ref().map(|m| m.cents).unwrap_or(0),
-            change_given_cents: payment.change_given.as_ref().map(|m| m.cents).unwrap_or(0),
-            reference: if payment.reference.is_empty() { None } else { Some(payment.reference.clone()) },
-            authorization_code: if payment.authorization_code.is_empty() { None } else { Some(payment.authorization_code.clone()) },
-            created_at,
-        };
-
-        self.state.db.insert_payment(&record).await.map_err(|e| SyncError {
-            entity_id: payment.id.clone(),
-            error_code: "DB_ERROR".to_string(),
-            error_message: e.to_string(),
-            retryable: true,
-        })?;
-
-        Ok(())
-    }
-
-    /// Process an inventory delta (CRDT).
-    async fn process_inventory_delta(
-        &self,
-        auth: &AuthContext,
-        delta: &crate::proto::InventoryDelta,
-    ) -> Result<(), SyncError> {
-        let created_at = parse_timestamp(&delta.created_at)?;
-
-        let record = InventoryDeltaRecord {
-            id: delta.id.clone(),
-            store_id: auth.store_id.clone(),
-            device_id: delta.device_id.clone(),
-            tenant_id: auth.tenant_id.clone(),
-            product_id: delta.product_id.clone(),
-            delta: delta.delta,
-            reason: delta.reason.clone(),
-            reference_id: if delta.reference_id.is_empty() { None } else { Some(delta.reference_id.clone()) },
-            created_at,
-        };
-
-        self.state.db.apply_inventory_delta(&record).await.map_err(|e| SyncError {
-            entity_id: delta.id.clone(),
-            error_code: "DB_ERROR".to_string(),
-            error_message: e.to_string(),
-            retryable: true,
-        })?;
-
-        Ok(())
-    }
-}
-
-#[tonic::async_trait]
-impl SyncService for SyncServiceImpl {
-    /// Upload a batch of entities.
-    async fn upload_batch(
+    /// Process every entity of a batch and record its cursors.
+    ///
+    /// Used in the call and by the queue workers; `status` is left for the
+    /// caller to set.
+    pub(crate) async fn process_batch(
         &self,
-        request: Request<UploadBatchRequest>,
-    ) -> Result<Response<UploadBatchResponse>, Status> {
-        let auth = self.authenticate(&request)?;
-        let req = request.into_inner();
-
+        auth: &StoreContext,
+        req: &UploadBatchRequest,
+    ) -> UploadBatchResponse {
         info!(
             store_id = %auth.store_id,
+            device_id = %auth.device_id,
             batch_id = %req.batch_id,
             entity_count = req.entities.len(),
             "Processing upload batch"
@@ -255,13 +105,14 @@ impl SyncService for SyncServiceImpl {
         let mut errors = Vec::new();
 
         for entity in &req.entities {
-            match self.process_entity(&auth, entity).await {
+            match self.process_entity(auth, entity).await {
                 Ok(()) => {
                     synced_ids.push(entity.entity_id.clone());
                 }
                 Err(sync_error) => {
                     warn!(
                         entity_id = %sync_error.entity_id,
+                        correlation_id = %entity.correlation_id,
                         error = %sync_error.error_message,
                         "Failed to process entity"
                     );
@@ -281,7 +132,7 @@ impl SyncService for SyncServiceImpl {
         }
 
         let success = errors.is_empty();
-        
+
         info!(
             store_id = %auth.store_id,
             batch_id = %req.batch_id,
@@ -290,13 +141,99 @@ impl SyncService for SyncServiceImpl {
             "Batch processing complete"
         );
 
-        Ok(Response::new(UploadBatchResponse {
-            batch_id: req.batch_id,
+        UploadBatchResponse {
+            batch_id: req.batch_id.clone(),
             success,
             synced_ids,
             errors,
             new_cursor: None, // Will be set by cursor tracking
-        }))
+            ..Default::default()
+        }
+    }
+
+    /// Store a batch for the queue workers.
+    async fn enqueue_batch(
+        &self,
+        queue: &batch_queue::BatchQueue,
+        auth: &StoreContext,
+        req: &UploadBatchRequest,
+    ) -> Result<UploadBatchResponse, Status> {
+        batch_queue::validate_batch(req).map_err(Status::invalid_argument)?;
+
+        let batch = QueuedUploadBatch {
+            id: uuid::Uuid::new_v4().to_string(),
+            batch_id: req.batch_id.clone(),
+            tenant_id: auth.tenant_id.clone(),
+            store_id: auth.store_id.clone(),
+            device_id: auth.device_id.clone(),
+            entity_count: req.entities.len() as i32,
+            payload: req.encode_to_vec(),
+        };
+        let id = self.state.db.enqueue_upload_batch(&batch).await?;
+
+        // Once stored the batch is safe: if Redis is down now, the sweeper
+        // pushes it later
+        if let Err(e) = queue.push(&id).await {
+            warn!(batch_status_id = %id, error = %e, "Could not push batch to the queue");
+        }
+
+        info!(
+            store_id = %auth.store_id,
+            batch_id = %req.batch_id,
+            batch_status_id = %id,
+            entity_count = batch.entity_count,
+            "Upload batch queued"
+        );
+
+        Ok(UploadBatchResponse {
+            batch_id: req.batch_id.clone(),
+            success: true,
+            status: STATUS_ACCEPTED.to_string(),
+            batch_status_id: id,
+            ..Default::default()
+        })
+    }
+
+    /// Process a single sync entity.
+    async fn process_entity(
+        &self,
+        auth: &StoreContext,
+        entity: &SyncEntity,
+    ) -> Result<(), SyncError> {
+        self.processors.process(&self.state.db, auth, entity).await
+    }
+
+}
+
+#[tonic::async_trait]
+impl SyncService for SyncServiceImpl {
+    /// Upload a batch of entities.
+    async fn upload_batch(
+        &self,
+        request: Request<UploadBatchRequest>,
+    ) -> Result<Response<UploadBatchResponse>, Status> {
+        let auth = self.authenticate(&request)?;
+        let _in_flight = self.state.drain.track()?;
+        let span = info_span!("upload_batch", store_id = %auth.store_id);
+        telemetry::set_parent_from_metadata(&span, request.metadata());
+        let req = request.into_inner();
+
+        let ack = async {
+            match &self.state.batch_queue {
+                Some(queue) if req.allow_queued => self.enqueue_batch(queue, &auth, &req).await,
+                _ => Ok(UploadBatchResponse {
+                    status: STATUS_COMPLETED.to_string(),
+                    ..self.process_batch(&auth, &req).await
+                }),
+            }
+        }
+        .instrument(span)
+        .await?;
+
+        let mut response = Response::new(ack);
+        AuditContext::entities([format!("batch:{}", req.batch_id)]).attach(&mut response);
+
+        Ok(response)
     }
 
     type StreamUploadStream = Pin<Box<dyn Stream<Item = Result<UploadBatchResponse, Status>> + Send>>;
@@ -307,19 +244,37 @@ impl SyncService for SyncServiceImpl {
         request: Request<Streaming<UploadBatchRequest>>,
     ) -> Result<Response<Self::StreamUploadStream>, Status> {
         let auth = self.authenticate(&request)?;
+        if self.state.drain.is_draining() {
+            return Err(drain::unavailable());
+        }
         let mut stream = request.into_inner();
 
         let state = self.state.clone();
-        let jwt_manager = JwtManager::new(
-            state.config.jwt_secret.clone(),
-            state.config.jwt_access_lifetime_secs,
-            state.config.jwt_refresh_lifetime_secs,
-        );
-        
+        let processors = self.processors.clone();
         let (tx, rx) = mpsc::channel(32);
 
         tokio::spawn(async move {
-            while let Some(result) = stream.next().await {
+            loop {
+                // On shutdown, stop taking batches; the one being processed
+                // below always finishes first
+                let result = tokio::select! {
+                    next = stream.next() => match next {
+                        Some(result) => result,
+                        None => break,
+                    },
+                    _ = state.drain.draining() => {
+                        let _ = tx.send(Err(drain::unavailable())).await;
+                        break;
+                    }
+                };
+                let _in_flight = match state.drain.track() {
+                    Ok(guard) => guard,
+                    Err(status) => {
+                        let _ = tx.send(Err(status)).await;
+                        break;
+                    }
+                };
+
                 let response = match result {
                     Ok(req) => {
                         // Process each batch in the stream
@@ -327,17 +282,7 @@ impl SyncService for SyncServiceImpl {
                         let mut errors = Vec::new();
 
                         for entity in &req.entities {
-                            // Create a temporary service for processing
-                            let service = SyncServiceImpl {
-                                state: state.clone(),
-                                jwt_manager: JwtManager::new(
-                                    state.config.jwt_secret.clone(),
-                                    state.config.jwt_access_lifetime_secs,
-                                    state.config.jwt_refresh_lifetime_secs,
-                                ),
-                            };
-                            
-                            match service.process_entity(&auth, entity).await {
+                            match processors.process(&state.db, &auth, entity).await {
                                 Ok(()) => synced_ids.push(entity.entity_id.clone()),
                                 Err(e) => errors.push(e),
                             }
@@ -349,6 +294,8 @@ impl SyncService for SyncServiceImpl {
                             synced_ids,
                             errors,
                             new_cursor: None,
+                            status: STATUS_COMPLETED.to_string(),
+                            batch_status_id: String::new(),
                         })
                     }
                     Err(e) => Err(e),
@@ -380,19 +327,147 @@ impl SyncService for SyncServiceImpl {
         info!(
             store_id = %auth.store_id,
             since_version = since_version,
+            entity_types = ?req.entity_types,
             "Fetching pending updates"
         );
 
-        // Fetch pending product updates
-        let products = self.state.db
-            .get_pending_product_updates(&auth.store_id, since_version, limit)
+        // An empty filter asks for every entity type
+        let wants = |entity_type: &str| {
+            req.entity_types.is_empty() || req.entity_types.iter().any(|t| t == entity_type)
+        };
+
+        // Fetch pending product updates (limited to the store's assortment)
+        let products = if wants("PRODUCT") {
+            self.state.db
+                .get_pending_product_updates(&auth.store_id, since_version, limit)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?
+        } else {
+            Vec::new()
+        };
+        let product_ids: Vec<String> = products.iter().map(|p| p.id.clone()).collect();
+        let mut attributes = self.state.db
+            .get_product_attributes(&product_ids)
             .await
             .map_err(|e| Status::internal(e.to_string()))?;
 
+        // Transfers follow their own cursor ("transfers" stream) since their
+        // positions come from the cloud's routing sequence.
+        let transfers = if wants("STORE_TRANSFER") {
+            let transfer_cursor = self.state.db
+                .get_sync_cursor(&auth.store_id, TRANSFER_STREAM)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?
+                .unwrap_or(0);
+            self.state.db
+                .get_pending_store_transfers(&auth.store_id, transfer_cursor, limit)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?
+        } else {
+            Vec::new()
+        };
+
+        // Store credit is shared by the whole tenant and follows its own
+        // cursor ("store_credits" stream) for the same reason.
+        let credits = if wants("STORE_CREDIT") {
+            let credit_cursor = self.state.db
+                .get_sync_cursor(&auth.store_id, STORE_CREDIT_STREAM)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?
+                .unwrap_or(0);
+            self.state.db
+                .get_pending_store_credits(&auth.tenant_id, credit_cursor, limit)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?
+        } else {
+            Vec::new()
+        };
+
+        // Customer erasures go to every store of the tenant, on their own
+        // cursor ("erasures" stream). The store that made one gets it back
+        // and applies it again, which changes nothing.
+        let erasures = if wants("CUSTOMER_ERASURE") {
+            let erasure_cursor = self.state.db
+                .get_sync_cursor(&auth.store_id, ERASURE_STREAM)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?
+                .unwrap_or(0);
+            self.state.db
+                .get_pending_erasures(&auth.tenant_id, erasure_cursor, limit)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?
+        } else {
+            Vec::new()
+        };
+
+        // Coupons go to every store of the tenant on their own cursor
+        // ("coupons" stream), moving up whenever the back office changes one
+        // or a store uploads a redemption.
+        let coupons = if wants("COUPON") {
+            let coupon_cursor = self.state.db
+                .get_sync_cursor(&auth.store_id, COUPON_STREAM)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?
+                .unwrap_or(0);
+            self.state.db
+                .get_pending_coupons(&auth.tenant_id, coupon_cursor, limit)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?
+        } else {
+            Vec::new()
+        };
+
+        // Loyalty accounts likewise ("loyalty" stream), moving up with every
+        // point earned or reward spent in any store.
+        let loyalty = if wants("LOYALTY") {
+            let loyalty_cursor = self.state.db
+                .get_sync_cursor(&auth.store_id, LOYALTY_STREAM)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?
+                .unwrap_or(0);
+            self.state.db
+                .get_pending_loyalty(&auth.tenant_id, loyalty_cursor, limit)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?
+        } else {
+            Vec::new()
+        };
+
         let (tx, rx) = mpsc::channel(32);
 
         tokio::spawn(async move {
+            for (transfer, items) in transfers {
+                if tx.send(Ok(transfer_update(transfer, items))).await.is_err() {
+                    return;
+                }
+            }
+
+            for (account, entries) in credits {
+                if tx.send(Ok(store_credit_update(account, entries))).await.is_err() {
+                    return;
+                }
+            }
+
+            for erasure in erasures {
+                if tx.send(Ok(erasure_update(erasure))).await.is_err() {
+                    return;
+                }
+            }
+
+            for (coupon, redemptions) in coupons {
+                if tx.send(Ok(coupon_update(coupon, redemptions))).await.is_err() {
+                    return;
+                }
+            }
+
+            for (account, entries) in loyalty {
+                if tx.send(Ok(loyalty_update(account, entries))).await.is_err() {
+                    return;
+                }
+            }
+
             for product in products {
+                let product_attributes = attributes.remove(&product.id).unwrap_or_default();
                 let update = EntityUpdate {
                     update_id: format!("product-{}-{}", product.id, product.version),
                     entity_type: "PRODUCT".to_string(),
@@ -411,6 +486,10 @@ impl SyncService for SyncServiceImpl {
                                 cents: c,
                                 currency: "USD".to_string(),
                             }),
+                            base_price: product.base_price_cents.map(|c| crate::proto::Money {
+                                cents: c,
+                                currency: "USD".to_string(),
+                            }),
                             tax_rate_id: product.tax_rate_id.unwrap_or_default(),
                             tax_rate_bps: product.tax_rate_bps,
                             track_inventory: product.track_inventory,
@@ -426,6 +505,7 @@ impl SyncService for SyncServiceImpl {
                                 value: product.updated_at.to_rfc3339(),
                             }),
                             version: product.version,
+                            attributes: product_attributes,
                         },
                     )),
                     version: product.version,
@@ -537,34 +617,471 @@ impl SyncService for SyncServiceImpl {
             server_position,
         }))
     }
+
+    /// Outcome of a queued batch.
+    async fn get_batch_status(
+        &self,
+        request: Request<GetBatchStatusRequest>,
+    ) -> Result<Response<GetBatchStatusResponse>, Status> {
+        let auth = self.authenticate(&request)?;
+        let req = request.into_inner();
+
+        let batch = self.state.db
+            .get_upload_batch(&req.batch_status_id, &auth.store_id)
+            .await?
+            .ok_or_else(|| Status::not_found("Unknown batch"))?;
+
+        let result = batch
+            .result
+            .as_deref()
+            .map(UploadBatchResponse::decode)
+            .transpose()
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(GetBatchStatusResponse {
+            batch_status_id: batch.id,
+            status: batch.status,
+            result,
+            error_message: batch.error_message.unwrap_or_default(),
+            entity_count: batch.entity_count,
+            accepted_at: Some(ProtoTimestamp {
+                value: batch.created_at.to_rfc3339(),
+            }),
+            completed_at: batch.completed_at.map(|at| ProtoTimestamp {
+                value: at.to_rfc3339(),
+            }),
+        }))
+    }
+
+    /// A sale a terminal no longer keeps, by receipt number.
+    async fn get_sale(
+        &self,
+        request: Request<GetSaleRequest>,
+    ) -> Result<Response<GetSaleResponse>, Status> {
+        let auth = self.authenticate(&request)?;
+        let req = request.into_inner();
+
+        let (sale, items, payments) = self.state.db
+            .get_sale_by_receipt(&auth.store_id, &req.receipt_number)
+            .await?
+            .ok_or_else(|| Status::not_found("Unknown receipt number"))?;
+
+        info!(
+            store_id = %auth.store_id,
+            receipt_number = %req.receipt_number,
+            "Sale looked up"
+        );
+        Ok(Response::new(sale_response(sale, items, payments)))
+    }
+
+    /// Spend loyalty rewards against the tenant's ledger.
+    async fn redeem_loyalty_reward(
+        &self,
+        request: Request<RedeemLoyaltyRewardRequest>,
+    ) -> Result<Response<RedeemLoyaltyRewardResponse>, Status> {
+        let auth = self.authenticate(&request)?;
+        let req = request.into_inner();
+
+        let account = req
+            .account
+            .ok_or_else(|| Status::invalid_argument("Missing loyalty account"))?;
+        let redemption = req
+            .redemption
+            .ok_or_else(|| Status::invalid_argument("Missing redemption"))?;
+        let invalid = |e: SyncError| Status::invalid_argument(e.error_message);
+        let (record, entries) = loyalty_records(&account, &auth.tenant_id).map_err(invalid)?;
+        let redemption = loyalty_entry_record(&redemption, &account.id).map_err(invalid)?;
+        if redemption.kind != "REDEEM" || redemption.reward_cents >= 0 {
+            return Err(Status::invalid_argument(
+                "Redemption must be a REDEEM entry with a negative reward",
+            ));
+        }
+
+        let (approved, balance_cents) = self.state.db
+            .redeem_loyalty_reward(&record, &entries, &redemption)
+            .await?;
+
+        info!(
+            store_id = %auth.store_id,
+            member_number = %account.member_number,
+            amount_cents = -redemption.reward_cents,
+            approved,
+            balance_cents,
+            "Loyalty redemption checked"
+        );
+        let mut response = Response::new(RedeemLoyaltyRewardResponse {
+            approved,
+            balance: Some(crate::proto::Money {
+                cents: balance_cents,
+                currency: "USD".to_string(),
+            }),
+            reason: if approved {
+                String::new()
+            } else {
+                format!("Only {} cents of rewards left", balance_cents)
+            },
+        });
+        AuditContext::entities([format!("loyalty:{}", account.id)]).attach(&mut response);
+
+        Ok(response)
+    }
 }
 
 // =============================================================================
 // Helper Types
 // =============================================================================
 
-/// Authentication context extracted from JWT.
-struct AuthContext {
-    store_id: String,
-    tenant_id: String,
-    device_id: String,
+/// Build the answer to a sale lookup.
+fn sale_response(
+    sale: SaleRecord,
+    items: Vec<SaleItemRecord>,
+    payments: Vec<PaymentRecord>,
+) -> GetSaleResponse {
+    let ts = |dt: DateTime<Utc>| ProtoTimestamp {
+        value: dt.to_rfc3339(),
+    };
+    let money = |cents: i64| crate::proto::Money {
+        cents,
+        currency: "USD".to_string(),
+    };
+
+    GetSaleResponse {
+        sale: Some(crate::proto::Sale {
+            id: sale.id,
+            store_id: sale.store_id,
+            device_id: sale.device_id,
+            receipt_number: sale.receipt_number,
+            station_number: sale.station_number.unwrap_or(0),
+            subtotal: Some(money(sale.subtotal_cents)),
+            tax_amount: Some(money(sale.tax_amount_cents)),
+            discount_amount: Some(money(sale.discount_amount_cents)),
+            total: Some(money(sale.total_cents)),
+            status: sale.status,
+            created_at: Some(ts(sale.created_at)),
+            completed_at: sale.completed_at.map(ts),
+            items: items
+                .into_iter()
+                .map(|item| crate::proto::SaleItem {
+                    id: item.id,
+                    sale_id: item.sale_id,
+                    product_id: item.product_id,
+                    sku: item.sku,
+                    name: item.name,
+                    quantity: item.quantity,
+                    unit_price: Some(money(item.unit_price_cents)),
+                    line_total: Some(money(item.line_total_cents)),
+                    tax_amount: Some(money(item.tax_amount_cents)),
+                    tax_rate_bps: item.tax_rate_bps,
+                    tracking_kind: item.tracking_kind.unwrap_or_default(),
+                    tracking_codes: item.tracking_codes,
+                    base_price: item.base_price_cents.map(money),
+                })
+                .collect(),
+        }),
+        payments: payments
+            .into_iter()
+            .map(|payment| crate::proto::Payment {
+                id: payment.id,
+                sale_id: payment.sale_id,
+                store_id: payment.store_id,
+                method: payment.method,
+                amount: Some(money(payment.amount_cents)),
+                change_given: Some(money(payment.change_given_cents)),
+                reference: payment.reference.unwrap_or_default(),
+                authorization_code: payment.authorization_code.unwrap_or_default(),
+                created_at: Some(ts(payment.created_at)),
+            })
+            .collect(),
+    }
 }
 
-/// Parse a proto timestamp to DateTime<Utc>.
-fn parse_timestamp(ts: &Option<ProtoTimestamp>) -> Result<DateTime<Utc>, SyncError> {
-    let ts = ts.as_ref().ok_or_else(|| SyncError {
-        entity_id: String::new(),
-        error_code: "INVALID_TIMESTAMP".to_string(),
-        error_message: "Missing timestamp".to_string(),
-        retryable: false,
-    })?;
-
-    DateTime::parse_from_rfc3339(&ts.value)
-        .map(|dt| dt.with_timezone(&Utc))
-        .map_err(|e| SyncError {
-            entity_id: String::new(),
-            error_code: "INVALID_TIMESTAMP".to_string(),
-            error_message: format!("Invalid timestamp format: {}", e),
-            retryable: false,
-        })
+/// Cursor stream for store transfer downloads.
+const TRANSFER_STREAM: &str = "transfers";
+
+/// Build the download update for a store transfer.
+///
+/// `EntityUpdate.version` carries the routing sequence so the store can
+/// acknowledge it on the `transfers` stream; the transfer's own version is
+/// inside the payload.
+fn transfer_update(
+    transfer: StoreTransferRecord,
+    items: Vec<StoreTransferItemRecord>,
+) -> EntityUpdate {
+    let ts = |dt: DateTime<Utc>| ProtoTimestamp {
+        value: dt.to_rfc3339(),
+    };
+
+    EntityUpdate {
+        update_id: format!("transfer-{}-{}", transfer.id, transfer.route_seq),
+        entity_type: "STORE_TRANSFER".to_string(),
+        operation: "UPDATE".to_string(),
+        data: Some(crate::proto::entity_update::Data::StoreTransfer(
+            crate::proto::StoreTransfer {
+                id: transfer.id,
+                transfer_number: transfer.transfer_number,
+                from_store_id: transfer.from_store_id,
+                to_store_id: transfer.to_store_id,
+                status: transfer.status,
+                notes: transfer.notes.unwrap_or_default(),
+                created_by: transfer.created_by,
+                received_by: transfer.received_by.unwrap_or_default(),
+                items: items
+                    .into_iter()
+                    .map(|item| crate::proto::StoreTransferItem {
+                        id: item.id,
+                        product_id: item.product_id,
+                        sku: item.sku,
+                        name: item.name,
+                        quantity_sent: item.quantity_sent,
+                        quantity_received: item.quantity_received,
+                    })
+                    .collect(),
+                created_at: Some(ts(transfer.created_at)),
+                updated_at: Some(ts(transfer.updated_at)),
+                received_at: transfer.received_at.map(ts),
+                cancelled_at: transfer.cancelled_at.map(ts),
+                version: transfer.version,
+            },
+        )),
+        version: transfer.route_seq,
+        updated_at: Some(ts(transfer.updated_at)),
+    }
+}
+
+/// Cursor stream for store credit downloads.
+const STORE_CREDIT_STREAM: &str = "store_credits";
+
+/// Build the download update for a store credit account.
+///
+/// As with transfers, `EntityUpdate.version` carries the routing sequence
+/// for the `store_credits` stream.
+fn store_credit_update(
+    account: StoreCreditRecord,
+    entries: Vec<StoreCreditEntryRecord>,
+) -> EntityUpdate {
+    let ts = |dt: DateTime<Utc>| ProtoTimestamp {
+        value: dt.to_rfc3339(),
+    };
+
+    EntityUpdate {
+        update_id: format!("store-credit-{}-{}", account.id, account.route_seq),
+        entity_type: "STORE_CREDIT".to_string(),
+        operation: "UPDATE".to_string(),
+        data: Some(crate::proto::entity_update::Data::StoreCredit(
+            crate::proto::StoreCredit {
+                id: account.id,
+                credit_number: account.credit_number,
+                customer_name: account.customer_name,
+                customer_phone: account.customer_phone.unwrap_or_default(),
+                entries: entries
+                    .into_iter()
+                    .map(|entry| crate::proto::StoreCreditEntry {
+                        id: entry.id,
+                        kind: entry.kind,
+                        amount: Some(crate::proto::Money {
+                            cents: entry.amount_cents,
+                            currency: "USD".to_string(),
+                        }),
+                        sale_id: entry.sale_id.unwrap_or_default(),
+                        device_id: entry.device_id,
+                        created_at: Some(ts(entry.created_at)),
+                    })
+                    .collect(),
+                created_at: Some(ts(account.created_at)),
+                updated_at: Some(ts(account.updated_at)),
+                version: account.version,
+            },
+        )),
+        version: account.route_seq,
+        updated_at: Some(ts(account.updated_at)),
+    }
+}
+
+/// Cursor stream for coupon downloads.
+const COUPON_STREAM: &str = "coupons";
+
+/// Build the download update for a coupon.
+///
+/// As with store credit, `EntityUpdate.version` carries the routing
+/// sequence for the `coupons` stream.
+fn coupon_update(coupon: CouponRecord, redemptions: Vec<CouponRedemptionRecord>) -> EntityUpdate {
+    let route_seq = coupon.route_seq;
+    let updated_at = ProtoTimestamp {
+        value: coupon.updated_at.to_rfc3339(),
+    };
+
+    EntityUpdate {
+        update_id: format!("coupon-{}-{}", coupon.id, route_seq),
+        entity_type: "COUPON".to_string(),
+        operation: "UPDATE".to_string(),
+        data: Some(crate::proto::entity_update::Data::Coupon(coupon_proto(
+            coupon,
+            redemptions,
+        ))),
+        version: route_seq,
+        updated_at: Some(updated_at),
+    }
+}
+
+/// Convert a coupon record and its redemptions to the proto message.
+pub(crate) fn coupon_proto(
+    coupon: CouponRecord,
+    redemptions: Vec<CouponRedemptionRecord>,
+) -> crate::proto::Coupon {
+    let ts = |dt: DateTime<Utc>| ProtoTimestamp {
+        value: dt.to_rfc3339(),
+    };
+
+    crate::proto::Coupon {
+        id: coupon.id,
+        code: coupon.code,
+        description: coupon.description.unwrap_or_default(),
+        kind: coupon.kind,
+        value: coupon.value,
+        min_subtotal_cents: coupon.min_subtotal_cents,
+        product_ids: coupon.product_ids,
+        starts_at: coupon.starts_at.map(ts),
+        expires_at: coupon.expires_at.map(ts),
+        max_uses: coupon.max_uses,
+        active: coupon.active,
+        redemptions: redemptions
+            .into_iter()
+            .map(|redemption| crate::proto::CouponRedemption {
+                id: redemption.id,
+                sale_id: redemption.sale_id,
+                store_id: redemption.store_id,
+                device_id: redemption.device_id,
+                discount: Some(crate::proto::Money {
+                    cents: redemption.discount_cents,
+                    currency: "USD".to_string(),
+                }),
+                redeemed_at: Some(ts(redemption.redeemed_at)),
+            })
+            .collect(),
+        created_at: Some(ts(coupon.created_at)),
+        updated_at: Some(ts(coupon.updated_at)),
+        version: coupon.version,
+    }
+}
+
+/// Cursor stream for loyalty downloads.
+const LOYALTY_STREAM: &str = "loyalty";
+
+/// Build the download update for a loyalty account.
+///
+/// As with store credit, `EntityUpdate.version` carries the routing
+/// sequence for the `loyalty` stream.
+fn loyalty_update(account: LoyaltyRecord, entries: Vec<LoyaltyEntryRecord>) -> EntityUpdate {
+    let ts = |dt: DateTime<Utc>| ProtoTimestamp {
+        value: dt.to_rfc3339(),
+    };
+
+    EntityUpdate {
+        update_id: format!("loyalty-{}-{}", account.id, account.route_seq),
+        entity_type: "LOYALTY".to_string(),
+        operation: "UPDATE".to_string(),
+        data: Some(crate::proto::entity_update::Data::Loyalty(
+            crate::proto::LoyaltyAccount {
+                id: account.id,
+                member_number: account.member_number,
+                entries: entries
+                    .into_iter()
+                    .map(|entry| crate::proto::LoyaltyEntry {
+                        id: entry.id,
+                        kind: entry.kind,
+                        points: entry.points,
+                        reward: Some(crate::proto::Money {
+                            cents: entry.reward_cents,
+                            currency: "USD".to_string(),
+                        }),
+                        sale_id: entry.sale_id.unwrap_or_default(),
+                        device_id: entry.device_id,
+                        created_at: Some(ts(entry.created_at)),
+                    })
+                    .collect(),
+                created_at: Some(ts(account.created_at)),
+                updated_at: Some(ts(account.updated_at)),
+                version: account.version,
+            },
+        )),
+        version: account.route_seq,
+        updated_at: Some(ts(account.updated_at)),
+    }
+}
+
+/// Cursor stream for customer erasure downloads.
+const ERASURE_STREAM: &str = "erasures";
+
+/// Build the download update for a customer erasure.
+///
+/// `EntityUpdate.version` carries the routing sequence for the `erasures`
+/// stream.
+fn erasure_update(erasure: CustomerErasureRecord) -> EntityUpdate {
+    let route_seq = erasure.route_seq;
+    let updated_at = ProtoTimestamp {
+        value: erasure.erased_at.to_rfc3339(),
+    };
+
+    EntityUpdate {
+        update_id: format!("erasure-{}", erasure.request_id),
+        entity_type: "CUSTOMER_ERASURE".to_string(),
+        operation: "UPDATE".to_string(),
+        data: Some(crate::proto::entity_update::Data::CustomerErasure(
+            erasure_proto(erasure),
+        )),
+        version: route_seq,
+        updated_at: Some(updated_at),
+    }
+}
+
+/// Convert an erasure record to its proto message.
+pub(crate) fn erasure_proto(erasure: CustomerErasureRecord) -> crate::proto::CustomerErasure {
+    crate::proto::CustomerErasure {
+        id: erasure.request_id,
+        business_customer_id: erasure.business_customer_id.unwrap_or_default(),
+        phone: erasure.phone.unwrap_or_default(),
+        email: erasure.email.unwrap_or_default(),
+        reason: erasure.reason.unwrap_or_default(),
+        requested_by: erasure.requested_by,
+        requested_at: Some(ProtoTimestamp {
+            value: erasure.requested_at.to_rfc3339(),
+        }),
+    }
+}
+
+/// Build an erasure record from a request's identifiers.
+///
+/// Identifiers are trimmed and the email lowercased, as at the terminals.
+/// Returns `None` if no identifier is left.
+pub(crate) fn erasure_record(
+    erasure: &crate::proto::CustomerErasure,
+    tenant_id: &str,
+    requested_at: DateTime<Utc>,
+    origin_store_id: Option<String>,
+) -> Option<CustomerErasureRecord> {
+    let clean = |s: &str| {
+        let s = s.trim();
+        (!s.is_empty()).then(|| s.to_string())
+    };
+    let business_customer_id = clean(&erasure.business_customer_id);
+    let phone = clean(&erasure.phone);
+    let email = clean(&erasure.email).map(|e| e.to_lowercase());
+    if business_customer_id.is_none() && phone.is_none() && email.is_none() {
+        return None;
+    }
+
+    Some(CustomerErasureRecord {
+        request_id: erasure.id.clone(),
+        tenant_id: tenant_id.to_string(),
+        business_customer_id,
+        phone,
+        email,
+        reason: clean(&erasure.reason),
+        requested_by: erasure.requested_by.clone(),
+        requested_at,
+        origin_store_id,
+        store_credit_accounts: 0,
+        erased_at: requested_at,
+        route_seq: 0,
+    })
 }
@@ -0,0 +1,275 @@
+//! User gRPC service implementation.
+//!
+//! Tenant back-office accounts: invitations, invite acceptance, disabling
+//! and listing. See `crate::rbac` for what each role may do.
+
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use sha2::{Digest, Sha256};
+use tonic::{Request, Response, Status};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::audit::AuditContext;
+use crate::auth::JwtManager;
+use crate::db::{hash_password, Database, TenantUserRecord};
+use crate::proto::{
+    tenant_user::Role as ProtoRole, user_service_server::UserService, AcceptInviteRequest,
+    DisableUserRequest, DisableUserResponse, InviteUserRequest, InviteUserResponse,
+    ListUsersRequest, ListUsersResponse, LoginResponse, TenantUser, Timestamp,
+};
+use crate::rbac::{Permission, Principal, Role};
+use crate::services::auth_service::new_session_id;
+use crate::AppState;
+
+/// How long an invite token can be redeemed.
+const INVITE_LIFETIME_DAYS: i64 = 7;
+
+/// Shortest password accepted when redeeming an invite.
+const MIN_PASSWORD_LEN: usize = 10;
+
+/// User service implementation.
+pub struct UserServiceImpl {
+    state: Arc<AppState>,
+    jwt_manager: JwtManager,
+}
+
+impl UserServiceImpl {
+    /// Create a new user service.
+    pub fn new(state: Arc<AppState>) -> Self {
+        let jwt_manager = JwtManager::new(
+            state.config.jwt_secret.clone(),
+            state.config.jwt_access_lifetime_secs,
+            state.config.jwt_refresh_lifetime_secs,
+        );
+
+        UserServiceImpl { state, jwt_manager }
+    }
+
+    /// Authenticate a request and require user management rights.
+    async fn authorize<T>(&self, request: &Request<T>) -> Result<(String, String), Status> {
+        let auth_header = request
+            .metadata()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok());
+        let principal =
+            Principal::authenticate(&self.state.db, &self.jwt_manager, auth_header).await?;
+        principal.require(Permission::ManageUsers)?;
+
+        match principal {
+            Principal::User {
+                user_id, tenant_id, ..
+            } => Ok((user_id, tenant_id)),
+            Principal::Store { .. } => Err(Status::permission_denied("User token required")),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl UserService for UserServiceImpl {
+    /// Create a user and return a one-time invite token.
+    async fn invite_user(
+        &self,
+        request: Request<InviteUserRequest>,
+    ) -> Result<Response<InviteUserResponse>, Status> {
+        let (owner_id, tenant_id) = self.authorize(&request).await?;
+        let req = request.into_inner();
+
+        let email = req.email.trim().to_lowercase();
+        if !email.contains('@') {
+            return Err(Status::invalid_argument("Invalid email address"));
+        }
+        let display_name = req.display_name.trim();
+        if display_name.is_empty() {
+            return Err(Status::invalid_argument("display_name is required"));
+        }
+        let role = match ProtoRole::try_from(req.role) {
+            Ok(ProtoRole::Owner) => Role::Owner,
+            Ok(ProtoRole::Manager) => Role::Manager,
+            Ok(ProtoRole::Analyst) => Role::Analyst,
+            _ => return Err(Status::invalid_argument("Unknown role")),
+        };
+
+        let invite_token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        let invite_expires_at = Utc::now() + Duration::days(INVITE_LIFETIME_DAYS);
+
+        let user = self
+            .state
+            .db
+            .invite_tenant_user(
+                &Uuid::new_v4().to_string(),
+                &tenant_id,
+                &email,
+                display_name,
+                role.as_str(),
+                &hash_invite_token(&invite_token),
+                invite_expires_at,
+                &owner_id,
+            )
+            .await?;
+
+        info!(
+            tenant_id = %tenant_id,
+            invited_by = %owner_id,
+            user_id = %user.id,
+            role = %role,
+            "Tenant user invited"
+        );
+
+        let entity_id = format!("user:{}", user.id);
+        let mut response = Response::new(InviteUserResponse {
+            user: Some(user_to_proto(user)),
+            invite_token,
+            invite_expires_at: Some(Timestamp {
+                value: invite_expires_at.to_rfc3339(),
+            }),
+        });
+        AuditContext::entities([entity_id]).attach(&mut response);
+
+        Ok(response)
+    }
+
+    /// Set a password with an invite token.
+    async fn accept_invite(
+        &self,
+        request: Request<AcceptInviteRequest>,
+    ) -> Result<Response<LoginResponse>, Status> {
+        let req = request.into_inner();
+
+        if req.password.chars().count() < MIN_PASSWORD_LEN {
+            return Err(Status::invalid_argument(format!(
+                "Password must be at least {} characters",
+                MIN_PASSWORD_LEN
+            )));
+        }
+
+        let password_hash = hash_password(&req.password)?;
+        let user = self
+            .state
+            .db
+            .accept_tenant_invite(&hash_invite_token(&req.invite_token), &password_hash)
+            .await?
+            .ok_or_else(|| Status::unauthenticated("Invalid or expired invite"))?;
+
+        info!(tenant_id = %user.tenant_id, user_id = %user.id, "Tenant user invite accepted");
+
+        // The caller has no token yet, so name the tenant for the audit log
+        let audit = AuditContext {
+            tenant_id: Some(user.tenant_id.clone()),
+            entity_ids: vec![format!("user:{}", user.id)],
+            ..Default::default()
+        };
+        let mut response = Response::new(login_response(
+            &self.state.db,
+            &self.jwt_manager,
+            self.state.config.jwt_access_lifetime_secs,
+            user,
+        )
+        .await?);
+        audit.attach(&mut response);
+
+        Ok(response)
+    }
+
+    /// Disable a user.
+    async fn disable_user(
+        &self,
+        request: Request<DisableUserRequest>,
+    ) -> Result<Response<DisableUserResponse>, Status> {
+        let (owner_id, tenant_id) = self.authorize(&request).await?;
+        let req = request.into_inner();
+
+        if req.user_id == owner_id {
+            return Err(Status::failed_precondition("Cannot disable yourself"));
+        }
+
+        let disabled = self
+            .state
+            .db
+            .disable_tenant_user(&req.user_id, &tenant_id)
+            .await?;
+        if !disabled {
+            return Err(Status::not_found(format!(
+                "User not found: {}",
+                req.user_id
+            )));
+        }
+
+        info!(tenant_id = %tenant_id, disabled_by = %owner_id, user_id = %req.user_id, "Tenant user disabled");
+
+        let mut response = Response::new(DisableUserResponse { success: true });
+        AuditContext::entities([format!("user:{}", req.user_id)]).attach(&mut response);
+
+        Ok(response)
+    }
+
+    /// List the tenant's users.
+    async fn list_users(
+        &self,
+        request: Request<ListUsersRequest>,
+    ) -> Result<Response<ListUsersResponse>, Status> {
+        let (_owner_id, tenant_id) = self.authorize(&request).await?;
+
+        let users = self.state.db.list_tenant_users(&tenant_id).await?;
+
+        Ok(Response::new(ListUsersResponse {
+            users: users.into_iter().map(user_to_proto).collect(),
+        }))
+    }
+}
+
+/// Issue user tokens for a signed-in user.
+#[allow(clippy::result_large_err)] // tonic::Status is the service error type
+pub(crate) async fn login_response(
+    db: &Database,
+    jwt_manager: &JwtManager,
+    expires_in: i64,
+    user: TenantUserRecord,
+) -> Result<LoginResponse, Status> {
+    let role = Role::parse(&user.role)
+        .ok_or_else(|| Status::internal(format!("Unknown role: {}", user.role)))?;
+
+    let session_id = new_session_id();
+    let (refresh_token, refresh_claims) =
+        jwt_manager.generate_user_refresh_token(&user.id, &user.tenant_id, role, &session_id)?;
+    db.start_session(&refresh_claims).await?;
+
+    Ok(LoginResponse {
+        access_token: jwt_manager
+            .generate_user_access_token(&user.id, &user.tenant_id, role, &session_id)?,
+        refresh_token,
+        expires_in,
+        user: Some(user_to_proto(user)),
+    })
+}
+
+pub(crate) fn user_to_proto(user: TenantUserRecord) -> TenantUser {
+    let role = match Role::parse(&user.role) {
+        Some(Role::Owner) => ProtoRole::Owner,
+        Some(Role::Manager) => ProtoRole::Manager,
+        Some(Role::Analyst) => ProtoRole::Analyst,
+        None => ProtoRole::Unknown,
+    };
+
+    TenantUser {
+        user_id: user.id,
+        email: user.email,
+        display_name: user.display_name,
+        role: role as i32,
+        is_active: user.is_active,
+        invite_pending: user.invite_pending,
+        created_at: Some(Timestamp {
+            value: user.created_at.to_rfc3339(),
+        }),
+        last_login_at: user.last_login_at.map(|t| Timestamp {
+            value: t.to_rfc3339(),
+        }),
+    }
+}
+
+/// Invite tokens are stored as their SHA-256 so a database leak does not
+/// expose redeemable invites.
+fn hash_invite_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
@@ -0,0 +1,154 @@
+//! # Sales Tax Report
+//!
+//! Taxable and non-taxable sales and the tax collected per tax rate,
+//! across a tenant's stores, served by `ReportService.GetTaxReport`.
+//! Terminals build the same report from their own sales (see
+//! `titan_core::tax_report`); the CSV layout matches.
+//!
+//! ## Report Window
+//! ```text
+//! from = 2026-01-01, to = 2026-03-31 (inclusive, UTC)
+//!   ──► sales with status COMPLETED and
+//!       completed_at in [2026-01-01 00:00, 2026-04-01 00:00)
+//! ```
+//!
+//! The jurisdiction of a rate is the name of the tenant's active tax
+//! rate(s) with that rate; sale items only carry the rate itself.
+
+use chrono::{DateTime, Days, NaiveDate, NaiveTime, Utc};
+
+use crate::db::TaxRateRecord;
+use crate::error::CloudError;
+
+/// Longest range one report may cover (a little over a year).
+pub const MAX_REPORT_DAYS: i64 = 366;
+
+/// CSV header, the same as the terminals'.
+pub const CSV_HEADER: &str = "rate_percent,jurisdiction,taxable,non_taxable,tax,lines";
+
+/// The completion-time window `[start, end)` for the days `from` to `to`
+/// (`YYYY-MM-DD`, inclusive, UTC).
+///
+/// ## Errors
+/// `CloudError::InvalidRequest` for a malformed date, `to` before `from`,
+/// or more than [`MAX_REPORT_DAYS`] days.
+pub fn report_window(from: &str, to: &str) -> Result<(DateTime<Utc>, DateTime<Utc>), CloudError> {
+    let parse = |value: &str, field: &str| {
+        NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+            .map_err(|_| CloudError::InvalidRequest(format!("{} must be YYYY-MM-DD", field)))
+    };
+    let from = parse(from, "from")?;
+    let to = parse(to, "to")?;
+
+    if to < from {
+        return Err(CloudError::InvalidRequest("to must not be before from".to_string()));
+    }
+    if (to - from).num_days() + 1 > MAX_REPORT_DAYS {
+        return Err(CloudError::InvalidRequest(format!(
+            "A report covers at most {} days",
+            MAX_REPORT_DAYS
+        )));
+    }
+    let end = to
+        .checked_add_days(Days::new(1))
+        .ok_or_else(|| CloudError::InvalidRequest("to is out of range".to_string()))?;
+
+    Ok((
+        from.and_time(NaiveTime::MIN).and_utc(),
+        end.and_time(NaiveTime::MIN).and_utc(),
+    ))
+}
+
+/// The report as CSV: [`CSV_HEADER`], one line per rate, then a `TOTAL`
+/// line. Amounts are decimal with two places.
+pub fn to_csv(rates: &[TaxRateRecord]) -> Result<String, CloudError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let csv_error = |e: csv::Error| CloudError::Internal(format!("Failed to write CSV: {}", e));
+
+    writer
+        .write_record(CSV_HEADER.split(','))
+        .map_err(csv_error)?;
+    for rate in rates {
+        writer
+            .write_record([
+                format_amount(i64::from(rate.tax_rate_bps)),
+                rate.jurisdiction.clone().unwrap_or_default(),
+                format_amount(rate.taxable_cents),
+                format_amount(rate.non_taxable_cents),
+                format_amount(rate.tax_cents),
+                rate.line_count.to_string(),
+            ])
+            .map_err(csv_error)?;
+    }
+
+    let total = |f: fn(&TaxRateRecord) -> i64| rates.iter().map(f).sum::<i64>();
+    writer
+        .write_record([
+            "TOTAL".to_string(),
+            String::new(),
+            format_amount(total(|r| r.taxable_cents)),
+            format_amount(total(|r| r.non_taxable_cents)),
+            format_amount(total(|r| r.tax_cents)),
+            total(|r| r.line_count).to_string(),
+        ])
+        .map_err(csv_error)?;
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| CloudError::Internal(format!("Failed to write CSV: {}", e)))?;
+    String::from_utf8(bytes).map_err(|e| CloudError::Internal(e.to_string()))
+}
+
+/// Hundredths as a decimal string, e.g. `825` → `"8.25"`.
+fn format_amount(hundredths: i64) -> String {
+    let sign = if hundredths < 0 { "-" } else { "" };
+    let abs = hundredths.abs();
+    format!("{}{}.{:02}", sign, abs / 100, abs % 100)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate(bps: i32, jurisdiction: Option<&str>, taxable: i64, non_taxable: i64, tax: i64) -> TaxRateRecord {
+        TaxRateRecord {
+            tax_rate_bps: bps,
+            jurisdiction: jurisdiction.map(str::to_string),
+            taxable_cents: taxable,
+            non_taxable_cents: non_taxable,
+            tax_cents: tax,
+            line_count: 2,
+        }
+    }
+
+    #[test]
+    fn test_report_window() {
+        let (start, end) = report_window("2026-01-01", " 2026-03-31 ").unwrap();
+        assert_eq!(start.to_rfc3339(), "2026-01-01T00:00:00+00:00");
+        assert_eq!(end.to_rfc3339(), "2026-04-01T00:00:00+00:00");
+
+        assert!(matches!(
+            report_window("2026-02-01", "2026-01-31"),
+            Err(CloudError::InvalidRequest(_))
+        ));
+        assert!(report_window("01/02/2026", "2026-03-01").is_err());
+        assert!(report_window("2025-01-01", "2026-06-30").is_err());
+    }
+
+    #[test]
+    fn test_csv_matches_terminal_layout() {
+        let csv = to_csv(&[
+            rate(0, Some("NY Food Tax"), 0, 1250, 0),
+            rate(800, Some("NY Sales Tax, City"), 10_000, 0, 800),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            csv,
+            "rate_percent,jurisdiction,taxable,non_taxable,tax,lines\n\
+             0.00,NY Food Tax,0.00,12.50,0.00,2\n\
+             8.00,\"NY Sales Tax, City\",100.00,0.00,8.00,2\n\
+             TOTAL,,100.00,12.50,8.00,4\n"
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! # Distributed Tracing
+//!
+//! OTLP span export for the cloud API, and the trace context hubs and
+//! terminals send with their uploads (see titan-sync `telemetry`):
+//!
+//! ```text
+//! "traceparent" gRPC header  ──► span "upload_batch"   (child)
+//! SyncEntity.trace_context   ──► span "process_entity" (child)
+//! ```
+//!
+//! so the cloud's part of a sale's journey lands in the same trace as the
+//! terminal's and the hub's. Export is off unless
+//! `OTEL_EXPORTER_OTLP_ENDPOINT` names an OTLP/gRPC collector.
+
+use std::collections::HashMap;
+
+use opentelemetry::propagation::TextMapPropagator;
+use opentelemetry::trace::{TraceContextExt, TraceError, TracerProvider as _};
+use opentelemetry::{Context, KeyValue};
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{Tracer, TracerProvider};
+use opentelemetry_sdk::{runtime, Resource};
+use tonic::metadata::MetadataMap;
+use tracing::{warn, Span, Subscriber};
+use tracing_opentelemetry::{OpenTelemetryLayer, OpenTelemetrySpanExt};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Environment variable naming the OTLP/gRPC collector.
+pub const OTLP_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+/// gRPC metadata key carrying the caller's trace context.
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Service name spans are exported under.
+const SERVICE_NAME: &str = "titan-cloud-api";
+
+/// Layer exporting a subscriber's spans over OTLP.
+pub type OtlpLayer<S> = OpenTelemetryLayer<S, Tracer>;
+
+/// Keeps span export running. Dropping it flushes the spans still
+/// buffered, so hold it until the server exits.
+pub struct Telemetry {
+    provider: TracerProvider,
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            warn!(error = %e, "Could not flush spans on shutdown");
+        }
+    }
+}
+
+/// The layer exporting spans to the OTLP collector, or `None` when
+/// `OTEL_EXPORTER_OTLP_ENDPOINT` is not set. Call it inside the runtime.
+pub fn otlp_layer<S>() -> Result<Option<(OtlpLayer<S>, Telemetry)>, TraceError>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let endpoint = match std::env::var(OTLP_ENDPOINT_ENV) {
+        Ok(endpoint) if !endpoint.is_empty() => endpoint,
+        _ => return Ok(None),
+    };
+
+    let exporter = SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint)
+        .build()?;
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_resource(Resource::new([KeyValue::new("service.name", SERVICE_NAME)]))
+        .build();
+    let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME));
+
+    Ok(Some((layer, Telemetry { provider })))
+}
+
+/// Makes `span` a child of the caller's span, from the `traceparent`
+/// header. Call it before the span is first entered.
+pub fn set_parent_from_metadata(span: &Span, metadata: &MetadataMap) {
+    let traceparent = metadata
+        .get(TRACEPARENT_HEADER)
+        .and_then(|value| value.to_str().ok());
+    set_parent(span, traceparent.unwrap_or_default());
+}
+
+/// Makes `span` a child of the span `traceparent` came from. An empty or
+/// malformed `traceparent` leaves `span` as it is.
+pub fn set_parent(span: &Span, traceparent: &str) {
+    if let Some(cx) = parent_context(traceparent) {
+        span.set_parent(cx);
+    }
+}
+
+fn parent_context(traceparent: &str) -> Option<Context> {
+    if traceparent.is_empty() {
+        return None;
+    }
+    let carrier = HashMap::from([(TRACEPARENT_HEADER.to_string(), traceparent.to_string())]);
+    let cx = TraceContextPropagator::new().extract(&carrier);
+    cx.span().span_context().is_valid().then_some(cx)
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parent_context_from_traceparent() {
+        let cx = parent_context("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01").unwrap();
+        let parent = cx.span().span_context().clone();
+        assert_eq!(
+            parent.trace_id().to_string(),
+            "4bf92f3577b34da6a3ce929d0e0e4736"
+        );
+        assert!(parent.is_remote() && parent.is_sampled());
+
+        assert!(parent_context("").is_none());
+        assert!(parent_context("00-not-a-trace").is_none());
+    }
+}
@@ -0,0 +1,307 @@
+//! # Tenant Webhooks
+//!
+//! Outgoing HTTP callbacks that let a tenant's own monitoring react to
+//! events in the cloud (a store hub going offline, ...). Endpoints are rows
+//! in `tenant_webhooks`, each subscribed to some or all events.
+//!
+//! ## Delivery
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Webhook Delivery                                │
+//! │                                                                         │
+//! │  event ──► enqueue_webhook_event ──► webhook_deliveries (one row per    │
+//! │                                      subscribed, enabled webhook)       │
+//! │                                                                         │
+//! │  WebhookDispatcher (every DISPATCH_INTERVAL):                           │
+//! │    claim due rows (lease DELIVERY_LEASE) ──► POST url                   │
+//! │      2xx ──► delivered                                                  │
+//! │      error / non-2xx ──► retry after retry_delay(attempts), or failed   │
+//! │                          after MAX_DELIVERY_ATTEMPTS                    │
+//! │    finished for KEEP_FINISHED ──► deleted                               │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Request
+//! `POST <url>` with the JSON body `{"id", "event", "occurred_at", "data"}`
+//! and the headers:
+//!
+//! - `X-Titan-Event` - event name, e.g. `store.offline`
+//! - `X-Titan-Delivery` - delivery ID, the same on every retry
+//! - `X-Titan-Timestamp` - Unix seconds of this attempt
+//! - `X-Titan-Signature` - `sha256=<hex>`, HMAC-SHA256 with the webhook
+//!   secret over `<timestamp>.<body>`
+//!
+//! Delivery is at least once: receivers should drop bodies whose `id` they
+//! have seen.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::db::{Database, WebhookDeliveryRecord};
+use crate::error::CloudError;
+use crate::liveness::Liveness;
+
+/// A store hub has been silent for `STORE_OFFLINE_AFTER_SECS`.
+pub const EVENT_STORE_OFFLINE: &str = "store.offline";
+
+/// An offline store hub was heard from again.
+pub const EVENT_STORE_ONLINE: &str = "store.online";
+
+/// Name the dispatcher reports under in [`Liveness`].
+pub const WEBHOOK_WORKER: &str = "webhooks";
+
+/// Time between dispatcher runs.
+pub const DISPATCH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Attempts before a delivery is given up on.
+pub const MAX_DELIVERY_ATTEMPTS: i32 = 8;
+
+/// How long a claimed delivery is hidden from other instances; longer
+/// than a run that waits out REQUEST_TIMEOUT on every claimed delivery.
+const DELIVERY_LEASE: Duration = Duration::from_secs(600);
+
+/// Longest wait for an endpoint to answer.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Deliveries claimed per run.
+const CLAIM_BATCH: i64 = 50;
+
+/// How long delivered and failed deliveries are kept.
+const KEEP_FINISHED: Duration = Duration::from_secs(7 * 24 * 3600);
+
+/// First retry delay, doubled on every attempt.
+const FIRST_RETRY: Duration = Duration::from_secs(30);
+
+/// Longest retry delay.
+const MAX_RETRY: Duration = Duration::from_secs(3600);
+
+/// The JSON body sent for an event.
+pub fn event_body(event: &str, data: serde_json::Value, occurred_at: DateTime<Utc>) -> String {
+    serde_json::json!({
+        "id": Uuid::new_v4().to_string(),
+        "event": event,
+        "occurred_at": occurred_at.to_rfc3339(),
+        "data": data,
+    })
+    .to_string()
+}
+
+/// `X-Titan-Signature` of `body` sent at `timestamp` (Unix seconds).
+pub fn signature(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Wait before the next attempt after `attempts` failed ones.
+pub fn retry_delay(attempts: i32) -> Duration {
+    let doublings = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    FIRST_RETRY.saturating_mul(1 << doublings).min(MAX_RETRY)
+}
+
+// =============================================================================
+// Dispatcher
+// =============================================================================
+
+/// What a dispatcher run did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DispatchReport {
+    pub delivered: u32,
+    pub retrying: u32,
+    pub failed: u32,
+}
+
+/// Sends queued webhook deliveries.
+///
+/// Deliveries are claimed with a lease, so several cloud instances can run
+/// a dispatcher each.
+pub struct WebhookDispatcher {
+    db: Database,
+    http: reqwest::Client,
+    liveness: Option<Liveness>,
+}
+
+impl WebhookDispatcher {
+    /// Creates a dispatcher.
+    pub fn new(db: Database) -> Self {
+        WebhookDispatcher {
+            db,
+            http: reqwest::Client::new(),
+            liveness: None,
+        }
+    }
+
+    /// Reports the dispatcher's loop to `liveness` as [`WEBHOOK_WORKER`].
+    pub fn with_liveness(mut self, liveness: Liveness) -> Self {
+        self.liveness = Some(liveness);
+        self
+    }
+
+    /// Runs the dispatcher every `interval`, starting now.
+    pub fn spawn(self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        if let Some(liveness) = &self.liveness {
+            // A run can wait on CLAIM_BATCH slow endpoints
+            liveness.register(
+                WEBHOOK_WORKER,
+                interval * 2 + REQUEST_TIMEOUT * CLAIM_BATCH as u32,
+            );
+        }
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+
+            loop {
+                ticker.tick().await;
+                if let Some(liveness) = &self.liveness {
+                    liveness.beat(WEBHOOK_WORKER);
+                }
+
+                match self.run_once(Utc::now()).await {
+                    Ok(report) if report != DispatchReport::default() => info!(
+                        delivered = report.delivered,
+                        retrying = report.retrying,
+                        failed = report.failed,
+                        "Webhook deliveries sent"
+                    ),
+                    Ok(_) => {}
+                    Err(e) => error!(error = %e, "Webhook dispatch failed"),
+                }
+            }
+        })
+    }
+
+    /// Sends the deliveries due at `now` and clears out old finished ones.
+    pub async fn run_once(&self, now: DateTime<Utc>) -> Result<DispatchReport, CloudError> {
+        let mut report = DispatchReport::default();
+
+        let lease_until = now + chrono::Duration::from_std(DELIVERY_LEASE).unwrap_or_default();
+        let deliveries = self
+            .db
+            .claim_webhook_deliveries(now, lease_until, CLAIM_BATCH)
+            .await?;
+
+        for delivery in deliveries {
+            match self.send(&delivery).await {
+                Ok(()) => {
+                    self.db.mark_webhook_delivered(delivery.id).await?;
+                    report.delivered += 1;
+                }
+                Err(reason) if delivery.attempts >= MAX_DELIVERY_ATTEMPTS => {
+                    warn!(
+                        delivery_id = delivery.id,
+                        event = %delivery.event,
+                        attempts = delivery.attempts,
+                        %reason,
+                        "Webhook delivery failed, giving up"
+                    );
+                    self.db
+                        .fail_webhook_delivery(delivery.id, &reason, None)
+                        .await?;
+                    report.failed += 1;
+                }
+                Err(reason) => {
+                    let retry_at = Utc::now()
+                        + chrono::Duration::from_std(retry_delay(delivery.attempts))
+                            .unwrap_or_default();
+                    self.db
+                        .fail_webhook_delivery(delivery.id, &reason, Some(retry_at))
+                        .await?;
+                    report.retrying += 1;
+                }
+            }
+        }
+
+        let keep = chrono::Duration::from_std(KEEP_FINISHED).unwrap_or_default();
+        self.db
+            .delete_finished_webhook_deliveries(now - keep)
+            .await?;
+
+        Ok(report)
+    }
+
+    /// POSTs one delivery; `Err` carries the reason it did not land.
+    async fn send(&self, delivery: &WebhookDeliveryRecord) -> Result<(), String> {
+        let timestamp = Utc::now().timestamp();
+
+        let response = self
+            .http
+            .post(&delivery.url)
+            .timeout(REQUEST_TIMEOUT)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header("X-Titan-Event", &delivery.event)
+            .header("X-Titan-Delivery", delivery.id.to_string())
+            .header("X-Titan-Timestamp", timestamp.to_string())
+            .header(
+                "X-Titan-Signature",
+                signature(&delivery.secret, timestamp, &delivery.payload),
+            )
+            .body(delivery.payload.clone())
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("HTTP {}", response.status()))
+        }
+    }
+}
+
+// =============================================================================
+// Unit Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_covers_timestamp_and_body() {
+        let body = r#"{"event":"store.offline"}"#;
+        let sig = signature("s3cret", 1_700_000_000, body);
+
+        assert!(sig.starts_with("sha256="));
+        assert_eq!(sig.len(), "sha256=".len() + 64);
+        assert_eq!(sig, signature("s3cret", 1_700_000_000, body));
+        assert_ne!(sig, signature("s3cret", 1_700_000_001, body));
+        assert_ne!(
+            sig,
+            signature("s3cret", 1_700_000_000, r#"{"event":"store.online"}"#)
+        );
+        assert_ne!(sig, signature("other", 1_700_000_000, body));
+    }
+
+    #[test]
+    fn test_retry_delay_doubles_up_to_the_cap() {
+        assert_eq!(retry_delay(1), Duration::from_secs(30));
+        assert_eq!(retry_delay(2), Duration::from_secs(60));
+        assert_eq!(retry_delay(4), Duration::from_secs(240));
+        assert_eq!(retry_delay(8), MAX_RETRY);
+        assert_eq!(retry_delay(i32::MAX), MAX_RETRY);
+    }
+
+    #[test]
+    fn test_event_body_shape() {
+        let at = Utc::now();
+        let body = event_body(
+            EVENT_STORE_ONLINE,
+            serde_json::json!({"store_id": "store-001"}),
+            at,
+        );
+        let value: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+        assert_eq!(value["event"], "store.online");
+        assert_eq!(value["occurred_at"], at.to_rfc3339());
+        assert_eq!(value["data"]["store_id"], "store-001");
+        assert!(Uuid::parse_str(value["id"].as_str().unwrap()).is_ok());
+    }
+}
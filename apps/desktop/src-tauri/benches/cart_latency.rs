@@ -0,0 +1,134 @@
+//! # Cart Latency Benchmark
+//!
+//! Scan-to-response latency of `CartState` while other threads keep
+//! reading the cart, the way the register sees it during rapid scanning:
+//! the UI polls `get_cart`, the stock check reads the cart before every
+//! add, and background snapshots copy the whole cart.
+//!
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                       cart_latency                                      │
+//! │                                                                         │
+//! │  scanner thread ── add line ──► respond (with_totals) ── timed         │
+//! │  SNAPSHOT_THREADS ── with_totals(clone items + totals) in a loop       │
+//! │                                                                         │
+//! │  prints p50 / p99 / max per scan and fails if p99 misses the budget    │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Run with `cargo bench --bench cart_latency`.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use titan_core::{ItemTracking, Product, DEFAULT_TENANT_ID};
+use titan_desktop_lib::commands::cart::CartResponse;
+use titan_desktop_lib::state::CartState;
+
+/// Scans timed per run.
+const SCANS: usize = 2_000;
+
+/// Distinct products scanned (the cart grows to this many lines).
+const PRODUCTS: usize = 100;
+
+/// Threads copying the cart in a loop while scanning.
+const SNAPSHOT_THREADS: usize = 4;
+
+/// p99 scan-to-response budget: one frame at 60 Hz.
+const P99_BUDGET: Duration = Duration::from_micros(16_667);
+
+fn product(n: usize) -> Product {
+    Product {
+        id: format!("bench-{}", n),
+        tenant_id: DEFAULT_TENANT_ID.to_string(),
+        sku: format!("SKU-{}", n),
+        barcode: None,
+        name: format!("Bench Product {}", n),
+        description: None,
+        price_cents: 199 + n as i64,
+        base_price_cents: None,
+        cost_cents: None,
+        tax_rate_bps: 825,
+        track_inventory: false,
+        allow_negative_stock: false,
+        current_stock: None,
+        item_tracking: ItemTracking::None,
+        min_purchase_age: None,
+        is_active: true,
+        deleted_at: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        sync_version: 0,
+    }
+}
+
+fn percentile(sorted: &[Duration], pct: usize) -> Duration {
+    sorted[(sorted.len() - 1) * pct / 100]
+}
+
+fn main() {
+    let cart = Arc::new(CartState::new());
+    let products: Vec<Product> = (0..PRODUCTS).map(product).collect();
+    let stop = Arc::new(AtomicBool::new(false));
+    let snapshots = Arc::new(AtomicU64::new(0));
+
+    let readers: Vec<_> = (0..SNAPSHOT_THREADS)
+        .map(|_| {
+            let cart = Arc::clone(&cart);
+            let stop = Arc::clone(&stop);
+            let snapshots = Arc::clone(&snapshots);
+            thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let snapshot = cart.with_totals(CartResponse::new);
+                    std::hint::black_box(snapshot);
+                    snapshots.fetch_add(1, Ordering::Relaxed);
+                }
+            })
+        })
+        .collect();
+
+    let mut latencies = Vec::with_capacity(SCANS);
+    let started = Instant::now();
+    for i in 0..SCANS {
+        let product = &products[i % PRODUCTS];
+        let scan = Instant::now();
+        cart.with_cart_mut(|c| c.add_item(product, 1))
+            .expect("bench scan failed");
+        let response = cart.with_totals(CartResponse::new);
+        latencies.push(scan.elapsed());
+        std::hint::black_box(response);
+    }
+    let elapsed = started.elapsed();
+
+    stop.store(true, Ordering::Relaxed);
+    for reader in readers {
+        reader.join().expect("snapshot thread panicked");
+    }
+
+    latencies.sort();
+    let p50 = percentile(&latencies, 50);
+    let p99 = percentile(&latencies, 99);
+    let max = latencies[latencies.len() - 1];
+
+    println!(
+        "cart_latency: {} scans over {} lines, {} snapshot threads",
+        SCANS, PRODUCTS, SNAPSHOT_THREADS
+    );
+    println!("  p50 {:?}  p99 {:?}  max {:?}", p50, p99, max);
+    println!(
+        "  {} snapshots in {:?} ({:.0}/s)",
+        snapshots.load(Ordering::Relaxed),
+        elapsed,
+        snapshots.load(Ordering::Relaxed) as f64 / elapsed.as_secs_f64()
+    );
+
+    assert!(
+        p99 <= P99_BUDGET,
+        "p99 scan-to-response {:?} over the {:?} budget",
+        p99,
+        P99_BUDGET
+    );
+}
@@ -0,0 +1,201 @@
+//! # Age Verification Commands
+//!
+//! Record how a customer's age was checked for a sale that contains
+//! age-restricted products.
+//!
+//! ## Checkout With Restricted Items
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                    Age Check at Checkout                                │
+//! │                                                                         │
+//! │  get_cart ──► requiredAge: 21  (UI shows "ID check needed")             │
+//! │                                                                         │
+//! │  finalize_sale ──► AGE_VERIFICATION_REQUIRED ──► UI prompts cashier     │
+//! │                                                                         │
+//! │  verify_customer_age(saleId, "birthdate", "2001-04-09")                 │
+//! │  verify_customer_age(saleId, "id_scan")                                 │
+//! │        │                                                                │
+//! │        ▼                                                                │
+//! │  finalize_sale ──► COMPLETE (check stays on the sale for audits)        │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{debug, info};
+use ts_rs::TS;
+
+use crate::error::{ApiError, ErrorCode};
+use crate::middleware::traced;
+use crate::state::{ConfigState, DbState};
+use titan_core::age::{check_age_verification, required_age};
+use titan_core::{AgeVerification, AgeVerificationMethod, CoreError, SaleItem, SaleStatus};
+use titan_db::Database;
+
+/// Cashier ID recorded on the check (matches the sale commands).
+const USER_ID: &str = "default";
+
+/// Age check as returned to the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct AgeVerificationDto {
+    pub sale_id: String,
+    pub required_age: u32,
+    pub method: String,
+    pub birthdate: Option<String>,
+    pub verified_by: String,
+    pub verified_at: String,
+}
+
+impl From<AgeVerification> for AgeVerificationDto {
+    fn from(v: AgeVerification) -> Self {
+        AgeVerificationDto {
+            sale_id: v.sale_id,
+            required_age: v.required_age,
+            method: v.method.as_str().to_string(),
+            birthdate: v.birthdate.map(|d| d.to_string()),
+            verified_by: v.verified_by,
+            verified_at: v.verified_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Records the customer's age check on a draft sale.
+///
+/// ## Arguments
+/// * `sale_id` - Draft sale containing restricted items
+/// * `method` - "birthdate" or "id_scan"
+/// * `birthdate` - Date of birth (YYYY-MM-DD); required for "birthdate",
+///   optional for "id_scan" (e.g. read from the ID barcode)
+#[tauri::command]
+pub async fn verify_customer_age(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    sale_id: String,
+    method: String,
+    birthdate: Option<String>,
+) -> Result<AgeVerificationDto, ApiError> {
+    traced("verify_customer_age", async move {
+        debug!(sale_id = %sale_id, method = %method, "verify_customer_age command");
+
+        let db_inner: &Database = (*db).inner()?;
+
+        let method = match method.as_str() {
+            "birthdate" => AgeVerificationMethod::Birthdate,
+            "id_scan" => AgeVerificationMethod::IdScan,
+            other => {
+                return Err(ApiError::validation(format!(
+                    "Unknown verification method: {}",
+                    other
+                )))
+            }
+        };
+
+        let birthdate = birthdate
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                    .map_err(|_| ApiError::validation("Birthdate must be YYYY-MM-DD"))
+            })
+            .transpose()?;
+
+        let sale = db_inner
+            .sales()
+            .get_by_id(&sale_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Sale", &sale_id))?;
+
+        if sale.status != SaleStatus::Draft {
+            return Err(ApiError::new(
+                ErrorCode::BusinessLogic,
+                format!("Sale is {:?}, cannot verify age", sale.status),
+            ));
+        }
+
+        let items = db_inner.sales().get_items(&sale_id).await?;
+        let Some(required_age) = sale_required_age(db_inner, &items).await? else {
+            return Err(ApiError::new(
+                ErrorCode::BusinessLogic,
+                "Sale has no age-restricted items",
+            ));
+        };
+
+        let now = Utc::now();
+        check_age_verification(required_age, method, birthdate, now.date_naive())?;
+
+        let verification = AgeVerification {
+            sale_id: sale_id.clone(),
+            required_age,
+            method,
+            birthdate,
+            verified_by: USER_ID.to_string(),
+            device_id: config.station.device_id(),
+            verified_at: now,
+        };
+        db_inner.sales().set_age_verification(&verification).await?;
+
+        info!(sale_id = %sale_id, required_age, method = method.as_str(), "Customer age verified");
+
+        Ok(verification.into())
+    })
+    .await
+}
+
+/// Gets the age check recorded on a sale, if any.
+#[tauri::command]
+pub async fn get_age_verification(
+    db: State<'_, DbState>,
+    sale_id: String,
+) -> Result<Option<AgeVerificationDto>, ApiError> {
+    traced("get_age_verification", async move {
+        debug!(sale_id = %sale_id, "get_age_verification command");
+
+        let db_inner: &Database = (*db).inner()?;
+        let verification = db_inner.sales().get_age_verification(&sale_id).await?;
+
+        Ok(verification.map(AgeVerificationDto::from))
+    })
+    .await
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Highest minimum purchase age among a sale's products.
+async fn sale_required_age(db: &Database, items: &[SaleItem]) -> Result<Option<u32>, ApiError> {
+    let mut ages = Vec::with_capacity(items.len());
+    for item in items {
+        if let Some(product) = db.products().get_by_id(&item.product_id).await? {
+            ages.push(product.min_purchase_age);
+        }
+    }
+    Ok(required_age(ages))
+}
+
+/// Blocks finalization until a restricted sale has a covering age check.
+pub(crate) async fn ensure_age_verified(
+    db: &Database,
+    sale_id: &str,
+    items: &[SaleItem],
+) -> Result<(), ApiError> {
+    let Some(required_age) = sale_required_age(db, items).await? else {
+        return Ok(());
+    };
+
+    let reason = match db.sales().get_age_verification(sale_id).await? {
+        Some(v) if v.covers(required_age) => return Ok(()),
+        Some(v) => format!("verified for {} only", v.required_age),
+        None => "no age verification recorded".to_string(),
+    };
+
+    Err(CoreError::AgeRestricted {
+        required_age,
+        reason,
+    }
+    .into())
+}
@@ -0,0 +1,194 @@
+//! # Bundle Commands
+//!
+//! Bill of materials for bundle products (see `titan_core::bundle`), and
+//! the component checks the cart and checkout run for bundle lines.
+//!
+//! ## Checkout With Bundles
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                    Bundle at Checkout                                   │
+//! │                                                                         │
+//! │  set_product_bundle(GIFT-01, [1 × WINE, 2 × CHOC])                      │
+//! │                                                                         │
+//! │  add_to_cart(GIFT-01, 3) ──► components checked, not the bundle        │
+//! │                                                                         │
+//! │  finalize_sale ──► components checked again (with any WINE/CHOC sold   │
+//! │                    on their own lines), then WINE -3, CHOC -6, each    │
+//! │                    with an inventory delta referencing the sale        │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{debug, info};
+use ts_rs::TS;
+
+use crate::error::ApiError;
+use crate::middleware::traced;
+use crate::state::DbState;
+use titan_core::bundle::{check_components_available, merge_demand};
+use titan_core::{BundleComponent, ProductBundle, SaleItem};
+use titan_db::Database;
+
+/// One component of a bundle, with what the picker shows.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleComponentDto {
+    pub product_id: String,
+    pub sku: String,
+    pub name: String,
+    /// Units of the component in one bundle.
+    #[ts(type = "number")]
+    pub quantity: i64,
+    pub track_inventory: bool,
+    #[ts(type = "number | null")]
+    pub current_stock: Option<i64>,
+}
+
+/// A bundle product's bill of materials.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductBundleDto {
+    pub product_id: String,
+    /// Empty for a product that is not a bundle.
+    pub components: Vec<BundleComponentDto>,
+    /// Bundles the components in stock can make up; `None` when no
+    /// component limits it.
+    #[ts(type = "number | null")]
+    pub available_bundles: Option<i64>,
+    #[ts(type = "number")]
+    pub sync_version: i64,
+    pub updated_at: String,
+}
+
+/// Gets a product's bill of materials.
+///
+/// ## Errors
+/// `NOT_FOUND` if the product doesn't exist.
+#[tauri::command]
+pub async fn get_product_bundle(
+    db: State<'_, DbState>,
+    id: String,
+) -> Result<ProductBundleDto, ApiError> {
+    traced("get_product_bundle", async move {
+        debug!(id = %id, "get_product_bundle command");
+        let db_inner: &Database = (*db).inner()?;
+        let bundle = db_inner.bundles().get(&id).await?;
+        bundle_dto(db_inner, bundle).await
+    })
+    .await
+}
+
+/// Replaces a product's bill of materials; the new list syncs to the
+/// other terminals. Pass an empty list to make it an ordinary product.
+///
+/// Selling the bundle takes its components out of stock, never the bundle
+/// itself, so a bundle product normally doesn't track inventory.
+///
+/// ## Errors
+/// - `VALIDATION_ERROR` for a repeated component, a quantity out of
+///   range, the bundle listing itself, or nested bundles
+/// - `NOT_FOUND` if the product or a component doesn't exist
+#[tauri::command]
+pub async fn set_product_bundle(
+    db: State<'_, DbState>,
+    id: String,
+    components: Vec<BundleComponent>,
+) -> Result<ProductBundleDto, ApiError> {
+    traced("set_product_bundle", async move {
+        info!(id = %id, count = components.len(), "set_product_bundle command");
+        let db_inner: &Database = (*db).inner()?;
+        let bundle = db_inner.bundles().set_components(&id, &components).await?;
+        bundle_dto(db_inner, bundle).await
+    })
+    .await
+}
+
+/// Checks that the components in stock cover `quantity` of a bundle, for
+/// the cart. Returns `false` (nothing checked) for a product that is not a
+/// bundle.
+///
+/// ## Errors
+/// `INSUFFICIENT_STOCK` naming the first component that is short.
+pub(crate) async fn check_bundle_available(
+    db: &Database,
+    product_id: &str,
+    quantity: i64,
+) -> Result<bool, ApiError> {
+    let bundle = db.bundles().get(product_id).await?;
+    if !bundle.is_bundle() {
+        return Ok(false);
+    }
+
+    let products = db.bundles().component_products(&bundle).await?;
+    check_components_available(&bundle.component_quantities(quantity), &products)?;
+    Ok(true)
+}
+
+/// Checks the component stock of every bundle line of a sale before it is
+/// finalized. Components also sold on their own lines count against the
+/// same stock.
+///
+/// ## Errors
+/// `INSUFFICIENT_STOCK` naming the first component that is short.
+pub(crate) async fn ensure_bundle_components_available(
+    db: &Database,
+    items: &[SaleItem],
+) -> Result<(), ApiError> {
+    let mut demand = Vec::new();
+    for item in items {
+        let bundle = db.bundles().get(&item.product_id).await?;
+        demand.extend(bundle.component_quantities(item.quantity));
+    }
+    if demand.is_empty() {
+        return Ok(());
+    }
+
+    for item in items {
+        if demand.iter().any(|c| c.product_id == item.product_id) {
+            demand.push(BundleComponent::new(item.product_id.as_str(), item.quantity));
+        }
+    }
+    let demand = merge_demand(demand);
+
+    let mut products = Vec::with_capacity(demand.len());
+    for component in &demand {
+        if let Some(product) = db.products().get_by_id(&component.product_id).await? {
+            products.push(product);
+        }
+    }
+
+    check_components_available(&demand, &products)?;
+    Ok(())
+}
+
+async fn bundle_dto(db: &Database, bundle: ProductBundle) -> Result<ProductBundleDto, ApiError> {
+    let products = db.bundles().component_products(&bundle).await?;
+    let available_bundles = bundle.available_bundles(&products);
+
+    let components = bundle
+        .components
+        .into_iter()
+        .map(|c| {
+            let product = products.iter().find(|p| p.id == c.product_id);
+            BundleComponentDto {
+                sku: product.map(|p| p.sku.clone()).unwrap_or_default(),
+                name: product.map(|p| p.name.clone()).unwrap_or_default(),
+                track_inventory: product.is_some_and(|p| p.track_inventory),
+                current_stock: product.and_then(|p| p.current_stock),
+                product_id: c.product_id,
+                quantity: c.quantity,
+            }
+        })
+        .collect();
+
+    Ok(ProductBundleDto {
+        product_id: bundle.product_id,
+        components,
+        available_bundles,
+        sync_version: bundle.sync_version,
+        updated_at: bundle.updated_at.to_rfc3339(),
+    })
+}
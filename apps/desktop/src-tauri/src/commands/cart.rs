@@ -13,7 +13,13 @@
 //! │  └──────────┘     └──────────┘     └──────────┘     └──────────┘       │
 //! │                        │                 │                              │
 //! │                   add_to_cart       finalize_sale                      │
-//! │                   update_item       (sale.rs)                          │
+//! │                   add_department_item  (sale.rs)                       │
+//! │                   add_price_embedded_item                              │
+//! │                   add_weighed_item (scale.rs)                          │
+//! │                   update_item                                          │
+//! │                   override_line_price                                   │
+//! │                   duplicate_cart_item / move_cart_item                  │
+//! │                   set_quantities_bulk                                   │
 //! │                   remove_item                                           │
 //! │                        │                                                │
 //! │                        ▼                                                │
@@ -25,28 +31,62 @@
 use serde::{Deserialize, Serialize};
 use tauri::State;
 use tracing::debug;
+use ts_rs::TS;
 
+use crate::commands::bundle::check_bundle_available;
+use crate::commands::margin::audit_bypass;
 use crate::error::ApiError;
-use crate::state::{Cart, CartItem, CartState, CartTotals, DbState};
+use crate::middleware::traced;
+use crate::state::{
+    training_mode, Cart, CartItem, CartState, CartTotals, ConfigState, DbState, LineQuantity,
+};
+use titan_core::department::department_product_id;
+use titan_core::tracking::normalize_tracking_codes;
+use titan_core::validation::validate_price_cents;
+use titan_core::{
+    CoreError, DepartmentLine, EntityEvent, ManagerBypass, MarginCheck, PriceChangeKind,
+};
 use titan_db::Database;
 
 /// Cart response including items and totals.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct CartResponse {
     pub items: Vec<CartItem>,
     pub totals: CartTotals,
+    /// Set when the cart holds age-restricted products.
+    pub required_age: Option<u32>,
+    /// Whether this is a training sale (see `set_training_mode`).
+    pub training_mode: bool,
 }
 
-impl From<&Cart> for CartResponse {
-    fn from(cart: &Cart) -> Self {
+impl CartResponse {
+    /// Builds the response from the cart and its cached totals.
+    ///
+    /// Meant for `CartState::with_totals`, so the totals aren't recomputed
+    /// for every response.
+    pub fn new(cart: &Cart, totals: &CartTotals) -> Self {
         CartResponse {
             items: cart.items.clone(),
-            totals: CartTotals::from(cart),
+            totals: totals.clone(),
+            required_age: cart.required_age(),
+            training_mode: training_mode(),
         }
     }
 }
 
+/// Cart after a line price override, with the margin check of the new
+/// price.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct LinePriceResponse {
+    pub cart: CartResponse,
+    /// `verdict` is "warn" when the price is below the margin floor.
+    pub margin: MarginCheck,
+}
+
 /// Gets the current cart contents.
 ///
 /// ## User Workflow
@@ -75,7 +115,7 @@ impl From<&Cart> for CartResponse {
 #[tauri::command]
 pub fn get_cart(cart: State<'_, CartState>) -> CartResponse {
     debug!("get_cart command");
-    cart.with_cart(|c| CartResponse::from(c))
+    cart.with_totals(CartResponse::new)
 }
 
 /// Adds a product to the cart.
@@ -110,6 +150,8 @@ pub fn get_cart(cart: State<'_, CartState>) -> CartResponse {
 /// ## Arguments
 /// * `product_id` - Product UUID to add
 /// * `quantity` - Quantity to add (default: 1)
+/// * `tracking_codes` - Serial numbers (one per unit) or the lot number,
+///   required for products with `item_tracking` set
 ///
 /// ## Returns
 /// Updated cart with all items and totals
@@ -119,38 +161,37 @@ pub async fn add_to_cart(
     cart: State<'_, CartState>,
     product_id: String,
     quantity: Option<i64>,
+    tracking_codes: Option<Vec<String>>,
 ) -> Result<CartResponse, ApiError> {
-    let quantity = quantity.unwrap_or(1);
-    debug!(product_id = %product_id, quantity = %quantity, "add_to_cart command");
-
-    // Explicit type annotation helps Rust resolve the method chain
-    // db is State<DbState>, so we dereference to get &DbState first
-    let db_inner: &Database = (*db).inner();
-    let product = db_inner
-        .products()
-        .get_by_id(&product_id)
-        .await?
-        .ok_or_else(|| ApiError::not_found("Product", &product_id))?;
-
-    // Check if product is active
-    if !product.is_active {
-        return Err(ApiError::validation("Product is not available for sale"));
-    }
+    traced("add_to_cart", async move {
+        let quantity = quantity.unwrap_or(1);
+        debug!(product_id = %product_id, quantity = %quantity, "add_to_cart command");
 
-    // Stock validation respecting trackInventory and allowNegativeStock flags
-    // ┌─────────────────────────────────────────────────────────────────────────┐
-    // │  Stock Behavior Matrix                                                  │
-    // │                                                                         │
-    // │  track_inventory │ allow_negative │ stock <= 0  │ Result               │
-    // │  ────────────────┼────────────────┼─────────────┼───────────────────── │
-    // │  false           │ (ignored)      │ (ignored)   │ Always allow         │
-    // │  true            │ false          │ yes         │ BLOCK - out of stock │
-    // │  true            │ true           │ yes         │ Allow (back-order)   │
-    // │  true            │ any            │ no          │ Allow                │
-    // └─────────────────────────────────────────────────────────────────────────┘
-    if product.track_inventory {
-        let current_stock = product.current_stock.unwrap_or(0);
-        
+        // Explicit type annotation helps Rust resolve the method chain
+        // db is State<DbState>, so we dereference to get &DbState first
+        let db_inner: &Database = (*db).inner()?;
+        let product = db_inner
+            .products()
+            .get_by_id(&product_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Product", &product_id))?;
+
+        // Check if product is active and not deleted
+        if !product.is_active || product.deleted_at.is_some() {
+            return Err(ApiError::validation("Product is not available for sale"));
+        }
+
+        // Stock validation respecting trackInventory and allowNegativeStock flags
+        // ┌─────────────────────────────────────────────────────────────────────────┐
+        // │  Stock Behavior Matrix                                                  │
+        // │                                                                         │
+        // │  track_inventory │ allow_negative │ stock <= 0  │ Result               │
+        // │  ────────────────┼────────────────┼─────────────┼───────────────────── │
+        // │  false           │ (ignored)      │ (ignored)   │ Always allow         │
+        // │  true            │ false          │ yes         │ BLOCK - out of stock │
+        // │  true            │ true           │ yes         │ Allow (back-order)   │
+        // │  true            │ any            │ no          │ Allow                │
+        // └─────────────────────────────────────────────────────────────────────────┘
         // Get current quantity in cart for this product
         let existing_qty = cart.with_cart(|c| {
             c.items
@@ -159,26 +200,147 @@ pub async fn add_to_cart(
                 .map(|i| i.quantity)
                 .unwrap_or(0)
         });
-        
         let total_requested = existing_qty + quantity;
+
+        // A bundle is limited by its components' stock, not its own
+        let is_bundle = check_bundle_available(db_inner, &product_id, total_requested).await?;
+
+        if product.track_inventory && !is_bundle {
+            let current_stock = product.current_stock.unwrap_or(0);
         
-        // Check if we have enough stock (or if back-orders are allowed)
-        if current_stock < total_requested && !product.allow_negative_stock {
-            return Err(ApiError::insufficient_stock(
-                &product.sku,
-                current_stock,
-                total_requested,
-            ));
+            // Check if we have enough stock (or if back-orders are allowed)
+            if current_stock < total_requested && !product.allow_negative_stock {
+                return Err(ApiError::insufficient_stock(
+                    &product.sku,
+                    current_stock,
+                    total_requested,
+                ));
+            }
         }
-    }
 
-    // Add to cart (thread-safe via Mutex)
-    let result = cart.with_cart_mut(|c| {
-        c.add_item(&product, quantity)?;
-        Ok::<CartResponse, String>(CartResponse::from(&*c))
-    });
+        // Serial/lot capture is enforced by the cart (see Cart::add_tracked_item)
+        let tracking_codes = normalize_tracking_codes(&tracking_codes.unwrap_or_default());
 
-    result.map_err(ApiError::cart)
+        // Add to cart under the write lock, then answer from the cached
+        // totals under a read lock so snapshots aren't held up
+        cart.with_cart_mut(|c| c.add_tracked_item(&product, quantity, &tracking_codes))
+            .map_err(ApiError::cart)?;
+        publish_line_added(
+            db_inner,
+            &cart,
+            &product.id,
+            &product.name,
+            product.price_cents,
+            quantity,
+        );
+
+        Ok(cart.with_totals(CartResponse::new))
+    })
+    .await
+}
+
+/// Adds an open-price sale on a department key (see
+/// `ConfigState::departments`).
+///
+/// The line is taxed by the department's tax group and recorded against
+/// the department, not a product, so it is reported apart from SKU sales.
+///
+/// ## Arguments
+/// * `department_code` - Department key, e.g. `"BAKERY"`
+/// * `price_cents` - Unit price keyed in by the cashier
+/// * `quantity` - Quantity to add (default: 1)
+///
+/// ## Errors
+/// `VALIDATION_ERROR` for an unknown department or a price outside the
+/// department's range.
+#[tauri::command]
+pub async fn add_department_item(
+    db: State<'_, DbState>,
+    cart: State<'_, CartState>,
+    config: State<'_, ConfigState>,
+    department_code: String,
+    price_cents: i64,
+    quantity: Option<i64>,
+) -> Result<CartResponse, ApiError> {
+    traced("add_department_item", async move {
+        let quantity = quantity.unwrap_or(1);
+        debug!(department_code = %department_code, price_cents, quantity, "add_department_item command");
+
+        let line = config
+            .departments
+            .open_price(department_code.trim(), price_cents)?;
+        add_department_line(&db, &cart, &line, quantity).await
+    })
+    .await
+}
+
+/// Adds a department sale from a price-embedded barcode, as printed by
+/// scales and in-store labels (prefix, price, check digit).
+///
+/// ## Errors
+/// - `NOT_FOUND` if no department has the barcode's prefix
+/// - `VALIDATION_ERROR` for a bad check digit or a price outside the
+///   department's range
+#[tauri::command]
+pub async fn add_price_embedded_item(
+    db: State<'_, DbState>,
+    cart: State<'_, CartState>,
+    config: State<'_, ConfigState>,
+    barcode: String,
+) -> Result<CartResponse, ApiError> {
+    traced("add_price_embedded_item", async move {
+        debug!(barcode = %barcode, "add_price_embedded_item command");
+
+        let line = config
+            .departments
+            .price_embedded(barcode.trim())
+            .ok_or_else(|| ApiError::not_found("Department barcode", barcode.trim()))??;
+        add_department_line(&db, &cart, &line, 1).await
+    })
+    .await
+}
+
+/// Adds a checked department line to the cart, making sure the product
+/// its sale lines are recorded against exists.
+pub(crate) async fn add_department_line(
+    db: &DbState,
+    cart: &CartState,
+    line: &DepartmentLine,
+    quantity: i64,
+) -> Result<CartResponse, ApiError> {
+    let db_inner: &Database = db.inner()?;
+    db_inner.products().ensure_department_product(line).await?;
+
+    cart.with_cart_mut(|c| c.add_department_item(line, quantity))
+        .map_err(ApiError::cart)?;
+    publish_line_added(
+        db_inner,
+        cart,
+        &department_product_id(&line.department_code),
+        &line.name,
+        line.unit_price_cents,
+        quantity,
+    );
+    Ok(cart.with_totals(CartResponse::new))
+}
+
+/// Announces a line added to the cart on the entity event bus, for the
+/// customer display.
+fn publish_line_added(
+    db: &Database,
+    cart: &CartState,
+    product_id: &str,
+    name: &str,
+    unit_price_cents: i64,
+    quantity: i64,
+) {
+    db.events().publish(EntityEvent::CartLineAdded {
+        product_id: product_id.to_string(),
+        name: name.to_string(),
+        unit_price_cents,
+        quantity,
+        cart_total_cents: cart.with_totals(|_, t| t.total_cents),
+    });
 }
 
 /// Updates the quantity of an item in the cart.
@@ -201,12 +363,157 @@ pub fn update_cart_item(
 ) -> Result<CartResponse, ApiError> {
     debug!(product_id = %product_id, quantity = %quantity, "update_cart_item command");
 
-    let result = cart.with_cart_mut(|c| {
-        c.update_quantity(&product_id, quantity)?;
-        Ok::<CartResponse, String>(CartResponse::from(&*c))
-    });
+    cart.with_cart_mut(|c| c.update_quantity(&product_id, quantity))
+        .map_err(ApiError::cart)?;
+
+    Ok(cart.with_totals(CartResponse::new))
+}
+
+/// Repeats a cart line, for the keypad's "x3" on an existing line.
+///
+/// The line's quantity is multiplied by `times` (x3 on a line of 2 leaves
+/// 6); lines are unique by product, so no second line is added.
+///
+/// ## Arguments
+/// * `product_id` - Product UUID in cart
+/// * `times` - How many times over (at least 2, default 2)
+///
+/// ## Errors
+/// `CART_ERROR` if the product is not in the cart, the quantity would go
+/// over the maximum, or the line is serial-tracked.
+#[tauri::command]
+pub fn duplicate_cart_item(
+    cart: State<'_, CartState>,
+    product_id: String,
+    times: Option<i64>,
+) -> Result<CartResponse, ApiError> {
+    let times = times.unwrap_or(2);
+    debug!(product_id = %product_id, times, "duplicate_cart_item command");
+
+    cart.with_cart_mut(|c| c.duplicate_item(&product_id, times))
+        .map_err(ApiError::cart)?;
+
+    Ok(cart.with_totals(CartResponse::new))
+}
+
+/// Moves a cart line to another position, for grouping lines on the
+/// receipt.
+///
+/// ## Arguments
+/// * `product_id` - Product UUID in cart
+/// * `to_index` - New position, 0 for the first line
+///
+/// ## Errors
+/// `CART_ERROR` if the product is not in the cart or the position is past
+/// the last line.
+#[tauri::command]
+pub fn move_cart_item(
+    cart: State<'_, CartState>,
+    product_id: String,
+    to_index: usize,
+) -> Result<CartResponse, ApiError> {
+    debug!(product_id = %product_id, to_index, "move_cart_item command");
+
+    cart.with_cart_mut(|c| c.move_item(&product_id, to_index))
+        .map_err(ApiError::cart)?;
 
-    result.map_err(ApiError::cart)
+    Ok(cart.with_totals(CartResponse::new))
+}
+
+/// Sets the quantity of several cart lines in one go; a quantity of 0
+/// removes the line.
+///
+/// All or nothing: if one change is refused no line changes.
+///
+/// ## Errors
+/// `CART_ERROR` for an empty list, a line not in the cart or listed
+/// twice, a negative or too large quantity, or a serial-tracked line.
+#[tauri::command]
+pub fn set_quantities_bulk(
+    cart: State<'_, CartState>,
+    changes: Vec<LineQuantity>,
+) -> Result<CartResponse, ApiError> {
+    debug!(count = changes.len(), "set_quantities_bulk command");
+
+    cart.with_cart_mut(|c| c.set_quantities(&changes))
+        .map_err(ApiError::cart)?;
+
+    Ok(cart.with_totals(CartResponse::new))
+}
+
+/// Overrides the unit price of an item in the cart.
+///
+/// The new price is checked against the store's margin floor (see
+/// `ConfigState::margin_guardrail`); a manager's bypass of a blocking
+/// floor is written to the margin override audit trail.
+///
+/// ## Arguments
+/// * `product_id` - Product UUID in cart
+/// * `price_cents` - New unit price
+/// * `bypass` - Manager approval, needed below a blocking floor
+///
+/// ## Errors
+/// - `MANAGER_OVERRIDE_REQUIRED` below a blocking floor without a bypass
+/// - `VALIDATION_ERROR` for a negative price, or a bypass without a
+///   manager or reason
+/// - `CART_ERROR` if the product is not in the cart
+#[tauri::command]
+pub async fn override_line_price(
+    db: State<'_, DbState>,
+    cart: State<'_, CartState>,
+    config: State<'_, ConfigState>,
+    product_id: String,
+    price_cents: i64,
+    bypass: Option<ManagerBypass>,
+) -> Result<LinePriceResponse, ApiError> {
+    traced("override_line_price", async move {
+        debug!(product_id = %product_id, price_cents, "override_line_price command");
+        validate_price_cents(price_cents).map_err(CoreError::from)?;
+
+        let old_price = cart
+            .with_cart(|c| {
+                c.items
+                    .iter()
+                    .find(|i| i.product_id == product_id)
+                    .map(|i| i.unit_price_cents)
+            })
+            .ok_or_else(|| ApiError::cart(format!("Product {} not in cart", product_id)))?;
+
+        let db_inner: &Database = (*db).inner()?;
+        let product = db_inner
+            .products()
+            .get_by_id(&product_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Product", &product_id))?;
+
+        let margin = config.margin_guardrail.check(
+            &product.sku,
+            price_cents,
+            product.cost_cents,
+            bypass.as_ref(),
+        )?;
+
+        // Audited before the cart changes, so no bypass goes unrecorded
+        audit_bypass(
+            db_inner,
+            &config.station.device_id(),
+            PriceChangeKind::LinePrice,
+            &product,
+            old_price,
+            &margin,
+            bypass.as_ref(),
+        )
+        .await?;
+
+        cart.with_cart_mut(|c| c.set_line_price(&product_id, price_cents))
+            .map_err(ApiError::cart)?;
+
+        Ok(LinePriceResponse {
+            cart: cart.with_totals(CartResponse::new),
+            margin,
+        })
+    })
+    .await
 }
 
 /// Removes an item from the cart.
@@ -223,12 +530,10 @@ pub fn remove_from_cart(
 ) -> Result<CartResponse, ApiError> {
     debug!(product_id = %product_id, "remove_from_cart command");
 
-    let result = cart.with_cart_mut(|c| {
-        c.remove_item(&product_id)?;
-        Ok::<CartResponse, String>(CartResponse::from(&*c))
-    });
+    cart.with_cart_mut(|c| c.remove_item(&product_id))
+        .map_err(ApiError::cart)?;
 
-    result.map_err(ApiError::cart)
+    Ok(cart.with_totals(CartResponse::new))
 }
 
 /// Clears all items from the cart.
@@ -243,8 +548,6 @@ pub fn remove_from_cart(
 pub fn clear_cart(cart: State<'_, CartState>) -> CartResponse {
     debug!("clear_cart command");
 
-    cart.with_cart_mut(|c| {
-        c.clear();
-        CartResponse::from(&*c)
-    })
+    cart.with_cart_mut(|c| c.clear());
+    cart.with_totals(CartResponse::new)
 }
@@ -0,0 +1,280 @@
+//! # Coupon Commands
+//!
+//! Apply a coupon code to the cart and fetch the tenant's coupons from the
+//! cloud. The coupon is redeemed by `create_sale`, which takes its
+//! discount off the sale.
+//!
+//! ## Coupon Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                           Coupon Flow                                   │
+//! │                                                                         │
+//! │  refresh_coupons ──► cloud "coupons" stream ──► local coupons table     │
+//! │                                                                         │
+//! │  apply_coupon(code)                                                     │
+//! │         │                                                               │
+//! │         ├── unknown code            ──► NOT_FOUND                       │
+//! │         ├── withdrawn, expired,                                         │
+//! │         │   used up, cart too small ──► COUPON_REJECTED                 │
+//! │         └── ok ──► cart.coupon (discount follows the cart)              │
+//! │                                                                         │
+//! │  create_sale ──► redeem (checked again) ──► sale.discount_cents         │
+//! │         │        at the hub when connected, so the last use of a        │
+//! │         │        coupon goes to one register only                       │
+//! │         │                                                               │
+//! │         └── COUPON queued ──► hub ──► other terminals ──► cloud         │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Coupons also arrive without `refresh_coupons`: the hub relays every
+//! redemption in the store and the cloud pushes changes to the sync agent.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{debug, info, warn};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::commands::cart::CartResponse;
+use crate::commands::sync::connect_cloud;
+use crate::error::{ApiError, ErrorCode};
+use crate::middleware::traced;
+use crate::state::{training_mode, CartState, ConfigState, DbState, SyncState};
+use titan_core::coupon::normalize_code;
+use titan_core::{CoreError, Coupon, CouponDocument, CouponRedemption, SaleItem};
+use titan_db::Database;
+use titan_sync::protocol::CouponRedeemRequest;
+use titan_sync::SyncError;
+
+/// Result of a coupon refresh.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct CouponRefreshDto {
+    /// Coupons the cloud sent.
+    pub downloaded: usize,
+    /// Coupons that changed locally.
+    pub changed: usize,
+}
+
+/// Applies a coupon code to the cart, replacing any coupon already on it.
+///
+/// # Errors
+/// - `NOT_FOUND` if no coupon has the code
+/// - `COUPON_REJECTED` if it cannot be used or the cart does not qualify
+#[tauri::command]
+pub async fn apply_coupon(
+    db: State<'_, DbState>,
+    cart: State<'_, CartState>,
+    code: String,
+) -> Result<CartResponse, ApiError> {
+    traced("apply_coupon", async move {
+        let code = normalize_code(&code);
+        debug!(code = %code, "apply_coupon command");
+        if code.is_empty() {
+            return Err(ApiError::validation("Coupon code is required"));
+        }
+
+        let db_inner: &Database = (*db).inner()?;
+        let doc = load_coupon(db_inner, &code).await?;
+
+        let lines = cart.with_cart(|c| c.coupon_lines());
+        let discount = doc.evaluate(&lines, Utc::now())?;
+        info!(code = %code, discount = discount.discount_cents, "Coupon applied");
+
+        cart.with_cart_mut(|c| c.coupon = Some(doc.coupon));
+        Ok(cart.with_totals(CartResponse::new))
+    })
+    .await
+}
+
+/// Takes the coupon off the cart.
+#[tauri::command]
+pub fn remove_coupon(cart: State<'_, CartState>) -> CartResponse {
+    debug!("remove_coupon command");
+    cart.with_cart_mut(|c| c.coupon = None);
+    cart.with_totals(CartResponse::new)
+}
+
+/// Fetches the coupons that changed in the cloud since the last refresh.
+///
+/// # Errors
+/// - `CLOUD_ERROR` if the cloud could not be reached
+#[tauri::command]
+pub async fn refresh_coupons(
+    db: State<'_, DbState>,
+    sync: State<'_, SyncState>,
+    config: State<'_, ConfigState>,
+) -> Result<CouponRefreshDto, ApiError> {
+    traced("refresh_coupons", async move {
+        let sync_config = sync
+            .get_config()
+            .ok_or_else(|| ApiError::internal("Sync is not configured"))?;
+        let cloud_error = |e: titan_sync::SyncError| {
+            ApiError::new(
+                ErrorCode::CloudError,
+                format!("Coupon refresh failed: {}", e),
+            )
+        };
+
+        let mut uplink = connect_cloud(&sync_config, &config)
+            .await
+            .map_err(cloud_error)?;
+        let downloaded = uplink.download_coupons().await;
+        let (coupons, position) = match downloaded {
+            Ok(downloaded) => downloaded,
+            Err(e) => {
+                uplink.disconnect().await;
+                return Err(cloud_error(e));
+            }
+        };
+
+        // Stored before the stream is acknowledged, so a failure here
+        // downloads the same coupons again next time
+        let db_inner: &Database = (*db).inner()?;
+        let mut changed = 0;
+        for doc in &coupons {
+            if db_inner.coupons().upsert_from_sync(doc).await? {
+                changed += 1;
+            }
+        }
+
+        let acked = uplink.acknowledge_coupons(position).await;
+        uplink.disconnect().await;
+        acked.map_err(cloud_error)?;
+
+        info!(downloaded = coupons.len(), changed, "Coupons refreshed");
+        Ok(CouponRefreshDto {
+            downloaded: coupons.len(),
+            changed,
+        })
+    })
+    .await
+}
+
+/// Redeems the cart's coupon on a new sale and spreads the discount over
+/// its lines, in cart order.
+///
+/// The use is counted by the hub when the sync agent runs, and by the
+/// local ledger in training mode, without an agent or when the hub
+/// predates coupon counting.
+/// Returns the discount taken; the redemption is queued for the other
+/// terminals and the cloud.
+///
+/// # Errors
+/// `COUPON_REJECTED` if the coupon was used up or withdrawn since it was
+/// applied, the cart no longer qualifies, or the hub could not be asked.
+pub(crate) async fn redeem_coupon(
+    db: &Database,
+    device_id: &str,
+    sync: &SyncState,
+    coupon: &Coupon,
+    sale_id: &str,
+    items: &mut [SaleItem],
+) -> Result<i64, ApiError> {
+    let lines: Vec<_> = items
+        .iter()
+        .map(|i| titan_core::coupon::CouponLine {
+            product_id: i.product_id.clone(),
+            line_total_cents: i.line_total_cents,
+        })
+        .collect();
+    let discount = coupon.discount_for(&lines)?;
+
+    let redemption = CouponRedemption {
+        id: Uuid::new_v4().to_string(),
+        coupon_id: coupon.id.clone(),
+        sale_id: sale_id.to_string(),
+        store_id: String::new(),
+        device_id: device_id.to_string(),
+        discount_cents: discount.discount_cents,
+        redeemed_at: Utc::now(),
+    };
+
+    // Training sales count against the sandbox, never the store's coupons
+    let hub = if training_mode() {
+        None
+    } else {
+        sync.agent_handle()
+    };
+    let rejection = match hub {
+        Some(handle) => {
+            let request = CouponRedeemRequest {
+                request_id: Uuid::new_v4().to_string(),
+                device_id: device_id.to_string(),
+                coupon: coupon.clone(),
+                redemption_id: redemption.id.clone(),
+                sale_id: redemption.sale_id.clone(),
+                discount_cents: redemption.discount_cents,
+                timestamp: redemption.redeemed_at.to_rfc3339(),
+            };
+            match handle.redeem_coupon(request).await {
+                Ok(result) if result.approved => {
+                    // The hub also broadcasts the coupon; recording the use
+                    // now keeps this terminal's count right if that arrives
+                    // late.
+                    db.coupons()
+                        .upsert_from_sync(&CouponDocument {
+                            coupon: coupon.clone(),
+                            redemptions: vec![redemption],
+                        })
+                        .await?;
+                    None
+                }
+                Ok(result) => Some(result.reason.unwrap_or_default()),
+                // Hubs before v11 do not count coupons
+                Err(SyncError::UnsupportedVersion(_)) => {
+                    db.coupons().redeem(&redemption).await?.rejection
+                }
+                Err(e) => {
+                    warn!(code = %coupon.code, ?e, "Coupon could not be verified");
+                    Some(format!("it could not be checked with the store hub: {}", e))
+                }
+            }
+        }
+        None => db.coupons().redeem(&redemption).await?.rejection,
+    };
+    if let Some(reason) = rejection {
+        return Err(CoreError::CouponRejected {
+            code: coupon.code.clone(),
+            reason,
+        }
+        .into());
+    }
+
+    for (item, line_discount) in items.iter_mut().zip(&discount.line_discounts) {
+        item.discount_cents = *line_discount;
+    }
+
+    let doc = db
+        .coupons()
+        .get_document(&coupon.id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Coupon", &coupon.id))?;
+    queue_coupon(db, &doc).await?;
+
+    Ok(discount.discount_cents)
+}
+
+async fn load_coupon(db: &Database, code: &str) -> Result<CouponDocument, ApiError> {
+    let coupon = db
+        .coupons()
+        .get_by_code(code)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Coupon", code))?;
+    db.coupons()
+        .get_document(&coupon.id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Coupon", code))
+}
+
+/// Queues the coupon and all its redemptions for other terminals and stores.
+async fn queue_coupon(db: &Database, doc: &CouponDocument) -> Result<(), ApiError> {
+    let payload = serde_json::to_string(doc)
+        .map_err(|e| ApiError::new(ErrorCode::Internal, e.to_string()))?;
+    db.sync_outbox()
+        .upsert_for_sync("COUPON", &doc.coupon.id, &payload)
+        .await?;
+    Ok(())
+}
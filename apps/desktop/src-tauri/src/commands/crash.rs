@@ -0,0 +1,265 @@
+//! # Crash Report Commands
+//!
+//! Crash reports queued on the terminal (see `CrashState`), frontend
+//! crashes, and uploading the queue through the cloud.
+//!
+//! ## Commands
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                       Crash Report Commands                             │
+//! │                                                                         │
+//! │  report_frontend_crash(message, stack, source)                          │
+//! │       window.onerror / unhandledrejection ──► queued report             │
+//! │                                                                         │
+//! │  list_crash_reports   queued and uploaded reports, newest first         │
+//! │                                                                         │
+//! │  upload_crash_reports  for each queued report:                          │
+//! │       cloud CreateSignedUrl(CRASH_REPORT, WRITE, file name)             │
+//! │       ──► PUT the JSON ──► moved to crashes/sent/                       │
+//! │       a failed upload stays queued for the next attempt                 │
+//! │                                                                         │
+//! │  With TITAN_CRASH_UPLOAD=1 the queue is also uploaded at startup.       │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+use tracing::{debug, info, warn};
+use ts_rs::TS;
+
+use crate::commands::sync::connect_cloud;
+use crate::error::{ApiError, ErrorCode};
+use crate::middleware::traced;
+use crate::state::{ConfigState, CrashState, SyncState};
+use titan_core::{CrashKind, CrashReport};
+use titan_sync::proto::create_signed_url_request::{Access, Kind};
+
+/// Longest a report upload may take.
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Longest frontend error message or stack kept.
+const MAX_FRONTEND_TEXT_LEN: usize = 20_000;
+
+/// A crash report as listed in the UI.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReportDto {
+    pub id: String,
+    pub file_name: String,
+    pub kind: CrashKind,
+    pub occurred_at: String,
+    pub message: String,
+    pub location: Option<String>,
+    pub app_version: String,
+    /// The report has been uploaded through the cloud.
+    pub uploaded: bool,
+}
+
+impl CrashReportDto {
+    fn new(report: &CrashReport, uploaded: bool) -> Self {
+        CrashReportDto {
+            id: report.id.clone(),
+            file_name: report.file_name(),
+            kind: report.kind,
+            occurred_at: report.occurred_at.to_rfc3339(),
+            message: report.message.clone(),
+            location: report.location.clone(),
+            app_version: report.app_version.clone(),
+            uploaded,
+        }
+    }
+}
+
+/// Result of uploading the queued reports.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashUploadDto {
+    pub uploaded: u32,
+    /// Reports still queued (their upload failed).
+    pub pending: u32,
+}
+
+/// Records an uncaught frontend error as a crash report.
+///
+/// # Arguments
+/// * `message` - Error message
+/// * `stack` - JavaScript stack, if any
+/// * `source` - `file:line` the error came from, if known
+#[tauri::command]
+pub async fn report_frontend_crash(
+    crash: State<'_, CrashState>,
+    message: String,
+    stack: Option<String>,
+    source: Option<String>,
+) -> Result<CrashReportDto, ApiError> {
+    traced("report_frontend_crash", async move {
+        let message = truncate(message.trim());
+        if message.is_empty() {
+            return Err(ApiError::validation("'message' is required"));
+        }
+
+        let report = crash.report(
+            CrashKind::Frontend,
+            message,
+            source.map(|s| truncate(&s)),
+            None,
+            stack.map(|s| truncate(&s)).unwrap_or_default(),
+        );
+        crash
+            .write(&report)
+            .map_err(|e| ApiError::internal(format!("Cannot save crash report: {}", e)))?;
+        warn!(id = %report.id, message = %report.message, "Frontend crash recorded");
+
+        Ok(CrashReportDto::new(&report, false))
+    })
+    .await
+}
+
+/// Lists the crash reports on this terminal, newest first.
+#[tauri::command]
+pub async fn list_crash_reports(
+    crash: State<'_, CrashState>,
+) -> Result<Vec<CrashReportDto>, ApiError> {
+    traced("list_crash_reports", async move {
+        let pending = crash.pending().into_iter().map(|(_, r)| (r, false));
+        let sent = crash.sent().into_iter().map(|(_, r)| (r, true));
+        let mut reports: Vec<CrashReportDto> = pending
+            .chain(sent)
+            .map(|(report, uploaded)| CrashReportDto::new(&report, uploaded))
+            .collect();
+        reports.sort_by(|a, b| b.file_name.cmp(&a.file_name));
+        Ok(reports)
+    })
+    .await
+}
+
+/// Uploads the queued crash reports through the cloud.
+///
+/// # Errors
+/// - `INTERNAL` if sync is not configured (no cloud credentials)
+/// - `CLOUD_ERROR` if the cloud could not be reached
+#[tauri::command]
+pub async fn upload_crash_reports(
+    crash: State<'_, CrashState>,
+    sync: State<'_, SyncState>,
+    config: State<'_, ConfigState>,
+) -> Result<CrashUploadDto, ApiError> {
+    traced("upload_crash_reports", async move {
+        upload_pending(&crash, &sync, &config).await
+    })
+    .await
+}
+
+/// Uploads the queue in the background if crash upload is on and sync is
+/// configured. Called once at startup.
+pub fn spawn_queued_upload(app_handle: AppHandle) {
+    if !app_handle.state::<CrashState>().upload_enabled() {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        let crash = app_handle.state::<CrashState>();
+        let sync = app_handle.state::<SyncState>();
+        let config = app_handle.state::<ConfigState>();
+        if crash.pending().is_empty() {
+            return;
+        }
+        if sync.get_config().is_none() {
+            debug!("Sync is not configured, crash reports stay queued");
+            return;
+        }
+        match upload_pending(&crash, &sync, &config).await {
+            Ok(result) => info!(
+                uploaded = result.uploaded,
+                pending = result.pending,
+                "Uploaded queued crash reports"
+            ),
+            Err(e) => warn!(error = %e.message, "Crash report upload failed"),
+        }
+    });
+}
+
+async fn upload_pending(
+    crash: &CrashState,
+    sync: &SyncState,
+    config: &ConfigState,
+) -> Result<CrashUploadDto, ApiError> {
+    let pending = crash.pending();
+    if pending.is_empty() {
+        return Ok(CrashUploadDto {
+            uploaded: 0,
+            pending: 0,
+        });
+    }
+
+    let sync_config = sync
+        .get_config()
+        .ok_or_else(|| ApiError::internal("Sync is not configured"))?;
+    let mut uplink = connect_cloud(&sync_config, config)
+        .await
+        .map_err(cloud_error)?;
+    let client = reqwest::Client::builder()
+        .timeout(UPLOAD_TIMEOUT)
+        .build()
+        .map_err(|e| ApiError::internal(format!("Cannot create HTTP client: {}", e)))?;
+
+    let mut uploaded = 0;
+    for (path, report) in &pending {
+        let name = report.file_name();
+        let signed = match uplink
+            .create_signed_url(Kind::CrashReport, Access::Write, &name, 0)
+            .await
+        {
+            Ok(signed) => signed,
+            Err(e) => {
+                warn!(?e, name = %name, "No upload URL for crash report");
+                continue;
+            }
+        };
+        let body = match std::fs::read(path) {
+            Ok(body) => body,
+            Err(e) => {
+                warn!(?e, name = %name, "Cannot read crash report");
+                continue;
+            }
+        };
+        let sent = client
+            .put(&signed.url)
+            .header("content-type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        if let Err(e) = sent {
+            warn!(?e, name = %name, "Crash report upload failed");
+            continue;
+        }
+
+        if let Err(e) = crash.mark_sent(path) {
+            warn!(?e, name = %name, "Uploaded crash report could not be moved");
+        }
+        info!(name = %name, key = %signed.key, "Crash report uploaded");
+        uploaded += 1;
+    }
+    uplink.disconnect().await;
+
+    Ok(CrashUploadDto {
+        uploaded,
+        pending: pending.len() as u32 - uploaded,
+    })
+}
+
+/// Cuts frontend text to `MAX_FRONTEND_TEXT_LEN` characters.
+fn truncate(text: &str) -> String {
+    text.chars().take(MAX_FRONTEND_TEXT_LEN).collect()
+}
+
+fn cloud_error(e: titan_sync::SyncError) -> ApiError {
+    ApiError::new(
+        ErrorCode::CloudError,
+        format!("Crash report upload failed: {}", e),
+    )
+}
@@ -0,0 +1,135 @@
+//! # Cash Drawer Commands
+//!
+//! Opening the station's cash drawer (see `DrawerState` for the drivers)
+//! and the audit trail of manual opens.
+//!
+//! ## Commands
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                       Cash Drawer Commands                              │
+//! │                                                                         │
+//! │  open_cash_drawer(sale_id)        cash tender: open, no audit entry     │
+//! │  open_cash_drawer(reason)         manual "no sale": open + DrawerOpen   │
+//! │        │                          (recorded even when the open fails)   │
+//! │        ▼                                                                │
+//! │  DrawerState ──► dyn CashDrawer::open (printer kick / direct port)      │
+//! │                                                                         │
+//! │  get_drawer_opens(date)           the day's manual opens, for managers  │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{NaiveDate, Utc};
+use tauri::State;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::middleware::traced;
+use crate::state::{ConfigState, DbState, DrawerState};
+use titan_core::cash_drawer::drawer_reason;
+use titan_core::tax_report::report_window;
+use titan_core::{CoreError, DrawerOpen, ErrorCode};
+use titan_db::Database;
+
+/// Cashier ID recorded on opens (matches the sale commands).
+const USER_ID: &str = "default";
+
+/// Opens the station's cash drawer.
+///
+/// # Arguments
+/// * `reason` - Why staff opened it, for manual opens (max 200 characters)
+/// * `sale_id` - Sale the open pays out for; opens without one are manual
+///   and written to the audit trail, including failed ones
+///
+/// # Errors
+/// - `CONFIG_ERROR` if the station has no drawer, or an invalid setting
+/// - `NOT_FOUND` for an unknown sale
+/// - `VALIDATION_ERROR` for a reason that is too long
+/// - `UNAVAILABLE` if the drawer could not be reached
+#[tauri::command]
+pub async fn open_cash_drawer(
+    db: State<'_, DbState>,
+    drawer: State<'_, DrawerState>,
+    config: State<'_, ConfigState>,
+    reason: Option<String>,
+    sale_id: Option<String>,
+) -> Result<(), ApiError> {
+    traced("open_cash_drawer", async move {
+        debug!(sale_id = ?sale_id, "open_cash_drawer command");
+        let reason = drawer_reason(reason.as_deref()).map_err(CoreError::from)?;
+        let Some(device) = drawer.drawer() else {
+            let message = match drawer.problem() {
+                Some(problem) => format!("The cash drawer setting is invalid: {}", problem),
+                None => "No cash drawer is configured for this station".to_string(),
+            };
+            return Err(ApiError::new(ErrorCode::ConfigError, message));
+        };
+
+        let db_inner: &Database = (*db).inner()?;
+        if let Some(sale_id) = &sale_id {
+            db_inner
+                .sales()
+                .get_by_id(sale_id)
+                .await?
+                .ok_or_else(|| ApiError::not_found("Sale", sale_id))?;
+        }
+
+        let kind = device.connection().kind();
+        let result = tauri::async_runtime::spawn_blocking(move || device.open())
+            .await
+            .map_err(|e| ApiError::internal(format!("Cash drawer task failed: {}", e)))?;
+
+        if sale_id.is_none() {
+            let entry = DrawerOpen {
+                id: Uuid::new_v4().to_string(),
+                station_number: config.station.sale_station(),
+                drawer: kind.to_string(),
+                reason,
+                succeeded: result.is_ok(),
+                error: result.as_ref().err().map(|e| e.to_string()),
+                user_id: USER_ID.to_string(),
+                device_id: config.station.device_id(),
+                created_at: Utc::now(),
+            };
+            db_inner.cash_drawer_opens().record(&entry).await?;
+        }
+
+        match result {
+            Ok(()) => {
+                info!(
+                    drawer = kind,
+                    manual = sale_id.is_none(),
+                    "Cash drawer opened"
+                );
+                Ok(())
+            }
+            Err(e) => {
+                warn!(drawer = kind, error = %e, "Cash drawer did not open");
+                Err(e.into())
+            }
+        }
+    })
+    .await
+}
+
+/// Lists the manual drawer opens on one day (`YYYY-MM-DD`, UTC), oldest
+/// first.
+#[tauri::command]
+pub async fn get_drawer_opens(
+    db: State<'_, DbState>,
+    date: String,
+) -> Result<Vec<DrawerOpen>, ApiError> {
+    traced("get_drawer_opens", async move {
+        debug!(date = %date, "get_drawer_opens command");
+        let db_inner: &Database = (*db).reporting()?;
+
+        let day = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+            .map_err(|_| ApiError::validation("'date' must be YYYY-MM-DD"))?;
+        let (start, end) = report_window(day, day).map_err(CoreError::from)?;
+        Ok(db_inner
+            .cash_drawer_opens()
+            .list_between(start, end)
+            .await?)
+    })
+    .await
+}
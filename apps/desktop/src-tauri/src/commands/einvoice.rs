@@ -0,0 +1,520 @@
+//! # E-Invoice Commands
+//!
+//! Manage business customers, link sales to them, and export linked
+//! completed sales as UBL 2.1 invoice XML, one sale at a time or in a
+//! batch for a date range.
+//!
+//! ## E-Invoice Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         E-Invoice Flow                                  │
+//! │                                                                         │
+//! │  save_business_customer(name, taxId, address…) ──► customer             │
+//! │  delete_business_customer / restore_business_customer                   │
+//! │                                                                         │
+//! │  set_sale_invoice_customer(saleId, customerId)   (before or after       │
+//! │        │                                          finalize_sale)        │
+//! │        ▼                                                                │
+//! │  export_einvoice(saleId) ──► UBL XML for one sale (print / email)      │
+//! │                                                                         │
+//! │  export_einvoices(from, to, dir) ──► dir/<receipt number>.xml          │
+//! │        for every linked sale completed in the date range               │
+//! │                                                                         │
+//! │  Seller details (name, address, tax ID, country) come from config;     │
+//! │  sales without a linked customer are never exported.                    │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use std::path::PathBuf;
+
+use chrono::{Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{debug, info};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::error::{ApiError, ErrorCode};
+use crate::middleware::traced;
+use crate::state::{ConfigState, DbState};
+use titan_core::einvoice::{normalize_country_code, normalize_tax_id, render_ubl};
+use titan_core::quote::validate_customer_email;
+use titan_core::{
+    BusinessCustomer, CoreError, Invoice, InvoiceLine, InvoiceParty, Page, PageRequest, SaleStatus,
+    ValidationError,
+};
+use titan_db::Database;
+
+/// Default number of customers returned by a search.
+const DEFAULT_SEARCH_LIMIT: u32 = 20;
+
+/// A business customer as shown in the UI.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct BusinessCustomerDto {
+    pub id: String,
+    pub name: String,
+    pub tax_id: String,
+    pub address_line: Option<String>,
+    pub city: Option<String>,
+    pub postal_code: Option<String>,
+    pub country_code: String,
+    pub email: Option<String>,
+    /// Pass back as `expectedVersion` when saving an edit.
+    #[ts(type = "number")]
+    pub version: i64,
+}
+
+impl From<BusinessCustomer> for BusinessCustomerDto {
+    fn from(c: BusinessCustomer) -> Self {
+        BusinessCustomerDto {
+            id: c.id,
+            name: c.name,
+            tax_id: c.tax_id,
+            address_line: c.address_line,
+            city: c.city,
+            postal_code: c.postal_code,
+            country_code: c.country_code,
+            email: c.email,
+            version: c.sync_version,
+        }
+    }
+}
+
+/// A sale rendered as a UBL invoice.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct EInvoiceDto {
+    pub sale_id: String,
+    pub invoice_number: String,
+    /// Suggested file name, e.g. `260131-101500-0042.xml`.
+    pub file_name: String,
+    /// Customer email, if known (the frontend opens the mail client).
+    pub recipient: Option<String>,
+    pub xml: String,
+}
+
+/// Result of a batch export.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct EInvoiceBatchDto {
+    pub dir: String,
+    /// File names written, in completion order.
+    pub files: Vec<String>,
+}
+
+/// Creates a business customer, or updates one when `customer_id` is given.
+///
+/// For an update, `expected_version` is the `version` the form was loaded
+/// with; if the customer changed since (e.g. through sync), nothing is
+/// saved and a `CONFLICT` error is returned.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn save_business_customer(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    customer_id: Option<String>,
+    name: String,
+    tax_id: String,
+    address_line: Option<String>,
+    city: Option<String>,
+    postal_code: Option<String>,
+    country_code: String,
+    email: Option<String>,
+    expected_version: Option<i64>,
+) -> Result<BusinessCustomerDto, ApiError> {
+    traced("save_business_customer", async move {
+        debug!(customer_id = ?customer_id, tax_id = %tax_id, "save_business_customer command");
+
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(CoreError::from(ValidationError::Required {
+                field: "name".to_string(),
+            })
+            .into());
+        }
+        let tax_id = normalize_tax_id(&tax_id).map_err(CoreError::from)?;
+        let country_code = normalize_country_code(&country_code).map_err(CoreError::from)?;
+        let email = non_empty(email);
+        if let Some(ref email) = email {
+            validate_customer_email(email).map_err(CoreError::from)?;
+        }
+
+        let db_inner: &Database = (*db).inner()?;
+        let now = Utc::now();
+        let (id, created_at, deleted_at, sync_version) = match customer_id {
+            Some(id) => {
+                let existing = db_inner
+                    .business_customers()
+                    .get_by_id(&id)
+                    .await?
+                    .ok_or_else(|| ApiError::not_found("Business customer", &id))?;
+                (
+                    existing.id,
+                    existing.created_at,
+                    existing.deleted_at,
+                    expected_version.unwrap_or(existing.sync_version),
+                )
+            }
+            None => (Uuid::new_v4().to_string(), now, None, 0),
+        };
+
+        let mut customer = BusinessCustomer {
+            id,
+            tenant_id: config.tenant_id.clone(),
+            name,
+            tax_id,
+            address_line: non_empty(address_line),
+            city: non_empty(city),
+            postal_code: non_empty(postal_code),
+            country_code,
+            email,
+            created_at,
+            updated_at: now,
+            deleted_at,
+            sync_version,
+        };
+        customer.sync_version = db_inner.business_customers().save(&customer).await?;
+
+        info!(customer_id = %customer.id, "Business customer saved");
+
+        Ok(customer.into())
+    })
+    .await
+}
+
+/// Searches business customers by name or tax ID.
+#[tauri::command]
+pub async fn find_business_customers(
+    db: State<'_, DbState>,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<BusinessCustomerDto>, ApiError> {
+    traced("find_business_customers", async move {
+        debug!(query = %query, "find_business_customers command");
+
+        let db_inner: &Database = (*db).inner()?;
+        let customers = db_inner
+            .business_customers()
+            .search(query.trim(), limit.unwrap_or(DEFAULT_SEARCH_LIMIT))
+            .await?;
+
+        Ok(customers
+            .into_iter()
+            .map(BusinessCustomerDto::from)
+            .collect())
+    })
+    .await
+}
+
+/// Searches business customers like `find_business_customers`, one page
+/// at a time, by name.
+///
+/// ## Errors
+/// `VALIDATION_ERROR` for a cursor from another list.
+#[tauri::command]
+pub async fn find_business_customers_page(
+    db: State<'_, DbState>,
+    query: String,
+    cursor: Option<String>,
+    limit: Option<u32>,
+    include_total: Option<bool>,
+) -> Result<Page<BusinessCustomerDto>, ApiError> {
+    traced("find_business_customers_page", async move {
+        debug!(query = %query, "find_business_customers_page command");
+        let request = PageRequest {
+            limit,
+            cursor,
+            include_total: include_total.unwrap_or(false),
+        };
+
+        let db_inner: &Database = (*db).inner()?;
+        let page = db_inner
+            .business_customers()
+            .search_page(query.trim(), &request)
+            .await?;
+
+        Ok(page.map(BusinessCustomerDto::from))
+    })
+    .await
+}
+
+/// Deletes a business customer.
+///
+/// Sales already invoiced to the customer keep it; it no longer shows in
+/// search and cannot be linked to new sales. The delete syncs to the
+/// other terminals.
+///
+/// ## Errors
+/// `NOT_FOUND` if the customer doesn't exist or is already deleted.
+#[tauri::command]
+pub async fn delete_business_customer(
+    db: State<'_, DbState>,
+    customer_id: String,
+) -> Result<(), ApiError> {
+    traced("delete_business_customer", async move {
+        debug!(customer_id = %customer_id, "delete_business_customer command");
+        let db_inner: &Database = (*db).inner()?;
+        db_inner
+            .business_customers()
+            .soft_delete(&customer_id)
+            .await?;
+        info!(customer_id = %customer_id, "Business customer deleted");
+        Ok(())
+    })
+    .await
+}
+
+/// Restores a deleted business customer.
+///
+/// ## Errors
+/// `NOT_FOUND` if the customer doesn't exist or isn't deleted.
+#[tauri::command]
+pub async fn restore_business_customer(
+    db: State<'_, DbState>,
+    customer_id: String,
+) -> Result<BusinessCustomerDto, ApiError> {
+    traced("restore_business_customer", async move {
+        debug!(customer_id = %customer_id, "restore_business_customer command");
+        let db_inner: &Database = (*db).inner()?;
+        let customers = db_inner.business_customers();
+        customers.restore(&customer_id).await?;
+        let customer = customers
+            .get_by_id(&customer_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Business customer", &customer_id))?;
+        info!(customer_id = %customer_id, "Business customer restored");
+        Ok(customer.into())
+    })
+    .await
+}
+
+/// Links a sale to the business customer it is invoiced to, or unlinks it
+/// when `customer_id` is `None`.
+#[tauri::command]
+pub async fn set_sale_invoice_customer(
+    db: State<'_, DbState>,
+    sale_id: String,
+    customer_id: Option<String>,
+) -> Result<Option<BusinessCustomerDto>, ApiError> {
+    traced("set_sale_invoice_customer", async move {
+        debug!(sale_id = %sale_id, customer_id = ?customer_id, "set_sale_invoice_customer command");
+
+        let db_inner: &Database = (*db).inner()?;
+        let sale = db_inner
+            .sales()
+            .get_by_id(&sale_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Sale", &sale_id))?;
+        if sale.status == SaleStatus::Voided {
+            return Err(ApiError::new(
+                ErrorCode::BusinessLogic,
+                "Voided sales cannot be invoiced",
+            ));
+        }
+
+        let customer = match customer_id {
+            Some(ref id) => Some(
+                db_inner
+                    .business_customers()
+                    .get_by_id(id)
+                    .await?
+                    .filter(|c| c.deleted_at.is_none())
+                    .ok_or_else(|| ApiError::not_found("Business customer", id))?,
+            ),
+            None => None,
+        };
+
+        db_inner
+            .sales()
+            .set_invoice_customer(&sale_id, customer_id.as_deref())
+            .await?;
+
+        Ok(customer.map(BusinessCustomerDto::from))
+    })
+    .await
+}
+
+/// Renders one completed, linked sale as UBL invoice XML.
+#[tauri::command]
+pub async fn export_einvoice(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    sale_id: String,
+) -> Result<EInvoiceDto, ApiError> {
+    traced("export_einvoice", async move {
+        debug!(sale_id = %sale_id, "export_einvoice command");
+
+        let db_inner: &Database = (*db).inner()?;
+        let (invoice, customer) = build_invoice(db_inner, &config, &sale_id).await?;
+
+        Ok(EInvoiceDto {
+            sale_id,
+            file_name: file_name(&invoice),
+            recipient: customer.email,
+            xml: render_ubl(&invoice),
+            invoice_number: invoice.invoice_number,
+        })
+    })
+    .await
+}
+
+/// Writes a UBL invoice for every linked sale completed between `from` and
+/// `to` (inclusive, `YYYY-MM-DD`, UTC) into `dir`.
+#[tauri::command]
+pub async fn export_einvoices(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    from: String,
+    to: String,
+    dir: String,
+) -> Result<EInvoiceBatchDto, ApiError> {
+    traced("export_einvoices", async move {
+        debug!(from = %from, to = %to, dir = %dir, "export_einvoices command");
+
+        let from = parse_date(&from, "from")?;
+        let to = parse_date(&to, "to")?;
+        if to < from {
+            return Err(ApiError::validation("'to' must not be before 'from'"));
+        }
+
+        let start = from.and_time(chrono::NaiveTime::MIN).and_utc();
+        let end = to
+            .checked_add_days(Days::new(1))
+            .ok_or_else(|| ApiError::validation("'to' is out of range"))?
+            .and_time(chrono::NaiveTime::MIN)
+            .and_utc();
+
+        let db_inner: &Database = (*db).inner()?;
+        let sale_ids = db_inner.sales().list_invoiced(start, end).await?;
+
+        // Render everything first so a bad record does not leave a half export
+        let mut documents = Vec::with_capacity(sale_ids.len());
+        for sale_id in &sale_ids {
+            let (invoice, _) = build_invoice(db_inner, &config, sale_id).await?;
+            documents.push((file_name(&invoice), render_ubl(&invoice)));
+        }
+
+        let out_dir = PathBuf::from(&dir);
+        std::fs::create_dir_all(&out_dir).map_err(|e| {
+            ApiError::new(ErrorCode::Internal, format!("Cannot create {}: {}", dir, e))
+        })?;
+
+        let mut files = Vec::with_capacity(documents.len());
+        for (name, xml) in documents {
+            std::fs::write(out_dir.join(&name), xml).map_err(|e| {
+                ApiError::new(ErrorCode::Internal, format!("Cannot write {}: {}", name, e))
+            })?;
+            files.push(name);
+        }
+
+        info!(count = files.len(), dir = %dir, "E-invoices exported");
+
+        Ok(EInvoiceBatchDto { dir, files })
+    })
+    .await
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Builds the invoice for a completed sale linked to a business customer.
+async fn build_invoice(
+    db: &Database,
+    config: &ConfigState,
+    sale_id: &str,
+) -> Result<(Invoice, BusinessCustomer), ApiError> {
+    let sale = db
+        .sales()
+        .get_by_id(sale_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Sale", sale_id))?;
+    if sale.status != SaleStatus::Completed {
+        return Err(ApiError::new(
+            ErrorCode::BusinessLogic,
+            format!(
+                "Sale is {:?}, only completed sales can be invoiced",
+                sale.status
+            ),
+        ));
+    }
+
+    let customer_id = db
+        .sales()
+        .get_invoice_customer(sale_id)
+        .await?
+        .ok_or_else(|| {
+            ApiError::new(
+                ErrorCode::BusinessLogic,
+                format!(
+                    "Sale {} is not linked to a business customer",
+                    sale.receipt_number
+                ),
+            )
+        })?;
+    let customer = db
+        .business_customers()
+        .get_by_id(&customer_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Business customer", &customer_id))?;
+
+    let items = db.sales().get_items(sale_id).await?;
+    let mut lines = Vec::with_capacity(items.len());
+    for item in items {
+        // Rate frozen on the line; older lines fall back to the product's
+        let tax_rate_bps = match item.tax_rate_bps {
+            Some(bps) => bps,
+            None => match db.products().get_by_id(&item.product_id).await? {
+                Some(product) => product.tax_rate_bps,
+                None => config.default_tax_rate_bps,
+            },
+        };
+        lines.push(InvoiceLine {
+            name: item.name_snapshot,
+            quantity: item.quantity,
+            unit_price_cents: item.unit_price_cents,
+            net_cents: item.line_total_cents - item.discount_cents,
+            tax_cents: item.tax_cents,
+            tax_rate_bps,
+        });
+    }
+
+    let invoice = Invoice {
+        invoice_number: sale.receipt_number,
+        issue_date: sale.completed_at.unwrap_or(sale.created_at).date_naive(),
+        currency_code: config.currency_code.clone(),
+        currency_decimals: config.currency_decimals,
+        seller: InvoiceParty {
+            name: config.store_name.clone(),
+            tax_id: config.store_tax_id.clone(),
+            address_lines: config.store_address.clone(),
+            city: None,
+            postal_code: None,
+            country_code: config.country_code.clone(),
+        },
+        buyer: InvoiceParty::from(&customer),
+        lines,
+    };
+    invoice.validate().map_err(CoreError::from)?;
+
+    Ok((invoice, customer))
+}
+
+fn file_name(invoice: &Invoice) -> String {
+    format!("{}.xml", invoice.invoice_number)
+}
+
+fn parse_date(value: &str, field: &str) -> Result<NaiveDate, ApiError> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| ApiError::validation(format!("'{}' must be YYYY-MM-DD", field)))
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
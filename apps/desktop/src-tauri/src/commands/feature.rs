@@ -0,0 +1,188 @@
+//! # Feature Commands
+//!
+//! Feature flags set in the cloud back office, cached on the terminal (see
+//! `titan_core::feature`).
+//!
+//! ## Commands
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Feature Commands                                │
+//! │                                                                         │
+//! │  get_feature_flags      cached flags; refreshed from the cloud first   │
+//! │                         when stale (the cache answers if that fails)   │
+//! │  refresh_feature_flags  fetch now, e.g. right after a back-office edit │
+//! │  is_feature_enabled     one flag, from the cache only                  │
+//! │                                                                         │
+//! │  Commands of a gated subsystem call require_feature(db, LOYALTY) and   │
+//! │  fail with FEATURE_DISABLED while its flag is off.                     │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{info, warn};
+use ts_rs::TS;
+
+use crate::commands::sync::connect_cloud;
+use crate::error::{ApiError, ErrorCode};
+use crate::middleware::traced;
+use crate::state::{ConfigState, DbState, SyncState};
+use titan_core::feature::{feature_name, KNOWN_FEATURES};
+use titan_core::FeatureFlags;
+use titan_db::Database;
+
+/// One feature flag as shown in the UI.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureFlagDto {
+    pub key: String,
+    /// Name shown to staff.
+    pub name: String,
+    pub enabled: bool,
+}
+
+/// The terminal's feature flags.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureFlagsDto {
+    /// Flags this build knows, then any others the cloud sent.
+    pub flags: Vec<FeatureFlagDto>,
+    /// When the flags were fetched (`null`: never, every feature is off).
+    pub fetched_at: Option<String>,
+    pub expires_at: Option<String>,
+    /// The flags are past their TTL and the cloud could not be reached.
+    pub stale: bool,
+}
+
+impl From<&FeatureFlags> for FeatureFlagsDto {
+    fn from(flags: &FeatureFlags) -> Self {
+        let known = KNOWN_FEATURES.iter().map(|(key, _)| *key);
+        let others = flags
+            .flags
+            .keys()
+            .map(String::as_str)
+            .filter(|key| !KNOWN_FEATURES.iter().any(|(known, _)| known == key));
+
+        FeatureFlagsDto {
+            flags: known
+                .chain(others)
+                .map(|key| FeatureFlagDto {
+                    key: key.to_string(),
+                    name: feature_name(key).to_string(),
+                    enabled: flags.is_enabled(key),
+                })
+                .collect(),
+            fetched_at: flags.fetched_at.map(|t| t.to_rfc3339()),
+            expires_at: flags.expires_at().map(|t| t.to_rfc3339()),
+            stale: flags.is_stale(Utc::now()),
+        }
+    }
+}
+
+/// Gets the feature flags, refreshing them from the cloud first if they
+/// are past their TTL.
+///
+/// A failed refresh is logged and the cached flags are returned (`stale`
+/// is then true), so the terminal keeps working offline.
+#[tauri::command]
+pub async fn get_feature_flags(
+    db: State<'_, DbState>,
+    sync: State<'_, SyncState>,
+    config: State<'_, ConfigState>,
+) -> Result<FeatureFlagsDto, ApiError> {
+    traced("get_feature_flags", async move {
+        let db_inner: &Database = (*db).inner()?;
+        let cached = db_inner.feature_flags().load().await?;
+        if !cached.is_stale(Utc::now()) {
+            return Ok(FeatureFlagsDto::from(&cached));
+        }
+        let Some(sync_config) = sync.get_config() else {
+            return Ok(FeatureFlagsDto::from(&cached));
+        };
+
+        match fetch_flags(&sync_config, &config).await {
+            Ok(fresh) => {
+                db_inner.feature_flags().save(&fresh).await?;
+                Ok(FeatureFlagsDto::from(&fresh))
+            }
+            Err(e) => {
+                warn!(error = %e, "Feature flag refresh failed; using cached flags");
+                Ok(FeatureFlagsDto::from(&cached))
+            }
+        }
+    })
+    .await
+}
+
+/// Fetches the feature flags from the cloud now.
+///
+/// # Errors
+/// `CLOUD_ERROR` if the cloud could not be reached (the cache is kept).
+#[tauri::command]
+pub async fn refresh_feature_flags(
+    db: State<'_, DbState>,
+    sync: State<'_, SyncState>,
+    config: State<'_, ConfigState>,
+) -> Result<FeatureFlagsDto, ApiError> {
+    traced("refresh_feature_flags", async move {
+        let sync_config = sync
+            .get_config()
+            .ok_or_else(|| ApiError::internal("Sync is not configured"))?;
+        let fresh = fetch_flags(&sync_config, &config).await.map_err(|e| {
+            ApiError::new(
+                ErrorCode::CloudError,
+                format!("Feature flag refresh failed: {}", e),
+            )
+        })?;
+
+        let db_inner: &Database = (*db).inner()?;
+        db_inner.feature_flags().save(&fresh).await?;
+        Ok(FeatureFlagsDto::from(&fresh))
+    })
+    .await
+}
+
+/// Whether a feature is on, from the cached flags.
+///
+/// # Arguments
+/// * `key` - Flag key, e.g. "loyalty"
+#[tauri::command]
+pub async fn is_feature_enabled(db: State<'_, DbState>, key: String) -> Result<bool, ApiError> {
+    traced("is_feature_enabled", async move {
+        let db_inner: &Database = (*db).inner()?;
+        Ok(db_inner.feature_flags().load().await?.is_enabled(&key))
+    })
+    .await
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Fails with `FEATURE_DISABLED` unless the cached flags have `key` on.
+///
+/// For commands of a subsystem that is rolled out behind a flag.
+pub async fn require_feature(db: &Database, key: &str) -> Result<(), ApiError> {
+    db.feature_flags().load().await?.require(key)?;
+    Ok(())
+}
+
+async fn fetch_flags(
+    sync_config: &titan_sync::SyncConfig,
+    config: &ConfigState,
+) -> titan_sync::SyncResult<FeatureFlags> {
+    let mut uplink = connect_cloud(sync_config, config).await?;
+    let flags = uplink.get_feature_flags().await;
+    uplink.disconnect().await;
+
+    let flags = flags?;
+    info!(
+        enabled = flags.flags.values().filter(|on| **on).count(),
+        ttl_secs = flags.ttl_secs,
+        "Feature flags refreshed"
+    );
+    Ok(flags)
+}
@@ -0,0 +1,163 @@
+//! # Fiscal Commands
+//!
+//! Signing of sales by the configured fiscal backend, and lookup of the
+//! stored signature for receipt reprints.
+//!
+//! ## At Finalization
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                    Fiscal Signing in finalize_sale                      │
+//! │                                                                         │
+//! │  tracking + age checks pass                                             │
+//! │        │                                                                │
+//! │        ▼                                                                │
+//! │  sign_sale ── already signed? ──► reuse stored signature (retry)        │
+//! │        │                                                                │
+//! │        ▼                                                                │
+//! │  chain = after(last signature of this backend)                          │
+//! │  adapter.sign(receipt, chain) ──► FISCAL_ERROR: sale stays a draft      │
+//! │        │                                                                │
+//! │        ▼                                                                │
+//! │  store signature + payload ──► stock, complete, outbox                  │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{debug, info};
+use ts_rs::TS;
+
+use crate::error::ApiError;
+use crate::middleware::traced;
+use crate::state::{DbState, FiscalState};
+use titan_core::fiscal::canonical_payload;
+use titan_core::{
+    FiscalChain, FiscalLine, FiscalPayment, FiscalReceipt, FiscalSignature, PaymentMethod, Sale,
+    SaleItem,
+};
+use titan_db::Database;
+
+/// Fiscal signature as returned to the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct FiscalSignatureDto {
+    pub sale_id: String,
+    pub backend: String,
+    #[ts(type = "number")]
+    pub sequence: i64,
+    pub signature: String,
+    pub previous_signature: Option<String>,
+    pub signed_at: String,
+}
+
+impl From<FiscalSignature> for FiscalSignatureDto {
+    fn from(s: FiscalSignature) -> Self {
+        FiscalSignatureDto {
+            sale_id: s.sale_id,
+            backend: s.backend,
+            sequence: s.sequence,
+            signature: s.signature,
+            previous_signature: s.previous_signature,
+            signed_at: s.signed_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Gets the fiscal signature stored on a sale, if it was signed.
+#[tauri::command]
+pub async fn get_fiscal_signature(
+    db: State<'_, DbState>,
+    sale_id: String,
+) -> Result<Option<FiscalSignatureDto>, ApiError> {
+    traced("get_fiscal_signature", async move {
+        debug!(sale_id = %sale_id, "get_fiscal_signature command");
+
+        let db_inner: &Database = (*db).inner()?;
+        let signature = db_inner.sales().get_fiscal_signature(&sale_id).await?;
+
+        Ok(signature.map(FiscalSignatureDto::from))
+    })
+    .await
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Signs a sale with the configured backend and stores the signature.
+///
+/// Idempotent: a sale that was signed by an earlier, failed finalize
+/// attempt keeps its signature and sequence number.
+///
+/// ## Returns
+/// The stored signature, or `None` if the backend does not sign.
+pub(crate) async fn sign_sale(
+    db: &Database,
+    fiscal: &FiscalState,
+    sale: &Sale,
+    items: &[SaleItem],
+) -> Result<Option<FiscalSignature>, ApiError> {
+    if let Some(existing) = db.sales().get_fiscal_signature(&sale.id).await? {
+        return Ok(Some(existing));
+    }
+
+    let adapter = fiscal.adapter();
+    let payments = db.sales().get_payments(&sale.id).await?;
+    let receipt = FiscalReceipt {
+        sale_id: sale.id.clone(),
+        receipt_number: sale.receipt_number.clone(),
+        tenant_id: sale.tenant_id.clone(),
+        device_id: sale.device_id.clone(),
+        subtotal_cents: sale.subtotal_cents,
+        tax_cents: sale.tax_cents,
+        total_cents: sale.total_cents,
+        lines: items
+            .iter()
+            .map(|i| FiscalLine {
+                name: i.name_snapshot.clone(),
+                quantity: i.quantity,
+                unit_price_cents: i.unit_price_cents,
+                line_total_cents: i.line_total_cents,
+                tax_cents: i.tax_cents,
+            })
+            .collect(),
+        payments: payments
+            .iter()
+            .map(|p| FiscalPayment {
+                method: payment_method_name(p.method).to_string(),
+                amount_cents: p.amount_cents,
+            })
+            .collect(),
+        issued_at: chrono::Utc::now(),
+    };
+
+    let last = db
+        .sales()
+        .get_last_fiscal_signature(adapter.backend())
+        .await?;
+    let chain = FiscalChain::after(last.as_ref());
+
+    let Some(signature) = adapter.sign(&receipt, &chain)? else {
+        return Ok(None);
+    };
+
+    let payload = canonical_payload(&receipt, &chain);
+    db.sales()
+        .add_fiscal_signature(&signature, &payload)
+        .await?;
+
+    info!(sale_id = %sale.id, backend = %signature.backend, sequence = signature.sequence, "Sale fiscally signed");
+
+    Ok(Some(signature))
+}
+
+/// Tender name as printed on fiscal records.
+fn payment_method_name(method: PaymentMethod) -> &'static str {
+    match method {
+        PaymentMethod::Cash => "cash",
+        PaymentMethod::ExternalCard => "external_card",
+        PaymentMethod::StoreCredit => "store_credit",
+        PaymentMethod::LoyaltyReward => "loyalty_reward",
+    }
+}
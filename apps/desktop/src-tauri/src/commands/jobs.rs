@@ -0,0 +1,137 @@
+//! # Job Commands
+//!
+//! Background job schedules, run history and manual runs for the
+//! maintenance screen.
+//!
+//! ## Commands
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                          Job Commands                                   │
+//! │                                                                         │
+//! │  list_jobs            every job with schedule, next run, last status    │
+//! │  list_job_runs        run history, newest first (optionally one job)    │
+//! │  update_job_schedule  change the cron schedule / enable or disable      │
+//! │  run_job_now          run a job immediately, outside its schedule       │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::Utc;
+use tauri::{AppHandle, State};
+use tracing::info;
+
+use crate::error::{ApiError, ErrorCode};
+use crate::middleware::traced;
+use crate::state::{local_offset, DbState, SchedulerState};
+use titan_core::{CoreError, CronSchedule, JobRun, ScheduledJob};
+use titan_db::Database;
+
+/// Default number of runs returned by `list_job_runs`.
+const DEFAULT_RUN_LIMIT: u32 = 50;
+
+/// Lists every registered background job.
+#[tauri::command]
+pub async fn list_jobs(db: State<'_, DbState>) -> Result<Vec<ScheduledJob>, ApiError> {
+    traced("list_jobs", async move {
+        let db_inner: &Database = (*db).inner()?;
+        Ok(db_inner.jobs().list().await?)
+    })
+    .await
+}
+
+/// Lists recent job runs, newest first.
+///
+/// # Arguments
+/// * `job_name` - Only runs of this job (default: all jobs)
+/// * `limit` - Maximum runs returned (default 50, at most 500)
+#[tauri::command]
+pub async fn list_job_runs(
+    db: State<'_, DbState>,
+    job_name: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<JobRun>, ApiError> {
+    traced("list_job_runs", async move {
+        let limit = limit.unwrap_or(DEFAULT_RUN_LIMIT).clamp(1, 500);
+        let db_inner: &Database = (*db).inner()?;
+        Ok(db_inner
+            .jobs()
+            .recent_runs(job_name.as_deref(), limit)
+            .await?)
+    })
+    .await
+}
+
+/// Changes a job's schedule and whether it runs.
+///
+/// # Arguments
+/// * `name` - Job name
+/// * `schedule` - Cron expression in local time, e.g. `"0 3 * * *"`
+/// * `enabled` - Whether the scheduler runs the job
+///
+/// # Returns
+/// The updated job, with its next run time.
+#[tauri::command]
+pub async fn update_job_schedule(
+    db: State<'_, DbState>,
+    name: String,
+    schedule: String,
+    enabled: bool,
+) -> Result<ScheduledJob, ApiError> {
+    traced("update_job_schedule", async move {
+        let schedule = schedule.trim();
+        let parsed: CronSchedule = schedule.parse().map_err(CoreError::from)?;
+        let next_run_at = if enabled {
+            let next = parsed.next_after(Utc::now(), local_offset());
+            if next.is_none() {
+                return Err(ApiError::validation(format!(
+                    "Schedule '{}' never runs",
+                    schedule
+                )));
+            }
+            next
+        } else {
+            None
+        };
+
+        let db_inner: &Database = (*db).inner()?;
+        let jobs = db_inner.jobs();
+        jobs.update_schedule(&name, schedule, enabled, next_run_at)
+            .await?;
+        info!(job = %name, schedule, enabled, "Job schedule updated");
+
+        jobs.get(&name)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Job", &name))
+    })
+    .await
+}
+
+/// Runs a job now, outside its schedule.
+///
+/// The run happens in the background; its result shows up in
+/// `list_job_runs`.
+///
+/// # Errors
+/// `NOT_FOUND` for an unknown job, `BUSY` if the job is already running.
+#[tauri::command]
+pub async fn run_job_now(
+    app: AppHandle,
+    db: State<'_, DbState>,
+    scheduler: State<'_, SchedulerState>,
+    name: String,
+) -> Result<(), ApiError> {
+    traced("run_job_now", async move {
+        if !scheduler.has_job(&name) {
+            return Err(ApiError::not_found("Job", &name));
+        }
+        let db_inner: &Database = (*db).inner()?;
+        if !scheduler.run_now(app, db_inner.clone(), &name) {
+            return Err(ApiError::new(
+                ErrorCode::Busy,
+                format!("Job {} is already running", name),
+            ));
+        }
+        info!(job = %name, "Job started manually");
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,382 @@
+//! # Label Commands
+//!
+//! The shelf label queue and the label template designer.
+//!
+//! ## Commands
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Label Commands                                  │
+//! │                                                                         │
+//! │  list_label_queue        pending labels with current product data       │
+//! │  queue_labels            queue labels by hand (new stock, torn label)   │
+//! │  print_labels            render pending labels, mark them printed       │
+//! │                                                                         │
+//! │  list_label_templates    saved templates, default first                 │
+//! │  save_label_template     create / edit a template (validated)           │
+//! │  preview_label_template  render a draft with sample data                │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Price changes arriving through sync are queued automatically by the
+//! sync agent. `print_labels` returns the printer job; the frontend sends
+//! it to the label printer as raw data.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{debug, info, warn};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::middleware::traced;
+use crate::state::DbState;
+use titan_core::label::{render_label, validate_template, MAX_LABEL_COPIES};
+use titan_core::{
+    CoreError, LabelData, LabelFormat, LabelReason, LabelTemplate, Product, QueuedLabel,
+};
+use titan_db::Database;
+
+/// Default number of labels returned by `list_label_queue`.
+const DEFAULT_QUEUE_LIMIT: u32 = 200;
+
+/// Most labels one `print_labels` call renders.
+const MAX_PRINT_BATCH: u32 = 1000;
+
+/// Name reported for the built-in template.
+const BUILT_IN_TEMPLATE: &str = "Built-in";
+
+/// A queued label as shown in the UI.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedLabelDto {
+    pub id: String,
+    pub product_id: String,
+    pub sku: String,
+    pub name: String,
+    pub reason: LabelReason,
+    /// Price before the change, for "was" labels.
+    #[ts(type = "number | null")]
+    pub old_price_cents: Option<i64>,
+    /// Current price (what the label will show).
+    #[ts(type = "number")]
+    pub price_cents: i64,
+    #[ts(type = "number")]
+    pub copies: i64,
+    pub queued_at: String,
+}
+
+impl QueuedLabelDto {
+    fn new(label: QueuedLabel, product: &Product) -> Self {
+        QueuedLabelDto {
+            id: label.id,
+            product_id: label.product_id,
+            sku: product.sku.clone(),
+            name: product.name.clone(),
+            reason: label.reason,
+            old_price_cents: label.old_price_cents,
+            price_cents: product.price_cents,
+            copies: label.copies,
+            queued_at: label.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// A label template as shown in the designer.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelTemplateDto {
+    pub id: String,
+    pub name: String,
+    pub format: LabelFormat,
+    pub body: String,
+    pub is_default: bool,
+    pub updated_at: String,
+}
+
+impl From<LabelTemplate> for LabelTemplateDto {
+    fn from(t: LabelTemplate) -> Self {
+        LabelTemplateDto {
+            id: t.id,
+            name: t.name,
+            format: t.format,
+            body: t.body,
+            is_default: t.is_default,
+            updated_at: t.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Rendered labels, ready to send to the printer.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelPrintJobDto {
+    pub format: LabelFormat,
+    pub template_name: String,
+    /// Queue entries marked printed by this job.
+    pub label_ids: Vec<String>,
+    /// Labels the printer will produce (copies included).
+    #[ts(type = "number")]
+    pub label_count: i64,
+    /// Printer commands for every label, in queue order.
+    pub document: String,
+}
+
+/// Lists pending labels, oldest first.
+///
+/// # Arguments
+/// * `limit` - Maximum labels returned (default 200, at most 1000)
+#[tauri::command]
+pub async fn list_label_queue(
+    db: State<'_, DbState>,
+    limit: Option<u32>,
+) -> Result<Vec<QueuedLabelDto>, ApiError> {
+    traced("list_label_queue", async move {
+        let limit = limit
+            .unwrap_or(DEFAULT_QUEUE_LIMIT)
+            .clamp(1, MAX_PRINT_BATCH);
+        let db_inner: &Database = (*db).inner()?;
+
+        let mut labels = Vec::new();
+        for label in db_inner.labels().list_pending(limit).await? {
+            if let Some(product) = db_inner.products().get_by_id(&label.product_id).await? {
+                labels.push(QueuedLabelDto::new(label, &product));
+            }
+        }
+        Ok(labels)
+    })
+    .await
+}
+
+/// Queues labels for products by hand.
+///
+/// # Arguments
+/// * `product_ids` - Products to label
+/// * `copies` - Labels per product (default 1, at most 100); added to a
+///   product's pending labels
+///
+/// # Errors
+/// `NOT_FOUND` for an unknown or deleted product (nothing is queued).
+#[tauri::command]
+pub async fn queue_labels(
+    db: State<'_, DbState>,
+    product_ids: Vec<String>,
+    copies: Option<i64>,
+) -> Result<Vec<QueuedLabelDto>, ApiError> {
+    traced("queue_labels", async move {
+        let copies = copies.unwrap_or(1);
+        if !(1..=MAX_LABEL_COPIES).contains(&copies) {
+            return Err(ApiError::validation(format!(
+                "Copies must be 1 to {}",
+                MAX_LABEL_COPIES
+            )));
+        }
+        let db_inner: &Database = (*db).inner()?;
+
+        let mut products = Vec::with_capacity(product_ids.len());
+        for id in &product_ids {
+            match db_inner.products().get_by_id(id).await? {
+                Some(product) if product.deleted_at.is_none() => products.push(product),
+                _ => return Err(ApiError::not_found("Product", id)),
+            }
+        }
+
+        let mut queued = Vec::with_capacity(products.len());
+        for product in products {
+            let label = db_inner
+                .labels()
+                .enqueue(
+                    &product.id,
+                    LabelReason::Manual,
+                    None,
+                    product.price_cents,
+                    copies,
+                )
+                .await?;
+            queued.push(QueuedLabelDto::new(label, &product));
+        }
+
+        info!(products = queued.len(), copies, "Labels queued");
+        Ok(queued)
+    })
+    .await
+}
+
+/// Renders pending labels and marks them printed.
+///
+/// Labels show the product's price now, not when they were queued. Labels
+/// of products deleted since are dropped from the queue unprinted. If the
+/// printer fails, queue the products again with `queue_labels`.
+///
+/// # Arguments
+/// * `label_ids` - Queue entries to print (default: the whole queue)
+/// * `template_id` - Template to use (default: the default template, else
+///   the built-in ZPL one)
+///
+/// # Errors
+/// `VALIDATION_ERROR` if nothing is queued, `NOT_FOUND` for an unknown
+/// template.
+#[tauri::command]
+pub async fn print_labels(
+    db: State<'_, DbState>,
+    label_ids: Option<Vec<String>>,
+    template_id: Option<String>,
+) -> Result<LabelPrintJobDto, ApiError> {
+    traced("print_labels", async move {
+        let db_inner: &Database = (*db).inner()?;
+        let labels_repo = db_inner.labels();
+
+        let (format, template_name, body) = match template_id {
+            Some(id) => {
+                let t = labels_repo
+                    .get_template(&id)
+                    .await?
+                    .ok_or_else(|| ApiError::not_found("LabelTemplate", &id))?;
+                (t.format, t.name, t.body)
+            }
+            None => match labels_repo.default_template().await? {
+                Some(t) => (t.format, t.name, t.body),
+                None => (
+                    LabelFormat::Zpl,
+                    BUILT_IN_TEMPLATE.to_string(),
+                    LabelFormat::Zpl.default_template().to_string(),
+                ),
+            },
+        };
+
+        let mut pending = labels_repo.list_pending(MAX_PRINT_BATCH).await?;
+        if let Some(ids) = &label_ids {
+            pending.retain(|l| ids.contains(&l.id));
+        }
+        if pending.is_empty() {
+            return Err(ApiError::validation("No labels queued"));
+        }
+
+        let mut document = String::new();
+        let mut label_count = 0;
+        for label in &pending {
+            let product = match db_inner.products().get_by_id(&label.product_id).await? {
+                Some(product) if product.deleted_at.is_none() => product,
+                _ => {
+                    warn!(
+                        label_id = %label.id,
+                        product_id = %label.product_id,
+                        "Dropping label of deleted product"
+                    );
+                    continue;
+                }
+            };
+            let data = LabelData {
+                name: product.name,
+                sku: product.sku,
+                barcode: product.barcode,
+                price_cents: product.price_cents,
+                was_price_cents: label.old_price_cents,
+                copies: label.copies,
+            };
+            document.push_str(&render_label(format, &body, &data).map_err(CoreError::from)?);
+            label_count += label.copies;
+        }
+
+        let ids: Vec<String> = pending.into_iter().map(|l| l.id).collect();
+        labels_repo.mark_printed(&ids).await?;
+        info!(labels = label_count, template = %template_name, "Labels printed");
+
+        Ok(LabelPrintJobDto {
+            format,
+            template_name,
+            label_ids: ids,
+            label_count,
+            document,
+        })
+    })
+    .await
+}
+
+/// Lists saved label templates, the default first.
+#[tauri::command]
+pub async fn list_label_templates(
+    db: State<'_, DbState>,
+) -> Result<Vec<LabelTemplateDto>, ApiError> {
+    traced("list_label_templates", async move {
+        let db_inner: &Database = (*db).inner()?;
+        let templates = db_inner.labels().list_templates().await?;
+        Ok(templates.into_iter().map(LabelTemplateDto::from).collect())
+    })
+    .await
+}
+
+/// Creates or edits a label template.
+///
+/// # Arguments
+/// * `id` - Template to edit (default: a new template)
+/// * `name`, `format`, `body` - See `titan_core::label` for placeholders
+/// * `is_default` - Make this the template `print_labels` uses by default
+///
+/// # Errors
+/// `VALIDATION_ERROR` if the body does not render or is not a complete
+/// label; `NOT_FOUND` for an unknown `id`.
+#[tauri::command]
+pub async fn save_label_template(
+    db: State<'_, DbState>,
+    id: Option<String>,
+    name: String,
+    format: LabelFormat,
+    body: String,
+    is_default: bool,
+) -> Result<LabelTemplateDto, ApiError> {
+    traced("save_label_template", async move {
+        debug!(id = ?id, name = %name, ?format, "save_label_template command");
+        validate_template(&name, format, &body).map_err(CoreError::from)?;
+
+        let db_inner: &Database = (*db).inner()?;
+        let labels_repo = db_inner.labels();
+        let now = Utc::now();
+
+        let created_at = match &id {
+            Some(id) => {
+                labels_repo
+                    .get_template(id)
+                    .await?
+                    .ok_or_else(|| ApiError::not_found("LabelTemplate", id))?
+                    .created_at
+            }
+            None => now,
+        };
+        let template = LabelTemplate {
+            id: id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+            name: name.trim().to_string(),
+            format,
+            body,
+            is_default,
+            created_at,
+            updated_at: now,
+        };
+        labels_repo.save_template(&template).await?;
+
+        info!(
+            template_id = %template.id,
+            name = %template.name,
+            is_default,
+            "Label template saved"
+        );
+        Ok(LabelTemplateDto::from(template))
+    })
+    .await
+}
+
+/// Renders a draft template with sample product data, for the designer's
+/// preview.
+///
+/// # Errors
+/// `VALIDATION_ERROR` for an unknown or unclosed placeholder.
+#[tauri::command]
+pub async fn preview_label_template(format: LabelFormat, body: String) -> Result<String, ApiError> {
+    traced("preview_label_template", async move {
+        Ok(render_label(format, &body, &LabelData::sample()).map_err(CoreError::from)?)
+    })
+    .await
+}
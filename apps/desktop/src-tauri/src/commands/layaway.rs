@@ -0,0 +1,550 @@
+//! # Layaway Commands
+//!
+//! Put the current cart on layaway against a deposit, take further payments,
+//! and close it out at pickup or on cancellation.
+//!
+//! ## Layaway Workflow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Layaway Workflow                                 │
+//! │                                                                         │
+//! │  Build cart ──► create_layaway(customer, deposit) ──► stock reserved   │
+//! │                    │                                                    │
+//! │                    │ (queued as LAYAWAY → other terminals)             │
+//! │                    ▼                                                    │
+//! │  add_layaway_payment ... (until balance due = 0)                       │
+//! │                    │                                                    │
+//! │          ┌─────────┴──────────┐                                         │
+//! │          ▼                    ▼                                         │
+//! │  complete_layaway       cancel_layaway                                  │
+//! │  (pickup → sale)        (stock released, refund = paid - fee)           │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{debug, info};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::commands::sale::{generate_receipt_number, CreateSaleResponse};
+use crate::error::{ApiError, ErrorCode};
+use crate::middleware::traced;
+use crate::state::{training_mode, CartState, ConfigState, DbState};
+use titan_core::layaway::validate_layaway_payment;
+use titan_core::{
+    CoreError, Layaway, LayawayDocument, LayawayItem, LayawayPayment, LayawayPolicy,
+    LayawayStatus, PaymentMethod, Sale, SaleItem, SaleStatus,
+};
+use titan_db::Database;
+
+/// Cashier ID used for layaways (matches the sale commands).
+const USER_ID: &str = "default";
+
+/// Default page size for `list_layaways`.
+const DEFAULT_LIST_LIMIT: u32 = 50;
+
+/// A layaway line as shown in the UI.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct LayawayItemDto {
+    pub product_id: String,
+    pub sku: String,
+    pub name: String,
+    #[ts(type = "number")]
+    pub quantity: i64,
+    #[ts(type = "number")]
+    pub unit_price_cents: i64,
+    #[ts(type = "number")]
+    pub line_total_cents: i64,
+}
+
+impl From<LayawayItem> for LayawayItemDto {
+    fn from(i: LayawayItem) -> Self {
+        LayawayItemDto {
+            product_id: i.product_id,
+            sku: i.sku_snapshot,
+            name: i.name_snapshot,
+            quantity: i.quantity,
+            unit_price_cents: i.unit_price_cents,
+            line_total_cents: i.line_total_cents,
+        }
+    }
+}
+
+/// A layaway payment (negative for a refund) as shown in the UI.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct LayawayPaymentDto {
+    pub method: String,
+    #[ts(type = "number")]
+    pub amount_cents: i64,
+    pub created_at: String,
+}
+
+impl From<LayawayPayment> for LayawayPaymentDto {
+    fn from(p: LayawayPayment) -> Self {
+        LayawayPaymentDto {
+            method: format!("{:?}", p.method),
+            amount_cents: p.amount_cents,
+            created_at: p.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// A layaway as shown in the UI.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct LayawayDto {
+    pub id: String,
+    pub layaway_number: String,
+    pub status: String,
+    pub customer_name: String,
+    pub customer_phone: Option<String>,
+    #[ts(type = "number")]
+    pub total_cents: i64,
+    #[ts(type = "number")]
+    pub paid_cents: i64,
+    #[ts(type = "number")]
+    pub balance_due_cents: i64,
+    #[ts(type = "number")]
+    pub restocking_fee_cents: i64,
+    pub sale_id: Option<String>,
+    pub notes: Option<String>,
+    pub created_at: String,
+    /// Empty in list views.
+    pub items: Vec<LayawayItemDto>,
+    /// Empty in list views.
+    pub payments: Vec<LayawayPaymentDto>,
+}
+
+impl LayawayDto {
+    fn new(layaway: Layaway, items: Vec<LayawayItem>, payments: Vec<LayawayPayment>) -> Self {
+        LayawayDto {
+            balance_due_cents: layaway.balance_due_cents(),
+            status: format!("{:?}", layaway.status).to_lowercase(),
+            id: layaway.id,
+            layaway_number: layaway.layaway_number,
+            customer_name: layaway.customer_name,
+            customer_phone: layaway.customer_phone,
+            total_cents: layaway.total_cents,
+            paid_cents: layaway.paid_cents,
+            restocking_fee_cents: layaway.restocking_fee_cents,
+            sale_id: layaway.sale_id,
+            notes: layaway.notes,
+            created_at: layaway.created_at.to_rfc3339(),
+            items: items.into_iter().map(LayawayItemDto::from).collect(),
+            payments: payments.into_iter().map(LayawayPaymentDto::from).collect(),
+        }
+    }
+
+    fn from_document(doc: LayawayDocument) -> Self {
+        LayawayDto::new(doc.layaway, doc.items, doc.payments)
+    }
+}
+
+/// Puts the current cart on layaway against a deposit and clears the cart.
+///
+/// The deposit must meet the store's minimum (see `ConfigState::layaway`).
+/// Stock for every tracked item is reserved immediately.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)] // One argument per invoke parameter
+pub async fn create_layaway(
+    db: State<'_, DbState>,
+    cart: State<'_, CartState>,
+    config: State<'_, ConfigState>,
+    customer_name: String,
+    customer_phone: Option<String>,
+    deposit_cents: i64,
+    method: String,
+    notes: Option<String>,
+) -> Result<LayawayDto, ApiError> {
+    traced("create_layaway", async move {
+        debug!(deposit = %deposit_cents, method = %method, "create_layaway command");
+
+        let items = cart.with_cart(|c| c.items.clone());
+        if items.is_empty() {
+            return Err(ApiError::validation("Cart is empty"));
+        }
+
+        let customer_name = customer_name.trim().to_string();
+        if customer_name.is_empty() {
+            return Err(ApiError::validation("Customer name is required for a layaway"));
+        }
+
+        let now = Utc::now();
+        let layaway_id = Uuid::new_v4().to_string();
+        let device_id = config.station.device_id();
+
+        let layaway_items: Vec<LayawayItem> = items
+            .iter()
+            .map(|i| LayawayItem {
+                id: Uuid::new_v4().to_string(),
+                layaway_id: layaway_id.clone(),
+                product_id: i.sale_product_id(),
+                sku_snapshot: i.sku.clone(),
+                name_snapshot: i.name.clone(),
+                unit_price_cents: i.unit_price_cents,
+                quantity: i.quantity,
+                line_total_cents: i.line_total_cents(),
+                tax_cents: i.tax_cents(),
+                created_at: now,
+            })
+            .collect();
+
+        let subtotal: i64 = layaway_items.iter().map(|i| i.line_total_cents).sum();
+        let tax: i64 = layaway_items.iter().map(|i| i.tax_cents).sum();
+        let total = subtotal + tax;
+
+        config
+            .layaway
+            .validate_deposit(total, deposit_cents)
+            .map_err(CoreError::from)?;
+
+        let doc = LayawayDocument {
+            layaway: Layaway {
+                id: layaway_id.clone(),
+                tenant_id: config.tenant_id.clone(),
+                layaway_number: generate_layaway_number(),
+                status: LayawayStatus::Active,
+                customer_name,
+                customer_phone: customer_phone
+                    .map(|p| p.trim().to_string())
+                    .filter(|p| !p.is_empty()),
+                subtotal_cents: subtotal,
+                tax_cents: tax,
+                total_cents: total,
+                paid_cents: deposit_cents,
+                restocking_fee_cents: 0,
+                sale_id: None,
+                user_id: USER_ID.to_string(),
+                device_id: device_id.clone(),
+                notes,
+                created_at: now,
+                updated_at: now,
+                closed_at: None,
+                sync_version: 1,
+            },
+            items: layaway_items,
+            payments: vec![new_payment(
+                &layaway_id,
+                parse_method(&method),
+                deposit_cents,
+                &device_id,
+            )],
+        };
+
+        let db_inner: &Database = (*db).inner()?;
+        db_inner.layaways().create(&doc).await?;
+        queue_layaway(db_inner, &doc).await?;
+
+        cart.with_cart_mut(|c| c.clear());
+
+        info!(
+            layaway_id = %layaway_id,
+            layaway_number = %doc.layaway.layaway_number,
+            total = total,
+            deposit = deposit_cents,
+            "Layaway created"
+        );
+
+        Ok(LayawayDto::from_document(doc))
+    })
+    .await
+}
+
+/// Records a further payment against an active layaway.
+#[tauri::command]
+pub async fn add_layaway_payment(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    layaway_id: String,
+    amount_cents: i64,
+    method: String,
+) -> Result<LayawayDto, ApiError> {
+    traced("add_layaway_payment", async move {
+        debug!(layaway_id = %layaway_id, amount = %amount_cents, method = %method, "add_layaway_payment command");
+
+        let db_inner: &Database = (*db).inner()?;
+        let layaway = load_document(db_inner, &layaway_id).await?.layaway;
+
+        layaway.ensure_active()?;
+        validate_layaway_payment(amount_cents, layaway.balance_due_cents()).map_err(CoreError::from)?;
+
+        let device_id = config.station.device_id();
+        let payment = new_payment(&layaway.id, parse_method(&method), amount_cents, &device_id);
+        db_inner.layaways().add_payment(&payment).await?;
+
+        let doc = load_document(db_inner, &layaway.id).await?;
+        queue_layaway(db_inner, &doc).await?;
+
+        info!(
+            layaway_id = %layaway.id,
+            amount = amount_cents,
+            balance_due = doc.layaway.balance_due_cents(),
+            "Layaway payment added"
+        );
+
+        Ok(LayawayDto::from_document(doc))
+    })
+    .await
+}
+
+/// Lists recent layaways, optionally filtered by status.
+#[tauri::command]
+pub async fn list_layaways(
+    db: State<'_, DbState>,
+    status: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<LayawayDto>, ApiError> {
+    traced("list_layaways", async move {
+        debug!(status = ?status, "list_layaways command");
+
+        let status = match status.as_deref() {
+            None => None,
+            Some("active") => Some(LayawayStatus::Active),
+            Some("completed") => Some(LayawayStatus::Completed),
+            Some("cancelled") => Some(LayawayStatus::Cancelled),
+            Some(other) => {
+                return Err(ApiError::validation(format!("Unknown layaway status: {}", other)));
+            }
+        };
+
+        let db_inner: &Database = (*db).inner()?;
+        let layaways = db_inner
+            .layaways()
+            .list(status, limit.unwrap_or(DEFAULT_LIST_LIMIT))
+            .await?;
+
+        Ok(layaways
+            .into_iter()
+            .map(|l| LayawayDto::new(l, Vec::new(), Vec::new()))
+            .collect())
+    })
+    .await
+}
+
+/// Gets a layaway with its items and payments by ID or printed number.
+#[tauri::command]
+pub async fn get_layaway(
+    db: State<'_, DbState>,
+    layaway_ref: String,
+) -> Result<LayawayDto, ApiError> {
+    traced("get_layaway", async move {
+        debug!(layaway_ref = %layaway_ref, "get_layaway command");
+
+        let db_inner: &Database = (*db).inner()?;
+        let doc = load_document(db_inner, &layaway_ref).await?;
+
+        Ok(LayawayDto::from_document(doc))
+    })
+    .await
+}
+
+/// Completes a fully paid layaway at pickup.
+///
+/// Records a completed sale at the layaway prices. The money was already
+/// taken as layaway payments and the stock already reserved, so the sale
+/// has no payments of its own and does not touch stock.
+#[tauri::command]
+pub async fn complete_layaway(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    layaway_id: String,
+) -> Result<CreateSaleResponse, ApiError> {
+    traced("complete_layaway", async move {
+        debug!(layaway_id = %layaway_id, "complete_layaway command");
+
+        let db_inner: &Database = (*db).inner()?;
+        let doc = load_document(db_inner, &layaway_id).await?;
+        doc.layaway.ensure_ready_for_pickup()?;
+
+        let now = Utc::now();
+        let sale_id = Uuid::new_v4().to_string();
+        let sale = Sale {
+            id: sale_id.clone(),
+            tenant_id: config.tenant_id.clone(),
+            receipt_number: config.station.receipt_number(&generate_receipt_number()),
+            status: SaleStatus::Completed,
+            subtotal_cents: doc.layaway.subtotal_cents,
+            tax_cents: doc.layaway.tax_cents,
+            discount_cents: 0,
+            total_cents: doc.layaway.total_cents,
+            user_id: USER_ID.to_string(),
+            device_id: config.station.device_id(),
+            station_number: config.station.sale_station(),
+            notes: Some(format!("Layaway pickup {}", doc.layaway.layaway_number)),
+            created_at: now,
+            updated_at: now,
+            completed_at: Some(now),
+            sync_version: 0,
+        };
+
+        let sale_items: Vec<SaleItem> = doc
+            .items
+            .iter()
+            .map(|i| SaleItem {
+                id: Uuid::new_v4().to_string(),
+                sale_id: sale_id.clone(),
+                product_id: i.product_id.clone(),
+                sku_snapshot: i.sku_snapshot.clone(),
+                name_snapshot: i.name_snapshot.clone(),
+                unit_price_cents: i.unit_price_cents,
+                quantity: i.quantity,
+                line_total_cents: i.line_total_cents,
+                tax_cents: i.tax_cents,
+                discount_cents: 0,
+                base_price_cents: None,
+                tax_rate_bps: None,
+                created_at: now,
+            })
+            .collect();
+
+        db_inner
+            .layaways()
+            .complete(&doc.layaway.id, &sale, &sale_items)
+            .await?;
+
+        let sale_doc = db_inner
+            .sales()
+            .get_document(&sale_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Sale", &sale_id))?;
+        let payload = serde_json::to_string(&sale_doc).unwrap_or_default();
+        db_inner
+            .sync_outbox()
+            .queue_for_sync("SALE", &sale_id, &payload)
+            .await?;
+
+        let completed = load_document(db_inner, &doc.layaway.id).await?;
+        queue_layaway(db_inner, &completed).await?;
+
+        info!(layaway_id = %doc.layaway.id, sale_id = %sale_id, "Layaway completed");
+
+        Ok(CreateSaleResponse {
+            sale_id,
+            total_cents: sale.total_cents,
+            item_count: sale_items.len(),
+            training_mode: training_mode(),
+        })
+    })
+    .await
+}
+
+/// Cancels an active layaway, returning the goods to stock.
+///
+/// The restocking fee comes from the store's layaway policy unless
+/// `restocking_fee_bps` overrides it for this cancellation (e.g. a manager
+/// waiving the fee with `0`). The refund is paid out with `refund_method`
+/// (cash by default).
+#[tauri::command]
+pub async fn cancel_layaway(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    layaway_id: String,
+    restocking_fee_bps: Option<u32>,
+    refund_method: Option<String>,
+) -> Result<LayawayDto, ApiError> {
+    traced("cancel_layaway", async move {
+        debug!(layaway_id = %layaway_id, fee_bps = ?restocking_fee_bps, "cancel_layaway command");
+
+        let policy = LayawayPolicy {
+            restocking_fee_bps: restocking_fee_bps.unwrap_or(config.layaway.restocking_fee_bps),
+            ..config.layaway
+        };
+        policy.validate().map_err(CoreError::from)?;
+
+        let db_inner: &Database = (*db).inner()?;
+        let layaway = load_document(db_inner, &layaway_id).await?.layaway;
+        layaway.ensure_active()?;
+
+        let device_id = config.station.device_id();
+        let settlement = policy.cancellation_settlement(&layaway);
+        let refund = (settlement.refund_cents > 0).then(|| {
+            let method = refund_method.as_deref().map_or(PaymentMethod::Cash, parse_method);
+            new_payment(&layaway.id, method, -settlement.refund_cents, &device_id)
+        });
+
+        db_inner
+            .layaways()
+            .cancel(&layaway.id, &settlement, refund.as_ref(), &device_id)
+            .await?;
+
+        let doc = load_document(db_inner, &layaway.id).await?;
+        queue_layaway(db_inner, &doc).await?;
+
+        info!(
+            layaway_id = %layaway.id,
+            fee = settlement.restocking_fee_cents,
+            refund = settlement.refund_cents,
+            "Layaway cancelled"
+        );
+
+        Ok(LayawayDto::from_document(doc))
+    })
+    .await
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Loads a layaway document by ID, falling back to the printed number.
+async fn load_document(db: &Database, layaway_ref: &str) -> Result<LayawayDocument, ApiError> {
+    if let Some(doc) = db.layaways().get_document(layaway_ref).await? {
+        return Ok(doc);
+    }
+
+    let layaway = db
+        .layaways()
+        .get_by_number(layaway_ref)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Layaway", layaway_ref))?;
+
+    db.layaways()
+        .get_document(&layaway.id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Layaway", layaway_ref))
+}
+
+/// Queues the latest state of a layaway for other terminals.
+async fn queue_layaway(db: &Database, doc: &LayawayDocument) -> Result<(), ApiError> {
+    let payload = serde_json::to_string(doc)
+        .map_err(|e| ApiError::new(ErrorCode::Internal, e.to_string()))?;
+    db.sync_outbox()
+        .upsert_for_sync("LAYAWAY", &doc.layaway.id, &payload)
+        .await?;
+    Ok(())
+}
+
+/// Maps a tender name to a payment method (same rules as `add_payment`).
+fn parse_method(method: &str) -> PaymentMethod {
+    match method.to_lowercase().as_str() {
+        "cash" => PaymentMethod::Cash,
+        _ => PaymentMethod::ExternalCard,
+    }
+}
+
+fn new_payment(
+    layaway_id: &str,
+    method: PaymentMethod,
+    amount_cents: i64,
+    device_id: &str,
+) -> LayawayPayment {
+    LayawayPayment {
+        id: Uuid::new_v4().to_string(),
+        layaway_id: layaway_id.to_string(),
+        method,
+        amount_cents,
+        device_id: device_id.to_string(),
+        created_at: Utc::now(),
+    }
+}
+
+fn generate_layaway_number() -> String {
+    format!("L-{}", generate_receipt_number())
+}
@@ -0,0 +1,371 @@
+//! # Loyalty Commands
+//!
+//! Enroll members, look them up and put them on the cart. A member on the
+//! cart earns points when the sale is finalized; rewards are spent as a
+//! tender on `add_payment` (method `loyalty_reward`).
+//!
+//! ## Loyalty Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                            Loyalty Flow                                 │
+//! │                                                                         │
+//! │  set_loyalty_member(LM-…) ──► cart.loyalty_member                       │
+//! │                                                                         │
+//! │  finalize_sale ──► points at the tier's rate ──► rewards issued         │
+//! │         │                                                               │
+//! │         └── LOYALTY queued ──► hub ──► other terminals ──► cloud        │
+//! │                                                                         │
+//! │  add_payment(method: loyalty_reward, reference: LM-…)                   │
+//! │         │                                                               │
+//! │         ├── sync running:  LoyaltyRedeem ──► hub ──► cloud checks       │
+//! │         │                  not verified  ──► rejected                   │
+//! │         └── standalone:    local ledger checks rewards                  │
+//! │                                                                         │
+//! │  Rewards are checked where every store writes, so the same reward       │
+//! │  cannot be spent in two stores.                                         │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Every command is behind the `loyalty` feature flag.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{debug, info, warn};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::commands::cart::CartResponse;
+use crate::commands::feature::require_feature;
+use crate::commands::sale::generate_receipt_number;
+use crate::commands::training::ensure_tender_allowed;
+use crate::error::{ApiError, ErrorCode};
+use crate::middleware::traced;
+use crate::state::{CartState, ConfigState, DbState, SyncState};
+use titan_core::feature::LOYALTY;
+use titan_core::{
+    CoreError, LoyaltyAccount, LoyaltyAccrual, LoyaltyDocument, LoyaltyEntry, LoyaltyEntryKind,
+    LoyaltyProgram, PaymentMethod,
+};
+use titan_db::Database;
+use titan_sync::protocol::LoyaltyRedeemRequest;
+
+/// A loyalty member as shown in the UI.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct LoyaltyDto {
+    pub id: String,
+    pub member_number: String,
+    #[ts(type = "number")]
+    pub points_balance: i64,
+    #[ts(type = "number")]
+    pub lifetime_points: i64,
+    /// Rewards that can be spent, in cents.
+    #[ts(type = "number")]
+    pub reward_balance_cents: i64,
+    /// Name of the member's tier.
+    pub tier: Option<String>,
+    /// Name of the next tier up, if any.
+    pub next_tier: Option<String>,
+    /// Lifetime points still needed for the next tier.
+    #[ts(type = "number | null")]
+    pub points_to_next_tier: Option<i64>,
+    pub entries: Vec<LoyaltyEntry>,
+}
+
+impl LoyaltyDto {
+    fn new(doc: LoyaltyDocument, program: &LoyaltyProgram) -> Self {
+        let lifetime = doc.lifetime_points();
+        let next_tier = program.next_tier(lifetime);
+        LoyaltyDto {
+            points_balance: doc.points_balance(),
+            lifetime_points: lifetime,
+            reward_balance_cents: doc.reward_balance_cents(),
+            tier: program.tier(lifetime).map(|t| t.name.clone()),
+            next_tier: next_tier.map(|t| t.name.clone()),
+            points_to_next_tier: next_tier.map(|t| t.min_points - lifetime),
+            id: doc.account.id,
+            member_number: doc.account.member_number,
+            entries: doc.entries,
+        }
+    }
+}
+
+/// Enrolls a loyalty member.
+///
+/// Pass the `member_number` printed on a pre-printed card, or none to have
+/// one generated.
+///
+/// # Errors
+/// - `FEATURE_DISABLED` if loyalty is not enabled for the store
+/// - `CONFLICT` if the member number is taken
+#[tauri::command]
+pub async fn enroll_loyalty_member(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    member_number: Option<String>,
+) -> Result<LoyaltyDto, ApiError> {
+    traced("enroll_loyalty_member", async move {
+        debug!(member_number = ?member_number, "enroll_loyalty_member command");
+
+        let db_inner: &Database = (*db).inner()?;
+        require_feature(db_inner, LOYALTY).await?;
+
+        let now = Utc::now();
+        let account = LoyaltyAccount {
+            id: Uuid::new_v4().to_string(),
+            tenant_id: config.tenant_id.clone(),
+            member_number: member_number
+                .map(|n| n.trim().to_uppercase())
+                .filter(|n| !n.is_empty())
+                .unwrap_or_else(generate_member_number),
+            created_at: now,
+            updated_at: now,
+            sync_version: 1,
+        };
+        db_inner.loyalty().create_account(&account).await?;
+
+        let doc = load_document(db_inner, &account.id).await?;
+        queue_loyalty(db_inner, &doc).await?;
+
+        Ok(LoyaltyDto::new(doc, &config.loyalty))
+    })
+    .await
+}
+
+/// Gets a loyalty member with their ledger, by ID or member number.
+#[tauri::command]
+pub async fn get_loyalty_member(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    member_ref: String,
+) -> Result<LoyaltyDto, ApiError> {
+    traced("get_loyalty_member", async move {
+        debug!(member_ref = %member_ref, "get_loyalty_member command");
+
+        let db_inner: &Database = (*db).inner()?;
+        require_feature(db_inner, LOYALTY).await?;
+
+        let account = load_account(db_inner, member_ref.trim()).await?;
+        let doc = load_document(db_inner, &account.id).await?;
+
+        Ok(LoyaltyDto::new(doc, &config.loyalty))
+    })
+    .await
+}
+
+/// Puts a loyalty member on the cart, or takes them off with no
+/// `member_ref`.
+#[tauri::command]
+pub async fn set_loyalty_member(
+    db: State<'_, DbState>,
+    cart: State<'_, CartState>,
+    member_ref: Option<String>,
+) -> Result<CartResponse, ApiError> {
+    traced("set_loyalty_member", async move {
+        debug!(member_ref = ?member_ref, "set_loyalty_member command");
+
+        let member_number = match member_ref
+            .as_deref()
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+        {
+            Some(member_ref) => {
+                let db_inner: &Database = (*db).inner()?;
+                require_feature(db_inner, LOYALTY).await?;
+                Some(load_account(db_inner, member_ref).await?.member_number)
+            }
+            None => None,
+        };
+
+        cart.with_cart_mut(|c| c.loyalty_member = member_number);
+        Ok(cart.with_totals(CartResponse::new))
+    })
+    .await
+}
+
+// =============================================================================
+// Sale Hooks (used by add_payment and finalize_sale)
+// =============================================================================
+
+/// Spends loyalty rewards on a sale.
+///
+/// With the sync agent running the hub has the redemption checked by the
+/// cloud, which sees every store's ledger; if it cannot be verified the
+/// tender is refused rather than risk spending the same reward twice. A
+/// standalone terminal checks against its own ledger. Refused in training
+/// mode, where the hub would still spend the live rewards.
+///
+/// ## Returns
+/// The rewards left on the account.
+pub(crate) async fn redeem_loyalty_reward(
+    db: &Database,
+    device_id: &str,
+    sync: &SyncState,
+    account: &LoyaltyAccount,
+    amount_cents: i64,
+    sale_id: &str,
+) -> Result<i64, ApiError> {
+    ensure_tender_allowed(PaymentMethod::LoyaltyReward)?;
+    require_feature(db, LOYALTY).await?;
+
+    let entry = LoyaltyEntry {
+        id: Uuid::new_v4().to_string(),
+        account_id: account.id.clone(),
+        kind: LoyaltyEntryKind::Redeem,
+        points: 0,
+        reward_cents: -amount_cents,
+        sale_id: Some(sale_id.to_string()),
+        device_id: device_id.to_string(),
+        created_at: Utc::now(),
+    };
+
+    let balance = match sync.agent_handle() {
+        Some(handle) => {
+            let request = LoyaltyRedeemRequest {
+                request_id: Uuid::new_v4().to_string(),
+                device_id: device_id.to_string(),
+                account_id: account.id.clone(),
+                entry_id: entry.id.clone(),
+                amount_cents,
+                sale_id: entry.sale_id.clone(),
+                timestamp: entry.created_at.to_rfc3339(),
+            };
+            let result = handle.redeem_loyalty(request).await.map_err(|e| {
+                warn!(member_number = %account.member_number, ?e, "Loyalty rewards could not be verified");
+                ApiError::new(
+                    ErrorCode::InsufficientRewards,
+                    format!("Rewards could not be verified with the store hub: {}", e),
+                )
+            })?;
+
+            if !result.approved {
+                if result.balance_cents < amount_cents {
+                    return Err(insufficient(account, amount_cents, result.balance_cents));
+                }
+                return Err(ApiError::new(
+                    ErrorCode::InsufficientRewards,
+                    format!(
+                        "Rewards of member {} were refused: {}",
+                        account.member_number,
+                        result.reason.unwrap_or_default()
+                    ),
+                ));
+            }
+
+            // The hub also broadcasts the account; writing the entry now
+            // keeps this terminal's balance right if that arrives late.
+            db.loyalty()
+                .upsert_from_sync(&LoyaltyDocument {
+                    account: account.clone(),
+                    entries: vec![entry],
+                })
+                .await?;
+            result.balance_cents
+        }
+        None => {
+            let redemption = db.loyalty().redeem(&entry).await?;
+            if !redemption.applied {
+                return Err(insufficient(
+                    account,
+                    amount_cents,
+                    redemption.balance_cents,
+                ));
+            }
+            redemption.balance_cents
+        }
+    };
+
+    let doc = load_document(db, &account.id).await?;
+    queue_loyalty(db, &doc).await?;
+
+    Ok(balance)
+}
+
+/// Records the points a member earned on a finalized sale, and any rewards
+/// they completed.
+///
+/// `spent_cents` is what the sale cost the member, rewards tendered
+/// excluded. Safe to run twice for the same sale: its entries are written
+/// once.
+pub(crate) async fn accrue_loyalty(
+    db: &Database,
+    device_id: &str,
+    program: &LoyaltyProgram,
+    member_number: &str,
+    sale_id: &str,
+    spent_cents: i64,
+) -> Result<LoyaltyAccrual, ApiError> {
+    let account = load_account(db, member_number).await?;
+    let doc = load_document(db, &account.id).await?;
+
+    let accrual = program.accrue(&doc, sale_id, spent_cents, device_id, Utc::now());
+    if accrual.entries.is_empty() {
+        return Ok(accrual);
+    }
+    db.loyalty()
+        .add_entries(&account.id, &accrual.entries)
+        .await?;
+
+    let doc = load_document(db, &account.id).await?;
+    queue_loyalty(db, &doc).await?;
+
+    info!(
+        member_number = %account.member_number,
+        sale_id = %sale_id,
+        points = accrual.points,
+        rewards = accrual.rewards_cents,
+        "Loyalty points earned"
+    );
+    Ok(accrual)
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Loads an account by ID, falling back to the member number.
+pub(crate) async fn load_account(
+    db: &Database,
+    member_ref: &str,
+) -> Result<LoyaltyAccount, ApiError> {
+    if let Some(account) = db.loyalty().get_by_id(member_ref).await? {
+        return Ok(account);
+    }
+
+    db.loyalty()
+        .get_by_member_number(&member_ref.to_uppercase())
+        .await?
+        .ok_or_else(|| ApiError::not_found("Loyalty member", member_ref))
+}
+
+async fn load_document(db: &Database, account_id: &str) -> Result<LoyaltyDocument, ApiError> {
+    db.loyalty()
+        .get_document(account_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Loyalty member", account_id))
+}
+
+/// Queues the account and its full ledger for other terminals and stores.
+async fn queue_loyalty(db: &Database, doc: &LoyaltyDocument) -> Result<(), ApiError> {
+    let payload = serde_json::to_string(doc)
+        .map_err(|e| ApiError::new(ErrorCode::Internal, e.to_string()))?;
+    db.sync_outbox()
+        .upsert_for_sync("LOYALTY", &doc.account.id, &payload)
+        .await?;
+    Ok(())
+}
+
+fn generate_member_number() -> String {
+    format!("LM-{}", generate_receipt_number())
+}
+
+fn insufficient(account: &LoyaltyAccount, requested_cents: i64, available_cents: i64) -> ApiError {
+    CoreError::InsufficientRewards {
+        member_number: account.member_number.clone(),
+        available_cents,
+        requested_cents,
+    }
+    .into()
+}
@@ -0,0 +1,91 @@
+//! # Margin Guardrail Commands
+//!
+//! The audit trail of manager approvals for manual prices below the
+//! store's margin floor (see `titan_core::margin_guard` and
+//! `ConfigState::margin_guardrail`). The floor itself is checked by
+//! `update_product` and `override_line_price`.
+//!
+//! ## Commands
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                    Margin Guardrail Commands                            │
+//! │                                                                         │
+//! │  update_product(.., price, bypass)  ─┐  below a blocking floor:        │
+//! │  override_line_price(.., bypass)    ─┤  no bypass ──► MANAGER_OVERRIDE │
+//! │                                      │                _REQUIRED         │
+//! │                                      └► bypass ──► saved + audited     │
+//! │                                                                         │
+//! │  get_margin_overrides(date)   the day's approvals, for managers        │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{NaiveDate, Utc};
+use tauri::State;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::middleware::traced;
+use crate::state::DbState;
+use titan_core::tax_report::report_window;
+use titan_core::{
+    CoreError, ManagerBypass, MarginCheck, MarginOverride, MarginVerdict, PriceChangeKind, Product,
+};
+use titan_db::Database;
+
+/// Cashier ID recorded on overrides (matches the sale commands).
+const USER_ID: &str = "default";
+
+/// Writes a manager bypass of the margin floor to the audit trail.
+///
+/// Does nothing unless `check` was allowed by the bypass.
+pub(crate) async fn audit_bypass(
+    db: &Database,
+    device_id: &str,
+    kind: PriceChangeKind,
+    product: &Product,
+    old_price_cents: i64,
+    check: &MarginCheck,
+    bypass: Option<&ManagerBypass>,
+) -> Result<(), ApiError> {
+    let Some(bypass) = bypass.filter(|_| check.verdict == MarginVerdict::Bypassed) else {
+        return Ok(());
+    };
+
+    let entry = MarginOverride {
+        id: Uuid::new_v4().to_string(),
+        kind,
+        product_id: product.id.clone(),
+        old_price_cents,
+        new_price_cents: check.price_cents,
+        cost_cents: check.cost_cents,
+        margin_bps: check.margin_bps,
+        floor_bps: check.floor_bps,
+        manager_id: bypass.manager_id.trim().to_string(),
+        reason: bypass.reason.trim().to_string(),
+        user_id: USER_ID.to_string(),
+        device_id: device_id.to_string(),
+        created_at: Utc::now(),
+    };
+    db.margin_overrides().record(&entry).await?;
+    Ok(())
+}
+
+/// Lists the margin floor bypasses approved on one day (`YYYY-MM-DD`,
+/// UTC), oldest first.
+#[tauri::command]
+pub async fn get_margin_overrides(
+    db: State<'_, DbState>,
+    date: String,
+) -> Result<Vec<MarginOverride>, ApiError> {
+    traced("get_margin_overrides", async move {
+        debug!(date = %date, "get_margin_overrides command");
+        let db_inner: &Database = (*db).reporting()?;
+
+        let day = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+            .map_err(|_| ApiError::validation("'date' must be YYYY-MM-DD"))?;
+        let (start, end) = report_window(day, day).map_err(CoreError::from)?;
+        Ok(db_inner.margin_overrides().list_between(start, end).await?)
+    })
+    .await
+}
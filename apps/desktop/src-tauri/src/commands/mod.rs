@@ -6,11 +6,41 @@
 //! ```text
 //! commands/
 //! ├── mod.rs      ◄─── You are here (exports)
-//! ├── product.rs  ◄─── Product search, CRUD
+//! ├── product.rs  ◄─── Product search, CRUD, attributes, tags, variants
+//! ├── bundle.rs   ◄─── Bundle bills of materials, component stock checks
 //! ├── cart.rs     ◄─── Cart manipulation
 //! ├── sale.rs     ◄─── Sale/payment processing
+//! ├── receipt.rs  ◄─── Receipt variants: gift, reprint, refund
+//! ├── scale.rs    ◄─── Scale readings, live weight, weighed cart lines
+//! ├── quote.rs    ◄─── Quotes: save, print/email, convert to sale
+//! ├── tracking.rs ◄─── Serial/lot capture and recall lookup
+//! ├── age.rs      ◄─── Customer age checks for restricted products
+//! ├── layaway.rs  ◄─── Layaways: deposits, payments, pickup, cancel
+//! ├── transfer.rs ◄─── Store-to-store stock transfers
+//! ├── receiving.rs ◄─── Case packs, receiving by case or each, stock levels
+//! ├── waste.rs    ◄─── Waste write-offs, daily waste report
+//! ├── supplier.rs ◄─── Suppliers, price lists, product costs
+//! ├── margin.rs   ◄─── Margin floor bypass audit trail
+//! ├── override_token.rs ◄─── Manager override tokens: issue, redeem, audit trail
+//! ├── store_credit.rs ◄─── Returnless refunds, store credit lookup
+//! ├── coupon.rs   ◄─── Coupon codes on the cart, coupon refresh from the cloud
+//! ├── loyalty.rs  ◄─── Loyalty members, tiers, members on the cart
+//! ├── drawer.rs   ◄─── Cash drawer opens, manual open audit trail
+//! ├── fiscal.rs   ◄─── Fiscal receipt signing and signature lookup
+//! ├── einvoice.rs ◄─── Business customers, UBL e-invoice export
+//! ├── privacy.rs  ◄─── Customer data erasure, erasure log
 //! ├── config.rs   ◄─── Configuration retrieval
-//! └── sync.rs     ◄─── Sync status and control
+//! ├── startup.rs  ◄─── Startup status for the splash screen
+//! ├── crash.rs    ◄─── Crash reports: frontend crashes, list, upload
+//! ├── training.rs ◄─── Training mode sandbox toggle
+//! ├── window.rs   ◄─── Manager back office window
+//! ├── sync.rs     ◄─── Sync status and control
+//! ├── jobs.rs     ◄─── Background job schedules and run history
+//! ├── label.rs    ◄─── Shelf label queue, label templates
+//! ├── update.rs   ◄─── Release checks, verified downloads, staged rollout
+//! ├── feature.rs  ◄─── Remote feature flags and their local cache
+//! ├── report.rs   ◄─── Sales tax report, CSV export, dashboard stats
+//! └── till.rs     ◄─── Till open, blind close, variance report
 //! ```
 //!
 //! ## How Commands Work
@@ -44,6 +74,27 @@
 //! └─────────────────────────────────────────────────────────────────────────┘
 //! ```
 //!
+//! ## Middleware
+//! Every async command runs its body through [`crate::middleware::traced`],
+//! which logs entry and exit with the duration and error code and gives the
+//! command a correlation ID. Outbox entries queued by the command carry
+//! that ID to the hub and the cloud; a failed command returns it in
+//! `ApiError.correlationId`.
+//!
+//! ## TypeScript Bindings
+//! Every DTO a command takes or returns, and every event payload, derives
+//! `ts_rs::TS` with `#[ts(export)]`. `cargo test` writes them (and the
+//! titan-core types they use) to `apps/desktop/src/bindings`, which
+//! `src/types` re-exports, so the frontend never guesses a shape:
+//! ```text
+//! ProductDto (Rust) ──cargo test──► src/bindings/ProductDto.ts
+//!                                        │
+//!                                        ▼
+//!                    import type { ProductDto } from '../types'
+//! ```
+//! JSON carries numbers, so `i64` fields are marked `#[ts(type = "number")]`
+//! rather than ts-rs's default `bigint`.
+//!
 //! ## State Injection (Option B)
 //! Each command declares only the state it needs:
 //! ```rust,ignore
@@ -60,8 +111,38 @@
 //! async fn get_sync_status(sync: State<'_, SyncState>)
 //! ```
 
+pub mod age;
+pub mod bundle;
 pub mod cart;
 pub mod config;
+pub mod coupon;
+pub mod crash;
+pub mod drawer;
+pub mod einvoice;
+pub mod feature;
+pub mod fiscal;
+pub mod jobs;
+pub mod label;
+pub mod layaway;
+pub mod loyalty;
+pub mod margin;
+pub mod override_token;
+pub mod privacy;
 pub mod product;
+pub mod quote;
+pub mod receipt;
+pub mod receiving;
+pub mod report;
 pub mod sale;
+pub mod scale;
+pub mod startup;
+pub mod store_credit;
+pub mod supplier;
 pub mod sync;
+pub mod till;
+pub mod tracking;
+pub mod training;
+pub mod transfer;
+pub mod update;
+pub mod waste;
+pub mod window;
@@ -0,0 +1,324 @@
+//! # Manager Override Token Commands
+//!
+//! Remote authorization for operations above what a cashier may do alone
+//! (see `titan_core::override_token`). A manager's terminal issues a
+//! short-lived signed token; the till checks it offline against the
+//! store's public key and writes it to the audit trail, which refuses a
+//! token the second time. A token names the terminal that issued it, which
+//! cannot use it.
+//!
+//! ## Commands
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                   Manager Override Token Commands                       │
+//! │                                                                         │
+//! │  manager's terminal (device.override_signing_key)                       │
+//! │  issue_override_token(refund, sale, max, ..) ──► token (10 min)         │
+//! │       till ──► MANAGER_OVERRIDE_REQUIRED                                │
+//! │                                                                         │
+//! │  till                                                                   │
+//! │  refund_sale(.., override_token)   above the refund approval limit:     │
+//! │       no token ──► MANAGER_OVERRIDE_REQUIRED                            │
+//! │       token ──► verify ──► authorizes ──► audit (once) ──► refund       │
+//! │                                                                         │
+//! │  get_manager_overrides(date)   the day's used tokens, for managers      │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Keys come from the sync configuration, loaded at startup. Every
+//! terminal has the store's `override_public_key` and can only check
+//! tokens; the signing key (`device.override_signing_key` or
+//! `TITAN_OVERRIDE_SIGNING_KEY`) is given to the manager's terminal alone,
+//! so a till cannot mint a token to approve its own refund.
+
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{debug, info};
+use ts_rs::TS;
+
+use crate::error::ApiError;
+use crate::middleware::traced;
+use crate::state::{DbState, SyncState};
+use titan_core::override_token::DEFAULT_OVERRIDE_TTL_MINUTES;
+use titan_core::tax_report::report_window;
+use titan_core::{
+    CoreError, ManagerOverride, OverrideAction, OverrideClaims, OverrideKey, OverrideRequest,
+    OverrideSigner,
+};
+use titan_db::{Database, DbError};
+
+/// Cashier ID recorded on used tokens (matches the sale commands).
+const USER_ID: &str = "default";
+
+/// A token ready to hand to the cashier.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct IssuedOverride {
+    pub token: String,
+    pub token_id: String,
+    pub expires_at: String,
+}
+
+fn rejected(reason: &str) -> ApiError {
+    CoreError::OverrideRejected {
+        reason: reason.to_string(),
+    }
+    .into()
+}
+
+/// Store ID, this terminal's ID and the store's public override key, from
+/// the sync configuration.
+fn store_identity(sync: &SyncState) -> Result<(String, String, OverrideKey), ApiError> {
+    let no_key = || rejected("this terminal has no store override key");
+    let config = sync.get_config().ok_or_else(no_key)?;
+    let key = config
+        .store
+        .override_public_key
+        .as_deref()
+        .ok_or_else(no_key)?;
+    let key = OverrideKey::from_public_key(key)?;
+    Ok((config.store.id, config.device.id, key))
+}
+
+/// Store ID, this terminal's ID and the store's override signing key,
+/// which only the manager's terminal has.
+fn store_signer(sync: &SyncState) -> Result<(String, String, OverrideSigner), ApiError> {
+    let no_key = || rejected("only the manager's terminal can issue override tokens");
+    let config = sync.get_config().ok_or_else(no_key)?;
+    let key = config
+        .device
+        .override_signing_key
+        .as_deref()
+        .ok_or_else(no_key)?;
+    let signer = OverrideSigner::from_signing_key(key)?;
+    Ok((config.store.id, config.device.id, signer))
+}
+
+/// Checks `token` for `action` and writes it to the audit trail.
+///
+/// Returns the audit entry. The token is spent even if the operation it
+/// approved fails afterwards.
+///
+/// ## Errors
+/// - `MANAGER_OVERRIDE_REQUIRED` for a token that is forged, expired,
+///   issued for something else, or already used
+pub(crate) async fn redeem_override(
+    db: &Database,
+    sync: &SyncState,
+    token: &str,
+    action: OverrideAction,
+    subject: Option<&str>,
+    amount_cents: Option<i64>,
+) -> Result<ManagerOverride, ApiError> {
+    let (store_id, device_id, key) = store_identity(sync)?;
+    let now = Utc::now();
+    let claims = key.verify(token, now)?;
+    let request = OverrideRequest {
+        action,
+        store_id: &store_id,
+        device_id: &device_id,
+        subject,
+        amount_cents,
+    };
+    claims.authorizes(&request)?;
+
+    let entry = ManagerOverride::used(&claims, &request, USER_ID, now);
+    db.manager_overrides()
+        .record(&entry)
+        .await
+        .map_err(|e| match e {
+            DbError::UniqueViolation { .. } => rejected("token was already used"),
+            other => ApiError::from(other),
+        })?;
+
+    info!(
+        token_id = %entry.id,
+        action = action.as_str(),
+        manager_id = %entry.manager_id,
+        "Manager override used"
+    );
+    Ok(entry)
+}
+
+/// Issues a manager override token signed with the store's signing key.
+///
+/// `action` is "refund", "price_override" or "void_sale". `sale_id`,
+/// `device_id` and `max_amount_cents` narrow what the token approves;
+/// `ttl_minutes` defaults to 10 (at most 30).
+///
+/// # Errors
+/// - `MANAGER_OVERRIDE_REQUIRED` on a terminal without the store's
+///   signing key (any till)
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn issue_override_token(
+    sync: State<'_, SyncState>,
+    action: String,
+    manager_id: String,
+    reason: String,
+    sale_id: Option<String>,
+    device_id: Option<String>,
+    max_amount_cents: Option<i64>,
+    ttl_minutes: Option<i64>,
+) -> Result<IssuedOverride, ApiError> {
+    traced("issue_override_token", async move {
+        debug!(action = %action, manager_id = %manager_id, "issue_override_token command");
+
+        let action = OverrideAction::parse(&action).ok_or_else(|| {
+            ApiError::validation("'action' must be refund, price_override or void_sale")
+        })?;
+        let (store_id, issued_by, signer) = store_signer(&sync)?;
+
+        let claims = OverrideClaims {
+            device_id: device_id
+                .map(|d| d.trim().to_string())
+                .filter(|d| !d.is_empty()),
+            subject: sale_id
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty()),
+            max_amount_cents,
+            ..OverrideClaims::new(
+                action,
+                store_id,
+                issued_by,
+                manager_id,
+                reason,
+                Utc::now(),
+                ttl_minutes.unwrap_or(DEFAULT_OVERRIDE_TTL_MINUTES),
+            )
+        };
+        let token = signer.sign(&claims)?;
+
+        info!(
+            token_id = %claims.id,
+            action = action.as_str(),
+            manager_id = %claims.manager_id,
+            "Manager override issued"
+        );
+
+        Ok(IssuedOverride {
+            token,
+            token_id: claims.id,
+            expires_at: claims.expires_at.to_rfc3339(),
+        })
+    })
+    .await
+}
+
+/// Lists the override tokens used on one day (`YYYY-MM-DD`, UTC), oldest
+/// first.
+#[tauri::command]
+pub async fn get_manager_overrides(
+    db: State<'_, DbState>,
+    date: String,
+) -> Result<Vec<ManagerOverride>, ApiError> {
+    traced("get_manager_overrides", async move {
+        debug!(date = %date, "get_manager_overrides command");
+        let db_inner: &Database = (*db).reporting()?;
+
+        let day = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+            .map_err(|_| ApiError::validation("'date' must be YYYY-MM-DD"))?;
+        let (start, end) = report_window(day, day).map_err(CoreError::from)?;
+        Ok(db_inner
+            .manager_overrides()
+            .list_between(start, end)
+            .await?)
+    })
+    .await
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use titan_core::ErrorCode;
+    use titan_db::DbConfig;
+    use titan_sync::SyncConfig;
+
+    fn terminal(device_id: &str, public_key: &str, signing_key: Option<&str>) -> SyncState {
+        let mut config = SyncConfig::default();
+        config.store.id = "store-1".to_string();
+        config.store.override_public_key = Some(public_key.to_string());
+        config.device.id = device_id.to_string();
+        config.device.override_signing_key = signing_key.map(str::to_string);
+        config.validate().unwrap();
+        let sync = SyncState::new();
+        sync.set_config(config);
+        sync
+    }
+
+    #[tokio::test]
+    async fn test_issue_then_redeem() {
+        let db = Database::new(DbConfig::in_memory()).await.unwrap();
+        let signing_key = OverrideSigner::generate().unwrap();
+        let public_key = OverrideSigner::from_signing_key(&signing_key)
+            .unwrap()
+            .public_key();
+        let manager = terminal("mgr-pc", &public_key, Some(&signing_key));
+        let till = terminal("pos-01", &public_key, None);
+
+        // A till has no signing key and cannot issue
+        let err = store_signer(&till).unwrap_err();
+        assert_eq!(err.code, ErrorCode::ManagerOverrideRequired);
+
+        // The manager's terminal issues, the till redeems once
+        let (store_id, issued_by, signer) = store_signer(&manager).unwrap();
+        let claims = OverrideClaims {
+            subject: Some("sale-1".to_string()),
+            max_amount_cents: Some(25_000),
+            ..OverrideClaims::new(
+                OverrideAction::Refund,
+                store_id,
+                issued_by,
+                "mgr-1",
+                "Faulty TV, receipt lost",
+                Utc::now(),
+                DEFAULT_OVERRIDE_TTL_MINUTES,
+            )
+        };
+        let token = signer.sign(&claims).unwrap();
+
+        let used = redeem_override(
+            &db,
+            &till,
+            &token,
+            OverrideAction::Refund,
+            Some("sale-1"),
+            Some(20_000),
+        )
+        .await
+        .unwrap();
+        assert_eq!(used.id, claims.id);
+        assert_eq!(used.device_id, "pos-01");
+
+        let again = redeem_override(
+            &db,
+            &till,
+            &token,
+            OverrideAction::Refund,
+            Some("sale-1"),
+            Some(20_000),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(again.code, ErrorCode::ManagerOverrideRequired);
+
+        // The issuing terminal cannot use its own token
+        let own = Database::new(DbConfig::in_memory()).await.unwrap();
+        assert!(redeem_override(
+            &own,
+            &manager,
+            &token,
+            OverrideAction::Refund,
+            Some("sale-1"),
+            None
+        )
+        .await
+        .is_err());
+    }
+}
@@ -0,0 +1,97 @@
+//! # Privacy Commands
+//!
+//! Customer data erasure ("right to erasure") and the erasure log.
+//!
+//! ## Commands
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Privacy Commands                                 │
+//! │                                                                         │
+//! │  forget_customer  anonymize a customer here; queued as                  │
+//! │                   CUSTOMER_ERASURE → hub → other terminals → cloud      │
+//! │  list_erasures    erasure log, newest first (local and received)        │
+//! │                                                                         │
+//! │  Sales, payments and store credit balances are kept; only names and    │
+//! │  contact details are overwritten (see titan_core::erasure).            │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::Utc;
+use tauri::State;
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::middleware::traced;
+use crate::state::{ConfigState, DbState};
+use titan_core::{CoreError, ErasureRecord, ErasureRequest, ErasureSubject};
+use titan_db::Database;
+
+/// Cashier ID recorded as the requester (matches the sale commands).
+const USER_ID: &str = "default";
+
+/// Default number of entries returned by `list_erasures`.
+const DEFAULT_ERASURE_LIMIT: u32 = 50;
+
+/// Erases a customer's personal data on this terminal and everywhere it
+/// syncs to.
+///
+/// # Arguments
+/// * `subject` - Business customer ID, phone and/or email (at least one)
+/// * `reason` - Why, e.g. the customer's request reference
+///
+/// # Returns
+/// The erasure log entry, with what was anonymized on this terminal.
+///
+/// ## Errors
+/// `VALIDATION_ERROR` if the subject names no customer.
+#[tauri::command]
+pub async fn forget_customer(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    subject: ErasureSubject,
+    reason: Option<String>,
+) -> Result<ErasureRecord, ApiError> {
+    traced("forget_customer", async move {
+        debug!("forget_customer command");
+
+        let request = ErasureRequest::new(
+            Uuid::new_v4().to_string(),
+            &config.tenant_id,
+            &subject,
+            reason,
+            USER_ID,
+            Utc::now(),
+        )
+        .map_err(CoreError::from)?;
+
+        let db_inner: &Database = (*db).inner()?;
+        let record = db_inner.erasures().forget(&request).await?;
+
+        info!(
+            request_id = %request.id,
+            rows = record.counts.total(),
+            "Customer erased"
+        );
+
+        Ok(record)
+    })
+    .await
+}
+
+/// Lists the erasure log, newest first.
+///
+/// # Arguments
+/// * `limit` - Maximum entries returned (default 50, at most 500)
+#[tauri::command]
+pub async fn list_erasures(
+    db: State<'_, DbState>,
+    limit: Option<u32>,
+) -> Result<Vec<ErasureRecord>, ApiError> {
+    traced("list_erasures", async move {
+        let limit = limit.unwrap_or(DEFAULT_ERASURE_LIMIT).clamp(1, 500);
+        let db_inner: &Database = (*db).inner()?;
+        Ok(db_inner.erasures().list(limit).await?)
+    })
+    .await
+}
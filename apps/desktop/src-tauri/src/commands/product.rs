@@ -1,6 +1,10 @@
 //! # Product Commands
 //!
-//! Tauri commands for product search and retrieval.
+//! Tauri commands for product search and retrieval, for editing (with
+//! the margin guardrail, see `titan_core::margin_guard`), deleting and
+//! restoring products, for product attributes and tags (see
+//! `titan_core::attribute`), and for product styles and their variants
+//! (see `titan_core::variant`).
 //!
 //! ## Search Flow
 //! ```text
@@ -36,10 +40,18 @@ use serde::{Deserialize, Serialize};
 use std::time::Instant;
 use tauri::State;
 use tracing::{debug, info};
+use ts_rs::TS;
 
+use crate::commands::margin::audit_bypass;
 use crate::error::ApiError;
-use crate::state::DbState;
-use titan_core::Product;
+use crate::middleware::traced;
+use crate::state::{ConfigState, DbState};
+use titan_core::validation::{validate_price_cents, validate_product_name};
+use titan_core::{
+    AttributeFilter, CoreError, ManagerBypass, MarginCheck, Page, PageRequest, PriceChangeKind,
+    Product, ProductAttribute, ProductAttributes, ProductStyle, SearchResult, Tracked, VariantGroup,
+    VariantMatrix,
+};
 use titan_db::Database;
 
 /// Product DTO (Data Transfer Object) for frontend.
@@ -48,7 +60,8 @@ use titan_db::Database;
 /// - Decouples internal domain model from API contract
 /// - Allows selective field exposure
 /// - Handles serde rename to camelCase for JS consumption
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct ProductDto {
     pub id: String,
@@ -56,14 +69,22 @@ pub struct ProductDto {
     pub barcode: Option<String>,
     pub name: String,
     pub description: Option<String>,
+    #[ts(type = "number")]
     pub price_cents: i64,
     pub tax_rate_bps: u32,
     pub track_inventory: bool,
     /// Whether selling is allowed when stock is 0 or negative.
     /// Used by frontend to show "Back-order" vs "Out of Stock".
     pub allow_negative_stock: bool,
+    #[ts(type = "number | null")]
     pub current_stock: Option<i64>,
     pub is_active: bool,
+    /// "none", "serial" or "lot" - frontend prompts for codes when set.
+    pub item_tracking: String,
+    /// Minimum customer age; frontend warns that an ID check will be needed.
+    pub min_purchase_age: Option<u32>,
+    /// Set once the product is deleted (RFC 3339).
+    pub deleted_at: Option<String>,
 }
 
 impl From<Product> for ProductDto {
@@ -80,10 +101,24 @@ impl From<Product> for ProductDto {
             allow_negative_stock: p.allow_negative_stock,
             current_stock: p.current_stock,
             is_active: p.is_active,
+            item_tracking: p.item_tracking.as_str().to_string(),
+            min_purchase_age: p.min_purchase_age,
+            deleted_at: p.deleted_at.map(|at| at.to_rfc3339()),
         }
     }
 }
 
+/// A product after an edit, with the margin check of a new price.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductUpdateDto {
+    pub product: ProductDto,
+    /// Set when the price changed; `verdict` is "warn" when it is below
+    /// the margin floor.
+    pub margin: Option<MarginCheck>,
+}
+
 /// Checks if a query looks like a barcode (8-13 numeric digits).
 ///
 /// ## Barcode Formats Detected
@@ -140,44 +175,84 @@ pub async fn search_products(
     query: String,
     limit: Option<u32>,
 ) -> Result<Vec<ProductDto>, ApiError> {
-    let start = Instant::now();
-    let query = query.trim();
-    let limit = limit.unwrap_or(20).min(100);
-
-    debug!(query = %query, limit = %limit, "search_products command");
-
-    let db_inner: &Database = (*db).inner();
-
-    // Optimization: If query looks like a barcode, try exact lookup first
-    // This gives instant response for barcode scanners
-    if is_barcode_query(query) {
-        debug!(barcode = %query, "Detected barcode pattern, trying exact lookup");
-        if let Some(product) = db_inner.products().get_by_barcode(query).await? {
-            let elapsed = start.elapsed();
-            info!(
-                elapsed_ms = elapsed.as_secs_f64() * 1000.0,
-                count = 1,
-                "search_products barcode lookup"
-            );
-            return Ok(vec![ProductDto::from(product)]);
+    traced("search_products", async move {
+        let start = Instant::now();
+        let query = query.trim();
+        let limit = limit.unwrap_or(20).min(100);
+
+        debug!(query = %query, limit = %limit, "search_products command");
+
+        let db_inner: &Database = (*db).inner()?;
+
+        // Optimization: If query looks like a barcode, try exact lookup first
+        // This gives instant response for barcode scanners
+        if is_barcode_query(query) {
+            debug!(barcode = %query, "Detected barcode pattern, trying exact lookup");
+            if let Some(product) = db_inner.products().get_by_barcode(query).await? {
+                let elapsed = start.elapsed();
+                info!(
+                    elapsed_ms = elapsed.as_secs_f64() * 1000.0,
+                    count = 1,
+                    "search_products barcode lookup"
+                );
+                return Ok(vec![ProductDto::from(product)]);
+            }
+            // Barcode not found, fall through to FTS search
+            debug!("Barcode not found, falling back to FTS search");
         }
-        // Barcode not found, fall through to FTS search
-        debug!("Barcode not found, falling back to FTS search");
-    }
 
-    // Full-text search
-    let products = db_inner.products().search(query, limit).await?;
-    let dtos: Vec<ProductDto> = products.into_iter().map(ProductDto::from).collect();
+        // Full-text search
+        let products = db_inner.products().search(query, limit).await?;
+        let dtos: Vec<ProductDto> = products.into_iter().map(ProductDto::from).collect();
+
+        let elapsed = start.elapsed();
+        info!(
+            elapsed_ms = elapsed.as_secs_f64() * 1000.0,
+            count = dtos.len(),
+            query = %query,
+            "search_products FTS complete"
+        );
+
+        Ok(dtos)
+    })
+    .await
+}
 
-    let elapsed = start.elapsed();
-    info!(
-        elapsed_ms = elapsed.as_secs_f64() * 1000.0,
-        count = dtos.len(),
-        query = %query,
-        "search_products FTS complete"
-    );
+/// Searches or browses active products one page at a time.
+///
+/// For catalog screens that scroll through many results. Results are
+/// ordered by name, not relevance; the POS search box keeps using
+/// `search_products`.
+///
+/// ## Arguments
+/// * `query` - Search term (empty: the whole active catalog)
+/// * `cursor` - `nextCursor` of the previous page; omit for the first page
+/// * `limit` - Page size (default: 50, max: 500)
+/// * `include_total` - Also count every match
+///
+/// ## Errors
+/// `VALIDATION_ERROR` for a cursor from another list.
+#[tauri::command]
+pub async fn search_products_page(
+    db: State<'_, DbState>,
+    query: String,
+    cursor: Option<String>,
+    limit: Option<u32>,
+    include_total: Option<bool>,
+) -> Result<Page<ProductDto>, ApiError> {
+    traced("search_products_page", async move {
+        let request = PageRequest {
+            limit,
+            cursor,
+            include_total: include_total.unwrap_or(false),
+        };
+        debug!(query = %query, limit = request.page_size(), "search_products_page command");
 
-    Ok(dtos)
+        let db_inner: &Database = (*db).inner()?;
+        let page = db_inner.products().search_page(&query, &request).await?;
+        Ok(page.map(ProductDto::from))
+    })
+    .await
 }
 
 /// Gets a single product by its UUID.
@@ -193,14 +268,17 @@ pub async fn search_products(
 /// The product if found, or ApiError::NotFound
 #[tauri::command]
 pub async fn get_product_by_id(db: State<'_, DbState>, id: String) -> Result<ProductDto, ApiError> {
-    debug!(id = %id, "get_product_by_id command");
-    let db_inner: &Database = (*db).inner();
-    let product = db_inner
-        .products()
-        .get_by_id(&id)
-        .await?
-        .ok_or_else(|| ApiError::not_found("Product", &id))?;
-    Ok(ProductDto::from(product))
+    traced("get_product_by_id", async move {
+        debug!(id = %id, "get_product_by_id command");
+        let db_inner: &Database = (*db).inner()?;
+        let product = db_inner
+            .products()
+            .get_by_id(&id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Product", &id))?;
+        Ok(ProductDto::from(product))
+    })
+    .await
 }
 
 /// Gets a single product by its SKU.
@@ -219,12 +297,481 @@ pub async fn get_product_by_sku(
     db: State<'_, DbState>,
     sku: String,
 ) -> Result<ProductDto, ApiError> {
-    debug!(sku = %sku, "get_product_by_sku command");
-    let db_inner: &Database = (*db).inner();
-    let product = db_inner
+    traced("get_product_by_sku", async move {
+        debug!(sku = %sku, "get_product_by_sku command");
+        let db_inner: &Database = (*db).inner()?;
+        let product = db_inner
+            .products()
+            .get_by_sku(&sku)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Product", &sku))?;
+        Ok(ProductDto::from(product))
+    })
+    .await
+}
+
+/// Changes a product's name and price.
+///
+/// A new price is checked against the store's margin floor (see
+/// `ConfigState::margin_guardrail`); a manager's bypass of a blocking
+/// floor is written to the margin override audit trail. Only the changed
+/// fields sync to the other terminals (`PRODUCT_PATCH`).
+///
+/// ## Arguments
+/// * `name` - New name (unchanged if omitted)
+/// * `price_cents` - New price at this store (unchanged if omitted)
+/// * `bypass` - Manager approval, needed below a blocking floor
+///
+/// ## Errors
+/// - `MANAGER_OVERRIDE_REQUIRED` below a blocking floor without a bypass
+/// - `VALIDATION_ERROR` for an invalid name or a negative price, or a
+///   bypass without a manager or reason
+/// - `NOT_FOUND` if the product doesn't exist or is deleted
+/// - `CONFLICT` if the product changed while it was being edited
+#[tauri::command]
+pub async fn update_product(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    id: String,
+    name: Option<String>,
+    price_cents: Option<i64>,
+    bypass: Option<ManagerBypass>,
+) -> Result<ProductUpdateDto, ApiError> {
+    traced("update_product", async move {
+        debug!(id = %id, ?price_cents, "update_product command");
+        let db_inner: &Database = (*db).inner()?;
+        let product = db_inner
+            .products()
+            .get_by_id(&id)
+            .await?
+            .filter(|p| p.deleted_at.is_none())
+            .ok_or_else(|| ApiError::not_found("Product", &id))?;
+        let old_price = product.price_cents;
+        let mut product = Tracked::new(product);
+
+        if let Some(name) = name {
+            validate_product_name(&name).map_err(CoreError::from)?;
+            product.get_mut().name = name.trim().to_string();
+        }
+
+        let mut margin = None;
+        if let Some(price) = price_cents.filter(|price| *price != old_price) {
+            validate_price_cents(price).map_err(CoreError::from)?;
+            let current = product.current();
+            let check = config.margin_guardrail.check(
+                &current.sku,
+                price,
+                current.cost_cents,
+                bypass.as_ref(),
+            )?;
+
+            // Audited before saving, so no bypass goes unrecorded
+            audit_bypass(
+                db_inner,
+                &config.station.device_id(),
+                PriceChangeKind::ProductPrice,
+                current,
+                old_price,
+                &check,
+                bypass.as_ref(),
+            )
+            .await?;
+            product.get_mut().price_cents = price;
+            margin = Some(check);
+        }
+
+        db_inner.products().update_tracked(&product).await?;
+
+        info!(id = %id, "Product updated");
+        Ok(ProductUpdateDto {
+            product: product_dto(db_inner, &id).await?,
+            margin,
+        })
+    })
+    .await
+}
+
+/// Deletes a product.
+///
+/// The product stays in the database for the sales that reference it
+/// but disappears from search and can no longer be sold. The delete
+/// syncs to the other terminals.
+///
+/// ## Errors
+/// `NOT_FOUND` if the product doesn't exist or is already deleted.
+#[tauri::command]
+pub async fn delete_product(db: State<'_, DbState>, id: String) -> Result<ProductDto, ApiError> {
+    traced("delete_product", async move {
+        debug!(id = %id, "delete_product command");
+        let db_inner: &Database = (*db).inner()?;
+        db_inner.products().soft_delete(&id).await?;
+        info!(id = %id, "Product deleted");
+        product_dto(db_inner, &id).await
+    })
+    .await
+}
+
+/// Restores a deleted product.
+///
+/// ## Errors
+/// `NOT_FOUND` if the product doesn't exist or isn't deleted.
+#[tauri::command]
+pub async fn restore_product(db: State<'_, DbState>, id: String) -> Result<ProductDto, ApiError> {
+    traced("restore_product", async move {
+        debug!(id = %id, "restore_product command");
+        let db_inner: &Database = (*db).inner()?;
+        db_inner.products().restore(&id).await?;
+        info!(id = %id, "Product restored");
+        product_dto(db_inner, &id).await
+    })
+    .await
+}
+
+/// Lists deleted products, most recently deleted first.
+#[tauri::command]
+pub async fn list_deleted_products(
+    db: State<'_, DbState>,
+    limit: Option<u32>,
+) -> Result<Vec<ProductDto>, ApiError> {
+    traced("list_deleted_products", async move {
+        let db_inner: &Database = (*db).inner()?;
+        let products = db_inner
+            .products()
+            .list_deleted(limit.unwrap_or(50).min(500))
+            .await?;
+        Ok(products.into_iter().map(ProductDto::from).collect())
+    })
+    .await
+}
+
+/// A product's attributes and tags.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductAttributesDto {
+    pub product_id: String,
+    /// Sorted by key; a tag has an empty value.
+    pub attributes: Vec<ProductAttribute>,
+    #[ts(type = "number")]
+    pub sync_version: i64,
+    pub updated_at: String,
+}
+
+impl From<ProductAttributes> for ProductAttributesDto {
+    fn from(doc: ProductAttributes) -> Self {
+        ProductAttributesDto {
+            product_id: doc.product_id,
+            attributes: doc.attributes,
+            sync_version: doc.sync_version,
+            updated_at: doc.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Gets a product's attributes and tags.
+///
+/// ## Errors
+/// `NOT_FOUND` if the product doesn't exist.
+#[tauri::command]
+pub async fn get_product_attributes(
+    db: State<'_, DbState>,
+    id: String,
+) -> Result<ProductAttributesDto, ApiError> {
+    traced("get_product_attributes", async move {
+        debug!(id = %id, "get_product_attributes command");
+        let db_inner: &Database = (*db).inner()?;
+        let doc = db_inner.products().attributes(&id).await?;
+        Ok(ProductAttributesDto::from(doc))
+    })
+    .await
+}
+
+/// Replaces a product's attributes and tags; the new set syncs to the
+/// other terminals.
+///
+/// Keys are normalized to lowercase. Pass an empty list to clear them.
+///
+/// ## Errors
+/// - `VALIDATION_ERROR` for an invalid or repeated key, or a value that
+///   is too long
+/// - `NOT_FOUND` if the product doesn't exist or is deleted
+#[tauri::command]
+pub async fn set_product_attributes(
+    db: State<'_, DbState>,
+    id: String,
+    attributes: Vec<ProductAttribute>,
+) -> Result<ProductAttributesDto, ApiError> {
+    traced("set_product_attributes", async move {
+        debug!(id = %id, count = attributes.len(), "set_product_attributes command");
+        let db_inner: &Database = (*db).inner()?;
+        let doc = db_inner.products().set_attributes(&id, &attributes).await?;
+        info!(id = %id, version = doc.sync_version, "Product attributes saved");
+        Ok(ProductAttributesDto::from(doc))
+    })
+    .await
+}
+
+/// Searches active products by attribute, ordered by name.
+///
+/// ## Arguments
+/// * `filter` - `key=value` (e.g. `color=red`, case-insensitive) or `key`
+///   for every product with the attribute or tag (e.g. `organic`)
+/// * `limit` - Maximum results to return (default: 50, max: 500)
+///
+/// ## Errors
+/// `VALIDATION_ERROR` for an invalid key.
+#[tauri::command]
+pub async fn search_products_by_attribute(
+    db: State<'_, DbState>,
+    filter: String,
+    limit: Option<u32>,
+) -> Result<Vec<ProductDto>, ApiError> {
+    traced("search_products_by_attribute", async move {
+        debug!(filter = %filter, "search_products_by_attribute command");
+        let filter = AttributeFilter::parse(&filter).map_err(CoreError::from)?;
+        let db_inner: &Database = (*db).inner()?;
+        let products = db_inner
+            .products()
+            .search_by_attribute(&filter, limit.unwrap_or(50).min(500))
+            .await?;
+        Ok(products.into_iter().map(ProductDto::from).collect())
+    })
+    .await
+}
+
+/// A variant of a style, as shown in the variant picker.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct VariantDto {
+    pub product: ProductDto,
+    /// Option values in the style's option key order.
+    pub options: Vec<String>,
+    /// The variant's own description, else the style's.
+    pub description: Option<String>,
+}
+
+/// A style with its variants and the data to display them as a grid.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct VariantGroupDto {
+    pub style: ProductStyle,
+    pub variants: Vec<VariantDto>,
+    pub matrix: VariantMatrix,
+    /// Lowest and highest variant price; `None` without variants.
+    #[ts(type = "number | null")]
+    pub min_price_cents: Option<i64>,
+    #[ts(type = "number | null")]
+    pub max_price_cents: Option<i64>,
+    /// Stock across the variants that track inventory.
+    #[ts(type = "number | null")]
+    pub total_stock: Option<i64>,
+}
+
+impl From<VariantGroup> for VariantGroupDto {
+    fn from(group: VariantGroup) -> Self {
+        let price_range = group.price_range_cents();
+        let variants = group
+            .variants
+            .iter()
+            .map(|variant| VariantDto {
+                product: ProductDto::from(variant.product.clone()),
+                options: variant.options.clone(),
+                description: group.description(variant).map(str::to_string),
+            })
+            .collect();
+
+        VariantGroupDto {
+            matrix: group.matrix(),
+            total_stock: group.total_stock(),
+            min_price_cents: price_range.map(|(min, _)| min),
+            max_price_cents: price_range.map(|(_, max)| max),
+            style: group.style,
+            variants,
+        }
+    }
+}
+
+/// One grouped search result: a product of its own, or a style whose
+/// variants matched.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ProductSearchResultDto {
+    Product { product: ProductDto },
+    Style {
+        style: ProductStyle,
+        /// The variants that matched, in rank order.
+        matches: Vec<ProductDto>,
+    },
+}
+
+impl From<SearchResult> for ProductSearchResultDto {
+    fn from(result: SearchResult) -> Self {
+        match result {
+            SearchResult::Product { product } => ProductSearchResultDto::Product {
+                product: ProductDto::from(product),
+            },
+            SearchResult::Style { style, matches } => ProductSearchResultDto::Style {
+                style,
+                matches: matches.into_iter().map(ProductDto::from).collect(),
+            },
+        }
+    }
+}
+
+/// Creates a product style; variants are added with `set_product_variant`.
+///
+/// ## Arguments
+/// * `option_keys` - What the variants differ by, e.g. `["size", "color"]`
+///   (1 to 3 keys, normalized to lowercase)
+///
+/// ## Errors
+/// `VALIDATION_ERROR` for an empty name or invalid option keys.
+#[tauri::command]
+pub async fn create_product_style(
+    db: State<'_, DbState>,
+    name: String,
+    description: Option<String>,
+    option_keys: Vec<String>,
+) -> Result<VariantGroupDto, ApiError> {
+    traced("create_product_style", async move {
+        debug!(name = %name, "create_product_style command");
+        let db_inner: &Database = (*db).inner()?;
+        let style = db_inner
+            .styles()
+            .create(&name, description.as_deref(), &option_keys)
+            .await?;
+        Ok(VariantGroupDto::from(VariantGroup::new(style, Vec::new())))
+    })
+    .await
+}
+
+/// Changes a style's shared name and description.
+///
+/// ## Errors
+/// - `VALIDATION_ERROR` for an empty name
+/// - `NOT_FOUND` if the style doesn't exist
+#[tauri::command]
+pub async fn update_product_style(
+    db: State<'_, DbState>,
+    id: String,
+    name: String,
+    description: Option<String>,
+) -> Result<VariantGroupDto, ApiError> {
+    traced("update_product_style", async move {
+        debug!(id = %id, "update_product_style command");
+        let db_inner: &Database = (*db).inner()?;
+        db_inner
+            .styles()
+            .update_details(&id, &name, description.as_deref())
+            .await?;
+        variant_group_dto(db_inner, &id).await
+    })
+    .await
+}
+
+/// Gets a style with its variants, price range, stock and matrix.
+///
+/// ## Errors
+/// `NOT_FOUND` if the style doesn't exist.
+#[tauri::command]
+pub async fn get_variant_group(
+    db: State<'_, DbState>,
+    style_id: String,
+) -> Result<VariantGroupDto, ApiError> {
+    traced("get_variant_group", async move {
+        debug!(style_id = %style_id, "get_variant_group command");
+        let db_inner: &Database = (*db).inner()?;
+        variant_group_dto(db_inner, &style_id).await
+    })
+    .await
+}
+
+/// Makes a product a variant of a style, or changes its option values.
+///
+/// The values are saved as the product's attributes; its own barcode and
+/// price are unchanged.
+///
+/// ## Errors
+/// - `VALIDATION_ERROR` for a missing value or a combination another
+///   variant already has
+/// - `NOT_FOUND` if the style or product doesn't exist
+#[tauri::command]
+pub async fn set_product_variant(
+    db: State<'_, DbState>,
+    style_id: String,
+    product_id: String,
+    options: Vec<String>,
+) -> Result<VariantGroupDto, ApiError> {
+    traced("set_product_variant", async move {
+        debug!(style_id = %style_id, product_id = %product_id, "set_product_variant command");
+        let db_inner: &Database = (*db).inner()?;
+        let group = db_inner
+            .styles()
+            .add_variant(&style_id, &product_id, &options)
+            .await?;
+        Ok(VariantGroupDto::from(group))
+    })
+    .await
+}
+
+/// Detaches a variant from its style; it stays a product of its own.
+///
+/// ## Errors
+/// `NOT_FOUND` if the product is not a variant.
+#[tauri::command]
+pub async fn remove_product_variant(
+    db: State<'_, DbState>,
+    product_id: String,
+) -> Result<(), ApiError> {
+    traced("remove_product_variant", async move {
+        info!(product_id = %product_id, "remove_product_variant command");
+        let db_inner: &Database = (*db).inner()?;
+        db_inner.styles().remove_variant(&product_id).await?;
+        Ok(())
+    })
+    .await
+}
+
+/// Searches products like `search_products`, listing the variants of a
+/// style as one result.
+///
+/// ## Arguments
+/// * `limit` - Maximum products to match (default: 50, max: 500)
+#[tauri::command]
+pub async fn search_products_grouped(
+    db: State<'_, DbState>,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<ProductSearchResultDto>, ApiError> {
+    traced("search_products_grouped", async move {
+        debug!(query = %query, "search_products_grouped command");
+        let db_inner: &Database = (*db).inner()?;
+        let results = db_inner
+            .styles()
+            .search_grouped(&query, limit.unwrap_or(50).min(500))
+            .await?;
+        Ok(results.into_iter().map(ProductSearchResultDto::from).collect())
+    })
+    .await
+}
+
+async fn variant_group_dto(db: &Database, style_id: &str) -> Result<VariantGroupDto, ApiError> {
+    let group = db
+        .styles()
+        .group(style_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("ProductStyle", style_id))?;
+    Ok(VariantGroupDto::from(group))
+}
+
+async fn product_dto(db: &Database, id: &str) -> Result<ProductDto, ApiError> {
+    let product = db
         .products()
-        .get_by_sku(&sku)
+        .get_by_id(id)
         .await?
-        .ok_or_else(|| ApiError::not_found("Product", &sku))?;
+        .ok_or_else(|| ApiError::not_found("Product", id))?;
     Ok(ProductDto::from(product))
 }
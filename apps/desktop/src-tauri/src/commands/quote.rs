@@ -0,0 +1,474 @@
+//! # Quote Commands
+//!
+//! Save the current cart as a customer quote, look it up (on any terminal,
+//! via sync), print/email it, and convert it into a sale at quoted prices.
+//!
+//! ## Quote Workflow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Quote Workflow                                  │
+//! │                                                                         │
+//! │  Build cart ──► save_quote ──► render_quote ──► print / email          │
+//! │                    │                                                    │
+//! │                    │ (queued as QUOTE → other terminals)               │
+//! │                    ▼                                                    │
+//! │  Customer returns (any terminal)                                       │
+//! │    get_quote("Q-...") ──► convert_quote_to_sale ──► add_payment ──►    │
+//! │                                                     finalize_sale       │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{debug, info};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::commands::sale::{generate_receipt_number, CreateSaleResponse};
+use crate::error::{ApiError, ErrorCode};
+use crate::middleware::traced;
+use crate::state::{training_mode, CartState, ConfigState, DbState};
+use titan_core::quote::{quote_valid_until, validate_customer_email};
+use titan_core::{
+    CoreError, Quote, QuoteDocument, QuoteItem, QuoteStatus, Sale, SaleItem, SaleStatus,
+};
+use titan_db::Database;
+
+/// Cashier ID used for quotes (matches the sale commands).
+const USER_ID: &str = "default";
+
+/// Default page size for `list_quotes`.
+const DEFAULT_LIST_LIMIT: u32 = 50;
+
+/// Line width of the printable quote document.
+const DOCUMENT_WIDTH: usize = 42;
+
+/// A quote line as shown in the UI.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteItemDto {
+    pub product_id: String,
+    pub sku: String,
+    pub name: String,
+    #[ts(type = "number")]
+    pub quantity: i64,
+    #[ts(type = "number")]
+    pub unit_price_cents: i64,
+    #[ts(type = "number")]
+    pub line_total_cents: i64,
+    #[ts(type = "number")]
+    pub tax_cents: i64,
+}
+
+impl From<QuoteItem> for QuoteItemDto {
+    fn from(i: QuoteItem) -> Self {
+        QuoteItemDto {
+            product_id: i.product_id,
+            sku: i.sku_snapshot,
+            name: i.name_snapshot,
+            quantity: i.quantity,
+            unit_price_cents: i.unit_price_cents,
+            line_total_cents: i.line_total_cents,
+            tax_cents: i.tax_cents,
+        }
+    }
+}
+
+/// A quote as shown in the UI.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteDto {
+    pub id: String,
+    pub quote_number: String,
+    pub status: String,
+    pub is_expired: bool,
+    pub customer_name: Option<String>,
+    pub customer_email: Option<String>,
+    #[ts(type = "number")]
+    pub subtotal_cents: i64,
+    #[ts(type = "number")]
+    pub tax_cents: i64,
+    #[ts(type = "number")]
+    pub total_cents: i64,
+    pub notes: Option<String>,
+    pub valid_until: String,
+    pub converted_sale_id: Option<String>,
+    pub created_at: String,
+    /// Empty in list views.
+    pub items: Vec<QuoteItemDto>,
+}
+
+impl QuoteDto {
+    fn new(quote: Quote, items: Vec<QuoteItem>) -> Self {
+        QuoteDto {
+            is_expired: quote.is_expired(Utc::now()),
+            status: format!("{:?}", quote.status).to_lowercase(),
+            id: quote.id,
+            quote_number: quote.quote_number,
+            customer_name: quote.customer_name,
+            customer_email: quote.customer_email,
+            subtotal_cents: quote.subtotal_cents,
+            tax_cents: quote.tax_cents,
+            total_cents: quote.total_cents,
+            notes: quote.notes,
+            valid_until: quote.valid_until.to_rfc3339(),
+            converted_sale_id: quote.converted_sale_id,
+            created_at: quote.created_at.to_rfc3339(),
+            items: items.into_iter().map(QuoteItemDto::from).collect(),
+        }
+    }
+}
+
+/// A quote rendered for printing or emailing.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteDocumentDto {
+    pub quote_number: String,
+    /// Customer email, if known (the frontend opens the mail client).
+    pub recipient: Option<String>,
+    pub subject: String,
+    /// Plain-text document, one line per printed line.
+    pub body: String,
+}
+
+/// Saves the current cart as a quote and clears the cart.
+#[tauri::command]
+pub async fn save_quote(
+    db: State<'_, DbState>,
+    cart: State<'_, CartState>,
+    config: State<'_, ConfigState>,
+    customer_name: Option<String>,
+    customer_email: Option<String>,
+    validity_days: Option<i64>,
+    notes: Option<String>,
+) -> Result<QuoteDto, ApiError> {
+    traced("save_quote", async move {
+        debug!(validity_days = ?validity_days, "save_quote command");
+
+        let items = cart.with_cart(|c| c.items.clone());
+        if items.is_empty() {
+            return Err(ApiError::validation("Cart is empty"));
+        }
+
+        let customer_email = customer_email
+            .map(|e| e.trim().to_string())
+            .filter(|e| !e.is_empty());
+        if let Some(ref email) = customer_email {
+            validate_customer_email(email).map_err(CoreError::from)?;
+        }
+
+        let now = Utc::now();
+        let valid_until = quote_valid_until(now, validity_days).map_err(CoreError::from)?;
+        let quote_id = Uuid::new_v4().to_string();
+
+        let quote_items: Vec<QuoteItem> = items
+            .iter()
+            .map(|i| QuoteItem {
+                id: Uuid::new_v4().to_string(),
+                quote_id: quote_id.clone(),
+                product_id: i.sale_product_id(),
+                sku_snapshot: i.sku.clone(),
+                name_snapshot: i.name.clone(),
+                unit_price_cents: i.unit_price_cents,
+                tax_rate_bps: i.tax_rate_bps,
+                quantity: i.quantity,
+                line_total_cents: i.line_total_cents(),
+                tax_cents: i.tax_cents(),
+                created_at: now,
+            })
+            .collect();
+
+        let subtotal: i64 = quote_items.iter().map(|i| i.line_total_cents).sum();
+        let tax: i64 = quote_items.iter().map(|i| i.tax_cents).sum();
+
+        let doc = QuoteDocument {
+            quote: Quote {
+                id: quote_id.clone(),
+                tenant_id: config.tenant_id.clone(),
+                quote_number: generate_quote_number(),
+                status: QuoteStatus::Open,
+                customer_name: customer_name
+                    .map(|n| n.trim().to_string())
+                    .filter(|n| !n.is_empty()),
+                customer_email,
+                subtotal_cents: subtotal,
+                tax_cents: tax,
+                discount_cents: 0,
+                total_cents: subtotal + tax,
+                user_id: USER_ID.to_string(),
+                device_id: config.station.device_id(),
+                notes,
+                valid_until,
+                converted_sale_id: None,
+                created_at: now,
+                updated_at: now,
+                sync_version: 1,
+            },
+            items: quote_items,
+        };
+
+        let db_inner: &Database = (*db).inner()?;
+        db_inner.quotes().insert(&doc).await?;
+        queue_quote(db_inner, &doc).await?;
+
+        cart.with_cart_mut(|c| c.clear());
+
+        info!(quote_id = %quote_id, quote_number = %doc.quote.quote_number, "Quote saved");
+
+        Ok(QuoteDto::new(doc.quote, doc.items))
+    })
+    .await
+}
+
+/// Lists recent quotes, optionally filtered by status.
+#[tauri::command]
+pub async fn list_quotes(
+    db: State<'_, DbState>,
+    status: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<QuoteDto>, ApiError> {
+    traced("list_quotes", async move {
+        debug!(status = ?status, "list_quotes command");
+
+        let status = match status.as_deref() {
+            None => None,
+            Some("open") => Some(QuoteStatus::Open),
+            Some("converted") => Some(QuoteStatus::Converted),
+            Some("cancelled") => Some(QuoteStatus::Cancelled),
+            Some(other) => {
+                return Err(ApiError::validation(format!("Unknown quote status: {}", other)));
+            }
+        };
+
+        let db_inner: &Database = (*db).inner()?;
+        let quotes = db_inner
+            .quotes()
+            .list(status, limit.unwrap_or(DEFAULT_LIST_LIMIT))
+            .await?;
+
+        Ok(quotes.into_iter().map(|q| QuoteDto::new(q, Vec::new())).collect())
+    })
+    .await
+}
+
+/// Gets a quote with its items by ID or printed quote number.
+#[tauri::command]
+pub async fn get_quote(db: State<'_, DbState>, quote_ref: String) -> Result<QuoteDto, ApiError> {
+    traced("get_quote", async move {
+        debug!(quote_ref = %quote_ref, "get_quote command");
+
+        let db_inner: &Database = (*db).inner()?;
+        let doc = load_document(db_inner, &quote_ref).await?;
+
+        Ok(QuoteDto::new(doc.quote, doc.items))
+    })
+    .await
+}
+
+/// Cancels an open quote.
+#[tauri::command]
+pub async fn cancel_quote(db: State<'_, DbState>, quote_id: String) -> Result<QuoteDto, ApiError> {
+    traced("cancel_quote", async move {
+        debug!(quote_id = %quote_id, "cancel_quote command");
+
+        let db_inner: &Database = (*db).inner()?;
+        db_inner.quotes().cancel(&quote_id).await?;
+
+        let doc = load_document(db_inner, &quote_id).await?;
+        queue_quote(db_inner, &doc).await?;
+
+        info!(quote_id = %quote_id, "Quote cancelled");
+
+        Ok(QuoteDto::new(doc.quote, doc.items))
+    })
+    .await
+}
+
+/// Renders a quote as plain text for printing or emailing.
+#[tauri::command]
+pub async fn render_quote(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    quote_id: String,
+) -> Result<QuoteDocumentDto, ApiError> {
+    traced("render_quote", async move {
+        debug!(quote_id = %quote_id, "render_quote command");
+
+        let db_inner: &Database = (*db).inner()?;
+        let doc = load_document(db_inner, &quote_id).await?;
+
+        Ok(QuoteDocumentDto {
+            quote_number: doc.quote.quote_number.clone(),
+            recipient: doc.quote.customer_email.clone(),
+            subject: format!("Quote {} from {}", doc.quote.quote_number, config.store_name),
+            body: render_text(&config, &doc),
+        })
+    })
+    .await
+}
+
+/// Converts an open, unexpired quote into a draft sale at the quoted prices.
+///
+/// The returned sale is paid and finalized with `add_payment` and
+/// `finalize_sale`, exactly like a sale created from the cart.
+#[tauri::command]
+pub async fn convert_quote_to_sale(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    quote_id: String,
+) -> Result<CreateSaleResponse, ApiError> {
+    traced("convert_quote_to_sale", async move {
+        debug!(quote_id = %quote_id, "convert_quote_to_sale command");
+
+        let db_inner: &Database = (*db).inner()?;
+        let doc = load_document(db_inner, &quote_id).await?;
+
+        let now = Utc::now();
+        doc.quote.ensure_convertible(now)?;
+
+        let sale_id = Uuid::new_v4().to_string();
+        let sale = Sale {
+            id: sale_id.clone(),
+            tenant_id: config.tenant_id.clone(),
+            receipt_number: config.station.receipt_number(&generate_receipt_number()),
+            status: SaleStatus::Draft,
+            subtotal_cents: doc.quote.subtotal_cents,
+            tax_cents: doc.quote.tax_cents,
+            discount_cents: doc.quote.discount_cents,
+            total_cents: doc.quote.total_cents,
+            user_id: USER_ID.to_string(),
+            device_id: config.station.device_id(),
+            station_number: config.station.sale_station(),
+            notes: Some(format!("From quote {}", doc.quote.quote_number)),
+            created_at: now,
+            updated_at: now,
+            completed_at: None,
+            sync_version: 0,
+        };
+
+        let sale_items: Vec<SaleItem> = doc
+            .items
+            .iter()
+            .map(|i| SaleItem {
+                id: Uuid::new_v4().to_string(),
+                sale_id: sale_id.clone(),
+                product_id: i.product_id.clone(),
+                sku_snapshot: i.sku_snapshot.clone(),
+                name_snapshot: i.name_snapshot.clone(),
+                unit_price_cents: i.unit_price_cents,
+                quantity: i.quantity,
+                line_total_cents: i.line_total_cents,
+                tax_cents: i.tax_cents,
+                discount_cents: 0,
+                base_price_cents: None,
+                tax_rate_bps: Some(i.tax_rate_bps),
+                created_at: now,
+            })
+            .collect();
+
+        db_inner
+            .quotes()
+            .convert_to_sale(&doc.quote.id, &sale, &sale_items)
+            .await?;
+
+        let converted = load_document(db_inner, &doc.quote.id).await?;
+        queue_quote(db_inner, &converted).await?;
+
+        info!(quote_id = %doc.quote.id, sale_id = %sale_id, "Quote converted");
+
+        Ok(CreateSaleResponse {
+            sale_id,
+            total_cents: sale.total_cents,
+            item_count: sale_items.len(),
+            training_mode: training_mode(),
+        })
+    })
+    .await
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Loads a quote document by ID, falling back to the printed quote number.
+async fn load_document(db: &Database, quote_ref: &str) -> Result<QuoteDocument, ApiError> {
+    if let Some(doc) = db.quotes().get_document(quote_ref).await? {
+        return Ok(doc);
+    }
+
+    let quote = db
+        .quotes()
+        .get_by_number(quote_ref)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Quote", quote_ref))?;
+    let items = db.quotes().get_items(&quote.id).await?;
+
+    Ok(QuoteDocument { quote, items })
+}
+
+/// Queues the latest state of a quote for other terminals.
+async fn queue_quote(db: &Database, doc: &QuoteDocument) -> Result<(), ApiError> {
+    let payload = serde_json::to_string(doc)
+        .map_err(|e| ApiError::new(ErrorCode::Internal, e.to_string()))?;
+    db.sync_outbox()
+        .upsert_for_sync("QUOTE", &doc.quote.id, &payload)
+        .await?;
+    Ok(())
+}
+
+/// Formats a quote as a fixed-width plain-text document.
+fn render_text(config: &ConfigState, doc: &QuoteDocument) -> String {
+    let quote = &doc.quote;
+    let rule = "-".repeat(DOCUMENT_WIDTH);
+    let mut lines = Vec::new();
+
+    lines.push(config.store_name.clone());
+    lines.extend(config.store_address.iter().cloned());
+    lines.push(rule.clone());
+    lines.push(format!("QUOTE {}", quote.quote_number));
+    lines.push(format!("Date: {}", quote.created_at.format("%Y-%m-%d")));
+    lines.push(format!("Valid until: {}", quote.valid_until.format("%Y-%m-%d")));
+    if let Some(ref name) = quote.customer_name {
+        lines.push(format!("Customer: {}", name));
+    }
+    lines.push(rule.clone());
+
+    for item in &doc.items {
+        lines.push(item.name_snapshot.clone());
+        lines.push(two_column(
+            &format!(
+                "  {} x {}",
+                item.quantity,
+                config.format_currency(item.unit_price_cents)
+            ),
+            &config.format_currency(item.line_total_cents),
+        ));
+    }
+
+    lines.push(rule.clone());
+    lines.push(two_column("Subtotal", &config.format_currency(quote.subtotal_cents)));
+    lines.push(two_column("Tax", &config.format_currency(quote.tax_cents)));
+    lines.push(two_column("TOTAL", &config.format_currency(quote.total_cents)));
+    if let Some(ref notes) = quote.notes {
+        lines.push(rule);
+        lines.push(notes.clone());
+    }
+    lines.push(String::new());
+    lines.push("Prices honoured until the date above.".to_string());
+
+    lines.join("\n")
+}
+
+/// Left/right aligned line padded to the document width.
+fn two_column(left: &str, right: &str) -> String {
+    let pad = DOCUMENT_WIDTH.saturating_sub(left.chars().count() + right.chars().count());
+    format!("{}{}{}", left, " ".repeat(pad.max(1)), right)
+}
+
+fn generate_quote_number() -> String {
+    format!("Q-{}", generate_receipt_number())
+}
@@ -0,0 +1,300 @@
+//! # Receipt Commands
+//!
+//! Printing a completed sale's receipt again, in any of its variants, and
+//! the footer campaigns the cloud sets for the customer's receipt.
+//!
+//! ## Commands
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Receipt Commands                                │
+//! │                                                                         │
+//! │  print_receipt_variant   original / gift / reprint / refund receipt     │
+//! │                                                                         │
+//! │  sale document + refunds + fiscal signature                             │
+//! │        │                                                                │
+//! │        ▼                                                                │
+//! │  titan_core::receipt::render_receipt(variant) ──► text for the printer  │
+//! │  titan_core::receipt::receipt_barcode ──► receipt-number barcode image  │
+//! │  original only: cached campaigns ──► render_campaign_footer ──► footer  │
+//! │                                                                         │
+//! │  get_receipt_campaigns       the cached footer campaigns                │
+//! │  refresh_receipt_campaigns   fetch them from the cloud store config     │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! The frontend sends `document` to the receipt printer as raw text, like
+//! label print jobs, followed by the `barcode` bytes and then the `footer`
+//! bytes. A sale fetched back from the hub (see `fetch_sale_by_receipt`)
+//! prints the same way.
+//!
+//! Campaigns are picked by the time the sale completed, not the time of
+//! printing, and a campaign that cannot be rendered leaves the footer
+//! empty rather than failing the receipt.
+
+use chrono::{Local, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{debug, info, warn};
+use ts_rs::TS;
+
+use crate::commands::sync::connect_cloud;
+use crate::commands::training::TRAINING_WATERMARK;
+use crate::error::{ApiError, ErrorCode};
+use crate::middleware::traced;
+use crate::state::{training_mode, ConfigState, DbState, SyncState};
+use titan_core::receipt::{
+    receipt_barcode, render_receipt, DEFAULT_RECEIPT_COLUMNS, MAX_RECEIPT_COLUMNS,
+    MIN_RECEIPT_COLUMNS,
+};
+use titan_core::receipt_campaign::{parse_campaigns, render_campaign_footer, ParsedCampaigns};
+use titan_core::{
+    CampaignFooter, CoreError, IssuedCoupon, ReceiptCampaign, ReceiptData, ReceiptLine,
+    ReceiptVariant, SaleStatus,
+};
+use titan_db::Database;
+
+/// A rendered receipt, ready to send to the printer.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiptPrintJobDto {
+    pub sale_id: String,
+    pub receipt_number: String,
+    pub variant: ReceiptVariant,
+    /// Refund printed, for refund receipts.
+    pub refund_id: Option<String>,
+    /// Receipt text, one printer line per line.
+    pub document: String,
+    /// ESC/POS image of the receipt number as a Code 128 barcode, printed
+    /// after `document` and scanned to find the sale for a refund.
+    pub barcode: Vec<u8>,
+    /// ESC/POS footer campaigns (survey, coupon, messages), printed after
+    /// `barcode`; empty for every variant but the original.
+    pub footer: Vec<u8>,
+    /// Next-purchase coupons printed in `footer`.
+    pub coupons: Vec<IssuedCoupon>,
+}
+
+/// The terminal's receipt footer campaigns.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiptCampaignsDto {
+    /// In the order they are tried.
+    pub campaigns: Vec<ReceiptCampaign>,
+    /// When they were fetched (`null`: never, receipts have no campaigns).
+    pub fetched_at: Option<String>,
+    /// Campaigns from the cloud this build cannot print, with why.
+    pub skipped: Vec<String>,
+}
+
+/// Renders a receipt of a completed sale.
+///
+/// # Arguments
+/// * `sale_id` - Sale to print
+/// * `variant` - `original`, `gift` (no prices), `reprint` (marked
+///   REPRINT) or `refund`
+/// * `refund_id` - Refund a refund receipt is for (default: the latest)
+/// * `columns` - Characters per line (default 42, 32 to 64)
+///
+/// # Errors
+/// - `NOT_FOUND` for an unknown sale or refund
+/// - `VALIDATION_ERROR` for a sale that is not completed, a refund
+///   receipt of a sale without refunds, or a width out of range
+#[tauri::command]
+pub async fn print_receipt_variant(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    sale_id: String,
+    variant: ReceiptVariant,
+    refund_id: Option<String>,
+    columns: Option<u32>,
+) -> Result<ReceiptPrintJobDto, ApiError> {
+    traced("print_receipt_variant", async move {
+        debug!(sale_id = %sale_id, ?variant, "print_receipt_variant command");
+        let columns = columns.map_or(DEFAULT_RECEIPT_COLUMNS, |c| c as usize);
+        if !(MIN_RECEIPT_COLUMNS..=MAX_RECEIPT_COLUMNS).contains(&columns) {
+            return Err(ApiError::validation(format!(
+                "Receipt width must be {} to {} characters",
+                MIN_RECEIPT_COLUMNS, MAX_RECEIPT_COLUMNS
+            )));
+        }
+
+        let db_inner: &Database = (*db).inner()?;
+        let doc = db_inner
+            .sales()
+            .get_document(&sale_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Sale", &sale_id))?;
+        if doc.sale.status != SaleStatus::Completed {
+            return Err(ApiError::validation("Only completed sales have receipts"));
+        }
+
+        let refunds = db_inner.sales().get_refunds(&sale_id).await?;
+        let refunded_cents = refunds.iter().map(|r| r.amount_cents).sum();
+        let refund = match (variant, &refund_id) {
+            (ReceiptVariant::Refund, Some(id)) => Some(
+                refunds
+                    .iter()
+                    .find(|r| &r.id == id)
+                    .cloned()
+                    .ok_or_else(|| ApiError::not_found("SaleRefund", id))?,
+            ),
+            (ReceiptVariant::Refund, None) => Some(
+                refunds
+                    .last()
+                    .cloned()
+                    .ok_or_else(|| ApiError::validation("Sale has no refunds"))?,
+            ),
+            _ => None,
+        };
+        let fiscal_signature = db_inner
+            .sales()
+            .get_fiscal_signature(&sale_id)
+            .await?
+            .map(|s| s.signature);
+
+        let sale = doc.sale;
+        let sold_at = sale.completed_at.unwrap_or(sale.created_at);
+        let data = ReceiptData {
+            store_name: config.store_name.clone(),
+            receipt_number: sale.receipt_number.clone(),
+            station_number: sale.station_number,
+            sold_at: sold_at.with_timezone(&Local).fixed_offset(),
+            printed_at: Utc::now().with_timezone(&Local).fixed_offset(),
+            lines: doc
+                .items
+                .into_iter()
+                .map(|i| ReceiptLine {
+                    name: i.name_snapshot,
+                    sku: i.sku_snapshot,
+                    quantity: i.quantity,
+                    unit_price_cents: i.unit_price_cents,
+                    line_total_cents: i.line_total_cents,
+                })
+                .collect(),
+            subtotal_cents: sale.subtotal_cents,
+            tax_cents: sale.tax_cents,
+            total_cents: sale.total_cents,
+            payments: doc
+                .payments
+                .iter()
+                .map(|p| (p.method, p.amount_cents))
+                .collect(),
+            change_cents: doc.payments.iter().filter_map(|p| p.change_cents).sum(),
+            refund,
+            refunded_cents,
+            fiscal_signature,
+            watermark: training_mode().then(|| TRAINING_WATERMARK.to_string()),
+        };
+        let document = render_receipt(variant, &data, columns).map_err(CoreError::from)?;
+        let barcode = receipt_barcode(&sale.receipt_number, columns).map_err(CoreError::from)?;
+        let footer = if variant == ReceiptVariant::Original {
+            let (campaigns, _) = db_inner.receipt_campaigns().load().await?;
+            render_campaign_footer(&campaigns, &data, sold_at, columns).unwrap_or_else(|e| {
+                warn!(error = %e, "Receipt campaigns could not be rendered; printing none");
+                CampaignFooter::default()
+            })
+        } else {
+            CampaignFooter::default()
+        };
+
+        info!(
+            sale_id = %sale.id,
+            receipt = %sale.receipt_number,
+            ?variant,
+            campaigns = footer.campaign_ids.len(),
+            "Receipt rendered"
+        );
+        Ok(ReceiptPrintJobDto {
+            sale_id: sale.id,
+            receipt_number: sale.receipt_number,
+            variant,
+            refund_id: data.refund.map(|r| r.id),
+            document,
+            barcode,
+            footer: footer.escpos,
+            coupons: footer.coupons,
+        })
+    })
+    .await
+}
+
+/// Gets the cached receipt footer campaigns.
+#[tauri::command]
+pub async fn get_receipt_campaigns(
+    db: State<'_, DbState>,
+) -> Result<ReceiptCampaignsDto, ApiError> {
+    traced("get_receipt_campaigns", async move {
+        let db_inner: &Database = (*db).inner()?;
+        let (campaigns, fetched_at) = db_inner.receipt_campaigns().load().await?;
+        Ok(ReceiptCampaignsDto {
+            campaigns,
+            fetched_at: fetched_at.map(|t| t.to_rfc3339()),
+            skipped: Vec::new(),
+        })
+    })
+    .await
+}
+
+/// Fetches the receipt footer campaigns from the cloud store configuration
+/// and caches the ones this build can print.
+///
+/// # Errors
+/// - `CLOUD_ERROR` if the cloud could not be reached (the cache is kept)
+/// - `VALIDATION_ERROR` if the cloud value is not a JSON array
+#[tauri::command]
+pub async fn refresh_receipt_campaigns(
+    db: State<'_, DbState>,
+    sync: State<'_, SyncState>,
+    config: State<'_, ConfigState>,
+) -> Result<ReceiptCampaignsDto, ApiError> {
+    traced("refresh_receipt_campaigns", async move {
+        let sync_config = sync
+            .get_config()
+            .ok_or_else(|| ApiError::internal("Sync is not configured"))?;
+        let json = fetch_campaigns(&sync_config, &config).await.map_err(|e| {
+            ApiError::new(
+                ErrorCode::CloudError,
+                format!("Receipt campaign refresh failed: {}", e),
+            )
+        })?;
+        let ParsedCampaigns { campaigns, skipped } =
+            parse_campaigns(&json).map_err(CoreError::from)?;
+        for (id, reason) in &skipped {
+            warn!(campaign = %id, %reason, "Skipping receipt campaign");
+        }
+
+        let db_inner: &Database = (*db).inner()?;
+        db_inner.receipt_campaigns().save(&campaigns).await?;
+        info!(
+            campaigns = campaigns.len(),
+            skipped = skipped.len(),
+            "Receipt campaigns refreshed"
+        );
+        Ok(ReceiptCampaignsDto {
+            campaigns,
+            fetched_at: Some(Utc::now().to_rfc3339()),
+            skipped: skipped
+                .into_iter()
+                .map(|(id, reason)| format!("{}: {}", id, reason))
+                .collect(),
+        })
+    })
+    .await
+}
+
+/// The store's `receipt_campaigns` config value.
+async fn fetch_campaigns(
+    sync_config: &titan_sync::SyncConfig,
+    config: &ConfigState,
+) -> titan_sync::SyncResult<String> {
+    let mut uplink = connect_cloud(sync_config, config).await?;
+    let response = uplink.get_store_config().await;
+    uplink.disconnect().await;
+
+    Ok(response?
+        .config
+        .map(|c| c.receipt_campaigns)
+        .unwrap_or_default())
+}
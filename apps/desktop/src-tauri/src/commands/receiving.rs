@@ -0,0 +1,317 @@
+//! # Receiving Commands
+//!
+//! Pack sizes per product and receiving goods by the case or by the each
+//! (see `titan_core::pack`). Stock is always kept in eaches; stock levels
+//! and receipts can be reported in either unit.
+//!
+//! ## Receiving a Delivery
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                    Receiving Flow                                       │
+//! │                                                                         │
+//! │  scan case barcode ──► find_pack_by_barcode ──► COKE-330, case = 24    │
+//! │                                                                         │
+//! │  receive_stock("DN-1042", [{ COKE-330, "case", 3 },                     │
+//! │                            { COKE-330, "each", 4 }])                    │
+//! │        │                                                                │
+//! │        ▼                                                                │
+//! │  stock + 76, receipt keeps "3 case (24)" and "4 each"                  │
+//! │                                                                         │
+//! │  get_stock_levels([COKE-330], "case") ──► "3 case + 5 each"            │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{debug, info};
+use ts_rs::TS;
+
+use crate::error::ApiError;
+use crate::middleware::traced;
+use crate::state::{ConfigState, DbState};
+use titan_core::pack::EACH;
+use titan_core::{
+    CoreError, PackQuantity, ProductPack, ProductPacks, ReceivingLine, StockReceipt,
+    StockReceiptLine,
+};
+use titan_db::Database;
+
+/// Cashier ID recorded on receipts (matches the sale commands).
+const USER_ID: &str = "default";
+
+/// A product's pack sizes.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductPacksDto {
+    pub product_id: String,
+    /// Smallest pack first; empty for a product received by the each.
+    pub packs: Vec<ProductPack>,
+    #[ts(type = "number")]
+    pub sync_version: i64,
+    pub updated_at: String,
+}
+
+impl From<ProductPacks> for ProductPacksDto {
+    fn from(doc: ProductPacks) -> Self {
+        ProductPacksDto {
+            product_id: doc.product_id,
+            packs: doc.packs,
+            sync_version: doc.sync_version,
+            updated_at: doc.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+/// The product and pack a scanned case barcode belongs to.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct PackLookupDto {
+    pub product_id: String,
+    pub pack: ProductPack,
+}
+
+/// A stock receipt as returned to the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct StockReceiptDto {
+    pub id: String,
+    pub reference: Option<String>,
+    pub supplier_id: Option<String>,
+    pub lines: Vec<StockReceiptLine>,
+    /// Eaches received across all lines.
+    #[ts(type = "number")]
+    pub total_units: i64,
+    pub received_by: String,
+    pub received_at: String,
+}
+
+impl From<StockReceipt> for StockReceiptDto {
+    fn from(receipt: StockReceipt) -> Self {
+        StockReceiptDto {
+            total_units: receipt.total_units(),
+            id: receipt.id,
+            reference: receipt.reference,
+            supplier_id: receipt.supplier_id,
+            lines: receipt.lines,
+            received_by: receipt.received_by,
+            received_at: receipt.received_at.to_rfc3339(),
+        }
+    }
+}
+
+/// A product's stock in eaches and in the requested unit.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct StockLevelDto {
+    pub product_id: String,
+    pub sku: String,
+    pub name: String,
+    /// Stock in eaches; `None` if not counted.
+    #[ts(type = "number | null")]
+    pub units: Option<i64>,
+    /// Stock in the requested unit (eaches if the product has no such
+    /// pack).
+    pub quantity: Option<PackQuantity>,
+    /// e.g. "3 case + 5 each".
+    pub display: Option<String>,
+}
+
+/// Gets a product's pack sizes.
+///
+/// ## Errors
+/// `NOT_FOUND` if the product doesn't exist.
+#[tauri::command]
+pub async fn get_product_packs(
+    db: State<'_, DbState>,
+    id: String,
+) -> Result<ProductPacksDto, ApiError> {
+    traced("get_product_packs", async move {
+        debug!(id = %id, "get_product_packs command");
+        let db_inner: &Database = (*db).inner()?;
+        let packs = db_inner.packs().get(&id).await?;
+        Ok(ProductPacksDto::from(packs))
+    })
+    .await
+}
+
+/// Replaces a product's pack sizes; the new set syncs to the other
+/// terminals. Pass an empty list to receive it by the each only.
+///
+/// ## Errors
+/// - `VALIDATION_ERROR` for an invalid, reserved or repeated name, or a
+///   size out of range or used twice
+/// - `NOT_FOUND` if the product doesn't exist or is deleted
+#[tauri::command]
+pub async fn set_product_packs(
+    db: State<'_, DbState>,
+    id: String,
+    packs: Vec<ProductPack>,
+) -> Result<ProductPacksDto, ApiError> {
+    traced("set_product_packs", async move {
+        info!(id = %id, count = packs.len(), "set_product_packs command");
+        let db_inner: &Database = (*db).inner()?;
+        let packs = db_inner.packs().set_packs(&id, &packs).await?;
+        Ok(ProductPacksDto::from(packs))
+    })
+    .await
+}
+
+/// Looks up a scanned case barcode.
+///
+/// ## Errors
+/// `NOT_FOUND` if no pack has the barcode.
+#[tauri::command]
+pub async fn find_pack_by_barcode(
+    db: State<'_, DbState>,
+    barcode: String,
+) -> Result<PackLookupDto, ApiError> {
+    traced("find_pack_by_barcode", async move {
+        debug!(barcode = %barcode, "find_pack_by_barcode command");
+        let db_inner: &Database = (*db).inner()?;
+        let (product_id, pack) = db_inner
+            .packs()
+            .find_by_barcode(barcode.trim())
+            .await?
+            .ok_or_else(|| ApiError::not_found("Pack", &barcode))?;
+        Ok(PackLookupDto { product_id, pack })
+    })
+    .await
+}
+
+/// Receives goods into stock, each line in eaches or one of the
+/// product's packs.
+///
+/// ## Arguments
+/// * `reference` - Delivery note or supplier invoice number
+/// * `supplier_id` - Supplier the goods came from; its listed costs apply
+///   to lines without a cost
+/// * `lines` - What arrived, each optionally with its cost per unit; all
+///   lines are received or none
+///
+/// Costed lines update the products' last and average cost (see
+/// `get_product_cost`).
+///
+/// ## Errors
+/// - `VALIDATION_ERROR` for no lines, a quantity or cost out of range or a
+///   unit the product doesn't define
+/// - `NOT_FOUND` if the supplier or a product doesn't exist, or the
+///   product is deleted
+#[tauri::command]
+pub async fn receive_stock(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    reference: Option<String>,
+    supplier_id: Option<String>,
+    lines: Vec<ReceivingLine>,
+) -> Result<StockReceiptDto, ApiError> {
+    traced("receive_stock", async move {
+        info!(reference = ?reference, supplier_id = ?supplier_id, lines = lines.len(), "receive_stock command");
+        let db_inner: &Database = (*db).inner()?;
+        let receipt = db_inner
+            .packs()
+            .receive(reference.as_deref(), supplier_id.as_deref(), &lines, USER_ID, &config.station.device_id())
+            .await?;
+        Ok(StockReceiptDto::from(receipt))
+    })
+    .await
+}
+
+/// Gets a stock receipt.
+///
+/// ## Errors
+/// `NOT_FOUND` if the receipt doesn't exist.
+#[tauri::command]
+pub async fn get_stock_receipt(
+    db: State<'_, DbState>,
+    id: String,
+) -> Result<StockReceiptDto, ApiError> {
+    traced("get_stock_receipt", async move {
+        debug!(id = %id, "get_stock_receipt command");
+        let db_inner: &Database = (*db).inner()?;
+        let receipt = db_inner
+            .packs()
+            .get_receipt(&id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Stock receipt", &id))?;
+        Ok(StockReceiptDto::from(receipt))
+    })
+    .await
+}
+
+/// Lists recent stock receipts, newest first.
+///
+/// ## Arguments
+/// * `limit` - Maximum receipts to return (default: 50, max: 500)
+#[tauri::command]
+pub async fn list_stock_receipts(
+    db: State<'_, DbState>,
+    limit: Option<u32>,
+) -> Result<Vec<StockReceiptDto>, ApiError> {
+    traced("list_stock_receipts", async move {
+        let db_inner: &Database = (*db).inner()?;
+        let receipts = db_inner
+            .packs()
+            .recent_receipts(limit.unwrap_or(50).min(500))
+            .await?;
+        Ok(receipts.into_iter().map(StockReceiptDto::from).collect())
+    })
+    .await
+}
+
+/// Reports stock of the given products in eaches and in `unit`.
+///
+/// ## Arguments
+/// * `unit` - "each", a pack name, or omitted for each product's largest
+///   pack; products without the pack are reported in eaches
+///
+/// ## Errors
+/// `NOT_FOUND` if a product doesn't exist.
+#[tauri::command]
+pub async fn get_stock_levels(
+    db: State<'_, DbState>,
+    product_ids: Vec<String>,
+    unit: Option<String>,
+) -> Result<Vec<StockLevelDto>, ApiError> {
+    traced("get_stock_levels", async move {
+        debug!(count = product_ids.len(), unit = ?unit, "get_stock_levels command");
+        let db_inner: &Database = (*db).inner()?;
+
+        let mut levels = Vec::with_capacity(product_ids.len());
+        for id in &product_ids {
+            let product = db_inner
+                .products()
+                .get_by_id(id)
+                .await?
+                .ok_or_else(|| ApiError::not_found("Product", id))?;
+            let packs = db_inner.packs().get(id).await?;
+
+            let unit = match unit.as_deref() {
+                Some(unit) if packs.get(unit).is_some() => unit.to_string(),
+                Some(_) => EACH.to_string(),
+                None => packs
+                    .largest()
+                    .map_or_else(|| EACH.to_string(), |p| p.name.clone()),
+            };
+            let quantity = product
+                .current_stock
+                .map(|units| packs.in_unit(&unit, units))
+                .transpose()
+                .map_err(CoreError::from)?;
+
+            levels.push(StockLevelDto {
+                display: quantity.as_ref().map(ToString::to_string),
+                quantity,
+                units: product.current_stock,
+                product_id: product.id,
+                sku: product.sku,
+                name: product.name,
+            });
+        }
+        Ok(levels)
+    })
+    .await
+}
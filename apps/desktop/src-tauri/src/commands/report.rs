@@ -0,0 +1,310 @@
+//! # Report Commands
+//!
+//! The sales tax report for a date range, on screen or exported as CSV
+//! for filing (see `titan_core::tax_report`), the dashboard stats:
+//! margin of price over cost (see `titan_core::supplier`), and department
+//! sales (see `titan_core::department`).
+//!
+//! ## Commands
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Report Commands                                 │
+//! │                                                                         │
+//! │  get_tax_report(from, to)          taxable / non-taxable sales and tax  │
+//! │                                    per rate, with totals                │
+//! │  export_tax_report(from, to, dir)  the same as dir/tax-report-…csv      │
+//! │  get_dashboard_stats(from, to)     sales vs cost and margin, per       │
+//! │                                    product lowest margin first          │
+//! │  get_department_sales(from, to)    open-price and price-embedded sales  │
+//! │                                    per department, then SKU sales       │
+//! │                                                                         │
+//! │  Each also takes an optional station: only sales rung up on that        │
+//! │  register (see titan_core::station); without it, the whole store.       │
+//! │                                                                         │
+//! │  Dates are YYYY-MM-DD, inclusive, UTC. The report covers this          │
+//! │  terminal's database; the cloud GetTaxReport covers every store.       │
+//! │  Queries run on the reporting pool (see DbState::reporting), so the    │
+//! │  back office window can run them while the register sells.             │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{debug, info};
+use ts_rs::TS;
+
+use crate::error::{ApiError, ErrorCode};
+use crate::middleware::traced;
+use crate::state::{ConfigState, DbState};
+use titan_core::tax_report::report_window;
+use titan_core::{CoreError, DepartmentSalesRow, ProductMargin, TaxRateSummary, TaxReport};
+use titan_db::Database;
+
+/// Sales and tax at one rate.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TaxRateSummaryDto {
+    /// Basis points (`null`: lines sold without a recorded rate).
+    pub rate_bps: Option<u32>,
+    pub jurisdiction: Option<String>,
+    #[ts(type = "number")]
+    pub taxable_cents: i64,
+    #[ts(type = "number")]
+    pub non_taxable_cents: i64,
+    #[ts(type = "number")]
+    pub tax_cents: i64,
+    #[ts(type = "number")]
+    pub line_count: i64,
+}
+
+impl From<TaxRateSummary> for TaxRateSummaryDto {
+    fn from(r: TaxRateSummary) -> Self {
+        TaxRateSummaryDto {
+            rate_bps: r.rate_bps,
+            jurisdiction: r.jurisdiction,
+            taxable_cents: r.taxable_cents,
+            non_taxable_cents: r.non_taxable_cents,
+            tax_cents: r.tax_cents,
+            line_count: r.line_count,
+        }
+    }
+}
+
+/// The sales tax report for a date range.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TaxReportDto {
+    pub from: String,
+    pub to: String,
+    /// Lowest rate first; unknown rates last.
+    pub rates: Vec<TaxRateSummaryDto>,
+    #[ts(type = "number")]
+    pub taxable_cents: i64,
+    #[ts(type = "number")]
+    pub non_taxable_cents: i64,
+    #[ts(type = "number")]
+    pub tax_cents: i64,
+}
+
+impl From<TaxReport> for TaxReportDto {
+    fn from(report: TaxReport) -> Self {
+        TaxReportDto {
+            from: report.from.to_string(),
+            to: report.to.to_string(),
+            taxable_cents: report.taxable_cents(),
+            non_taxable_cents: report.non_taxable_cents(),
+            tax_cents: report.tax_cents(),
+            rates: report.rates.into_iter().map(TaxRateSummaryDto::from).collect(),
+        }
+    }
+}
+
+/// A tax report written to disk.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TaxReportExportDto {
+    /// Full path of the CSV file.
+    pub path: String,
+    pub report: TaxReportDto,
+}
+
+/// Dashboard figures for a date range.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardStatsDto {
+    pub from: String,
+    pub to: String,
+    /// Sales (net of line discounts, before tax) of lines with a cost.
+    #[ts(type = "number")]
+    pub sales_cents: i64,
+    /// Cost of those lines, at the cost when each was sold.
+    #[ts(type = "number")]
+    pub cost_cents: i64,
+    #[ts(type = "number")]
+    pub margin_cents: i64,
+    /// Margin in basis points of sales (`null` without costed sales).
+    #[ts(type = "number | null")]
+    pub margin_bps: Option<i64>,
+    /// Sales of lines sold without a cost, left out of the margin.
+    #[ts(type = "number")]
+    pub uncosted_sales_cents: i64,
+    /// Lowest margin first.
+    pub products: Vec<ProductMargin>,
+}
+
+/// Builds the dashboard stats for sales completed between `from` and `to`
+/// (inclusive, `YYYY-MM-DD`, UTC).
+#[tauri::command]
+pub async fn get_dashboard_stats(
+    db: State<'_, DbState>,
+    from: String,
+    to: String,
+    station: Option<u32>,
+) -> Result<DashboardStatsDto, ApiError> {
+    traced("get_dashboard_stats", async move {
+        debug!(from = %from, to = %to, ?station, "get_dashboard_stats command");
+        let db_inner: &Database = (*db).reporting()?;
+
+        let from = parse_date(&from, "from")?;
+        let to = parse_date(&to, "to")?;
+        let (start, end) = report_window(from, to).map_err(CoreError::from)?;
+        let stats = db_inner
+            .sales()
+            .margin_stats(start, end, station.map(i64::from))
+            .await?;
+
+        Ok(DashboardStatsDto {
+            from: from.to_string(),
+            to: to.to_string(),
+            margin_cents: stats.margin_cents(),
+            margin_bps: stats.margin_bps(),
+            sales_cents: stats.sales_cents,
+            cost_cents: stats.cost_cents,
+            uncosted_sales_cents: stats.uncosted_sales_cents,
+            products: stats.products,
+        })
+    })
+    .await
+}
+
+/// Department sales for a date range, apart from SKU sales.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct DepartmentSalesDto {
+    pub from: String,
+    pub to: String,
+    /// By department code.
+    pub departments: Vec<DepartmentSalesRow>,
+    /// Sales (net of line discounts, before tax) across every department.
+    #[ts(type = "number")]
+    pub department_sales_cents: i64,
+    /// Sales of products with a SKU.
+    #[ts(type = "number")]
+    pub sku_sales_cents: i64,
+}
+
+/// Builds the department sales report for sales completed between `from`
+/// and `to` (inclusive, `YYYY-MM-DD`, UTC).
+#[tauri::command]
+pub async fn get_department_sales(
+    db: State<'_, DbState>,
+    from: String,
+    to: String,
+    station: Option<u32>,
+) -> Result<DepartmentSalesDto, ApiError> {
+    traced("get_department_sales", async move {
+        debug!(from = %from, to = %to, ?station, "get_department_sales command");
+        let db_inner: &Database = (*db).reporting()?;
+
+        let from = parse_date(&from, "from")?;
+        let to = parse_date(&to, "to")?;
+        let (start, end) = report_window(from, to).map_err(CoreError::from)?;
+        let report = db_inner
+            .sales()
+            .department_sales(start, end, station.map(i64::from))
+            .await?;
+
+        Ok(DepartmentSalesDto {
+            from: from.to_string(),
+            to: to.to_string(),
+            department_sales_cents: report.department_sales_cents(),
+            sku_sales_cents: report.sku_sales_cents,
+            departments: report.departments,
+        })
+    })
+    .await
+}
+
+/// Builds the sales tax report for sales completed between `from` and `to`
+/// (inclusive, `YYYY-MM-DD`, UTC).
+#[tauri::command]
+pub async fn get_tax_report(
+    db: State<'_, DbState>,
+    from: String,
+    to: String,
+    station: Option<u32>,
+) -> Result<TaxReportDto, ApiError> {
+    traced("get_tax_report", async move {
+        debug!(from = %from, to = %to, ?station, "get_tax_report command");
+
+        let report = build_tax_report((*db).reporting()?, &from, &to, station).await?;
+
+        Ok(TaxReportDto::from(report))
+    })
+    .await
+}
+
+/// Writes the sales tax report for `from` to `to` as CSV into `dir`.
+#[tauri::command]
+pub async fn export_tax_report(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    from: String,
+    to: String,
+    dir: String,
+    station: Option<u32>,
+) -> Result<TaxReportExportDto, ApiError> {
+    traced("export_tax_report", async move {
+        debug!(
+            from = %from,
+            to = %to,
+            dir = %dir,
+            ?station,
+            "export_tax_report command"
+        );
+
+        let report = build_tax_report((*db).reporting()?, &from, &to, station).await?;
+
+        let out_dir = PathBuf::from(&dir);
+        std::fs::create_dir_all(&out_dir).map_err(|e| {
+            ApiError::new(ErrorCode::Internal, format!("Cannot create {}: {}", dir, e))
+        })?;
+        let path = out_dir.join(report.file_name());
+        std::fs::write(&path, report.to_csv(config.currency_decimals)).map_err(|e| {
+            ApiError::new(
+                ErrorCode::Internal,
+                format!("Cannot write {}: {}", path.display(), e),
+            )
+        })?;
+
+        info!(path = %path.display(), rates = report.rates.len(), "Tax report exported");
+
+        Ok(TaxReportExportDto {
+            path: path.display().to_string(),
+            report: TaxReportDto::from(report),
+        })
+    })
+    .await
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+async fn build_tax_report(
+    db: &Database,
+    from: &str,
+    to: &str,
+    station: Option<u32>,
+) -> Result<TaxReport, ApiError> {
+    let from = parse_date(from, "from")?;
+    let to = parse_date(to, "to")?;
+    let (start, end) = report_window(from, to).map_err(CoreError::from)?;
+
+    let rows = db.sales().tax_summary(start, end, station.map(i64::from)).await?;
+
+    Ok(TaxReport::new(from, to, rows))
+}
+
+fn parse_date(value: &str, field: &str) -> Result<NaiveDate, ApiError> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| ApiError::validation(format!("'{}' must be YYYY-MM-DD", field)))
+}
@@ -1,222 +1,391 @@
 //! # Sale Commands
 
+use std::time::Duration;
+
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use tauri::State;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
+use ts_rs::TS;
 use uuid::Uuid;
 
+use crate::commands::age::ensure_age_verified;
+use crate::commands::bundle::ensure_bundle_components_available;
+use crate::commands::coupon::redeem_coupon;
+use crate::commands::fiscal::sign_sale;
+use crate::commands::loyalty::{self, accrue_loyalty, redeem_loyalty_reward};
+use crate::commands::store_credit::{load_account, redeem_store_credit};
+use crate::commands::tracking::{ensure_tracking_captured, tracking_rows};
+use crate::commands::training::{ensure_tender_allowed, TRAINING_WATERMARK};
 use crate::error::{ApiError, ErrorCode};
-use crate::state::{CartState, ConfigState, DbState};
-use titan_core::{Payment, PaymentMethod, Sale, SaleItem, SaleStatus};
+use crate::middleware::traced;
+use crate::state::{training_mode, CartState, ConfigState, DbState, FiscalState, SyncState};
+use titan_core::{
+    Page, PageRequest, Payment, PaymentMethod, Sale, SaleDocument, SaleItem, SaleStatus,
+};
 use titan_db::Database;
+use titan_sync::{catalog_api, SyncError};
+
+/// How long a search waits for the hub to return a pruned sale.
+const HUB_FETCH_TIMEOUT: Duration = Duration::from_secs(5);
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateSaleResponse {
     pub sale_id: String,
+    #[ts(type = "number")]
     pub total_cents: i64,
     pub item_count: usize,
+    /// Whether this is a training sale (see `set_training_mode`).
+    pub training_mode: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct AddPaymentResponse {
     pub payment_id: String,
+    #[ts(type = "number")]
     pub amount_cents: i64,
+    #[ts(type = "number")]
     pub total_paid_cents: i64,
+    #[ts(type = "number")]
     pub remaining_cents: i64,
+    #[ts(type = "number")]
     pub change_cents: i64,
+    /// Whether this is a training sale (see `set_training_mode`).
+    pub training_mode: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct ReceiptResponse {
     pub sale_id: String,
     pub receipt_number: String,
     pub store_name: String,
+    /// Register the sale was rung up on.
+    #[ts(type = "number | null")]
+    pub station_number: Option<i64>,
     pub timestamp: String,
     pub items: Vec<ReceiptItem>,
+    #[ts(type = "number")]
     pub subtotal_cents: i64,
+    #[ts(type = "number")]
     pub tax_cents: i64,
+    /// Coupon discount taken off the sale.
+    #[ts(type = "number")]
+    pub discount_cents: i64,
+    #[ts(type = "number")]
     pub total_cents: i64,
     pub payments: Vec<ReceiptPayment>,
+    #[ts(type = "number")]
     pub change_cents: i64,
+    /// Fiscal sequence number, when the store signs receipts.
+    #[ts(type = "number | null")]
+    pub fiscal_sequence: Option<i64>,
+    /// Fiscal signature to print on the receipt.
+    pub fiscal_signature: Option<String>,
+    /// Printed across the receipt ("TRAINING" for a training sale).
+    pub watermark: Option<String>,
+    /// Whether this is a training sale (see `set_training_mode`).
+    pub training_mode: bool,
+    /// Points the loyalty member on the cart earned on the sale.
+    #[ts(type = "number | null")]
+    pub loyalty_points: Option<i64>,
+    /// Value of the loyalty rewards the sale completed, in cents.
+    #[ts(type = "number")]
+    pub loyalty_rewards_cents: i64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct ReceiptItem {
     pub name: String,
+    #[ts(type = "number")]
     pub quantity: i64,
+    #[ts(type = "number")]
     pub unit_price_cents: i64,
+    #[ts(type = "number")]
     pub line_total_cents: i64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct ReceiptPayment {
     pub method: String,
+    #[ts(type = "number")]
     pub amount_cents: i64,
 }
 
+/// A row of the sales history list.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct SaleSummaryDto {
+    pub id: String,
+    pub receipt_number: String,
+    pub status: SaleStatus,
+    #[ts(type = "number")]
+    pub total_cents: i64,
+    pub user_id: String,
+    pub device_id: String,
+    /// Register the sale was rung up on.
+    #[ts(type = "number | null")]
+    pub station_number: Option<i64>,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+}
+
+impl From<Sale> for SaleSummaryDto {
+    fn from(sale: Sale) -> Self {
+        SaleSummaryDto {
+            id: sale.id,
+            receipt_number: sale.receipt_number,
+            status: sale.status,
+            total_cents: sale.total_cents,
+            user_id: sale.user_id,
+            device_id: sale.device_id,
+            station_number: sale.station_number,
+            created_at: sale.created_at.to_rfc3339(),
+            completed_at: sale.completed_at.map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+/// Creates a draft sale from the cart.
+///
+/// A coupon on the cart is redeemed here, so a code used up on another
+/// register since it was applied is rejected before any payment is taken.
+///
+/// # Errors
+/// - `COUPON_REJECTED` if the cart's coupon can no longer be used
 #[tauri::command]
 pub async fn create_sale(
     db: State<'_, DbState>,
     cart: State<'_, CartState>,
     config: State<'_, ConfigState>,
+    sync: State<'_, SyncState>,
 ) -> Result<CreateSaleResponse, ApiError> {
-    debug!("create_sale command");
-
-    let (items, subtotal, tax, total) = cart.with_cart(|c| {
-        (
-            c.items.clone(),
-            c.subtotal_cents(),
-            c.tax_cents(),
-            c.total_cents(),
-        )
-    });
-
-    if items.is_empty() {
-        return Err(ApiError::validation("Cart is empty"));
-    }
+    traced("create_sale", async move {
+        debug!("create_sale command");
 
-    let db_inner: &Database = (*db).inner();
+        let (items, coupon, subtotal, tax) = cart.with_totals(|c, t| {
+            (c.items.clone(), c.coupon.clone(), t.subtotal_cents, t.tax_cents)
+        });
 
-    let sale_id = Uuid::new_v4().to_string();
-    let receipt_number = generate_receipt_number();
-    let now = Utc::now();
-
-    let sale = Sale {
-        id: sale_id.clone(),
-        tenant_id: config.tenant_id.clone(),
-        receipt_number: receipt_number.clone(),
-        status: SaleStatus::Draft,
-        subtotal_cents: subtotal,
-        tax_cents: tax,
-        discount_cents: 0,
-        total_cents: total,
-        user_id: "default".to_string(),
-        device_id: "pos-01".to_string(),
-        notes: None,
-        created_at: now,
-        updated_at: now,
-        completed_at: None,
-        sync_version: 0,
-    };
+        if items.is_empty() {
+            return Err(ApiError::validation("Cart is empty"));
+        }
 
-    db_inner.sales().insert_sale(&sale).await?;
+        let db_inner: &Database = (*db).inner()?;
+
+        let sale_id = Uuid::new_v4().to_string();
+        let receipt_number = config.station.receipt_number(&generate_receipt_number());
+        let now = Utc::now();
+
+        let mut sale_items: Vec<SaleItem> = items
+            .iter()
+            .map(|cart_item| SaleItem {
+                id: Uuid::new_v4().to_string(),
+                sale_id: sale_id.clone(),
+                product_id: cart_item.sale_product_id(),
+                sku_snapshot: cart_item.sku.clone(),
+                name_snapshot: cart_item.name.clone(),
+                quantity: cart_item.quantity,
+                unit_price_cents: cart_item.unit_price_cents,
+                line_total_cents: cart_item.line_total_cents(),
+                tax_cents: cart_item.tax_cents(),
+                discount_cents: 0,
+                base_price_cents: cart_item.base_price_cents,
+                tax_rate_bps: Some(cart_item.tax_rate_bps),
+                created_at: now,
+            })
+            .collect();
 
-    for cart_item in &items {
-        let sale_item = SaleItem {
-            id: Uuid::new_v4().to_string(),
-            sale_id: sale_id.clone(),
-            product_id: cart_item.product_id.clone(),
-            sku_snapshot: cart_item.sku.clone(),
-            name_snapshot: cart_item.name.clone(),
-            quantity: cart_item.quantity,
-            unit_price_cents: cart_item.unit_price_cents,
-            line_total_cents: cart_item.line_total_cents(),
-            tax_cents: cart_item.tax_cents(),
-            discount_cents: 0,
+        let discount = match &coupon {
+            Some(coupon) => redeem_coupon(db_inner, &config.station.device_id(), &sync, coupon, &sale_id, &mut sale_items).await?,
+            None => 0,
+        };
+        let total = subtotal + tax - discount;
+
+        let sale = Sale {
+            id: sale_id.clone(),
+            tenant_id: config.tenant_id.clone(),
+            receipt_number: receipt_number.clone(),
+            status: SaleStatus::Draft,
+            subtotal_cents: subtotal,
+            tax_cents: tax,
+            discount_cents: discount,
+            total_cents: total,
+            user_id: "default".to_string(),
+            device_id: config.station.device_id(),
+            station_number: config.station.sale_station(),
+            notes: None,
             created_at: now,
+            updated_at: now,
+            completed_at: None,
+            sync_version: 0,
         };
-        db_inner.sales().add_item(&sale_item).await?;
-    }
 
-    info!(sale_id = %sale_id, total = %total, items = items.len(), "Sale created");
+        db_inner.sales().insert_sale(&sale).await?;
 
-    Ok(CreateSaleResponse {
-        sale_id,
-        total_cents: total,
-        item_count: items.len(),
+        for (cart_item, sale_item) in items.iter().zip(&sale_items) {
+            db_inner.sales().add_item(sale_item).await?;
+
+            if !cart_item.tracking_codes.is_empty() {
+                let codes = tracking_rows(sale_item, cart_item.item_tracking, &cart_item.tracking_codes);
+                db_inner.sales().set_item_tracking(&sale_item.id, &codes).await?;
+            }
+        }
+
+        info!(sale_id = %sale_id, total = %total, discount = %discount, items = items.len(), "Sale created");
+
+        Ok(CreateSaleResponse {
+            sale_id,
+            total_cents: total,
+            item_count: items.len(),
+            training_mode: training_mode(),
+        })
     })
+    .await
 }
 
+/// Adds a payment to a draft sale.
+///
+/// For `store_credit`, `reference` is the credit number (or account ID);
+/// the credit is spent at once and never gives change. `loyalty_reward`
+/// works the same way, with the member number as `reference`. Neither can
+/// be tendered in training mode.
 #[tauri::command]
 pub async fn add_payment(
     db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    sync: State<'_, SyncState>,
     sale_id: String,
     amount_cents: i64,
     method: String,
+    reference: Option<String>,
 ) -> Result<AddPaymentResponse, ApiError> {
-    debug!(sale_id = %sale_id, amount = %amount_cents, method = %method, "add_payment command");
+    traced("add_payment", async move {
+        debug!(sale_id = %sale_id, amount = %amount_cents, method = %method, "add_payment command");
 
-    if amount_cents <= 0 {
-        return Err(ApiError::validation("Payment amount must be positive"));
-    }
+        if amount_cents <= 0 {
+            return Err(ApiError::validation("Payment amount must be positive"));
+        }
 
-    let payment_method = match method.to_lowercase().as_str() {
-        "cash" => PaymentMethod::Cash,
-        "card" | "credit" | "debit" => PaymentMethod::ExternalCard,
-        _ => PaymentMethod::ExternalCard,
-    };
+        let payment_method = match method.to_lowercase().as_str() {
+            "cash" => PaymentMethod::Cash,
+            "store_credit" => PaymentMethod::StoreCredit,
+            "loyalty_reward" => PaymentMethod::LoyaltyReward,
+            "card" | "credit" | "debit" => PaymentMethod::ExternalCard,
+            _ => PaymentMethod::ExternalCard,
+        };
 
-    let db_inner: &Database = (*db).inner();
+        ensure_tender_allowed(payment_method)?;
 
-    let sale = db_inner
-        .sales()
-        .get_by_id(&sale_id)
-        .await?
-        .ok_or_else(|| ApiError::not_found("Sale", &sale_id))?;
+        let db_inner: &Database = (*db).inner()?;
 
-    if sale.status != SaleStatus::Draft {
-        return Err(ApiError::new(
-            ErrorCode::BusinessLogic,
-            format!("Sale is {:?}, cannot add payment", sale.status),
-        ));
-    }
+        let sale = db_inner
+            .sales()
+            .get_by_id(&sale_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Sale", &sale_id))?;
 
-    // Calculate current total paid BEFORE this payment
-    let prev_total_paid = db_inner.sales().get_total_paid(&sale_id).await?;
-    let remaining_before = (sale.total_cents - prev_total_paid).max(0);
-
-    // Calculate effective amount applied to the sale and change
-    // ┌─────────────────────────────────────────────────────────────────────────┐
-    // │  Change Calculation (for cash payments)                                 │
-    // │                                                                         │
-    // │  tendered_cents = what customer gave us (e.g., $30.00 = 3000)          │
-    // │  amount_cents   = what applies to the sale (min of tendered, remaining)│
-    // │  change_cents   = what we give back (tendered - amount)                │
-    // │                                                                         │
-    // │  Example: $25.00 due, customer gives $30.00                            │
-    // │    tendered_cents = 3000                                                │
-    // │    amount_cents   = 2500 (applies to sale)                             │
-    // │    change_cents   = 500  (returned to customer)                        │
-    // └─────────────────────────────────────────────────────────────────────────┘
-    let effective_amount = amount_cents.min(remaining_before);
-    let change = if amount_cents > remaining_before {
-        amount_cents - remaining_before
-    } else {
-        0
-    };
+        if sale.status != SaleStatus::Draft {
+            return Err(ApiError::new(
+                ErrorCode::BusinessLogic,
+                format!("Sale is {:?}, cannot add payment", sale.status),
+            ));
+        }
 
-    let payment_id = Uuid::new_v4().to_string();
-    let payment = Payment {
-        id: payment_id.clone(),
-        sale_id: sale_id.clone(),
-        method: payment_method,
-        amount_cents: effective_amount,  // What applies to the sale
-        tendered_cents: Some(amount_cents),  // What was actually given
-        change_cents: if change > 0 { Some(change) } else { None },  // What to return
-        reference: None,
-        created_at: Utc::now(),
-    };
+        // Calculate current total paid BEFORE this payment
+        let prev_total_paid = db_inner.sales().get_total_paid(&sale_id).await?;
+        let remaining_before = (sale.total_cents - prev_total_paid).max(0);
+
+        // Calculate effective amount applied to the sale and change
+        // ┌─────────────────────────────────────────────────────────────────────────┐
+        // │  Change Calculation (for cash payments)                                 │
+        // │                                                                         │
+        // │  tendered_cents = what customer gave us (e.g., $30.00 = 3000)          │
+        // │  amount_cents   = what applies to the sale (min of tendered, remaining)│
+        // │  change_cents   = what we give back (tendered - amount)                │
+        // │                                                                         │
+        // │  Example: $25.00 due, customer gives $30.00                            │
+        // │    tendered_cents = 3000                                                │
+        // │    amount_cents   = 2500 (applies to sale)                             │
+        // │    change_cents   = 500  (returned to customer)                        │
+        // └─────────────────────────────────────────────────────────────────────────┘
+        let effective_amount = amount_cents.min(remaining_before);
+        let mut change = if amount_cents > remaining_before {
+            amount_cents - remaining_before
+        } else {
+            0
+        };
+
+        // Store credit: only what is due is spent, the rest stays on the account
+        let mut reference = reference.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+        if payment_method == PaymentMethod::StoreCredit {
+            let credit_ref = reference
+                .as_deref()
+                .ok_or_else(|| ApiError::validation("Store credit number is required"))?;
+            if effective_amount <= 0 {
+                return Err(ApiError::new(ErrorCode::PaymentError, "Sale is already paid"));
+            }
+            let account = load_account(db_inner, credit_ref).await?;
+            let balance = redeem_store_credit(db_inner, &config.station.device_id(), &sync, &account, effective_amount, &sale_id).await?;
+            info!(sale_id = %sale_id, credit_number = %account.credit_number, amount = effective_amount, balance, "Store credit redeemed");
+            reference = Some(account.credit_number);
+            change = 0;
+        }
+
+        // Loyalty rewards: likewise, only what is due is spent
+        if payment_method == PaymentMethod::LoyaltyReward {
+            let member_ref = reference
+                .as_deref()
+                .ok_or_else(|| ApiError::validation("Loyalty member number is required"))?;
+            if effective_amount <= 0 {
+                return Err(ApiError::new(ErrorCode::PaymentError, "Sale is already paid"));
+            }
+            let account = loyalty::load_account(db_inner, member_ref).await?;
+            let balance = redeem_loyalty_reward(db_inner, &config.station.device_id(), &sync, &account, effective_amount, &sale_id).await?;
+            info!(sale_id = %sale_id, member_number = %account.member_number, amount = effective_amount, balance, "Loyalty rewards redeemed");
+            reference = Some(account.member_number);
+            change = 0;
+        }
 
-    db_inner.sales().add_payment(&payment).await?;
+        let payment_id = Uuid::new_v4().to_string();
+        let payment = Payment {
+            id: payment_id.clone(),
+            sale_id: sale_id.clone(),
+            method: payment_method,
+            amount_cents: effective_amount,  // What applies to the sale
+            tendered_cents: Some(effective_amount + change),  // What was actually given
+            change_cents: if change > 0 { Some(change) } else { None },  // What to return
+            reference,
+            created_at: Utc::now(),
+        };
+
+        db_inner.sales().add_payment(&payment).await?;
 
-    let total_paid = prev_total_paid + effective_amount;
-    let remaining = (sale.total_cents - total_paid).max(0);
+        let total_paid = prev_total_paid + effective_amount;
+        let remaining = (sale.total_cents - total_paid).max(0);
 
-    info!(sale_id = %sale_id, payment_id = %payment_id, tendered = %amount_cents, applied = %effective_amount, change = %change, total_paid = %total_paid, remaining = %remaining, "Payment added");
+        info!(sale_id = %sale_id, payment_id = %payment_id, tendered = %amount_cents, applied = %effective_amount, change = %change, total_paid = %total_paid, remaining = %remaining, "Payment added");
 
-    Ok(AddPaymentResponse {
-        payment_id,
-        amount_cents: effective_amount,
-        total_paid_cents: total_paid,
-        remaining_cents: remaining,
-        change_cents: change,
+        Ok(AddPaymentResponse {
+            payment_id,
+            amount_cents: effective_amount,
+            total_paid_cents: total_paid,
+            remaining_cents: remaining,
+            change_cents: change,
+            training_mode: training_mode(),
+        })
     })
+    .await
 }
 
 #[tauri::command]
@@ -224,94 +393,350 @@ pub async fn finalize_sale(
     db: State<'_, DbState>,
     cart: State<'_, CartState>,
     config: State<'_, ConfigState>,
+    fiscal: State<'_, FiscalState>,
     sale_id: String,
 ) -> Result<ReceiptResponse, ApiError> {
-    debug!(sale_id = %sale_id, "finalize_sale command");
-
-    let db_inner: &Database = (*db).inner();
-
-    // Get sale items BEFORE finalizing so we can decrement stock
-    let items = db_inner.sales().get_items(&sale_id).await?;
-
-    // Decrement stock for each item sold
-    // ┌─────────────────────────────────────────────────────────────────────────┐
-    // │  Stock Deduction on Sale Finalization                                   │
-    // │                                                                         │
-    // │  For each item in the sale:                                             │
-    // │    1. Get product details to check track_inventory flag                │
-    // │    2. If tracking inventory, decrement by quantity sold                 │
-    // │    3. Use delta update (CRDT-friendly for sync)                         │
-    // │                                                                         │
-    // │  Example: Sell 3 bottles of Coke                                        │
-    // │    product.current_stock: 50 → 47                                       │
-    // │    SQL: UPDATE products SET current_stock = current_stock - 3           │
-    // └─────────────────────────────────────────────────────────────────────────┘
-    for item in &items {
-        // Get product to check if it tracks inventory
-        if let Some(product) = db_inner.products().get_by_id(&item.product_id).await? {
-            if product.track_inventory {
-                // Decrement stock by quantity sold (negative delta)
-                let delta = -(item.quantity as i32);
-                db_inner.products().update_stock(&item.product_id, delta).await?;
-                debug!(product_id = %item.product_id, sku = %item.sku_snapshot, quantity = item.quantity, "Stock decremented");
+    traced("finalize_sale", async move {
+        debug!(sale_id = %sale_id, "finalize_sale command");
+
+        let db_inner: &Database = (*db).inner()?;
+
+        // Get sale items BEFORE finalizing so we can decrement stock
+        let items = db_inner.sales().get_items(&sale_id).await?;
+
+        // Serial/lot numbers must be complete before anything is committed
+        ensure_tracking_captured(db_inner, &sale_id, &items).await?;
+
+        // Restricted items need a recorded age check
+        ensure_age_verified(db_inner, &sale_id, &items).await?;
+
+        // Bundles need their components in stock
+        ensure_bundle_components_available(db_inner, &items).await?;
+
+        // Fiscal signing comes last among the checks: once signed, the sale
+        // holds a sequence number and must be completed
+        let draft = db_inner
+            .sales()
+            .get_by_id(&sale_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Sale", &sale_id))?;
+        // Training sales never enter the fiscal chain
+        let training = training_mode();
+        let fiscal_signature = if training {
+            None
+        } else {
+            sign_sale(db_inner, &fiscal, &draft, &items).await?
+        };
+
+        // Decrement stock for each item sold
+        // ┌─────────────────────────────────────────────────────────────────────────┐
+        // │  Stock Deduction on Sale Finalization                                   │
+        // │                                                                         │
+        // │  For each item in the sale:                                             │
+        // │    1. Get product details to check track_inventory flag                │
+        // │    2. If tracking inventory, decrement by quantity sold                 │
+        // │    3. Use delta update (CRDT-friendly for sync)                         │
+        // │                                                                         │
+        // │  Example: Sell 3 bottles of Coke                                        │
+        // │    product.current_stock: 50 → 47                                       │
+        // │    SQL: UPDATE products SET current_stock = current_stock - 3           │
+        // │                                                                         │
+        // │  A bundle line decrements the bundle's components instead, one         │
+        // │  inventory delta per component (see commands/bundle.rs)                │
+        // └─────────────────────────────────────────────────────────────────────────┘
+        for item in &items {
+            // Get product to check if it tracks inventory
+            if let Some(product) = db_inner.products().get_by_id(&item.product_id).await? {
+                let bundle = db_inner.bundles().get(&item.product_id).await?;
+                if bundle.is_bundle() {
+                    let moved = db_inner
+                        .bundles()
+                        .consume(&sale_id, &draft.device_id, &bundle, item.quantity)
+                        .await?;
+                    debug!(product_id = %item.product_id, sku = %item.sku_snapshot, quantity = item.quantity, components = moved.len(), "Bundle components decremented");
+                } else if product.track_inventory {
+                    // Decrement stock by quantity sold (negative delta)
+                    let delta = -(item.quantity as i32);
+                    db_inner.products().update_stock(&item.product_id, delta).await?;
+                    debug!(product_id = %item.product_id, sku = %item.sku_snapshot, quantity = item.quantity, "Stock decremented");
+                }
             }
         }
+
+        // Now finalize the sale (marks as complete, updates timestamp)
+        db_inner.sales().finalize_sale(&sale_id).await?;
+
+        // The whole sale goes out, so the hub can relay it to the other
+        // registers for lookups, reprints and refunds
+        let doc = db_inner
+            .sales()
+            .get_document(&sale_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Sale", &sale_id))?;
+
+        let payload = serde_json::to_string(&doc).unwrap_or_default();
+        db_inner
+            .sync_outbox()
+            .queue_for_sync("SALE", &sale_id, &payload)
+            .await?;
+
+        let SaleDocument { sale, payments, .. } = doc;
+
+        // Points are earned on what the member paid, not on rewards; a
+        // failure here leaves the sale complete without the points
+        let member = cart.with_cart(|c| c.loyalty_member.clone());
+        let accrual = match member.filter(|_| !training) {
+            Some(member) => {
+                let rewards_paid: i64 = payments
+                    .iter()
+                    .filter(|p| p.method == PaymentMethod::LoyaltyReward)
+                    .map(|p| p.amount_cents)
+                    .sum();
+                let spent = sale.total_cents - rewards_paid;
+                match accrue_loyalty(db_inner, &config.station.device_id(), &config.loyalty, &member, &sale_id, spent).await {
+                    Ok(accrual) => Some(accrual),
+                    Err(e) => {
+                        warn!(sale_id = %sale_id, member_number = %member, error = %e.message, "Loyalty points not recorded");
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        cart.with_cart_mut(|c| c.clear());
+
+        info!(sale_id = %sale_id, items_count = items.len(), "Sale finalized and stock updated");
+
+        let total_change: i64 = payments.iter().filter_map(|p| p.change_cents).sum();
+
+        let receipt = ReceiptResponse {
+            sale_id: sale.id,
+            receipt_number: sale.receipt_number,
+            store_name: config.store_name.clone(),
+            station_number: sale.station_number,
+            timestamp: sale.completed_at.unwrap_or(sale.created_at).to_rfc3339(),
+            items: items
+                .into_iter()
+                .map(|i| ReceiptItem {
+                    name: i.name_snapshot,
+                    quantity: i.quantity,
+                    unit_price_cents: i.unit_price_cents,
+                    line_total_cents: i.line_total_cents,
+                })
+                .collect(),
+            subtotal_cents: sale.subtotal_cents,
+            tax_cents: sale.tax_cents,
+            discount_cents: sale.discount_cents,
+            total_cents: sale.total_cents,
+            payments: payments
+                .into_iter()
+                .map(|p| ReceiptPayment {
+                    method: format!("{:?}", p.method),
+                    amount_cents: p.amount_cents,
+                })
+                .collect(),
+            change_cents: total_change,
+            fiscal_sequence: fiscal_signature.as_ref().map(|s| s.sequence),
+            fiscal_signature: fiscal_signature.map(|s| s.signature),
+            watermark: training.then(|| TRAINING_WATERMARK.to_string()),
+            training_mode: training,
+            loyalty_points: accrual.as_ref().map(|a| a.points),
+            loyalty_rewards_cents: accrual.map_or(0, |a| a.rewards_cents),
+        };
+
+        Ok(receipt)
+    })
+    .await
+}
+
+/// Pages through the sales history, newest first.
+///
+/// ## Arguments
+/// * `query` - Start of a receipt number (empty: every sale)
+/// * `cursor` - `nextCursor` of the previous page; omit for the first page
+/// * `limit` - Page size (default: 50, max: 500)
+/// * `include_total` - Also count every match
+///
+/// A receipt number pruned from this terminal (see `RetentionPolicy`) is
+/// fetched back from the hub and then found as usual.
+///
+/// ## Errors
+/// - `VALIDATION_ERROR` for a cursor from another list.
+/// - `UNAVAILABLE` when the query is a pruned receipt and the hub cannot
+///   return it.
+#[tauri::command]
+pub async fn search_sales_page(
+    db: State<'_, DbState>,
+    sync: State<'_, SyncState>,
+    query: String,
+    cursor: Option<String>,
+    limit: Option<u32>,
+    include_total: Option<bool>,
+) -> Result<Page<SaleSummaryDto>, ApiError> {
+    traced("search_sales_page", async move {
+        let request = PageRequest {
+            limit,
+            cursor,
+            include_total: include_total.unwrap_or(false),
+        };
+        debug!(query = %query, limit = request.page_size(), "search_sales_page command");
+
+        let db_inner: &Database = (*db).inner()?;
+        let mut page = db_inner.sales().search_page(&query, &request).await?;
+        if page.items.is_empty()
+            && request.cursor.is_none()
+            && restore_pruned_sale(db_inner, &sync, query.trim()).await?
+        {
+            page = db_inner.sales().search_page(&query, &request).await?;
+        }
+        Ok(page.map(SaleSummaryDto::from))
+    })
+    .await
+}
+
+/// Fetches a sale this terminal does not have from the hub, which asks
+/// the cloud when it has none either.
+///
+/// Returns the sale summary; the sale itself is stored as a relayed copy
+/// so it can be reprinted or refunded like any other.
+///
+/// ## Errors
+/// - `VALIDATION_ERROR` for an empty receipt number.
+/// - `NOT_FOUND` when neither the hub nor the cloud has the receipt.
+/// - `UNAVAILABLE` when the hub cannot be asked.
+#[tauri::command]
+pub async fn fetch_sale_by_receipt(
+    db: State<'_, DbState>,
+    sync: State<'_, SyncState>,
+    receipt_number: String,
+) -> Result<SaleSummaryDto, ApiError> {
+    traced("fetch_sale_by_receipt", async move {
+        let receipt_number = receipt_number.trim();
+        debug!(receipt = %receipt_number, "fetch_sale_by_receipt command");
+        if receipt_number.is_empty() {
+            return Err(ApiError::validation("Receipt number is required"));
+        }
+
+        let db_inner: &Database = (*db).inner()?;
+        if let Some(doc) = db_inner
+            .sales()
+            .get_document_by_receipt(receipt_number)
+            .await?
+        {
+            return Ok(SaleSummaryDto::from(doc.sale));
+        }
+
+        let doc = hub_sale(&sync, receipt_number)
+            .await
+            .map_err(|reason| {
+                warn!(receipt = %receipt_number, %reason, "Could not fetch sale from the hub");
+                ApiError::new(
+                    ErrorCode::Unavailable,
+                    format!("The hub could not look up receipt {}", receipt_number),
+                )
+            })?
+            .ok_or_else(|| ApiError::not_found("Sale", receipt_number))?;
+
+        db_inner.sales().restore_pruned(&doc).await?;
+        info!(receipt = %receipt_number, sale_id = %doc.sale.id, "Fetched sale from the hub");
+        Ok(SaleSummaryDto::from(doc.sale))
+    })
+    .await
+}
+
+/// Fetches a pruned sale back from the hub when `receipt_number` is one.
+///
+/// ## Returns
+/// `true` if a sale was restored.
+async fn restore_pruned_sale(
+    db: &Database,
+    sync: &SyncState,
+    receipt_number: &str,
+) -> Result<bool, ApiError> {
+    if receipt_number.is_empty() {
+        return Ok(false);
     }
+    let Some(marker) = db.sales().find_pruned(receipt_number).await? else {
+        return Ok(false);
+    };
 
-    // Now finalize the sale (marks as complete, updates timestamp)
-    db_inner.sales().finalize_sale(&sale_id).await?;
-
-    let sale = db_inner
-        .sales()
-        .get_by_id(&sale_id)
-        .await?
-        .ok_or_else(|| ApiError::not_found("Sale", &sale_id))?;
-
-    let payload = serde_json::to_string(&sale).unwrap_or_default();
-    db_inner
-        .sync_outbox()
-        .queue_for_sync("SALE", &sale_id, &payload)
-        .await?;
-
-    let payments = db_inner.sales().get_payments(&sale_id).await?;
-
-    cart.with_cart_mut(|c| c.clear());
-
-    info!(sale_id = %sale_id, items_count = items.len(), "Sale finalized and stock updated");
-
-    let total_change: i64 = payments.iter().filter_map(|p| p.change_cents).sum();
-
-    let receipt = ReceiptResponse {
-        sale_id: sale.id,
-        receipt_number: sale.receipt_number,
-        store_name: config.store_name.clone(),
-        timestamp: sale.completed_at.unwrap_or(sale.created_at).to_rfc3339(),
-        items: items
-            .into_iter()
-            .map(|i| ReceiptItem {
-                name: i.name_snapshot,
-                quantity: i.quantity,
-                unit_price_cents: i.unit_price_cents,
-                line_total_cents: i.line_total_cents,
-            })
-            .collect(),
-        subtotal_cents: sale.subtotal_cents,
-        tax_cents: sale.tax_cents,
-        total_cents: sale.total_cents,
-        payments: payments
-            .into_iter()
-            .map(|p| ReceiptPayment {
-                method: format!("{:?}", p.method),
-                amount_cents: p.amount_cents,
-            })
-            .collect(),
-        change_cents: total_change,
+    let unavailable = |reason: String| {
+        warn!(receipt = %receipt_number, %reason, "Could not fetch pruned sale from the hub");
+        ApiError::new(
+            ErrorCode::Unavailable,
+            format!(
+                "Receipt {} was archived on {} and the hub could not return it",
+                receipt_number,
+                marker.pruned_at.format("%Y-%m-%d")
+            ),
+        )
     };
+    let doc = hub_sale(sync, receipt_number)
+        .await
+        .map_err(unavailable)?
+        .ok_or_else(|| unavailable("not found on the hub or in the cloud".to_string()))?;
+
+    let restored = db.sales().restore_pruned(&doc).await?;
+    info!(receipt = %receipt_number, sale_id = %doc.sale.id, "Restored pruned sale from the hub");
+    Ok(restored)
+}
+
+/// Asks the hub for a sale by receipt number: over the sync connection
+/// when the agent is running (the hub falls back to the cloud), else from
+/// the hub's catalog API.
+///
+/// ## Returns
+/// `None` if the receipt is unknown.
+async fn hub_sale(sync: &SyncState, receipt_number: &str) -> Result<Option<SaleDocument>, String> {
+    if let Some(agent) = sync.agent_handle() {
+        match agent.lookup_sale(receipt_number).await {
+            Ok(result) => {
+                return match result.error {
+                    Some(error) => Err(error),
+                    None => Ok(result.sale),
+                }
+            }
+            // Hubs before v9 only have their own copy
+            Err(SyncError::UnsupportedVersion(_)) => {}
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+
+    let url = sync
+        .hub_url()
+        .and_then(|hub_url| catalog_api::sale_url(&hub_url, receipt_number))
+        .ok_or_else(|| "no hub configured".to_string())?;
+    let token = sync.get_config().and_then(|c| c.hub.catalog_token);
+    fetch_sale(&url, token.as_deref()).await
+}
 
-    Ok(receipt)
+/// GETs a sale document from the hub's catalog API (`None` on 404).
+async fn fetch_sale(url: &str, token: Option<&str>) -> Result<Option<SaleDocument>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(HUB_FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut request = client.get(url);
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let body = response
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .bytes()
+        .await
+        .map_err(|e| e.to_string())?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| e.to_string())
 }
 
-fn generate_receipt_number() -> String {
+pub(crate) fn generate_receipt_number() -> String {
     let now = Utc::now();
     let nanos = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
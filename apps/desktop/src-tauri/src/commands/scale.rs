@@ -0,0 +1,119 @@
+//! # Scale Commands
+//!
+//! Reading the station's scale (see `ScaleState` for the drivers and the
+//! live weight feed) and selling loose goods by weight.
+//!
+//! ## Commands
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                           Scale Commands                                │
+//! │                                                                         │
+//! │  watch_weight(true/false)   live "scale:weight" events on or off        │
+//! │  read_weight                one reading, settled or not                 │
+//! │                                                                         │
+//! │  add_weighed_item(department_code, tare_grams)                          │
+//! │     read ──► WeighRules::net_weight ──► DepartmentConfig::weighed       │
+//! │          ──► cart line "Loose Produce 0.455 kg @ 2.99/kg"               │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use std::sync::Arc;
+
+use tauri::State;
+use tracing::{debug, info};
+
+use crate::commands::cart::{add_department_line, CartResponse};
+use crate::error::ApiError;
+use crate::middleware::traced;
+use crate::state::{CartState, ConfigState, DbState, ScaleState};
+use titan_core::{CoreError, ErrorCode, Scale, WeighRules, WeightReading};
+
+/// The station's scale, or why there is none.
+fn station_scale(scale: &ScaleState) -> Result<Arc<dyn Scale>, ApiError> {
+    scale.scale().ok_or_else(|| {
+        let message = match scale.problem() {
+            Some(problem) => format!("The scale setting is invalid: {}", problem),
+            None => "No scale is configured for this station".to_string(),
+        };
+        ApiError::new(ErrorCode::ConfigError, message)
+    })
+}
+
+/// Reads the scale once, off the async runtime.
+async fn read_scale(scale: Arc<dyn Scale>) -> Result<WeightReading, ApiError> {
+    tauri::async_runtime::spawn_blocking(move || scale.read())
+        .await
+        .map_err(|e| ApiError::internal(format!("Scale task failed: {}", e)))?
+        .map_err(ApiError::from)
+}
+
+/// Reads the weight on the scale, whether or not it has settled.
+///
+/// # Errors
+/// - `CONFIG_ERROR` if the station has no scale, or an invalid setting
+/// - `UNAVAILABLE` if the scale could not be read
+#[tauri::command]
+pub async fn read_weight(scale: State<'_, ScaleState>) -> Result<WeightReading, ApiError> {
+    traced("read_weight", async move {
+        debug!("read_weight command");
+        read_scale(station_scale(&scale)?).await
+    })
+    .await
+}
+
+/// Turns the live weight feed on (while the weighing screen is open) or
+/// off. Readings arrive as `scale:weight` events when they change.
+///
+/// # Errors
+/// `CONFIG_ERROR` if the station has no scale, or an invalid setting.
+#[tauri::command]
+pub fn watch_weight(scale: State<'_, ScaleState>, watching: bool) -> Result<(), ApiError> {
+    debug!(watching, "watch_weight command");
+    station_scale(&scale)?;
+    scale.set_watching(watching);
+    Ok(())
+}
+
+/// Weighs loose goods and adds them to the cart on a department sold by
+/// weight (see `DepartmentKey::price_per_kg_cents`).
+///
+/// # Arguments
+/// * `department_code` - Department key, e.g. `"PRODUCE"`
+/// * `tare_grams` - Container weight to take off (default 0)
+///
+/// # Errors
+/// - `CONFIG_ERROR` if the station has no scale, or an invalid setting
+/// - `UNAVAILABLE` if the scale could not be read
+/// - `VALIDATION_ERROR` for a department not sold by weight, a scale that
+///   has not settled, a tare out of range or a weight below the minimum
+#[tauri::command]
+pub async fn add_weighed_item(
+    db: State<'_, DbState>,
+    cart: State<'_, CartState>,
+    config: State<'_, ConfigState>,
+    scale: State<'_, ScaleState>,
+    department_code: String,
+    tare_grams: Option<i64>,
+) -> Result<CartResponse, ApiError> {
+    traced("add_weighed_item", async move {
+        let tare_grams = tare_grams.unwrap_or(0);
+        debug!(department_code = %department_code, tare_grams, "add_weighed_item command");
+
+        let reading = read_scale(station_scale(&scale)?).await?;
+        let net_grams = WeighRules::default()
+            .net_weight(&reading, tare_grams)
+            .map_err(CoreError::from)?;
+        let line = config
+            .departments
+            .weighed(department_code.trim(), net_grams)?;
+
+        info!(
+            department = %line.department_code,
+            net_grams,
+            price_cents = line.unit_price_cents,
+            "Weighed item added"
+        );
+        add_department_line(&db, &cart, &line, 1).await
+    })
+    .await
+}
@@ -0,0 +1,40 @@
+//! # Startup Commands
+//!
+//! The window paints before the database is open: migrations run in a
+//! background task (see `spawn_startup` in lib.rs), and commands that need
+//! the database fail with `NOT_READY` until it finishes. The frontend
+//! shows a splash screen in the meantime.
+//!
+//! ## Readiness
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Startup Readiness                                │
+//! │                                                                         │
+//! │  window loads ──► get_startup_status                                   │
+//! │                     ├── ready ─────► register screen                   │
+//! │                     ├── failed ────► error screen (restart needed)     │
+//! │                     └── starting ──► splash, wait for:                 │
+//! │                                                                         │
+//! │  "startup:ready"    { phase: "ready" }                                 │
+//! │  "startup:failed"   { phase: "failed", error: "..." }                  │
+//! │                                                                         │
+//! │  Ask first, then listen: the events may fire before the window        │
+//! │  subscribes.                                                            │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use tauri::State;
+
+use crate::state::{DbState, StartupStatus};
+
+/// Emitted once the database is open (payload: [`StartupStatus`]).
+pub const STARTUP_READY_EVENT: &str = "startup:ready";
+
+/// Emitted if startup failed (payload: [`StartupStatus`]).
+pub const STARTUP_FAILED_EVENT: &str = "startup:failed";
+
+/// Gets where startup is, for the splash screen.
+#[tauri::command]
+pub fn get_startup_status(db: State<'_, DbState>) -> StartupStatus {
+    db.status()
+}
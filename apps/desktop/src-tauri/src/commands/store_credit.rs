@@ -0,0 +1,468 @@
+//! # Store Credit & Refund Commands
+//!
+//! Refund a completed sale without taking the goods back, paying out in
+//! cash, back to the card, or as store credit; look up a customer's credit.
+//! Spending credit is a tender on `add_payment` (method `store_credit`).
+//!
+//! ## Store Credit Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Store Credit Flow                                │
+//! │                                                                         │
+//! │  refund_sale(destination: store_credit) ──► account SC-… (+amount)     │
+//! │         │                                                               │
+//! │         │ (queued as STORE_CREDIT → hub → other terminals → cloud)     │
+//! │         ▼                                                               │
+//! │  add_payment(method: store_credit, reference: SC-…)                    │
+//! │         │                                                               │
+//! │         ├── sync running:  StoreCreditRedeem ──► hub checks balance    │
+//! │         │                  hub unreachable   ──► rejected              │
+//! │         └── standalone:    local ledger checks balance                 │
+//! │                                                                         │
+//! │  The balance is checked where every terminal of the store writes, so   │
+//! │  the same credit cannot be spent twice while offline from the cloud.   │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{debug, info, warn};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::commands::override_token::redeem_override;
+use crate::commands::sale::generate_receipt_number;
+use crate::commands::training::ensure_tender_allowed;
+use crate::error::{ApiError, ErrorCode};
+use crate::middleware::traced;
+use crate::state::{ConfigState, DbState, SyncState};
+use titan_core::store_credit::validate_refund_amount;
+use titan_core::{
+    CoreError, OverrideAction, PaymentMethod, RefundDestination, SaleRefund, SaleStatus,
+    StoreCreditAccount, StoreCreditDocument, StoreCreditEntry, StoreCreditEntryKind,
+};
+use titan_db::Database;
+use titan_sync::protocol::StoreCreditRedeemRequest;
+
+/// Cashier ID used for refunds (matches the sale commands).
+const USER_ID: &str = "default";
+
+/// A ledger entry as shown in the UI.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreCreditEntryDto {
+    pub id: String,
+    /// "issue" or "redeem".
+    pub kind: String,
+    /// Signed: positive for issue, negative for redeem.
+    #[ts(type = "number")]
+    pub amount_cents: i64,
+    pub sale_id: Option<String>,
+    pub created_at: String,
+}
+
+impl From<StoreCreditEntry> for StoreCreditEntryDto {
+    fn from(e: StoreCreditEntry) -> Self {
+        StoreCreditEntryDto {
+            id: e.id,
+            kind: e.kind.as_str().to_string(),
+            amount_cents: e.amount_cents,
+            sale_id: e.sale_id,
+            created_at: e.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// A store credit account as shown in the UI.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreCreditDto {
+    pub id: String,
+    pub credit_number: String,
+    pub customer_name: String,
+    pub customer_phone: Option<String>,
+    #[ts(type = "number")]
+    pub balance_cents: i64,
+    /// Empty in search results.
+    pub entries: Vec<StoreCreditEntryDto>,
+}
+
+impl StoreCreditDto {
+    fn new(account: StoreCreditAccount, balance_cents: i64, entries: Vec<StoreCreditEntry>) -> Self {
+        StoreCreditDto {
+            id: account.id,
+            credit_number: account.credit_number,
+            customer_name: account.customer_name,
+            customer_phone: account.customer_phone,
+            balance_cents,
+            entries: entries.into_iter().map(StoreCreditEntryDto::from).collect(),
+        }
+    }
+
+    fn from_document(doc: StoreCreditDocument) -> Self {
+        let balance = doc.balance_cents();
+        StoreCreditDto::new(doc.account, balance, doc.entries)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RefundResponse {
+    pub refund_id: String,
+    #[ts(type = "number")]
+    pub amount_cents: i64,
+    pub destination: String,
+    /// Total refunded on the sale, including this refund.
+    #[ts(type = "number")]
+    pub total_refunded_cents: i64,
+    /// The credited account when the refund went to store credit.
+    pub store_credit: Option<StoreCreditDto>,
+}
+
+/// Refunds part or all of a completed sale.
+///
+/// The customer keeps the goods, so stock is not touched. For store credit,
+/// pass `credit_ref` to add to an existing account, or `customer_name`
+/// (and optionally `customer_phone`) to open a new one.
+///
+/// A refund above the store's refund approval limit needs a manager
+/// `override_token` for this sale and amount (see `issue_override_token`).
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn refund_sale(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    sync: State<'_, SyncState>,
+    sale_id: String,
+    amount_cents: i64,
+    destination: String,
+    credit_ref: Option<String>,
+    customer_name: Option<String>,
+    customer_phone: Option<String>,
+    reason: Option<String>,
+    override_token: Option<String>,
+) -> Result<RefundResponse, ApiError> {
+    traced("refund_sale", async move {
+        debug!(sale_id = %sale_id, amount = amount_cents, destination = %destination, "refund_sale command");
+
+        let destination = parse_destination(&destination)?;
+        let db_inner: &Database = (*db).inner()?;
+
+        let sale = db_inner
+            .sales()
+            .get_by_id(&sale_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Sale", &sale_id))?;
+
+        if sale.status != SaleStatus::Completed {
+            return Err(ApiError::new(
+                ErrorCode::BusinessLogic,
+                format!("Sale is {:?}, only completed sales can be refunded", sale.status),
+            ));
+        }
+
+        let already_refunded = db_inner.sales().get_total_refunded(&sale_id).await?;
+        validate_refund_amount(amount_cents, sale.total_cents, already_refunded).map_err(CoreError::from)?;
+
+        if config.refund_approval_cents.is_some_and(|limit| amount_cents > limit) {
+            let token = override_token
+                .as_deref()
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .ok_or_else(|| CoreError::OverrideRequired {
+                    action: "refund".to_string(),
+                })?;
+            redeem_override(
+                db_inner,
+                &sync,
+                token,
+                OverrideAction::Refund,
+                Some(&sale_id),
+                Some(amount_cents),
+            )
+            .await?;
+        }
+
+        let now = Utc::now();
+        let credit = match destination {
+            RefundDestination::StoreCredit => {
+                let account = match credit_ref.as_deref().map(str::trim).filter(|r| !r.is_empty()) {
+                    Some(credit_ref) => load_account(db_inner, credit_ref).await?,
+                    None => {
+                        let customer_name = customer_name
+                            .as_deref()
+                            .map(str::trim)
+                            .filter(|n| !n.is_empty())
+                            .ok_or_else(|| ApiError::validation("Customer name is required for new store credit"))?;
+                        StoreCreditAccount {
+                            id: Uuid::new_v4().to_string(),
+                            tenant_id: config.tenant_id.clone(),
+                            credit_number: generate_credit_number(),
+                            customer_name: customer_name.to_string(),
+                            customer_phone: customer_phone
+                                .map(|p| p.trim().to_string())
+                                .filter(|p| !p.is_empty()),
+                            created_at: now,
+                            updated_at: now,
+                            sync_version: 1,
+                        }
+                    }
+                };
+                let entry = StoreCreditEntry {
+                    id: Uuid::new_v4().to_string(),
+                    account_id: account.id.clone(),
+                    kind: StoreCreditEntryKind::Issue,
+                    amount_cents,
+                    sale_id: Some(sale_id.clone()),
+                    device_id: config.station.device_id(),
+                    created_at: now,
+                };
+                Some(StoreCreditDocument {
+                    account,
+                    entries: vec![entry],
+                })
+            }
+            RefundDestination::Cash | RefundDestination::ExternalCard => None,
+        };
+
+        let refund = SaleRefund {
+            id: Uuid::new_v4().to_string(),
+            sale_id: sale_id.clone(),
+            amount_cents,
+            destination,
+            store_credit_id: credit.as_ref().map(|doc| doc.account.id.clone()),
+            reason: reason.map(|r| r.trim().to_string()).filter(|r| !r.is_empty()),
+            user_id: USER_ID.to_string(),
+            device_id: config.station.device_id(),
+            created_at: now,
+        };
+
+        db_inner.sales().add_refund(&refund, credit.as_ref()).await?;
+
+        let store_credit = match &credit {
+            Some(issued) => {
+                let doc = load_document(db_inner, &issued.account.id).await?;
+                queue_store_credit(db_inner, &doc).await?;
+                Some(StoreCreditDto::from_document(doc))
+            }
+            None => None,
+        };
+
+        info!(
+            sale_id = %sale_id,
+            refund_id = %refund.id,
+            amount = amount_cents,
+            destination = ?destination,
+            "Sale refunded"
+        );
+
+        Ok(RefundResponse {
+            refund_id: refund.id,
+            amount_cents,
+            destination: destination_name(destination).to_string(),
+            total_refunded_cents: already_refunded + amount_cents,
+            store_credit,
+        })
+    })
+    .await
+}
+
+/// Gets a store credit account with its ledger, by ID or credit number.
+#[tauri::command]
+pub async fn get_store_credit(
+    db: State<'_, DbState>,
+    credit_ref: String,
+) -> Result<StoreCreditDto, ApiError> {
+    traced("get_store_credit", async move {
+        debug!(credit_ref = %credit_ref, "get_store_credit command");
+
+        let db_inner: &Database = (*db).inner()?;
+        let account = load_account(db_inner, credit_ref.trim()).await?;
+        let doc = load_document(db_inner, &account.id).await?;
+
+        Ok(StoreCreditDto::from_document(doc))
+    })
+    .await
+}
+
+/// Finds a customer's store credit accounts by phone number.
+#[tauri::command]
+pub async fn find_store_credits(
+    db: State<'_, DbState>,
+    customer_phone: String,
+) -> Result<Vec<StoreCreditDto>, ApiError> {
+    traced("find_store_credits", async move {
+        debug!(customer_phone = %customer_phone, "find_store_credits command");
+
+        let db_inner: &Database = (*db).inner()?;
+        let accounts = db_inner.store_credits().find_by_phone(customer_phone.trim()).await?;
+
+        let mut results = Vec::with_capacity(accounts.len());
+        for account in accounts {
+            let balance = db_inner.store_credits().get_balance(&account.id).await?;
+            results.push(StoreCreditDto::new(account, balance, Vec::new()));
+        }
+
+        Ok(results)
+    })
+    .await
+}
+
+// =============================================================================
+// Redemption (used by add_payment)
+// =============================================================================
+
+/// Spends store credit on a sale.
+///
+/// With the sync agent running the hub checks and writes the redemption;
+/// if the hub cannot be reached the tender is refused rather than risk
+/// spending the same credit on two terminals. A standalone terminal checks
+/// against its own ledger. Refused in training mode, where the hub would
+/// still spend the live balance.
+///
+/// ## Returns
+/// The balance left on the account.
+pub(crate) async fn redeem_store_credit(
+    db: &Database,
+    device_id: &str,
+    sync: &SyncState,
+    account: &StoreCreditAccount,
+    amount_cents: i64,
+    sale_id: &str,
+) -> Result<i64, ApiError> {
+    ensure_tender_allowed(PaymentMethod::StoreCredit)?;
+
+    let entry = StoreCreditEntry {
+        id: Uuid::new_v4().to_string(),
+        account_id: account.id.clone(),
+        kind: StoreCreditEntryKind::Redeem,
+        amount_cents: -amount_cents,
+        sale_id: Some(sale_id.to_string()),
+        device_id: device_id.to_string(),
+        created_at: Utc::now(),
+    };
+
+    let balance = match sync.agent_handle() {
+        Some(handle) => {
+            let request = StoreCreditRedeemRequest {
+                request_id: Uuid::new_v4().to_string(),
+                device_id: device_id.to_string(),
+                account_id: account.id.clone(),
+                entry_id: entry.id.clone(),
+                amount_cents,
+                sale_id: entry.sale_id.clone(),
+                timestamp: entry.created_at.to_rfc3339(),
+            };
+            let result = handle.redeem_store_credit(request).await.map_err(|e| {
+                warn!(credit_number = %account.credit_number, ?e, "Store credit could not be verified");
+                ApiError::new(
+                    ErrorCode::InsufficientStoreCredit,
+                    format!("Store credit could not be verified with the store hub: {}", e),
+                )
+            })?;
+
+            if !result.approved {
+                if result.balance_cents < amount_cents {
+                    return Err(insufficient(account, amount_cents, result.balance_cents));
+                }
+                return Err(ApiError::new(
+                    ErrorCode::InsufficientStoreCredit,
+                    format!(
+                        "Store credit {} was refused: {}",
+                        account.credit_number,
+                        result.reason.unwrap_or_default()
+                    ),
+                ));
+            }
+
+            // The hub also broadcasts the account; writing the entry now
+            // keeps this terminal's balance right if that arrives late.
+            db.store_credits()
+                .upsert_from_sync(&StoreCreditDocument {
+                    account: account.clone(),
+                    entries: vec![entry],
+                })
+                .await?;
+            result.balance_cents
+        }
+        None => {
+            let redemption = db.store_credits().redeem(&entry).await?;
+            if !redemption.applied {
+                return Err(insufficient(account, amount_cents, redemption.balance_cents));
+            }
+            redemption.balance_cents
+        }
+    };
+
+    let doc = load_document(db, &account.id).await?;
+    queue_store_credit(db, &doc).await?;
+
+    Ok(balance)
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Loads an account by ID, falling back to the printed credit number.
+pub(crate) async fn load_account(db: &Database, credit_ref: &str) -> Result<StoreCreditAccount, ApiError> {
+    if let Some(account) = db.store_credits().get_by_id(credit_ref).await? {
+        return Ok(account);
+    }
+
+    db.store_credits()
+        .get_by_number(credit_ref)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Store credit", credit_ref))
+}
+
+async fn load_document(db: &Database, account_id: &str) -> Result<StoreCreditDocument, ApiError> {
+    db.store_credits()
+        .get_document(account_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Store credit", account_id))
+}
+
+/// Queues the account and its full ledger for other terminals and stores.
+async fn queue_store_credit(db: &Database, doc: &StoreCreditDocument) -> Result<(), ApiError> {
+    let payload = serde_json::to_string(doc)
+        .map_err(|e| ApiError::new(ErrorCode::Internal, e.to_string()))?;
+    db.sync_outbox()
+        .upsert_for_sync("STORE_CREDIT", &doc.account.id, &payload)
+        .await?;
+    Ok(())
+}
+
+fn insufficient(account: &StoreCreditAccount, requested_cents: i64, available_cents: i64) -> ApiError {
+    CoreError::InsufficientStoreCredit {
+        credit_number: account.credit_number.clone(),
+        available_cents,
+        requested_cents,
+    }
+    .into()
+}
+
+fn parse_destination(destination: &str) -> Result<RefundDestination, ApiError> {
+    match destination.to_lowercase().as_str() {
+        "cash" => Ok(RefundDestination::Cash),
+        "card" | "external_card" => Ok(RefundDestination::ExternalCard),
+        "store_credit" => Ok(RefundDestination::StoreCredit),
+        other => Err(ApiError::validation(format!("Unknown refund destination: {}", other))),
+    }
+}
+
+fn destination_name(destination: RefundDestination) -> &'static str {
+    match destination {
+        RefundDestination::Cash => "cash",
+        RefundDestination::ExternalCard => "external_card",
+        RefundDestination::StoreCredit => "store_credit",
+    }
+}
+
+fn generate_credit_number() -> String {
+    format!("SC-{}", generate_receipt_number())
+}
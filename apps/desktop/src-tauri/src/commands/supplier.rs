@@ -0,0 +1,212 @@
+//! # Supplier Commands
+//!
+//! Suppliers, their price lists and what products cost (see
+//! `titan_core::supplier`). Costs are kept up to date by receiving (see
+//! `receive_stock`); margins are in the dashboard stats.
+//!
+//! ## Commands
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Supplier Commands                                │
+//! │                                                                         │
+//! │  create_supplier / update_supplier   name, contact, active             │
+//! │  set_supplier_items(id, items)       price list: product, supplier     │
+//! │                                      SKU, cost per each                 │
+//! │  get_supplier / list_suppliers                                          │
+//! │  get_product_cost(product)           last and average cost             │
+//! │                                                                         │
+//! │  Every change syncs to the other terminals and the cloud.              │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{debug, info};
+use ts_rs::TS;
+
+use crate::error::ApiError;
+use crate::middleware::traced;
+use crate::state::DbState;
+use titan_core::{ProductCost, Supplier, SupplierDocument, SupplierItem};
+use titan_db::Database;
+
+/// A supplier as returned to the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct SupplierDto {
+    pub id: String,
+    pub name: String,
+    pub contact_name: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub is_active: bool,
+    /// The price list, by product ID; empty in supplier lists.
+    pub items: Vec<SupplierItem>,
+    #[ts(type = "number")]
+    pub sync_version: i64,
+    pub updated_at: String,
+}
+
+impl From<Supplier> for SupplierDto {
+    fn from(supplier: Supplier) -> Self {
+        SupplierDto {
+            id: supplier.id,
+            name: supplier.name,
+            contact_name: supplier.contact_name,
+            phone: supplier.phone,
+            email: supplier.email,
+            is_active: supplier.is_active,
+            items: Vec::new(),
+            sync_version: supplier.sync_version,
+            updated_at: supplier.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+impl From<SupplierDocument> for SupplierDto {
+    fn from(doc: SupplierDocument) -> Self {
+        SupplierDto {
+            items: doc.items,
+            ..SupplierDto::from(doc.supplier)
+        }
+    }
+}
+
+/// Creates a supplier with an empty price list.
+///
+/// ## Errors
+/// `VALIDATION_ERROR` for an empty or too long name.
+#[tauri::command]
+pub async fn create_supplier(
+    db: State<'_, DbState>,
+    name: String,
+    contact_name: Option<String>,
+    phone: Option<String>,
+    email: Option<String>,
+) -> Result<SupplierDto, ApiError> {
+    traced("create_supplier", async move {
+        info!(name = %name, "create_supplier command");
+        let db_inner: &Database = (*db).inner()?;
+        let supplier = db_inner
+            .suppliers()
+            .create(&name, contact_name.as_deref(), phone.as_deref(), email.as_deref())
+            .await?;
+        Ok(SupplierDto::from(supplier))
+    })
+    .await
+}
+
+/// Changes a supplier's name, contact details and whether it is active.
+///
+/// ## Errors
+/// - `VALIDATION_ERROR` for an empty or too long name
+/// - `NOT_FOUND` if the supplier doesn't exist
+#[tauri::command]
+pub async fn update_supplier(
+    db: State<'_, DbState>,
+    id: String,
+    name: String,
+    contact_name: Option<String>,
+    phone: Option<String>,
+    email: Option<String>,
+    is_active: bool,
+) -> Result<SupplierDto, ApiError> {
+    traced("update_supplier", async move {
+        info!(id = %id, is_active, "update_supplier command");
+        let db_inner: &Database = (*db).inner()?;
+        let doc = db_inner
+            .suppliers()
+            .update(
+                &id,
+                &name,
+                contact_name.as_deref(),
+                phone.as_deref(),
+                email.as_deref(),
+                is_active,
+            )
+            .await?;
+        Ok(SupplierDto::from(doc))
+    })
+    .await
+}
+
+/// Replaces a supplier's price list.
+///
+/// ## Errors
+/// - `VALIDATION_ERROR` for a product listed twice, a cost out of range or
+///   a supplier SKU that is too long
+/// - `NOT_FOUND` if the supplier or a product doesn't exist
+#[tauri::command]
+pub async fn set_supplier_items(
+    db: State<'_, DbState>,
+    id: String,
+    items: Vec<SupplierItem>,
+) -> Result<SupplierDto, ApiError> {
+    traced("set_supplier_items", async move {
+        info!(id = %id, count = items.len(), "set_supplier_items command");
+        let db_inner: &Database = (*db).inner()?;
+        let doc = db_inner.suppliers().set_items(&id, &items).await?;
+        Ok(SupplierDto::from(doc))
+    })
+    .await
+}
+
+/// Gets a supplier with its price list.
+///
+/// ## Errors
+/// `NOT_FOUND` if the supplier doesn't exist.
+#[tauri::command]
+pub async fn get_supplier(db: State<'_, DbState>, id: String) -> Result<SupplierDto, ApiError> {
+    traced("get_supplier", async move {
+        debug!(id = %id, "get_supplier command");
+        let db_inner: &Database = (*db).inner()?;
+        let doc = db_inner
+            .suppliers()
+            .get(&id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Supplier", &id))?;
+        Ok(SupplierDto::from(doc))
+    })
+    .await
+}
+
+/// Lists suppliers by name, without their price lists.
+///
+/// ## Arguments
+/// * `include_inactive` - Also list inactive suppliers (default: false)
+#[tauri::command]
+pub async fn list_suppliers(
+    db: State<'_, DbState>,
+    include_inactive: Option<bool>,
+) -> Result<Vec<SupplierDto>, ApiError> {
+    traced("list_suppliers", async move {
+        let db_inner: &Database = (*db).inner()?;
+        let suppliers = db_inner
+            .suppliers()
+            .list(include_inactive.unwrap_or(false))
+            .await?;
+        Ok(suppliers.into_iter().map(SupplierDto::from).collect())
+    })
+    .await
+}
+
+/// Gets a product's last and average cost from costed receipts.
+///
+/// ## Errors
+/// `NOT_FOUND` if the product doesn't exist.
+#[tauri::command]
+pub async fn get_product_cost(
+    db: State<'_, DbState>,
+    product_id: String,
+) -> Result<ProductCost, ApiError> {
+    traced("get_product_cost", async move {
+        debug!(product_id = %product_id, "get_product_cost command");
+        let db_inner: &Database = (*db).inner()?;
+        if db_inner.products().get_by_id(&product_id).await?.is_none() {
+            return Err(ApiError::not_found("Product", &product_id));
+        }
+        Ok(db_inner.suppliers().product_cost(&product_id).await?)
+    })
+    .await
+}
@@ -10,15 +10,40 @@
 //! │  get_sync_status()   - Returns current sync status                     │
 //! │  get_sync_config()   - Returns current sync configuration              │
 //! │  set_sync_mode()     - Changes the sync mode                           │
-//! │  get_pending_sync()  - Returns pending outbox count                    │
+//! │  set_metered_mode()  - Throttles uploads for metered (LTE) links       │
+//! │  pause_sync()        - Stops uploads and inbound updates               │
+//! │  resume_sync()       - Undoes pause_sync()                             │
+//! │  trigger_full_resync() - Drops sync cursors and re-downloads the       │
+//! │                        catalog and stock from the hub                  │
+//! │  get_pending_sync()  - Returns pending outbox counts by ack level      │
+//! │  list_outbox_entries() - Outbox inspector: entries and their ack level │
+//! │  get_sync_history()  - Connection periods and daily uptime percentages │
+//! │  diagnose_cloud_connectivity() - Walks DNS → TCP → proxy → TLS →       │
+//! │                        gRPC → auth and reports the failing hop         │
 //! └─────────────────────────────────────────────────────────────────────────┘
 //! ```
 
+use chrono::{Duration, Local, Offset, Utc};
 use serde::{Deserialize, Serialize};
 use tauri::State;
+use titan_core::{
+    daily_uptime, DailyUptime, OutboxAckCounts, OutboxEntryStatus, SyncStatusPeriod,
+};
+use titan_db::Database;
+use ts_rs::TS;
 
-use crate::error::ApiError;
-use crate::state::{SyncState, SyncStatusDto};
+use crate::error::{ApiError, ErrorCode};
+use crate::middleware::traced;
+use crate::state::{ConfigState, DbState, SyncState, SyncStatusDto};
+
+/// Days of history returned when none are asked for.
+const DEFAULT_HISTORY_DAYS: u32 = 7;
+
+/// Outbox entries listed when no limit is given.
+const DEFAULT_OUTBOX_LIMIT: u32 = 100;
+
+/// Most outbox entries listed at once.
+const MAX_OUTBOX_LIMIT: u32 = 1000;
 
 /// Gets the current sync status.
 ///
@@ -28,11 +53,15 @@ use crate::state::{SyncState, SyncStatusDto};
 pub async fn get_sync_status(
     sync: State<'_, SyncState>,
 ) -> Result<SyncStatusDto, ApiError> {
-    Ok(sync.get_status())
+    traced("get_sync_status", async move {
+        Ok(sync.get_status())
+    })
+    .await
 }
 
 /// Response DTO for sync configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct SyncConfigDto {
     /// Device UUID
@@ -62,39 +91,43 @@ pub struct SyncConfigDto {
 pub async fn get_sync_config(
     sync: State<'_, SyncState>,
 ) -> Result<SyncConfigDto, ApiError> {
-    let config = sync.get_config();
-    let is_running = sync.is_running();
-
-    match config {
-        Some(cfg) => {
-            let sync_mode = match cfg.sync.mode {
-                titan_sync::SyncMode::Auto => "auto",
-                titan_sync::SyncMode::Primary => "primary",
-                titan_sync::SyncMode::Secondary => "secondary",
-                titan_sync::SyncMode::Offline => "offline",
-            };
-
-            Ok(SyncConfigDto {
-                device_id: cfg.device.id.clone(),
-                device_name: cfg.device.name.clone(),
-                store_id: cfg.store.id.clone(),
-                store_name: cfg.store.name.clone(),
-                sync_mode: sync_mode.to_string(),
-                is_running,
-            })
-        }
-        None => {
-            // Return default config when not configured
-            Ok(SyncConfigDto {
-                device_id: "unconfigured".to_string(),
-                device_name: "Unconfigured Device".to_string(),
-                store_id: "".to_string(),
-                store_name: "".to_string(),
-                sync_mode: "offline".to_string(),
-                is_running: false,
-            })
+    traced("get_sync_config", async move {
+        let config = sync.get_config();
+        let is_running = sync.is_running();
+
+        match config {
+            Some(cfg) => {
+                let sync_mode = match cfg.sync.mode {
+                    titan_sync::SyncMode::Auto => "auto",
+                    titan_sync::SyncMode::Primary => "primary",
+                    titan_sync::SyncMode::Secondary => "secondary",
+                    titan_sync::SyncMode::Offline => "offline",
+                    titan_sync::SyncMode::Peer => "peer",
+                };
+
+                Ok(SyncConfigDto {
+                    device_id: cfg.device.id.clone(),
+                    device_name: cfg.device.name.clone(),
+                    store_id: cfg.store.id.clone(),
+                    store_name: cfg.store.name.clone(),
+                    sync_mode: sync_mode.to_string(),
+                    is_running,
+                })
+            }
+            None => {
+                // Return default config when not configured
+                Ok(SyncConfigDto {
+                    device_id: "unconfigured".to_string(),
+                    device_name: "Unconfigured Device".to_string(),
+                    store_id: "".to_string(),
+                    store_name: "".to_string(),
+                    sync_mode: "offline".to_string(),
+                    is_running: false,
+                })
+            }
         }
-    }
+    })
+    .await
 }
 
 /// Sets the sync mode.
@@ -102,6 +135,10 @@ pub async fn get_sync_config(
 /// # Arguments
 /// * `mode` - New sync mode: "auto", "primary", "secondary", or "offline"
 ///
+/// Peer mode needs the other terminal's address, so it can only be set in
+/// `sync.toml` (`mode = "peer"` with `peer_url`) or with `TITAN_PEER_URL`;
+/// asking for it here is a validation error.
+///
 /// # Returns
 /// Updated `SyncStatusDto` reflecting the new mode.
 #[tauri::command]
@@ -109,33 +146,353 @@ pub async fn set_sync_mode(
     sync: State<'_, SyncState>,
     mode: String,
 ) -> Result<SyncStatusDto, ApiError> {
-    let _sync_mode = match mode.as_str() {
-        "auto" => titan_sync::SyncMode::Auto,
-        "primary" => titan_sync::SyncMode::Primary,
-        "secondary" => titan_sync::SyncMode::Secondary,
-        "offline" => titan_sync::SyncMode::Offline,
-        _ => {
-            return Err(ApiError::validation(format!(
-                "Invalid sync mode: {}. Must be 'auto', 'primary', 'secondary', or 'offline'",
-                mode
-            )));
-        }
-    };
+    traced("set_sync_mode", async move {
+        let _sync_mode = match mode.as_str() {
+            "auto" => titan_sync::SyncMode::Auto,
+            "primary" => titan_sync::SyncMode::Primary,
+            "secondary" => titan_sync::SyncMode::Secondary,
+            "offline" => titan_sync::SyncMode::Offline,
+            "peer" => {
+                return Err(ApiError::validation(
+                    "Peer mode can only be set in sync.toml (mode = \"peer\" with peer_url) or with TITAN_PEER_URL",
+                ));
+            }
+            _ => {
+                return Err(ApiError::validation(format!(
+                    "Invalid sync mode: {}. Must be 'auto', 'primary', 'secondary', or 'offline'",
+                    mode
+                )));
+            }
+        };
+
+        // TODO: Implement mode change when SyncAgent supports runtime mode changes
+        // For now, this just validates the mode and returns current status
+        tracing::info!(mode = %mode, "Sync mode change requested (not yet implemented)");
 
-    // TODO: Implement mode change when SyncAgent supports runtime mode changes
-    // For now, this just validates the mode and returns current status
-    tracing::info!(mode = %mode, "Sync mode change requested (not yet implemented)");
+        Ok(sync.get_status())
+    })
+    .await
+}
 
-    Ok(sync.get_status())
+/// Turns metered mode on or off.
+///
+/// While metered, uploads are capped at the configured metered bandwidth
+/// and only run inside the configured sync windows. The change applies to
+/// the running agent immediately.
+///
+/// # Arguments
+/// * `enabled` - Whether the store is on a metered link
+///
+/// # Returns
+/// Updated `SyncStatusDto` reflecting the new setting.
+#[tauri::command]
+pub async fn set_metered_mode(
+    sync: State<'_, SyncState>,
+    enabled: bool,
+) -> Result<SyncStatusDto, ApiError> {
+    traced("set_metered_mode", async move {
+        sync.set_metered(enabled).await;
+        Ok(sync.get_status())
+    })
+    .await
 }
 
-/// Gets the pending outbox count.
+/// Pauses sync on this terminal.
+///
+/// Sales keep queueing in the outbox and updates from the hub are dropped
+/// until `resume_sync` is called; a full resync afterwards catches up
+/// anything missed. The hub connection stays up.
 ///
 /// # Returns
-/// Number of pending outbox entries.
+/// Updated `SyncStatusDto` with `paused` set.
+#[tauri::command]
+pub async fn pause_sync(
+    sync: State<'_, SyncState>,
+) -> Result<SyncStatusDto, ApiError> {
+    traced("pause_sync", async move {
+        let handle = running_agent(&sync)?;
+        sync.update_status(handle.pause().await.into());
+        Ok(sync.get_status())
+    })
+    .await
+}
+
+/// Resumes sync after `pause_sync`.
+///
+/// # Returns
+/// Updated `SyncStatusDto` with `paused` cleared.
+#[tauri::command]
+pub async fn resume_sync(
+    sync: State<'_, SyncState>,
+) -> Result<SyncStatusDto, ApiError> {
+    traced("resume_sync", async move {
+        let handle = running_agent(&sync)?;
+        sync.update_status(handle.resume().await.into());
+        Ok(sync.get_status())
+    })
+    .await
+}
+
+/// Re-downloads the catalog from the hub.
+///
+/// Drops this terminal's inbound sync cursors and asks the hub for every
+/// product, stock counts included. Local stock that disagrees with the
+/// hub is corrected. Progress is reported through `resyncing` in the sync
+/// status; the command returns once the request is sent.
+///
+/// # Returns
+/// Updated `SyncStatusDto` with `resyncing` set.
+///
+/// # Errors
+/// Fails when sync is paused, the hub is not connected, or the hub is too
+/// old to support a full resync.
+#[tauri::command]
+pub async fn trigger_full_resync(
+    sync: State<'_, SyncState>,
+) -> Result<SyncStatusDto, ApiError> {
+    traced("trigger_full_resync", async move {
+        let handle = running_agent(&sync)?;
+        let status = handle.trigger_full_resync().await.map_err(|e| {
+            ApiError::new(e.code(), format!("Full resync could not start: {}", e))
+        })?;
+        sync.update_status(status.into());
+        Ok(sync.get_status())
+    })
+    .await
+}
+
+/// The running sync agent, or an error for commands that need one.
+pub(crate) fn running_agent(sync: &SyncState) -> Result<titan_sync::SyncAgentHandle, ApiError> {
+    sync.agent_handle().ok_or_else(|| {
+        ApiError::new(ErrorCode::BusinessLogic, "Sync agent is not running")
+    })
+}
+
+/// Connects to the cloud as this terminal, for commands that call cloud
+/// services directly rather than through the hub.
+///
+/// The cloud URL comes from the environment profile (`TITAN_CLOUD_URL`
+/// overrides it) and credentials from the environment (see
+/// `CloudAuthConfig::from_env_or`). Call `disconnect` when done.
+pub(crate) async fn connect_cloud(
+    config: &titan_sync::SyncConfig,
+    app_config: &ConfigState,
+) -> titan_sync::SyncResult<titan_sync::CloudUplink> {
+    let auth = titan_sync::CloudAuthConfig::from_env_or(
+        Some(app_config.cloud_url.clone()),
+        config.store.id.clone(),
+        app_config.tenant_id.clone(),
+        None,
+        config.device.id.clone(),
+        Some(config.device.name.clone()),
+    );
+    let mut uplink = titan_sync::CloudUplink::new(titan_sync::CloudUplinkConfig {
+        cloud_url: auth.cloud_url,
+        store_id: auth.store_id,
+        tenant_id: auth.tenant_id,
+        api_key: auth.api_key,
+        device_id: auth.device_id,
+        device_name: auth.device_name,
+        verify_tls: auth.verify_tls,
+        proxy: auth.proxy,
+        ..Default::default()
+    })?;
+    uplink.connect().await?;
+    Ok(uplink)
+}
+
+/// Gets the pending outbox counts.
+///
+/// An entry the hub has acked is not done yet if it is bound for the
+/// cloud: the hub holds it until the cloud acks it, and it is uploaded
+/// again if that ack does not arrive within `cloud_ack_timeout_secs`.
+///
+/// # Returns
+/// `OutboxAckCounts` with the entries not acked yet and those held by
+/// the hub.
 #[tauri::command]
 pub async fn get_pending_sync_count(
+    db: State<'_, DbState>,
+) -> Result<OutboxAckCounts, ApiError> {
+    traced("get_pending_sync_count", async move {
+        let db_inner: &Database = (*db).inner()?;
+        Ok(db_inner.sync_outbox().count_by_ack_level().await?)
+    })
+    .await
+}
+
+/// Lists outbox entries with their ack level (outbox inspector).
+///
+/// # Arguments
+/// * `limit` - Most entries to return (default 100, at most 1000)
+/// * `include_synced` - Also list synced entries, after the pending ones
+///
+/// # Returns
+/// Pending entries oldest first, then synced entries newest first.
+#[tauri::command]
+pub async fn list_outbox_entries(
+    db: State<'_, DbState>,
+    limit: Option<u32>,
+    include_synced: Option<bool>,
+) -> Result<Vec<OutboxEntryStatus>, ApiError> {
+    traced("list_outbox_entries", async move {
+        let limit = limit
+            .unwrap_or(DEFAULT_OUTBOX_LIMIT)
+            .clamp(1, MAX_OUTBOX_LIMIT);
+
+        let db_inner: &Database = (*db).inner()?;
+        let entries = db_inner
+            .sync_outbox()
+            .list_entries(limit, include_synced.unwrap_or(false))
+            .await?;
+        Ok(entries)
+    })
+    .await
+}
+
+/// Response DTO for the sync status history.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncHistoryDto {
+    /// Connection periods, oldest first
+    pub periods: Vec<SyncStatusPeriod>,
+
+    /// Uptime per local calendar day, oldest first
+    pub daily_uptime: Vec<DailyUptime>,
+}
+
+/// Gets the hub connection history and daily uptime.
+///
+/// Lists every connection state change (with the reason for lost
+/// connections) and how much of each day this terminal was connected, for
+/// showing an unreliable store network to the ISP.
+///
+/// # Arguments
+/// * `days` - How many days back to report (default 7, at most 90)
+///
+/// # Returns
+/// `SyncHistoryDto` with the periods and per-day uptime.
+#[tauri::command]
+pub async fn get_sync_history(
+    db: State<'_, DbState>,
+    days: Option<u32>,
+) -> Result<SyncHistoryDto, ApiError> {
+    traced("get_sync_history", async move {
+        let days = days
+            .unwrap_or(DEFAULT_HISTORY_DAYS)
+            .clamp(1, titan_sync::agent::STATUS_HISTORY_RETENTION_DAYS);
+        let now = Utc::now();
+        let since = now - Duration::days(days as i64);
+
+        let db_inner: &Database = (*db).inner()?;
+        let periods = db_inner.sync_outbox().sync_status_history(since).await?;
+
+        let offset = Local::now().offset().fix();
+        let daily_uptime = daily_uptime(&periods, offset, now)
+            .into_iter()
+            .filter(|day| day.day >= since.with_timezone(&offset).date_naive())
+            .collect();
+
+        Ok(SyncHistoryDto {
+            periods,
+            daily_uptime,
+        })
+    })
+    .await
+}
+
+/// One hop of the connectivity diagnostic.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct HopResultDto {
+    /// "dns", "tcp", "proxy", "tls", "grpc" or "auth"
+    pub hop: String,
+
+    /// Whether the hop succeeded
+    pub ok: bool,
+
+    /// What was reached, or why it failed
+    pub detail: String,
+
+    /// Time spent on the hop
+    #[ts(type = "number")]
+    pub elapsed_ms: u64,
+}
+
+/// Response DTO for the connectivity diagnostic.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityReportDto {
+    /// Cloud API URL that was checked
+    pub cloud_url: String,
+
+    /// Proxy used (without credentials)
+    pub proxy: Option<String>,
+
+    /// Whether every hop succeeded
+    pub ok: bool,
+
+    /// The hop where the path broke, if any
+    pub failed_hop: Option<String>,
+
+    /// Hops attempted, in order
+    pub hops: Vec<HopResultDto>,
+}
+
+/// Checks the path to the cloud API hop by hop.
+///
+/// Uses the profile's cloud URL (`TITAN_CLOUD_URL` overrides it) and the
+/// cloud settings from the environment (`TITAN_API_KEY`,
+/// `TITAN_CLOUD_PROXY`/`HTTPS_PROXY`). Authentication is only attempted
+/// when an API key is set.
+///
+/// # Returns
+/// `ConnectivityReportDto` naming the first hop that failed.
+#[tauri::command]
+pub async fn diagnose_cloud_connectivity(
     sync: State<'_, SyncState>,
-) -> Result<i64, ApiError> {
-    Ok(sync.get_status().pending_outbox_count)
+    app_config: State<'_, ConfigState>,
+) -> Result<ConnectivityReportDto, ApiError> {
+    traced("diagnose_cloud_connectivity", async move {
+        let config = sync
+            .get_config()
+            .ok_or_else(|| ApiError::internal("Sync is not configured"))?;
+
+        let auth_config = titan_sync::CloudAuthConfig::from_env_or(
+            Some(app_config.cloud_url.clone()),
+            config.store.id.clone(),
+            std::env::var("TITAN_TENANT_ID").unwrap_or_default(),
+            None,
+            config.device.id.clone(),
+            Some(config.device.name.clone()),
+        );
+
+        let report = titan_sync::cloud_net::diagnose(&auth_config).await;
+        tracing::info!(
+            ok = report.is_ok(),
+            failed_hop = ?report.failed_hop().map(|h| h.hop),
+            "Cloud connectivity diagnostic finished"
+        );
+
+        Ok(ConnectivityReportDto {
+            cloud_url: report.cloud_url.clone(),
+            proxy: report.proxy.clone(),
+            ok: report.is_ok(),
+            failed_hop: report.failed_hop().map(|h| h.hop.to_string()),
+            hops: report
+                .hops
+                .into_iter()
+                .map(|h| HopResultDto {
+                    hop: h.hop.to_string(),
+                    ok: h.ok,
+                    detail: h.detail,
+                    elapsed_ms: h.elapsed.as_millis() as u64,
+                })
+                .collect(),
+        })
+    })
+    .await
 }
@@ -0,0 +1,300 @@
+//! # Till Commands
+//!
+//! Till (cash drawer) open, blind close, the variance exceptions report,
+//! and change breakdown for the configured currency.
+//!
+//! ## What the Cashier Sees
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                    Blind Close: Visibility Rules                        │
+//! │                                                                         │
+//! │  Command                  │ Cashier sees            │ Hidden            │
+//! │  ─────────────────────────┼─────────────────────────┼─────────────────  │
+//! │  open_till                │ float, opened_at        │ -                 │
+//! │  get_till_session         │ float, opened_at        │ expected cash     │
+//! │  close_till_blind         │ counted total           │ expected/variance │
+//! │  get_variance_exceptions  │ (manager report)        │ -                 │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{debug, info};
+use ts_rs::TS;
+
+use crate::error::{ApiError, ErrorCode};
+use crate::middleware::traced;
+use crate::state::{ConfigState, DbState};
+use titan_core::till::validate_denomination_counts;
+use titan_core::{
+    ChangeBreakdown, CoreError, DenominationCount, TillSession, VarianceException,
+    VariancePolicy,
+};
+use titan_db::Database;
+
+/// Cashier ID used for till sessions (matches the sale commands).
+const USER_ID: &str = "default";
+
+/// Default look-back window for the exceptions report.
+const DEFAULT_EXCEPTION_WINDOW_DAYS: i64 = 30;
+
+/// Till session as shown to the cashier (expected cash is never exposed).
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TillSessionDto {
+    pub id: String,
+    pub device_id: String,
+    pub user_id: String,
+    pub status: String,
+    #[ts(type = "number")]
+    pub opening_float_cents: i64,
+    pub opened_at: String,
+}
+
+impl From<TillSession> for TillSessionDto {
+    fn from(s: TillSession) -> Self {
+        TillSessionDto {
+            id: s.id,
+            device_id: s.device_id,
+            user_id: s.user_id,
+            status: format!("{:?}", s.status).to_lowercase(),
+            opening_float_cents: s.opening_float_cents,
+            opened_at: s.opened_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Result of a blind close. Deliberately omits expected cash and variance.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct BlindCloseResponse {
+    pub session_id: String,
+    #[ts(type = "number")]
+    pub counted_cash_cents: i64,
+    pub closed_at: String,
+}
+
+/// A single denomination line from the count screen.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct DenominationCountInput {
+    #[ts(type = "number")]
+    pub denomination_cents: i64,
+    #[ts(type = "number")]
+    pub quantity: i64,
+}
+
+/// Row in the variance exceptions report.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct VarianceExceptionDto {
+    pub user_id: String,
+    #[ts(type = "number")]
+    pub large_variance_count: i64,
+    #[ts(type = "number")]
+    pub net_variance_cents: i64,
+    #[ts(type = "number")]
+    pub absolute_variance_cents: i64,
+    pub last_closed_at: Option<String>,
+}
+
+impl From<VarianceException> for VarianceExceptionDto {
+    fn from(e: VarianceException) -> Self {
+        VarianceExceptionDto {
+            user_id: e.user_id,
+            large_variance_count: e.large_variance_count,
+            net_variance_cents: e.net_variance_cents,
+            absolute_variance_cents: e.absolute_variance_cents,
+            last_closed_at: e.last_closed_at.map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+/// Notes and coins to hand back for a change amount.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeBreakdownDto {
+    pub currency_code: String,
+    pub pieces: Vec<DenominationCountInput>,
+    #[ts(type = "number")]
+    pub piece_count: i64,
+    #[ts(type = "number")]
+    pub total_cents: i64,
+    /// Part of the change that no note/coin can cover (e.g. sub-rupee amounts).
+    #[ts(type = "number")]
+    pub remainder_cents: i64,
+}
+
+impl ChangeBreakdownDto {
+    fn new(currency_code: String, breakdown: ChangeBreakdown) -> Self {
+        ChangeBreakdownDto {
+            currency_code,
+            piece_count: breakdown.piece_count(),
+            pieces: breakdown
+                .pieces
+                .into_iter()
+                .map(|p| DenominationCountInput {
+                    denomination_cents: p.denomination_cents,
+                    quantity: p.quantity,
+                })
+                .collect(),
+            total_cents: breakdown.total_cents,
+            remainder_cents: breakdown.remainder_cents,
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn open_till(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    opening_float_cents: i64,
+) -> Result<TillSessionDto, ApiError> {
+    traced("open_till", async move {
+        debug!(opening_float = %opening_float_cents, "open_till command");
+
+        if opening_float_cents < 0 {
+            return Err(ApiError::validation("Opening float cannot be negative"));
+        }
+
+        let db_inner: &Database = (*db).inner()?;
+        let session = db_inner
+            .tills()
+            .open_session(&config.station.device_id(), USER_ID, opening_float_cents)
+            .await?;
+
+        info!(session_id = %session.id, "Till opened");
+
+        Ok(session.into())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_till_session(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+) -> Result<Option<TillSessionDto>, ApiError> {
+    traced("get_till_session", async move {
+        debug!("get_till_session command");
+
+        let db_inner: &Database = (*db).inner()?;
+        let session = db_inner
+            .tills()
+            .get_open_session(&config.station.device_id())
+            .await?;
+
+        Ok(session.map(TillSessionDto::from))
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn close_till_blind(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    counts: Vec<DenominationCountInput>,
+    notes: Option<String>,
+) -> Result<BlindCloseResponse, ApiError> {
+    traced("close_till_blind", async move {
+        debug!(lines = counts.len(), "close_till_blind command");
+
+        let counts: Vec<DenominationCount> = counts
+            .into_iter()
+            .map(|c| DenominationCount {
+                denomination_cents: c.denomination_cents,
+                quantity: c.quantity,
+            })
+            .collect();
+
+        match config.denominations() {
+            Some(set) => set.counted_total(&counts).map(|_| ()),
+            None => validate_denomination_counts(&counts),
+        }
+        .map_err(CoreError::from)?;
+
+        let db_inner: &Database = (*db).inner()?;
+
+        let session = db_inner
+            .tills()
+            .get_open_session(&config.station.device_id())
+            .await?
+            .ok_or_else(|| ApiError::new(ErrorCode::BusinessLogic, "No open till session"))?;
+
+        let closed = db_inner
+            .tills()
+            .close_session(&session.id, USER_ID, &counts, notes.as_deref())
+            .await?;
+
+        let payload = serde_json::to_string(&closed)
+            .map_err(|e| ApiError::new(ErrorCode::Internal, e.to_string()))?;
+        db_inner
+            .sync_outbox()
+            .queue_for_sync("TILL_SESSION", &closed.id, &payload)
+            .await?;
+
+        info!(session_id = %closed.id, "Till closed (blind)");
+
+        Ok(BlindCloseResponse {
+            session_id: closed.id,
+            counted_cash_cents: closed.counted_cash_cents.unwrap_or(0),
+            closed_at: closed.closed_at.unwrap_or_else(Utc::now).to_rfc3339(),
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_variance_exceptions(
+    db: State<'_, DbState>,
+    large_variance_cents: Option<i64>,
+    flag_after: Option<i64>,
+    days: Option<i64>,
+) -> Result<Vec<VarianceExceptionDto>, ApiError> {
+    traced("get_variance_exceptions", async move {
+        debug!("get_variance_exceptions command");
+
+        let defaults = VariancePolicy::default();
+        let policy = VariancePolicy {
+            large_variance_cents: large_variance_cents.unwrap_or(defaults.large_variance_cents),
+            flag_after: flag_after.unwrap_or(defaults.flag_after),
+        };
+
+        if policy.large_variance_cents <= 0 || policy.flag_after <= 0 {
+            return Err(ApiError::validation("Variance thresholds must be positive"));
+        }
+
+        let since = Utc::now() - Duration::days(days.unwrap_or(DEFAULT_EXCEPTION_WINDOW_DAYS));
+
+        let db_inner: &Database = (*db).reporting()?;
+        let rows = db_inner.tills().variance_exceptions(&policy, since).await?;
+
+        Ok(rows.into_iter().map(VarianceExceptionDto::from).collect())
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn calculate_change_breakdown(
+    config: State<'_, ConfigState>,
+    change_cents: i64,
+) -> Result<ChangeBreakdownDto, ApiError> {
+    debug!(change = %change_cents, "calculate_change_breakdown command");
+
+    let set = config.denominations().ok_or_else(|| {
+        ApiError::new(
+            ErrorCode::BusinessLogic,
+            format!("No denominations configured for {}", config.currency_code),
+        )
+    })?;
+
+    let breakdown = set.change_breakdown(change_cents).map_err(CoreError::from)?;
+
+    Ok(ChangeBreakdownDto::new(set.currency_code, breakdown))
+}
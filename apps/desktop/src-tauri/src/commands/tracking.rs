@@ -0,0 +1,219 @@
+//! # Serial & Lot Tracking Commands
+//!
+//! Capture serial/lot numbers on draft sales and look up sold units for
+//! product recalls.
+//!
+//! ## Where Codes Are Captured
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                    Tracking Code Capture                                │
+//! │                                                                         │
+//! │  Scan at the counter                                                    │
+//! │    add_to_cart(productId, qty, trackingCodes) ──► create_sale           │
+//! │                                                     (codes copied)      │
+//! │                                                                         │
+//! │  Sale created another way (e.g. convert_quote_to_sale)                  │
+//! │    set_sale_item_tracking(saleId, saleItemId, trackingCodes)            │
+//! │                                                                         │
+//! │  finalize_sale ──► every tracked line must have its codes, or BLOCK     │
+//! │                                                                         │
+//! │  Recall: find_sales_by_lot("LOT-2024-07") ──► receipts to contact       │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{debug, info};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::error::{ApiError, ErrorCode};
+use crate::middleware::traced;
+use crate::state::DbState;
+use titan_core::tracking::{normalize_tracking_codes, validate_tracking_codes};
+use titan_core::{CoreError, ItemTracking, SaleItem, SaleItemTracking, SaleStatus, TrackedSaleLine};
+use titan_db::Database;
+
+/// Default page size for recall lookups.
+const DEFAULT_LOOKUP_LIMIT: u32 = 200;
+
+/// A sold line matched by a recall lookup.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackedSaleDto {
+    pub sale_id: String,
+    pub receipt_number: String,
+    pub device_id: String,
+    pub completed_at: Option<String>,
+    pub product_id: String,
+    pub sku: String,
+    pub name: String,
+    #[ts(type = "number")]
+    pub quantity: i64,
+    pub code: String,
+}
+
+impl From<TrackedSaleLine> for TrackedSaleDto {
+    fn from(l: TrackedSaleLine) -> Self {
+        TrackedSaleDto {
+            sale_id: l.sale_id,
+            receipt_number: l.receipt_number,
+            device_id: l.device_id,
+            completed_at: l.completed_at.map(|t| t.to_rfc3339()),
+            product_id: l.product_id,
+            sku: l.sku_snapshot,
+            name: l.name_snapshot,
+            quantity: l.quantity,
+            code: l.code,
+        }
+    }
+}
+
+/// Records serial/lot numbers for a line on a draft sale.
+///
+/// Replaces any codes captured earlier for the line.
+#[tauri::command]
+pub async fn set_sale_item_tracking(
+    db: State<'_, DbState>,
+    sale_id: String,
+    sale_item_id: String,
+    tracking_codes: Vec<String>,
+) -> Result<(), ApiError> {
+    traced("set_sale_item_tracking", async move {
+        debug!(sale_id = %sale_id, sale_item_id = %sale_item_id, "set_sale_item_tracking command");
+
+        let db_inner: &Database = (*db).inner()?;
+
+        let sale = db_inner
+            .sales()
+            .get_by_id(&sale_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Sale", &sale_id))?;
+
+        if sale.status != SaleStatus::Draft {
+            return Err(ApiError::new(
+                ErrorCode::BusinessLogic,
+                format!("Sale is {:?}, cannot change tracking codes", sale.status),
+            ));
+        }
+
+        let item = db_inner
+            .sales()
+            .get_items(&sale_id)
+            .await?
+            .into_iter()
+            .find(|i| i.id == sale_item_id)
+            .ok_or_else(|| ApiError::not_found("Sale item", &sale_item_id))?;
+
+        let product = db_inner
+            .products()
+            .get_by_id(&item.product_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Product", &item.product_id))?;
+
+        let codes = normalize_tracking_codes(&tracking_codes);
+        validate_tracking_codes(product.item_tracking, item.quantity, &codes).map_err(CoreError::from)?;
+
+        let rows = tracking_rows(&item, product.item_tracking, &codes);
+        db_inner.sales().set_item_tracking(&item.id, &rows).await?;
+
+        info!(sale_id = %sale_id, sale_item_id = %item.id, count = rows.len(), "Tracking codes captured");
+
+        Ok(())
+    })
+    .await
+}
+
+/// Finds completed sales that included a given lot number.
+///
+/// ## Arguments
+/// * `lot_number` - Lot/batch number from the recall notice
+/// * `product_id` - Optionally restrict to one product
+/// * `limit` - Maximum lines to return (default: 200)
+#[tauri::command]
+pub async fn find_sales_by_lot(
+    db: State<'_, DbState>,
+    lot_number: String,
+    product_id: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<TrackedSaleDto>, ApiError> {
+    traced("find_sales_by_lot", async move {
+        debug!(lot_number = %lot_number, product_id = ?product_id, "find_sales_by_lot command");
+
+        let lot_number = lot_number.trim();
+        if lot_number.is_empty() {
+            return Err(ApiError::validation("Lot number is required"));
+        }
+
+        let db_inner: &Database = (*db).inner()?;
+        let lines = db_inner
+            .sales()
+            .find_by_tracking_code(
+                ItemTracking::Lot,
+                lot_number,
+                product_id.as_deref(),
+                limit.unwrap_or(DEFAULT_LOOKUP_LIMIT),
+            )
+            .await?;
+
+        Ok(lines.into_iter().map(TrackedSaleDto::from).collect())
+    })
+    .await
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Builds the tracking rows for a sale line.
+pub(crate) fn tracking_rows(
+    item: &SaleItem,
+    kind: ItemTracking,
+    codes: &[String],
+) -> Vec<SaleItemTracking> {
+    let now = Utc::now();
+    codes
+        .iter()
+        .map(|code| SaleItemTracking {
+            id: Uuid::new_v4().to_string(),
+            sale_id: item.sale_id.clone(),
+            sale_item_id: item.id.clone(),
+            product_id: item.product_id.clone(),
+            kind,
+            code: code.clone(),
+            created_at: now,
+        })
+        .collect()
+}
+
+/// Blocks finalization while any serial/lot-tracked line is missing codes.
+pub(crate) async fn ensure_tracking_captured(
+    db: &Database,
+    sale_id: &str,
+    items: &[SaleItem],
+) -> Result<(), ApiError> {
+    let captured = db.sales().get_tracking(sale_id).await?;
+
+    for item in items {
+        let Some(product) = db.products().get_by_id(&item.product_id).await? else {
+            continue;
+        };
+        if product.item_tracking == ItemTracking::None {
+            continue;
+        }
+
+        let codes: Vec<String> = captured
+            .iter()
+            .filter(|c| c.sale_item_id == item.id)
+            .map(|c| c.code.clone())
+            .collect();
+
+        validate_tracking_codes(product.item_tracking, item.quantity, &codes).map_err(|e| {
+            ApiError::validation(format!("{}: {}", item.sku_snapshot, e))
+        })?;
+    }
+
+    Ok(())
+}
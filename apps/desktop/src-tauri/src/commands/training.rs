@@ -0,0 +1,140 @@
+//! # Training Commands
+//!
+//! Training mode lets new cashiers practice on a sandbox copy of the
+//! database (see `DbState::set_training_mode`). Nothing done in training
+//! reaches the live database, the sync outbox or the fiscal chain.
+//!
+//! ## Commands
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Training Commands                                │
+//! │                                                                         │
+//! │  set_training_mode(true)    empty cart only; sandbox copied on first   │
+//! │                             use this run, then every command uses it   │
+//! │  set_training_mode(false)   back to the live database                  │
+//! │  get_training_mode          for the banner on every screen             │
+//! │                                                                         │
+//! │  While on: receipts carry the TRAINING watermark, cart and sale        │
+//! │  responses and every command error have trainingMode = true.           │
+//! │  Store credit and loyalty rewards can't be tendered: those balances    │
+//! │  are kept at the hub and belong to real customers.                     │
+//! │  Coupons are counted in the sandbox only, never at the hub.            │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::info;
+use ts_rs::TS;
+
+use titan_core::PaymentMethod;
+
+use crate::error::{ApiError, ErrorCode};
+use crate::middleware::traced;
+use crate::state::{training_mode, CartState, DbState};
+
+/// Watermark printed across training receipts.
+pub const TRAINING_WATERMARK: &str = "TRAINING";
+
+/// Whether training mode is on.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TrainingModeDto {
+    pub enabled: bool,
+    /// Watermark on receipts while training (`null` when off).
+    pub watermark: Option<String>,
+}
+
+impl TrainingModeDto {
+    fn current() -> Self {
+        let enabled = training_mode();
+        TrainingModeDto {
+            enabled,
+            watermark: enabled.then(|| TRAINING_WATERMARK.to_string()),
+        }
+    }
+}
+
+/// Turns training mode on or off.
+///
+/// ## Errors
+/// - `CART_ERROR` if the cart is not empty: a sale can't move between the
+///   live database and the sandbox
+/// - `NOT_READY` while the app is starting
+/// - `INTERNAL` if the sandbox could not be copied
+#[tauri::command]
+pub async fn set_training_mode(
+    db: State<'_, DbState>,
+    cart: State<'_, CartState>,
+    enabled: bool,
+) -> Result<TrainingModeDto, ApiError> {
+    traced("set_training_mode", async move {
+        info!(enabled, "set_training_mode command");
+
+        if enabled != training_mode() && cart.with_cart(|c| !c.items.is_empty()) {
+            return Err(ApiError::cart(
+                "Finish or clear the current sale before switching training mode",
+            ));
+        }
+
+        // NOT_READY rather than a database error while starting up
+        db.live()?;
+        db.set_training_mode(enabled).await?;
+        Ok(TrainingModeDto::current())
+    })
+    .await
+}
+
+/// Gets whether training mode is on.
+#[tauri::command]
+pub fn get_training_mode() -> TrainingModeDto {
+    TrainingModeDto::current()
+}
+
+/// Refuses tenders that spend a real customer's balance while training.
+///
+/// Store credit and loyalty rewards are redeemed at the hub, which the
+/// sandbox does not replace, so a practice sale would spend live money.
+///
+/// ## Errors
+/// - `PAYMENT_ERROR` for `store_credit` or `loyalty_reward` in training mode
+pub(crate) fn ensure_tender_allowed(method: PaymentMethod) -> Result<(), ApiError> {
+    tender_allowed(method, training_mode())
+}
+
+fn tender_allowed(method: PaymentMethod, training: bool) -> Result<(), ApiError> {
+    let tender = match method {
+        PaymentMethod::StoreCredit => "Store credit",
+        PaymentMethod::LoyaltyReward => "Loyalty rewards",
+        PaymentMethod::Cash | PaymentMethod::ExternalCard => return Ok(()),
+    };
+    if training {
+        return Err(ApiError::new(
+            ErrorCode::PaymentError,
+            format!("{} can't be tendered in training mode", tender),
+        ));
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_training_refuses_hub_balances() {
+        for method in [PaymentMethod::StoreCredit, PaymentMethod::LoyaltyReward] {
+            let err = tender_allowed(method, true).unwrap_err();
+            assert_eq!(err.code, ErrorCode::PaymentError);
+            assert!(tender_allowed(method, false).is_ok());
+        }
+        for method in [PaymentMethod::Cash, PaymentMethod::ExternalCard] {
+            assert!(tender_allowed(method, true).is_ok());
+        }
+    }
+}
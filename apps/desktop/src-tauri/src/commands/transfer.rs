@@ -0,0 +1,430 @@
+//! # Store Transfer Commands
+//!
+//! Ship stock to another store of the same tenant and count in transfers
+//! shipped to this store. The cloud routes each transfer between the two
+//! stores, so both sides see it while it is in transit.
+//!
+//! ## Transfer Workflow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Transfer Workflow                                │
+//! │                                                                         │
+//! │  Store A                                        Store B                 │
+//! │  ───────                                        ───────                 │
+//! │  create_transfer(to B, lines) ──► stock -= sent                         │
+//! │         │                                                               │
+//! │         │ (queued as STORE_TRANSFER → cloud → B)                        │
+//! │         ▼                                                               │
+//! │  list_transfers: outbound, in_transit     list_transfers: inbound       │
+//! │                                                  │                      │
+//! │                                                  ▼                      │
+//! │                                 receive_transfer(counts)                │
+//! │                                 stock += received                       │
+//! │                                                                         │
+//! │  cancel_transfer (sender only, while in transit): stock += sent         │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{debug, info};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::commands::sale::generate_receipt_number;
+use crate::error::{ApiError, ErrorCode};
+use crate::middleware::traced;
+use crate::state::{ConfigState, DbState, SyncState};
+use titan_core::transfer::{
+    validate_received_quantity, validate_transfer_items, validate_transfer_route,
+};
+use titan_core::{
+    CoreError, StoreTransfer, StoreTransferDocument, StoreTransferItem, TransferDirection,
+    TransferStatus,
+};
+use titan_db::Database;
+
+/// Cashier ID used for transfers (matches the sale commands).
+const USER_ID: &str = "default";
+
+/// Default page size for `list_transfers`.
+const DEFAULT_LIST_LIMIT: u32 = 50;
+
+/// A line requested by the UI when creating a transfer.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferLineInput {
+    pub product_id: String,
+    #[ts(type = "number")]
+    pub quantity: i64,
+}
+
+/// A counted-in quantity for one transfer line.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferCountInput {
+    pub item_id: String,
+    #[ts(type = "number")]
+    pub quantity_received: i64,
+}
+
+/// A transfer line as shown in the UI.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferItemDto {
+    pub id: String,
+    pub product_id: String,
+    pub sku: String,
+    pub name: String,
+    #[ts(type = "number")]
+    pub quantity_sent: i64,
+    #[ts(type = "number | null")]
+    pub quantity_received: Option<i64>,
+    #[ts(type = "number")]
+    pub shortfall: i64,
+}
+
+impl From<StoreTransferItem> for TransferItemDto {
+    fn from(i: StoreTransferItem) -> Self {
+        TransferItemDto {
+            shortfall: i.shortfall(),
+            id: i.id,
+            product_id: i.product_id,
+            sku: i.sku_snapshot,
+            name: i.name_snapshot,
+            quantity_sent: i.quantity_sent,
+            quantity_received: i.quantity_received,
+        }
+    }
+}
+
+/// A store transfer as shown in the UI.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferDto {
+    pub id: String,
+    pub transfer_number: String,
+    /// "outbound" or "inbound" relative to this store.
+    pub direction: String,
+    pub status: String,
+    pub from_store_id: String,
+    pub to_store_id: String,
+    pub notes: Option<String>,
+    pub created_at: String,
+    pub received_at: Option<String>,
+    pub cancelled_at: Option<String>,
+    /// Empty in list views.
+    pub items: Vec<TransferItemDto>,
+}
+
+impl TransferDto {
+    fn new(transfer: StoreTransfer, items: Vec<StoreTransferItem>, store_id: &str) -> Self {
+        let direction = match transfer.direction_for(store_id) {
+            Some(TransferDirection::Inbound) => "inbound",
+            _ => "outbound",
+        };
+
+        TransferDto {
+            direction: direction.to_string(),
+            status: transfer.status.as_str().to_string(),
+            id: transfer.id,
+            transfer_number: transfer.transfer_number,
+            from_store_id: transfer.from_store_id,
+            to_store_id: transfer.to_store_id,
+            notes: transfer.notes,
+            created_at: transfer.created_at.to_rfc3339(),
+            received_at: transfer.received_at.map(|t| t.to_rfc3339()),
+            cancelled_at: transfer.cancelled_at.map(|t| t.to_rfc3339()),
+            items: items.into_iter().map(TransferItemDto::from).collect(),
+        }
+    }
+
+    fn from_document(doc: StoreTransferDocument, store_id: &str) -> Self {
+        TransferDto::new(doc.transfer, doc.items, store_id)
+    }
+}
+
+/// Ships stock from this store to another store.
+///
+/// Stock for every tracked product leaves this store immediately; the
+/// receiving store adds what it counts in when it receives the transfer.
+#[tauri::command]
+pub async fn create_transfer(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    sync: State<'_, SyncState>,
+    to_store_id: String,
+    items: Vec<TransferLineInput>,
+    notes: Option<String>,
+) -> Result<TransferDto, ApiError> {
+    traced("create_transfer", async move {
+        debug!(to_store_id = %to_store_id, lines = items.len(), "create_transfer command");
+
+        let store_id = local_store_id(&sync)?;
+        let to_store_id = to_store_id.trim().to_string();
+        validate_transfer_route(&store_id, &to_store_id).map_err(CoreError::from)?;
+
+        let db_inner: &Database = (*db).inner()?;
+        let now = Utc::now();
+        let transfer_id = Uuid::new_v4().to_string();
+
+        let mut transfer_items = Vec::with_capacity(items.len());
+        for line in &items {
+            let product = db_inner
+                .products()
+                .get_by_id(&line.product_id)
+                .await?
+                .ok_or_else(|| ApiError::not_found("Product", &line.product_id))?;
+
+            let available = product.current_stock.unwrap_or(0);
+            if product.track_inventory && available < line.quantity && !product.allow_negative_stock {
+                return Err(ApiError::insufficient_stock(&product.sku, available, line.quantity));
+            }
+
+            transfer_items.push(StoreTransferItem {
+                id: Uuid::new_v4().to_string(),
+                transfer_id: transfer_id.clone(),
+                product_id: product.id,
+                sku_snapshot: product.sku,
+                name_snapshot: product.name,
+                quantity_sent: line.quantity,
+                quantity_received: None,
+            });
+        }
+        validate_transfer_items(&transfer_items).map_err(CoreError::from)?;
+
+        let doc = StoreTransferDocument {
+            transfer: StoreTransfer {
+                id: transfer_id.clone(),
+                tenant_id: config.tenant_id.clone(),
+                transfer_number: generate_transfer_number(),
+                from_store_id: store_id.clone(),
+                to_store_id,
+                status: TransferStatus::InTransit,
+                notes: notes.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
+                created_by: USER_ID.to_string(),
+                received_by: None,
+                created_at: now,
+                updated_at: now,
+                received_at: None,
+                cancelled_at: None,
+                sync_version: 1,
+            },
+            items: transfer_items,
+        };
+
+        db_inner
+            .transfers()
+            .create(&doc, &config.station.device_id())
+            .await?;
+        queue_transfer(db_inner, &doc).await?;
+
+        info!(
+            transfer_id = %transfer_id,
+            transfer_number = %doc.transfer.transfer_number,
+            to_store_id = %doc.transfer.to_store_id,
+            lines = doc.items.len(),
+            "Store transfer created"
+        );
+
+        Ok(TransferDto::from_document(doc, &store_id))
+    })
+    .await
+}
+
+/// Counts in a transfer shipped to this store.
+///
+/// `counts` must cover every line. Only the received quantities are added
+/// to stock; any shortfall stays on the transfer for follow-up.
+#[tauri::command]
+pub async fn receive_transfer(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    sync: State<'_, SyncState>,
+    transfer_ref: String,
+    counts: Vec<TransferCountInput>,
+) -> Result<TransferDto, ApiError> {
+    traced("receive_transfer", async move {
+        debug!(transfer_ref = %transfer_ref, lines = counts.len(), "receive_transfer command");
+
+        let store_id = local_store_id(&sync)?;
+        let db_inner: &Database = (*db).inner()?;
+        let doc = load_document(db_inner, &transfer_ref).await?;
+
+        if doc.transfer.direction_for(&store_id) != Some(TransferDirection::Inbound) {
+            return Err(ApiError::validation("Only the receiving store can receive a transfer"));
+        }
+        doc.transfer.ensure_in_transit()?;
+
+        let counts: HashMap<&str, i64> = counts
+            .iter()
+            .map(|c| (c.item_id.as_str(), c.quantity_received))
+            .collect();
+
+        let mut received = Vec::with_capacity(doc.items.len());
+        for item in &doc.items {
+            let quantity = *counts.get(item.id.as_str()).ok_or_else(|| {
+                ApiError::validation(format!("Missing received quantity for {}", item.sku_snapshot))
+            })?;
+            validate_received_quantity(item.quantity_sent, quantity).map_err(CoreError::from)?;
+            received.push((item.id.clone(), quantity));
+        }
+
+        db_inner
+            .transfers()
+            .receive(&doc.transfer.id, &received, USER_ID, &config.station.device_id())
+            .await?;
+
+        let doc = load_document(db_inner, &doc.transfer.id).await?;
+        queue_transfer(db_inner, &doc).await?;
+
+        let shortfall: i64 = doc.items.iter().map(StoreTransferItem::shortfall).sum();
+        info!(transfer_id = %doc.transfer.id, shortfall = shortfall, "Store transfer received");
+
+        Ok(TransferDto::from_document(doc, &store_id))
+    })
+    .await
+}
+
+/// Cancels a transfer this store shipped, putting the goods back in stock.
+#[tauri::command]
+pub async fn cancel_transfer(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    sync: State<'_, SyncState>,
+    transfer_ref: String,
+) -> Result<TransferDto, ApiError> {
+    traced("cancel_transfer", async move {
+        debug!(transfer_ref = %transfer_ref, "cancel_transfer command");
+
+        let store_id = local_store_id(&sync)?;
+        let db_inner: &Database = (*db).inner()?;
+        let doc = load_document(db_inner, &transfer_ref).await?;
+
+        if doc.transfer.direction_for(&store_id) != Some(TransferDirection::Outbound) {
+            return Err(ApiError::validation("Only the sending store can cancel a transfer"));
+        }
+        doc.transfer.ensure_in_transit()?;
+
+        db_inner
+            .transfers()
+            .cancel(&doc.transfer.id, &config.station.device_id())
+            .await?;
+
+        let doc = load_document(db_inner, &doc.transfer.id).await?;
+        queue_transfer(db_inner, &doc).await?;
+
+        info!(transfer_id = %doc.transfer.id, "Store transfer cancelled");
+
+        Ok(TransferDto::from_document(doc, &store_id))
+    })
+    .await
+}
+
+/// Lists recent transfers in and out of this store, optionally by status.
+#[tauri::command]
+pub async fn list_transfers(
+    db: State<'_, DbState>,
+    sync: State<'_, SyncState>,
+    status: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<TransferDto>, ApiError> {
+    traced("list_transfers", async move {
+        debug!(status = ?status, "list_transfers command");
+
+        let status = match status.as_deref() {
+            None => None,
+            Some("in_transit") => Some(TransferStatus::InTransit),
+            Some("received") => Some(TransferStatus::Received),
+            Some("cancelled") => Some(TransferStatus::Cancelled),
+            Some(other) => {
+                return Err(ApiError::validation(format!("Unknown transfer status: {}", other)));
+            }
+        };
+
+        let store_id = local_store_id(&sync)?;
+        let db_inner: &Database = (*db).inner()?;
+        let transfers = db_inner
+            .transfers()
+            .list(&store_id, status, limit.unwrap_or(DEFAULT_LIST_LIMIT))
+            .await?;
+
+        Ok(transfers
+            .into_iter()
+            .map(|t| TransferDto::new(t, Vec::new(), &store_id))
+            .collect())
+    })
+    .await
+}
+
+/// Gets a transfer with its lines by ID or printed number.
+#[tauri::command]
+pub async fn get_transfer(
+    db: State<'_, DbState>,
+    sync: State<'_, SyncState>,
+    transfer_ref: String,
+) -> Result<TransferDto, ApiError> {
+    traced("get_transfer", async move {
+        debug!(transfer_ref = %transfer_ref, "get_transfer command");
+
+        let store_id = local_store_id(&sync)?;
+        let db_inner: &Database = (*db).inner()?;
+        let doc = load_document(db_inner, &transfer_ref).await?;
+
+        Ok(TransferDto::from_document(doc, &store_id))
+    })
+    .await
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// The store this terminal belongs to, from the sync configuration.
+///
+/// Transfers are routed by store ID, so they need a configured store.
+fn local_store_id(sync: &SyncState) -> Result<String, ApiError> {
+    sync.get_config()
+        .map(|cfg| cfg.store.id)
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| ApiError::validation("Store is not configured for sync; transfers need a store ID"))
+}
+
+/// Loads a transfer document by ID, falling back to the printed number.
+async fn load_document(db: &Database, transfer_ref: &str) -> Result<StoreTransferDocument, ApiError> {
+    if let Some(doc) = db.transfers().get_document(transfer_ref).await? {
+        return Ok(doc);
+    }
+
+    let transfer = db
+        .transfers()
+        .get_by_number(transfer_ref)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Transfer", transfer_ref))?;
+
+    db.transfers()
+        .get_document(&transfer.id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Transfer", transfer_ref))
+}
+
+/// Queues the latest state of a transfer for the cloud to route.
+async fn queue_transfer(db: &Database, doc: &StoreTransferDocument) -> Result<(), ApiError> {
+    let payload = serde_json::to_string(doc)
+        .map_err(|e| ApiError::new(ErrorCode::Internal, e.to_string()))?;
+    db.sync_outbox()
+        .upsert_for_sync("STORE_TRANSFER", &doc.transfer.id, &payload)
+        .await?;
+    Ok(())
+}
+
+fn generate_transfer_number() -> String {
+    format!("T-{}", generate_receipt_number())
+}
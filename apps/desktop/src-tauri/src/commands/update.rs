@@ -0,0 +1,335 @@
+//! # Update Commands
+//!
+//! App self-update: release checks against the cloud, verified bundle
+//! downloads, and the hub's staged rollout.
+//!
+//! ## Update Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                           Update Flow                                   │
+//! │                                                                         │
+//! │  check_for_update         cloud GetLatestRelease (channel, platform,    │
+//! │                           current version) ──► release or none          │
+//! │                                                                         │
+//! │  download_update          fetch bundle ──► SHA-256 matches?             │
+//! │                           ──► Ed25519 signature valid? ──► saved        │
+//! │                           (nothing is saved if either check fails)      │
+//! │                                                                         │
+//! │  request_update_slot      hub: one device at a time, hub last           │
+//! │                           not granted ──► ask again after retryAfterSecs│
+//! │                                                                         │
+//! │  frontend runs the installer; the new build starts and calls           │
+//! │  report_update_finished   ──► hub frees the slot for the next device    │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! The release signing public key is built in from
+//! `TITAN_UPDATE_PUBLIC_KEY` (64 hex characters) at compile time; builds
+//! without one cannot install updates.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use directories::ProjectDirs;
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::State;
+use tracing::{info, warn};
+use ts_rs::TS;
+
+use crate::commands::sync::{connect_cloud, running_agent};
+use crate::error::{ApiError, ErrorCode};
+use crate::middleware::traced;
+use crate::state::{ConfigState, SyncState, UpdateChannel};
+
+/// Public key release bundles are signed with (hex).
+const UPDATE_PUBLIC_KEY: Option<&str> = option_env!("TITAN_UPDATE_PUBLIC_KEY");
+
+/// Version of this build.
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Longest a bundle download may take.
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// A release offered by the cloud.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseDto {
+    pub version: String,
+    pub channel: String,
+    pub download_url: String,
+    /// Hex SHA-256 of the bundle.
+    pub sha256: String,
+    /// Hex Ed25519 signature of the bundle.
+    pub signature: String,
+    pub notes: String,
+    /// The store must install this release.
+    pub mandatory: bool,
+    pub published_at: Option<String>,
+}
+
+/// Result of a release check.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCheckDto {
+    pub current_version: String,
+    pub channel: UpdateChannel,
+    pub update_available: bool,
+    pub release: Option<ReleaseDto>,
+}
+
+/// A verified bundle, ready to install.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadedUpdateDto {
+    pub version: String,
+    /// Where the bundle was saved.
+    pub path: String,
+    #[ts(type = "number")]
+    pub size_bytes: u64,
+}
+
+/// The hub's answer to an update slot request.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSlotDto {
+    /// Install now.
+    pub granted: bool,
+    /// Place in line when not granted (1 = next).
+    pub queue_position: u32,
+    /// When to ask again if not granted.
+    #[ts(type = "number")]
+    pub retry_after_secs: u64,
+}
+
+/// Asks the cloud for the newest release on this terminal's channel.
+///
+/// # Errors
+/// `CLOUD_ERROR` if the cloud could not be reached or refused the check.
+#[tauri::command]
+pub async fn check_for_update(
+    sync: State<'_, SyncState>,
+    config: State<'_, ConfigState>,
+) -> Result<UpdateCheckDto, ApiError> {
+    traced("check_for_update", async move {
+        let sync_config = sync
+            .get_config()
+            .ok_or_else(|| ApiError::internal("Sync is not configured"))?;
+        let channel = config.update_channel;
+
+        let mut uplink = connect_cloud(&sync_config, &config)
+            .await
+            .map_err(cloud_error)?;
+
+        let response = uplink
+            .get_latest_release(channel.as_str(), &platform(), CURRENT_VERSION)
+            .await
+            .map_err(cloud_error)?;
+        uplink.disconnect().await;
+
+        let release = response
+            .release
+            .filter(|_| response.update_available)
+            .map(|r| ReleaseDto {
+                version: r.version,
+                channel: r.channel,
+                download_url: r.download_url,
+                sha256: r.sha256,
+                signature: r.signature,
+                notes: r.notes,
+                mandatory: r.mandatory,
+                published_at: r.published_at.map(|t| t.value),
+            });
+        info!(
+            channel = channel.as_str(),
+            current_version = CURRENT_VERSION,
+            offered = release.as_ref().map(|r| r.version.as_str()),
+            "Update check finished"
+        );
+
+        Ok(UpdateCheckDto {
+            current_version: CURRENT_VERSION.to_string(),
+            channel,
+            update_available: release.is_some(),
+            release,
+        })
+    })
+    .await
+}
+
+/// Downloads a release bundle and verifies its digest and signature.
+///
+/// # Arguments
+/// * `release` - A release returned by `check_for_update`
+///
+/// # Errors
+/// - `CONFIG_ERROR` if this build has no release signing key
+/// - `VALIDATION_ERROR` for a download URL that is not HTTPS
+/// - `INTEGRITY_FAILED` if the digest or signature does not match
+/// - `UNAVAILABLE` if the download fails
+#[tauri::command]
+pub async fn download_update(release: ReleaseDto) -> Result<DownloadedUpdateDto, ApiError> {
+    traced("download_update", async move {
+        let public_key = UPDATE_PUBLIC_KEY
+            .and_then(|key| hex::decode(key.trim()).ok())
+            .ok_or_else(|| {
+                ApiError::new(
+                    ErrorCode::ConfigError,
+                    "This build has no release signing key",
+                )
+            })?;
+        if !release.download_url.starts_with("https://") {
+            return Err(ApiError::validation("Update downloads must use HTTPS"));
+        }
+
+        let bundle = fetch(&release.download_url).await.map_err(|e| {
+            ApiError::new(
+                ErrorCode::Unavailable,
+                format!("Update download failed: {}", e),
+            )
+        })?;
+        if let Err(reason) =
+            verify_bundle(&bundle, &release.sha256, &release.signature, &public_key)
+        {
+            warn!(version = %release.version, reason = %reason, "Rejected update bundle");
+            return Err(ApiError::new(
+                ErrorCode::IntegrityFailed,
+                format!("Update {} rejected: {}", release.version, reason),
+            ));
+        }
+
+        let path = updates_dir()?.join(bundle_file_name(&release));
+        tokio::fs::write(&path, &bundle)
+            .await
+            .map_err(|e| ApiError::internal(format!("Could not save update: {}", e)))?;
+
+        info!(version = %release.version, path = %path.display(), "Update downloaded and verified");
+        Ok(DownloadedUpdateDto {
+            version: release.version,
+            path: path.display().to_string(),
+            size_bytes: bundle.len() as u64,
+        })
+    })
+    .await
+}
+
+/// Asks the hub whether this terminal may install `version` now.
+///
+/// # Errors
+/// Fails when the sync agent is not running or the hub is unreachable.
+#[tauri::command]
+pub async fn request_update_slot(
+    sync: State<'_, SyncState>,
+    version: String,
+) -> Result<UpdateSlotDto, ApiError> {
+    traced("request_update_slot", async move {
+        let handle = running_agent(&sync)?;
+        let result = handle
+            .request_update_slot(&version)
+            .await
+            .map_err(|e| ApiError::new(e.code(), format!("Update slot request failed: {}", e)))?;
+
+        Ok(UpdateSlotDto {
+            granted: result.granted,
+            queue_position: result.queue_position,
+            retry_after_secs: result.retry_after_secs,
+        })
+    })
+    .await
+}
+
+/// Tells the hub this terminal finished updating (or gave up), so the
+/// next device can go.
+///
+/// # Arguments
+/// * `version` - The version that was installed
+/// * `success` - Whether the install worked
+#[tauri::command]
+pub async fn report_update_finished(
+    sync: State<'_, SyncState>,
+    version: String,
+    success: bool,
+) -> Result<(), ApiError> {
+    traced("report_update_finished", async move {
+        let handle = running_agent(&sync)?;
+        handle
+            .report_update_finished(&version, success)
+            .await
+            .map_err(|e| ApiError::new(e.code(), format!("Update report failed: {}", e)))
+    })
+    .await
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Release platform name, e.g. "windows-x86_64".
+fn platform() -> String {
+    format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH)
+}
+
+fn cloud_error(e: titan_sync::SyncError) -> ApiError {
+    ApiError::new(ErrorCode::CloudError, format!("Update check failed: {}", e))
+}
+
+async fn fetch(url: &str) -> Result<Vec<u8>, reqwest::Error> {
+    let client = reqwest::Client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .build()?;
+    let response = client.get(url).send().await?.error_for_status()?;
+    Ok(response.bytes().await?.to_vec())
+}
+
+/// Checks a bundle against its published digest and signature.
+fn verify_bundle(
+    bundle: &[u8],
+    sha256_hex: &str,
+    signature_hex: &str,
+    public_key: &[u8],
+) -> Result<(), String> {
+    let digest = hex::encode(Sha256::digest(bundle));
+    if !digest.eq_ignore_ascii_case(sha256_hex.trim()) {
+        return Err("SHA-256 digest does not match".to_string());
+    }
+
+    let signature =
+        hex::decode(signature_hex.trim()).map_err(|_| "signature is not hex".to_string())?;
+    UnparsedPublicKey::new(&ED25519, public_key)
+        .verify(bundle, &signature)
+        .map_err(|_| "signature is not valid".to_string())
+}
+
+/// Directory bundles are saved in (created if missing).
+fn updates_dir() -> Result<PathBuf, ApiError> {
+    let dirs = ProjectDirs::from("com", "titan", "pos")
+        .ok_or_else(|| ApiError::internal("Could not determine app data directory"))?;
+    let dir = dirs.data_dir().join("updates");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| ApiError::internal(format!("Could not create update directory: {}", e)))?;
+    Ok(dir)
+}
+
+/// The bundle's file name from its URL, limited to safe characters.
+fn bundle_file_name(release: &ReleaseDto) -> String {
+    let from_url = release
+        .download_url
+        .split(['?', '#'])
+        .next()
+        .and_then(|path| path.rsplit('/').next())
+        .unwrap_or_default();
+    let safe = from_url
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+
+    if safe && !from_url.is_empty() && !from_url.starts_with('.') {
+        from_url.to_string()
+    } else {
+        format!("titan-pos-{}", release.version.replace(['/', '\\'], "_"))
+    }
+}
@@ -0,0 +1,152 @@
+//! # Waste Commands
+//!
+//! Writing off damaged, expired or stolen goods, and the daily waste
+//! report (see `titan_core::waste`).
+//!
+//! ## Commands
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                          Waste Commands                                 │
+//! │                                                                         │
+//! │  record_waste(product, 4, "expiry", note)   stock - 4, delta tagged    │
+//! │                                             "expiry", queued for cloud │
+//! │  get_waste_report(date)                     per reason units / value,  │
+//! │                                             and the day's records      │
+//! │                                                                         │
+//! │  The report covers this terminal's database; the cloud                 │
+//! │  GetShrinkageReport covers every store of the tenant.                  │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use tracing::{debug, info};
+use ts_rs::TS;
+
+use crate::error::ApiError;
+use crate::middleware::traced;
+use crate::state::{ConfigState, DbState};
+use titan_core::tax_report::report_window;
+use titan_core::{CoreError, WasteReason, WasteReasonSummary, WasteRecord, WasteReport};
+use titan_db::Database;
+
+/// Cashier ID recorded on waste records (matches the sale commands).
+const USER_ID: &str = "default";
+
+/// A waste record with what the report shows.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct WasteRecordDto {
+    pub id: String,
+    pub product_id: String,
+    pub sku: String,
+    pub name: String,
+    #[ts(type = "number")]
+    pub quantity: i64,
+    pub reason: WasteReason,
+    /// Retail value of the goods when written off.
+    #[ts(type = "number")]
+    pub value_cents: i64,
+    pub note: Option<String>,
+    pub recorded_by: String,
+    pub recorded_at: String,
+}
+
+/// Waste recorded on one day.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct WasteReportDto {
+    pub date: String,
+    /// One row per reason with waste.
+    pub reasons: Vec<WasteReasonSummary>,
+    /// Oldest first.
+    pub records: Vec<WasteRecordDto>,
+    #[ts(type = "number")]
+    pub total_units: i64,
+    #[ts(type = "number")]
+    pub total_value_cents: i64,
+}
+
+/// Writes goods off stock with a reason.
+///
+/// ## Arguments
+/// * `product_id` - The product written off
+/// * `quantity` - Units (eaches) written off
+/// * `reason` - "damage", "expiry" or "theft"
+/// * `note` - Optional, e.g. where the goods were found
+///
+/// ## Errors
+/// - `VALIDATION_ERROR` for a quantity out of range or a note that is too
+///   long
+/// - `NOT_FOUND` if the product doesn't exist or is deleted
+#[tauri::command]
+pub async fn record_waste(
+    db: State<'_, DbState>,
+    config: State<'_, ConfigState>,
+    product_id: String,
+    quantity: i64,
+    reason: WasteReason,
+    note: Option<String>,
+) -> Result<WasteRecordDto, ApiError> {
+    traced("record_waste", async move {
+        info!(product_id = %product_id, quantity, reason = reason.as_str(), "record_waste command");
+        let db_inner: &Database = (*db).inner()?;
+        let record = db_inner
+            .waste()
+            .record(&product_id, quantity, reason, note.as_deref(), USER_ID, &config.station.device_id())
+            .await?;
+        waste_record_dto(db_inner, record).await
+    })
+    .await
+}
+
+/// Builds the waste report for one day (`YYYY-MM-DD`, UTC).
+#[tauri::command]
+pub async fn get_waste_report(
+    db: State<'_, DbState>,
+    date: String,
+) -> Result<WasteReportDto, ApiError> {
+    traced("get_waste_report", async move {
+        debug!(date = %date, "get_waste_report command");
+        let db_inner: &Database = (*db).reporting()?;
+
+        let day = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+            .map_err(|_| ApiError::validation("'date' must be YYYY-MM-DD"))?;
+        let (start, end) = report_window(day, day).map_err(CoreError::from)?;
+        let report = WasteReport::new(day, db_inner.waste().list_between(start, end).await?);
+
+        let mut records = Vec::with_capacity(report.records.len());
+        for record in report.records.iter().cloned() {
+            records.push(waste_record_dto(db_inner, record).await?);
+        }
+
+        Ok(WasteReportDto {
+            date: report.date.to_string(),
+            total_units: report.total_units(),
+            total_value_cents: report.total_value_cents(),
+            reasons: report.reasons,
+            records,
+        })
+    })
+    .await
+}
+
+async fn waste_record_dto(db: &Database, record: WasteRecord) -> Result<WasteRecordDto, ApiError> {
+    let product = db.products().get_by_id(&record.product_id).await?;
+
+    Ok(WasteRecordDto {
+        sku: product.as_ref().map(|p| p.sku.clone()).unwrap_or_default(),
+        name: product.map(|p| p.name).unwrap_or_default(),
+        value_cents: record.value_cents(),
+        id: record.id,
+        product_id: record.product_id,
+        quantity: record.quantity,
+        reason: record.reason,
+        note: record.note,
+        recorded_by: record.recorded_by,
+        recorded_at: record.recorded_at.to_rfc3339(),
+    })
+}
@@ -0,0 +1,94 @@
+//! # Window Commands
+//!
+//! The manager back office opens in its own window next to the register.
+//! Both windows share the app's state, but the back office only runs
+//! reporting and product maintenance commands: reports use the reporting
+//! pool (see `DbState::reporting`) and never touch the cart, so a long
+//! report doesn't hold up scanning.
+//!
+//! ## Windows
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                          Desktop Windows                                │
+//! │                                                                         │
+//! │  "main"      register: cart, tender, receipts (tauri.conf.json)        │
+//! │  "manager"   back office: reports, products, suppliers, waste          │
+//! │              open_manager_window   opens it, or focuses it if open     │
+//! │              close_manager_window                                      │
+//! │                                                                         │
+//! │  entity:changed and sync events go to every window, so a price        │
+//! │  edited in the back office shows on the register at once.              │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+use tracing::info;
+use ts_rs::TS;
+
+use crate::error::ApiError;
+use crate::middleware::traced;
+
+/// Label of the manager back office window.
+pub const MANAGER_WINDOW: &str = "manager";
+
+/// Frontend route the manager window loads.
+const MANAGER_ROUTE: &str = "index.html#/manager";
+
+/// A window after opening it.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowDto {
+    pub label: String,
+    /// The window was already open and has been focused.
+    pub already_open: bool,
+}
+
+/// Opens the manager back office window, or focuses it if it is open.
+///
+/// ## Errors
+/// `INTERNAL` if the window could not be created or focused.
+#[tauri::command]
+pub async fn open_manager_window(app: AppHandle) -> Result<WindowDto, ApiError> {
+    traced("open_manager_window", async move {
+        if let Some(window) = app.get_webview_window(MANAGER_WINDOW) {
+            window
+                .set_focus()
+                .map_err(|e| ApiError::internal(format!("Cannot focus back office: {}", e)))?;
+            return Ok(WindowDto {
+                label: MANAGER_WINDOW.to_string(),
+                already_open: true,
+            });
+        }
+
+        WebviewWindowBuilder::new(&app, MANAGER_WINDOW, WebviewUrl::App(MANAGER_ROUTE.into()))
+            .title("Titan POS - Back Office")
+            .inner_size(1280.0, 800.0)
+            .min_inner_size(1024.0, 600.0)
+            .build()
+            .map_err(|e| ApiError::internal(format!("Cannot open back office: {}", e)))?;
+
+        info!("Manager window opened");
+        Ok(WindowDto {
+            label: MANAGER_WINDOW.to_string(),
+            already_open: false,
+        })
+    })
+    .await
+}
+
+/// Closes the manager back office window, if it is open.
+#[tauri::command]
+pub async fn close_manager_window(app: AppHandle) -> Result<(), ApiError> {
+    traced("close_manager_window", async move {
+        if let Some(window) = app.get_webview_window(MANAGER_WINDOW) {
+            window
+                .close()
+                .map_err(|e| ApiError::internal(format!("Cannot close back office: {}", e)))?;
+            info!("Manager window closed");
+        }
+        Ok(())
+    })
+    .await
+}
@@ -45,6 +45,9 @@
 use serde::Serialize;
 use titan_core::CoreError;
 use titan_db::DbError;
+use ts_rs::TS;
+
+use crate::state::NotReady;
 
 /// API error returned from Tauri commands.
 ///
@@ -53,10 +56,14 @@ use titan_db::DbError;
 /// ```json
 /// {
 ///   "code": "NOT_FOUND",
-///   "message": "Product not found: SKU-123"
+///   "message": "Product not found: SKU-123",
+///   "retryable": false,
+///   "correlationId": "5f0c…",
+///   "trainingMode": false
 /// }
 /// ```
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct ApiError {
     /// Machine-readable error code for programmatic handling
@@ -64,10 +71,24 @@ pub struct ApiError {
 
     /// Human-readable error message for display
     pub message: String,
+
+    /// Whether retrying the same command later may succeed
+    pub retryable: bool,
+
+    /// Correlation ID of the failed command, for finding it in the logs
+    /// (set by [`crate::middleware::traced`])
+    pub correlation_id: Option<String>,
+
+    /// Whether the command ran against the training sandbox (set by
+    /// [`crate::middleware::traced`])
+    pub training_mode: bool,
 }
 
 /// Error codes for API responses.
 ///
+/// Shared with the other crates (see [`titan_core::ErrorCode`]) so sync
+/// errors carry the same codes as command errors.
+///
 /// ## Usage in Frontend
 /// ```typescript
 /// try {
@@ -81,37 +102,12 @@ pub struct ApiError {
 ///       showForm(e.message);
 ///       break;
 ///     default:
-///       showError('An error occurred');
+///       if (e.retryable) scheduleRetry();
+///       else showError('An error occurred');
 ///   }
 /// }
 /// ```
-#[derive(Debug, Clone, Copy, Serialize)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-pub enum ErrorCode {
-    /// Resource not found (404)
-    NotFound,
-
-    /// Input validation failed (400)
-    ValidationError,
-
-    /// Database operation failed (500)
-    DatabaseError,
-
-    /// Business logic error (422)
-    BusinessLogic,
-
-    /// Internal server error (500)
-    Internal,
-
-    /// Cart operation failed
-    CartError,
-
-    /// Insufficient stock
-    InsufficientStock,
-
-    /// Payment processing error
-    PaymentError,
-}
+pub use titan_core::ErrorCode;
 
 impl ApiError {
     /// Creates a new API error.
@@ -119,6 +115,9 @@ impl ApiError {
         ApiError {
             code,
             message: message.into(),
+            retryable: code.is_retryable(),
+            correlation_id: None,
+            training_mode: false,
         }
     }
 
@@ -163,47 +162,64 @@ impl ApiError {
 }
 
 /// Converts database errors to API errors.
+///
+/// The code comes from [`DbError::code`]; internal details are logged and
+/// replaced by a generic message.
 impl From<DbError> for ApiError {
     fn from(err: DbError) -> Self {
+        let code = err.code();
         match err {
             DbError::NotFound { entity, id } => ApiError::not_found(&entity, &id),
-            DbError::UniqueViolation { field, value } => ApiError::new(
-                ErrorCode::ValidationError,
-                format!("{} '{}' already exists", field, value),
-            ),
-            DbError::ConnectionFailed(_) => {
-                ApiError::new(ErrorCode::DatabaseError, "Database connection failed")
-            }
-            DbError::MigrationFailed(_) => {
-                ApiError::new(ErrorCode::DatabaseError, "Database migration failed")
+            DbError::UniqueViolation { field, value } => {
+                ApiError::new(code, format!("{} '{}' already exists", field, value))
             }
+            DbError::VersionConflict { entity, .. } => ApiError::new(
+                code,
+                format!("{} was changed elsewhere; reload and try again", entity),
+            ),
+            DbError::ConnectionFailed(_) => ApiError::new(code, "Database connection failed"),
+            DbError::MigrationFailed(_) => ApiError::new(code, "Database migration failed"),
             DbError::QueryFailed(e) => {
                 // Log the actual error but return a generic message
                 tracing::error!("Database query failed: {}", e);
-                ApiError::new(ErrorCode::DatabaseError, "Database operation failed")
+                ApiError::new(code, "Database operation failed")
             }
             DbError::TransactionFailed(e) => {
                 tracing::error!("Transaction failed: {}", e);
-                ApiError::new(ErrorCode::DatabaseError, "Database transaction failed")
+                ApiError::new(code, "Database transaction failed")
             }
             DbError::ForeignKeyViolation { message } => {
                 tracing::error!("Foreign key violation: {}", message);
-                ApiError::new(ErrorCode::ValidationError, "Invalid reference")
+                ApiError::new(code, "Invalid reference")
             }
-            DbError::PoolExhausted => {
-                ApiError::new(ErrorCode::DatabaseError, "Database pool exhausted")
+            DbError::PoolExhausted => ApiError::new(code, "Database is busy, try again"),
+            DbError::InvalidSyncPayload(e) => {
+                tracing::error!("Invalid sync payload: {}", e);
+                ApiError::new(code, "Could not queue the change for sync")
             }
+            DbError::InvalidInput(e) => ApiError::new(code, e.to_string()),
             DbError::Internal(e) => {
                 tracing::error!("Internal database error: {}", e);
-                ApiError::new(ErrorCode::DatabaseError, "Database operation failed")
+                ApiError::new(code, "Database operation failed")
             }
         }
     }
 }
 
-/// Converts core errors to API errors.
+/// Converts "database not open yet" into `NOT_READY`: retryable while the
+/// app is starting, not once startup has failed.
+impl From<NotReady> for ApiError {
+    fn from(err: NotReady) -> Self {
+        let mut api = ApiError::new(ErrorCode::NotReady, err.to_string());
+        api.retryable = err.failure.is_none();
+        api
+    }
+}
+
+/// Converts core errors to API errors, with the code from [`CoreError::code`].
 impl From<CoreError> for ApiError {
     fn from(err: CoreError) -> Self {
+        let code = err.code();
         match err {
             CoreError::ProductNotFound(id) => ApiError::not_found("Product", &id),
             CoreError::SaleNotFound(id) => ApiError::not_found("Sale", &id),
@@ -212,7 +228,7 @@ impl From<CoreError> for ApiError {
                 available,
                 requested,
             } => ApiError::new(
-                ErrorCode::InsufficientStock,
+                code,
                 format!(
                     "Insufficient stock for {}: {} available, {} requested",
                     sku, available, requested
@@ -222,21 +238,73 @@ impl From<CoreError> for ApiError {
                 sale_id,
                 current_status,
             } => ApiError::new(
-                ErrorCode::BusinessLogic,
+                code,
                 format!("Sale {} is in {} status", sale_id, current_status),
             ),
             CoreError::CartTooLarge { max } => ApiError::new(
-                ErrorCode::CartError,
+                code,
                 format!("Cart cannot have more than {} items", max),
             ),
             CoreError::QuantityTooLarge { requested, max } => ApiError::new(
-                ErrorCode::ValidationError,
+                code,
                 format!("Quantity {} exceeds maximum allowed ({})", requested, max),
             ),
             CoreError::InvalidPaymentAmount { reason } => ApiError::new(
-                ErrorCode::PaymentError,
+                code,
                 format!("Invalid payment amount: {}", reason),
             ),
+            CoreError::QuoteNotConvertible { quote_id, reason } => ApiError::new(
+                code,
+                format!("Quote {} cannot be converted: {}", quote_id, reason),
+            ),
+            CoreError::InvalidLayawayStatus {
+                layaway_id,
+                current_status,
+            } => ApiError::new(
+                code,
+                format!("Layaway {} is in {} status", layaway_id, current_status),
+            ),
+            CoreError::InvalidTransferStatus {
+                transfer_id,
+                current_status,
+            } => ApiError::new(
+                code,
+                format!("Transfer {} is in {} status", transfer_id, current_status),
+            ),
+            CoreError::AgeRestricted {
+                required_age,
+                reason,
+            } => ApiError::new(
+                code,
+                format!("Customer must be at least {} to purchase: {}", required_age, reason),
+            ),
+            CoreError::InsufficientStoreCredit {
+                credit_number,
+                available_cents,
+                requested_cents,
+            } => ApiError::new(
+                code,
+                format!(
+                    "Store credit {} has {} available, {} requested",
+                    credit_number, available_cents, requested_cents
+                ),
+            ),
+            CoreError::FiscalSigningFailed { sale_id, reason } => ApiError::new(
+                code,
+                format!("Sale {} could not be fiscally signed: {}", sale_id, reason),
+            ),
+            CoreError::FeatureDisabled { feature } => ApiError::new(
+                code,
+                format!("{} is not enabled for this store", feature),
+            ),
+            other @ (CoreError::MarginBelowFloor { .. }
+            | CoreError::OverrideRequired { .. }
+            | CoreError::OverrideRejected { .. }
+            | CoreError::CouponRejected { .. }
+            | CoreError::InsufficientRewards { .. }) => ApiError::new(code, other.to_string()),
+            CoreError::DeviceFailed { device, reason } => {
+                ApiError::new(code, format!("{} failed: {}", device, reason))
+            }
             CoreError::Validation(e) => ApiError::validation(e.to_string()),
         }
     }
@@ -12,6 +12,15 @@
 //! │   ├── db.rs       ◄─── Database state wrapper
 //! │   ├── cart.rs     ◄─── Cart state management
 //! │   ├── config.rs   ◄─── Configuration state
+//! │   ├── crash.rs    ◄─── Panic hook, log tail, crash report queue
+//! │   ├── day_end.rs  ◄─── Scheduled day end: Z close, backup, sync flush
+//! │   ├── display.rs  ◄─── Customer pole display, fed by entity events
+//! │   ├── drawer.rs   ◄─── Cash drawer driver for the station
+//! │   ├── fiscal.rs   ◄─── Fiscal signing backend
+//! │   ├── portable.rs ◄─── Portable (USB) mode: data next to the executable
+//! │   ├── profile.rs  ◄─── Environment profiles (TITAN_ENV)
+//! │   ├── scale.rs    ◄─── Scale driver and live weight feed
+//! │   ├── scheduler.rs ◄─── Background job scheduler
 //! │   └── sync.rs     ◄─── Sync agent state
 //! ├── commands/
 //! │   ├── mod.rs      ◄─── Command exports
@@ -19,6 +28,7 @@
 //! │   ├── sale.rs     ◄─── Sale/transaction commands
 //! │   ├── cart.rs     ◄─── Cart manipulation commands
 //! │   └── sync.rs     ◄─── Sync status/control commands
+//! ├── middleware.rs   ◄─── Command logging, timing, correlation IDs
 //! └── error.rs        ◄─── API error type for commands
 //! ```
 //!
@@ -47,16 +57,30 @@
 
 pub mod commands;
 pub mod error;
+pub mod middleware;
 pub mod state;
 
-use directories::ProjectDirs;
-use std::path::PathBuf;
-use tauri::Manager;
-use tracing::{info, Level};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{error, info, warn, Level};
+use tracing_subscriber::fmt::writer::{BoxMakeWriter, MakeWriterExt};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::EnvFilter;
 
-use state::{CartState, ConfigState, DbState, SyncState};
-use titan_db::{Database, DbConfig};
+use commands::startup::{STARTUP_FAILED_EVENT, STARTUP_READY_EVENT};
+use state::{
+    day_end_job, load_payload_cipher, load_pii_master_key, sale_retention_job, AppProfile,
+    CartState, ConfigState, CrashState, DbState, DrawerState, EventBusState, FiscalState,
+    LineDisplayState, PortableMode, ScaleState, SchedulerState, SyncState,
+    DAY_END_DEFAULT_SCHEDULE, DAY_END_JOB, DEV_SEED_PRODUCTS, SALE_RETENTION_DEFAULT_SCHEDULE,
+    SALE_RETENTION_JOB,
+};
+use titan_db::{Database, DbConfig, Fixtures, PerformanceProfile};
+use titan_sync::telemetry::{self, Telemetry};
 
 /// Runs the Tauri application.
 ///
@@ -66,62 +90,148 @@ use titan_db::{Database, DbConfig};
 /// │                       Application Startup                               │
 /// │                                                                         │
 /// │  1. Initialize Logging ───────────────────────────────────────────────► │
+/// │     • Portable mode (--portable, TITAN_PORTABLE or titan.portable):     │
+/// │       load titan.env, log to titan-data/logs/titan.log                  │
+/// │     • Environment profile from TITAN_ENV (dev / staging / prod)         │
 /// │     • tracing-subscriber with env filter                                │
-/// │     • Default: INFO, can be overridden with RUST_LOG                    │
+/// │     • OTLP span export when OTEL_EXPORTER_OTLP_ENDPOINT is set          │
+/// │     • Default: the profile's filter, can be overridden with RUST_LOG    │
+/// │     • Log lines also kept in memory for crash reports; panic hook       │
+/// │       installed (CrashState)                                            │
 /// │                                                                         │
-/// │  2. Determine Database Path ──────────────────────────────────────────► │
+/// │  2. Determine Database Path (from the profile) ───────────────────────► │
 /// │     • macOS: ~/Library/Application Support/com.titan.pos/titan.db       │
 /// │     • Windows: %APPDATA%/titan/pos/titan.db                             │
 /// │     • Linux: ~/.local/share/titan-pos/titan.db                          │
+/// │     • dev: repo data/titan.db if seeded; dev and staging otherwise      │
+/// │       use a dev/ or staging/ subdirectory                               │
+/// │     • Portable: titan-data/ next to the executable instead              │
 /// │                                                                         │
-/// │  3. Connect to Database ──────────────────────────────────────────────► │
-/// │     • SQLite with WAL mode                                              │
-/// │     • Run pending migrations                                            │
-/// │                                                                         │
-/// │  4. Initialize State Objects ─────────────────────────────────────────► │
-/// │     • DbState: Wraps Database connection                                │
-/// │     • CartState: Empty cart with Mutex for thread-safe updates          │
-/// │     • ConfigState: Default configuration                                │
+/// │  3. Initialize State Objects ─────────────────────────────────────────► │
+/// │     • DbState: starting, no database yet (commands get NOT_READY)       │
+/// │     • CartState: Empty cart with RwLock for thread-safe updates         │
+/// │     • ConfigState: TITAN_* env vars and defaults, for the profile       │
+/// │     • SyncState: sync.toml and TITAN_* env vars (store, device, keys);  │
+/// │       the agent itself is not started                                   │
+/// │     • FiscalState: Fiscal backend from TITAN_FISCAL_* env vars          │
+/// │     • DrawerState: Cash drawer driver from the station's drawer setting │
+/// │     • ScaleState: Scale driver from TITAN_SCALE, live feed started      │
+/// │     • LineDisplayState: Pole display from TITAN_LINE_DISPLAY, fed by    │
+/// │       the event bus                                                     │
+/// │     • SchedulerState: Built-in jobs, loop not started yet; the day-end  │
+/// │       job (off by default) backs up into backups/ next to the database  │
+/// │       and the sale retention job prunes per ConfigState.retention       │
+/// │     • EventBusState: Entity events from the database, forwarded to UI   │
 /// │                                                                         │
-/// │  5. Build & Run Tauri App ────────────────────────────────────────────► │
+/// │  4. Build & Run Tauri App ────────────────────────────────────────────► │
 /// │     • Register all commands                                             │
 /// │     • Manage state                                                      │
-/// │     • Launch window                                                     │
+/// │     • Launch window (the frontend shows its splash while starting)      │
+/// │                                                                         │
+/// │  5. Connect to Database (background task, see spawn_startup) ────────► │
+/// │     • SQLite with WAL mode, pragmas from TITAN_DB_PROFILE               │
+/// │     • Run pending migrations                                            │
+/// │     • dev: seed a demo catalog into an empty database                   │
+/// │     • Open customer data key (TITAN_PII_KEY or pii.key)                 │
+/// │     • Load outbox payload keys (TITAN_PAYLOAD_KEYS or payload.keys)     │
+/// │     • Open a second, 2-connection pool for back office reports          │
+/// │     • Start the scheduling loop, emit "startup:ready"                   │
+/// │       (or "startup:failed")                                             │
+/// │                                                                         │
+/// │  6. Upload queued crash reports (TITAN_CRASH_UPLOAD=1) ──────────────► │
 /// └─────────────────────────────────────────────────────────────────────────┘
 /// ```
 pub fn run() {
-    // Initialize tracing (logging)
-    init_tracing();
+    // Portable mode loads its config file before anything reads the
+    // environment; problems are logged once logging is up
+    let portable = PortableMode::detect();
+    let mut startup_warnings = Vec::new();
+    let mut log_file = None;
+    if let Some(portable) = &portable {
+        if let Err(e) = portable.load_config() {
+            startup_warnings.push(format!(
+                "Cannot read {}: {}",
+                portable.config_path().display(),
+                e
+            ));
+        }
+        match portable.open_log_file() {
+            Ok(file) => log_file = Some(file),
+            Err(e) => startup_warnings.push(format!("Cannot open the portable log file: {}", e)),
+        }
+    }
+
+    // Pick the environment profile, then initialize tracing (logging) and
+    // crash capture
+    let profile = AppProfile::from_env().unwrap_or_else(|e| {
+        startup_warnings.push(format!("{}; using the build's default profile", e));
+        AppProfile::build_default()
+    });
+    let crash_state = CrashState::from_env();
+    let _telemetry = init_tracing(profile, &crash_state, log_file, &mut startup_warnings);
+    crash_state.install_panic_hook();
+    for message in &startup_warnings {
+        warn!(%message, "Startup configuration problem");
+    }
 
-    info!("Starting Titan POS Desktop Application");
+    info!(%profile, "Starting Titan POS Desktop Application");
+    if let Some(portable) = &portable {
+        info!(root = %portable.root().display(), "Portable mode, data is kept next to the executable");
+    }
 
     // Build and run the Tauri app
     tauri::Builder::default()
         // Setup hook runs before the app starts
-        .setup(|app| {
+        .setup(move |app| {
             // Determine database path
-            let db_path = get_database_path(app)?;
+            let db_path = profile.database_path(portable.as_ref())?;
             info!(?db_path, "Database path determined");
+            crash_state.set_dir(db_path.with_file_name("crashes"));
 
-            // Initialize database (blocking in setup, async in runtime)
-            let db = tauri::async_runtime::block_on(async {
-                let config = DbConfig::new(db_path);
-                Database::new(config).await
-            })?;
-
-            info!("Database connected and migrations applied");
-
-            // Initialize state objects
-            let db_state = DbState::new(db);
+            // Initialize state objects; the database itself is opened in
+            // the background so the window can paint first
+            let fiscal_dir = db_path.with_file_name("fiscal");
+            let config_state = ConfigState::for_profile(profile);
+            let event_bus = EventBusState::new();
+            event_bus.start(app.handle().clone());
+            let db_state = DbState::starting()
+                .with_training_sandbox(db_path.with_file_name("titan-training.db"));
             let cart_state = CartState::new();
-            let config_state = ConfigState::default();
-            let sync_state = SyncState::new();
+            let sync_state = SyncState::load();
+            let fiscal_state = FiscalState::from_env(&fiscal_dir)?;
+            let drawer_state = DrawerState::for_station(&config_state.station);
+            let scale_state = ScaleState::for_station(&config_state.station);
+            scale_state.start(app.handle().clone());
+            let display_state = LineDisplayState::for_station(&config_state.station);
+            display_state.start(&event_bus);
+            let scheduler_state = SchedulerState::with_builtin_jobs()
+                .job(
+                    SALE_RETENTION_JOB,
+                    SALE_RETENTION_DEFAULT_SCHEDULE,
+                    sale_retention_job(config_state.retention),
+                )
+                .app_job(
+                    DAY_END_JOB,
+                    DAY_END_DEFAULT_SCHEDULE,
+                    false,
+                    day_end_job(db_path.with_file_name("backups")),
+                );
 
             // Register state with Tauri
             app.manage(db_state);
             app.manage(cart_state);
             app.manage(config_state);
             app.manage(sync_state);
+            app.manage(fiscal_state);
+            app.manage(drawer_state);
+            app.manage(scale_state);
+            app.manage(display_state);
+            app.manage(scheduler_state);
+            app.manage(event_bus);
+            app.manage(crash_state);
+
+            spawn_startup(app.handle().clone(), db_path);
+            commands::crash::spawn_queued_upload(app.handle().clone());
 
             info!("State initialized (sync agent not started - requires configuration)");
             Ok(())
@@ -130,112 +240,338 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             // Product commands
             commands::product::search_products,
+            commands::product::search_products_page,
             commands::product::get_product_by_id,
             commands::product::get_product_by_sku,
+            commands::product::update_product,
+            commands::product::delete_product,
+            commands::product::restore_product,
+            commands::product::list_deleted_products,
+            commands::product::get_product_attributes,
+            commands::product::set_product_attributes,
+            commands::product::search_products_by_attribute,
+            commands::product::create_product_style,
+            commands::product::update_product_style,
+            commands::product::get_variant_group,
+            commands::product::set_product_variant,
+            commands::product::remove_product_variant,
+            commands::product::search_products_grouped,
+            commands::bundle::get_product_bundle,
+            commands::bundle::set_product_bundle,
+            commands::receiving::get_product_packs,
+            commands::receiving::set_product_packs,
+            commands::receiving::find_pack_by_barcode,
+            commands::receiving::receive_stock,
+            commands::receiving::get_stock_receipt,
+            commands::receiving::list_stock_receipts,
+            commands::receiving::get_stock_levels,
+            commands::waste::record_waste,
+            commands::waste::get_waste_report,
+            commands::supplier::create_supplier,
+            commands::supplier::update_supplier,
+            commands::supplier::set_supplier_items,
+            commands::supplier::get_supplier,
+            commands::supplier::list_suppliers,
+            commands::supplier::get_product_cost,
+            commands::margin::get_margin_overrides,
+            commands::override_token::issue_override_token,
+            commands::override_token::get_manager_overrides,
             // Cart commands
             commands::cart::get_cart,
             commands::cart::add_to_cart,
             commands::cart::update_cart_item,
+            commands::cart::override_line_price,
+            commands::cart::duplicate_cart_item,
+            commands::cart::move_cart_item,
+            commands::cart::set_quantities_bulk,
+            commands::cart::add_department_item,
+            commands::cart::add_price_embedded_item,
             commands::cart::remove_from_cart,
             commands::cart::clear_cart,
             // Sale commands
             commands::sale::create_sale,
             commands::sale::add_payment,
             commands::sale::finalize_sale,
+            commands::sale::search_sales_page,
+            commands::sale::fetch_sale_by_receipt,
+            commands::receipt::print_receipt_variant,
+            commands::receipt::get_receipt_campaigns,
+            commands::receipt::refresh_receipt_campaigns,
+            // Quote commands
+            commands::quote::save_quote,
+            commands::quote::list_quotes,
+            commands::quote::get_quote,
+            commands::quote::cancel_quote,
+            commands::quote::render_quote,
+            commands::quote::convert_quote_to_sale,
+            // Tracking commands
+            commands::tracking::set_sale_item_tracking,
+            commands::tracking::find_sales_by_lot,
+            // Age verification commands
+            commands::age::verify_customer_age,
+            commands::age::get_age_verification,
+            // Layaway commands
+            commands::layaway::create_layaway,
+            commands::layaway::add_layaway_payment,
+            commands::layaway::list_layaways,
+            commands::layaway::get_layaway,
+            commands::layaway::complete_layaway,
+            commands::layaway::cancel_layaway,
+            // Transfer commands
+            commands::transfer::create_transfer,
+            commands::transfer::receive_transfer,
+            commands::transfer::cancel_transfer,
+            commands::transfer::list_transfers,
+            commands::transfer::get_transfer,
+            // Store credit commands
+            commands::store_credit::refund_sale,
+            commands::store_credit::get_store_credit,
+            commands::store_credit::find_store_credits,
+            // Coupon commands
+            commands::coupon::apply_coupon,
+            commands::coupon::remove_coupon,
+            commands::coupon::refresh_coupons,
+            // Loyalty commands
+            commands::loyalty::enroll_loyalty_member,
+            commands::loyalty::get_loyalty_member,
+            commands::loyalty::set_loyalty_member,
+            // Cash drawer commands
+            commands::drawer::open_cash_drawer,
+            commands::drawer::get_drawer_opens,
+            // Scale commands
+            commands::scale::read_weight,
+            commands::scale::watch_weight,
+            commands::scale::add_weighed_item,
+            // Fiscal commands
+            commands::fiscal::get_fiscal_signature,
+            // E-invoice commands
+            commands::einvoice::save_business_customer,
+            commands::einvoice::find_business_customers,
+            commands::einvoice::find_business_customers_page,
+            commands::einvoice::delete_business_customer,
+            commands::einvoice::restore_business_customer,
+            commands::einvoice::set_sale_invoice_customer,
+            commands::einvoice::export_einvoice,
+            commands::einvoice::export_einvoices,
+            // Privacy commands
+            commands::privacy::forget_customer,
+            commands::privacy::list_erasures,
             // Config commands
             commands::config::get_config,
+            // Window commands
+            commands::window::open_manager_window,
+            commands::window::close_manager_window,
+            // Startup commands
+            commands::startup::get_startup_status,
+            // Crash report commands
+            commands::crash::report_frontend_crash,
+            commands::crash::list_crash_reports,
+            commands::crash::upload_crash_reports,
+            // Training commands
+            commands::training::set_training_mode,
+            commands::training::get_training_mode,
             // Sync commands
             commands::sync::get_sync_status,
             commands::sync::get_sync_config,
             commands::sync::set_sync_mode,
+            commands::sync::set_metered_mode,
+            commands::sync::pause_sync,
+            commands::sync::resume_sync,
+            commands::sync::trigger_full_resync,
             commands::sync::get_pending_sync_count,
+            commands::sync::list_outbox_entries,
+            commands::sync::get_sync_history,
+            commands::sync::diagnose_cloud_connectivity,
+            // Till commands
+            commands::till::open_till,
+            commands::till::get_till_session,
+            commands::till::close_till_blind,
+            commands::till::get_variance_exceptions,
+            commands::till::calculate_change_breakdown,
+            // Job commands
+            commands::jobs::list_jobs,
+            commands::jobs::list_job_runs,
+            commands::jobs::update_job_schedule,
+            commands::jobs::run_job_now,
+            // Label commands
+            commands::label::list_label_queue,
+            commands::label::queue_labels,
+            commands::label::print_labels,
+            commands::label::list_label_templates,
+            commands::label::save_label_template,
+            commands::label::preview_label_template,
+            // Update commands
+            commands::update::check_for_update,
+            commands::update::download_update,
+            commands::update::request_update_slot,
+            commands::update::report_update_finished,
+            // Feature flag commands
+            commands::feature::get_feature_flags,
+            commands::feature::refresh_feature_flags,
+            commands::feature::is_feature_enabled,
+            // Report commands
+            commands::report::get_tax_report,
+            commands::report::export_tax_report,
+            commands::report::get_dashboard_stats,
+            commands::report::get_department_sales,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
 
-/// Initializes the tracing subscriber for structured logging.
+/// Opens the database in the background, then starts what needs it.
 ///
-/// ## Log Levels
-/// - `RUST_LOG=debug` - Show debug messages
-/// - `RUST_LOG=titan=trace` - Show trace for titan crates only
-/// - Default: INFO level
-fn init_tracing() {
-    let filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| EnvFilter::new("info,titan=debug,sqlx=warn"));
+/// Until this finishes, commands that use the database fail with
+/// `NOT_READY` and the frontend shows its splash. It ends by emitting
+/// `startup:ready`, or `startup:failed` with the reason.
+fn spawn_startup(app_handle: AppHandle, db_path: PathBuf) {
+    tauri::async_runtime::spawn(async move {
+        let started = Instant::now();
+        let db_state = app_handle.state::<DbState>();
 
-    tracing_subscriber::fmt()
-        .with_env_filter(filter)
-        .with_max_level(Level::TRACE)
-        .init();
+        match open_databases(&app_handle, &db_path).await {
+            Ok((db, reporting_db)) => {
+                db_state.open(db.clone(), Some(reporting_db));
+                app_handle
+                    .state::<SchedulerState>()
+                    .start(app_handle.clone(), db);
+                info!(
+                    elapsed_ms = started.elapsed().as_millis() as u64,
+                    "Database connected and migrations applied"
+                );
+                if let Err(e) = app_handle.emit(STARTUP_READY_EVENT, db_state.status()) {
+                    error!(?e, "Failed to emit startup:ready event");
+                }
+            }
+            Err(message) => {
+                error!(%message, "Startup failed");
+                db_state.fail(message);
+                if let Err(e) = app_handle.emit(STARTUP_FAILED_EVENT, db_state.status()) {
+                    error!(?e, "Failed to emit startup:failed event");
+                }
+            }
+        }
+    });
 }
 
-/// Determines the database file path based on the platform.
-///
-/// ## Development Mode
-/// In development, the app looks for a seeded database in `data/titan.db`
-/// relative to the project root. This allows using the same database
-/// seeded by `cargo run -p titan-db --bin seed`.
-///
-/// ## Platform-Specific Paths (Production)
-/// - **macOS**: `~/Library/Application Support/com.titan.pos/titan.db`
-/// - **Windows**: `%APPDATA%\titan\pos\titan.db`
-/// - **Linux**: `~/.local/share/titan-pos/titan.db`
-///
-/// ## Environment Override
-/// Set `TITAN_DB_PATH` environment variable to use a custom path.
-///
-/// ## Development Workflow
-/// ```bash
-/// # 1. Seed the database from project root
-/// cargo run -p titan-db --bin seed
-///
-/// # 2. Run the Tauri app (auto-detects data/titan.db)
-/// cd apps/desktop && pnpm tauri dev
-/// ```
-fn get_database_path(_app: &tauri::App) -> Result<PathBuf, Box<dyn std::error::Error>> {
-    // Check for explicit override first
-    if let Ok(path) = std::env::var("TITAN_DB_PATH") {
-        info!(path = %path, "Using TITAN_DB_PATH override");
-        return Ok(PathBuf::from(path));
+/// Opens the database (running migrations) and the reporting pool.
+async fn open_databases(
+    app_handle: &AppHandle,
+    db_path: &Path,
+) -> Result<(Database, Database), String> {
+    let config = app_handle.state::<ConfigState>();
+    let tenant_id = config.tenant_id.clone();
+    let events = app_handle.state::<EventBusState>().publisher();
+    let pii_key = load_pii_master_key(&db_path.with_file_name("pii.key"))?;
+    let payload_cipher = load_payload_cipher(&db_path.with_file_name("payload.keys"))?;
+    let profile = database_profile()?;
+
+    let db = Database::new(DbConfig::new(db_path).profile(profile))
+        .await
+        .map_err(|e| e.to_string())?
+        .with_pii_key(&tenant_id, &pii_key)
+        .await
+        .map_err(|e| e.to_string())?
+        .with_payload_cipher(payload_cipher)
+        .with_events(events);
+    if config.profile.seeds_empty_database() {
+        seed_demo_catalog(&db).await?;
     }
 
-    // In development, look for the seeded database in data/titan.db
-    // Note: Tauri runs the binary from target/debug, so relative paths won't work
-    // We use CARGO_MANIFEST_DIR at compile time to find the project root
-    #[cfg(debug_assertions)]
-    {
-        // Paths to try, in order of preference:
-        // 1. Relative to CARGO_MANIFEST_DIR (set at compile time for src-tauri)
-        // 2. Standard project root locations
-        let paths_to_try = [
-            // From apps/desktop/src-tauri, go up to project root
-            PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/../../../data/titan.db")),
-            // From project root (if running cargo run directly)
-            PathBuf::from("./data/titan.db"),
-            // From apps/desktop directory
-            PathBuf::from("../../data/titan.db"),
-        ];
-
-        for path in &paths_to_try {
-            if path.exists() {
-                let canonical = path.canonicalize()?;
-                info!(?canonical, "Using development database");
-                return Ok(canonical);
-            }
-        }
+    // Second, smaller pool on the same file for back office reports
+    let reporting_config = DbConfig::new(db_path)
+        .profile(profile)
+        .max_connections(2)
+        .run_migrations(false);
+    let reporting_db = Database::new(reporting_config)
+        .await
+        .map_err(|e| e.to_string())?
+        .with_pii_key(&tenant_id, &pii_key)
+        .await
+        .map_err(|e| e.to_string())?;
 
-        info!("No development database found, using platform-specific path");
+    Ok((db, reporting_db))
+}
+
+/// Fills an empty database with the demo catalog (dev profile), so a fresh
+/// checkout has something to sell without running the seed tool.
+async fn seed_demo_catalog(db: &Database) -> Result<(), String> {
+    if db.products().count().await.map_err(|e| e.to_string())? > 0 {
+        return Ok(());
     }
+    let products = Fixtures::new()
+        .products(DEV_SEED_PRODUCTS)
+        .seed_products(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    info!(
+        products = products.len(),
+        "Seeded demo catalog into the empty dev database"
+    );
+    Ok(())
+}
 
-    // Use platform-specific app data directory (production)
-    let proj_dirs =
-        ProjectDirs::from("com", "titan", "pos").ok_or("Could not determine app data directory")?;
+/// SQLite performance profile for this deployment: `TITAN_DB_PROFILE`
+/// (`durable`, `balanced` or `fast`), `balanced` if unset.
+///
+/// Low-end terminals on unreliable power want `durable`; the hub machine
+/// wants `fast`.
+fn database_profile() -> Result<PerformanceProfile, String> {
+    match std::env::var("TITAN_DB_PROFILE") {
+        Ok(name) if !name.trim().is_empty() => {
+            let profile = name.parse::<PerformanceProfile>()?;
+            info!(%profile, "Using TITAN_DB_PROFILE");
+            Ok(profile)
+        }
+        _ => Ok(PerformanceProfile::default()),
+    }
+}
 
-    let data_dir = proj_dirs.data_dir();
+/// Initializes the tracing subscriber for structured logging.
+///
+/// ## Log Levels
+/// - `RUST_LOG=debug` - Show debug messages
+/// - `RUST_LOG=titan=trace` - Show trace for titan crates only
+/// - Default: the profile's filter (see `AppProfile::log_filter`)
+///
+/// Output goes to stdout and into the crash report log tail, and in
+/// portable mode also to `log_file` (without colors). With
+/// `OTEL_EXPORTER_OTLP_ENDPOINT` set, spans are also exported over OTLP
+/// (see `titan_sync::telemetry`); keep the returned guard until exit.
+fn init_tracing(
+    profile: AppProfile,
+    crash: &CrashState,
+    log_file: Option<File>,
+    warnings: &mut Vec<String>,
+) -> Option<Telemetry> {
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(profile.log_filter()));
 
-    // Create directory if it doesn't exist
-    std::fs::create_dir_all(data_dir)?;
+    let crash = crash.clone();
+    let console = move || crash.log_writer();
+    let with_ansi = log_file.is_none();
+    let writer = match log_file {
+        Some(file) => BoxMakeWriter::new(console.and(Mutex::new(file))),
+        None => BoxMakeWriter::new(console),
+    };
 
-    Ok(data_dir.join("titan.db"))
+    // The exporter runs on the async runtime
+    let otlp = tauri::async_runtime::block_on(async { telemetry::otlp_layer("titan-pos") });
+    let (otlp, telemetry) = match otlp {
+        Ok(otlp) => otlp.unzip(),
+        Err(e) => {
+            warnings.push(format!("Cannot export spans: {}", e));
+            (None, None)
+        }
+    };
+
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_max_level(Level::TRACE)
+        .with_ansi(with_ansi)
+        .with_writer(writer)
+        .finish()
+        .with(otlp)
+        .init();
+    telemetry
 }
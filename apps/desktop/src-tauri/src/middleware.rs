@@ -0,0 +1,83 @@
+//! # Command Middleware
+//!
+//! Wraps a Tauri command's body with request logging, timing and a
+//! correlation ID.
+//!
+//! ## Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                    traced("finalize_sale", body)                        │
+//! │                                                                         │
+//! │  1. correlation_id = new UUID                                           │
+//! │  2. span "command" { command, correlation_id, training }                │
+//! │  3. log "Command started"                                               │
+//! │  4. run body inside titan_db::correlation::scope                        │
+//! │        └── outbox rows queued by the body get the correlation_id,       │
+//! │            which rides on to the hub and the cloud sales row, and       │
+//! │            the span's trace context when spans are exported             │
+//! │  5. log "Command completed" / "Command failed" with duration_ms         │
+//! │     and, on failure, the error code                                     │
+//! │  6. failed → ApiError.correlationId = correlation_id,                  │
+//! │              ApiError.trainingMode = training mode on                  │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Usage
+//! ```rust,ignore
+//! #[tauri::command]
+//! pub async fn finalize_sale(db: State<'_, DbState>, ...) -> Result<SaleDto, ApiError> {
+//!     traced("finalize_sale", async move {
+//!         // command body, unchanged
+//!     })
+//!     .await
+//! }
+//! ```
+//!
+//! Synchronous commands only touch in-memory state and are not wrapped.
+
+use std::future::Future;
+use std::time::Instant;
+
+use titan_db::correlation;
+use titan_sync::telemetry;
+use tracing::{debug, info_span, warn, Instrument};
+
+use crate::error::ApiError;
+use crate::state::training_mode;
+
+/// Runs a command body with logging, timing and a fresh correlation ID.
+pub async fn traced<T, F>(command: &'static str, body: F) -> Result<T, ApiError>
+where
+    F: Future<Output = Result<T, ApiError>>,
+{
+    let correlation_id = correlation::new_id();
+    let span = info_span!(
+        "command",
+        command,
+        correlation_id = %correlation_id,
+        training = training_mode()
+    );
+    let trace_context = telemetry::trace_context(&span);
+
+    async move {
+        debug!("Command started");
+        let started = Instant::now();
+        let result = correlation::scope(correlation_id.clone(), trace_context, body).await;
+        let duration_ms = started.elapsed().as_millis() as u64;
+
+        match result {
+            Ok(value) => {
+                debug!(duration_ms, "Command completed");
+                Ok(value)
+            }
+            Err(mut e) => {
+                warn!(duration_ms, code = %e.code, message = %e.message, "Command failed");
+                e.correlation_id = Some(correlation_id);
+                e.training_mode = training_mode();
+                Err(e)
+            }
+        }
+    }
+    .instrument(span)
+    .await
+}
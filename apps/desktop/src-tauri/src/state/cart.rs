@@ -3,10 +3,11 @@
 //! Manages the current shopping cart state.
 //!
 //! ## Thread Safety
-//! The cart is wrapped in `Arc<Mutex<T>>` because:
+//! The cart is wrapped in `Arc<RwLock<T>>` because:
 //! 1. Multiple commands may access/modify the cart
 //! 2. Only one command should modify the cart at a time
-//! 3. Tauri commands can run concurrently
+//! 3. Tauri commands can run concurrently, and reads (cart display, stock
+//!    checks, sale snapshots) far outnumber changes while scanning
 //!
 //! ## Cart Operations Flow
 //! ```text
@@ -22,20 +23,31 @@
 //! │                                                                         │
 //! │  Click Remove ───────────► remove_from_cart() ──► items.remove(i)      │
 //! │                                                                         │
+//! │  x3 on a Line ───────────► duplicate_cart_item() ► items[i].qty *= 3   │
+//! │                                                                         │
+//! │  Drag a Line ────────────► move_cart_item() ────► items.insert(j, ..)  │
+//! │                                                                         │
+//! │  Edit Several ───────────► set_quantities_bulk() ► all or none         │
+//! │                                                                         │
 //! │  Click Clear ────────────► clear_cart() ────────► items.clear()        │
 //! │                                                                         │
 //! │  View Cart ──────────────► get_cart() ──────────► (read only)          │
 //! │                                                                         │
-//! │  NOTE: All write operations acquire the Mutex lock exclusively.         │
-//! │        Read operations also acquire the lock but release it quickly.    │
+//! │  NOTE: Write operations take the write lock and recompute the cached   │
+//! │        totals once. Reads share the read lock and reuse those totals.  │
 //! └─────────────────────────────────────────────────────────────────────────┘
 //! ```
 
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, RwLock};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use titan_core::{Money, Product, TaxRate};
+use titan_core::coupon::{CouponDiscount, CouponLine};
+use titan_core::department::department_product_id;
+use titan_core::tracking::validate_tracking_codes;
+use titan_core::validation::{validate_price_cents, validate_quantity};
+use titan_core::{Coupon, DepartmentLine, ItemTracking, LineAmounts, Product, RunningTotals};
+use ts_rs::TS;
 
 /// An item in the shopping cart.
 ///
@@ -44,7 +56,8 @@ use titan_core::{Money, Product, TaxRate};
 /// - `product_snapshot`: Frozen copy of product data at time of adding
 ///   This ensures the cart displays consistent data even if the product
 ///   is updated in the database after being added to cart.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct CartItem {
     /// Product ID (UUID)
@@ -58,16 +71,48 @@ pub struct CartItem {
 
     /// Price in cents at time of adding (frozen)
     /// This is critical: we lock in the price when added to cart
+    #[ts(type = "number")]
     pub unit_price_cents: i64,
 
+    /// Tenant base price when `unit_price_cents` is a store override
+    /// (frozen, for reporting)
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub base_price_cents: Option<i64>,
+
     /// Tax rate in basis points at time of adding (frozen)
     pub tax_rate_bps: u32,
 
     /// Quantity in cart
+    #[ts(type = "number")]
     pub quantity: i64,
 
     /// When this item was added to cart
+    #[ts(as = "String")]
     pub added_at: DateTime<Utc>,
+
+    /// Serial/lot capture required for this product
+    #[serde(default)]
+    pub item_tracking: ItemTracking,
+
+    /// Captured serial numbers (one per unit) or lot number (one per line)
+    #[serde(default)]
+    pub tracking_codes: Vec<String>,
+
+    /// Minimum customer age for this product (None = unrestricted)
+    #[serde(default)]
+    pub min_purchase_age: Option<u32>,
+
+    /// Price the line was added at, when the cashier has overridden
+    /// `unit_price_cents` (None = not overridden)
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub list_price_cents: Option<i64>,
+
+    /// Department key the line was sold on (None = a product with a SKU);
+    /// `product_id` is then a cart-only line key
+    #[serde(default)]
+    pub department_code: Option<String>,
 }
 
 impl CartItem {
@@ -82,9 +127,45 @@ impl CartItem {
             sku: product.sku.clone(),
             name: product.name.clone(),
             unit_price_cents: product.price_cents,
+            base_price_cents: product.base_price_cents,
             tax_rate_bps: product.tax_rate_bps,
             quantity,
             added_at: Utc::now(),
+            item_tracking: product.item_tracking,
+            tracking_codes: Vec::new(),
+            min_purchase_age: product.min_purchase_age,
+            list_price_cents: None,
+            department_code: None,
+        }
+    }
+
+    /// Creates a cart item for a department sale (see
+    /// `titan_core::department`). `line_key` tells apart lines of the same
+    /// department at different prices.
+    pub fn from_department(line: &DepartmentLine, line_key: String, quantity: i64) -> Self {
+        CartItem {
+            product_id: line_key,
+            sku: format!("DEPT-{}", line.department_code),
+            name: line.name.clone(),
+            unit_price_cents: line.unit_price_cents,
+            base_price_cents: None,
+            tax_rate_bps: line.tax_rate_bps,
+            quantity,
+            added_at: Utc::now(),
+            item_tracking: ItemTracking::None,
+            tracking_codes: Vec::new(),
+            min_purchase_age: None,
+            list_price_cents: None,
+            department_code: Some(line.department_code.clone()),
+        }
+    }
+
+    /// Product the sale line is recorded against: the item's product, or
+    /// the department's product for a department sale.
+    pub fn sale_product_id(&self) -> String {
+        match &self.department_code {
+            Some(code) => department_product_id(code),
+            None => self.product_id.clone(),
         }
     }
 
@@ -97,10 +178,12 @@ impl CartItem {
     ///
     /// Uses Bankers Rounding (round half to even) for financial accuracy.
     pub fn tax_cents(&self) -> i64 {
-        let line_total = Money::from_cents(self.line_total_cents());
-        line_total
-            .calculate_tax(TaxRate::from_bps(self.tax_rate_bps))
-            .cents()
+        self.amounts().tax_cents
+    }
+
+    /// The line's amounts, as counted in the cart's running totals.
+    pub fn amounts(&self) -> LineAmounts {
+        LineAmounts::new(self.unit_price_cents, self.quantity, self.tax_rate_bps)
     }
 
     /// Calculates line total including tax.
@@ -116,14 +199,60 @@ impl CartItem {
 /// - Quantity must be > 0 (removing sets qty to 0 removes the item)
 /// - Maximum items: 100 (configured in titan-core)
 /// - Maximum quantity per item: 999 (configured in titan-core)
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// - Items change only through the methods below, which keep the running
+///   totals in step line by line (see `titan_core::cart_totals`); debug
+///   builds check the totals after every change
+#[derive(Debug, Clone, Serialize, Default, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct Cart {
     /// Items in the cart
     pub items: Vec<CartItem>,
 
     /// When the cart was created/last cleared
+    #[ts(as = "String")]
     pub created_at: DateTime<Utc>,
+
+    /// Coupon applied with `apply_coupon`, redeemed when the sale is created
+    pub coupon: Option<Coupon>,
+
+    /// Loyalty member set with `set_loyalty_member`, who earns points on
+    /// the sale
+    pub loyalty_member: Option<String>,
+
+    /// Totals of `items`, updated by each change
+    #[serde(skip)]
+    #[ts(skip)]
+    totals: RunningTotals,
+}
+
+impl<'de> Deserialize<'de> for Cart {
+    /// Rebuilds the running totals from the saved items.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct SavedCart {
+            items: Vec<CartItem>,
+            created_at: DateTime<Utc>,
+            #[serde(default)]
+            coupon: Option<Coupon>,
+            #[serde(default)]
+            loyalty_member: Option<String>,
+        }
+
+        let saved = SavedCart::deserialize(deserializer)?;
+        let totals = RunningTotals::from_lines(saved.items.iter().map(CartItem::amounts));
+        Ok(Cart {
+            items: saved.items,
+            created_at: saved.created_at,
+            coupon: saved.coupon,
+            loyalty_member: saved.loyalty_member,
+            totals,
+        })
+    }
 }
 
 impl Cart {
@@ -132,6 +261,9 @@ impl Cart {
         Cart {
             items: Vec::new(),
             created_at: Utc::now(),
+            coupon: None,
+            loyalty_member: None,
+            totals: RunningTotals::default(),
         }
     }
 
@@ -145,6 +277,25 @@ impl Cart {
     /// - `Ok(())` on success
     /// - `Err(String)` if quantity would exceed maximum
     pub fn add_item(&mut self, product: &Product, quantity: i64) -> Result<(), String> {
+        self.add_tracked_item(product, quantity, &[])
+    }
+
+    /// Adds a product with the serial/lot numbers captured for these units.
+    ///
+    /// ## Tracking Rules
+    /// - Serial: one code per unit added; codes accumulate on the line and
+    ///   must stay unique
+    /// - Lot: one code; adding more of the product must use the same lot
+    /// - Untracked products accept no codes
+    pub fn add_tracked_item(
+        &mut self,
+        product: &Product,
+        quantity: i64,
+        tracking_codes: &[String],
+    ) -> Result<(), String> {
+        validate_tracking_codes(product.item_tracking, quantity, tracking_codes)
+            .map_err(|e| e.to_string())?;
+
         // Check if product already in cart
         if let Some(item) = self.items.iter_mut().find(|i| i.product_id == product.id) {
             let new_qty = item.quantity + quantity;
@@ -154,7 +305,29 @@ impl Cart {
                     titan_core::MAX_ITEM_QUANTITY
                 ));
             }
+
+            let mut codes = item.tracking_codes.clone();
+            match product.item_tracking {
+                ItemTracking::None => {}
+                ItemTracking::Serial => codes.extend_from_slice(tracking_codes),
+                ItemTracking::Lot => {
+                    if codes != tracking_codes {
+                        return Err(format!(
+                            "{} is already in the cart from lot {}",
+                            item.sku,
+                            codes.join(", ")
+                        ));
+                    }
+                }
+            }
+            validate_tracking_codes(product.item_tracking, new_qty, &codes)
+                .map_err(|e| e.to_string())?;
+
+            let old = item.amounts();
             item.quantity = new_qty;
+            item.tracking_codes = codes;
+            self.totals.change_line(old, item.amounts());
+            self.check_totals();
             return Ok(());
         }
 
@@ -167,7 +340,66 @@ impl Cart {
         }
 
         // Add new item
-        self.items.push(CartItem::from_product(product, quantity));
+        let mut item = CartItem::from_product(product, quantity);
+        item.tracking_codes = tracking_codes.to_vec();
+        self.push_line(item);
+        Ok(())
+    }
+
+    /// Adds a department sale, or more of an identical one (same
+    /// department, price and name; weighed lines are named by weight).
+    ///
+    /// Each price gets its own line, keyed `department:<code>:<n>`, so
+    /// lines can be changed and removed like product lines.
+    pub fn add_department_item(
+        &mut self,
+        line: &DepartmentLine,
+        quantity: i64,
+    ) -> Result<(), String> {
+        validate_quantity(quantity).map_err(|e| e.to_string())?;
+
+        if let Some(item) = self.items.iter_mut().find(|i| {
+            i.department_code.as_deref() == Some(line.department_code.as_str())
+                && i.unit_price_cents == line.unit_price_cents
+                && i.name == line.name
+        }) {
+            let new_qty = item.quantity + quantity;
+            if new_qty > titan_core::MAX_ITEM_QUANTITY {
+                return Err(format!(
+                    "Quantity would exceed maximum of {}",
+                    titan_core::MAX_ITEM_QUANTITY
+                ));
+            }
+            let old = item.amounts();
+            item.quantity = new_qty;
+            self.totals.change_line(old, item.amounts());
+            self.check_totals();
+            return Ok(());
+        }
+
+        if self.items.len() >= titan_core::MAX_CART_ITEMS {
+            return Err(format!(
+                "Cart cannot have more than {} items",
+                titan_core::MAX_CART_ITEMS
+            ));
+        }
+
+        let base = department_product_id(&line.department_code);
+        let next = self
+            .items
+            .iter()
+            .filter_map(|i| {
+                i.product_id
+                    .strip_prefix(&base)?
+                    .strip_prefix(':')?
+                    .parse::<u32>()
+                    .ok()
+            })
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let key = format!("{}:{}", base, next);
+        self.push_line(CartItem::from_department(line, key, quantity));
         Ok(())
     }
 
@@ -189,71 +421,270 @@ impl Cart {
         }
 
         if let Some(item) = self.items.iter_mut().find(|i| i.product_id == product_id) {
+            if item.item_tracking == ItemTracking::Serial {
+                return Err(format!(
+                    "{} is serial-tracked: scan or remove individual units instead",
+                    item.sku
+                ));
+            }
+            let old = item.amounts();
             item.quantity = quantity;
+            self.totals.change_line(old, item.amounts());
+            self.check_totals();
             Ok(())
         } else {
             Err(format!("Product {} not in cart", product_id))
         }
     }
 
+    /// Overrides the unit price of an item in the cart.
+    ///
+    /// The price the line was added at is kept in `list_price_cents`
+    /// (through repeated overrides); margin rules are the caller's job.
+    ///
+    /// ## Returns
+    /// The unit price before this override.
+    pub fn set_line_price(&mut self, product_id: &str, price_cents: i64) -> Result<i64, String> {
+        validate_price_cents(price_cents).map_err(|e| e.to_string())?;
+
+        let item = self
+            .items
+            .iter_mut()
+            .find(|i| i.product_id == product_id)
+            .ok_or_else(|| format!("Product {} not in cart", product_id))?;
+
+        let old = item.amounts();
+        let old_price = item.unit_price_cents;
+        item.list_price_cents.get_or_insert(old_price);
+        item.unit_price_cents = price_cents;
+        self.totals.change_line(old, item.amounts());
+        self.check_totals();
+        Ok(old_price)
+    }
+
+    /// Repeats a line `times` over (the keypad's "x3" on a line of 2
+    /// leaves 6). Lines are unique by product, so the repeat is counted on
+    /// the same line.
+    ///
+    /// ## Rules
+    /// - `times` is at least 2, and the new quantity within the maximum
+    /// - Serial-tracked lines are refused: each unit needs its own scan
+    ///
+    /// ## Returns
+    /// The line's new quantity.
+    pub fn duplicate_item(&mut self, product_id: &str, times: i64) -> Result<i64, String> {
+        if times < 2 {
+            return Err("A line must be repeated at least twice".to_string());
+        }
+
+        let item = self
+            .items
+            .iter_mut()
+            .find(|i| i.product_id == product_id)
+            .ok_or_else(|| format!("Product {} not in cart", product_id))?;
+
+        if item.item_tracking == ItemTracking::Serial {
+            return Err(format!(
+                "{} is serial-tracked: scan each unit instead",
+                item.sku
+            ));
+        }
+        let new_qty = item.quantity.saturating_mul(times);
+        if new_qty > titan_core::MAX_ITEM_QUANTITY {
+            return Err(format!(
+                "Quantity would exceed maximum of {}",
+                titan_core::MAX_ITEM_QUANTITY
+            ));
+        }
+
+        let old = item.amounts();
+        item.quantity = new_qty;
+        self.totals.change_line(old, item.amounts());
+        self.check_totals();
+        Ok(new_qty)
+    }
+
+    /// Moves a line to `to_index` (0 = first), shifting the lines between;
+    /// receipts print lines in cart order.
+    pub fn move_item(&mut self, product_id: &str, to_index: usize) -> Result<(), String> {
+        let from = self
+            .items
+            .iter()
+            .position(|i| i.product_id == product_id)
+            .ok_or_else(|| format!("Product {} not in cart", product_id))?;
+        if to_index >= self.items.len() {
+            return Err(format!(
+                "Position {} is past the last line ({})",
+                to_index,
+                self.items.len() - 1
+            ));
+        }
+
+        let item = self.items.remove(from);
+        self.items.insert(to_index, item);
+        Ok(())
+    }
+
+    /// Sets the quantity of several lines at once; a quantity of 0 removes
+    /// the line.
+    ///
+    /// All or nothing: if any change is refused (a line not in the cart or
+    /// named twice, a negative or too large quantity, a serial-tracked
+    /// line) the cart is left as it was.
+    pub fn set_quantities(&mut self, changes: &[LineQuantity]) -> Result<(), String> {
+        if changes.is_empty() {
+            return Err("No quantities to set".to_string());
+        }
+
+        let mut draft = self.clone();
+        for (i, change) in changes.iter().enumerate() {
+            let earlier = &changes[..i];
+            if earlier.iter().any(|c| c.product_id == change.product_id) {
+                return Err(format!("Product {} is listed twice", change.product_id));
+            }
+            if change.quantity < 0 {
+                return Err(format!(
+                    "Quantity for {} cannot be negative",
+                    change.product_id
+                ));
+            }
+            draft.update_quantity(&change.product_id, change.quantity)?;
+        }
+
+        *self = draft;
+        Ok(())
+    }
+
     /// Removes an item from the cart by product ID.
     pub fn remove_item(&mut self, product_id: &str) -> Result<(), String> {
-        let initial_len = self.items.len();
-        self.items.retain(|i| i.product_id != product_id);
+        let index = self
+            .items
+            .iter()
+            .position(|i| i.product_id == product_id)
+            .ok_or_else(|| format!("Product {} not in cart", product_id))?;
 
-        if self.items.len() == initial_len {
-            Err(format!("Product {} not in cart", product_id))
-        } else {
-            Ok(())
-        }
+        let removed = self.items.remove(index);
+        self.totals.remove_line(removed.amounts());
+        self.check_totals();
+        Ok(())
     }
 
     /// Clears all items from the cart.
     pub fn clear(&mut self) {
         self.items.clear();
+        self.totals = RunningTotals::default();
+        self.coupon = None;
+        self.loyalty_member = None;
         self.created_at = Utc::now();
     }
 
     /// Returns the number of unique items in the cart.
     pub fn item_count(&self) -> usize {
-        self.items.len()
+        self.totals.line_count
     }
 
     /// Returns the total quantity of all items.
     pub fn total_quantity(&self) -> i64 {
-        self.items.iter().map(|i| i.quantity).sum()
+        self.totals.total_quantity
     }
 
-    /// Calculates the subtotal (before tax).
+    /// The subtotal (before tax).
     pub fn subtotal_cents(&self) -> i64 {
-        self.items.iter().map(|i| i.line_total_cents()).sum()
+        self.totals.subtotal_cents
     }
 
-    /// Calculates the total tax.
+    /// The total tax (rounded per line).
     pub fn tax_cents(&self) -> i64 {
-        self.items.iter().map(|i| i.tax_cents()).sum()
+        self.totals.tax_cents
     }
 
-    /// Calculates the grand total (subtotal + tax).
+    /// The grand total (subtotal + tax - coupon discount).
     pub fn total_cents(&self) -> i64 {
-        self.subtotal_cents() + self.tax_cents()
+        self.totals.total_cents() - self.discount_cents()
+    }
+
+    /// The lines as the coupon rules see them, in cart order.
+    pub fn coupon_lines(&self) -> Vec<CouponLine> {
+        self.items
+            .iter()
+            .map(|i| CouponLine {
+                product_id: i.sale_product_id(),
+                line_total_cents: i.line_total_cents(),
+            })
+            .collect()
+    }
+
+    /// The applied coupon's discount on the cart as it is now; `None`
+    /// without a coupon or when the cart no longer qualifies for it.
+    pub fn coupon_discount(&self) -> Option<CouponDiscount> {
+        self.coupon
+            .as_ref()?
+            .discount_for(&self.coupon_lines())
+            .ok()
+    }
+
+    /// The coupon discount (0 when the cart does not qualify).
+    pub fn discount_cents(&self) -> i64 {
+        self.coupon_discount()
+            .map(|d| d.discount_cents)
+            .unwrap_or(0)
     }
 
     /// Checks if the cart is empty.
     pub fn is_empty(&self) -> bool {
         self.items.is_empty()
     }
+
+    /// Highest minimum purchase age in the cart (None = no ID check needed).
+    pub fn required_age(&self) -> Option<u32> {
+        titan_core::age::required_age(self.items.iter().map(|i| i.min_purchase_age))
+    }
+
+    /// Appends a new line and counts it in the totals.
+    fn push_line(&mut self, item: CartItem) {
+        self.totals.add_line(item.amounts());
+        self.items.push(item);
+        self.check_totals();
+    }
+
+    /// Debug builds: the running totals still match the items.
+    fn check_totals(&self) {
+        self.totals.verify(self.items.iter().map(CartItem::amounts));
+    }
+}
+
+/// One line's new quantity in a bulk edit (see `Cart::set_quantities`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct LineQuantity {
+    pub product_id: String,
+    /// New quantity (0 removes the line)
+    #[ts(type = "number")]
+    pub quantity: i64,
 }
 
 /// Cart totals summary for API responses.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct CartTotals {
     pub item_count: usize,
+    #[ts(type = "number")]
     pub total_quantity: i64,
+    #[ts(type = "number")]
     pub subtotal_cents: i64,
+    #[ts(type = "number")]
     pub tax_cents: i64,
+    /// Coupon discount; 0 while the cart does not qualify for the coupon
+    #[ts(type = "number")]
+    pub discount_cents: i64,
+    /// Code of the applied coupon, if any
+    pub coupon_code: Option<String>,
+    #[ts(type = "number")]
     pub total_cents: i64,
+    /// Member number of the loyalty member on the cart, if any
+    pub loyalty_member: Option<String>,
 }
 
 impl From<&Cart> for CartTotals {
@@ -263,7 +694,10 @@ impl From<&Cart> for CartTotals {
             total_quantity: cart.total_quantity(),
             subtotal_cents: cart.subtotal_cents(),
             tax_cents: cart.tax_cents(),
+            discount_cents: cart.discount_cents(),
+            coupon_code: cart.coupon.as_ref().map(|c| c.code.clone()),
             total_cents: cart.total_cents(),
+            loyalty_member: cart.loyalty_member.clone(),
         }
     }
 }
@@ -271,23 +705,36 @@ impl From<&Cart> for CartTotals {
 /// Tauri-managed cart state.
 ///
 /// ## Thread Safety
-/// Uses `Arc<Mutex<Cart>>` because:
+/// Uses `Arc<RwLock<..>>` because:
 /// - `Arc`: Allows shared ownership across threads
-/// - `Mutex`: Ensures only one thread modifies the cart at a time
+/// - `RwLock`: Only one thread modifies the cart at a time, but readers
+///   don't queue behind each other
 ///
-/// ## Why Not RwLock?
-/// Cart operations are typically quick, and most operations modify state.
-/// A RwLock would add complexity with minimal benefit.
+/// ## Why Not a Mutex?
+/// Under rapid scanning every scan's response, the stock check before it
+/// and background cart snapshots all read the cart. Behind a single Mutex
+/// they serialized with the scans and the UI stuttered. Reads now share
+/// the lock, and the totals are computed once per change instead of once
+/// per read (see `benches/cart_latency.rs`).
 #[derive(Debug)]
 pub struct CartState {
-    cart: Arc<Mutex<Cart>>,
+    slot: Arc<RwLock<CartSlot>>,
+}
+
+/// The cart with its totals as of the last change.
+#[derive(Debug)]
+struct CartSlot {
+    cart: Cart,
+    totals: CartTotals,
 }
 
 impl CartState {
     /// Creates a new empty cart state.
     pub fn new() -> Self {
+        let cart = Cart::new();
+        let totals = CartTotals::from(&cart);
         CartState {
-            cart: Arc::new(Mutex::new(Cart::new())),
+            slot: Arc::new(RwLock::new(CartSlot { cart, totals })),
         }
     }
 
@@ -295,17 +742,38 @@ impl CartState {
     ///
     /// ## Usage
     /// ```rust,ignore
-    /// let totals = cart_state.with_cart(|cart| CartTotals::from(cart));
+    /// let count = cart_state.with_cart(|cart| cart.item_count());
     /// ```
     pub fn with_cart<F, R>(&self, f: F) -> R
     where
         F: FnOnce(&Cart) -> R,
     {
-        let cart = self.cart.lock().expect("Cart mutex poisoned");
-        f(&cart)
+        let slot = self.slot.read().expect("Cart lock poisoned");
+        f(&slot.cart)
     }
 
-    /// Executes a function with write access to the cart.
+    /// Executes a function with read access to the cart and its cached
+    /// totals.
+    ///
+    /// ## Usage
+    /// ```rust,ignore
+    /// let response = cart_state.with_totals(CartResponse::new);
+    /// ```
+    pub fn with_totals<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&Cart, &CartTotals) -> R,
+    {
+        let slot = self.slot.read().expect("Cart lock poisoned");
+        f(&slot.cart, &slot.totals)
+    }
+
+    /// Returns the cached cart totals.
+    pub fn totals(&self) -> CartTotals {
+        self.with_totals(|_, totals| totals.clone())
+    }
+
+    /// Executes a function with write access to the cart, then refreshes
+    /// the cached totals.
     ///
     /// ## Usage
     /// ```rust,ignore
@@ -315,8 +783,10 @@ impl CartState {
     where
         F: FnOnce(&mut Cart) -> R,
     {
-        let mut cart = self.cart.lock().expect("Cart mutex poisoned");
-        f(&mut cart)
+        let mut slot = self.slot.write().expect("Cart lock poisoned");
+        let result = f(&mut slot.cart);
+        slot.totals = CartTotals::from(&slot.cart);
+        result
     }
 }
 
@@ -340,12 +810,16 @@ mod tests {
             name: format!("Product {}", id),
             description: None,
             price_cents,
+            base_price_cents: None,
             cost_cents: None,
             tax_rate_bps: 825, // 8.25%
             track_inventory: false,
             allow_negative_stock: false,
             current_stock: None,
+            item_tracking: ItemTracking::None,
+            min_purchase_age: None,
             is_active: true,
+            deleted_at: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             sync_version: 0,
@@ -388,6 +862,213 @@ mod tests {
         assert_eq!(cart.total_cents(), 1083); // $10.83
     }
 
+    #[test]
+    fn test_cart_serial_tracking() {
+        let mut cart = Cart::new();
+        let mut product = test_product("1", 999);
+        product.item_tracking = ItemTracking::Serial;
+        let sn = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert!(cart.add_item(&product, 1).is_err());
+        cart.add_tracked_item(&product, 2, &sn(&["SN1", "SN2"])).unwrap();
+        cart.add_tracked_item(&product, 1, &sn(&["SN3"])).unwrap();
+        assert_eq!(cart.items[0].tracking_codes, sn(&["SN1", "SN2", "SN3"]));
+
+        // Same serial twice is rejected, and quantity can't be edited directly
+        assert!(cart.add_tracked_item(&product, 1, &sn(&["SN1"])).is_err());
+        assert!(cart.update_quantity("1", 1).is_err());
+        assert_eq!(cart.total_quantity(), 3);
+    }
+
+    #[test]
+    fn test_cart_lot_tracking() {
+        let mut cart = Cart::new();
+        let mut product = test_product("1", 999);
+        product.item_tracking = ItemTracking::Lot;
+        let lot = |v: &str| vec![v.to_string()];
+
+        cart.add_tracked_item(&product, 2, &lot("LOT-A")).unwrap();
+        cart.add_tracked_item(&product, 3, &lot("LOT-A")).unwrap();
+        assert!(cart.add_tracked_item(&product, 1, &lot("LOT-B")).is_err());
+        assert_eq!(cart.total_quantity(), 5);
+        assert_eq!(cart.items[0].tracking_codes, lot("LOT-A"));
+    }
+
+    #[test]
+    fn test_cart_required_age() {
+        let mut cart = Cart::new();
+        cart.add_item(&test_product("1", 199), 1).unwrap();
+        assert_eq!(cart.required_age(), None);
+
+        let mut beer = test_product("2", 899);
+        beer.min_purchase_age = Some(21);
+        cart.add_item(&beer, 1).unwrap();
+        assert_eq!(cart.required_age(), Some(21));
+    }
+
+    #[test]
+    fn test_cart_line_price_override() {
+        let mut cart = Cart::new();
+        cart.add_item(&test_product("1", 999), 2).unwrap();
+
+        assert_eq!(cart.set_line_price("1", 750), Ok(999));
+        assert_eq!(cart.set_line_price("1", 800), Ok(750));
+        assert_eq!(cart.items[0].unit_price_cents, 800);
+        assert_eq!(cart.items[0].list_price_cents, Some(999));
+        assert_eq!(cart.subtotal_cents(), 1600);
+
+        assert!(cart.set_line_price("1", -1).is_err());
+        assert!(cart.set_line_price("2", 100).is_err());
+    }
+
+    #[test]
+    fn test_cart_department_items() {
+        let mut cart = Cart::new();
+        let mut line = DepartmentLine {
+            department_code: "BAKERY".to_string(),
+            name: "Misc Bakery".to_string(),
+            tax_rate_bps: 0,
+            unit_price_cents: 350,
+        };
+        cart.add_department_item(&line, 1).unwrap();
+        cart.add_department_item(&line, 1).unwrap();
+        line.unit_price_cents = 125;
+        cart.add_department_item(&line, 1).unwrap();
+
+        assert_eq!(cart.items.len(), 2);
+        assert_eq!(cart.items[0].product_id, "department:BAKERY:1");
+        assert_eq!(cart.items[0].quantity, 2);
+        assert_eq!(cart.items[1].product_id, "department:BAKERY:2");
+        assert_eq!(cart.items[1].sale_product_id(), "department:BAKERY");
+        assert_eq!(cart.tax_cents(), 0);
+        assert_eq!(cart.subtotal_cents(), 825);
+
+        cart.remove_item("department:BAKERY:1").unwrap();
+        assert!(cart.add_department_item(&line, 0).is_err());
+    }
+
+    #[test]
+    fn test_cart_totals_follow_every_change() {
+        let mut cart = Cart::new();
+        for i in 0..titan_core::MAX_CART_ITEMS {
+            cart.add_item(&test_product(&i.to_string(), 100 + i as i64), 1)
+                .unwrap();
+        }
+        cart.update_quantity("7", 5).unwrap();
+        cart.set_line_price("8", 50).unwrap();
+        cart.remove_item("9").unwrap();
+
+        let recomputed: i64 = cart.items.iter().map(|i| i.tax_cents()).sum();
+        assert_eq!(cart.item_count(), titan_core::MAX_CART_ITEMS - 1);
+        assert_eq!(cart.tax_cents(), recomputed);
+
+        // A saved cart comes back with its totals rebuilt
+        let json = serde_json::to_string(&cart).unwrap();
+        let restored: Cart = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.total_cents(), cart.total_cents());
+        assert_eq!(restored.total_quantity(), cart.total_quantity());
+    }
+
+    #[test]
+    fn test_cart_state_caches_totals() {
+        let state = CartState::new();
+        assert_eq!(state.totals().total_cents, 0);
+
+        let added = state.with_cart_mut(|c| c.add_item(&test_product("1", 1000), 2));
+        assert!(added.is_ok());
+        let failed = state.with_cart_mut(|c| c.update_quantity("missing", 1));
+        assert!(failed.is_err());
+
+        state.with_totals(|cart, totals| assert_eq!(*totals, CartTotals::from(cart)));
+        assert_eq!(state.totals().subtotal_cents, 2000);
+        assert_eq!(state.totals().tax_cents, 165);
+
+        state.with_cart_mut(|c| c.clear());
+        assert_eq!(state.totals().item_count, 0);
+    }
+
+    #[test]
+    fn test_cart_duplicate_and_move_lines() {
+        let mut cart = Cart::new();
+        for id in ["1", "2", "3"] {
+            cart.add_item(&test_product(id, 100), 2).unwrap();
+        }
+
+        assert_eq!(cart.duplicate_item("2", 3), Ok(6));
+        assert_eq!(cart.total_quantity(), 10);
+        assert_eq!(cart.subtotal_cents(), 1000);
+        assert!(cart.duplicate_item("2", 1).is_err());
+        assert!(cart.duplicate_item("2", 200).is_err());
+        assert!(cart.duplicate_item("9", 2).is_err());
+
+        cart.move_item("3", 0).unwrap();
+        cart.move_item("1", 2).unwrap();
+        let order: Vec<&str> = cart.items.iter().map(|i| i.product_id.as_str()).collect();
+        assert_eq!(order, ["3", "2", "1"]);
+        assert!(cart.move_item("1", 3).is_err());
+        assert_eq!(cart.subtotal_cents(), 1000);
+    }
+
+    #[test]
+    fn test_cart_bulk_quantities_all_or_nothing() {
+        let mut cart = Cart::new();
+        cart.add_item(&test_product("1", 100), 1).unwrap();
+        cart.add_item(&test_product("2", 200), 1).unwrap();
+        let change = |id: &str, quantity| LineQuantity {
+            product_id: id.to_string(),
+            quantity,
+        };
+
+        // One bad change leaves every line as it was
+        let refused = cart.set_quantities(&[change("1", 5), change("9", 1)]);
+        assert!(refused.is_err());
+        let twice = cart.set_quantities(&[change("1", 5), change("1", 6)]);
+        assert!(twice.is_err());
+        assert!(cart.set_quantities(&[change("1", -1)]).is_err());
+        assert_eq!(cart.total_quantity(), 2);
+
+        let changes = [change("1", 5), change("2", 0)];
+        cart.set_quantities(&changes).unwrap();
+        assert_eq!(cart.item_count(), 1);
+        assert_eq!(cart.subtotal_cents(), 500);
+    }
+
+    #[test]
+    fn test_cart_coupon_discount_follows_cart() {
+        let mut cart = Cart::new();
+        cart.add_item(&test_product("1", 1000), 1).unwrap();
+        cart.coupon = Some(Coupon {
+            id: "cp-1".to_string(),
+            tenant_id: DEFAULT_TENANT_ID.to_string(),
+            code: "SAVE5".to_string(),
+            description: None,
+            kind: titan_core::CouponKind::Amount,
+            value: 500,
+            min_subtotal_cents: Some(2000),
+            product_ids: Vec::new(),
+            starts_at: None,
+            expires_at: None,
+            max_uses: None,
+            active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            sync_version: 1,
+        });
+
+        // Below the minimum: no discount, the coupon stays on the cart
+        assert_eq!(cart.discount_cents(), 0);
+        assert_eq!(cart.total_cents(), 1083);
+
+        cart.add_item(&test_product("2", 1000), 1).unwrap();
+        let totals = CartTotals::from(&cart);
+        assert_eq!(totals.discount_cents, 500);
+        assert_eq!(totals.coupon_code.as_deref(), Some("SAVE5"));
+        assert_eq!(totals.total_cents, 2000 + 166 - 500);
+
+        cart.clear();
+        assert!(cart.coupon.is_none());
+    }
+
     #[test]
     fn test_cart_clear() {
         let mut cart = Cart::new();
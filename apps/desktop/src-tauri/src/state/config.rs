@@ -13,14 +13,23 @@
 //! If hot-reloading is added later, we'd wrap in `RwLock`.
 
 use serde::{Deserialize, Serialize};
-use titan_core::DEFAULT_TENANT_ID;
+use titan_core::loyalty::parse_tiers;
+use titan_core::{
+    CurrencyDenominations, DepartmentConfig, DepartmentKey, GuardrailAction, LayawayPolicy,
+    LoyaltyProgram, MarginGuardrail, RetentionPolicy, StationConfig, TaxGroup, DEFAULT_TENANT_ID,
+};
+use tracing::warn;
+use ts_rs::TS;
+
+use super::AppProfile;
 
 /// Application configuration.
 ///
 /// ## Fields
 /// Most fields have sensible defaults for development.
 /// Production deployments should configure these properly.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct ConfigState {
     /// Tenant ID for multi-tenant support.
@@ -33,6 +42,12 @@ pub struct ConfigState {
     /// Store address lines (for receipts)
     pub store_address: Vec<String>,
 
+    /// Store's VAT / tax registration number (seller on e-invoices)
+    pub store_tax_id: Option<String>,
+
+    /// Store country (ISO 3166-1 alpha-2)
+    pub country_code: String,
+
     /// Currency code (ISO 4217)
     pub currency_code: String,
 
@@ -42,6 +57,9 @@ pub struct ConfigState {
     /// Number of decimal places for currency
     pub currency_decimals: u8,
 
+    /// Notes and coins that replace a currency's built-in set
+    pub custom_denominations: Vec<CurrencyDenominations>,
+
     /// Default tax rate in basis points
     /// e.g., 825 = 8.25%
     pub default_tax_rate_bps: u32,
@@ -54,24 +72,47 @@ pub struct ConfigState {
 
     /// Receipt printer configuration
     pub receipt_printer: Option<PrinterConfig>,
-}
 
-/// How tax is calculated on items.
-#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
-pub enum TaxMode {
-    /// Tax is calculated on top of the price (US style)
-    /// Display: $10.00 + $0.83 tax = $10.83
-    #[default]
-    Exclusive,
+    /// Layaway deposit and restocking fee terms
+    pub layaway: LayawayPolicy,
+
+    /// Margin floor for manual product and line prices
+    pub margin_guardrail: MarginGuardrail,
+
+    /// Department keys for items without a SKU, and their tax groups
+    pub departments: DepartmentConfig,
+
+    /// Release channel app updates come from
+    pub update_channel: UpdateChannel,
+
+    /// Environment profile (`TITAN_ENV`)
+    pub profile: AppProfile,
+
+    /// Cloud API URL (the profile's, unless `TITAN_CLOUD_URL` is set)
+    pub cloud_url: String,
+
+    /// Register identity of this terminal (stamped on sales and receipts)
+    pub station: StationConfig,
+
+    /// How long finished sales are kept on this terminal
+    pub retention: RetentionPolicy,
 
-    /// Tax is included in the displayed price (EU style)
-    /// Display: $10.83 (includes $0.83 tax)
-    Inclusive,
+    /// Refunds above this amount need a manager override token
+    /// (None: no limit)
+    #[ts(type = "number | null")]
+    pub refund_approval_cents: Option<i64>,
+
+    /// Loyalty tiers and rewards
+    pub loyalty: LoyaltyProgram,
 }
 
+/// How tax is calculated on items (shared with titan-core, so the
+/// frontend sees one `TaxMode` type).
+pub use titan_core::TaxMode;
+
 /// Printer configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct PrinterConfig {
     /// Printer type
@@ -85,7 +126,8 @@ pub struct PrinterConfig {
 }
 
 /// Supported printer types.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "lowercase")]
 pub enum PrinterType {
     /// ESC/POS thermal printer
@@ -98,38 +140,169 @@ pub enum PrinterType {
     System,
 }
 
+/// App release channels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    /// Fully rolled-out releases
+    #[default]
+    Stable,
+
+    /// Pre-releases as well as stable releases (pilot stores)
+    Beta,
+}
+
+impl UpdateChannel {
+    /// Channel name as the cloud knows it.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => "stable",
+            UpdateChannel::Beta => "beta",
+        }
+    }
+}
+
 impl Default for ConfigState {
     /// Returns default configuration suitable for development.
     ///
     /// ## Default Values
     /// - Store: "Titan POS Dev Store"
-    /// - Currency: USD ($)
+    /// - Currency: USD ($), built-in notes and coins
     /// - Tax: 8.25% exclusive
     /// - Sounds: enabled
     /// - Printer: none (dev mode)
+    /// - Layaway: 20% deposit, 10% restocking fee
+    /// - Margin guardrail: warn when selling below cost
+    /// - Departments: Misc Bakery (food, 0%) and Misc General (8.25%)
+    /// - Updates: stable channel
+    /// - Profile: dev (local cloud)
+    /// - Station: register 1 (`R01`)
+    /// - Retention: finished sales kept 90 days after the cloud has them
+    /// - Refunds: no approval limit
+    /// - Loyalty: 1 point per dollar, $5 per 500 points, Member/Silver/Gold
     fn default() -> Self {
         ConfigState {
             tenant_id: DEFAULT_TENANT_ID.to_string(),
             store_name: "Titan POS Dev Store".to_string(),
             store_address: vec!["123 Main Street".to_string(), "City, ST 12345".to_string()],
+            store_tax_id: None,
+            country_code: "US".to_string(),
             currency_code: "USD".to_string(),
             currency_symbol: "$".to_string(),
             currency_decimals: 2,
+            custom_denominations: Vec::new(),
             default_tax_rate_bps: 825, // 8.25%
             tax_mode: TaxMode::Exclusive,
             sound_enabled: true,
             receipt_printer: None,
+            layaway: LayawayPolicy::default(),
+            margin_guardrail: MarginGuardrail::default(),
+            departments: default_departments(),
+            update_channel: UpdateChannel::Stable,
+            profile: AppProfile::Dev,
+            cloud_url: AppProfile::Dev.default_cloud_url().to_string(),
+            station: StationConfig::default(),
+            retention: RetentionPolicy::default(),
+            refund_approval_cents: None,
+            loyalty: LoyaltyProgram::default(),
         }
     }
 }
 
+/// Development department keys: a food department on a price-embedded
+/// barcode range, loose produce sold by weight and a general one at the
+/// standard rate.
+fn default_departments() -> DepartmentConfig {
+    DepartmentConfig {
+        tax_groups: vec![
+            TaxGroup {
+                code: "STANDARD".to_string(),
+                name: "Standard".to_string(),
+                rate_bps: 825,
+            },
+            TaxGroup {
+                code: "FOOD".to_string(),
+                name: "Food".to_string(),
+                rate_bps: 0,
+            },
+        ],
+        departments: vec![
+            DepartmentKey {
+                code: "BAKERY".to_string(),
+                name: "Misc Bakery".to_string(),
+                tax_group: "FOOD".to_string(),
+                min_price_cents: 1,
+                max_price_cents: 5_000,
+                barcode_prefix: Some("2100042".to_string()),
+                price_per_kg_cents: None,
+            },
+            DepartmentKey {
+                code: "PRODUCE".to_string(),
+                name: "Loose Produce".to_string(),
+                tax_group: "FOOD".to_string(),
+                min_price_cents: 1,
+                max_price_cents: 10_000,
+                barcode_prefix: None,
+                price_per_kg_cents: Some(299),
+            },
+            DepartmentKey {
+                code: "GENERAL".to_string(),
+                name: "Misc General".to_string(),
+                tax_group: "STANDARD".to_string(),
+                min_price_cents: 1,
+                max_price_cents: 50_000,
+                barcode_prefix: None,
+                price_per_kg_cents: None,
+            },
+        ],
+    }
+}
+
 impl ConfigState {
+    /// Configuration for an environment profile, from environment
+    /// variables (see `from_env`) and defaults.
+    ///
+    /// In portable mode the variables may come from `titan.env` next to the
+    /// executable.
+    pub fn for_profile(profile: AppProfile) -> Self {
+        ConfigState {
+            profile,
+            cloud_url: profile.cloud_url(),
+            ..ConfigState::from_env()
+        }
+    }
+
     /// Creates a new ConfigState from environment variables and defaults.
     ///
     /// ## Environment Variables
     /// - `TITAN_TENANT_ID`: Override tenant ID
     /// - `TITAN_STORE_NAME`: Override store name
+    /// - `TITAN_STORE_TAX_ID`: Store tax registration number (for e-invoices)
+    /// - `TITAN_COUNTRY_CODE`: Store country (e.g., "GB")
+    /// - `TITAN_DENOMINATIONS`: Notes and coins of the store currency, in
+    ///   major units (e.g., "notes:5000,1000,500,100;coins:10,5,2,1")
     /// - `TITAN_TAX_RATE`: Override default tax rate (e.g., "8.25")
+    /// - `TITAN_LAYAWAY_RESTOCKING_FEE`: Override layaway restocking fee (e.g., "15")
+    /// - `TITAN_MARGIN_FLOOR`: Lowest margin for manual prices (e.g., "20")
+    /// - `TITAN_MARGIN_ACTION`: Below the floor, "warn" or "block" (manager
+    ///   approval)
+    /// - `TITAN_UPDATE_CHANNEL`: App release channel ("stable" or "beta")
+    /// - `TITAN_STATION_NUMBER`: Register number of this terminal (1-999)
+    /// - `TITAN_RECEIPT_PREFIX`: Receipt number prefix (default "R" and the
+    ///   station number, e.g., "R03")
+    /// - `TITAN_DEFAULT_PRINTER`: Printer this register prints to
+    /// - `TITAN_CASH_DRAWER`: Cash drawer this register opens
+    /// - `TITAN_SCALE`: Scale this register weighs on (e.g., "serial:COM4")
+    /// - `TITAN_LINE_DISPLAY`: Customer pole display (e.g., "epson:COM5")
+    /// - `TITAN_SALE_RETENTION_DAYS`: Days finished sales are kept once the
+    ///   cloud has them (at least 30; "0" or "off" keeps every sale)
+    /// - `TITAN_REFUND_APPROVAL_LIMIT`: Refunds above this amount need a
+    ///   manager override token (e.g., "250.00"; "off" for no limit)
+    /// - `TITAN_LOYALTY_TIERS`: Loyalty tiers as name:min_points:multiplier
+    ///   (e.g., "Member:0:1,Silver:1000:1.25,Gold:5000:1.5")
+    /// - `TITAN_LOYALTY_REWARD`: Points per reward and its value (e.g.,
+    ///   "500:5.00")
     pub fn from_env() -> Self {
         let mut config = ConfigState::default();
 
@@ -141,15 +314,164 @@ impl ConfigState {
             config.store_name = store_name;
         }
 
+        if let Ok(tax_id) = std::env::var("TITAN_STORE_TAX_ID") {
+            config.store_tax_id = Some(tax_id);
+        }
+
+        if let Ok(country_code) = std::env::var("TITAN_COUNTRY_CODE") {
+            config.country_code = country_code;
+        }
+
+        if let Ok(set_str) = std::env::var("TITAN_DENOMINATIONS") {
+            match CurrencyDenominations::parse(&config.currency_code, &set_str) {
+                Ok(set) => config.custom_denominations = vec![set],
+                Err(e) => warn!(error = %e, "Invalid denominations, using the built-in set"),
+            }
+        }
+
         if let Ok(tax_rate_str) = std::env::var("TITAN_TAX_RATE") {
             if let Ok(rate) = tax_rate_str.parse::<f64>() {
                 config.default_tax_rate_bps = (rate * 100.0) as u32;
             }
         }
 
+        if let Ok(fee_str) = std::env::var("TITAN_LAYAWAY_RESTOCKING_FEE") {
+            if let Ok(fee) = fee_str.parse::<f64>() {
+                config.layaway.restocking_fee_bps = (fee * 100.0) as u32;
+            }
+        }
+
+        if let Ok(floor_str) = std::env::var("TITAN_MARGIN_FLOOR") {
+            if let Ok(floor) = floor_str.parse::<f64>() {
+                config.margin_guardrail.floor_bps = (floor * 100.0) as u32;
+            }
+        }
+
+        if let Ok(action) = std::env::var("TITAN_MARGIN_ACTION") {
+            match action.to_lowercase().as_str() {
+                "warn" => config.margin_guardrail.action = GuardrailAction::Warn,
+                "block" => config.margin_guardrail.action = GuardrailAction::Block,
+                other => warn!(action = %other, "Unknown margin action, using warn"),
+            }
+        }
+
+        if let Err(e) = config.margin_guardrail.validate() {
+            warn!(error = %e, "Invalid margin floor, using the default");
+            config.margin_guardrail = MarginGuardrail::default();
+        }
+
+        if let Ok(channel) = std::env::var("TITAN_UPDATE_CHANNEL") {
+            match channel.to_lowercase().as_str() {
+                "stable" => config.update_channel = UpdateChannel::Stable,
+                "beta" => config.update_channel = UpdateChannel::Beta,
+                other => warn!(channel = %other, "Unknown update channel, using stable"),
+            }
+        }
+
+        if let Ok(number_str) = std::env::var("TITAN_STATION_NUMBER") {
+            match number_str.trim().parse::<u32>() {
+                Ok(number) => config.station = StationConfig::new(number),
+                Err(_) => warn!(station = %number_str, "Invalid station number, using 1"),
+            }
+        }
+
+        if let Ok(prefix) = std::env::var("TITAN_RECEIPT_PREFIX") {
+            config.station.receipt_prefix = prefix.trim().to_uppercase();
+        }
+
+        if let Ok(printer) = std::env::var("TITAN_DEFAULT_PRINTER") {
+            config.station.default_printer = Some(printer);
+        }
+
+        if let Ok(drawer) = std::env::var("TITAN_CASH_DRAWER") {
+            config.station.default_cash_drawer = Some(drawer);
+        }
+
+        if let Ok(scale) = std::env::var("TITAN_SCALE") {
+            config.station.scale = Some(scale);
+        }
+
+        if let Ok(display) = std::env::var("TITAN_LINE_DISPLAY") {
+            config.station.line_display = Some(display);
+        }
+
+        if let Err(e) = config.station.validate() {
+            warn!(error = %e, "Invalid station configuration, using station 1");
+            config.station = StationConfig::default();
+        }
+
+        if let Ok(days_str) = std::env::var("TITAN_SALE_RETENTION_DAYS") {
+            match days_str.trim().to_lowercase().as_str() {
+                "0" | "off" => config.retention = RetentionPolicy::keep_all(),
+                days => match days.parse::<u32>() {
+                    Ok(days) => config.retention.sale_days = Some(days),
+                    Err(_) => warn!(days = %days_str, "Invalid sale retention, using 90 days"),
+                },
+            }
+        }
+
+        if let Err(e) = config.retention.validate() {
+            warn!(error = %e, "Invalid sale retention, using 90 days");
+            config.retention = RetentionPolicy::default();
+        }
+
+        if let Ok(limit_str) = std::env::var("TITAN_REFUND_APPROVAL_LIMIT") {
+            match limit_str.trim().to_lowercase().as_str() {
+                "off" => config.refund_approval_cents = None,
+                limit => match limit.parse::<f64>() {
+                    Ok(limit) if limit >= 0.0 => {
+                        config.refund_approval_cents = Some((limit * 100.0).round() as i64)
+                    }
+                    _ => warn!(limit = %limit_str, "Invalid refund approval limit, using none"),
+                },
+            }
+        }
+
+        if let Ok(tiers_str) = std::env::var("TITAN_LOYALTY_TIERS") {
+            match parse_tiers(&tiers_str) {
+                Ok(tiers) => config.loyalty.tiers = tiers,
+                Err(_) => warn!(tiers = %tiers_str, "Invalid loyalty tiers, using defaults"),
+            }
+        }
+
+        if let Ok(reward_str) = std::env::var("TITAN_LOYALTY_REWARD") {
+            let (points, value) = reward_str.split_once(':').unwrap_or((&reward_str, ""));
+            match (points.trim().parse::<i64>(), value.trim().parse::<f64>()) {
+                (Ok(points), Ok(value)) => {
+                    config.loyalty.reward_points = points;
+                    config.loyalty.reward_value_cents = (value * 100.0).round() as i64;
+                }
+                _ => warn!(reward = %reward_str, "Invalid loyalty reward, using $5 per 500"),
+            }
+        }
+
+        if let Err(e) = config.loyalty.validate() {
+            warn!(error = %e, "Invalid loyalty program, using the default");
+            config.loyalty = LoyaltyProgram::default();
+        }
+
         config
     }
 
+    /// Returns the notes and coins for the configured currency.
+    ///
+    /// A custom set for `currency_code` is used if it passes
+    /// `CurrencyDenominations::new`, otherwise the built-in set. `None` if
+    /// there is neither.
+    pub fn denominations(&self) -> Option<CurrencyDenominations> {
+        let custom = self
+            .custom_denominations
+            .iter()
+            .find(|set| set.currency_code.eq_ignore_ascii_case(&self.currency_code));
+        if let Some(set) = custom {
+            match CurrencyDenominations::new(&self.currency_code, set.denominations.clone()) {
+                Ok(set) => return Some(set),
+                Err(e) => warn!(error = %e, "Invalid custom denominations, using the built-in set"),
+            }
+        }
+        CurrencyDenominations::for_currency(&self.currency_code)
+    }
+
     /// Formats a cent amount as a currency string.
     ///
     /// ## Example
@@ -183,6 +505,7 @@ impl ConfigState {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use titan_core::Denomination;
 
     #[test]
     fn test_format_currency_positive() {
@@ -204,4 +527,57 @@ mod tests {
         let config = ConfigState::default();
         assert_eq!(config.format_currency(123456789), "$1234567.89");
     }
+
+    #[test]
+    fn test_default_departments_are_valid() {
+        let config = ConfigState::default();
+        assert!(config.departments.validate().is_ok());
+        assert_eq!(
+            config
+                .departments
+                .open_price("BAKERY", 350)
+                .unwrap()
+                .tax_rate_bps,
+            0
+        );
+    }
+
+    #[test]
+    fn test_denominations_follow_currency() {
+        let mut config = ConfigState::default();
+        assert_eq!(config.denominations().unwrap().currency_code, "USD");
+
+        config.currency_code = "XYZ".to_string();
+        assert!(config.denominations().is_none());
+    }
+
+    #[test]
+    fn test_custom_denominations_replace_the_builtin_set() {
+        let mut config = ConfigState::default();
+        config.custom_denominations =
+            vec![CurrencyDenominations::parse("USD", "notes:20,1;coins:0.25,0.01").unwrap()];
+        let usd = config.denominations().unwrap();
+        assert_eq!(usd.denominations.len(), 4);
+        assert!(!usd.contains(500));
+
+        // A set for another currency is ignored
+        config.currency_code = "EUR".to_string();
+        assert_eq!(config.denominations().unwrap().denominations.len(), 15);
+
+        // One that fails validation falls back to the built-in set
+        config.currency_code = "USD".to_string();
+        config.custom_denominations[0].denominations = vec![
+            Denomination::coin(4),
+            Denomination::coin(3),
+            Denomination::coin(1),
+        ];
+        assert_eq!(config.denominations().unwrap().denominations.len(), 10);
+    }
+
+    #[test]
+    fn test_default_loyalty_program_is_valid() {
+        let config = ConfigState::default();
+        assert!(config.loyalty.validate().is_ok());
+        assert_eq!(config.loyalty.tier(1_200).unwrap().name, "Silver");
+    }
 }
@@ -0,0 +1,283 @@
+//! # Crash State Module
+//!
+//! Captures crashes on the terminal as reports on disk (see
+//! `titan_core::crash_report`). The panic hook is installed before
+//! anything else runs, and every log line also goes into a short in-memory
+//! tail so a report shows what led up to the crash.
+//!
+//! ## Capture
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Crash Capture                                   │
+//! │                                                                         │
+//! │  run():  CrashState::from_env()                                         │
+//! │            ├── init_tracing(log_writer) ──► stdout + LogTail            │
+//! │            └── install_panic_hook()                                     │
+//! │  setup:  set_dir(<data dir>/crashes)                                    │
+//! │                                                                         │
+//! │  panic (any thread) ──► hook ──► write crashes/crash-….json            │
+//! │                              └─► previous hook (prints the panic)      │
+//! │  report_frontend_crash ──► write crashes/crash-….json                  │
+//! │                                                                         │
+//! │  TITAN_CRASH_UPLOAD=1 ──► queued reports are uploaded at startup and   │
+//! │  moved to crashes/sent/ (see commands::crash)                          │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Until `set_dir` is called (a panic during early startup), reports go to
+//! `titan-pos-crashes` in the system temp directory.
+
+use std::backtrace::Backtrace;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use chrono::Utc;
+use titan_core::{CrashKind, CrashReport, LogTail};
+use tracing::warn;
+use uuid::Uuid;
+
+/// Version of this build.
+const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Uploaded reports kept on the terminal.
+const SENT_REPORTS_KEPT: usize = 20;
+
+/// Crash capture state managed by Tauri.
+#[derive(Clone)]
+pub struct CrashState {
+    inner: Arc<CrashReporter>,
+}
+
+struct CrashReporter {
+    /// Where reports are written (set in setup).
+    dir: OnceLock<PathBuf>,
+
+    /// Recent log lines.
+    log_tail: LogTail,
+
+    /// Upload queued reports through the cloud.
+    upload_enabled: bool,
+}
+
+impl CrashState {
+    /// Creates the crash state.
+    ///
+    /// ## Environment Variables
+    /// - `TITAN_CRASH_UPLOAD`: "1" or "true" to upload reports through the
+    ///   cloud (off by default; reports are always kept on disk)
+    pub fn from_env() -> Self {
+        let upload_enabled = std::env::var("TITAN_CRASH_UPLOAD")
+            .map(|value| matches!(value.trim().to_lowercase().as_str(), "1" | "true"))
+            .unwrap_or(false);
+        CrashState {
+            inner: Arc::new(CrashReporter {
+                dir: OnceLock::new(),
+                log_tail: LogTail::default(),
+                upload_enabled,
+            }),
+        }
+    }
+
+    /// A writer for the tracing subscriber: output goes to stdout and
+    /// into the log tail.
+    pub fn log_writer(&self) -> LogTailWriter {
+        LogTailWriter {
+            state: self.clone(),
+        }
+    }
+
+    /// Installs the panic hook. The previous hook still runs afterwards.
+    pub fn install_panic_hook(&self) {
+        let state = self.clone();
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let location = info
+                .location()
+                .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+            let report = state.report(
+                CrashKind::Panic,
+                titan_core::crash_report::panic_message(info.payload()),
+                location,
+                std::thread::current().name().map(str::to_string),
+                Backtrace::force_capture().to_string(),
+            );
+            // Nothing left to report a failure to; the previous hook still
+            // prints the panic
+            let _ = state.write(&report);
+            previous(info);
+        }));
+    }
+
+    /// Sets the report directory (once; later calls are ignored).
+    pub fn set_dir(&self, dir: PathBuf) {
+        let _ = self.inner.dir.set(dir);
+    }
+
+    /// Directory of queued reports.
+    pub fn dir(&self) -> PathBuf {
+        self.inner
+            .dir
+            .get()
+            .cloned()
+            .unwrap_or_else(|| std::env::temp_dir().join("titan-pos-crashes"))
+    }
+
+    /// Directory of uploaded reports.
+    pub fn sent_dir(&self) -> PathBuf {
+        self.dir().join("sent")
+    }
+
+    /// Whether queued reports are uploaded through the cloud.
+    pub fn upload_enabled(&self) -> bool {
+        self.inner.upload_enabled
+    }
+
+    /// Builds a report of a crash happening now.
+    pub fn report(
+        &self,
+        kind: CrashKind,
+        message: String,
+        location: Option<String>,
+        thread: Option<String>,
+        backtrace: String,
+    ) -> CrashReport {
+        CrashReport {
+            id: Uuid::new_v4().to_string(),
+            kind,
+            occurred_at: Utc::now(),
+            message,
+            location,
+            thread,
+            backtrace,
+            log_tail: self.inner.log_tail.lines(),
+            app_version: APP_VERSION.to_string(),
+            db_schema_version: Some(titan_db::migrations::latest_version()),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+        }
+    }
+
+    /// Writes a report to the queue directory.
+    pub fn write(&self, report: &CrashReport) -> io::Result<PathBuf> {
+        let dir = self.dir();
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(report.file_name());
+        let json = serde_json::to_vec_pretty(report).map_err(io::Error::other)?;
+        fs::write(&path, json)?;
+        Ok(path)
+    }
+
+    /// Queued (not yet uploaded) reports, oldest first.
+    pub fn pending(&self) -> Vec<(PathBuf, CrashReport)> {
+        read_reports(&self.dir())
+    }
+
+    /// Uploaded reports still on the terminal, oldest first.
+    pub fn sent(&self) -> Vec<(PathBuf, CrashReport)> {
+        read_reports(&self.sent_dir())
+    }
+
+    /// Moves an uploaded report out of the queue, keeping only the newest
+    /// `SENT_REPORTS_KEPT` uploaded reports.
+    pub fn mark_sent(&self, path: &Path) -> io::Result<()> {
+        let sent_dir = self.sent_dir();
+        fs::create_dir_all(&sent_dir)?;
+        let Some(name) = path.file_name() else {
+            return Ok(());
+        };
+        fs::rename(path, sent_dir.join(name))?;
+
+        let sent = self.sent();
+        let excess = sent.len().saturating_sub(SENT_REPORTS_KEPT);
+        for (old, _) in &sent[..excess] {
+            if let Err(e) = fs::remove_file(old) {
+                warn!(?e, path = %old.display(), "Failed to remove old crash report");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads the reports in `dir`, oldest first; unreadable files are skipped.
+fn read_reports(dir: &Path) -> Vec<(PathBuf, CrashReport)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<(PathBuf, CrashReport)> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(CrashReport::is_report_file)
+        })
+        .filter_map(|path| match fs::read(&path) {
+            Ok(bytes) => match serde_json::from_slice(&bytes) {
+                Ok(report) => Some((path, report)),
+                Err(e) => {
+                    warn!(?e, path = %path.display(), "Skipping unreadable crash report");
+                    None
+                }
+            },
+            Err(e) => {
+                warn!(?e, path = %path.display(), "Skipping unreadable crash report");
+                None
+            }
+        })
+        .collect();
+    // File names start with the crash time
+    reports.sort_by(|(a, _), (b, _)| a.file_name().cmp(&b.file_name()));
+    reports
+}
+
+/// Log output writer: stdout, plus the crash report log tail.
+pub struct LogTailWriter {
+    state: CrashState,
+}
+
+impl Write for LogTailWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.state.inner.log_tail.push(buf);
+        io::stdout().write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stdout().flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reports_queue_and_move_to_sent() {
+        let dir = std::env::temp_dir().join(format!("titan-crash-test-{}", Uuid::new_v4()));
+        let crash = CrashState::from_env();
+        crash.set_dir(dir.clone());
+        writeln!(crash.log_writer(), "INFO opening drawer").unwrap();
+
+        let report = crash.report(
+            CrashKind::Frontend,
+            "TypeError: total is undefined".to_string(),
+            Some("Tender.tsx:42".to_string()),
+            None,
+            String::new(),
+        );
+        let path = crash.write(&report).unwrap();
+
+        let pending = crash.pending();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].1, report);
+        assert_eq!(report.log_tail, vec!["INFO opening drawer"]);
+
+        crash.mark_sent(&path).unwrap();
+        assert!(crash.pending().is_empty());
+        assert_eq!(crash.sent().len(), 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
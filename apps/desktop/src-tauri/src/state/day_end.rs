@@ -0,0 +1,283 @@
+//! # Day-End Job
+//!
+//! The scheduled close of the business day (see `titan_core::day_end`).
+//! Registered as the `day_end` job, off until a manager enables it and
+//! sets the closing time with `update_job_schedule`.
+//!
+//! ## Run
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Day-End Job                                     │
+//! │                                                                         │
+//! │  scheduler ("day_end", default "0 23 * * *", disabled)                  │
+//! │       │                                                                 │
+//! │       ├── open_tills   db.tills().open_sessions()                       │
+//! │       ├── z_close      db.z_reports().close_day(device, station, date)  │
+//! │       ├── backup       VACUUM INTO backups/titan-<date>-<time>.db,      │
+//! │       │                oldest beyond BACKUPS_KEPT deleted               │
+//! │       └── sync_flush   agent.flush_outbox(FLUSH_WAIT) (skipped when     │
+//! │                        sync is not running)                             │
+//! │       │                                                                 │
+//! │       ▼                                                                 │
+//! │  DayEndReport ──► "day_end:finished" event (notification on screen)     │
+//! │               ──► hub admin API (agent.report_day_end)                  │
+//! │               ──► job run: failed if any step failed                    │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Local, Utc};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{error, info, warn};
+
+use super::{local_offset, ConfigState, JobFuture, SyncState};
+use titan_core::day_end::{business_date, BACKUPS_KEPT};
+use titan_core::{DayEndReport, DayEndStep, DayEndStepResult, StepOutcome};
+use titan_db::Database;
+use titan_sync::SyncError;
+
+/// Event emitted with the `DayEndReport` when a run finishes.
+pub const DAY_END_EVENT: &str = "day_end:finished";
+
+/// Name of the scheduled job.
+pub const DAY_END_JOB: &str = "day_end";
+
+/// Default closing time, once enabled: 23:00 local time.
+pub const DAY_END_DEFAULT_SCHEDULE: &str = "0 23 * * *";
+
+/// How long the sync flush waits for the outbox to drain.
+const FLUSH_WAIT: Duration = Duration::from_secs(60);
+
+/// Backup files are `titan-<date>-<time>.db`, so names sort by age.
+const BACKUP_PREFIX: &str = "titan-";
+
+/// The `day_end` job handler, writing backups to `backup_dir`.
+pub fn day_end_job(
+    backup_dir: PathBuf,
+) -> impl Fn(AppHandle, Database) -> JobFuture + Send + Sync + 'static {
+    move |app_handle, db| {
+        let backup_dir = backup_dir.clone();
+        Box::pin(async move {
+            let report = run_day_end(&app_handle, &db, &backup_dir).await;
+
+            if let Err(e) = app_handle.emit(DAY_END_EVENT, &report) {
+                error!(?e, "Failed to emit day_end:finished event");
+            }
+            let agent = app_handle.state::<SyncState>().agent_handle();
+            if let Some(agent) = agent {
+                if let Err(e) = agent.report_day_end(report.clone()).await {
+                    warn!(%e, "Could not report day end to the hub");
+                }
+            }
+
+            let failures = report.failures();
+            if failures.is_empty() {
+                Ok(())
+            } else {
+                Err(failures.join("; "))
+            }
+        })
+    }
+}
+
+/// Runs every step, each one even if an earlier one failed.
+async fn run_day_end(app_handle: &AppHandle, db: &Database, backup_dir: &Path) -> DayEndReport {
+    let started_at = Utc::now();
+    let business_date = business_date(started_at, local_offset());
+    let station = app_handle.state::<ConfigState>().station.clone();
+    let device_id = station.device_id();
+    let station_number = station.sale_station();
+    info!(%business_date, "Day end started");
+
+    let mut steps = vec![check_open_tills(db).await];
+
+    let z_report = match db
+        .z_reports()
+        .close_day(&device_id, station_number, business_date, started_at)
+        .await
+    {
+        Ok(z) => {
+            steps.push(result(
+                DayEndStep::ZClose,
+                StepOutcome::Ok,
+                format!(
+                    "Z {}: {} sales, {} voided",
+                    z.sequence, z.sale_count, z.void_count
+                ),
+            ));
+            Some(z)
+        }
+        Err(e) => {
+            steps.push(result(
+                DayEndStep::ZClose,
+                StepOutcome::Failed,
+                e.to_string(),
+            ));
+            None
+        }
+    };
+
+    steps.push(back_up(db, backup_dir, started_at).await);
+    steps.push(flush_sync(app_handle, db).await);
+
+    let report = DayEndReport {
+        device_id,
+        station_number,
+        business_date,
+        started_at,
+        finished_at: Utc::now(),
+        z_report,
+        steps,
+    };
+    info!(%business_date, outcome = ?report.outcome(), "Day end finished");
+    report
+}
+
+/// Warns about tills left open; counting the drawer stays with the cashier.
+async fn check_open_tills(db: &Database) -> DayEndStepResult {
+    match db.tills().open_sessions().await {
+        Ok(sessions) if sessions.is_empty() => result(
+            DayEndStep::OpenTills,
+            StepOutcome::Ok,
+            "All tills closed".to_string(),
+        ),
+        Ok(sessions) => {
+            let open: Vec<String> = sessions
+                .iter()
+                .map(|s| {
+                    let since = s.opened_at.with_timezone(&Local).format("%H:%M");
+                    format!("{} (open since {})", s.device_id, since)
+                })
+                .collect();
+            warn!(open = sessions.len(), "Tills still open at day end");
+            result(
+                DayEndStep::OpenTills,
+                StepOutcome::Warning,
+                format!("Tills still open: {}", open.join(", ")),
+            )
+        }
+        Err(e) => result(DayEndStep::OpenTills, StepOutcome::Failed, e.to_string()),
+    }
+}
+
+/// Copies the database into `backup_dir`, then deletes the oldest copies
+/// beyond `BACKUPS_KEPT`.
+async fn back_up(db: &Database, backup_dir: &Path, at: DateTime<Utc>) -> DayEndStepResult {
+    let name = format!(
+        "{}{}.db",
+        BACKUP_PREFIX,
+        at.with_timezone(&Local).format("%Y%m%d-%H%M%S")
+    );
+    let path = backup_dir.join(&name);
+
+    if let Err(e) = std::fs::create_dir_all(backup_dir) {
+        return result(
+            DayEndStep::Backup,
+            StepOutcome::Failed,
+            format!("Cannot create {}: {}", backup_dir.display(), e),
+        );
+    }
+    if let Err(e) = db.backup_to(&path).await {
+        return result(DayEndStep::Backup, StepOutcome::Failed, e.to_string());
+    }
+
+    let existing: Vec<String> = match std::fs::read_dir(backup_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .collect(),
+        Err(e) => {
+            return result(
+                DayEndStep::Backup,
+                StepOutcome::Warning,
+                format!("Wrote {}, but cannot list old backups: {}", name, e),
+            )
+        }
+    };
+    for old in backups_to_prune(existing) {
+        if let Err(e) = std::fs::remove_file(backup_dir.join(&old)) {
+            warn!(file = %old, %e, "Failed to delete old backup");
+        }
+    }
+
+    result(
+        DayEndStep::Backup,
+        StepOutcome::Ok,
+        format!("Wrote {}", name),
+    )
+}
+
+/// Backup files beyond the newest `BACKUPS_KEPT`, oldest first. Other
+/// files in the directory are left alone.
+fn backups_to_prune(names: Vec<String>) -> Vec<String> {
+    let mut backups: Vec<String> = names
+        .into_iter()
+        .filter(|name| name.starts_with(BACKUP_PREFIX) && name.ends_with(".db"))
+        .collect();
+    backups.sort();
+    let excess = backups.len().saturating_sub(BACKUPS_KEPT);
+    backups.truncate(excess);
+    backups
+}
+
+/// Pushes the outbox to the hub now, so the day's sales leave the store
+/// before it closes.
+async fn flush_sync(app_handle: &AppHandle, db: &Database) -> DayEndStepResult {
+    let Some(agent) = app_handle.state::<SyncState>().agent_handle() else {
+        return result(
+            DayEndStep::SyncFlush,
+            StepOutcome::Skipped,
+            "Sync is not running".to_string(),
+        );
+    };
+
+    match agent.flush_outbox(FLUSH_WAIT).await {
+        Ok(0) => result(
+            DayEndStep::SyncFlush,
+            StepOutcome::Ok,
+            "Outbox empty".to_string(),
+        ),
+        Ok(pending) => result(
+            DayEndStep::SyncFlush,
+            StepOutcome::Warning,
+            format!("{} entries still queued", pending),
+        ),
+        Err(SyncError::Disconnected) => {
+            let pending = db.sync_outbox().count_pending().await.unwrap_or(0);
+            result(
+                DayEndStep::SyncFlush,
+                StepOutcome::Warning,
+                format!("Hub not connected, {} entries queued", pending),
+            )
+        }
+        Err(e) => result(DayEndStep::SyncFlush, StepOutcome::Failed, e.to_string()),
+    }
+}
+
+fn result(step: DayEndStep, outcome: StepOutcome, detail: String) -> DayEndStepResult {
+    DayEndStepResult {
+        step,
+        outcome,
+        detail,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oldest_backups_are_pruned() {
+        let mut names: Vec<String> = (1..=BACKUPS_KEPT + 2)
+            .map(|day| format!("titan-202603{:02}-230000.db", day))
+            .collect();
+        names.push("notes.txt".to_string());
+        names.push("titan.db".to_string());
+
+        assert_eq!(
+            backups_to_prune(names),
+            vec!["titan-20260301-230000.db", "titan-20260302-230000.db"]
+        );
+    }
+}
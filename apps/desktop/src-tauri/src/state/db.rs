@@ -14,37 +14,325 @@
 //!     db: State<'_, DbState>,
 //!     query: String,
 //! ) -> Result<Vec<ProductDto>, ApiError> {
-//!     let products = db.inner().products().search(&query, 20).await?;
+//!     let products = db.inner()?.products().search(&query, 20).await?;
 //!     Ok(products.into_iter().map(ProductDto::from).collect())
 //! }
 //! ```
+//!
+//! ## Startup
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                          Deferred Startup                               │
+//! │                                                                         │
+//! │  setup hook: DbState::starting() ──► window paints the splash          │
+//! │       │                                                                 │
+//! │       └─► background task: open titan.db, run migrations,              │
+//! │           open reporting pool                                           │
+//! │                 ├── ok ──► open(db, reporting) ──► "startup:ready"     │
+//! │                 └── err ─► fail(message) ────────► "startup:failed"    │
+//! │                                                                         │
+//! │  inner() / reporting() / live() before then ──► NotReady               │
+//! │       (ApiError code NOT_READY: retryable while starting,              │
+//! │        not once startup has failed)                                     │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Training Mode
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                          Training Mode                                  │
+//! │                                                                         │
+//! │  set_training_mode(true)                                                │
+//! │       │  first time this run: titan.db ──copy──► titan-training.db      │
+//! │       ▼                                                                 │
+//! │  inner() ──► sandbox          live() ──► titan.db (scheduler, sync)     │
+//! │                                                                         │
+//! │  set_training_mode(false) ──► inner() ──► titan.db again                │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//! The sandbox never queues anything for sync (see
+//! `Database::training_sandbox`). It is copied once per run, so practice
+//! sales survive switching training off and on again until the app
+//! restarts.
+//!
+//! ## Reporting Pool
+//! The back office window runs heavy report queries while the register
+//! keeps selling. Reports use their own small pool on the same file
+//! (`reporting()`), so they can never take the connections the register
+//! needs; with WAL, their reads don't block the register's writes either.
+//!
+//! ## Customer Data Key
+//! Customer emails and phone numbers are encrypted in the database (see
+//! `titan_db::pii`). The master key that protects the tenant's data key
+//! comes from `TITAN_PII_KEY` (hex) or, failing that, from `pii.key` next
+//! to the database, which is created on first run.
+//!
+//! ## Outbox Payload Keys
+//! Customer data and payment references in outbox payloads are sealed
+//! with store-wide keys (see `titan_db::payload_crypto`), which the hub
+//! needs too, so they are provisioned rather than generated here: from
+//! `TITAN_PAYLOAD_KEYS` or `payload.keys` next to the database, sealing
+//! key first. Without either, payloads are queued in plain text.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use titan_db::{Database, DbError, MasterKey, PayloadCipher};
+use tokio::sync::OnceCell;
+use ts_rs::TS;
+
+/// Whether commands run against the training sandbox. Process-wide, so
+/// [`crate::middleware::traced`] can flag failed commands without the
+/// state.
+static TRAINING_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Whether training mode is on (see [`DbState::set_training_mode`]).
+pub fn training_mode() -> bool {
+    TRAINING_MODE.load(Ordering::SeqCst)
+}
+
+/// A command needed the database before startup finished.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotReady {
+    /// Why startup failed (None: still starting).
+    pub failure: Option<String>,
+}
+
+impl fmt::Display for NotReady {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.failure {
+            Some(failure) => write!(f, "Startup failed: {}", failure),
+            None => f.write_str("Still starting up, try again in a moment"),
+        }
+    }
+}
 
-use titan_db::Database;
+impl std::error::Error for NotReady {}
+
+/// Where startup is (see [`DbState::status`]).
+///
+/// Serialized as `{ "phase": "failed", "error": "..." }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "phase", content = "error", rename_all = "lowercase")]
+pub enum StartupStatus {
+    /// Opening the database and running migrations.
+    Starting,
+    /// Commands can use the database.
+    Ready,
+    /// Startup failed; the app needs a restart once the cause is fixed.
+    Failed(String),
+}
+
+/// The databases, once opened.
+#[derive(Debug)]
+struct OpenDatabases {
+    db: Database,
+    /// Separate pool for report queries (None: reports share `db`).
+    reporting: Option<Database>,
+}
 
 /// Wrapper around `Database` for Tauri state management.
 ///
 /// ## Why a Wrapper?
 /// Tauri's state management requires types to implement `Send + Sync`.
 /// This wrapper makes the intent explicit and provides a clean API
-/// for accessing the database in commands.
+/// for accessing the database in commands. It also lets the app start
+/// before the database is open (see Startup above).
 #[derive(Debug)]
 pub struct DbState {
-    db: Database,
+    /// Set once the background startup task has opened the databases.
+    open: OnceLock<OpenDatabases>,
+    /// Set if the background startup task failed.
+    failure: OnceLock<String>,
+    /// Where the training sandbox is copied to (None: no training mode).
+    sandbox_path: Option<PathBuf>,
+    /// The training sandbox, copied the first time training mode is on.
+    sandbox: OnceCell<Database>,
 }
 
 impl DbState {
-    /// Creates a new DbState wrapping the database connection.
+    /// Creates a DbState with no database yet; commands get [`NotReady`]
+    /// until [`DbState::open`] is called.
+    pub fn starting() -> Self {
+        DbState {
+            open: OnceLock::new(),
+            failure: OnceLock::new(),
+            sandbox_path: None,
+            sandbox: OnceCell::new(),
+        }
+    }
+
+    /// Creates a DbState wrapping an open database connection.
     pub fn new(db: Database) -> Self {
-        DbState { db }
+        let state = DbState::starting();
+        state.open(db, None);
+        state
     }
 
-    /// Returns a reference to the inner Database.
+    /// Hands over the opened databases: `db`, and optionally `reporting`,
+    /// a second handle on the same file for report queries.
+    ///
+    /// Only the first call has any effect.
+    pub fn open(&self, db: Database, reporting: Option<Database>) {
+        if self.open.set(OpenDatabases { db, reporting }).is_err() {
+            tracing::warn!("Database already open, ignoring");
+        }
+    }
+
+    /// Records that startup failed; commands then report `failure`.
+    pub fn fail(&self, failure: impl Into<String>) {
+        let _ = self.failure.set(failure.into());
+    }
+
+    /// Where startup is.
+    pub fn status(&self) -> StartupStatus {
+        if self.open.get().is_some() {
+            StartupStatus::Ready
+        } else if let Some(failure) = self.failure.get() {
+            StartupStatus::Failed(failure.clone())
+        } else {
+            StartupStatus::Starting
+        }
+    }
+
+    fn databases(&self) -> Result<&OpenDatabases, NotReady> {
+        self.open.get().ok_or_else(|| NotReady {
+            failure: self.failure.get().cloned(),
+        })
+    }
+
+    /// Enables training mode, with the sandbox copied to `path`.
+    pub fn with_training_sandbox(mut self, path: impl Into<PathBuf>) -> Self {
+        self.sandbox_path = Some(path.into());
+        self
+    }
+
+    /// Returns a reference to the inner Database: the training sandbox
+    /// while training mode is on, the live database otherwise.
     ///
     /// ## Usage
     /// ```rust,ignore
-    /// let products = db_state.inner().products().search("query", 20).await?;
+    /// let products = db_state.inner()?.products().search("query", 20).await?;
     /// ```
-    pub fn inner(&self) -> &Database {
-        &self.db
+    ///
+    /// ## Errors
+    /// [`NotReady`] until startup has opened the database.
+    pub fn inner(&self) -> Result<&Database, NotReady> {
+        let open = self.databases()?;
+        Ok(match self.sandbox.get() {
+            Some(sandbox) if training_mode() => sandbox,
+            _ => &open.db,
+        })
+    }
+
+    /// Returns the database for report queries: the reporting pool, or the
+    /// sandbox while training mode is on.
+    pub fn reporting(&self) -> Result<&Database, NotReady> {
+        match &self.databases()?.reporting {
+            Some(reporting) if !training_mode() => Ok(reporting),
+            _ => self.inner(),
+        }
+    }
+
+    /// Returns the live database, whatever the training mode.
+    pub fn live(&self) -> Result<&Database, NotReady> {
+        Ok(&self.databases()?.db)
+    }
+
+    /// Turns training mode on or off.
+    ///
+    /// Turning it on the first time copies the live database into the
+    /// sandbox.
+    ///
+    /// ## Errors
+    /// `DbError::Internal` if no sandbox path was configured or the
+    /// database isn't open yet, or any error copying the database.
+    pub async fn set_training_mode(&self, enabled: bool) -> Result<(), DbError> {
+        if enabled {
+            let path = self
+                .sandbox_path
+                .as_ref()
+                .ok_or_else(|| DbError::Internal("Training mode is not available".to_string()))?;
+            let live = self.live().map_err(|e| DbError::Internal(e.to_string()))?;
+            self.sandbox
+                .get_or_try_init(|| live.training_sandbox(path.clone()))
+                .await?;
+        }
+        TRAINING_MODE.store(enabled, Ordering::SeqCst);
+        tracing::info!(enabled, "Training mode changed");
+        Ok(())
+    }
+}
+
+/// Loads the customer data master key.
+///
+/// `TITAN_PII_KEY` takes precedence; otherwise the key is read from
+/// `key_file`, which is generated if it doesn't exist yet.
+///
+/// ## Errors
+/// A key that isn't 32 bytes of hex, or a key file that can't be read or
+/// written. Starting without the key would leave stored customer data
+/// unreadable, so this is fatal.
+pub fn load_pii_master_key(key_file: &Path) -> Result<MasterKey, String> {
+    if let Ok(hex) = std::env::var("TITAN_PII_KEY") {
+        if !hex.is_empty() {
+            return MasterKey::from_hex(hex.trim()).map_err(|e| format!("TITAN_PII_KEY: {}", e));
+        }
+    }
+
+    if key_file.exists() {
+        let hex = std::fs::read_to_string(key_file)
+            .map_err(|e| format!("Failed to read {}: {}", key_file.display(), e))?;
+        return MasterKey::from_hex(hex.trim())
+            .map_err(|e| format!("{}: {}", key_file.display(), e));
+    }
+
+    let key = MasterKey::generate().map_err(|e| e.to_string())?;
+    write_key_file(key_file, &key.to_hex())
+        .map_err(|e| format!("Failed to write {}: {}", key_file.display(), e))?;
+    tracing::info!(path = %key_file.display(), "Customer data key created");
+    Ok(key)
+}
+
+/// Loads the outbox payload keys: `TITAN_PAYLOAD_KEYS` if set, else
+/// `key_file` if it exists, else none (payloads stay plain text).
+///
+/// ## Errors
+/// A key that isn't 32 bytes of hex, or a key file that can't be read.
+pub fn load_payload_cipher(key_file: &Path) -> Result<PayloadCipher, String> {
+    if let Ok(keys) = std::env::var("TITAN_PAYLOAD_KEYS") {
+        if !keys.is_empty() {
+            return PayloadCipher::from_hex_list(&keys)
+                .map_err(|e| format!("TITAN_PAYLOAD_KEYS: {}", e));
+        }
+    }
+
+    if !key_file.exists() {
+        tracing::warn!("No outbox payload keys provisioned, payloads are queued in plain text");
+        return Ok(PayloadCipher::disabled());
+    }
+    let keys = std::fs::read_to_string(key_file)
+        .map_err(|e| format!("Failed to read {}: {}", key_file.display(), e))?;
+    let cipher = PayloadCipher::from_hex_list(&keys)
+        .map_err(|e| format!("{}: {}", key_file.display(), e))?;
+    tracing::info!(keys = ?cipher.key_ids(), "Outbox payload keys loaded");
+    Ok(cipher)
+}
+
+/// Writes a new key file readable only by the current user.
+fn write_key_file(path: &Path, hex: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
     }
+    options.open(path)?.write_all(hex.as_bytes())
 }
@@ -0,0 +1,175 @@
+//! # Line Display State
+//!
+//! Holds the driver for this station's customer pole display, chosen once
+//! at startup from `ConfigState.station` (see `titan_core::line_display`),
+//! and keeps the display in step with the entity event bus.
+//!
+//! ## Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                       Line Display Updates                              │
+//! │                                                                         │
+//! │  add_to_cart / add_department_item / …   ──► CartLineAdded ─┐           │
+//! │  create_sale   ──► sales() insert_sale   ──► SaleCreated   ─┼─► bus     │
+//! │  finalize_sale ──► sales() finalize_sale ──► SaleFinalized ─┘    │      │
+//! │                                                                  ▼      │
+//! │  start(): bus.subscribe() ──► frame_for_event ──► DeviceLineDisplay     │
+//! │           (frames written in order on a blocking thread; a display      │
+//! │           that fails is logged and tried again with the next frame)     │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! `TITAN_LINE_DISPLAY` is `epson:<port>` or `cd5220:<port>`; unset,
+//! `none` or invalid leaves the station without a display (an invalid
+//! setting is logged at startup).
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::Arc;
+
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, info, warn};
+
+use crate::state::EventBusState;
+use titan_core::error::CoreResult;
+use titan_core::line_display::frame_for_event;
+use titan_core::{CoreError, DisplayConnection, DisplayFrame, LineDisplay, StationConfig};
+
+/// Managed state wrapping this station's line display driver.
+#[derive(Clone, Default)]
+pub struct LineDisplayState {
+    display: Option<Arc<dyn LineDisplay>>,
+}
+
+impl LineDisplayState {
+    /// Wraps a line display driver.
+    pub fn new(display: Arc<dyn LineDisplay>) -> Self {
+        LineDisplayState {
+            display: Some(display),
+        }
+    }
+
+    /// Creates the driver for the station's display setting.
+    ///
+    /// An invalid setting leaves the station without a display rather than
+    /// stopping the app.
+    pub fn for_station(station: &StationConfig) -> Self {
+        let Some(setting) = &station.line_display else {
+            return LineDisplayState::default();
+        };
+        match DisplayConnection::parse(setting) {
+            Ok(Some(connection)) => {
+                LineDisplayState::new(Arc::new(DeviceLineDisplay::new(connection)))
+            }
+            Ok(None) => LineDisplayState::default(),
+            Err(e) => {
+                warn!(setting = %setting, error = %e, "Invalid line display setting, no display");
+                LineDisplayState::default()
+            }
+        }
+    }
+
+    /// The configured driver, if any.
+    pub fn display(&self) -> Option<Arc<dyn LineDisplay>> {
+        self.display.clone()
+    }
+
+    /// Shows events from `bus` on the display. Does nothing without one.
+    pub fn start(&self, bus: &EventBusState) {
+        let Some(display) = self.display() else {
+            return;
+        };
+        let mut rx = bus.subscribe();
+        info!(port = %display.connection().port, "Line display started");
+        tauri::async_runtime::spawn(async move {
+            loop {
+                let event = match rx.recv().await {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(missed)) => {
+                        warn!(missed, "Line display fell behind");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                let Some(frame) = frame_for_event(&event) else {
+                    continue;
+                };
+
+                debug!(entity_id = event.entity_id(), "Updating line display");
+                let device = display.clone();
+                match tauri::async_runtime::spawn_blocking(move || device.show(&frame)).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => warn!(error = %e, "Line display update failed"),
+                    Err(e) => warn!(?e, "Line display task failed"),
+                }
+            }
+        });
+    }
+}
+
+// =============================================================================
+// Device Line Display
+// =============================================================================
+
+/// Drives a display by writing each frame's bytes to its port.
+pub struct DeviceLineDisplay {
+    connection: DisplayConnection,
+}
+
+impl DeviceLineDisplay {
+    /// Creates a driver for `connection`.
+    pub fn new(connection: DisplayConnection) -> Self {
+        DeviceLineDisplay { connection }
+    }
+}
+
+impl LineDisplay for DeviceLineDisplay {
+    fn connection(&self) -> &DisplayConnection {
+        &self.connection
+    }
+
+    fn show(&self, frame: &DisplayFrame) -> CoreResult<()> {
+        let failed = |e: std::io::Error| CoreError::DeviceFailed {
+            device: format!("Line display ({})", self.connection.port),
+            reason: e.to_string(),
+        };
+        let mut port = OpenOptions::new()
+            .write(true)
+            .open(&self.connection.port)
+            .map_err(failed)?;
+        port.write_all(&frame.encode(self.connection.protocol))
+            .and_then(|_| port.flush())
+            .map_err(failed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use titan_core::DisplayProtocol;
+
+    #[test]
+    fn test_device_display_writes_frame() {
+        let path = std::env::temp_dir().join(format!("titan-display-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, b"").unwrap();
+        let mut station = StationConfig::new(1);
+        station.line_display = Some(format!("cd5220:{}", path.display()));
+
+        let frame = DisplayFrame::total_due(1240);
+        let state = LineDisplayState::for_station(&station);
+        state.display().unwrap().show(&frame).unwrap();
+        assert_eq!(
+            std::fs::read(&path).unwrap(),
+            frame.encode(DisplayProtocol::Cd5220)
+        );
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_invalid_setting_leaves_no_display() {
+        let mut station = StationConfig::new(1);
+        station.line_display = Some("vfd:COM5".to_string());
+        assert!(LineDisplayState::for_station(&station).display().is_none());
+    }
+}
@@ -0,0 +1,165 @@
+//! # Cash Drawer State
+//!
+//! Holds the driver for this station's cash drawer, chosen once at startup
+//! from `ConfigState.station` (see `titan_core::cash_drawer`).
+//!
+//! ## Drivers
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Cash Drawer Drivers                              │
+//! │                                                                         │
+//! │  TITAN_CASH_DRAWER=printer[:pin5]  (TITAN_DEFAULT_PRINTER required)     │
+//! │    DeviceDrawer: kick pulse written raw to the receipt printer          │
+//! │    (/dev/usb/lp0, COM3, \\host\Receipt shared printer)                  │
+//! │                                                                         │
+//! │  TITAN_CASH_DRAWER=direct:<port>                                        │
+//! │    DeviceDrawer: trigger byte written to the interface box's port       │
+//! │                                                                         │
+//! │  unset / none / invalid                                                 │
+//! │    no driver: open_cash_drawer fails with CONFIG_ERROR (an invalid      │
+//! │    setting is logged at startup and reported there)                     │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Thread Safety
+//! The driver is immutable after startup and shared behind an `Arc`.
+//! Writes block while the device is busy, so callers run `open` on a
+//! blocking thread.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::Arc;
+
+use tracing::warn;
+
+use titan_core::error::CoreResult;
+use titan_core::{CashDrawer, CoreError, DrawerConnection, StationConfig};
+
+/// Managed state wrapping this station's cash drawer driver.
+#[derive(Clone, Default)]
+pub struct DrawerState {
+    drawer: Option<Arc<dyn CashDrawer>>,
+    /// Why there is no driver, when the setting was invalid.
+    problem: Option<String>,
+}
+
+impl DrawerState {
+    /// Wraps a cash drawer driver.
+    pub fn new(drawer: Arc<dyn CashDrawer>) -> Self {
+        DrawerState {
+            drawer: Some(drawer),
+            problem: None,
+        }
+    }
+
+    /// Creates the driver for the station's drawer setting.
+    ///
+    /// An invalid setting leaves the station without a drawer rather than
+    /// stopping the app: sales can go on while it is fixed.
+    pub fn for_station(station: &StationConfig) -> Self {
+        let Some(setting) = &station.default_cash_drawer else {
+            return DrawerState::default();
+        };
+        match DrawerConnection::parse(setting, station.default_printer.as_deref()) {
+            Ok(Some(connection)) => DrawerState::new(Arc::new(DeviceDrawer::new(connection))),
+            Ok(None) => DrawerState::default(),
+            Err(e) => {
+                warn!(setting = %setting, error = %e, "Invalid cash drawer setting, no drawer");
+                DrawerState {
+                    drawer: None,
+                    problem: Some(e.to_string()),
+                }
+            }
+        }
+    }
+
+    /// The configured driver, if any.
+    pub fn drawer(&self) -> Option<Arc<dyn CashDrawer>> {
+        self.drawer.clone()
+    }
+
+    /// Why the station has no driver, when its setting is invalid.
+    pub fn problem(&self) -> Option<&str> {
+        self.problem.as_deref()
+    }
+}
+
+// =============================================================================
+// Device Drawer
+// =============================================================================
+
+/// Opens a drawer by writing its open command to a device path: the
+/// receipt printer for a kick pulse, or the drawer's own port.
+pub struct DeviceDrawer {
+    connection: DrawerConnection,
+}
+
+impl DeviceDrawer {
+    /// Creates a driver for `connection`.
+    pub fn new(connection: DrawerConnection) -> Self {
+        DeviceDrawer { connection }
+    }
+}
+
+impl CashDrawer for DeviceDrawer {
+    fn connection(&self) -> &DrawerConnection {
+        &self.connection
+    }
+
+    fn open(&self) -> CoreResult<()> {
+        let device = self.connection.device();
+        let failed = |e: std::io::Error| CoreError::DeviceFailed {
+            device: format!("Cash drawer ({})", device),
+            reason: e.to_string(),
+        };
+
+        let mut port = OpenOptions::new()
+            .write(true)
+            .open(device)
+            .map_err(failed)?;
+        port.write_all(&self.connection.open_command())
+            .and_then(|_| port.flush())
+            .map_err(failed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use titan_core::cash_drawer::kick_pulse;
+    use titan_core::KickPin;
+
+    #[test]
+    fn test_printer_kick_writes_pulse() {
+        let path = std::env::temp_dir().join(format!("titan-drawer-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, b"").unwrap();
+        let mut station = StationConfig::new(2);
+        station.default_printer = Some(path.to_string_lossy().into_owned());
+        station.default_cash_drawer = Some("printer:pin5".to_string());
+
+        let state = DrawerState::for_station(&station);
+        state.drawer().unwrap().open().unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), kick_pulse(KickPin::Pin5));
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_missing_device_fails_open() {
+        let drawer = DeviceDrawer::new(DrawerConnection::Direct {
+            port: "/nonexistent/titan-drawer".to_string(),
+        });
+        let err = drawer.open().unwrap_err();
+        assert!(matches!(err, CoreError::DeviceFailed { .. }));
+    }
+
+    #[test]
+    fn test_invalid_setting_leaves_no_drawer() {
+        let mut station = StationConfig::new(1);
+        station.default_cash_drawer = Some("printer".to_string());
+
+        let state = DrawerState::for_station(&station);
+        assert!(state.drawer().is_none());
+        assert!(state.problem().is_some());
+    }
+}
@@ -0,0 +1,85 @@
+//! # Event Bus State Module
+//!
+//! The in-process channel that carries [`EntityEvent`]s from the
+//! repositories to whoever reacts to them, so a write path doesn't have to
+//! call each consumer itself.
+//!
+//! ## Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Entity Event Bus                                │
+//! │                                                                         │
+//! │  setup:  EventBusState::new()                                           │
+//! │          db.with_events(bus.publisher())                                │
+//! │                                                                         │
+//! │  commands, sync agent, scheduled jobs                                   │
+//! │     └─► db.products() / db.sales() ─► commit ─► publish                 │
+//! │                                                      │                  │
+//! │                                          broadcast channel              │
+//! │                        ┌─────────────────────────────┤                  │
+//! │                        ▼                             ▼                  │
+//! │       start(): "entity:changed" to the      bus.subscribe() in any     │
+//! │       frontend (EntityEvent)                 backend consumer           │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! A receiver that falls [`EVENT_CHANNEL_CAPACITY`] events behind skips
+//! the oldest and is told how many it missed.
+
+use tauri::{AppHandle, Emitter};
+use titan_core::EntityEvent;
+use titan_db::events::EVENT_CHANNEL_CAPACITY;
+use titan_db::EventPublisher;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, error, warn};
+
+/// Event bus state managed by Tauri.
+#[derive(Clone)]
+pub struct EventBusState {
+    tx: broadcast::Sender<EntityEvent>,
+}
+
+impl EventBusState {
+    /// Creates the bus with no subscribers.
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        EventBusState { tx }
+    }
+
+    /// A publisher for the database to send events through.
+    pub fn publisher(&self) -> EventPublisher {
+        EventPublisher::new(self.tx.clone())
+    }
+
+    /// Receives every event published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<EntityEvent> {
+        self.tx.subscribe()
+    }
+
+    /// Forwards events to the frontend as `entity:changed`.
+    pub fn start(&self, app_handle: AppHandle) {
+        let mut rx = self.subscribe();
+        tauri::async_runtime::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => {
+                        debug!(entity_id = event.entity_id(), "Forwarding entity event");
+                        if let Err(e) = app_handle.emit("entity:changed", &event) {
+                            error!(?e, "Failed to emit entity:changed event");
+                        }
+                    }
+                    Err(RecvError::Lagged(missed)) => {
+                        warn!(missed, "Frontend event forwarder fell behind");
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+}
+
+impl Default for EventBusState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,272 @@
+//! # Fiscal State
+//!
+//! Holds the fiscal backend that signs sales at finalization, chosen once
+//! at startup.
+//!
+//! ## Backends
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Fiscal Backends                                  │
+//! │                                                                         │
+//! │  TITAN_FISCAL_BACKEND=none (default)                                    │
+//! │    NoopFiscalAdapter: sales are not signed                              │
+//! │                                                                         │
+//! │  TITAN_FISCAL_BACKEND=file_export                                       │
+//! │    FileExportSigner: HMAC-SHA256 over the canonical payload (which      │
+//! │    includes the previous signature), one JSON file per receipt:         │
+//! │                                                                         │
+//! │    <export dir>/00000001-<receipt number>.json                          │
+//! │    <export dir>/00000002-<receipt number>.json                          │
+//! │                                                                         │
+//! │    The files are picked up by whatever reports to the tax authority.   │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Thread Safety
+//! The adapter is immutable after startup and shared behind an `Arc`.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use titan_core::error::CoreResult;
+use titan_core::fiscal::canonical_payload;
+use titan_core::{
+    CoreError, FiscalAdapter, FiscalChain, FiscalReceipt, FiscalSignature, NoopFiscalAdapter,
+};
+
+/// Managed state wrapping the configured fiscal backend.
+#[derive(Clone)]
+pub struct FiscalState {
+    adapter: Arc<dyn FiscalAdapter>,
+}
+
+impl FiscalState {
+    /// Wraps a fiscal backend.
+    pub fn new(adapter: Arc<dyn FiscalAdapter>) -> Self {
+        FiscalState { adapter }
+    }
+
+    /// Creates the fiscal backend from environment variables.
+    ///
+    /// ## Environment Variables
+    /// - `TITAN_FISCAL_BACKEND`: `none` (default) or `file_export`
+    /// - `TITAN_FISCAL_EXPORT_DIR`: Where signed receipts are written
+    ///   (default: `default_dir`)
+    /// - `TITAN_FISCAL_KEY`: Signing key, required for `file_export`
+    ///
+    /// ## Errors
+    /// An unknown backend or a missing key; starting without the signing
+    /// the store is configured for is not allowed.
+    pub fn from_env(default_dir: &Path) -> Result<Self, String> {
+        let backend = std::env::var("TITAN_FISCAL_BACKEND").unwrap_or_else(|_| "none".to_string());
+
+        match backend.as_str() {
+            "none" => Ok(FiscalState::default()),
+            "file_export" => {
+                let key = std::env::var("TITAN_FISCAL_KEY")
+                    .ok()
+                    .filter(|k| !k.is_empty())
+                    .ok_or_else(|| {
+                        "TITAN_FISCAL_KEY is required for the file_export fiscal backend"
+                            .to_string()
+                    })?;
+                let dir = std::env::var("TITAN_FISCAL_EXPORT_DIR")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|_| default_dir.to_path_buf());
+                Ok(FiscalState::new(Arc::new(FileExportSigner::new(
+                    dir,
+                    key.into_bytes(),
+                ))))
+            }
+            other => Err(format!("Unknown fiscal backend: {}", other)),
+        }
+    }
+
+    /// The configured backend.
+    pub fn adapter(&self) -> &dyn FiscalAdapter {
+        self.adapter.as_ref()
+    }
+}
+
+impl Default for FiscalState {
+    fn default() -> Self {
+        FiscalState::new(Arc::new(NoopFiscalAdapter))
+    }
+}
+
+// =============================================================================
+// File Export Signer
+// =============================================================================
+
+/// Signs receipts with a shared key and exports each one as a JSON file.
+pub struct FileExportSigner {
+    dir: PathBuf,
+    key: Vec<u8>,
+}
+
+/// Contents of an exported receipt file.
+#[derive(Serialize)]
+struct ExportedReceipt<'a> {
+    #[serde(flatten)]
+    signature: &'a FiscalSignature,
+    receipt_number: &'a str,
+    total_cents: i64,
+    /// The exact text that was signed.
+    payload: &'a str,
+}
+
+impl FileExportSigner {
+    /// Creates a signer writing into `dir` (created on first use).
+    pub fn new(dir: PathBuf, key: Vec<u8>) -> Self {
+        FileExportSigner { dir, key }
+    }
+
+    /// Hex HMAC-SHA256 of a payload.
+    fn mac(&self, payload: &str) -> String {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(payload.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn export(
+        &self,
+        receipt: &FiscalReceipt,
+        signature: &FiscalSignature,
+        payload: &str,
+    ) -> std::io::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+
+        let file = ExportedReceipt {
+            signature,
+            receipt_number: &receipt.receipt_number,
+            total_cents: receipt.total_cents,
+            payload,
+        };
+        let json = serde_json::to_vec_pretty(&file)?;
+        let path = self.dir.join(format!(
+            "{:08}-{}.json",
+            signature.sequence, receipt.receipt_number
+        ));
+
+        std::fs::write(path, json)
+    }
+}
+
+impl FiscalAdapter for FileExportSigner {
+    fn backend(&self) -> &'static str {
+        "file_export"
+    }
+
+    fn sign(
+        &self,
+        receipt: &FiscalReceipt,
+        chain: &FiscalChain,
+    ) -> CoreResult<Option<FiscalSignature>> {
+        let payload = canonical_payload(receipt, chain);
+        let signature = FiscalSignature {
+            sale_id: receipt.sale_id.clone(),
+            backend: self.backend().to_string(),
+            sequence: chain.sequence,
+            signature: self.mac(&payload),
+            previous_signature: chain.previous_signature.clone(),
+            signed_at: Utc::now(),
+        };
+
+        self.export(receipt, &signature, &payload)
+            .map_err(|e| CoreError::FiscalSigningFailed {
+                sale_id: receipt.sale_id.clone(),
+                reason: format!("could not export to {}: {}", self.dir.display(), e),
+            })?;
+
+        Ok(Some(signature))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receipt(sale_id: &str) -> FiscalReceipt {
+        FiscalReceipt {
+            sale_id: sale_id.to_string(),
+            receipt_number: format!("R-{}", sale_id),
+            tenant_id: "t".to_string(),
+            device_id: "pos-01".to_string(),
+            subtotal_cents: 1000,
+            tax_cents: 83,
+            total_cents: 1083,
+            lines: Vec::new(),
+            payments: Vec::new(),
+            issued_at: Utc::now(),
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("titan-fiscal-{}-{}", name, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_file_export_signs_chains_and_writes() {
+        let dir = temp_dir("chain");
+        let signer = FileExportSigner::new(dir.clone(), b"secret".to_vec());
+
+        let first = signer
+            .sign(&receipt("s1"), &FiscalChain::after(None))
+            .unwrap()
+            .unwrap();
+        let second = signer
+            .sign(&receipt("s2"), &FiscalChain::after(Some(&first)))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(first.sequence, 1);
+        assert_eq!(second.sequence, 2);
+        assert_eq!(
+            second.previous_signature.as_deref(),
+            Some(first.signature.as_str())
+        );
+        assert_eq!(first.signature.len(), 64);
+        assert!(dir.join("00000001-R-s1.json").exists());
+        assert!(dir.join("00000002-R-s2.json").exists());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_signature_depends_on_key() {
+        let dir = temp_dir("key");
+        let a = FileExportSigner::new(dir.clone(), b"a".to_vec());
+        let b = FileExportSigner::new(dir.clone(), b"b".to_vec());
+        let r = receipt("s1");
+        let chain = FiscalChain::after(None);
+        let payload = canonical_payload(&r, &chain);
+
+        assert_eq!(a.mac(&payload), a.mac(&payload));
+        assert_ne!(a.mac(&payload), b.mac(&payload));
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_unwritable_dir_fails_signing() {
+        let dir = temp_dir("blocked");
+        let blocker = dir.join("file");
+        std::fs::write(&blocker, b"x").unwrap();
+        let signer = FileExportSigner::new(blocker, b"k".to_vec());
+
+        let err = signer
+            .sign(&receipt("s1"), &FiscalChain::after(None))
+            .unwrap_err();
+        assert!(matches!(err, CoreError::FiscalSigningFailed { .. }));
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
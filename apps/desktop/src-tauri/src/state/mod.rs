@@ -28,25 +28,63 @@
 //! │  ┌──────────────┐  ┌──────────────┐  ┌─────────────┐  ┌──────────┐    │
 //! │  │   DbState    │  │  CartState   │  │ ConfigState │  │SyncState │    │
 //! │  │              │  │              │  │             │  │          │    │
-//! │  │  Database    │  │  Arc<Mutex<  │  │ tenant_id   │  │SyncAgent │    │
+//! │  │  Database    │  │  Arc<RwLock< │  │ tenant_id   │  │SyncAgent │    │
 //! │  │  (SQLite     │  │    Cart      │  │ store_name  │  │  handle  │    │
 //! │  │   pool)      │  │  >>          │  │ tax_rate    │  │          │    │
 //! │  └──────────────┘  └──────────────┘  └─────────────┘  └──────────┘    │
 //! │                                                                         │
 //! │  THREAD SAFETY:                                                        │
-//! │  • DbState: Database has internal connection pool (thread-safe)        │
-//! │  • CartState: Protected by Arc<Mutex<T>> for exclusive access          │
-//! │  • ConfigState: Read-only after initialization                         │
+//! │  • DbState: Database has internal connection pool (thread-safe);       │
+//! │    opened in the background at startup (NotReady until then);         │
+//! │    training mode swaps in a sandbox copy                               │
+//! │  • CartState: Arc<RwLock<T>>, shared reads, cached totals              │
+//! │  • ConfigState: Read-only after initialization (profile included)      │
 //! │  • SyncState: RwLock for status, agent runs in background task         │
+//! │  • FiscalState: Arc<dyn FiscalAdapter>, fixed at startup               │
+//! │  • DrawerState: Arc<dyn CashDrawer>, fixed at startup                  │
+//! │  • ScaleState: Arc<dyn Scale> fixed at startup, reads behind a mutex,  │
+//! │    live feed toggled through an AtomicBool                             │
+//! │  • LineDisplayState: Arc<dyn LineDisplay>, its own bus subscriber      │
+//! │  • SchedulerState: job handlers fixed at startup, loop in background   │
+//! │    (the day-end job reads ConfigState and SyncState when it runs)      │
+//! │  • EventBusState: broadcast sender, each subscriber gets its own copy  │
+//! │  • CrashState: log tail behind a mutex, report dir set once in setup   │
 //! └─────────────────────────────────────────────────────────────────────────┘
 //! ```
 
 mod cart;
 mod config;
+mod crash;
+mod day_end;
 mod db;
+mod display;
+mod drawer;
+mod events;
+mod fiscal;
+mod portable;
+mod profile;
+mod scale;
+mod scheduler;
 mod sync;
 
-pub use cart::{Cart, CartItem, CartState, CartTotals};
-pub use config::ConfigState;
-pub use db::DbState;
+pub use cart::{Cart, CartItem, CartState, CartTotals, LineQuantity};
+pub use config::{ConfigState, UpdateChannel};
+pub use crash::{CrashState, LogTailWriter};
+pub use day_end::{day_end_job, DAY_END_DEFAULT_SCHEDULE, DAY_END_EVENT, DAY_END_JOB};
+pub use db::{
+    load_payload_cipher, load_pii_master_key, training_mode, DbState, NotReady, StartupStatus,
+};
+pub use display::{DeviceLineDisplay, LineDisplayState};
+pub use drawer::{DeviceDrawer, DrawerState};
+pub use events::EventBusState;
+pub use fiscal::{FileExportSigner, FiscalState};
+pub use portable::PortableMode;
+pub use profile::{AppProfile, DEV_SEED_PRODUCTS};
+pub use scale::{
+    DeviceScale, ScaleState, SCALE_ERROR_EVENT, SCALE_POLL_INTERVAL, SCALE_WEIGHT_EVENT,
+};
+pub use scheduler::{
+    local_offset, next_run, sale_retention_job, JobAlertEvent, JobFuture, SchedulerState,
+    SALE_RETENTION_DEFAULT_SCHEDULE, SALE_RETENTION_JOB,
+};
 pub use sync::{SyncState, SyncStatusDto, TauriSyncEventEmitter};
@@ -0,0 +1,190 @@
+//! # Portable Mode
+//!
+//! For pop-up stores and kiosks that run the app from a USB stick, where
+//! the platform data directory is wiped between sessions. In portable mode
+//! the database, its neighbours (crash reports, customer data key, fiscal
+//! exports), the configuration and the logs all live next to the
+//! executable, so the stick carries everything.
+//!
+//! ## Layout
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Portable Mode                                   │
+//! │                                                                         │
+//! │  Turned on by any of:                                                   │
+//! │    titan-pos --portable                                                 │
+//! │    TITAN_PORTABLE=1                                                     │
+//! │    a file named `titan.portable` next to the executable                 │
+//! │                                                                         │
+//! │  <exe dir>/                                                             │
+//! │  ├── titan-pos(.exe)                                                    │
+//! │  ├── titan.portable          marker (contents ignored)                  │
+//! │  ├── titan.env               config: TITAN_* settings, KEY=VALUE        │
+//! │  └── titan-data/                                                        │
+//! │      ├── titan.db            (dev/, staging/ for those profiles)        │
+//! │      ├── crashes/  pii.key  fiscal/                                     │
+//! │      └── logs/titan.log      (previous run's log in titan.log.1)        │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Settings already in the environment win over `titan.env`, so one
+//! session can still be started differently from the shell.
+
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Command line flag that turns portable mode on.
+pub const PORTABLE_FLAG: &str = "--portable";
+
+/// Marker file next to the executable that turns portable mode on.
+pub const PORTABLE_MARKER: &str = "titan.portable";
+
+/// Configuration file next to the executable.
+pub const PORTABLE_CONFIG: &str = "titan.env";
+
+/// Directory next to the executable holding the data.
+const DATA_DIR: &str = "titan-data";
+
+/// A log bigger than this is moved aside at startup.
+const MAX_LOG_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Portable deployment, rooted at the executable's directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortableMode {
+    root: PathBuf,
+}
+
+impl PortableMode {
+    /// Portable mode, if turned on for this launch.
+    ///
+    /// ## Environment Variables
+    /// - `TITAN_PORTABLE`: "1" or "true" to turn portable mode on
+    pub fn detect() -> Option<Self> {
+        let exe = std::env::current_exe().ok()?;
+        let root = exe.parent()?.to_path_buf();
+
+        let flag = std::env::args().any(|arg| arg == PORTABLE_FLAG);
+        let env = std::env::var("TITAN_PORTABLE")
+            .map(|value| matches!(value.trim().to_lowercase().as_str(), "1" | "true"))
+            .unwrap_or(false);
+        let marker = root.join(PORTABLE_MARKER).is_file();
+
+        (flag || env || marker).then(|| PortableMode::at(root))
+    }
+
+    /// Portable mode rooted at `root`.
+    pub fn at(root: impl Into<PathBuf>) -> Self {
+        PortableMode { root: root.into() }
+    }
+
+    /// The executable's directory.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Where data is kept, instead of the platform data directory.
+    pub fn data_dir(&self) -> PathBuf {
+        self.root.join(DATA_DIR)
+    }
+
+    /// The configuration file (`titan.env`).
+    pub fn config_path(&self) -> PathBuf {
+        self.root.join(PORTABLE_CONFIG)
+    }
+
+    /// Where logs are written.
+    pub fn log_dir(&self) -> PathBuf {
+        self.data_dir().join("logs")
+    }
+
+    /// Loads `titan.env` into the environment, skipping settings that are
+    /// already set. Returns how many were loaded (0 without a file).
+    ///
+    /// Must run before anything reads the environment and before other
+    /// threads start.
+    pub fn load_config(&self) -> io::Result<usize> {
+        let text = match fs::read_to_string(self.config_path()) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e),
+        };
+
+        let mut loaded = 0;
+        for (key, value) in parse_env_file(&text) {
+            if std::env::var_os(&key).is_none() {
+                std::env::set_var(key, value);
+                loaded += 1;
+            }
+        }
+        Ok(loaded)
+    }
+
+    /// Opens `logs/titan.log` for appending. A log over 10 MB is moved to
+    /// `titan.log.1` first (replacing the one before).
+    pub fn open_log_file(&self) -> io::Result<File> {
+        let dir = self.log_dir();
+        fs::create_dir_all(&dir)?;
+        let path = dir.join("titan.log");
+        if fs::metadata(&path).is_ok_and(|meta| meta.len() > MAX_LOG_BYTES) {
+            fs::rename(&path, dir.join("titan.log.1"))?;
+        }
+        OpenOptions::new().create(true).append(true).open(path)
+    }
+}
+
+/// Parses `KEY=VALUE` lines. Blank lines and `#` comments are skipped,
+/// and quotes around a value are removed.
+pub fn parse_env_file(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let key = key.trim();
+            if key.is_empty() {
+                return None;
+            }
+            let value = value.trim();
+            let value = ['"', '\'']
+                .iter()
+                .find_map(|q| value.strip_prefix(*q)?.strip_suffix(*q))
+                .unwrap_or(value);
+            Some((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_env_file() {
+        let text = "# Pop-up store on the pier\n\
+                    TITAN_STORE_NAME=\"Pier Pop-Up\"\n\
+                    \n\
+                    export TITAN_ENV = staging\n\
+                    TITAN_TAX_RATE='0'\n\
+                    not a setting\n\
+                    =orphan\n";
+        assert_eq!(
+            parse_env_file(text),
+            vec![
+                ("TITAN_STORE_NAME".to_string(), "Pier Pop-Up".to_string()),
+                ("TITAN_ENV".to_string(), "staging".to_string()),
+                ("TITAN_TAX_RATE".to_string(), "0".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_data_lives_next_to_the_executable() {
+        let root = Path::new("/media/usb/titan");
+        let portable = PortableMode::at(root);
+        assert_eq!(portable.data_dir(), root.join("titan-data"));
+        assert_eq!(portable.config_path(), root.join("titan.env"));
+        assert_eq!(portable.log_dir(), root.join("titan-data/logs"));
+    }
+}
@@ -0,0 +1,252 @@
+//! # Environment Profiles
+//!
+//! Which environment this terminal runs in: `dev`, `staging` or `prod`.
+//! The profile decides where the database lives, how much is logged, which
+//! cloud the terminal talks to, and whether an empty database is seeded.
+//! It is chosen at launch with `TITAN_ENV`, so a release build can be
+//! pointed at staging without rebuilding; without it, debug builds are
+//! `dev` and release builds are `prod`.
+//!
+//! ## Profiles
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Environment Profiles                            │
+//! │                                                                         │
+//! │             dev                  staging               prod             │
+//! │  database   data/titan.db        <data dir>/staging/   <data dir>/      │
+//! │             (repo, if seeded),   titan.db              titan.db         │
+//! │             else <data dir>/dev/                                        │
+//! │  logs       info,titan=debug     info,titan=debug      warn,titan=info  │
+//! │  cloud      localhost:50051      staging-api…          api.titanpos.io  │
+//! │  seeding    empty db gets a      never                 never            │
+//! │             demo catalog                                                │
+//! │                                                                         │
+//! │  TITAN_DB_PATH, RUST_LOG and TITAN_CLOUD_URL still override the         │
+//! │  profile's choice.                                                      │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! In portable mode `<data dir>` is `titan-data` next to the executable.
+//! Crash reports, the customer data key, fiscal exports and the training
+//! sandbox sit next to the database, so each profile keeps its own.
+
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use ts_rs::TS;
+
+use super::PortableMode;
+
+/// Products in the demo catalog seeded into an empty dev database.
+pub const DEV_SEED_PRODUCTS: usize = 500;
+
+/// Environment the app runs in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum AppProfile {
+    /// Developer machine: repo database, local cloud, demo data
+    Dev,
+
+    /// Pre-release testing against the staging cloud
+    Staging,
+
+    /// Store terminals
+    Prod,
+}
+
+impl AppProfile {
+    /// Reads the profile from `TITAN_ENV`.
+    ///
+    /// ## Environment Variables
+    /// - `TITAN_ENV`: "dev", "staging" or "prod" ("development" and
+    ///   "production" also work). Unset: `dev` for debug builds, `prod`
+    ///   for release builds
+    ///
+    /// # Errors
+    /// The value of `TITAN_ENV` is not a profile name.
+    pub fn from_env() -> Result<Self, String> {
+        match std::env::var("TITAN_ENV") {
+            Ok(name) if !name.trim().is_empty() => name.parse(),
+            _ => Ok(Self::build_default()),
+        }
+    }
+
+    /// Profile used when `TITAN_ENV` is not set.
+    pub fn build_default() -> Self {
+        if cfg!(debug_assertions) {
+            AppProfile::Dev
+        } else {
+            AppProfile::Prod
+        }
+    }
+
+    /// Profile name as `TITAN_ENV` takes it.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AppProfile::Dev => "dev",
+            AppProfile::Staging => "staging",
+            AppProfile::Prod => "prod",
+        }
+    }
+
+    /// Log filter used unless `RUST_LOG` is set.
+    ///
+    /// Staging logs like dev, so problems found there come with full logs.
+    pub fn log_filter(&self) -> &'static str {
+        match self {
+            AppProfile::Dev | AppProfile::Staging => "info,titan=debug,sqlx=warn",
+            AppProfile::Prod => "warn,titan=info",
+        }
+    }
+
+    /// Cloud API the profile talks to unless `TITAN_CLOUD_URL` is set.
+    pub fn default_cloud_url(&self) -> &'static str {
+        match self {
+            AppProfile::Dev => "http://localhost:50051",
+            AppProfile::Staging => "https://staging-api.titanpos.io:50051",
+            AppProfile::Prod => "https://api.titanpos.io:50051",
+        }
+    }
+
+    /// Cloud API URL: `TITAN_CLOUD_URL`, or the profile's default.
+    pub fn cloud_url(&self) -> String {
+        std::env::var("TITAN_CLOUD_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty())
+            .unwrap_or_else(|| self.default_cloud_url().to_string())
+    }
+
+    /// Whether an empty database is filled with a demo catalog
+    /// (`DEV_SEED_PRODUCTS` products) when it is opened.
+    pub fn seeds_empty_database(&self) -> bool {
+        *self == AppProfile::Dev
+    }
+
+    /// Determines the database file path.
+    ///
+    /// ## Development Database
+    /// The dev profile looks for a seeded database in `data/titan.db`
+    /// relative to the project root, so the app uses the same database
+    /// seeded by `cargo run -p titan-db --bin seed`. Without one it uses
+    /// its own directory, never the prod database.
+    ///
+    /// ## Platform Data Directory
+    /// - **macOS**: `~/Library/Application Support/com.titan.pos/`
+    /// - **Windows**: `%APPDATA%\titan\pos\`
+    /// - **Linux**: `~/.local/share/titan-pos/`
+    ///
+    /// In portable mode the data directory next to the executable is used
+    /// instead (see `PortableMode`), and the repo database is not looked for.
+    ///
+    /// Prod uses `titan.db` there; dev and staging use `dev/titan.db` and
+    /// `staging/titan.db`.
+    ///
+    /// ## Environment Override
+    /// Set `TITAN_DB_PATH` to use a custom path with any profile.
+    pub fn database_path(
+        &self,
+        portable: Option<&PortableMode>,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        // Check for explicit override first
+        if let Ok(path) = std::env::var("TITAN_DB_PATH") {
+            info!(path = %path, "Using TITAN_DB_PATH override");
+            return Ok(PathBuf::from(path));
+        }
+
+        if *self == AppProfile::Dev && portable.is_none() {
+            if let Some(path) = find_dev_database()? {
+                info!(?path, "Using development database");
+                return Ok(path);
+            }
+            info!("No development database found, using the dev data directory");
+        }
+
+        let base_dir = match portable {
+            Some(portable) => portable.data_dir(),
+            None => ProjectDirs::from("com", "titan", "pos")
+                .ok_or("Could not determine app data directory")?
+                .data_dir()
+                .to_path_buf(),
+        };
+        let data_dir = match self {
+            AppProfile::Prod => base_dir,
+            AppProfile::Dev | AppProfile::Staging => base_dir.join(self.as_str()),
+        };
+
+        // Create directory if it doesn't exist
+        std::fs::create_dir_all(&data_dir)?;
+
+        Ok(data_dir.join("titan.db"))
+    }
+}
+
+/// The repo's seeded `data/titan.db`, if there is one.
+///
+/// Tauri runs the binary from target/debug, so relative paths alone won't
+/// work; `CARGO_MANIFEST_DIR` (set at compile time) finds the project root.
+fn find_dev_database() -> Result<Option<PathBuf>, std::io::Error> {
+    // Paths to try, in order of preference
+    let paths_to_try = [
+        // From apps/desktop/src-tauri, go up to project root
+        PathBuf::from(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../../data/titan.db"
+        )),
+        // From project root (if running cargo run directly)
+        PathBuf::from("./data/titan.db"),
+        // From apps/desktop directory
+        PathBuf::from("../../data/titan.db"),
+    ];
+
+    for path in &paths_to_try {
+        if path.exists() {
+            return path.canonicalize().map(Some);
+        }
+    }
+    Ok(None)
+}
+
+impl FromStr for AppProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "dev" | "development" => Ok(AppProfile::Dev),
+            "staging" => Ok(AppProfile::Staging),
+            "prod" | "production" => Ok(AppProfile::Prod),
+            other => Err(format!(
+                "Unknown TITAN_ENV '{}' (expected dev, staging or prod)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for AppProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_names() {
+        assert_eq!("production".parse::<AppProfile>(), Ok(AppProfile::Prod));
+        assert_eq!(" Staging ".parse::<AppProfile>(), Ok(AppProfile::Staging));
+        assert!("qa".parse::<AppProfile>().is_err());
+
+        for profile in [AppProfile::Dev, AppProfile::Staging, AppProfile::Prod] {
+            assert_eq!(profile.to_string().parse::<AppProfile>(), Ok(profile));
+        }
+        assert!(AppProfile::Dev.seeds_empty_database());
+        assert!(!AppProfile::Staging.seeds_empty_database());
+    }
+}
@@ -0,0 +1,293 @@
+//! # Scale State
+//!
+//! Holds the driver for this station's scale, chosen once at startup from
+//! `ConfigState.station` (see `titan_core::scale`), and the live weight
+//! feed the weighing screen shows while goods are on the platter.
+//!
+//! ## Live Weight
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                          Live Weight Feed                               │
+//! │                                                                         │
+//! │  watch_weight(true)  ──► watching                                       │
+//! │                                                                         │
+//! │  start() loop, every SCALE_POLL_INTERVAL while watching:                │
+//! │    dyn Scale::read (blocking thread)                                    │
+//! │       ├─ weight or status changed ──► "scale:weight" (WeightReading)    │
+//! │       └─ read failed (first time)  ──► "scale:error" (message)          │
+//! │                                                                         │
+//! │  watch_weight(false) ──► idle; the scale is left alone                  │
+//! │                                                                         │
+//! │  read_weight / add_weighed_item read the scale directly; reads are      │
+//! │  serialized so the feed and a sale never talk over each other           │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Drivers
+//! - `TITAN_SCALE=serial:<port>`: [`DeviceScale`] writes `W` and reads the
+//!   reply up to the carriage return
+//! - `TITAN_SCALE=hid:<path>`: [`DeviceScale`] reads one HID scale report
+//! - unset, `none` or invalid: no driver (an invalid setting is logged at
+//!   startup and reported by the commands)
+
+use std::fs::OpenOptions;
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::Utc;
+use tauri::{AppHandle, Emitter};
+use tracing::{debug, error, warn};
+
+use titan_core::error::CoreResult;
+use titan_core::scale::{parse_hid_report, parse_serial_frame, HID_REPORT_LEN, WEIGHT_REQUEST};
+use titan_core::{CoreError, Scale, ScaleConnection, StationConfig, WeightReading};
+
+/// Event carrying each changed [`WeightReading`] while watching.
+pub const SCALE_WEIGHT_EVENT: &str = "scale:weight";
+
+/// Event carrying the message of a failed read while watching.
+pub const SCALE_ERROR_EVENT: &str = "scale:error";
+
+/// How often the scale is read while watching.
+pub const SCALE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Longest serial reply read before giving up on a carriage return.
+const MAX_SERIAL_REPLY: usize = 64;
+
+/// Managed state wrapping this station's scale driver.
+#[derive(Clone, Default)]
+pub struct ScaleState {
+    scale: Option<Arc<dyn Scale>>,
+    /// Why there is no driver, when the setting was invalid.
+    problem: Option<String>,
+    watching: Arc<AtomicBool>,
+}
+
+impl ScaleState {
+    /// Wraps a scale driver.
+    pub fn new(scale: Arc<dyn Scale>) -> Self {
+        ScaleState {
+            scale: Some(scale),
+            ..ScaleState::default()
+        }
+    }
+
+    /// Creates the driver for the station's scale setting.
+    ///
+    /// An invalid setting leaves the station without a scale rather than
+    /// stopping the app.
+    pub fn for_station(station: &StationConfig) -> Self {
+        let Some(setting) = &station.scale else {
+            return ScaleState::default();
+        };
+        match ScaleConnection::parse(setting) {
+            Ok(Some(connection)) => ScaleState::new(Arc::new(DeviceScale::new(connection))),
+            Ok(None) => ScaleState::default(),
+            Err(e) => {
+                warn!(setting = %setting, error = %e, "Invalid scale setting, no scale");
+                ScaleState {
+                    problem: Some(e.to_string()),
+                    ..ScaleState::default()
+                }
+            }
+        }
+    }
+
+    /// The configured driver, if any.
+    pub fn scale(&self) -> Option<Arc<dyn Scale>> {
+        self.scale.clone()
+    }
+
+    /// Why the station has no driver, when its setting is invalid.
+    pub fn problem(&self) -> Option<&str> {
+        self.problem.as_deref()
+    }
+
+    /// Turns the live weight feed on or off.
+    pub fn set_watching(&self, watching: bool) {
+        self.watching.store(watching, Ordering::SeqCst);
+    }
+
+    /// Starts the live weight loop. Does nothing without a scale.
+    pub fn start(&self, app_handle: AppHandle) {
+        let Some(scale) = self.scale() else {
+            return;
+        };
+        let watching = self.watching.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut last: Option<WeightReading> = None;
+            let mut failing = false;
+            loop {
+                tokio::time::sleep(SCALE_POLL_INTERVAL).await;
+                if !watching.load(Ordering::SeqCst) {
+                    last = None;
+                    failing = false;
+                    continue;
+                }
+
+                let device = scale.clone();
+                let read = tauri::async_runtime::spawn_blocking(move || device.read()).await;
+                match read {
+                    Ok(Ok(reading)) => {
+                        failing = false;
+                        let changed = last.as_ref().map_or(true, |l| {
+                            l.gross_grams != reading.gross_grams || l.status != reading.status
+                        });
+                        if changed {
+                            debug!(
+                                grams = reading.gross_grams,
+                                status = ?reading.status,
+                                "Weight changed"
+                            );
+                            if let Err(e) = app_handle.emit(SCALE_WEIGHT_EVENT, &reading) {
+                                error!(?e, "Failed to emit scale:weight event");
+                            }
+                            last = Some(reading);
+                        }
+                    }
+                    Ok(Err(e)) if !failing => {
+                        failing = true;
+                        warn!(error = %e, "Scale read failed");
+                        if let Err(e) = app_handle.emit(SCALE_ERROR_EVENT, e.to_string()) {
+                            error!(?e, "Failed to emit scale:error event");
+                        }
+                    }
+                    Ok(Err(_)) => {}
+                    Err(e) => error!(?e, "Scale read task failed"),
+                }
+            }
+        });
+    }
+}
+
+// =============================================================================
+// Device Scale
+// =============================================================================
+
+/// Reads a scale through its device path: a serial port polled with `W`,
+/// or a HID device's report stream.
+pub struct DeviceScale {
+    connection: ScaleConnection,
+    /// Held for the length of a read.
+    port: Mutex<()>,
+}
+
+impl DeviceScale {
+    /// Creates a driver for `connection`.
+    pub fn new(connection: ScaleConnection) -> Self {
+        DeviceScale {
+            connection,
+            port: Mutex::new(()),
+        }
+    }
+}
+
+impl Scale for DeviceScale {
+    fn connection(&self) -> &ScaleConnection {
+        &self.connection
+    }
+
+    fn read(&self) -> CoreResult<WeightReading> {
+        let _port = self.port.lock().unwrap_or_else(|e| e.into_inner());
+        let device = self.connection.device();
+        let failed = |reason: String| CoreError::DeviceFailed {
+            device: format!("Scale ({})", device),
+            reason,
+        };
+
+        let frame = match &self.connection {
+            ScaleConnection::Serial { port } => {
+                let mut port = OpenOptions::new()
+                    .read(true)
+                    .write(true)
+                    .open(port)
+                    .map_err(|e| failed(e.to_string()))?;
+                port.write_all(&[WEIGHT_REQUEST, b'\r'])
+                    .map_err(|e| failed(e.to_string()))?;
+
+                let mut reply = Vec::new();
+                let mut byte = [0u8; 1];
+                while reply.len() < MAX_SERIAL_REPLY {
+                    match port.read(&mut byte).map_err(|e| failed(e.to_string()))? {
+                        0 => break,
+                        _ if byte[0] == b'\r' && !reply.is_empty() => break,
+                        _ => reply.push(byte[0]),
+                    }
+                }
+                parse_serial_frame(&String::from_utf8_lossy(&reply))
+            }
+            ScaleConnection::Hid { path } => {
+                let mut report = [0u8; HID_REPORT_LEN];
+                OpenOptions::new()
+                    .read(true)
+                    .open(path)
+                    .and_then(|mut hid| hid.read_exact(&mut report))
+                    .map_err(|e| failed(e.to_string()))?;
+                parse_hid_report(&report)
+            }
+        }
+        .map_err(|e| failed(e.to_string()))?;
+
+        Ok(frame.reading(Utc::now()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use titan_core::ScaleStatus;
+
+    fn temp_file(name: &str, contents: &[u8]) -> std::path::PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("titan-scale-{}-{}", name, uuid::Uuid::new_v4()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_hid_scale_reads_report() {
+        let path = temp_file("hid", &[3, 4, 2, 0, 0xC7, 0x01]);
+        let mut station = StationConfig::new(1);
+        station.scale = Some(format!("hid:{}", path.display()));
+
+        let reading = ScaleState::for_station(&station)
+            .scale()
+            .unwrap()
+            .read()
+            .unwrap();
+        assert_eq!(reading.gross_grams, 455);
+        assert_eq!(reading.status, ScaleStatus::Stable);
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_unreadable_scale_fails() {
+        let scale = DeviceScale::new(ScaleConnection::Serial {
+            port: "/nonexistent/titan-scale".to_string(),
+        });
+        assert!(matches!(
+            scale.read().unwrap_err(),
+            CoreError::DeviceFailed { .. }
+        ));
+
+        let path = temp_file("short", &[3, 4]);
+        let scale = DeviceScale::new(ScaleConnection::Hid {
+            path: path.to_string_lossy().into_owned(),
+        });
+        assert!(scale.read().is_err());
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_invalid_setting_leaves_no_scale() {
+        let mut station = StationConfig::new(1);
+        station.scale = Some("serial".to_string());
+
+        let state = ScaleState::for_station(&station);
+        assert!(state.scale().is_none());
+        assert!(state.problem().is_some());
+    }
+}
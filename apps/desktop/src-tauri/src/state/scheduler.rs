@@ -0,0 +1,389 @@
+//! # Scheduler State Module
+//!
+//! Runs background jobs on cron-style schedules, instead of each feature
+//! spawning its own timer task.
+//!
+//! ## Architecture
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                       Job Scheduler                                     │
+//! │                                                                         │
+//! │  SchedulerState::new()                                                  │
+//! │    .job("outbox_cleanup", "0 3 * * *", outbox_cleanup)   ◄── handlers   │
+//! │    .job("sale_retention", "0 4 * * *", sale_retention_job(policy))      │
+//! │    .app_job("day_end", "0 23 * * *", false, day_end_job)  (off until    │
+//! │       │                                   enabled, gets the AppHandle)  │
+//! │       ▼  start(app, db)  (once, in setup)                               │
+//! │  1. register each job in scheduled_jobs (stored schedule wins)          │
+//! │  2. close runs cut off by the last exit as failed                       │
+//! │  3. every TICK_INTERVAL:                                                │
+//! │       db.jobs().due(now) ──► run each job not already running           │
+//! │                                                                         │
+//! │  run: start_run ─► handler(db) ─► finish_run(next_run_at)               │
+//! │       next_run_at is the first slot after the run finished, so runs    │
+//! │       missed while the app was closed collapse into one.               │
+//! │                                                                         │
+//! │  Failure streak reaches JOB_FAILURE_ALERT_THRESHOLD:                   │
+//! │       error log + "jobs:alert" event (JobAlertEvent)                   │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Schedules are read in the terminal's local time.
+
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, FixedOffset, Local, Offset, Utc};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use titan_core::schedule::{raises_alert, JOB_RUN_RETENTION_DAYS};
+use titan_core::{CronSchedule, RetentionPolicy};
+use titan_db::{Database, DbError};
+use tracing::{debug, error, info, warn};
+use ts_rs::TS;
+
+/// How often the scheduler looks for due jobs.
+const TICK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Days synced outbox entries are kept.
+const OUTBOX_RETENTION_DAYS: u32 = 30;
+
+/// Name of the job pruning old sales.
+pub const SALE_RETENTION_JOB: &str = "sale_retention";
+
+/// Default schedule of the sale retention job (nightly, after the outbox
+/// cleanup).
+pub const SALE_RETENTION_DEFAULT_SCHEDULE: &str = "0 4 * * *";
+
+/// Sales pruned per transaction, so a first run on a years-old database
+/// does not hold the write lock for long.
+const SALE_PRUNE_BATCH: u32 = 500;
+
+/// What a job handler returns: `Err` holds the message recorded for the run.
+pub type JobFuture = Pin<Box<dyn Future<Output = Result<(), String>> + Send>>;
+
+type JobHandler = Arc<dyn Fn(AppHandle, Database) -> JobFuture + Send + Sync>;
+
+struct JobSpec {
+    default_schedule: &'static str,
+    /// Whether the job runs before anyone enables it
+    enabled_by_default: bool,
+    handler: JobHandler,
+}
+
+/// Scheduler state managed by Tauri.
+#[derive(Clone)]
+pub struct SchedulerState {
+    /// Registered jobs by name (fixed once the app starts)
+    jobs: Arc<HashMap<&'static str, JobSpec>>,
+
+    /// Jobs with a run in progress
+    running: Arc<Mutex<HashSet<String>>>,
+}
+
+impl SchedulerState {
+    /// Creates a scheduler with no jobs.
+    pub fn new() -> Self {
+        SchedulerState {
+            jobs: Arc::new(HashMap::new()),
+            running: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Creates a scheduler with the app's built-in maintenance jobs.
+    pub fn with_builtin_jobs() -> Self {
+        SchedulerState::new()
+            .job("outbox_cleanup", "0 3 * * *", outbox_cleanup)
+            .job("job_history_prune", "30 3 * * *", job_history_prune)
+    }
+
+    /// Adds a job with its default schedule, enabled.
+    ///
+    /// ## Panics
+    /// If called after the scheduler has been cloned (i.e. started).
+    pub fn job<F>(self, name: &'static str, default_schedule: &'static str, handler: F) -> Self
+    where
+        F: Fn(Database) -> JobFuture + Send + Sync + 'static,
+    {
+        self.app_job(name, default_schedule, true, move |_, db| handler(db))
+    }
+
+    /// Adds a job whose handler also gets the app (for state and events).
+    /// A job not enabled by default waits for `update_job_schedule`.
+    ///
+    /// ## Panics
+    /// If called after the scheduler has been cloned (i.e. started).
+    pub fn app_job<F>(
+        mut self,
+        name: &'static str,
+        default_schedule: &'static str,
+        enabled_by_default: bool,
+        handler: F,
+    ) -> Self
+    where
+        F: Fn(AppHandle, Database) -> JobFuture + Send + Sync + 'static,
+    {
+        debug_assert!(default_schedule.parse::<CronSchedule>().is_ok());
+        Arc::get_mut(&mut self.jobs)
+            .expect("jobs are added before the scheduler starts")
+            .insert(
+                name,
+                JobSpec {
+                    default_schedule,
+                    enabled_by_default,
+                    handler: Arc::new(handler),
+                },
+            );
+        self
+    }
+
+    /// Whether a job with this name is registered.
+    pub fn has_job(&self, name: &str) -> bool {
+        self.jobs.contains_key(name)
+    }
+
+    /// Registers the jobs and starts the scheduling loop.
+    pub fn start(&self, app_handle: AppHandle, db: Database) {
+        let scheduler = self.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = scheduler.prepare(&db).await {
+                error!(?e, "Failed to prepare job scheduler");
+                return;
+            }
+            info!(jobs = scheduler.jobs.len(), "Job scheduler started");
+
+            loop {
+                scheduler.tick(&app_handle, &db).await;
+                tokio::time::sleep(TICK_INTERVAL).await;
+            }
+        });
+    }
+
+    /// Runs a job now, outside its schedule.
+    ///
+    /// Returns `false` if the job is already running.
+    pub fn run_now(&self, app_handle: AppHandle, db: Database, name: &str) -> bool {
+        self.spawn_run(app_handle, db, name)
+    }
+
+    /// Registers jobs, closes interrupted runs and fills in missing
+    /// next-run times.
+    async fn prepare(&self, db: &Database) -> Result<(), DbError> {
+        let jobs = db.jobs();
+        for (name, spec) in self.jobs.iter() {
+            jobs.register(name, spec.default_schedule, spec.enabled_by_default)
+                .await?;
+        }
+
+        let interrupted = jobs.fail_interrupted_runs().await?;
+        if interrupted > 0 {
+            warn!(interrupted, "Closed job runs interrupted by the last exit");
+        }
+
+        let now = Utc::now();
+        for job in jobs.list().await? {
+            if job.enabled && job.next_run_at.is_none() {
+                jobs.set_next_run(&job.name, next_run(&job.schedule, now))
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Starts every due job that is not already running.
+    async fn tick(&self, app_handle: &AppHandle, db: &Database) {
+        let due = match db.jobs().due(Utc::now()).await {
+            Ok(due) => due,
+            Err(e) => {
+                error!(?e, "Failed to load due jobs");
+                return;
+            }
+        };
+        for job in due {
+            if self.has_job(&job.name) {
+                self.spawn_run(app_handle.clone(), db.clone(), &job.name);
+            } else {
+                debug!(job = %job.name, "Skipping job with no handler in this build");
+            }
+        }
+    }
+
+    fn spawn_run(&self, app_handle: AppHandle, db: Database, name: &str) -> bool {
+        let Some(spec) = self.jobs.get(name) else {
+            return false;
+        };
+        {
+            let Ok(mut running) = self.running.lock() else {
+                return false;
+            };
+            if !running.insert(name.to_string()) {
+                return false;
+            }
+        }
+
+        let handler = spec.handler.clone();
+        let running = self.running.clone();
+        let name = name.to_string();
+        tauri::async_runtime::spawn(async move {
+            run_job(&app_handle, &db, &name, handler).await;
+            if let Ok(mut running) = running.lock() {
+                running.remove(&name);
+            }
+        });
+        true
+    }
+}
+
+impl Default for SchedulerState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Runs one job and records the outcome.
+async fn run_job(app_handle: &AppHandle, db: &Database, name: &str, handler: JobHandler) {
+    let jobs = db.jobs();
+    let started_at = Utc::now();
+    let run_id = match jobs.start_run(name, started_at).await {
+        Ok(id) => id,
+        Err(e) => {
+            error!(job = %name, ?e, "Failed to record job start");
+            return;
+        }
+    };
+
+    info!(job = %name, run_id, "Job started");
+    let result = handler(app_handle.clone(), db.clone()).await;
+    let error = result.err();
+
+    // Read the schedule again: it may have been edited during the run
+    let next_run_at = match jobs.get(name).await {
+        Ok(Some(job)) if job.enabled => next_run(&job.schedule, Utc::now()),
+        _ => None,
+    };
+
+    let failures = match jobs
+        .finish_run(run_id, name, started_at, error.as_deref(), next_run_at)
+        .await
+    {
+        Ok(failures) => failures,
+        Err(e) => {
+            error!(job = %name, ?e, "Failed to record job result");
+            return;
+        }
+    };
+
+    match &error {
+        None => info!(job = %name, run_id, "Job succeeded"),
+        Some(message) => {
+            warn!(job = %name, run_id, error = %message, failures, "Job failed");
+            if raises_alert(failures) {
+                error!(job = %name, failures, "Job keeps failing");
+                let event = JobAlertEvent {
+                    job_name: name.to_string(),
+                    consecutive_failures: failures,
+                    error: message.clone(),
+                };
+                if let Err(e) = app_handle.emit("jobs:alert", &event) {
+                    error!(?e, "Failed to emit jobs:alert event");
+                }
+            }
+        }
+    }
+}
+
+/// The terminal's current UTC offset.
+pub fn local_offset() -> FixedOffset {
+    Local::now().offset().fix()
+}
+
+/// Next run of `schedule` after `after`, or `None` if it never fires or
+/// does not parse.
+pub fn next_run(schedule: &str, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    match schedule.parse::<CronSchedule>() {
+        Ok(schedule) => schedule.next_after(after, local_offset()),
+        Err(e) => {
+            warn!(schedule, %e, "Invalid job schedule");
+            None
+        }
+    }
+}
+
+// =============================================================================
+// Events
+// =============================================================================
+
+/// Payload of the `jobs:alert` event.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct JobAlertEvent {
+    pub job_name: String,
+    #[ts(type = "number")]
+    pub consecutive_failures: i64,
+    /// Error of the latest run
+    pub error: String,
+}
+
+// =============================================================================
+// Built-in Jobs
+// =============================================================================
+
+/// Deletes outbox entries synced more than `OUTBOX_RETENTION_DAYS` ago.
+fn outbox_cleanup(db: Database) -> JobFuture {
+    Box::pin(async move {
+        let deleted = db
+            .sync_outbox()
+            .cleanup_old_entries(OUTBOX_RETENTION_DAYS)
+            .await
+            .map_err(|e| e.to_string())?;
+        debug!(deleted, "Cleaned up synced outbox entries");
+        Ok(())
+    })
+}
+
+/// Deletes job runs older than `JOB_RUN_RETENTION_DAYS`.
+fn job_history_prune(db: Database) -> JobFuture {
+    Box::pin(async move {
+        let deleted = db
+            .jobs()
+            .prune_runs(JOB_RUN_RETENTION_DAYS)
+            .await
+            .map_err(|e| e.to_string())?;
+        debug!(deleted, "Pruned job run history");
+        Ok(())
+    })
+}
+
+/// Prunes finished sales older than the retention period (see
+/// `titan_core::retention`), in batches.
+pub fn sale_retention_job(
+    policy: RetentionPolicy,
+) -> impl Fn(Database) -> JobFuture + Send + Sync + 'static {
+    move |db| {
+        Box::pin(async move {
+            let Some(cutoff) = policy.sale_cutoff(Utc::now()) else {
+                return Ok(());
+            };
+            let mut pruned = 0;
+            loop {
+                let batch = db
+                    .sales()
+                    .prune_before(cutoff, SALE_PRUNE_BATCH)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                pruned += batch;
+                if batch < u64::from(SALE_PRUNE_BATCH) {
+                    break;
+                }
+            }
+            if pruned > 0 {
+                info!(pruned, cutoff = %cutoff, "Pruned sales past the retention period");
+            }
+            Ok(())
+        })
+    }
+}
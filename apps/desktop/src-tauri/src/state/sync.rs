@@ -19,7 +19,7 @@
 //! │  │  ┌─────────────────┐  ┌─────────────────────────────────────┐  │   │
 //! │  │  │  SyncAgent      │  │  SyncStatus                        │  │   │
 //! │  │  │  (Background    │  │                                     │  │   │
-//! │  │  │   Task)         │  │  • connection_state (Connected/...)│  │   │
+//! │  │  │   Task)         │  │  • connection_state (Ready/...)    │  │   │
 //! │  │  │                 │  │  • last_sync                       │  │   │
 //! │  │  │  - WebSocket    │  │  • pending_count                   │  │   │
 //! │  │  │  - Outbox       │  │  • mode (Auto/Primary/...)         │  │   │
@@ -55,10 +55,12 @@
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, RwLock};
 use tauri::{AppHandle, Emitter};
+use titan_core::ErrorCode;
 use titan_sync::{
     ConnectionState, SyncAgentHandle, SyncConfig, SyncEventEmitter, SyncMode, SyncStatus,
 };
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
+use ts_rs::TS;
 
 /// Sync state managed by Tauri.
 ///
@@ -86,6 +88,19 @@ impl SyncState {
         }
     }
 
+    /// Creates a SyncState with the configuration from sync.toml and the
+    /// TITAN_* environment (see `SyncConfig::load`), so the store's
+    /// identity and keys are known before the agent starts. A configuration
+    /// that fails to load is logged and left unset.
+    pub fn load() -> Self {
+        let state = Self::new();
+        match SyncConfig::load(None) {
+            Ok(config) => state.set_config(config),
+            Err(e) => warn!(error = %e, "Sync configuration not loaded"),
+        }
+        state
+    }
+
     /// Gets the current sync status.
     pub fn get_status(&self) -> SyncStatusDto {
         self.status
@@ -109,6 +124,14 @@ impl SyncState {
             .unwrap_or(false)
     }
 
+    /// Gets a handle to the running sync agent, if any.
+    ///
+    /// Used for requests that must be answered by the hub (store credit
+    /// redemption, full resync) and for pausing sync.
+    pub fn agent_handle(&self) -> Option<SyncAgentHandle> {
+        self.agent_handle.read().ok().and_then(|h| h.clone())
+    }
+
     /// Gets the current sync configuration.
     pub fn get_config(&self) -> Option<SyncConfig> {
         self.config
@@ -117,6 +140,15 @@ impl SyncState {
             .and_then(|c| c.clone())
     }
 
+    /// URL of the hub: the one the agent is connected to, else the
+    /// configured one.
+    pub fn hub_url(&self) -> Option<String> {
+        self.get_status().hub_url.or_else(|| {
+            self.get_config()
+                .and_then(|c| c.hub_url().map(str::to_string))
+        })
+    }
+
     /// Sets the sync agent handle (called when agent starts).
     pub fn set_agent_handle(&self, handle: SyncAgentHandle) {
         if let Ok(mut h) = self.agent_handle.write() {
@@ -131,6 +163,26 @@ impl SyncState {
         }
     }
 
+    /// Turns metered mode on or off, for the running agent and for the
+    /// stored configuration used on the next start.
+    pub async fn set_metered(&self, metered: bool) {
+        if let Ok(mut c) = self.config.write() {
+            if let Some(config) = c.as_mut() {
+                config.sync.metered = metered;
+            }
+        }
+
+        match self.agent_handle() {
+            Some(handle) => self.update_status(handle.set_metered(metered).await.into()),
+            None => {
+                if let Ok(mut s) = self.status.write() {
+                    s.metered = metered;
+                }
+            }
+        }
+        info!(metered, "Metered sync mode changed");
+    }
+
     /// Stops the sync agent.
     pub async fn stop_agent(&self) {
         let handle = {
@@ -155,7 +207,8 @@ impl Default for SyncState {
 }
 
 /// DTO for sync status that can be serialized to the frontend.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
 #[serde(rename_all = "camelCase")]
 pub struct SyncStatusDto {
     /// Current connection state
@@ -168,6 +221,7 @@ pub struct SyncStatusDto {
     pub last_sync_at: Option<String>,
 
     /// Number of pending outbox entries
+    #[ts(type = "number")]
     pub pending_outbox_count: i64,
 
     /// Whether sync is healthy (connected and no errors)
@@ -176,20 +230,45 @@ pub struct SyncStatusDto {
     /// Last error message if any
     pub error_message: Option<String>,
 
+    /// Machine-readable code of the last error
+    pub error_code: Option<ErrorCode>,
+
     /// Hub URL if connected
     pub hub_url: Option<String>,
+
+    /// Last ping round trip to the hub in milliseconds
+    #[ts(type = "number | null")]
+    pub latency_ms: Option<u64>,
+
+    /// Whether uploads are throttled for a metered link
+    pub metered: bool,
+
+    /// Whether the outbox is uploading straight to the cloud (no hub)
+    pub cloud_fallback: bool,
+
+    /// Whether sync was paused by support staff
+    pub paused: bool,
+
+    /// Whether a full resync from the hub is in progress
+    pub resyncing: bool,
 }
 
 impl Default for SyncStatusDto {
     fn default() -> Self {
         Self {
-            connection_state: "disconnected".to_string(),
+            connection_state: "idle".to_string(),
             sync_mode: "offline".to_string(),
             last_sync_at: None,
             pending_outbox_count: 0,
             is_healthy: false,
             error_message: None,
+            error_code: None,
             hub_url: None,
+            latency_ms: None,
+            metered: false,
+            cloud_fallback: false,
+            paused: false,
+            resyncing: false,
         }
     }
 }
@@ -197,11 +276,12 @@ impl Default for SyncStatusDto {
 impl From<SyncStatus> for SyncStatusDto {
     fn from(status: SyncStatus) -> Self {
         let connection_state = match status.connection_state {
-            ConnectionState::Disconnected => "disconnected",
+            ConnectionState::Idle => "idle",
             ConnectionState::Connecting => "connecting",
-            ConnectionState::Connected => "connected",
-            ConnectionState::Backoff { .. } => "backoff",
-            ConnectionState::Reconnecting { .. } => "reconnecting",
+            ConnectionState::Handshaking => "handshaking",
+            ConnectionState::Ready => "ready",
+            ConnectionState::Draining => "draining",
+            ConnectionState::Backoff => "backoff",
         };
 
         let sync_mode = match status.mode {
@@ -209,6 +289,7 @@ impl From<SyncStatus> for SyncStatusDto {
             SyncMode::Primary => "primary",
             SyncMode::Secondary => "secondary",
             SyncMode::Offline => "offline",
+            SyncMode::Peer => "peer",
         };
 
         Self {
@@ -218,11 +299,47 @@ impl From<SyncStatus> for SyncStatusDto {
             pending_outbox_count: status.pending_count,
             is_healthy: status.is_connected,
             error_message: status.last_error,
+            error_code: status.last_error_code,
             hub_url: status.hub_url,
+            latency_ms: status.latency_ms,
+            metered: status.metered,
+            cloud_fallback: status.cloud_fallback,
+            paused: status.paused,
+            resyncing: status.resyncing,
         }
     }
 }
 
+// =============================================================================
+// Events
+// =============================================================================
+
+/// Payload of the `sync:progress` event.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct SyncProgressEvent {
+    #[ts(type = "number")]
+    pub pending: i64,
+    #[ts(type = "number")]
+    pub synced: i64,
+}
+
+/// Payload of the `sync:error` event.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct SyncErrorEvent {
+    pub code: ErrorCode,
+    pub message: String,
+    pub retryable: bool,
+}
+
+/// Payload of the `sync:upgrade-required` event.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct SyncUpgradeRequiredEvent {
+    pub message: String,
+}
+
 /// Tauri-based sync event emitter.
 ///
 /// Implements the SyncEventEmitter trait from titan-sync to emit events
@@ -261,27 +378,18 @@ impl SyncEventEmitter for TauriSyncEventEmitter {
     }
 
     fn emit_progress(&self, pending: i64, synced: i64) {
-        #[derive(Serialize, Clone)]
-        struct ProgressEvent {
-            pending: i64,
-            synced: i64,
-        }
-
-        if let Err(e) = self.app_handle.emit("sync:progress", ProgressEvent { pending, synced }) {
+        let event = SyncProgressEvent { pending, synced };
+        if let Err(e) = self.app_handle.emit("sync:progress", &event) {
             error!(?e, "Failed to emit sync:progress event");
         }
 
         debug!(pending, synced, "Emitted sync:progress");
     }
 
-    fn emit_error(&self, message: &str, retryable: bool) {
-        #[derive(Serialize, Clone)]
-        struct ErrorEvent {
-            message: String,
-            retryable: bool,
-        }
-
-        let event = ErrorEvent {
+    fn emit_error(&self, code: ErrorCode, message: &str) {
+        let retryable = code.is_retryable();
+        let event = SyncErrorEvent {
+            code,
             message: message.to_string(),
             retryable,
         };
@@ -290,6 +398,18 @@ impl SyncEventEmitter for TauriSyncEventEmitter {
             error!(?e, "Failed to emit sync:error event");
         }
 
-        error!(message, retryable, "Emitted sync:error");
+        error!(%code, message, retryable, "Emitted sync:error");
+    }
+
+    fn emit_upgrade_required(&self, message: &str) {
+        let event = SyncUpgradeRequiredEvent {
+            message: message.to_string(),
+        };
+
+        if let Err(e) = self.app_handle.emit("sync:upgrade-required", &event) {
+            error!(?e, "Failed to emit sync:upgrade-required event");
+        }
+
+        error!(message, "Emitted sync:upgrade-required");
     }
 }
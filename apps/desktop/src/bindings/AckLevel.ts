@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How far an outbox entry has got on its way to the cloud.
+ */
+export type AckLevel = "pending" | "hub_acked" | "cloud_acked";
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AgeVerificationMethod } from "./AgeVerificationMethod";
+
+/**
+ * Age check recorded against a sale.
+ */
+export type AgeVerification = { sale_id: string, 
+/**
+ * Highest minimum age among the sale's items when verified.
+ */
+required_age: number, method: AgeVerificationMethod, birthdate: string | null, verified_by: string, device_id: string, verified_at: string, };
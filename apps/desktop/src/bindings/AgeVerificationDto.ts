@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Age check as returned to the frontend.
+ */
+export type AgeVerificationDto = { saleId: string, requiredAge: number, method: string, birthdate: string | null, verifiedBy: string, verifiedAt: string, };
@@ -0,0 +1,41 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ErrorCode } from "./ErrorCode";
+
+/**
+ * API error returned from Tauri commands.
+ *
+ * ## Serialization
+ * This is what the frontend receives when a command fails:
+ * ```json
+ * {
+ *   "code": "NOT_FOUND",
+ *   "message": "Product not found: SKU-123",
+ *   "retryable": false,
+ *   "correlationId": "5f0c…",
+ *   "trainingMode": false
+ * }
+ * ```
+ */
+export type ApiError = { 
+/**
+ * Machine-readable error code for programmatic handling
+ */
+code: ErrorCode, 
+/**
+ * Human-readable error message for display
+ */
+message: string, 
+/**
+ * Whether retrying the same command later may succeed
+ */
+retryable: boolean, 
+/**
+ * Correlation ID of the failed command, for finding it in the logs
+ * (set by [`crate::middleware::traced`])
+ */
+correlationId: string | null, 
+/**
+ * Whether the command ran against the training sandbox (set by
+ * [`crate::middleware::traced`])
+ */
+trainingMode: boolean, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Selects products by one attribute: having the key (`organic`) or
+ * having it with a given value (`color=red`, case-insensitive).
+ */
+export type AttributeFilter = { key: string, 
+/**
+ * `None`: any value, tags included.
+ */
+value: string | null, };
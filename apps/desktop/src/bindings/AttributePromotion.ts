@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AttributeFilter } from "./AttributeFilter";
+
+/**
+ * A percentage discount on every line whose product passes `filter`,
+ * e.g. 10% off `vintage=2019`.
+ */
+export type AttributePromotion = { 
+/**
+ * Shown on the receipt next to the discount.
+ */
+name: string, filter: AttributeFilter, 
+/**
+ * Discount in basis points (1000 = 10%).
+ */
+discount_bps: number, };
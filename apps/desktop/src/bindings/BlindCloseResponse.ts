@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of a blind close. Deliberately omits expected cash and variance.
+ */
+export type BlindCloseResponse = { sessionId: string, countedCashCents: number, closedAt: string, };
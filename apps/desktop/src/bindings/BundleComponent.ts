@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One line of a bill of materials: `quantity` of `product_id` per bundle.
+ */
+export type BundleComponent = { product_id: string, quantity: bigint, };
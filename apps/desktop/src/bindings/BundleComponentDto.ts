@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One component of a bundle, with what the picker shows.
+ */
+export type BundleComponentDto = { productId: string, sku: string, name: string, 
+/**
+ * Units of the component in one bundle.
+ */
+quantity: number, trackInventory: boolean, currentStock: number | null, };
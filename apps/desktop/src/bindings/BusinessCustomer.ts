@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A business buyer that receives e-invoices for its purchases.
+ */
+export type BusinessCustomer = { id: string, tenant_id: string, 
+/**
+ * Registered company name.
+ */
+name: string, 
+/**
+ * VAT / tax registration number, normalized (see [`normalize_tax_id`]).
+ */
+tax_id: string, address_line: string | null, city: string | null, postal_code: string | null, 
+/**
+ * ISO 3166-1 alpha-2 country code.
+ */
+country_code: string, 
+/**
+ * Where invoices are emailed.
+ */
+email: string | null, created_at: string, updated_at: string, 
+/**
+ * When the customer was deleted (see [`crate::tombstone`]).
+ */
+deleted_at: string | null, 
+/**
+ * Bumped on every change, for sync.
+ */
+sync_version: bigint, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A business customer as shown in the UI.
+ */
+export type BusinessCustomerDto = { id: string, name: string, taxId: string, addressLine: string | null, city: string | null, postalCode: string | null, countryCode: string, email: string | null, 
+/**
+ * Pass back as `expectedVersion` when saving an edit.
+ */
+version: number, };
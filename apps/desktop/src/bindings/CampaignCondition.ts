@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Which baskets a campaign prints for. Every condition set must hold.
+ */
+export type CampaignCondition = { 
+/**
+ * Sale total at least this much.
+ */
+min_total_cents: number | null, 
+/**
+ * At least this many units across the lines.
+ */
+min_units: number | null, 
+/**
+ * The basket holds at least one of these SKUs (empty: any basket).
+ */
+any_sku: Array<string>, };
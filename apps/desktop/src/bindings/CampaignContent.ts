@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What a campaign prints.
+ */
+export type CampaignContent = { "type": "survey", message: string, url: string, } | { "type": "coupon", message: string, 
+/**
+ * Start of every code, e.g. "NEXT" (upper case letters and digits).
+ */
+code_prefix: string, 
+/**
+ * Days after the sale the coupon can be used.
+ */
+valid_days: number, } | { "type": "message", message: string, };
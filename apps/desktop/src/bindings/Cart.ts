@@ -0,0 +1,31 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CartItem } from "./CartItem";
+import type { Coupon } from "./Coupon";
+
+/**
+ * The shopping cart.
+ *
+ * ## Invariants
+ * - Items are unique by `product_id` (adding same product increases quantity)
+ * - Quantity must be > 0 (removing sets qty to 0 removes the item)
+ * - Maximum items: 100 (configured in titan-core)
+ * - Maximum quantity per item: 999 (configured in titan-core)
+ */
+export type Cart = { 
+/**
+ * Items in the cart
+ */
+items: Array<CartItem>, 
+/**
+ * When the cart was created/last cleared
+ */
+createdAt: string, 
+/**
+ * Coupon applied with `apply_coupon`, redeemed when the sale is created
+ */
+coupon: Coupon | null, 
+/**
+ * Loyalty member set with `set_loyalty_member`, who earns points on
+ * the sale
+ */
+loyaltyMember: string | null, };
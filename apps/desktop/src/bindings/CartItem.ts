@@ -0,0 +1,69 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ItemTracking } from "./ItemTracking";
+
+/**
+ * An item in the shopping cart.
+ *
+ * ## Design Notes
+ * - `product_id`: Reference to the product (for database lookup)
+ * - `product_snapshot`: Frozen copy of product data at time of adding
+ *   This ensures the cart displays consistent data even if the product
+ *   is updated in the database after being added to cart.
+ */
+export type CartItem = { 
+/**
+ * Product ID (UUID)
+ */
+productId: string, 
+/**
+ * SKU at time of adding (frozen)
+ */
+sku: string, 
+/**
+ * Product name at time of adding (frozen)
+ */
+name: string, 
+/**
+ * Price in cents at time of adding (frozen)
+ * This is critical: we lock in the price when added to cart
+ */
+unitPriceCents: number, 
+/**
+ * Tenant base price when `unit_price_cents` is a store override
+ * (frozen, for reporting)
+ */
+basePriceCents: number | null, 
+/**
+ * Tax rate in basis points at time of adding (frozen)
+ */
+taxRateBps: number, 
+/**
+ * Quantity in cart
+ */
+quantity: number, 
+/**
+ * When this item was added to cart
+ */
+addedAt: string, 
+/**
+ * Serial/lot capture required for this product
+ */
+itemTracking: ItemTracking, 
+/**
+ * Captured serial numbers (one per unit) or lot number (one per line)
+ */
+trackingCodes: Array<string>, 
+/**
+ * Minimum customer age for this product (None = unrestricted)
+ */
+minPurchaseAge: number | null, 
+/**
+ * Price the line was added at, when the cashier has overridden
+ * `unit_price_cents` (None = not overridden)
+ */
+listPriceCents: number | null, 
+/**
+ * Department key the line was sold on (None = a product with a SKU);
+ * `product_id` is then a cart-only line key
+ */
+departmentCode: string | null, };
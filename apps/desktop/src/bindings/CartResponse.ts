@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CartItem } from "./CartItem";
+import type { CartTotals } from "./CartTotals";
+
+/**
+ * Cart response including items and totals.
+ */
+export type CartResponse = { items: Array<CartItem>, totals: CartTotals, 
+/**
+ * Set when the cart holds age-restricted products.
+ */
+requiredAge: number | null, 
+/**
+ * Whether this is a training sale (see `set_training_mode`).
+ */
+trainingMode: boolean, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Cart totals summary for API responses.
+ */
+export type CartTotals = { itemCount: number, totalQuantity: number, subtotalCents: number, taxCents: number, 
+/**
+ * Coupon discount; 0 while the cart does not qualify for the coupon
+ */
+discountCents: number, 
+/**
+ * Code of the applied coupon, if any
+ */
+couponCode: string | null, totalCents: number, 
+/**
+ * Member number of the loyalty member on the cart, if any
+ */
+loyaltyMember: string | null, };
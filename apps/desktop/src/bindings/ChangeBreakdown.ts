@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DenominationCount } from "./DenominationCount";
+
+/**
+ * Result of breaking a change amount into notes and coins.
+ */
+export type ChangeBreakdown = { 
+/**
+ * Pieces to hand back, largest first (zero quantities omitted).
+ */
+pieces: Array<DenominationCount>, 
+/**
+ * Value covered by `pieces`.
+ */
+total_cents: bigint, 
+/**
+ * Amount that cannot be paid with the available denominations.
+ */
+remainder_cents: bigint, };
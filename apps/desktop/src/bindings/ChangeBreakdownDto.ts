@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DenominationCountInput } from "./DenominationCountInput";
+
+/**
+ * Notes and coins to hand back for a change amount.
+ */
+export type ChangeBreakdownDto = { currencyCode: string, pieces: Array<DenominationCountInput>, pieceCount: number, totalCents: number, 
+/**
+ * Part of the change that no note/coin can cover (e.g. sub-rupee amounts).
+ */
+remainderCents: number, };
@@ -0,0 +1,116 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AppProfile } from "./AppProfile";
+import type { CurrencyDenominations } from "./CurrencyDenominations";
+import type { DepartmentConfig } from "./DepartmentConfig";
+import type { LayawayPolicy } from "./LayawayPolicy";
+import type { LoyaltyProgram } from "./LoyaltyProgram";
+import type { MarginGuardrail } from "./MarginGuardrail";
+import type { PrinterConfig } from "./PrinterConfig";
+import type { RetentionPolicy } from "./RetentionPolicy";
+import type { StationConfig } from "./StationConfig";
+import type { TaxMode } from "./TaxMode";
+import type { UpdateChannel } from "./UpdateChannel";
+
+/**
+ * Application configuration.
+ *
+ * ## Fields
+ * Most fields have sensible defaults for development.
+ * Production deployments should configure these properly.
+ */
+export type ConfigState = { 
+/**
+ * Tenant ID for multi-tenant support.
+ * Default: "default" (single-tenant mode)
+ */
+tenantId: string, 
+/**
+ * Store name (displayed on receipts)
+ */
+storeName: string, 
+/**
+ * Store address lines (for receipts)
+ */
+storeAddress: Array<string>, 
+/**
+ * Store's VAT / tax registration number (seller on e-invoices)
+ */
+storeTaxId: string | null, 
+/**
+ * Store country (ISO 3166-1 alpha-2)
+ */
+countryCode: string, 
+/**
+ * Currency code (ISO 4217)
+ */
+currencyCode: string, 
+/**
+ * Currency symbol (for display)
+ */
+currencySymbol: string, 
+/**
+ * Number of decimal places for currency
+ */
+currencyDecimals: number, 
+/**
+ * Notes and coins that replace a currency's built-in set
+ */
+customDenominations: Array<CurrencyDenominations>, 
+/**
+ * Default tax rate in basis points
+ * e.g., 825 = 8.25%
+ */
+defaultTaxRateBps: number, 
+/**
+ * Tax calculation mode
+ */
+taxMode: TaxMode, 
+/**
+ * Enable sound effects
+ */
+soundEnabled: boolean, 
+/**
+ * Receipt printer configuration
+ */
+receiptPrinter: PrinterConfig | null, 
+/**
+ * Layaway deposit and restocking fee terms
+ */
+layaway: LayawayPolicy, 
+/**
+ * Margin floor for manual product and line prices
+ */
+marginGuardrail: MarginGuardrail, 
+/**
+ * Department keys for items without a SKU, and their tax groups
+ */
+departments: DepartmentConfig, 
+/**
+ * Release channel app updates come from
+ */
+updateChannel: UpdateChannel, 
+/**
+ * Environment profile (`TITAN_ENV`)
+ */
+profile: AppProfile, 
+/**
+ * Cloud API URL (the profile's, unless `TITAN_CLOUD_URL` is set)
+ */
+cloudUrl: string, 
+/**
+ * Register identity of this terminal (stamped on sales and receipts)
+ */
+station: StationConfig, 
+/**
+ * How long finished sales are kept on this terminal
+ */
+retention: RetentionPolicy, 
+/**
+ * Refunds above this amount need a manager override token
+ * (None: no limit)
+ */
+refundApprovalCents: number | null, 
+/**
+ * Loyalty tiers and rewards
+ */
+loyalty: LoyaltyProgram, };
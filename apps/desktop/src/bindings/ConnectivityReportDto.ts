@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HopResultDto } from "./HopResultDto";
+
+/**
+ * Response DTO for the connectivity diagnostic.
+ */
+export type ConnectivityReportDto = { 
+/**
+ * Cloud API URL that was checked
+ */
+cloudUrl: string, 
+/**
+ * Proxy used (without credentials)
+ */
+proxy: string | null, 
+/**
+ * Whether every hop succeeded
+ */
+ok: boolean, 
+/**
+ * The hop where the path broke, if any
+ */
+failedHop: string | null, 
+/**
+ * Hops attempted, in order
+ */
+hops: Array<HopResultDto>, };
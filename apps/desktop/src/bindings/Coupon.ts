@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CouponKind } from "./CouponKind";
+
+/**
+ * A coupon definition.
+ */
+export type Coupon = { id: string, tenant_id: string, 
+/**
+ * Code the cashier scans or types, normalized with [`normalize_code`].
+ */
+code: string, description: string | null, kind: CouponKind, 
+/**
+ * Basis points for `Percent`, cents for `Amount`.
+ */
+value: number, 
+/**
+ * Smallest cart subtotal (before tax) the coupon applies to.
+ */
+min_subtotal_cents: number | null, 
+/**
+ * Products the discount applies to; empty for the whole cart.
+ */
+product_ids: Array<string>, starts_at: string | null, expires_at: string | null, 
+/**
+ * Total redemptions allowed across all stores: 1 for single-use,
+ * `None` for unlimited.
+ */
+max_uses: number | null, active: boolean, created_at: string, updated_at: string, sync_version: bigint, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One use of a coupon on a sale.
+ */
+export type CouponRedemption = { id: string, coupon_id: string, sale_id: string, 
+/**
+ * Store the sale was rung up in; empty until the hub or cloud stamps it.
+ */
+store_id: string, device_id: string, discount_cents: bigint, redeemed_at: string, };
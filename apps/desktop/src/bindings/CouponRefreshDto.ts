@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of a coupon refresh.
+ */
+export type CouponRefreshDto = { 
+/**
+ * Coupons the cloud sent.
+ */
+downloaded: number, 
+/**
+ * Coupons that changed locally.
+ */
+changed: number, };
@@ -1,3 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type PaymentMethod = "cash" | "external_card";
+/**
+ * What crashed.
+ */
+export type CrashKind = "panic" | "frontend";
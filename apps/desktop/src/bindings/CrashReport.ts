@@ -0,0 +1,31 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CrashKind } from "./CrashKind";
+
+/**
+ * One crash, as written to disk and uploaded.
+ */
+export type CrashReport = { id: string, kind: CrashKind, occurred_at: string, 
+/**
+ * Panic message or error text.
+ */
+message: string, 
+/**
+ * `file:line:column` of the panic, or the frontend source location.
+ */
+location: string | null, 
+/**
+ * Name of the thread that panicked.
+ */
+thread: string | null, 
+/**
+ * Rust backtrace or JavaScript stack (empty when unavailable).
+ */
+backtrace: string, 
+/**
+ * Log lines leading up to the crash, oldest first.
+ */
+log_tail: Array<string>, app_version: string, 
+/**
+ * Newest database migration this build applies.
+ */
+db_schema_version: number | null, os: string, arch: string, };
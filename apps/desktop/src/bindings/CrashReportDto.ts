@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CrashKind } from "./CrashKind";
+
+/**
+ * A crash report as listed in the UI.
+ */
+export type CrashReportDto = { id: string, fileName: string, kind: CrashKind, occurredAt: string, message: string, location: string | null, appVersion: string, 
+/**
+ * The report has been uploaded through the cloud.
+ */
+uploaded: boolean, };
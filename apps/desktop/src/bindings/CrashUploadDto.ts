@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of uploading the queued reports.
+ */
+export type CrashUploadDto = { uploaded: number, 
+/**
+ * Reports still queued (their upload failed).
+ */
+pending: number, };
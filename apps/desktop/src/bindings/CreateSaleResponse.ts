@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CreateSaleResponse = { saleId: string, totalCents: number, itemCount: number, 
+/**
+ * Whether this is a training sale (see `set_training_mode`).
+ */
+trainingMode: boolean, };
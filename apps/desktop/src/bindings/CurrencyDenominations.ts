@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Denomination } from "./Denomination";
+
+/**
+ * The configured notes and coins for one currency.
+ *
+ * Denominations are kept sorted largest first.
+ */
+export type CurrencyDenominations = { 
+/**
+ * ISO 4217 currency code (e.g., "USD").
+ */
+currency_code: string, denominations: Array<Denomination>, };
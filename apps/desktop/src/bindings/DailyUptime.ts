@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Connection figures for one local calendar day.
+ */
+export type DailyUptime = { day: string, 
+/**
+ * Time the agent was running.
+ */
+observed_ms: bigint, 
+/**
+ * Time the terminal was connected to its hub.
+ */
+connected_ms: bigint, 
+/**
+ * Connections lost during the day.
+ */
+disconnects: number, 
+/**
+ * `connected_ms / observed_ms` in basis points (10000 = 100%).
+ */
+uptime_bps: number, };
@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProductMargin } from "./ProductMargin";
+
+/**
+ * Dashboard figures for a date range.
+ */
+export type DashboardStatsDto = { from: string, to: string, 
+/**
+ * Sales (net of line discounts, before tax) of lines with a cost.
+ */
+salesCents: number, 
+/**
+ * Cost of those lines, at the cost when each was sold.
+ */
+costCents: number, marginCents: number, 
+/**
+ * Margin in basis points of sales (`null` without costed sales).
+ */
+marginBps: number | null, 
+/**
+ * Sales of lines sold without a cost, left out of the margin.
+ */
+uncostedSalesCents: number, 
+/**
+ * Lowest margin first.
+ */
+products: Array<ProductMargin>, };
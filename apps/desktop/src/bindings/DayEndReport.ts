@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DayEndStepResult } from "./DayEndStepResult";
+import type { ZReport } from "./ZReport";
+
+/**
+ * Outcome of a day-end run.
+ */
+export type DayEndReport = { device_id: string, station_number: number | null, business_date: string, started_at: string, finished_at: string, 
+/**
+ * The Z report, if the close succeeded.
+ */
+z_report: ZReport | null, steps: Array<DayEndStepResult>, };
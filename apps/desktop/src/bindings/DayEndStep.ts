@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A step of the day-end job.
+ */
+export type DayEndStep = "open_tills" | "z_close" | "backup" | "sync_flush";
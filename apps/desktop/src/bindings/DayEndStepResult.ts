@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DayEndStep } from "./DayEndStep";
+import type { StepOutcome } from "./StepOutcome";
+
+/**
+ * Result of one step.
+ */
+export type DayEndStepResult = { step: DayEndStep, outcome: StepOutcome, 
+/**
+ * What happened, for the manager.
+ */
+detail: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DenominationKind } from "./DenominationKind";
+
+/**
+ * A single note or coin of a currency.
+ */
+export type Denomination = { 
+/**
+ * Face value in the smallest currency unit.
+ */
+value_cents: bigint, kind: DenominationKind, };
@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Quantity of a single denomination counted in the drawer.
+ *
+ * ## Example
+ * ```text
+ * $20 bills × 4  → DenominationCount { denomination_cents: 2000, quantity: 4 }
+ * 25¢ coins × 12 → DenominationCount { denomination_cents: 25,   quantity: 12 }
+ * ```
+ */
+export type DenominationCount = { 
+/**
+ * Face value of the note/coin in cents.
+ */
+denomination_cents: bigint, 
+/**
+ * Number of notes/coins counted.
+ */
+quantity: bigint, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A single denomination line from the count screen.
+ */
+export type DenominationCountInput = { denominationCents: number, quantity: number, };
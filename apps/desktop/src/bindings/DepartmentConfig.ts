@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DepartmentKey } from "./DepartmentKey";
+import type { TaxGroup } from "./TaxGroup";
+
+/**
+ * Department keys and tax groups configured for the store.
+ */
+export type DepartmentConfig = { tax_groups: Array<TaxGroup>, departments: Array<DepartmentKey>, };
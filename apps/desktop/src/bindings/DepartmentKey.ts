@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A key that sells goods of one department at an entered price.
+ */
+export type DepartmentKey = { code: string, 
+/**
+ * Shown on the key, the receipt and the report.
+ */
+name: string, 
+/**
+ * Code of the department's tax group.
+ */
+tax_group: string, min_price_cents: number, max_price_cents: number, 
+/**
+ * First 7 digits of this department's price-embedded barcodes.
+ */
+barcode_prefix: string | null, 
+/**
+ * Price per kilogram, for goods weighed at the register (see
+ * `titan_core::scale`); None = not sold by weight.
+ */
+price_per_kg_cents: number | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A department sale ready for the cart.
+ */
+export type DepartmentLine = { department_code: string, name: string, tax_rate_bps: number, unit_price_cents: number, };
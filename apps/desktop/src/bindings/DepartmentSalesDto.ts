@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DepartmentSalesRow } from "./DepartmentSalesRow";
+
+/**
+ * Department sales over a date range, apart from SKU sales.
+ */
+export type DepartmentSalesDto = { from: string, to: string, 
+/**
+ * By department code.
+ */
+departments: Array<DepartmentSalesRow>, 
+/**
+ * Sales (net of line discounts, before tax) across every department.
+ */
+departmentSalesCents: number, 
+/**
+ * Sales of products with a SKU.
+ */
+skuSalesCents: number, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DepartmentSalesRow } from "./DepartmentSalesRow";
+
+/**
+ * Department sales over a period, apart from SKU sales.
+ */
+export type DepartmentSalesReport = { 
+/**
+ * By department code.
+ */
+departments: Array<DepartmentSalesRow>, 
+/**
+ * Sales of products with a SKU, net of line discounts.
+ */
+sku_sales_cents: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Sales of one department over a period.
+ */
+export type DepartmentSalesRow = { department_code: string, name: string, units: number, sales_cents: number, tax_cents: number, line_count: number, };
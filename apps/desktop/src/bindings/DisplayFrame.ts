@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What the display shows: two lines, each exactly [`DISPLAY_COLUMNS`]
+ * ASCII characters.
+ */
+export type DisplayFrame = { top: string, bottom: string, };
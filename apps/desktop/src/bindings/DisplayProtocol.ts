@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Command set a display understands.
+ */
+export type DisplayProtocol = "epson" | "cd5220";
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A verified bundle, ready to install.
+ */
+export type DownloadedUpdateDto = { version: string, 
+/**
+ * Where the bundle was saved.
+ */
+path: string, sizeBytes: number, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A manual drawer open, as kept in the audit trail.
+ */
+export type DrawerOpen = { id: string, station_number: number | null, 
+/**
+ * `DrawerConnection::kind` of the drawer.
+ */
+drawer: string, 
+/**
+ * Why staff opened it ("change for a customer").
+ */
+reason: string | null, 
+/**
+ * Whether the open command reached the drawer.
+ */
+succeeded: boolean, 
+/**
+ * What went wrong when it did not.
+ */
+error: string | null, user_id: string, device_id: string, created_at: string, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of a batch export.
+ */
+export type EInvoiceBatchDto = { dir: string, 
+/**
+ * File names written, in completion order.
+ */
+files: Array<string>, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A sale rendered as a UBL invoice.
+ */
+export type EInvoiceDto = { saleId: string, invoiceNumber: string, 
+/**
+ * Suggested file name, e.g. `260131-101500-0042.xml`.
+ */
+fileName: string, 
+/**
+ * Customer email, if known (the frontend opens the mail client).
+ */
+recipient: string | null, xml: string, };
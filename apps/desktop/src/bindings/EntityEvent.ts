@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProductChange } from "./ProductChange";
+
+/**
+ * A committed change to an entity.
+ */
+export type EntityEvent = { "type": "product_changed", product_id: string, change: ProductChange, 
+/**
+ * The product's `sync_version` after the change.
+ */
+version: number, } | { "type": "stock_changed", product_id: string, delta: number, 
+/**
+ * Stock after the move (`None` if not counted).
+ */
+stock: number | null, } | { "type": "cart_line_added", product_id: string, name: string, unit_price_cents: number, 
+/**
+ * Quantity added.
+ */
+quantity: number, 
+/**
+ * Cart total after the line, tax included.
+ */
+cart_total_cents: number, } | { "type": "sale_created", sale_id: string, total_cents: number, } | { "type": "sale_finalized", sale_id: string, total_cents: number, };
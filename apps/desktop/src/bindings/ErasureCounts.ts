@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Rows anonymized by one erasure, per kind.
+ */
+export type ErasureCounts = { business_customers: number, age_verifications: number, store_credit_accounts: number, layaways: number, quotes: number, };
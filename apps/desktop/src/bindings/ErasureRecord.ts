@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ErasureCounts } from "./ErasureCounts";
+import type { ErasureOrigin } from "./ErasureOrigin";
+import type { ErasureRequest } from "./ErasureRequest";
+
+/**
+ * An erasure log entry: the request and what it anonymized here.
+ */
+export type ErasureRecord = { request: ErasureRequest, origin: ErasureOrigin, counts: ErasureCounts, erased_at: string, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ErasureSubject } from "./ErasureSubject";
+
+/**
+ * A request to erase one customer's data (outbox `CUSTOMER_ERASURE`).
+ */
+export type ErasureRequest = { id: string, tenant_id: string, subject: ErasureSubject, 
+/**
+ * Why the data was erased (e.g. the customer's request reference).
+ */
+reason: string | null, 
+/**
+ * Cashier or back-office user who made the request.
+ */
+requested_by: string, requested_at: string, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Identifies the customer whose data is erased.
+ *
+ * Each identifier that is set is matched on its own: the business
+ * customer by ID, retail documents by phone or email.
+ */
+export type ErasureSubject = { business_customer_id: string | null, phone: string | null, email: string | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Stable, machine-readable error codes shared by every crate.
+ *
+ * Serialized as `SCREAMING_SNAKE_CASE` to the frontend (`ApiError.code`,
+ * `sync:error` events) and into sync `Error` messages, so the UI and the
+ * hub can react to a failure without parsing its message.
+ */
+export type ErrorCode = "NOT_FOUND" | "VALIDATION_ERROR" | "CONFLICT" | "DATABASE_ERROR" | "BUSINESS_LOGIC" | "INTERNAL" | "CART_ERROR" | "INSUFFICIENT_STOCK" | "PAYMENT_ERROR" | "AGE_VERIFICATION_REQUIRED" | "INSUFFICIENT_STORE_CREDIT" | "FISCAL_ERROR" | "CONFIG_ERROR" | "UNAVAILABLE" | "TIMEOUT" | "BUSY" | "SYNC_DEFERRED" | "SYNC_PAUSED" | "AUTH_FAILED" | "UPGRADE_REQUIRED" | "STORE_MISMATCH" | "INTEGRITY_FAILED" | "PROTOCOL_ERROR" | "CLOUD_ERROR" | "FEATURE_DISABLED" | "MANAGER_OVERRIDE_REQUIRED" | "NOT_READY" | "COUPON_REJECTED" | "INSUFFICIENT_REWARDS";
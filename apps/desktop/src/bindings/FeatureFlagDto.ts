@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One feature flag as shown in the UI.
+ */
+export type FeatureFlagDto = { key: string, 
+/**
+ * Name shown to staff.
+ */
+name: string, enabled: boolean, };
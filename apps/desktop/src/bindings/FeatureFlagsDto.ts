@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FeatureFlagDto } from "./FeatureFlagDto";
+
+/**
+ * The terminal's feature flags.
+ */
+export type FeatureFlagsDto = { 
+/**
+ * Flags this build knows, then any others the cloud sent.
+ */
+flags: Array<FeatureFlagDto>, 
+/**
+ * When the flags were fetched (`null`: never, every feature is off).
+ */
+fetchedAt: string | null, expiresAt: string | null, 
+/**
+ * The flags are past their TTL and the cloud could not be reached.
+ */
+stale: boolean, };
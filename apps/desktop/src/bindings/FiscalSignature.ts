@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The fiscal record stored on a sale.
+ */
+export type FiscalSignature = { sale_id: string, 
+/**
+ * Backend that produced the signature, e.g. "file_export".
+ */
+backend: string, 
+/**
+ * Position in the backend's sequence, starting at 1.
+ */
+sequence: bigint, signature: string, 
+/**
+ * Signature of the previous receipt (`None` for the first).
+ */
+previous_signature: string | null, signed_at: string, };
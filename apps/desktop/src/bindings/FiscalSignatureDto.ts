@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Fiscal signature as returned to the frontend.
+ */
+export type FiscalSignatureDto = { saleId: string, backend: string, sequence: number, signature: string, previousSignature: string | null, signedAt: string, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One hop of the connectivity diagnostic.
+ */
+export type HopResultDto = { 
+/**
+ * "dns", "tcp", "proxy", "tls", "grpc" or "auth"
+ */
+hop: string, 
+/**
+ * Whether the hop succeeded
+ */
+ok: boolean, 
+/**
+ * What was reached, or why it failed
+ */
+detail: string, 
+/**
+ * Time spent on the hop
+ */
+elapsedMs: number, };
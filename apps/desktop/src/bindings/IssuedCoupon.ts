@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A next-purchase coupon printed under a receipt.
+ */
+export type IssuedCoupon = { campaignId: string, code: string, 
+/**
+ * Last day the coupon can be used.
+ */
+validUntil: string, };
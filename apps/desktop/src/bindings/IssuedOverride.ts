@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A token ready to hand to the cashier.
+ */
+export type IssuedOverride = { token: string, tokenId: string, expiresAt: string, };
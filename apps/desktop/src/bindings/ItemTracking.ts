@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Whether a product needs serial or lot numbers captured at sale time.
+ */
+export type ItemTracking = "none" | "serial" | "lot";
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `jobs:alert` event.
+ */
+export type JobAlertEvent = { jobName: string, consecutiveFailures: number, 
+/**
+ * Error of the latest run
+ */
+error: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JobRunStatus } from "./JobRunStatus";
+
+/**
+ * One run of a background job.
+ */
+export type JobRun = { id: bigint, job_name: string, status: JobRunStatus, error: string | null, started_at: string, finished_at: string | null, duration_ms: bigint | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Printer drawer-port pin the kick-out pulse goes to.
+ */
+export type KickPin = "pin2" | "pin5";
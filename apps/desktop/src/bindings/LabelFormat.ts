@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Printer language a template is written in.
+ */
+export type LabelFormat = "zpl" | "epl";
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LabelFormat } from "./LabelFormat";
+
+/**
+ * Rendered labels, ready to send to the printer.
+ */
+export type LabelPrintJobDto = { format: LabelFormat, templateName: string, 
+/**
+ * Queue entries marked printed by this job.
+ */
+labelIds: Array<string>, 
+/**
+ * Labels the printer will produce (copies included).
+ */
+labelCount: number, 
+/**
+ * Printer commands for every label, in queue order.
+ */
+document: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LabelFormat } from "./LabelFormat";
+
+/**
+ * A saved label template (see the module docs for placeholders).
+ */
+export type LabelTemplate = { id: string, name: string, format: LabelFormat, body: string, 
+/**
+ * Used by `print_labels` when no template is named.
+ */
+is_default: boolean, created_at: string, updated_at: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LabelFormat } from "./LabelFormat";
+
+/**
+ * A label template as shown in the designer.
+ */
+export type LabelTemplateDto = { id: string, name: string, format: LabelFormat, body: string, isDefault: boolean, updatedAt: string, };
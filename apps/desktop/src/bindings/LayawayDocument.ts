@@ -0,0 +1,38 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LayawayItem } from "./LayawayItem";
+import type { LayawayPayment } from "./LayawayPayment";
+import type { LayawayStatus } from "./LayawayStatus";
+
+/**
+ * A layaway with its lines and payments, as stored in the sync outbox and
+ * relayed to other terminals.
+ */
+export type LayawayDocument = { items: Array<LayawayItem>, payments: Array<LayawayPayment>, id: string, tenant_id: string, 
+/**
+ * Human-readable number printed on the customer's slip.
+ */
+layaway_number: string, status: LayawayStatus, 
+/**
+ * Customer name (required - the goods are held for a person).
+ */
+customer_name: string, customer_phone: string | null, subtotal_cents: bigint, tax_cents: bigint, total_cents: bigint, 
+/**
+ * Net amount paid so far (payments minus any cancellation refund).
+ */
+paid_cents: bigint, 
+/**
+ * Fee retained on cancellation (0 unless cancelled).
+ */
+restocking_fee_cents: bigint, 
+/**
+ * Sale recorded at pickup.
+ */
+sale_id: string | null, user_id: string, 
+/**
+ * Terminal the layaway was created on.
+ */
+device_id: string, notes: string | null, created_at: string, updated_at: string, 
+/**
+ * When the layaway was completed or cancelled.
+ */
+closed_at: string | null, sync_version: bigint, };
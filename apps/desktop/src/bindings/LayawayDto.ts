@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LayawayItemDto } from "./LayawayItemDto";
+import type { LayawayPaymentDto } from "./LayawayPaymentDto";
+
+/**
+ * A layaway as shown in the UI.
+ */
+export type LayawayDto = { id: string, layawayNumber: string, status: string, customerName: string, customerPhone: string | null, totalCents: number, paidCents: number, balanceDueCents: number, restockingFeeCents: number, saleId: string | null, notes: string | null, createdAt: string, 
+/**
+ * Empty in list views.
+ */
+items: Array<LayawayItemDto>, 
+/**
+ * Empty in list views.
+ */
+payments: Array<LayawayPaymentDto>, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A line on a layaway.
+ * Uses the snapshot pattern so the agreed price survives catalog changes.
+ */
+export type LayawayItem = { id: string, layaway_id: string, product_id: string, sku_snapshot: string, name_snapshot: string, unit_price_cents: bigint, quantity: bigint, line_total_cents: bigint, tax_cents: bigint, created_at: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A layaway line as shown in the UI.
+ */
+export type LayawayItemDto = { productId: string, sku: string, name: string, quantity: number, unitPriceCents: number, lineTotalCents: number, };
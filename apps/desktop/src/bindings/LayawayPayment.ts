@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PaymentMethod } from "./PaymentMethod";
+
+/**
+ * Money taken (or refunded) against a layaway.
+ *
+ * Refunds on cancellation are recorded as a negative amount so that the
+ * sum of a layaway's payments is always its net `paid_cents`.
+ */
+export type LayawayPayment = { id: string, layaway_id: string, method: PaymentMethod, amount_cents: bigint, 
+/**
+ * Terminal that took the payment (for till reconciliation).
+ */
+device_id: string, created_at: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A layaway payment (negative for a refund) as shown in the UI.
+ */
+export type LayawayPaymentDto = { method: string, amountCents: number, createdAt: string, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Store-configurable layaway terms.
+ */
+export type LayawayPolicy = { 
+/**
+ * Minimum deposit as a share of the total, in basis points.
+ */
+min_deposit_bps: number, 
+/**
+ * Restocking fee on cancellation as a share of the total, in basis points.
+ */
+restocking_fee_bps: number, 
+/**
+ * Flat minimum restocking fee in cents.
+ */
+min_restocking_fee_cents: bigint, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What happens to the money when a layaway is cancelled.
+ */
+export type LayawaySettlement = { 
+/**
+ * Retained by the store.
+ */
+restocking_fee_cents: bigint, 
+/**
+ * Returned to the customer.
+ */
+refund_cents: bigint, };
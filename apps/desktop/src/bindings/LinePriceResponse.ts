@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CartResponse } from "./CartResponse";
+import type { MarginCheck } from "./MarginCheck";
+
+/**
+ * Cart after a line price override, with the margin check of the new
+ * price.
+ */
+export type LinePriceResponse = { cart: CartResponse, 
+/**
+ * `verdict` is "warn" when the price is below the margin floor.
+ */
+margin: MarginCheck, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One line's new quantity in a bulk edit (see `Cart::set_quantities`).
+ */
+export type LineQuantity = { productId: string, 
+/**
+ * New quantity (0 removes the line)
+ */
+quantity: number, };
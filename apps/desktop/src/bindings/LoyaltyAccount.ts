@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A loyalty member's account.
+ */
+export type LoyaltyAccount = { id: string, tenant_id: string, 
+/**
+ * Number printed on the member's card; what the cashier scans or types.
+ */
+member_number: string, created_at: string, updated_at: string, sync_version: bigint, };
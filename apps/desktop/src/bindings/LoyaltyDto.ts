@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LoyaltyEntry } from "./LoyaltyEntry";
+
+/**
+ * A loyalty member as shown in the UI.
+ */
+export type LoyaltyDto = { id: string, memberNumber: string, pointsBalance: number, lifetimePoints: number, 
+/**
+ * Rewards that can be spent, in cents.
+ */
+rewardBalanceCents: number, 
+/**
+ * Name of the member's tier.
+ */
+tier: string | null, 
+/**
+ * Name of the next tier up, if any.
+ */
+nextTier: string | null, 
+/**
+ * Lifetime points still needed for the next tier.
+ */
+pointsToNextTier: number | null, entries: Array<LoyaltyEntry>, };
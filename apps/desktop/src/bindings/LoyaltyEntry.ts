@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LoyaltyEntryKind } from "./LoyaltyEntryKind";
+
+/**
+ * A single movement on a loyalty account.
+ */
+export type LoyaltyEntry = { id: string, account_id: string, kind: LoyaltyEntryKind, 
+/**
+ * Signed: positive when earned, negative when converted to a reward.
+ */
+points: number, 
+/**
+ * Signed: positive when a reward is issued, negative when spent.
+ */
+reward_cents: number, 
+/**
+ * Sale the points were earned or the reward spent on.
+ */
+sale_id: string | null, device_id: string, created_at: string, };
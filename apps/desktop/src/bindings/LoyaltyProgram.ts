@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LoyaltyTier } from "./LoyaltyTier";
+
+/**
+ * Store-configurable loyalty rules.
+ */
+export type LoyaltyProgram = { 
+/**
+ * Base points per whole currency unit spent.
+ */
+points_per_dollar: number, 
+/**
+ * Points converted into one reward.
+ */
+reward_points: number, 
+/**
+ * Value of one reward, in cents.
+ */
+reward_value_cents: number, 
+/**
+ * Tiers by ascending `min_points`; the first starts at 0.
+ */
+tiers: Array<LoyaltyTier>, };
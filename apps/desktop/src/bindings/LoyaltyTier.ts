@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A loyalty tier, reached once a member's lifetime points get to
+ * `min_points`.
+ */
+export type LoyaltyTier = { name: string, min_points: number, 
+/**
+ * Points earned relative to the base rate, in basis points
+ * (12500 = 1.25 times the base points).
+ */
+earn_bps: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A manager's approval of a price below a blocking floor.
+ */
+export type ManagerBypass = { manager_id: string, reason: string, };
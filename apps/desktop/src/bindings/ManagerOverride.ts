@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OverrideAction } from "./OverrideAction";
+
+/**
+ * A used override token, as kept in the audit trail.
+ */
+export type ManagerOverride = { 
+/**
+ * The token ID.
+ */
+id: string, action: OverrideAction, subject: string | null, 
+/**
+ * Amount the token was used for.
+ */
+amount_cents: number | null, manager_id: string, reason: string, issued_at: string, expires_at: string, 
+/**
+ * Cashier who used the token.
+ */
+user_id: string, device_id: string, used_at: string, };
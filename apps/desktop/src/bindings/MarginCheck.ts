@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MarginVerdict } from "./MarginVerdict";
+
+/**
+ * A price checked against the guardrail.
+ */
+export type MarginCheck = { price_cents: number, cost_cents: number | null, 
+/**
+ * Margin of the price (None: no cost, or a price of zero or less).
+ */
+margin_bps: number | null, floor_bps: number, verdict: MarginVerdict, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GuardrailAction } from "./GuardrailAction";
+
+/**
+ * Store-configurable margin floor for manual prices.
+ */
+export type MarginGuardrail = { 
+/**
+ * Lowest acceptable margin, in basis points of the price.
+ */
+floor_bps: number, action: GuardrailAction, };
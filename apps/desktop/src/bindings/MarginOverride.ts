@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PriceChangeKind } from "./PriceChangeKind";
+
+/**
+ * A manager bypass of the margin floor, as kept in the audit trail.
+ */
+export type MarginOverride = { id: string, kind: PriceChangeKind, product_id: string, old_price_cents: number, new_price_cents: number, cost_cents: number | null, margin_bps: number | null, floor_bps: number, manager_id: string, reason: string, 
+/**
+ * Cashier who entered the price.
+ */
+user_id: string, device_id: string, created_at: string, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProductMargin } from "./ProductMargin";
+
+/**
+ * Price vs cost of everything sold over a period.
+ */
+export type MarginStats = { 
+/**
+ * Sales of lines with a known cost.
+ */
+sales_cents: number, cost_cents: number, 
+/**
+ * Sales of lines sold without a cost, left out of the margin.
+ */
+uncosted_sales_cents: number, 
+/**
+ * Lowest margin first.
+ */
+products: Array<ProductMargin>, };
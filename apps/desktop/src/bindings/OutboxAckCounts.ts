@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Unsynced outbox entries by ack level.
+ */
+export type OutboxAckCounts = { 
+/**
+ * Not acked by the hub yet.
+ */
+pending: bigint, 
+/**
+ * Held by the hub, waiting for the cloud's ack.
+ */
+hub_acked: bigint, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AckLevel } from "./AckLevel";
+
+/**
+ * An outbox entry as shown in the outbox inspector, without its payload.
+ */
+export type OutboxEntryStatus = { id: string, entity_type: string, entity_id: string, ack_level: AckLevel, attempts: bigint, last_error: string | null, 
+/**
+ * Hub holding the entry until the cloud acks it.
+ */
+held_by_hub: string | null, created_at: string, hub_acked_at: string | null, cloud_acked_at: string | null, synced_at: string | null, };
@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OverrideAction } from "./OverrideAction";
+
+/**
+ * The approval a token carries, signed as a whole.
+ */
+export type OverrideClaims = { 
+/**
+ * Unique token ID; the audit trail refuses it a second time.
+ */
+id: string, action: OverrideAction, store_id: string, 
+/**
+ * Terminal that issued the token; it cannot use the token itself.
+ */
+issued_by: string, 
+/**
+ * Terminal the token is for (None: any terminal of the store).
+ */
+device_id: string | null, 
+/**
+ * Sale the token is for (None: any sale).
+ */
+subject: string | null, 
+/**
+ * Largest amount approved (None: no limit).
+ */
+max_amount_cents: number | null, manager_id: string, reason: string, issued_at: string, expires_at: string, };
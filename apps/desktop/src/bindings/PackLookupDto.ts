@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProductPack } from "./ProductPack";
+
+/**
+ * The product and pack a scanned case barcode belongs to.
+ */
+export type PackLookupDto = { productId: string, pack: ProductPack, };
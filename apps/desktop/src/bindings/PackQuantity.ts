@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A stock quantity in whole packs plus the eaches left over.
+ */
+export type PackQuantity = { 
+/**
+ * Pack name, or [`EACH`].
+ */
+unit: string, 
+/**
+ * Eaches in one `unit`.
+ */
+units_per_pack: number, 
+/**
+ * Whole packs.
+ */
+packs: number, 
+/**
+ * Eaches that don't fill a pack (same sign as `packs`).
+ */
+remainder: number, };
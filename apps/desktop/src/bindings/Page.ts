@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One page of results.
+ */
+export type Page<T> = { items: Array<T>, 
+/**
+ * Pass to [`PageRequest::after`] for the next page; `None` on the
+ * last page.
+ */
+next_cursor: string | null, 
+/**
+ * Every matching row, when requested.
+ */
+total: number | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PaymentMethod } from "./PaymentMethod";
+
+/**
+ * Payments of one method in a Z report.
+ */
+export type PaymentTotal = { method: PaymentMethod, count: number, amount_cents: number, };
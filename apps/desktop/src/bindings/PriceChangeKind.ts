@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Which manual price a bypass approved.
+ */
+export type PriceChangeKind = "product_price" | "line_price";
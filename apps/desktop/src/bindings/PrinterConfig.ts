@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PrinterType } from "./PrinterType";
+
+/**
+ * Printer configuration.
+ */
+export type PrinterConfig = { 
+/**
+ * Printer type
+ */
+printerType: PrinterType, 
+/**
+ * Connection string (e.g., USB path, IP address)
+ */
+connection: string, 
+/**
+ * Paper width in characters (typically 32, 42, or 48)
+ */
+paperWidth: number, };
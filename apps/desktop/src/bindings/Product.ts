@@ -1,4 +1,5 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ItemTracking } from "./ItemTracking";
 
 /**
  * A product available for sale.
@@ -29,9 +30,14 @@ name: string,
  */
 description: string | null, 
 /**
- * Price in cents (smallest currency unit).
+ * Price in cents (smallest currency unit) charged at this store.
  */
 price_cents: bigint, 
+/**
+ * Tenant base price when `price_cents` is a store override (None =
+ * `price_cents` is the base price).
+ */
+base_price_cents: bigint | null, 
 /**
  * Cost in cents (for profit margin calculations).
  */
@@ -53,9 +59,21 @@ allow_negative_stock: boolean,
  */
 current_stock: bigint | null, 
 /**
- * Whether product is active (soft delete).
+ * Serial/lot capture required at sale time.
+ */
+item_tracking: ItemTracking, 
+/**
+ * Minimum customer age to buy this product (None = unrestricted).
+ */
+min_purchase_age: number | null, 
+/**
+ * Whether product is offered for sale.
  */
 is_active: boolean, 
+/**
+ * When the product was deleted (see [`crate::tombstone`]).
+ */
+deleted_at: string | null, 
 /**
  * When the product was created.
  */
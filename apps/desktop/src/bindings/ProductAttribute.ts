@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One attribute of a product; a tag when `value` is empty.
+ */
+export type ProductAttribute = { key: string, value: string, };
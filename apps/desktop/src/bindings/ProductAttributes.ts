@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProductAttribute } from "./ProductAttribute";
+
+/**
+ * The attributes of one product, as stored in the sync outbox
+ * (`PRODUCT_ATTRIBUTES`) and relayed to other terminals.
+ */
+export type ProductAttributes = { product_id: string, 
+/**
+ * Sorted by key.
+ */
+attributes: Array<ProductAttribute>, 
+/**
+ * Version of the set (independent of the product's own version).
+ */
+sync_version: bigint, updated_at: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProductAttribute } from "./ProductAttribute";
+
+/**
+ * A product's attributes and tags.
+ */
+export type ProductAttributesDto = { productId: string, 
+/**
+ * Sorted by key; a tag has an empty value.
+ */
+attributes: Array<ProductAttribute>, syncVersion: number, updatedAt: string, };
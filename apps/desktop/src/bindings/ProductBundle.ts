@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BundleComponent } from "./BundleComponent";
+
+/**
+ * A bundle product's bill of materials.
+ */
+export type ProductBundle = { 
+/**
+ * The bundle product.
+ */
+product_id: string, 
+/**
+ * Sorted by component product ID.
+ */
+components: Array<BundleComponent>, 
+/**
+ * Version of the bill of materials (0 for a product that was never
+ * a bundle).
+ */
+sync_version: bigint, updated_at: string, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BundleComponentDto } from "./BundleComponentDto";
+
+/**
+ * A bundle product's bill of materials.
+ */
+export type ProductBundleDto = { productId: string, 
+/**
+ * Empty for a product that is not a bundle.
+ */
+components: Array<BundleComponentDto>, 
+/**
+ * Bundles the components in stock can make up; `None` when no
+ * component limits it.
+ */
+availableBundles: number | null, syncVersion: number, updatedAt: string, };
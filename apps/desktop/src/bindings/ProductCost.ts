@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What a product's stock cost, maintained at receiving.
+ */
+export type ProductCost = { product_id: string, 
+/**
+ * Cost per each on the latest costed receipt.
+ */
+last_cost_cents: number | null, 
+/**
+ * Weighted average cost per each of the stock on hand.
+ */
+average_cost_cents: number | null, 
+/**
+ * Supplier of the latest costed receipt, if it named one.
+ */
+last_supplier_id: string | null, updated_at: string | null, };
@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Product DTO (Data Transfer Object) for frontend.
+ *
+ * ## Why DTO?
+ * - Decouples internal domain model from API contract
+ * - Allows selective field exposure
+ * - Handles serde rename to camelCase for JS consumption
+ */
+export type ProductDto = { id: string, sku: string, barcode: string | null, name: string, description: string | null, priceCents: number, taxRateBps: number, trackInventory: boolean, 
+/**
+ * Whether selling is allowed when stock is 0 or negative.
+ * Used by frontend to show "Back-order" vs "Out of Stock".
+ */
+allowNegativeStock: boolean, currentStock: number | null, isActive: boolean, 
+/**
+ * "none", "serial" or "lot" - frontend prompts for codes when set.
+ */
+itemTracking: string, 
+/**
+ * Minimum customer age; frontend warns that an ID check will be needed.
+ */
+minPurchaseAge: number | null, 
+/**
+ * Set once the product is deleted (RFC 3339).
+ */
+deletedAt: string | null, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What one product sold for and cost over a period.
+ */
+export type ProductMargin = { product_id: string, sku: string, name: string, units: number, 
+/**
+ * Net of line discounts, before tax.
+ */
+sales_cents: number, 
+/**
+ * Cost of the units sold, at the cost frozen on each line.
+ */
+cost_cents: number, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A pack a product is bought in, e.g. `case` of 24.
+ */
+export type ProductPack = { name: string, 
+/**
+ * Eaches in one pack.
+ */
+units: number, 
+/**
+ * Barcode printed on the pack (e.g. a case GTIN), if any.
+ */
+barcode: string | null, };
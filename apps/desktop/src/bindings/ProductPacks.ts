@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProductPack } from "./ProductPack";
+
+/**
+ * The packs of one product, as stored in the sync outbox
+ * (`PRODUCT_PACKS`) and relayed to other terminals.
+ */
+export type ProductPacks = { product_id: string, 
+/**
+ * Smallest pack first.
+ */
+packs: Array<ProductPack>, 
+/**
+ * Version of the set (independent of the product's own version).
+ */
+sync_version: bigint, updated_at: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProductPack } from "./ProductPack";
+
+/**
+ * A product's pack sizes.
+ */
+export type ProductPacksDto = { productId: string, 
+/**
+ * Smallest pack first; empty for a product received by the each.
+ */
+packs: Array<ProductPack>, syncVersion: number, updatedAt: string, };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProductDto } from "./ProductDto";
+import type { ProductStyle } from "./ProductStyle";
+
+/**
+ * One grouped search result: a product of its own, or a style whose
+ * variants matched.
+ */
+export type ProductSearchResultDto = { "kind": "product", product: ProductDto, } | { "kind": "style", style: ProductStyle, 
+/**
+ * The variants that matched, in rank order.
+ */
+matches: Array<ProductDto>, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A group of products sold as variants of one item.
+ */
+export type ProductStyle = { id: string, tenant_id: string, name: string, 
+/**
+ * Shared description, shown for variants without their own.
+ */
+description: string | null, 
+/**
+ * Attribute keys the variants differ by, e.g. `["size", "color"]`.
+ */
+option_keys: Array<string>, created_at: string, updated_at: string, sync_version: bigint, };
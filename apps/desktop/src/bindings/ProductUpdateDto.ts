@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MarginCheck } from "./MarginCheck";
+import type { ProductDto } from "./ProductDto";
+
+/**
+ * A product after an edit, with the margin check of a new price.
+ */
+export type ProductUpdateDto = { product: ProductDto, 
+/**
+ * Set when the price changed; `verdict` is "warn" when it is below
+ * the margin floor.
+ */
+margin: MarginCheck | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The marker left behind for a sale pruned from this terminal.
+ */
+export type PrunedSale = { saleId: string, receiptNumber: string, 
+/**
+ * Terminal the sale was rung up on.
+ */
+deviceId: string, totalCents: number, completedAt: string | null, prunedAt: string, };
@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A sync payload that failed its schema, held for inspection instead of
+ * being sent or applied.
+ */
+export type QuarantinedPayload = { id: string, tenant_id: string, 
+/**
+ * Where it came from: "outbox" for this device's own queue, otherwise
+ * the ID of the device that sent it to the hub.
+ */
+source: string, entity_type: string, entity_id: string, 
+/**
+ * The payload exactly as received.
+ */
+payload: string, 
+/**
+ * Schema version the payload claimed.
+ */
+schema_version: bigint, 
+/**
+ * Why it failed validation.
+ */
+reason: string, quarantined_at: string, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LabelReason } from "./LabelReason";
+import type { LabelStatus } from "./LabelStatus";
+
+/**
+ * A product's labels waiting in the print queue.
+ */
+export type QueuedLabel = { id: string, product_id: string, reason: LabelReason, status: LabelStatus, 
+/**
+ * Price before the first change since the last print.
+ */
+old_price_cents: bigint | null, 
+/**
+ * Price when queued (the label prints the price at print time).
+ */
+price_cents: bigint, copies: bigint, created_at: string, updated_at: string, printed_at: string | null, };
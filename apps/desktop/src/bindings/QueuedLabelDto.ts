@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LabelReason } from "./LabelReason";
+
+/**
+ * A queued label as shown in the UI.
+ */
+export type QueuedLabelDto = { id: string, productId: string, sku: string, name: string, reason: LabelReason, 
+/**
+ * Price before the change, for "was" labels.
+ */
+oldPriceCents: number | null, 
+/**
+ * Current price (what the label will show).
+ */
+priceCents: number, copies: number, queuedAt: string, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { QuoteStatus } from "./QuoteStatus";
+
+/**
+ * A saved, priced estimate for a customer.
+ */
+export type Quote = { id: string, tenant_id: string, 
+/**
+ * Human-readable number printed on the document.
+ */
+quote_number: string, status: QuoteStatus, customer_name: string | null, customer_email: string | null, subtotal_cents: bigint, tax_cents: bigint, discount_cents: bigint, total_cents: bigint, user_id: string, 
+/**
+ * Terminal the quote was created on.
+ */
+device_id: string, notes: string | null, valid_until: string, 
+/**
+ * Sale created from this quote (set on conversion).
+ */
+converted_sale_id: string | null, created_at: string, updated_at: string, sync_version: bigint, };
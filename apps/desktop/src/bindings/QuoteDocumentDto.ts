@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A quote rendered for printing or emailing.
+ */
+export type QuoteDocumentDto = { quoteNumber: string, 
+/**
+ * Customer email, if known (the frontend opens the mail client).
+ */
+recipient: string | null, subject: string, 
+/**
+ * Plain-text document, one line per printed line.
+ */
+body: string, };
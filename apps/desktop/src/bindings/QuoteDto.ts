@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { QuoteItemDto } from "./QuoteItemDto";
+
+/**
+ * A quote as shown in the UI.
+ */
+export type QuoteDto = { id: string, quoteNumber: string, status: string, isExpired: boolean, customerName: string | null, customerEmail: string | null, subtotalCents: number, taxCents: number, totalCents: number, notes: string | null, validUntil: string, convertedSaleId: string | null, createdAt: string, 
+/**
+ * Empty in list views.
+ */
+items: Array<QuoteItemDto>, };
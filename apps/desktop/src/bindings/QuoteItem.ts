@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A line on a quote.
+ * Uses the snapshot pattern so the quoted price survives catalog changes.
+ */
+export type QuoteItem = { id: string, quote_id: string, product_id: string, sku_snapshot: string, name_snapshot: string, 
+/**
+ * Quoted unit price in cents (frozen).
+ */
+unit_price_cents: bigint, 
+/**
+ * Tax rate at time of quoting (frozen).
+ */
+tax_rate_bps: number, quantity: bigint, line_total_cents: bigint, tax_cents: bigint, created_at: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A quote line as shown in the UI.
+ */
+export type QuoteItemDto = { productId: string, sku: string, name: string, quantity: number, unitPriceCents: number, lineTotalCents: number, taxCents: number, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The stored status of a quote.
+ *
+ * Expiry is not a stored status; see [`Quote::is_expired`].
+ */
+export type QuoteStatus = "open" | "converted" | "cancelled";
@@ -0,0 +1,35 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CampaignCondition } from "./CampaignCondition";
+
+/**
+ * One footer campaign, as set in the back office.
+ */
+export type ReceiptCampaign = { 
+/**
+ * Unique within the store; part of every code the campaign prints.
+ */
+id: string, 
+/**
+ * Name for the back office; not printed.
+ */
+name: string, 
+/**
+ * Baskets the campaign prints for (default: every sale).
+ */
+when: CampaignCondition, 
+/**
+ * First moment the campaign runs (default: already running).
+ */
+starts_at: string | null, 
+/**
+ * Moment the campaign stops (default: runs until removed).
+ */
+ends_at: string | null, } & ({ "type": "survey", message: string, url: string, } | { "type": "coupon", message: string, 
+/**
+ * Start of every code, e.g. "NEXT" (upper case letters and digits).
+ */
+code_prefix: string, 
+/**
+ * Days after the sale the coupon can be used.
+ */
+valid_days: number, } | { "type": "message", message: string, });
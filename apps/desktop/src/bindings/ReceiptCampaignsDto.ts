@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ReceiptCampaign } from "./ReceiptCampaign";
+
+/**
+ * The terminal's receipt footer campaigns.
+ */
+export type ReceiptCampaignsDto = { 
+/**
+ * In the order they are tried.
+ */
+campaigns: Array<ReceiptCampaign>, 
+/**
+ * When they were fetched (`null`: never, receipts have no campaigns).
+ */
+fetchedAt: string | null, 
+/**
+ * Campaigns from the cloud this build cannot print, with why.
+ */
+skipped: Array<string>, };
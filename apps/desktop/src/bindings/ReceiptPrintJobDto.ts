@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { IssuedCoupon } from "./IssuedCoupon";
+import type { ReceiptVariant } from "./ReceiptVariant";
+
+/**
+ * A rendered receipt, ready to send to the printer.
+ */
+export type ReceiptPrintJobDto = { saleId: string, receiptNumber: string, variant: ReceiptVariant, 
+/**
+ * Refund printed, for refund receipts.
+ */
+refundId: string | null, 
+/**
+ * Receipt text, one printer line per line.
+ */
+document: string, 
+/**
+ * ESC/POS image of the receipt number as a Code 128 barcode, printed
+ * after `document` and scanned to find the sale for a refund.
+ */
+barcode: Array<number>, 
+/**
+ * ESC/POS footer campaigns (survey, coupon, messages), printed after
+ * `barcode`; empty for every variant but the original.
+ */
+footer: Array<number>, 
+/**
+ * Next-purchase coupons printed in `footer`.
+ */
+coupons: Array<IssuedCoupon>, };
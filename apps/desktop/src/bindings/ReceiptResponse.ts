@@ -0,0 +1,37 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ReceiptItem } from "./ReceiptItem";
+import type { ReceiptPayment } from "./ReceiptPayment";
+
+export type ReceiptResponse = { saleId: string, receiptNumber: string, storeName: string, 
+/**
+ * Register the sale was rung up on.
+ */
+stationNumber: number | null, timestamp: string, items: Array<ReceiptItem>, subtotalCents: number, taxCents: number, 
+/**
+ * Coupon discount taken off the sale.
+ */
+discountCents: number, totalCents: number, payments: Array<ReceiptPayment>, changeCents: number, 
+/**
+ * Fiscal sequence number, when the store signs receipts.
+ */
+fiscalSequence: number | null, 
+/**
+ * Fiscal signature to print on the receipt.
+ */
+fiscalSignature: string | null, 
+/**
+ * Printed across the receipt ("TRAINING" for a training sale).
+ */
+watermark: string | null, 
+/**
+ * Whether this is a training sale (see `set_training_mode`).
+ */
+trainingMode: boolean, 
+/**
+ * Points the loyalty member on the cart earned on the sale.
+ */
+loyaltyPoints: number | null, 
+/**
+ * Value of the loyalty rewards the sale completed, in cents.
+ */
+loyaltyRewardsCents: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Which receipt to print for a sale.
+ */
+export type ReceiptVariant = "original" | "gift" | "reprint" | "refund";
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One line of goods being received, in any unit of the product.
+ */
+export type ReceivingLine = { product_id: string, 
+/**
+ * [`EACH`] or one of the product's pack names.
+ */
+unit: string, quantity: number, 
+/**
+ * Cost per `unit` from the delivery note; the supplier's listed cost
+ * is used when omitted.
+ */
+unit_cost_cents: number | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where the money from a refund goes.
+ */
+export type RefundDestination = "cash" | "external_card" | "store_credit";
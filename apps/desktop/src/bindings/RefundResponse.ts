@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { StoreCreditDto } from "./StoreCreditDto";
+
+export type RefundResponse = { refundId: string, amountCents: number, destination: string, 
+/**
+ * Total refunded on the sale, including this refund.
+ */
+totalRefundedCents: number, 
+/**
+ * The credited account when the refund went to store credit.
+ */
+storeCredit: StoreCreditDto | null, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A release offered by the cloud.
+ */
+export type ReleaseDto = { version: string, channel: string, downloadUrl: string, 
+/**
+ * Hex SHA-256 of the bundle.
+ */
+sha256: string, 
+/**
+ * Hex Ed25519 signature of the bundle.
+ */
+signature: string, notes: string, 
+/**
+ * The store must install this release.
+ */
+mandatory: boolean, publishedAt: string | null, };
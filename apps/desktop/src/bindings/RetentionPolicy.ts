@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How long a terminal keeps local data.
+ */
+export type RetentionPolicy = { 
+/**
+ * Days a finished sale is kept once the cloud has it. `None` keeps
+ * every sale.
+ */
+saleDays: number | null, };
@@ -4,4 +4,9 @@ import type { SaleStatus } from "./SaleStatus";
 /**
  * A completed or in-progress sale transaction.
  */
-export type Sale = { id: string, tenant_id: string, receipt_number: string, status: SaleStatus, subtotal_cents: bigint, tax_cents: bigint, discount_cents: bigint, total_cents: bigint, user_id: string, device_id: string, notes: string | null, created_at: string, updated_at: string, completed_at: string | null, sync_version: bigint, };
+export type Sale = { id: string, tenant_id: string, receipt_number: string, status: SaleStatus, subtotal_cents: bigint, tax_cents: bigint, discount_cents: bigint, total_cents: bigint, user_id: string, device_id: string, 
+/**
+ * Register the sale was rung up on (see `titan_core::station`);
+ * `None` for sales from before stations were configured.
+ */
+station_number: number | null, notes: string | null, created_at: string, updated_at: string, completed_at: string | null, sync_version: bigint, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Payment } from "./Payment";
+import type { SaleItem } from "./SaleItem";
+import type { SaleStatus } from "./SaleStatus";
+
+/**
+ * A completed sale with its lines and payments, as stored in the sync
+ * outbox and relayed to the other terminals.
+ *
+ * Terminals before relaying queued the sale alone, so `items` and
+ * `payments` may be missing.
+ */
+export type SaleDocument = { items: Array<SaleItem>, payments: Array<Payment>, id: string, tenant_id: string, receipt_number: string, status: SaleStatus, subtotal_cents: bigint, tax_cents: bigint, discount_cents: bigint, total_cents: bigint, user_id: string, device_id: string, 
+/**
+ * Register the sale was rung up on (see `titan_core::station`);
+ * `None` for sales from before stations were configured.
+ */
+station_number: number | null, notes: string | null, created_at: string, updated_at: string, completed_at: string | null, sync_version: bigint, };
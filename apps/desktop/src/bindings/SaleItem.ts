@@ -32,4 +32,14 @@ tax_cents: bigint,
 /**
  * Discount applied to this line.
  */
-discount_cents: bigint, created_at: string, };
+discount_cents: bigint, 
+/**
+ * Tenant base price when the line was sold at a store override price
+ * (frozen).
+ */
+base_price_cents: bigint | null, 
+/**
+ * Tax rate the line was taxed at, in basis points (frozen; `None` when
+ * not known, e.g. layaway pickups).
+ */
+tax_rate_bps: number | null, created_at: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ItemTracking } from "./ItemTracking";
+
+/**
+ * A serial or lot number captured for a sale line.
+ */
+export type SaleItemTracking = { id: string, sale_id: string, sale_item_id: string, product_id: string, kind: ItemTracking, code: string, created_at: string, };
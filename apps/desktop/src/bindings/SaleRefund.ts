@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RefundDestination } from "./RefundDestination";
+
+/**
+ * A refund against a completed sale.
+ *
+ * Refunds here are returnless: the customer keeps the goods, so stock is
+ * not touched.
+ */
+export type SaleRefund = { id: string, sale_id: string, amount_cents: bigint, destination: RefundDestination, 
+/**
+ * Account credited when `destination` is `StoreCredit`.
+ */
+store_credit_id: string | null, reason: string | null, user_id: string, device_id: string, created_at: string, };
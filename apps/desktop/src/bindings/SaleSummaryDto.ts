@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SaleStatus } from "./SaleStatus";
+
+/**
+ * A row of the sales history list.
+ */
+export type SaleSummaryDto = { id: string, receiptNumber: string, status: SaleStatus, totalCents: number, userId: string, deviceId: string, 
+/**
+ * Register the sale was rung up on.
+ */
+stationNumber: number | null, createdAt: string, completedAt: string | null, };
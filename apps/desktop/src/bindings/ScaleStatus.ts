@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * State of the scale when it was read.
+ */
+export type ScaleStatus = "stable" | "motion" | "over_capacity" | "under_zero";
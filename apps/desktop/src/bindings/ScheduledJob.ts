@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JobRunStatus } from "./JobRunStatus";
+
+/**
+ * A background job's definition and latest state.
+ */
+export type ScheduledJob = { 
+/**
+ * Stable job name, e.g. "outbox_cleanup".
+ */
+name: string, 
+/**
+ * Cron expression (see [`CronSchedule`]).
+ */
+schedule: string, enabled: boolean, last_run_at: string | null, last_status: JobRunStatus | null, 
+/**
+ * When the scheduler will next run the job.
+ */
+next_run_at: string | null, 
+/**
+ * Failed runs since the last success.
+ */
+consecutive_failures: bigint, updated_at: string, };
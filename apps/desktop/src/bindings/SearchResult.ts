@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Product } from "./Product";
+import type { ProductStyle } from "./ProductStyle";
+
+/**
+ * One search result: a product of its own, or a style whose variants
+ * matched (listed once, where its best-ranked variant was).
+ */
+export type SearchResult = { "kind": "product", product: Product, } | { "kind": "style", style: ProductStyle, 
+/**
+ * The variants that matched, in rank order.
+ */
+matches: Array<Product>, };
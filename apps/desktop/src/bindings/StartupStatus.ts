@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where startup is (see [`DbState::status`]).
+ *
+ * Serialized as `{ "phase": "failed", "error": "..." }`.
+ */
+export type StartupStatus = { "phase": "starting" } | { "phase": "ready" } | { "phase": "failed", "error": string };
@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Register identity of this terminal.
+ */
+export type StationConfig = { 
+/**
+ * Register number staff and reports know the terminal by.
+ */
+number: number, 
+/**
+ * Start of every receipt number printed here.
+ */
+receipt_prefix: string, 
+/**
+ * Printer receipts go to unless another is chosen.
+ */
+default_printer: string | null, 
+/**
+ * Cash drawer opened for cash tenders.
+ */
+default_cash_drawer: string | null, 
+/**
+ * Scale loose goods are weighed on.
+ */
+scale: string | null, 
+/**
+ * Pole display facing the customer.
+ */
+line_display: string | null, };
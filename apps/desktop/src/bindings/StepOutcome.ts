@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How a step went, from best to worst.
+ */
+export type StepOutcome = "ok" | "skipped" | "warning" | "failed";
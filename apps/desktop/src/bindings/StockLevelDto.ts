@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PackQuantity } from "./PackQuantity";
+
+/**
+ * A product's stock in eaches and in the requested unit.
+ */
+export type StockLevelDto = { productId: string, sku: string, name: string, 
+/**
+ * Stock in eaches; `None` if not counted.
+ */
+units: number | null, 
+/**
+ * Stock in the requested unit (eaches if the product has no such
+ * pack).
+ */
+quantity: PackQuantity | null, 
+/**
+ * e.g. "3 case + 5 each".
+ */
+display: string | null, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { StockReceiptLine } from "./StockReceiptLine";
+
+/**
+ * Goods received into stock, e.g. one supplier delivery.
+ */
+export type StockReceipt = { id: string, 
+/**
+ * Delivery note or supplier invoice number.
+ */
+reference: string | null, 
+/**
+ * Supplier the goods came from.
+ */
+supplier_id: string | null, lines: Array<StockReceiptLine>, received_by: string, device_id: string, received_at: string, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { StockReceiptLine } from "./StockReceiptLine";
+
+/**
+ * A stock receipt as returned to the frontend.
+ */
+export type StockReceiptDto = { id: string, reference: string | null, supplierId: string | null, lines: Array<StockReceiptLine>, 
+/**
+ * Eaches received across all lines.
+ */
+totalUnits: number, receivedBy: string, receivedAt: string, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A received line with its conversion to eaches, as recorded.
+ */
+export type StockReceiptLine = { product_id: string, unit: string, 
+/**
+ * Eaches per `unit` when received.
+ */
+units_per_pack: number, 
+/**
+ * Quantity in `unit`.
+ */
+quantity: number, 
+/**
+ * Eaches added to stock.
+ */
+units: number, 
+/**
+ * Cost per each, if known.
+ */
+each_cost_cents: number | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A customer's store credit account.
+ */
+export type StoreCreditAccount = { id: string, tenant_id: string, 
+/**
+ * Number printed on the credit slip; what the cashier scans or types.
+ */
+credit_number: string, customer_name: string, customer_phone: string | null, created_at: string, updated_at: string, sync_version: bigint, };
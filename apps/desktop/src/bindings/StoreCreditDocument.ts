@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { StoreCreditEntry } from "./StoreCreditEntry";
+
+/**
+ * An account with its full ledger, as synced between terminals and stores.
+ */
+export type StoreCreditDocument = { entries: Array<StoreCreditEntry>, id: string, tenant_id: string, 
+/**
+ * Number printed on the credit slip; what the cashier scans or types.
+ */
+credit_number: string, customer_name: string, customer_phone: string | null, created_at: string, updated_at: string, sync_version: bigint, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { StoreCreditEntryDto } from "./StoreCreditEntryDto";
+
+/**
+ * A store credit account as shown in the UI.
+ */
+export type StoreCreditDto = { id: string, creditNumber: string, customerName: string, customerPhone: string | null, balanceCents: number, 
+/**
+ * Empty in search results.
+ */
+entries: Array<StoreCreditEntryDto>, };
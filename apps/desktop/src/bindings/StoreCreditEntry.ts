@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { StoreCreditEntryKind } from "./StoreCreditEntryKind";
+
+/**
+ * A single movement on a store credit account.
+ */
+export type StoreCreditEntry = { id: string, account_id: string, kind: StoreCreditEntryKind, 
+/**
+ * Signed: positive for issue, negative for redeem.
+ */
+amount_cents: bigint, 
+/**
+ * Sale the credit was refunded from or spent on.
+ */
+sale_id: string | null, device_id: string, created_at: string, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A ledger entry as shown in the UI.
+ */
+export type StoreCreditEntryDto = { id: string, 
+/**
+ * "issue" or "redeem".
+ */
+kind: string, 
+/**
+ * Signed: positive for issue, negative for redeem.
+ */
+amountCents: number, saleId: string | null, createdAt: string, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TransferStatus } from "./TransferStatus";
+
+/**
+ * A store transfer header.
+ */
+export type StoreTransfer = { id: string, tenant_id: string, 
+/**
+ * Human-readable number printed on the packing slip.
+ */
+transfer_number: string, from_store_id: string, to_store_id: string, status: TransferStatus, notes: string | null, 
+/**
+ * Cashier who shipped the goods.
+ */
+created_by: string, 
+/**
+ * Cashier who counted the goods in.
+ */
+received_by: string | null, created_at: string, updated_at: string, received_at: string | null, cancelled_at: string | null, sync_version: bigint, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A line on a store transfer.
+ */
+export type StoreTransferItem = { id: string, transfer_id: string, product_id: string, sku_snapshot: string, name_snapshot: string, quantity_sent: bigint, 
+/**
+ * Set when the receiver counts the line in.
+ */
+quantity_received: bigint | null, };
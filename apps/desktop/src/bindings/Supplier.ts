@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A supplier the store buys from.
+ */
+export type Supplier = { id: string, name: string, contact_name: string | null, phone: string | null, email: string | null, 
+/**
+ * Inactive suppliers keep their history but aren't offered at
+ * receiving.
+ */
+is_active: boolean, created_at: string, updated_at: string, sync_version: bigint, };
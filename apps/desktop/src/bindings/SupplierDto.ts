@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SupplierItem } from "./SupplierItem";
+
+/**
+ * A supplier as returned to the frontend.
+ */
+export type SupplierDto = { id: string, name: string, contactName: string | null, phone: string | null, email: string | null, isActive: boolean, 
+/**
+ * The price list, by product ID; empty in supplier lists.
+ */
+items: Array<SupplierItem>, syncVersion: number, updatedAt: string, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What one supplier charges for one product.
+ */
+export type SupplierItem = { product_id: string, 
+/**
+ * The supplier's own code for the product.
+ */
+supplier_sku: string | null, 
+/**
+ * Cost per each.
+ */
+cost_cents: number, };
@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Response DTO for sync configuration.
+ */
+export type SyncConfigDto = { 
+/**
+ * Device UUID
+ */
+deviceId: string, 
+/**
+ * Human-readable device name
+ */
+deviceName: string, 
+/**
+ * Store ID this device belongs to
+ */
+storeId: string, 
+/**
+ * Store name
+ */
+storeName: string, 
+/**
+ * Current sync mode
+ */
+syncMode: string, 
+/**
+ * Whether the sync agent is running
+ */
+isRunning: boolean, };
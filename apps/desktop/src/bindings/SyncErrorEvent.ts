@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ErrorCode } from "./ErrorCode";
+
+/**
+ * Payload of the `sync:error` event.
+ */
+export type SyncErrorEvent = { code: ErrorCode, message: string, retryable: boolean, };
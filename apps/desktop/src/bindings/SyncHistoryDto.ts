@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DailyUptime } from "./DailyUptime";
+import type { SyncStatusPeriod } from "./SyncStatusPeriod";
+
+/**
+ * Response DTO for the sync status history.
+ */
+export type SyncHistoryDto = { 
+/**
+ * Connection periods, oldest first
+ */
+periods: Array<SyncStatusPeriod>, 
+/**
+ * Uptime per local calendar day, oldest first
+ */
+dailyUptime: Array<DailyUptime>, };
@@ -32,4 +32,13 @@ attempted_at: string | null,
 /**
  * When successfully synced.
  */
-synced_at: string | null, };
+synced_at: string | null, 
+/**
+ * Correlation ID of the command that queued the entry, if any.
+ */
+correlation_id: string | null, 
+/**
+ * W3C `traceparent` of the command that queued the entry, when spans
+ * are exported.
+ */
+trace_context: string | null, };
@@ -0,0 +1,59 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ErrorCode } from "./ErrorCode";
+
+/**
+ * DTO for sync status that can be serialized to the frontend.
+ */
+export type SyncStatusDto = { 
+/**
+ * Current connection state
+ */
+connectionState: string, 
+/**
+ * Current sync mode
+ */
+syncMode: string, 
+/**
+ * Last successful sync timestamp (ISO8601)
+ */
+lastSyncAt: string | null, 
+/**
+ * Number of pending outbox entries
+ */
+pendingOutboxCount: number, 
+/**
+ * Whether sync is healthy (connected and no errors)
+ */
+isHealthy: boolean, 
+/**
+ * Last error message if any
+ */
+errorMessage: string | null, 
+/**
+ * Machine-readable code of the last error
+ */
+errorCode: ErrorCode | null, 
+/**
+ * Hub URL if connected
+ */
+hubUrl: string | null, 
+/**
+ * Last ping round trip to the hub in milliseconds
+ */
+latencyMs: number | null, 
+/**
+ * Whether uploads are throttled for a metered link
+ */
+metered: boolean, 
+/**
+ * Whether the outbox is uploading straight to the cloud (no hub)
+ */
+cloudFallback: boolean, 
+/**
+ * Whether sync was paused by support staff
+ */
+paused: boolean, 
+/**
+ * Whether a full resync from the hub is in progress
+ */
+resyncing: boolean, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A stretch of time the sync connection spent in one state.
+ */
+export type SyncStatusPeriod = { id: bigint, 
+/**
+ * Connection state ("connecting", "ready", "backoff", ...).
+ */
+state: string, 
+/**
+ * Why the connection left its previous state, for losses and errors.
+ */
+reason: string | null, started_at: string, 
+/**
+ * `None` while this is the current state.
+ */
+ended_at: string | null, 
+/**
+ * Length of the period once it has ended.
+ */
+duration_ms: bigint | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A tax rate shared by departments (e.g. "FOOD" at 0%).
+ */
+export type TaxGroup = { code: string, name: string, rate_bps: number, };
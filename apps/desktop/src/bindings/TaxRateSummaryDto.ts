@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Sales and tax at one rate.
+ */
+export type TaxRateSummaryDto = { 
+/**
+ * Basis points (`null`: lines sold without a recorded rate).
+ */
+rateBps: number | null, jurisdiction: string | null, taxableCents: number, nonTaxableCents: number, taxCents: number, lineCount: number, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TaxRateSummaryDto } from "./TaxRateSummaryDto";
+
+/**
+ * The sales tax report for a date range.
+ */
+export type TaxReportDto = { from: string, to: string, 
+/**
+ * Lowest rate first; unknown rates last.
+ */
+rates: Array<TaxRateSummaryDto>, taxableCents: number, nonTaxableCents: number, taxCents: number, };
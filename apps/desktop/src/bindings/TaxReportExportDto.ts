@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TaxReportDto } from "./TaxReportDto";
+
+/**
+ * A tax report written to disk.
+ */
+export type TaxReportExportDto = { 
+/**
+ * Full path of the CSV file.
+ */
+path: string, report: TaxReportDto, };
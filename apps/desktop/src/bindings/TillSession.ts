@@ -0,0 +1,42 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TillSessionStatus } from "./TillSessionStatus";
+
+/**
+ * A till (cash drawer) session on a single terminal.
+ *
+ * `expected_cash_cents`, `counted_cash_cents` and `variance_cents` are only
+ * populated once the session is closed.
+ */
+export type TillSession = { id: string, tenant_id: string, 
+/**
+ * Terminal the drawer belongs to.
+ */
+device_id: string, 
+/**
+ * Cashier who opened the drawer.
+ */
+user_id: string, status: TillSessionStatus, 
+/**
+ * Cash placed in the drawer at open.
+ */
+opening_float_cents: bigint, 
+/**
+ * Opening float + cash taken (computed at close).
+ */
+expected_cash_cents: bigint | null, 
+/**
+ * Sum of the denomination count (entered at close).
+ */
+counted_cash_cents: bigint | null, 
+/**
+ * counted - expected (positive = over, negative = short).
+ */
+variance_cents: bigint | null, 
+/**
+ * Whether the close was performed blind.
+ */
+blind_close: boolean, 
+/**
+ * Cashier who performed the close.
+ */
+closed_by: string | null, notes: string | null, opened_at: string, closed_at: string | null, sync_version: bigint, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Till session as shown to the cashier (expected cash is never exposed).
+ */
+export type TillSessionDto = { id: string, deviceId: string, userId: string, status: string, openingFloatCents: number, openedAt: string, };
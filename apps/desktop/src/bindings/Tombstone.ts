@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TombstoneAction } from "./TombstoneAction";
+
+/**
+ * A soft delete or restore, as queued for sync (`TOMBSTONE`).
+ */
+export type Tombstone = { 
+/**
+ * One of [`SOFT_DELETE_ENTITY_TYPES`].
+ */
+entity_type: string, entity_id: string, action: TombstoneAction, 
+/**
+ * The entity's `sync_version` after the change.
+ */
+version: number, 
+/**
+ * When the change was made.
+ */
+at: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A sold line matched by a recall lookup.
+ */
+export type TrackedSaleDto = { saleId: string, receiptNumber: string, deviceId: string, completedAt: string | null, productId: string, sku: string, name: string, quantity: number, code: string, };
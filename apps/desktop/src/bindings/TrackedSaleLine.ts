@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ItemTracking } from "./ItemTracking";
+
+/**
+ * A completed sale line matched by a recall lookup.
+ */
+export type TrackedSaleLine = { sale_id: string, receipt_number: string, device_id: string, completed_at: string | null, product_id: string, sku_snapshot: string, name_snapshot: string, quantity: bigint, kind: ItemTracking, code: string, };
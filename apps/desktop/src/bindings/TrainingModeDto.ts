@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Whether training mode is on.
+ */
+export type TrainingModeDto = { enabled: boolean, 
+/**
+ * Watermark on receipts while training (`null` when off).
+ */
+watermark: string | null, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TransferItemDto } from "./TransferItemDto";
+
+/**
+ * A store transfer as shown in the UI.
+ */
+export type TransferDto = { id: string, transferNumber: string, 
+/**
+ * "outbound" or "inbound" relative to this store.
+ */
+direction: string, status: string, fromStoreId: string, toStoreId: string, notes: string | null, createdAt: string, receivedAt: string | null, cancelledAt: string | null, 
+/**
+ * Empty in list views.
+ */
+items: Array<TransferItemDto>, };
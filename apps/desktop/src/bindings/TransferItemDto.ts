@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A transfer line as shown in the UI.
+ */
+export type TransferItemDto = { id: string, productId: string, sku: string, name: string, quantitySent: number, quantityReceived: number | null, shortfall: number, };
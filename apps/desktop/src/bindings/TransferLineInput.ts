@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A line requested by the UI when creating a transfer.
+ */
+export type TransferLineInput = { productId: string, quantity: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The status of a store transfer.
+ */
+export type TransferStatus = "in_transit" | "received" | "cancelled";
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ReleaseDto } from "./ReleaseDto";
+import type { UpdateChannel } from "./UpdateChannel";
+
+/**
+ * Result of a release check.
+ */
+export type UpdateCheckDto = { currentVersion: string, channel: UpdateChannel, updateAvailable: boolean, release: ReleaseDto | null, };
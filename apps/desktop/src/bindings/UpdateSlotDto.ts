@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The hub's answer to an update slot request.
+ */
+export type UpdateSlotDto = { 
+/**
+ * Install now.
+ */
+granted: boolean, 
+/**
+ * Place in line when not granted (1 = next).
+ */
+queuePosition: number, 
+/**
+ * When to ask again if not granted.
+ */
+retryAfterSecs: number, };
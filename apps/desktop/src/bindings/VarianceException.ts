@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A cashier with repeated large variances (exceptions report row).
+ */
+export type VarianceException = { user_id: string, 
+/**
+ * Number of closes with a large variance in the report window.
+ */
+large_variance_count: bigint, 
+/**
+ * Net variance across those closes (over and short cancel out).
+ */
+net_variance_cents: bigint, 
+/**
+ * Sum of absolute variances across those closes.
+ */
+absolute_variance_cents: bigint, last_closed_at: string | null, };
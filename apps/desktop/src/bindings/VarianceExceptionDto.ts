@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Row in the variance exceptions report.
+ */
+export type VarianceExceptionDto = { userId: string, largeVarianceCount: number, netVarianceCents: number, absoluteVarianceCents: number, lastClosedAt: string | null, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Thresholds for flagging cashiers with repeated large variances.
+ */
+export type VariancePolicy = { 
+/**
+ * Absolute variance at or above which a close counts as "large".
+ */
+large_variance_cents: bigint, 
+/**
+ * Number of large closes before the cashier is flagged.
+ */
+flag_after: bigint, };
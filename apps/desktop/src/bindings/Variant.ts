@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Product } from "./Product";
+
+/**
+ * One variant: the product and its option values.
+ */
+export type Variant = { product: Product, 
+/**
+ * Values in the style's `option_keys` order.
+ */
+options: Array<string>, };
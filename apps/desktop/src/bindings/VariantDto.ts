@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProductDto } from "./ProductDto";
+
+/**
+ * A variant of a style, as shown in the variant picker.
+ */
+export type VariantDto = { product: ProductDto, 
+/**
+ * Option values in the style's option key order.
+ */
+options: Array<string>, 
+/**
+ * The variant's own description, else the style's.
+ */
+description: string | null, };
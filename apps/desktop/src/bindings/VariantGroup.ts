@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProductStyle } from "./ProductStyle";
+import type { Variant } from "./Variant";
+
+/**
+ * A style with its variants, for display and for picking a variant.
+ */
+export type VariantGroup = { style: ProductStyle, variants: Array<Variant>, };
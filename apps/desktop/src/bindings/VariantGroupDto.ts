@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProductStyle } from "./ProductStyle";
+import type { VariantDto } from "./VariantDto";
+import type { VariantMatrix } from "./VariantMatrix";
+
+/**
+ * A style with its variants and the data to display them as a grid.
+ */
+export type VariantGroupDto = { style: ProductStyle, variants: Array<VariantDto>, matrix: VariantMatrix, 
+/**
+ * Lowest and highest variant price; `None` without variants.
+ */
+minPriceCents: number | null, maxPriceCents: number | null, 
+/**
+ * Stock across the variants that track inventory.
+ */
+totalStock: number | null, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Variants laid out as a grid: rows by the first option, columns by the
+ * others (joined with `" / "`); each cell is a product ID or empty.
+ */
+export type VariantMatrix = { row_key: string, 
+/**
+ * `None` for a style with one option (a single column).
+ */
+column_key: string | null, rows: Array<string>, columns: Array<string>, 
+/**
+ * `cells[row][column]`.
+ */
+cells: Array<Array<string | null>>, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WasteReason } from "./WasteReason";
+
+/**
+ * Waste for one reason (one row of the report).
+ */
+export type WasteReasonSummary = { reason: WasteReason, units: number, value_cents: number, entries: number, };
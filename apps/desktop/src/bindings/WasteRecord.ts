@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WasteReason } from "./WasteReason";
+
+/**
+ * Goods written off from stock.
+ */
+export type WasteRecord = { id: string, product_id: string, 
+/**
+ * Units written off (positive; stock goes down by this much).
+ */
+quantity: number, reason: WasteReason, 
+/**
+ * The product's price when the waste was recorded.
+ */
+unit_price_cents: number, note: string | null, recorded_by: string, device_id: string, recorded_at: string, };
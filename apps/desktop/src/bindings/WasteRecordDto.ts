@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WasteReason } from "./WasteReason";
+
+/**
+ * A waste record with what the report shows.
+ */
+export type WasteRecordDto = { id: string, productId: string, sku: string, name: string, quantity: number, reason: WasteReason, 
+/**
+ * Retail value of the goods when written off.
+ */
+valueCents: number, note: string | null, recordedBy: string, recordedAt: string, };
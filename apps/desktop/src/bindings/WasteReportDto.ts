@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WasteReasonSummary } from "./WasteReasonSummary";
+import type { WasteRecordDto } from "./WasteRecordDto";
+
+/**
+ * Waste recorded on one day.
+ */
+export type WasteReportDto = { date: string, 
+/**
+ * One row per reason with waste.
+ */
+reasons: Array<WasteReasonSummary>, 
+/**
+ * Oldest first.
+ */
+records: Array<WasteRecordDto>, totalUnits: number, totalValueCents: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Limits a weight must fall within to be sold.
+ */
+export type WeighRules = { min_net_grams: number, capacity_grams: number, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ScaleStatus } from "./ScaleStatus";
+
+/**
+ * One reading of the scale.
+ */
+export type WeightReading = { 
+/**
+ * Weight on the platter, container included.
+ */
+gross_grams: number, status: ScaleStatus, read_at: string, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A window after opening it.
+ */
+export type WindowDto = { label: string, 
+/**
+ * The window was already open and has been focused.
+ */
+alreadyOpen: boolean, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PaymentTotal } from "./PaymentTotal";
+
+/**
+ * A Z close: the sales a device completed since its previous close.
+ */
+export type ZReport = { id: string, device_id: string, station_number: number | null, 
+/**
+ * Z number on this device, from 1.
+ */
+sequence: number, business_date: string, 
+/**
+ * Previous close (`None` for the device's first).
+ */
+period_start: string | null, closed_at: string, sale_count: number, 
+/**
+ * Sales voided in the period.
+ */
+void_count: number, subtotal_cents: number, tax_cents: number, discount_cents: number, total_cents: number, 
+/**
+ * By payment method.
+ */
+payments: Array<PaymentTotal>, };
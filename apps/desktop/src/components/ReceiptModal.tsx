@@ -195,6 +195,11 @@ const ReceiptModal: Component<ReceiptModalProps> = (props) => {
 
           {/* Footer */}
           <div class="border-t border-dashed border-gray-300 pt-4 text-center">
+            <Show when={props.receipt.fiscalSignature}>
+              <p class="text-xs text-gray-500 font-mono break-all mb-2">
+                Fiscal #{props.receipt.fiscalSequence}: {props.receipt.fiscalSignature}
+              </p>
+            </Show>
             <p class="text-sm text-gray-600">Thank you for your purchase!</p>
             <p class="text-xs text-gray-400 mt-1">
               Sale ID: {props.receipt.saleId.slice(0, 8)}...
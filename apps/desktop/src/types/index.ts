@@ -1,8 +1,10 @@
 /**
  * TypeScript Types for Titan POS Frontend
  *
- * These types mirror the Rust DTOs from the backend.
- * They are used for type-safe Tauri invoke calls.
+ * Command inputs/outputs and event payloads, re-exported from the bindings
+ * that ts-rs generates from the Rust DTOs (`src/bindings`). Do not declare
+ * backend shapes by hand here: change the Rust type and regenerate with
+ * `npm run bindings` (runs the `export_bindings` tests).
  *
  * ## Naming Convention
  * - Rust uses snake_case, TypeScript uses camelCase
@@ -17,195 +19,275 @@
 // Product Types
 // ─────────────────────────────────────────────────────────────────────────────
 
-/**
- * Product data from the backend.
- * 
- * ## Stock Behavior
- * The combination of `trackInventory` and `allowNegativeStock` determines
- * how out-of-stock products are handled:
- * 
- * | trackInventory | allowNegativeStock | Behavior |
- * |----------------|-------------------|----------|
- * | false          | (ignored)         | Always sellable |
- * | true           | false             | Cannot sell when stock <= 0 |
- * | true           | true              | Can sell (back-order), shows warning |
- */
-export interface ProductDto {
-  /** UUID */
-  id: string;
-  /** Stock Keeping Unit (e.g., "COKE-330") */
-  sku: string;
-  /** Barcode (EAN-13, UPC, etc.) */
-  barcode: string | null;
-  /** Display name */
-  name: string;
-  /** Optional description */
-  description: string | null;
-  /** Price in cents (integer!) */
-  priceCents: number;
-  /** Tax rate in basis points (825 = 8.25%) */
-  taxRateBps: number;
-  /** Whether inventory is tracked */
-  trackInventory: boolean;
-  /** Whether selling is allowed when stock is 0 or negative (back-order) */
-  allowNegativeStock: boolean;
-  /** Current stock level (if tracked) */
-  currentStock: number | null;
-  /** Whether product is available for sale */
-  isActive: boolean;
-}
+export type { ProductDto } from '../bindings/ProductDto';
+export type { ProductAttribute } from '../bindings/ProductAttribute';
+export type { ProductAttributesDto } from '../bindings/ProductAttributesDto';
+export type { AttributeFilter } from '../bindings/AttributeFilter';
+export type { AttributePromotion } from '../bindings/AttributePromotion';
+export type { ProductStyle } from '../bindings/ProductStyle';
+export type { VariantMatrix } from '../bindings/VariantMatrix';
+export type { VariantDto } from '../bindings/VariantDto';
+export type { VariantGroupDto } from '../bindings/VariantGroupDto';
+export type { ProductSearchResultDto } from '../bindings/ProductSearchResultDto';
+export type { BundleComponent } from '../bindings/BundleComponent';
+export type { BundleComponentDto } from '../bindings/BundleComponentDto';
+export type { ProductBundleDto } from '../bindings/ProductBundleDto';
+export type { ProductPack } from '../bindings/ProductPack';
+export type { PackQuantity } from '../bindings/PackQuantity';
+export type { ReceivingLine } from '../bindings/ReceivingLine';
+export type { StockReceiptLine } from '../bindings/StockReceiptLine';
+export type { ProductPacksDto } from '../bindings/ProductPacksDto';
+export type { PackLookupDto } from '../bindings/PackLookupDto';
+export type { StockReceiptDto } from '../bindings/StockReceiptDto';
+export type { StockLevelDto } from '../bindings/StockLevelDto';
+export type { WasteReason } from '../bindings/WasteReason';
+export type { WasteReasonSummary } from '../bindings/WasteReasonSummary';
+export type { WasteRecordDto } from '../bindings/WasteRecordDto';
+export type { WasteReportDto } from '../bindings/WasteReportDto';
+export type { Supplier } from '../bindings/Supplier';
+export type { SupplierItem } from '../bindings/SupplierItem';
+export type { SupplierDto } from '../bindings/SupplierDto';
+export type { ProductCost } from '../bindings/ProductCost';
+export type { ProductUpdateDto } from '../bindings/ProductUpdateDto';
+export type { MarginGuardrail } from '../bindings/MarginGuardrail';
+export type { GuardrailAction } from '../bindings/GuardrailAction';
+export type { MarginCheck } from '../bindings/MarginCheck';
+export type { MarginVerdict } from '../bindings/MarginVerdict';
+export type { ManagerBypass } from '../bindings/ManagerBypass';
+export type { MarginOverride } from '../bindings/MarginOverride';
+export type { DrawerOpen } from '../bindings/DrawerOpen';
+export type { KickPin } from '../bindings/KickPin';
+export type { ScaleStatus } from '../bindings/ScaleStatus';
+export type { WeightReading } from '../bindings/WeightReading';
+export type { WeighRules } from '../bindings/WeighRules';
+export type { DisplayFrame } from '../bindings/DisplayFrame';
+export type { DisplayProtocol } from '../bindings/DisplayProtocol';
+export type { Symbology } from '../bindings/Symbology';
+export type { OverrideAction } from '../bindings/OverrideAction';
+export type { OverrideClaims } from '../bindings/OverrideClaims';
+export type { ManagerOverride } from '../bindings/ManagerOverride';
+export type { IssuedOverride } from '../bindings/IssuedOverride';
+export type { PriceChangeKind } from '../bindings/PriceChangeKind';
+export type { DepartmentConfig } from '../bindings/DepartmentConfig';
+export type { DepartmentKey } from '../bindings/DepartmentKey';
+export type { TaxGroup } from '../bindings/TaxGroup';
+export type { DepartmentLine } from '../bindings/DepartmentLine';
 
 // ─────────────────────────────────────────────────────────────────────────────
-// Cart Types
+// Pagination Types
 // ─────────────────────────────────────────────────────────────────────────────
 
-/**
- * Cart item (product snapshot in cart).
- */
-export interface CartItem {
-  /** Product UUID */
-  productId: string;
-  /** SKU (frozen at time of adding) */
-  sku: string;
-  /** Name (frozen at time of adding) */
-  name: string;
-  /** Unit price in cents (frozen at time of adding) */
-  unitPriceCents: number;
-  /** Tax rate in basis points (frozen) */
-  taxRateBps: number;
-  /** Quantity in cart */
-  quantity: number;
-  /** When added to cart */
-  addedAt: string;
-}
+export type { Page } from '../bindings/Page';
 
-/**
- * Cart totals calculated by the backend.
- */
-export interface CartTotals {
-  /** Number of unique items */
-  itemCount: number;
-  /** Total quantity of all items */
-  totalQuantity: number;
-  /** Subtotal before tax (cents) */
-  subtotalCents: number;
-  /** Total tax amount (cents) */
-  taxCents: number;
-  /** Grand total (cents) */
-  totalCents: number;
-}
+// ─────────────────────────────────────────────────────────────────────────────
+// Cart Types
+// ─────────────────────────────────────────────────────────────────────────────
 
-/**
- * Full cart response from backend.
- */
-export interface CartResponse {
-  items: CartItem[];
-  totals: CartTotals;
-}
+export type { CartItem } from '../bindings/CartItem';
+export type { CartTotals } from '../bindings/CartTotals';
+export type { LineQuantity } from '../bindings/LineQuantity';
+export type { CartResponse } from '../bindings/CartResponse';
+export type { LinePriceResponse } from '../bindings/LinePriceResponse';
+export type { AgeVerificationDto } from '../bindings/AgeVerificationDto';
+export type { TrackedSaleDto } from '../bindings/TrackedSaleDto';
 
 // ─────────────────────────────────────────────────────────────────────────────
 // Sale Types
 // ─────────────────────────────────────────────────────────────────────────────
 
-/**
- * Response after creating a sale.
- */
-export interface CreateSaleResponse {
-  saleId: string;
-  totalCents: number;
-  itemCount: number;
-}
+export type { CreateSaleResponse } from '../bindings/CreateSaleResponse';
+export type { AddPaymentResponse } from '../bindings/AddPaymentResponse';
+export type { ReceiptItem } from '../bindings/ReceiptItem';
+export type { ReceiptPayment } from '../bindings/ReceiptPayment';
+export type { ReceiptResponse } from '../bindings/ReceiptResponse';
+export type { ReceiptVariant } from '../bindings/ReceiptVariant';
+export type { ReceiptPrintJobDto } from '../bindings/ReceiptPrintJobDto';
+export type { ReceiptCampaignsDto } from '../bindings/ReceiptCampaignsDto';
+export type { ReceiptCampaign } from '../bindings/ReceiptCampaign';
+export type { CampaignContent } from '../bindings/CampaignContent';
+export type { CampaignCondition } from '../bindings/CampaignCondition';
+export type { IssuedCoupon } from '../bindings/IssuedCoupon';
+export type { FiscalSignatureDto } from '../bindings/FiscalSignatureDto';
+export type { SaleStatus } from '../bindings/SaleStatus';
+export type { SaleSummaryDto } from '../bindings/SaleSummaryDto';
+export type { SaleDocument } from '../bindings/SaleDocument';
 
-/**
- * Response after adding a payment.
- */
-export interface AddPaymentResponse {
-  paymentId: string;
-  amountCents: number;
-  totalPaidCents: number;
-  remainingCents: number;
-  changeCents: number;
-}
+// ─────────────────────────────────────────────────────────────────────────────
+// Quote, Layaway and Transfer Types
+// ─────────────────────────────────────────────────────────────────────────────
 
-/**
- * Receipt item for display.
- */
-export interface ReceiptItem {
-  name: string;
-  quantity: number;
-  unitPriceCents: number;
-  lineTotalCents: number;
-}
+export type { QuoteItemDto } from '../bindings/QuoteItemDto';
+export type { QuoteDto } from '../bindings/QuoteDto';
+export type { QuoteDocumentDto } from '../bindings/QuoteDocumentDto';
+export type { LayawayItemDto } from '../bindings/LayawayItemDto';
+export type { LayawayPaymentDto } from '../bindings/LayawayPaymentDto';
+export type { LayawayDto } from '../bindings/LayawayDto';
+export type { TransferLineInput } from '../bindings/TransferLineInput';
+export type { TransferCountInput } from '../bindings/TransferCountInput';
+export type { TransferItemDto } from '../bindings/TransferItemDto';
+export type { TransferDto } from '../bindings/TransferDto';
 
-/**
- * Receipt payment entry.
- */
-export interface ReceiptPayment {
-  method: string;
-  amountCents: number;
-}
+// ─────────────────────────────────────────────────────────────────────────────
+// Store Credit and E-Invoice Types
+// ─────────────────────────────────────────────────────────────────────────────
 
-/**
- * Full receipt data after finalizing sale.
- */
-export interface ReceiptResponse {
-  saleId: string;
-  receiptNumber: string;
-  storeName: string;
-  timestamp: string;
-  items: ReceiptItem[];
-  subtotalCents: number;
-  taxCents: number;
-  totalCents: number;
-  payments: ReceiptPayment[];
-  changeCents: number;
-}
+export type { StoreCreditEntryDto } from '../bindings/StoreCreditEntryDto';
+export type { StoreCreditDto } from '../bindings/StoreCreditDto';
+export type { RefundResponse } from '../bindings/RefundResponse';
+export type { BusinessCustomerDto } from '../bindings/BusinessCustomerDto';
+export type { EInvoiceDto } from '../bindings/EInvoiceDto';
+export type { EInvoiceBatchDto } from '../bindings/EInvoiceBatchDto';
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Coupon Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+export type { Coupon } from '../bindings/Coupon';
+export type { CouponKind } from '../bindings/CouponKind';
+export type { CouponRefreshDto } from '../bindings/CouponRefreshDto';
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Loyalty Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+export type { LoyaltyDto } from '../bindings/LoyaltyDto';
+export type { LoyaltyEntry } from '../bindings/LoyaltyEntry';
+export type { LoyaltyEntryKind } from '../bindings/LoyaltyEntryKind';
+export type { LoyaltyProgram } from '../bindings/LoyaltyProgram';
+export type { LoyaltyTier } from '../bindings/LoyaltyTier';
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Till Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+export type { TillSessionDto } from '../bindings/TillSessionDto';
+export type { BlindCloseResponse } from '../bindings/BlindCloseResponse';
+export type { DenominationCountInput } from '../bindings/DenominationCountInput';
+export type { VarianceExceptionDto } from '../bindings/VarianceExceptionDto';
+export type { ChangeBreakdownDto } from '../bindings/ChangeBreakdownDto';
 
 // ─────────────────────────────────────────────────────────────────────────────
 // Config Types
 // ─────────────────────────────────────────────────────────────────────────────
 
-/**
- * Tax calculation mode.
- */
-export type TaxMode = 'exclusive' | 'inclusive';
+export type { TaxMode } from '../bindings/TaxMode';
+export type { ConfigState } from '../bindings/ConfigState';
+export type { PrinterConfig } from '../bindings/PrinterConfig';
+export type { PrinterType } from '../bindings/PrinterType';
+export type { UpdateChannel } from '../bindings/UpdateChannel';
+export type { AppProfile } from '../bindings/AppProfile';
+export type { StationConfig } from '../bindings/StationConfig';
+export type { TrainingModeDto } from '../bindings/TrainingModeDto';
+export type { WindowDto } from '../bindings/WindowDto';
+export type { StartupStatus } from '../bindings/StartupStatus';
+export type { CrashKind } from '../bindings/CrashKind';
+export type { CrashReport } from '../bindings/CrashReport';
+export type { CrashReportDto } from '../bindings/CrashReportDto';
+export type { CrashUploadDto } from '../bindings/CrashUploadDto';
 
-/**
- * Application configuration.
- */
-export interface ConfigState {
-  tenantId: string;
-  storeName: string;
-  storeAddress: string[];
-  currencyCode: string;
-  currencySymbol: string;
-  currencyDecimals: number;
-  defaultTaxRateBps: number;
-  taxMode: TaxMode;
-  soundEnabled: boolean;
-}
+// ─────────────────────────────────────────────────────────────────────────────
+// Sync Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+export type { SyncStatusDto } from '../bindings/SyncStatusDto';
+export type { SyncConfigDto } from '../bindings/SyncConfigDto';
+export type { SyncHistoryDto } from '../bindings/SyncHistoryDto';
+export type { AckLevel } from '../bindings/AckLevel';
+export type { OutboxAckCounts } from '../bindings/OutboxAckCounts';
+export type { OutboxEntryStatus } from '../bindings/OutboxEntryStatus';
+export type { HopResultDto } from '../bindings/HopResultDto';
+export type { ConnectivityReportDto } from '../bindings/ConnectivityReportDto';
+export type { SyncProgressEvent } from '../bindings/SyncProgressEvent';
+export type { SyncErrorEvent } from '../bindings/SyncErrorEvent';
+export type { SyncUpgradeRequiredEvent } from '../bindings/SyncUpgradeRequiredEvent';
 
 // ─────────────────────────────────────────────────────────────────────────────
-// Error Types
+// Job Types
 // ─────────────────────────────────────────────────────────────────────────────
 
-/**
- * API error from Tauri commands.
- */
-export interface ApiError {
-  code: ErrorCode;
-  message: string;
-}
+export type { ScheduledJob } from '../bindings/ScheduledJob';
+export type { JobRun } from '../bindings/JobRun';
+export type { JobRunStatus } from '../bindings/JobRunStatus';
+export type { JobAlertEvent } from '../bindings/JobAlertEvent';
 
-/**
- * Error codes returned by the backend.
- */
-export type ErrorCode =
-  | 'NOT_FOUND'
-  | 'VALIDATION_ERROR'
-  | 'DATABASE_ERROR'
-  | 'BUSINESS_LOGIC'
-  | 'INTERNAL'
-  | 'CART_ERROR'
-  | 'INSUFFICIENT_STOCK'
-  | 'PAYMENT_ERROR';
+// ─────────────────────────────────────────────────────────────────────────────
+// Retention Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+export type { RetentionPolicy } from '../bindings/RetentionPolicy';
+export type { PrunedSale } from '../bindings/PrunedSale';
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Day-End Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+export type { DayEndReport } from '../bindings/DayEndReport';
+export type { DayEndStep } from '../bindings/DayEndStep';
+export type { DayEndStepResult } from '../bindings/DayEndStepResult';
+export type { StepOutcome } from '../bindings/StepOutcome';
+export type { ZReport } from '../bindings/ZReport';
+export type { PaymentTotal } from '../bindings/PaymentTotal';
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Label Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+export type { QueuedLabelDto } from '../bindings/QueuedLabelDto';
+export type { LabelTemplateDto } from '../bindings/LabelTemplateDto';
+export type { LabelPrintJobDto } from '../bindings/LabelPrintJobDto';
+export type { LabelFormat } from '../bindings/LabelFormat';
+export type { LabelReason } from '../bindings/LabelReason';
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Update Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+export type { UpdateCheckDto } from '../bindings/UpdateCheckDto';
+export type { ReleaseDto } from '../bindings/ReleaseDto';
+export type { DownloadedUpdateDto } from '../bindings/DownloadedUpdateDto';
+export type { UpdateSlotDto } from '../bindings/UpdateSlotDto';
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Feature Flag Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+export type { FeatureFlagsDto } from '../bindings/FeatureFlagsDto';
+export type { FeatureFlagDto } from '../bindings/FeatureFlagDto';
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Report Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+export type { TaxReportDto } from '../bindings/TaxReportDto';
+export type { TaxRateSummaryDto } from '../bindings/TaxRateSummaryDto';
+export type { TaxReportExportDto } from '../bindings/TaxReportExportDto';
+export type { ProductMargin } from '../bindings/ProductMargin';
+export type { MarginStats } from '../bindings/MarginStats';
+export type { DashboardStatsDto } from '../bindings/DashboardStatsDto';
+export type { DepartmentSalesRow } from '../bindings/DepartmentSalesRow';
+export type { DepartmentSalesReport } from '../bindings/DepartmentSalesReport';
+export type { DepartmentSalesDto } from '../bindings/DepartmentSalesDto';
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Entity Events
+// ─────────────────────────────────────────────────────────────────────────────
+
+export type { EntityEvent } from '../bindings/EntityEvent';
+export type { ProductChange } from '../bindings/ProductChange';
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Privacy Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+export type { ErasureCounts } from '../bindings/ErasureCounts';
+export type { ErasureOrigin } from '../bindings/ErasureOrigin';
+export type { ErasureRecord } from '../bindings/ErasureRecord';
+export type { ErasureRequest } from '../bindings/ErasureRequest';
+export type { ErasureSubject } from '../bindings/ErasureSubject';
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Error Types
+// ─────────────────────────────────────────────────────────────────────────────
+
+export type { ApiError } from '../bindings/ApiError';
+export type { ErrorCode } from '../bindings/ErrorCode';
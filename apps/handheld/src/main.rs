@@ -0,0 +1,417 @@
+//! # Titan Handheld
+//!
+//! Headless inventory client for small Linux devices with a barcode
+//! scanner. It reads one scan or command per line from stdin (scanners in
+//! keyboard-wedge mode type the code followed by Enter), keeps a local
+//! SQLite copy of the catalog, and joins the Store Hub as a SECONDARY.
+//!
+//! ## Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                          Handheld Flow                                  │
+//! │                                                                         │
+//! │  scan ──► barcode, then SKU ──► local catalog (titan-db)                │
+//! │                                      │                                  │
+//! │         ┌────────────────────────────┼───────────────────────┐          │
+//! │         ▼                            ▼                       ▼          │
+//! │      Lookup                        Count                  Labels        │
+//! │   price + stock              counted vs expected       label queue      │
+//! │                                      │                       │          │
+//! │                             :commit  ▼              :export  ▼          │
+//! │                    InventoryDelta ──► Hub          labels CSV file      │
+//! │                    (then local stock)                                   │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! The catalog arrives through the hub like on any terminal; a new device
+//! starts empty, so run `:resync` once it is connected. Count corrections
+//! need the hub: when it is unreachable `:commit` refuses and the count is
+//! kept for later.
+//!
+//! ## Usage
+//! ```bash
+//! titan-handheld --db ./data/handheld.db --config ./sync.toml
+//! ```
+
+mod session;
+
+use std::env;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tracing::{info, warn};
+use tracing_subscriber::EnvFilter;
+
+use titan_core::{Money, Product};
+use titan_db::{Database, DbConfig};
+use titan_sync::{SyncAgent, SyncAgentHandle, SyncConfig, SyncMode};
+
+use session::{parse_line, CountSession, Input, LabelQueue, Mode, HELP};
+
+/// Where `:export` writes labels when no path is given.
+const DEFAULT_LABELS_PATH: &str = "./labels.csv";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Logs go to stderr so they do not interleave with scan results
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")),
+        )
+        .with_writer(std::io::stderr)
+        .init();
+
+    let args: Vec<String> = env::args().collect();
+    let mut db_path = String::from("./data/handheld.db");
+    let mut config_path: Option<PathBuf> = None;
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--db" | "-d" if i + 1 < args.len() => {
+                db_path = args[i + 1].clone();
+                i += 1;
+            }
+            "--config" | "-c" if i + 1 < args.len() => {
+                config_path = Some(PathBuf::from(&args[i + 1]));
+                i += 1;
+            }
+            "--help" | "-h" => {
+                println!("Titan POS Handheld");
+                println!();
+                println!("Usage: titan-handheld [OPTIONS]");
+                println!();
+                println!("Options:");
+                println!(
+                    "  -d, --db <PATH>          Database file path (default: ./data/handheld.db)"
+                );
+                println!(
+                    "  -c, --config <PATH>      Sync config file (default: standard location)"
+                );
+                println!("  -h, --help               Show this help message");
+                println!();
+                println!("{}", HELP);
+                return Ok(());
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    if let Some(dir) = PathBuf::from(&db_path).parent() {
+        if !dir.as_os_str().is_empty() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+    }
+    let db = Arc::new(Database::new(DbConfig::new(&db_path)).await?);
+
+    // A handheld never serves other terminals
+    let mut config = SyncConfig::load_or_default(config_path);
+    config.sync.mode = SyncMode::Secondary;
+
+    let mut agent = SyncAgent::new(config, db.clone());
+    if let Err(e) = agent.start().await {
+        warn!(error = %e, "Sync agent did not start, working offline");
+    }
+
+    let mut app = Handheld {
+        db,
+        sync: agent.handle(),
+        mode: Mode::Lookup,
+        count: CountSession::default(),
+        labels: LabelQueue::default(),
+    };
+    info!(db = %db_path, "Handheld ready");
+
+    println!(
+        "Titan POS Handheld ({} products). Type :help for commands.",
+        app.product_count().await
+    );
+    app.prompt();
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    while let Some(line) = lines.next_line().await? {
+        match parse_line(&line) {
+            Ok(Some(Input::Quit)) => break,
+            Ok(Some(input)) => app.handle(input).await,
+            Ok(None) => {}
+            Err(message) => println!("! {}", message),
+        }
+        app.prompt();
+    }
+
+    if !app.count.is_empty() {
+        println!(
+            "! Uncommitted count discarded ({} products)",
+            app.count.lines().len()
+        );
+    }
+    if let Some(sync) = &app.sync {
+        sync.shutdown().await;
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Handheld
+// =============================================================================
+
+struct Handheld {
+    db: Arc<Database>,
+    /// None when the sync agent is not running.
+    sync: Option<SyncAgentHandle>,
+    mode: Mode,
+    count: CountSession,
+    labels: LabelQueue,
+}
+
+impl Handheld {
+    fn prompt(&self) {
+        use std::io::Write;
+        print!("[{}] > ", self.mode);
+        let _ = std::io::stdout().flush();
+    }
+
+    async fn product_count(&self) -> i64 {
+        self.db.products().count().await.unwrap_or(0)
+    }
+
+    async fn handle(&mut self, input: Input) {
+        match input {
+            Input::Scan { code, quantity } => self.scan(&code, quantity).await,
+            Input::SetMode(mode) => {
+                self.mode = mode;
+                println!("Mode: {}", mode);
+            }
+            Input::List => self.list(),
+            Input::Undo => self.undo(),
+            Input::Commit => self.commit().await,
+            Input::Export(path) => self.export(path).await,
+            Input::Clear => match self.mode {
+                Mode::Count => {
+                    self.count.clear();
+                    println!("Count cleared");
+                }
+                Mode::Labels => {
+                    self.labels.clear();
+                    println!("Label queue cleared");
+                }
+                Mode::Lookup => println!("! Nothing to clear in lookup mode"),
+            },
+            Input::Resync => self.resync().await,
+            Input::Status => self.status().await,
+            Input::Help => println!("{}", HELP),
+            Input::Quit => {}
+        }
+    }
+
+    /// Finds a product by barcode, then by SKU.
+    async fn find(&self, code: &str) -> Option<Product> {
+        let products = self.db.products();
+        let found = match products.get_by_barcode(code).await {
+            Ok(Some(product)) => Some(product),
+            Ok(None) => products.get_by_sku(code).await.unwrap_or_else(|e| {
+                warn!(error = %e, "SKU lookup failed");
+                None
+            }),
+            Err(e) => {
+                warn!(error = %e, "Barcode lookup failed");
+                None
+            }
+        };
+        found.filter(|p| p.deleted_at.is_none())
+    }
+
+    async fn scan(&mut self, code: &str, quantity: i64) {
+        let Some(product) = self.find(code).await else {
+            println!("! Not found: {}", code);
+            return;
+        };
+
+        match self.mode {
+            Mode::Lookup => println!(
+                "{}  {}  {}  stock: {}{}",
+                product.sku,
+                product.name,
+                Money::from_cents(product.price_cents),
+                stock_text(&product),
+                if product.is_active {
+                    ""
+                } else {
+                    "  (inactive)"
+                }
+            ),
+            Mode::Count if !product.track_inventory => {
+                println!("! {} does not track stock", product.sku)
+            }
+            Mode::Count => {
+                let line = self.count.record(&product, quantity);
+                println!(
+                    "{}  {}  counted {} (on record {})",
+                    line.sku, line.name, line.counted, line.expected
+                );
+            }
+            Mode::Labels => {
+                let line = self.labels.add(&product, quantity);
+                println!("{}  {}  x{}", line.sku, line.name, line.copies);
+            }
+        }
+    }
+
+    fn list(&self) {
+        match self.mode {
+            Mode::Count if self.count.is_empty() => println!("Nothing counted"),
+            Mode::Count => {
+                for line in self.count.lines() {
+                    println!(
+                        "{}  {}  counted {}  on record {}  diff {:+}",
+                        line.sku,
+                        line.name,
+                        line.counted,
+                        line.expected,
+                        line.delta()
+                    );
+                }
+            }
+            Mode::Labels if self.labels.is_empty() => println!("No labels queued"),
+            Mode::Labels => {
+                for line in self.labels.lines() {
+                    println!(
+                        "{}  {}  {}  x{}",
+                        line.sku,
+                        line.name,
+                        Money::from_cents(line.price_cents),
+                        line.copies
+                    );
+                }
+            }
+            Mode::Lookup => println!("! Nothing to list in lookup mode"),
+        }
+    }
+
+    fn undo(&mut self) {
+        match self.mode {
+            Mode::Count => match self.count.undo() {
+                Some(line) => println!("Undone: {} now counted {}", line.sku, line.counted),
+                None => println!("! Nothing to undo"),
+            },
+            Mode::Labels => match self.labels.undo() {
+                Some(line) => println!("Undone: {} now x{}", line.sku, line.copies),
+                None => println!("! Nothing to undo"),
+            },
+            Mode::Lookup => println!("! Nothing to undo in lookup mode"),
+        }
+    }
+
+    /// Sends each count correction to the hub, then applies it locally.
+    /// Sent products leave the count; the rest stay for another try.
+    async fn commit(&mut self) {
+        if self.mode != Mode::Count {
+            println!("! :commit works in count mode");
+            return;
+        }
+        let corrections = self.count.corrections();
+        if corrections.is_empty() {
+            println!("Count matches stock on record, nothing to send");
+            self.count.clear();
+            return;
+        }
+        let Some(sync) = self.sync.clone() else {
+            println!("! Sync is not running; the count is kept");
+            return;
+        };
+
+        let mut sent = 0;
+        for line in &corrections {
+            let Ok(delta) = i32::try_from(line.delta()) else {
+                println!("! {}: correction {} is too large", line.sku, line.delta());
+                continue;
+            };
+            if let Err(e) = sync
+                .send_inventory_delta(&line.product_id, &line.sku, delta)
+                .await
+            {
+                println!(
+                    "! Hub did not take the count ({}); {} left to send",
+                    e,
+                    corrections.len() - sent
+                );
+                return;
+            }
+            if let Err(e) = self
+                .db
+                .products()
+                .update_stock(&line.product_id, delta)
+                .await
+            {
+                warn!(product_id = %line.product_id, error = %e, "Local stock not updated");
+            }
+            self.count.remove(&line.product_id);
+            sent += 1;
+        }
+
+        // Products counted as on record need no correction
+        if self.count.corrections().is_empty() {
+            self.count.clear();
+        }
+        println!("Sent {} stock corrections", sent);
+    }
+
+    async fn export(&mut self, path: Option<String>) {
+        if self.labels.is_empty() {
+            println!("! No labels queued");
+            return;
+        }
+        let path = path.unwrap_or_else(|| DEFAULT_LABELS_PATH.to_string());
+        match tokio::fs::write(&path, self.labels.to_csv()).await {
+            Ok(()) => {
+                let copies: i64 = self.labels.lines().iter().map(|l| l.copies).sum();
+                println!("Wrote {} labels to {}", copies, path);
+                self.labels.clear();
+            }
+            Err(e) => println!("! Could not write {}: {}", path, e),
+        }
+    }
+
+    async fn resync(&self) {
+        let Some(sync) = &self.sync else {
+            println!("! Sync is not running");
+            return;
+        };
+        match sync.trigger_full_resync().await {
+            Ok(_) => println!("Catalog resync requested"),
+            Err(e) => println!("! Resync failed: {}", e),
+        }
+    }
+
+    async fn status(&self) {
+        let Some(sync) = &self.sync else {
+            println!(
+                "Sync: not running ({} products)",
+                self.product_count().await
+            );
+            return;
+        };
+        let status = sync.status().await;
+        println!(
+            "Hub: {} ({})  products: {}  pending: {}{}",
+            status.hub_url.as_deref().unwrap_or("unknown"),
+            if status.is_connected {
+                "connected"
+            } else {
+                "disconnected"
+            },
+            self.product_count().await,
+            status.pending_count,
+            if status.resyncing { "  resyncing" } else { "" }
+        );
+    }
+}
+
+fn stock_text(product: &Product) -> String {
+    match (product.track_inventory, product.current_stock) {
+        (false, _) => "not tracked".to_string(),
+        (true, stock) => stock.unwrap_or(0).to_string(),
+    }
+}
@@ -0,0 +1,456 @@
+//! # Handheld Session
+//!
+//! What the operator is doing between scans: the input parser, the count
+//! being built up and the label queue. No I/O happens here; `main` looks
+//! products up and talks to the hub.
+//!
+//! ## Input
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                          One line per scan                              │
+//! │                                                                         │
+//! │  5000112637922        scan (barcode or SKU), quantity 1                 │
+//! │  12*5000112637922     scan with quantity 12 (a full case)               │
+//! │  :count               command (see HELP)                                │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use titan_core::Product;
+
+/// Largest quantity one scan line may carry.
+pub const MAX_SCAN_QUANTITY: i64 = 9_999;
+
+/// Command reference printed by `:help`.
+pub const HELP: &str = "\
+Scan a barcode or SKU; prefix N* to scan N units (12*5000112637922).
+  :lookup           show stock and price for each scan (default)
+  :count            count stock; :commit sends the differences to the hub
+  :labels           queue shelf labels; :export writes them to CSV
+  :list             show the current count or label queue
+  :undo             take back the last scan
+  :commit           send count corrections to the hub
+  :export [PATH]    write the label queue to CSV
+  :clear            discard the current count or label queue
+  :resync           fetch the whole catalog from the hub again
+  :status           show the hub connection
+  :help             show this help
+  :quit             exit";
+
+// =============================================================================
+// Input
+// =============================================================================
+
+/// What the scanner is used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Show stock and price.
+    Lookup,
+    /// Build a stock count.
+    Count,
+    /// Queue shelf labels.
+    Labels,
+}
+
+impl std::fmt::Display for Mode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Mode::Lookup => write!(f, "lookup"),
+            Mode::Count => write!(f, "count"),
+            Mode::Labels => write!(f, "labels"),
+        }
+    }
+}
+
+/// One parsed input line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    Scan { code: String, quantity: i64 },
+    SetMode(Mode),
+    List,
+    Undo,
+    Commit,
+    Export(Option<String>),
+    Clear,
+    Resync,
+    Status,
+    Help,
+    Quit,
+}
+
+/// Parses one line. Blank lines give `Ok(None)`.
+pub fn parse_line(line: &str) -> Result<Option<Input>, String> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+
+    if let Some(command) = line.strip_prefix(':') {
+        let mut words = command.split_whitespace();
+        let name = words.next().unwrap_or_default().to_ascii_lowercase();
+        let arg = words.next().map(str::to_string);
+        let input = match name.as_str() {
+            "lookup" | "l" => Input::SetMode(Mode::Lookup),
+            "count" | "c" => Input::SetMode(Mode::Count),
+            "labels" | "label" => Input::SetMode(Mode::Labels),
+            "list" | "ls" => Input::List,
+            "undo" | "u" => Input::Undo,
+            "commit" => Input::Commit,
+            "export" => Input::Export(arg),
+            "clear" => Input::Clear,
+            "resync" => Input::Resync,
+            "status" => Input::Status,
+            "help" | "h" | "?" => Input::Help,
+            "quit" | "q" | "exit" => Input::Quit,
+            _ => return Err(format!("Unknown command :{} (try :help)", name)),
+        };
+        return Ok(Some(input));
+    }
+
+    let (quantity, code) = match line.split_once('*') {
+        Some((quantity, code)) => {
+            let quantity: i64 = quantity
+                .trim()
+                .parse()
+                .map_err(|_| format!("Bad quantity '{}'", quantity.trim()))?;
+            if !(1..=MAX_SCAN_QUANTITY).contains(&quantity) {
+                return Err(format!("Quantity must be 1 to {}", MAX_SCAN_QUANTITY));
+            }
+            (quantity, code.trim())
+        }
+        None => (1, line),
+    };
+    if code.is_empty() {
+        return Err("Nothing scanned".to_string());
+    }
+
+    Ok(Some(Input::Scan {
+        code: code.to_string(),
+        quantity,
+    }))
+}
+
+// =============================================================================
+// Count Session
+// =============================================================================
+
+/// Units of one product counted so far.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CountLine {
+    pub product_id: String,
+    pub sku: String,
+    pub name: String,
+    /// Stock on record when the product was first scanned.
+    pub expected: i64,
+    pub counted: i64,
+}
+
+impl CountLine {
+    /// Correction to apply to the stock on record.
+    pub fn delta(&self) -> i64 {
+        self.counted - self.expected
+    }
+}
+
+/// A stock count in progress.
+///
+/// The expected stock is taken at the first scan of each product, so sales
+/// rung up while the shelf is being counted are not lost: the correction
+/// only covers the difference seen at that moment.
+#[derive(Debug, Default)]
+pub struct CountSession {
+    lines: Vec<CountLine>,
+    /// (product_id, quantity) of every scan, newest last, for undo.
+    scans: Vec<(String, i64)>,
+}
+
+impl CountSession {
+    /// Adds `quantity` units of `product` to the count.
+    pub fn record(&mut self, product: &Product, quantity: i64) -> &CountLine {
+        self.scans.push((product.id.clone(), quantity));
+        let index = match self.lines.iter().position(|l| l.product_id == product.id) {
+            Some(index) => index,
+            None => {
+                self.lines.push(CountLine {
+                    product_id: product.id.clone(),
+                    sku: product.sku.clone(),
+                    name: product.name.clone(),
+                    expected: product.current_stock.unwrap_or(0),
+                    counted: 0,
+                });
+                self.lines.len() - 1
+            }
+        };
+        let line = &mut self.lines[index];
+        line.counted += quantity;
+        line
+    }
+
+    /// Takes back the last scan. Returns the line as it is now, or `None`
+    /// if nothing was scanned; a product with no scans left is dropped.
+    pub fn undo(&mut self) -> Option<CountLine> {
+        let (product_id, quantity) = self.scans.pop()?;
+        let index = self.lines.iter().position(|l| l.product_id == product_id)?;
+        self.lines[index].counted -= quantity;
+
+        if self.scans.iter().any(|(id, _)| *id == product_id) {
+            Some(self.lines[index].clone())
+        } else {
+            let mut line = self.lines.remove(index);
+            line.counted = 0;
+            Some(line)
+        }
+    }
+
+    /// Counted products, in scan order.
+    pub fn lines(&self) -> &[CountLine] {
+        &self.lines
+    }
+
+    /// Lines whose count differs from the stock on record.
+    pub fn corrections(&self) -> Vec<CountLine> {
+        self.lines
+            .iter()
+            .filter(|l| l.delta() != 0)
+            .cloned()
+            .collect()
+    }
+
+    /// Drops a product once its correction is sent.
+    pub fn remove(&mut self, product_id: &str) {
+        self.lines.retain(|l| l.product_id != product_id);
+        self.scans.retain(|(id, _)| id != product_id);
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.scans.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+}
+
+// =============================================================================
+// Label Queue
+// =============================================================================
+
+/// Shelf labels to print for one product.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelLine {
+    pub product_id: String,
+    pub sku: String,
+    pub barcode: Option<String>,
+    pub name: String,
+    pub price_cents: i64,
+    pub copies: i64,
+}
+
+/// Labels waiting to be exported for printing.
+#[derive(Debug, Default)]
+pub struct LabelQueue {
+    lines: Vec<LabelLine>,
+    /// (product_id, copies) of every scan, newest last, for undo.
+    scans: Vec<(String, i64)>,
+}
+
+impl LabelQueue {
+    /// Queues `copies` labels for `product` at its current price.
+    pub fn add(&mut self, product: &Product, copies: i64) -> &LabelLine {
+        self.scans.push((product.id.clone(), copies));
+        let index = match self.lines.iter().position(|l| l.product_id == product.id) {
+            Some(index) => index,
+            None => {
+                self.lines.push(LabelLine {
+                    product_id: product.id.clone(),
+                    sku: product.sku.clone(),
+                    barcode: product.barcode.clone(),
+                    name: product.name.clone(),
+                    price_cents: product.price_cents,
+                    copies: 0,
+                });
+                self.lines.len() - 1
+            }
+        };
+        let line = &mut self.lines[index];
+        line.copies += copies;
+        line
+    }
+
+    /// Takes back the last scan (see [`CountSession::undo`]).
+    pub fn undo(&mut self) -> Option<LabelLine> {
+        let (product_id, copies) = self.scans.pop()?;
+        let index = self.lines.iter().position(|l| l.product_id == product_id)?;
+        self.lines[index].copies -= copies;
+
+        if self.lines[index].copies > 0 {
+            Some(self.lines[index].clone())
+        } else {
+            Some(self.lines.remove(index))
+        }
+    }
+
+    pub fn lines(&self) -> &[LabelLine] {
+        &self.lines
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.scans.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// The queue as CSV for the label printer's software, one row per
+    /// product with a `copies` column.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("sku,barcode,name,price,copies\n");
+        for line in &self.lines {
+            csv.push_str(&format!(
+                "{},{},{},{}.{:02},{}\n",
+                csv_field(&line.sku),
+                csv_field(line.barcode.as_deref().unwrap_or("")),
+                csv_field(&line.name),
+                line.price_cents / 100,
+                line.price_cents % 100,
+                line.copies
+            ));
+        }
+        csv
+    }
+}
+
+/// Quotes a field holding a comma, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use titan_core::ItemTracking;
+
+    fn product(id: &str, stock: i64) -> Product {
+        let now = Utc::now();
+        Product {
+            id: id.to_string(),
+            tenant_id: "default".to_string(),
+            sku: format!("SKU-{}", id),
+            barcode: Some(format!("500{}", id)),
+            name: format!("Product {}", id),
+            description: None,
+            price_cents: 1_299,
+            base_price_cents: None,
+            cost_cents: None,
+            tax_rate_bps: 0,
+            track_inventory: true,
+            allow_negative_stock: false,
+            current_stock: Some(stock),
+            item_tracking: ItemTracking::default(),
+            min_purchase_age: None,
+            is_active: true,
+            deleted_at: None,
+            created_at: now,
+            updated_at: now,
+            sync_version: 1,
+        }
+    }
+
+    #[test]
+    fn test_parse_scans_and_commands() {
+        assert_eq!(parse_line("   ").unwrap(), None);
+        assert_eq!(
+            parse_line("5000112637922").unwrap(),
+            Some(Input::Scan {
+                code: "5000112637922".to_string(),
+                quantity: 1
+            })
+        );
+        assert_eq!(
+            parse_line("12 * COKE-330").unwrap(),
+            Some(Input::Scan {
+                code: "COKE-330".to_string(),
+                quantity: 12
+            })
+        );
+        assert_eq!(
+            parse_line(":COUNT").unwrap(),
+            Some(Input::SetMode(Mode::Count))
+        );
+        assert_eq!(
+            parse_line(":export /tmp/labels.csv").unwrap(),
+            Some(Input::Export(Some("/tmp/labels.csv".to_string())))
+        );
+
+        assert!(parse_line(":frobnicate").is_err());
+        assert!(parse_line("0*COKE").is_err());
+        assert!(parse_line("x*COKE").is_err());
+        assert!(parse_line("3*").is_err());
+    }
+
+    #[test]
+    fn test_count_corrections_use_stock_at_first_scan() {
+        let mut count = CountSession::default();
+        let a = product("1", 10);
+        let b = product("2", 4);
+
+        count.record(&a, 6);
+        count.record(&b, 4);
+        // Stock moved after the first scan: the expectation stays
+        count.record(&product("1", 8), 1);
+
+        assert_eq!(count.lines()[0].counted, 7);
+        let corrections = count.corrections();
+        assert_eq!(corrections.len(), 1);
+        assert_eq!(corrections[0].product_id, "1");
+        assert_eq!(corrections[0].delta(), -3);
+
+        count.remove("1");
+        assert!(count.corrections().is_empty());
+        assert_eq!(count.lines().len(), 1);
+    }
+
+    #[test]
+    fn test_count_undo() {
+        let mut count = CountSession::default();
+        let a = product("1", 10);
+
+        count.record(&a, 1);
+        count.record(&a, 12);
+        assert_eq!(count.undo().unwrap().counted, 1);
+        assert_eq!(count.undo().unwrap().counted, 0);
+        assert!(count.is_empty());
+        assert!(count.undo().is_none());
+    }
+
+    #[test]
+    fn test_label_queue_and_csv() {
+        let mut labels = LabelQueue::default();
+        let mut a = product("1", 0);
+        a.name = "Chips, \"Salted\"".to_string();
+
+        labels.add(&a, 2);
+        labels.add(&a, 1);
+        labels.add(&product("2", 0), 1);
+        assert_eq!(labels.lines()[0].copies, 3);
+
+        assert_eq!(labels.undo().unwrap().copies, 0);
+        assert_eq!(labels.lines().len(), 1);
+
+        assert_eq!(
+            labels.to_csv(),
+            "sku,barcode,name,price,copies\nSKU-1,5001,\"Chips, \"\"Salted\"\"\",12.99,3\n"
+        );
+    }
+}
@@ -0,0 +1,230 @@
+//! # Age Verification
+//!
+//! Rules for selling age-restricted products (alcohol, tobacco, solvents).
+//! A product carries a minimum purchase age; a sale containing one cannot be
+//! finalized until the cashier records how the customer's age was checked.
+//!
+//! ## Verification Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                     Age-Restricted Sale                                 │
+//! │                                                                         │
+//! │  Cart: Beer 6-pack (min age 21), Chips (none)                           │
+//! │    required_age = max(min_purchase_age of all lines) = 21               │
+//! │                                                                         │
+//! │  finalize_sale ──► no verification ──► BLOCK (prompt cashier)           │
+//! │                                                                         │
+//! │  verify_customer_age                                                    │
+//! │    Birthdate  - cashier types the date of birth; age computed here     │
+//! │    IdScan     - ID scanned at the counter; birthdate optional           │
+//! │                                                                         │
+//! │  finalize_sale ──► verification.required_age >= 21 ──► COMPLETE         │
+//! │                                                                         │
+//! │  The verification row stays with the sale for compliance audits.        │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::{CoreError, CoreResult, ValidationError};
+use crate::validation::ValidationResult;
+
+/// Highest minimum age a product may be flagged with.
+pub const MAX_PURCHASE_AGE: u32 = 99;
+
+// =============================================================================
+// Verification Method
+// =============================================================================
+
+/// How the customer's age was checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "snake_case"))]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum AgeVerificationMethod {
+    /// Cashier entered the customer's date of birth.
+    Birthdate,
+    /// Customer's ID was scanned or visually checked.
+    IdScan,
+}
+
+impl AgeVerificationMethod {
+    /// Name as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AgeVerificationMethod::Birthdate => "birthdate",
+            AgeVerificationMethod::IdScan => "id_scan",
+        }
+    }
+}
+
+// =============================================================================
+// Verification Record
+// =============================================================================
+
+/// Age check recorded against a sale.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AgeVerification {
+    pub sale_id: String,
+    /// Highest minimum age among the sale's items when verified.
+    pub required_age: u32,
+    pub method: AgeVerificationMethod,
+    #[ts(as = "Option<String>")]
+    pub birthdate: Option<NaiveDate>,
+    pub verified_by: String,
+    pub device_id: String,
+    #[ts(as = "String")]
+    pub verified_at: DateTime<Utc>,
+}
+
+impl AgeVerification {
+    /// Whether this check covers a sale that now requires `required_age`.
+    ///
+    /// Items added after the check may raise the requirement (e.g. 18 → 21).
+    pub fn covers(&self, required_age: u32) -> bool {
+        self.required_age >= required_age
+    }
+}
+
+// =============================================================================
+// Rules
+// =============================================================================
+
+/// Highest minimum age among a sale's products, or `None` if unrestricted.
+pub fn required_age<I>(min_ages: I) -> Option<u32>
+where
+    I: IntoIterator<Item = Option<u32>>,
+{
+    min_ages.into_iter().flatten().max()
+}
+
+/// Age in whole years on a given day.
+pub fn age_on(birthdate: NaiveDate, on: NaiveDate) -> u32 {
+    let mut years = on.year() - birthdate.year();
+    if (on.month(), on.day()) < (birthdate.month(), birthdate.day()) {
+        years -= 1;
+    }
+    years.max(0) as u32
+}
+
+/// Validates a product's minimum purchase age.
+pub fn validate_min_purchase_age(min_age: Option<u32>) -> ValidationResult<()> {
+    match min_age {
+        Some(age) if age == 0 || age > MAX_PURCHASE_AGE => Err(ValidationError::OutOfRange {
+            field: "min_purchase_age".to_string(),
+            min: 1,
+            max: MAX_PURCHASE_AGE as i64,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Checks an age verification before it is recorded.
+///
+/// ## Rules
+/// - `Birthdate` requires a birthdate
+/// - A birthdate in the future is rejected
+/// - When a birthdate is known, the customer must be at least `required_age`
+///   on `today`
+pub fn check_age_verification(
+    required_age: u32,
+    method: AgeVerificationMethod,
+    birthdate: Option<NaiveDate>,
+    today: NaiveDate,
+) -> CoreResult<()> {
+    let birthdate = match (method, birthdate) {
+        (AgeVerificationMethod::Birthdate, None) => {
+            return Err(ValidationError::Required {
+                field: "birthdate".to_string(),
+            }
+            .into())
+        }
+        (_, None) => return Ok(()),
+        (_, Some(date)) => date,
+    };
+
+    if birthdate > today {
+        return Err(ValidationError::InvalidFormat {
+            field: "birthdate".to_string(),
+            reason: "date is in the future".to_string(),
+        }
+        .into());
+    }
+
+    let age = age_on(birthdate, today);
+    if age < required_age {
+        return Err(CoreError::AgeRestricted {
+            required_age,
+            reason: format!("customer is {}", age),
+        });
+    }
+
+    Ok(())
+}
+
+// =============================================================================
+// Unit Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_age_on_birthday_boundary() {
+        let born = date(2003, 6, 15);
+        assert_eq!(age_on(born, date(2024, 6, 14)), 20);
+        assert_eq!(age_on(born, date(2024, 6, 15)), 21);
+        assert_eq!(age_on(date(2004, 2, 29), date(2022, 2, 28)), 17);
+        assert_eq!(age_on(date(2004, 2, 29), date(2022, 3, 1)), 18);
+    }
+
+    #[test]
+    fn test_required_age_is_highest_restriction() {
+        assert_eq!(required_age([None, Some(18), Some(21)]), Some(21));
+        assert_eq!(required_age([None, None]), None);
+    }
+
+    #[test]
+    fn test_check_age_verification() {
+        let today = date(2024, 6, 15);
+        let m = AgeVerificationMethod::Birthdate;
+
+        assert!(check_age_verification(21, m, Some(date(2003, 6, 15)), today).is_ok());
+        assert!(matches!(
+            check_age_verification(21, m, Some(date(2003, 6, 16)), today),
+            Err(CoreError::AgeRestricted { required_age: 21, .. })
+        ));
+        assert!(check_age_verification(21, m, None, today).is_err());
+        assert!(check_age_verification(18, m, Some(date(2030, 1, 1)), today).is_err());
+
+        // ID checked visually: no birthdate needed
+        assert!(check_age_verification(21, AgeVerificationMethod::IdScan, None, today).is_ok());
+    }
+
+    #[test]
+    fn test_verification_covers_lower_requirement_only() {
+        let v = AgeVerification {
+            sale_id: "s".to_string(),
+            required_age: 18,
+            method: AgeVerificationMethod::IdScan,
+            birthdate: None,
+            verified_by: "u".to_string(),
+            device_id: "d".to_string(),
+            verified_at: Utc::now(),
+        };
+        assert!(v.covers(18));
+        assert!(!v.covers(21));
+        assert!(validate_min_purchase_age(Some(0)).is_err());
+        assert!(validate_min_purchase_age(Some(21)).is_ok());
+        assert!(validate_min_purchase_age(None).is_ok());
+    }
+}
@@ -0,0 +1,368 @@
+//! # Product Attributes and Tags
+//!
+//! Free-form key-value attributes on products (size, color, vintage, ...)
+//! and plain tags, stored beside the product rather than as product
+//! columns, so a store can describe its range without a schema change.
+//!
+//! ## Model
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                     Product Attributes                                  │
+//! │                                                                         │
+//! │  product WINE-0042 "Rioja Reserva"                                     │
+//! │    color   = red          ◄── attribute (key = value)                  │
+//! │    vintage = 2019                                                      │
+//! │    organic                ◄── tag (key with an empty value)            │
+//! │                                                                         │
+//! │  The whole set is one document (ProductAttributes) with its own        │
+//! │  sync_version: edits replace the set, sync ships the set, and the      │
+//! │  newer version wins on every terminal.                                 │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Rules
+//! - Keys are lowercased and trimmed; letters, digits, `_`, `-` and `.`
+//!   only, at most [`MAX_KEY_LEN`] characters, unique per product
+//! - Values are trimmed, at most [`MAX_VALUE_LEN`] characters; an empty
+//!   value makes the attribute a tag
+//! - At most [`MAX_ATTRIBUTES`] per product
+//!
+//! ## Filters and Promotions
+//! An [`AttributeFilter`] (`color=red`, or `organic` for "has the tag")
+//! selects products by attribute; searches and [`AttributePromotion`]
+//! discount rules are both built on it. Values compare case-insensitively.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::ValidationError;
+use crate::money::Money;
+use crate::validation::ValidationResult;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Longest attribute key.
+pub const MAX_KEY_LEN: usize = 40;
+
+/// Longest attribute value.
+pub const MAX_VALUE_LEN: usize = 100;
+
+/// Most attributes (tags included) one product may carry.
+pub const MAX_ATTRIBUTES: usize = 50;
+
+// =============================================================================
+// Attributes
+// =============================================================================
+
+/// One attribute of a product; a tag when `value` is empty.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProductAttribute {
+    pub key: String,
+    #[serde(default)]
+    pub value: String,
+}
+
+impl ProductAttribute {
+    /// An attribute `key = value`.
+    pub fn new(key: impl Into<String>, value: impl Into<String>) -> Self {
+        ProductAttribute {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+
+    /// A tag (an attribute with no value).
+    pub fn tag(key: impl Into<String>) -> Self {
+        ProductAttribute::new(key, "")
+    }
+
+    /// Whether this attribute is a plain tag.
+    pub fn is_tag(&self) -> bool {
+        self.value.is_empty()
+    }
+}
+
+/// The attributes of one product, as stored in the sync outbox
+/// (`PRODUCT_ATTRIBUTES`) and relayed to other terminals.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProductAttributes {
+    pub product_id: String,
+    /// Sorted by key.
+    pub attributes: Vec<ProductAttribute>,
+    /// Version of the set (independent of the product's own version).
+    pub sync_version: i64,
+    #[ts(as = "String")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ProductAttributes {
+    /// Value of `key`, if the product has it (`Some("")` for a tag).
+    pub fn get(&self, key: &str) -> Option<&str> {
+        find(&self.attributes, key)
+    }
+
+    /// Whether the set passes `filter`.
+    pub fn matches(&self, filter: &AttributeFilter) -> bool {
+        filter.matches(&self.attributes)
+    }
+}
+
+fn find<'a>(attributes: &'a [ProductAttribute], key: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|a| a.key == key)
+        .map(|a| a.value.as_str())
+}
+
+/// Normalizes an attribute key: trimmed and lowercased.
+///
+/// ## Errors
+/// Empty, longer than [`MAX_KEY_LEN`], or holding characters other than
+/// letters, digits, `_`, `-` and `.`.
+pub fn normalize_key(key: &str) -> ValidationResult<String> {
+    let key = key.trim().to_lowercase();
+    if key.is_empty() {
+        return Err(ValidationError::Required {
+            field: "attribute key".to_string(),
+        });
+    }
+    if key.chars().count() > MAX_KEY_LEN {
+        return Err(ValidationError::TooLong {
+            field: "attribute key".to_string(),
+            max: MAX_KEY_LEN,
+        });
+    }
+    if !key
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        return Err(ValidationError::InvalidFormat {
+            field: "attribute key".to_string(),
+            reason: format!("'{}' may only hold letters, digits, '_', '-' and '.'", key),
+        });
+    }
+    Ok(key)
+}
+
+/// Normalizes a full attribute set for saving: keys normalized, values
+/// trimmed, sorted by key.
+///
+/// ## Errors
+/// An invalid key, a value longer than [`MAX_VALUE_LEN`], the same key
+/// twice, or more than [`MAX_ATTRIBUTES`] attributes.
+pub fn normalize_attributes(
+    attributes: &[ProductAttribute],
+) -> ValidationResult<Vec<ProductAttribute>> {
+    if attributes.len() > MAX_ATTRIBUTES {
+        return Err(ValidationError::OutOfRange {
+            field: "attributes".to_string(),
+            min: 0,
+            max: MAX_ATTRIBUTES as i64,
+        });
+    }
+
+    let mut normalized: Vec<ProductAttribute> = Vec::with_capacity(attributes.len());
+    for attribute in attributes {
+        let key = normalize_key(&attribute.key)?;
+        let value = attribute.value.trim();
+        if value.chars().count() > MAX_VALUE_LEN {
+            return Err(ValidationError::TooLong {
+                field: format!("attribute '{}'", key),
+                max: MAX_VALUE_LEN,
+            });
+        }
+        if normalized.iter().any(|a| a.key == key) {
+            return Err(ValidationError::Duplicate {
+                field: "attribute key".to_string(),
+                value: key,
+            });
+        }
+        normalized.push(ProductAttribute::new(key, value));
+    }
+
+    normalized.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(normalized)
+}
+
+// =============================================================================
+// Filters
+// =============================================================================
+
+/// Selects products by one attribute: having the key (`organic`) or
+/// having it with a given value (`color=red`, case-insensitive).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AttributeFilter {
+    pub key: String,
+    /// `None`: any value, tags included.
+    pub value: Option<String>,
+}
+
+impl AttributeFilter {
+    /// Parses `key` or `key=value`.
+    ///
+    /// ## Errors
+    /// An invalid key (see [`normalize_key`]).
+    pub fn parse(filter: &str) -> ValidationResult<Self> {
+        let (key, value) = match filter.split_once('=') {
+            Some((key, value)) => (key, Some(value.trim().to_string())),
+            None => (filter, None),
+        };
+        Ok(AttributeFilter {
+            key: normalize_key(key)?,
+            value,
+        })
+    }
+
+    /// Whether `attributes` pass the filter.
+    pub fn matches(&self, attributes: &[ProductAttribute]) -> bool {
+        match (find(attributes, &self.key), &self.value) {
+            (None, _) => false,
+            (Some(_), None) => true,
+            (Some(found), Some(wanted)) => found.eq_ignore_ascii_case(wanted),
+        }
+    }
+}
+
+// =============================================================================
+// Promotions
+// =============================================================================
+
+/// A percentage discount on every line whose product passes `filter`,
+/// e.g. 10% off `vintage=2019`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AttributePromotion {
+    /// Shown on the receipt next to the discount.
+    pub name: String,
+    pub filter: AttributeFilter,
+    /// Discount in basis points (1000 = 10%).
+    pub discount_bps: u32,
+}
+
+impl AttributePromotion {
+    /// Checks the rule can be applied.
+    ///
+    /// ## Errors
+    /// An empty name, or a discount outside 1..=10000 bps.
+    pub fn validate(&self) -> ValidationResult<()> {
+        if self.name.trim().is_empty() {
+            return Err(ValidationError::Required {
+                field: "promotion name".to_string(),
+            });
+        }
+        if self.discount_bps == 0 || self.discount_bps > 10_000 {
+            return Err(ValidationError::OutOfRange {
+                field: "discount_bps".to_string(),
+                min: 1,
+                max: 10_000,
+            });
+        }
+        Ok(())
+    }
+
+    /// Discount on a line of `line_total_cents` for a product with
+    /// `attributes`; zero when the rule does not apply.
+    pub fn discount_cents(&self, attributes: &[ProductAttribute], line_total_cents: i64) -> i64 {
+        if !self.filter.matches(attributes) {
+            return 0;
+        }
+        let total = Money::from_cents(line_total_cents);
+        total.cents() - total.apply_percentage_discount(self.discount_bps).cents()
+    }
+}
+
+/// The promotion giving the largest discount on a line, with that
+/// discount. Promotions do not stack.
+pub fn best_promotion<'a>(
+    promotions: &'a [AttributePromotion],
+    attributes: &[ProductAttribute],
+    line_total_cents: i64,
+) -> Option<(&'a AttributePromotion, i64)> {
+    promotions
+        .iter()
+        .map(|p| (p, p.discount_cents(attributes, line_total_cents)))
+        .filter(|(_, discount)| *discount > 0)
+        .max_by_key(|(_, discount)| *discount)
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wine() -> Vec<ProductAttribute> {
+        normalize_attributes(&[
+            ProductAttribute::new(" Vintage ", "2019"),
+            ProductAttribute::new("color", "Red "),
+            ProductAttribute::tag("Organic"),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_normalize_attributes() {
+        let attributes = wine();
+        let keys: Vec<&str> = attributes.iter().map(|a| a.key.as_str()).collect();
+        assert_eq!(keys, vec!["color", "organic", "vintage"]);
+        assert_eq!(attributes[0].value, "Red");
+        assert!(attributes[1].is_tag());
+
+        let duplicate = normalize_attributes(&[
+            ProductAttribute::new("Color", "red"),
+            ProductAttribute::new("color", "blue"),
+        ]);
+        assert!(matches!(duplicate, Err(ValidationError::Duplicate { .. })));
+
+        assert!(normalize_key("shelf life").is_err());
+        assert!(normalize_key("  ").is_err());
+        assert!(normalize_key(&"k".repeat(MAX_KEY_LEN + 1)).is_err());
+        assert!(normalize_attributes(&[ProductAttribute::new("note", "x".repeat(101))]).is_err());
+    }
+
+    #[test]
+    fn test_filter() {
+        let attributes = wine();
+
+        assert!(AttributeFilter::parse("color=red").unwrap().matches(&attributes));
+        assert!(AttributeFilter::parse("COLOR = RED").unwrap().matches(&attributes));
+        assert!(AttributeFilter::parse("organic").unwrap().matches(&attributes));
+        assert!(AttributeFilter::parse("vintage").unwrap().matches(&attributes));
+        assert!(!AttributeFilter::parse("color=white").unwrap().matches(&attributes));
+        assert!(!AttributeFilter::parse("size").unwrap().matches(&attributes));
+        // A tag has no value to match
+        assert!(!AttributeFilter::parse("organic=yes").unwrap().matches(&attributes));
+    }
+
+    #[test]
+    fn test_best_promotion() {
+        let promotion = |name: &str, filter: &str, bps: u32| AttributePromotion {
+            name: name.to_string(),
+            filter: AttributeFilter::parse(filter).unwrap(),
+            discount_bps: bps,
+        };
+        let promotions = vec![
+            promotion("Organic week", "organic", 500),
+            promotion("2019 clearance", "vintage=2019", 2000),
+            promotion("White wine", "color=white", 5000),
+        ];
+
+        let (best, discount) = best_promotion(&promotions, &wine(), 2499).unwrap();
+        assert_eq!(best.name, "2019 clearance");
+        assert_eq!(discount, 500);
+
+        assert!(best_promotion(&promotions, &[], 2499).is_none());
+
+        assert!(promotion("", "organic", 500).validate().is_err());
+        assert!(promotion("Free", "organic", 10_001).validate().is_err());
+        assert!(promotions[0].validate().is_ok());
+    }
+}
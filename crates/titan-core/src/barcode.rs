@@ -0,0 +1,935 @@
+//! # Barcodes
+//!
+//! Barcode and QR symbols drawn by the POS itself, for printers that have
+//! no barcode fonts of their own or need an image: the receipt-number
+//! barcode at the foot of every receipt (scanned to find the sale for a
+//! refund) and barcode images on shelf labels.
+//!
+//! ## Rendering Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                           Barcode Rendering                             │
+//! │                                                                         │
+//! │  Barcode::encode(symbology, data)                                       │
+//! │     Code128  printable ASCII; all-digit data packed two per symbol      │
+//! │     Ean13    12 digits (check digit added) or 13 (check digit checked), │
+//! │              or a 12-digit UPC-A                                        │
+//! │     Qr       up to 213 bytes, medium error correction, versions 1-10    │
+//! │        │                                                                │
+//! │        ▼ modules (dark/light), linear codes one row high                │
+//! │  Barcode::render(module_dots, bar_height) ──► Bitmap (quiet zone added) │
+//! │        │                                                                │
+//! │        ├─► Bitmap::escpos_raster   receipt printers (GS v 0)            │
+//! │        └─► Bitmap::zpl_graphic     label printers (^GFA)                │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Every symbol is built from the data alone, so the same receipt number
+//! always prints the same barcode.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::ValidationError;
+use crate::validation::ValidationResult;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Most bytes a QR symbol holds (version 10, medium error correction).
+pub const MAX_QR_BYTES: usize = 213;
+
+/// Code 128 symbol widths (bar, space, bar, space, bar, space) by value;
+/// 106 is the stop symbol, which ends with a final bar.
+const CODE128_PATTERNS: [&[u8]; 107] = [
+    b"212222", b"222122", b"222221", b"121223", b"121322", b"131222", b"122213", b"122312",
+    b"132212", b"221213", b"221312", b"231212", b"112232", b"122132", b"122231", b"113222",
+    b"123122", b"123221", b"223211", b"221132", b"221231", b"213212", b"223112", b"312131",
+    b"311222", b"321122", b"321221", b"312212", b"322112", b"322211", b"212123", b"212321",
+    b"232121", b"111323", b"131123", b"131321", b"112313", b"132113", b"132311", b"211313",
+    b"231113", b"231311", b"112133", b"112331", b"132131", b"113123", b"113321", b"133121",
+    b"313121", b"211331", b"231131", b"213113", b"213311", b"213131", b"311123", b"311321",
+    b"331121", b"312113", b"312311", b"332111", b"314111", b"221411", b"431111", b"111224",
+    b"111422", b"121124", b"121421", b"141122", b"141221", b"112214", b"112412", b"122114",
+    b"122411", b"142112", b"142211", b"241211", b"221114", b"413111", b"241112", b"134111",
+    b"111242", b"121142", b"121241", b"114212", b"124112", b"124211", b"411212", b"421112",
+    b"421211", b"212141", b"214121", b"412121", b"111143", b"111341", b"131141", b"114113",
+    b"114311", b"411113", b"411311", b"113141", b"114131", b"311141", b"411131", b"211412",
+    b"211214", b"211232", b"2331112",
+];
+
+const CODE128_START_B: usize = 104;
+const CODE128_START_C: usize = 105;
+const CODE128_STOP: usize = 106;
+
+/// EAN-13 left-hand odd parity (L) codes; the G and R codes derive
+/// from them.
+const EAN_L_CODES: [u8; 10] = [
+    0b0001101, 0b0011001, 0b0010011, 0b0111101, 0b0100011, 0b0110001, 0b0101111, 0b0111011,
+    0b0110111, 0b0001011,
+];
+
+/// Which of digits 2-7 use G codes (bit 5 = digit 2), by first digit.
+const EAN_PARITY: [u8; 10] = [
+    0b000000, 0b001011, 0b001101, 0b001110, 0b010011, 0b011001, 0b011100, 0b010101, 0b010110,
+    0b011010,
+];
+
+/// QR versions 1-10 at error correction level M: (error correction
+/// codewords per block, short blocks, data codewords per short block,
+/// long blocks). Long blocks hold one more data codeword.
+const QR_BLOCKS: [(usize, usize, usize, usize); 10] = [
+    (10, 1, 16, 0),
+    (16, 1, 28, 0),
+    (26, 1, 44, 0),
+    (18, 2, 32, 0),
+    (24, 2, 43, 0),
+    (16, 4, 27, 0),
+    (18, 4, 31, 0),
+    (22, 2, 38, 2),
+    (22, 3, 36, 2),
+    (26, 4, 43, 1),
+];
+
+/// Alignment pattern centres by version.
+const QR_ALIGNMENT: [&[usize]; 10] = [
+    &[],
+    &[6, 18],
+    &[6, 22],
+    &[6, 26],
+    &[6, 30],
+    &[6, 34],
+    &[6, 22, 38],
+    &[6, 24, 42],
+    &[6, 26, 46],
+    &[6, 28, 50],
+];
+
+// =============================================================================
+// Symbology
+// =============================================================================
+
+/// Kind of barcode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum Symbology {
+    /// Code 128: any printable ASCII (receipt numbers, SKUs).
+    Code128,
+    /// EAN-13 / UPC-A retail product codes.
+    Ean13,
+    /// QR code (byte mode, medium error correction).
+    Qr,
+}
+
+impl Symbology {
+    /// Light modules required around the symbol on each side.
+    pub fn quiet_zone(&self) -> usize {
+        match self {
+            Symbology::Code128 => 10,
+            Symbology::Ean13 => 11,
+            Symbology::Qr => 4,
+        }
+    }
+}
+
+// =============================================================================
+// Barcode
+// =============================================================================
+
+/// An encoded symbol: a grid of dark and light modules, one row high for
+/// linear symbologies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Barcode {
+    pub symbology: Symbology,
+    /// Modules across, without the quiet zone.
+    pub width: usize,
+    /// Module rows: 1 for linear symbologies.
+    pub height: usize,
+    modules: Vec<bool>,
+}
+
+impl Barcode {
+    /// Encodes `data` (see the module docs for what each symbology takes).
+    ///
+    /// ## Errors
+    /// - `ValidationError::Required` for empty data
+    /// - `ValidationError::InvalidFormat` for characters or a length the
+    ///   symbology cannot hold, or a wrong EAN-13 check digit
+    pub fn encode(symbology: Symbology, data: &str) -> ValidationResult<Self> {
+        if data.is_empty() {
+            return Err(ValidationError::Required {
+                field: "barcode".to_string(),
+            });
+        }
+        match symbology {
+            Symbology::Code128 => Ok(Barcode::linear(symbology, &code128_widths(data)?)),
+            Symbology::Ean13 => Ok(Barcode::linear(symbology, &ean13_widths(data)?)),
+            Symbology::Qr => encode_qr(data.as_bytes()),
+        }
+    }
+
+    /// A one-row symbol from alternating bar/space widths.
+    fn linear(symbology: Symbology, widths: &[u8]) -> Self {
+        let mut modules = Vec::new();
+        for (i, width) in widths.iter().enumerate() {
+            modules.extend(std::iter::repeat_n(i.is_multiple_of(2), usize::from(*width)));
+        }
+        Barcode {
+            symbology,
+            width: modules.len(),
+            height: 1,
+            modules,
+        }
+    }
+
+    /// Whether the module at column `x`, row `y` is dark.
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.modules[y * self.width + x]
+    }
+
+    /// Draws the symbol with its quiet zone, `module_dots` printer dots per
+    /// module. Linear symbols are `bar_height` dots high; QR modules are
+    /// square and `bar_height` is ignored.
+    pub fn render(&self, module_dots: usize, bar_height: usize) -> Bitmap {
+        let module_dots = module_dots.max(1);
+        let quiet = self.symbology.quiet_zone();
+        let width = (self.width + 2 * quiet) * module_dots;
+        let (height, rows_per_module, top) = if self.height == 1 {
+            (bar_height.max(1), bar_height.max(1), 0)
+        } else {
+            let side = (self.height + 2 * quiet) * module_dots;
+            (side, module_dots, quiet)
+        };
+
+        let mut bitmap = Bitmap::new(width, height);
+        for y in 0..height {
+            let row = y / rows_per_module;
+            if row < top || row - top >= self.height {
+                continue;
+            }
+            for x in 0..width {
+                let column = x / module_dots;
+                if column >= quiet && self.is_dark(column - quiet, row - top) {
+                    bitmap.set(x, y);
+                }
+            }
+        }
+        bitmap
+    }
+}
+
+// =============================================================================
+// Bitmap
+// =============================================================================
+
+/// A one-bit image, rows packed eight dots to a byte (first dot in the
+/// high bit, 1 = black).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bitmap {
+    pub width: usize,
+    pub height: usize,
+    data: Vec<u8>,
+}
+
+impl Bitmap {
+    /// A blank (all white) image.
+    pub fn new(width: usize, height: usize) -> Self {
+        Bitmap {
+            width,
+            height,
+            data: vec![0; width.div_ceil(8) * height],
+        }
+    }
+
+    /// Bytes per packed row.
+    pub fn bytes_per_row(&self) -> usize {
+        self.width.div_ceil(8)
+    }
+
+    /// Blackens the dot at `x`, `y`.
+    pub fn set(&mut self, x: usize, y: usize) {
+        if x < self.width && y < self.height {
+            let i = y * self.bytes_per_row() + x / 8;
+            self.data[i] |= 0x80 >> (x % 8);
+        }
+    }
+
+    /// Whether the dot at `x`, `y` is black.
+    pub fn is_black(&self, x: usize, y: usize) -> bool {
+        x < self.width
+            && y < self.height
+            && self.data[y * self.bytes_per_row() + x / 8] & (0x80 >> (x % 8)) != 0
+    }
+
+    /// The packed rows.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// ESC/POS raster image command (`GS v 0`, normal density).
+    pub fn escpos_raster(&self) -> Vec<u8> {
+        let x = self.bytes_per_row() as u16;
+        let y = self.height as u16;
+        let mut out = Vec::with_capacity(8 + self.data.len());
+        out.extend_from_slice(&[0x1D, b'v', b'0', 0]);
+        out.extend_from_slice(&x.to_le_bytes());
+        out.extend_from_slice(&y.to_le_bytes());
+        out.extend_from_slice(&self.data);
+        out
+    }
+
+    /// ZPL graphic field (`^GFA`, hex data), drawn at the current `^FO`.
+    pub fn zpl_graphic(&self) -> String {
+        let total = self.data.len();
+        let mut out = format!("^GFA,{},{},{},", total, total, self.bytes_per_row());
+        for byte in &self.data {
+            out.push_str(&format!("{:02X}", byte));
+        }
+        out
+    }
+}
+
+// =============================================================================
+// Code 128
+// =============================================================================
+
+/// Bar/space widths of a Code 128 symbol: code set C for all-digit data
+/// of even length, code set B otherwise.
+fn code128_widths(data: &str) -> ValidationResult<Vec<u8>> {
+    let digits_only = data.len() >= 4 && data.len().is_multiple_of(2);
+    let digits_only = digits_only && data.bytes().all(|b| b.is_ascii_digit());
+
+    let mut values = Vec::with_capacity(data.len() + 3);
+    if digits_only {
+        values.push(CODE128_START_C);
+        for pair in data.as_bytes().chunks(2) {
+            values.push(usize::from((pair[0] - b'0') * 10 + (pair[1] - b'0')));
+        }
+    } else {
+        values.push(CODE128_START_B);
+        for c in data.chars() {
+            if !(' '..='~').contains(&c) {
+                return Err(ValidationError::InvalidFormat {
+                    field: "barcode".to_string(),
+                    reason: format!("Code 128 cannot encode {:?}", c),
+                });
+            }
+            values.push(c as usize - 32);
+        }
+    }
+
+    let checksum = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| value * i.max(1))
+        .sum::<usize>()
+        % 103;
+    values.push(checksum);
+    values.push(CODE128_STOP);
+
+    Ok(values
+        .into_iter()
+        .flat_map(|value| CODE128_PATTERNS[value].iter().map(|w| w - b'0'))
+        .collect())
+}
+
+// =============================================================================
+// EAN-13
+// =============================================================================
+
+/// The EAN-13 check digit of the first twelve digits.
+pub fn ean13_check_digit(digits: &[u8]) -> u8 {
+    let sum: u32 = digits
+        .iter()
+        .take(12)
+        .enumerate()
+        .map(|(i, d)| u32::from(*d) * if i.is_multiple_of(2) { 1 } else { 3 })
+        .sum();
+    ((10 - sum % 10) % 10) as u8
+}
+
+/// The thirteen digits of `data`: 12 digits get their check digit, and a
+/// 12-digit UPC-A that already carries a valid one is read as `0` + UPC-A.
+fn ean13_digits(data: &str) -> ValidationResult<Vec<u8>> {
+    let invalid = |reason: &str| ValidationError::InvalidFormat {
+        field: "barcode".to_string(),
+        reason: reason.to_string(),
+    };
+    if !data.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid("EAN-13 barcodes are digits only"));
+    }
+    let mut digits: Vec<u8> = data.bytes().map(|b| b - b'0').collect();
+
+    match digits.len() {
+        12 => {
+            let upc: Vec<u8> = std::iter::once(0).chain(digits.iter().copied()).collect();
+            if ean13_check_digit(&upc) == upc[12] {
+                return Ok(upc);
+            }
+            digits.push(ean13_check_digit(&digits));
+            Ok(digits)
+        }
+        13 if ean13_check_digit(&digits) == digits[12] => Ok(digits),
+        13 => Err(invalid("wrong EAN-13 check digit")),
+        _ => Err(invalid("EAN-13 barcodes have 12 or 13 digits")),
+    }
+}
+
+/// Bar/space widths of an EAN-13 symbol (95 modules).
+fn ean13_widths(data: &str) -> ValidationResult<Vec<u8>> {
+    let digits = ean13_digits(data)?;
+    let parity = EAN_PARITY[usize::from(digits[0])];
+
+    let mut modules: Vec<bool> = Vec::with_capacity(95);
+    let mut push = |bits: u8, count: usize| {
+        for i in (0..count).rev() {
+            modules.push(bits >> i & 1 == 1);
+        }
+    };
+    push(0b101, 3);
+    for (i, digit) in digits[1..7].iter().enumerate() {
+        let l = EAN_L_CODES[usize::from(*digit)];
+        if parity >> (5 - i) & 1 == 1 {
+            // G code: the R code read backwards
+            push(reverse7(!l & 0x7F), 7);
+        } else {
+            push(l, 7);
+        }
+    }
+    push(0b01010, 5);
+    for digit in &digits[7..] {
+        push(!EAN_L_CODES[usize::from(*digit)] & 0x7F, 7);
+    }
+    push(0b101, 3);
+
+    // Run lengths, starting with the first bar
+    let mut widths = Vec::new();
+    let mut run = 0u8;
+    let mut dark = true;
+    for module in modules {
+        if module == dark {
+            run += 1;
+        } else {
+            widths.push(run);
+            run = 1;
+            dark = module;
+        }
+    }
+    widths.push(run);
+    Ok(widths)
+}
+
+fn reverse7(bits: u8) -> u8 {
+    bits.reverse_bits() >> 1
+}
+
+// =============================================================================
+// QR
+// =============================================================================
+
+/// Appends bits most significant first.
+#[derive(Default)]
+struct BitBuffer {
+    bits: Vec<bool>,
+}
+
+impl BitBuffer {
+    fn push(&mut self, value: u32, count: usize) {
+        for i in (0..count).rev() {
+            self.bits.push(value >> i & 1 == 1);
+        }
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        self.bits
+            .chunks(8)
+            .map(|byte| byte.iter().fold(0u8, |acc, bit| acc << 1 | u8::from(*bit)))
+            .collect()
+    }
+}
+
+/// Multiplies in GF(256) with the QR polynomial 0x11D.
+fn gf_mul(x: u8, y: u8) -> u8 {
+    let mut z: u32 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x11D);
+        z ^= u32::from(y >> i & 1) * u32::from(x);
+    }
+    z as u8
+}
+
+/// Reed-Solomon generator polynomial of `degree` (highest term dropped).
+fn rs_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0u8; degree];
+    result[degree - 1] = 1;
+    let mut root = 1u8;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_mul(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_mul(root, 0x02);
+    }
+    result
+}
+
+/// Error correction codewords of `data`.
+fn rs_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0u8; divisor.len()];
+    for byte in data {
+        let factor = byte ^ result.remove(0);
+        result.push(0);
+        for (r, d) in result.iter_mut().zip(divisor) {
+            *r ^= gf_mul(*d, factor);
+        }
+    }
+    result
+}
+
+/// The 15 format bits (level M) for `mask`, BCH protected and masked.
+fn qr_format_bits(mask: u8) -> u32 {
+    // Level M is 00
+    let data = u32::from(mask);
+    let mut rem = data;
+    for _ in 0..10 {
+        rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+    }
+    ((data << 10) | rem) ^ 0x5412
+}
+
+/// The 18 version bits, for versions 7 and up.
+fn qr_version_bits(version: usize) -> u32 {
+    let version = version as u32;
+    let mut rem = version;
+    for _ in 0..12 {
+        rem = (rem << 1) ^ ((rem >> 11) * 0x1F25);
+    }
+    (version << 12) | rem
+}
+
+fn qr_mask(mask: u8, x: usize, y: usize) -> bool {
+    match mask {
+        0 => (x + y).is_multiple_of(2),
+        1 => y.is_multiple_of(2),
+        2 => x.is_multiple_of(3),
+        3 => (x + y).is_multiple_of(3),
+        4 => (x / 3 + y / 2).is_multiple_of(2),
+        5 => x * y % 2 + x * y % 3 == 0,
+        6 => (x * y % 2 + x * y % 3).is_multiple_of(2),
+        _ => ((x + y) % 2 + x * y % 3).is_multiple_of(2),
+    }
+}
+
+/// A QR symbol under construction.
+#[derive(Clone)]
+struct QrMatrix {
+    size: usize,
+    modules: Vec<bool>,
+    /// Function patterns, which data and masks leave alone.
+    function: Vec<bool>,
+}
+
+impl QrMatrix {
+    fn new(version: usize) -> Self {
+        let size = 17 + 4 * version;
+        let mut qr = QrMatrix {
+            size,
+            modules: vec![false; size * size],
+            function: vec![false; size * size],
+        };
+
+        for i in 0..size {
+            qr.set_function(6, i, i.is_multiple_of(2));
+            qr.set_function(i, 6, i.is_multiple_of(2));
+        }
+        for (x, y) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            qr.finder(x, y);
+        }
+        let centres = QR_ALIGNMENT[version - 1];
+        for (i, &x) in centres.iter().enumerate() {
+            for (j, &y) in centres.iter().enumerate() {
+                let last = centres.len() - 1;
+                let on_finder = (i == 0 && (j == 0 || j == last)) || (i == last && j == 0);
+                if !on_finder {
+                    qr.alignment(x, y);
+                }
+            }
+        }
+        // Reserved now, drawn once the mask is chosen
+        qr.draw_format(0);
+        if version >= 7 {
+            let bits = qr_version_bits(version);
+            for i in 0..18 {
+                let dark = bits >> i & 1 == 1;
+                let (a, b) = (size - 11 + i % 3, i / 3);
+                qr.set_function(a, b, dark);
+                qr.set_function(b, a, dark);
+            }
+        }
+        qr
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        let i = y * self.size + x;
+        self.modules[i] = dark;
+        self.function[i] = true;
+    }
+
+    /// Finder pattern and separator centred on `x`, `y`.
+    fn finder(&mut self, x: usize, y: usize) {
+        for dy in -4i32..=4 {
+            for dx in -4i32..=4 {
+                let (xx, yy) = (x as i32 + dx, y as i32 + dy);
+                if (0..self.size as i32).contains(&xx) && (0..self.size as i32).contains(&yy) {
+                    let ring = dx.abs().max(dy.abs());
+                    self.set_function(xx as usize, yy as usize, ring != 2 && ring != 4);
+                }
+            }
+        }
+    }
+
+    fn alignment(&mut self, x: usize, y: usize) {
+        for dy in -2i32..=2 {
+            for dx in -2i32..=2 {
+                let ring = dx.abs().max(dy.abs());
+                let (xx, yy) = ((x as i32 + dx) as usize, (y as i32 + dy) as usize);
+                self.set_function(xx, yy, ring != 1);
+            }
+        }
+    }
+
+    /// Both copies of the format bits, and the dark module.
+    fn draw_format(&mut self, mask: u8) {
+        let bits = qr_format_bits(mask);
+        let bit = |i: usize| bits >> i & 1 == 1;
+        let size = self.size;
+
+        for i in 0..6 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    /// Places codewords in the two-column zigzag from the bottom right.
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let size = self.size;
+        let total_bits = codewords.len() * 8;
+        let mut i = 0;
+        let mut right = size - 1;
+        loop {
+            if right == 6 {
+                right = 5;
+            }
+            for vert in 0..size {
+                for j in 0..2 {
+                    let x = right - j;
+                    let upward = (right + 1) & 2 == 0;
+                    let y = if upward { size - 1 - vert } else { vert };
+                    if !self.function[y * size + x] && i < total_bits {
+                        self.modules[y * size + x] = codewords[i >> 3] >> (7 - (i & 7)) & 1 == 1;
+                        i += 1;
+                    }
+                }
+            }
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    fn apply_mask(&mut self, mask: u8) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let i = y * self.size + x;
+                if !self.function[i] && qr_mask(mask, x, y) {
+                    self.modules[i] = !self.modules[i];
+                }
+            }
+        }
+    }
+
+    fn dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    /// Penalty score of the finished symbol; the mask with the lowest
+    /// is used.
+    fn penalty(&self) -> usize {
+        let size = self.size;
+        let mut score = 0;
+
+        // Rows and columns: runs of five or more, finder-like patterns
+        let finder_like: [bool; 11] = [
+            true, false, true, true, true, false, true, false, false, false, false,
+        ];
+        for transpose in [false, true] {
+            for a in 0..size {
+                let line: Vec<bool> = (0..size)
+                    .map(|b| if transpose { self.dark(a, b) } else { self.dark(b, a) })
+                    .collect();
+                let mut run = 1;
+                for b in 1..=size {
+                    if b < size && line[b] == line[b - 1] {
+                        run += 1;
+                        continue;
+                    }
+                    if run >= 5 {
+                        score += run - 2;
+                    }
+                    run = 1;
+                }
+                for window in line.windows(11) {
+                    if window == finder_like || window.iter().rev().eq(finder_like.iter()) {
+                        score += 40;
+                    }
+                }
+            }
+        }
+
+        // 2x2 blocks of one colour
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let c = self.dark(x, y);
+                if c == self.dark(x + 1, y) && c == self.dark(x, y + 1) && c == self.dark(x + 1, y + 1)
+                {
+                    score += 3;
+                }
+            }
+        }
+
+        // Balance of dark and light
+        let total = size * size;
+        let dark = self.modules.iter().filter(|m| **m).count();
+        let deviation = (dark * 20).abs_diff(total * 10);
+        score + deviation.div_ceil(total).saturating_sub(1) * 10
+    }
+}
+
+/// Encodes `data` in byte mode at level M, in the smallest version that
+/// holds it.
+fn encode_qr(data: &[u8]) -> ValidationResult<Barcode> {
+    let count_bits = |version: usize| if version < 10 { 8 } else { 16 };
+    let capacity = |(_, short, short_len, long): (usize, usize, usize, usize)| {
+        short * short_len + long * (short_len + 1)
+    };
+    let version = (1..=QR_BLOCKS.len())
+        .find(|&v| 4 + count_bits(v) + data.len() * 8 <= capacity(QR_BLOCKS[v - 1]) * 8)
+        .ok_or_else(|| ValidationError::InvalidFormat {
+            field: "barcode".to_string(),
+            reason: format!("QR codes hold at most {} bytes", MAX_QR_BYTES),
+        })?;
+    let blocks = QR_BLOCKS[version - 1];
+    let (ec_len, short, short_len, long) = blocks;
+    let capacity = capacity(blocks);
+
+    // Mode, length, data, terminator, then pad to capacity
+    let mut bits = BitBuffer::default();
+    bits.push(0b0100, 4);
+    bits.push(data.len() as u32, count_bits(version));
+    for byte in data {
+        bits.push(u32::from(*byte), 8);
+    }
+    let terminator = (capacity * 8 - bits.bits.len()).min(4);
+    bits.push(0, terminator);
+    bits.push(0, (8 - bits.bits.len() % 8) % 8);
+    let mut codewords = bits.bytes();
+    for pad in [0xEC, 0x11].into_iter().cycle() {
+        if codewords.len() >= capacity {
+            break;
+        }
+        codewords.push(pad);
+    }
+
+    // Split into blocks, add error correction, interleave
+    let divisor = rs_divisor(ec_len);
+    let mut data_blocks = Vec::with_capacity(short + long);
+    let mut offset = 0;
+    for i in 0..short + long {
+        let len = short_len + usize::from(i >= short);
+        data_blocks.push(&codewords[offset..offset + len]);
+        offset += len;
+    }
+    let ec_blocks: Vec<Vec<u8>> = data_blocks
+        .iter()
+        .map(|block| rs_remainder(block, &divisor))
+        .collect();
+    let mut interleaved = Vec::with_capacity(capacity + ec_len * data_blocks.len());
+    for i in 0..=short_len {
+        interleaved.extend(data_blocks.iter().filter_map(|block| block.get(i)));
+    }
+    for i in 0..ec_len {
+        interleaved.extend(ec_blocks.iter().map(|block| block[i]));
+    }
+
+    let mut qr = QrMatrix::new(version);
+    qr.draw_codewords(&interleaved);
+    let mask = (0..8u8)
+        .min_by_key(|&mask| {
+            let mut candidate = qr.clone();
+            candidate.apply_mask(mask);
+            candidate.draw_format(mask);
+            candidate.penalty()
+        })
+        .unwrap_or(0);
+    qr.apply_mask(mask);
+    qr.draw_format(mask);
+
+    Ok(Barcode {
+        symbology: Symbology::Qr,
+        width: qr.size,
+        height: qr.size,
+        modules: qr.modules,
+    })
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code128() {
+        for pattern in &CODE128_PATTERNS[..CODE128_STOP] {
+            let widths: Vec<u8> = pattern.iter().map(|w| w - b'0').collect();
+            assert_eq!(widths.iter().sum::<u8>(), 11);
+            assert_eq!((widths[0] + widths[2] + widths[4]) % 2, 0);
+        }
+
+        // Start B, "R", "1", checksum, stop: 11 modules each, 13 for stop
+        let barcode = Barcode::encode(Symbology::Code128, "R1").unwrap();
+        assert_eq!(barcode.width, 4 * 11 + 13);
+        // Checksum (104 + 50*1 + 17*2) % 103 = 85
+        let widths = code128_widths("R1").unwrap();
+        assert_eq!(&widths[18..24], &[1, 2, 4, 2, 1, 1]);
+        assert!(barcode.is_dark(0, 0) && !barcode.is_dark(2, 0));
+
+        // All digits go in code set C, two per symbol
+        assert_eq!(code128_widths("000123").unwrap().len(), 6 * 5 + 7);
+        assert_eq!(&code128_widths("000123").unwrap()[..6], &[2, 1, 1, 2, 3, 2]);
+
+        assert!(Barcode::encode(Symbology::Code128, "").is_err());
+        assert!(Barcode::encode(Symbology::Code128, "café").is_err());
+    }
+
+    #[test]
+    fn test_ean13() {
+        assert_eq!(ean13_check_digit(&[4, 0, 0, 6, 3, 8, 1, 3, 3, 3, 9, 3]), 1);
+
+        let full = Barcode::encode(Symbology::Ean13, "4006381333931").unwrap();
+        let short = Barcode::encode(Symbology::Ean13, "400638133393").unwrap();
+        assert_eq!(full, short);
+        assert_eq!(full.width, 95);
+        let row: String = (0..95).map(|x| if full.is_dark(x, 0) { '1' } else { '0' }).collect();
+        assert!(row.starts_with("101"));
+        assert_eq!(&row[45..50], "01010");
+        assert!(row.ends_with("101"));
+        // First digit 4 is LGLLGG: digit 2 (0) in L, digit 3 (0) in G
+        assert_eq!(&row[3..10], "0001101");
+        assert_eq!(&row[10..17], "0100111");
+        // Right half in R codes: the last digit 1
+        assert_eq!(&row[85..92], "1100110");
+
+        // UPC-A reads as EAN-13 with a leading zero
+        assert_eq!(
+            Barcode::encode(Symbology::Ean13, "012345678905").unwrap(),
+            Barcode::encode(Symbology::Ean13, "0012345678905").unwrap()
+        );
+        assert!(Barcode::encode(Symbology::Ean13, "4006381333932").is_err());
+        assert!(Barcode::encode(Symbology::Ean13, "40063813").is_err());
+    }
+
+    #[test]
+    fn test_qr_error_correction_and_format() {
+        // "HELLO WORLD" at 1-M (alphanumeric), from the QR specification
+        let data = [32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17];
+        assert_eq!(
+            rs_remainder(&data, &rs_divisor(10)),
+            vec![196, 35, 39, 119, 235, 215, 231, 226, 93, 23]
+        );
+
+        assert_eq!(qr_format_bits(0), 0b101010000010010);
+        assert_eq!(qr_format_bits(1), 0b101000100100101);
+        assert_eq!(qr_format_bits(7), 0b100101010100000);
+        assert_eq!(qr_version_bits(7), 0b000111110010010100);
+    }
+
+    #[test]
+    fn test_qr_symbol() {
+        let barcode = Barcode::encode(Symbology::Qr, "S01-000123").unwrap();
+        assert_eq!((barcode.width, barcode.height), (21, 21));
+
+        // Finder patterns in three corners, timing between them
+        for (x, y) in [(0, 0), (14, 0), (0, 14)] {
+            assert!(barcode.is_dark(x, y) && barcode.is_dark(x + 6, y + 6));
+            assert!(!barcode.is_dark(x + 1, y + 1) && barcode.is_dark(x + 3, y + 3));
+        }
+        assert!((8..13).all(|i| barcode.is_dark(i, 6) == i.is_multiple_of(2)));
+        assert!(barcode.is_dark(8, 21 - 8));
+
+        // Both copies of the format bits agree on one mask
+        let first: u32 = (0..6)
+            .map(|i| (8, i))
+            .chain([(8, 7), (8, 8), (7, 8)])
+            .chain((9..15).map(|i| (14 - i, 8)))
+            .enumerate()
+            .map(|(i, (x, y))| u32::from(barcode.is_dark(x, y)) << i)
+            .sum();
+        let second: u32 = (0..8)
+            .map(|i| (20 - i, 8))
+            .chain((8..15).map(|i| (8, 21 - 15 + i)))
+            .enumerate()
+            .map(|(i, (x, y))| u32::from(barcode.is_dark(x, y)) << i)
+            .sum();
+        assert_eq!(first, second);
+        assert!((0..8).any(|mask| qr_format_bits(mask) == first));
+
+        // Longer data moves up a version; too much is refused
+        let long = "x".repeat(100);
+        assert_eq!(Barcode::encode(Symbology::Qr, &long).unwrap().width, 17 + 4 * 6);
+        let longest = "x".repeat(MAX_QR_BYTES);
+        assert_eq!(Barcode::encode(Symbology::Qr, &longest).unwrap().width, 57);
+        assert!(Barcode::encode(Symbology::Qr, &"x".repeat(MAX_QR_BYTES + 1)).is_err());
+    }
+
+    #[test]
+    fn test_render_and_printer_output() {
+        let barcode = Barcode::encode(Symbology::Code128, "R1").unwrap();
+        let bitmap = barcode.render(2, 40);
+        assert_eq!(bitmap.width, (57 + 20) * 2);
+        assert_eq!(bitmap.height, 40);
+        assert!(!bitmap.is_black(19, 0));
+        assert!(bitmap.is_black(20, 0) && bitmap.is_black(21, 39));
+
+        let raster = bitmap.escpos_raster();
+        assert_eq!(&raster[..4], &[0x1D, b'v', b'0', 0]);
+        assert_eq!(u16::from_le_bytes([raster[4], raster[5]]), 20);
+        assert_eq!(u16::from_le_bytes([raster[6], raster[7]]), 40);
+        assert_eq!(raster.len(), 8 + 20 * 40);
+
+        let qr = Barcode::encode(Symbology::Qr, "S01-000123").unwrap().render(3, 0);
+        assert_eq!((qr.width, qr.height), (29 * 3, 29 * 3));
+        assert!(qr.is_black(12, 12) && !qr.is_black(11, 12));
+        assert!(qr.zpl_graphic().starts_with("^GFA,957,957,11,"));
+    }
+}
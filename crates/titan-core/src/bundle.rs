@@ -0,0 +1,344 @@
+//! # Bundle Products
+//!
+//! Products sold as a set of other products (a gift basket, a meal deal):
+//! the bundle has its own SKU, barcode and price, and a bill of materials
+//! listing the components it is made of. Selling a bundle takes the
+//! components out of stock, not the bundle itself.
+//!
+//! ## Bill of Materials
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                      Selling a Bundle                                   │
+//! │                                                                         │
+//! │  GIFT-01 "Gift Basket"  (bundle, price 49.99)                          │
+//! │    ├── 1 × WINE-0042                                                   │
+//! │    ├── 2 × CHOC-0007                                                   │
+//! │    └── 1 × BASKET-S                                                    │
+//! │                                                                         │
+//! │  sell 3 × GIFT-01                                                      │
+//! │    check_components_available: every tracked component must have      │
+//! │      3 × its quantity in stock (unless it allows negative stock)       │
+//! │    component_quantities(3) ──► one inventory delta per component:      │
+//! │      WINE-0042 -3   CHOC-0007 -6   BASKET-S -3                         │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Rules
+//! - At most [`MAX_COMPONENTS`] components, each listed once, with a
+//!   quantity of 1 to [`MAX_COMPONENT_QUANTITY`]
+//! - A bundle is not a component of itself, and bundles don't nest
+//!   (enforced where the catalog is known, in the database layer)
+//! - An empty bill of materials makes the product an ordinary product
+//!
+//! Like attribute sets, the bill of materials is one document
+//! ([`ProductBundle`]) with its own `sync_version`; the newer version wins
+//! on every terminal.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::{CoreError, ValidationError};
+use crate::types::Product;
+use crate::validation::ValidationResult;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Most components one bundle may list.
+pub const MAX_COMPONENTS: usize = 20;
+
+/// Largest quantity of one component in a bundle.
+pub const MAX_COMPONENT_QUANTITY: i64 = 999;
+
+// =============================================================================
+// Bill of Materials
+// =============================================================================
+
+/// One line of a bill of materials: `quantity` of `product_id` per bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct BundleComponent {
+    pub product_id: String,
+    pub quantity: i64,
+}
+
+impl BundleComponent {
+    /// `quantity` of `product_id`.
+    pub fn new(product_id: impl Into<String>, quantity: i64) -> Self {
+        BundleComponent {
+            product_id: product_id.into(),
+            quantity,
+        }
+    }
+}
+
+/// A bundle product's bill of materials.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProductBundle {
+    /// The bundle product.
+    pub product_id: String,
+    /// Sorted by component product ID.
+    pub components: Vec<BundleComponent>,
+    /// Version of the bill of materials (0 for a product that was never
+    /// a bundle).
+    pub sync_version: i64,
+    #[ts(as = "String")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ProductBundle {
+    /// Whether the product is a bundle at all.
+    pub fn is_bundle(&self) -> bool {
+        !self.components.is_empty()
+    }
+
+    /// Checks the bill of materials (see the module rules).
+    pub fn validate(&self) -> ValidationResult<()> {
+        let normalized = normalize_components(&self.product_id, &self.components)?;
+        if normalized != self.components {
+            return Err(ValidationError::InvalidFormat {
+                field: "components".to_string(),
+                reason: "must be sorted by product ID".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// The component quantities for `bundles` bundles, e.g. the stock to
+    /// take out when they are sold.
+    pub fn component_quantities(&self, bundles: i64) -> Vec<BundleComponent> {
+        self.components
+            .iter()
+            .map(|c| BundleComponent::new(c.product_id.as_str(), c.quantity * bundles))
+            .collect()
+    }
+
+    /// How many bundles the components in stock can make up, or `None`
+    /// when no component limits it (untracked, or negative stock allowed).
+    /// `products` must hold every component.
+    pub fn available_bundles(&self, products: &[Product]) -> Option<i64> {
+        self.components
+            .iter()
+            .filter_map(|c| {
+                let product = products.iter().find(|p| p.id == c.product_id)?;
+                limits_stock(product).then(|| product.current_stock.unwrap_or(0).max(0) / c.quantity)
+            })
+            .min()
+    }
+}
+
+/// Validates a bill of materials and returns it sorted by component.
+///
+/// ## Errors
+/// - `Required` for an empty component ID
+/// - `NotAllowed` for the bundle listing itself
+/// - `OutOfRange` for a quantity outside 1..=[`MAX_COMPONENT_QUANTITY`]
+///   or more than [`MAX_COMPONENTS`] components
+/// - `Duplicate` for a component listed twice
+pub fn normalize_components(
+    bundle_id: &str,
+    components: &[BundleComponent],
+) -> ValidationResult<Vec<BundleComponent>> {
+    if components.len() > MAX_COMPONENTS {
+        return Err(ValidationError::OutOfRange {
+            field: "components".to_string(),
+            min: 0,
+            max: MAX_COMPONENTS as i64,
+        });
+    }
+
+    let mut normalized: Vec<BundleComponent> = Vec::with_capacity(components.len());
+    for component in components {
+        let product_id = component.product_id.trim();
+        if product_id.is_empty() {
+            return Err(ValidationError::Required {
+                field: "component".to_string(),
+            });
+        }
+        if product_id == bundle_id {
+            return Err(ValidationError::NotAllowed {
+                field: "component".to_string(),
+                allowed: vec!["products other than the bundle".to_string()],
+            });
+        }
+        if !(1..=MAX_COMPONENT_QUANTITY).contains(&component.quantity) {
+            return Err(ValidationError::OutOfRange {
+                field: "quantity".to_string(),
+                min: 1,
+                max: MAX_COMPONENT_QUANTITY,
+            });
+        }
+        if normalized.iter().any(|c| c.product_id == product_id) {
+            return Err(ValidationError::Duplicate {
+                field: "component".to_string(),
+                value: product_id.to_string(),
+            });
+        }
+        normalized.push(BundleComponent::new(product_id, component.quantity));
+    }
+
+    normalized.sort_by(|a, b| a.product_id.cmp(&b.product_id));
+    Ok(normalized)
+}
+
+// =============================================================================
+// Availability
+// =============================================================================
+
+/// Adds up component demand, e.g. from several bundle lines of one sale,
+/// into one entry per product.
+pub fn merge_demand(demand: impl IntoIterator<Item = BundleComponent>) -> Vec<BundleComponent> {
+    let mut merged: Vec<BundleComponent> = Vec::new();
+    for component in demand {
+        match merged.iter_mut().find(|c| c.product_id == component.product_id) {
+            Some(existing) => existing.quantity += component.quantity,
+            None => merged.push(component),
+        }
+    }
+    merged
+}
+
+/// Checks that the components in stock cover `demand` (one entry per
+/// product; see [`merge_demand`]). Follows the cart's stock rules:
+/// untracked components and components that allow negative stock always
+/// pass.
+///
+/// ## Errors
+/// - `CoreError::ProductNotFound` for a component missing from `products`
+/// - `CoreError::InsufficientStock` for the first component short of stock
+pub fn check_components_available(
+    demand: &[BundleComponent],
+    products: &[Product],
+) -> Result<(), CoreError> {
+    for component in demand {
+        let product = products
+            .iter()
+            .find(|p| p.id == component.product_id)
+            .ok_or_else(|| CoreError::ProductNotFound(component.product_id.clone()))?;
+
+        let available = product.current_stock.unwrap_or(0);
+        if limits_stock(product) && available < component.quantity {
+            return Err(CoreError::InsufficientStock {
+                sku: product.sku.clone(),
+                available,
+                requested: component.quantity,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Whether a product's stock level can block a sale.
+fn limits_stock(product: &Product) -> bool {
+    product.track_inventory && !product.allow_negative_stock
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracking::ItemTracking;
+
+    fn component_product(id: &str, stock: i64) -> Product {
+        Product {
+            id: id.to_string(),
+            tenant_id: "t-1".to_string(),
+            sku: id.to_uppercase(),
+            barcode: None,
+            name: id.to_string(),
+            description: None,
+            price_cents: 100,
+            base_price_cents: None,
+            cost_cents: None,
+            tax_rate_bps: 0,
+            track_inventory: true,
+            allow_negative_stock: false,
+            current_stock: Some(stock),
+            item_tracking: ItemTracking::None,
+            min_purchase_age: None,
+            is_active: true,
+            deleted_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            sync_version: 1,
+        }
+    }
+
+    fn basket() -> ProductBundle {
+        ProductBundle {
+            product_id: "basket".to_string(),
+            components: normalize_components(
+                "basket",
+                &[BundleComponent::new("wine", 1), BundleComponent::new("choc", 2)],
+            )
+            .unwrap(),
+            sync_version: 1,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_components() {
+        let bundle = basket();
+        assert_eq!(bundle.components[0].product_id, "choc");
+        assert!(bundle.validate().is_ok());
+
+        let invalid = [
+            vec![BundleComponent::new("basket", 1)],
+            vec![BundleComponent::new("wine", 0)],
+            vec![BundleComponent::new(" ", 1)],
+            vec![BundleComponent::new("wine", 1), BundleComponent::new("wine ", 2)],
+        ];
+        for components in invalid {
+            assert!(normalize_components("basket", &components).is_err());
+        }
+
+        let mut unsorted = basket();
+        unsorted.components.reverse();
+        assert!(unsorted.validate().is_err());
+    }
+
+    #[test]
+    fn test_component_quantities_and_availability() {
+        let bundle = basket();
+        let demand = bundle.component_quantities(3);
+        assert_eq!(demand, vec![BundleComponent::new("choc", 6), BundleComponent::new("wine", 3)]);
+
+        let products = vec![component_product("wine", 10), component_product("choc", 5)];
+        assert_eq!(bundle.available_bundles(&products), Some(2));
+
+        let err = check_components_available(&demand, &products).unwrap_err();
+        assert!(matches!(
+            err,
+            CoreError::InsufficientStock { ref sku, available: 5, requested: 6 } if sku == "CHOC"
+        ));
+        assert!(check_components_available(&bundle.component_quantities(2), &products).is_ok());
+
+        // Back-orderable and untracked components never block
+        let mut products = products;
+        products[1].allow_negative_stock = true;
+        assert!(check_components_available(&demand, &products).is_ok());
+        assert_eq!(bundle.available_bundles(&products), Some(10));
+        products[0].track_inventory = false;
+        assert_eq!(bundle.available_bundles(&products), None);
+
+        assert!(matches!(
+            check_components_available(&demand, &products[..1]),
+            Err(CoreError::ProductNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_merge_demand() {
+        let bundle = basket();
+        let merged = merge_demand(
+            bundle
+                .component_quantities(1)
+                .into_iter()
+                .chain(bundle.component_quantities(2)),
+        );
+        assert_eq!(merged, vec![BundleComponent::new("choc", 6), BundleComponent::new("wine", 3)]);
+    }
+}
@@ -0,0 +1,205 @@
+//! # Cart Totals
+//!
+//! Running totals of a cart, kept up to date one line at a time. Adding,
+//! changing or removing a line applies the difference between the line's
+//! old and new amounts, so a change costs one line's tax instead of a
+//! pass over the whole cart, and a 100-line cart is as quick to change as
+//! a 1-line one.
+//!
+//! ## Line Deltas
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Running Totals                                  │
+//! │                                                                         │
+//! │  LineAmounts::new(price, qty, rate)   subtotal + tax of one line,      │
+//! │                                       tax rounded per line             │
+//! │                                                                         │
+//! │  add_line(new)          ──► totals += new                              │
+//! │  change_line(old, new)  ──► totals += new - old                        │
+//! │  remove_line(old)       ──► totals -= old                              │
+//! │                                                                         │
+//! │  debug builds: verify(lines) after every change                        │
+//! │       totals == from_lines(lines), or panic naming both                │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Rules
+//! - Tax is rounded per line (as on the receipt), so the running tax is
+//!   always the sum of the lines' rounded taxes
+//! - Totals never go negative and the line count never underflows
+//! - `verify` recomputes from scratch and only runs in debug builds
+
+use serde::{Deserialize, Serialize};
+
+use crate::money::Money;
+use crate::types::TaxRate;
+
+/// Amounts of one cart line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct LineAmounts {
+    pub quantity: i64,
+    /// Unit price × quantity.
+    pub subtotal_cents: i64,
+    /// Tax on the line subtotal, rounded.
+    pub tax_cents: i64,
+}
+
+impl LineAmounts {
+    /// Computes a line's amounts.
+    pub fn new(unit_price_cents: i64, quantity: i64, tax_rate_bps: u32) -> Self {
+        let subtotal = Money::from_cents(unit_price_cents * quantity);
+        LineAmounts {
+            quantity,
+            subtotal_cents: subtotal.cents(),
+            tax_cents: subtotal
+                .calculate_tax(TaxRate::from_bps(tax_rate_bps))
+                .cents(),
+        }
+    }
+
+    /// Line subtotal plus tax.
+    pub fn total_cents(&self) -> i64 {
+        self.subtotal_cents + self.tax_cents
+    }
+}
+
+/// Totals of a cart, maintained line by line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct RunningTotals {
+    pub line_count: usize,
+    pub total_quantity: i64,
+    pub subtotal_cents: i64,
+    pub tax_cents: i64,
+}
+
+impl RunningTotals {
+    /// Computes the totals of a set of lines from scratch.
+    pub fn from_lines<I>(lines: I) -> Self
+    where
+        I: IntoIterator<Item = LineAmounts>,
+    {
+        let mut totals = RunningTotals::default();
+        for line in lines {
+            totals.add_line(line);
+        }
+        totals
+    }
+
+    /// Adds a new line.
+    pub fn add_line(&mut self, line: LineAmounts) {
+        self.line_count += 1;
+        self.apply(line, 1);
+    }
+
+    /// Replaces a line's old amounts with its new ones.
+    pub fn change_line(&mut self, old: LineAmounts, new: LineAmounts) {
+        self.apply(old, -1);
+        self.apply(new, 1);
+    }
+
+    /// Removes a line.
+    pub fn remove_line(&mut self, line: LineAmounts) {
+        debug_assert!(
+            self.line_count > 0,
+            "removing a line from empty cart totals"
+        );
+        self.line_count = self.line_count.saturating_sub(1);
+        self.apply(line, -1);
+    }
+
+    /// Grand total (subtotal + tax).
+    pub fn total_cents(&self) -> i64 {
+        self.subtotal_cents + self.tax_cents
+    }
+
+    /// Checks the running totals against a recomputation from `lines`.
+    ///
+    /// Only runs in debug builds; `lines` isn't even iterated in release.
+    ///
+    /// ## Panics
+    /// In debug builds, when the totals have drifted from the lines.
+    #[track_caller]
+    pub fn verify<I>(&self, lines: I)
+    where
+        I: IntoIterator<Item = LineAmounts>,
+    {
+        if cfg!(debug_assertions) {
+            let expected = RunningTotals::from_lines(lines);
+            assert_eq!(
+                *self, expected,
+                "running cart totals drifted from the cart lines"
+            );
+            assert!(
+                self.total_quantity >= 0 && self.subtotal_cents >= 0 && self.tax_cents >= 0,
+                "running cart totals went negative: {:?}",
+                self
+            );
+        }
+    }
+
+    fn apply(&mut self, line: LineAmounts, sign: i64) {
+        self.total_quantity += sign * line.quantity;
+        self.subtotal_cents += sign * line.subtotal_cents;
+        self.tax_cents += sign * line.tax_cents;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deltas_match_full_recompute() {
+        let mut lines: Vec<LineAmounts> = (0..100)
+            .map(|i| LineAmounts::new(199 + i, 1 + i % 3, 825))
+            .collect();
+        let mut totals = RunningTotals::from_lines(lines.iter().copied());
+
+        // Change every other line, then drop a few
+        for i in (0..100).step_by(2) {
+            let new = LineAmounts::new(lines[i].subtotal_cents / lines[i].quantity, 7, 825);
+            totals.change_line(lines[i], new);
+            lines[i] = new;
+        }
+        for _ in 0..10 {
+            let old = lines.pop().unwrap();
+            totals.remove_line(old);
+        }
+
+        totals.verify(lines.iter().copied());
+        assert_eq!(totals.line_count, 90);
+        assert_eq!(totals, RunningTotals::from_lines(lines.iter().copied()));
+    }
+
+    #[test]
+    fn test_tax_is_rounded_per_line() {
+        // Two lines at $0.06 with 8.25% tax: 0.495 → 0 cents each, not 1
+        // cent on the combined $0.12 (0.99 → 1)
+        let line = LineAmounts::new(6, 1, 825);
+        let totals = RunningTotals::from_lines([line, line]);
+        assert_eq!(totals.tax_cents, 0);
+        assert_eq!(totals.subtotal_cents, 12);
+        assert_eq!(totals.total_cents(), 12);
+    }
+
+    #[test]
+    fn test_remove_last_line_returns_to_zero() {
+        let line = LineAmounts::new(1099, 2, 825);
+        let mut totals = RunningTotals::default();
+        totals.add_line(line);
+        assert_eq!(totals.total_cents(), line.total_cents());
+
+        totals.remove_line(line);
+        assert_eq!(totals, RunningTotals::default());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "drifted")]
+    fn test_verify_catches_drift() {
+        let line = LineAmounts::new(500, 1, 0);
+        let mut totals = RunningTotals::from_lines([line]);
+        totals.subtotal_cents += 1;
+        totals.verify([line]);
+    }
+}
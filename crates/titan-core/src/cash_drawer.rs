@@ -0,0 +1,274 @@
+//! # Cash Drawers
+//!
+//! The hardware contract for opening a register's cash drawer, the
+//! per-station setting that picks the hardware, and the audit record kept
+//! for every manual ("no sale") open.
+//!
+//! ## Drawer Hardware
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Cash Drawer Hardware                            │
+//! │                                                                         │
+//! │  StationConfig.default_cash_drawer (TITAN_CASH_DRAWER)                  │
+//! │       │ DrawerConnection::parse                                         │
+//! │       ▼                                                                 │
+//! │  "printer"        ──► PrinterKick { printer: default_printer, pin 2 }   │
+//! │  "printer:pin5"   ──► PrinterKick { printer: default_printer, pin 5 }   │
+//! │  "direct:<port>"  ──► Direct { port }  (drawer interface box)           │
+//! │  "none" / unset   ──► None: open_cash_drawer fails with CONFIG_ERROR    │
+//! │                                                                         │
+//! │  dyn CashDrawer::open                                                   │
+//! │    PrinterKick: ESC p <pin> <on> <off> to the receipt printer, which    │
+//! │                 pulses the drawer port (RJ11/RJ12)                      │
+//! │    Direct:      DIRECT_TRIGGER byte to the interface's port             │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Drivers do the I/O and live with the app; this module only defines the
+//! contract and the bytes that get sent.
+//!
+//! ## Audit
+//! Opening the drawer outside a cash sale is how cash walks out of a till,
+//! so every manual open is recorded as a [`DrawerOpen`], including opens
+//! the hardware failed. Opens for a cash tender are covered by the sale.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::{CoreResult, ValidationError};
+use crate::validation::ValidationResult;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Pulse on-time, in units of 2 ms (50 ms).
+pub const KICK_ON_TIME: u8 = 25;
+
+/// Pulse off-time, in units of 2 ms (500 ms).
+pub const KICK_OFF_TIME: u8 = 250;
+
+/// Byte that fires a drawer interface box.
+pub const DIRECT_TRIGGER: u8 = 0x07;
+
+/// Longest reason kept with a manual open.
+pub const MAX_DRAWER_REASON_LEN: usize = 200;
+
+// =============================================================================
+// Connection
+// =============================================================================
+
+/// Printer drawer-port pin the kick-out pulse goes to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum KickPin {
+    /// Connector pin 2: the first (or only) drawer.
+    #[default]
+    Pin2,
+    /// Connector pin 5: the second drawer on a shared port.
+    Pin5,
+}
+
+/// How a station's cash drawer is wired.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DrawerConnection {
+    /// Chained to the receipt printer's drawer port.
+    PrinterKick { printer: String, pin: KickPin },
+    /// On its own interface (USB or serial trigger box).
+    Direct { port: String },
+}
+
+impl DrawerConnection {
+    /// Reads the station's drawer setting (see the module docs).
+    ///
+    /// Returns `None` when the station has no drawer.
+    ///
+    /// ## Errors
+    /// `ValidationError::InvalidFormat` for an unknown setting, a direct
+    /// drawer without a port, or a printer kick on a station without a
+    /// printer.
+    pub fn parse(setting: &str, printer: Option<&str>) -> ValidationResult<Option<Self>> {
+        let invalid = |reason: &str| ValidationError::InvalidFormat {
+            field: "cash_drawer".to_string(),
+            reason: reason.to_string(),
+        };
+        let setting = setting.trim();
+        let (kind, arg) = match setting.split_once(':') {
+            Some((kind, arg)) => (kind, Some(arg.trim())),
+            None => (setting, None),
+        };
+
+        match kind.to_lowercase().as_str() {
+            "" | "none" => Ok(None),
+            "printer" => {
+                let pin = match arg.map(str::to_lowercase).as_deref() {
+                    None | Some("pin2") => KickPin::Pin2,
+                    Some("pin5") => KickPin::Pin5,
+                    Some(_) => return Err(invalid("printer drawers use pin2 or pin5")),
+                };
+                let printer = printer
+                    .map(str::trim)
+                    .filter(|p| !p.is_empty())
+                    .ok_or_else(|| invalid("a printer kick needs the station's printer"))?;
+                Ok(Some(DrawerConnection::PrinterKick {
+                    printer: printer.to_string(),
+                    pin,
+                }))
+            }
+            "direct" => match arg.filter(|port| !port.is_empty()) {
+                Some(port) => Ok(Some(DrawerConnection::Direct {
+                    port: port.to_string(),
+                })),
+                None => Err(invalid("a direct drawer needs its port, e.g. direct:COM3")),
+            },
+            _ => Err(invalid("expected none, printer[:pin2|pin5] or direct:<port>")),
+        }
+    }
+
+    /// Stable name of the connection, kept in the audit trail.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DrawerConnection::PrinterKick { .. } => "printer_kick",
+            DrawerConnection::Direct { .. } => "direct",
+        }
+    }
+
+    /// Device the open command is written to.
+    pub fn device(&self) -> &str {
+        match self {
+            DrawerConnection::PrinterKick { printer, .. } => printer,
+            DrawerConnection::Direct { port } => port,
+        }
+    }
+
+    /// Bytes that open the drawer.
+    pub fn open_command(&self) -> Vec<u8> {
+        match self {
+            DrawerConnection::PrinterKick { pin, .. } => kick_pulse(*pin).to_vec(),
+            DrawerConnection::Direct { .. } => vec![DIRECT_TRIGGER],
+        }
+    }
+}
+
+/// ESC/POS "generate pulse" (`ESC p m t1 t2`) for `pin`.
+pub fn kick_pulse(pin: KickPin) -> [u8; 5] {
+    let m = match pin {
+        KickPin::Pin2 => 0,
+        KickPin::Pin5 => 1,
+    };
+    [0x1B, b'p', m, KICK_ON_TIME, KICK_OFF_TIME]
+}
+
+// =============================================================================
+// Driver Trait
+// =============================================================================
+
+/// A cash drawer driver.
+///
+/// Implementations live with the I/O they need (printer queues, serial
+/// ports).
+///
+/// ## Errors
+/// `CoreError::DeviceFailed` if the open command could not be delivered.
+/// Drawers do not report back, so success only means it was sent.
+pub trait CashDrawer: Send + Sync {
+    /// How the drawer is wired.
+    fn connection(&self) -> &DrawerConnection;
+
+    /// Fires the drawer.
+    fn open(&self) -> CoreResult<()>;
+}
+
+// =============================================================================
+// Audit
+// =============================================================================
+
+/// A manual drawer open, as kept in the audit trail.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DrawerOpen {
+    pub id: String,
+    #[ts(type = "number | null")]
+    pub station_number: Option<i64>,
+    /// `DrawerConnection::kind` of the drawer.
+    pub drawer: String,
+    /// Why staff opened it ("change for a customer").
+    pub reason: Option<String>,
+    /// Whether the open command reached the drawer.
+    pub succeeded: bool,
+    /// What went wrong when it did not.
+    pub error: Option<String>,
+    pub user_id: String,
+    pub device_id: String,
+    #[ts(as = "String")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Normalizes the reason given for a manual open: trimmed, empty as none.
+///
+/// ## Errors
+/// `ValidationError::TooLong` past [`MAX_DRAWER_REASON_LEN`] characters.
+pub fn drawer_reason(reason: Option<&str>) -> ValidationResult<Option<String>> {
+    let Some(reason) = reason.map(str::trim).filter(|r| !r.is_empty()) else {
+        return Ok(None);
+    };
+    if reason.chars().count() > MAX_DRAWER_REASON_LEN {
+        return Err(ValidationError::TooLong {
+            field: "reason".to_string(),
+            max: MAX_DRAWER_REASON_LEN,
+        });
+    }
+    Ok(Some(reason.to_string()))
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_drawer_settings() {
+        assert_eq!(DrawerConnection::parse("", None).unwrap(), None);
+        assert_eq!(DrawerConnection::parse("none", Some("lp0")).unwrap(), None);
+
+        let kick = DrawerConnection::parse("printer", Some("/dev/usb/lp0"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(kick.kind(), "printer_kick");
+        assert_eq!(kick.device(), "/dev/usb/lp0");
+        assert_eq!(kick.open_command(), vec![0x1B, b'p', 0, 25, 250]);
+
+        let second = DrawerConnection::parse("Printer:PIN5", Some("lp0"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(second.open_command()[2], 1);
+
+        let direct = DrawerConnection::parse("direct: COM3", None).unwrap().unwrap();
+        assert_eq!(direct.device(), "COM3");
+        assert_eq!(direct.open_command(), vec![DIRECT_TRIGGER]);
+    }
+
+    #[test]
+    fn test_bad_drawer_settings_rejected() {
+        assert!(DrawerConnection::parse("printer", None).is_err());
+        assert!(DrawerConnection::parse("printer:pin7", Some("lp0")).is_err());
+        assert!(DrawerConnection::parse("direct", None).is_err());
+        assert!(DrawerConnection::parse("usb", None).is_err());
+    }
+
+    #[test]
+    fn test_drawer_reason() {
+        assert_eq!(drawer_reason(None).unwrap(), None);
+        assert_eq!(drawer_reason(Some("  ")).unwrap(), None);
+        assert_eq!(
+            drawer_reason(Some(" Change for a customer ")).unwrap(),
+            Some("Change for a customer".to_string())
+        );
+        assert!(drawer_reason(Some(&"x".repeat(MAX_DRAWER_REASON_LEN + 1))).is_err());
+    }
+}
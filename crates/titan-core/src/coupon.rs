@@ -0,0 +1,471 @@
+//! # Coupons
+//!
+//! Coupon definitions issued in the cloud back office, the rules a cart has
+//! to meet to use one, and the redemptions that count against its uses.
+//!
+//! ## Coupon Lifecycle
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                           Coupon Lifecycle                              │
+//! │                                                                         │
+//! │  Back office ── UpsertCoupon ──► cloud ── "coupons" stream ──► stores   │
+//! │                                                                         │
+//! │  apply_coupon(code)                                                     │
+//! │     ├── check_usable: active, inside starts_at..expires_at, uses left   │
+//! │     └── discount_for(cart lines): min subtotal, eligible products       │
+//! │                                                                         │
+//! │  create_sale ──► CouponRedemption (checked again in one transaction)    │
+//! │     │                                                                   │
+//! │     └── COUPON document ──► hub ──► other terminals ──► cloud           │
+//! │                                       (redemptions merge by id)         │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Uses Across Stores
+//! Redemptions are append-only and keyed by UUID, so copies of a coupon
+//! merge by union wherever they meet (see [`crate::store_credit`] for the
+//! same model). A single-use coupon is therefore blocked on every terminal
+//! of a store as soon as the hub has relayed the redemption, and in every
+//! other store once the cloud has pushed it down. A code spent in two
+//! stores that were both offline from the cloud shows up as an over-used
+//! coupon when the redemptions meet; it is reported, not prevented.
+//!
+//! ## Discount Model
+//! The discount comes off the pre-tax line totals of the eligible lines and
+//! is allocated to them pro rata, the rounding remainder going to the
+//! largest line. Tax is charged on the undiscounted price, as for a
+//! manufacturer's coupon.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::{CoreError, CoreResult, ValidationError};
+use crate::validation::ValidationResult;
+
+/// Longest coupon code accepted.
+pub const MAX_COUPON_CODE_LEN: usize = 32;
+
+/// Highest percentage a coupon may take off, in basis points (100%).
+pub const MAX_PERCENT_BPS: i64 = 10_000;
+
+// =============================================================================
+// Coupon Kind
+// =============================================================================
+
+/// How a coupon's `value` is applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "snake_case"))]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum CouponKind {
+    /// `value` is a percentage in basis points (1000 = 10%).
+    Percent,
+    /// `value` is a fixed amount in cents.
+    Amount,
+}
+
+impl CouponKind {
+    /// Name as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CouponKind::Percent => "percent",
+            CouponKind::Amount => "amount",
+        }
+    }
+
+    /// Parses a stored name.
+    pub fn parse(s: &str) -> Option<CouponKind> {
+        match s {
+            "percent" => Some(CouponKind::Percent),
+            "amount" => Some(CouponKind::Amount),
+            _ => None,
+        }
+    }
+}
+
+// =============================================================================
+// Coupon & Redemptions
+// =============================================================================
+
+/// A coupon definition.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Coupon {
+    pub id: String,
+    pub tenant_id: String,
+    /// Code the cashier scans or types, normalized with [`normalize_code`].
+    pub code: String,
+    pub description: Option<String>,
+    pub kind: CouponKind,
+    /// Basis points for `Percent`, cents for `Amount`.
+    #[ts(type = "number")]
+    pub value: i64,
+    /// Smallest cart subtotal (before tax) the coupon applies to.
+    #[ts(type = "number | null")]
+    pub min_subtotal_cents: Option<i64>,
+    /// Products the discount applies to; empty for the whole cart.
+    #[serde(default)]
+    pub product_ids: Vec<String>,
+    #[ts(as = "Option<String>")]
+    pub starts_at: Option<DateTime<Utc>>,
+    #[ts(as = "Option<String>")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Total redemptions allowed across all stores: 1 for single-use,
+    /// `None` for unlimited.
+    #[ts(type = "number | null")]
+    pub max_uses: Option<i64>,
+    pub active: bool,
+    #[ts(as = "String")]
+    pub created_at: DateTime<Utc>,
+    #[ts(as = "String")]
+    pub updated_at: DateTime<Utc>,
+    pub sync_version: i64,
+}
+
+/// One use of a coupon on a sale.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CouponRedemption {
+    pub id: String,
+    pub coupon_id: String,
+    pub sale_id: String,
+    /// Store the sale was rung up in; empty until the hub or cloud stamps it.
+    #[serde(default)]
+    pub store_id: String,
+    pub device_id: String,
+    pub discount_cents: i64,
+    #[ts(as = "String")]
+    pub redeemed_at: DateTime<Utc>,
+}
+
+/// A coupon with every redemption known so far, as synced between
+/// terminals and stores.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CouponDocument {
+    #[serde(flatten)]
+    pub coupon: Coupon,
+    pub redemptions: Vec<CouponRedemption>,
+}
+
+impl CouponDocument {
+    /// Number of times the coupon has been used.
+    pub fn uses(&self) -> i64 {
+        self.redemptions.len() as i64
+    }
+
+    /// Checks the coupon against a cart at `now` and prices the discount.
+    pub fn evaluate(&self, lines: &[CouponLine], now: DateTime<Utc>) -> CoreResult<CouponDiscount> {
+        self.coupon.check_usable(self.uses(), now)?;
+        self.coupon.discount_for(lines)
+    }
+}
+
+// =============================================================================
+// Cart Evaluation
+// =============================================================================
+
+/// A cart line as the coupon rules see it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CouponLine {
+    pub product_id: String,
+    /// Pre-tax line total in cents.
+    pub line_total_cents: i64,
+}
+
+/// The discount a coupon gives on a cart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CouponDiscount {
+    /// Total discount in cents.
+    pub discount_cents: i64,
+    /// Discount per cart line, in the order given (0 for ineligible lines).
+    pub line_discounts: Vec<i64>,
+}
+
+impl Coupon {
+    /// Checks the coupon can be used at all, regardless of the cart.
+    ///
+    /// ## Errors
+    /// `CoreError::CouponRejected` if it is inactive, not started yet,
+    /// expired, or has no uses left.
+    pub fn check_usable(&self, uses: i64, now: DateTime<Utc>) -> CoreResult<()> {
+        if !self.active {
+            return Err(self.rejected("it has been withdrawn"));
+        }
+        if let Some(starts_at) = self.starts_at {
+            if now < starts_at {
+                return Err(self.rejected(format!("it is not valid before {}", starts_at.date_naive())));
+            }
+        }
+        if let Some(expires_at) = self.expires_at {
+            if now >= expires_at {
+                return Err(self.rejected(format!("it expired on {}", expires_at.date_naive())));
+            }
+        }
+        if let Some(max_uses) = self.max_uses {
+            if uses >= max_uses {
+                return Err(self.rejected(if max_uses == 1 {
+                    "it has already been used".to_string()
+                } else {
+                    format!("it has been used {} of {} times", uses, max_uses)
+                }));
+            }
+        }
+        Ok(())
+    }
+
+    /// Prices the discount on a cart.
+    ///
+    /// ## Errors
+    /// `CoreError::CouponRejected` if the cart is below the minimum
+    /// subtotal or holds none of the eligible products.
+    pub fn discount_for(&self, lines: &[CouponLine]) -> CoreResult<CouponDiscount> {
+        let subtotal: i64 = lines.iter().map(|l| l.line_total_cents).sum();
+        if let Some(min) = self.min_subtotal_cents {
+            if subtotal < min {
+                return Err(self.rejected(format!(
+                    "the cart must come to at least {}.{:02} before tax",
+                    min / 100,
+                    min % 100
+                )));
+            }
+        }
+
+        let eligible: Vec<bool> = lines
+            .iter()
+            .map(|l| {
+                l.line_total_cents > 0
+                    && (self.product_ids.is_empty() || self.product_ids.contains(&l.product_id))
+            })
+            .collect();
+        let eligible_total: i64 = lines
+            .iter()
+            .zip(&eligible)
+            .filter(|(_, e)| **e)
+            .map(|(l, _)| l.line_total_cents)
+            .sum();
+        if eligible_total == 0 {
+            return Err(self.rejected("none of the products in the cart qualify"));
+        }
+
+        let discount_cents = match self.kind {
+            CouponKind::Percent => {
+                ((eligible_total as i128 * self.value as i128 + 5000) / 10000) as i64
+            }
+            CouponKind::Amount => self.value,
+        }
+        .clamp(0, eligible_total);
+
+        // Pro rata by line total, rounding down; the remainder goes to the
+        // largest eligible line so the parts add up exactly.
+        let mut line_discounts: Vec<i64> = lines
+            .iter()
+            .zip(&eligible)
+            .map(|(l, e)| {
+                if *e {
+                    (discount_cents as i128 * l.line_total_cents as i128 / eligible_total as i128) as i64
+                } else {
+                    0
+                }
+            })
+            .collect();
+        let remainder = discount_cents - line_discounts.iter().sum::<i64>();
+        if let Some(largest) = (0..lines.len())
+            .filter(|i| eligible[*i])
+            .max_by_key(|i| lines[*i].line_total_cents)
+        {
+            line_discounts[largest] += remainder;
+        }
+
+        Ok(CouponDiscount {
+            discount_cents,
+            line_discounts,
+        })
+    }
+
+    fn rejected(&self, reason: impl Into<String>) -> CoreError {
+        CoreError::CouponRejected {
+            code: self.code.clone(),
+            reason: reason.into(),
+        }
+    }
+}
+
+// =============================================================================
+// Rules
+// =============================================================================
+
+/// Normalizes a scanned or typed code: trimmed, upper case, no inner spaces
+/// or dashes.
+pub fn normalize_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+/// Validates a coupon definition before it is saved.
+///
+/// ## Rules
+/// - Code is 1 to [`MAX_COUPON_CODE_LEN`] letters and digits
+/// - Value is positive; a percentage is at most [`MAX_PERCENT_BPS`]
+/// - Minimum subtotal and maximum uses are positive when set
+/// - The validity window is not empty
+pub fn validate_coupon(coupon: &Coupon) -> ValidationResult<()> {
+    if coupon.code.is_empty() {
+        return Err(ValidationError::Required {
+            field: "code".to_string(),
+        });
+    }
+    if coupon.code.len() > MAX_COUPON_CODE_LEN {
+        return Err(ValidationError::TooLong {
+            field: "code".to_string(),
+            max: MAX_COUPON_CODE_LEN,
+        });
+    }
+    if normalize_code(&coupon.code) != coupon.code
+        || !coupon.code.chars().all(|c| c.is_ascii_alphanumeric())
+    {
+        return Err(ValidationError::InvalidFormat {
+            field: "code".to_string(),
+            reason: "use upper-case letters and digits only".to_string(),
+        });
+    }
+    if coupon.value <= 0 {
+        return Err(ValidationError::MustBePositive {
+            field: "value".to_string(),
+        });
+    }
+    if coupon.kind == CouponKind::Percent && coupon.value > MAX_PERCENT_BPS {
+        return Err(ValidationError::OutOfRange {
+            field: "value".to_string(),
+            min: 1,
+            max: MAX_PERCENT_BPS,
+        });
+    }
+    if coupon.min_subtotal_cents.is_some_and(|m| m <= 0) {
+        return Err(ValidationError::MustBePositive {
+            field: "min_subtotal_cents".to_string(),
+        });
+    }
+    if coupon.max_uses.is_some_and(|m| m <= 0) {
+        return Err(ValidationError::MustBePositive {
+            field: "max_uses".to_string(),
+        });
+    }
+    if let (Some(starts_at), Some(expires_at)) = (coupon.starts_at, coupon.expires_at) {
+        if expires_at <= starts_at {
+            return Err(ValidationError::InvalidFormat {
+                field: "expires_at".to_string(),
+                reason: "must be after starts_at".to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Unit Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn coupon(kind: CouponKind, value: i64) -> Coupon {
+        Coupon {
+            id: "c1".to_string(),
+            tenant_id: "t".to_string(),
+            code: "SAVE10".to_string(),
+            description: None,
+            kind,
+            value,
+            min_subtotal_cents: None,
+            product_ids: Vec::new(),
+            starts_at: None,
+            expires_at: None,
+            max_uses: None,
+            active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            sync_version: 1,
+        }
+    }
+
+    fn line(product_id: &str, line_total_cents: i64) -> CouponLine {
+        CouponLine {
+            product_id: product_id.to_string(),
+            line_total_cents,
+        }
+    }
+
+    #[test]
+    fn test_discount_is_allocated_to_eligible_lines() {
+        let lines = [line("a", 1000), line("b", 333), line("c", 500)];
+
+        let percent = coupon(CouponKind::Percent, 1000).discount_for(&lines).unwrap();
+        assert_eq!(percent.discount_cents, 183);
+        assert_eq!(percent.line_discounts, vec![101, 33, 49]);
+
+        let mut only_b = coupon(CouponKind::Amount, 5000);
+        only_b.product_ids = vec!["b".to_string()];
+        let capped = only_b.discount_for(&lines).unwrap();
+        assert_eq!(capped.discount_cents, 333);
+        assert_eq!(capped.line_discounts, vec![0, 333, 0]);
+
+        only_b.product_ids = vec!["z".to_string()];
+        assert!(matches!(
+            only_b.discount_for(&lines),
+            Err(CoreError::CouponRejected { .. })
+        ));
+
+        let mut minimum = coupon(CouponKind::Amount, 100);
+        minimum.min_subtotal_cents = Some(2000);
+        assert!(minimum.discount_for(&lines).is_err());
+        minimum.min_subtotal_cents = Some(1833);
+        assert!(minimum.discount_for(&lines).is_ok());
+    }
+
+    #[test]
+    fn test_usable_window_and_uses() {
+        let now = Utc::now();
+        let mut c = coupon(CouponKind::Amount, 500);
+        c.max_uses = Some(1);
+        assert!(c.check_usable(0, now).is_ok());
+        assert!(c.check_usable(1, now).is_err());
+
+        c.max_uses = None;
+        c.starts_at = Some(now + Duration::days(1));
+        assert!(c.check_usable(0, now).is_err());
+        c.starts_at = None;
+        c.expires_at = Some(now);
+        assert!(c.check_usable(0, now).is_err());
+        c.expires_at = Some(now + Duration::seconds(1));
+        assert!(c.check_usable(0, now).is_ok());
+
+        c.active = false;
+        assert_eq!(
+            c.check_usable(0, now).unwrap_err().code(),
+            crate::error::ErrorCode::CouponRejected
+        );
+    }
+
+    #[test]
+    fn test_code_normalization_and_validation() {
+        assert_eq!(normalize_code(" save-10 "), "SAVE10");
+
+        let mut c = coupon(CouponKind::Percent, 1000);
+        assert!(validate_coupon(&c).is_ok());
+        c.value = MAX_PERCENT_BPS + 1;
+        assert!(validate_coupon(&c).is_err());
+        c.value = 1000;
+        c.code = "save10".to_string();
+        assert!(validate_coupon(&c).is_err());
+        c.code = "SAVE10".to_string();
+        c.max_uses = Some(0);
+        assert!(validate_coupon(&c).is_err());
+    }
+}
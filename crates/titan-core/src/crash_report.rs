@@ -0,0 +1,264 @@
+//! # Crash Reports
+//!
+//! What a terminal writes to disk when it crashes, so a field crash comes
+//! back as a backtrace and the log lines leading up to it instead of
+//! "the till froze at lunch". The desktop app writes one report per crash
+//! (a Rust panic, or an error the frontend reports) and uploads the
+//! queued reports through the cloud when crash upload is turned on.
+//!
+//! ## Report Lifecycle
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Crash Report                                    │
+//! │                                                                         │
+//! │  tracing output ──► LogTail (last LOG_TAIL_LINES lines, ANSI stripped)  │
+//! │                                                                         │
+//! │  panic / frontend error                                                 │
+//! │    ──► CrashReport { message, location, backtrace, log_tail,            │
+//! │                      app_version, db_schema_version, os, arch }         │
+//! │    ──► crashes/crash-20261017T121500Z-<id>.json   (queued)              │
+//! │    ──► uploaded ──► crashes/sent/…                                      │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Rules
+//! - The log tail keeps whole lines only; a line longer than
+//!   `MAX_LOG_LINE_LEN` is cut, so one huge log line cannot crowd out the rest
+//! - Report file names sort by crash time and are valid object names in the
+//!   cloud's storage area
+
+use std::any::Any;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Log lines kept for crash reports.
+pub const LOG_TAIL_LINES: usize = 200;
+
+/// Longest log line kept (in characters).
+pub const MAX_LOG_LINE_LEN: usize = 2_000;
+
+/// File name prefix of crash reports.
+const FILE_PREFIX: &str = "crash-";
+
+/// File name extension of crash reports.
+const FILE_EXTENSION: &str = ".json";
+
+/// What crashed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashKind {
+    /// A Rust panic in the app process.
+    Panic,
+    /// An uncaught error in the frontend.
+    Frontend,
+}
+
+/// One crash, as written to disk and uploaded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CrashReport {
+    pub id: String,
+    pub kind: CrashKind,
+    #[ts(as = "String")]
+    pub occurred_at: DateTime<Utc>,
+    /// Panic message or error text.
+    pub message: String,
+    /// `file:line:column` of the panic, or the frontend source location.
+    pub location: Option<String>,
+    /// Name of the thread that panicked.
+    pub thread: Option<String>,
+    /// Rust backtrace or JavaScript stack (empty when unavailable).
+    pub backtrace: String,
+    /// Log lines leading up to the crash, oldest first.
+    pub log_tail: Vec<String>,
+    pub app_version: String,
+    /// Newest database migration this build applies.
+    #[ts(type = "number | null")]
+    pub db_schema_version: Option<i64>,
+    pub os: String,
+    pub arch: String,
+}
+
+impl CrashReport {
+    /// File name of the report: `crash-<UTC time>-<id>.json`.
+    pub fn file_name(&self) -> String {
+        format!(
+            "{}{}-{}{}",
+            FILE_PREFIX,
+            self.occurred_at.format("%Y%m%dT%H%M%SZ"),
+            self.id,
+            FILE_EXTENSION
+        )
+    }
+
+    /// Whether `name` is a crash report file name.
+    pub fn is_report_file(name: &str) -> bool {
+        name.starts_with(FILE_PREFIX) && name.ends_with(FILE_EXTENSION)
+    }
+}
+
+/// The message of a panic payload (`panic!` with a literal or a format).
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
+
+// =============================================================================
+// Log Tail
+// =============================================================================
+
+/// The most recent log lines, fed with raw log output.
+#[derive(Debug)]
+pub struct LogTail {
+    capacity: usize,
+    inner: Mutex<TailBuffer>,
+}
+
+#[derive(Debug, Default)]
+struct TailBuffer {
+    lines: VecDeque<String>,
+    /// Output after the last newline.
+    partial: String,
+}
+
+impl LogTail {
+    /// Creates a tail keeping the last `capacity` lines.
+    pub fn new(capacity: usize) -> Self {
+        LogTail {
+            capacity,
+            inner: Mutex::new(TailBuffer::default()),
+        }
+    }
+
+    /// Adds log output, which may hold several lines or part of one.
+    pub fn push(&self, output: &[u8]) {
+        let text = String::from_utf8_lossy(output);
+        // A panic while holding the lock must not stop logging
+        let mut buffer = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut pieces = text.split('\n').peekable();
+        while let Some(piece) = pieces.next() {
+            if pieces.peek().is_none() {
+                // No newline after this piece (yet)
+                append_capped(&mut buffer.partial, piece);
+                break;
+            }
+            let mut line = std::mem::take(&mut buffer.partial);
+            append_capped(&mut line, piece);
+            let line = strip_ansi(line.trim_end_matches('\r'));
+            if buffer.lines.len() == self.capacity {
+                buffer.lines.pop_front();
+            }
+            buffer.lines.push_back(line);
+        }
+    }
+
+    /// The kept lines, oldest first.
+    pub fn lines(&self) -> Vec<String> {
+        let buffer = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        buffer.lines.iter().cloned().collect()
+    }
+}
+
+impl Default for LogTail {
+    fn default() -> Self {
+        LogTail::new(LOG_TAIL_LINES)
+    }
+}
+
+/// Appends up to `MAX_LOG_LINE_LEN` characters in total.
+fn append_capped(line: &mut String, piece: &str) {
+    let room = MAX_LOG_LINE_LEN.saturating_sub(line.chars().count());
+    line.extend(piece.chars().take(room));
+}
+
+/// Removes terminal color codes (`ESC [ … letter`).
+fn strip_ansi(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn report() -> CrashReport {
+        CrashReport {
+            id: "0c5e".to_string(),
+            kind: CrashKind::Panic,
+            occurred_at: Utc.with_ymd_and_hms(2026, 10, 17, 12, 15, 0).unwrap(),
+            message: "index out of bounds".to_string(),
+            location: Some("src/state/cart.rs:88:17".to_string()),
+            thread: Some("tokio-runtime-worker".to_string()),
+            backtrace: String::new(),
+            log_tail: vec![],
+            app_version: "0.1.0".to_string(),
+            db_schema_version: Some(32),
+            os: "windows".to_string(),
+            arch: "x86_64".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_file_name_sorts_by_time() {
+        let report = report();
+        assert_eq!(report.file_name(), "crash-20261017T121500Z-0c5e.json");
+        assert!(CrashReport::is_report_file(&report.file_name()));
+        assert!(!CrashReport::is_report_file("pii.key"));
+    }
+
+    #[test]
+    fn test_log_tail_keeps_last_whole_lines() {
+        let tail = LogTail::new(3);
+        tail.push(b"one\ntwo\nthr");
+        assert_eq!(tail.lines(), vec!["one", "two"]);
+
+        tail.push(b"ee\nfour\n");
+        assert_eq!(tail.lines(), vec!["two", "three", "four"]);
+    }
+
+    #[test]
+    fn test_log_tail_strips_colors_and_caps_lines() {
+        let tail = LogTail::new(10);
+        tail.push(b"\x1b[2m2026-10-17\x1b[0m \x1b[32m INFO\x1b[0m started\r\n");
+        tail.push(format!("{}\n", "x".repeat(MAX_LOG_LINE_LEN + 50)).as_bytes());
+
+        let lines = tail.lines();
+        assert_eq!(lines[0], "2026-10-17  INFO started");
+        assert_eq!(lines[1].len(), MAX_LOG_LINE_LEN);
+    }
+
+    #[test]
+    fn test_panic_message() {
+        let literal = std::panic::catch_unwind(|| panic!("boom")).unwrap_err();
+        assert_eq!(panic_message(literal.as_ref()), "boom");
+
+        let formatted = std::panic::catch_unwind(|| panic!("line {}", 3)).unwrap_err();
+        assert_eq!(panic_message(formatted.as_ref()), "line 3");
+    }
+}
@@ -0,0 +1,242 @@
+//! # Day End
+//!
+//! The end-of-day routine a terminal runs at closing time: check that the
+//! tills were closed, run the Z close, back up the database and push the
+//! day's sales out before the store goes dark. The result is one
+//! [`DayEndReport`], shown on the terminal and sent to the hub so the
+//! manager sees every register's close in one place.
+//!
+//! ## Steps
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Day-End Job                                     │
+//! │                                                                         │
+//! │  1. open_tills   tills still open ──► warning (they are not closed      │
+//! │                  for the cashier: counting the drawer is their job)     │
+//! │  2. z_close      ZReport: completed sales since the previous Z close,   │
+//! │                  totals and payments by method; numbered per device     │
+//! │  3. backup       copy of the database, the oldest ones pruned           │
+//! │  4. sync_flush   outbox pushed to the hub now; entries still queued     │
+//! │                  afterwards ──► warning                                 │
+//! │                                                                         │
+//! │  Each step runs even if an earlier one failed; the report's outcome     │
+//! │  is its worst step.                                                     │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Rules
+//! - A Z close covers every sale completed since the device's previous Z
+//!   close, so a missed day is picked up by the next close
+//! - One Z close per device and business date: running the day end again
+//!   returns the same Z report
+//! - The business date of a run before `DAY_ROLLOVER_HOURS` in the
+//!   morning is the day before (a 02:00 close belongs to the night before)
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::types::PaymentMethod;
+
+/// Local hour before which a run belongs to the previous business date.
+pub const DAY_ROLLOVER_HOURS: i64 = 4;
+
+/// Database backups kept by the day-end job.
+pub const BACKUPS_KEPT: usize = 14;
+
+/// A step of the day-end job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum DayEndStep {
+    /// Warn about tills still open
+    OpenTills,
+    /// Close the day's sales into a Z report
+    ZClose,
+    /// Back up the database
+    Backup,
+    /// Push the outbox to the hub
+    SyncFlush,
+}
+
+/// How a step went, from best to worst.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum StepOutcome {
+    Ok,
+    /// Not run (e.g. sync not configured)
+    Skipped,
+    /// Done, but needs a look (open tills, entries still queued)
+    Warning,
+    Failed,
+}
+
+/// Result of one step.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DayEndStepResult {
+    pub step: DayEndStep,
+    pub outcome: StepOutcome,
+    /// What happened, for the manager.
+    pub detail: String,
+}
+
+/// Payments of one method in a Z report.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PaymentTotal {
+    pub method: PaymentMethod,
+    #[ts(type = "number")]
+    pub count: i64,
+    #[ts(type = "number")]
+    pub amount_cents: i64,
+}
+
+/// A Z close: the sales a device completed since its previous close.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ZReport {
+    pub id: String,
+    pub device_id: String,
+    #[ts(type = "number | null")]
+    pub station_number: Option<i64>,
+    /// Z number on this device, from 1.
+    #[ts(type = "number")]
+    pub sequence: i64,
+    #[ts(as = "String")]
+    pub business_date: NaiveDate,
+    /// Previous close (`None` for the device's first).
+    #[ts(as = "Option<String>")]
+    pub period_start: Option<DateTime<Utc>>,
+    #[ts(as = "String")]
+    pub closed_at: DateTime<Utc>,
+    #[ts(type = "number")]
+    pub sale_count: i64,
+    /// Sales voided in the period.
+    #[ts(type = "number")]
+    pub void_count: i64,
+    #[ts(type = "number")]
+    pub subtotal_cents: i64,
+    #[ts(type = "number")]
+    pub tax_cents: i64,
+    #[ts(type = "number")]
+    pub discount_cents: i64,
+    #[ts(type = "number")]
+    pub total_cents: i64,
+    /// By payment method.
+    pub payments: Vec<PaymentTotal>,
+}
+
+/// Outcome of a day-end run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DayEndReport {
+    pub device_id: String,
+    #[ts(type = "number | null")]
+    pub station_number: Option<i64>,
+    #[ts(as = "String")]
+    pub business_date: NaiveDate,
+    #[ts(as = "String")]
+    pub started_at: DateTime<Utc>,
+    #[ts(as = "String")]
+    pub finished_at: DateTime<Utc>,
+    /// The Z report, if the close succeeded.
+    pub z_report: Option<ZReport>,
+    pub steps: Vec<DayEndStepResult>,
+}
+
+impl DayEndReport {
+    /// The worst step outcome (`Ok` without steps).
+    pub fn outcome(&self) -> StepOutcome {
+        self.steps
+            .iter()
+            .map(|s| s.outcome)
+            .max()
+            .unwrap_or(StepOutcome::Ok)
+    }
+
+    /// The result of `step`, if it ran.
+    pub fn step(&self, step: DayEndStep) -> Option<&DayEndStepResult> {
+        self.steps.iter().find(|s| s.step == step)
+    }
+
+    /// One line per failed step, e.g. "backup: disk full".
+    pub fn failures(&self) -> Vec<String> {
+        self.steps
+            .iter()
+            .filter(|s| s.outcome == StepOutcome::Failed)
+            .map(|s| format!("{}: {}", s.step.as_str(), s.detail))
+            .collect()
+    }
+}
+
+impl DayEndStep {
+    /// Step name as in job logs.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DayEndStep::OpenTills => "open_tills",
+            DayEndStep::ZClose => "z_close",
+            DayEndStep::Backup => "backup",
+            DayEndStep::SyncFlush => "sync_flush",
+        }
+    }
+}
+
+/// Business date of a day end run at `at`, in the store's local time at
+/// `offset`: runs before `DAY_ROLLOVER_HOURS` belong to the day before.
+pub fn business_date(at: DateTime<Utc>, offset: FixedOffset) -> NaiveDate {
+    (at.with_timezone(&offset) - Duration::hours(DAY_ROLLOVER_HOURS)).date_naive()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn step(step: DayEndStep, outcome: StepOutcome) -> DayEndStepResult {
+        DayEndStepResult {
+            step,
+            outcome,
+            detail: "disk full".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_business_date_rolls_over_in_the_morning() {
+        let karachi = FixedOffset::east_opt(5 * 3600).unwrap();
+        let date = |d| NaiveDate::from_ymd_opt(2026, 3, d).unwrap();
+        // 23:00 local on the 10th
+        let evening = Utc.with_ymd_and_hms(2026, 3, 10, 18, 0, 0).unwrap();
+        assert_eq!(business_date(evening, karachi), date(10));
+        // 02:00 local on the 11th still closes the 10th
+        let night = Utc.with_ymd_and_hms(2026, 3, 10, 21, 0, 0).unwrap();
+        assert_eq!(business_date(night, karachi), date(10));
+        // 05:00 local on the 11th
+        let morning = Utc.with_ymd_and_hms(2026, 3, 11, 0, 0, 0).unwrap();
+        assert_eq!(business_date(morning, karachi), date(11));
+    }
+
+    #[test]
+    fn test_outcome_is_the_worst_step() {
+        let mut report = DayEndReport {
+            device_id: "pos-01".to_string(),
+            station_number: Some(1),
+            business_date: NaiveDate::from_ymd_opt(2026, 3, 10).unwrap(),
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+            z_report: None,
+            steps: vec![
+                step(DayEndStep::OpenTills, StepOutcome::Warning),
+                step(DayEndStep::SyncFlush, StepOutcome::Skipped),
+            ],
+        };
+        assert_eq!(report.outcome(), StepOutcome::Warning);
+        assert!(report.failures().is_empty());
+
+        report.steps.push(step(DayEndStep::Backup, StepOutcome::Failed));
+        assert_eq!(report.outcome(), StepOutcome::Failed);
+        assert_eq!(report.failures(), vec!["backup: disk full"]);
+        assert!(report.step(DayEndStep::ZClose).is_none());
+    }
+}
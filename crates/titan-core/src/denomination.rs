@@ -0,0 +1,501 @@
+//! # Currency Denominations
+//!
+//! The notes and coins of a currency, with helpers for counting a drawer
+//! and breaking a change amount into physical cash.
+//!
+//! ## Change Breakdown
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                  Change Breakdown (USD, $6.41 change)                   │
+//! │                                                                         │
+//! │  Denominations (largest first):                                         │
+//! │    $100 $50 $20 $10 $5 $1 25¢ 10¢ 5¢ 1¢                                 │
+//! │                                                                         │
+//! │  641 ─► $5  × 1 ─► 141                                                 │
+//! │         $1  × 1 ─►  41                                                 │
+//! │         25¢ × 1 ─►  16                                                 │
+//! │         10¢ × 1 ─►   6                                                 │
+//! │         5¢  × 1 ─►   1                                                 │
+//! │         1¢  × 1 ─►   0   (6 pieces - the minimum possible)             │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Why Canonical Sets Only?
+//! Taking the largest piece first is only optimal for *canonical* sets.
+//! Every real currency is canonical, but a hand-configured set like
+//! {1¢, 3¢, 4¢} is not (6¢ → 4+1+1 instead of 3+3). Such sets are rejected
+//! at construction, so [`CurrencyDenominations::change_breakdown`] can stay
+//! a simple greedy pass and still return the fewest pieces.
+//!
+//! Canonicity is checked up to the sum of the two largest values, so a
+//! custom set's largest piece is capped at [`MAX_DENOMINATION_CENTS`].
+//!
+//! ## Amounts That Cannot Be Made
+//! Some currencies have no coin for the smallest accounting unit (e.g. PKR
+//! has no paisa coins). The part of a change amount that cannot be paid out
+//! is reported as `remainder_cents` instead of being silently dropped.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::ValidationError;
+use crate::till::DenominationCount;
+use crate::validation::ValidationResult;
+
+// =============================================================================
+// Denomination
+// =============================================================================
+
+/// Largest note or coin a set may have (10,000 in major units).
+pub const MAX_DENOMINATION_CENTS: i64 = 1_000_000;
+
+/// Whether a denomination is a banknote or a coin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum DenominationKind {
+    Note,
+    Coin,
+}
+
+/// A single note or coin of a currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Denomination {
+    /// Face value in the smallest currency unit.
+    pub value_cents: i64,
+    pub kind: DenominationKind,
+}
+
+impl Denomination {
+    /// Creates a banknote denomination.
+    pub const fn note(value_cents: i64) -> Self {
+        Denomination {
+            value_cents,
+            kind: DenominationKind::Note,
+        }
+    }
+
+    /// Creates a coin denomination.
+    pub const fn coin(value_cents: i64) -> Self {
+        Denomination {
+            value_cents,
+            kind: DenominationKind::Coin,
+        }
+    }
+}
+
+// =============================================================================
+// Built-in Currency Sets
+// =============================================================================
+
+const USD: &[Denomination] = &[
+    Denomination::note(10000),
+    Denomination::note(5000),
+    Denomination::note(2000),
+    Denomination::note(1000),
+    Denomination::note(500),
+    Denomination::note(100),
+    Denomination::coin(25),
+    Denomination::coin(10),
+    Denomination::coin(5),
+    Denomination::coin(1),
+];
+
+const EUR: &[Denomination] = &[
+    Denomination::note(50000),
+    Denomination::note(20000),
+    Denomination::note(10000),
+    Denomination::note(5000),
+    Denomination::note(2000),
+    Denomination::note(1000),
+    Denomination::note(500),
+    Denomination::coin(200),
+    Denomination::coin(100),
+    Denomination::coin(50),
+    Denomination::coin(20),
+    Denomination::coin(10),
+    Denomination::coin(5),
+    Denomination::coin(2),
+    Denomination::coin(1),
+];
+
+const GBP: &[Denomination] = &[
+    Denomination::note(5000),
+    Denomination::note(2000),
+    Denomination::note(1000),
+    Denomination::note(500),
+    Denomination::coin(200),
+    Denomination::coin(100),
+    Denomination::coin(50),
+    Denomination::coin(20),
+    Denomination::coin(10),
+    Denomination::coin(5),
+    Denomination::coin(2),
+    Denomination::coin(1),
+];
+
+/// PKR (1 rupee = 100 paisa; no paisa coins are in circulation).
+const PKR: &[Denomination] = &[
+    Denomination::note(500000),
+    Denomination::note(100000),
+    Denomination::note(50000),
+    Denomination::note(10000),
+    Denomination::note(5000),
+    Denomination::note(2000),
+    Denomination::note(1000),
+    Denomination::coin(500),
+    Denomination::coin(200),
+    Denomination::coin(100),
+];
+
+// =============================================================================
+// Currency Denominations
+// =============================================================================
+
+/// The configured notes and coins for one currency.
+///
+/// Denominations are kept sorted largest first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CurrencyDenominations {
+    /// ISO 4217 currency code (e.g., "USD").
+    pub currency_code: String,
+    pub denominations: Vec<Denomination>,
+}
+
+/// Result of breaking a change amount into notes and coins.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ChangeBreakdown {
+    /// Pieces to hand back, largest first (zero quantities omitted).
+    pub pieces: Vec<DenominationCount>,
+    /// Value covered by `pieces`.
+    pub total_cents: i64,
+    /// Amount that cannot be paid with the available denominations.
+    pub remainder_cents: i64,
+}
+
+impl ChangeBreakdown {
+    /// Total number of notes and coins.
+    pub fn piece_count(&self) -> i64 {
+        self.pieces.iter().map(|p| p.quantity).sum()
+    }
+}
+
+impl CurrencyDenominations {
+    /// Creates a custom denomination set.
+    ///
+    /// ## Rules
+    /// - At least one denomination
+    /// - All values positive, unique and at most [`MAX_DENOMINATION_CENTS`]
+    /// - The set must be canonical (see module docs)
+    pub fn new(
+        currency_code: impl Into<String>,
+        mut denominations: Vec<Denomination>,
+    ) -> ValidationResult<Self> {
+        if denominations.is_empty() {
+            return Err(ValidationError::Required {
+                field: "denominations".to_string(),
+            });
+        }
+
+        denominations.sort_by_key(|d| std::cmp::Reverse(d.value_cents));
+
+        for (i, d) in denominations.iter().enumerate() {
+            if d.value_cents <= 0 {
+                return Err(ValidationError::MustBePositive {
+                    field: "value_cents".to_string(),
+                });
+            }
+            if d.value_cents > MAX_DENOMINATION_CENTS {
+                return Err(ValidationError::OutOfRange {
+                    field: "value_cents".to_string(),
+                    min: 1,
+                    max: MAX_DENOMINATION_CENTS,
+                });
+            }
+            if i > 0 && denominations[i - 1].value_cents == d.value_cents {
+                return Err(ValidationError::Duplicate {
+                    field: "value_cents".to_string(),
+                    value: d.value_cents.to_string(),
+                });
+            }
+        }
+
+        let set = CurrencyDenominations {
+            currency_code: currency_code.into(),
+            denominations,
+        };
+
+        if !set.is_canonical() {
+            return Err(ValidationError::InvalidFormat {
+                field: "denominations".to_string(),
+                reason: "largest-first change would not give the fewest pieces".to_string(),
+            });
+        }
+
+        Ok(set)
+    }
+
+    /// Parses a custom set written as `notes:<values>;coins:<values>` in
+    /// major units, e.g. `"notes:5000,1000,500,100;coins:10,5,2,1"`.
+    ///
+    /// ## Errors
+    /// `ValidationError::InvalidFormat` for anything else, and the errors of
+    /// [`CurrencyDenominations::new`].
+    pub fn parse(currency_code: &str, s: &str) -> ValidationResult<Self> {
+        let invalid = |part: &str| ValidationError::InvalidFormat {
+            field: "denominations".to_string(),
+            reason: format!("'{}' is not notes:<values> or coins:<values>", part.trim()),
+        };
+
+        let mut denominations = Vec::new();
+        for part in s.split(';').filter(|part| !part.trim().is_empty()) {
+            let (kind, values) = part.split_once(':').ok_or_else(|| invalid(part))?;
+            let kind = match kind.trim().to_lowercase().as_str() {
+                "notes" => DenominationKind::Note,
+                "coins" => DenominationKind::Coin,
+                _ => return Err(invalid(part)),
+            };
+            for value in values.split(',').filter(|v| !v.trim().is_empty()) {
+                let value = value.trim().parse::<f64>().map_err(|_| invalid(part))?;
+                denominations.push(Denomination {
+                    value_cents: (value * 100.0).round() as i64,
+                    kind,
+                });
+            }
+        }
+
+        Self::new(currency_code.to_ascii_uppercase(), denominations)
+    }
+
+    /// Returns the built-in set for a currency code, if one exists.
+    pub fn for_currency(currency_code: &str) -> Option<Self> {
+        let code = currency_code.to_ascii_uppercase();
+        let denominations = match code.as_str() {
+            "USD" => USD,
+            "EUR" => EUR,
+            "GBP" => GBP,
+            "PKR" => PKR,
+            _ => return None,
+        };
+
+        Some(CurrencyDenominations {
+            currency_code: code,
+            denominations: denominations.to_vec(),
+        })
+    }
+
+    /// Returns true if `value_cents` is one of this currency's denominations.
+    pub fn contains(&self, value_cents: i64) -> bool {
+        self.denominations.iter().any(|d| d.value_cents == value_cents)
+    }
+
+    /// Sums a drawer count, rejecting denominations this currency doesn't have.
+    pub fn counted_total(&self, counts: &[DenominationCount]) -> ValidationResult<i64> {
+        crate::till::validate_denomination_counts(counts)?;
+
+        if let Some(bad) = counts.iter().find(|c| !self.contains(c.denomination_cents)) {
+            return Err(ValidationError::NotAllowed {
+                field: format!("denomination_cents ({})", bad.denomination_cents),
+                allowed: self
+                    .denominations
+                    .iter()
+                    .map(|d| d.value_cents.to_string())
+                    .collect(),
+            });
+        }
+
+        Ok(crate::till::counted_total_cents(counts))
+    }
+
+    /// Breaks a change amount into the fewest notes and coins.
+    ///
+    /// ## Errors
+    /// Returns `ValidationError::OutOfRange` if `change_cents` is negative.
+    pub fn change_breakdown(&self, change_cents: i64) -> ValidationResult<ChangeBreakdown> {
+        if change_cents < 0 {
+            return Err(ValidationError::OutOfRange {
+                field: "change_cents".to_string(),
+                min: 0,
+                max: i64::MAX,
+            });
+        }
+
+        let mut remaining = change_cents;
+        let mut pieces = Vec::new();
+
+        for d in &self.denominations {
+            let quantity = remaining / d.value_cents;
+            if quantity > 0 {
+                pieces.push(DenominationCount {
+                    denomination_cents: d.value_cents,
+                    quantity,
+                });
+                remaining -= quantity * d.value_cents;
+            }
+        }
+
+        Ok(ChangeBreakdown {
+            pieces,
+            total_cents: change_cents - remaining,
+            remainder_cents: remaining,
+        })
+    }
+
+    /// Checks that largest-first change is optimal for every amount.
+    ///
+    /// If a set is not canonical, a counterexample exists below the sum of
+    /// its two largest denominations (Kozen & Zaks), so only that range is
+    /// checked against an exact dynamic-programming solution.
+    fn is_canonical(&self) -> bool {
+        let values: Vec<i64> = self.denominations.iter().map(|d| d.value_cents).collect();
+        if values.len() < 3 {
+            return true;
+        }
+
+        // `new` caps the values; a set built any other way that is too
+        // large to check is treated as not canonical.
+        let Some(limit) = values[0]
+            .checked_add(values[1])
+            .filter(|limit| *limit <= 2 * MAX_DENOMINATION_CENTS)
+        else {
+            return false;
+        };
+        let limit = limit as usize;
+        let mut best = vec![u32::MAX; limit + 1];
+        best[0] = 0;
+
+        for amount in 1..=limit {
+            for &v in &values {
+                let v = v as usize;
+                if v <= amount && best[amount - v] != u32::MAX {
+                    best[amount] = best[amount].min(best[amount - v] + 1);
+                }
+            }
+
+            if best[amount] == u32::MAX {
+                continue;
+            }
+
+            let mut remaining = amount as i64;
+            let mut greedy = 0u32;
+            for &v in &values {
+                greedy += (remaining / v) as u32;
+                remaining %= v;
+            }
+
+            if remaining != 0 || greedy > best[amount] {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+// =============================================================================
+// Unit Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(denomination_cents: i64, quantity: i64) -> DenominationCount {
+        DenominationCount {
+            denomination_cents,
+            quantity,
+        }
+    }
+
+    #[test]
+    fn test_builtin_sets_are_canonical() {
+        for code in ["USD", "EUR", "GBP", "PKR"] {
+            let set = CurrencyDenominations::for_currency(code).unwrap();
+            assert!(set.is_canonical(), "{code} should be canonical");
+        }
+        assert!(CurrencyDenominations::for_currency("usd").is_some());
+        assert!(CurrencyDenominations::for_currency("XYZ").is_none());
+    }
+
+    #[test]
+    fn test_change_breakdown_usd() {
+        let usd = CurrencyDenominations::for_currency("USD").unwrap();
+        let change = usd.change_breakdown(641).unwrap();
+
+        assert_eq!(
+            change.pieces,
+            vec![count(500, 1), count(100, 1), count(25, 1), count(10, 1), count(5, 1), count(1, 1)]
+        );
+        assert_eq!(change.total_cents, 641);
+        assert_eq!(change.remainder_cents, 0);
+        assert_eq!(change.piece_count(), 6);
+
+        assert!(usd.change_breakdown(0).unwrap().pieces.is_empty());
+        assert!(usd.change_breakdown(-1).is_err());
+    }
+
+    #[test]
+    fn test_change_breakdown_remainder() {
+        // Rs 17.50 → Rs 10 + Rs 5 + Rs 2, 50 paisa can't be paid out
+        let pkr = CurrencyDenominations::for_currency("PKR").unwrap();
+        let change = pkr.change_breakdown(1750).unwrap();
+
+        assert_eq!(change.pieces, vec![count(1000, 1), count(500, 1), count(200, 1)]);
+        assert_eq!(change.total_cents, 1700);
+        assert_eq!(change.remainder_cents, 50);
+    }
+
+    #[test]
+    fn test_custom_set_validation() {
+        let ok = CurrencyDenominations::new(
+            "XTS",
+            vec![Denomination::coin(1), Denomination::note(100), Denomination::coin(10)],
+        )
+        .unwrap();
+        assert_eq!(ok.denominations[0].value_cents, 100);
+
+        let coins = |values: &[i64]| values.iter().map(|&v| Denomination::coin(v)).collect();
+        assert!(CurrencyDenominations::new("XTS", vec![]).is_err());
+        assert!(CurrencyDenominations::new("XTS", coins(&[0, 1])).is_err());
+        assert!(CurrencyDenominations::new("XTS", coins(&[5, 5, 1])).is_err());
+        // 6 → 4+1+1 greedy vs 3+3 optimal
+        assert!(CurrencyDenominations::new("XTS", coins(&[1, 3, 4])).is_err());
+        // Too large to check without a huge table
+        assert!(CurrencyDenominations::new("XTS", coins(&[i64::MAX, i64::MAX - 1, 1])).is_err());
+        assert!(
+            CurrencyDenominations::new("XTS", coins(&[MAX_DENOMINATION_CENTS + 1, 1])).is_err()
+        );
+        let unchecked = CurrencyDenominations {
+            currency_code: "XTS".to_string(),
+            denominations: coins(&[i64::MAX, i64::MAX - 1, 1]),
+        };
+        assert!(!unchecked.is_canonical());
+    }
+
+    #[test]
+    fn test_parse_custom_set() {
+        let pkr = CurrencyDenominations::parse("pkr", "notes: 5000, 1000, 500; coins: 10, 5, 2, 1")
+            .unwrap();
+        assert_eq!(pkr.currency_code, "PKR");
+        assert_eq!(pkr.denominations[0], Denomination::note(500_000));
+        assert_eq!(pkr.denominations[6], Denomination::coin(100));
+
+        let cents = CurrencyDenominations::parse("XTS", "coins:0.25,0.10,0.05,0.01").unwrap();
+        assert_eq!(cents.denominations.len(), 4);
+
+        assert!(CurrencyDenominations::parse("XTS", "5000,1000").is_err());
+        assert!(CurrencyDenominations::parse("XTS", "bills:5000").is_err());
+        assert!(CurrencyDenominations::parse("XTS", "coins:abc").is_err());
+        assert!(CurrencyDenominations::parse("XTS", "").is_err());
+    }
+
+    #[test]
+    fn test_counted_total() {
+        let usd = CurrencyDenominations::for_currency("USD").unwrap();
+        assert_eq!(usd.counted_total(&[count(2000, 2), count(25, 4)]).unwrap(), 4100);
+        assert!(usd.counted_total(&[count(200, 1)]).is_err());
+    }
+}
@@ -0,0 +1,488 @@
+//! # Department Sales
+//!
+//! Goods sold without a SKU of their own: a loose pastry rung up on the
+//! "Misc Bakery" key at whatever price the cashier enters, or a deli item
+//! weighed and labelled in the back with a price-embedded barcode. Each
+//! department key belongs to a tax group, which sets the line's tax rate,
+//! and department sales are reported apart from SKU sales.
+//!
+//! ## Department Lines
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Department Sales                                 │
+//! │                                                                         │
+//! │  open price:  BAKERY key, 3.50 ──► within 0.01..50.00? ──┐             │
+//! │                                                         │             │
+//! │  price-embedded: 2 100042 00350 C                       │             │
+//! │                  │ └────┘ └───┘ └ EAN-13 check digit    │             │
+//! │                  │ prefix  price                        ▼             │
+//! │                  └ "2100042" = BAKERY's barcode_prefix ─► DepartmentLine│
+//! │                                                  name "Misc Bakery"    │
+//! │                                                  tax group FOOD (0%)   │
+//! │                                                  price 3.50            │
+//! │                                                                         │
+//! │  Sale line: product "department:BAKERY" (no stock, hidden from search) │
+//! │  Report:    per department units / sales / tax, then SKU sales        │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Rules
+//! - Department and tax group codes are 1 to [`MAX_CODE_LEN`] characters
+//!   of `A-Z`, `0-9`, `_` and `-`, unique within the config
+//! - Every department names an existing tax group
+//! - An open price is above zero and within the department's range (at
+//!   most [`MAX_OPEN_PRICE_CENTS`])
+//! - A barcode prefix is 7 digits starting with `2` (the in-store range);
+//!   a scanned code must be 13 digits with a valid check digit
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::{CoreError, CoreResult, ValidationError};
+use crate::scale::{format_kg, weighed_price_cents};
+use crate::validation::{validate_tax_rate_bps, ValidationResult};
+
+/// Longest department or tax group code.
+pub const MAX_CODE_LEN: usize = 16;
+
+/// Highest open price a department may accept.
+pub const MAX_OPEN_PRICE_CENTS: i64 = 1_000_000;
+
+/// Product ID prefix of the products that carry department sale lines.
+pub const DEPARTMENT_PRODUCT_PREFIX: &str = "department:";
+
+/// A tax rate shared by departments (e.g. "FOOD" at 0%).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TaxGroup {
+    pub code: String,
+    pub name: String,
+    pub rate_bps: u32,
+}
+
+/// A key that sells goods of one department at an entered price.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DepartmentKey {
+    pub code: String,
+    /// Shown on the key, the receipt and the report.
+    pub name: String,
+    /// Code of the department's tax group.
+    pub tax_group: String,
+    #[ts(type = "number")]
+    pub min_price_cents: i64,
+    #[ts(type = "number")]
+    pub max_price_cents: i64,
+    /// First 7 digits of this department's price-embedded barcodes.
+    #[serde(default)]
+    pub barcode_prefix: Option<String>,
+    /// Price per kilogram, for goods weighed at the register (see
+    /// `titan_core::scale`); None = not sold by weight.
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub price_per_kg_cents: Option<i64>,
+}
+
+/// Department keys and tax groups configured for the store.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DepartmentConfig {
+    pub tax_groups: Vec<TaxGroup>,
+    pub departments: Vec<DepartmentKey>,
+}
+
+/// A department sale ready for the cart.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DepartmentLine {
+    pub department_code: String,
+    pub name: String,
+    pub tax_rate_bps: u32,
+    #[ts(type = "number")]
+    pub unit_price_cents: i64,
+}
+
+/// ID of the product that carries a department's sale lines.
+pub fn department_product_id(code: &str) -> String {
+    format!("{}{}", DEPARTMENT_PRODUCT_PREFIX, code)
+}
+
+/// Department code of a sale line's product ID (`None` for SKU sales).
+pub fn department_code_of(product_id: &str) -> Option<&str> {
+    product_id.strip_prefix(DEPARTMENT_PRODUCT_PREFIX)
+}
+
+fn validate_code(field: &str, code: &str) -> ValidationResult<()> {
+    if code.is_empty() {
+        return Err(ValidationError::Required {
+            field: field.to_string(),
+        });
+    }
+    if code.len() > MAX_CODE_LEN {
+        return Err(ValidationError::TooLong {
+            field: field.to_string(),
+            max: MAX_CODE_LEN,
+        });
+    }
+    if !code
+        .chars()
+        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_' || c == '-')
+    {
+        return Err(ValidationError::InvalidFormat {
+            field: field.to_string(),
+            reason: "use A-Z, 0-9, '_' and '-'".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Whether `digits` (13 ASCII digits) ends in a valid EAN-13 check digit.
+fn has_valid_check_digit(digits: &[u8]) -> bool {
+    let sum: u32 = digits[..12]
+        .iter()
+        .enumerate()
+        .map(|(i, d)| u32::from(d - b'0') * if i % 2 == 0 { 1 } else { 3 })
+        .sum();
+    (10 - sum % 10) % 10 == u32::from(digits[12] - b'0')
+}
+
+impl DepartmentConfig {
+    /// Validates the tax groups and department keys.
+    pub fn validate(&self) -> ValidationResult<()> {
+        let mut group_codes: Vec<&str> = Vec::new();
+        for group in &self.tax_groups {
+            validate_code("tax_group", &group.code)?;
+            validate_tax_rate_bps(group.rate_bps)?;
+            if group_codes.contains(&group.code.as_str()) {
+                return Err(ValidationError::Duplicate {
+                    field: "tax_group".to_string(),
+                    value: group.code.clone(),
+                });
+            }
+            group_codes.push(&group.code);
+        }
+
+        let mut codes: Vec<&str> = Vec::new();
+        let mut prefixes: Vec<&str> = Vec::new();
+        for dept in &self.departments {
+            validate_code("department", &dept.code)?;
+            if codes.contains(&dept.code.as_str()) {
+                return Err(ValidationError::Duplicate {
+                    field: "department".to_string(),
+                    value: dept.code.clone(),
+                });
+            }
+            codes.push(&dept.code);
+
+            if dept.name.trim().is_empty() {
+                return Err(ValidationError::Required {
+                    field: "name".to_string(),
+                });
+            }
+            if !group_codes.contains(&dept.tax_group.as_str()) {
+                return Err(ValidationError::NotAllowed {
+                    field: "tax_group".to_string(),
+                    allowed: group_codes.iter().map(|c| c.to_string()).collect(),
+                });
+            }
+            if dept.min_price_cents < 1
+                || dept.max_price_cents > MAX_OPEN_PRICE_CENTS
+                || dept.min_price_cents > dept.max_price_cents
+            {
+                return Err(ValidationError::OutOfRange {
+                    field: "price_range".to_string(),
+                    min: 1,
+                    max: MAX_OPEN_PRICE_CENTS,
+                });
+            }
+
+            if let Some(prefix) = &dept.barcode_prefix {
+                if prefix.len() != 7
+                    || !prefix.starts_with('2')
+                    || !prefix.bytes().all(|b| b.is_ascii_digit())
+                {
+                    return Err(ValidationError::InvalidFormat {
+                        field: "barcode_prefix".to_string(),
+                        reason: "must be 7 digits starting with 2".to_string(),
+                    });
+                }
+                if prefixes.contains(&prefix.as_str()) {
+                    return Err(ValidationError::Duplicate {
+                        field: "barcode_prefix".to_string(),
+                        value: prefix.clone(),
+                    });
+                }
+                prefixes.push(prefix);
+            }
+
+            if let Some(price) = dept.price_per_kg_cents {
+                if !(1..=MAX_OPEN_PRICE_CENTS).contains(&price) {
+                    return Err(ValidationError::OutOfRange {
+                        field: "price_per_kg".to_string(),
+                        min: 1,
+                        max: MAX_OPEN_PRICE_CENTS,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The department key with `code`.
+    pub fn department(&self, code: &str) -> Option<&DepartmentKey> {
+        self.departments.iter().find(|d| d.code == code)
+    }
+
+    /// Tax rate of a department's tax group.
+    pub fn tax_rate_bps(&self, dept: &DepartmentKey) -> Option<u32> {
+        self.tax_groups
+            .iter()
+            .find(|g| g.code == dept.tax_group)
+            .map(|g| g.rate_bps)
+    }
+
+    /// Prices a sale on a department key at an entered price.
+    ///
+    /// ## Errors
+    /// - `CoreError::Validation` for an unknown department, or a price
+    ///   outside the department's range
+    pub fn open_price(&self, code: &str, price_cents: i64) -> CoreResult<DepartmentLine> {
+        let dept = self.department(code).ok_or_else(|| ValidationError::NotAllowed {
+            field: "department".to_string(),
+            allowed: self.departments.iter().map(|d| d.code.clone()).collect(),
+        })?;
+        if price_cents < dept.min_price_cents || price_cents > dept.max_price_cents {
+            return Err(CoreError::Validation(ValidationError::OutOfRange {
+                field: "price".to_string(),
+                min: dept.min_price_cents,
+                max: dept.max_price_cents,
+            }));
+        }
+        let tax_rate_bps = self.tax_rate_bps(dept).ok_or_else(|| ValidationError::Required {
+            field: "tax_group".to_string(),
+        })?;
+
+        Ok(DepartmentLine {
+            department_code: dept.code.clone(),
+            name: dept.name.clone(),
+            tax_rate_bps,
+            unit_price_cents: price_cents,
+        })
+    }
+
+    /// Reads a price-embedded barcode.
+    ///
+    /// ## Returns
+    /// - `None` if the code doesn't start with any department's prefix
+    /// - `Some(Err(..))` for a bad check digit or a price outside the
+    ///   department's range
+    pub fn price_embedded(&self, barcode: &str) -> Option<CoreResult<DepartmentLine>> {
+        let barcode = barcode.trim();
+        let digits = barcode.as_bytes();
+        if digits.len() != 13 || !digits.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        let dept = self
+            .departments
+            .iter()
+            .find(|d| d.barcode_prefix.as_deref() == Some(&barcode[..7]))?;
+
+        if !has_valid_check_digit(digits) {
+            return Some(Err(CoreError::Validation(ValidationError::InvalidFormat {
+                field: "barcode".to_string(),
+                reason: "check digit does not match".to_string(),
+            })));
+        }
+        let price_cents: i64 = barcode[7..12].parse().ok()?;
+        Some(self.open_price(&dept.code, price_cents))
+    }
+
+    /// Prices `net_grams` of a department sold by weight, named with the
+    /// weight and unit price ("Loose Apples 0.455 kg @ 2.99/kg").
+    ///
+    /// ## Errors
+    /// - `CoreError::Validation` for an unknown department, one not sold
+    ///   by weight, or a price outside the department's range
+    pub fn weighed(&self, code: &str, net_grams: i64) -> CoreResult<DepartmentLine> {
+        let per_kg = self
+            .department(code)
+            .and_then(|d| d.price_per_kg_cents)
+            .ok_or_else(|| ValidationError::NotAllowed {
+                field: "department".to_string(),
+                allowed: self
+                    .departments
+                    .iter()
+                    .filter(|d| d.price_per_kg_cents.is_some())
+                    .map(|d| d.code.clone())
+                    .collect(),
+            })?;
+
+        let mut line = self.open_price(code, weighed_price_cents(net_grams, per_kg))?;
+        line.name = format!(
+            "{} {} @ {}.{:02}/kg",
+            line.name,
+            format_kg(net_grams),
+            per_kg / 100,
+            per_kg % 100
+        );
+        Ok(line)
+    }
+}
+
+// =============================================================================
+// Report
+// =============================================================================
+
+/// Sales of one department over a period.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DepartmentSalesRow {
+    pub department_code: String,
+    pub name: String,
+    #[ts(type = "number")]
+    pub units: i64,
+    #[ts(type = "number")]
+    pub sales_cents: i64,
+    #[ts(type = "number")]
+    pub tax_cents: i64,
+    #[ts(type = "number")]
+    pub line_count: i64,
+}
+
+/// Department sales over a period, apart from SKU sales.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DepartmentSalesReport {
+    /// By department code.
+    pub departments: Vec<DepartmentSalesRow>,
+    /// Sales of products with a SKU, net of line discounts.
+    #[ts(type = "number")]
+    pub sku_sales_cents: i64,
+}
+
+impl DepartmentSalesReport {
+    /// Builds the report (rows sorted by department code).
+    pub fn new(mut departments: Vec<DepartmentSalesRow>, sku_sales_cents: i64) -> Self {
+        departments.sort_by(|a, b| a.department_code.cmp(&b.department_code));
+        DepartmentSalesReport {
+            departments,
+            sku_sales_cents,
+        }
+    }
+
+    /// Sales across every department.
+    pub fn department_sales_cents(&self) -> i64 {
+        self.departments.iter().map(|d| d.sales_cents).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> DepartmentConfig {
+        DepartmentConfig {
+            tax_groups: vec![
+                TaxGroup {
+                    code: "STD".to_string(),
+                    name: "Standard".to_string(),
+                    rate_bps: 825,
+                },
+                TaxGroup {
+                    code: "FOOD".to_string(),
+                    name: "Food".to_string(),
+                    rate_bps: 0,
+                },
+            ],
+            departments: vec![
+                DepartmentKey {
+                    code: "BAKERY".to_string(),
+                    name: "Misc Bakery".to_string(),
+                    tax_group: "FOOD".to_string(),
+                    min_price_cents: 1,
+                    max_price_cents: 5_000,
+                    barcode_prefix: Some("2100042".to_string()),
+                    price_per_kg_cents: None,
+                },
+                DepartmentKey {
+                    code: "PRODUCE".to_string(),
+                    name: "Loose Apples".to_string(),
+                    tax_group: "FOOD".to_string(),
+                    min_price_cents: 1,
+                    max_price_cents: 5_000,
+                    barcode_prefix: None,
+                    price_per_kg_cents: Some(299),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(config().validate().is_ok());
+        assert!(DepartmentConfig::default().validate().is_ok());
+
+        let mut bad = config();
+        bad.departments[0].tax_group = "LUXURY".to_string();
+        assert!(bad.validate().is_err());
+
+        let mut bad = config();
+        bad.departments.push(bad.departments[0].clone());
+        assert!(matches!(bad.validate(), Err(ValidationError::Duplicate { .. })));
+
+        let mut bad = config();
+        bad.departments[0].code = "bakery".to_string();
+        assert!(bad.validate().is_err());
+
+        let mut bad = config();
+        bad.departments[0].min_price_cents = 0;
+        assert!(bad.validate().is_err());
+
+        let mut bad = config();
+        bad.departments[0].barcode_prefix = Some("4100042".to_string());
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_open_price() {
+        let line = config().open_price("BAKERY", 350).unwrap();
+        assert_eq!(line.name, "Misc Bakery");
+        assert_eq!(line.tax_rate_bps, 0);
+        assert_eq!(line.unit_price_cents, 350);
+
+        assert!(config().open_price("BAKERY", 0).is_err());
+        assert!(config().open_price("BAKERY", 5_001).is_err());
+        assert!(config().open_price("DELI", 350).is_err());
+    }
+
+    #[test]
+    fn test_weighed_line() {
+        let line = config().weighed("PRODUCE", 455).unwrap();
+        assert_eq!(line.name, "Loose Apples 0.455 kg @ 2.99/kg");
+        assert_eq!(line.unit_price_cents, 136);
+
+        assert!(config().weighed("BAKERY", 455).is_err());
+        assert!(config().weighed("PRODUCE", 2_000_000).is_err());
+    }
+
+    #[test]
+    fn test_price_embedded_barcode() {
+        // 2100042 00350, check digit 7
+        let line = config().price_embedded("2100042003507").unwrap().unwrap();
+        assert_eq!(line.department_code, "BAKERY");
+        assert_eq!(line.unit_price_cents, 350);
+
+        assert!(config().price_embedded("2100042003508").unwrap().is_err());
+        assert!(config().price_embedded("2100043003507").is_none());
+        assert!(config().price_embedded("5901234123457").is_none());
+        assert!(config().price_embedded("210004200350").is_none());
+    }
+
+    #[test]
+    fn test_department_product_ids() {
+        let id = department_product_id("BAKERY");
+        assert_eq!(id, "department:BAKERY");
+        assert_eq!(department_code_of(&id), Some("BAKERY"));
+        assert_eq!(department_code_of("9b2f0c4e"), None);
+    }
+}
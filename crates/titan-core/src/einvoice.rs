@@ -0,0 +1,588 @@
+//! # E-Invoicing
+//!
+//! Business customers and rendering of completed sales as UBL 2.1 invoice
+//! XML (Peppol BIS Billing 3.0 profile), for buyers that need a structured
+//! invoice instead of a till receipt.
+//!
+//! ## Invoice Structure
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                       UBL 2.1 Invoice                                   │
+//! │                                                                         │
+//! │  Invoice  (ID = receipt number, IssueDate, currency)                    │
+//! │  ├── AccountingSupplierParty   store: name, address, tax ID             │
+//! │  ├── AccountingCustomerParty   BusinessCustomer: name, address, tax ID  │
+//! │  ├── TaxTotal                                                           │
+//! │  │   ├── TaxSubtotal  8.25%  taxable 100.00  tax 8.25                   │
+//! │  │   └── TaxSubtotal  0%     taxable  20.00  tax 0.00                   │
+//! │  ├── LegalMonetaryTotal        net, tax exclusive/inclusive, payable    │
+//! │  └── InvoiceLine × n           qty, net amount, tax category, price     │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Amounts are stored in minor units and written with the currency's
+//! decimal places; one `TaxSubtotal` is written per distinct line tax rate.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::ValidationError;
+use crate::validation::ValidationResult;
+
+/// Peppol BIS Billing 3.0 customization identifier.
+pub const PEPPOL_CUSTOMIZATION_ID: &str =
+    "urn:cen.eu:en16931:2017#compliant#urn:fdc:peppol.eu:2017:poacc:billing:3.0";
+
+/// Peppol billing process identifier.
+pub const PEPPOL_PROFILE_ID: &str = "urn:fdc:peppol.eu:2017:poacc:billing:01:1.0";
+
+/// UBL invoice type code for a commercial invoice.
+const INVOICE_TYPE_CODE: &str = "380";
+
+/// UN/ECE unit code for "one" (each).
+const UNIT_CODE_EACH: &str = "C62";
+
+// =============================================================================
+// Business Customer
+// =============================================================================
+
+/// A business buyer that receives e-invoices for its purchases.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct BusinessCustomer {
+    pub id: String,
+    pub tenant_id: String,
+    /// Registered company name.
+    pub name: String,
+    /// VAT / tax registration number, normalized (see [`normalize_tax_id`]).
+    pub tax_id: String,
+    pub address_line: Option<String>,
+    pub city: Option<String>,
+    pub postal_code: Option<String>,
+    /// ISO 3166-1 alpha-2 country code.
+    pub country_code: String,
+    /// Where invoices are emailed.
+    pub email: Option<String>,
+    #[ts(as = "String")]
+    pub created_at: DateTime<Utc>,
+    #[ts(as = "String")]
+    pub updated_at: DateTime<Utc>,
+    /// When the customer was deleted (see [`crate::tombstone`]).
+    #[serde(default)]
+    #[ts(as = "Option<String>")]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Bumped on every change, for sync.
+    #[serde(default)]
+    pub sync_version: i64,
+}
+
+/// Normalizes and validates a tax registration number.
+///
+/// Spaces, dots and dashes are dropped and letters uppercased, so
+/// `"gb 123.456-789"` is stored as `"GB123456789"`.
+///
+/// ## Rules
+/// - 4 to 20 characters after normalization
+/// - Letters and digits only
+pub fn normalize_tax_id(tax_id: &str) -> ValidationResult<String> {
+    let normalized: String = tax_id
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '.' | '-'))
+        .collect::<String>()
+        .to_uppercase();
+
+    if normalized.is_empty() {
+        return Err(ValidationError::Required {
+            field: "tax_id".to_string(),
+        });
+    }
+    if !normalized.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(ValidationError::InvalidFormat {
+            field: "tax_id".to_string(),
+            reason: "only letters and digits are allowed".to_string(),
+        });
+    }
+    if normalized.len() < 4 {
+        return Err(ValidationError::TooShort {
+            field: "tax_id".to_string(),
+            min: 4,
+        });
+    }
+    if normalized.len() > 20 {
+        return Err(ValidationError::TooLong {
+            field: "tax_id".to_string(),
+            max: 20,
+        });
+    }
+    Ok(normalized)
+}
+
+/// Normalizes and validates an ISO 3166-1 alpha-2 country code.
+pub fn normalize_country_code(code: &str) -> ValidationResult<String> {
+    let code = code.trim().to_uppercase();
+    if code.len() != 2 || !code.chars().all(|c| c.is_ascii_uppercase()) {
+        return Err(ValidationError::InvalidFormat {
+            field: "country_code".to_string(),
+            reason: "expected a two-letter ISO country code".to_string(),
+        });
+    }
+    Ok(code)
+}
+
+// =============================================================================
+// Invoice Model
+// =============================================================================
+
+/// Seller or buyer on an invoice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvoiceParty {
+    pub name: String,
+    pub tax_id: Option<String>,
+    pub address_lines: Vec<String>,
+    pub city: Option<String>,
+    pub postal_code: Option<String>,
+    pub country_code: String,
+}
+
+impl From<&BusinessCustomer> for InvoiceParty {
+    fn from(c: &BusinessCustomer) -> Self {
+        InvoiceParty {
+            name: c.name.clone(),
+            tax_id: Some(c.tax_id.clone()),
+            address_lines: c.address_line.iter().cloned().collect(),
+            city: c.city.clone(),
+            postal_code: c.postal_code.clone(),
+            country_code: c.country_code.clone(),
+        }
+    }
+}
+
+/// One invoiced line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvoiceLine {
+    pub name: String,
+    pub quantity: i64,
+    pub unit_price_cents: i64,
+    /// Line amount after discounts, before tax.
+    pub net_cents: i64,
+    pub tax_cents: i64,
+    /// Tax rate applied to the line, in basis points.
+    pub tax_rate_bps: u32,
+}
+
+/// Tax totals for one tax rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaxBreakdown {
+    pub rate_bps: u32,
+    pub taxable_cents: i64,
+    pub tax_cents: i64,
+}
+
+/// A completed sale as an invoice to a business customer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invoice {
+    /// Invoice number; the sale's receipt number.
+    pub invoice_number: String,
+    pub issue_date: NaiveDate,
+    /// ISO 4217 currency code.
+    pub currency_code: String,
+    /// Decimal places of the currency's minor unit.
+    pub currency_decimals: u8,
+    pub seller: InvoiceParty,
+    pub buyer: InvoiceParty,
+    pub lines: Vec<InvoiceLine>,
+}
+
+impl Invoice {
+    /// Sum of line net amounts.
+    pub fn net_cents(&self) -> i64 {
+        self.lines.iter().map(|l| l.net_cents).sum()
+    }
+
+    /// Sum of line taxes.
+    pub fn tax_cents(&self) -> i64 {
+        self.lines.iter().map(|l| l.tax_cents).sum()
+    }
+
+    /// Taxable and tax amounts per rate, lowest rate first.
+    pub fn tax_breakdown(&self) -> Vec<TaxBreakdown> {
+        let mut breakdown: Vec<TaxBreakdown> = Vec::new();
+        for line in &self.lines {
+            match breakdown
+                .iter_mut()
+                .find(|b| b.rate_bps == line.tax_rate_bps)
+            {
+                Some(b) => {
+                    b.taxable_cents += line.net_cents;
+                    b.tax_cents += line.tax_cents;
+                }
+                None => breakdown.push(TaxBreakdown {
+                    rate_bps: line.tax_rate_bps,
+                    taxable_cents: line.net_cents,
+                    tax_cents: line.tax_cents,
+                }),
+            }
+        }
+        breakdown.sort_by_key(|b| b.rate_bps);
+        breakdown
+    }
+
+    /// Checks the fields UBL requires before rendering.
+    ///
+    /// ## Rules
+    /// - Seller and buyer both carry a tax ID
+    /// - At least one line
+    pub fn validate(&self) -> ValidationResult<()> {
+        if self.seller.tax_id.is_none() {
+            return Err(ValidationError::Required {
+                field: "seller_tax_id".to_string(),
+            });
+        }
+        if self.buyer.tax_id.is_none() {
+            return Err(ValidationError::Required {
+                field: "buyer_tax_id".to_string(),
+            });
+        }
+        if self.lines.is_empty() {
+            return Err(ValidationError::Required {
+                field: "lines".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+// =============================================================================
+// UBL Rendering
+// =============================================================================
+
+/// Renders an invoice as UBL 2.1 XML.
+pub fn render_ubl(invoice: &Invoice) -> String {
+    let mut xml = XmlWriter::default();
+    let currency = invoice.currency_code.as_str();
+    let amount = |cents: i64| format_amount(cents, invoice.currency_decimals);
+
+    xml.raw(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.raw(
+        r#"<Invoice xmlns="urn:oasis:names:specification:ubl:schema:xsd:Invoice-2" xmlns:cac="urn:oasis:names:specification:ubl:schema:xsd:CommonAggregateComponents-2" xmlns:cbc="urn:oasis:names:specification:ubl:schema:xsd:CommonBasicComponents-2">"#,
+    );
+    xml.indent += 1;
+    xml.leaf("cbc:CustomizationID", PEPPOL_CUSTOMIZATION_ID);
+    xml.leaf("cbc:ProfileID", PEPPOL_PROFILE_ID);
+    xml.leaf("cbc:ID", &invoice.invoice_number);
+    xml.leaf(
+        "cbc:IssueDate",
+        &invoice.issue_date.format("%Y-%m-%d").to_string(),
+    );
+    xml.leaf("cbc:InvoiceTypeCode", INVOICE_TYPE_CODE);
+    xml.leaf("cbc:DocumentCurrencyCode", currency);
+
+    xml.open("cac:AccountingSupplierParty");
+    write_party(&mut xml, &invoice.seller);
+    xml.close("cac:AccountingSupplierParty");
+
+    xml.open("cac:AccountingCustomerParty");
+    write_party(&mut xml, &invoice.buyer);
+    xml.close("cac:AccountingCustomerParty");
+
+    xml.open("cac:TaxTotal");
+    xml.amount("cbc:TaxAmount", currency, &amount(invoice.tax_cents()));
+    for b in invoice.tax_breakdown() {
+        xml.open("cac:TaxSubtotal");
+        xml.amount("cbc:TaxableAmount", currency, &amount(b.taxable_cents));
+        xml.amount("cbc:TaxAmount", currency, &amount(b.tax_cents));
+        write_tax_category(&mut xml, "cac:TaxCategory", b.rate_bps);
+        xml.close("cac:TaxSubtotal");
+    }
+    xml.close("cac:TaxTotal");
+
+    let net = invoice.net_cents();
+    let gross = net + invoice.tax_cents();
+    xml.open("cac:LegalMonetaryTotal");
+    xml.amount("cbc:LineExtensionAmount", currency, &amount(net));
+    xml.amount("cbc:TaxExclusiveAmount", currency, &amount(net));
+    xml.amount("cbc:TaxInclusiveAmount", currency, &amount(gross));
+    xml.amount("cbc:PayableAmount", currency, &amount(gross));
+    xml.close("cac:LegalMonetaryTotal");
+
+    for (i, line) in invoice.lines.iter().enumerate() {
+        xml.open("cac:InvoiceLine");
+        xml.leaf("cbc:ID", &(i + 1).to_string());
+        xml.raw(&format!(
+            r#"<cbc:InvoicedQuantity unitCode="{}">{}</cbc:InvoicedQuantity>"#,
+            UNIT_CODE_EACH, line.quantity
+        ));
+        xml.amount("cbc:LineExtensionAmount", currency, &amount(line.net_cents));
+        xml.open("cac:Item");
+        xml.leaf("cbc:Name", &line.name);
+        write_tax_category(&mut xml, "cac:ClassifiedTaxCategory", line.tax_rate_bps);
+        xml.close("cac:Item");
+        xml.open("cac:Price");
+        xml.amount("cbc:PriceAmount", currency, &amount(line.unit_price_cents));
+        xml.close("cac:Price");
+        xml.close("cac:InvoiceLine");
+    }
+
+    xml.indent -= 1;
+    xml.raw("</Invoice>");
+    xml.finish()
+}
+
+fn write_party(xml: &mut XmlWriter, party: &InvoiceParty) {
+    xml.open("cac:Party");
+
+    xml.open("cac:PartyName");
+    xml.leaf("cbc:Name", &party.name);
+    xml.close("cac:PartyName");
+
+    xml.open("cac:PostalAddress");
+    if let Some(street) = party.address_lines.first() {
+        xml.leaf("cbc:StreetName", street);
+    }
+    if let Some(extra) = party.address_lines.get(1) {
+        xml.leaf("cbc:AdditionalStreetName", extra);
+    }
+    if let Some(ref city) = party.city {
+        xml.leaf("cbc:CityName", city);
+    }
+    if let Some(ref postal_code) = party.postal_code {
+        xml.leaf("cbc:PostalZone", postal_code);
+    }
+    xml.open("cac:Country");
+    xml.leaf("cbc:IdentificationCode", &party.country_code);
+    xml.close("cac:Country");
+    xml.close("cac:PostalAddress");
+
+    if let Some(ref tax_id) = party.tax_id {
+        xml.open("cac:PartyTaxScheme");
+        xml.leaf("cbc:CompanyID", tax_id);
+        write_tax_scheme(xml);
+        xml.close("cac:PartyTaxScheme");
+    }
+
+    xml.open("cac:PartyLegalEntity");
+    xml.leaf("cbc:RegistrationName", &party.name);
+    xml.close("cac:PartyLegalEntity");
+
+    xml.close("cac:Party");
+}
+
+fn write_tax_category(xml: &mut XmlWriter, element: &str, rate_bps: u32) {
+    xml.open(element);
+    // UNCL5305: S = standard rate, Z = zero rated
+    xml.leaf("cbc:ID", if rate_bps > 0 { "S" } else { "Z" });
+    xml.leaf("cbc:Percent", &format_percent(rate_bps));
+    write_tax_scheme(xml);
+    xml.close(element);
+}
+
+fn write_tax_scheme(xml: &mut XmlWriter) {
+    xml.open("cac:TaxScheme");
+    xml.leaf("cbc:ID", "VAT");
+    xml.close("cac:TaxScheme");
+}
+
+/// Minor units as a decimal string, e.g. `1083` with 2 decimals → `"10.83"`.
+pub(crate) fn format_amount(cents: i64, decimals: u8) -> String {
+    if decimals == 0 {
+        return cents.to_string();
+    }
+    let scale = 10_i64.pow(decimals as u32);
+    let sign = if cents < 0 { "-" } else { "" };
+    let abs = cents.abs();
+    format!(
+        "{}{}.{:0width$}",
+        sign,
+        abs / scale,
+        abs % scale,
+        width = decimals as usize
+    )
+}
+
+/// Basis points as a percentage, e.g. `825` → `"8.25"`.
+fn format_percent(rate_bps: u32) -> String {
+    format!("{}.{:02}", rate_bps / 100, rate_bps % 100)
+}
+
+/// Minimal indenting XML writer; all text content is escaped.
+#[derive(Default)]
+struct XmlWriter {
+    out: String,
+    indent: usize,
+}
+
+impl XmlWriter {
+    fn raw(&mut self, line: &str) {
+        for _ in 0..self.indent {
+            self.out.push_str("  ");
+        }
+        self.out.push_str(line);
+        self.out.push('\n');
+    }
+
+    fn open(&mut self, name: &str) {
+        self.raw(&format!("<{}>", name));
+        self.indent += 1;
+    }
+
+    fn close(&mut self, name: &str) {
+        self.indent -= 1;
+        self.raw(&format!("</{}>", name));
+    }
+
+    fn leaf(&mut self, name: &str, text: &str) {
+        self.raw(&format!("<{}>{}</{}>", name, escape_xml(text), name));
+    }
+
+    fn amount(&mut self, name: &str, currency: &str, value: &str) {
+        self.raw(&format!(
+            r#"<{} currencyID="{}">{}</{}>"#,
+            name,
+            escape_xml(currency),
+            value,
+            name
+        ));
+    }
+
+    fn finish(self) -> String {
+        self.out
+    }
+}
+
+/// Escapes text for XML element content and attribute values.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// =============================================================================
+// Unit Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn party(name: &str, tax_id: Option<&str>) -> InvoiceParty {
+        InvoiceParty {
+            name: name.to_string(),
+            tax_id: tax_id.map(str::to_string),
+            address_lines: vec!["1 High St".to_string()],
+            city: Some("Leeds".to_string()),
+            postal_code: Some("LS1 1AA".to_string()),
+            country_code: "GB".to_string(),
+        }
+    }
+
+    fn line(name: &str, net_cents: i64, tax_cents: i64, tax_rate_bps: u32) -> InvoiceLine {
+        InvoiceLine {
+            name: name.to_string(),
+            quantity: 1,
+            unit_price_cents: net_cents,
+            net_cents,
+            tax_cents,
+            tax_rate_bps,
+        }
+    }
+
+    fn invoice() -> Invoice {
+        Invoice {
+            invoice_number: "R-1".to_string(),
+            issue_date: NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(),
+            currency_code: "GBP".to_string(),
+            currency_decimals: 2,
+            seller: party("Titan Store", Some("GB123456789")),
+            buyer: party("Smith & Sons", Some("GB987654321")),
+            lines: vec![
+                line("Widget", 10000, 2000, 2000),
+                line("Book", 2000, 0, 0),
+                line("Gadget", 500, 100, 2000),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_normalize_tax_id() {
+        assert_eq!(normalize_tax_id("gb 123.456-789").unwrap(), "GB123456789");
+        assert!(normalize_tax_id("  ").is_err());
+        assert!(normalize_tax_id("GB1").is_err());
+        assert!(normalize_tax_id("GB/123456").is_err());
+        assert!(normalize_tax_id(&"1".repeat(21)).is_err());
+    }
+
+    #[test]
+    fn test_normalize_country_code() {
+        assert_eq!(normalize_country_code(" gb ").unwrap(), "GB");
+        assert!(normalize_country_code("GBR").is_err());
+        assert!(normalize_country_code("1A").is_err());
+    }
+
+    #[test]
+    fn test_tax_breakdown_groups_by_rate() {
+        let breakdown = invoice().tax_breakdown();
+        assert_eq!(
+            breakdown,
+            vec![
+                TaxBreakdown {
+                    rate_bps: 0,
+                    taxable_cents: 2000,
+                    tax_cents: 0
+                },
+                TaxBreakdown {
+                    rate_bps: 2000,
+                    taxable_cents: 10500,
+                    tax_cents: 2100
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_requires_tax_ids_and_lines() {
+        assert!(invoice().validate().is_ok());
+
+        let mut no_buyer_tax = invoice();
+        no_buyer_tax.buyer.tax_id = None;
+        assert!(no_buyer_tax.validate().is_err());
+
+        let mut empty = invoice();
+        empty.lines.clear();
+        assert!(empty.validate().is_err());
+    }
+
+    #[test]
+    fn test_render_ubl() {
+        let xml = render_ubl(&invoice());
+        assert!(xml.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+        assert!(xml.contains("<cbc:ID>R-1</cbc:ID>"));
+        assert!(xml.contains("<cbc:IssueDate>2024-05-01</cbc:IssueDate>"));
+        assert!(xml.contains("<cbc:Name>Smith &amp; Sons</cbc:Name>"));
+        assert!(xml.contains("<cbc:CompanyID>GB987654321</cbc:CompanyID>"));
+        assert!(xml.contains(r#"<cbc:TaxAmount currencyID="GBP">21.00</cbc:TaxAmount>"#));
+        assert!(xml.contains(r#"<cbc:PayableAmount currencyID="GBP">146.00</cbc:PayableAmount>"#));
+        assert!(xml.contains("<cbc:Percent>20.00</cbc:Percent>"));
+        assert_eq!(xml.matches("<cac:TaxSubtotal>").count(), 2);
+        assert_eq!(xml.matches("<cac:InvoiceLine>").count(), 3);
+        assert!(xml.trim_end().ends_with("</Invoice>"));
+    }
+
+    #[test]
+    fn test_format_amount() {
+        assert_eq!(format_amount(1083, 2), "10.83");
+        assert_eq!(format_amount(5, 2), "0.05");
+        assert_eq!(format_amount(-150, 2), "-1.50");
+        assert_eq!(format_amount(1500, 0), "1500");
+        assert_eq!(format_amount(1500, 3), "1.500");
+    }
+}
@@ -0,0 +1,133 @@
+//! # Entity Events
+//!
+//! Notifications that an entity changed, published by the repositories
+//! once the write is committed. Consumers (receipt printing, the customer
+//! display, rollups, caches, the frontend) react to these instead of
+//! every write path calling each of them.
+//!
+//! The cart lives in memory, not in a table, so the cart commands publish
+//! its events themselves once the cart has changed.
+//!
+//! ## Event Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Entity Events                                    │
+//! │                                                                         │
+//! │  POS command ─┐                                                         │
+//! │               ├─► repository write ─► commit ─► EntityEvent            │
+//! │  inbound sync ┘                                   │                     │
+//! │  cart command ──► cart changed ───────────────────┤                     │
+//! │                                                   ▼                     │
+//! │                                      broadcast channel (in-process)     │
+//! │                          ┌────────────────┬───────┴────────┐            │
+//! │                          ▼                ▼                ▼            │
+//! │                      frontend       customer display    rollups …       │
+//! │                                                                         │
+//! │  Events say what changed, not the new state: a consumer that needs     │
+//! │  the row reads it. A slow consumer may miss events and should          │
+//! │  reload what it caches.                                                │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// How a product changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum ProductChange {
+    Created,
+    Updated,
+    Deleted,
+    Restored,
+}
+
+/// A committed change to an entity.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EntityEvent {
+    /// A product was created, edited, deleted or restored (locally or
+    /// through sync).
+    ProductChanged {
+        product_id: String,
+        change: ProductChange,
+        /// The product's `sync_version` after the change.
+        #[ts(type = "number")]
+        version: i64,
+    },
+    /// A product's stock moved.
+    StockChanged {
+        product_id: String,
+        #[ts(type = "number")]
+        delta: i64,
+        /// Stock after the move (`None` if not counted).
+        #[ts(type = "number | null")]
+        stock: Option<i64>,
+    },
+    /// An item was scanned or keyed into the cart (including more of one
+    /// already there).
+    CartLineAdded {
+        product_id: String,
+        name: String,
+        #[ts(type = "number")]
+        unit_price_cents: i64,
+        /// Quantity added.
+        #[ts(type = "number")]
+        quantity: i64,
+        /// Cart total after the line, tax included.
+        #[ts(type = "number")]
+        cart_total_cents: i64,
+    },
+    /// A sale was rung up from the cart and is waiting for payment.
+    SaleCreated {
+        sale_id: String,
+        #[ts(type = "number")]
+        total_cents: i64,
+    },
+    /// A sale was completed.
+    SaleFinalized {
+        sale_id: String,
+        #[ts(type = "number")]
+        total_cents: i64,
+    },
+}
+
+impl EntityEvent {
+    /// The ID of the entity the event is about.
+    pub fn entity_id(&self) -> &str {
+        match self {
+            EntityEvent::ProductChanged { product_id, .. }
+            | EntityEvent::StockChanged { product_id, .. }
+            | EntityEvent::CartLineAdded { product_id, .. } => product_id,
+            EntityEvent::SaleCreated { sale_id, .. }
+            | EntityEvent::SaleFinalized { sale_id, .. } => sale_id,
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_are_tagged() {
+        let event = EntityEvent::StockChanged {
+            product_id: "p-1".to_string(),
+            delta: -2,
+            stock: Some(8),
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "stock_changed");
+        assert_eq!(json["delta"], -2);
+        assert_eq!(event.entity_id(), "p-1");
+
+        let back: EntityEvent = serde_json::from_value(json).unwrap();
+        assert_eq!(back, event);
+    }
+}
@@ -0,0 +1,248 @@
+//! # Customer Erasure
+//!
+//! "Right to erasure" requests: a customer's personal data is anonymized
+//! everywhere it is held, while the sales, payments and store credit
+//! balances it is attached to stay in the books.
+//!
+//! ## What Is Erased
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                     Customer Erasure                                    │
+//! │                                                                         │
+//! │  Subject: business customer ID and/or phone and/or email               │
+//! │                                                                         │
+//! │  business customer (by ID)   name → ERASED_NAME, tax ID, address,      │
+//! │                              email cleared; soft-deleted               │
+//! │    └─ its invoiced sales     age verification birthdate cleared        │
+//! │  store credit (by phone)     name → ERASED_NAME, phone cleared         │
+//! │  layaways (by phone)         name → ERASED_NAME, phone, notes cleared  │
+//! │  quotes (by email)           name, email, notes cleared                │
+//! │                                                                         │
+//! │  Kept: sales, lines, payments, invoice links, credit ledger entries,   │
+//! │  layaway payments, totals. Nothing is deleted.                          │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Propagation
+//! ```text
+//! Terminal: forget_customer ──► anonymize locally, erasure_log
+//!             │  outbox CUSTOMER_ERASURE
+//!             ▼
+//! Hub: anonymize its copy ──► relay to every terminal of the store
+//!             │  (terminal direct upload when no hub: cloud fallback)
+//!             ▼
+//! Cloud: anonymize store credit ──► route to every store of the tenant
+//!
+//! Cloud ForgetCustomer RPC starts at the cloud and reaches the stores the
+//! same way.
+//! ```
+//!
+//! Applying a request twice changes nothing more, so every copy may apply
+//! it on arrival. Each copy records the request in its erasure log with
+//! what it anonymized; the log keeps the request's identifiers so a later
+//! audit can show the request was carried out.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::ValidationError;
+use crate::validation::ValidationResult;
+
+/// Name written over an erased customer's name.
+pub const ERASED_NAME: &str = "Erased customer";
+
+// =============================================================================
+// Erasure Subject
+// =============================================================================
+
+/// Identifies the customer whose data is erased.
+///
+/// Each identifier that is set is matched on its own: the business
+/// customer by ID, retail documents by phone or email.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ErasureSubject {
+    pub business_customer_id: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+}
+
+impl ErasureSubject {
+    /// Trims the identifiers, drops empty ones and lowercases the email.
+    ///
+    /// ## Errors
+    /// `ValidationError::Required` if no identifier is left.
+    pub fn normalized(&self) -> ValidationResult<Self> {
+        let clean = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        let subject = ErasureSubject {
+            business_customer_id: clean(&self.business_customer_id),
+            phone: clean(&self.phone),
+            email: clean(&self.email).map(|e| e.to_lowercase()),
+        };
+
+        if subject.business_customer_id.is_none()
+            && subject.phone.is_none()
+            && subject.email.is_none()
+        {
+            return Err(ValidationError::Required {
+                field: "business_customer_id, phone or email".to_string(),
+            });
+        }
+        Ok(subject)
+    }
+}
+
+// =============================================================================
+// Erasure Request
+// =============================================================================
+
+/// A request to erase one customer's data (outbox `CUSTOMER_ERASURE`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ErasureRequest {
+    pub id: String,
+    pub tenant_id: String,
+    pub subject: ErasureSubject,
+    /// Why the data was erased (e.g. the customer's request reference).
+    pub reason: Option<String>,
+    /// Cashier or back-office user who made the request.
+    pub requested_by: String,
+    #[ts(as = "String")]
+    pub requested_at: DateTime<Utc>,
+}
+
+impl ErasureRequest {
+    /// A new request with a normalized subject.
+    ///
+    /// ## Errors
+    /// `ValidationError::Required` if the subject has no identifier or
+    /// `requested_by` is empty.
+    pub fn new(
+        id: String,
+        tenant_id: &str,
+        subject: &ErasureSubject,
+        reason: Option<String>,
+        requested_by: &str,
+        requested_at: DateTime<Utc>,
+    ) -> ValidationResult<Self> {
+        if requested_by.trim().is_empty() {
+            return Err(ValidationError::Required {
+                field: "requested_by".to_string(),
+            });
+        }
+        Ok(ErasureRequest {
+            id,
+            tenant_id: tenant_id.to_string(),
+            subject: subject.normalized()?,
+            reason: reason
+                .map(|r| r.trim().to_string())
+                .filter(|r| !r.is_empty()),
+            requested_by: requested_by.trim().to_string(),
+            requested_at,
+        })
+    }
+}
+
+// =============================================================================
+// Erasure Record
+// =============================================================================
+
+/// Where an erasure log entry came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "snake_case"))]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum ErasureOrigin {
+    /// Requested on this device.
+    Local,
+    /// Received through sync from another device or the cloud.
+    Sync,
+}
+
+/// Rows anonymized by one erasure, per kind.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ErasureCounts {
+    pub business_customers: u32,
+    pub age_verifications: u32,
+    pub store_credit_accounts: u32,
+    pub layaways: u32,
+    pub quotes: u32,
+}
+
+impl ErasureCounts {
+    /// Total rows anonymized.
+    pub fn total(&self) -> u32 {
+        self.business_customers
+            + self.age_verifications
+            + self.store_credit_accounts
+            + self.layaways
+            + self.quotes
+    }
+}
+
+/// An erasure log entry: the request and what it anonymized here.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ErasureRecord {
+    pub request: ErasureRequest,
+    pub origin: ErasureOrigin,
+    pub counts: ErasureCounts,
+    #[ts(as = "String")]
+    pub erased_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subject_is_normalized() {
+        let subject = ErasureSubject {
+            business_customer_id: Some("  ".to_string()),
+            phone: Some(" 555-0100 ".to_string()),
+            email: Some("Ann@Example.COM".to_string()),
+        };
+        let normalized = subject.normalized().unwrap();
+        assert_eq!(normalized.business_customer_id, None);
+        assert_eq!(normalized.phone.as_deref(), Some("555-0100"));
+        assert_eq!(normalized.email.as_deref(), Some("ann@example.com"));
+
+        assert!(ErasureSubject::default().normalized().is_err());
+    }
+
+    #[test]
+    fn test_request_requires_requester() {
+        let subject = ErasureSubject {
+            phone: Some("555-0100".to_string()),
+            ..Default::default()
+        };
+        let request = |reason: Option<&str>, requested_by: &str| {
+            ErasureRequest::new(
+                "e-1".to_string(),
+                "t-1",
+                &subject,
+                reason.map(str::to_string),
+                requested_by,
+                Utc::now(),
+            )
+        };
+        assert!(request(None, " ").is_err());
+
+        let request = request(Some(" ticket 42 "), "u-1").unwrap();
+        assert_eq!(request.reason.as_deref(), Some("ticket 42"));
+        assert_eq!(request.subject, subject);
+    }
+}
@@ -9,7 +9,9 @@
 //! │                                                                         │
 //! │  titan-core errors (this file)                                         │
 //! │  ├── CoreError        - General domain errors                          │
-//! │  └── ValidationError  - Input validation failures                      │
+//! │  ├── ValidationError  - Input validation failures                      │
+//! │  ├── PayloadError     - Sync payloads that fail their schema           │
+//! │  └── ErrorCode        - Stable machine-readable code for every error   │
 //! │                                                                         │
 //! │  titan-db errors (separate crate)                                      │
 //! │  └── DbError          - Database operation failures                    │
@@ -26,8 +28,30 @@
 //! 2. Include context in error messages (SKU, ID, etc.)
 //! 3. Errors are enum variants, never String
 //! 4. Each error variant maps to a user-facing message
+//! 5. Each error variant maps to an [`ErrorCode`] via `code()`
+//!
+//! ## Error Codes
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Error Codes                                     │
+//! │                                                                         │
+//! │  CoreError::code() ─┐                                                   │
+//! │  DbError::code()   ─┼──► ErrorCode ──► ApiError { code, retryable }    │
+//! │  SyncError::code() ─┘        │          (frontend)                      │
+//! │                              └──────► SyncMessage::Error { code }       │
+//! │                                         (hub ◄──► terminal)             │
+//! │                                                                         │
+//! │  Codes are part of the wire format: never rename one, only add.        │
+//! │  A receiver that does not know a code treats it as not retryable.      │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
 
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use ts_rs::TS;
 
 // =============================================================================
 // Core Error
@@ -102,11 +126,170 @@ pub enum CoreError {
     #[error("Invalid payment amount: {reason}")]
     InvalidPaymentAmount { reason: String },
 
+    /// Quote cannot be converted into a sale.
+    ///
+    /// ## When This Occurs
+    /// - Quote was already converted or cancelled
+    /// - Quote's validity window has passed
+    #[error("Quote {quote_id} cannot be converted: {reason}")]
+    QuoteNotConvertible { quote_id: String, reason: String },
+
+    /// Layaway is not in a state that allows the requested operation.
+    ///
+    /// ## When This Occurs
+    /// - Taking a payment on a completed or cancelled layaway
+    /// - Picking up or cancelling a layaway twice
+    #[error("Layaway {layaway_id} is {current_status}, cannot perform operation")]
+    InvalidLayawayStatus {
+        layaway_id: String,
+        current_status: String,
+    },
+
+    /// Store transfer is not in a state that allows the requested operation.
+    ///
+    /// ## When This Occurs
+    /// - Receiving a transfer that was cancelled by the sender
+    /// - Receiving or cancelling a transfer twice
+    #[error("Transfer {transfer_id} is {current_status}, cannot perform operation")]
+    InvalidTransferStatus {
+        transfer_id: String,
+        current_status: String,
+    },
+
+    /// Sale contains age-restricted products and the customer's age is not
+    /// verified.
+    ///
+    /// ## When This Occurs
+    /// - Finalizing without a recorded age check
+    /// - Entered birthdate makes the customer younger than required
+    #[error("Customer must be at least {required_age} to purchase: {reason}")]
+    AgeRestricted { required_age: u32, reason: String },
+
+    /// Store credit balance does not cover the requested redemption.
+    ///
+    /// ## When This Occurs
+    /// - Tendering more credit than the account holds
+    /// - Another terminal spent the balance first
+    #[error("Store credit {credit_number} has {available_cents} available, {requested_cents} requested")]
+    InsufficientStoreCredit {
+        credit_number: String,
+        available_cents: i64,
+        requested_cents: i64,
+    },
+
+    /// A fiscal backend could not sign the receipt.
+    ///
+    /// ## When This Occurs
+    /// - Fiscal export location is not writable
+    /// - Signing device or service is unavailable
+    #[error("Fiscal signing failed for sale {sale_id}: {reason}")]
+    FiscalSigningFailed { sale_id: String, reason: String },
+
+    /// A hardware device did not take a command.
+    ///
+    /// ## When This Occurs
+    /// - The cash drawer's printer or port is unplugged, off or busy
+    /// - The configured device path does not exist
+    #[error("{device} failed: {reason}")]
+    DeviceFailed { device: String, reason: String },
+
+    /// A feature flag gating the operation is off for this store.
+    ///
+    /// ## When This Occurs
+    /// - The feature is still being rolled out to other stores
+    /// - The flag was switched off in the cloud back office
+    #[error("{feature} is not enabled for this store")]
+    FeatureDisabled { feature: String },
+
+    /// A manual price is below the store's blocking margin floor.
+    ///
+    /// ## When This Occurs
+    /// - A product price or line price override leaves too little margin
+    ///   over cost, and no manager approved it
+    #[error(
+        "Margin on {sku} is below the {}.{:02}% floor; a manager must approve the price",
+        .floor_bps / 100,
+        .floor_bps % 100
+    )]
+    MarginBelowFloor {
+        sku: String,
+        margin_bps: Option<i64>,
+        floor_bps: u32,
+    },
+
+    /// The operation needs a manager override token and none was given.
+    ///
+    /// ## When This Occurs
+    /// - A refund above the store's approval limit, with no manager at
+    ///   the till to approve it
+    #[error("A manager must approve this {action}")]
+    OverrideRequired { action: String },
+
+    /// A manager override token was refused.
+    ///
+    /// ## When This Occurs
+    /// - The signature does not match the store key (forged or altered)
+    /// - The token expired, or was issued for another store, terminal,
+    ///   action, sale or a lower amount
+    /// - The token was already used
+    #[error("Manager override rejected: {reason}")]
+    OverrideRejected { reason: String },
+
+    /// A coupon cannot be used on this cart.
+    ///
+    /// ## When This Occurs
+    /// - The coupon is withdrawn, not yet valid or expired
+    /// - A single-use coupon was already redeemed, here or in another store
+    /// - The cart is below the minimum or holds no eligible products
+    #[error("Coupon {code} cannot be used: {reason}")]
+    CouponRejected { code: String, reason: String },
+
+    /// A loyalty member's rewards do not cover the requested redemption.
+    ///
+    /// ## When This Occurs
+    /// - Tendering more rewards than the member holds
+    /// - The rewards were spent first on another terminal or in another store
+    #[error("Loyalty member {member_number} has {available_cents} in rewards, {requested_cents} requested")]
+    InsufficientRewards {
+        member_number: String,
+        available_cents: i64,
+        requested_cents: i64,
+    },
+
     /// Validation error (wraps ValidationError).
     #[error("Validation error: {0}")]
     Validation(#[from] ValidationError),
 }
 
+impl CoreError {
+    /// Stable code for this error.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            CoreError::ProductNotFound(_) | CoreError::SaleNotFound(_) => ErrorCode::NotFound,
+            CoreError::InsufficientStock { .. } => ErrorCode::InsufficientStock,
+            CoreError::InvalidSaleStatus { .. }
+            | CoreError::QuoteNotConvertible { .. }
+            | CoreError::InvalidLayawayStatus { .. }
+            | CoreError::InvalidTransferStatus { .. } => ErrorCode::BusinessLogic,
+            CoreError::CartTooLarge { .. } => ErrorCode::CartError,
+            CoreError::QuantityTooLarge { .. } | CoreError::Validation(_) => {
+                ErrorCode::ValidationError
+            }
+            CoreError::InvalidPaymentAmount { .. } => ErrorCode::PaymentError,
+            CoreError::AgeRestricted { .. } => ErrorCode::AgeVerificationRequired,
+            CoreError::InsufficientStoreCredit { .. } => ErrorCode::InsufficientStoreCredit,
+            CoreError::FiscalSigningFailed { .. } => ErrorCode::FiscalError,
+            CoreError::DeviceFailed { .. } => ErrorCode::Unavailable,
+            CoreError::FeatureDisabled { .. } => ErrorCode::FeatureDisabled,
+            CoreError::MarginBelowFloor { .. }
+            | CoreError::OverrideRequired { .. }
+            | CoreError::OverrideRejected { .. } => ErrorCode::ManagerOverrideRequired,
+            CoreError::CouponRejected { .. } => ErrorCode::CouponRejected,
+            CoreError::InsufficientRewards { .. } => ErrorCode::InsufficientRewards,
+        }
+    }
+}
+
 // =============================================================================
 // Validation Error
 // =============================================================================
@@ -150,6 +333,222 @@ pub enum ValidationError {
     Duplicate { field: String, value: String },
 }
 
+// =============================================================================
+// Payload Error
+// =============================================================================
+
+/// Sync payloads that do not match their schema.
+///
+/// Raised when queuing an entity for sync and when the hub receives one;
+/// see [`crate::sync_payload`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PayloadError {
+    /// No schema exists for the entity type.
+    #[error("Unknown sync entity type: {0}")]
+    UnknownEntityType(String),
+
+    /// Written against a schema version this build does not know.
+    #[error("Payload schema v{found} is not supported (up to v{supported})")]
+    UnsupportedSchema { found: u32, supported: u32 },
+
+    /// Not valid JSON, or does not fit the entity type's DTO.
+    #[error("Malformed {entity_type} payload: {reason}")]
+    Malformed {
+        entity_type: &'static str,
+        reason: String,
+    },
+
+    /// The payload describes a different entity than its outbox entry.
+    #[error("{entity_type} payload is for {found}, but the entry is for {expected}")]
+    EntityMismatch {
+        entity_type: &'static str,
+        expected: String,
+        found: String,
+    },
+
+    /// Well-formed but inconsistent (e.g. a line of another document).
+    #[error("Invalid {entity_type} payload: {reason}")]
+    Invalid {
+        entity_type: &'static str,
+        reason: String,
+    },
+}
+
+// =============================================================================
+// Error Codes
+// =============================================================================
+
+/// Stable, machine-readable error codes shared by every crate.
+///
+/// Serialized as `SCREAMING_SNAKE_CASE` to the frontend (`ApiError.code`,
+/// `sync:error` events) and into sync `Error` messages, so the UI and the
+/// hub can react to a failure without parsing its message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[ts(export)]
+pub enum ErrorCode {
+    /// Resource not found (404)
+    NotFound,
+    /// Input validation failed (400)
+    ValidationError,
+    /// The change collides with existing data (duplicate, stale version)
+    Conflict,
+    /// Database operation failed (500)
+    DatabaseError,
+    /// Business logic error (422)
+    BusinessLogic,
+    /// Internal error (500)
+    Internal,
+    /// Cart operation failed
+    CartError,
+    /// Insufficient stock
+    InsufficientStock,
+    /// Payment processing error
+    PaymentError,
+    /// Sale has age-restricted items and needs an age check
+    AgeVerificationRequired,
+    /// Store credit balance is too low (or could not be verified)
+    InsufficientStoreCredit,
+    /// The fiscal backend could not sign the sale
+    FiscalError,
+    /// Settings are missing or invalid
+    ConfigError,
+    /// A dependency (database pool, hub, network) is unreachable for now
+    Unavailable,
+    /// The operation took too long
+    Timeout,
+    /// The hub is shedding load; back off and retry
+    Busy,
+    /// Metered sync is outside its upload window
+    SyncDeferred,
+    /// Sync was paused by an operator
+    SyncPaused,
+    /// Credentials or device enrollment were rejected
+    AuthFailed,
+    /// Hub and terminal share no protocol version
+    UpgradeRequired,
+    /// The terminal belongs to a different store than the hub
+    StoreMismatch,
+    /// A message failed signature or replay checks
+    IntegrityFailed,
+    /// A message could not be understood
+    ProtocolError,
+    /// The cloud rejected or failed the request
+    CloudError,
+    /// The feature is switched off for this store
+    FeatureDisabled,
+    /// The operation needs a manager's approval
+    ManagerOverrideRequired,
+    /// The app is still starting up (database not open yet)
+    NotReady,
+    /// The coupon does not apply (expired, used up, cart does not qualify)
+    CouponRejected,
+    /// Loyalty rewards balance is too low (or could not be verified)
+    InsufficientRewards,
+}
+
+impl ErrorCode {
+    /// Every code, in declaration order.
+    pub const ALL: [ErrorCode; 29] = [
+        ErrorCode::NotFound,
+        ErrorCode::ValidationError,
+        ErrorCode::Conflict,
+        ErrorCode::DatabaseError,
+        ErrorCode::BusinessLogic,
+        ErrorCode::Internal,
+        ErrorCode::CartError,
+        ErrorCode::InsufficientStock,
+        ErrorCode::PaymentError,
+        ErrorCode::AgeVerificationRequired,
+        ErrorCode::InsufficientStoreCredit,
+        ErrorCode::FiscalError,
+        ErrorCode::ConfigError,
+        ErrorCode::Unavailable,
+        ErrorCode::Timeout,
+        ErrorCode::Busy,
+        ErrorCode::SyncDeferred,
+        ErrorCode::SyncPaused,
+        ErrorCode::AuthFailed,
+        ErrorCode::UpgradeRequired,
+        ErrorCode::StoreMismatch,
+        ErrorCode::IntegrityFailed,
+        ErrorCode::ProtocolError,
+        ErrorCode::CloudError,
+        ErrorCode::FeatureDisabled,
+        ErrorCode::ManagerOverrideRequired,
+        ErrorCode::NotReady,
+        ErrorCode::CouponRejected,
+        ErrorCode::InsufficientRewards,
+    ];
+
+    /// The wire form of the code (same as its serde form).
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::ValidationError => "VALIDATION_ERROR",
+            ErrorCode::Conflict => "CONFLICT",
+            ErrorCode::DatabaseError => "DATABASE_ERROR",
+            ErrorCode::BusinessLogic => "BUSINESS_LOGIC",
+            ErrorCode::Internal => "INTERNAL",
+            ErrorCode::CartError => "CART_ERROR",
+            ErrorCode::InsufficientStock => "INSUFFICIENT_STOCK",
+            ErrorCode::PaymentError => "PAYMENT_ERROR",
+            ErrorCode::AgeVerificationRequired => "AGE_VERIFICATION_REQUIRED",
+            ErrorCode::InsufficientStoreCredit => "INSUFFICIENT_STORE_CREDIT",
+            ErrorCode::FiscalError => "FISCAL_ERROR",
+            ErrorCode::ConfigError => "CONFIG_ERROR",
+            ErrorCode::Unavailable => "UNAVAILABLE",
+            ErrorCode::Timeout => "TIMEOUT",
+            ErrorCode::Busy => "BUSY",
+            ErrorCode::SyncDeferred => "SYNC_DEFERRED",
+            ErrorCode::SyncPaused => "SYNC_PAUSED",
+            ErrorCode::AuthFailed => "AUTH_FAILED",
+            ErrorCode::UpgradeRequired => "UPGRADE_REQUIRED",
+            ErrorCode::StoreMismatch => "STORE_MISMATCH",
+            ErrorCode::IntegrityFailed => "INTEGRITY_FAILED",
+            ErrorCode::ProtocolError => "PROTOCOL_ERROR",
+            ErrorCode::CloudError => "CLOUD_ERROR",
+            ErrorCode::FeatureDisabled => "FEATURE_DISABLED",
+            ErrorCode::ManagerOverrideRequired => "MANAGER_OVERRIDE_REQUIRED",
+            ErrorCode::NotReady => "NOT_READY",
+            ErrorCode::CouponRejected => "COUPON_REJECTED",
+            ErrorCode::InsufficientRewards => "INSUFFICIENT_REWARDS",
+        }
+    }
+
+    /// Whether the same request may succeed if simply tried again later.
+    ///
+    /// Only transient conditions qualify; anything needing a different
+    /// input, an operator or an upgrade does not.
+    pub const fn is_retryable(self) -> bool {
+        matches!(
+            self,
+            ErrorCode::Unavailable
+                | ErrorCode::Timeout
+                | ErrorCode::Busy
+                | ErrorCode::SyncDeferred
+                | ErrorCode::NotReady
+        )
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ErrorCode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ErrorCode::ALL
+            .into_iter()
+            .find(|code| code.as_str() == s)
+            .ok_or_else(|| format!("Unknown error code: {}", s))
+    }
+}
+
 // =============================================================================
 // Result Type Alias
 // =============================================================================
@@ -199,5 +598,22 @@ mod tests {
         };
         let core_err: CoreError = validation_err.into();
         assert!(matches!(core_err, CoreError::Validation(_)));
+        assert_eq!(core_err.code(), ErrorCode::ValidationError);
+    }
+
+    #[test]
+    fn test_error_codes_are_stable() {
+        for code in ErrorCode::ALL {
+            let json = serde_json::to_string(&code).unwrap();
+            assert_eq!(json, format!("\"{}\"", code.as_str()));
+            assert_eq!(code.as_str().parse::<ErrorCode>(), Ok(code));
+        }
+        assert!("SOMETHING_NEW".parse::<ErrorCode>().is_err());
+
+        assert!(ErrorCode::Busy.is_retryable());
+        assert!(ErrorCode::Unavailable.is_retryable());
+        assert!(ErrorCode::NotReady.is_retryable());
+        assert!(!ErrorCode::UpgradeRequired.is_retryable());
+        assert!(!ErrorCode::ValidationError.is_retryable());
     }
 }
@@ -0,0 +1,173 @@
+//! # Feature Flags
+//!
+//! Switches that gate new subsystems (loyalty, gift cards, ...) so they can
+//! be rolled out one tenant or store at a time. The cloud decides which
+//! flags are on for a store; terminals cache the answer and check it
+//! locally before using a gated feature.
+//!
+//! ## Cache Lifecycle
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Feature Flag Cache                              │
+//! │                                                                         │
+//! │  cloud GetFeatureFlags ──► FeatureFlags { flags, fetched_at, ttl }      │
+//! │                                 │ saved to the local cache              │
+//! │                                 ▼                                       │
+//! │  is_enabled(LOYALTY) ──► listed and on? ──► true                        │
+//! │                          otherwise (unknown, off, never fetched) false  │
+//! │                                                                         │
+//! │  is_stale(now): never fetched, or fetched_at + ttl has passed           │
+//! │       ──► refresh from the cloud; until that succeeds the stale        │
+//! │           flags stay in effect (the store keeps working offline)       │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! A feature is off until the cloud says otherwise, so a new terminal that
+//! has never reached the cloud runs without the gated subsystems.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CoreError, CoreResult};
+
+/// Loyalty points and member pricing.
+pub const LOYALTY: &str = "loyalty";
+
+/// Gift card sale and redemption.
+pub const GIFT_CARDS: &str = "gift_cards";
+
+/// Flags this build knows, with the name shown to staff.
+pub const KNOWN_FEATURES: &[(&str, &str)] = &[(LOYALTY, "Loyalty"), (GIFT_CARDS, "Gift cards")];
+
+/// Cache lifetime used when the cloud does not send one.
+pub const DEFAULT_TTL_SECS: u32 = 15 * 60;
+
+/// Shortest cache lifetime accepted, so a misconfigured TTL cannot turn
+/// every flag check into a cloud call.
+pub const MIN_TTL_SECS: u32 = 60;
+
+/// The name shown to staff for a flag key (the key itself if unknown).
+pub fn feature_name(key: &str) -> &str {
+    KNOWN_FEATURES
+        .iter()
+        .find(|(known, _)| *known == key)
+        .map_or(key, |(_, name)| name)
+}
+
+/// Feature flags in effect for this store, as last fetched from the cloud.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeatureFlags {
+    /// Flag key to on/off. Keys not listed are off.
+    pub flags: BTreeMap<String, bool>,
+    /// When the flags were fetched (`None`: never).
+    pub fetched_at: Option<DateTime<Utc>>,
+    /// How long the flags may be used before refreshing.
+    pub ttl_secs: u32,
+}
+
+impl FeatureFlags {
+    /// Flags fetched at `fetched_at`, cached for `ttl_secs` (0 means
+    /// [`DEFAULT_TTL_SECS`], and at least [`MIN_TTL_SECS`]).
+    pub fn new<I, K>(flags: I, fetched_at: DateTime<Utc>, ttl_secs: u32) -> Self
+    where
+        I: IntoIterator<Item = (K, bool)>,
+        K: Into<String>,
+    {
+        let ttl_secs = match ttl_secs {
+            0 => DEFAULT_TTL_SECS,
+            ttl => ttl.max(MIN_TTL_SECS),
+        };
+        FeatureFlags {
+            flags: flags.into_iter().map(|(k, v)| (k.into(), v)).collect(),
+            fetched_at: Some(fetched_at),
+            ttl_secs,
+        }
+    }
+
+    /// Whether the feature is on. Unknown flags are off.
+    pub fn is_enabled(&self, key: &str) -> bool {
+        self.flags.get(key).copied().unwrap_or(false)
+    }
+
+    /// Fails with `CoreError::FeatureDisabled` unless the feature is on.
+    pub fn require(&self, key: &str) -> CoreResult<()> {
+        if self.is_enabled(key) {
+            Ok(())
+        } else {
+            Err(CoreError::FeatureDisabled {
+                feature: feature_name(key).to_string(),
+            })
+        }
+    }
+
+    /// When the flags should be refreshed (`None`: never fetched).
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.fetched_at
+            .map(|at| at + Duration::seconds(i64::from(self.ttl_secs)))
+    }
+
+    /// Whether the flags should be refreshed from the cloud.
+    pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at().is_none_or(|expires| now >= expires)
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+
+    #[test]
+    fn test_unknown_and_unfetched_flags_are_off() {
+        let never = FeatureFlags::default();
+        assert!(!never.is_enabled(LOYALTY));
+        assert!(never.is_stale(Utc::now()));
+        assert_eq!(never.expires_at(), None);
+
+        let flags = FeatureFlags::new([(LOYALTY, true), (GIFT_CARDS, false)], Utc::now(), 600);
+        assert!(flags.is_enabled(LOYALTY));
+        assert!(!flags.is_enabled(GIFT_CARDS));
+        assert!(!flags.is_enabled("self_checkout"));
+    }
+
+    #[test]
+    fn test_require() {
+        let flags = FeatureFlags::new([(LOYALTY, true)], Utc::now(), 600);
+        assert!(flags.require(LOYALTY).is_ok());
+
+        let err = flags.require(GIFT_CARDS).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::FeatureDisabled);
+        assert_eq!(err.to_string(), "Gift cards is not enabled for this store");
+    }
+
+    #[test]
+    fn test_staleness_follows_ttl() {
+        let fetched = Utc::now();
+        let flags = FeatureFlags::new([(LOYALTY, true)], fetched, 600);
+
+        assert!(!flags.is_stale(fetched + Duration::seconds(599)));
+        assert!(flags.is_stale(fetched + Duration::seconds(600)));
+        // Stale flags still answer until a refresh replaces them
+        assert!(flags.is_enabled(LOYALTY));
+    }
+
+    #[test]
+    fn test_ttl_defaults_and_floor() {
+        let now = Utc::now();
+        let no_flags: [(&str, bool); 0] = [];
+        assert_eq!(FeatureFlags::new(no_flags, now, 0).ttl_secs, DEFAULT_TTL_SECS);
+        assert_eq!(FeatureFlags::new(no_flags, now, 5).ttl_secs, MIN_TTL_SECS);
+    }
+
+    #[test]
+    fn test_feature_name() {
+        assert_eq!(feature_name(GIFT_CARDS), "Gift cards");
+        assert_eq!(feature_name("self_checkout"), "self_checkout");
+    }
+}
@@ -0,0 +1,303 @@
+//! # Fiscal Signing
+//!
+//! The adapter layer for markets that require fiscal receipts: every
+//! finalized sale gets a sequential, signed record that chains to the one
+//! before it, so a missing or altered receipt is detectable by the tax
+//! authority.
+//!
+//! ## Signing Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Fiscal Signing Flow                             │
+//! │                                                                         │
+//! │  finalize_sale                                                          │
+//! │       │                                                                 │
+//! │       ▼                                                                 │
+//! │  FiscalReceipt (sale snapshot)   FiscalChain (next seq, prev signature) │
+//! │       │                                 │                               │
+//! │       └──────────────┬──────────────────┘                               │
+//! │                      ▼                                                  │
+//! │            dyn FiscalAdapter::sign                                      │
+//! │                      │                                                  │
+//! │        ┌─────────────┼──────────────────────┐                           │
+//! │        ▼             ▼                      ▼                           │
+//! │   NoopFiscal     file export signer     (country backends)              │
+//! │   → None         → FiscalSignature      → FiscalSignature               │
+//! │                                                                         │
+//! │  The signature is stored on the sale before it is completed; signing   │
+//! │  failures block the sale.                                              │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Chaining
+//! Each signature covers [`canonical_payload`], which includes the sequence
+//! number and the previous signature:
+//!
+//! ```text
+//! seq 1: sign(payload₁ | prev="")        = S₁
+//! seq 2: sign(payload₂ | prev=S₁)        = S₂
+//! seq 3: sign(payload₃ | prev=S₂)        = S₃
+//! ```
+//!
+//! Backends do the signing (and any I/O); this module only defines the
+//! contract and the bytes that get signed.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::CoreResult;
+
+// =============================================================================
+// Receipt Snapshot
+// =============================================================================
+
+/// A sale line as it appears on the fiscal receipt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FiscalLine {
+    pub name: String,
+    pub quantity: i64,
+    pub unit_price_cents: i64,
+    pub line_total_cents: i64,
+    pub tax_cents: i64,
+}
+
+/// A payment as it appears on the fiscal receipt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FiscalPayment {
+    /// Tender name, e.g. "cash".
+    pub method: String,
+    pub amount_cents: i64,
+}
+
+/// Everything about a sale that a fiscal backend signs or reports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FiscalReceipt {
+    pub sale_id: String,
+    pub receipt_number: String,
+    pub tenant_id: String,
+    pub device_id: String,
+    pub subtotal_cents: i64,
+    pub tax_cents: i64,
+    pub total_cents: i64,
+    pub lines: Vec<FiscalLine>,
+    pub payments: Vec<FiscalPayment>,
+    /// When the sale is being finalized.
+    pub issued_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// Signature & Chain
+// =============================================================================
+
+/// The fiscal record stored on a sale.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct FiscalSignature {
+    pub sale_id: String,
+    /// Backend that produced the signature, e.g. "file_export".
+    pub backend: String,
+    /// Position in the backend's sequence, starting at 1.
+    pub sequence: i64,
+    pub signature: String,
+    /// Signature of the previous receipt (`None` for the first).
+    pub previous_signature: Option<String>,
+    #[ts(as = "String")]
+    pub signed_at: DateTime<Utc>,
+}
+
+/// Where the next receipt goes in a backend's sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FiscalChain {
+    pub sequence: i64,
+    pub previous_signature: Option<String>,
+}
+
+impl FiscalChain {
+    /// The chain position after `last`, or the start of the chain.
+    pub fn after(last: Option<&FiscalSignature>) -> Self {
+        match last {
+            Some(last) => FiscalChain {
+                sequence: last.sequence + 1,
+                previous_signature: Some(last.signature.clone()),
+            },
+            None => FiscalChain {
+                sequence: 1,
+                previous_signature: None,
+            },
+        }
+    }
+}
+
+/// The exact text a backend signs for a receipt.
+///
+/// One `key=value` field per line, in a fixed order, with integer cents
+/// and RFC 3339 timestamps, so the same receipt always yields the same
+/// bytes and a verifier can rebuild it.
+pub fn canonical_payload(receipt: &FiscalReceipt, chain: &FiscalChain) -> String {
+    let mut out = String::new();
+    let mut field = |key: &str, value: &str| {
+        out.push_str(key);
+        out.push('=');
+        out.push_str(value);
+        out.push('\n');
+    };
+
+    field("seq", &chain.sequence.to_string());
+    field("prev", chain.previous_signature.as_deref().unwrap_or(""));
+    field("tenant", &receipt.tenant_id);
+    field("device", &receipt.device_id);
+    field("sale", &receipt.sale_id);
+    field("receipt", &receipt.receipt_number);
+    field(
+        "issued",
+        &receipt
+            .issued_at
+            .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    );
+    for line in &receipt.lines {
+        field(
+            "line",
+            &format!(
+                "{}|{}|{}|{}|{}",
+                line.name,
+                line.quantity,
+                line.unit_price_cents,
+                line.line_total_cents,
+                line.tax_cents
+            ),
+        );
+    }
+    for payment in &receipt.payments {
+        field(
+            "pay",
+            &format!("{}|{}", payment.method, payment.amount_cents),
+        );
+    }
+    field("subtotal", &receipt.subtotal_cents.to_string());
+    field("tax", &receipt.tax_cents.to_string());
+    field("total", &receipt.total_cents.to_string());
+
+    out
+}
+
+// =============================================================================
+// Adapter Trait
+// =============================================================================
+
+/// A fiscal backend.
+///
+/// Implementations live with the I/O they need (files, devices, tax
+/// authority APIs). `sign` returns `Ok(None)` when the backend does not
+/// sign receipts.
+///
+/// ## Errors
+/// `CoreError::FiscalSigningFailed` if the receipt could not be signed;
+/// the sale must not be completed.
+pub trait FiscalAdapter: Send + Sync {
+    /// Stable backend name, stored with each signature.
+    fn backend(&self) -> &'static str;
+
+    /// Signs a receipt at the given chain position.
+    fn sign(
+        &self,
+        receipt: &FiscalReceipt,
+        chain: &FiscalChain,
+    ) -> CoreResult<Option<FiscalSignature>>;
+}
+
+/// Backend for markets without fiscal requirements: signs nothing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopFiscalAdapter;
+
+impl FiscalAdapter for NoopFiscalAdapter {
+    fn backend(&self) -> &'static str {
+        "none"
+    }
+
+    fn sign(
+        &self,
+        _receipt: &FiscalReceipt,
+        _chain: &FiscalChain,
+    ) -> CoreResult<Option<FiscalSignature>> {
+        Ok(None)
+    }
+}
+
+// =============================================================================
+// Unit Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn receipt() -> FiscalReceipt {
+        FiscalReceipt {
+            sale_id: "s1".to_string(),
+            receipt_number: "R-1".to_string(),
+            tenant_id: "t".to_string(),
+            device_id: "pos-01".to_string(),
+            subtotal_cents: 1000,
+            tax_cents: 83,
+            total_cents: 1083,
+            lines: vec![FiscalLine {
+                name: "Coke".to_string(),
+                quantity: 2,
+                unit_price_cents: 500,
+                line_total_cents: 1000,
+                tax_cents: 83,
+            }],
+            payments: vec![FiscalPayment {
+                method: "cash".to_string(),
+                amount_cents: 1083,
+            }],
+            issued_at: Utc.with_ymd_and_hms(2024, 5, 1, 12, 30, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_chain_starts_at_one_and_links_previous() {
+        let first = FiscalChain::after(None);
+        assert_eq!(first.sequence, 1);
+        assert!(first.previous_signature.is_none());
+
+        let last = FiscalSignature {
+            sale_id: "s1".to_string(),
+            backend: "file_export".to_string(),
+            sequence: 7,
+            signature: "abc".to_string(),
+            previous_signature: None,
+            signed_at: Utc::now(),
+        };
+        let next = FiscalChain::after(Some(&last));
+        assert_eq!(next.sequence, 8);
+        assert_eq!(next.previous_signature.as_deref(), Some("abc"));
+    }
+
+    #[test]
+    fn test_canonical_payload_is_stable_and_covers_chain() {
+        let chain = FiscalChain::after(None);
+        let payload = canonical_payload(&receipt(), &chain);
+        assert_eq!(payload, canonical_payload(&receipt(), &chain));
+        assert!(payload.starts_with("seq=1\nprev=\n"));
+        assert!(payload.contains("issued=2024-05-01T12:30:00Z\n"));
+        assert!(payload.contains("line=Coke|2|500|1000|83\n"));
+        assert!(payload.ends_with("total=1083\n"));
+
+        let chained = FiscalChain {
+            sequence: 2,
+            previous_signature: Some("S1".to_string()),
+        };
+        assert_ne!(payload, canonical_payload(&receipt(), &chained));
+    }
+
+    #[test]
+    fn test_noop_adapter_signs_nothing() {
+        let result = NoopFiscalAdapter
+            .sign(&receipt(), &FiscalChain::after(None))
+            .unwrap();
+        assert!(result.is_none());
+    }
+}
@@ -0,0 +1,449 @@
+//! # Shelf Labels
+//!
+//! Types and rendering for shelf-edge labels: the queue of labels waiting
+//! to be printed (mostly after price changes) and the printer templates
+//! they are rendered with.
+//!
+//! ## Label Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                           Label Flow                                    │
+//! │                                                                         │
+//! │  inbound price change ──┐                                               │
+//! │  manual request ────────┴──► label queue (PENDING, one row per product) │
+//! │                                     │                                   │
+//! │                                     ▼ print_labels                      │
+//! │  template (ZPL / EPL) + current product data ──► printer job            │
+//! │                                     │                                   │
+//! │                                     ▼                                   │
+//! │                                  PRINTED                                │
+//! │                                                                         │
+//! │  A product changing price twice before printing keeps one queued row:  │
+//! │  the label shows today's price, with the price before the first change │
+//! │  as {was_price}.                                                        │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Templates
+//! A template is the printer's own language with placeholders:
+//!
+//! | Placeholder   | Value                                        |
+//! |---------------|----------------------------------------------|
+//! | `{name}`      | Product name                                 |
+//! | `{sku}`       | SKU                                          |
+//! | `{barcode}`   | Barcode, or the SKU when the product has none |
+//! | `{price}`     | Current price, e.g. `$12.99`                 |
+//! | `{was_price}` | Price before the change, or empty            |
+//! | `{copies}`    | Number of labels to print                    |
+//!
+//! Values are escaped for the template's language, so a product name can
+//! never end a field or start a printer command.
+//!
+//! ZPL templates can also place the barcode as an image (see
+//! [`crate::barcode`]), for printers whose own barcode commands are not
+//! enough:
+//!
+//! | Placeholder       | Image                                            |
+//! |-------------------|--------------------------------------------------|
+//! | `{barcode_image}` | `{barcode}` as EAN-13 if it is one, or Code 128  |
+//! | `{barcode_qr}`    | `{barcode}` as a QR code                         |
+//!
+//! Both render to a `^GFA` graphic field drawn at the preceding `^FO`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::barcode::{Barcode, Symbology};
+use crate::error::ValidationError;
+use crate::money::Money;
+use crate::validation::ValidationResult;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Most labels one queued row may ask for.
+pub const MAX_LABEL_COPIES: i64 = 100;
+
+/// Printer dots per module of barcode images (0.25 mm at 203 dpi).
+const IMAGE_MODULE_DOTS: usize = 2;
+
+/// Printer dots per module of QR images.
+const QR_MODULE_DOTS: usize = 4;
+
+/// Height of linear barcode images, in printer dots.
+const IMAGE_BAR_HEIGHT: usize = 50;
+
+/// Built-in ZPL template: 2" x 1" label at 203 dpi.
+pub const DEFAULT_ZPL_TEMPLATE: &str = "^XA\n\
+^CI28\n\
+^FO20,15^A0N,26,26^FB370,2,0,L^FD{name}^FS\n\
+^FO20,75^A0N,50,50^FD{price}^FS\n\
+^FO250,85^A0N,22,22^FD{was_price}^FS\n\
+^FO20,135^BY2^BCN,50,Y,N,N^FD{barcode}^FS\n\
+^PQ{copies}\n\
+^XZ\n";
+
+/// Built-in EPL template: 2" x 1" label at 203 dpi.
+pub const DEFAULT_EPL_TEMPLATE: &str = "\nN\n\
+A20,15,0,3,1,1,N,\"{name}\"\n\
+A20,60,0,4,2,2,N,\"{price}\"\n\
+A250,70,0,2,1,1,N,\"{was_price}\"\n\
+B20,120,0,1,2,4,50,B,\"{barcode}\"\n\
+P{copies}\n";
+
+// =============================================================================
+// Label Format
+// =============================================================================
+
+/// Printer language a template is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "lowercase"))]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelFormat {
+    /// Zebra Programming Language (Zebra and most compatible printers).
+    #[default]
+    Zpl,
+    /// Eltron Programming Language (older Zebra/Eltron desktop printers).
+    Epl,
+}
+
+impl LabelFormat {
+    /// The built-in template for this language.
+    pub fn default_template(&self) -> &'static str {
+        match self {
+            LabelFormat::Zpl => DEFAULT_ZPL_TEMPLATE,
+            LabelFormat::Epl => DEFAULT_EPL_TEMPLATE,
+        }
+    }
+
+    /// Makes `value` safe inside a field of this language.
+    fn escape(&self, value: &str) -> String {
+        let value: String = value.chars().filter(|c| !c.is_control()).collect();
+        match self {
+            // ^ and ~ start commands anywhere in a ZPL stream
+            LabelFormat::Zpl => value.replace(['^', '~'], " "),
+            // EPL fields are double-quoted, with backslash escapes
+            LabelFormat::Epl => value.replace('\\', "\\\\").replace('"', "\\\""),
+        }
+    }
+}
+
+// =============================================================================
+// Label Queue
+// =============================================================================
+
+/// Why a label was queued.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "snake_case"))]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelReason {
+    /// The price changed (queued automatically).
+    #[default]
+    PriceChange,
+    /// Staff asked for it.
+    Manual,
+}
+
+/// Print status of a queued label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "lowercase"))]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelStatus {
+    #[default]
+    Pending,
+    Printed,
+}
+
+/// A product's labels waiting in the print queue.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct QueuedLabel {
+    pub id: String,
+    pub product_id: String,
+    pub reason: LabelReason,
+    pub status: LabelStatus,
+    /// Price before the first change since the last print.
+    pub old_price_cents: Option<i64>,
+    /// Price when queued (the label prints the price at print time).
+    pub price_cents: i64,
+    pub copies: i64,
+    #[ts(as = "String")]
+    pub created_at: DateTime<Utc>,
+    #[ts(as = "String")]
+    pub updated_at: DateTime<Utc>,
+    #[ts(as = "Option<String>")]
+    pub printed_at: Option<DateTime<Utc>>,
+}
+
+// =============================================================================
+// Templates
+// =============================================================================
+
+/// A saved label template (see the module docs for placeholders).
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LabelTemplate {
+    pub id: String,
+    pub name: String,
+    pub format: LabelFormat,
+    pub body: String,
+    /// Used by `print_labels` when no template is named.
+    pub is_default: bool,
+    #[ts(as = "String")]
+    pub created_at: DateTime<Utc>,
+    #[ts(as = "String")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// What one label shows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelData {
+    pub name: String,
+    pub sku: String,
+    pub barcode: Option<String>,
+    pub price_cents: i64,
+    pub was_price_cents: Option<i64>,
+    pub copies: i64,
+}
+
+impl LabelData {
+    /// Sample values for template previews and validation.
+    pub fn sample() -> Self {
+        LabelData {
+            name: "Sample Product 500g".to_string(),
+            sku: "SAMPLE-001".to_string(),
+            barcode: Some("012345678905".to_string()),
+            price_cents: 1299,
+            was_price_cents: Some(1499),
+            copies: 1,
+        }
+    }
+
+    fn value(&self, placeholder: &str) -> Option<String> {
+        let value = match placeholder {
+            "name" => self.name.clone(),
+            "sku" => self.sku.clone(),
+            "barcode" => self.barcode.clone().unwrap_or_else(|| self.sku.clone()),
+            "price" => Money::from_cents(self.price_cents).to_string(),
+            "was_price" => self
+                .was_price_cents
+                .filter(|was| *was != self.price_cents)
+                .map(|was| Money::from_cents(was).to_string())
+                .unwrap_or_default(),
+            "copies" => self.copies.to_string(),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Printer commands for an image placeholder, `None` for any other.
+    fn image(&self, format: LabelFormat, placeholder: &str) -> ValidationResult<Option<String>> {
+        if !matches!(placeholder, "barcode_image" | "barcode_qr") {
+            return Ok(None);
+        }
+        if format != LabelFormat::Zpl {
+            return Err(ValidationError::InvalidFormat {
+                field: "body".to_string(),
+                reason: format!("{{{}}} is only available in ZPL templates", placeholder),
+            });
+        }
+
+        let code = self.barcode.as_deref().unwrap_or(&self.sku);
+        let bitmap = if placeholder == "barcode_qr" {
+            Barcode::encode(Symbology::Qr, code)?.render(QR_MODULE_DOTS, 0)
+        } else {
+            Barcode::encode(Symbology::Ean13, code)
+                .or_else(|_| Barcode::encode(Symbology::Code128, code))?
+                .render(IMAGE_MODULE_DOTS, IMAGE_BAR_HEIGHT)
+        };
+        Ok(Some(bitmap.zpl_graphic()))
+    }
+}
+
+/// Renders one label: every placeholder in `body` is replaced with the
+/// escaped value from `data`, or its image.
+///
+/// ## Errors
+/// `ValidationError::InvalidFormat` for an unknown or unclosed placeholder,
+/// an image placeholder outside ZPL, or a barcode no symbology can hold.
+pub fn render_label(format: LabelFormat, body: &str, data: &LabelData) -> ValidationResult<String> {
+    let mut out = String::with_capacity(body.len() + 64);
+    let mut rest = body;
+
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| ValidationError::InvalidFormat {
+                field: "body".to_string(),
+                reason: "unclosed placeholder".to_string(),
+            })?;
+        let placeholder = &after[..end];
+        if let Some(image) = data.image(format, placeholder)? {
+            out.push_str(&image);
+            rest = &after[end + 1..];
+            continue;
+        }
+        let value = data
+            .value(placeholder)
+            .ok_or_else(|| ValidationError::InvalidFormat {
+                field: "body".to_string(),
+                reason: format!("unknown placeholder {{{}}}", placeholder),
+            })?;
+        out.push_str(&format.escape(&value));
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+
+    Ok(out)
+}
+
+/// Checks a template before it is saved: it must render, and be framed
+/// as a complete label for its language.
+///
+/// ## Errors
+/// - `ValidationError::Required` for an empty name or body
+/// - `ValidationError::InvalidFormat` for bad placeholders or framing
+pub fn validate_template(name: &str, format: LabelFormat, body: &str) -> ValidationResult<()> {
+    if name.trim().is_empty() {
+        return Err(ValidationError::Required {
+            field: "name".to_string(),
+        });
+    }
+    if body.trim().is_empty() {
+        return Err(ValidationError::Required {
+            field: "body".to_string(),
+        });
+    }
+
+    let rendered = render_label(format, body, &LabelData::sample())?;
+    let framed = match format {
+        LabelFormat::Zpl => {
+            let trimmed = rendered.trim();
+            trimmed.starts_with("^XA") && trimmed.ends_with("^XZ")
+        }
+        // A form that never reaches a P command is never printed
+        LabelFormat::Epl => rendered.lines().any(|l| l.starts_with('P')),
+    };
+    if !framed {
+        let reason = match format {
+            LabelFormat::Zpl => "ZPL labels must start with ^XA and end with ^XZ",
+            LabelFormat::Epl => "EPL labels need a P (print) command",
+        };
+        return Err(ValidationError::InvalidFormat {
+            field: "body".to_string(),
+            reason: reason.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data() -> LabelData {
+        LabelData {
+            name: "Coca-Cola 330ml".to_string(),
+            sku: "COKE-330".to_string(),
+            barcode: None,
+            price_cents: 150,
+            was_price_cents: Some(175),
+            copies: 3,
+        }
+    }
+
+    #[test]
+    fn test_render_default_templates() {
+        let zpl = render_label(LabelFormat::Zpl, DEFAULT_ZPL_TEMPLATE, &data()).unwrap();
+        assert!(zpl.contains("^FDCoca-Cola 330ml^FS"));
+        assert!(zpl.contains("^FD$1.50^FS"));
+        assert!(zpl.contains("^FD$1.75^FS"));
+        // No barcode: the SKU is printed instead
+        assert!(zpl.contains("^FDCOKE-330^FS"));
+        assert!(zpl.contains("^PQ3"));
+
+        let epl = render_label(LabelFormat::Epl, DEFAULT_EPL_TEMPLATE, &data()).unwrap();
+        assert!(epl.contains("N,\"$1.50\""));
+        assert!(epl.contains("\nP3\n"));
+    }
+
+    #[test]
+    fn test_was_price_empty_without_change() {
+        let mut data = data();
+        data.was_price_cents = Some(150);
+        assert_eq!(
+            render_label(LabelFormat::Zpl, "[{was_price}]", &data).unwrap(),
+            "[]"
+        );
+        data.was_price_cents = None;
+        assert_eq!(
+            render_label(LabelFormat::Zpl, "[{was_price}]", &data).unwrap(),
+            "[]"
+        );
+    }
+
+    #[test]
+    fn test_values_are_escaped() {
+        let mut data = data();
+        data.name = "Evil ^XZ~JR \"quoted\" \\ name\n".to_string();
+
+        let zpl = render_label(LabelFormat::Zpl, "{name}", &data).unwrap();
+        assert_eq!(zpl, "Evil  XZ JR \"quoted\" \\ name");
+
+        let epl = render_label(LabelFormat::Epl, "{name}", &data).unwrap();
+        assert_eq!(epl, "Evil ^XZ~JR \\\"quoted\\\" \\\\ name");
+    }
+
+    #[test]
+    fn test_bad_placeholders_rejected() {
+        assert!(render_label(LabelFormat::Zpl, "{colour}", &data()).is_err());
+        assert!(render_label(LabelFormat::Zpl, "^XA{name", &data()).is_err());
+    }
+
+    #[test]
+    fn test_barcode_images() {
+        let mut ean = data();
+        ean.barcode = Some("4006381333931".to_string());
+
+        // EAN-13: 95 modules and 22 of quiet zone, 2 dots each, 50 high
+        let zpl = render_label(LabelFormat::Zpl, "^FO20,135{barcode_image}^FS", &ean).unwrap();
+        assert!(zpl.starts_with("^FO20,135^GFA,1500,1500,30,"));
+        assert!(zpl.ends_with("^FS"));
+
+        // Not an EAN: the SKU as Code 128
+        let zpl = render_label(LabelFormat::Zpl, "{barcode_image}", &data()).unwrap();
+        assert!(zpl.starts_with("^GFA,"));
+        assert!(render_label(LabelFormat::Zpl, "{barcode_qr}", &ean)
+            .unwrap()
+            .starts_with("^GFA,"));
+
+        assert!(render_label(LabelFormat::Epl, "{barcode_qr}", &ean).is_err());
+    }
+
+    #[test]
+    fn test_validate_template() {
+        assert!(validate_template("Shelf", LabelFormat::Zpl, DEFAULT_ZPL_TEMPLATE).is_ok());
+        assert!(validate_template("Shelf", LabelFormat::Epl, DEFAULT_EPL_TEMPLATE).is_ok());
+
+        assert!(validate_template("", LabelFormat::Zpl, DEFAULT_ZPL_TEMPLATE).is_err());
+        assert!(validate_template("Shelf", LabelFormat::Zpl, "^XA^FD{name}^FS").is_err());
+        assert!(
+            validate_template("Shelf", LabelFormat::Epl, "N\nA20,15,0,3,1,1,N,\"{name}\"").is_err()
+        );
+    }
+}
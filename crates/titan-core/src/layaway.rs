@@ -0,0 +1,439 @@
+//! # Layaway
+//!
+//! Types and pure rules for layaway orders: the customer pays a deposit, the
+//! goods are put aside, and further payments are taken until the balance is
+//! cleared and the goods are picked up.
+//!
+//! ## Layaway Lifecycle
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Layaway Lifecycle                                │
+//! │                                                                         │
+//! │  Cart + deposit ──► ACTIVE ──────────────────────────► COMPLETED        │
+//! │  (≥ min deposit)     │  ▲     pickup (balance = 0)     (sale recorded)  │
+//! │                      │  │                                               │
+//! │                      │  └── add payment (≤ balance due)                 │
+//! │                      │                                                  │
+//! │                      └──► CANCELLED                                     │
+//! │                           goods returned to stock,                      │
+//! │                           refund = paid - restocking fee                │
+//! │                                                                         │
+//! │  Stock is reserved (decremented) when the layaway is created, with a   │
+//! │  distinct inventory delta reason, so reserved goods are never sold to  │
+//! │  another customer. Pickup does NOT decrement stock a second time.      │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Restocking Fee
+//! ```text
+//! fee = max(total × restocking_fee_bps / 10000, min_restocking_fee)
+//!       capped at the amount paid (the store never asks for more money
+//!       on a cancellation)
+//!
+//! Example: $200.00 layaway, $80.00 paid, 10% fee
+//!   fee    = $20.00
+//!   refund = $80.00 - $20.00 = $60.00
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::{CoreError, ValidationError};
+use crate::types::PaymentMethod;
+use crate::validation::ValidationResult;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Default minimum deposit: 20% of the layaway total.
+pub const DEFAULT_MIN_DEPOSIT_BPS: u32 = 2000;
+
+/// Default restocking fee on cancellation: 10% of the layaway total.
+pub const DEFAULT_RESTOCKING_FEE_BPS: u32 = 1000;
+
+// =============================================================================
+// Layaway Status
+// =============================================================================
+
+/// The status of a layaway order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "lowercase"))]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum LayawayStatus {
+    /// Goods reserved, payments still being taken.
+    #[default]
+    Active,
+    /// Paid in full and picked up.
+    Completed,
+    /// Cancelled; goods returned to stock.
+    Cancelled,
+}
+
+// =============================================================================
+// Layaway
+// =============================================================================
+
+/// A layaway order header.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Layaway {
+    pub id: String,
+    pub tenant_id: String,
+    /// Human-readable number printed on the customer's slip.
+    pub layaway_number: String,
+    pub status: LayawayStatus,
+    /// Customer name (required - the goods are held for a person).
+    pub customer_name: String,
+    pub customer_phone: Option<String>,
+    pub subtotal_cents: i64,
+    pub tax_cents: i64,
+    pub total_cents: i64,
+    /// Net amount paid so far (payments minus any cancellation refund).
+    pub paid_cents: i64,
+    /// Fee retained on cancellation (0 unless cancelled).
+    pub restocking_fee_cents: i64,
+    /// Sale recorded at pickup.
+    pub sale_id: Option<String>,
+    pub user_id: String,
+    /// Terminal the layaway was created on.
+    pub device_id: String,
+    pub notes: Option<String>,
+    #[ts(as = "String")]
+    pub created_at: DateTime<Utc>,
+    #[ts(as = "String")]
+    pub updated_at: DateTime<Utc>,
+    /// When the layaway was completed or cancelled.
+    #[ts(as = "Option<String>")]
+    pub closed_at: Option<DateTime<Utc>>,
+    pub sync_version: i64,
+}
+
+impl Layaway {
+    /// Amount still owed before pickup.
+    #[inline]
+    pub fn balance_due_cents(&self) -> i64 {
+        (self.total_cents - self.paid_cents).max(0)
+    }
+
+    /// Checks that the layaway can still take payments, be picked up or be
+    /// cancelled.
+    ///
+    /// ## Errors
+    /// `CoreError::InvalidLayawayStatus` if it is completed or cancelled.
+    pub fn ensure_active(&self) -> Result<(), CoreError> {
+        if self.status == LayawayStatus::Active {
+            Ok(())
+        } else {
+            Err(CoreError::InvalidLayawayStatus {
+                layaway_id: self.id.clone(),
+                current_status: format!("{:?}", self.status),
+            })
+        }
+    }
+
+    /// Checks that the layaway can be picked up.
+    ///
+    /// ## Errors
+    /// - `CoreError::InvalidLayawayStatus` if it is not active
+    /// - `CoreError::InvalidPaymentAmount` if a balance is still due
+    pub fn ensure_ready_for_pickup(&self) -> Result<(), CoreError> {
+        self.ensure_active()?;
+
+        let due = self.balance_due_cents();
+        if due > 0 {
+            return Err(CoreError::InvalidPaymentAmount {
+                reason: format!("{} cents still due before pickup", due),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Layaway Item & Payment
+// =============================================================================
+
+/// A line on a layaway.
+/// Uses the snapshot pattern so the agreed price survives catalog changes.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LayawayItem {
+    pub id: String,
+    pub layaway_id: String,
+    pub product_id: String,
+    pub sku_snapshot: String,
+    pub name_snapshot: String,
+    pub unit_price_cents: i64,
+    pub quantity: i64,
+    pub line_total_cents: i64,
+    pub tax_cents: i64,
+    #[ts(as = "String")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Money taken (or refunded) against a layaway.
+///
+/// Refunds on cancellation are recorded as a negative amount so that the
+/// sum of a layaway's payments is always its net `paid_cents`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LayawayPayment {
+    pub id: String,
+    pub layaway_id: String,
+    pub method: PaymentMethod,
+    pub amount_cents: i64,
+    /// Terminal that took the payment (for till reconciliation).
+    pub device_id: String,
+    #[ts(as = "String")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// A layaway with its lines and payments, as stored in the sync outbox and
+/// relayed to other terminals.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LayawayDocument {
+    #[serde(flatten)]
+    pub layaway: Layaway,
+    pub items: Vec<LayawayItem>,
+    pub payments: Vec<LayawayPayment>,
+}
+
+// =============================================================================
+// Policy
+// =============================================================================
+
+/// Store-configurable layaway terms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LayawayPolicy {
+    /// Minimum deposit as a share of the total, in basis points.
+    pub min_deposit_bps: u32,
+    /// Restocking fee on cancellation as a share of the total, in basis points.
+    pub restocking_fee_bps: u32,
+    /// Flat minimum restocking fee in cents.
+    pub min_restocking_fee_cents: i64,
+}
+
+impl Default for LayawayPolicy {
+    fn default() -> Self {
+        LayawayPolicy {
+            min_deposit_bps: DEFAULT_MIN_DEPOSIT_BPS,
+            restocking_fee_bps: DEFAULT_RESTOCKING_FEE_BPS,
+            min_restocking_fee_cents: 0,
+        }
+    }
+}
+
+/// What happens to the money when a layaway is cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LayawaySettlement {
+    /// Retained by the store.
+    pub restocking_fee_cents: i64,
+    /// Returned to the customer.
+    pub refund_cents: i64,
+}
+
+impl LayawayPolicy {
+    /// Smallest deposit accepted for a layaway of `total_cents` (rounded up).
+    pub fn minimum_deposit_cents(&self, total_cents: i64) -> i64 {
+        let bps = self.min_deposit_bps as i64;
+        (total_cents * bps + 9_999) / 10_000
+    }
+
+    /// Validates the policy values.
+    ///
+    /// ## Rules
+    /// - Basis points must be between 0 and 10000
+    /// - Flat fee must not be negative
+    pub fn validate(&self) -> ValidationResult<()> {
+        for (field, bps) in [
+            ("min_deposit_bps", self.min_deposit_bps),
+            ("restocking_fee_bps", self.restocking_fee_bps),
+        ] {
+            if bps > 10_000 {
+                return Err(ValidationError::OutOfRange {
+                    field: field.to_string(),
+                    min: 0,
+                    max: 10_000,
+                });
+            }
+        }
+
+        if self.min_restocking_fee_cents < 0 {
+            return Err(ValidationError::OutOfRange {
+                field: "min_restocking_fee_cents".to_string(),
+                min: 0,
+                max: i64::MAX,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Validates the opening deposit.
+    ///
+    /// ## Rules
+    /// - At least [`minimum_deposit_cents`](Self::minimum_deposit_cents)
+    /// - No more than the total (a fully paid order is a normal sale)
+    pub fn validate_deposit(&self, total_cents: i64, deposit_cents: i64) -> ValidationResult<()> {
+        let min = self.minimum_deposit_cents(total_cents).max(1);
+
+        if deposit_cents < min || deposit_cents > total_cents {
+            return Err(ValidationError::OutOfRange {
+                field: "deposit".to_string(),
+                min,
+                max: total_cents,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Works out the fee and refund for cancelling `layaway`.
+    pub fn cancellation_settlement(&self, layaway: &Layaway) -> LayawaySettlement {
+        let percentage = layaway.total_cents * self.restocking_fee_bps as i64 / 10_000;
+        let fee = percentage
+            .max(self.min_restocking_fee_cents)
+            .min(layaway.paid_cents)
+            .max(0);
+
+        LayawaySettlement {
+            restocking_fee_cents: fee,
+            refund_cents: layaway.paid_cents - fee,
+        }
+    }
+}
+
+/// Validates a follow-up payment against the balance due.
+///
+/// ## Rules
+/// - Must be positive
+/// - Must not exceed the balance (no change is given on layaway payments)
+pub fn validate_layaway_payment(amount_cents: i64, balance_due_cents: i64) -> ValidationResult<()> {
+    if amount_cents <= 0 {
+        return Err(ValidationError::MustBePositive {
+            field: "payment amount".to_string(),
+        });
+    }
+
+    if amount_cents > balance_due_cents {
+        return Err(ValidationError::OutOfRange {
+            field: "payment amount".to_string(),
+            min: 1,
+            max: balance_due_cents,
+        });
+    }
+
+    Ok(())
+}
+
+// =============================================================================
+// Unit Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layaway(total: i64, paid: i64, status: LayawayStatus) -> Layaway {
+        let now = Utc::now();
+        Layaway {
+            id: "l-1".to_string(),
+            tenant_id: "t".to_string(),
+            layaway_number: "L-0001".to_string(),
+            status,
+            customer_name: "Jane".to_string(),
+            customer_phone: None,
+            subtotal_cents: total,
+            tax_cents: 0,
+            total_cents: total,
+            paid_cents: paid,
+            restocking_fee_cents: 0,
+            sale_id: None,
+            user_id: "u".to_string(),
+            device_id: "d".to_string(),
+            notes: None,
+            created_at: now,
+            updated_at: now,
+            closed_at: None,
+            sync_version: 1,
+        }
+    }
+
+    #[test]
+    fn test_deposit_rules() {
+        let policy = LayawayPolicy::default();
+
+        // 20% of $99.99 = $19.998 → rounded up to $20.00
+        assert_eq!(policy.minimum_deposit_cents(9999), 2000);
+        assert!(policy.validate_deposit(9999, 2000).is_ok());
+        assert!(policy.validate_deposit(9999, 1999).is_err());
+        assert!(policy.validate_deposit(9999, 10_000).is_err());
+
+        // A zero-percent policy still requires some deposit
+        let no_min = LayawayPolicy {
+            min_deposit_bps: 0,
+            ..policy
+        };
+        assert!(no_min.validate_deposit(9999, 0).is_err());
+        assert!(no_min.validate_deposit(9999, 1).is_ok());
+    }
+
+    #[test]
+    fn test_payment_and_pickup() {
+        let l = layaway(20_000, 8_000, LayawayStatus::Active);
+        assert_eq!(l.balance_due_cents(), 12_000);
+        assert!(validate_layaway_payment(12_000, l.balance_due_cents()).is_ok());
+        assert!(validate_layaway_payment(12_001, l.balance_due_cents()).is_err());
+        assert!(validate_layaway_payment(0, l.balance_due_cents()).is_err());
+        assert!(l.ensure_ready_for_pickup().is_err());
+
+        assert!(layaway(20_000, 20_000, LayawayStatus::Active)
+            .ensure_ready_for_pickup()
+            .is_ok());
+        assert!(layaway(20_000, 20_000, LayawayStatus::Completed)
+            .ensure_ready_for_pickup()
+            .is_err());
+    }
+
+    #[test]
+    fn test_cancellation_settlement() {
+        let policy = LayawayPolicy::default();
+
+        let s = policy.cancellation_settlement(&layaway(20_000, 8_000, LayawayStatus::Active));
+        assert_eq!(s.restocking_fee_cents, 2_000);
+        assert_eq!(s.refund_cents, 6_000);
+
+        // Fee never exceeds what the customer paid
+        let s = policy.cancellation_settlement(&layaway(20_000, 1_500, LayawayStatus::Active));
+        assert_eq!(s.restocking_fee_cents, 1_500);
+        assert_eq!(s.refund_cents, 0);
+
+        // Flat minimum applies to small orders
+        let flat = LayawayPolicy {
+            min_restocking_fee_cents: 500,
+            ..policy
+        };
+        let s = flat.cancellation_settlement(&layaway(2_000, 1_000, LayawayStatus::Active));
+        assert_eq!(s.restocking_fee_cents, 500);
+        assert_eq!(s.refund_cents, 500);
+    }
+
+    #[test]
+    fn test_policy_validation() {
+        assert!(LayawayPolicy::default().validate().is_ok());
+        assert!(LayawayPolicy {
+            restocking_fee_bps: 10_001,
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -41,8 +41,51 @@
 //!
 //! - [`types`] - Domain types (Product, Sale, Payment, etc.)
 //! - [`money`] - Money type with integer arithmetic (no floating point!)
-//! - [`error`] - Domain error types
+//! - [`error`] - Domain error types and stable error codes
 //! - [`validation`] - Business rule validation
+//! - [`till`] - Till sessions, blind close, and cash variance rules
+//! - [`quote`] - Customer quotes/estimates with expiry and conversion rules
+//! - [`denomination`] - Notes/coins per currency, drawer totals, change breakdown
+//! - [`layaway`] - Layaway orders: deposits, payments, restocking fee
+//! - [`tracking`] - Serial/lot number capture for regulated products
+//! - [`age`] - Minimum purchase age and customer age verification
+//! - [`transfer`] - Stock transfers between stores of a tenant
+//! - [`store_credit`] - Store credit ledger, returnless refunds, credit tender
+//! - [`coupon`] - Coupon rules, cart discounts and redemptions synced across stores
+//! - [`loyalty`] - Loyalty points, tiers, rewards and reward redemption as a tender
+//! - [`fiscal`] - Fiscal receipt signing adapter contract and signed payload
+//! - [`einvoice`] - Business customers and UBL 2.1 e-invoice rendering
+//! - [`patch`] - Dirty-field tracking and field-level patches for sync
+//! - [`sync_payload`] - Typed, versioned schemas for outbox payloads
+//! - [`sync_history`] - Connection history periods and daily uptime
+//! - [`schedule`] - Cron schedules and run records for background jobs
+//! - [`page`] - Keyset cursor pagination for list and search results
+//! - [`tombstone`] - Soft delete/restore and the tombstones that sync them
+//! - [`entity_event`] - Change notifications published after committed writes
+//! - [`erasure`] - Customer data erasure requests and the erasure log
+//! - [`label`] - Shelf label queue and ZPL/EPL label templates
+//! - [`receipt`] - Receipt variants (gift, reprint, refund) and their text rendering
+//! - [`receipt_campaign`] - Footer campaigns under the receipt: surveys, coupons, messages
+//! - [`feature`] - Remote feature flags and their local cache
+//! - [`tax_report`] - Sales tax report by rate and jurisdiction
+//! - [`attribute`] - Product attributes and tags, filters and promotions
+//! - [`variant`] - Product styles, variants and matrix display
+//! - [`bundle`] - Bundle products and their bill of materials
+//! - [`pack`] - Case packs, unit conversion and stock receiving
+//! - [`waste`] - Shrinkage and waste write-offs and the daily waste report
+//! - [`supplier`] - Suppliers, product costs at receiving and margins
+//! - [`department`] - Department keys, open-price and price-embedded sales, tax groups
+//! - [`margin_guard`] - Margin floor for manual prices, manager bypass and its audit
+//! - [`cart_totals`] - Running cart totals maintained line by line
+//! - [`crash_report`] - Crash reports and the log tail they carry
+//! - [`station`] - Register stations: station numbers and receipt prefixes
+//! - [`day_end`] - Day-end job steps, Z close reports and business dates
+//! - [`cash_drawer`] - Cash drawer hardware contract, station drawer setting, open audit
+//! - [`scale`] - Checkout scale contract, weight frames, tare and minimum-weight rules
+//! - [`line_display`] - Customer pole display frames, command sets, event-driven content
+//! - [`barcode`] - Code 128, EAN-13 and QR symbols as bitmaps, ESC/POS and ZPL images
+//! - [`override_token`] - Signed, short-lived manager override tokens checked offline
+//! - [`pii`] - Envelope encryption of customer emails and phones, shared with the cloud
 //!
 //! ## Design Principles
 //!
@@ -72,10 +115,54 @@
 // Module Declarations
 // =============================================================================
 
+pub mod age;
+pub mod attribute;
+pub mod barcode;
+pub mod bundle;
+pub mod cart_totals;
+pub mod cash_drawer;
+pub mod coupon;
+pub mod crash_report;
+pub mod day_end;
+pub mod denomination;
+pub mod department;
+pub mod einvoice;
+pub mod entity_event;
+pub mod erasure;
 pub mod error;
+pub mod feature;
+pub mod fiscal;
+pub mod label;
+pub mod layaway;
+pub mod line_display;
+pub mod loyalty;
+pub mod margin_guard;
 pub mod money;
+pub mod override_token;
+pub mod pack;
+pub mod page;
+pub mod patch;
+pub mod pii;
+pub mod quote;
+pub mod receipt;
+pub mod receipt_campaign;
+pub mod retention;
+pub mod scale;
+pub mod schedule;
+pub mod station;
+pub mod store_credit;
+pub mod supplier;
+pub mod sync_history;
+pub mod sync_payload;
+pub mod tax_report;
+pub mod till;
+pub mod tombstone;
+pub mod tracking;
+pub mod transfer;
 pub mod types;
 pub mod validation;
+pub mod variant;
+pub mod waste;
 
 // =============================================================================
 // Re-exports for Convenience
@@ -83,9 +170,82 @@ pub mod validation;
 // These allow users to do `use titan_core::Money` instead of
 // `use titan_core::money::Money`
 
-pub use error::{CoreError, ValidationError};
+pub use age::{AgeVerification, AgeVerificationMethod};
+pub use attribute::{AttributeFilter, AttributePromotion, ProductAttribute, ProductAttributes};
+pub use barcode::{Barcode, Bitmap, Symbology};
+pub use bundle::{BundleComponent, ProductBundle};
+pub use cart_totals::{LineAmounts, RunningTotals};
+pub use cash_drawer::{CashDrawer, DrawerConnection, DrawerOpen, KickPin};
+pub use coupon::{Coupon, CouponDocument, CouponKind, CouponRedemption};
+pub use crash_report::{CrashKind, CrashReport, LogTail};
+pub use day_end::{
+    DayEndReport, DayEndStep, DayEndStepResult, PaymentTotal, StepOutcome, ZReport,
+};
+pub use denomination::{ChangeBreakdown, CurrencyDenominations, Denomination, DenominationKind};
+pub use department::{
+    DepartmentConfig, DepartmentKey, DepartmentLine, DepartmentSalesReport, DepartmentSalesRow, TaxGroup,
+};
+pub use einvoice::{BusinessCustomer, Invoice, InvoiceLine, InvoiceParty, TaxBreakdown};
+pub use entity_event::{EntityEvent, ProductChange};
+pub use erasure::{ErasureCounts, ErasureOrigin, ErasureRecord, ErasureRequest, ErasureSubject};
+pub use error::{CoreError, ErrorCode, PayloadError, ValidationError};
+pub use feature::FeatureFlags;
+pub use fiscal::{
+    FiscalAdapter, FiscalChain, FiscalLine, FiscalPayment, FiscalReceipt, FiscalSignature, NoopFiscalAdapter,
+};
+pub use label::{
+    LabelData, LabelFormat, LabelReason, LabelStatus, LabelTemplate, QueuedLabel,
+};
+pub use layaway::{
+    Layaway, LayawayDocument, LayawayItem, LayawayPayment, LayawayPolicy, LayawaySettlement,
+    LayawayStatus,
+};
+pub use line_display::{DisplayConnection, DisplayFrame, DisplayProtocol, LineDisplay};
+pub use loyalty::{
+    LoyaltyAccount, LoyaltyAccrual, LoyaltyDocument, LoyaltyEntry, LoyaltyEntryKind, LoyaltyProgram,
+    LoyaltyTier,
+};
+pub use margin_guard::{
+    GuardrailAction, ManagerBypass, MarginCheck, MarginGuardrail, MarginOverride, MarginVerdict,
+    PriceChangeKind,
+};
 pub use money::Money;
+pub use override_token::{
+    ManagerOverride, OverrideAction, OverrideClaims, OverrideKey, OverrideRequest, OverrideSigner,
+};
+pub use pack::{
+    PackQuantity, ProductPack, ProductPacks, ReceivingLine, StockReceipt, StockReceiptLine,
+};
+pub use page::{Page, PageRequest};
+pub use patch::{EntityPatch, MergeOutcome, Tracked};
+pub use quote::{Quote, QuoteDocument, QuoteItem, QuoteStatus};
+pub use receipt::{ReceiptData, ReceiptLine, ReceiptVariant};
+pub use receipt_campaign::{CampaignFooter, IssuedCoupon, ReceiptCampaign};
+pub use retention::{PrunedSale, RetentionPolicy};
+pub use scale::{Scale, ScaleConnection, ScaleStatus, WeighRules, WeightReading};
+pub use schedule::{CronSchedule, JobRun, JobRunStatus, ScheduledJob};
+pub use station::StationConfig;
+pub use store_credit::{
+    RefundDestination, SaleRefund, StoreCreditAccount, StoreCreditDocument, StoreCreditEntry,
+    StoreCreditEntryKind,
+};
+pub use supplier::{
+    MarginStats, ProductCost, ProductMargin, Supplier, SupplierDocument, SupplierItem,
+};
+pub use sync_history::{daily_uptime, DailyUptime, SyncStatusPeriod};
+pub use sync_payload::{sensitive_fields, SyncPayload, PAYLOAD_SCHEMA_VERSION};
+pub use tax_report::{TaxRateSummary, TaxReport};
+pub use till::{DenominationCount, TillSession, TillSessionStatus, VarianceException, VariancePolicy};
+pub use tombstone::{Tombstone, TombstoneAction};
+pub use tracking::{ItemTracking, SaleItemTracking, TrackedSaleLine};
+pub use transfer::{
+    StoreTransfer, StoreTransferDocument, StoreTransferItem, TransferDirection, TransferStatus,
+};
 pub use types::*;
+pub use variant::{
+    ProductStyle, ProductStyleDocument, SearchResult, Variant, VariantGroup, VariantMatrix,
+};
+pub use waste::{WasteReason, WasteReasonSummary, WasteRecord, WasteReport};
 
 // =============================================================================
 // Crate-Level Constants
@@ -0,0 +1,341 @@
+//! # Line Displays
+//!
+//! The customer-facing pole display: two lines of twenty characters on a
+//! serial or USB VFD, showing each item as it is scanned and the total to
+//! pay at tender. What it shows is driven by [`EntityEvent`]s, so no cart
+//! or sale command has to know a display exists.
+//!
+//! ## Display Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                          Line Display                                   │
+//! │                                                                         │
+//! │  StationConfig.line_display (TITAN_LINE_DISPLAY)                        │
+//! │       │ DisplayConnection::parse                                        │
+//! │       ▼                                                                 │
+//! │  "epson:<port>"  ──► ESC/POS display commands (most 2x20 VFDs)          │
+//! │  "cd5220:<port>" ──► CD5220 command set                                 │
+//! │  "none" / unset  ──► no display                                         │
+//! │                                                                         │
+//! │  EntityEvent            frame_for_event        ┌────────────────────┐   │
+//! │  CartLineAdded     ──►  item and price    ──►  │Loose Produce   1.36│   │
+//! │                                                │TOTAL          12.40│   │
+//! │  SaleCreated       ──►  total to pay      ──►  │TOTAL DUE           │   │
+//! │                                                │              $12.40│   │
+//! │  SaleFinalized     ──►  thank you         ──►  │     THANK YOU      │   │
+//! │                                                │TOTAL         $12.40│   │
+//! │  anything else     ──►  None (unchanged)       └────────────────────┘   │
+//! │                                                                         │
+//! │  DisplayFrame::encode(protocol) ──► bytes ──► dyn LineDisplay::show     │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Pole displays only have ASCII, so anything else is shown as `?`.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::entity_event::EntityEvent;
+use crate::error::{CoreResult, ValidationError};
+use crate::money::Money;
+use crate::validation::ValidationResult;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Characters per display line.
+pub const DISPLAY_COLUMNS: usize = 20;
+
+// =============================================================================
+// Connection
+// =============================================================================
+
+/// Command set a display understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum DisplayProtocol {
+    /// Epson ESC/POS customer display commands.
+    Epson,
+    /// CD5220 command set.
+    Cd5220,
+}
+
+/// How a station's line display is connected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayConnection {
+    pub protocol: DisplayProtocol,
+    /// Serial port or device path.
+    pub port: String,
+}
+
+impl DisplayConnection {
+    /// Reads the station's display setting (see the module docs).
+    ///
+    /// Returns `None` when the station has no display.
+    ///
+    /// ## Errors
+    /// `ValidationError::InvalidFormat` for an unknown command set or a
+    /// setting without its port.
+    pub fn parse(setting: &str) -> ValidationResult<Option<Self>> {
+        let invalid = |reason: &str| ValidationError::InvalidFormat {
+            field: "line_display".to_string(),
+            reason: reason.to_string(),
+        };
+        let setting = setting.trim();
+        let (kind, port) = match setting.split_once(':') {
+            Some((kind, port)) => (kind, port.trim()),
+            None => (setting, ""),
+        };
+
+        let protocol = match kind.to_lowercase().as_str() {
+            "" | "none" => return Ok(None),
+            "epson" => DisplayProtocol::Epson,
+            "cd5220" => DisplayProtocol::Cd5220,
+            _ => return Err(invalid("expected none, epson:<port> or cd5220:<port>")),
+        };
+        if port.is_empty() {
+            return Err(invalid("the display needs its port, e.g. epson:COM5"));
+        }
+        Ok(Some(DisplayConnection {
+            protocol,
+            port: port.to_string(),
+        }))
+    }
+}
+
+// =============================================================================
+// Frames
+// =============================================================================
+
+/// What the display shows: two lines, each exactly [`DISPLAY_COLUMNS`]
+/// ASCII characters.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DisplayFrame {
+    pub top: String,
+    pub bottom: String,
+}
+
+impl DisplayFrame {
+    /// A frame of two free lines, cut or padded to width.
+    pub fn new(top: &str, bottom: &str) -> Self {
+        DisplayFrame {
+            top: fit(top),
+            bottom: fit(bottom),
+        }
+    }
+
+    /// An item and its price over the running total.
+    pub fn item(name: &str, price_cents: i64, cart_total_cents: i64) -> Self {
+        DisplayFrame {
+            top: row(name, &amount(price_cents)),
+            bottom: row("TOTAL", &amount(cart_total_cents)),
+        }
+    }
+
+    /// The total to pay, shown at tender.
+    pub fn total_due(total_cents: i64) -> Self {
+        DisplayFrame {
+            top: fit("TOTAL DUE"),
+            bottom: row("", &Money::from_cents(total_cents).to_string()),
+        }
+    }
+
+    /// Thanks once the sale is paid.
+    pub fn thank_you(total_cents: i64) -> Self {
+        DisplayFrame {
+            top: centered("THANK YOU"),
+            bottom: row("TOTAL", &Money::from_cents(total_cents).to_string()),
+        }
+    }
+
+    /// Bytes that clear the display and show the frame.
+    pub fn encode(&self, protocol: DisplayProtocol) -> Vec<u8> {
+        let mut out = Vec::with_capacity(2 * DISPLAY_COLUMNS + 8);
+        match protocol {
+            DisplayProtocol::Epson => {
+                // Clear, home, then line 2 by cursor position (US $ x y)
+                out.push(0x0C);
+                out.extend_from_slice(self.top.as_bytes());
+                out.extend_from_slice(&[0x1F, b'$', 1, 2]);
+                out.extend_from_slice(self.bottom.as_bytes());
+            }
+            DisplayProtocol::Cd5220 => {
+                // ESC Q A <upper> CR, ESC Q B <lower> CR
+                out.extend_from_slice(&[0x1B, b'Q', b'A']);
+                out.extend_from_slice(self.top.as_bytes());
+                out.push(b'\r');
+                out.extend_from_slice(&[0x1B, b'Q', b'B']);
+                out.extend_from_slice(self.bottom.as_bytes());
+                out.push(b'\r');
+            }
+        }
+        out
+    }
+}
+
+/// What the display should change to for `event`, if anything.
+pub fn frame_for_event(event: &EntityEvent) -> Option<DisplayFrame> {
+    match event {
+        EntityEvent::CartLineAdded {
+            name,
+            unit_price_cents,
+            cart_total_cents,
+            ..
+        } => Some(DisplayFrame::item(
+            name,
+            *unit_price_cents,
+            *cart_total_cents,
+        )),
+        EntityEvent::SaleCreated { total_cents, .. } => Some(DisplayFrame::total_due(*total_cents)),
+        EntityEvent::SaleFinalized { total_cents, .. } => {
+            Some(DisplayFrame::thank_you(*total_cents))
+        }
+        EntityEvent::ProductChanged { .. } | EntityEvent::StockChanged { .. } => None,
+    }
+}
+
+/// `12.40` (no currency sign, which costs a column next to a name).
+fn amount(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    format!("{}{}.{:02}", sign, cents.abs() / 100, cents.abs() % 100)
+}
+
+/// ASCII only, no control characters.
+fn ascii(text: &str) -> impl Iterator<Item = char> + '_ {
+    text.chars()
+        .filter(|c| !c.is_control())
+        .map(|c| if c.is_ascii() { c } else { '?' })
+}
+
+fn fit(text: &str) -> String {
+    let text: String = ascii(text).take(DISPLAY_COLUMNS).collect();
+    format!("{:<width$}", text, width = DISPLAY_COLUMNS)
+}
+
+fn centered(text: &str) -> String {
+    let text: String = ascii(text).take(DISPLAY_COLUMNS).collect();
+    let pad = (DISPLAY_COLUMNS - text.len()) / 2;
+    fit(&format!("{}{}", " ".repeat(pad), text))
+}
+
+/// `left` and `right` on one line, `right` flush right; `left` is cut to
+/// make room.
+fn row(left: &str, right: &str) -> String {
+    let right: String = ascii(right).take(DISPLAY_COLUMNS).collect();
+    let room = DISPLAY_COLUMNS.saturating_sub(right.len() + 1);
+    let left: String = ascii(left).take(room).collect();
+    format!(
+        "{}{}{}",
+        left,
+        " ".repeat(DISPLAY_COLUMNS - left.len() - right.len()),
+        right
+    )
+}
+
+// =============================================================================
+// Driver Trait
+// =============================================================================
+
+/// A line display driver.
+///
+/// Implementations live with the I/O they need (serial ports).
+///
+/// ## Errors
+/// `CoreError::DeviceFailed` if the frame could not be sent.
+pub trait LineDisplay: Send + Sync {
+    /// How the display is connected.
+    fn connection(&self) -> &DisplayConnection;
+
+    /// Replaces what the display shows.
+    fn show(&self, frame: &DisplayFrame) -> CoreResult<()>;
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_display_settings() {
+        assert_eq!(DisplayConnection::parse("").unwrap(), None);
+        assert_eq!(DisplayConnection::parse("none").unwrap(), None);
+
+        let display = DisplayConnection::parse("CD5220: /dev/ttyUSB1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(display.protocol, DisplayProtocol::Cd5220);
+        assert_eq!(display.port, "/dev/ttyUSB1");
+
+        assert!(DisplayConnection::parse("epson").is_err());
+        assert!(DisplayConnection::parse("vfd:COM5").is_err());
+    }
+
+    #[test]
+    fn test_frames_fit_the_display() {
+        let frame = DisplayFrame::item("Organic Bananas Fairtrade 1kg", 199, 1240);
+        assert_eq!(frame.top, "Organic Bananas 1.99");
+        assert_eq!(frame.bottom, "TOTAL          12.40");
+
+        let frame = DisplayFrame::total_due(1240);
+        assert_eq!(frame.top, "TOTAL DUE           ");
+        assert_eq!(frame.bottom, "              $12.40");
+
+        let frame = DisplayFrame::new("Crème brûlée", "");
+        assert_eq!(frame.top, "Cr?me br?l?e        ");
+        assert_eq!(frame.top.len(), DISPLAY_COLUMNS);
+        assert_eq!(frame.bottom.len(), DISPLAY_COLUMNS);
+
+        assert_eq!(DisplayFrame::thank_you(500).top, "     THANK YOU      ");
+    }
+
+    #[test]
+    fn test_encode() {
+        let frame = DisplayFrame::new("A", "B");
+        let epson = frame.encode(DisplayProtocol::Epson);
+        assert_eq!(epson[0], 0x0C);
+        assert_eq!(&epson[21..25], &[0x1F, b'$', 1, 2]);
+        assert_eq!(epson.len(), 1 + 20 + 4 + 20);
+
+        let cd5220 = frame.encode(DisplayProtocol::Cd5220);
+        assert_eq!(&cd5220[..4], b"\x1bQAA");
+        assert_eq!(cd5220[23], b'\r');
+        assert_eq!(&cd5220[24..28], b"\x1bQBB");
+    }
+
+    #[test]
+    fn test_frames_for_events() {
+        let added = EntityEvent::CartLineAdded {
+            product_id: "p-1".to_string(),
+            name: "Coffee".to_string(),
+            unit_price_cents: 350,
+            quantity: 1,
+            cart_total_cents: 379,
+        };
+        assert_eq!(
+            frame_for_event(&added),
+            Some(DisplayFrame::item("Coffee", 350, 379))
+        );
+
+        let created = EntityEvent::SaleCreated {
+            sale_id: "s-1".to_string(),
+            total_cents: 379,
+        };
+        assert_eq!(
+            frame_for_event(&created),
+            Some(DisplayFrame::total_due(379))
+        );
+
+        let stock = EntityEvent::StockChanged {
+            product_id: "p-1".to_string(),
+            delta: -1,
+            stock: None,
+        };
+        assert_eq!(frame_for_event(&stock), None);
+    }
+}
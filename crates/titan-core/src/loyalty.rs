@@ -0,0 +1,556 @@
+//! # Loyalty
+//!
+//! Types and pure rules for loyalty: members earn points on what they
+//! spend, move up tiers as their lifetime points grow, and turn points
+//! into rewards they can spend as a payment tender.
+//!
+//! ## Ledger Model
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                          Loyalty Ledger                                 │
+//! │                                                                         │
+//! │  LoyaltyAccount (member number printed on the card)                     │
+//! │        │                                 points    rewards              │
+//! │        ├── Earn    (sale R-1, store A)     +320                         │
+//! │        ├── Earn    (sale R-4, store B)     +260                         │
+//! │        ├── Reward  (reward #1)             -500     +500                │
+//! │        └── Redeem  (tender on sale R-9)             -300                │
+//! │                                                                         │
+//! │  points balance  = Σ points  = 80                                       │
+//! │  lifetime points = Σ earned  = 580  ──► tier                            │
+//! │  rewards balance = Σ rewards = 200 cents                                │
+//! │                                                                         │
+//! │  Entries are append-only and merge by ID wherever copies meet, like     │
+//! │  store credit (see [`crate::store_credit`]).                            │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Issuing Rewards
+//! Whenever the points balance reaches the program's `reward_points`, the
+//! terminal that recorded the sale converts them into a reward. The n-th
+//! reward of an account always has the ID `{account_id}:reward:{n}`, so two
+//! terminals that issue it from the same points while offline write the
+//! same entry, and it is counted once when the copies merge. Earn entries
+//! are keyed by sale the same way, so a sale never earns twice.
+//!
+//! ## Spending Rewards
+//! Earning and issuing only ever add to a member's rewards; spending is
+//! what must not happen twice. A redemption is written only after the
+//! rewards balance has been checked, in one transaction, against the
+//! ledger every store writes through: the cloud's when the hub has cloud
+//! settings, the hub's otherwise, the terminal's own when it runs
+//! standalone.
+//!
+//! Accounts carry only the member number, no customer details, so there is
+//! nothing on them for a customer erasure to anonymize.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::{CoreError, CoreResult, ValidationError};
+use crate::validation::ValidationResult;
+
+/// Default points earned per whole currency unit spent.
+pub const DEFAULT_POINTS_PER_DOLLAR: i64 = 1;
+
+/// Default points converted into one reward.
+pub const DEFAULT_REWARD_POINTS: i64 = 500;
+
+/// Default value of one reward, in cents.
+pub const DEFAULT_REWARD_VALUE_CENTS: i64 = 500;
+
+/// Earn rate of a tier that earns the base points, in basis points.
+pub const BASE_EARN_BPS: u32 = 10_000;
+
+// =============================================================================
+// Entry Kind
+// =============================================================================
+
+/// The kind of a loyalty ledger entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "snake_case"))]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum LoyaltyEntryKind {
+    /// Points earned on a sale (positive points).
+    Earn,
+    /// Points converted into a reward (negative points, positive rewards).
+    Reward,
+    /// Rewards spent as a tender (negative rewards).
+    Redeem,
+}
+
+impl LoyaltyEntryKind {
+    /// Name as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoyaltyEntryKind::Earn => "earn",
+            LoyaltyEntryKind::Reward => "reward",
+            LoyaltyEntryKind::Redeem => "redeem",
+        }
+    }
+
+    /// Parses a name as stored in the database (any case).
+    pub fn parse(s: &str) -> Option<LoyaltyEntryKind> {
+        match s.to_lowercase().as_str() {
+            "earn" => Some(LoyaltyEntryKind::Earn),
+            "reward" => Some(LoyaltyEntryKind::Reward),
+            "redeem" => Some(LoyaltyEntryKind::Redeem),
+            _ => None,
+        }
+    }
+}
+
+// =============================================================================
+// Account & Entries
+// =============================================================================
+
+/// A loyalty member's account.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LoyaltyAccount {
+    pub id: String,
+    pub tenant_id: String,
+    /// Number printed on the member's card; what the cashier scans or types.
+    pub member_number: String,
+    #[ts(as = "String")]
+    pub created_at: DateTime<Utc>,
+    #[ts(as = "String")]
+    pub updated_at: DateTime<Utc>,
+    pub sync_version: i64,
+}
+
+/// A single movement on a loyalty account.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LoyaltyEntry {
+    pub id: String,
+    pub account_id: String,
+    pub kind: LoyaltyEntryKind,
+    /// Signed: positive when earned, negative when converted to a reward.
+    #[ts(type = "number")]
+    pub points: i64,
+    /// Signed: positive when a reward is issued, negative when spent.
+    #[ts(type = "number")]
+    pub reward_cents: i64,
+    /// Sale the points were earned or the reward spent on.
+    pub sale_id: Option<String>,
+    pub device_id: String,
+    #[ts(as = "String")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// An account with its full ledger, as synced between terminals and stores.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LoyaltyDocument {
+    #[serde(flatten)]
+    pub account: LoyaltyAccount,
+    pub entries: Vec<LoyaltyEntry>,
+}
+
+impl LoyaltyDocument {
+    /// Points not yet converted into rewards.
+    pub fn points_balance(&self) -> i64 {
+        self.entries.iter().map(|e| e.points).sum()
+    }
+
+    /// Every point ever earned; decides the member's tier.
+    pub fn lifetime_points(&self) -> i64 {
+        self.entries
+            .iter()
+            .filter(|e| e.kind == LoyaltyEntryKind::Earn)
+            .map(|e| e.points)
+            .sum()
+    }
+
+    /// Rewards issued and not yet spent, in cents.
+    pub fn reward_balance_cents(&self) -> i64 {
+        self.entries.iter().map(|e| e.reward_cents).sum()
+    }
+
+    /// Number of rewards issued so far.
+    pub fn rewards_issued(&self) -> i64 {
+        self.entries
+            .iter()
+            .filter(|e| e.kind == LoyaltyEntryKind::Reward)
+            .count() as i64
+    }
+}
+
+// =============================================================================
+// Program
+// =============================================================================
+
+/// A loyalty tier, reached once a member's lifetime points get to
+/// `min_points`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LoyaltyTier {
+    pub name: String,
+    #[ts(type = "number")]
+    pub min_points: i64,
+    /// Points earned relative to the base rate, in basis points
+    /// (12500 = 1.25 times the base points).
+    pub earn_bps: u32,
+}
+
+/// Store-configurable loyalty rules.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LoyaltyProgram {
+    /// Base points per whole currency unit spent.
+    #[ts(type = "number")]
+    pub points_per_dollar: i64,
+    /// Points converted into one reward.
+    #[ts(type = "number")]
+    pub reward_points: i64,
+    /// Value of one reward, in cents.
+    #[ts(type = "number")]
+    pub reward_value_cents: i64,
+    /// Tiers by ascending `min_points`; the first starts at 0.
+    pub tiers: Vec<LoyaltyTier>,
+}
+
+impl Default for LoyaltyProgram {
+    /// 1 point per dollar, $5 for every 500 points, and three tiers:
+    /// Member, Silver from 1000 lifetime points (1.25x) and Gold from
+    /// 5000 (1.5x).
+    fn default() -> Self {
+        let tier = |name: &str, min_points, earn_bps| LoyaltyTier {
+            name: name.to_string(),
+            min_points,
+            earn_bps,
+        };
+        LoyaltyProgram {
+            points_per_dollar: DEFAULT_POINTS_PER_DOLLAR,
+            reward_points: DEFAULT_REWARD_POINTS,
+            reward_value_cents: DEFAULT_REWARD_VALUE_CENTS,
+            tiers: vec![
+                tier("Member", 0, BASE_EARN_BPS),
+                tier("Silver", 1_000, 12_500),
+                tier("Gold", 5_000, 15_000),
+            ],
+        }
+    }
+}
+
+/// What a sale added to a member's account.
+#[derive(Debug, Clone)]
+pub struct LoyaltyAccrual {
+    /// The earn entry, then any rewards it completed.
+    pub entries: Vec<LoyaltyEntry>,
+    /// Points earned on the sale.
+    pub points: i64,
+    /// Value of the rewards issued, in cents.
+    pub rewards_cents: i64,
+}
+
+impl LoyaltyProgram {
+    /// The tier for `lifetime_points` (the highest one reached).
+    pub fn tier(&self, lifetime_points: i64) -> Option<&LoyaltyTier> {
+        self.tiers
+            .iter()
+            .rev()
+            .find(|t| lifetime_points >= t.min_points)
+    }
+
+    /// The next tier up from `lifetime_points`, if any.
+    pub fn next_tier(&self, lifetime_points: i64) -> Option<&LoyaltyTier> {
+        self.tiers.iter().find(|t| t.min_points > lifetime_points)
+    }
+
+    /// Points earned on `spent_cents` at the tier for `lifetime_points`
+    /// (whole currency units only, rounded down).
+    pub fn points_for(&self, spent_cents: i64, lifetime_points: i64) -> i64 {
+        let base = spent_cents.max(0) / 100 * self.points_per_dollar;
+        let earn_bps = self
+            .tier(lifetime_points)
+            .map(|t| t.earn_bps)
+            .unwrap_or(BASE_EARN_BPS);
+        (base as i128 * earn_bps as i128 / BASE_EARN_BPS as i128) as i64
+    }
+
+    /// Works out the entries a sale adds to `doc`: points for
+    /// `spent_cents` at the member's current tier, then a reward for every
+    /// `reward_points` the balance then holds.
+    ///
+    /// Entry IDs are derived from the account, so running this again for
+    /// the same sale, or on another terminal, writes the same entries.
+    pub fn accrue(
+        &self,
+        doc: &LoyaltyDocument,
+        sale_id: &str,
+        spent_cents: i64,
+        device_id: &str,
+        now: DateTime<Utc>,
+    ) -> LoyaltyAccrual {
+        let account_id = &doc.account.id;
+        let points = self.points_for(spent_cents, doc.lifetime_points());
+        let entry = |id: String, kind, points, reward_cents| LoyaltyEntry {
+            id,
+            account_id: account_id.clone(),
+            kind,
+            points,
+            reward_cents,
+            sale_id: Some(sale_id.to_string()),
+            device_id: device_id.to_string(),
+            created_at: now,
+        };
+
+        let mut entries = Vec::new();
+        if points > 0 {
+            entries.push(entry(
+                earn_entry_id(account_id, sale_id),
+                LoyaltyEntryKind::Earn,
+                points,
+                0,
+            ));
+        }
+
+        let mut rewards_cents = 0;
+        if self.reward_points > 0 {
+            let mut balance = doc.points_balance() + points;
+            let mut issued = doc.rewards_issued();
+            while balance >= self.reward_points {
+                issued += 1;
+                balance -= self.reward_points;
+                rewards_cents += self.reward_value_cents;
+                entries.push(entry(
+                    reward_entry_id(account_id, issued),
+                    LoyaltyEntryKind::Reward,
+                    -self.reward_points,
+                    self.reward_value_cents,
+                ));
+            }
+        }
+
+        LoyaltyAccrual {
+            entries,
+            points,
+            rewards_cents,
+        }
+    }
+
+    /// Validates the program.
+    ///
+    /// ## Rules
+    /// - Points per dollar must not be negative
+    /// - Reward points and value must be positive
+    /// - At least one tier; the first starts at 0 and each later one
+    ///   needs more points than the one before
+    /// - Earn rates between 0 and 10x
+    pub fn validate(&self) -> ValidationResult<()> {
+        if self.points_per_dollar < 0 {
+            return Err(ValidationError::OutOfRange {
+                field: "points_per_dollar".to_string(),
+                min: 0,
+                max: i64::MAX,
+            });
+        }
+        for (field, value) in [
+            ("reward_points", self.reward_points),
+            ("reward_value_cents", self.reward_value_cents),
+        ] {
+            if value <= 0 {
+                return Err(ValidationError::MustBePositive {
+                    field: field.to_string(),
+                });
+            }
+        }
+
+        let Some(first) = self.tiers.first() else {
+            return Err(ValidationError::Required {
+                field: "tiers".to_string(),
+            });
+        };
+        if first.min_points != 0 {
+            return Err(ValidationError::InvalidFormat {
+                field: "tiers".to_string(),
+                reason: "the first tier must start at 0 points".to_string(),
+            });
+        }
+        for pair in self.tiers.windows(2) {
+            if pair[1].min_points <= pair[0].min_points {
+                return Err(ValidationError::InvalidFormat {
+                    field: "tiers".to_string(),
+                    reason: format!("{} must need more points than {}", pair[1].name, pair[0].name),
+                });
+            }
+        }
+        for tier in &self.tiers {
+            if tier.name.trim().is_empty() {
+                return Err(ValidationError::Required {
+                    field: "tier name".to_string(),
+                });
+            }
+            if tier.earn_bps > 10 * BASE_EARN_BPS {
+                return Err(ValidationError::OutOfRange {
+                    field: "earn_bps".to_string(),
+                    min: 0,
+                    max: 10 * BASE_EARN_BPS as i64,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses tiers written as `name:min_points:multiplier`, comma separated,
+/// e.g. `"Member:0:1,Silver:1000:1.25,Gold:5000:1.5"`.
+pub fn parse_tiers(s: &str) -> ValidationResult<Vec<LoyaltyTier>> {
+    let invalid = |part: &str| ValidationError::InvalidFormat {
+        field: "tiers".to_string(),
+        reason: format!("'{}' is not name:min_points:multiplier", part.trim()),
+    };
+
+    s.split(',')
+        .filter(|part| !part.trim().is_empty())
+        .map(|part| {
+            let mut fields = part.split(':').map(str::trim);
+            let (Some(name), Some(min_points), Some(multiplier), None) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                return Err(invalid(part));
+            };
+            let min_points = min_points.parse::<i64>().map_err(|_| invalid(part))?;
+            let multiplier = multiplier.parse::<f64>().map_err(|_| invalid(part))?;
+            if name.is_empty() || !(0.0..=10.0).contains(&multiplier) {
+                return Err(invalid(part));
+            }
+            Ok(LoyaltyTier {
+                name: name.to_string(),
+                min_points,
+                earn_bps: (multiplier * BASE_EARN_BPS as f64).round() as u32,
+            })
+        })
+        .collect()
+}
+
+// =============================================================================
+// Rules
+// =============================================================================
+
+/// ID of the earn entry for `sale_id` on an account.
+pub fn earn_entry_id(account_id: &str, sale_id: &str) -> String {
+    format!("{}:earn:{}", account_id, sale_id)
+}
+
+/// ID of the account's `n`-th reward (from 1).
+pub fn reward_entry_id(account_id: &str, n: i64) -> String {
+    format!("{}:reward:{}", account_id, n)
+}
+
+/// Checks that a rewards balance covers a redemption.
+///
+/// ## Errors
+/// `CoreError::InsufficientRewards` if the balance is too low.
+pub fn check_reward_redemption(
+    member_number: &str,
+    amount_cents: i64,
+    balance_cents: i64,
+) -> CoreResult<()> {
+    if amount_cents <= 0 {
+        return Err(ValidationError::MustBePositive {
+            field: "amount".to_string(),
+        }
+        .into());
+    }
+    if amount_cents > balance_cents {
+        return Err(CoreError::InsufficientRewards {
+            member_number: member_number.to_string(),
+            available_cents: balance_cents,
+            requested_cents: amount_cents,
+        });
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Unit Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(entries: Vec<LoyaltyEntry>) -> LoyaltyDocument {
+        LoyaltyDocument {
+            account: LoyaltyAccount {
+                id: "la1".to_string(),
+                tenant_id: "t".to_string(),
+                member_number: "LM-1".to_string(),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+                sync_version: 1,
+            },
+            entries,
+        }
+    }
+
+    #[test]
+    fn test_tiers_follow_lifetime_points() {
+        let program = LoyaltyProgram::default();
+        assert!(program.validate().is_ok());
+
+        assert_eq!(program.tier(0).unwrap().name, "Member");
+        assert_eq!(program.tier(999).unwrap().name, "Member");
+        assert_eq!(program.tier(1_000).unwrap().name, "Silver");
+        assert_eq!(program.tier(7_000).unwrap().name, "Gold");
+        assert_eq!(program.next_tier(1_000).unwrap().name, "Gold");
+        assert!(program.next_tier(5_000).is_none());
+
+        // $40.99 earns 40 points, 50 at Silver
+        assert_eq!(program.points_for(4_099, 0), 40);
+        assert_eq!(program.points_for(4_099, 1_500), 50);
+    }
+
+    #[test]
+    fn test_accrual_issues_rewards_with_stable_ids() {
+        let program = LoyaltyProgram::default();
+        let now = Utc::now();
+        let mut doc = document(Vec::new());
+
+        // 420 points: no reward yet
+        let accrual = program.accrue(&doc, "s1", 42_000, "pos-01", now);
+        assert_eq!(accrual.points, 420);
+        assert_eq!(accrual.rewards_cents, 0);
+        doc.entries.extend(accrual.entries);
+
+        // 420 + 630 = 1050: two rewards, 50 points left
+        let accrual = program.accrue(&doc, "s2", 63_000, "pos-02", now);
+        assert_eq!(accrual.rewards_cents, 1_000);
+        let ids: Vec<_> = accrual.entries.iter().map(|e| e.id.clone()).collect();
+        assert_eq!(ids, vec!["la1:earn:s2", "la1:reward:1", "la1:reward:2"]);
+        doc.entries.extend(accrual.entries);
+
+        assert_eq!(doc.points_balance(), 50);
+        assert_eq!(doc.lifetime_points(), 1_050);
+        assert_eq!(doc.reward_balance_cents(), 1_000);
+        assert_eq!(program.tier(doc.lifetime_points()).unwrap().name, "Silver");
+    }
+
+    #[test]
+    fn test_check_reward_redemption() {
+        assert!(check_reward_redemption("LM-1", 500, 500).is_ok());
+        assert!(matches!(
+            check_reward_redemption("LM-1", 501, 500),
+            Err(CoreError::InsufficientRewards { available_cents: 500, .. })
+        ));
+        assert!(check_reward_redemption("LM-1", 0, 500).is_err());
+    }
+
+    #[test]
+    fn test_parse_tiers() {
+        let tiers = parse_tiers("Member:0:1, Silver:1000:1.25").unwrap();
+        assert_eq!(tiers.len(), 2);
+        assert_eq!(tiers[1].earn_bps, 12_500);
+
+        assert!(parse_tiers("Silver:1000").is_err());
+        assert!(parse_tiers("Silver:lots:1").is_err());
+        assert!(parse_tiers("Silver:1000:20").is_err());
+    }
+}
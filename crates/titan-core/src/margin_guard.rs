@@ -0,0 +1,340 @@
+//! # Margin Guardrails
+//!
+//! Store-configurable floor under the margin of manually entered prices:
+//! a product's price edited in the back office, or a line price overridden
+//! at the till. A price whose margin over the product's cost falls below
+//! the floor either warns the cashier or is blocked until a manager
+//! approves it; every approval is written to the override audit trail.
+//!
+//! ## Evaluation
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Margin Guardrail                                 │
+//! │                                                                         │
+//! │  check(price 8.00, cost 7.00)   margin 12.5%, floor 20%                 │
+//! │       │                                                                 │
+//! │       ├── cost unknown, or margin >= floor ─────────► Pass             │
+//! │       ├── below floor, action = warn ───────────────► Warn (saved)     │
+//! │       └── below floor, action = block                                   │
+//! │              ├── no bypass ──► CoreError::MarginBelowFloor             │
+//! │              │                 (MANAGER_OVERRIDE_REQUIRED)              │
+//! │              └── manager bypass ──► Bypassed (saved, audited)          │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Rules
+//! - The floor is in basis points of the price (2000 = 20%), 0 to 10000
+//! - A product without a cost is not checked
+//! - A price of zero or less is below any floor when the cost is above zero
+//! - A bypass needs the approving manager and a reason
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::{CoreError, CoreResult, ValidationError};
+use crate::supplier::margin_bps;
+use crate::validation::ValidationResult;
+
+/// Default margin floor: warn when selling below cost.
+pub const DEFAULT_MARGIN_FLOOR_BPS: u32 = 0;
+
+/// Maximum length of a bypass reason.
+pub const MAX_BYPASS_REASON_LENGTH: usize = 200;
+
+/// What happens to a price below the margin floor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum GuardrailAction {
+    /// Saved, with a warning for the cashier.
+    #[default]
+    Warn,
+    /// Refused unless a manager approves it.
+    Block,
+}
+
+/// Store-configurable margin floor for manual prices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MarginGuardrail {
+    /// Lowest acceptable margin, in basis points of the price.
+    pub floor_bps: u32,
+    pub action: GuardrailAction,
+}
+
+impl Default for MarginGuardrail {
+    fn default() -> Self {
+        MarginGuardrail {
+            floor_bps: DEFAULT_MARGIN_FLOOR_BPS,
+            action: GuardrailAction::Warn,
+        }
+    }
+}
+
+/// Outcome of checking one price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "lowercase")]
+pub enum MarginVerdict {
+    /// At or above the floor, or no cost to check against.
+    Pass,
+    /// Below the floor; allowed with a warning.
+    Warn,
+    /// Below a blocking floor; allowed because a manager approved it.
+    Bypassed,
+}
+
+/// A price checked against the guardrail.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MarginCheck {
+    #[ts(type = "number")]
+    pub price_cents: i64,
+    #[ts(type = "number | null")]
+    pub cost_cents: Option<i64>,
+    /// Margin of the price (None: no cost, or a price of zero or less).
+    #[ts(type = "number | null")]
+    pub margin_bps: Option<i64>,
+    pub floor_bps: u32,
+    pub verdict: MarginVerdict,
+}
+
+impl MarginCheck {
+    /// Whether the price is below the floor (warned or bypassed).
+    pub fn is_below_floor(&self) -> bool {
+        self.verdict != MarginVerdict::Pass
+    }
+}
+
+/// A manager's approval of a price below a blocking floor.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ManagerBypass {
+    pub manager_id: String,
+    pub reason: String,
+}
+
+impl ManagerBypass {
+    /// Validates the bypass (manager and reason required).
+    pub fn validate(&self) -> ValidationResult<()> {
+        if self.manager_id.trim().is_empty() {
+            return Err(ValidationError::Required {
+                field: "manager_id".to_string(),
+            });
+        }
+        let reason = self.reason.trim();
+        if reason.is_empty() {
+            return Err(ValidationError::Required {
+                field: "reason".to_string(),
+            });
+        }
+        if reason.chars().count() > MAX_BYPASS_REASON_LENGTH {
+            return Err(ValidationError::TooLong {
+                field: "reason".to_string(),
+                max: MAX_BYPASS_REASON_LENGTH,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl MarginGuardrail {
+    /// Validates the guardrail values.
+    ///
+    /// ## Rules
+    /// - Floor must be between 0 and 10000 basis points
+    pub fn validate(&self) -> ValidationResult<()> {
+        if self.floor_bps > 10_000 {
+            return Err(ValidationError::OutOfRange {
+                field: "floor_bps".to_string(),
+                min: 0,
+                max: 10_000,
+            });
+        }
+        Ok(())
+    }
+
+    /// Whether `price_cents` falls below the floor for `cost_cents`.
+    pub fn is_below_floor(&self, price_cents: i64, cost_cents: Option<i64>) -> bool {
+        let Some(cost) = cost_cents else {
+            return false;
+        };
+        match margin_bps(price_cents, cost) {
+            Some(margin) => margin < i64::from(self.floor_bps),
+            None => cost > 0,
+        }
+    }
+
+    /// Checks a manual price for the product `sku`.
+    ///
+    /// ## Errors
+    /// - `CoreError::MarginBelowFloor` for a price below a blocking floor
+    ///   without a bypass
+    /// - `CoreError::Validation` for a bypass without a manager or reason
+    pub fn check(
+        &self,
+        sku: &str,
+        price_cents: i64,
+        cost_cents: Option<i64>,
+        bypass: Option<&ManagerBypass>,
+    ) -> CoreResult<MarginCheck> {
+        let margin = cost_cents.and_then(|cost| margin_bps(price_cents, cost));
+        let verdict = if !self.is_below_floor(price_cents, cost_cents) {
+            MarginVerdict::Pass
+        } else {
+            match (self.action, bypass) {
+                (GuardrailAction::Warn, _) => MarginVerdict::Warn,
+                (GuardrailAction::Block, Some(bypass)) => {
+                    bypass.validate()?;
+                    MarginVerdict::Bypassed
+                }
+                (GuardrailAction::Block, None) => {
+                    return Err(CoreError::MarginBelowFloor {
+                        sku: sku.to_string(),
+                        margin_bps: margin,
+                        floor_bps: self.floor_bps,
+                    })
+                }
+            }
+        };
+
+        Ok(MarginCheck {
+            price_cents,
+            cost_cents,
+            margin_bps: margin,
+            floor_bps: self.floor_bps,
+            verdict,
+        })
+    }
+}
+
+// =============================================================================
+// Audit Trail
+// =============================================================================
+
+/// Which manual price a bypass approved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "snake_case"))]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceChangeKind {
+    /// A product's price in the catalog.
+    ProductPrice,
+    /// One cart line's price.
+    LinePrice,
+}
+
+impl PriceChangeKind {
+    /// Name as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PriceChangeKind::ProductPrice => "product_price",
+            PriceChangeKind::LinePrice => "line_price",
+        }
+    }
+}
+
+/// A manager bypass of the margin floor, as kept in the audit trail.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MarginOverride {
+    pub id: String,
+    pub kind: PriceChangeKind,
+    pub product_id: String,
+    #[ts(type = "number")]
+    pub old_price_cents: i64,
+    #[ts(type = "number")]
+    pub new_price_cents: i64,
+    #[ts(type = "number | null")]
+    pub cost_cents: Option<i64>,
+    #[ts(type = "number | null")]
+    pub margin_bps: Option<i64>,
+    pub floor_bps: u32,
+    pub manager_id: String,
+    pub reason: String,
+    /// Cashier who entered the price.
+    pub user_id: String,
+    pub device_id: String,
+    #[ts(as = "String")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+
+    fn block(floor_bps: u32) -> MarginGuardrail {
+        MarginGuardrail {
+            floor_bps,
+            action: GuardrailAction::Block,
+        }
+    }
+
+    fn bypass() -> ManagerBypass {
+        ManagerBypass {
+            manager_id: "mgr-1".to_string(),
+            reason: "Price match".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_floor_and_uncosted_products() {
+        let guard = block(2000);
+        assert!(!guard.is_below_floor(1000, Some(800)));
+        assert!(guard.is_below_floor(1000, Some(801)));
+        assert!(!guard.is_below_floor(1, None));
+        assert!(guard.is_below_floor(0, Some(1)));
+        assert!(!guard.is_below_floor(0, Some(0)));
+
+        let check = guard.check("COKE", 1000, None, None).unwrap();
+        assert_eq!(check.verdict, MarginVerdict::Pass);
+        assert_eq!(check.margin_bps, None);
+    }
+
+    #[test]
+    fn test_warn_allows_price_below_floor() {
+        let guard = MarginGuardrail {
+            floor_bps: 2000,
+            action: GuardrailAction::Warn,
+        };
+        let check = guard.check("COKE", 800, Some(700), None).unwrap();
+        assert_eq!(check.verdict, MarginVerdict::Warn);
+        assert_eq!(check.margin_bps, Some(1250));
+        assert!(check.is_below_floor());
+    }
+
+    #[test]
+    fn test_block_needs_manager_bypass() {
+        let guard = block(2000);
+        let err = guard.check("COKE", 800, Some(700), None).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::ManagerOverrideRequired);
+        assert_eq!(
+            err.to_string(),
+            "Margin on COKE is below the 20.00% floor; a manager must approve the price"
+        );
+
+        let check = guard.check("COKE", 800, Some(700), Some(&bypass())).unwrap();
+        assert_eq!(check.verdict, MarginVerdict::Bypassed);
+
+        let no_reason = ManagerBypass {
+            reason: " ".to_string(),
+            ..bypass()
+        };
+        let err = guard.check("COKE", 800, Some(700), Some(&no_reason)).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::ValidationError);
+
+        // A price above the floor needs no approval
+        let check = guard.check("COKE", 1000, Some(700), Some(&no_reason)).unwrap();
+        assert_eq!(check.verdict, MarginVerdict::Pass);
+    }
+
+    #[test]
+    fn test_validate_guardrail() {
+        assert!(MarginGuardrail::default().validate().is_ok());
+        assert!(block(10_000).validate().is_ok());
+        assert!(block(10_001).validate().is_err());
+    }
+}
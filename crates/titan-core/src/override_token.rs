@@ -0,0 +1,645 @@
+//! # Manager Override Tokens
+//!
+//! Short-lived signed approvals for remote authorization: a manager away
+//! from the store (at home, in the cloud back office) approves a large
+//! refund, and the cashier types or scans the token at the till. The till
+//! checks it offline against the store's public key; nothing has to be
+//! reachable.
+//!
+//! ## Token Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                      Manager Override Token                             │
+//! │                                                                         │
+//! │  Ed25519 key pair, one per store, made by OverrideSigner::generate:     │
+//! │  the signing key lives only on the manager's device (or the cloud),     │
+//! │  every till holds the public key and can check but never mint.          │
+//! │                                                                         │
+//! │  manager device / cloud                      till (offline)             │
+//! │  OverrideClaims { refund, sale, ≤ $250,      OverrideKey::verify        │
+//! │    manager, reason, expires in 10 min }        ├── signature            │
+//! │       │ OverrideSigner::sign                   ├── not expired          │
+//! │       ▼                                        └── lifetime ≤ 30 min    │
+//! │  token = base64url(claims) "." base64url(sig)   claims.authorizes(..)   │
+//! │       │                                        ├── action, store        │
+//! │       │                                        ├── not the issuer       │
+//! │       └──── read out, texted, scanned ───────► ├── terminal, sale       │
+//! │                                                └── amount ≤ approved    │
+//! │                                                       │                 │
+//! │                                   audit trail, keyed by token ID:       │
+//! │                                   a token is used at most once          │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Rules
+//! - A token lives at most [`MAX_OVERRIDE_TTL_MINUTES`]; clocks may differ
+//!   by [`CLOCK_SKEW_SECONDS`]
+//! - Terminal and sale are optional: a token without them is good on any
+//!   terminal of the store, or for any sale
+//! - The approving manager and a reason are required, as for a bypass at
+//!   the till (see `margin_guard::ManagerBypass`)
+//! - A token names the terminal that issued it, and that terminal cannot
+//!   use it: the manager's own terminal cannot approve its own refund
+//! - Keys are base64url: the 32-byte Ed25519 seed for the signing key, the
+//!   32-byte public key for tills
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::{CoreError, CoreResult, ValidationError};
+use crate::margin_guard::MAX_BYPASS_REASON_LENGTH;
+use crate::validation::ValidationResult;
+
+/// Token lifetime when the issuer does not choose one.
+pub const DEFAULT_OVERRIDE_TTL_MINUTES: i64 = 10;
+
+/// Longest lifetime a token may be issued with.
+pub const MAX_OVERRIDE_TTL_MINUTES: i64 = 30;
+
+/// How far the issuer's clock may be ahead of or behind the till's.
+pub const CLOCK_SKEW_SECONDS: i64 = 120;
+
+/// Length of an Ed25519 seed and public key.
+const KEY_LEN: usize = 32;
+
+// =============================================================================
+// Claims
+// =============================================================================
+
+/// What an override token approves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "snake_case"))]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum OverrideAction {
+    /// Refund of a completed sale.
+    Refund,
+    /// A manual price below the margin floor.
+    PriceOverride,
+    /// Voiding a sale.
+    VoidSale,
+}
+
+impl OverrideAction {
+    /// Name as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OverrideAction::Refund => "refund",
+            OverrideAction::PriceOverride => "price_override",
+            OverrideAction::VoidSale => "void_sale",
+        }
+    }
+
+    /// Parses a stored or user-supplied name.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "refund" => Some(OverrideAction::Refund),
+            "price_override" => Some(OverrideAction::PriceOverride),
+            "void_sale" => Some(OverrideAction::VoidSale),
+            _ => None,
+        }
+    }
+}
+
+/// The approval a token carries, signed as a whole.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct OverrideClaims {
+    /// Unique token ID; the audit trail refuses it a second time.
+    pub id: String,
+    pub action: OverrideAction,
+    pub store_id: String,
+    /// Terminal that issued the token; it cannot use the token itself.
+    pub issued_by: String,
+    /// Terminal the token is for (None: any terminal of the store).
+    pub device_id: Option<String>,
+    /// Sale the token is for (None: any sale).
+    pub subject: Option<String>,
+    /// Largest amount approved (None: no limit).
+    #[ts(type = "number | null")]
+    pub max_amount_cents: Option<i64>,
+    pub manager_id: String,
+    pub reason: String,
+    #[ts(as = "String")]
+    pub issued_at: DateTime<Utc>,
+    #[ts(as = "String")]
+    pub expires_at: DateTime<Utc>,
+}
+
+impl OverrideClaims {
+    /// Creates claims for `action` at `store_id`, issued by terminal
+    /// `issued_by` at `now` and valid for `ttl_minutes`, with a fresh
+    /// token ID.
+    pub fn new(
+        action: OverrideAction,
+        store_id: impl Into<String>,
+        issued_by: impl Into<String>,
+        manager_id: impl Into<String>,
+        reason: impl Into<String>,
+        now: DateTime<Utc>,
+        ttl_minutes: i64,
+    ) -> Self {
+        OverrideClaims {
+            id: uuid::Uuid::new_v4().to_string(),
+            action,
+            store_id: store_id.into(),
+            issued_by: issued_by.into(),
+            device_id: None,
+            subject: None,
+            max_amount_cents: None,
+            manager_id: manager_id.into().trim().to_string(),
+            reason: reason.into().trim().to_string(),
+            issued_at: now,
+            expires_at: now + Duration::minutes(ttl_minutes),
+        }
+    }
+
+    /// Validates the claims before they are signed.
+    ///
+    /// ## Rules
+    /// - Store, issuing terminal, manager and reason are required
+    /// - Lifetime is 1 to [`MAX_OVERRIDE_TTL_MINUTES`] minutes
+    /// - An amount limit, if any, is above zero
+    pub fn validate(&self) -> ValidationResult<()> {
+        for (field, value) in [
+            ("id", &self.id),
+            ("store_id", &self.store_id),
+            ("issued_by", &self.issued_by),
+            ("manager_id", &self.manager_id),
+            ("reason", &self.reason),
+        ] {
+            if value.trim().is_empty() {
+                return Err(ValidationError::Required {
+                    field: field.to_string(),
+                });
+            }
+        }
+        if self.reason.trim().chars().count() > MAX_BYPASS_REASON_LENGTH {
+            return Err(ValidationError::TooLong {
+                field: "reason".to_string(),
+                max: MAX_BYPASS_REASON_LENGTH,
+            });
+        }
+        let ttl = self.expires_at - self.issued_at;
+        if ttl < Duration::minutes(1) || ttl > Duration::minutes(MAX_OVERRIDE_TTL_MINUTES) {
+            return Err(ValidationError::OutOfRange {
+                field: "ttl_minutes".to_string(),
+                min: 1,
+                max: MAX_OVERRIDE_TTL_MINUTES,
+            });
+        }
+        if self.max_amount_cents.is_some_and(|max| max <= 0) {
+            return Err(ValidationError::MustBePositive {
+                field: "max_amount_cents".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks that the claims approve `request`.
+    ///
+    /// ## Errors
+    /// - `CoreError::OverrideRejected` naming the first mismatch
+    pub fn authorizes(&self, request: &OverrideRequest<'_>) -> CoreResult<()> {
+        if self.action != request.action {
+            return Err(rejected(format!(
+                "token approves a {}, not a {}",
+                self.action.as_str(),
+                request.action.as_str()
+            )));
+        }
+        if self.store_id != request.store_id {
+            return Err(rejected("token was issued for another store"));
+        }
+        if self.issued_by == request.device_id {
+            return Err(rejected("a terminal cannot use a token it issued"));
+        }
+        if self
+            .device_id
+            .as_deref()
+            .is_some_and(|device| device != request.device_id)
+        {
+            return Err(rejected("token was issued for another terminal"));
+        }
+        if self
+            .subject
+            .as_deref()
+            .is_some_and(|subject| Some(subject) != request.subject)
+        {
+            return Err(rejected("token was issued for another sale"));
+        }
+        if let (Some(max), Some(amount)) = (self.max_amount_cents, request.amount_cents) {
+            if amount > max {
+                return Err(rejected(format!(
+                    "token approves up to {} cents, {} requested",
+                    max, amount
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The operation a till wants a token to approve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverrideRequest<'a> {
+    pub action: OverrideAction,
+    pub store_id: &'a str,
+    pub device_id: &'a str,
+    /// Sale the operation is on.
+    pub subject: Option<&'a str>,
+    pub amount_cents: Option<i64>,
+}
+
+fn rejected(reason: impl Into<String>) -> CoreError {
+    CoreError::OverrideRejected {
+        reason: reason.into(),
+    }
+}
+
+// =============================================================================
+// Signing Key
+// =============================================================================
+
+/// The store's private key, held by the manager's device or the cloud.
+/// Only its holder can issue tokens.
+pub struct OverrideSigner(Ed25519KeyPair);
+
+impl OverrideSigner {
+    /// Generates a new store key pair, returning the signing key as
+    /// stored in the manager's configuration.
+    ///
+    /// ## Errors
+    /// - `CoreError::OverrideRejected` if the system has no random source
+    pub fn generate() -> CoreResult<String> {
+        let mut seed = [0u8; KEY_LEN];
+        SystemRandom::new()
+            .fill(&mut seed)
+            .map_err(|_| rejected("no random source to generate a key"))?;
+        Ok(URL_SAFE_NO_PAD.encode(seed))
+    }
+
+    /// Opens a signing key as generated by [`OverrideSigner::generate`].
+    ///
+    /// ## Errors
+    /// - `CoreError::Validation` for anything but a base64url 32-byte seed
+    pub fn from_signing_key(signing_key: &str) -> CoreResult<Self> {
+        let seed = decode_key("override_signing_key", signing_key)?;
+        let pair = Ed25519KeyPair::from_seed_unchecked(&seed)
+            .map_err(|e| invalid_key("override_signing_key", e.to_string()))?;
+        Ok(OverrideSigner(pair))
+    }
+
+    /// The public key tills check tokens against.
+    pub fn public_key(&self) -> String {
+        URL_SAFE_NO_PAD.encode(self.0.public_key().as_ref())
+    }
+
+    /// Signs `claims` into a token.
+    ///
+    /// ## Errors
+    /// - `CoreError::Validation` for claims that fail [`OverrideClaims::validate`]
+    pub fn sign(&self, claims: &OverrideClaims) -> CoreResult<String> {
+        claims.validate()?;
+        let body = serde_json::to_vec(claims).map_err(|e| rejected(e.to_string()))?;
+        let body = URL_SAFE_NO_PAD.encode(body);
+        let signature = URL_SAFE_NO_PAD.encode(self.0.sign(body.as_bytes()));
+        Ok(format!("{}.{}", body, signature))
+    }
+}
+
+impl std::fmt::Debug for OverrideSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("OverrideSigner(..)")
+    }
+}
+
+/// The store's public key, held by every till: checks tokens, cannot
+/// sign them.
+#[derive(Debug, Clone)]
+pub struct OverrideKey([u8; KEY_LEN]);
+
+impl OverrideKey {
+    /// Opens a public key as given by [`OverrideSigner::public_key`].
+    ///
+    /// ## Errors
+    /// - `CoreError::Validation` for anything but a base64url 32-byte key
+    pub fn from_public_key(public_key: &str) -> CoreResult<Self> {
+        Ok(OverrideKey(decode_key("override_public_key", public_key)?))
+    }
+
+    /// Checks a token's signature and lifetime at `now`, returning its
+    /// claims. Whether they approve an operation is up to
+    /// [`OverrideClaims::authorizes`].
+    ///
+    /// ## Errors
+    /// - `CoreError::OverrideRejected` for a malformed, forged, altered,
+    ///   expired or not yet valid token
+    pub fn verify(&self, token: &str, now: DateTime<Utc>) -> CoreResult<OverrideClaims> {
+        let malformed = || rejected("token is not a manager override");
+        let (body, signature) = token.trim().split_once('.').ok_or_else(malformed)?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| malformed())?;
+
+        UnparsedPublicKey::new(&ED25519, &self.0)
+            .verify(body.as_bytes(), &signature)
+            .map_err(|_| rejected("signature does not match the store key"))?;
+
+        let body = URL_SAFE_NO_PAD.decode(body).map_err(|_| malformed())?;
+        let claims: OverrideClaims = serde_json::from_slice(&body).map_err(|_| malformed())?;
+
+        let skew = Duration::seconds(CLOCK_SKEW_SECONDS);
+        if claims.expires_at - claims.issued_at > Duration::minutes(MAX_OVERRIDE_TTL_MINUTES) {
+            return Err(rejected("token lifetime is too long"));
+        }
+        if now < claims.issued_at - skew {
+            return Err(rejected("token is not valid yet; check the clock"));
+        }
+        if now > claims.expires_at + skew {
+            return Err(rejected("token has expired"));
+        }
+        Ok(claims)
+    }
+}
+
+fn decode_key(field: &str, key: &str) -> CoreResult<[u8; KEY_LEN]> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(key.trim())
+        .map_err(|_| invalid_key(field, "not base64url"))?;
+    bytes
+        .try_into()
+        .map_err(|_| invalid_key(field, format!("must be {} bytes", KEY_LEN)))
+}
+
+fn invalid_key(field: &str, reason: impl Into<String>) -> CoreError {
+    ValidationError::InvalidFormat {
+        field: field.to_string(),
+        reason: reason.into(),
+    }
+    .into()
+}
+
+// =============================================================================
+// Audit Trail
+// =============================================================================
+
+/// A used override token, as kept in the audit trail.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ManagerOverride {
+    /// The token ID.
+    pub id: String,
+    pub action: OverrideAction,
+    pub subject: Option<String>,
+    /// Amount the token was used for.
+    #[ts(type = "number | null")]
+    pub amount_cents: Option<i64>,
+    pub manager_id: String,
+    pub reason: String,
+    #[ts(as = "String")]
+    pub issued_at: DateTime<Utc>,
+    #[ts(as = "String")]
+    pub expires_at: DateTime<Utc>,
+    /// Cashier who used the token.
+    pub user_id: String,
+    pub device_id: String,
+    #[ts(as = "String")]
+    pub used_at: DateTime<Utc>,
+}
+
+impl ManagerOverride {
+    /// Audit entry for `claims` used for `request` by `user_id` at `now`.
+    pub fn used(
+        claims: &OverrideClaims,
+        request: &OverrideRequest<'_>,
+        user_id: &str,
+        now: DateTime<Utc>,
+    ) -> Self {
+        ManagerOverride {
+            id: claims.id.clone(),
+            action: claims.action,
+            subject: request.subject.map(str::to_string),
+            amount_cents: request.amount_cents,
+            manager_id: claims.manager_id.clone(),
+            reason: claims.reason.clone(),
+            issued_at: claims.issued_at,
+            expires_at: claims.expires_at,
+            user_id: user_id.to_string(),
+            device_id: request.device_id.to_string(),
+            used_at: now,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+
+    /// A new store key pair: the manager's signer and the tills' key.
+    fn store_keys() -> (OverrideSigner, OverrideKey) {
+        let signer = OverrideSigner::from_signing_key(&OverrideSigner::generate().unwrap()).unwrap();
+        let key = OverrideKey::from_public_key(&signer.public_key()).unwrap();
+        (signer, key)
+    }
+
+    fn claims(now: DateTime<Utc>) -> OverrideClaims {
+        OverrideClaims {
+            subject: Some("sale-1".to_string()),
+            max_amount_cents: Some(25_000),
+            ..OverrideClaims::new(
+                OverrideAction::Refund,
+                "store-1",
+                "mgr-pc",
+                "mgr-1",
+                "Faulty TV, receipt lost",
+                now,
+                DEFAULT_OVERRIDE_TTL_MINUTES,
+            )
+        }
+    }
+
+    fn refund(amount_cents: i64) -> OverrideRequest<'static> {
+        OverrideRequest {
+            action: OverrideAction::Refund,
+            store_id: "store-1",
+            device_id: "pos-01",
+            subject: Some("sale-1"),
+            amount_cents: Some(amount_cents),
+        }
+    }
+
+    fn reason(err: CoreError) -> String {
+        assert_eq!(err.code(), ErrorCode::ManagerOverrideRequired);
+        match err {
+            CoreError::OverrideRejected { reason } => reason,
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let now = Utc::now();
+        let (signer, key) = store_keys();
+        let issued = claims(now);
+        let token = signer.sign(&issued).unwrap();
+
+        let verified = key.verify(&token, now + Duration::minutes(5)).unwrap();
+        assert_eq!(verified, issued);
+        verified.authorizes(&refund(25_000)).unwrap();
+
+        // Another store's key, or an altered token, fails the signature
+        let (_, other) = store_keys();
+        assert!(reason(other.verify(&token, now).unwrap_err()).contains("signature"));
+        let (body, signature) = token.split_once('.').unwrap();
+        let mut forged = claims(now);
+        forged.max_amount_cents = Some(1_000_000);
+        let forged_body = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&forged).unwrap());
+        assert_ne!(forged_body, body);
+        let forged_token = format!("{}.{}", forged_body, signature);
+        assert!(reason(key.verify(&forged_token, now).unwrap_err()).contains("signature"));
+        assert!(reason(key.verify("not-a-token", now).unwrap_err()).contains("not a manager"));
+    }
+
+    #[test]
+    fn test_keys() {
+        let signing_key = OverrideSigner::generate().unwrap();
+        let signer = OverrideSigner::from_signing_key(&signing_key).unwrap();
+        assert_eq!(
+            OverrideSigner::from_signing_key(&signing_key)
+                .unwrap()
+                .public_key(),
+            signer.public_key()
+        );
+        assert_ne!(signing_key, signer.public_key());
+        assert!(OverrideKey::from_public_key(&signer.public_key()).is_ok());
+
+        // Short, long or garbled keys are refused
+        for bad in ["", "not base64!", "c2hvcnQ", &format!("{}AA", signing_key)] {
+            let err = OverrideKey::from_public_key(bad).unwrap_err();
+            assert_eq!(err.code(), ErrorCode::ValidationError);
+            assert!(OverrideSigner::from_signing_key(bad).is_err());
+        }
+    }
+
+    #[test]
+    fn test_expiry_and_clock_skew() {
+        let now = Utc::now();
+        let (signer, key) = store_keys();
+        let token = signer.sign(&claims(now)).unwrap();
+
+        assert!(key
+            .verify(&token, now - Duration::seconds(CLOCK_SKEW_SECONDS))
+            .is_ok());
+        let early = key.verify(&token, now - Duration::minutes(5)).unwrap_err();
+        assert!(reason(early).contains("not valid yet"));
+
+        let expiry = now + Duration::minutes(DEFAULT_OVERRIDE_TTL_MINUTES);
+        assert!(key
+            .verify(&token, expiry + Duration::seconds(CLOCK_SKEW_SECONDS))
+            .is_ok());
+        let late = key
+            .verify(&token, expiry + Duration::minutes(3))
+            .unwrap_err();
+        assert_eq!(reason(late), "token has expired");
+
+        // Lifetimes over the maximum are not signed
+        let mut long = claims(now);
+        long.expires_at = now + Duration::minutes(MAX_OVERRIDE_TTL_MINUTES + 1);
+        assert_eq!(
+            signer.sign(&long).unwrap_err().code(),
+            ErrorCode::ValidationError
+        );
+    }
+
+    #[test]
+    fn test_till_cannot_approve_its_own_override() {
+        let now = Utc::now();
+        let (signer, key) = store_keys();
+
+        // The manager's terminal issues a token for itself...
+        let own = OverrideClaims {
+            subject: Some("sale-1".to_string()),
+            ..OverrideClaims::new(
+                OverrideAction::Refund,
+                "store-1",
+                "pos-01",
+                "mgr-1",
+                "Self-approved",
+                now,
+                DEFAULT_OVERRIDE_TTL_MINUTES,
+            )
+        };
+        let token = signer.sign(&own).unwrap();
+
+        // ...and cannot use it, pinned to itself or not
+        let claims = key.verify(&token, now).unwrap();
+        let err = claims.authorizes(&refund(100)).unwrap_err();
+        assert_eq!(reason(err), "a terminal cannot use a token it issued");
+        let pinned = OverrideClaims {
+            device_id: Some("pos-01".to_string()),
+            ..claims
+        };
+        assert!(pinned.authorizes(&refund(100)).is_err());
+
+        // Another terminal can
+        let other = OverrideRequest {
+            device_id: "pos-02",
+            ..refund(100)
+        };
+        key.verify(&token, now).unwrap().authorizes(&other).unwrap();
+    }
+
+    #[test]
+    fn test_claims_must_match_the_request() {
+        let now = Utc::now();
+        let claims = claims(now);
+
+        let over = claims.authorizes(&refund(25_001)).unwrap_err();
+        assert!(reason(over).contains("up to 25000 cents"));
+
+        let void = OverrideRequest {
+            action: OverrideAction::VoidSale,
+            ..refund(100)
+        };
+        assert!(reason(claims.authorizes(&void).unwrap_err()).contains("not a void_sale"));
+
+        let other_sale = OverrideRequest {
+            subject: Some("sale-2"),
+            ..refund(100)
+        };
+        assert!(reason(claims.authorizes(&other_sale).unwrap_err()).contains("another sale"));
+
+        let other_store = OverrideRequest {
+            store_id: "store-2",
+            ..refund(100)
+        };
+        assert!(claims.authorizes(&other_store).is_err());
+
+        // Pinned to one terminal
+        let pinned = OverrideClaims {
+            device_id: Some("pos-02".to_string()),
+            ..claims.clone()
+        };
+        assert!(reason(pinned.authorizes(&refund(100)).unwrap_err()).contains("terminal"));
+
+        // Issuing terminal is required to sign
+        let unsigned = OverrideClaims {
+            issued_by: String::new(),
+            ..claims.clone()
+        };
+        assert!(unsigned.validate().is_err());
+
+        // Manager and reason are required to sign
+        let anonymous = OverrideClaims {
+            manager_id: " ".to_string(),
+            ..claims
+        };
+        assert!(anonymous.validate().is_err());
+    }
+}
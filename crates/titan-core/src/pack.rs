@@ -0,0 +1,458 @@
+//! # Pack Sizes and Receiving
+//!
+//! Goods bought by the case and sold by the each: a product can define
+//! packs ("case" = 24 units, "inner" = 6 units). Stock is always kept in
+//! eaches; packs only matter when goods are received and when stock is
+//! reported.
+//!
+//! ## Receiving
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                      Case-Pack Receiving                                │
+//! │                                                                         │
+//! │  COKE-330  packs: inner = 6, case = 24                                 │
+//! │                                                                         │
+//! │  receive 3 × case  ──► convert_line ──► 3 × 24 = 72 units              │
+//! │  receive 4 × each  ──► convert_line ──► 4 units                        │
+//! │                                                                         │
+//! │  The receipt keeps the unit and its size at the time of receiving,     │
+//! │  so a later change to the case size doesn't rewrite history.           │
+//! │                                                                         │
+//! │  Reporting: 77 units in cases ──► "3 case + 5 each"                    │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Rules
+//! - Pack names are lowercased and trimmed, 1 to [`MAX_PACK_NAME_LEN`]
+//!   characters, unique per product; [`EACH`] is the base unit and can't
+//!   be a pack name
+//! - A pack holds 2 to [`MAX_PACK_UNITS`] units; two packs of one product
+//!   can't hold the same number
+//! - At most [`MAX_PACKS`] packs per product
+//!
+//! Like attribute sets, a product's packs are one document
+//! ([`ProductPacks`]) with its own `sync_version`; the newer version wins
+//! on every terminal.
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::ValidationError;
+use crate::supplier::{each_cost, validate_cost};
+use crate::validation::ValidationResult;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Name of the base unit stock is kept in.
+pub const EACH: &str = "each";
+
+/// Most packs one product may define.
+pub const MAX_PACKS: usize = 5;
+
+/// Most units one pack may hold.
+pub const MAX_PACK_UNITS: i64 = 10_000;
+
+/// Longest pack name.
+pub const MAX_PACK_NAME_LEN: usize = 20;
+
+/// Largest quantity on one receiving line, in the line's unit.
+pub const MAX_RECEIVE_QUANTITY: i64 = 100_000;
+
+// =============================================================================
+// Packs
+// =============================================================================
+
+/// A pack a product is bought in, e.g. `case` of 24.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProductPack {
+    pub name: String,
+    /// Eaches in one pack.
+    #[ts(type = "number")]
+    pub units: i64,
+    /// Barcode printed on the pack (e.g. a case GTIN), if any.
+    #[serde(default)]
+    pub barcode: Option<String>,
+}
+
+impl ProductPack {
+    /// A pack `name` of `units` eaches, without a barcode.
+    pub fn new(name: impl Into<String>, units: i64) -> Self {
+        ProductPack {
+            name: name.into(),
+            units,
+            barcode: None,
+        }
+    }
+}
+
+/// The packs of one product, as stored in the sync outbox
+/// (`PRODUCT_PACKS`) and relayed to other terminals.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProductPacks {
+    pub product_id: String,
+    /// Smallest pack first.
+    pub packs: Vec<ProductPack>,
+    /// Version of the set (independent of the product's own version).
+    pub sync_version: i64,
+    #[ts(as = "String")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ProductPacks {
+    /// The pack called `name` (normalized first).
+    pub fn get(&self, name: &str) -> Option<&ProductPack> {
+        let name = name.trim().to_lowercase();
+        self.packs.iter().find(|p| p.name == name)
+    }
+
+    /// The biggest pack, the usual unit for stock reports.
+    pub fn largest(&self) -> Option<&ProductPack> {
+        self.packs.last()
+    }
+
+    /// Eaches in `quantity` of `unit` ([`EACH`] or a pack name).
+    ///
+    /// ## Errors
+    /// `NotAllowed` for a unit the product doesn't define.
+    pub fn to_units(&self, unit: &str, quantity: i64) -> ValidationResult<i64> {
+        Ok(quantity * self.unit_size(unit)?)
+    }
+
+    /// Eaches in one `unit`.
+    fn unit_size(&self, unit: &str) -> ValidationResult<i64> {
+        if unit.trim().eq_ignore_ascii_case(EACH) {
+            return Ok(1);
+        }
+        self.get(unit)
+            .map(|p| p.units)
+            .ok_or_else(|| ValidationError::NotAllowed {
+                field: "unit".to_string(),
+                allowed: std::iter::once(EACH.to_string())
+                    .chain(self.packs.iter().map(|p| p.name.clone()))
+                    .collect(),
+            })
+    }
+
+    /// `units` eaches expressed in `unit`, e.g. 77 in cases of 24 is
+    /// 3 cases and 5 eaches.
+    ///
+    /// ## Errors
+    /// `NotAllowed` for a unit the product doesn't define.
+    pub fn in_unit(&self, unit: &str, units: i64) -> ValidationResult<PackQuantity> {
+        let size = self.unit_size(unit)?;
+        let unit = if size == 1 {
+            EACH.to_string()
+        } else {
+            unit.trim().to_lowercase()
+        };
+        Ok(PackQuantity::new(unit, size, units))
+    }
+}
+
+/// A stock quantity in whole packs plus the eaches left over.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PackQuantity {
+    /// Pack name, or [`EACH`].
+    pub unit: String,
+    /// Eaches in one `unit`.
+    #[ts(type = "number")]
+    pub units_per_pack: i64,
+    /// Whole packs.
+    #[ts(type = "number")]
+    pub packs: i64,
+    /// Eaches that don't fill a pack (same sign as `packs`).
+    #[ts(type = "number")]
+    pub remainder: i64,
+}
+
+impl PackQuantity {
+    /// `units` eaches in packs of `units_per_pack`.
+    pub fn new(unit: impl Into<String>, units_per_pack: i64, units: i64) -> Self {
+        let units_per_pack = units_per_pack.max(1);
+        PackQuantity {
+            unit: unit.into(),
+            units_per_pack,
+            packs: units / units_per_pack,
+            remainder: units % units_per_pack,
+        }
+    }
+
+    /// The quantity in eaches.
+    pub fn total_units(&self) -> i64 {
+        self.packs * self.units_per_pack + self.remainder
+    }
+}
+
+impl fmt::Display for PackQuantity {
+    /// `3 case + 5 each`, `3 case`, or `5 each`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.units_per_pack == 1 {
+            return write!(f, "{} {}", self.packs, EACH);
+        }
+        match (self.packs, self.remainder) {
+            (packs, 0) => write!(f, "{} {}", packs, self.unit),
+            (0, remainder) => write!(f, "{} {}", remainder, EACH),
+            (packs, remainder) => write!(f, "{} {} + {} {}", packs, self.unit, remainder, EACH),
+        }
+    }
+}
+
+/// Normalizes a product's packs for saving: names lowercased and trimmed,
+/// empty barcodes dropped, smallest pack first.
+///
+/// ## Errors
+/// An empty, too long or reserved name, a size outside 2 to
+/// [`MAX_PACK_UNITS`], a repeated name or size, or more than
+/// [`MAX_PACKS`] packs.
+pub fn normalize_packs(packs: &[ProductPack]) -> ValidationResult<Vec<ProductPack>> {
+    if packs.len() > MAX_PACKS {
+        return Err(ValidationError::OutOfRange {
+            field: "packs".to_string(),
+            min: 0,
+            max: MAX_PACKS as i64,
+        });
+    }
+
+    let mut normalized: Vec<ProductPack> = Vec::with_capacity(packs.len());
+    for pack in packs {
+        let name = pack.name.trim().to_lowercase();
+        if name.is_empty() {
+            return Err(ValidationError::Required {
+                field: "pack name".to_string(),
+            });
+        }
+        if name.chars().count() > MAX_PACK_NAME_LEN {
+            return Err(ValidationError::TooLong {
+                field: "pack name".to_string(),
+                max: MAX_PACK_NAME_LEN,
+            });
+        }
+        if name == EACH {
+            return Err(ValidationError::InvalidFormat {
+                field: "pack name".to_string(),
+                reason: format!("'{}' is the base unit", EACH),
+            });
+        }
+        if !(2..=MAX_PACK_UNITS).contains(&pack.units) {
+            return Err(ValidationError::OutOfRange {
+                field: format!("pack '{}'", name),
+                min: 2,
+                max: MAX_PACK_UNITS,
+            });
+        }
+        if normalized.iter().any(|p| p.name == name) {
+            return Err(ValidationError::Duplicate {
+                field: "pack name".to_string(),
+                value: name,
+            });
+        }
+        if normalized.iter().any(|p| p.units == pack.units) {
+            return Err(ValidationError::Duplicate {
+                field: "pack size".to_string(),
+                value: pack.units.to_string(),
+            });
+        }
+
+        let barcode = pack
+            .barcode
+            .as_deref()
+            .map(str::trim)
+            .filter(|b| !b.is_empty())
+            .map(str::to_string);
+        normalized.push(ProductPack {
+            name,
+            units: pack.units,
+            barcode,
+        });
+    }
+
+    normalized.sort_by_key(|p| p.units);
+    Ok(normalized)
+}
+
+// =============================================================================
+// Receiving
+// =============================================================================
+
+/// One line of goods being received, in any unit of the product.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ReceivingLine {
+    pub product_id: String,
+    /// [`EACH`] or one of the product's pack names.
+    pub unit: String,
+    #[ts(type = "number")]
+    pub quantity: i64,
+    /// Cost per `unit` from the delivery note; the supplier's listed cost
+    /// is used when omitted.
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub unit_cost_cents: Option<i64>,
+}
+
+/// A received line with its conversion to eaches, as recorded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct StockReceiptLine {
+    pub product_id: String,
+    pub unit: String,
+    /// Eaches per `unit` when received.
+    #[ts(type = "number")]
+    pub units_per_pack: i64,
+    /// Quantity in `unit`.
+    #[ts(type = "number")]
+    pub quantity: i64,
+    /// Eaches added to stock.
+    #[ts(type = "number")]
+    pub units: i64,
+    /// Cost per each, if known.
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub each_cost_cents: Option<i64>,
+}
+
+/// Goods received into stock, e.g. one supplier delivery.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct StockReceipt {
+    pub id: String,
+    /// Delivery note or supplier invoice number.
+    pub reference: Option<String>,
+    /// Supplier the goods came from.
+    #[serde(default)]
+    pub supplier_id: Option<String>,
+    pub lines: Vec<StockReceiptLine>,
+    pub received_by: String,
+    pub device_id: String,
+    #[ts(as = "String")]
+    pub received_at: DateTime<Utc>,
+}
+
+impl StockReceipt {
+    /// Eaches received across all lines.
+    pub fn total_units(&self) -> i64 {
+        self.lines.iter().map(|l| l.units).sum()
+    }
+}
+
+/// Converts a receiving line to eaches with the product's packs, and its
+/// cost, if given, to a cost per each.
+///
+/// ## Errors
+/// - `OutOfRange` for a quantity outside 1 to [`MAX_RECEIVE_QUANTITY`] or
+///   a cost out of range
+/// - `NotAllowed` for a unit the product doesn't define
+pub fn convert_line(line: &ReceivingLine, packs: &ProductPacks) -> ValidationResult<StockReceiptLine> {
+    if !(1..=MAX_RECEIVE_QUANTITY).contains(&line.quantity) {
+        return Err(ValidationError::OutOfRange {
+            field: "quantity".to_string(),
+            min: 1,
+            max: MAX_RECEIVE_QUANTITY,
+        });
+    }
+
+    if let Some(cost) = line.unit_cost_cents {
+        validate_cost(cost)?;
+    }
+
+    let size = packs.unit_size(&line.unit)?;
+    let unit = if size == 1 {
+        EACH.to_string()
+    } else {
+        line.unit.trim().to_lowercase()
+    };
+    Ok(StockReceiptLine {
+        product_id: line.product_id.clone(),
+        unit,
+        units_per_pack: size,
+        quantity: line.quantity,
+        units: line.quantity * size,
+        each_cost_cents: line.unit_cost_cents.map(|cost| each_cost(cost, size)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packs() -> ProductPacks {
+        ProductPacks {
+            product_id: "p-1".to_string(),
+            packs: normalize_packs(&[ProductPack::new(" Case ", 24), ProductPack::new("inner", 6)])
+                .unwrap(),
+            sync_version: 1,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_packs() {
+        let packs = packs();
+        assert_eq!(packs.packs[0].name, "inner");
+        assert_eq!(packs.largest().unwrap().name, "case");
+        assert_eq!(packs.get("CASE").unwrap().units, 24);
+
+        let invalid = [
+            vec![ProductPack::new("", 24)],
+            vec![ProductPack::new("each", 24)],
+            vec![ProductPack::new("case", 1)],
+            vec![ProductPack::new("case", 24), ProductPack::new("CASE", 12)],
+            vec![ProductPack::new("case", 24), ProductPack::new("tray", 24)],
+        ];
+        for packs in invalid {
+            assert!(normalize_packs(&packs).is_err());
+        }
+    }
+
+    #[test]
+    fn test_unit_conversion() {
+        let packs = packs();
+        assert_eq!(packs.to_units("case", 3).unwrap(), 72);
+        assert_eq!(packs.to_units("Each", 3).unwrap(), 3);
+        assert!(matches!(
+            packs.to_units("pallet", 1),
+            Err(ValidationError::NotAllowed { ref allowed, .. }) if allowed.len() == 3
+        ));
+
+        let quantity = packs.in_unit("case", 77).unwrap();
+        assert_eq!((quantity.packs, quantity.remainder), (3, 5));
+        assert_eq!(quantity.to_string(), "3 case + 5 each");
+        assert_eq!(quantity.total_units(), 77);
+        assert_eq!(packs.in_unit("case", 48).unwrap().to_string(), "2 case");
+        assert_eq!(packs.in_unit("case", 5).unwrap().to_string(), "5 each");
+        assert_eq!(packs.in_unit("each", 77).unwrap().to_string(), "77 each");
+    }
+
+    #[test]
+    fn test_convert_receiving_line() {
+        let packs = packs();
+        let line = |unit: &str, quantity: i64| ReceivingLine {
+            product_id: "p-1".to_string(),
+            unit: unit.to_string(),
+            quantity,
+            unit_cost_cents: None,
+        };
+
+        let converted = convert_line(&line(" Case", 3), &packs).unwrap();
+        assert_eq!(converted.unit, "case");
+        assert_eq!((converted.units_per_pack, converted.units), (24, 72));
+        assert_eq!(convert_line(&line("each", 4), &packs).unwrap().units, 4);
+        assert!(convert_line(&line("case", 0), &packs).is_err());
+        assert!(convert_line(&line("pallet", 1), &packs).is_err());
+
+        let costed = ReceivingLine {
+            unit_cost_cents: Some(1032),
+            ..line("case", 2)
+        };
+        assert_eq!(convert_line(&costed, &packs).unwrap().each_cost_cents, Some(43));
+        assert_eq!(convert_line(&line("case", 2), &packs).unwrap().each_cost_cents, None);
+    }
+}
@@ -0,0 +1,266 @@
+//! # Pagination
+//!
+//! Keyset (cursor) pagination for list and search results that are too
+//! large to return at once: the product catalog, the sales history and
+//! the business customer list.
+//!
+//! ## Keyset Cursors
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Keyset Pagination                                │
+//! │                                                                         │
+//! │  PageRequest::first(50)                                                 │
+//! │       │   ORDER BY name, id  LIMIT 51                                   │
+//! │       ▼                                                                 │
+//! │  Page { items: 50 rows, next_cursor: "…" (keys of row 50) }             │
+//! │       │   51st row came back → there is a next page                     │
+//! │       ▼                                                                 │
+//! │  PageRequest::after(next_cursor, 50)                                    │
+//! │       │   WHERE (name, id) > (cursor name, cursor id)                   │
+//! │       ▼                                                                 │
+//! │  ... until next_cursor is None                                          │
+//! │                                                                         │
+//! │  Unlike OFFSET, each page costs the same however deep it is, and rows  │
+//! │  inserted meanwhile do not shift later pages.                           │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Cursors are opaque to callers: the sort keys of the last row, hex
+//! encoded. A cursor only makes sense for the query that produced it.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::ValidationError;
+
+/// Page size when the caller does not ask for one.
+pub const DEFAULT_PAGE_SIZE: u32 = 50;
+
+/// Largest page returned.
+pub const MAX_PAGE_SIZE: u32 = 500;
+
+/// Separates the keys inside a cursor (ASCII unit separator).
+const KEY_SEPARATOR: char = '\u{1f}';
+
+// =============================================================================
+// Request
+// =============================================================================
+
+/// Which page to fetch.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageRequest {
+    /// Rows per page (default [`DEFAULT_PAGE_SIZE`], at most [`MAX_PAGE_SIZE`]).
+    pub limit: Option<u32>,
+    /// `next_cursor` of the previous page; `None` for the first page.
+    pub cursor: Option<String>,
+    /// Also count every matching row (an extra query).
+    pub include_total: bool,
+}
+
+impl PageRequest {
+    /// The first page.
+    pub fn first(limit: u32) -> Self {
+        PageRequest {
+            limit: Some(limit),
+            ..Default::default()
+        }
+    }
+
+    /// The page after the one that returned `cursor`.
+    pub fn after(cursor: impl Into<String>, limit: u32) -> Self {
+        PageRequest {
+            limit: Some(limit),
+            cursor: Some(cursor.into()),
+            include_total: false,
+        }
+    }
+
+    /// Also return the total number of matching rows.
+    pub fn with_total(mut self) -> Self {
+        self.include_total = true;
+        self
+    }
+
+    /// The page size, clamped to `1..=MAX_PAGE_SIZE`.
+    pub fn page_size(&self) -> u32 {
+        self.limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE)
+    }
+
+    /// Rows to fetch: one more than the page size, to see whether a next
+    /// page exists.
+    pub fn fetch_limit(&self) -> u32 {
+        self.page_size() + 1
+    }
+
+    /// The cursor's `count` keys, or `None` for the first page.
+    ///
+    /// ## Errors
+    /// `InvalidFormat` if the cursor is malformed or has another number
+    /// of keys (i.e. it came from a different query).
+    pub fn cursor_keys(&self, count: usize) -> Result<Option<Vec<String>>, ValidationError> {
+        match self.cursor.as_deref().filter(|c| !c.is_empty()) {
+            None => Ok(None),
+            Some(cursor) => decode_cursor(cursor, count).map(Some),
+        }
+    }
+}
+
+// =============================================================================
+// Page
+// =============================================================================
+
+/// One page of results.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Pass to [`PageRequest::after`] for the next page; `None` on the
+    /// last page.
+    pub next_cursor: Option<String>,
+    /// Every matching row, when requested.
+    #[ts(type = "number | null")]
+    pub total: Option<i64>,
+}
+
+impl<T> Page<T> {
+    /// Builds a page from rows fetched with [`PageRequest::fetch_limit`].
+    ///
+    /// `keys` gives a row's sort keys, in ORDER BY order; the cursor is
+    /// made from the last row kept.
+    pub fn from_rows<F>(mut rows: Vec<T>, request: &PageRequest, keys: F) -> Self
+    where
+        F: Fn(&T) -> Vec<String>,
+    {
+        let size = request.page_size() as usize;
+        let next_cursor = if rows.len() > size {
+            rows.truncate(size);
+            rows.last().map(|row| encode_cursor(&keys(row)))
+        } else {
+            None
+        };
+        Page {
+            items: rows,
+            next_cursor,
+            total: None,
+        }
+    }
+
+    /// Sets the total row count.
+    pub fn with_total(mut self, total: Option<i64>) -> Self {
+        self.total = total;
+        self
+    }
+
+    /// Converts the items, keeping the cursor and total.
+    pub fn map<U, F>(self, f: F) -> Page<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+            total: self.total,
+        }
+    }
+}
+
+// =============================================================================
+// Cursor Encoding
+// =============================================================================
+
+/// Encodes sort keys into an opaque cursor.
+pub fn encode_cursor<S: AsRef<str>>(keys: &[S]) -> String {
+    let joined = keys
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<Vec<_>>()
+        .join(&KEY_SEPARATOR.to_string());
+    joined.bytes().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Decodes a cursor made by [`encode_cursor`] with `count` keys.
+pub fn decode_cursor(cursor: &str, count: usize) -> Result<Vec<String>, ValidationError> {
+    let invalid = || ValidationError::InvalidFormat {
+        field: "cursor".to_string(),
+        reason: "not a cursor from this list".to_string(),
+    };
+
+    if !cursor.len().is_multiple_of(2) {
+        return Err(invalid());
+    }
+    let bytes = (0..cursor.len())
+        .step_by(2)
+        .map(|i| {
+            cursor
+                .get(i..i + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+        })
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(invalid)?;
+    let joined = String::from_utf8(bytes).map_err(|_| invalid())?;
+
+    let keys: Vec<String> = joined.split(KEY_SEPARATOR).map(String::from).collect();
+    if keys.len() != count {
+        return Err(invalid());
+    }
+    Ok(keys)
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = encode_cursor(&["Coca-Cola 330ml", "a1b2"]);
+        assert_eq!(
+            decode_cursor(&cursor, 2).unwrap(),
+            vec!["Coca-Cola 330ml".to_string(), "a1b2".to_string()]
+        );
+        // Keys may be empty or non-ASCII
+        let cursor = encode_cursor(&["", "Crème brûlée"]);
+        assert_eq!(decode_cursor(&cursor, 2).unwrap()[1], "Crème brûlée");
+    }
+
+    #[test]
+    fn test_invalid_cursors() {
+        assert!(decode_cursor("zz", 1).is_err());
+        assert!(decode_cursor("abc", 1).is_err());
+        // Wrong number of keys: a cursor from another list
+        let cursor = encode_cursor(&["a", "b"]);
+        assert!(decode_cursor(&cursor, 3).is_err());
+    }
+
+    #[test]
+    fn test_page_size_is_clamped() {
+        assert_eq!(PageRequest::default().page_size(), DEFAULT_PAGE_SIZE);
+        assert_eq!(PageRequest::first(0).page_size(), 1);
+        assert_eq!(PageRequest::first(10_000).page_size(), MAX_PAGE_SIZE);
+        assert_eq!(PageRequest::first(20).fetch_limit(), 21);
+    }
+
+    #[test]
+    fn test_page_from_rows() {
+        let request = PageRequest::first(2);
+        let keys = |n: &i32| vec![n.to_string()];
+
+        let page = Page::from_rows(vec![1, 2, 3], &request, keys);
+        assert_eq!(page.items, vec![1, 2]);
+        let next = page.next_cursor.unwrap();
+        assert_eq!(
+            PageRequest::after(next, 2).cursor_keys(1).unwrap(),
+            Some(vec!["2".to_string()])
+        );
+
+        // No extra row: last page
+        let page = Page::from_rows(vec![1, 2], &request, keys);
+        assert_eq!(page.next_cursor, None);
+        assert_eq!(request.cursor_keys(1).unwrap(), None);
+    }
+}
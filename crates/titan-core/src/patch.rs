@@ -0,0 +1,375 @@
+//! # Field Patches
+//!
+//! Dirty-field tracking for entity edits, so the outbox carries only the
+//! fields that changed instead of the whole entity.
+//!
+//! ## Patch Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                          Field Patch Flow                               │
+//! │                                                                         │
+//! │  Tracked::new(product)          snapshot of the loaded entity           │
+//! │       │                                                                 │
+//! │       ▼  edit via get_mut()                                             │
+//! │  tracked.dirty_fields()         ["price_cents", "name"]                 │
+//! │       │                                                                 │
+//! │       ▼                                                                 │
+//! │  tracked.patch(version, now)    EntityPatch {                           │
+//! │                                   base_version: 7,                      │
+//! │                                   fields: { price_cents: 299, ... }     │
+//! │                                 }                                       │
+//! │       │                                                                 │
+//! │       ▼                                                                 │
+//! │  sync_outbox (operation "patch")  ──►  hub / other terminals            │
+//! │                                                                         │
+//! │  FIELD-LEVEL MERGE (receiving side)                                     │
+//! │  ──────────────────────────────────                                     │
+//! │  Fields only the incoming patch touched   → applied                     │
+//! │  Fields a pending local patch also touched → newer updated_at wins      │
+//! │  Untouched fields are never overwritten, so concurrent edits to         │
+//! │  different fields (price here, name there) both survive.                │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Patches are shallow: a field is a top-level key of the entity's JSON
+//! form and is replaced wholesale. Bookkeeping fields (`updated_at`,
+//! `sync_version`, ...) are never part of a patch.
+
+use chrono::{DateTime, Utc};
+use serde::de::{DeserializeOwned, Error as _};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Fields maintained by the store itself rather than edited by a user.
+pub const UNPATCHED_FIELDS: &[&str] = &[
+    "id",
+    "tenant_id",
+    "created_at",
+    "updated_at",
+    "sync_version",
+    // Deletes travel as tombstones
+    "deleted_at",
+];
+
+// =============================================================================
+// Entity Patch
+// =============================================================================
+
+/// The changed fields of one entity edit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityPatch {
+    /// Entity version the edit was made against.
+    pub base_version: i64,
+
+    /// When the edit was made (decides per-field conflicts).
+    pub updated_at: DateTime<Utc>,
+
+    /// New values by field name.
+    pub fields: Map<String, Value>,
+}
+
+impl EntityPatch {
+    /// Fields that differ between `before` and `after`, or `None` if the
+    /// edit changed nothing.
+    pub fn diff<T: Serialize>(
+        before: &T,
+        after: &T,
+        base_version: i64,
+        updated_at: DateTime<Utc>,
+    ) -> Result<Option<Self>, serde_json::Error> {
+        let before = to_object(before)?;
+        let after = to_object(after)?;
+
+        let fields: Map<String, Value> = after
+            .into_iter()
+            .filter(|(field, value)| {
+                !UNPATCHED_FIELDS.contains(&field.as_str()) && before.get(field) != Some(value)
+            })
+            .collect();
+
+        Ok((!fields.is_empty()).then_some(EntityPatch {
+            base_version,
+            updated_at,
+            fields,
+        }))
+    }
+
+    /// Folds a later edit of the same entity into this one, keeping this
+    /// patch's base version.
+    pub fn absorb(&mut self, later: EntityPatch) {
+        self.fields.extend(later.fields);
+        self.updated_at = self.updated_at.max(later.updated_at);
+    }
+
+    /// Names of the patched fields.
+    pub fn field_names(&self) -> impl Iterator<Item = &str> {
+        self.fields.keys().map(String::as_str)
+    }
+
+    /// Returns `entity` with the patched fields replaced.
+    ///
+    /// Fails if a patched value does not fit the entity's field type.
+    pub fn apply<T: Serialize + DeserializeOwned>(
+        &self,
+        entity: &T,
+    ) -> Result<T, serde_json::Error> {
+        let mut object = to_object(entity)?;
+        for (field, value) in &self.fields {
+            object.insert(field.clone(), value.clone());
+        }
+        serde_json::from_value(Value::Object(object))
+    }
+
+    /// Field-level merge of this (incoming) patch against a local edit that
+    /// has not synced yet.
+    ///
+    /// Fields only this patch touched are kept. Fields both touched go to
+    /// the newer edit; on a tie the incoming patch wins so every terminal
+    /// converges on the same value.
+    pub fn merge(&self, local: Option<&EntityPatch>) -> MergeOutcome {
+        let Some(local) = local else {
+            return MergeOutcome {
+                patch: self.clone(),
+                kept_local: Vec::new(),
+            };
+        };
+
+        let mut fields = Map::new();
+        let mut kept_local = Vec::new();
+        for (field, value) in &self.fields {
+            match local.fields.get(field) {
+                Some(mine) if mine != value && local.updated_at > self.updated_at => {
+                    kept_local.push(field.clone());
+                }
+                _ => {
+                    fields.insert(field.clone(), value.clone());
+                }
+            }
+        }
+
+        MergeOutcome {
+            patch: EntityPatch {
+                base_version: self.base_version,
+                updated_at: self.updated_at,
+                fields,
+            },
+            kept_local,
+        }
+    }
+}
+
+/// Result of [`EntityPatch::merge`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeOutcome {
+    /// The incoming fields to apply.
+    pub patch: EntityPatch,
+
+    /// Incoming fields dropped because the local edit is newer.
+    pub kept_local: Vec<String>,
+}
+
+fn to_object<T: Serialize>(entity: &T) -> Result<Map<String, Value>, serde_json::Error> {
+    match serde_json::to_value(entity)? {
+        Value::Object(object) => Ok(object),
+        _ => Err(serde_json::Error::custom(
+            "patched entity must serialize to a JSON object",
+        )),
+    }
+}
+
+// =============================================================================
+// Dirty-Field Tracking
+// =============================================================================
+
+/// An entity together with the state it was loaded in.
+///
+/// ```rust,ignore
+/// let mut product = Tracked::new(db.products().get_by_id(id).await?.unwrap());
+/// product.get_mut().price_cents = 299;
+/// assert_eq!(product.dirty_fields()?, ["price_cents"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Tracked<T> {
+    original: T,
+    current: T,
+}
+
+impl<T: Clone> Tracked<T> {
+    /// Starts tracking edits to `entity`.
+    pub fn new(entity: T) -> Self {
+        Tracked {
+            original: entity.clone(),
+            current: entity,
+        }
+    }
+}
+
+impl<T> Tracked<T> {
+    /// The entity as loaded.
+    pub fn original(&self) -> &T {
+        &self.original
+    }
+
+    /// The entity with edits applied.
+    pub fn current(&self) -> &T {
+        &self.current
+    }
+
+    /// Mutable access for editing.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.current
+    }
+
+    /// The edited entity.
+    pub fn into_inner(self) -> T {
+        self.current
+    }
+}
+
+impl<T: Serialize> Tracked<T> {
+    /// Names of the fields edited since loading.
+    pub fn dirty_fields(&self) -> Result<Vec<String>, serde_json::Error> {
+        Ok(self
+            .patch(0, Utc::now())?
+            .map(|patch| patch.fields.into_iter().map(|(field, _)| field).collect())
+            .unwrap_or_default())
+    }
+
+    /// The edits as a patch against `base_version`, or `None` if nothing
+    /// changed.
+    pub fn patch(
+        &self,
+        base_version: i64,
+        updated_at: DateTime<Utc>,
+    ) -> Result<Option<EntityPatch>, serde_json::Error> {
+        EntityPatch::diff(&self.original, &self.current, base_version, updated_at)
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use serde_json::json;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Item {
+        id: String,
+        name: String,
+        price_cents: i64,
+        barcode: Option<String>,
+        updated_at: DateTime<Utc>,
+        sync_version: i64,
+    }
+
+    fn item() -> Item {
+        Item {
+            id: "p-1".into(),
+            name: "Cola 330ml".into(),
+            price_cents: 199,
+            barcode: None,
+            updated_at: Utc::now(),
+            sync_version: 3,
+        }
+    }
+
+    fn patch(fields: Value, updated_at: DateTime<Utc>) -> EntityPatch {
+        EntityPatch {
+            base_version: 3,
+            updated_at,
+            fields: fields.as_object().unwrap().clone(),
+        }
+    }
+
+    #[test]
+    fn test_tracked_reports_dirty_fields() {
+        let mut tracked = Tracked::new(item());
+        assert!(tracked.dirty_fields().unwrap().is_empty());
+        assert_eq!(tracked.patch(3, Utc::now()).unwrap(), None);
+
+        tracked.get_mut().price_cents = 249;
+        tracked.get_mut().barcode = Some("5449000000996".into());
+        // Bookkeeping fields never show up
+        tracked.get_mut().updated_at += Duration::seconds(5);
+        tracked.get_mut().sync_version = 4;
+
+        assert_eq!(tracked.dirty_fields().unwrap(), ["barcode", "price_cents"]);
+        let patch = tracked.patch(3, Utc::now()).unwrap().unwrap();
+        assert_eq!(patch.base_version, 3);
+        assert_eq!(patch.fields["price_cents"], json!(249));
+        assert_eq!(patch.fields["barcode"], json!("5449000000996"));
+    }
+
+    #[test]
+    fn test_patch_round_trips_through_apply() {
+        let mut tracked = Tracked::new(item());
+        tracked.get_mut().name = "Cola Zero 330ml".into();
+        tracked.get_mut().barcode = None;
+        let patch = tracked.patch(3, Utc::now()).unwrap().unwrap();
+
+        let applied = patch.apply(tracked.original()).unwrap();
+        assert_eq!(&applied, tracked.current());
+
+        // Clearing an optional field is a change too
+        let mut cleared = Tracked::new(Item {
+            barcode: Some("123".into()),
+            ..item()
+        });
+        cleared.get_mut().barcode = None;
+        let patch = cleared.patch(3, Utc::now()).unwrap().unwrap();
+        assert_eq!(patch.fields["barcode"], Value::Null);
+        assert_eq!(patch.apply(cleared.original()).unwrap().barcode, None);
+    }
+
+    #[test]
+    fn test_absorb_coalesces_edits() {
+        let now = Utc::now();
+        let mut pending = patch(json!({ "price_cents": 249, "name": "Cola" }), now);
+        let mut later = patch(json!({ "price_cents": 229 }), now + Duration::seconds(10));
+        later.base_version = 4;
+
+        pending.absorb(later);
+        assert_eq!(pending.base_version, 3);
+        assert_eq!(pending.updated_at, now + Duration::seconds(10));
+        assert_eq!(pending.fields["price_cents"], json!(229));
+        assert_eq!(pending.fields["name"], json!("Cola"));
+    }
+
+    #[test]
+    fn test_apply_rejects_mistyped_value() {
+        let patch = patch(json!({ "price_cents": "free" }), Utc::now());
+        assert!(patch.apply(&item()).is_err());
+    }
+
+    #[test]
+    fn test_merge_keeps_newer_edit_per_field() {
+        let now = Utc::now();
+        let incoming = patch(json!({ "price_cents": 249, "name": "Cola" }), now);
+
+        // No local edit: everything applies
+        let outcome = incoming.merge(None);
+        assert_eq!(outcome.patch, incoming);
+        assert!(outcome.kept_local.is_empty());
+
+        // Newer local price edit survives; the name still applies
+        let local = patch(json!({ "price_cents": 229 }), now + Duration::seconds(10));
+        let outcome = incoming.merge(Some(&local));
+        assert_eq!(outcome.kept_local, ["price_cents"]);
+        assert_eq!(outcome.patch.fields.len(), 1);
+        assert_eq!(outcome.patch.fields["name"], json!("Cola"));
+
+        // Older local edit loses
+        let local = patch(json!({ "price_cents": 229 }), now - Duration::seconds(10));
+        let outcome = incoming.merge(Some(&local));
+        assert!(outcome.kept_local.is_empty());
+        assert_eq!(outcome.patch.fields["price_cents"], json!(249));
+    }
+}
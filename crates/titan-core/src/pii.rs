@@ -0,0 +1,366 @@
+//! # PII Encryption
+//!
+//! The envelope scheme for customer emails and phone numbers, shared by
+//! the terminals (`titan_db::pii`, SQLite) and the cloud
+//! (`titan_cloud_api::pii`, PostgreSQL) so a value sealed on one side
+//! opens on the other. Each side stores the wrapped data keys and the
+//! values in its own tables; only the cryptography lives here.
+//!
+//! ## Envelope Encryption
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Envelope Encryption                              │
+//! │                                                                         │
+//! │  MasterKey (outside the database: env var or key file)                  │
+//! │       │ unwraps                                                         │
+//! │       ▼                                                                 │
+//! │  wrapped data key "wk1:…" ──► tenant data key (random, one per tenant)  │
+//! │                               │ HKDF                                    │
+//! │                  ┌────────────┴─────────────┐                           │
+//! │                  ▼                          ▼                           │
+//! │         AES-256-GCM key               HMAC-SHA256 key                   │
+//! │  customer_phone = "pii1:…"      customer_phone_hash = hex(HMAC(value))  │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Values are hashed after normalizing (trimmed; emails lowercased), so a
+//! lookup matches however the value was typed. Values without the `pii1:`
+//! prefix are plain text from before encryption and are returned as they
+//! are. Without a key (tests, tools) values are stored in plain text and
+//! the hashes use a fixed key.
+
+use std::fmt;
+use std::sync::Arc;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::{hkdf, hmac};
+
+
+/// Prefix of an encrypted value.
+pub const CIPHERTEXT_PREFIX: &str = "pii1:";
+
+/// Prefix of a wrapped tenant data key.
+const WRAPPED_KEY_PREFIX: &str = "wk1:";
+
+/// Key length in bytes (AES-256).
+pub const KEY_LEN: usize = 32;
+
+/// A PII key or value that cannot be used.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{0}")]
+pub struct PiiError(String);
+
+impl PiiError {
+    fn new(message: impl Into<String>) -> Self {
+        PiiError(message.into())
+    }
+}
+
+type PiiResult<T> = Result<T, PiiError>;
+
+/// The kinds of personal data encrypted at rest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PiiField {
+    Email,
+    Phone,
+}
+
+impl PiiField {
+    fn as_str(self) -> &'static str {
+        match self {
+            PiiField::Email => "email",
+            PiiField::Phone => "phone",
+        }
+    }
+
+    /// The form a value is hashed in.
+    pub fn normalize(self, value: &str) -> String {
+        match self {
+            PiiField::Email => value.trim().to_lowercase(),
+            PiiField::Phone => value.trim().to_string(),
+        }
+    }
+}
+
+// =============================================================================
+// Master Key
+// =============================================================================
+
+/// Key that wraps the tenant data keys. Never stored in the database.
+#[derive(Clone)]
+pub struct MasterKey([u8; KEY_LEN]);
+
+impl MasterKey {
+    /// Parses a key written as 64 hex characters.
+    ///
+    /// ## Errors
+    /// `PiiError` if the text is not a 32-byte hex key.
+    pub fn from_hex(text: &str) -> PiiResult<Self> {
+        let bytes = hex::decode(text.trim())
+            .map_err(|_| PiiError::new("PII master key is not hex"))?;
+        let key = bytes
+            .try_into()
+            .map_err(|_| PiiError::new("PII master key must be 32 bytes"))?;
+        Ok(MasterKey(key))
+    }
+
+    /// A new random key.
+    pub fn generate() -> PiiResult<Self> {
+        Ok(MasterKey(random_key()?))
+    }
+
+    /// The key as 64 hex characters, for writing to a key file.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+
+    /// Encrypts a tenant data key for storage.
+    pub fn wrap_data_key(&self, tenant_id: &str, data_key: &[u8; KEY_LEN]) -> PiiResult<String> {
+        let sealed = seal(&aead_key(&self.0), tenant_id.as_bytes(), data_key)?;
+        Ok(format!("{}{}", WRAPPED_KEY_PREFIX, sealed))
+    }
+
+    /// Decrypts a stored tenant data key.
+    ///
+    /// ## Errors
+    /// `PiiError` if the key was wrapped with another master key.
+    pub fn unwrap_data_key(&self, tenant_id: &str, wrapped: &str) -> PiiResult<[u8; KEY_LEN]> {
+        let sealed = wrapped.strip_prefix(WRAPPED_KEY_PREFIX).ok_or_else(|| {
+            PiiError::new("Unknown PII data key format")
+        })?;
+        let key = open(&aead_key(&self.0), tenant_id.as_bytes(), sealed).map_err(|_| {
+            PiiError::new("PII data key cannot be unwrapped with this master key")
+        })?;
+        key.try_into()
+            .map_err(|_| PiiError::new("PII data key has the wrong length"))
+    }
+}
+
+impl fmt::Debug for MasterKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MasterKey(..)")
+    }
+}
+
+// =============================================================================
+// PII Cipher
+// =============================================================================
+
+/// Encrypts, decrypts and hashes one tenant's personal data.
+#[derive(Clone)]
+pub struct PiiCipher {
+    /// `None`: store plain text (no key configured).
+    aead: Option<Arc<LessSafeKey>>,
+    index: hmac::Key,
+}
+
+impl PiiCipher {
+    /// A cipher using a tenant data key.
+    pub fn new(data_key: &[u8; KEY_LEN]) -> Self {
+        let prk = hkdf::Salt::new(hkdf::HKDF_SHA256, b"titan-pii").extract(data_key);
+        let aead = prk
+            .expand(&[b"encrypt"], &AES_256_GCM)
+            .map(UnboundKey::from)
+            .expect("AES-256-GCM key length is valid for HKDF-SHA256");
+        let index = prk
+            .expand(&[b"index"], hmac::HMAC_SHA256)
+            .map(hmac::Key::from)
+            .expect("HMAC-SHA256 key length is valid for HKDF-SHA256");
+
+        PiiCipher {
+            aead: Some(Arc::new(LessSafeKey::new(aead))),
+            index,
+        }
+    }
+
+    /// A cipher that stores plain text.
+    pub fn disabled() -> Self {
+        PiiCipher {
+            aead: None,
+            index: hmac::Key::new(hmac::HMAC_SHA256, &[0; KEY_LEN]),
+        }
+    }
+
+    /// Whether values are encrypted.
+    pub fn is_enabled(&self) -> bool {
+        self.aead.is_some()
+    }
+
+    /// Encrypts a value for storage.
+    pub fn encrypt(&self, field: PiiField, value: &str) -> PiiResult<String> {
+        match &self.aead {
+            Some(key) => {
+                let sealed = seal(key, field.as_str().as_bytes(), value.as_bytes())?;
+                Ok(format!("{}{}", CIPHERTEXT_PREFIX, sealed))
+            }
+            None => Ok(value.to_string()),
+        }
+    }
+
+    /// Encrypts an optional value for storage.
+    pub fn encrypt_opt(&self, field: PiiField, value: Option<&str>) -> PiiResult<Option<String>> {
+        value.map(|v| self.encrypt(field, v)).transpose()
+    }
+
+    /// Decrypts a stored value; plain text is returned as it is.
+    ///
+    /// ## Errors
+    /// `PiiError` if the value was encrypted with another key, or
+    /// is encrypted and this cipher has no key.
+    pub fn decrypt(&self, field: PiiField, stored: &str) -> PiiResult<String> {
+        let Some(sealed) = stored.strip_prefix(CIPHERTEXT_PREFIX) else {
+            return Ok(stored.to_string());
+        };
+        let key = self.aead.as_ref().ok_or_else(|| {
+            PiiError::new("Encrypted customer data but no PII key is configured")
+        })?;
+        let plain = open(key, field.as_str().as_bytes(), sealed).map_err(|_| {
+            PiiError::new(format!("Cannot decrypt customer {}", field.as_str()))
+        })?;
+        String::from_utf8(plain)
+            .map_err(|_| PiiError::new(format!("Decrypted {} is not text", field.as_str())))
+    }
+
+    /// Decrypts an optional stored value.
+    pub fn decrypt_opt(&self, field: PiiField, stored: Option<String>) -> PiiResult<Option<String>> {
+        stored.map(|v| self.decrypt(field, &v)).transpose()
+    }
+
+    /// The lookup hash of a value (hex HMAC-SHA256 of its normalized form).
+    pub fn blind_index(&self, field: PiiField, value: &str) -> String {
+        let tag = hmac::sign(&self.index, field.normalize(value).as_bytes());
+        hex::encode(tag.as_ref())
+    }
+
+    /// The lookup hash of an optional value.
+    pub fn blind_index_opt(&self, field: PiiField, value: Option<&str>) -> Option<String> {
+        value.map(|v| self.blind_index(field, v))
+    }
+}
+
+impl Default for PiiCipher {
+    fn default() -> Self {
+        PiiCipher::disabled()
+    }
+}
+
+impl fmt::Debug for PiiCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PiiCipher")
+            .field("enabled", &self.is_enabled())
+            .finish()
+    }
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// A random 32-byte key.
+pub fn random_key() -> PiiResult<[u8; KEY_LEN]> {
+    let mut key = [0; KEY_LEN];
+    SystemRandom::new()
+        .fill(&mut key)
+        .map_err(|_| PiiError::new("No system randomness for PII key"))?;
+    Ok(key)
+}
+
+/// An AES-256-GCM key (also used for sync payload keys).
+pub fn aead_key(key: &[u8; KEY_LEN]) -> LessSafeKey {
+    LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key).expect("32-byte AES-256-GCM key"))
+}
+
+/// Encrypts with a random nonce; returns base64(nonce ‖ ciphertext ‖ tag).
+pub fn seal(key: &LessSafeKey, aad: &[u8], plain: &[u8]) -> PiiResult<String> {
+    let mut nonce = [0; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| PiiError::new("No system randomness for PII nonce"))?;
+
+    let mut in_out = plain.to_vec();
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(aad), &mut in_out)
+        .map_err(|_| PiiError::new("PII encryption failed"))?;
+
+    let mut out = nonce.to_vec();
+    out.extend_from_slice(&in_out);
+    Ok(BASE64.encode(out))
+}
+
+/// Reverses [`seal`].
+///
+/// ## Errors
+/// `PiiError` if `sealed` is malformed, or was sealed with another key or
+/// `aad`.
+pub fn open(key: &LessSafeKey, aad: &[u8], sealed: &str) -> PiiResult<Vec<u8>> {
+    let failed = || PiiError::new("PII decryption failed");
+    let bytes = BASE64.decode(sealed).map_err(|_| failed())?;
+    if bytes.len() < NONCE_LEN {
+        return Err(failed());
+    }
+    let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| failed())?;
+
+    let mut in_out = ciphertext.to_vec();
+    let plain = key
+        .open_in_place(nonce, Aad::from(aad), &mut in_out)
+        .map_err(|_| failed())?;
+    Ok(plain.to_vec())
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_round_trip() {
+        let cipher = PiiCipher::new(&random_key().unwrap());
+        let stored = cipher.encrypt(PiiField::Phone, "555-0100").unwrap();
+        assert!(stored.starts_with(CIPHERTEXT_PREFIX));
+        assert_ne!(stored, cipher.encrypt(PiiField::Phone, "555-0100").unwrap());
+        assert_eq!(cipher.decrypt(PiiField::Phone, &stored).unwrap(), "555-0100");
+
+        // Bound to the field, and to the key
+        assert!(cipher.decrypt(PiiField::Email, &stored).is_err());
+        let other = PiiCipher::new(&random_key().unwrap());
+        assert!(other.decrypt(PiiField::Phone, &stored).is_err());
+        assert!(PiiCipher::disabled().decrypt(PiiField::Phone, &stored).is_err());
+
+        // Plain text from before encryption passes through
+        assert_eq!(cipher.decrypt(PiiField::Phone, "555-0199").unwrap(), "555-0199");
+    }
+
+    #[test]
+    fn test_blind_index_normalizes() {
+        let cipher = PiiCipher::new(&random_key().unwrap());
+        assert_eq!(
+            cipher.blind_index(PiiField::Email, " Ann@Example.com"),
+            cipher.blind_index(PiiField::Email, "ann@example.com")
+        );
+        assert_ne!(
+            cipher.blind_index(PiiField::Email, "ann@example.com"),
+            PiiCipher::disabled().blind_index(PiiField::Email, "ann@example.com")
+        );
+    }
+
+    #[test]
+    fn test_wrapped_key_needs_master_and_tenant() {
+        let master = MasterKey::generate().unwrap();
+        let data_key = random_key().unwrap();
+        let wrapped = master.wrap_data_key("t-1", &data_key).unwrap();
+
+        assert_eq!(master.unwrap_data_key("t-1", &wrapped).unwrap(), data_key);
+        assert!(master.unwrap_data_key("t-2", &wrapped).is_err());
+        assert!(MasterKey::generate().unwrap().unwrap_data_key("t-1", &wrapped).is_err());
+
+        let parsed = MasterKey::from_hex(&master.to_hex()).unwrap();
+        assert_eq!(parsed.unwrap_data_key("t-1", &wrapped).unwrap(), data_key);
+        assert!(MasterKey::from_hex("abcd").is_err());
+    }
+}
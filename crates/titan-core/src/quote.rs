@@ -0,0 +1,300 @@
+//! # Quotes (Estimates)
+//!
+//! Types and pure rules for customer-facing quotes: a priced cart saved
+//! with an expiry date that can later be converted into a sale.
+//!
+//! ## Quote Lifecycle
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Quote Lifecycle                                 │
+//! │                                                                         │
+//! │  Cart ──► save as quote ──► OPEN ──────────────► CONVERTED             │
+//! │                              │    convert to sale   (sale_id recorded)  │
+//! │                              │                                          │
+//! │                              ├──► CANCELLED (by staff)                  │
+//! │                              │                                          │
+//! │                              └──► expired (valid_until has passed)      │
+//! │                                   Derived from the clock - never        │
+//! │                                   stored, so terminals with different   │
+//! │                                   sync timing always agree.             │
+//! │                                                                         │
+//! │  Prices are frozen on the quote. Converting creates a sale with the     │
+//! │  quoted unit prices, even if the catalog price has changed since.       │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::{CoreError, ValidationError};
+use crate::validation::ValidationResult;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Default number of days a quote stays valid.
+pub const DEFAULT_QUOTE_VALIDITY_DAYS: i64 = 30;
+
+/// Maximum number of days a quote can stay valid.
+///
+/// ## Business Reason
+/// Prices and stock drift over time; a year is the longest a store should
+/// reasonably honour a quoted price.
+pub const MAX_QUOTE_VALIDITY_DAYS: i64 = 365;
+
+// =============================================================================
+// Quote Status
+// =============================================================================
+
+/// The stored status of a quote.
+///
+/// Expiry is not a stored status; see [`Quote::is_expired`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "lowercase"))]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteStatus {
+    /// Quote can still be converted.
+    #[default]
+    Open,
+    /// Quote has been turned into a sale.
+    Converted,
+    /// Quote was withdrawn by staff.
+    Cancelled,
+}
+
+// =============================================================================
+// Quote
+// =============================================================================
+
+/// A saved, priced estimate for a customer.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Quote {
+    pub id: String,
+    pub tenant_id: String,
+    /// Human-readable number printed on the document.
+    pub quote_number: String,
+    pub status: QuoteStatus,
+    pub customer_name: Option<String>,
+    pub customer_email: Option<String>,
+    pub subtotal_cents: i64,
+    pub tax_cents: i64,
+    pub discount_cents: i64,
+    pub total_cents: i64,
+    pub user_id: String,
+    /// Terminal the quote was created on.
+    pub device_id: String,
+    pub notes: Option<String>,
+    #[ts(as = "String")]
+    pub valid_until: DateTime<Utc>,
+    /// Sale created from this quote (set on conversion).
+    pub converted_sale_id: Option<String>,
+    #[ts(as = "String")]
+    pub created_at: DateTime<Utc>,
+    #[ts(as = "String")]
+    pub updated_at: DateTime<Utc>,
+    pub sync_version: i64,
+}
+
+impl Quote {
+    /// Returns true if the quote's validity window has passed.
+    #[inline]
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now > self.valid_until
+    }
+
+    /// Checks that the quote can be converted into a sale.
+    ///
+    /// ## Errors
+    /// `CoreError::QuoteNotConvertible` if the quote is not open or expired.
+    pub fn ensure_convertible(&self, now: DateTime<Utc>) -> Result<(), CoreError> {
+        let reason = match self.status {
+            QuoteStatus::Converted => Some("already converted"),
+            QuoteStatus::Cancelled => Some("cancelled"),
+            QuoteStatus::Open if self.is_expired(now) => Some("expired"),
+            QuoteStatus::Open => None,
+        };
+
+        match reason {
+            Some(reason) => Err(CoreError::QuoteNotConvertible {
+                quote_id: self.id.clone(),
+                reason: reason.to_string(),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+// =============================================================================
+// Quote Item
+// =============================================================================
+
+/// A line on a quote.
+/// Uses the snapshot pattern so the quoted price survives catalog changes.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct QuoteItem {
+    pub id: String,
+    pub quote_id: String,
+    pub product_id: String,
+    pub sku_snapshot: String,
+    pub name_snapshot: String,
+    /// Quoted unit price in cents (frozen).
+    pub unit_price_cents: i64,
+    /// Tax rate at time of quoting (frozen).
+    pub tax_rate_bps: u32,
+    pub quantity: i64,
+    pub line_total_cents: i64,
+    pub tax_cents: i64,
+    #[ts(as = "String")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// A quote with its lines, as stored in the sync outbox and relayed to
+/// other terminals.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct QuoteDocument {
+    #[serde(flatten)]
+    pub quote: Quote,
+    pub items: Vec<QuoteItem>,
+}
+
+// =============================================================================
+// Rules
+// =============================================================================
+
+/// Computes `valid_until` from a validity window in days.
+///
+/// ## Rules
+/// - Defaults to [`DEFAULT_QUOTE_VALIDITY_DAYS`]
+/// - Must be between 1 and [`MAX_QUOTE_VALIDITY_DAYS`]
+pub fn quote_valid_until(
+    created_at: DateTime<Utc>,
+    validity_days: Option<i64>,
+) -> ValidationResult<DateTime<Utc>> {
+    let days = validity_days.unwrap_or(DEFAULT_QUOTE_VALIDITY_DAYS);
+
+    if !(1..=MAX_QUOTE_VALIDITY_DAYS).contains(&days) {
+        return Err(ValidationError::OutOfRange {
+            field: "validity_days".to_string(),
+            min: 1,
+            max: MAX_QUOTE_VALIDITY_DAYS,
+        });
+    }
+
+    Ok(created_at + Duration::days(days))
+}
+
+/// Validates a customer email address for sending a quote.
+///
+/// Deliberately loose: one `@`, something before it, and a dot in the domain.
+pub fn validate_customer_email(email: &str) -> ValidationResult<()> {
+    let email = email.trim();
+
+    let valid = match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+        }
+        None => false,
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(ValidationError::InvalidFormat {
+            field: "customer_email".to_string(),
+            reason: "must be a valid email address".to_string(),
+        })
+    }
+}
+
+// =============================================================================
+// Unit Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(status: QuoteStatus, valid_until: DateTime<Utc>) -> Quote {
+        let now = Utc::now();
+        Quote {
+            id: "q-1".to_string(),
+            tenant_id: "t".to_string(),
+            quote_number: "Q-0001".to_string(),
+            status,
+            customer_name: None,
+            customer_email: None,
+            subtotal_cents: 1000,
+            tax_cents: 83,
+            discount_cents: 0,
+            total_cents: 1083,
+            user_id: "u".to_string(),
+            device_id: "d".to_string(),
+            notes: None,
+            valid_until,
+            converted_sale_id: None,
+            created_at: now,
+            updated_at: now,
+            sync_version: 1,
+        }
+    }
+
+    #[test]
+    fn test_convertible() {
+        let now = Utc::now();
+        let tomorrow = now + Duration::days(1);
+
+        assert!(quote(QuoteStatus::Open, tomorrow).ensure_convertible(now).is_ok());
+        assert!(quote(QuoteStatus::Open, now - Duration::seconds(1))
+            .ensure_convertible(now)
+            .is_err());
+        assert!(quote(QuoteStatus::Converted, tomorrow).ensure_convertible(now).is_err());
+        assert!(quote(QuoteStatus::Cancelled, tomorrow).ensure_convertible(now).is_err());
+    }
+
+    #[test]
+    fn test_valid_until() {
+        let now = Utc::now();
+        assert_eq!(
+            quote_valid_until(now, None).unwrap(),
+            now + Duration::days(DEFAULT_QUOTE_VALIDITY_DAYS)
+        );
+        assert_eq!(quote_valid_until(now, Some(7)).unwrap(), now + Duration::days(7));
+        assert!(quote_valid_until(now, Some(0)).is_err());
+        assert!(quote_valid_until(now, Some(MAX_QUOTE_VALIDITY_DAYS + 1)).is_err());
+    }
+
+    #[test]
+    fn test_customer_email() {
+        assert!(validate_customer_email("jane@example.com").is_ok());
+        assert!(validate_customer_email(" jane@shop.co.uk ").is_ok());
+        assert!(validate_customer_email("jane").is_err());
+        assert!(validate_customer_email("@example.com").is_err());
+        assert!(validate_customer_email("jane@localhost").is_err());
+        assert!(validate_customer_email("a@b@c.com").is_err());
+    }
+
+    #[test]
+    fn test_document_flattens_quote() {
+        let doc = QuoteDocument {
+            quote: quote(QuoteStatus::Open, Utc::now()),
+            items: vec![],
+        };
+        let json = serde_json::to_value(&doc).unwrap();
+        assert_eq!(json["sync_version"], 1);
+        assert!(json["items"].is_array());
+
+        let back: QuoteDocument = serde_json::from_value(json).unwrap();
+        assert_eq!(back.quote.quote_number, "Q-0001");
+    }
+}
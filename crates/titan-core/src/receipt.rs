@@ -0,0 +1,597 @@
+//! # Receipt Rendering
+//!
+//! Plain-text receipts for the thermal receipt printer. A completed sale
+//! prints in one of four variants, each with its own template: the
+//! original, a gift receipt without prices, a duplicate marked REPRINT,
+//! and a refund receipt for a returnless refund.
+//!
+//! ## Variants
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Receipt Variants                                │
+//! │                                                                         │
+//! │  Original   header, priced lines, totals, payments, fiscal, thank-you   │
+//! │  Gift       *** GIFT RECEIPT ***, header, lines without prices,         │
+//! │             exchange note (no totals, no payments)                      │
+//! │  Reprint    *** REPRINT ***, everything the original shows, the time    │
+//! │             of the reprint, *** REPRINT *** again at the bottom         │
+//! │  Refund     *** REFUND ***, header, one refund: amount, where it went,  │
+//! │             reason, total refunded and what is left on the sale         │
+//! │                                                                         │
+//! │  ReceiptData ──► variant.template() ──► sections ──► text, N columns    │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! A watermark on the data itself (training mode) is printed as a banner
+//! at the top and bottom of every variant. Long product names are cut to
+//! fit the paper; amounts are never cut.
+//!
+//! Under the text every variant prints the receipt number as a Code 128
+//! barcode ([`receipt_barcode`]), so a returned receipt is found by
+//! scanning it instead of typing the number.
+
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::barcode::{Barcode, Symbology};
+use crate::error::ValidationError;
+use crate::money::Money;
+use crate::store_credit::{RefundDestination, SaleRefund};
+use crate::types::PaymentMethod;
+use crate::validation::ValidationResult;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Characters per line on 80 mm paper in the printer's small font.
+pub const DEFAULT_RECEIPT_COLUMNS: usize = 42;
+
+/// Narrowest supported paper (58 mm).
+pub const MIN_RECEIPT_COLUMNS: usize = 32;
+
+/// Widest supported paper (80 mm, condensed font).
+pub const MAX_RECEIPT_COLUMNS: usize = 64;
+
+/// Banner printed on duplicates.
+pub const REPRINT_WATERMARK: &str = "REPRINT";
+
+/// Timestamp format on receipts.
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Printer dots per column of text, for sizing the barcode to the paper.
+const DOTS_PER_COLUMN: usize = 12;
+
+/// Printable width of 80 mm paper, in dots.
+const MAX_PRINT_DOTS: usize = 576;
+
+/// Widest barcode module, in dots.
+const MAX_MODULE_DOTS: usize = 3;
+
+/// Height of the receipt-number barcode, in dots (about 7.5 mm).
+const BARCODE_HEIGHT: usize = 60;
+
+// =============================================================================
+// Variants and Templates
+// =============================================================================
+
+/// Which receipt to print for a sale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiptVariant {
+    /// The customer's receipt, as printed at checkout.
+    #[default]
+    Original,
+    /// For a gift: what was bought, not what it cost.
+    Gift,
+    /// A duplicate of the original, marked as such.
+    Reprint,
+    /// Proof of a refund against the sale.
+    Refund,
+}
+
+/// One block of a receipt template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReceiptSection {
+    /// Centered `*** TEXT ***` line.
+    Banner(&'static str),
+    /// Store name, receipt number, register and time of sale.
+    Header,
+    /// When this copy was printed.
+    PrintedAt,
+    /// Sale lines, with or without prices.
+    Lines { prices: bool },
+    /// Subtotal, tax and total.
+    Totals,
+    /// Tenders and change.
+    Payments,
+    /// The refund being printed and what is left to refund.
+    Refund,
+    /// Fiscal signature, when the sale has one.
+    Fiscal,
+    /// Centered closing line.
+    Footer(&'static str),
+}
+
+/// Template of the customer's receipt.
+pub const ORIGINAL_TEMPLATE: &[ReceiptSection] = &[
+    ReceiptSection::Header,
+    ReceiptSection::Lines { prices: true },
+    ReceiptSection::Totals,
+    ReceiptSection::Payments,
+    ReceiptSection::Fiscal,
+    ReceiptSection::Footer("Thank you for your purchase!"),
+];
+
+/// Template of the gift receipt.
+pub const GIFT_TEMPLATE: &[ReceiptSection] = &[
+    ReceiptSection::Banner("GIFT RECEIPT"),
+    ReceiptSection::Header,
+    ReceiptSection::Lines { prices: false },
+    ReceiptSection::Footer("Present this receipt to exchange or return"),
+];
+
+/// Template of a duplicate receipt.
+pub const REPRINT_TEMPLATE: &[ReceiptSection] = &[
+    ReceiptSection::Banner(REPRINT_WATERMARK),
+    ReceiptSection::Header,
+    ReceiptSection::PrintedAt,
+    ReceiptSection::Lines { prices: true },
+    ReceiptSection::Totals,
+    ReceiptSection::Payments,
+    ReceiptSection::Fiscal,
+    ReceiptSection::Banner(REPRINT_WATERMARK),
+];
+
+/// Template of the refund receipt.
+pub const REFUND_TEMPLATE: &[ReceiptSection] = &[
+    ReceiptSection::Banner("REFUND"),
+    ReceiptSection::Header,
+    ReceiptSection::PrintedAt,
+    ReceiptSection::Refund,
+    ReceiptSection::Footer("Keep this receipt for your records"),
+];
+
+impl ReceiptVariant {
+    /// The sections this variant prints, top to bottom.
+    pub fn template(&self) -> &'static [ReceiptSection] {
+        match self {
+            ReceiptVariant::Original => ORIGINAL_TEMPLATE,
+            ReceiptVariant::Gift => GIFT_TEMPLATE,
+            ReceiptVariant::Reprint => REPRINT_TEMPLATE,
+            ReceiptVariant::Refund => REFUND_TEMPLATE,
+        }
+    }
+}
+
+// =============================================================================
+// Receipt Data
+// =============================================================================
+
+/// One line of the sale as printed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceiptLine {
+    pub name: String,
+    /// SKU at the time of sale; footer campaigns match on it.
+    pub sku: String,
+    pub quantity: i64,
+    pub unit_price_cents: i64,
+    pub line_total_cents: i64,
+}
+
+/// Everything a receipt of a sale can show. Times are printed as given,
+/// so pass them in the store's time zone.
+#[derive(Debug, Clone)]
+pub struct ReceiptData {
+    pub store_name: String,
+    pub receipt_number: String,
+    pub station_number: Option<i64>,
+    pub sold_at: DateTime<FixedOffset>,
+    pub printed_at: DateTime<FixedOffset>,
+    pub lines: Vec<ReceiptLine>,
+    pub subtotal_cents: i64,
+    pub tax_cents: i64,
+    pub total_cents: i64,
+    /// Tenders in the order they were taken.
+    pub payments: Vec<(PaymentMethod, i64)>,
+    pub change_cents: i64,
+    /// The refund a refund receipt is for.
+    pub refund: Option<SaleRefund>,
+    /// Every refund on the sale so far, this one included.
+    pub refunded_cents: i64,
+    pub fiscal_signature: Option<String>,
+    /// Banner for the top and bottom of every variant ("TRAINING").
+    pub watermark: Option<String>,
+}
+
+// =============================================================================
+// Rendering
+// =============================================================================
+
+/// Renders `variant` of a sale's receipt, `columns` characters wide.
+///
+/// ## Errors
+/// - `ValidationError::OutOfRange` for a width outside
+///   [`MIN_RECEIPT_COLUMNS`]..=[`MAX_RECEIPT_COLUMNS`]
+/// - `ValidationError::Required` for a refund receipt without a refund
+pub fn render_receipt(
+    variant: ReceiptVariant,
+    data: &ReceiptData,
+    columns: usize,
+) -> ValidationResult<String> {
+    if !(MIN_RECEIPT_COLUMNS..=MAX_RECEIPT_COLUMNS).contains(&columns) {
+        return Err(ValidationError::OutOfRange {
+            field: "columns".to_string(),
+            min: MIN_RECEIPT_COLUMNS as i64,
+            max: MAX_RECEIPT_COLUMNS as i64,
+        });
+    }
+    if variant == ReceiptVariant::Refund && data.refund.is_none() {
+        return Err(ValidationError::Required {
+            field: "refund".to_string(),
+        });
+    }
+
+    let mut out = Writer::new(columns);
+    if let Some(watermark) = &data.watermark {
+        out.banner(watermark);
+    }
+    for section in variant.template() {
+        render_section(&mut out, *section, data);
+    }
+    if let Some(watermark) = &data.watermark {
+        out.banner(watermark);
+    }
+    Ok(out.into_text())
+}
+
+fn render_section(out: &mut Writer, section: ReceiptSection, data: &ReceiptData) {
+    match section {
+        ReceiptSection::Banner(text) => out.banner(text),
+        ReceiptSection::Header => {
+            out.centered(&data.store_name);
+            out.rule();
+            out.row("Receipt #", &data.receipt_number);
+            if let Some(station) = data.station_number {
+                out.row("Register", &station.to_string());
+            }
+            out.row("Date", &data.sold_at.format(TIME_FORMAT).to_string());
+            out.rule();
+        }
+        ReceiptSection::PrintedAt => {
+            out.row("Printed", &data.printed_at.format(TIME_FORMAT).to_string());
+            out.rule();
+        }
+        ReceiptSection::Lines { prices } => {
+            for line in &data.lines {
+                if prices {
+                    out.row(&line.name, &money(line.line_total_cents));
+                    if line.quantity != 1 {
+                        out.line(&format!(
+                            "  {} x {}",
+                            line.quantity,
+                            money(line.unit_price_cents)
+                        ));
+                    }
+                } else {
+                    out.line(&format!("{} x {}", line.quantity, line.name));
+                }
+            }
+            out.rule();
+        }
+        ReceiptSection::Totals => {
+            out.row("Subtotal", &money(data.subtotal_cents));
+            out.row("Tax", &money(data.tax_cents));
+            out.row("TOTAL", &money(data.total_cents));
+            out.rule();
+        }
+        ReceiptSection::Payments => {
+            for (method, amount_cents) in &data.payments {
+                out.row(payment_label(*method), &money(*amount_cents));
+            }
+            if data.change_cents != 0 {
+                out.row("CHANGE", &money(data.change_cents));
+            }
+            out.rule();
+        }
+        ReceiptSection::Refund => {
+            // Checked in render_receipt
+            let Some(refund) = &data.refund else {
+                return;
+            };
+            out.row("Sale total", &money(data.total_cents));
+            out.row("REFUNDED", &money(refund.amount_cents));
+            out.row("Refunded to", refund_label(refund.destination));
+            if let Some(account) = &refund.store_credit_id {
+                out.row("Credit account", account);
+            }
+            if let Some(reason) = refund.reason.as_deref().filter(|r| !r.trim().is_empty()) {
+                out.line(&format!("Reason: {}", reason.trim()));
+            }
+            out.rule();
+            out.row("Total refunded", &money(data.refunded_cents));
+            out.row(
+                "Left to refund",
+                &money((data.total_cents - data.refunded_cents).max(0)),
+            );
+            out.rule();
+        }
+        ReceiptSection::Fiscal => {
+            if let Some(signature) = &data.fiscal_signature {
+                out.line("Fiscal signature:");
+                let chars: Vec<char> = signature.chars().collect();
+                for chunk in chars.chunks(out.columns) {
+                    out.line(&chunk.iter().collect::<String>());
+                }
+                out.rule();
+            }
+        }
+        ReceiptSection::Footer(text) => out.centered(text),
+    }
+}
+
+fn money(cents: i64) -> String {
+    Money::from_cents(cents).to_string()
+}
+
+fn payment_label(method: PaymentMethod) -> &'static str {
+    match method {
+        PaymentMethod::Cash => "Cash",
+        PaymentMethod::ExternalCard => "Card",
+        PaymentMethod::StoreCredit => "Store credit",
+        PaymentMethod::LoyaltyReward => "Loyalty reward",
+    }
+}
+
+fn refund_label(destination: RefundDestination) -> &'static str {
+    match destination {
+        RefundDestination::Cash => "Cash",
+        RefundDestination::ExternalCard => "Card",
+        RefundDestination::StoreCredit => "Store credit",
+    }
+}
+
+/// Fixed-width text, one receipt line at a time.
+pub(crate) struct Writer {
+    columns: usize,
+    text: String,
+}
+
+impl Writer {
+    pub(crate) fn new(columns: usize) -> Self {
+        Writer {
+            columns,
+            text: String::new(),
+        }
+    }
+
+    pub(crate) fn into_text(self) -> String {
+        self.text
+    }
+
+    pub(crate) fn line(&mut self, text: &str) {
+        let text: String = text
+            .chars()
+            .filter(|c| !c.is_control())
+            .take(self.columns)
+            .collect();
+        self.text.push_str(text.trim_end());
+        self.text.push('\n');
+    }
+
+    pub(crate) fn rule(&mut self) {
+        self.line(&"-".repeat(self.columns));
+    }
+
+    pub(crate) fn centered(&mut self, text: &str) {
+        let width = text.chars().count();
+        let pad = self.columns.saturating_sub(width) / 2;
+        self.line(&format!("{}{}", " ".repeat(pad), text));
+    }
+
+    /// `text` word-wrapped to the paper, each line centred. A word longer
+    /// than a line is split.
+    pub(crate) fn paragraph(&mut self, text: &str) {
+        let mut current = String::new();
+        for word in text.split_whitespace() {
+            let chars: Vec<char> = word.chars().collect();
+            for piece in chars.chunks(self.columns) {
+                let piece: String = piece.iter().collect();
+                let width = current.chars().count();
+                if width > 0 && width + 1 + piece.chars().count() > self.columns {
+                    self.centered(&current);
+                    current.clear();
+                }
+                if !current.is_empty() {
+                    current.push(' ');
+                }
+                current.push_str(&piece);
+            }
+        }
+        if !current.is_empty() {
+            self.centered(&current);
+        }
+    }
+
+    fn banner(&mut self, text: &str) {
+        self.centered(&format!("*** {} ***", text));
+    }
+
+    /// `left` and `right` on one line, `right` flush right; `left` is cut
+    /// to make room.
+    fn row(&mut self, left: &str, right: &str) {
+        let right_width = right.chars().count();
+        let room = self.columns.saturating_sub(right_width + 1);
+        let left: String = left
+            .chars()
+            .filter(|c| !c.is_control())
+            .take(room)
+            .collect();
+        let pad = self
+            .columns
+            .saturating_sub(left.chars().count() + right_width);
+        self.line(&format!("{}{}{}", left, " ".repeat(pad), right));
+    }
+}
+
+// =============================================================================
+// Receipt Barcode
+// =============================================================================
+
+/// ESC/POS commands printing `receipt_number` as a centred Code 128
+/// barcode, with modules as wide as a `columns`-wide receipt allows.
+///
+/// ## Errors
+/// `ValidationError::Required` / `InvalidFormat` for a receipt number
+/// Code 128 cannot hold (empty, or not printable ASCII).
+pub fn receipt_barcode(receipt_number: &str, columns: usize) -> ValidationResult<Vec<u8>> {
+    let barcode = Barcode::encode(Symbology::Code128, receipt_number)?;
+    let quiet = 2 * Symbology::Code128.quiet_zone();
+    let paper_dots = (columns * DOTS_PER_COLUMN).min(MAX_PRINT_DOTS);
+    let module_dots = (paper_dots / (barcode.width + quiet)).clamp(1, MAX_MODULE_DOTS);
+
+    let mut out = vec![0x1B, b'a', 1];
+    out.extend(barcode.render(module_dots, BARCODE_HEIGHT).escpos_raster());
+    out.extend_from_slice(&[0x1B, b'a', 0, b'\n']);
+    Ok(out)
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn data() -> ReceiptData {
+        let tz = FixedOffset::east_opt(5 * 3600).unwrap();
+        ReceiptData {
+            store_name: "Titan Store".to_string(),
+            receipt_number: "R01-0042".to_string(),
+            station_number: Some(1),
+            sold_at: tz.with_ymd_and_hms(2026, 10, 1, 14, 30, 0).unwrap(),
+            printed_at: tz.with_ymd_and_hms(2026, 10, 17, 9, 5, 0).unwrap(),
+            lines: vec![
+                ReceiptLine {
+                    name: "Coca-Cola 330ml".to_string(),
+                    sku: "COKE-330".to_string(),
+                    quantity: 2,
+                    unit_price_cents: 199,
+                    line_total_cents: 398,
+                },
+                ReceiptLine {
+                    name: "Chips Lays Classic Family Size Extra Large Bag".to_string(),
+                    sku: "LAYS-XL".to_string(),
+                    quantity: 1,
+                    unit_price_cents: 249,
+                    line_total_cents: 249,
+                },
+            ],
+            subtotal_cents: 647,
+            tax_cents: 53,
+            total_cents: 700,
+            payments: vec![(PaymentMethod::Cash, 1000)],
+            change_cents: 300,
+            refund: None,
+            refunded_cents: 0,
+            fiscal_signature: None,
+            watermark: None,
+        }
+    }
+
+    #[test]
+    fn test_original_receipt() {
+        let text =
+            render_receipt(ReceiptVariant::Original, &data(), DEFAULT_RECEIPT_COLUMNS).unwrap();
+        assert!(text.contains("Receipt #"));
+        assert!(text.contains("2026-10-01 14:30"));
+        assert!(text.contains("  2 x $1.99"));
+        assert!(text.contains("$7.00"));
+        assert!(text.contains("CHANGE"));
+        assert!(!text.contains("REPRINT"));
+        assert!(text
+            .lines()
+            .all(|l| l.chars().count() <= DEFAULT_RECEIPT_COLUMNS));
+        // Cut names still leave the amount in place
+        assert!(text
+            .lines()
+            .any(|l| l.starts_with("Chips Lays") && l.ends_with("$2.49")));
+    }
+
+    #[test]
+    fn test_gift_receipt_has_no_prices() {
+        let text = render_receipt(ReceiptVariant::Gift, &data(), DEFAULT_RECEIPT_COLUMNS).unwrap();
+        assert!(text.contains("*** GIFT RECEIPT ***"));
+        assert!(text.contains("2 x Coca-Cola 330ml"));
+        assert!(!text.contains('$'));
+        assert!(!text.contains("Cash"));
+    }
+
+    #[test]
+    fn test_reprint_is_marked_top_and_bottom() {
+        let text = render_receipt(ReceiptVariant::Reprint, &data(), MIN_RECEIPT_COLUMNS).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines.first().unwrap().contains("*** REPRINT ***"));
+        assert!(lines.last().unwrap().contains("*** REPRINT ***"));
+        assert!(text.contains("2026-10-17 09:05"));
+        assert!(text.contains("$7.00"));
+    }
+
+    #[test]
+    fn test_refund_receipt() {
+        let mut data = data();
+        assert!(render_receipt(ReceiptVariant::Refund, &data, DEFAULT_RECEIPT_COLUMNS).is_err());
+
+        data.refund = Some(SaleRefund {
+            id: "r-1".to_string(),
+            sale_id: "s-1".to_string(),
+            amount_cents: 250,
+            destination: RefundDestination::StoreCredit,
+            store_credit_id: Some("SC-0007".to_string()),
+            reason: Some("Damaged".to_string()),
+            user_id: "u-1".to_string(),
+            device_id: "pos-1".to_string(),
+            created_at: chrono::Utc::now(),
+        });
+        data.refunded_cents = 400;
+        data.watermark = Some("TRAINING".to_string());
+
+        let text = render_receipt(ReceiptVariant::Refund, &data, DEFAULT_RECEIPT_COLUMNS).unwrap();
+        assert_eq!(text.lines().next().unwrap().trim(), "*** TRAINING ***");
+        assert!(text.contains("*** REFUND ***"));
+        assert!(text.contains("SC-0007"));
+        assert!(text.contains("Reason: Damaged"));
+        assert!(text
+            .lines()
+            .any(|l| l.starts_with("Left to refund") && l.ends_with("$3.00")));
+        assert!(!text.contains("Coca-Cola"));
+    }
+
+    #[test]
+    fn test_receipt_barcode() {
+        // "R01-0042": 8 symbols + start, check, stop = 123 modules + quiet
+        let wide = receipt_barcode("R01-0042", DEFAULT_RECEIPT_COLUMNS).unwrap();
+        assert_eq!(&wide[..3], &[0x1B, b'a', 1]);
+        assert_eq!(&wide[3..6], &[0x1D, b'v', b'0']);
+        // 143 modules at 3 dots: 429 dots, 54 bytes a row
+        assert_eq!(u16::from_le_bytes([wide[7], wide[8]]), 54);
+        assert_eq!(u16::from_le_bytes([wide[9], wide[10]]), 60);
+
+        // 58 mm paper: 384 dots fit 2-dot modules
+        let narrow = receipt_barcode("R01-0042", MIN_RECEIPT_COLUMNS).unwrap();
+        assert_eq!(u16::from_le_bytes([narrow[7], narrow[8]]), 36);
+        assert!(narrow.ends_with(&[0x1B, b'a', 0, b'\n']));
+
+        assert!(receipt_barcode("", DEFAULT_RECEIPT_COLUMNS).is_err());
+    }
+
+    #[test]
+    fn test_width_is_bounded() {
+        assert!(render_receipt(ReceiptVariant::Original, &data(), 20).is_err());
+        assert!(render_receipt(ReceiptVariant::Original, &data(), 80).is_err());
+    }
+}
@@ -0,0 +1,552 @@
+//! # Receipt Footer Campaigns
+//!
+//! Promotions printed under the customer's receipt, set per store in the
+//! cloud back office (the `receipt_campaigns` config value, a JSON array).
+//! Each campaign prints one block: a survey invitation, a coupon for the
+//! next purchase, or a plain message, optionally only for baskets that
+//! match a condition.
+//!
+//! ## Evaluation
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                       Receipt Footer Campaigns                          │
+//! │                                                                         │
+//! │  campaigns (cloud order) ──► active when the sale completed?            │
+//! │                          ──► basket matches? (total, units, SKUs)       │
+//! │                          ──► first MAX_FOOTER_CAMPAIGNS                 │
+//! │                                                                         │
+//! │  survey    message, URL, survey code, QR code of URL?code=…             │
+//! │  coupon    message, coupon code, valid until, Code 128 of the code      │
+//! │  message   message                                                      │
+//! │                                                                         │
+//! │  ──► ESC/POS bytes, printed after the receipt-number barcode            │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Survey and coupon codes are derived from the campaign ID and the receipt
+//! number ([`campaign_code`]), so the same sale always gets the same code
+//! and the code leads back to the sale. Only the original receipt prints
+//! campaigns; gift, reprint and refund receipts do not.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use ts_rs::TS;
+
+use crate::barcode::{Barcode, Symbology};
+use crate::error::ValidationError;
+use crate::receipt::{ReceiptData, Writer, MAX_RECEIPT_COLUMNS, MIN_RECEIPT_COLUMNS};
+use crate::validation::ValidationResult;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Most campaigns printed under one receipt.
+pub const MAX_FOOTER_CAMPAIGNS: usize = 3;
+
+/// Longest campaign message, in characters.
+pub const MAX_CAMPAIGN_MESSAGE_LEN: usize = 200;
+
+/// Longest coupon code prefix.
+pub const MAX_COUPON_PREFIX_LEN: usize = 8;
+
+/// Days a next-purchase coupon is valid for when the campaign does not say.
+pub const DEFAULT_COUPON_VALID_DAYS: i64 = 30;
+
+/// Longest validity of a next-purchase coupon, in days.
+pub const MAX_COUPON_VALID_DAYS: i64 = 365;
+
+/// Characters of a generated survey or coupon code (after the prefix).
+const CODE_LEN: usize = 8;
+
+/// Crockford base32: no I, L, O or U to misread.
+const CODE_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Printer dots per QR module.
+const QR_MODULE_DOTS: usize = 4;
+
+/// Printer dots per Code 128 module.
+const CODE128_MODULE_DOTS: usize = 2;
+
+/// Height of a coupon barcode, in dots.
+const COUPON_BARCODE_HEIGHT: usize = 60;
+
+// =============================================================================
+// Campaigns
+// =============================================================================
+
+/// One footer campaign, as set in the back office.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ReceiptCampaign {
+    /// Unique within the store; part of every code the campaign prints.
+    pub id: String,
+    /// Name for the back office; not printed.
+    #[serde(default)]
+    pub name: String,
+    #[serde(flatten)]
+    pub content: CampaignContent,
+    /// Baskets the campaign prints for (default: every sale).
+    #[serde(default)]
+    pub when: CampaignCondition,
+    /// First moment the campaign runs (default: already running).
+    #[serde(default)]
+    #[ts(as = "Option<String>")]
+    pub starts_at: Option<DateTime<Utc>>,
+    /// Moment the campaign stops (default: runs until removed).
+    #[serde(default)]
+    #[ts(as = "Option<String>")]
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+/// What a campaign prints.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CampaignContent {
+    /// Invitation to an online survey, with a code proving the visit.
+    Survey { message: String, url: String },
+    /// A coupon for the customer's next purchase, with its barcode.
+    Coupon {
+        message: String,
+        /// Start of every code, e.g. "NEXT" (upper case letters and digits).
+        #[serde(default)]
+        code_prefix: String,
+        /// Days after the sale the coupon can be used.
+        #[serde(default = "default_valid_days")]
+        #[ts(type = "number")]
+        valid_days: i64,
+    },
+    /// A line of text, e.g. a recipe tip for a basket with pasta.
+    Message { message: String },
+}
+
+fn default_valid_days() -> i64 {
+    DEFAULT_COUPON_VALID_DAYS
+}
+
+/// Which baskets a campaign prints for. Every condition set must hold.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CampaignCondition {
+    /// Sale total at least this much.
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub min_total_cents: Option<i64>,
+    /// At least this many units across the lines.
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub min_units: Option<i64>,
+    /// The basket holds at least one of these SKUs (empty: any basket).
+    #[serde(default)]
+    pub any_sku: Vec<String>,
+}
+
+impl CampaignCondition {
+    /// Whether the basket of `data` meets the condition.
+    pub fn matches(&self, data: &ReceiptData) -> bool {
+        let units: i64 = data.lines.iter().map(|l| l.quantity).sum();
+        self.min_total_cents
+            .is_none_or(|min| data.total_cents >= min)
+            && self.min_units.is_none_or(|min| units >= min)
+            && (self.any_sku.is_empty()
+                || data.lines.iter().any(|line| {
+                    self.any_sku
+                        .iter()
+                        .any(|sku| sku.trim().eq_ignore_ascii_case(line.sku.trim()))
+                }))
+    }
+}
+
+impl ReceiptCampaign {
+    /// The campaign's message.
+    pub fn message(&self) -> &str {
+        match &self.content {
+            CampaignContent::Survey { message, .. }
+            | CampaignContent::Coupon { message, .. }
+            | CampaignContent::Message { message } => message,
+        }
+    }
+
+    /// Whether the campaign runs at `at`.
+    pub fn is_active(&self, at: DateTime<Utc>) -> bool {
+        self.starts_at.is_none_or(|start| at >= start) && self.ends_at.is_none_or(|end| at < end)
+    }
+
+    /// Checks the campaign can be printed.
+    ///
+    /// ## Errors
+    /// - `ValidationError::Required` for an empty ID, message or survey URL
+    /// - `ValidationError::TooLong` for a message or code prefix too long
+    /// - `ValidationError::InvalidFormat` for a survey URL that is not
+    ///   http(s), a code prefix with other than letters and digits, or an
+    ///   end before the start
+    /// - `ValidationError::OutOfRange` for coupon validity outside
+    ///   1..=[`MAX_COUPON_VALID_DAYS`]
+    pub fn validate(&self) -> ValidationResult<()> {
+        if self.id.trim().is_empty() {
+            return Err(ValidationError::Required {
+                field: "campaign id".to_string(),
+            });
+        }
+        let message = self.message().trim();
+        if message.is_empty() {
+            return Err(ValidationError::Required {
+                field: "message".to_string(),
+            });
+        }
+        if message.chars().count() > MAX_CAMPAIGN_MESSAGE_LEN {
+            return Err(ValidationError::TooLong {
+                field: "message".to_string(),
+                max: MAX_CAMPAIGN_MESSAGE_LEN,
+            });
+        }
+        if let (Some(start), Some(end)) = (self.starts_at, self.ends_at) {
+            if end <= start {
+                return Err(ValidationError::InvalidFormat {
+                    field: "ends_at".to_string(),
+                    reason: "must be after starts_at".to_string(),
+                });
+            }
+        }
+
+        match &self.content {
+            CampaignContent::Survey { url, .. } => {
+                let url = url.trim();
+                if url.is_empty() {
+                    return Err(ValidationError::Required {
+                        field: "url".to_string(),
+                    });
+                }
+                if !(url.starts_with("https://") || url.starts_with("http://"))
+                    || url.contains(char::is_whitespace)
+                {
+                    return Err(ValidationError::InvalidFormat {
+                        field: "url".to_string(),
+                        reason: "must be an http(s) URL".to_string(),
+                    });
+                }
+            }
+            CampaignContent::Coupon {
+                code_prefix,
+                valid_days,
+                ..
+            } => {
+                if code_prefix.chars().count() > MAX_COUPON_PREFIX_LEN {
+                    return Err(ValidationError::TooLong {
+                        field: "code_prefix".to_string(),
+                        max: MAX_COUPON_PREFIX_LEN,
+                    });
+                }
+                if !code_prefix.chars().all(|c| c.is_ascii_alphanumeric()) {
+                    return Err(ValidationError::InvalidFormat {
+                        field: "code_prefix".to_string(),
+                        reason: "letters and digits only".to_string(),
+                    });
+                }
+                if !(1..=MAX_COUPON_VALID_DAYS).contains(valid_days) {
+                    return Err(ValidationError::OutOfRange {
+                        field: "valid_days".to_string(),
+                        min: 1,
+                        max: MAX_COUPON_VALID_DAYS,
+                    });
+                }
+            }
+            CampaignContent::Message { .. } => {}
+        }
+        Ok(())
+    }
+}
+
+/// Campaigns read from a `receipt_campaigns` config value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParsedCampaigns {
+    /// Campaigns that can be printed, in the order given.
+    pub campaigns: Vec<ReceiptCampaign>,
+    /// IDs (or positions) of the entries left out, with why.
+    pub skipped: Vec<(String, String)>,
+}
+
+/// Reads a `receipt_campaigns` config value (a JSON array; empty text is
+/// no campaigns). An entry this build cannot print (an unknown type, a
+/// failed [`ReceiptCampaign::validate`], a repeated ID) is skipped, not
+/// fatal, so one bad campaign does not take down the others.
+///
+/// ## Errors
+/// `ValidationError::InvalidFormat` if the value is not a JSON array.
+pub fn parse_campaigns(json: &str) -> ValidationResult<ParsedCampaigns> {
+    if json.trim().is_empty() {
+        return Ok(ParsedCampaigns::default());
+    }
+    let entries: Vec<serde_json::Value> =
+        serde_json::from_str(json).map_err(|e| ValidationError::InvalidFormat {
+            field: "receipt_campaigns".to_string(),
+            reason: e.to_string(),
+        })?;
+
+    let mut parsed = ParsedCampaigns::default();
+    for (i, entry) in entries.into_iter().enumerate() {
+        let label = entry
+            .get("id")
+            .and_then(|id| id.as_str())
+            .map_or_else(|| format!("#{}", i + 1), str::to_string);
+        let campaign = match serde_json::from_value::<ReceiptCampaign>(entry) {
+            Ok(campaign) => campaign,
+            Err(e) => {
+                parsed.skipped.push((label, e.to_string()));
+                continue;
+            }
+        };
+        if let Err(e) = campaign.validate() {
+            parsed.skipped.push((label, e.to_string()));
+        } else if parsed.campaigns.iter().any(|c| c.id == campaign.id) {
+            parsed.skipped.push((label, "duplicate id".to_string()));
+        } else {
+            parsed.campaigns.push(campaign);
+        }
+    }
+    Ok(parsed)
+}
+
+/// The campaigns that print under the receipt of `data`: active when the
+/// sale completed (`at`) and matching its basket, in the order given, at
+/// most [`MAX_FOOTER_CAMPAIGNS`].
+pub fn select_campaigns<'a>(
+    campaigns: &'a [ReceiptCampaign],
+    data: &ReceiptData,
+    at: DateTime<Utc>,
+) -> Vec<&'a ReceiptCampaign> {
+    campaigns
+        .iter()
+        .filter(|c| c.is_active(at) && c.when.matches(data))
+        .take(MAX_FOOTER_CAMPAIGNS)
+        .collect()
+}
+
+/// The survey or coupon code `campaign_id` prints on `receipt_number`:
+/// [`CODE_LEN`] base32 characters of a hash of both.
+pub fn campaign_code(campaign_id: &str, receipt_number: &str) -> String {
+    let digest = Sha256::new()
+        .chain_update(campaign_id.as_bytes())
+        .chain_update([0])
+        .chain_update(receipt_number.as_bytes())
+        .finalize();
+    // 40 bits, five at a time
+    let bits = digest[..5]
+        .iter()
+        .fold(0u64, |acc, byte| (acc << 8) | u64::from(*byte));
+    (0..CODE_LEN)
+        .rev()
+        .map(|i| char::from(CODE_ALPHABET[((bits >> (i * 5)) & 0x1F) as usize]))
+        .collect()
+}
+
+// =============================================================================
+// Rendering
+// =============================================================================
+
+/// A next-purchase coupon printed under a receipt.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct IssuedCoupon {
+    pub campaign_id: String,
+    pub code: String,
+    /// Last day the coupon can be used.
+    #[ts(as = "String")]
+    pub valid_until: NaiveDate,
+}
+
+/// The campaign footer of one receipt.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CampaignFooter {
+    /// Campaigns printed, top to bottom.
+    pub campaign_ids: Vec<String>,
+    /// Coupons printed.
+    pub coupons: Vec<IssuedCoupon>,
+    /// ESC/POS commands: text and barcodes (empty: nothing to print).
+    pub escpos: Vec<u8>,
+}
+
+/// Renders the campaigns that apply to the sale in `data`, `columns`
+/// characters wide. `completed_at` decides which campaigns are running;
+/// coupon validity counts from the day of the sale.
+///
+/// ## Errors
+/// - `ValidationError::OutOfRange` for a width outside
+///   [`MIN_RECEIPT_COLUMNS`]..=[`MAX_RECEIPT_COLUMNS`]
+/// - `ValidationError::InvalidFormat` for a URL or code a barcode cannot
+///   hold
+pub fn render_campaign_footer(
+    campaigns: &[ReceiptCampaign],
+    data: &ReceiptData,
+    completed_at: DateTime<Utc>,
+    columns: usize,
+) -> ValidationResult<CampaignFooter> {
+    if !(MIN_RECEIPT_COLUMNS..=MAX_RECEIPT_COLUMNS).contains(&columns) {
+        return Err(ValidationError::OutOfRange {
+            field: "columns".to_string(),
+            min: MIN_RECEIPT_COLUMNS as i64,
+            max: MAX_RECEIPT_COLUMNS as i64,
+        });
+    }
+
+    let mut footer = CampaignFooter::default();
+    for campaign in select_campaigns(campaigns, data, completed_at) {
+        let mut out = Writer::new(columns);
+        out.rule();
+        out.paragraph(campaign.message().trim());
+
+        let barcode = match &campaign.content {
+            CampaignContent::Survey { url, .. } => {
+                let code = campaign_code(&campaign.id, &data.receipt_number);
+                let url = url.trim();
+                out.paragraph(url);
+                out.centered(&format!("Survey code: {}", code));
+                let separator = if url.contains('?') { '&' } else { '?' };
+                let link = format!("{}{}code={}", url, separator, code);
+                Some(Barcode::encode(Symbology::Qr, &link)?.render(QR_MODULE_DOTS, 0))
+            }
+            CampaignContent::Coupon {
+                code_prefix,
+                valid_days,
+                ..
+            } => {
+                let code = format!(
+                    "{}{}",
+                    code_prefix.to_ascii_uppercase(),
+                    campaign_code(&campaign.id, &data.receipt_number)
+                );
+                let valid_until = data.sold_at.date_naive() + Duration::days(*valid_days);
+                out.centered(&format!("Code: {}", code));
+                out.centered(&format!("Valid until {}", valid_until.format("%Y-%m-%d")));
+                let bitmap = Barcode::encode(Symbology::Code128, &code)?
+                    .render(CODE128_MODULE_DOTS, COUPON_BARCODE_HEIGHT);
+                footer.coupons.push(IssuedCoupon {
+                    campaign_id: campaign.id.clone(),
+                    code,
+                    valid_until,
+                });
+                Some(bitmap)
+            }
+            CampaignContent::Message { .. } => None,
+        };
+
+        footer.escpos.extend(out.into_text().into_bytes());
+        if let Some(bitmap) = barcode {
+            footer.escpos.extend_from_slice(&[0x1B, b'a', 1]);
+            footer.escpos.extend(bitmap.escpos_raster());
+            footer.escpos.extend_from_slice(&[0x1B, b'a', 0, b'\n']);
+        }
+        footer.campaign_ids.push(campaign.id.clone());
+    }
+    Ok(footer)
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receipt::ReceiptLine;
+    use chrono::{FixedOffset, TimeZone};
+
+    fn data() -> ReceiptData {
+        let tz = FixedOffset::east_opt(5 * 3600).unwrap();
+        let sold_at = tz.with_ymd_and_hms(2026, 10, 1, 14, 30, 0).unwrap();
+        ReceiptData {
+            store_name: "Titan Store".to_string(),
+            receipt_number: "R01-0042".to_string(),
+            station_number: Some(1),
+            sold_at,
+            printed_at: sold_at,
+            lines: vec![ReceiptLine {
+                name: "Spaghetti 500g".to_string(),
+                sku: "PASTA-500".to_string(),
+                quantity: 2,
+                unit_price_cents: 350,
+                line_total_cents: 700,
+            }],
+            subtotal_cents: 700,
+            tax_cents: 0,
+            total_cents: 700,
+            payments: Vec::new(),
+            change_cents: 0,
+            refund: None,
+            refunded_cents: 0,
+            fiscal_signature: None,
+            watermark: None,
+        }
+    }
+
+    const CAMPAIGNS: &str = r#"[
+        {"id": "survey", "type": "survey", "message": "Tell us how we did",
+         "url": "https://example.com/survey"},
+        {"id": "next", "type": "coupon", "message": "$5 off your next visit",
+         "code_prefix": "next", "valid_days": 14, "when": {"min_total_cents": 1000}},
+        {"id": "pasta", "type": "message", "message": "Try our pesto!",
+         "when": {"any_sku": ["pasta-500"]}},
+        {"id": "later", "type": "message", "message": "Spring sale",
+         "starts_at": "2027-03-01T00:00:00Z"},
+        {"id": "bad", "type": "survey", "message": "No URL", "url": "example.com"},
+        {"id": "raffle", "type": "raffle", "message": "Win a car"}
+    ]"#;
+
+    #[test]
+    fn test_parse_skips_what_cannot_print() {
+        let parsed = parse_campaigns(CAMPAIGNS).unwrap();
+        let ids: Vec<&str> = parsed.campaigns.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, ["survey", "next", "pasta", "later"]);
+        let skipped: Vec<&str> = parsed.skipped.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(skipped, ["bad", "raffle"]);
+
+        assert!(parse_campaigns("").unwrap().campaigns.is_empty());
+        assert!(parse_campaigns("{}").is_err());
+    }
+
+    #[test]
+    fn test_footer_follows_basket_and_schedule() {
+        let campaigns = parse_campaigns(CAMPAIGNS).unwrap().campaigns;
+        let completed_at = Utc.with_ymd_and_hms(2026, 10, 1, 9, 30, 0).unwrap();
+        let mut data = data();
+
+        let footer = render_campaign_footer(&campaigns, &data, completed_at, 42).unwrap();
+        // Coupon needs $10; the spring sale has not started
+        assert_eq!(footer.campaign_ids, ["survey", "pasta"]);
+        assert!(footer.coupons.is_empty());
+        let text = String::from_utf8_lossy(&footer.escpos);
+        let code = campaign_code("survey", "R01-0042");
+        assert!(text.contains(&format!("Survey code: {}", code)));
+        assert!(text.contains("Try our pesto!"));
+
+        data.total_cents = 1500;
+        let footer = render_campaign_footer(&campaigns, &data, completed_at, 42).unwrap();
+        assert_eq!(footer.campaign_ids, ["survey", "next", "pasta"]);
+        let coupon = &footer.coupons[0];
+        assert_eq!(
+            coupon.code,
+            format!("NEXT{}", campaign_code("next", "R01-0042"))
+        );
+        assert_eq!(
+            coupon.valid_until,
+            NaiveDate::from_ymd_opt(2026, 10, 15).unwrap()
+        );
+
+        data.lines[0].sku = "RICE-1KG".to_string();
+        let footer = render_campaign_footer(&campaigns, &data, completed_at, 42).unwrap();
+        assert_eq!(footer.campaign_ids, ["survey", "next"]);
+    }
+
+    #[test]
+    fn test_campaign_code_is_stable_per_sale() {
+        let code = campaign_code("next", "R01-0042");
+        assert_eq!(code.len(), CODE_LEN);
+        assert!(code.bytes().all(|b| CODE_ALPHABET.contains(&b)));
+        assert_eq!(code, campaign_code("next", "R01-0042"));
+        assert_ne!(code, campaign_code("next", "R01-0043"));
+        assert_ne!(code, campaign_code("survey", "R01-0042"));
+    }
+}
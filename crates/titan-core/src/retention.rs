@@ -0,0 +1,133 @@
+//! # Local Data Retention
+//!
+//! A terminal does not need every sale it has ever seen: once the cloud has
+//! a sale, the local copy only serves reprints and refunds, which rarely go
+//! back more than a few months. Finished sales past the retention period
+//! are pruned from the terminal's SQLite, leaving a small marker behind so
+//! a search for the receipt can still fetch it from the hub.
+//!
+//! ## Pruning
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Sale Retention                                  │
+//! │                                                                         │
+//! │  sale_retention job (nightly)                                           │
+//! │    cutoff = now - sale_days                                             │
+//! │    prunable: completed or voided, and                                  │
+//! │      rung up here: cloud acked before the cutoff                       │
+//! │      relayed from another register: completed before the cutoff        │
+//! │    kept: drafts, refunded sales, fiscally signed sales                 │
+//! │      and sales with serial/lot numbers or an age check                 │
+//! │       │                                                                 │
+//! │       ▼                                                                 │
+//! │  pruned_sales marker (receipt number, total, dates) ◄── PrunedSale      │
+//! │  sales, sale_items, payments deleted                                   │
+//! │                                                                         │
+//! │  Search for a pruned receipt                                           │
+//! │    ──► hub GET /api/sales/{receipt} ──► restored as a relayed copy     │
+//! │        (takes no stock out), marker removed                            │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::ValidationError;
+use crate::validation::ValidationResult;
+
+/// Days finished sales are kept on a terminal by default.
+pub const DEFAULT_SALE_RETENTION_DAYS: u32 = 90;
+
+/// Fewest days a retention period may be (refunds within a month must not
+/// need the hub).
+pub const MIN_SALE_RETENTION_DAYS: u32 = 30;
+
+/// How long a terminal keeps local data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPolicy {
+    /// Days a finished sale is kept once the cloud has it. `None` keeps
+    /// every sale.
+    pub sale_days: Option<u32>,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        RetentionPolicy {
+            sale_days: Some(DEFAULT_SALE_RETENTION_DAYS),
+        }
+    }
+}
+
+impl RetentionPolicy {
+    /// A policy that keeps everything.
+    pub fn keep_all() -> Self {
+        RetentionPolicy { sale_days: None }
+    }
+
+    /// Sales finished (and cloud acked) before this time are pruned at
+    /// `now`; `None` when nothing is.
+    pub fn sale_cutoff(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.sale_days
+            .map(|days| now - Duration::days(i64::from(days)))
+    }
+
+    /// Validates the policy values.
+    ///
+    /// ## Rules
+    /// - A sale retention period is at least [`MIN_SALE_RETENTION_DAYS`]
+    pub fn validate(&self) -> ValidationResult<()> {
+        match self.sale_days {
+            Some(days) if days < MIN_SALE_RETENTION_DAYS => Err(ValidationError::OutOfRange {
+                field: "sale_days".to_string(),
+                min: i64::from(MIN_SALE_RETENTION_DAYS),
+                max: i64::from(u32::MAX),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The marker left behind for a sale pruned from this terminal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct PrunedSale {
+    pub sale_id: String,
+    pub receipt_number: String,
+    /// Terminal the sale was rung up on.
+    pub device_id: String,
+    #[ts(type = "number")]
+    pub total_cents: i64,
+    #[ts(as = "Option<String>")]
+    pub completed_at: Option<DateTime<Utc>>,
+    #[ts(as = "String")]
+    pub pruned_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sale_cutoff() {
+        let now = Utc::now();
+        let policy = RetentionPolicy::default();
+        assert_eq!(policy.sale_cutoff(now), Some(now - Duration::days(90)));
+        assert_eq!(RetentionPolicy::keep_all().sale_cutoff(now), None);
+    }
+
+    #[test]
+    fn test_retention_period_has_a_floor() {
+        assert!(RetentionPolicy::default().validate().is_ok());
+        assert!(RetentionPolicy::keep_all().validate().is_ok());
+        assert!(RetentionPolicy { sale_days: Some(7) }.validate().is_err());
+        assert!(RetentionPolicy {
+            sale_days: Some(30)
+        }
+        .validate()
+        .is_ok());
+    }
+}
@@ -0,0 +1,539 @@
+//! # Scales
+//!
+//! Weighing loose goods at the register: the hardware contract for a
+//! checkout scale, the per-station setting that picks it, decoding what
+//! scales send back, and the rules a weight must pass before it is sold.
+//!
+//! ## Weighing a Line
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Weighed Sales                                   │
+//! │                                                                         │
+//! │  StationConfig.scale (TITAN_SCALE)                                      │
+//! │       │ ScaleConnection::parse                                          │
+//! │       ▼                                                                 │
+//! │  "serial:<port>" ──► Serial: send 'W', read "ST,GS,+0.455kg" / "0.455KG"│
+//! │  "hid:<path>"    ──► Hid: read a 6-byte USB HID POS scale report        │
+//! │  "none" / unset  ──► None: read_weight fails with CONFIG_ERROR          │
+//! │                                                                         │
+//! │  dyn Scale::read ──► WeightReading { gross 480 g, Stable }              │
+//! │                            │ net_weight(tare 25 g, WeighRules)          │
+//! │                            ▼                                            │
+//! │                      455 g net ── DepartmentKey.price_per_kg_cents 299  │
+//! │                            │ weighed_price_cents                        │
+//! │                            ▼                                            │
+//! │                      line "Loose Apples 0.455 kg @ 2.99/kg", 1.36       │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Drivers do the I/O and live with the app; this module only decodes and
+//! checks weights.
+//!
+//! ## Rules
+//! - Only a settled reading is sold: in motion, over capacity and below
+//!   zero readings are refused
+//! - Tare is 0 to [`MAX_TARE_GRAMS`] and less than the gross weight
+//! - The net weight is at least the minimum weight (legal-for-trade scales
+//!   are not accurate near zero) and at most the scale's capacity
+//! - Weights are whole grams; the price is rounded half up to the cent
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::{CoreResult, ValidationError};
+use crate::validation::ValidationResult;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Byte that asks a serial scale for its weight.
+pub const WEIGHT_REQUEST: u8 = b'W';
+
+/// Smallest net weight sold by default (20 divisions of 2 g).
+pub const DEFAULT_MIN_NET_GRAMS: i64 = 40;
+
+/// Default capacity of a checkout scale.
+pub const DEFAULT_CAPACITY_GRAMS: i64 = 15_000;
+
+/// Heaviest container tare accepted.
+pub const MAX_TARE_GRAMS: i64 = 5_000;
+
+/// Length of a USB HID POS scale report.
+pub const HID_REPORT_LEN: usize = 6;
+
+// =============================================================================
+// Connection
+// =============================================================================
+
+/// How a station's scale is connected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScaleConnection {
+    /// RS-232 or USB serial scale, polled with [`WEIGHT_REQUEST`].
+    Serial { port: String },
+    /// USB HID POS scale, which sends weight reports on its own.
+    Hid { path: String },
+}
+
+impl ScaleConnection {
+    /// Reads the station's scale setting (see the module docs).
+    ///
+    /// Returns `None` when the station has no scale.
+    ///
+    /// ## Errors
+    /// `ValidationError::InvalidFormat` for an unknown setting or one
+    /// without its port or path.
+    pub fn parse(setting: &str) -> ValidationResult<Option<Self>> {
+        let invalid = |reason: &str| ValidationError::InvalidFormat {
+            field: "scale".to_string(),
+            reason: reason.to_string(),
+        };
+        let setting = setting.trim();
+        let (kind, arg) = match setting.split_once(':') {
+            Some((kind, arg)) => (kind, arg.trim()),
+            None => (setting, ""),
+        };
+
+        match kind.to_lowercase().as_str() {
+            "" | "none" => Ok(None),
+            "serial" if !arg.is_empty() => Ok(Some(ScaleConnection::Serial {
+                port: arg.to_string(),
+            })),
+            "hid" if !arg.is_empty() => Ok(Some(ScaleConnection::Hid {
+                path: arg.to_string(),
+            })),
+            "serial" | "hid" => Err(invalid("the scale needs its port, e.g. serial:COM4")),
+            _ => Err(invalid("expected none, serial:<port> or hid:<path>")),
+        }
+    }
+
+    /// Stable name of the connection.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ScaleConnection::Serial { .. } => "serial",
+            ScaleConnection::Hid { .. } => "hid",
+        }
+    }
+
+    /// Device the scale is read from.
+    pub fn device(&self) -> &str {
+        match self {
+            ScaleConnection::Serial { port } => port,
+            ScaleConnection::Hid { path } => path,
+        }
+    }
+}
+
+// =============================================================================
+// Readings
+// =============================================================================
+
+/// State of the scale when it was read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum ScaleStatus {
+    /// Settled; the weight can be sold.
+    Stable,
+    /// Still moving.
+    Motion,
+    /// Heavier than the scale can weigh.
+    OverCapacity,
+    /// Below zero (the scale needs re-zeroing).
+    UnderZero,
+}
+
+/// One reading of the scale.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WeightReading {
+    /// Weight on the platter, container included.
+    #[ts(type = "number")]
+    pub gross_grams: i64,
+    pub status: ScaleStatus,
+    #[ts(as = "String")]
+    pub read_at: DateTime<Utc>,
+}
+
+/// What a scale sent back, decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScaleFrame {
+    pub gross_grams: i64,
+    pub status: ScaleStatus,
+}
+
+impl ScaleFrame {
+    /// The frame as a reading taken at `read_at`.
+    pub fn reading(self, read_at: DateTime<Utc>) -> WeightReading {
+        WeightReading {
+            gross_grams: self.gross_grams,
+            status: self.status,
+            read_at,
+        }
+    }
+}
+
+/// Grams per unit, as a fraction (numerator, denominator).
+fn unit_grams(unit: &str) -> Option<(i128, i128)> {
+    match unit {
+        "g" => Some((1, 1)),
+        "kg" => Some((1_000, 1)),
+        "lb" => Some((45_359_237, 100_000)),
+        "oz" => Some((28_349_523_125, 1_000_000_000)),
+        _ => None,
+    }
+}
+
+/// `mantissa × 10^-decimals` of `unit`, in whole grams (half up).
+///
+/// `None` if the weight does not fit, e.g. a frame with a long fraction.
+fn to_grams(mantissa: i128, decimals: u32, unit: (i128, i128)) -> Option<i64> {
+    let num = mantissa.checked_mul(unit.0)?;
+    let den = 10i128.checked_pow(decimals)?.checked_mul(unit.1)?;
+    let grams = num.checked_abs()?.checked_mul(2)?.checked_add(den)? / den.checked_mul(2)?;
+    i64::try_from(if num < 0 { -grams } else { grams }).ok()
+}
+
+/// Decodes a serial scale's answer to [`WEIGHT_REQUEST`].
+///
+/// Accepts the common ASCII formats: `ST,GS,+0.455kg` (status `ST`
+/// stable, `US` in motion, `OL` over capacity) and a bare `0.455KG`,
+/// `455 g` or `1.003LB`, marked in motion by a leading `?`.
+///
+/// ## Errors
+/// `ValidationError::InvalidFormat` for anything else.
+pub fn parse_serial_frame(frame: &str) -> ValidationResult<ScaleFrame> {
+    let invalid = |reason: &str| ValidationError::InvalidFormat {
+        field: "scale_frame".to_string(),
+        reason: reason.to_string(),
+    };
+    let frame = frame.trim_matches(|c: char| c.is_whitespace() || c.is_ascii_control());
+
+    let (mut status, weight) = match frame.split(',').collect::<Vec<_>>().as_slice() {
+        [header, _, weight] => match header.trim() {
+            "ST" => (ScaleStatus::Stable, *weight),
+            "US" => (ScaleStatus::Motion, *weight),
+            "OL" => return Ok(overload()),
+            _ => return Err(invalid("unknown status header")),
+        },
+        [weight] => match weight.strip_prefix('?') {
+            Some(rest) => (ScaleStatus::Motion, rest),
+            None => (ScaleStatus::Stable, *weight),
+        },
+        _ => return Err(invalid("expected <status>,<kind>,<weight> or <weight>")),
+    };
+
+    let weight = weight.trim().to_lowercase();
+    let split = weight
+        .find(|c: char| c.is_ascii_alphabetic())
+        .ok_or_else(|| invalid("missing unit"))?;
+    let (number, unit) = weight.split_at(split);
+    let unit = unit_grams(unit.trim()).ok_or_else(|| invalid("unit must be g, kg, lb or oz"))?;
+
+    let number: String = number.chars().filter(|c| !c.is_whitespace()).collect();
+    let (int, frac) = number.split_once('.').unwrap_or((&number, ""));
+    let digits = format!("{}{}", int.trim_start_matches('+'), frac);
+    let mantissa: i128 = digits
+        .parse()
+        .map_err(|_| invalid("weight is not a number"))?;
+
+    let decimals = u32::try_from(frac.len()).map_err(|_| invalid("weight is out of range"))?;
+    let gross_grams =
+        to_grams(mantissa, decimals, unit).ok_or_else(|| invalid("weight is out of range"))?;
+    if gross_grams < 0 {
+        status = ScaleStatus::UnderZero;
+    }
+    Ok(ScaleFrame {
+        gross_grams,
+        status,
+    })
+}
+
+/// Decodes a USB HID POS scale report: report ID 3, status, unit,
+/// exponent, then the weight as a little-endian `u16`.
+///
+/// ## Errors
+/// `ValidationError::InvalidFormat` for a short report, another report ID,
+/// a fault status, an unknown unit or a weight out of range.
+pub fn parse_hid_report(report: &[u8]) -> ValidationResult<ScaleFrame> {
+    let invalid = |reason: &str| ValidationError::InvalidFormat {
+        field: "scale_report".to_string(),
+        reason: reason.to_string(),
+    };
+    if report.len() < HID_REPORT_LEN || report[0] != 3 {
+        return Err(invalid("not a scale data report"));
+    }
+
+    let status = match report[1] {
+        2 | 4 => ScaleStatus::Stable,
+        3 => ScaleStatus::Motion,
+        5 => ScaleStatus::UnderZero,
+        6 => return Ok(overload()),
+        _ => return Err(invalid("the scale reports a fault or needs calibration")),
+    };
+    let unit = match report[2] {
+        2 => "g",
+        3 => "kg",
+        11 => "oz",
+        12 => "lb",
+        _ => return Err(invalid("unit must be g, kg, lb or oz")),
+    };
+    let raw = i128::from(u16::from_le_bytes([report[4], report[5]]));
+    let exponent = report[3] as i8;
+
+    let unit = unit_grams(unit).expect("known unit");
+    let gross_grams = if exponent >= 0 {
+        10i128
+            .checked_pow(exponent as u32)
+            .and_then(|scale| raw.checked_mul(scale))
+            .and_then(|weight| to_grams(weight, 0, unit))
+    } else {
+        to_grams(raw, u32::from(exponent.unsigned_abs()), unit)
+    }
+    .ok_or_else(|| invalid("weight is out of range"))?;
+    Ok(ScaleFrame {
+        gross_grams: if status == ScaleStatus::UnderZero {
+            -gross_grams
+        } else {
+            gross_grams
+        },
+        status,
+    })
+}
+
+fn overload() -> ScaleFrame {
+    ScaleFrame {
+        gross_grams: 0,
+        status: ScaleStatus::OverCapacity,
+    }
+}
+
+// =============================================================================
+// Driver Trait
+// =============================================================================
+
+/// A scale driver.
+///
+/// Implementations live with the I/O they need (serial ports, HID
+/// devices).
+///
+/// ## Errors
+/// `CoreError::DeviceFailed` if the scale could not be read or sent
+/// something that is not a weight.
+pub trait Scale: Send + Sync {
+    /// How the scale is connected.
+    fn connection(&self) -> &ScaleConnection;
+
+    /// Reads the weight on the platter.
+    fn read(&self) -> CoreResult<WeightReading>;
+}
+
+// =============================================================================
+// Rules
+// =============================================================================
+
+/// Limits a weight must fall within to be sold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WeighRules {
+    #[ts(type = "number")]
+    pub min_net_grams: i64,
+    #[ts(type = "number")]
+    pub capacity_grams: i64,
+}
+
+impl Default for WeighRules {
+    fn default() -> Self {
+        WeighRules {
+            min_net_grams: DEFAULT_MIN_NET_GRAMS,
+            capacity_grams: DEFAULT_CAPACITY_GRAMS,
+        }
+    }
+}
+
+impl WeighRules {
+    /// Net weight of `reading` less `tare_grams`, checked against the
+    /// rules (see the module docs).
+    ///
+    /// ## Errors
+    /// - `ValidationError::InvalidFormat` for a reading that is not settled
+    /// - `ValidationError::OutOfRange` for a tare or net weight out of range
+    pub fn net_weight(&self, reading: &WeightReading, tare_grams: i64) -> ValidationResult<i64> {
+        let refused = |reason: &str| ValidationError::InvalidFormat {
+            field: "weight".to_string(),
+            reason: reason.to_string(),
+        };
+        match reading.status {
+            ScaleStatus::Stable => {}
+            ScaleStatus::Motion => return Err(refused("the scale has not settled")),
+            ScaleStatus::OverCapacity => return Err(refused("the scale is over capacity")),
+            ScaleStatus::UnderZero => return Err(refused("the scale needs zeroing")),
+        }
+
+        let max_tare = MAX_TARE_GRAMS.min(reading.gross_grams - 1).max(0);
+        if tare_grams < 0 || tare_grams > max_tare {
+            return Err(ValidationError::OutOfRange {
+                field: "tare".to_string(),
+                min: 0,
+                max: max_tare,
+            });
+        }
+
+        let net = reading.gross_grams - tare_grams;
+        if net < self.min_net_grams || reading.gross_grams > self.capacity_grams {
+            return Err(ValidationError::OutOfRange {
+                field: "weight".to_string(),
+                min: self.min_net_grams,
+                max: self.capacity_grams,
+            });
+        }
+        Ok(net)
+    }
+}
+
+/// Price of `net_grams` at `price_per_kg_cents`, rounded half up.
+pub fn weighed_price_cents(net_grams: i64, price_per_kg_cents: i64) -> i64 {
+    ((i128::from(net_grams) * i128::from(price_per_kg_cents) + 500) / 1000) as i64
+}
+
+/// Weight as shown on receipts: kilograms to three places ("0.455 kg").
+pub fn format_kg(grams: i64) -> String {
+    format!("{}.{:03} kg", grams / 1000, grams % 1000)
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(gross_grams: i64, status: ScaleStatus) -> WeightReading {
+        ScaleFrame {
+            gross_grams,
+            status,
+        }
+        .reading(Utc::now())
+    }
+
+    #[test]
+    fn test_parse_scale_settings() {
+        assert_eq!(ScaleConnection::parse("").unwrap(), None);
+        assert_eq!(ScaleConnection::parse("None").unwrap(), None);
+
+        let serial = ScaleConnection::parse("serial: COM4").unwrap().unwrap();
+        assert_eq!(serial.kind(), "serial");
+        assert_eq!(serial.device(), "COM4");
+
+        let hid = ScaleConnection::parse("hid:/dev/hidraw0").unwrap().unwrap();
+        assert_eq!(hid.device(), "/dev/hidraw0");
+
+        assert!(ScaleConnection::parse("serial").is_err());
+        assert!(ScaleConnection::parse("bluetooth:scale").is_err());
+    }
+
+    #[test]
+    fn test_parse_serial_frames() {
+        let frame = parse_serial_frame("ST,GS,+0.455kg\r\n").unwrap();
+        assert_eq!(frame.gross_grams, 455);
+        assert_eq!(frame.status, ScaleStatus::Stable);
+
+        assert_eq!(
+            parse_serial_frame("US,GS,  1.200 kg").unwrap().status,
+            ScaleStatus::Motion
+        );
+        assert_eq!(
+            parse_serial_frame("OL,GS,+---.--kg").unwrap().status,
+            ScaleStatus::OverCapacity
+        );
+        assert_eq!(
+            parse_serial_frame("\x02  455 g\r").unwrap().gross_grams,
+            455
+        );
+        assert_eq!(parse_serial_frame("1.000LB").unwrap().gross_grams, 454);
+        assert_eq!(
+            parse_serial_frame("?0.300KG").unwrap().status,
+            ScaleStatus::Motion
+        );
+        assert_eq!(
+            parse_serial_frame("-0.010kg").unwrap().status,
+            ScaleStatus::UnderZero
+        );
+
+        // Too many places to scale, or too heavy to hold
+        assert!(parse_serial_frame(&format!("0.{}1kg", "0".repeat(40))).is_err());
+        assert!(parse_serial_frame(&format!("{}kg", "9".repeat(38))).is_err());
+
+        assert!(parse_serial_frame("0.455").is_err());
+        assert!(parse_serial_frame("abc kg").is_err());
+        assert!(parse_serial_frame("0.455 stone").is_err());
+    }
+
+    #[test]
+    fn test_parse_hid_reports() {
+        // 455 g, exponent 0
+        let frame = parse_hid_report(&[3, 4, 2, 0, 0xC7, 0x01]).unwrap();
+        assert_eq!(frame.gross_grams, 455);
+        assert_eq!(frame.status, ScaleStatus::Stable);
+
+        // 1.25 lb as 125 × 10^-2
+        let frame = parse_hid_report(&[3, 3, 12, 0xFE, 125, 0]).unwrap();
+        assert_eq!(frame.gross_grams, 567);
+        assert_eq!(frame.status, ScaleStatus::Motion);
+
+        assert_eq!(
+            parse_hid_report(&[3, 6, 2, 0, 0, 0]).unwrap().status,
+            ScaleStatus::OverCapacity
+        );
+        assert!(parse_hid_report(&[3, 1, 2, 0, 0, 0]).is_err());
+        assert!(parse_hid_report(&[4, 4, 2, 0, 0, 0]).is_err());
+        assert!(parse_hid_report(&[3, 4, 2]).is_err());
+
+        // Exponents of +127 and -128 overflow rather than panic
+        assert!(parse_hid_report(&[3, 4, 2, 0x7F, 0xC7, 0x01]).is_err());
+        assert!(parse_hid_report(&[3, 4, 2, 0x80, 0xC7, 0x01]).is_err());
+        assert!(parse_hid_report(&[3, 4, 2, 38, 0xFF, 0xFF]).is_err());
+    }
+
+    #[test]
+    fn test_net_weight_rules() {
+        let rules = WeighRules::default();
+        assert_eq!(
+            rules
+                .net_weight(&reading(480, ScaleStatus::Stable), 25)
+                .unwrap(),
+            455
+        );
+
+        assert!(rules
+            .net_weight(&reading(480, ScaleStatus::Motion), 0)
+            .is_err());
+        assert!(rules
+            .net_weight(&reading(0, ScaleStatus::OverCapacity), 0)
+            .is_err());
+        assert!(rules
+            .net_weight(&reading(480, ScaleStatus::Stable), -1)
+            .is_err());
+        assert!(rules
+            .net_weight(&reading(480, ScaleStatus::Stable), 480)
+            .is_err());
+        // Below the minimum once the container is taken off
+        assert!(rules
+            .net_weight(&reading(60, ScaleStatus::Stable), 25)
+            .is_err());
+        assert!(rules
+            .net_weight(&reading(15_001, ScaleStatus::Stable), 0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_weighed_price_and_format() {
+        assert_eq!(weighed_price_cents(455, 299), 136); // 136.045
+        assert_eq!(weighed_price_cents(500, 299), 150); // 149.5 rounds up
+        assert_eq!(weighed_price_cents(1000, 299), 299);
+        assert_eq!(format_kg(455), "0.455 kg");
+        assert_eq!(format_kg(12_050), "12.050 kg");
+    }
+}
@@ -0,0 +1,351 @@
+//! # Job Schedules
+//!
+//! Cron-style schedules and the records kept for background jobs on a
+//! terminal (maintenance, price activation, reconciliation, backups).
+//!
+//! ## Schedule Syntax
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Cron Schedule                                    │
+//! │                                                                         │
+//! │   ┌──────── minute        0-59                                          │
+//! │   │ ┌────── hour          0-23                                          │
+//! │   │ │ ┌──── day of month  1-31                                          │
+//! │   │ │ │ ┌── month         1-12                                          │
+//! │   │ │ │ │ ┌ day of week   0-6 (0 or 7 = Sunday)                         │
+//! │   30 3 * * *        every day at 03:30                                  │
+//! │   */15 8-20 * * 1-5 every 15 minutes, 08:00-20:45, Monday to Friday     │
+//! │                                                                         │
+//! │  Fields take *, n, a-b, lists (a,b) and steps (*/n, a-b/n).             │
+//! │  @hourly, @daily, @weekly and @monthly are shorthands.                  │
+//! │  If both day fields are restricted, a day matching either runs.        │
+//! │  Times are the store's local time (a fixed offset).                    │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Failure Alerts
+//! A job's consecutive failures are counted; when the count reaches
+//! [`JOB_FAILURE_ALERT_THRESHOLD`] the terminal raises an alert once. A
+//! successful run resets the count.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::ValidationError;
+
+/// Consecutive failures of a job that raise an alert.
+pub const JOB_FAILURE_ALERT_THRESHOLD: i64 = 3;
+
+/// Days of job run history kept.
+pub const JOB_RUN_RETENTION_DAYS: u32 = 30;
+
+/// Days searched for a schedule's next run (covers 29 February).
+const MAX_SEARCH_DAYS: u32 = 366 * 5;
+
+// =============================================================================
+// Cron Schedule
+// =============================================================================
+
+/// A parsed cron expression. Each field is a bit set of allowed values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+impl CronSchedule {
+    /// Whether the schedule allows a run on `date`.
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if !has(self.months, date.month()) {
+            return false;
+        }
+        let day = has(self.days, date.day());
+        let weekday = has(self.weekdays, date.weekday().num_days_from_sunday());
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            (true, false) => day,
+            (false, true) => weekday,
+            (false, false) => true,
+        }
+    }
+
+    /// The first run strictly after `after`, in the store's local time at
+    /// `offset`. `None` if the schedule never fires (e.g. 31 February).
+    pub fn next_after(&self, after: DateTime<Utc>, offset: FixedOffset) -> Option<DateTime<Utc>> {
+        let local = after.with_timezone(&offset).naive_local();
+        let start = (local + Duration::minutes(1))
+            .with_second(0)?
+            .with_nanosecond(0)?;
+
+        let mut date = start.date();
+        for _ in 0..MAX_SEARCH_DAYS {
+            if self.matches_date(date) {
+                let first_day = date == start.date();
+                let from_hour = if first_day { start.hour() } else { 0 };
+                for hour in (from_hour..24).filter(|h| has(self.hours, *h)) {
+                    let from_minute = if first_day && hour == start.hour() {
+                        start.minute()
+                    } else {
+                        0
+                    };
+                    if let Some(minute) = (from_minute..60).find(|m| has(self.minutes, *m)) {
+                        let run: NaiveDateTime = date.and_hms_opt(hour, minute, 0)?;
+                        return run
+                            .and_local_timezone(offset)
+                            .single()
+                            .map(|t| t.with_timezone(&Utc));
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expr = match s.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(invalid("expected 5 fields: minute hour day month weekday"));
+        };
+
+        let mut weekdays = parse_field(weekday, 0, 7)?;
+        // 7 is Sunday too
+        if has(weekdays, 7) {
+            weekdays = (weekdays & !(1 << 7)) | 1;
+        }
+
+        Ok(CronSchedule {
+            minutes: parse_field(minute, 0, 59)?,
+            hours: parse_field(hour, 0, 23)?,
+            days: parse_field(day, 1, 31)?,
+            months: parse_field(month, 1, 12)?,
+            weekdays,
+            days_restricted: day != "*",
+            weekdays_restricted: weekday != "*",
+        })
+    }
+}
+
+fn has(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+fn invalid(reason: impl Into<String>) -> ValidationError {
+    ValidationError::InvalidFormat {
+        field: "schedule".to_string(),
+        reason: reason.into(),
+    }
+}
+
+/// Parses one cron field into a bit set of values in `min..=max`.
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, ValidationError> {
+    let number = |s: &str| -> Result<u32, ValidationError> {
+        let n: u32 = s
+            .parse()
+            .map_err(|_| invalid(format!("'{}' is not a number", s)))?;
+        if n < min || n > max {
+            return Err(invalid(format!("{} is outside {}-{}", n, min, max)));
+        }
+        Ok(n)
+    };
+
+    let mut set = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| invalid(format!("'{}' has an invalid step", part)))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (from, to) = match range {
+            "*" => (min, max),
+            r => match r.split_once('-') {
+                Some((a, b)) => (number(a)?, number(b)?),
+                // "5/10" runs from 5 to the end of the range
+                None if step > 1 => (number(r)?, max),
+                None => {
+                    let n = number(r)?;
+                    (n, n)
+                }
+            },
+        };
+        if from > to {
+            return Err(invalid(format!("'{}' is a backwards range", part)));
+        }
+        for value in (from..=to).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+// =============================================================================
+// Job Records
+// =============================================================================
+
+/// Outcome of a job run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "lowercase"))]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum JobRunStatus {
+    /// Still running (or the app exited mid-run).
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// A background job's definition and latest state.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ScheduledJob {
+    /// Stable job name, e.g. "outbox_cleanup".
+    pub name: String,
+    /// Cron expression (see [`CronSchedule`]).
+    pub schedule: String,
+    pub enabled: bool,
+    #[ts(as = "Option<String>")]
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_status: Option<JobRunStatus>,
+    /// When the scheduler will next run the job.
+    #[ts(as = "Option<String>")]
+    pub next_run_at: Option<DateTime<Utc>>,
+    /// Failed runs since the last success.
+    pub consecutive_failures: i64,
+    #[ts(as = "String")]
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One run of a background job.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct JobRun {
+    pub id: i64,
+    pub job_name: String,
+    pub status: JobRunStatus,
+    pub error: Option<String>,
+    #[ts(as = "String")]
+    pub started_at: DateTime<Utc>,
+    #[ts(as = "Option<String>")]
+    pub finished_at: Option<DateTime<Utc>>,
+    pub duration_ms: Option<i64>,
+}
+
+/// Whether a failure that brought the count to `consecutive_failures`
+/// raises an alert. Fires once per failure streak.
+pub fn raises_alert(consecutive_failures: i64) -> bool {
+    consecutive_failures == JOB_FAILURE_ALERT_THRESHOLD
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn utc() -> FixedOffset {
+        FixedOffset::east_opt(0).unwrap()
+    }
+
+    fn at(d: u32, h: u32, m: u32) -> DateTime<Utc> {
+        // March 2026: the 1st is a Sunday
+        Utc.with_ymd_and_hms(2026, 3, d, h, m, 0).unwrap()
+    }
+
+    fn next(expr: &str, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        expr.parse::<CronSchedule>()
+            .unwrap()
+            .next_after(after, utc())
+    }
+
+    #[test]
+    fn test_daily_schedule() {
+        assert_eq!(next("30 3 * * *", at(10, 1, 0)), Some(at(10, 3, 30)));
+        assert_eq!(next("30 3 * * *", at(10, 3, 30)), Some(at(11, 3, 30)));
+        assert_eq!(next("@daily", at(10, 12, 0)), Some(at(11, 0, 0)));
+    }
+
+    #[test]
+    fn test_steps_ranges_and_weekdays() {
+        let expr = "*/15 8-20 * * 1-5";
+        assert_eq!(next(expr, at(10, 8, 7)), Some(at(10, 8, 15)));
+        assert_eq!(next(expr, at(10, 20, 45)), Some(at(11, 8, 0)));
+        // Friday evening to Monday morning
+        assert_eq!(next(expr, at(13, 21, 0)), Some(at(16, 8, 0)));
+        // 7 is Sunday
+        assert_eq!(next("0 9 * * 7", at(2, 0, 0)), Some(at(8, 9, 0)));
+    }
+
+    #[test]
+    fn test_day_fields_match_either() {
+        // The 20th (a Friday) or any Monday
+        let expr = "0 0 20 * 1";
+        assert_eq!(next(expr, at(17, 0, 0)), Some(at(20, 0, 0)));
+        assert_eq!(next(expr, at(20, 0, 0)), Some(at(23, 0, 0)));
+    }
+
+    #[test]
+    fn test_local_offset() {
+        let karachi = FixedOffset::east_opt(5 * 3600).unwrap();
+        let schedule: CronSchedule = "0 2 * * *".parse().unwrap();
+        // 02:00 local is 21:00 UTC the day before
+        assert_eq!(
+            schedule.next_after(at(10, 12, 0), karachi),
+            Some(at(10, 21, 0))
+        );
+    }
+
+    #[test]
+    fn test_impossible_schedule() {
+        assert_eq!(next("0 0 31 2 *", at(1, 0, 0)), None);
+    }
+
+    #[test]
+    fn test_invalid_schedules() {
+        for expr in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+        ] {
+            assert!(expr.parse::<CronSchedule>().is_err(), "{expr:?}");
+        }
+    }
+
+    #[test]
+    fn test_raises_alert_once_per_streak() {
+        assert!(!raises_alert(JOB_FAILURE_ALERT_THRESHOLD - 1));
+        assert!(raises_alert(JOB_FAILURE_ALERT_THRESHOLD));
+        assert!(!raises_alert(JOB_FAILURE_ALERT_THRESHOLD + 1));
+    }
+}
@@ -0,0 +1,182 @@
+//! # Stations
+//!
+//! A store with several registers needs to tell them apart on paper and in
+//! reports: "register 3" is what staff say and what the Z-report is run
+//! for. The station is configured per terminal, stamped on every sale, and
+//! its receipt prefix starts every receipt number it prints. It also names
+//! the terminal's `device_id` (`pos-03`), which its sales, till sessions
+//! and Z reports are filed under.
+//!
+//! ## Station Identity
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Station Config                                  │
+//! │                                                                         │
+//! │  StationConfig { number: 3, receipt_prefix: "R03",                      │
+//! │                  default_printer, default_cash_drawer, scale,           │
+//! │                  line_display }                                         │
+//! │                                                                         │
+//! │  sale ──► sales.station_number = 3                                      │
+//! │       ──► receipt_number = "R03-261017-121500-0042"                     │
+//! │                                                                         │
+//! │  reports(from, to, station: Some(3)) ──► register 3 only                │
+//! │  reports(from, to, station: None)    ──► whole store                    │
+//! │                                                                         │
+//! │  device_id = "pos-03": a replacement PC configured as station 3         │
+//! │  carries on as register 3, Z sequence included.                         │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Rules
+//! - Station numbers run from 1 to `MAX_STATION_NUMBER`
+//! - A receipt prefix is 1-8 upper case letters or digits, so receipt
+//!   numbers stay short and unambiguous on printed receipts
+//! - Without a configured prefix the station uses `R` and its number
+//!   (`R03`)
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::ValidationError;
+use crate::validation::ValidationResult;
+
+/// Highest station number.
+pub const MAX_STATION_NUMBER: u32 = 999;
+
+/// Longest receipt prefix.
+pub const MAX_RECEIPT_PREFIX_LEN: usize = 8;
+
+/// Register identity of this terminal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct StationConfig {
+    /// Register number staff and reports know the terminal by.
+    pub number: u32,
+    /// Start of every receipt number printed here.
+    pub receipt_prefix: String,
+    /// Printer receipts go to unless another is chosen.
+    pub default_printer: Option<String>,
+    /// Cash drawer opened for cash tenders.
+    pub default_cash_drawer: Option<String>,
+    /// Scale loose goods are weighed on.
+    #[serde(default)]
+    pub scale: Option<String>,
+    /// Pole display facing the customer.
+    #[serde(default)]
+    pub line_display: Option<String>,
+}
+
+impl StationConfig {
+    /// Station `number` with the default prefix and no devices.
+    pub fn new(number: u32) -> Self {
+        StationConfig {
+            number,
+            receipt_prefix: default_receipt_prefix(number),
+            default_printer: None,
+            default_cash_drawer: None,
+            scale: None,
+            line_display: None,
+        }
+    }
+
+    /// Validates the station number and receipt prefix.
+    pub fn validate(&self) -> ValidationResult<()> {
+        if self.number == 0 || self.number > MAX_STATION_NUMBER {
+            return Err(ValidationError::OutOfRange {
+                field: "station_number".to_string(),
+                min: 1,
+                max: i64::from(MAX_STATION_NUMBER),
+            });
+        }
+        validate_receipt_prefix(&self.receipt_prefix)
+    }
+
+    /// Receipt number for a sale: the prefix, then `serial`.
+    pub fn receipt_number(&self, serial: &str) -> String {
+        format!("{}-{}", self.receipt_prefix, serial)
+    }
+
+    /// Terminal ID the station files its sales, till sessions and Z
+    /// reports under (`pos-03` for station 3).
+    pub fn device_id(&self) -> String {
+        format!("pos-{:02}", self.number)
+    }
+
+    /// Station number as stored on sales.
+    pub fn sale_station(&self) -> Option<i64> {
+        Some(i64::from(self.number))
+    }
+}
+
+impl Default for StationConfig {
+    /// Station 1 (`R01`), for single-register stores.
+    fn default() -> Self {
+        StationConfig::new(1)
+    }
+}
+
+/// `R` and the station number, at least two digits (`R03`, `R112`).
+pub fn default_receipt_prefix(number: u32) -> String {
+    format!("R{:02}", number)
+}
+
+/// Checks a receipt prefix: 1-8 upper case letters or digits.
+pub fn validate_receipt_prefix(prefix: &str) -> ValidationResult<()> {
+    if prefix.is_empty() {
+        return Err(ValidationError::Required {
+            field: "receipt_prefix".to_string(),
+        });
+    }
+    if prefix.len() > MAX_RECEIPT_PREFIX_LEN {
+        return Err(ValidationError::TooLong {
+            field: "receipt_prefix".to_string(),
+            max: MAX_RECEIPT_PREFIX_LEN,
+        });
+    }
+    if !prefix
+        .chars()
+        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+    {
+        return Err(ValidationError::InvalidFormat {
+            field: "receipt_prefix".to_string(),
+            reason: "use upper case letters and digits only".to_string(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_prefix_and_receipt_number() {
+        let station = StationConfig::new(3);
+        assert_eq!(station.receipt_prefix, "R03");
+        assert_eq!(
+            station.receipt_number("261017-121500-0042"),
+            "R03-261017-121500-0042"
+        );
+        assert_eq!(station.sale_station(), Some(3));
+        assert_eq!(station.device_id(), "pos-03");
+        assert_eq!(StationConfig::default().device_id(), "pos-01");
+        assert_eq!(default_receipt_prefix(112), "R112");
+        assert!(station.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(StationConfig::new(0).validate().is_err());
+        assert!(StationConfig::new(MAX_STATION_NUMBER + 1)
+            .validate()
+            .is_err());
+
+        let mut station = StationConfig::new(2);
+        for bad in ["", "r02", "TILL-2", "ABCDEFGHI"] {
+            station.receipt_prefix = bad.to_string();
+            assert!(station.validate().is_err(), "{bad:?} accepted");
+        }
+        station.receipt_prefix = "BAR2".to_string();
+        assert!(station.validate().is_ok());
+    }
+}
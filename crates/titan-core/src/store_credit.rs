@@ -0,0 +1,277 @@
+//! # Store Credit
+//!
+//! Types and pure rules for store credit: a customer-linked balance that is
+//! issued as a refund destination and spent as a payment tender.
+//!
+//! ## Ledger Model
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Store Credit Ledger                              │
+//! │                                                                         │
+//! │  StoreCreditAccount (SC-…, customer name/phone)                         │
+//! │        │                                                                │
+//! │        ├── Issue   +2500   (returnless refund on sale R-1)              │
+//! │        ├── Redeem  -1000   (tender on sale R-7, store A)                │
+//! │        └── Redeem   -500   (tender on sale R-9, store B)                │
+//! │                                                                         │
+//! │  balance = Σ entries = 1000                                             │
+//! │                                                                         │
+//! │  Entries are append-only and keyed by UUID, so copies of an account     │
+//! │  merge by union on every terminal and store: no entry is ever lost or   │
+//! │  counted twice, whatever order they arrive in.                          │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Double-Spend Protection
+//! A redemption is only written after the balance has been checked against
+//! the ledger that every terminal in the store writes through: the hub's
+//! copy when sync is running, the local copy on a standalone terminal. The
+//! check and the write happen in one transaction, so two terminals cannot
+//! both spend the last of a balance while the store is offline from the
+//! cloud.
+//!
+//! Across stores the ledger converges through the cloud. A balance spent in
+//! two stores while both were offline from the cloud shows up as a negative
+//! balance once the entries meet; it is reported, not prevented.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::{CoreError, CoreResult, ValidationError};
+use crate::validation::ValidationResult;
+
+// =============================================================================
+// Entry Kind
+// =============================================================================
+
+/// The kind of a store credit ledger entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "snake_case"))]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum StoreCreditEntryKind {
+    /// Credit issued to the customer (positive amount).
+    Issue,
+    /// Credit spent as a tender (negative amount).
+    Redeem,
+}
+
+impl StoreCreditEntryKind {
+    /// Name as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StoreCreditEntryKind::Issue => "issue",
+            StoreCreditEntryKind::Redeem => "redeem",
+        }
+    }
+}
+
+// =============================================================================
+// Account & Entries
+// =============================================================================
+
+/// A customer's store credit account.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct StoreCreditAccount {
+    pub id: String,
+    pub tenant_id: String,
+    /// Number printed on the credit slip; what the cashier scans or types.
+    pub credit_number: String,
+    pub customer_name: String,
+    pub customer_phone: Option<String>,
+    #[ts(as = "String")]
+    pub created_at: DateTime<Utc>,
+    #[ts(as = "String")]
+    pub updated_at: DateTime<Utc>,
+    pub sync_version: i64,
+}
+
+/// A single movement on a store credit account.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct StoreCreditEntry {
+    pub id: String,
+    pub account_id: String,
+    pub kind: StoreCreditEntryKind,
+    /// Signed: positive for issue, negative for redeem.
+    pub amount_cents: i64,
+    /// Sale the credit was refunded from or spent on.
+    pub sale_id: Option<String>,
+    pub device_id: String,
+    #[ts(as = "String")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// An account with its full ledger, as synced between terminals and stores.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct StoreCreditDocument {
+    #[serde(flatten)]
+    pub account: StoreCreditAccount,
+    pub entries: Vec<StoreCreditEntry>,
+}
+
+impl StoreCreditDocument {
+    /// Current balance: the sum of all entries.
+    pub fn balance_cents(&self) -> i64 {
+        self.entries.iter().map(|e| e.amount_cents).sum()
+    }
+}
+
+// =============================================================================
+// Refunds
+// =============================================================================
+
+/// Where the money from a refund goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "snake_case"))]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum RefundDestination {
+    /// Paid out of the drawer.
+    Cash,
+    /// Credited back to the card on the external terminal.
+    ExternalCard,
+    /// Issued as store credit.
+    StoreCredit,
+}
+
+/// A refund against a completed sale.
+///
+/// Refunds here are returnless: the customer keeps the goods, so stock is
+/// not touched.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SaleRefund {
+    pub id: String,
+    pub sale_id: String,
+    pub amount_cents: i64,
+    pub destination: RefundDestination,
+    /// Account credited when `destination` is `StoreCredit`.
+    pub store_credit_id: Option<String>,
+    pub reason: Option<String>,
+    pub user_id: String,
+    pub device_id: String,
+    #[ts(as = "String")]
+    pub created_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// Rules
+// =============================================================================
+
+/// Validates a refund amount against what is left to refund on the sale.
+///
+/// ## Rules
+/// - Amount must be positive
+/// - Total refunds may not exceed the sale total
+pub fn validate_refund_amount(
+    amount_cents: i64,
+    sale_total_cents: i64,
+    already_refunded_cents: i64,
+) -> ValidationResult<()> {
+    if amount_cents <= 0 {
+        return Err(ValidationError::MustBePositive {
+            field: "amount".to_string(),
+        });
+    }
+    let refundable = (sale_total_cents - already_refunded_cents).max(0);
+    if amount_cents > refundable {
+        return Err(ValidationError::OutOfRange {
+            field: "amount".to_string(),
+            min: 1,
+            max: refundable,
+        });
+    }
+    Ok(())
+}
+
+/// Checks that a balance covers a redemption.
+///
+/// ## Errors
+/// `CoreError::InsufficientStoreCredit` if the balance is too low.
+pub fn check_redemption(
+    credit_number: &str,
+    amount_cents: i64,
+    balance_cents: i64,
+) -> CoreResult<()> {
+    if amount_cents <= 0 {
+        return Err(ValidationError::MustBePositive {
+            field: "amount".to_string(),
+        }
+        .into());
+    }
+    if amount_cents > balance_cents {
+        return Err(CoreError::InsufficientStoreCredit {
+            credit_number: credit_number.to_string(),
+            available_cents: balance_cents,
+            requested_cents: amount_cents,
+        });
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Unit Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, kind: StoreCreditEntryKind, amount_cents: i64) -> StoreCreditEntry {
+        StoreCreditEntry {
+            id: id.to_string(),
+            account_id: "sc1".to_string(),
+            kind,
+            amount_cents,
+            sale_id: None,
+            device_id: "pos-01".to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_balance_is_sum_of_entries() {
+        let doc = StoreCreditDocument {
+            account: StoreCreditAccount {
+                id: "sc1".to_string(),
+                tenant_id: "t".to_string(),
+                credit_number: "SC-1".to_string(),
+                customer_name: "Ada".to_string(),
+                customer_phone: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+                sync_version: 1,
+            },
+            entries: vec![
+                entry("e1", StoreCreditEntryKind::Issue, 2500),
+                entry("e2", StoreCreditEntryKind::Redeem, -1000),
+                entry("e3", StoreCreditEntryKind::Redeem, -500),
+            ],
+        };
+        assert_eq!(doc.balance_cents(), 1000);
+    }
+
+    #[test]
+    fn test_refund_amount_limits() {
+        assert!(validate_refund_amount(500, 1000, 0).is_ok());
+        assert!(validate_refund_amount(500, 1000, 500).is_ok());
+        assert!(validate_refund_amount(501, 1000, 500).is_err());
+        assert!(validate_refund_amount(0, 1000, 0).is_err());
+    }
+
+    #[test]
+    fn test_redemption_needs_balance() {
+        assert!(check_redemption("SC-1", 1000, 1000).is_ok());
+        assert!(matches!(
+            check_redemption("SC-1", 1001, 1000),
+            Err(CoreError::InsufficientStoreCredit { available_cents: 1000, .. })
+        ));
+        assert!(check_redemption("SC-1", 0, 1000).is_err());
+    }
+}
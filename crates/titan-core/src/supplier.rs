@@ -0,0 +1,423 @@
+//! # Suppliers and Costs
+//!
+//! Who a store buys from, what each supplier charges per product and
+//! under which of its own codes, and what the goods on the shelf cost:
+//! the last cost paid and the weighted average cost, both maintained when
+//! stock is received.
+//!
+//! ## Cost Maintenance
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                      Costs at Receiving                                 │
+//! │                                                                         │
+//! │  ACME Beverages   COKE-330  supplier SKU "AC-7781"  cost 0.42 each     │
+//! │                                                                         │
+//! │  on hand 100 @ average 0.40                                            │
+//! │  receive 2 case (48) from ACME, line cost 10.32 per case               │
+//! │       │  each cost = 10.32 / 24 = 0.43                                 │
+//! │       │  (no line cost: the supplier's cost, 0.42)                     │
+//! │       ▼                                                                 │
+//! │  last cost 0.43                                                        │
+//! │  average   (100 × 0.40 + 48 × 0.43) / 148 = 0.41                       │
+//! │  products.cost_cents = average (what margins are reported against)     │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Rules
+//! - A supplier's name is 1 to [`MAX_SUPPLIER_NAME_LEN`] characters
+//! - A supplier lists a product at most once; its SKU is optional, at most
+//!   [`MAX_SUPPLIER_SKU_LEN`] characters; costs are per each, 0 to
+//!   [`MAX_COST_CENTS`]
+//! - Stock on hand at zero or below carries no cost: the first receipt
+//!   after a stock-out sets the average to its own cost
+//! - Receipts without a known cost leave both costs alone
+//!
+//! Each sale line keeps the product's cost when it was sold, so
+//! [`MarginStats`] compare price with the cost of the goods actually sold.
+//!
+//! Like other shared documents, a supplier and its price list are one
+//! [`SupplierDocument`] with its own `sync_version`; the newer version
+//! wins on every terminal, and the cloud keeps the tenant's copy.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::ValidationError;
+use crate::validation::ValidationResult;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Longest supplier name.
+pub const MAX_SUPPLIER_NAME_LEN: usize = 100;
+
+/// Longest supplier SKU.
+pub const MAX_SUPPLIER_SKU_LEN: usize = 40;
+
+/// Most products on one supplier's price list.
+pub const MAX_SUPPLIER_ITEMS: usize = 10_000;
+
+/// Highest unit cost accepted (100,000.00).
+pub const MAX_COST_CENTS: i64 = 10_000_000;
+
+// =============================================================================
+// Supplier
+// =============================================================================
+
+/// A supplier the store buys from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Supplier {
+    pub id: String,
+    pub name: String,
+    pub contact_name: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    /// Inactive suppliers keep their history but aren't offered at
+    /// receiving.
+    pub is_active: bool,
+    #[ts(as = "String")]
+    pub created_at: DateTime<Utc>,
+    #[ts(as = "String")]
+    pub updated_at: DateTime<Utc>,
+    pub sync_version: i64,
+}
+
+impl Supplier {
+    /// Checks the name.
+    pub fn validate(&self) -> ValidationResult<()> {
+        validate_supplier_name(&self.name)
+    }
+}
+
+/// What one supplier charges for one product.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SupplierItem {
+    pub product_id: String,
+    /// The supplier's own code for the product.
+    #[serde(default)]
+    pub supplier_sku: Option<String>,
+    /// Cost per each.
+    #[ts(type = "number")]
+    pub cost_cents: i64,
+}
+
+impl SupplierItem {
+    /// Creates a price list entry.
+    pub fn new(product_id: impl Into<String>, supplier_sku: Option<&str>, cost_cents: i64) -> Self {
+        SupplierItem {
+            product_id: product_id.into(),
+            supplier_sku: supplier_sku.map(str::to_string),
+            cost_cents,
+        }
+    }
+}
+
+/// A supplier with its price list, as stored in the sync outbox
+/// (`SUPPLIER`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SupplierDocument {
+    #[serde(flatten)]
+    pub supplier: Supplier,
+    /// Sorted by product ID.
+    pub items: Vec<SupplierItem>,
+}
+
+impl SupplierDocument {
+    /// Checks the supplier and its price list.
+    pub fn validate(&self) -> ValidationResult<()> {
+        self.supplier.validate()?;
+        if normalize_items(&self.items)? != self.items {
+            return Err(ValidationError::InvalidFormat {
+                field: "items".to_string(),
+                reason: "must be normalized".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// The supplier's cost per each for a product, if it lists it.
+    pub fn cost_of(&self, product_id: &str) -> Option<i64> {
+        self.items
+            .iter()
+            .find(|i| i.product_id == product_id)
+            .map(|i| i.cost_cents)
+    }
+}
+
+/// Checks a supplier name.
+///
+/// ## Errors
+/// Empty, or longer than [`MAX_SUPPLIER_NAME_LEN`] characters.
+pub fn validate_supplier_name(name: &str) -> ValidationResult<()> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ValidationError::Required {
+            field: "supplier name".to_string(),
+        });
+    }
+    if name.chars().count() > MAX_SUPPLIER_NAME_LEN {
+        return Err(ValidationError::TooLong {
+            field: "supplier name".to_string(),
+            max: MAX_SUPPLIER_NAME_LEN,
+        });
+    }
+    Ok(())
+}
+
+/// Checks a unit cost.
+///
+/// ## Errors
+/// `OutOfRange` outside 0 to [`MAX_COST_CENTS`].
+pub fn validate_cost(cost_cents: i64) -> ValidationResult<()> {
+    if !(0..=MAX_COST_CENTS).contains(&cost_cents) {
+        return Err(ValidationError::OutOfRange {
+            field: "cost".to_string(),
+            min: 0,
+            max: MAX_COST_CENTS,
+        });
+    }
+    Ok(())
+}
+
+/// Trims supplier SKUs (blank ones become `None`), checks costs and sorts
+/// the list by product ID.
+///
+/// ## Errors
+/// - `OutOfRange` for more than [`MAX_SUPPLIER_ITEMS`] entries or a cost
+///   out of range
+/// - `TooLong` for a supplier SKU past [`MAX_SUPPLIER_SKU_LEN`]
+/// - `Duplicate` for a product listed twice
+pub fn normalize_items(items: &[SupplierItem]) -> ValidationResult<Vec<SupplierItem>> {
+    if items.len() > MAX_SUPPLIER_ITEMS {
+        return Err(ValidationError::OutOfRange {
+            field: "items".to_string(),
+            min: 0,
+            max: MAX_SUPPLIER_ITEMS as i64,
+        });
+    }
+
+    let mut normalized = Vec::with_capacity(items.len());
+    for item in items {
+        validate_cost(item.cost_cents)?;
+        let supplier_sku = item
+            .supplier_sku
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty());
+        if supplier_sku.is_some_and(|s| s.chars().count() > MAX_SUPPLIER_SKU_LEN) {
+            return Err(ValidationError::TooLong {
+                field: "supplier_sku".to_string(),
+                max: MAX_SUPPLIER_SKU_LEN,
+            });
+        }
+        normalized.push(SupplierItem::new(item.product_id.trim(), supplier_sku, item.cost_cents));
+    }
+
+    normalized.sort_by(|a, b| a.product_id.cmp(&b.product_id));
+    if let Some(pair) = normalized.windows(2).find(|w| w[0].product_id == w[1].product_id) {
+        return Err(ValidationError::Duplicate {
+            field: "product_id".to_string(),
+            value: pair[0].product_id.clone(),
+        });
+    }
+    Ok(normalized)
+}
+
+// =============================================================================
+// Product Costs
+// =============================================================================
+
+/// What a product's stock cost, maintained at receiving.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProductCost {
+    pub product_id: String,
+    /// Cost per each on the latest costed receipt.
+    #[ts(type = "number | null")]
+    pub last_cost_cents: Option<i64>,
+    /// Weighted average cost per each of the stock on hand.
+    #[ts(type = "number | null")]
+    pub average_cost_cents: Option<i64>,
+    /// Supplier of the latest costed receipt, if it named one.
+    pub last_supplier_id: Option<String>,
+    #[ts(as = "Option<String>")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Cost per each of a receiving line priced per `units_per_pack` eaches,
+/// rounded half up.
+pub fn each_cost(line_cost_cents: i64, units_per_pack: i64) -> i64 {
+    if units_per_pack <= 1 {
+        return line_cost_cents;
+    }
+    (line_cost_cents * 2 + units_per_pack) / (units_per_pack * 2)
+}
+
+/// The average cost after receiving `received` eaches at `unit_cost`
+/// onto `on_hand` eaches averaging `average`, rounded half up.
+///
+/// Stock at zero or below, or without a known average, carries no cost,
+/// so the result is the new cost.
+pub fn weighted_average_cost(on_hand: i64, average: Option<i64>, received: i64, unit_cost: i64) -> i64 {
+    let Some(average) = average.filter(|_| on_hand > 0) else {
+        return unit_cost;
+    };
+    if received <= 0 {
+        return average;
+    }
+
+    let total = i128::from(on_hand) + i128::from(received);
+    let value = i128::from(on_hand) * i128::from(average) + i128::from(received) * i128::from(unit_cost);
+    ((value * 2 + total) / (total * 2)) as i64
+}
+
+// =============================================================================
+// Margins
+// =============================================================================
+
+/// Margin of a price over a cost, in basis points of the price (2500 =
+/// 25%); `None` for a price of zero or less.
+pub fn margin_bps(price_cents: i64, cost_cents: i64) -> Option<i64> {
+    (price_cents > 0).then(|| {
+        let margin = i128::from(price_cents - cost_cents) * 10_000;
+        (margin / i128::from(price_cents)) as i64
+    })
+}
+
+/// What one product sold for and cost over a period.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProductMargin {
+    pub product_id: String,
+    pub sku: String,
+    pub name: String,
+    #[ts(type = "number")]
+    pub units: i64,
+    /// Net of line discounts, before tax.
+    #[ts(type = "number")]
+    pub sales_cents: i64,
+    /// Cost of the units sold, at the cost frozen on each line.
+    #[ts(type = "number")]
+    pub cost_cents: i64,
+}
+
+impl ProductMargin {
+    /// Sales less cost.
+    pub fn margin_cents(&self) -> i64 {
+        self.sales_cents - self.cost_cents
+    }
+
+    /// Margin in basis points of sales.
+    pub fn margin_bps(&self) -> Option<i64> {
+        margin_bps(self.sales_cents, self.cost_cents)
+    }
+}
+
+/// Price vs cost of everything sold over a period.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MarginStats {
+    /// Sales of lines with a known cost.
+    #[ts(type = "number")]
+    pub sales_cents: i64,
+    #[ts(type = "number")]
+    pub cost_cents: i64,
+    /// Sales of lines sold without a cost, left out of the margin.
+    #[ts(type = "number")]
+    pub uncosted_sales_cents: i64,
+    /// Lowest margin first.
+    pub products: Vec<ProductMargin>,
+}
+
+impl MarginStats {
+    /// Totals the per-product rows.
+    pub fn new(mut products: Vec<ProductMargin>, uncosted_sales_cents: i64) -> Self {
+        products.sort_by_key(|p| (p.margin_bps().unwrap_or(i64::MIN), p.product_id.clone()));
+        MarginStats {
+            sales_cents: products.iter().map(|p| p.sales_cents).sum(),
+            cost_cents: products.iter().map(|p| p.cost_cents).sum(),
+            uncosted_sales_cents,
+            products,
+        }
+    }
+
+    /// Sales less cost, costed lines only.
+    pub fn margin_cents(&self) -> i64 {
+        self.sales_cents - self.cost_cents
+    }
+
+    /// Margin in basis points of costed sales.
+    pub fn margin_bps(&self) -> Option<i64> {
+        margin_bps(self.sales_cents, self.cost_cents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_items() {
+        let items = normalize_items(&[
+            SupplierItem::new("p2", Some(" AC-7781 "), 42),
+            SupplierItem::new("p1", Some("  "), 0),
+        ])
+        .unwrap();
+        assert_eq!(items[0], SupplierItem::new("p1", None, 0));
+        assert_eq!(items[1], SupplierItem::new("p2", Some("AC-7781"), 42));
+
+        assert!(matches!(
+            normalize_items(&[SupplierItem::new("p1", None, 1), SupplierItem::new("p1", None, 2)]),
+            Err(ValidationError::Duplicate { .. })
+        ));
+        assert!(normalize_items(&[SupplierItem::new("p1", None, -1)]).is_err());
+        assert!(normalize_items(&[SupplierItem::new("p1", Some(&"x".repeat(41)), 1)]).is_err());
+
+        assert!(validate_supplier_name(" ").is_err());
+        assert!(validate_supplier_name("ACME Beverages").is_ok());
+    }
+
+    #[test]
+    fn test_each_cost_rounds_half_up() {
+        assert_eq!(each_cost(1032, 24), 43);
+        assert_eq!(each_cost(1000, 24), 42); // 41.67
+        assert_eq!(each_cost(30, 4), 8); // 7.5
+        assert_eq!(each_cost(55, 1), 55);
+    }
+
+    #[test]
+    fn test_weighted_average_cost() {
+        assert_eq!(weighted_average_cost(100, Some(40), 48, 43), 41); // 40.97
+        assert_eq!(weighted_average_cost(0, Some(40), 48, 43), 43);
+        assert_eq!(weighted_average_cost(-5, Some(40), 10, 50), 50);
+        assert_eq!(weighted_average_cost(100, None, 10, 50), 50);
+        assert_eq!(weighted_average_cost(10, Some(100), 10, 101), 101); // 100.5
+    }
+
+    #[test]
+    fn test_margin_stats() {
+        assert_eq!(margin_bps(200, 150), Some(2500));
+        assert_eq!(margin_bps(100, 120), Some(-2000));
+        assert_eq!(margin_bps(0, 10), None);
+
+        let product = |id: &str, sales: i64, cost: i64| ProductMargin {
+            product_id: id.to_string(),
+            sku: id.to_uppercase(),
+            name: id.to_string(),
+            units: 1,
+            sales_cents: sales,
+            cost_cents: cost,
+        };
+        let stats = MarginStats::new(vec![product("a", 1000, 600), product("b", 1000, 900)], 250);
+
+        assert_eq!(stats.products[0].product_id, "b");
+        assert_eq!(stats.margin_cents(), 500);
+        assert_eq!(stats.margin_bps(), Some(2500));
+        assert_eq!(stats.uncosted_sales_cents, 250);
+    }
+}
@@ -0,0 +1,226 @@
+//! # Sync Status History
+//!
+//! A terminal's connection to its hub, recorded as a run of periods, one
+//! per connection state. From these the support screen shows when the link
+//! dropped and why, and how much of each day the terminal was connected,
+//! which is the figure to show the ISP when the store's network is flaky.
+//!
+//! ## Periods and Uptime
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Daily Uptime                                    │
+//! │                                                                         │
+//! │  08:00 connecting ─ ready ─────────── backoff ─ ready ───── idle 22:00  │
+//! │                     │◄── connected ──►│ "pong   │◄─ conn. ─►│           │
+//! │                                         timeout"                        │
+//! │  observed  = every period except idle (agent stopped)                  │
+//! │  connected = ready periods                                              │
+//! │  uptime    = connected / observed          (basis points, 10000=100%)   │
+//! │  disconnects = periods that started with a reason (a lost connection)  │
+//! │                                                                         │
+//! │  Periods crossing midnight are split between the two days, in the      │
+//! │  store's local offset. The open period counts up to "now".              │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// State recorded while the terminal is connected to its hub.
+pub const CONNECTED_STATE: &str = "ready";
+
+/// State recorded while the sync agent is stopped; not counted as downtime.
+pub const STOPPED_STATE: &str = "idle";
+
+// =============================================================================
+// Types
+// =============================================================================
+
+/// A stretch of time the sync connection spent in one state.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SyncStatusPeriod {
+    pub id: i64,
+    /// Connection state ("connecting", "ready", "backoff", ...).
+    pub state: String,
+    /// Why the connection left its previous state, for losses and errors.
+    pub reason: Option<String>,
+    #[ts(as = "String")]
+    pub started_at: DateTime<Utc>,
+    /// `None` while this is the current state.
+    #[ts(as = "Option<String>")]
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Length of the period once it has ended.
+    pub duration_ms: Option<i64>,
+}
+
+/// Connection figures for one local calendar day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DailyUptime {
+    #[ts(as = "String")]
+    pub day: NaiveDate,
+    /// Time the agent was running.
+    pub observed_ms: i64,
+    /// Time the terminal was connected to its hub.
+    pub connected_ms: i64,
+    /// Connections lost during the day.
+    pub disconnects: u32,
+    /// `connected_ms / observed_ms` in basis points (10000 = 100%).
+    pub uptime_bps: u32,
+}
+
+// =============================================================================
+// Uptime
+// =============================================================================
+
+/// Per-day uptime from a run of periods, oldest day first.
+///
+/// Days are calendar days at `offset`; periods still open are counted up
+/// to `now`. Days where the agent never ran are left out.
+pub fn daily_uptime(
+    periods: &[SyncStatusPeriod],
+    offset: FixedOffset,
+    now: DateTime<Utc>,
+) -> Vec<DailyUptime> {
+    let mut days: BTreeMap<NaiveDate, DailyUptime> = BTreeMap::new();
+
+    for period in periods {
+        if period.state == STOPPED_STATE {
+            continue;
+        }
+        let connected = period.state == CONNECTED_STATE;
+        let end = period.ended_at.unwrap_or(now).max(period.started_at);
+
+        if period.reason.is_some() {
+            let day = period.started_at.with_timezone(&offset).date_naive();
+            day_entry(&mut days, day).disconnects += 1;
+        }
+
+        // Walk the period one local day at a time
+        let mut start = period.started_at;
+        while start < end {
+            let day = start.with_timezone(&offset).date_naive();
+            let next_midnight = day
+                .succ_opt()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .and_then(|t| t.and_local_timezone(offset).single())
+                .map(|t| t.with_timezone(&Utc))
+                .unwrap_or(end);
+            let slice_end = next_midnight.min(end);
+            let ms = (slice_end - start).num_milliseconds();
+
+            let entry = day_entry(&mut days, day);
+            entry.observed_ms += ms;
+            if connected {
+                entry.connected_ms += ms;
+            }
+            start = slice_end;
+        }
+    }
+
+    days.into_values()
+        .map(|mut day| {
+            day.uptime_bps = uptime_bps(day.connected_ms, day.observed_ms);
+            day
+        })
+        .collect()
+}
+
+fn day_entry(days: &mut BTreeMap<NaiveDate, DailyUptime>, day: NaiveDate) -> &mut DailyUptime {
+    days.entry(day).or_insert(DailyUptime {
+        day,
+        observed_ms: 0,
+        connected_ms: 0,
+        disconnects: 0,
+        uptime_bps: 0,
+    })
+}
+
+/// Share of `observed` that was `connected`, in basis points (rounded down).
+fn uptime_bps(connected_ms: i64, observed_ms: i64) -> u32 {
+    if observed_ms <= 0 {
+        return 0;
+    }
+    (connected_ms.clamp(0, observed_ms) * 10_000 / observed_ms) as u32
+}
+
+/// How long a period lasted if it ended at `end`.
+pub fn period_duration(started_at: DateTime<Utc>, end: DateTime<Utc>) -> Duration {
+    (end - started_at).max(Duration::zero())
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(h: u32, m: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 10, h, m, 0).unwrap()
+    }
+
+    fn period(
+        state: &str,
+        reason: Option<&str>,
+        from: DateTime<Utc>,
+        to: Option<DateTime<Utc>>,
+    ) -> SyncStatusPeriod {
+        SyncStatusPeriod {
+            id: 0,
+            state: state.to_string(),
+            reason: reason.map(str::to_string),
+            started_at: from,
+            ended_at: to,
+            duration_ms: to.map(|to| (to - from).num_milliseconds()),
+        }
+    }
+
+    #[test]
+    fn test_daily_uptime_single_day() {
+        let utc = FixedOffset::east_opt(0).unwrap();
+        let periods = [
+            period("connecting", None, at(8, 0), Some(at(8, 0))),
+            period("ready", None, at(8, 0), Some(at(11, 0))),
+            period("backoff", Some("pong timeout"), at(11, 0), Some(at(12, 0))),
+            period("ready", None, at(12, 0), Some(at(16, 0))),
+            period("idle", None, at(16, 0), None),
+        ];
+
+        let days = daily_uptime(&periods, utc, at(20, 0));
+        assert_eq!(days.len(), 1);
+        assert_eq!(days[0].observed_ms, 8 * 3_600_000);
+        assert_eq!(days[0].connected_ms, 7 * 3_600_000);
+        assert_eq!(days[0].disconnects, 1);
+        assert_eq!(days[0].uptime_bps, 8750);
+    }
+
+    #[test]
+    fn test_daily_uptime_splits_at_local_midnight() {
+        // UTC+5: local midnight of the 11th is 19:00 UTC on the 10th
+        let pkt = FixedOffset::east_opt(5 * 3600).unwrap();
+        let periods = [period("ready", None, at(18, 0), None)];
+
+        let days = daily_uptime(&periods, pkt, at(21, 0));
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].day, NaiveDate::from_ymd_opt(2026, 3, 10).unwrap());
+        assert_eq!(days[0].connected_ms, 3_600_000);
+        assert_eq!(days[1].day, NaiveDate::from_ymd_opt(2026, 3, 11).unwrap());
+        assert_eq!(days[1].connected_ms, 2 * 3_600_000);
+        assert_eq!(days[1].uptime_bps, 10_000);
+    }
+
+    #[test]
+    fn test_uptime_bps_edges() {
+        assert_eq!(uptime_bps(0, 0), 0);
+        assert_eq!(uptime_bps(1, 3), 3333);
+        assert_eq!(uptime_bps(5, 5), 10_000);
+        assert_eq!(period_duration(at(9, 0), at(8, 0)), Duration::zero());
+    }
+}
@@ -0,0 +1,770 @@
+//! # Sync Payload Schemas
+//!
+//! Typed schemas for outbox payloads. The outbox stores each payload as a
+//! JSON string, which the hub and the cloud used to take on trust; every
+//! entity type now has a typed DTO that a payload must deserialize into and
+//! pass checks against before it is queued or relayed.
+//!
+//! ## Validation Points
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                      Sync Payload Validation                            │
+//! │                                                                         │
+//! │  Terminal                                                               │
+//! │  ────────                                                               │
+//! │  queue_for_sync / upsert_for_sync ──► SyncPayload::parse               │
+//! │       invalid → rejected, nothing queued                               │
+//! │  OutboxProcessor (rows queued by older builds) ──► SyncPayload::parse  │
+//! │       invalid → moved to sync_quarantine, never sent                   │
+//! │                                                                         │
+//! │  Hub                                                                    │
+//! │  ───                                                                    │
+//! │  OutboxBatch entry { entity_type, schema_version, payload }            │
+//! │       ──► SyncPayload::parse                                            │
+//! │       invalid → sync_quarantine (with the sending device), not relayed │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Schema Versions
+//! The envelope around a payload (the outbox entry on the wire) carries the
+//! schema version it was written against. A receiver accepts versions up to
+//! [`PAYLOAD_SCHEMA_VERSION`] and quarantines newer ones instead of guessing.
+//! - v1: the payloads below, as first shipped
+
+use serde::de::DeserializeOwned;
+
+use crate::error::PayloadError;
+use crate::patch::UNPATCHED_FIELDS;
+use crate::tombstone::SOFT_DELETE_ENTITY_TYPES;
+use crate::{
+    CouponDocument, EntityPatch, ErasureRequest, LayawayDocument, LoyaltyDocument, ProductAttributes, ProductBundle, ProductPacks, ProductStyleDocument, QuoteDocument, SaleDocument, StoreCreditDocument, StoreTransferDocument,
+    SupplierDocument, TillSession, Tombstone, WasteRecord,
+};
+
+/// Payload schema version written by this build.
+pub const PAYLOAD_SCHEMA_VERSION: u32 = 1;
+
+/// Payload fields holding customer data or payment references, which the
+/// outbox stores encrypted (see `titan_db::payload_crypto`).
+///
+/// Paths are dotted from the top of the payload; `name[]` steps into each
+/// element of an array. Every field listed is a string or null, so an
+/// encrypted payload still parses against its schema.
+pub fn sensitive_fields(entity_type: &str) -> &'static [&'static str] {
+    match entity_type {
+        "SALE" => &["payments[].reference"],
+        "QUOTE" => &["customer_name", "customer_email"],
+        "LAYAWAY" => &["customer_name", "customer_phone"],
+        "STORE_CREDIT" => &["customer_name", "customer_phone"],
+        "CUSTOMER_ERASURE" => &["subject.phone", "subject.email"],
+        _ => &[],
+    }
+}
+
+// =============================================================================
+// Sync Payload
+// =============================================================================
+
+/// A validated outbox payload.
+#[derive(Debug, Clone)]
+pub enum SyncPayload {
+    /// `SALE`: a completed sale, with its lines and payments.
+    Sale(SaleDocument),
+    /// `TILL_SESSION`: a closed till session.
+    TillSession(TillSession),
+    /// `QUOTE`: a quote with its lines.
+    Quote(QuoteDocument),
+    /// `LAYAWAY`: a layaway with its lines and payments.
+    Layaway(LayawayDocument),
+    /// `STORE_TRANSFER`: a transfer with its lines.
+    StoreTransfer(StoreTransferDocument),
+    /// `STORE_CREDIT`: a store credit account with its ledger.
+    StoreCredit(StoreCreditDocument),
+    /// `PRODUCT_PATCH`: changed product fields.
+    ProductPatch(EntityPatch),
+    /// `TOMBSTONE`: a soft delete or restore.
+    Tombstone(Tombstone),
+    /// `CUSTOMER_ERASURE`: a customer data erasure request.
+    CustomerErasure(ErasureRequest),
+    /// `PRODUCT_ATTRIBUTES`: a product's full attribute set.
+    ProductAttributes(ProductAttributes),
+    /// `PRODUCT_STYLE`: a product style with its variant IDs.
+    ProductStyle(ProductStyleDocument),
+    /// `PRODUCT_BUNDLE`: a bundle product's bill of materials.
+    ProductBundle(ProductBundle),
+    /// `PRODUCT_PACKS`: a product's pack sizes.
+    ProductPacks(ProductPacks),
+    /// `WASTE`: goods written off from stock.
+    Waste(WasteRecord),
+    /// `SUPPLIER`: a supplier and its price list.
+    Supplier(SupplierDocument),
+    /// `COUPON`: a coupon with its redemptions.
+    Coupon(CouponDocument),
+    /// `LOYALTY`: a loyalty account with its ledger.
+    Loyalty(LoyaltyDocument),
+}
+
+impl SyncPayload {
+    /// Entity types with a payload schema.
+    pub const ENTITY_TYPES: &'static [&'static str] = &[
+        "SALE",
+        "TILL_SESSION",
+        "QUOTE",
+        "LAYAWAY",
+        "STORE_TRANSFER",
+        "STORE_CREDIT",
+        "PRODUCT_PATCH",
+        "TOMBSTONE",
+        "CUSTOMER_ERASURE",
+        "PRODUCT_ATTRIBUTES",
+        "PRODUCT_STYLE",
+        "PRODUCT_BUNDLE",
+        "PRODUCT_PACKS",
+        "WASTE",
+        "SUPPLIER",
+        "COUPON",
+        "LOYALTY",
+    ];
+
+    /// Parses and checks the payload of an outbox entry.
+    ///
+    /// ## Checks
+    /// - the schema version is one this build understands
+    /// - the JSON deserializes into the entity type's DTO
+    /// - the payload describes `entity_id`, and child lines belong to it
+    /// - patches change at least one field and no bookkeeping fields
+    pub fn parse(
+        entity_type: &str,
+        entity_id: &str,
+        schema_version: u32,
+        payload: &str,
+    ) -> Result<Self, PayloadError> {
+        if schema_version == 0 || schema_version > PAYLOAD_SCHEMA_VERSION {
+            return Err(PayloadError::UnsupportedSchema {
+                found: schema_version,
+                supported: PAYLOAD_SCHEMA_VERSION,
+            });
+        }
+
+        let Some(&entity_type) = Self::ENTITY_TYPES.iter().find(|t| **t == entity_type) else {
+            return Err(PayloadError::UnknownEntityType(entity_type.to_string()));
+        };
+
+        let parsed = match entity_type {
+            "SALE" => SyncPayload::Sale(decode(entity_type, payload)?),
+            "TILL_SESSION" => SyncPayload::TillSession(decode(entity_type, payload)?),
+            "QUOTE" => SyncPayload::Quote(decode(entity_type, payload)?),
+            "LAYAWAY" => SyncPayload::Layaway(decode(entity_type, payload)?),
+            "STORE_TRANSFER" => SyncPayload::StoreTransfer(decode(entity_type, payload)?),
+            "STORE_CREDIT" => SyncPayload::StoreCredit(decode(entity_type, payload)?),
+            "PRODUCT_PATCH" => SyncPayload::ProductPatch(decode(entity_type, payload)?),
+            "TOMBSTONE" => SyncPayload::Tombstone(decode(entity_type, payload)?),
+            "CUSTOMER_ERASURE" => SyncPayload::CustomerErasure(decode(entity_type, payload)?),
+            "PRODUCT_ATTRIBUTES" => SyncPayload::ProductAttributes(decode(entity_type, payload)?),
+            "PRODUCT_STYLE" => SyncPayload::ProductStyle(decode(entity_type, payload)?),
+            "PRODUCT_BUNDLE" => SyncPayload::ProductBundle(decode(entity_type, payload)?),
+            "PRODUCT_PACKS" => SyncPayload::ProductPacks(decode(entity_type, payload)?),
+            "WASTE" => SyncPayload::Waste(decode(entity_type, payload)?),
+            "SUPPLIER" => SyncPayload::Supplier(decode(entity_type, payload)?),
+            "COUPON" => SyncPayload::Coupon(decode(entity_type, payload)?),
+            "LOYALTY" => SyncPayload::Loyalty(decode(entity_type, payload)?),
+            _ => unreachable!("entity type listed in ENTITY_TYPES"),
+        };
+
+        parsed.check(entity_id)?;
+        Ok(parsed)
+    }
+
+    /// The outbox entity type of this payload.
+    pub fn entity_type(&self) -> &'static str {
+        match self {
+            SyncPayload::Sale(_) => "SALE",
+            SyncPayload::TillSession(_) => "TILL_SESSION",
+            SyncPayload::Quote(_) => "QUOTE",
+            SyncPayload::Layaway(_) => "LAYAWAY",
+            SyncPayload::StoreTransfer(_) => "STORE_TRANSFER",
+            SyncPayload::StoreCredit(_) => "STORE_CREDIT",
+            SyncPayload::ProductPatch(_) => "PRODUCT_PATCH",
+            SyncPayload::Tombstone(_) => "TOMBSTONE",
+            SyncPayload::CustomerErasure(_) => "CUSTOMER_ERASURE",
+            SyncPayload::ProductAttributes(_) => "PRODUCT_ATTRIBUTES",
+            SyncPayload::ProductStyle(_) => "PRODUCT_STYLE",
+            SyncPayload::ProductBundle(_) => "PRODUCT_BUNDLE",
+            SyncPayload::ProductPacks(_) => "PRODUCT_PACKS",
+            SyncPayload::Waste(_) => "WASTE",
+            SyncPayload::Supplier(_) => "SUPPLIER",
+            SyncPayload::Coupon(_) => "COUPON",
+            SyncPayload::Loyalty(_) => "LOYALTY",
+        }
+    }
+
+    /// ID of the entity the payload describes (patches do not carry one).
+    pub fn entity_id(&self) -> Option<&str> {
+        match self {
+            SyncPayload::Sale(doc) => Some(&doc.sale.id),
+            SyncPayload::TillSession(session) => Some(&session.id),
+            SyncPayload::Quote(doc) => Some(&doc.quote.id),
+            SyncPayload::Layaway(doc) => Some(&doc.layaway.id),
+            SyncPayload::StoreTransfer(doc) => Some(&doc.transfer.id),
+            SyncPayload::StoreCredit(doc) => Some(&doc.account.id),
+            SyncPayload::ProductPatch(_) => None,
+            SyncPayload::Tombstone(tombstone) => Some(&tombstone.entity_id),
+            SyncPayload::CustomerErasure(request) => Some(&request.id),
+            SyncPayload::ProductAttributes(doc) => Some(&doc.product_id),
+            SyncPayload::ProductStyle(doc) => Some(&doc.style.id),
+            SyncPayload::ProductBundle(doc) => Some(&doc.product_id),
+            SyncPayload::ProductPacks(doc) => Some(&doc.product_id),
+            SyncPayload::Waste(record) => Some(&record.id),
+            SyncPayload::Supplier(doc) => Some(&doc.supplier.id),
+            SyncPayload::Coupon(doc) => Some(&doc.coupon.id),
+            SyncPayload::Loyalty(doc) => Some(&doc.account.id),
+        }
+    }
+
+    fn check(&self, entity_id: &str) -> Result<(), PayloadError> {
+        let entity_type = self.entity_type();
+        if let Some(found) = self.entity_id() {
+            if found != entity_id {
+                return Err(PayloadError::EntityMismatch {
+                    entity_type,
+                    expected: entity_id.to_string(),
+                    found: found.to_string(),
+                });
+            }
+        }
+
+        let invalid = |reason: String| {
+            Err(PayloadError::Invalid {
+                entity_type,
+                reason,
+            })
+        };
+        match self {
+            SyncPayload::Sale(doc) => {
+                let owner = &doc.sale.id;
+                if let Some(item) = doc.items.iter().find(|i| &i.sale_id != owner) {
+                    return invalid(format!("line {} belongs to sale {}", item.id, item.sale_id));
+                }
+                if let Some(payment) = doc.payments.iter().find(|p| &p.sale_id != owner) {
+                    return invalid(format!(
+                        "payment {} belongs to sale {}",
+                        payment.id, payment.sale_id
+                    ));
+                }
+            }
+            SyncPayload::Quote(doc) => {
+                if let Some(item) = doc.items.iter().find(|i| i.quote_id != doc.quote.id) {
+                    return invalid(format!(
+                        "line {} belongs to quote {}",
+                        item.id, item.quote_id
+                    ));
+                }
+            }
+            SyncPayload::Layaway(doc) => {
+                let owner = &doc.layaway.id;
+                if let Some(item) = doc.items.iter().find(|i| &i.layaway_id != owner) {
+                    return invalid(format!(
+                        "line {} belongs to layaway {}",
+                        item.id, item.layaway_id
+                    ));
+                }
+                if let Some(payment) = doc.payments.iter().find(|p| &p.layaway_id != owner) {
+                    return invalid(format!(
+                        "payment {} belongs to layaway {}",
+                        payment.id, payment.layaway_id
+                    ));
+                }
+            }
+            SyncPayload::StoreTransfer(doc) => {
+                if let Some(item) = doc.items.iter().find(|i| i.transfer_id != doc.transfer.id) {
+                    return invalid(format!(
+                        "line {} belongs to transfer {}",
+                        item.id, item.transfer_id
+                    ));
+                }
+            }
+            SyncPayload::StoreCredit(doc) => {
+                if let Some(entry) = doc.entries.iter().find(|e| e.account_id != doc.account.id) {
+                    return invalid(format!(
+                        "entry {} belongs to account {}",
+                        entry.id, entry.account_id
+                    ));
+                }
+            }
+            SyncPayload::ProductPatch(patch) => {
+                if patch.fields.is_empty() {
+                    return invalid("patch changes no fields".to_string());
+                }
+                if let Some(field) = patch.field_names().find(|f| UNPATCHED_FIELDS.contains(f)) {
+                    return invalid(format!("field {} cannot be patched", field));
+                }
+            }
+            SyncPayload::Tombstone(tombstone) => {
+                if !SOFT_DELETE_ENTITY_TYPES.contains(&tombstone.entity_type.as_str()) {
+                    return invalid(format!("{} cannot be soft-deleted", tombstone.entity_type));
+                }
+            }
+            SyncPayload::CustomerErasure(request) => {
+                if let Err(e) = request.subject.normalized() {
+                    return invalid(e.to_string());
+                }
+            }
+            SyncPayload::ProductAttributes(doc) => {
+                match crate::attribute::normalize_attributes(&doc.attributes) {
+                    Ok(normalized) if normalized == doc.attributes => {}
+                    Ok(_) => return invalid("attributes are not normalized".to_string()),
+                    Err(e) => return invalid(e.to_string()),
+                }
+            }
+            SyncPayload::ProductStyle(doc) => {
+                if let Err(e) = doc.style.validate() {
+                    return invalid(e.to_string());
+                }
+            }
+            SyncPayload::ProductBundle(doc) => {
+                if let Err(e) = doc.validate() {
+                    return invalid(e.to_string());
+                }
+            }
+            SyncPayload::ProductPacks(doc) => match crate::pack::normalize_packs(&doc.packs) {
+                Ok(normalized) if normalized == doc.packs => {}
+                Ok(_) => return invalid("packs are not normalized".to_string()),
+                Err(e) => return invalid(e.to_string()),
+            },
+            SyncPayload::Waste(record) => {
+                if let Err(e) = record.validate() {
+                    return invalid(e.to_string());
+                }
+            }
+            SyncPayload::Supplier(doc) => {
+                if let Err(e) = doc.validate() {
+                    return invalid(e.to_string());
+                }
+            }
+            SyncPayload::Coupon(doc) => {
+                if let Some(r) = doc.redemptions.iter().find(|r| r.coupon_id != doc.coupon.id) {
+                    return invalid(format!(
+                        "redemption {} belongs to coupon {}",
+                        r.id, r.coupon_id
+                    ));
+                }
+            }
+            SyncPayload::Loyalty(doc) => {
+                if let Some(e) = doc.entries.iter().find(|e| e.account_id != doc.account.id) {
+                    return invalid(format!("entry {} belongs to account {}", e.id, e.account_id));
+                }
+            }
+            SyncPayload::TillSession(_) => {}
+        }
+
+        Ok(())
+    }
+}
+
+fn decode<T: DeserializeOwned>(
+    entity_type: &'static str,
+    payload: &str,
+) -> Result<T, PayloadError> {
+    serde_json::from_str(payload).map_err(|e| PayloadError::Malformed {
+        entity_type,
+        reason: e.to_string(),
+    })
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn patch_payload(fields: serde_json::Value) -> String {
+        json!({
+            "base_version": 3,
+            "updated_at": Utc::now(),
+            "fields": fields,
+        })
+        .to_string()
+    }
+
+    fn till_payload(id: &str) -> String {
+        json!({
+            "id": id,
+            "tenant_id": "t-1",
+            "device_id": "pos-01",
+            "user_id": "u-1",
+            "status": "closed",
+            "opening_float_cents": 10000,
+            "expected_cash_cents": 25000,
+            "counted_cash_cents": 25000,
+            "variance_cents": 0,
+            "blind_close": true,
+            "closed_by": "u-1",
+            "notes": null,
+            "opened_at": Utc::now(),
+            "closed_at": Utc::now(),
+            "sync_version": 1,
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_parse_accepts_valid_payloads() {
+        let parsed =
+            SyncPayload::parse("TILL_SESSION", "till-1", 1, &till_payload("till-1")).unwrap();
+        assert_eq!(parsed.entity_type(), "TILL_SESSION");
+        assert_eq!(parsed.entity_id(), Some("till-1"));
+
+        let patch = patch_payload(json!({ "price_cents": 249 }));
+        let parsed = SyncPayload::parse("PRODUCT_PATCH", "p-1", 1, &patch).unwrap();
+        assert!(matches!(parsed, SyncPayload::ProductPatch(_)));
+        assert_eq!(parsed.entity_id(), None);
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_and_malformed() {
+        assert!(matches!(
+            SyncPayload::parse("WIDGET", "w-1", 1, "{}"),
+            Err(PayloadError::UnknownEntityType(_))
+        ));
+        assert!(matches!(
+            SyncPayload::parse("TILL_SESSION", "till-1", 1, ""),
+            Err(PayloadError::Malformed { .. })
+        ));
+        assert!(matches!(
+            SyncPayload::parse("TILL_SESSION", "till-1", 1, r#"{"id":"till-1"}"#),
+            Err(PayloadError::Malformed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_rejects_newer_schema() {
+        let err = SyncPayload::parse(
+            "TILL_SESSION",
+            "till-1",
+            PAYLOAD_SCHEMA_VERSION + 1,
+            &till_payload("till-1"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            PayloadError::UnsupportedSchema {
+                found: PAYLOAD_SCHEMA_VERSION + 1,
+                supported: PAYLOAD_SCHEMA_VERSION,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_checks_entity_and_fields() {
+        assert!(matches!(
+            SyncPayload::parse("TILL_SESSION", "till-2", 1, &till_payload("till-1")),
+            Err(PayloadError::EntityMismatch { .. })
+        ));
+        assert!(matches!(
+            SyncPayload::parse("PRODUCT_PATCH", "p-1", 1, &patch_payload(json!({}))),
+            Err(PayloadError::Invalid { .. })
+        ));
+        assert!(matches!(
+            SyncPayload::parse(
+                "PRODUCT_PATCH",
+                "p-1",
+                1,
+                &patch_payload(json!({ "sync_version": 9 }))
+            ),
+            Err(PayloadError::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_tombstone() {
+        let tombstone = |entity_type: &str| {
+            json!({
+                "entity_type": entity_type,
+                "entity_id": "p-1",
+                "action": "delete",
+                "version": 5,
+                "at": Utc::now(),
+            })
+            .to_string()
+        };
+        let parsed = SyncPayload::parse("TOMBSTONE", "p-1", 1, &tombstone("product")).unwrap();
+        assert_eq!(parsed.entity_id(), Some("p-1"));
+        assert!(matches!(
+            SyncPayload::parse("TOMBSTONE", "p-1", 1, &tombstone("sale")),
+            Err(PayloadError::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_customer_erasure() {
+        let erasure = |phone: &str| {
+            json!({
+                "id": "e-1",
+                "tenant_id": "t-1",
+                "subject": { "business_customer_id": null, "phone": phone, "email": null },
+                "reason": null,
+                "requested_by": "u-1",
+                "requested_at": Utc::now(),
+            })
+            .to_string()
+        };
+        let parsed =
+            SyncPayload::parse("CUSTOMER_ERASURE", "e-1", 1, &erasure("555-0100")).unwrap();
+        assert_eq!(parsed.entity_id(), Some("e-1"));
+        assert!(matches!(
+            SyncPayload::parse("CUSTOMER_ERASURE", "e-1", 1, &erasure(" ")),
+            Err(PayloadError::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_product_attributes() {
+        let attributes = |key: &str| {
+            json!({
+                "product_id": "p-1",
+                "attributes": [{ "key": key, "value": "red" }],
+                "sync_version": 2,
+                "updated_at": Utc::now(),
+            })
+            .to_string()
+        };
+        let parsed =
+            SyncPayload::parse("PRODUCT_ATTRIBUTES", "p-1", 1, &attributes("color")).unwrap();
+        assert_eq!(parsed.entity_id(), Some("p-1"));
+        assert!(matches!(
+            SyncPayload::parse("PRODUCT_ATTRIBUTES", "p-1", 1, &attributes("Color")),
+            Err(PayloadError::Invalid { .. })
+        ));
+        assert!(matches!(
+            SyncPayload::parse("PRODUCT_ATTRIBUTES", "p-2", 1, &attributes("color")),
+            Err(PayloadError::EntityMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_product_style() {
+        let style = |option_keys: serde_json::Value| {
+            json!({
+                "id": "s-1",
+                "tenant_id": "t-1",
+                "name": "Classic Tee",
+                "description": null,
+                "option_keys": option_keys,
+                "created_at": Utc::now(),
+                "updated_at": Utc::now(),
+                "sync_version": 1,
+                "variant_ids": ["p-1", "p-2"],
+            })
+            .to_string()
+        };
+        let parsed =
+            SyncPayload::parse("PRODUCT_STYLE", "s-1", 1, &style(json!(["size", "color"]))).unwrap();
+        assert_eq!(parsed.entity_id(), Some("s-1"));
+        assert!(matches!(
+            SyncPayload::parse("PRODUCT_STYLE", "s-1", 1, &style(json!([]))),
+            Err(PayloadError::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_product_packs() {
+        let packs = |packs: serde_json::Value| {
+            json!({
+                "product_id": "p-1",
+                "packs": packs,
+                "sync_version": 1,
+                "updated_at": Utc::now(),
+            })
+            .to_string()
+        };
+        let parsed = SyncPayload::parse(
+            "PRODUCT_PACKS",
+            "p-1",
+            1,
+            &packs(json!([{"name": "inner", "units": 6}, {"name": "case", "units": 24}])),
+        )
+        .unwrap();
+        assert_eq!(parsed.entity_id(), Some("p-1"));
+        assert!(matches!(
+            SyncPayload::parse("PRODUCT_PACKS", "p-1", 1, &packs(json!([{"name": "each", "units": 6}]))),
+            Err(PayloadError::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_waste() {
+        let waste = |quantity: i64| {
+            json!({
+                "id": "w-1",
+                "product_id": "p-1",
+                "quantity": quantity,
+                "reason": "expiry",
+                "unit_price_cents": 129,
+                "note": null,
+                "recorded_by": "default",
+                "device_id": "pos-01",
+                "recorded_at": Utc::now(),
+            })
+            .to_string()
+        };
+        let parsed = SyncPayload::parse("WASTE", "w-1", 1, &waste(4)).unwrap();
+        assert_eq!(parsed.entity_id(), Some("w-1"));
+        assert!(matches!(
+            SyncPayload::parse("WASTE", "w-1", 1, &waste(0)),
+            Err(PayloadError::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_supplier() {
+        let supplier = |items: serde_json::Value| {
+            json!({
+                "id": "sup-1",
+                "name": "ACME Beverages",
+                "contact_name": null,
+                "phone": null,
+                "email": null,
+                "is_active": true,
+                "created_at": Utc::now(),
+                "updated_at": Utc::now(),
+                "sync_version": 2,
+                "items": items,
+            })
+            .to_string()
+        };
+        let parsed = SyncPayload::parse(
+            "SUPPLIER",
+            "sup-1",
+            1,
+            &supplier(json!([{"product_id": "p-1", "supplier_sku": "AC-7781", "cost_cents": 42}])),
+        )
+        .unwrap();
+        assert_eq!(parsed.entity_id(), Some("sup-1"));
+        assert!(matches!(
+            SyncPayload::parse("SUPPLIER", "sup-1", 1, &supplier(json!([{"product_id": "p-1", "cost_cents": -1}]))),
+            Err(PayloadError::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_coupon() {
+        let coupon = |redemption_coupon_id: &str| {
+            json!({
+                "id": "cp-1",
+                "tenant_id": "t",
+                "code": "SAVE10",
+                "description": null,
+                "kind": "percent",
+                "value": 1000,
+                "min_subtotal_cents": null,
+                "product_ids": [],
+                "starts_at": null,
+                "expires_at": null,
+                "max_uses": 1,
+                "active": true,
+                "created_at": Utc::now(),
+                "updated_at": Utc::now(),
+                "sync_version": 1,
+                "redemptions": [{
+                    "id": "r-1",
+                    "coupon_id": redemption_coupon_id,
+                    "sale_id": "s-1",
+                    "device_id": "pos-01",
+                    "discount_cents": 120,
+                    "redeemed_at": Utc::now(),
+                }],
+            })
+            .to_string()
+        };
+        let parsed = SyncPayload::parse("COUPON", "cp-1", 1, &coupon("cp-1")).unwrap();
+        assert_eq!(parsed.entity_id(), Some("cp-1"));
+        assert!(matches!(
+            SyncPayload::parse("COUPON", "cp-1", 1, &coupon("cp-2")),
+            Err(PayloadError::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_product_bundle() {
+        let bundle = |components: serde_json::Value| {
+            json!({
+                "product_id": "b-1",
+                "components": components,
+                "sync_version": 2,
+                "updated_at": Utc::now(),
+            })
+            .to_string()
+        };
+        let parsed = SyncPayload::parse(
+            "PRODUCT_BUNDLE",
+            "b-1",
+            1,
+            &bundle(json!([{"product_id": "p-1", "quantity": 2}])),
+        )
+        .unwrap();
+        assert_eq!(parsed.entity_type(), "PRODUCT_BUNDLE");
+        assert!(matches!(
+            SyncPayload::parse(
+                "PRODUCT_BUNDLE",
+                "b-1",
+                1,
+                &bundle(json!([{"product_id": "b-1", "quantity": 1}]))
+            ),
+            Err(PayloadError::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_sale_with_and_without_lines() {
+        let header = json!({
+            "id": "s-1",
+            "tenant_id": "t-1",
+            "receipt_number": "R-0001",
+            "status": "completed",
+            "subtotal_cents": 200,
+            "tax_cents": 0,
+            "discount_cents": 0,
+            "total_cents": 200,
+            "user_id": "u-1",
+            "device_id": "pos-01",
+            "notes": null,
+            "created_at": Utc::now(),
+            "updated_at": Utc::now(),
+            "completed_at": Utc::now(),
+            "sync_version": 1,
+        });
+        let parsed = SyncPayload::parse("SALE", "s-1", 1, &header.to_string()).unwrap();
+        assert!(matches!(parsed, SyncPayload::Sale(ref doc) if doc.items.is_empty()));
+
+        let document = |sale_id: &str| {
+            let mut doc = header.clone();
+            doc["items"] = json!([{
+                "id": "si-1",
+                "sale_id": sale_id,
+                "product_id": "p-1",
+                "sku_snapshot": "COLA",
+                "name_snapshot": "Cola",
+                "unit_price_cents": 100,
+                "quantity": 2,
+                "line_total_cents": 200,
+                "tax_cents": 0,
+                "discount_cents": 0,
+                "created_at": Utc::now(),
+            }]);
+            doc["payments"] = json!([]);
+            doc.to_string()
+        };
+        match SyncPayload::parse("SALE", "s-1", 1, &document("s-1")).unwrap() {
+            SyncPayload::Sale(doc) => assert_eq!(doc.items.len(), 1),
+            other => panic!("expected a sale, got {:?}", other),
+        }
+        assert!(matches!(
+            SyncPayload::parse("SALE", "s-1", 1, &document("s-2")),
+            Err(PayloadError::Invalid { .. })
+        ));
+    }
+}
@@ -0,0 +1,285 @@
+//! # Sales Tax Report
+//!
+//! Taxable and non-taxable sales and the tax collected, per tax rate, for
+//! a range of days. This is the figure a store files with its tax
+//! authority, so it is built from what was charged (the frozen line tax and
+//! rate on each sale item), never recomputed from today's products.
+//!
+//! ## Report Shape
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Sales Tax Report                                 │
+//! │                                                                         │
+//! │  completed sales, completed_at in [from 00:00, to + 1 day 00:00) UTC    │
+//! │       │ sale items grouped by (tax rate, jurisdiction)                  │
+//! │       ▼                                                                 │
+//! │  rate    jurisdiction   taxable   non-taxable   tax    lines           │
+//! │  0.00%   NY Food Tax          0      1,250.00     0       310           │
+//! │  8.00%   NY Sales Tax  4,820.00             0   385.60    912           │
+//! │  (unknown)             120.00             0     9.60      4           │
+//! │  ─────────────────────────────────────────────────────────────          │
+//! │  totals               4,940.00      1,250.00   395.20                   │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Rules
+//! - Sales are counted net of line discounts and before tax.
+//! - A line is taxable when its rate is above zero. Lines with no recorded
+//!   rate (layaway pickups, lines sold before rates were frozen) are
+//!   taxable when tax was collected on them.
+//! - Terminals do not know jurisdictions; the cloud report names them
+//!   from the tenant's tax rates.
+//! - Voided and draft sales are left out. Returnless refunds carry no
+//!   tax split and are not netted here.
+
+use chrono::{DateTime, Days, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::einvoice::format_amount;
+use crate::error::ValidationError;
+use crate::validation::ValidationResult;
+
+/// Longest range one report may cover (a little over a year, so annual
+/// filings fit).
+pub const MAX_REPORT_DAYS: u64 = 366;
+
+/// CSV header written by [`TaxReport::to_csv`].
+pub const CSV_HEADER: &str = "rate_percent,jurisdiction,taxable,non_taxable,tax,lines";
+
+/// Sales and tax at one rate (one row of the report).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaxRateSummary {
+    /// Rate the lines were taxed at, in basis points (`None`: unknown).
+    pub rate_bps: Option<u32>,
+    /// Tax jurisdiction the rate belongs to, when known.
+    pub jurisdiction: Option<String>,
+    /// Net sales on taxable lines.
+    pub taxable_cents: i64,
+    /// Net sales on non-taxable (zero-rated or exempt) lines.
+    pub non_taxable_cents: i64,
+    /// Tax collected.
+    pub tax_cents: i64,
+    /// Sale items counted.
+    pub line_count: i64,
+}
+
+/// The sales tax report for the days `from` to `to` (inclusive, UTC).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaxReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// One row per rate, lowest rate first; unknown rates last.
+    pub rates: Vec<TaxRateSummary>,
+}
+
+impl TaxReport {
+    /// Builds the report from per-rate rows, merging rows for the same
+    /// rate and jurisdiction.
+    pub fn new(from: NaiveDate, to: NaiveDate, rows: Vec<TaxRateSummary>) -> Self {
+        let mut rates: Vec<TaxRateSummary> = Vec::with_capacity(rows.len());
+        for row in rows {
+            match rates
+                .iter_mut()
+                .find(|r| r.rate_bps == row.rate_bps && r.jurisdiction == row.jurisdiction)
+            {
+                Some(r) => {
+                    r.taxable_cents += row.taxable_cents;
+                    r.non_taxable_cents += row.non_taxable_cents;
+                    r.tax_cents += row.tax_cents;
+                    r.line_count += row.line_count;
+                }
+                None => rates.push(row),
+            }
+        }
+        // None sorts first as an Option; unknown rates belong at the end
+        rates.sort_by(|a, b| {
+            (a.rate_bps.is_none(), a.rate_bps, &a.jurisdiction).cmp(&(
+                b.rate_bps.is_none(),
+                b.rate_bps,
+                &b.jurisdiction,
+            ))
+        });
+
+        TaxReport { from, to, rates }
+    }
+
+    /// Net sales on taxable lines, all rates.
+    pub fn taxable_cents(&self) -> i64 {
+        self.rates.iter().map(|r| r.taxable_cents).sum()
+    }
+
+    /// Net sales on non-taxable lines, all rates.
+    pub fn non_taxable_cents(&self) -> i64 {
+        self.rates.iter().map(|r| r.non_taxable_cents).sum()
+    }
+
+    /// Tax collected, all rates.
+    pub fn tax_cents(&self) -> i64 {
+        self.rates.iter().map(|r| r.tax_cents).sum()
+    }
+
+    /// The report as CSV: [`CSV_HEADER`], one line per rate, then a
+    /// `TOTAL` line. Amounts are decimal with `currency_decimals` places.
+    pub fn to_csv(&self, currency_decimals: u8) -> String {
+        let amount = |cents: i64| format_amount(cents, currency_decimals);
+        let mut csv = String::from(CSV_HEADER);
+        csv.push('\n');
+
+        for rate in &self.rates {
+            let percent = rate
+                .rate_bps
+                .map(|bps| format_amount(i64::from(bps), 2))
+                .unwrap_or_default();
+            csv.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                percent,
+                csv_field(rate.jurisdiction.as_deref().unwrap_or("")),
+                amount(rate.taxable_cents),
+                amount(rate.non_taxable_cents),
+                amount(rate.tax_cents),
+                rate.line_count
+            ));
+        }
+
+        csv.push_str(&format!(
+            "TOTAL,,{},{},{},{}\n",
+            amount(self.taxable_cents()),
+            amount(self.non_taxable_cents()),
+            amount(self.tax_cents()),
+            self.rates.iter().map(|r| r.line_count).sum::<i64>()
+        ));
+        csv
+    }
+
+    /// Suggested file name, e.g. `tax-report-2026-01-01-2026-03-31.csv`.
+    pub fn file_name(&self) -> String {
+        format!("tax-report-{}-{}.csv", self.from, self.to)
+    }
+}
+
+/// The completion-time window `[start, end)` covering the days `from` to
+/// `to` (inclusive, UTC).
+///
+/// ## Errors
+/// `to` before `from`, or more than [`MAX_REPORT_DAYS`] days.
+pub fn report_window(
+    from: NaiveDate,
+    to: NaiveDate,
+) -> ValidationResult<(DateTime<Utc>, DateTime<Utc>)> {
+    if to < from {
+        return Err(ValidationError::InvalidFormat {
+            field: "to".to_string(),
+            reason: "must not be before 'from'".to_string(),
+        });
+    }
+    let days = (to - from).num_days() + 1;
+    if days > MAX_REPORT_DAYS as i64 {
+        return Err(ValidationError::OutOfRange {
+            field: "days".to_string(),
+            min: 1,
+            max: MAX_REPORT_DAYS as i64,
+        });
+    }
+
+    let end = to
+        .checked_add_days(Days::new(1))
+        .ok_or_else(|| ValidationError::InvalidFormat {
+            field: "to".to_string(),
+            reason: "out of range".to_string(),
+        })?;
+
+    Ok((
+        from.and_time(NaiveTime::MIN).and_utc(),
+        end.and_time(NaiveTime::MIN).and_utc(),
+    ))
+}
+
+/// Quotes a CSV field when it holds a delimiter, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn row(rate_bps: Option<u32>, taxable: i64, non_taxable: i64, tax: i64) -> TaxRateSummary {
+        TaxRateSummary {
+            rate_bps,
+            jurisdiction: None,
+            taxable_cents: taxable,
+            non_taxable_cents: non_taxable,
+            tax_cents: tax,
+            line_count: 1,
+        }
+    }
+
+    #[test]
+    fn test_rows_merge_and_sort() {
+        let report = TaxReport::new(
+            date("2026-01-01"),
+            date("2026-01-31"),
+            vec![
+                row(None, 1000, 0, 80),
+                row(Some(800), 2000, 0, 160),
+                row(Some(0), 0, 500, 0),
+                row(Some(800), 1000, 0, 80),
+            ],
+        );
+
+        let rates: Vec<Option<u32>> = report.rates.iter().map(|r| r.rate_bps).collect();
+        assert_eq!(rates, vec![Some(0), Some(800), None]);
+        assert_eq!(report.rates[1].taxable_cents, 3000);
+        assert_eq!(report.rates[1].line_count, 2);
+
+        assert_eq!(report.taxable_cents(), 4000);
+        assert_eq!(report.non_taxable_cents(), 500);
+        assert_eq!(report.tax_cents(), 320);
+    }
+
+    #[test]
+    fn test_csv() {
+        let mut food = row(Some(0), 0, 1250, 0);
+        food.jurisdiction = Some("NY, Food".to_string());
+        let report = TaxReport::new(
+            date("2026-01-01"),
+            date("2026-01-31"),
+            vec![row(Some(825), 10_000, 0, 825), food],
+        );
+
+        assert_eq!(
+            report.to_csv(2),
+            "rate_percent,jurisdiction,taxable,non_taxable,tax,lines\n\
+             0.00,\"NY, Food\",0.00,12.50,0.00,1\n\
+             8.25,,100.00,0.00,8.25,1\n\
+             TOTAL,,100.00,12.50,8.25,2\n"
+        );
+        assert_eq!(report.file_name(), "tax-report-2026-01-01-2026-01-31.csv");
+    }
+
+    #[test]
+    fn test_report_window() {
+        let (start, end) = report_window(date("2026-03-01"), date("2026-03-31")).unwrap();
+        assert_eq!(start.to_rfc3339(), "2026-03-01T00:00:00+00:00");
+        assert_eq!(end.to_rfc3339(), "2026-04-01T00:00:00+00:00");
+
+        // A single day
+        let (start, end) = report_window(date("2026-03-01"), date("2026-03-01")).unwrap();
+        assert_eq!((end - start).num_hours(), 24);
+
+        assert!(report_window(date("2026-03-02"), date("2026-03-01")).is_err());
+        assert!(report_window(date("2025-01-01"), date("2026-12-31")).is_err());
+    }
+}
@@ -0,0 +1,302 @@
+//! # Till Sessions and Cash Variance
+//!
+//! Types and pure rules for till (cash drawer) sessions, blind close, and
+//! cash variance tracking.
+//!
+//! ## Till Session Lifecycle
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                       Till Session Lifecycle                            │
+//! │                                                                         │
+//! │  1. OPEN                                                               │
+//! │     └── Cashier declares the opening float (e.g., $100.00)             │
+//! │                                                                         │
+//! │  2. TRADE                                                              │
+//! │     └── Cash payments accumulate in the drawer                         │
+//! │     └── expected = opening_float + Σ cash payments (completed sales)   │
+//! │                                                                         │
+//! │  3. BLIND CLOSE                                                        │
+//! │     └── Cashier counts the drawer per denomination                     │
+//! │     └── Expected amount is NEVER shown during the count                │
+//! │     └── variance = counted - expected                                  │
+//! │           > 0  → drawer is OVER                                        │
+//! │           < 0  → drawer is SHORT                                       │
+//! │                                                                         │
+//! │  4. REVIEW (manager)                                                   │
+//! │     └── Large variances are recorded per cashier                       │
+//! │     └── Repeated large variances flag the cashier in the               │
+//! │         exceptions report                                              │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Why Blind Close?
+//! If the cashier can see the expected amount while counting, a short drawer
+//! can be "fixed" by miscounting. A blind count records what is physically
+//! in the drawer, so variances reflect reality.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use ts_rs::TS;
+
+use crate::error::ValidationError;
+use crate::validation::ValidationResult;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Absolute variance (in cents) at or above which a close is "large".
+///
+/// ## Business Reason
+/// Small variances (a few coins) are normal drawer noise. $5.00 is a common
+/// retail threshold for manager review. Configurable per-tenant later.
+pub const DEFAULT_LARGE_VARIANCE_CENTS: i64 = 500;
+
+/// Number of large-variance closes before a cashier is flagged.
+pub const DEFAULT_VARIANCE_FLAG_COUNT: i64 = 3;
+
+/// Maximum quantity of a single denomination in one count.
+///
+/// Guards against typos like 1000 instead of 10 on the count screen.
+pub const MAX_DENOMINATION_QUANTITY: i64 = 10_000;
+
+// =============================================================================
+// Till Session Status
+// =============================================================================
+
+/// The status of a till session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "lowercase"))]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum TillSessionStatus {
+    /// Drawer is open and taking cash.
+    #[default]
+    Open,
+    /// Drawer has been counted and closed.
+    Closed,
+}
+
+// =============================================================================
+// Till Session
+// =============================================================================
+
+/// A till (cash drawer) session on a single terminal.
+///
+/// `expected_cash_cents`, `counted_cash_cents` and `variance_cents` are only
+/// populated once the session is closed.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TillSession {
+    pub id: String,
+    pub tenant_id: String,
+    /// Terminal the drawer belongs to.
+    pub device_id: String,
+    /// Cashier who opened the drawer.
+    pub user_id: String,
+    pub status: TillSessionStatus,
+    /// Cash placed in the drawer at open.
+    pub opening_float_cents: i64,
+    /// Opening float + cash taken (computed at close).
+    pub expected_cash_cents: Option<i64>,
+    /// Sum of the denomination count (entered at close).
+    pub counted_cash_cents: Option<i64>,
+    /// counted - expected (positive = over, negative = short).
+    pub variance_cents: Option<i64>,
+    /// Whether the close was performed blind.
+    pub blind_close: bool,
+    /// Cashier who performed the close.
+    pub closed_by: Option<String>,
+    pub notes: Option<String>,
+    #[ts(as = "String")]
+    pub opened_at: DateTime<Utc>,
+    #[ts(as = "Option<String>")]
+    pub closed_at: Option<DateTime<Utc>>,
+    pub sync_version: i64,
+}
+
+impl TillSession {
+    /// Returns true if the session is still open.
+    #[inline]
+    pub fn is_open(&self) -> bool {
+        self.status == TillSessionStatus::Open
+    }
+}
+
+// =============================================================================
+// Denomination Count
+// =============================================================================
+
+/// Quantity of a single denomination counted in the drawer.
+///
+/// ## Example
+/// ```text
+/// $20 bills × 4  → DenominationCount { denomination_cents: 2000, quantity: 4 }
+/// 25¢ coins × 12 → DenominationCount { denomination_cents: 25,   quantity: 12 }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DenominationCount {
+    /// Face value of the note/coin in cents.
+    pub denomination_cents: i64,
+    /// Number of notes/coins counted.
+    pub quantity: i64,
+}
+
+impl DenominationCount {
+    /// Returns the value of this line (denomination × quantity).
+    #[inline]
+    pub fn total_cents(&self) -> i64 {
+        self.denomination_cents * self.quantity
+    }
+}
+
+/// Sums a denomination count into a cash total.
+pub fn counted_total_cents(counts: &[DenominationCount]) -> i64 {
+    counts.iter().map(DenominationCount::total_cents).sum()
+}
+
+/// Validates a denomination count before it is recorded.
+///
+/// ## Rules
+/// - Denomination must be positive
+/// - Quantity must be between 0 and [`MAX_DENOMINATION_QUANTITY`]
+/// - Each denomination may appear only once
+pub fn validate_denomination_counts(counts: &[DenominationCount]) -> ValidationResult<()> {
+    let mut seen = HashSet::new();
+
+    for count in counts {
+        if count.denomination_cents <= 0 {
+            return Err(ValidationError::MustBePositive {
+                field: "denomination_cents".to_string(),
+            });
+        }
+
+        if count.quantity < 0 || count.quantity > MAX_DENOMINATION_QUANTITY {
+            return Err(ValidationError::OutOfRange {
+                field: "quantity".to_string(),
+                min: 0,
+                max: MAX_DENOMINATION_QUANTITY,
+            });
+        }
+
+        if !seen.insert(count.denomination_cents) {
+            return Err(ValidationError::Duplicate {
+                field: "denomination_cents".to_string(),
+                value: count.denomination_cents.to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+// =============================================================================
+// Variance Rules
+// =============================================================================
+
+/// Calculates drawer variance (positive = over, negative = short).
+#[inline]
+pub fn calculate_variance(expected_cents: i64, counted_cents: i64) -> i64 {
+    counted_cents - expected_cents
+}
+
+/// Thresholds for flagging cashiers with repeated large variances.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct VariancePolicy {
+    /// Absolute variance at or above which a close counts as "large".
+    pub large_variance_cents: i64,
+    /// Number of large closes before the cashier is flagged.
+    pub flag_after: i64,
+}
+
+impl Default for VariancePolicy {
+    fn default() -> Self {
+        VariancePolicy {
+            large_variance_cents: DEFAULT_LARGE_VARIANCE_CENTS,
+            flag_after: DEFAULT_VARIANCE_FLAG_COUNT,
+        }
+    }
+}
+
+impl VariancePolicy {
+    /// Returns true if the variance (over or short) is large.
+    #[inline]
+    pub fn is_large(&self, variance_cents: i64) -> bool {
+        variance_cents.abs() >= self.large_variance_cents
+    }
+
+    /// Returns true if a cashier with this many large closes should be flagged.
+    #[inline]
+    pub fn should_flag(&self, large_variance_count: i64) -> bool {
+        large_variance_count >= self.flag_after
+    }
+}
+
+/// A cashier with repeated large variances (exceptions report row).
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct VarianceException {
+    pub user_id: String,
+    /// Number of closes with a large variance in the report window.
+    pub large_variance_count: i64,
+    /// Net variance across those closes (over and short cancel out).
+    pub net_variance_cents: i64,
+    /// Sum of absolute variances across those closes.
+    pub absolute_variance_cents: i64,
+    #[ts(as = "Option<String>")]
+    pub last_closed_at: Option<DateTime<Utc>>,
+}
+
+// =============================================================================
+// Unit Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(denomination_cents: i64, quantity: i64) -> DenominationCount {
+        DenominationCount {
+            denomination_cents,
+            quantity,
+        }
+    }
+
+    #[test]
+    fn test_counted_total() {
+        let counts = [count(2000, 4), count(500, 3), count(25, 12)];
+        // $80 + $15 + $3 = $98
+        assert_eq!(counted_total_cents(&counts), 9800);
+        assert_eq!(counted_total_cents(&[]), 0);
+    }
+
+    #[test]
+    fn test_variance_sign() {
+        assert_eq!(calculate_variance(10000, 9800), -200); // short
+        assert_eq!(calculate_variance(10000, 10300), 300); // over
+        assert_eq!(calculate_variance(10000, 10000), 0);
+    }
+
+    #[test]
+    fn test_validate_counts() {
+        assert!(validate_denomination_counts(&[count(100, 5), count(25, 0)]).is_ok());
+        assert!(validate_denomination_counts(&[count(0, 5)]).is_err());
+        assert!(validate_denomination_counts(&[count(100, -1)]).is_err());
+        assert!(validate_denomination_counts(&[count(100, 1), count(100, 2)]).is_err());
+        assert!(validate_denomination_counts(&[count(100, MAX_DENOMINATION_QUANTITY + 1)]).is_err());
+    }
+
+    #[test]
+    fn test_variance_policy() {
+        let policy = VariancePolicy::default();
+        assert!(policy.is_large(-500));
+        assert!(policy.is_large(750));
+        assert!(!policy.is_large(-499));
+        assert!(!policy.should_flag(2));
+        assert!(policy.should_flag(3));
+    }
+}
@@ -0,0 +1,133 @@
+//! # Soft Delete and Tombstones
+//!
+//! Products and business customers are never removed from the database:
+//! deleting one sets its `deleted_at`, restoring clears it. Either change
+//! bumps the entity's `sync_version` and is shared with the other
+//! terminals as a tombstone.
+//!
+//! ## Convergence
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                    Tombstone Propagation                                │
+//! │                                                                         │
+//! │  Terminal A: soft_delete(id)                                           │
+//! │     deleted_at = now, sync_version 4 → 5                               │
+//! │     outbox TOMBSTONE { action: delete, version: 5 }                    │
+//! │       │                                                                 │
+//! │       ▼                                                                 │
+//! │  Hub: applies to its own copy, relays as EntityUpdate                  │
+//! │       operation "delete" (or "restore"), version 5                     │
+//! │       │                                                                 │
+//! │       ▼                                                                 │
+//! │  Terminal B: applies only if its sync_version < 5                      │
+//! │                                                                         │
+//! │  Stale upserts (version <= 5) arriving later are skipped by the usual  │
+//! │  version check, so a delete is not undone by an older edit. The       │
+//! │  outbox keeps one tombstone per entity: delete-then-restore before a   │
+//! │  sync sends only the restore.                                          │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Sales are not soft-deleted: a sale is voided, which keeps it in the
+//! books and syncs like any other change of status.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Entity types (as used in entity updates) that can be soft-deleted.
+pub const SOFT_DELETE_ENTITY_TYPES: &[&str] = &["product", "business_customer"];
+
+/// Whether a tombstone deletes or restores its entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum TombstoneAction {
+    Delete,
+    Restore,
+}
+
+impl TombstoneAction {
+    /// The entity update operation that carries this action.
+    pub fn operation(&self) -> &'static str {
+        match self {
+            TombstoneAction::Delete => "delete",
+            TombstoneAction::Restore => "restore",
+        }
+    }
+}
+
+/// A soft delete or restore, as queued for sync (`TOMBSTONE`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Tombstone {
+    /// One of [`SOFT_DELETE_ENTITY_TYPES`].
+    pub entity_type: String,
+    pub entity_id: String,
+    pub action: TombstoneAction,
+    /// The entity's `sync_version` after the change.
+    #[ts(type = "number")]
+    pub version: i64,
+    /// When the change was made.
+    #[ts(as = "String")]
+    pub at: DateTime<Utc>,
+}
+
+impl Tombstone {
+    /// A tombstone for `action` on an entity now at `version`.
+    pub fn new(
+        entity_type: &str,
+        entity_id: &str,
+        action: TombstoneAction,
+        version: i64,
+        at: DateTime<Utc>,
+    ) -> Self {
+        Tombstone {
+            entity_type: entity_type.to_string(),
+            entity_id: entity_id.to_string(),
+            action,
+            version,
+            at,
+        }
+    }
+
+    /// `deleted_at` once the tombstone is applied.
+    pub fn deleted_at(&self) -> Option<DateTime<Utc>> {
+        match self.action {
+            TombstoneAction::Delete => Some(self.at),
+            TombstoneAction::Restore => None,
+        }
+    }
+
+    /// Whether the tombstone is newer than an entity at `current_version`.
+    pub fn supersedes(&self, current_version: i64) -> bool {
+        self.version > current_version
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deleted_at_follows_action() {
+        let at = Utc::now();
+        let delete = Tombstone::new("product", "p-1", TombstoneAction::Delete, 5, at);
+        assert_eq!(delete.deleted_at(), Some(at));
+        let restore = Tombstone::new("product", "p-1", TombstoneAction::Restore, 6, at);
+        assert_eq!(restore.deleted_at(), None);
+        assert_eq!(restore.action.operation(), "restore");
+    }
+
+    #[test]
+    fn test_only_newer_tombstones_apply() {
+        let tombstone = Tombstone::new("product", "p-1", TombstoneAction::Delete, 5, Utc::now());
+        assert!(tombstone.supersedes(4));
+        assert!(!tombstone.supersedes(5));
+        assert!(!tombstone.supersedes(6));
+    }
+}
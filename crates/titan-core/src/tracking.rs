@@ -0,0 +1,231 @@
+//! # Serial & Lot Tracking
+//!
+//! Types and pure rules for capturing serial or lot numbers when regulated
+//! products are sold, so a recall can find every sale of an affected batch.
+//!
+//! ## Capture Rules
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                     Tracking Code Capture                               │
+//! │                                                                         │
+//! │  Product.item_tracking    Codes required per cart line                  │
+//! │  ──────────────────────   ──────────────────────────────────────────── │
+//! │  None                     none (codes rejected)                         │
+//! │  Serial                   one unique serial per unit (qty 3 → 3 codes)  │
+//! │  Lot                      exactly one lot number for the line           │
+//! │                                                                         │
+//! │  Checked twice:                                                         │
+//! │    add_to_cart   - for the units being added                            │
+//! │    finalize_sale - for the full line, so sales created another way     │
+//! │                    (e.g. from a quote) cannot skip capture              │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::ValidationError;
+use crate::validation::ValidationResult;
+
+/// Maximum length of a serial or lot number.
+pub const MAX_TRACKING_CODE_LENGTH: usize = 64;
+
+// =============================================================================
+// Item Tracking
+// =============================================================================
+
+/// Whether a product needs serial or lot numbers captured at sale time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "lowercase"))]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemTracking {
+    /// No capture required.
+    #[default]
+    None,
+    /// Every unit carries its own serial number.
+    Serial,
+    /// Units come from a production lot/batch.
+    Lot,
+}
+
+impl ItemTracking {
+    /// Lowercase name, as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ItemTracking::None => "none",
+            ItemTracking::Serial => "serial",
+            ItemTracking::Lot => "lot",
+        }
+    }
+}
+
+// =============================================================================
+// Captured Codes
+// =============================================================================
+
+/// A serial or lot number captured for a sale line.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SaleItemTracking {
+    pub id: String,
+    pub sale_id: String,
+    pub sale_item_id: String,
+    pub product_id: String,
+    pub kind: ItemTracking,
+    pub code: String,
+    #[ts(as = "String")]
+    pub created_at: DateTime<Utc>,
+}
+
+/// A completed sale line matched by a recall lookup.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TrackedSaleLine {
+    pub sale_id: String,
+    pub receipt_number: String,
+    pub device_id: String,
+    #[ts(as = "Option<String>")]
+    pub completed_at: Option<DateTime<Utc>>,
+    pub product_id: String,
+    pub sku_snapshot: String,
+    pub name_snapshot: String,
+    pub quantity: i64,
+    pub kind: ItemTracking,
+    pub code: String,
+}
+
+// =============================================================================
+// Rules
+// =============================================================================
+
+/// Trims surrounding whitespace from scanned/typed codes and drops blanks.
+pub fn normalize_tracking_codes(codes: &[String]) -> Vec<String> {
+    codes
+        .iter()
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .collect()
+}
+
+/// Validates the codes captured for `quantity` units of a product.
+///
+/// ## Rules
+/// - `None`: no codes allowed
+/// - `Serial`: exactly `quantity` codes, no duplicates
+/// - `Lot`: exactly one code
+/// - Every code at most [`MAX_TRACKING_CODE_LENGTH`] characters
+pub fn validate_tracking_codes(
+    tracking: ItemTracking,
+    quantity: i64,
+    codes: &[String],
+) -> ValidationResult<()> {
+    let expected = match tracking {
+        ItemTracking::None => 0,
+        ItemTracking::Serial => quantity.max(0) as usize,
+        ItemTracking::Lot => 1,
+    };
+
+    if codes.len() != expected {
+        return Err(match (tracking, codes.is_empty()) {
+            (ItemTracking::None, _) => ValidationError::NotAllowed {
+                field: "tracking_codes".to_string(),
+                allowed: Vec::new(),
+            },
+            (ItemTracking::Serial, true) => ValidationError::Required {
+                field: "serial_number".to_string(),
+            },
+            (ItemTracking::Lot, true) => ValidationError::Required {
+                field: "lot_number".to_string(),
+            },
+            (ItemTracking::Serial, false) => ValidationError::OutOfRange {
+                field: "serial_numbers".to_string(),
+                min: expected as i64,
+                max: expected as i64,
+            },
+            (ItemTracking::Lot, false) => ValidationError::OutOfRange {
+                field: "lot_numbers".to_string(),
+                min: 1,
+                max: 1,
+            },
+        });
+    }
+
+    let mut seen = HashSet::new();
+    for code in codes {
+        if code.trim().is_empty() {
+            return Err(ValidationError::Required {
+                field: format!("{}_number", tracking.as_str()),
+            });
+        }
+        if code.chars().count() > MAX_TRACKING_CODE_LENGTH {
+            return Err(ValidationError::TooLong {
+                field: format!("{}_number", tracking.as_str()),
+                max: MAX_TRACKING_CODE_LENGTH,
+            });
+        }
+        if !seen.insert(code.as_str()) {
+            return Err(ValidationError::Duplicate {
+                field: "serial_number".to_string(),
+                value: code.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+// =============================================================================
+// Unit Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_untracked_rejects_codes() {
+        assert!(validate_tracking_codes(ItemTracking::None, 3, &[]).is_ok());
+        assert!(validate_tracking_codes(ItemTracking::None, 1, &codes(&["X"])).is_err());
+    }
+
+    #[test]
+    fn test_serial_needs_one_unique_code_per_unit() {
+        let t = ItemTracking::Serial;
+        assert!(validate_tracking_codes(t, 2, &codes(&["SN1", "SN2"])).is_ok());
+        assert!(matches!(
+            validate_tracking_codes(t, 2, &[]),
+            Err(ValidationError::Required { .. })
+        ));
+        assert!(validate_tracking_codes(t, 2, &codes(&["SN1"])).is_err());
+        assert!(matches!(
+            validate_tracking_codes(t, 2, &codes(&["SN1", "SN1"])),
+            Err(ValidationError::Duplicate { .. })
+        ));
+    }
+
+    #[test]
+    fn test_lot_needs_exactly_one_code() {
+        let t = ItemTracking::Lot;
+        assert!(validate_tracking_codes(t, 12, &codes(&["LOT-2024-07"])).is_ok());
+        assert!(validate_tracking_codes(t, 12, &[]).is_err());
+        assert!(validate_tracking_codes(t, 12, &codes(&["A", "B"])).is_err());
+        assert!(validate_tracking_codes(t, 1, &codes(&[&"9".repeat(65)])).is_err());
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            normalize_tracking_codes(&codes(&[" LOT1 ", "", "  "])),
+            codes(&["LOT1"])
+        );
+    }
+}
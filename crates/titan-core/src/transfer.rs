@@ -0,0 +1,318 @@
+//! # Store Transfers
+//!
+//! Types and pure rules for moving stock between stores of the same tenant.
+//! The sending store ships goods (its stock goes down), the cloud routes the
+//! transfer document to the receiving store, and the receiving store counts
+//! the goods in (its stock goes up).
+//!
+//! ## Transfer Lifecycle
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Store Transfer Lifecycle                         │
+//! │                                                                         │
+//! │  Store A (sender)            Cloud               Store B (receiver)     │
+//! │  ────────────────            ─────               ──────────────────     │
+//! │  create ──► IN_TRANSIT ──► routes to B ──────► IN_TRANSIT (inbound)     │
+//! │  stock -= sent                                        │                 │
+//! │                                                       ▼ receive         │
+//! │  RECEIVED (read-only) ◄── routes to A ◄──────── RECEIVED                │
+//! │                                                 stock += received       │
+//! │                                                                         │
+//! │  While IN_TRANSIT the sender may cancel instead:                        │
+//! │  CANCELLED, stock += sent at A; B sees the cancellation on next sync.   │
+//! │                                                                         │
+//! │  Both stores can list the transfer while it is in transit.              │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Receiving Variance
+//! The receiver records what actually arrived per line. A shortfall is kept
+//! on the document (`quantity_sent - quantity_received`) for follow-up; only
+//! the received quantity is added to the receiving store's stock.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::{CoreError, ValidationError};
+use crate::validation::ValidationResult;
+
+// =============================================================================
+// Transfer Status
+// =============================================================================
+
+/// The status of a store transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "snake_case"))]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferStatus {
+    /// Shipped by the sender, not yet counted in by the receiver.
+    #[default]
+    InTransit,
+    /// Counted in by the receiving store.
+    Received,
+    /// Cancelled by the sender before receipt; goods back in its stock.
+    Cancelled,
+}
+
+impl TransferStatus {
+    /// Name as stored in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransferStatus::InTransit => "in_transit",
+            TransferStatus::Received => "received",
+            TransferStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// Which side of a transfer the local store is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferDirection {
+    /// Local store is the sender.
+    Outbound,
+    /// Local store is the receiver.
+    Inbound,
+}
+
+// =============================================================================
+// Transfer
+// =============================================================================
+
+/// A store transfer header.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct StoreTransfer {
+    pub id: String,
+    pub tenant_id: String,
+    /// Human-readable number printed on the packing slip.
+    pub transfer_number: String,
+    pub from_store_id: String,
+    pub to_store_id: String,
+    pub status: TransferStatus,
+    pub notes: Option<String>,
+    /// Cashier who shipped the goods.
+    pub created_by: String,
+    /// Cashier who counted the goods in.
+    pub received_by: Option<String>,
+    #[ts(as = "String")]
+    pub created_at: DateTime<Utc>,
+    #[ts(as = "String")]
+    pub updated_at: DateTime<Utc>,
+    #[ts(as = "Option<String>")]
+    pub received_at: Option<DateTime<Utc>>,
+    #[ts(as = "Option<String>")]
+    pub cancelled_at: Option<DateTime<Utc>>,
+    pub sync_version: i64,
+}
+
+impl StoreTransfer {
+    /// Which side of this transfer `store_id` is on, if either.
+    pub fn direction_for(&self, store_id: &str) -> Option<TransferDirection> {
+        if self.from_store_id == store_id {
+            Some(TransferDirection::Outbound)
+        } else if self.to_store_id == store_id {
+            Some(TransferDirection::Inbound)
+        } else {
+            None
+        }
+    }
+
+    /// Checks that the transfer can still be received or cancelled.
+    ///
+    /// ## Errors
+    /// `CoreError::InvalidTransferStatus` if it is received or cancelled.
+    pub fn ensure_in_transit(&self) -> Result<(), CoreError> {
+        if self.status == TransferStatus::InTransit {
+            Ok(())
+        } else {
+            Err(CoreError::InvalidTransferStatus {
+                transfer_id: self.id.clone(),
+                current_status: self.status.as_str().to_string(),
+            })
+        }
+    }
+}
+
+/// A line on a store transfer.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct StoreTransferItem {
+    pub id: String,
+    pub transfer_id: String,
+    pub product_id: String,
+    pub sku_snapshot: String,
+    pub name_snapshot: String,
+    pub quantity_sent: i64,
+    /// Set when the receiver counts the line in.
+    pub quantity_received: Option<i64>,
+}
+
+impl StoreTransferItem {
+    /// Units sent but not received (0 until received).
+    pub fn shortfall(&self) -> i64 {
+        self.quantity_received
+            .map(|received| self.quantity_sent - received)
+            .unwrap_or(0)
+    }
+}
+
+/// A transfer with its lines, as synced between stores.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct StoreTransferDocument {
+    #[serde(flatten)]
+    pub transfer: StoreTransfer,
+    pub items: Vec<StoreTransferItem>,
+}
+
+// =============================================================================
+// Rules
+// =============================================================================
+
+/// Validates the stores on a new transfer.
+pub fn validate_transfer_route(from_store_id: &str, to_store_id: &str) -> ValidationResult<()> {
+    if from_store_id.trim().is_empty() {
+        return Err(ValidationError::Required {
+            field: "from_store_id".to_string(),
+        });
+    }
+    if to_store_id.trim().is_empty() {
+        return Err(ValidationError::Required {
+            field: "to_store_id".to_string(),
+        });
+    }
+    if from_store_id == to_store_id {
+        return Err(ValidationError::InvalidFormat {
+            field: "to_store_id".to_string(),
+            reason: "must be a different store".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Validates the lines on a new transfer.
+///
+/// ## Rules
+/// - At least one line
+/// - Every quantity positive
+/// - Each product at most once
+pub fn validate_transfer_items(items: &[StoreTransferItem]) -> ValidationResult<()> {
+    if items.is_empty() {
+        return Err(ValidationError::Required {
+            field: "items".to_string(),
+        });
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    for item in items {
+        if item.quantity_sent <= 0 {
+            return Err(ValidationError::MustBePositive {
+                field: "quantity".to_string(),
+            });
+        }
+        if !seen.insert(item.product_id.as_str()) {
+            return Err(ValidationError::Duplicate {
+                field: "product".to_string(),
+                value: item.sku_snapshot.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Validates a counted-in quantity against what was sent.
+pub fn validate_received_quantity(sent: i64, received: i64) -> ValidationResult<()> {
+    if received < 0 || received > sent {
+        return Err(ValidationError::OutOfRange {
+            field: "quantity_received".to_string(),
+            min: 0,
+            max: sent,
+        });
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Unit Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(product_id: &str, sent: i64) -> StoreTransferItem {
+        StoreTransferItem {
+            id: format!("i-{}", product_id),
+            transfer_id: "t1".to_string(),
+            product_id: product_id.to_string(),
+            sku_snapshot: format!("SKU-{}", product_id),
+            name_snapshot: "Item".to_string(),
+            quantity_sent: sent,
+            quantity_received: None,
+        }
+    }
+
+    fn transfer(status: TransferStatus) -> StoreTransfer {
+        StoreTransfer {
+            id: "t1".to_string(),
+            tenant_id: "tenant".to_string(),
+            transfer_number: "T-1".to_string(),
+            from_store_id: "store-a".to_string(),
+            to_store_id: "store-b".to_string(),
+            status,
+            notes: None,
+            created_by: "u".to_string(),
+            received_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            received_at: None,
+            cancelled_at: None,
+            sync_version: 1,
+        }
+    }
+
+    #[test]
+    fn test_route_must_be_two_stores() {
+        assert!(validate_transfer_route("store-a", "store-b").is_ok());
+        assert!(validate_transfer_route("store-a", "store-a").is_err());
+        assert!(validate_transfer_route("store-a", " ").is_err());
+    }
+
+    #[test]
+    fn test_items_validation() {
+        assert!(validate_transfer_items(&[item("p1", 5), item("p2", 1)]).is_ok());
+        assert!(validate_transfer_items(&[]).is_err());
+        assert!(validate_transfer_items(&[item("p1", 0)]).is_err());
+        assert!(matches!(
+            validate_transfer_items(&[item("p1", 1), item("p1", 2)]),
+            Err(ValidationError::Duplicate { .. })
+        ));
+    }
+
+    #[test]
+    fn test_received_quantity_and_shortfall() {
+        assert!(validate_received_quantity(10, 10).is_ok());
+        assert!(validate_received_quantity(10, 0).is_ok());
+        assert!(validate_received_quantity(10, 11).is_err());
+
+        let mut line = item("p1", 10);
+        assert_eq!(line.shortfall(), 0);
+        line.quantity_received = Some(8);
+        assert_eq!(line.shortfall(), 2);
+    }
+
+    #[test]
+    fn test_direction_and_status() {
+        let t = transfer(TransferStatus::InTransit);
+        assert_eq!(t.direction_for("store-a"), Some(TransferDirection::Outbound));
+        assert_eq!(t.direction_for("store-b"), Some(TransferDirection::Inbound));
+        assert_eq!(t.direction_for("store-c"), None);
+        assert!(t.ensure_in_transit().is_ok());
+        assert!(transfer(TransferStatus::Received).ensure_in_transit().is_err());
+    }
+}
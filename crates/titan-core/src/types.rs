@@ -21,8 +21,9 @@
 //! │  │  ─────────────  │   │  ─────────────  │   │  ─────────────  │       │
 //! │  │  bps (u32)      │   │  Draft          │   │  Cash           │       │
 //! │  │  825 = 8.25%    │   │  Completed      │   │  ExternalCard   │       │
-//! │  └─────────────────┘   │  Voided         │   └─────────────────┘       │
-//! │                        └─────────────────┘                              │
+//! │  └─────────────────┘   │  Voided         │   │  StoreCredit    │       │
+//! │                        └─────────────────┘   │  LoyaltyReward  │       │
+//! │                                              └─────────────────┘       │
 //! └─────────────────────────────────────────────────────────────────────────┘
 //! ```
 //!
@@ -36,6 +37,7 @@ use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
 use crate::money::Money;
+use crate::tracking::ItemTracking;
 
 // =============================================================================
 // Tax Rate
@@ -119,9 +121,14 @@ pub struct Product {
     /// Optional description for product details.
     pub description: Option<String>,
 
-    /// Price in cents (smallest currency unit).
+    /// Price in cents (smallest currency unit) charged at this store.
     pub price_cents: i64,
 
+    /// Tenant base price when `price_cents` is a store override (None =
+    /// `price_cents` is the base price).
+    #[serde(default)]
+    pub base_price_cents: Option<i64>,
+
     /// Cost in cents (for profit margin calculations).
     pub cost_cents: Option<i64>,
 
@@ -137,9 +144,22 @@ pub struct Product {
     /// Current stock level.
     pub current_stock: Option<i64>,
 
-    /// Whether product is active (soft delete).
+    /// Serial/lot capture required at sale time.
+    #[serde(default)]
+    pub item_tracking: ItemTracking,
+
+    /// Minimum customer age to buy this product (None = unrestricted).
+    #[serde(default)]
+    pub min_purchase_age: Option<u32>,
+
+    /// Whether product is offered for sale.
     pub is_active: bool,
 
+    /// When the product was deleted (see [`crate::tombstone`]).
+    #[serde(default)]
+    #[ts(as = "Option<String>")]
+    pub deleted_at: Option<DateTime<Utc>>,
+
     /// When the product was created.
     #[ts(as = "String")]
     pub created_at: DateTime<Utc>,
@@ -165,6 +185,13 @@ impl Product {
         TaxRate::from_bps(self.tax_rate_bps)
     }
 
+    /// Whether this store sells the product at its own price rather than
+    /// the tenant's.
+    #[inline]
+    pub fn has_price_override(&self) -> bool {
+        self.base_price_cents.is_some_and(|base| base != self.price_cents)
+    }
+
     /// Checks if product can be sold (in stock or doesn't track inventory).
     pub fn can_sell(&self, quantity: i64) -> bool {
         if !self.track_inventory {
@@ -214,6 +241,10 @@ pub enum PaymentMethod {
     Cash,
     /// Card payment on external terminal.
     ExternalCard,
+    /// Store credit; `Payment::reference` holds the credit number.
+    StoreCredit,
+    /// Loyalty rewards; `Payment::reference` holds the member number.
+    LoyaltyReward,
 }
 
 // =============================================================================
@@ -234,6 +265,11 @@ pub struct Sale {
     pub total_cents: i64,
     pub user_id: String,
     pub device_id: String,
+    /// Register the sale was rung up on (see `titan_core::station`);
+    /// `None` for sales from before stations were configured.
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub station_number: Option<i64>,
     pub notes: Option<String>,
     #[ts(as = "String")]
     pub created_at: DateTime<Utc>,
@@ -270,6 +306,14 @@ pub struct SaleItem {
     pub tax_cents: i64,
     /// Discount applied to this line.
     pub discount_cents: i64,
+    /// Tenant base price when the line was sold at a store override price
+    /// (frozen).
+    #[serde(default)]
+    pub base_price_cents: Option<i64>,
+    /// Tax rate the line was taxed at, in basis points (frozen; `None` when
+    /// not known, e.g. layaway pickups).
+    #[serde(default)]
+    pub tax_rate_bps: Option<u32>,
     #[ts(as = "String")]
     pub created_at: DateTime<Utc>,
 }
@@ -320,6 +364,22 @@ impl Payment {
     }
 }
 
+/// A completed sale with its lines and payments, as stored in the sync
+/// outbox and relayed to the other terminals.
+///
+/// Terminals before relaying queued the sale alone, so `items` and
+/// `payments` may be missing.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SaleDocument {
+    #[serde(flatten)]
+    pub sale: Sale,
+    #[serde(default)]
+    pub items: Vec<SaleItem>,
+    #[serde(default)]
+    pub payments: Vec<Payment>,
+}
+
 // =============================================================================
 // Sync Outbox
 // =============================================================================
@@ -349,6 +409,100 @@ pub struct SyncOutboxEntry {
     /// When successfully synced.
     #[ts(as = "Option<String>")]
     pub synced_at: Option<DateTime<Utc>>,
+    /// Correlation ID of the command that queued the entry, if any.
+    pub correlation_id: Option<String>,
+    /// W3C `traceparent` of the command that queued the entry, when spans
+    /// are exported.
+    pub trace_context: Option<String>,
+}
+
+/// How far an outbox entry has got on its way to the cloud.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "snake_case"))]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum AckLevel {
+    /// Not acked; sent on the next poll.
+    Pending,
+    /// The store hub has it. Entries that only go to the hub are done
+    /// here; cloud-bound ones are held until the cloud acks them.
+    HubAcked,
+    /// The cloud has it.
+    CloudAcked,
+}
+
+/// An outbox entry as shown in the outbox inspector, without its payload.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct OutboxEntryStatus {
+    pub id: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub ack_level: AckLevel,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    /// Hub holding the entry until the cloud acks it.
+    pub held_by_hub: Option<String>,
+    #[ts(as = "String")]
+    pub created_at: DateTime<Utc>,
+    #[ts(as = "Option<String>")]
+    pub hub_acked_at: Option<DateTime<Utc>>,
+    #[ts(as = "Option<String>")]
+    pub cloud_acked_at: Option<DateTime<Utc>>,
+    #[ts(as = "Option<String>")]
+    pub synced_at: Option<DateTime<Utc>>,
+}
+
+/// Unsynced outbox entries by ack level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct OutboxAckCounts {
+    /// Not acked by the hub yet.
+    pub pending: i64,
+    /// Held by the hub, waiting for the cloud's ack.
+    pub hub_acked: i64,
+}
+
+impl OutboxAckCounts {
+    /// Entries not in the cloud (or, for hub-only entries, the hub) yet.
+    pub fn total(&self) -> i64 {
+        self.pending + self.hub_acked
+    }
+}
+
+/// A sync payload that failed its schema, held for inspection instead of
+/// being sent or applied.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct QuarantinedPayload {
+    pub id: String,
+    pub tenant_id: String,
+    /// Where it came from: "outbox" for this device's own queue, otherwise
+    /// the ID of the device that sent it to the hub.
+    pub source: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    /// The payload exactly as received.
+    pub payload: String,
+    /// Schema version the payload claimed.
+    pub schema_version: i64,
+    /// Why it failed validation.
+    pub reason: String,
+    #[ts(as = "String")]
+    pub quarantined_at: DateTime<Utc>,
+}
+
+/// The hub a terminal last completed a handshake with, tried first on the
+/// next start before falling back to discovery.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnownHub {
+    pub store_id: String,
+    /// WebSocket URL the handshake succeeded on.
+    pub hub_url: String,
+    /// Election term the hub reported in its Welcome.
+    pub election_term: i64,
+    pub connected_at: DateTime<Utc>,
 }
 
 // =============================================================================
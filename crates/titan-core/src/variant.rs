@@ -0,0 +1,570 @@
+//! # Product Variants
+//!
+//! Styles group products that are one item in several sizes, colors, ...
+//! (matrix items). Every variant stays a full product with its own SKU,
+//! barcode, price and stock, so scanning, the cart and sync need nothing
+//! new; the style adds the shared name and description and says which
+//! attributes (see [`crate::attribute`]) tell its variants apart.
+//!
+//! ## Model
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Style and Variants                               │
+//! │                                                                         │
+//! │  style "Classic Tee"   option_keys = [size, color]                     │
+//! │    │                                                                    │
+//! │    ├── TEE-S-BLK   size=S  color=black   $19.99   barcode 4006…01       │
+//! │    ├── TEE-M-BLK   size=M  color=black   $19.99   barcode 4006…02       │
+//! │    ├── TEE-M-WHT   size=M  color=white   $19.99   barcode 4006…03       │
+//! │    └── TEE-XL-BLK  size=XL color=black   $21.99   barcode 4006…04       │
+//! │                                                                         │
+//! │  Matrix (rows: first option, columns: the others)                      │
+//! │            black        white                                          │
+//! │    S       TEE-S-BLK    -                                              │
+//! │    M       TEE-M-BLK    TEE-M-WHT                                      │
+//! │    XL      TEE-XL-BLK   -                                              │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Rules
+//! - A style has 1 to [`MAX_OPTION_KEYS`] option keys, normalized like
+//!   attribute keys
+//! - A variant has a non-empty value for every option key, and no two
+//!   variants of a style share all their values (compared
+//!   case-insensitively)
+//! - A variant without its own description shows the style's
+//! - Option values keep the order variants list them in (sizes do not
+//!   sort alphabetically)
+//!
+//! ## Sync
+//! A style syncs as one `PRODUCT_STYLE` document holding its variant IDs;
+//! the option values sync with each variant's attributes.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::attribute::{normalize_key, ProductAttribute};
+use crate::error::ValidationError;
+use crate::types::Product;
+use crate::validation::ValidationResult;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Most attributes one style may vary by.
+pub const MAX_OPTION_KEYS: usize = 3;
+
+/// Longest style name.
+pub const MAX_STYLE_NAME_LEN: usize = 200;
+
+// =============================================================================
+// Styles
+// =============================================================================
+
+/// A group of products sold as variants of one item.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProductStyle {
+    pub id: String,
+    pub tenant_id: String,
+    pub name: String,
+    /// Shared description, shown for variants without their own.
+    pub description: Option<String>,
+    /// Attribute keys the variants differ by, e.g. `["size", "color"]`.
+    pub option_keys: Vec<String>,
+    #[ts(as = "String")]
+    pub created_at: DateTime<Utc>,
+    #[ts(as = "String")]
+    pub updated_at: DateTime<Utc>,
+    pub sync_version: i64,
+}
+
+impl ProductStyle {
+    /// Checks the name and option keys.
+    ///
+    /// ## Errors
+    /// An empty or overlong name, or option keys that are not already
+    /// normalized (see [`normalize_option_keys`]).
+    pub fn validate(&self) -> ValidationResult<()> {
+        validate_style_name(&self.name)?;
+        if normalize_option_keys(&self.option_keys)? != self.option_keys {
+            return Err(ValidationError::InvalidFormat {
+                field: "option_keys".to_string(),
+                reason: "must be normalized".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// A style with the IDs of its variants, as stored in the sync outbox
+/// (`PRODUCT_STYLE`) and relayed to other terminals.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ProductStyleDocument {
+    #[serde(flatten)]
+    pub style: ProductStyle,
+    pub variant_ids: Vec<String>,
+}
+
+/// Checks a style name.
+///
+/// ## Errors
+/// Empty, or longer than [`MAX_STYLE_NAME_LEN`] characters.
+pub fn validate_style_name(name: &str) -> ValidationResult<()> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ValidationError::Required {
+            field: "style name".to_string(),
+        });
+    }
+    if name.chars().count() > MAX_STYLE_NAME_LEN {
+        return Err(ValidationError::TooLong {
+            field: "style name".to_string(),
+            max: MAX_STYLE_NAME_LEN,
+        });
+    }
+    Ok(())
+}
+
+/// Normalizes a style's option keys, keeping their order.
+///
+/// ## Errors
+/// No keys, more than [`MAX_OPTION_KEYS`], an invalid key, or the same
+/// key twice.
+pub fn normalize_option_keys(keys: &[String]) -> ValidationResult<Vec<String>> {
+    if keys.is_empty() || keys.len() > MAX_OPTION_KEYS {
+        return Err(ValidationError::OutOfRange {
+            field: "option_keys".to_string(),
+            min: 1,
+            max: MAX_OPTION_KEYS as i64,
+        });
+    }
+
+    let mut normalized: Vec<String> = Vec::with_capacity(keys.len());
+    for key in keys {
+        let key = normalize_key(key)?;
+        if normalized.contains(&key) {
+            return Err(ValidationError::Duplicate {
+                field: "option key".to_string(),
+                value: key,
+            });
+        }
+        normalized.push(key);
+    }
+    Ok(normalized)
+}
+
+/// A variant's values for `option_keys`, read from its attributes.
+///
+/// ## Errors
+/// An option key the attributes lack, or hold as a tag (no value).
+pub fn variant_options(
+    option_keys: &[String],
+    attributes: &[ProductAttribute],
+) -> ValidationResult<Vec<String>> {
+    option_keys
+        .iter()
+        .map(|key| {
+            attributes
+                .iter()
+                .find(|a| &a.key == key && !a.is_tag())
+                .map(|a| a.value.clone())
+                .ok_or_else(|| ValidationError::Required {
+                    field: format!("option '{}'", key),
+                })
+        })
+        .collect()
+}
+
+// =============================================================================
+// Variant Groups
+// =============================================================================
+
+/// One variant: the product and its option values.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Variant {
+    pub product: Product,
+    /// Values in the style's `option_keys` order.
+    pub options: Vec<String>,
+}
+
+/// A style with its variants, for display and for picking a variant.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct VariantGroup {
+    pub style: ProductStyle,
+    pub variants: Vec<Variant>,
+}
+
+/// Variants laid out as a grid: rows by the first option, columns by the
+/// others (joined with `" / "`); each cell is a product ID or empty.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct VariantMatrix {
+    pub row_key: String,
+    /// `None` for a style with one option (a single column).
+    pub column_key: Option<String>,
+    pub rows: Vec<String>,
+    pub columns: Vec<String>,
+    /// `cells[row][column]`.
+    pub cells: Vec<Vec<Option<String>>>,
+}
+
+impl VariantGroup {
+    /// Groups `variants` under `style`.
+    pub fn new(style: ProductStyle, variants: Vec<Variant>) -> Self {
+        VariantGroup { style, variants }
+    }
+
+    /// Distinct values of option `index`, in the order variants list them.
+    pub fn option_values(&self, index: usize) -> Vec<&str> {
+        let mut values: Vec<&str> = Vec::new();
+        for variant in &self.variants {
+            if let Some(value) = variant.options.get(index) {
+                if !values.iter().any(|v| v.eq_ignore_ascii_case(value)) {
+                    values.push(value);
+                }
+            }
+        }
+        values
+    }
+
+    /// The variant with `options`, compared case-insensitively.
+    pub fn find(&self, options: &[String]) -> Option<&Variant> {
+        self.variants.iter().find(|v| same_options(&v.options, options))
+    }
+
+    /// Checks that `product_id` can join (or stay in) the style with
+    /// `options`.
+    ///
+    /// ## Errors
+    /// The wrong number of values, an empty value, or another variant
+    /// already has these values.
+    pub fn check_options(&self, product_id: &str, options: &[String]) -> ValidationResult<()> {
+        if options.len() != self.style.option_keys.len() {
+            return Err(ValidationError::OutOfRange {
+                field: "options".to_string(),
+                min: self.style.option_keys.len() as i64,
+                max: self.style.option_keys.len() as i64,
+            });
+        }
+        if let Some((key, _)) = self
+            .style
+            .option_keys
+            .iter()
+            .zip(options)
+            .find(|(_, value)| value.trim().is_empty())
+        {
+            return Err(ValidationError::Required {
+                field: format!("option '{}'", key),
+            });
+        }
+        if let Some(other) = self
+            .variants
+            .iter()
+            .find(|v| v.product.id != product_id && same_options(&v.options, options))
+        {
+            return Err(ValidationError::Duplicate {
+                field: "variant".to_string(),
+                value: format!("{} ({})", options.join(" / "), other.product.sku),
+            });
+        }
+        Ok(())
+    }
+
+    /// Lowest and highest price across active variants.
+    pub fn price_range_cents(&self) -> Option<(i64, i64)> {
+        let prices = self
+            .variants
+            .iter()
+            .filter(|v| v.product.is_active)
+            .map(|v| v.product.price_cents);
+        let min = prices.clone().min()?;
+        let max = prices.max()?;
+        Some((min, max))
+    }
+
+    /// Stock across the variants that track inventory (`None` when none
+    /// does).
+    pub fn total_stock(&self) -> Option<i64> {
+        self.variants
+            .iter()
+            .filter(|v| v.product.track_inventory)
+            .map(|v| v.product.current_stock.unwrap_or(0))
+            .reduce(|a, b| a + b)
+    }
+
+    /// Description to show for `variant`: its own, else the style's.
+    pub fn description<'a>(&'a self, variant: &'a Variant) -> Option<&'a str> {
+        variant
+            .product
+            .description
+            .as_deref()
+            .filter(|d| !d.trim().is_empty())
+            .or(self.style.description.as_deref())
+    }
+
+    /// The variants as a grid (see [`VariantMatrix`]).
+    pub fn matrix(&self) -> VariantMatrix {
+        let keys = &self.style.option_keys;
+        let rows: Vec<String> = self.option_values(0).into_iter().map(str::to_string).collect();
+
+        let column_of = |options: &[String]| options.get(1..).unwrap_or_default().join(" / ");
+        let mut columns: Vec<String> = Vec::new();
+        if keys.len() > 1 {
+            for variant in &self.variants {
+                let column = column_of(&variant.options);
+                if !columns.iter().any(|c| c.eq_ignore_ascii_case(&column)) {
+                    columns.push(column);
+                }
+            }
+        }
+
+        let mut cells = vec![vec![None; columns.len().max(1)]; rows.len()];
+        for variant in &self.variants {
+            let Some(row) = variant
+                .options
+                .first()
+                .and_then(|value| rows.iter().position(|r| r.eq_ignore_ascii_case(value)))
+            else {
+                continue;
+            };
+            let column = if keys.len() > 1 {
+                let column = column_of(&variant.options);
+                match columns.iter().position(|c| c.eq_ignore_ascii_case(&column)) {
+                    Some(index) => index,
+                    None => continue,
+                }
+            } else {
+                0
+            };
+            cells[row][column] = Some(variant.product.id.clone());
+        }
+
+        VariantMatrix {
+            row_key: keys.first().cloned().unwrap_or_default(),
+            column_key: (keys.len() > 1).then(|| keys[1..].join(" / ")),
+            rows,
+            columns,
+            cells,
+        }
+    }
+}
+
+fn same_options(a: &[String], b: &[String]) -> bool {
+    a.len() == b.len()
+        && a
+            .iter()
+            .zip(b)
+            .all(|(a, b)| a.trim().eq_ignore_ascii_case(b.trim()))
+}
+
+// =============================================================================
+// Variant-Aware Search Results
+// =============================================================================
+
+/// One search result: a product of its own, or a style whose variants
+/// matched (listed once, where its best-ranked variant was).
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SearchResult {
+    Product { product: Product },
+    Style {
+        style: ProductStyle,
+        /// The variants that matched, in rank order.
+        matches: Vec<Product>,
+    },
+}
+
+/// Collapses ranked search hits by style. `style_of` gives the style of a
+/// product that is a variant (and `None` otherwise).
+pub fn group_search_results<'a>(
+    products: Vec<Product>,
+    style_of: impl Fn(&Product) -> Option<&'a ProductStyle>,
+) -> Vec<SearchResult> {
+    let mut results: Vec<SearchResult> = Vec::with_capacity(products.len());
+    for product in products {
+        let Some(style) = style_of(&product) else {
+            results.push(SearchResult::Product { product });
+            continue;
+        };
+        let existing = results.iter_mut().find_map(|r| match r {
+            SearchResult::Style { style: s, matches } if s.id == style.id => Some(matches),
+            _ => None,
+        });
+        match existing {
+            Some(matches) => matches.push(product),
+            None => results.push(SearchResult::Style {
+                style: style.clone(),
+                matches: vec![product],
+            }),
+        }
+    }
+    results
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ItemTracking;
+
+    fn style(keys: &[&str]) -> ProductStyle {
+        ProductStyle {
+            id: "s-1".to_string(),
+            tenant_id: "t-1".to_string(),
+            name: "Classic Tee".to_string(),
+            description: Some("Heavyweight cotton".to_string()),
+            option_keys: keys.iter().map(|k| k.to_string()).collect(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            sync_version: 1,
+        }
+    }
+
+    fn product(id: &str, price_cents: i64, stock: i64) -> Product {
+        Product {
+            id: id.to_string(),
+            tenant_id: "t-1".to_string(),
+            sku: id.to_uppercase(),
+            barcode: None,
+            name: id.to_string(),
+            description: None,
+            price_cents,
+            base_price_cents: None,
+            cost_cents: None,
+            tax_rate_bps: 0,
+            track_inventory: true,
+            allow_negative_stock: false,
+            current_stock: Some(stock),
+            item_tracking: ItemTracking::None,
+            min_purchase_age: None,
+            is_active: true,
+            deleted_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            sync_version: 1,
+        }
+    }
+
+    fn variant(id: &str, price_cents: i64, options: &[&str]) -> Variant {
+        Variant {
+            product: product(id, price_cents, 2),
+            options: options.iter().map(|o| o.to_string()).collect(),
+        }
+    }
+
+    fn tees() -> VariantGroup {
+        VariantGroup::new(
+            style(&["size", "color"]),
+            vec![
+                variant("s-blk", 1999, &["S", "black"]),
+                variant("m-blk", 1999, &["M", "black"]),
+                variant("m-wht", 1999, &["M", "white"]),
+                variant("xl-blk", 2199, &["XL", "black"]),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_option_keys() {
+        let keys = normalize_option_keys(&["Size".to_string(), " color ".to_string()]).unwrap();
+        assert_eq!(keys, vec!["size", "color"]);
+        assert!(normalize_option_keys(&[]).is_err());
+        assert!(normalize_option_keys(&["size".to_string(), "SIZE".to_string()]).is_err());
+        assert!(style(&["size", "color"]).validate().is_ok());
+        assert!(style(&["Size"]).validate().is_err());
+
+        let attributes = vec![
+            ProductAttribute::new("color", "black"),
+            ProductAttribute::new("size", "M"),
+            ProductAttribute::tag("organic"),
+        ];
+        assert_eq!(
+            variant_options(&keys, &attributes).unwrap(),
+            vec!["M", "black"]
+        );
+        assert!(variant_options(&["organic".to_string()], &attributes).is_err());
+    }
+
+    #[test]
+    fn test_group_summary() {
+        let group = tees();
+        assert_eq!(group.option_values(0), vec!["S", "M", "XL"]);
+        assert_eq!(group.price_range_cents(), Some((1999, 2199)));
+        assert_eq!(group.total_stock(), Some(8));
+        assert_eq!(
+            group.description(&group.variants[0]),
+            Some("Heavyweight cotton")
+        );
+        let found = group.find(&["m".to_string(), "WHITE".to_string()]).unwrap();
+        assert_eq!(found.product.id, "m-wht");
+    }
+
+    #[test]
+    fn test_check_options() {
+        let group = tees();
+        let options = |a: &str, b: &str| vec![a.to_string(), b.to_string()];
+
+        assert!(group.check_options("new", &options("L", "black")).is_ok());
+        // Keeping its own values is fine
+        assert!(group.check_options("m-blk", &options("M", "black")).is_ok());
+        assert!(matches!(
+            group.check_options("new", &options("m", "Black")),
+            Err(ValidationError::Duplicate { .. })
+        ));
+        assert!(group.check_options("new", &options("L", " ")).is_err());
+        assert!(group.check_options("new", &["L".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_matrix() {
+        let matrix = tees().matrix();
+        assert_eq!(matrix.row_key, "size");
+        assert_eq!(matrix.column_key.as_deref(), Some("color"));
+        assert_eq!(matrix.rows, vec!["S", "M", "XL"]);
+        assert_eq!(matrix.columns, vec!["black", "white"]);
+        assert_eq!(matrix.cells[0], vec![Some("s-blk".to_string()), None]);
+        assert_eq!(
+            matrix.cells[1],
+            vec![Some("m-blk".to_string()), Some("m-wht".to_string())]
+        );
+
+        let sizes = VariantGroup::new(
+            style(&["size"]),
+            vec![variant("s", 100, &["S"]), variant("m", 100, &["M"])],
+        );
+        let matrix = sizes.matrix();
+        assert!(matrix.column_key.is_none());
+        assert_eq!(matrix.cells, vec![vec![Some("s".to_string())], vec![Some("m".to_string())]]);
+    }
+
+    #[test]
+    fn test_group_search_results() {
+        let tee = style(&["size"]);
+        let products = vec![
+            product("m-blk", 1999, 1),
+            product("mug", 899, 1),
+            product("s-blk", 1999, 1),
+        ];
+
+        let results = group_search_results(products, |p| p.id.ends_with("blk").then_some(&tee));
+        assert_eq!(results.len(), 2);
+        match &results[0] {
+            SearchResult::Style { style, matches } => {
+                assert_eq!(style.id, "s-1");
+                assert_eq!(matches.len(), 2);
+            }
+            other => panic!("expected a style, got {:?}", other),
+        }
+        assert!(matches!(&results[1], SearchResult::Product { product } if product.id == "mug"));
+    }
+}
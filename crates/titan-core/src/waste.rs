@@ -0,0 +1,314 @@
+//! # Shrinkage and Waste
+//!
+//! Goods that leave stock without being sold: broken on the shelf, past
+//! their date, or stolen. Each write-off is a [`WasteRecord`] that takes
+//! the units out of stock with a negative inventory delta tagged with the
+//! reason, so shrinkage shows up in the stock audit trail and in the
+//! tenant's reports instead of as an unexplained count difference.
+//!
+//! ## Recording Waste
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Waste Logging                                    │
+//! │                                                                         │
+//! │  record_waste(MILK-1L, 4, expiry, "fridge 2")                          │
+//! │       │                                                                 │
+//! │       ├── waste_records row (retail value frozen: 4 × 1.29)            │
+//! │       ├── stock - 4, inventory delta "expiry" referencing the record   │
+//! │       └── outbox WASTE ──► cloud InventoryDelta, reason EXPIRY         │
+//! │                                                                         │
+//! │  Daily report (one day, UTC):                                          │
+//! │  reason    units   value    entries                                     │
+//! │  damage        3    12.50         2                                     │
+//! │  expiry       14    18.06         5                                     │
+//! │  theft         1    24.99         1                                     │
+//! │  ───────────────────────────────                                        │
+//! │  total        18    55.55         8                                     │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Rules
+//! - 1 to [`MAX_WASTE_QUANTITY`] units per record; the note is optional,
+//!   at most [`MAX_WASTE_NOTE_LEN`] characters
+//! - The value is the product's price when the waste was recorded, so a
+//!   later price change doesn't rewrite past shrinkage
+//! - Waste is recorded even when it takes stock below zero: the goods are
+//!   already gone, and the count was wrong before
+//! - Records are never edited; a mistake is corrected with a stock
+//!   adjustment
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::ValidationError;
+use crate::validation::ValidationResult;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Most units one waste record may write off.
+pub const MAX_WASTE_QUANTITY: i64 = 100_000;
+
+/// Longest note on a waste record.
+pub const MAX_WASTE_NOTE_LEN: usize = 200;
+
+// =============================================================================
+// Waste Reason
+// =============================================================================
+
+/// Why goods were written off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, TS)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::Type))]
+#[cfg_attr(feature = "sqlx", sqlx(rename_all = "snake_case"))]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum WasteReason {
+    /// Broken, spoiled or otherwise unsellable.
+    Damage,
+    /// Past its sell-by or use-by date.
+    Expiry,
+    /// Stolen or missing.
+    Theft,
+}
+
+impl WasteReason {
+    /// Every reason, in report order.
+    pub const ALL: [WasteReason; 3] = [WasteReason::Damage, WasteReason::Expiry, WasteReason::Theft];
+
+    /// Name as stored in the database and used as the inventory delta type.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WasteReason::Damage => "damage",
+            WasteReason::Expiry => "expiry",
+            WasteReason::Theft => "theft",
+        }
+    }
+
+    /// Inventory delta reason uploaded to the cloud.
+    pub fn cloud_reason(&self) -> &'static str {
+        match self {
+            WasteReason::Damage => "DAMAGE",
+            WasteReason::Expiry => "EXPIRY",
+            WasteReason::Theft => "THEFT",
+        }
+    }
+}
+
+// =============================================================================
+// Waste Record
+// =============================================================================
+
+/// Goods written off from stock.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WasteRecord {
+    pub id: String,
+    pub product_id: String,
+    /// Units written off (positive; stock goes down by this much).
+    #[ts(type = "number")]
+    pub quantity: i64,
+    pub reason: WasteReason,
+    /// The product's price when the waste was recorded.
+    #[ts(type = "number")]
+    pub unit_price_cents: i64,
+    pub note: Option<String>,
+    pub recorded_by: String,
+    pub device_id: String,
+    #[ts(as = "String")]
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl WasteRecord {
+    /// Retail value of the goods written off.
+    pub fn value_cents(&self) -> i64 {
+        self.quantity * self.unit_price_cents
+    }
+
+    /// Checks the quantity and note (see the module rules).
+    pub fn validate(&self) -> ValidationResult<()> {
+        validate_quantity(self.quantity)?;
+        if let Some(note) = &self.note {
+            validate_note(note)?;
+        }
+        if self.unit_price_cents < 0 {
+            return Err(ValidationError::OutOfRange {
+                field: "unit_price_cents".to_string(),
+                min: 0,
+                max: i64::MAX,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Checks a write-off quantity.
+///
+/// ## Errors
+/// `OutOfRange` outside 1 to [`MAX_WASTE_QUANTITY`].
+pub fn validate_quantity(quantity: i64) -> ValidationResult<()> {
+    if !(1..=MAX_WASTE_QUANTITY).contains(&quantity) {
+        return Err(ValidationError::OutOfRange {
+            field: "quantity".to_string(),
+            min: 1,
+            max: MAX_WASTE_QUANTITY,
+        });
+    }
+    Ok(())
+}
+
+/// Trims a note; blank notes become `None`.
+///
+/// ## Errors
+/// `TooLong` past [`MAX_WASTE_NOTE_LEN`] characters.
+pub fn normalize_note(note: Option<&str>) -> ValidationResult<Option<String>> {
+    let Some(note) = note.map(str::trim).filter(|n| !n.is_empty()) else {
+        return Ok(None);
+    };
+    validate_note(note)?;
+    Ok(Some(note.to_string()))
+}
+
+fn validate_note(note: &str) -> ValidationResult<()> {
+    if note.chars().count() > MAX_WASTE_NOTE_LEN {
+        return Err(ValidationError::TooLong {
+            field: "note".to_string(),
+            max: MAX_WASTE_NOTE_LEN,
+        });
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Daily Report
+// =============================================================================
+
+/// Waste for one reason (one row of the report).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WasteReasonSummary {
+    pub reason: WasteReason,
+    #[ts(type = "number")]
+    pub units: i64,
+    #[ts(type = "number")]
+    pub value_cents: i64,
+    #[ts(type = "number")]
+    pub entries: i64,
+}
+
+/// Waste recorded on one day (UTC).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WasteReport {
+    pub date: NaiveDate,
+    /// One row per reason that has waste, in [`WasteReason::ALL`] order.
+    pub reasons: Vec<WasteReasonSummary>,
+    /// The day's records, oldest first.
+    pub records: Vec<WasteRecord>,
+}
+
+impl WasteReport {
+    /// Builds the report from the day's records.
+    pub fn new(date: NaiveDate, mut records: Vec<WasteRecord>) -> Self {
+        records.sort_by_key(|r| r.recorded_at);
+
+        let reasons = WasteReason::ALL
+            .iter()
+            .filter_map(|&reason| {
+                let rows: Vec<&WasteRecord> = records.iter().filter(|r| r.reason == reason).collect();
+                (!rows.is_empty()).then(|| WasteReasonSummary {
+                    reason,
+                    units: rows.iter().map(|r| r.quantity).sum(),
+                    value_cents: rows.iter().map(|r| r.value_cents()).sum(),
+                    entries: rows.len() as i64,
+                })
+            })
+            .collect();
+
+        WasteReport {
+            date,
+            reasons,
+            records,
+        }
+    }
+
+    /// Units written off, all reasons.
+    pub fn total_units(&self) -> i64 {
+        self.reasons.iter().map(|r| r.units).sum()
+    }
+
+    /// Retail value written off, all reasons.
+    pub fn total_value_cents(&self) -> i64 {
+        self.reasons.iter().map(|r| r.value_cents).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn record(id: &str, reason: WasteReason, quantity: i64, price: i64, hour: u32) -> WasteRecord {
+        WasteRecord {
+            id: id.to_string(),
+            product_id: "p1".to_string(),
+            quantity,
+            reason,
+            unit_price_cents: price,
+            note: None,
+            recorded_by: "default".to_string(),
+            device_id: "pos-01".to_string(),
+            recorded_at: Utc.with_ymd_and_hms(2026, 3, 2, hour, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_validate_quantity_and_note() {
+        assert!(validate_quantity(1).is_ok());
+        assert!(validate_quantity(0).is_err());
+        assert!(validate_quantity(MAX_WASTE_QUANTITY + 1).is_err());
+
+        assert_eq!(normalize_note(Some("  fridge 2 ")).unwrap(), Some("fridge 2".to_string()));
+        assert_eq!(normalize_note(Some("   ")).unwrap(), None);
+        assert_eq!(normalize_note(None).unwrap(), None);
+        assert!(normalize_note(Some(&"x".repeat(MAX_WASTE_NOTE_LEN + 1))).is_err());
+
+        let mut r = record("w1", WasteReason::Damage, 2, 100, 9);
+        assert!(r.validate().is_ok());
+        r.unit_price_cents = -1;
+        assert!(r.validate().is_err());
+    }
+
+    #[test]
+    fn test_report_groups_by_reason() {
+        let report = WasteReport::new(
+            NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+            vec![
+                record("w3", WasteReason::Theft, 1, 2499, 15),
+                record("w1", WasteReason::Expiry, 4, 129, 9),
+                record("w2", WasteReason::Expiry, 10, 129, 11),
+            ],
+        );
+
+        assert_eq!(report.reasons.len(), 2);
+        assert_eq!(report.reasons[0].reason, WasteReason::Expiry);
+        assert_eq!(report.reasons[0].units, 14);
+        assert_eq!(report.reasons[0].value_cents, 1806);
+        assert_eq!(report.reasons[0].entries, 2);
+        assert_eq!(report.reasons[1].reason, WasteReason::Theft);
+
+        assert_eq!(report.total_units(), 15);
+        assert_eq!(report.total_value_cents(), 1806 + 2499);
+        let ids: Vec<&str> = report.records.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["w1", "w2", "w3"]);
+    }
+
+    #[test]
+    fn test_reason_names() {
+        for reason in WasteReason::ALL {
+            let json = serde_json::to_string(&reason).unwrap();
+            assert_eq!(json, format!("\"{}\"", reason.as_str()));
+            assert_eq!(reason.cloud_reason(), reason.as_str().to_uppercase());
+        }
+    }
+}
@@ -1,6 +1,8 @@
 //! # Seed Data Generator
 //!
-//! Populates the database with test products for development.
+//! Populates the database with realistic products, and optionally a sales
+//! history, for development and local performance testing (search
+//! latency, outbox backlog drain).
 //!
 //! ## Usage
 //! ```bash
@@ -10,6 +12,12 @@
 //! # Generate custom amount
 //! cargo run -p titan-db --bin seed -- --count 10000
 //!
+//! # Mostly beverages and snacks
+//! cargo run -p titan-db --bin seed -- --count 20000 --categories BEV=5,SNK=3,GRO=1
+//!
+//! # 50,000 sales over 90 days from 4 terminals, left pending in the outbox
+//! cargo run -p titan-db --bin seed -- --sales 50000 --days 90 --devices 4 --queue-sync
+//!
 //! # Specify database path
 //! cargo run -p titan-db --bin seed -- --db ./data/titan.db
 //! ```
@@ -22,164 +30,24 @@
 //! - Frozen (ice cream, frozen meals)
 //! - Grocery (canned goods, pasta, rice)
 //!
-//! Each product has:
-//! - Unique SKU: `{CATEGORY}-{INDEX}`
+//! Products come from [`ProductFixture::realistic_in`], so tests seeded with
+//! `titan_db::fixtures` see the same catalog. Each product has:
+//! - Unique SKU: `{CATEGORY}-{NAME}-{INDEX}`
 //! - Realistic name
 //! - Random price: $0.99 - $19.99
 //! - Random stock: 0 - 100
 //! - Random tax rate: 0%, 5%, 8.25%, 10%
+//!
+//! ## Generated Sales
+//! Completed cash sales of 1-4 lines, evenly spaced over `--days` and
+//! attributed round robin to the `--devices` terminals. With
+//! `--queue-sync` every sale is also pending in the sync outbox.
 
-use chrono::Utc;
 use std::env;
-use titan_core::{Product, DEFAULT_TENANT_ID};
-use titan_db::{Database, DbConfig};
-use uuid::Uuid;
+use std::time::Instant;
 
-/// Product categories for realistic test data
-const CATEGORIES: &[(&str, &[&str])] = &[
-    (
-        "BEV",
-        &[
-            "Coca-Cola",
-            "Pepsi",
-            "Sprite",
-            "Fanta",
-            "Dr Pepper",
-            "Mountain Dew",
-            "7-Up",
-            "Red Bull",
-            "Monster Energy",
-            "Gatorade",
-            "Dasani Water",
-            "Evian Water",
-            "Orange Juice",
-            "Apple Juice",
-            "Grape Juice",
-            "Lemonade",
-            "Iced Tea",
-            "Coffee",
-            "Hot Chocolate",
-            "Milk",
-        ],
-    ),
-    (
-        "SNK",
-        &[
-            "Lays Classic",
-            "Doritos Nacho",
-            "Cheetos",
-            "Pringles",
-            "Ruffles",
-            "Tostitos",
-            "Fritos",
-            "Snickers",
-            "M&Ms",
-            "Reeses",
-            "Kit Kat",
-            "Twix",
-            "Skittles",
-            "Starburst",
-            "Gummy Bears",
-            "Oreos",
-            "Chips Ahoy",
-            "Nutter Butter",
-            "Goldfish",
-            "Pretzels",
-        ],
-    ),
-    (
-        "DRY",
-        &[
-            "Whole Milk",
-            "2% Milk",
-            "Skim Milk",
-            "Almond Milk",
-            "Oat Milk",
-            "Cheddar Cheese",
-            "Mozzarella",
-            "Swiss Cheese",
-            "Cream Cheese",
-            "Butter",
-            "Greek Yogurt",
-            "Regular Yogurt",
-            "Sour Cream",
-            "Heavy Cream",
-            "Half & Half",
-            "Eggs Dozen",
-            "Eggs Half Dozen",
-            "Cottage Cheese",
-            "Parmesan",
-            "Feta Cheese",
-        ],
-    ),
-    (
-        "FRZ",
-        &[
-            "Vanilla Ice Cream",
-            "Chocolate Ice Cream",
-            "Strawberry Ice Cream",
-            "Cookie Dough Ice Cream",
-            "Mint Chip Ice Cream",
-            "Frozen Pizza",
-            "Frozen Burrito",
-            "Frozen Dinner",
-            "Ice Cream Bars",
-            "Popsicles",
-            "Frozen Vegetables",
-            "Frozen Fruit",
-            "Frozen Waffles",
-            "Fish Sticks",
-            "Chicken Nuggets",
-            "Frozen Fries",
-            "Ice Cream Sandwich",
-            "Sorbet",
-            "Frozen Breakfast",
-            "Frozen Pie",
-        ],
-    ),
-    (
-        "GRO",
-        &[
-            "White Bread",
-            "Wheat Bread",
-            "Pasta Spaghetti",
-            "Pasta Penne",
-            "Rice White",
-            "Rice Brown",
-            "Canned Beans",
-            "Canned Corn",
-            "Canned Tomatoes",
-            "Canned Soup",
-            "Cereal Cheerios",
-            "Cereal Frosted Flakes",
-            "Oatmeal",
-            "Peanut Butter",
-            "Jelly",
-            "Honey",
-            "Maple Syrup",
-            "Flour",
-            "Sugar",
-            "Salt",
-        ],
-    ),
-];
-
-/// Size variants for products
-const SIZES: &[(&str, i64)] = &[
-    ("Small", 0),
-    ("Medium", 100),
-    ("Large", 200),
-    ("XL", 350),
-    ("12oz", 0),
-    ("16oz", 50),
-    ("20oz", 100),
-    ("2L", 150),
-    ("6-Pack", 300),
-    ("12-Pack", 500),
-];
-
-/// Tax rates in basis points
-const TAX_RATES: &[u32] = &[0, 500, 825, 1000];
+use titan_db::fixtures::CATEGORIES;
+use titan_db::{Database, DbConfig, Fixtures, ProductFixture};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -187,23 +55,43 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = env::args().collect();
 
     let mut count: usize = 5000;
+    let mut categories: Option<String> = None;
+    let mut sales: usize = 0;
+    let mut days: u32 = 30;
+    let mut devices: Vec<String> = vec!["pos-01".to_string()];
+    let mut queue_sync = false;
     // Default to data/titan.db in project root for development
     let mut db_path = String::from("./data/titan.db");
 
     let mut i = 1;
     while i < args.len() {
         match args[i].as_str() {
-            "--count" | "-c" => {
-                if i + 1 < args.len() {
-                    count = args[i + 1].parse().unwrap_or(5000);
-                    i += 1;
-                }
+            "--count" | "-c" if i + 1 < args.len() => {
+                count = args[i + 1].parse().unwrap_or(5000);
+                i += 1;
             }
-            "--db" | "-d" => {
-                if i + 1 < args.len() {
-                    db_path = args[i + 1].clone();
-                    i += 1;
-                }
+            "--categories" if i + 1 < args.len() => {
+                categories = Some(args[i + 1].clone());
+                i += 1;
+            }
+            "--sales" | "-s" if i + 1 < args.len() => {
+                sales = args[i + 1].parse().unwrap_or(0);
+                i += 1;
+            }
+            "--days" if i + 1 < args.len() => {
+                days = args[i + 1].parse().unwrap_or(30);
+                i += 1;
+            }
+            "--devices" if i + 1 < args.len() => {
+                devices = parse_devices(&args[i + 1]);
+                i += 1;
+            }
+            "--queue-sync" => {
+                queue_sync = true;
+            }
+            "--db" | "-d" if i + 1 < args.len() => {
+                db_path = args[i + 1].clone();
+                i += 1;
             }
             "--help" | "-h" => {
                 println!("Titan POS Seed Data Generator");
@@ -211,9 +99,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 println!("Usage: seed [OPTIONS]");
                 println!();
                 println!("Options:");
-                println!("  -c, --count <N>    Number of products to generate (default: 5000)");
-                println!("  -d, --db <PATH>    Database file path (default: ./data/titan.db)");
-                println!("  -h, --help         Show this help message");
+                println!(
+                    "  -c, --count <N>          Number of products to generate (default: 5000)"
+                );
+                println!(
+                    "      --categories <LIST>  Category shares, e.g. BEV=5,SNK=3 (default: even)"
+                );
+                println!("  -s, --sales <N>          Number of sales to generate (default: 0)");
+                println!("      --days <N>           Days of sales history (default: 30)");
+                println!(
+                    "      --devices <N|LIST>   Terminal count or IDs, e.g. 4 or pos-01,pos-02"
+                );
+                println!("      --queue-sync         Leave the sales pending in the sync outbox");
+                println!(
+                    "  -d, --db <PATH>          Database file path (default: ./data/titan.db)"
+                );
+                println!("  -h, --help               Show this help message");
+                println!();
+                println!("Categories: {}", category_codes());
                 println!();
                 println!("Note: Run this from the project root directory.");
                 println!("      The Tauri app uses TITAN_DB_PATH env var to locate this DB.");
@@ -224,12 +127,31 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         i += 1;
     }
 
+    let mut fixtures = Fixtures::new()
+        .products(count)
+        .sales(sales)
+        .history_days(days)
+        .devices(devices.clone());
+    if let Some(list) = &categories {
+        let weights = parse_weights(list)?;
+        let weights: Vec<(&str, u32)> = weights.iter().map(|(c, w)| (c.as_str(), *w)).collect();
+        fixtures = fixtures
+            .category_weights(&weights)
+            .map_err(|e| format!("{} (known categories: {})", e, category_codes()))?;
+    }
+    if queue_sync {
+        fixtures = fixtures.queue_sales_for_sync();
+    }
+
     println!("🌱 Titan POS Seed Data Generator");
     println!("================================");
     println!("Database: {}", db_path);
     println!("Products: {}", count);
+    if sales > 0 {
+        println!("Sales:    {} over {} days", sales, days);
+        println!("Devices:  {}", devices.join(", "));
+    }
     println!();
-
     // Connect to database
     let config = DbConfig::new(&db_path);
     let db = Database::new(config).await?;
@@ -250,46 +172,31 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!();
     println!("Generating products...");
 
-    let mut generated = 0;
-    let start = std::time::Instant::now();
-
-    for (category_idx, (category_code, products)) in CATEGORIES.iter().enumerate() {
-        for (product_idx, product_name) in products.iter().enumerate() {
-            for (size_idx, (size_name, price_addon)) in SIZES.iter().enumerate() {
-                if generated >= count {
-                    break;
-                }
+    let mut products = Vec::with_capacity(count);
+    let start = Instant::now();
 
-                let product = generate_product(
-                    category_code,
-                    product_name,
-                    size_name,
-                    *price_addon,
-                    category_idx * 1000 + product_idx * 20 + size_idx,
-                );
+    for (category, category_count) in fixtures.category_counts().into_iter().enumerate() {
+        if category_count > 0 {
+            println!("  {}: {} products", CATEGORIES[category].0, category_count);
+        }
+        for index in 0..category_count {
+            let product = ProductFixture::realistic_in(category, index).build();
 
-                if let Err(e) = db.products().insert(&product).await {
+            match db.products().insert(&product).await {
+                Ok(product) => products.push(product),
+                Err(e) => {
                     eprintln!("Failed to insert {}: {}", product.sku, e);
                     continue;
                 }
-
-                generated += 1;
-
-                if generated % 500 == 0 {
-                    println!("  Generated {} products...", generated);
-                }
             }
 
-            if generated >= count {
-                break;
+            if products.len() % 500 == 0 {
+                println!("  Generated {} products...", products.len());
             }
         }
-
-        if generated >= count {
-            break;
-        }
     }
 
+    let generated = products.len();
     let elapsed = start.elapsed();
     println!();
     println!("✓ Generated {} products in {:?}", generated, elapsed);
@@ -298,14 +205,37 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         generated as f64 / elapsed.as_secs_f64()
     );
 
+    // Generate sales
+    if sales > 0 {
+        println!();
+        println!("Generating sales...");
+        let start = Instant::now();
+        let inserted = fixtures.seed_sales(&db, &products).await?;
+        let elapsed = start.elapsed();
+        println!("✓ Generated {} sales in {:?}", inserted, elapsed);
+        println!(
+            "  Rate: {:.0} sales/second",
+            inserted as f64 / elapsed.as_secs_f64()
+        );
+        if queue_sync {
+            let pending = db.sync_outbox().count_pending().await?;
+            println!("  Outbox backlog: {} entries", pending);
+        }
+    }
+
     // Verify FTS
     println!();
     println!("Verifying FTS index...");
-    let search_results = db.products().search("cola", 10).await?;
-    println!("  Search 'cola': {} results", search_results.len());
-
-    let search_results = db.products().search("BEV", 10).await?;
-    println!("  Search 'BEV': {} results", search_results.len());
+    for query in ["cola", "BEV"] {
+        let start = Instant::now();
+        let search_results = db.products().search(query, 10).await?;
+        println!(
+            "  Search '{}': {} results in {:?}",
+            query,
+            search_results.len(),
+            start.elapsed()
+        );
+    }
 
     println!();
     println!("✓ Seed complete!");
@@ -313,60 +243,40 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-/// Generates a single product with realistic data.
-fn generate_product(
-    category: &str,
-    name: &str,
-    size: &str,
-    price_addon: i64,
-    seed: usize,
-) -> Product {
-    let now = Utc::now();
-
-    // Generate unique SKU
-    let sku = format!(
-        "{}-{}-{:03}",
-        category,
-        &name.replace(' ', "")[..3].to_uppercase(),
-        seed
-    );
-
-    // Generate barcode (EAN-13 format, but not valid checksum)
-    let barcode = Some(format!("590{:010}", seed));
-
-    // Generate price: base $1.99-$9.99 + size addon
-    let base_price = 199 + ((seed * 17) % 800) as i64; // $1.99 - $9.99
-    let price_cents = base_price + price_addon;
-
-    // Generate cost (60-80% of price)
-    let cost_pct = 60 + (seed % 20) as i64;
-    let cost_cents = Some(price_cents * cost_pct / 100);
-
-    // Random tax rate
-    let tax_rate_bps = TAX_RATES[seed % TAX_RATES.len()];
-
-    // Random stock (0-100)
-    let current_stock = Some((seed % 101) as i64);
-
-    // Full product name with size
-    let full_name = format!("{} {}", name, size);
+/// Parses `BEV=5,SNK=3` into category weights.
+fn parse_weights(list: &str) -> Result<Vec<(String, u32)>, String> {
+    list.split(',')
+        .filter(|part| !part.trim().is_empty())
+        .map(|part| {
+            let (code, weight) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Expected CODE=WEIGHT, got '{}'", part))?;
+            let weight = weight
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid weight in '{}'", part))?;
+            Ok((code.trim().to_string(), weight))
+        })
+        .collect()
+}
 
-    Product {
-        id: Uuid::new_v4().to_string(),
-        tenant_id: DEFAULT_TENANT_ID.to_string(),
-        sku,
-        barcode,
-        name: full_name,
-        description: None,
-        price_cents,
-        cost_cents,
-        tax_rate_bps,
-        track_inventory: true,
-        allow_negative_stock: false,
-        current_stock,
-        is_active: true,
-        created_at: now,
-        updated_at: now,
-        sync_version: 0,
+/// Parses a terminal count (`4` → pos-01..pos-04) or a list of IDs.
+fn parse_devices(value: &str) -> Vec<String> {
+    match value.parse::<usize>() {
+        Ok(n) => (1..=n.max(1)).map(|i| format!("pos-{:02}", i)).collect(),
+        Err(_) => value
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(String::from)
+            .collect(),
     }
 }
+
+fn category_codes() -> String {
+    CATEGORIES
+        .iter()
+        .map(|(code, _)| *code)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,338 @@
+//! # Titan DB Admin CLI
+//!
+//! Support tool for a terminal's or hub's database, usable over a remote
+//! session without knowing SQL. It never runs migrations: a database from
+//! an older build is reported by `verify-schema`, not upgraded.
+//!
+//! ## Commands
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                           titan-db                                      │
+//! │                                                                         │
+//! │  outbox            entries waiting for the hub / cloud, newest first    │
+//! │  product <CODE>    product by SKU, barcode or ID, with its stock ledger │
+//! │  recompute-stock   counted stock + ledger movements since the count     │
+//! │                    (dry run unless --apply)                             │
+//! │  verify-schema     applied migrations vs. this build                    │
+//! │  describe-schema   tables, columns, keys and indexes as JSON or DOT;    │
+//! │                    --drift lists differences from this build's schema   │
+//! │  integrity         SQLite integrity and foreign key checks              │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Usage
+//! ```bash
+//! titan-db --db ./data/titan.db outbox --limit 20
+//! titan-db product 5449000000996
+//! titan-db describe-schema --dot | dot -Tsvg > schema.svg
+//! titan-db recompute-stock COKE-330 --counted 24 --since 2026-03-01T08:00:00Z --apply
+//! ```
+//!
+//! Exits with status 1 when `verify-schema`, `describe-schema --drift` or
+//! `integrity` finds a problem.
+
+use std::env;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+
+use titan_core::{AckLevel, Product};
+use titan_db::migrations::verify_schema;
+use titan_db::schema::{describe_schema, expected_schema};
+use titan_db::{Database, DbConfig};
+
+/// Outbox entries listed when `--limit` is not given.
+const DEFAULT_OUTBOX_LIMIT: u32 = 50;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = env::args().collect();
+
+    let mut db_path = env::var("TITAN_DB_PATH").unwrap_or_else(|_| "./data/titan.db".to_string());
+    let mut command: Vec<String> = Vec::new();
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--db" | "-d" if i + 1 < args.len() => {
+                db_path = args[i + 1].clone();
+                i += 1;
+            }
+            "--help" | "-h" => {
+                print_help();
+                return Ok(());
+            }
+            _ => command.push(args[i].clone()),
+        }
+        i += 1;
+    }
+
+    let Some(name) = command.first().cloned() else {
+        print_help();
+        return Ok(());
+    };
+    let rest = &command[1..];
+
+    // Opening a missing path would create an empty database
+    if !Path::new(&db_path).exists() {
+        return Err(format!("No database at {}", db_path).into());
+    }
+    let db = Database::new(DbConfig::new(&db_path).run_migrations(false)).await?;
+
+    let ok = match name.as_str() {
+        "outbox" => outbox(&db, rest).await?,
+        "product" => product(&db, rest).await?,
+        "recompute-stock" => recompute_stock(&db, rest).await?,
+        "verify-schema" => schema(&db).await?,
+        "describe-schema" => describe(&db, rest).await?,
+        "integrity" => integrity(&db).await?,
+        other => return Err(format!("Unknown command: {} (see --help)", other).into()),
+    };
+    if !ok {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn print_help() {
+    println!("Titan POS Database Admin");
+    println!();
+    println!("Usage: titan-db [--db <PATH>] <COMMAND>");
+    println!();
+    println!("Commands:");
+    println!("  outbox [--all] [--limit <N>]   Outbox entries (--all includes synced ones)");
+    println!("  product <SKU|BARCODE|ID>       Product details and stock ledger");
+    println!("  recompute-stock <SKU|BARCODE|ID> --counted <N> [--since <TIME>] [--apply]");
+    println!("                                 Stock = counted + movements since TIME (RFC 3339)");
+    println!("  verify-schema                  Applied migrations vs. this build");
+    println!("  describe-schema [--dot | --drift]");
+    println!("                                 Schema as JSON or DOT, or drift from this build");
+    println!("  integrity                      SQLite integrity and foreign key checks");
+    println!();
+    println!("Options:");
+    println!("  -d, --db <PATH>   Database file path (default: $TITAN_DB_PATH or ./data/titan.db)");
+    println!("  -h, --help        Show this help message");
+}
+
+/// The value after `option` in `args`, if it is there.
+fn option_value<'a>(args: &'a [String], option: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == option)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+/// Finds a product by SKU, then barcode, then ID.
+async fn find_product(db: &Database, code: &str) -> Result<Product, Box<dyn std::error::Error>> {
+    let products = db.products();
+    if let Some(product) = products.get_by_sku(code).await? {
+        return Ok(product);
+    }
+    if let Some(product) = products.get_by_barcode(code).await? {
+        return Ok(product);
+    }
+    products
+        .get_by_id(code)
+        .await?
+        .ok_or_else(|| format!("No product with SKU, barcode or ID {}", code).into())
+}
+
+async fn outbox(db: &Database, args: &[String]) -> Result<bool, Box<dyn std::error::Error>> {
+    let include_synced = args.iter().any(|arg| arg == "--all");
+    let limit = match option_value(args, "--limit") {
+        Some(value) => value
+            .parse()
+            .map_err(|_| format!("Invalid --limit: {}", value))?,
+        None => DEFAULT_OUTBOX_LIMIT,
+    };
+
+    let counts = db.sync_outbox().count_by_ack_level().await?;
+    println!(
+        "Waiting: {} for the hub, {} for the cloud",
+        counts.pending, counts.hub_acked
+    );
+    println!();
+
+    let entries = db.sync_outbox().list_entries(limit, include_synced).await?;
+    if entries.is_empty() {
+        println!("No entries.");
+        return Ok(true);
+    }
+    println!(
+        "{:<25} {:<18} {:<36} {:<11} {:>8}  LAST ERROR",
+        "CREATED", "TYPE", "ENTITY", "ACK", "ATTEMPTS"
+    );
+    for entry in entries {
+        println!(
+            "{:<25} {:<18} {:<36} {:<11} {:>8}  {}",
+            entry.created_at.format("%Y-%m-%d %H:%M:%S"),
+            entry.entity_type,
+            entry.entity_id,
+            ack_label(entry.ack_level, entry.held_by_hub.is_some()),
+            entry.attempts,
+            entry.last_error.unwrap_or_default()
+        );
+    }
+    Ok(true)
+}
+
+fn ack_label(level: AckLevel, held: bool) -> &'static str {
+    match (level, held) {
+        (AckLevel::Pending, _) => "pending",
+        (AckLevel::HubAcked, true) => "held",
+        (AckLevel::HubAcked, false) => "hub",
+        (AckLevel::CloudAcked, _) => "cloud",
+    }
+}
+
+async fn product(db: &Database, args: &[String]) -> Result<bool, Box<dyn std::error::Error>> {
+    let code = args.first().ok_or("product needs a SKU, barcode or ID")?;
+    let product = find_product(db, code).await?;
+
+    println!("{}", serde_json::to_string_pretty(&product)?);
+    println!();
+    print_ledger(db, &product, None).await?;
+    Ok(true)
+}
+
+async fn print_ledger(
+    db: &Database,
+    product: &Product,
+    since: Option<DateTime<Utc>>,
+) -> Result<i64, Box<dyn std::error::Error>> {
+    let ledger = db.products().stock_ledger(&product.id, since).await?;
+    match since {
+        Some(since) => println!("Stock movements since {}:", since.to_rfc3339()),
+        None => println!("Stock movements:"),
+    }
+    if ledger.is_empty() {
+        println!("  (none)");
+    }
+    for line in &ledger {
+        println!(
+            "  {:<16} {:>6} movements  {:>+8}",
+            line.delta_type, line.movements, line.total
+        );
+    }
+    Ok(ledger.iter().map(|line| line.total).sum())
+}
+
+async fn recompute_stock(
+    db: &Database,
+    args: &[String],
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let code = args
+        .first()
+        .filter(|arg| !arg.starts_with("--"))
+        .ok_or("recompute-stock needs a SKU, barcode or ID")?;
+    let counted: i64 = option_value(args, "--counted")
+        .ok_or("recompute-stock needs --counted <N>, the last physical count")?
+        .parse()
+        .map_err(|_| "--counted must be a whole number")?;
+    let since = match option_value(args, "--since") {
+        Some(value) => Some(
+            DateTime::parse_from_rfc3339(value)
+                .map_err(|_| format!("--since must be an RFC 3339 time, got {}", value))?
+                .with_timezone(&Utc),
+        ),
+        None => None,
+    };
+    let apply = args.iter().any(|arg| arg == "--apply");
+
+    let product = find_product(db, code).await?;
+    println!("{} {}", product.sku, product.name);
+    let moved = print_ledger(db, &product, since).await?;
+    println!();
+    println!("Current stock:    {}", product.current_stock.unwrap_or(0));
+    println!(
+        "Recomputed stock: {} ({} counted {:+})",
+        counted + moved,
+        counted,
+        moved
+    );
+
+    if !apply {
+        println!();
+        println!("Dry run: pass --apply to write the recomputed stock.");
+        return Ok(true);
+    }
+    let stock = db
+        .products()
+        .recompute_stock(&product.id, counted, since)
+        .await?;
+    println!("Stock set to {}.", stock);
+    Ok(true)
+}
+
+async fn schema(db: &Database) -> Result<bool, Box<dyn std::error::Error>> {
+    let report = verify_schema(db.pool()).await?;
+    println!(
+        "Migrations applied: {} (this build: up to {})",
+        report.applied, report.latest_version
+    );
+
+    let problems = [
+        (
+            "Not applied (the app applies them on start)",
+            &report.missing,
+        ),
+        ("Applied by a newer build", &report.unknown),
+        ("Applied from a different file", &report.modified),
+        ("Failed", &report.failed),
+    ];
+    for (label, versions) in problems {
+        if !versions.is_empty() {
+            let versions: Vec<String> = versions.iter().map(|v| format!("{:03}", v)).collect();
+            println!("{}: {}", label, versions.join(", "));
+        }
+    }
+    if report.is_ok() {
+        println!("Schema OK");
+    }
+    Ok(report.is_ok())
+}
+
+async fn describe(db: &Database, args: &[String]) -> Result<bool, Box<dyn std::error::Error>> {
+    let schema = describe_schema(db.pool()).await?;
+    if args.iter().any(|arg| arg == "--dot") {
+        print!("{}", schema.to_dot());
+        return Ok(true);
+    }
+    if !args.iter().any(|arg| arg == "--drift") {
+        println!("{}", schema.to_json());
+        return Ok(true);
+    }
+
+    let drift = schema.drift(&expected_schema().await?);
+    for difference in &drift {
+        println!("{}", difference);
+    }
+    if drift.is_empty() {
+        println!("Schema matches this build");
+    } else {
+        println!();
+        println!(
+            "{} difference(s) from this build's schema (migrations up to {:03}).",
+            drift.len(),
+            schema.latest_version
+        );
+    }
+    Ok(drift.is_empty())
+}
+
+async fn integrity(db: &Database) -> Result<bool, Box<dyn std::error::Error>> {
+    let problems = db.integrity_check().await?;
+    if problems.is_empty() {
+        println!("Integrity OK");
+        return Ok(true);
+    }
+    for problem in &problems {
+        println!("{}", problem);
+    }
+    println!();
+    println!(
+        "{} problem(s) found. Take a backup before changing anything.",
+        problems.len()
+    );
+    Ok(false)
+}
@@ -0,0 +1,70 @@
+//! # Correlation IDs
+//!
+//! Ties everything one user action causes to a single ID, so a sale can be
+//! followed from the button press through the outbox, the hub and the
+//! cloud row in the logs.
+//!
+//! ## Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Correlation ID                                   │
+//! │                                                                         │
+//! │  Tauri command (desktop middleware)                                     │
+//! │    correlation::scope(new_id(), trace_context, command body)            │
+//! │       │                                                                 │
+//! │       ▼                                                                 │
+//! │  SyncOutboxRepository::queue_for_sync / upsert_for_sync                 │
+//! │    sync_outbox.correlation_id = correlation::current()                  │
+//! │       │                                                                 │
+//! │       ├──► hub: OutboxEntry.correlationId (logged by the aggregator)    │
+//! │       └──► cloud: SyncEntity.correlation_id ──► sales.correlation_id    │
+//! │                                                                         │
+//! │  Outside a scope (sync agent, background jobs) current() is None.      │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! A scope can also carry the command span's trace context, stamped on
+//! outbox rows beside the ID so the hub and the cloud add their spans to
+//! the command's trace (see titan-sync `telemetry`).
+//!
+//! Both live in a tokio task-local: they follow the command's future
+//! across `.await`s but not into tasks it spawns.
+
+use std::future::Future;
+
+use uuid::Uuid;
+
+tokio::task_local! {
+    static CORRELATION: Correlation;
+}
+
+struct Correlation {
+    id: String,
+    trace_context: Option<String>,
+}
+
+/// A fresh correlation ID.
+pub fn new_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+/// Runs `fut` with `id` as the current correlation ID and
+/// `trace_context` (a W3C `traceparent`) as the current trace context.
+pub async fn scope<F: Future>(id: String, trace_context: Option<String>, fut: F) -> F::Output {
+    CORRELATION
+        .scope(Correlation { id, trace_context }, fut)
+        .await
+}
+
+/// The correlation ID of the running task, if it is inside a [`scope`].
+pub fn current() -> Option<String> {
+    CORRELATION.try_with(|c| c.id.clone()).ok()
+}
+
+/// The trace context of the running task's [`scope`], if it has one.
+pub fn trace_context() -> Option<String> {
+    CORRELATION
+        .try_with(|c| c.trace_context.clone())
+        .ok()
+        .flatten()
+}
@@ -22,6 +22,7 @@
 //! ```
 
 use thiserror::Error;
+use titan_core::ErrorCode;
 
 /// Database operation errors.
 ///
@@ -47,6 +48,22 @@ pub enum DbError {
     #[error("Duplicate {field}: '{value}' already exists")]
     UniqueViolation { field: String, value: String },
 
+    /// Optimistic lock failed: the row changed after it was read.
+    ///
+    /// ## When This Occurs
+    /// - Saving an edit made on a stale copy, e.g. an update from another
+    ///   terminal arrived through sync while the form was open
+    /// - Two local writers racing on the same row
+    ///
+    /// Reload the row and redo the change on top of `actual`.
+    #[error("{entity} {id} was changed elsewhere (expected version {expected}, found {actual})")]
+    VersionConflict {
+        entity: String,
+        id: String,
+        expected: i64,
+        actual: i64,
+    },
+
     /// Foreign key constraint violation.
     ///
     /// ## When This Occurs
@@ -89,6 +106,21 @@ pub enum DbError {
     #[error("Connection pool exhausted")]
     PoolExhausted,
 
+    /// Sync payload failed its schema.
+    ///
+    /// ## When This Occurs
+    /// - Queuing an entity whose payload does not fit its DTO
+    /// - Queuing an entity type without a payload schema
+    #[error("Invalid sync payload: {0}")]
+    InvalidSyncPayload(#[from] titan_core::PayloadError),
+
+    /// A caller-supplied argument failed validation.
+    ///
+    /// ## When This Occurs
+    /// - A pagination cursor that is corrupted or from another list
+    #[error("Invalid input: {0}")]
+    InvalidInput(#[from] titan_core::ValidationError),
+
     /// Internal database error.
     #[error("Internal database error: {0}")]
     Internal(String),
@@ -110,6 +142,42 @@ impl DbError {
             value: value.into(),
         }
     }
+
+    /// Creates a VersionConflict error.
+    pub fn version_conflict(
+        entity: impl Into<String>,
+        id: impl Into<String>,
+        expected: i64,
+        actual: i64,
+    ) -> Self {
+        DbError::VersionConflict {
+            entity: entity.into(),
+            id: id.into(),
+            expected,
+            actual,
+        }
+    }
+
+    /// Stable code for this error.
+    ///
+    /// A busy or closed pool is `UNAVAILABLE` (worth retrying); a failed
+    /// query is not.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            DbError::NotFound { .. } => ErrorCode::NotFound,
+            DbError::UniqueViolation { .. } | DbError::VersionConflict { .. } => {
+                ErrorCode::Conflict
+            }
+            DbError::ForeignKeyViolation { .. } => ErrorCode::ValidationError,
+            DbError::ConnectionFailed(_) | DbError::PoolExhausted => ErrorCode::Unavailable,
+            DbError::MigrationFailed(_)
+            | DbError::QueryFailed(_)
+            | DbError::TransactionFailed(_)
+            | DbError::Internal(_) => ErrorCode::DatabaseError,
+            DbError::InvalidSyncPayload(_) => ErrorCode::Internal,
+            DbError::InvalidInput(_) => ErrorCode::ValidationError,
+        }
+    }
 }
 
 /// Convert sqlx errors to DbError.
@@ -170,5 +238,11 @@ impl From<sqlx::migrate::MigrateError> for DbError {
     }
 }
 
+impl From<titan_core::pii::PiiError> for DbError {
+    fn from(err: titan_core::pii::PiiError) -> Self {
+        DbError::Internal(err.to_string())
+    }
+}
+
 /// Result type for database operations.
 pub type DbResult<T> = Result<T, DbError>;
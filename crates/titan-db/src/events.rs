@@ -0,0 +1,88 @@
+//! # Event Publishing
+//!
+//! Lets repositories announce committed writes as
+//! [`EntityEvent`](titan_core::EntityEvent)s without knowing who listens.
+//!
+//! ## Wiring
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Event Publishing                                 │
+//! │                                                                         │
+//! │  app (owns the channel)                                                 │
+//! │    let (tx, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);            │
+//! │    let db = db.with_events(EventPublisher::new(tx.clone()));            │
+//! │       │                                                                 │
+//! │       ▼  every clone of db, every repository it hands out               │
+//! │  ProductRepository::update ─► commit ─► publisher.publish(event)        │
+//! │                                             │                           │
+//! │                                             ▼                           │
+//! │                                   tx.subscribe() in each consumer       │
+//! │                                                                         │
+//! │  Without with_events (tests, tools) publishing does nothing.           │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Publishing never blocks or fails a write: an event with no subscriber
+//! is dropped, and a subscriber that falls more than
+//! [`EVENT_CHANNEL_CAPACITY`] events behind skips the oldest.
+
+use tokio::sync::broadcast;
+use tracing::trace;
+
+use titan_core::EntityEvent;
+
+/// Events buffered per subscriber before the oldest are skipped.
+pub const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Sends entity events to the app's event channel, if one is attached.
+#[derive(Debug, Clone, Default)]
+pub struct EventPublisher {
+    tx: Option<broadcast::Sender<EntityEvent>>,
+}
+
+impl EventPublisher {
+    /// A publisher sending to `tx`.
+    pub fn new(tx: broadcast::Sender<EntityEvent>) -> Self {
+        EventPublisher { tx: Some(tx) }
+    }
+
+    /// A publisher that drops every event.
+    pub fn disabled() -> Self {
+        EventPublisher::default()
+    }
+
+    /// Publishes an event to current subscribers.
+    pub fn publish(&self, event: EntityEvent) {
+        if let Some(tx) = &self.tx {
+            // Err only means nobody is subscribed right now
+            let receivers = tx.send(event).unwrap_or(0);
+            trace!(receivers, "Entity event published");
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publish_reaches_subscribers() {
+        let (tx, mut rx) = broadcast::channel(4);
+        let publisher = EventPublisher::new(tx);
+        let event = EntityEvent::SaleFinalized {
+            sale_id: "s-1".to_string(),
+            total_cents: 500,
+        };
+        publisher.publish(event.clone());
+        assert_eq!(rx.try_recv().unwrap(), event);
+
+        // No channel, or no subscribers: dropped quietly
+        EventPublisher::disabled().publish(event.clone());
+        drop(rx);
+        publisher.publish(event);
+    }
+}
@@ -0,0 +1,820 @@
+//! # Fixtures
+//!
+//! Builders for realistic products and sales, and a seed set that fills a
+//! database with them. Used by tests (with [`DbConfig::in_memory`]), the
+//! sync integration harness and the `seed` binary.
+//!
+//! ## Usage
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                           Fixtures                                      │
+//! │                                                                         │
+//! │  One-off rows                                                           │
+//! │    ProductFixture::new("COKE-330").price_cents(150).stock(10)           │
+//! │        .insert(&db)                                                     │
+//! │    SaleFixture::new().line(&product, 2).device("pos-02")                │
+//! │        .queued_for_sync().insert(&db)                                   │
+//! │                                                                         │
+//! │  A whole dataset, seeded right after migrations                         │
+//! │    DbConfig::in_memory().with_fixtures(                                 │
+//! │        Fixtures::new().products(500).sales(200)                         │
+//! │            .devices(["pos-01", "pos-02"]))                              │
+//! │                                                                         │
+//! │  Everything is deterministic except IDs: the same index gives the      │
+//! │  same SKU, barcode, name, price, tax rate and stock.                   │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! [`DbConfig::in_memory`]: crate::DbConfig::in_memory
+
+use chrono::{DateTime, Duration, Utc};
+use titan_core::money::Money;
+use titan_core::types::TaxRate;
+use titan_core::{
+    ItemTracking, Payment, PaymentMethod, Product, Sale, SaleDocument, SaleItem, SaleStatus,
+    DEFAULT_TENANT_ID,
+};
+use uuid::Uuid;
+
+use crate::error::{DbError, DbResult};
+use crate::pool::Database;
+
+// =============================================================================
+// Catalog
+// =============================================================================
+
+/// Product categories for realistic test data.
+pub const CATEGORIES: &[(&str, &[&str])] = &[
+    (
+        "BEV",
+        &[
+            "Coca-Cola",
+            "Pepsi",
+            "Sprite",
+            "Fanta",
+            "Dr Pepper",
+            "Mountain Dew",
+            "7-Up",
+            "Red Bull",
+            "Monster Energy",
+            "Gatorade",
+            "Dasani Water",
+            "Evian Water",
+            "Orange Juice",
+            "Apple Juice",
+            "Grape Juice",
+            "Lemonade",
+            "Iced Tea",
+            "Coffee",
+            "Hot Chocolate",
+            "Milk",
+        ],
+    ),
+    (
+        "SNK",
+        &[
+            "Lays Classic",
+            "Doritos Nacho",
+            "Cheetos",
+            "Pringles",
+            "Ruffles",
+            "Tostitos",
+            "Fritos",
+            "Snickers",
+            "M&Ms",
+            "Reeses",
+            "Kit Kat",
+            "Twix",
+            "Skittles",
+            "Starburst",
+            "Gummy Bears",
+            "Oreos",
+            "Chips Ahoy",
+            "Nutter Butter",
+            "Goldfish",
+            "Pretzels",
+        ],
+    ),
+    (
+        "DRY",
+        &[
+            "Whole Milk",
+            "2% Milk",
+            "Skim Milk",
+            "Almond Milk",
+            "Oat Milk",
+            "Cheddar Cheese",
+            "Mozzarella",
+            "Swiss Cheese",
+            "Cream Cheese",
+            "Butter",
+            "Greek Yogurt",
+            "Regular Yogurt",
+            "Sour Cream",
+            "Heavy Cream",
+            "Half & Half",
+            "Eggs Dozen",
+            "Eggs Half Dozen",
+            "Cottage Cheese",
+            "Parmesan",
+            "Feta Cheese",
+        ],
+    ),
+    (
+        "FRZ",
+        &[
+            "Vanilla Ice Cream",
+            "Chocolate Ice Cream",
+            "Strawberry Ice Cream",
+            "Cookie Dough Ice Cream",
+            "Mint Chip Ice Cream",
+            "Frozen Pizza",
+            "Frozen Burrito",
+            "Frozen Dinner",
+            "Ice Cream Bars",
+            "Popsicles",
+            "Frozen Vegetables",
+            "Frozen Fruit",
+            "Frozen Waffles",
+            "Fish Sticks",
+            "Chicken Nuggets",
+            "Frozen Fries",
+            "Ice Cream Sandwich",
+            "Sorbet",
+            "Frozen Breakfast",
+            "Frozen Pie",
+        ],
+    ),
+    (
+        "GRO",
+        &[
+            "White Bread",
+            "Wheat Bread",
+            "Pasta Spaghetti",
+            "Pasta Penne",
+            "Rice White",
+            "Rice Brown",
+            "Canned Beans",
+            "Canned Corn",
+            "Canned Tomatoes",
+            "Canned Soup",
+            "Cereal Cheerios",
+            "Cereal Frosted Flakes",
+            "Oatmeal",
+            "Peanut Butter",
+            "Jelly",
+            "Honey",
+            "Maple Syrup",
+            "Flour",
+            "Sugar",
+            "Salt",
+        ],
+    ),
+];
+
+/// Size variants and their price addon in cents.
+pub const SIZES: &[(&str, i64)] = &[
+    ("Small", 0),
+    ("Medium", 100),
+    ("Large", 200),
+    ("XL", 350),
+    ("12oz", 0),
+    ("16oz", 50),
+    ("20oz", 100),
+    ("2L", 150),
+    ("6-Pack", 300),
+    ("12-Pack", 500),
+];
+
+/// Tax rates in basis points.
+pub const TAX_RATES: &[u32] = &[0, 500, 825, 1000];
+
+/// Products per category (every name in every size).
+const PER_CATEGORY: usize = 20 * 10;
+
+// =============================================================================
+// Product Fixture
+// =============================================================================
+
+/// Builder for a product row.
+#[derive(Debug, Clone)]
+pub struct ProductFixture {
+    product: Product,
+}
+
+impl ProductFixture {
+    /// A plain taxable product with 100 in stock.
+    pub fn new(sku: &str) -> Self {
+        let now = Utc::now();
+        ProductFixture {
+            product: Product {
+                id: Uuid::new_v4().to_string(),
+                tenant_id: DEFAULT_TENANT_ID.to_string(),
+                sku: sku.to_string(),
+                barcode: None,
+                name: format!("Product {}", sku),
+                description: None,
+                price_cents: 199,
+                base_price_cents: None,
+                cost_cents: None,
+                tax_rate_bps: 0,
+                track_inventory: true,
+                allow_negative_stock: false,
+                current_stock: Some(100),
+                item_tracking: ItemTracking::None,
+                min_purchase_age: None,
+                is_active: true,
+                deleted_at: None,
+                created_at: now,
+                updated_at: now,
+                sync_version: 0,
+            },
+        }
+    }
+
+    /// The `index`-th product of the catalog, in category order.
+    ///
+    /// The first 1,000 indexes cover every category/name/size; later ones
+    /// repeat the catalog with distinct SKUs and barcodes.
+    pub fn realistic(index: usize) -> Self {
+        let category = index / PER_CATEGORY % CATEGORIES.len();
+        let round = index / (PER_CATEGORY * CATEGORIES.len());
+        Self::realistic_in(category, round * PER_CATEGORY + index % PER_CATEGORY)
+    }
+
+    /// The `index`-th product of one category (an index into
+    /// [`CATEGORIES`]). Indexes past the category's 200 name/size
+    /// combinations repeat them with distinct SKUs and barcodes.
+    pub fn realistic_in(category: usize, index: usize) -> Self {
+        let category = category % CATEGORIES.len();
+        let (category_code, names) = CATEGORIES[category];
+        let within = index % PER_CATEGORY;
+        let name = names[within / SIZES.len()];
+        let (size, price_addon) = SIZES[within % SIZES.len()];
+        let round = index / PER_CATEGORY;
+        let seed =
+            round * 5000 + category * 1000 + (within / SIZES.len()) * 20 + within % SIZES.len();
+
+        // Base $1.99-$9.99 plus the size addon; cost 60-80% of price
+        let price_cents = 199 + ((seed * 17) % 800) as i64 + price_addon;
+        let cost_pct = 60 + (seed % 20) as i64;
+
+        let sku = format!(
+            "{}-{}-{:03}",
+            category_code,
+            &name.replace(' ', "")[..3].to_uppercase(),
+            seed
+        );
+
+        ProductFixture::new(&sku)
+            .name(&format!("{} {}", name, size))
+            // EAN-13 shaped, checksum not valid
+            .barcode(&format!("590{:010}", seed))
+            .price_cents(price_cents)
+            .cost_cents(price_cents * cost_pct / 100)
+            .tax_rate_bps(TAX_RATES[seed % TAX_RATES.len()])
+            .stock((seed % 101) as i64)
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.product.name = name.to_string();
+        self
+    }
+
+    pub fn barcode(mut self, barcode: &str) -> Self {
+        self.product.barcode = Some(barcode.to_string());
+        self
+    }
+
+    pub fn price_cents(mut self, cents: i64) -> Self {
+        self.product.price_cents = cents;
+        self
+    }
+
+    pub fn cost_cents(mut self, cents: i64) -> Self {
+        self.product.cost_cents = Some(cents);
+        self
+    }
+
+    pub fn tax_rate_bps(mut self, bps: u32) -> Self {
+        self.product.tax_rate_bps = bps;
+        self
+    }
+
+    pub fn stock(mut self, stock: i64) -> Self {
+        self.product.current_stock = Some(stock);
+        self
+    }
+
+    /// Stock is not tracked for this product.
+    pub fn untracked(mut self) -> Self {
+        self.product.track_inventory = false;
+        self.product.current_stock = None;
+        self
+    }
+
+    pub fn min_purchase_age(mut self, age: u32) -> Self {
+        self.product.min_purchase_age = Some(age);
+        self
+    }
+
+    pub fn item_tracking(mut self, tracking: ItemTracking) -> Self {
+        self.product.item_tracking = tracking;
+        self
+    }
+
+    pub fn inactive(mut self) -> Self {
+        self.product.is_active = false;
+        self
+    }
+
+    pub fn build(self) -> Product {
+        self.product
+    }
+
+    /// Inserts the product.
+    pub async fn insert(self, db: &Database) -> DbResult<Product> {
+        db.products().insert(&self.product).await
+    }
+}
+
+// =============================================================================
+// Sale Fixture
+// =============================================================================
+
+/// Builder for a sale with its items and payment.
+///
+/// Completed and paid in full by default; totals are computed from the
+/// lines with the products' tax rates.
+#[derive(Debug, Clone)]
+pub struct SaleFixture {
+    id: String,
+    receipt_number: Option<String>,
+    device_id: String,
+    station_number: Option<i64>,
+    user_id: String,
+    lines: Vec<(Product, i64)>,
+    method: PaymentMethod,
+    created_at: DateTime<Utc>,
+    completed: bool,
+    queue_for_sync: bool,
+}
+
+impl Default for SaleFixture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SaleFixture {
+    pub fn new() -> Self {
+        SaleFixture {
+            id: Uuid::new_v4().to_string(),
+            receipt_number: None,
+            device_id: "pos-01".to_string(),
+            station_number: None,
+            user_id: "default".to_string(),
+            lines: Vec::new(),
+            method: PaymentMethod::Cash,
+            created_at: Utc::now(),
+            completed: true,
+            queue_for_sync: false,
+        }
+    }
+
+    /// Adds `quantity` of `product`.
+    pub fn line(mut self, product: &Product, quantity: i64) -> Self {
+        self.lines.push((product.clone(), quantity));
+        self
+    }
+
+    pub fn device(mut self, device_id: &str) -> Self {
+        self.device_id = device_id.to_string();
+        self
+    }
+
+    /// Rings the sale up on register `number`.
+    pub fn station(mut self, number: i64) -> Self {
+        self.station_number = Some(number);
+        self
+    }
+
+    pub fn user(mut self, user_id: &str) -> Self {
+        self.user_id = user_id.to_string();
+        self
+    }
+
+    pub fn receipt_number(mut self, receipt_number: &str) -> Self {
+        self.receipt_number = Some(receipt_number.to_string());
+        self
+    }
+
+    pub fn paid_with(mut self, method: PaymentMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    pub fn at(mut self, created_at: DateTime<Utc>) -> Self {
+        self.created_at = created_at;
+        self
+    }
+
+    /// Leaves the sale as an unpaid draft.
+    pub fn draft(mut self) -> Self {
+        self.completed = false;
+        self
+    }
+
+    /// Also queues the sale in the sync outbox, like a real checkout.
+    pub fn queued_for_sync(mut self) -> Self {
+        self.queue_for_sync = true;
+        self
+    }
+
+    /// The sale, its items and (when completed) its payment.
+    pub fn build(&self) -> (Sale, Vec<SaleItem>, Option<Payment>) {
+        let items: Vec<SaleItem> = self
+            .lines
+            .iter()
+            .map(|(product, quantity)| {
+                let line_total =
+                    Money::from_cents(product.price_cents).multiply_quantity(*quantity);
+                SaleItem {
+                    id: Uuid::new_v4().to_string(),
+                    sale_id: self.id.clone(),
+                    product_id: product.id.clone(),
+                    sku_snapshot: product.sku.clone(),
+                    name_snapshot: product.name.clone(),
+                    unit_price_cents: product.price_cents,
+                    quantity: *quantity,
+                    line_total_cents: line_total.cents(),
+                    tax_cents: line_total
+                        .calculate_tax(TaxRate::from_bps(product.tax_rate_bps))
+                        .cents(),
+                    discount_cents: 0,
+                    base_price_cents: None,
+                    tax_rate_bps: Some(product.tax_rate_bps),
+                    created_at: self.created_at,
+                }
+            })
+            .collect();
+
+        let subtotal_cents: i64 = items.iter().map(|i| i.line_total_cents).sum();
+        let tax_cents: i64 = items.iter().map(|i| i.tax_cents).sum();
+        let total_cents = subtotal_cents + tax_cents;
+
+        let sale = Sale {
+            id: self.id.clone(),
+            tenant_id: DEFAULT_TENANT_ID.to_string(),
+            receipt_number: self
+                .receipt_number
+                .clone()
+                .unwrap_or_else(|| format!("FX-{}", &self.id[..8])),
+            status: if self.completed {
+                SaleStatus::Completed
+            } else {
+                SaleStatus::Draft
+            },
+            subtotal_cents,
+            tax_cents,
+            discount_cents: 0,
+            total_cents,
+            user_id: self.user_id.clone(),
+            device_id: self.device_id.clone(),
+            station_number: self.station_number,
+            notes: None,
+            created_at: self.created_at,
+            updated_at: self.created_at,
+            completed_at: self.completed.then_some(self.created_at),
+            sync_version: i64::from(self.completed),
+        };
+
+        let payment = self.completed.then(|| Payment {
+            id: Uuid::new_v4().to_string(),
+            sale_id: self.id.clone(),
+            method: self.method,
+            amount_cents: total_cents,
+            tendered_cents: None,
+            change_cents: None,
+            reference: None,
+            created_at: self.created_at,
+        });
+
+        (sale, items, payment)
+    }
+
+    /// Inserts the sale, its items and payment (and outbox entry).
+    pub async fn insert(self, db: &Database) -> DbResult<Sale> {
+        let (sale, items, payment) = self.build();
+        let sales = db.sales();
+        sales.insert_sale(&sale).await?;
+        for item in &items {
+            sales.add_item(item).await?;
+        }
+        if let Some(payment) = &payment {
+            sales.add_payment(payment).await?;
+        }
+        if self.queue_for_sync {
+            let doc = SaleDocument {
+                sale: sale.clone(),
+                items,
+                payments: payment.into_iter().collect(),
+            };
+            let payload = serde_json::to_string(&doc)
+                .map_err(|e| DbError::Internal(format!("Failed to serialize sale: {}", e)))?;
+            db.sync_outbox()
+                .queue_for_sync("SALE", &sale.id, &payload)
+                .await?;
+        }
+        Ok(sale)
+    }
+}
+
+// =============================================================================
+// Seed Set
+// =============================================================================
+
+/// A dataset of realistic products and sales to seed a database with.
+#[derive(Debug, Clone)]
+pub struct Fixtures {
+    products: usize,
+    /// Relative share of products per category, by index into CATEGORIES
+    category_weights: Vec<u32>,
+    sales: usize,
+    history_days: u32,
+    devices: Vec<String>,
+    queue_sales: bool,
+}
+
+/// What [`Fixtures::seed`] inserted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SeedSummary {
+    pub products: usize,
+    pub sales: usize,
+}
+
+impl Default for Fixtures {
+    fn default() -> Self {
+        Fixtures {
+            products: 0,
+            category_weights: vec![1; CATEGORIES.len()],
+            sales: 0,
+            history_days: 7,
+            devices: Vec::new(),
+            queue_sales: false,
+        }
+    }
+}
+
+impl Fixtures {
+    /// An empty dataset.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of catalog products (see [`ProductFixture::realistic_in`]).
+    pub fn products(mut self, count: usize) -> Self {
+        self.products = count;
+        self
+    }
+
+    /// Share of products per category code, e.g. `[("BEV", 3), ("SNK", 1)]`
+    /// for three beverages per snack. Categories not listed get none.
+    /// Default: an even split over every category.
+    ///
+    /// ## Errors
+    /// Returns `DbError::NotFound` for a code not in [`CATEGORIES`].
+    pub fn category_weights(mut self, weights: &[(&str, u32)]) -> DbResult<Self> {
+        let mut category_weights = vec![0; CATEGORIES.len()];
+        for (code, weight) in weights {
+            let index = CATEGORIES
+                .iter()
+                .position(|(c, _)| c.eq_ignore_ascii_case(code))
+                .ok_or_else(|| DbError::NotFound {
+                    entity: "Category".to_string(),
+                    id: code.to_string(),
+                })?;
+            category_weights[index] = *weight;
+        }
+        self.category_weights = category_weights;
+        Ok(self)
+    }
+
+    /// Number of completed sales, spread over the products.
+    pub fn sales(mut self, count: usize) -> Self {
+        self.sales = count;
+        self
+    }
+
+    /// Days of history the sales are spread over, ending now (default 7).
+    pub fn history_days(mut self, days: u32) -> Self {
+        self.history_days = days;
+        self
+    }
+
+    /// Terminals the sales are attributed to, round robin
+    /// (default: "pos-01").
+    pub fn devices<I, S>(mut self, devices: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.devices = devices.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Queues every sale in the sync outbox.
+    pub fn queue_sales_for_sync(mut self) -> Self {
+        self.queue_sales = true;
+        self
+    }
+
+    /// Products per category under the configured weights.
+    ///
+    /// Shares are rounded down and the remainder goes to the heaviest
+    /// categories, so the counts always add up to the product count.
+    pub fn category_counts(&self) -> Vec<usize> {
+        let total: u64 = self.category_weights.iter().map(|w| u64::from(*w)).sum();
+        if total == 0 {
+            return vec![0; CATEGORIES.len()];
+        }
+        let mut counts: Vec<usize> = self
+            .category_weights
+            .iter()
+            .map(|w| (self.products as u64 * u64::from(*w) / total) as usize)
+            .collect();
+
+        let mut by_weight: Vec<usize> = (0..counts.len())
+            .filter(|i| self.category_weights[*i] > 0)
+            .collect();
+        by_weight.sort_by_key(|i| std::cmp::Reverse(self.category_weights[*i]));
+        let assigned: usize = counts.iter().sum();
+        for i in by_weight.iter().cycle().take(self.products - assigned) {
+            counts[*i] += 1;
+        }
+        counts
+    }
+
+    /// Inserts the dataset.
+    pub async fn seed(&self, db: &Database) -> DbResult<SeedSummary> {
+        let products = self.seed_products(db).await?;
+        let sales = self.seed_sales(db, &products).await?;
+        Ok(SeedSummary {
+            products: products.len(),
+            sales,
+        })
+    }
+
+    /// Inserts the products, category by category.
+    pub async fn seed_products(&self, db: &Database) -> DbResult<Vec<Product>> {
+        let mut products = Vec::with_capacity(self.products);
+        for (category, count) in self.category_counts().into_iter().enumerate() {
+            for index in 0..count {
+                products.push(
+                    ProductFixture::realistic_in(category, index)
+                        .insert(db)
+                        .await?,
+                );
+            }
+        }
+        Ok(products)
+    }
+
+    /// Inserts the sales against `products` and returns how many.
+    ///
+    /// Sales have 1-4 lines each and are evenly spaced over the history,
+    /// newest first. Nothing is inserted without products.
+    pub async fn seed_sales(&self, db: &Database, products: &[Product]) -> DbResult<usize> {
+        if products.is_empty() {
+            return Ok(0);
+        }
+        let now = Utc::now();
+        let spacing_secs = i64::from(self.history_days) * 86_400 / self.sales.max(1) as i64;
+
+        for index in 0..self.sales {
+            let device = self
+                .devices
+                .get(index % self.devices.len().max(1))
+                .map(String::as_str)
+                .unwrap_or("pos-01");
+            let mut sale = SaleFixture::new()
+                .device(device)
+                .receipt_number(&format!("FX-{}-{:06}", device, index))
+                .at(now - Duration::seconds(spacing_secs * index as i64));
+            for line in 0..=(index % 4) {
+                let product = &products[(index * 7 + line * 13) % products.len()];
+                sale = sale.line(product, 1 + (index + line) as i64 % 3);
+            }
+            if self.queue_sales {
+                sale = sale.queued_for_sync();
+            }
+            sale.insert(db).await?;
+        }
+        Ok(self.sales)
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pool::DbConfig;
+    use titan_core::PageRequest;
+
+    #[test]
+    fn test_realistic_products_are_stable() {
+        let first = ProductFixture::realistic(0).build();
+        assert_eq!(first.sku, "BEV-COC-000");
+        assert_eq!(first.name, "Coca-Cola Small");
+        assert_eq!(first.barcode.as_deref(), Some("5900000000000"));
+
+        // The catalog repeats with new SKUs after 1,000 products
+        let again = ProductFixture::realistic(1000).build();
+        assert_eq!(again.name, first.name);
+        assert_ne!(again.sku, first.sku);
+    }
+
+    #[test]
+    fn test_category_counts_follow_weights() {
+        let fixtures = Fixtures::new()
+            .products(10)
+            .category_weights(&[("BEV", 3), ("SNK", 1)])
+            .unwrap();
+        assert_eq!(fixtures.category_counts(), vec![8, 2, 0, 0, 0]);
+
+        let even = Fixtures::new().products(7);
+        assert_eq!(even.category_counts().iter().sum::<usize>(), 7);
+
+        assert!(Fixtures::new().category_weights(&[("XYZ", 1)]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_with_fixtures() {
+        let config = DbConfig::in_memory().with_fixtures(
+            Fixtures::new()
+                .products(30)
+                .sales(10)
+                .devices(["pos-01", "pos-02"])
+                .queue_sales_for_sync(),
+        );
+        let db = Database::new(config).await.unwrap();
+
+        assert_eq!(db.products().count().await.unwrap(), 30);
+        assert_eq!(db.sync_outbox().count_pending().await.unwrap(), 10);
+        let pending = db.sync_outbox().get_pending(10).await.unwrap();
+        let sale_id = &pending[0].entity_id;
+        let sale = db.sales().get_by_id(sale_id).await.unwrap().unwrap();
+        let items = db.sales().get_items(sale_id).await.unwrap();
+        assert_eq!(
+            sale.subtotal_cents,
+            items.iter().map(|i| i.line_total_cents).sum::<i64>()
+        );
+        assert_eq!(
+            db.sales().get_total_paid(sale_id).await.unwrap(),
+            sale.total_cents
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pages_cover_seeded_data() {
+        let config = DbConfig::in_memory().with_fixtures(Fixtures::new().products(25).sales(12));
+        let db = Database::new(config).await.unwrap();
+
+        let mut seen = Vec::new();
+        let mut request = PageRequest::first(10).with_total();
+        loop {
+            let page = db.products().search_page("", &request).await.unwrap();
+            seen.extend(page.items.into_iter().map(|p| p.id));
+            match page.next_cursor {
+                Some(cursor) => request = PageRequest::after(cursor, 10),
+                None => break,
+            }
+        }
+        seen.sort();
+        seen.dedup();
+        assert_eq!(seen.len(), 25);
+
+        let first = db
+            .sales()
+            .search_page("FX-", &PageRequest::first(5).with_total())
+            .await
+            .unwrap();
+        assert_eq!(first.total, Some(12));
+        let second = db
+            .sales()
+            .search_page("FX-", &PageRequest::after(first.next_cursor.unwrap(), 5))
+            .await
+            .unwrap();
+        // Newest first, continuing where the first page stopped
+        assert!(second.items[0].created_at < first.items[4].created_at);
+
+        let bad = PageRequest::after("zz", 5);
+        assert!(matches!(
+            db.products().search_page("", &bad).await,
+            Err(DbError::InvalidInput(_))
+        ));
+    }
+}
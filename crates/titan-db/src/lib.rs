@@ -35,9 +35,14 @@
 //!
 //! ## Module Organization
 //!
+//! - [`correlation`] - Correlation IDs carried from commands into the outbox
 //! - [`pool`] - Connection pool creation and configuration
 //! - [`migrations`] - Embedded database migrations
+//! - [`schema`] - Live schema description, ER export and drift check
 //! - [`error`] - Database error types
+//! - [`events`] - Entity events published by repositories after writes
+//! - [`fixtures`] - Realistic product/sale test data and in-memory seeding
+//! - [`payload_crypto`] - Encryption of sensitive outbox payload fields
 //! - [`repository`] - Repository implementations (product, sale, etc.)
 //!
 //! ## Usage
@@ -60,19 +65,54 @@
 // Module Declarations
 // =============================================================================
 
+pub mod correlation;
 pub mod error;
+pub mod events;
+pub mod fixtures;
 pub mod migrations;
+pub mod payload_crypto;
+pub mod pii;
 pub mod pool;
 pub mod repository;
+pub mod schema;
 
 // =============================================================================
 // Re-exports
 // =============================================================================
 
 pub use error::DbError;
-pub use pool::{Database, DbConfig};
+pub use events::EventPublisher;
+pub use fixtures::{Fixtures, ProductFixture, SaleFixture};
+pub use payload_crypto::{PayloadCipher, PayloadKey};
+pub use pii::{MasterKey, PiiCipher, PiiField};
+pub use pool::{Database, DbConfig, PerformanceProfile, SqlitePragmas, SyncLevel, TempStore};
+pub use schema::{SchemaDescription, SchemaDrift};
 
 // Repository re-exports for convenience
-pub use repository::product::ProductRepository;
+pub use repository::bundle::BundleRepository;
+pub use repository::business_customer::BusinessCustomerRepository;
+pub use repository::cash_drawer::CashDrawerRepository;
+pub use repository::coupon::{CouponRepository, CouponUse};
+pub use repository::erasure::ErasureRepository;
+pub use repository::feature_flag::FeatureFlagRepository;
+pub use repository::hub_queue::{HubUpload, HubUploadQueueRepository};
+pub use repository::job::JobRepository;
+pub use repository::label::LabelRepository;
+pub use repository::layaway::LayawayRepository;
+pub use repository::loyalty::{LoyaltyRedemption, LoyaltyRepository};
+pub use repository::manager_override::ManagerOverrideRepository;
+pub use repository::margin_override::MarginOverrideRepository;
+pub use repository::pack::PackRepository;
+pub use repository::pii_key::PiiKeyRepository;
+pub use repository::product::{ProductRepository, StockLedgerLine};
+pub use repository::quote::QuoteRepository;
+pub use repository::receipt_campaign::ReceiptCampaignRepository;
 pub use repository::sale::SaleRepository;
+pub use repository::store_credit::{Redemption, StoreCreditRepository};
+pub use repository::style::ProductStyleRepository;
+pub use repository::supplier::SupplierRepository;
 pub use repository::sync::SyncOutboxRepository;
+pub use repository::till::TillRepository;
+pub use repository::transfer::TransferRepository;
+pub use repository::waste::WasteRepository;
+pub use repository::z_report::ZReportRepository;
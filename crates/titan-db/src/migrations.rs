@@ -53,10 +53,12 @@ use crate::error::DbResult;
 /// directory into the binary at compile time. No runtime file access needed.
 ///
 /// ## Directory Structure
-/// ```
+/// ```text
 /// migrations/sqlite/
 /// ├── 001_initial_schema.sql  # Core tables
 /// ├── 002_add_fts.sql         # Full-text search
+/// ├── 003_sync_tables.sql     # Sync engine tables
+/// ├── 004_till_sessions.sql   # Till sessions and blind close
 /// └── ...
 /// ```
 static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("../../migrations/sqlite");
@@ -105,3 +107,116 @@ pub async fn migration_status(pool: &SqlitePool) -> DbResult<(usize, usize)> {
 
     Ok((total, applied as usize))
 }
+
+/// Version of the newest embedded migration (e.g. `32` for
+/// `032_….sql`), i.e. the schema a database has once this build has
+/// opened it.
+///
+/// ## Usage
+/// For crash reports and diagnostics, where the database may not be
+/// reachable.
+pub fn latest_version() -> i64 {
+    MIGRATOR
+        .migrations
+        .iter()
+        .map(|migration| migration.version)
+        .max()
+        .unwrap_or(0)
+}
+
+/// How a database's applied migrations compare with this build's.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaReport {
+    /// Newest migration embedded in this build.
+    pub latest_version: i64,
+    /// Migrations recorded in `_sqlx_migrations`.
+    pub applied: usize,
+    /// Embedded but not applied: the database is behind this build.
+    pub missing: Vec<i64>,
+    /// Applied but not embedded: the database was opened by a newer build.
+    pub unknown: Vec<i64>,
+    /// Applied from a different file than the one embedded.
+    pub modified: Vec<i64>,
+    /// Recorded as failed.
+    pub failed: Vec<i64>,
+}
+
+impl SchemaReport {
+    /// Whether the schema is exactly the one this build expects.
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty()
+            && self.unknown.is_empty()
+            && self.modified.is_empty()
+            && self.failed.is_empty()
+    }
+}
+
+/// Compares the migrations applied to a database with the embedded ones,
+/// by version and checksum.
+///
+/// ## Usage
+/// For support tooling: a database copied between builds or edited by
+/// hand shows up here before it fails in a query.
+pub async fn verify_schema(pool: &SqlitePool) -> DbResult<SchemaReport> {
+    let applied: Vec<(i64, Vec<u8>, bool)> =
+        sqlx::query_as("SELECT version, checksum, success FROM _sqlx_migrations ORDER BY version")
+            .fetch_all(pool)
+            .await?;
+
+    let mut report = SchemaReport {
+        latest_version: latest_version(),
+        applied: applied.len(),
+        ..SchemaReport::default()
+    };
+    for migration in MIGRATOR.migrations.iter() {
+        match applied.iter().find(|(version, _, _)| *version == migration.version) {
+            None => report.missing.push(migration.version),
+            Some((_, checksum, _)) if checksum.as_slice() != &*migration.checksum => {
+                report.modified.push(migration.version)
+            }
+            Some(_) => {}
+        }
+    }
+    for (version, _, success) in &applied {
+        if !MIGRATOR.migrations.iter().any(|m| m.version == *version) {
+            report.unknown.push(*version);
+        }
+        if !success {
+            report.failed.push(*version);
+        }
+    }
+
+    Ok(report)
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::pool::{Database, DbConfig};
+
+    #[tokio::test]
+    async fn test_schema_and_integrity_checks() {
+        let db = Database::new(DbConfig::in_memory()).await.unwrap();
+
+        let report = crate::migrations::verify_schema(db.pool()).await.unwrap();
+        assert!(report.is_ok(), "{:?}", report);
+        assert_eq!(report.latest_version, crate::migrations::latest_version());
+        assert_eq!(report.applied as i64, report.latest_version);
+        assert!(db.integrity_check().await.unwrap().is_empty());
+
+        // A migration recorded by a newer build
+        sqlx::query(
+            "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time) \
+             VALUES (999, 'future', 1, x'00', 0)",
+        )
+        .execute(db.pool())
+        .await
+        .unwrap();
+        let report = crate::migrations::verify_schema(db.pool()).await.unwrap();
+        assert_eq!(report.unknown, vec![999]);
+        assert!(!report.is_ok());
+    }
+}
@@ -0,0 +1,371 @@
+//! # Outbox Payload Encryption
+//!
+//! Outbox payloads are full documents: a sale with its payments, a layaway
+//! with the customer's name and phone. The fields listed by
+//! `titan_core::sensitive_fields` are encrypted before the payload is
+//! written to `sync_outbox`; everything else stays plain JSON, so totals,
+//! IDs and timestamps can still be read with `json_extract` or the admin
+//! CLI.
+//!
+//! ## Where Payloads Are Sealed and Opened
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                       Outbox Payload Encryption                         │
+//! │                                                                         │
+//! │  Terminal                                                               │
+//! │    queue_for_sync / upsert_for_sync ──► seal ──► sync_outbox.payload    │
+//! │      {"customer_phone":"opk1:9f86d081:…","total_cents":1250,…}          │
+//! │    OutboxBatch to the hub carries the payload as stored                 │
+//! │    cloud fallback ──► open ──► SyncEntity (plain, over TLS)             │
+//! │                                                                         │
+//! │  Hub                                                                    │
+//! │    hub_upload_queue.payload stored as received (sealed)                 │
+//! │    open ──► validate, apply, relay to registers                         │
+//! │    HubUplink ──► open ──► SyncEntity (plain, over TLS)                  │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Unlike the PII key (see [`crate::pii`]), payload keys are provisioned:
+//! a hub can only open what a terminal sealed if both were given the same
+//! key. Each sealed value names the key it was sealed with (the first four
+//! bytes of the key's SHA-256, in hex). The first key seals; every key
+//! opens, so a new key is rolled out to all devices before it is put
+//! first, and an old one is dropped once nothing sealed with it is queued.
+//!
+//! Without keys payloads are stored in plain text, as before.
+
+use std::fmt;
+use std::sync::Arc;
+
+use ring::aead::LessSafeKey;
+use ring::digest;
+use serde_json::Value;
+
+use crate::error::{DbError, DbResult};
+use crate::pii::{aead_key, open, random_key, seal, KEY_LEN};
+use titan_core::sensitive_fields;
+
+/// Prefix of a sealed payload field.
+pub const SEALED_PREFIX: &str = "opk1:";
+
+// =============================================================================
+// Payload Key
+// =============================================================================
+
+/// A provisioned payload key.
+#[derive(Clone)]
+pub struct PayloadKey {
+    /// Hex of the first four bytes of the key's SHA-256.
+    id: String,
+    bytes: [u8; KEY_LEN],
+    aead: Arc<LessSafeKey>,
+}
+
+impl PayloadKey {
+    fn new(bytes: [u8; KEY_LEN]) -> Self {
+        let id = hex::encode(&digest::digest(&digest::SHA256, &bytes).as_ref()[..4]);
+        PayloadKey {
+            id,
+            aead: Arc::new(aead_key(&bytes)),
+            bytes,
+        }
+    }
+
+    /// Parses a key written as 64 hex characters.
+    ///
+    /// ## Errors
+    /// `DbError::Internal` if the text is not a 32-byte hex key.
+    pub fn from_hex(text: &str) -> DbResult<Self> {
+        let bytes = hex::decode(text.trim())
+            .map_err(|_| DbError::Internal("Payload key is not hex".to_string()))?;
+        let bytes = bytes
+            .try_into()
+            .map_err(|_| DbError::Internal("Payload key must be 32 bytes".to_string()))?;
+        Ok(PayloadKey::new(bytes))
+    }
+
+    /// A new random key, for provisioning.
+    pub fn generate() -> DbResult<Self> {
+        Ok(PayloadKey::new(random_key()?))
+    }
+
+    /// The key as 64 hex characters.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.bytes)
+    }
+
+    /// The ID sealed values carry.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl fmt::Debug for PayloadKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PayloadKey({})", self.id)
+    }
+}
+
+// =============================================================================
+// Payload Cipher
+// =============================================================================
+
+/// Seals and opens the sensitive fields of outbox payloads.
+#[derive(Clone, Default)]
+pub struct PayloadCipher {
+    /// The first key seals; all of them open. Empty: plain text.
+    keys: Arc<Vec<PayloadKey>>,
+}
+
+impl PayloadCipher {
+    /// A cipher sealing with the first of `keys`.
+    pub fn new(keys: Vec<PayloadKey>) -> Self {
+        PayloadCipher {
+            keys: Arc::new(keys),
+        }
+    }
+
+    /// A cipher that stores plain text.
+    pub fn disabled() -> Self {
+        PayloadCipher::default()
+    }
+
+    /// Parses a list of hex keys separated by commas, spaces or newlines,
+    /// the sealing key first.
+    ///
+    /// ## Errors
+    /// `DbError::Internal` if an entry is not a 32-byte hex key.
+    pub fn from_hex_list(text: &str) -> DbResult<Self> {
+        let keys = text
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|key| !key.is_empty())
+            .map(PayloadKey::from_hex)
+            .collect::<DbResult<Vec<_>>>()?;
+        Ok(PayloadCipher::new(keys))
+    }
+
+    /// Whether payloads are sealed.
+    pub fn is_enabled(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    /// The IDs of the keys, the sealing key first.
+    pub fn key_ids(&self) -> Vec<&str> {
+        self.keys.iter().map(PayloadKey::id).collect()
+    }
+
+    /// Encrypts the sensitive fields of a payload. Fields already sealed
+    /// are left as they are; a payload with nothing to seal is returned
+    /// unchanged.
+    ///
+    /// ## Errors
+    /// `DbError::Internal` if the payload is not JSON.
+    pub fn seal(&self, entity_type: &str, payload: &str) -> DbResult<String> {
+        let Some(key) = self.keys.first() else {
+            return Ok(payload.to_string());
+        };
+        let fields = sensitive_fields(entity_type);
+        if fields.is_empty() {
+            return Ok(payload.to_string());
+        }
+
+        let mut doc = parse(payload)?;
+        let mut sealed = false;
+        for path in fields {
+            let aad = format!("{}:{}", entity_type, path);
+            for_each_field(&mut doc, path, &mut |value| {
+                if value.starts_with(SEALED_PREFIX) {
+                    return Ok(None);
+                }
+                let ciphertext = seal(&key.aead, aad.as_bytes(), value.as_bytes())?;
+                sealed = true;
+                Ok(Some(format!("{}{}:{}", SEALED_PREFIX, key.id, ciphertext)))
+            })?;
+        }
+        if !sealed {
+            return Ok(payload.to_string());
+        }
+        to_string(&doc)
+    }
+
+    /// Decrypts the sealed fields of a payload; a payload with none is
+    /// returned unchanged.
+    ///
+    /// ## Errors
+    /// `DbError::Internal` if a field was sealed with a key this cipher
+    /// does not have, or does not decrypt.
+    pub fn open(&self, entity_type: &str, payload: &str) -> DbResult<String> {
+        if !payload.contains(SEALED_PREFIX) {
+            return Ok(payload.to_string());
+        }
+
+        let mut doc = parse(payload)?;
+        for path in sensitive_fields(entity_type) {
+            let aad = format!("{}:{}", entity_type, path);
+            for_each_field(&mut doc, path, &mut |value| {
+                let Some(sealed) = value.strip_prefix(SEALED_PREFIX) else {
+                    return Ok(None);
+                };
+                let (key_id, ciphertext) = sealed.split_once(':').unwrap_or(("", sealed));
+                let key = self.keys.iter().find(|k| k.id == key_id).ok_or_else(|| {
+                    DbError::Internal(format!(
+                        "{} {} is sealed with payload key {}, which this device does not have",
+                        entity_type, path, key_id
+                    ))
+                })?;
+                let plain = open(&key.aead, aad.as_bytes(), ciphertext).map_err(|_| {
+                    DbError::Internal(format!("Cannot decrypt {} {}", entity_type, path))
+                })?;
+                String::from_utf8(plain).map(Some).map_err(|_| {
+                    DbError::Internal(format!("Decrypted {} {} is not text", entity_type, path))
+                })
+            })?;
+        }
+        to_string(&doc)
+    }
+}
+
+impl fmt::Debug for PayloadCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PayloadCipher")
+            .field("keys", &self.key_ids())
+            .finish()
+    }
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+fn parse(payload: &str) -> DbResult<Value> {
+    serde_json::from_str(payload)
+        .map_err(|e| DbError::Internal(format!("Outbox payload is not JSON: {}", e)))
+}
+
+fn to_string(doc: &Value) -> DbResult<String> {
+    serde_json::to_string(doc)
+        .map_err(|e| DbError::Internal(format!("Failed to serialize outbox payload: {}", e)))
+}
+
+/// Calls `f` with every string at `path` (see `titan_core::sensitive_fields`)
+/// and replaces it with what `f` returns, if anything. Missing fields and
+/// nulls are skipped.
+fn for_each_field(
+    value: &mut Value,
+    path: &str,
+    f: &mut dyn FnMut(&str) -> DbResult<Option<String>>,
+) -> DbResult<()> {
+    let (segment, rest) = match path.split_once('.') {
+        Some((segment, rest)) => (segment, Some(rest)),
+        None => (path, None),
+    };
+    let (name, each) = match segment.strip_suffix("[]") {
+        Some(name) => (name, true),
+        None => (segment, false),
+    };
+    let Some(child) = value.get_mut(name) else {
+        return Ok(());
+    };
+
+    let mut visit = |target: &mut Value| -> DbResult<()> {
+        match rest {
+            Some(rest) => for_each_field(target, rest, f),
+            None => {
+                if let Value::String(text) = target {
+                    if let Some(replaced) = f(text)? {
+                        *text = replaced;
+                    }
+                }
+                Ok(())
+            }
+        }
+    };
+    match child {
+        Value::Array(items) if each => items.iter_mut().try_for_each(&mut visit),
+        _ if each => Ok(()),
+        target => visit(target),
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cipher() -> PayloadCipher {
+        PayloadCipher::new(vec![PayloadKey::generate().unwrap()])
+    }
+
+    #[test]
+    fn test_seal_round_trip_keeps_other_fields_plain() {
+        let cipher = cipher();
+        let payload =
+            r#"{"id":"l-1","customer_name":"Jane","customer_phone":null,"total_cents":1250}"#;
+
+        let sealed = cipher.seal("LAYAWAY", payload).unwrap();
+        let doc: Value = serde_json::from_str(&sealed).unwrap();
+        let name = doc["customer_name"].as_str().unwrap();
+        assert!(name.starts_with(SEALED_PREFIX));
+        assert!(!sealed.contains("Jane"));
+        assert_eq!(doc["customer_phone"], Value::Null);
+        assert_eq!(doc["total_cents"], 1250);
+
+        // Sealing again changes nothing; opening restores the values
+        assert_eq!(cipher.seal("LAYAWAY", &sealed).unwrap(), sealed);
+        let opened: Value =
+            serde_json::from_str(&cipher.open("LAYAWAY", &sealed).unwrap()).unwrap();
+        assert_eq!(opened, serde_json::from_str::<Value>(payload).unwrap());
+
+        // Bound to the entity type and field
+        let mut moved = doc.clone();
+        moved["customer_phone"] = moved["customer_name"].clone();
+        moved["customer_name"] = "Jane".into();
+        assert!(cipher.open("LAYAWAY", &moved.to_string()).is_err());
+        assert!(cipher.open("STORE_CREDIT", &sealed).is_err());
+    }
+
+    #[test]
+    fn test_array_fields_and_untouched_types() {
+        let cipher = cipher();
+        let payload = r#"{"id":"s-1","payments":[{"reference":"AUTH-1"},{"reference":null}]}"#;
+        let sealed = cipher.seal("SALE", payload).unwrap();
+        assert!(!sealed.contains("AUTH-1"));
+        let opened: Value = serde_json::from_str(&cipher.open("SALE", &sealed).unwrap()).unwrap();
+        assert_eq!(opened["payments"][0]["reference"], "AUTH-1");
+
+        // Types without sensitive fields, and disabled ciphers, pass through
+        let waste = r#"{"id":"w-1","reason":"expired"}"#;
+        assert_eq!(cipher.seal("WASTE", waste).unwrap(), waste);
+        assert_eq!(
+            PayloadCipher::disabled().seal("SALE", payload).unwrap(),
+            payload
+        );
+    }
+
+    #[test]
+    fn test_key_rotation() {
+        let old = PayloadKey::generate().unwrap();
+        let new = PayloadKey::generate().unwrap();
+        let payload = r#"{"id":"e-1","subject":{"phone":"555-0100","email":null}}"#;
+        let sealed = PayloadCipher::new(vec![old.clone()])
+            .seal("CUSTOMER_ERASURE", payload)
+            .unwrap();
+
+        let rotated =
+            PayloadCipher::from_hex_list(&format!("{}, {}", new.to_hex(), old.to_hex())).unwrap();
+        assert_eq!(rotated.key_ids(), vec![new.id(), old.id()]);
+        assert!(rotated
+            .open("CUSTOMER_ERASURE", &sealed)
+            .unwrap()
+            .contains("555-0100"));
+
+        let err = PayloadCipher::new(vec![new])
+            .open("CUSTOMER_ERASURE", &sealed)
+            .unwrap_err();
+        assert!(err.to_string().contains(old.id()));
+        assert!(PayloadCipher::from_hex_list("not-a-key").is_err());
+    }
+}
@@ -0,0 +1,41 @@
+//! # PII Encryption
+//!
+//! Customer emails and phone numbers are stored encrypted, with a keyed
+//! hash beside each one so lookups by exact value still work.
+//!
+//! ## Envelope Encryption
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Envelope Encryption                              │
+//! │                                                                         │
+//! │  MasterKey (outside the database: env var or key file)                  │
+//! │       │ unwraps                                                         │
+//! │       ▼                                                                 │
+//! │  pii_keys.wrapped_key ──► tenant data key (random, one per tenant)      │
+//! │                               │ HKDF                                    │
+//! │                  ┌────────────┴─────────────┐                           │
+//! │                  ▼                          ▼                           │
+//! │         AES-256-GCM key               HMAC-SHA256 key                   │
+//! │  customer_phone = "pii1:…"      customer_phone_hash = hex(HMAC(value))  │
+//! │                                                                         │
+//! │  A copied database file shows neither the values nor (without the      │
+//! │  key) which rows share a value. Changing the master key re-wraps the   │
+//! │  data key only; no row is rewritten.                                   │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Values are hashed after normalizing (trimmed; emails lowercased), so a
+//! lookup matches however the value was typed. Values without the `pii1:`
+//! prefix are plain text from before encryption and are returned as they
+//! are; [`crate::Database::with_pii_key`] encrypts them when it opens the
+//! tenant's key.
+//!
+//! Without a key (tests, tools) values are stored in plain text and the
+//! hashes use a fixed key.
+//!
+//! The cryptography is [`titan_core::pii`], shared with the cloud, so a
+//! value encrypted here opens there with the same tenant data key.
+
+pub use titan_core::pii::{MasterKey, PiiCipher, PiiError, PiiField, CIPHERTEXT_PREFIX};
+
+pub(crate) use titan_core::pii::{aead_key, open, random_key, seal, KEY_LEN};
@@ -39,24 +39,209 @@
 //! - Readers don't block writers
 //! - Writers don't block readers
 //! - Better crash recovery
+//!
+//! ## Performance Profiles
+//! A low-end terminal on a flaky power supply needs different trade-offs
+//! than the hub machine, so the remaining pragmas come from a preset
+//! chosen per deployment, each of which can be overridden:
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                     PerformanceProfile Presets                          │
+//! │                                                                         │
+//! │              synchronous   cache_size   mmap_size   temp_store          │
+//! │  durable     FULL          2 MiB        off         FILE                │
+//! │  balanced    NORMAL        8 MiB        off         DEFAULT  (default)  │
+//! │  fast        NORMAL        64 MiB       256 MiB     MEMORY              │
+//! │                                                                         │
+//! │  DbConfig::new(path).profile(PerformanceProfile::Fast)                 │
+//! │                     .cache_size_kib(32 * 1024)   ◄── override one      │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//! No preset turns `synchronous` off: with WAL, NORMAL already can't
+//! corrupt the database and at worst loses the last transaction on power
+//! loss. FULL also keeps that transaction, at the cost of an fsync per
+//! commit.
 
 use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous};
 use sqlx::SqlitePool;
-use std::path::PathBuf;
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::Duration;
 use tracing::{debug, info};
 
 use crate::error::{DbError, DbResult};
+use crate::events::EventPublisher;
+use crate::fixtures::Fixtures;
+use crate::payload_crypto::PayloadCipher;
+use crate::pii::{MasterKey, PiiCipher};
 use crate::migrations;
 use crate::repository::product::ProductRepository;
 use crate::repository::sale::SaleRepository;
 use crate::repository::sync::SyncOutboxRepository;
+use crate::repository::layaway::LayawayRepository;
+use crate::repository::quote::QuoteRepository;
+use crate::repository::till::TillRepository;
+use crate::repository::transfer::TransferRepository;
+use crate::repository::store_credit::StoreCreditRepository;
+use crate::repository::coupon::CouponRepository;
+use crate::repository::loyalty::LoyaltyRepository;
+use crate::repository::business_customer::BusinessCustomerRepository;
+use crate::repository::erasure::ErasureRepository;
+use crate::repository::feature_flag::FeatureFlagRepository;
+use crate::repository::receipt_campaign::ReceiptCampaignRepository;
+use crate::repository::hub_queue::HubUploadQueueRepository;
+use crate::repository::label::LabelRepository;
+use crate::repository::job::JobRepository;
+use crate::repository::pii_key::PiiKeyRepository;
+use crate::repository::style::ProductStyleRepository;
+use crate::repository::bundle::BundleRepository;
+use crate::repository::margin_override::MarginOverrideRepository;
+use crate::repository::manager_override::ManagerOverrideRepository;
+use crate::repository::cash_drawer::CashDrawerRepository;
+use crate::repository::pack::PackRepository;
+use crate::repository::waste::WasteRepository;
+use crate::repository::supplier::SupplierRepository;
+use crate::repository::z_report::ZReportRepository;
 
 // =============================================================================
 // Configuration
 // =============================================================================
 
+/// SQLite `synchronous` level: how often commits wait for the disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncLevel {
+    /// Never waits; a power loss can corrupt the database.
+    Off,
+    /// Waits at checkpoints (with WAL: safe, may lose the last commit).
+    Normal,
+    /// Waits on every commit.
+    Full,
+    /// Like `Full`, and also syncs the directory.
+    Extra,
+}
+
+impl From<SyncLevel> for SqliteSynchronous {
+    fn from(level: SyncLevel) -> Self {
+        match level {
+            SyncLevel::Off => SqliteSynchronous::Off,
+            SyncLevel::Normal => SqliteSynchronous::Normal,
+            SyncLevel::Full => SqliteSynchronous::Full,
+            SyncLevel::Extra => SqliteSynchronous::Extra,
+        }
+    }
+}
+
+/// SQLite `temp_store`: where temporary tables and indices live.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TempStore {
+    /// SQLite's compile-time default (a file on most builds).
+    Default,
+    File,
+    Memory,
+}
+
+impl TempStore {
+    /// The pragma value.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            TempStore::Default => "DEFAULT",
+            TempStore::File => "FILE",
+            TempStore::Memory => "MEMORY",
+        }
+    }
+}
+
+/// SQLite pragmas applied to every connection in the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqlitePragmas {
+    pub synchronous: SyncLevel,
+    /// Page cache per connection, in KiB.
+    pub cache_size_kib: u32,
+    /// Memory-mapped I/O limit in bytes (0 = off).
+    pub mmap_size_bytes: u64,
+    pub temp_store: TempStore,
+}
+
+/// Preset trade-off between durability and speed (see Performance
+/// Profiles above).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PerformanceProfile {
+    /// Low-end terminals with unreliable power: every commit on disk,
+    /// little memory.
+    Durable,
+    /// Ordinary terminals.
+    #[default]
+    Balanced,
+    /// The hub machine: plenty of memory, heavy reads.
+    Fast,
+}
+
+impl PerformanceProfile {
+    /// Every profile.
+    pub const ALL: [PerformanceProfile; 3] = [
+        PerformanceProfile::Durable,
+        PerformanceProfile::Balanced,
+        PerformanceProfile::Fast,
+    ];
+
+    /// The profile's name, as accepted by `FromStr`.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            PerformanceProfile::Durable => "durable",
+            PerformanceProfile::Balanced => "balanced",
+            PerformanceProfile::Fast => "fast",
+        }
+    }
+
+    /// The pragmas this profile sets.
+    pub const fn pragmas(self) -> SqlitePragmas {
+        match self {
+            PerformanceProfile::Durable => SqlitePragmas {
+                synchronous: SyncLevel::Full,
+                cache_size_kib: 2 * 1024,
+                mmap_size_bytes: 0,
+                temp_store: TempStore::File,
+            },
+            PerformanceProfile::Balanced => SqlitePragmas {
+                synchronous: SyncLevel::Normal,
+                cache_size_kib: 8 * 1024,
+                mmap_size_bytes: 0,
+                temp_store: TempStore::Default,
+            },
+            PerformanceProfile::Fast => SqlitePragmas {
+                synchronous: SyncLevel::Normal,
+                cache_size_kib: 64 * 1024,
+                mmap_size_bytes: 256 * 1024 * 1024,
+                temp_store: TempStore::Memory,
+            },
+        }
+    }
+}
+
+impl fmt::Display for PerformanceProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PerformanceProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        PerformanceProfile::ALL
+            .into_iter()
+            .find(|profile| profile.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                format!(
+                    "Unknown database profile '{}' (expected durable, balanced or fast)",
+                    s
+                )
+            })
+    }
+}
+
 /// Database configuration.
 ///
 /// ## Example
@@ -89,6 +274,14 @@ pub struct DbConfig {
     /// Whether to run migrations on connect.
     /// Default: true
     pub run_migrations: bool,
+
+    /// Test data inserted after migrations.
+    /// Default: none
+    pub fixtures: Option<Fixtures>,
+
+    /// Pragmas set on every connection.
+    /// Default: the `balanced` profile
+    pub pragmas: SqlitePragmas,
 }
 
 impl DbConfig {
@@ -109,6 +302,8 @@ impl DbConfig {
             connect_timeout: Duration::from_secs(30),
             idle_timeout: Duration::from_secs(600),
             run_migrations: true,
+            fixtures: None,
+            pragmas: PerformanceProfile::default().pragmas(),
         }
     }
 
@@ -136,8 +331,62 @@ impl DbConfig {
         self
     }
 
+    /// Sets all pragmas from a preset; later calls to the pragma setters
+    /// override single values.
+    ///
+    /// ## Example
+    /// ```rust,ignore
+    /// let config = DbConfig::new("./titan.db")
+    ///     .profile(PerformanceProfile::Durable)
+    ///     .cache_size_kib(4 * 1024);
+    /// ```
+    pub fn profile(mut self, profile: PerformanceProfile) -> Self {
+        self.pragmas = profile.pragmas();
+        self
+    }
+
+    /// Sets the `synchronous` level.
+    pub fn synchronous(mut self, level: SyncLevel) -> Self {
+        self.pragmas.synchronous = level;
+        self
+    }
+
+    /// Sets the page cache per connection, in KiB.
+    pub fn cache_size_kib(mut self, kib: u32) -> Self {
+        self.pragmas.cache_size_kib = kib;
+        self
+    }
+
+    /// Sets the memory-mapped I/O limit in bytes (0 turns it off).
+    pub fn mmap_size(mut self, bytes: u64) -> Self {
+        self.pragmas.mmap_size_bytes = bytes;
+        self
+    }
+
+    /// Sets where temporary tables and indices live.
+    pub fn temp_store(mut self, temp_store: TempStore) -> Self {
+        self.pragmas.temp_store = temp_store;
+        self
+    }
+
+    /// Seeds the database with `fixtures` after migrations.
+    ///
+    /// ## Example
+    /// ```rust,ignore
+    /// let config = DbConfig::in_memory()
+    ///     .with_fixtures(Fixtures::new().products(200).sales(50));
+    /// ```
+    pub fn with_fixtures(mut self, fixtures: Fixtures) -> Self {
+        self.fixtures = Some(fixtures);
+        self
+    }
+
     /// Creates an in-memory database configuration (for testing).
     ///
+    /// Nothing touches the filesystem. The database lives as long as its
+    /// single connection, which is never closed for idleness or age; it
+    /// is gone once the last `Database` clone is dropped.
+    ///
     /// ## Usage
     /// ```rust,ignore
     /// let config = DbConfig::in_memory();
@@ -152,8 +401,15 @@ impl DbConfig {
             connect_timeout: Duration::from_secs(5),
             idle_timeout: Duration::from_secs(60),
             run_migrations: true,
+            fixtures: None,
+            pragmas: PerformanceProfile::default().pragmas(),
         }
     }
+
+    /// Whether this is an in-memory database.
+    pub fn is_in_memory(&self) -> bool {
+        self.database_path.as_os_str() == ":memory:"
+    }
 }
 
 // =============================================================================
@@ -196,6 +452,14 @@ impl DbConfig {
 pub struct Database {
     /// The SQLite connection pool.
     pool: SqlitePool,
+    /// Where repositories publish entity events.
+    events: EventPublisher,
+    /// Encrypts customer emails and phone numbers (disabled until
+    /// [`Database::with_pii_key`]).
+    pii: PiiCipher,
+    /// Seals sensitive outbox payload fields (disabled until
+    /// [`Database::with_payload_cipher`]).
+    payload: PayloadCipher,
 }
 
 impl Database {
@@ -205,10 +469,12 @@ impl Database {
     /// 1. Creates the database file if it doesn't exist
     /// 2. Configures SQLite for optimal POS performance:
     ///    - WAL mode for concurrent reads
-    ///    - NORMAL synchronous (balance of safety/speed)
     ///    - Foreign keys enabled
+    ///    - `synchronous`, `cache_size`, `mmap_size` and `temp_store` from
+    ///      `config.pragmas` (see [`PerformanceProfile`])
     /// 3. Creates the connection pool
     /// 4. Runs migrations (if enabled)
+    /// 5. Inserts fixtures (if configured)
     ///
     /// ## Arguments
     /// * `config` - Database configuration
@@ -231,15 +497,19 @@ impl Database {
         // Build connection options
         // sqlite://path creates file if not exists
         let connect_url = format!("sqlite://{}?mode=rwc", config.database_path.display());
+        let pragmas = config.pragmas;
 
         let connect_options = SqliteConnectOptions::from_str(&connect_url)
             .map_err(|e| DbError::ConnectionFailed(e.to_string()))?
             // WAL mode: Better concurrent read performance
             // Readers don't block writers, writers don't block readers
             .journal_mode(SqliteJournalMode::Wal)
-            // NORMAL synchronous: Good balance of durability and speed
-            // Data is safe from corruption, may lose last transaction on crash
-            .synchronous(SqliteSynchronous::Normal)
+            // Durability vs. speed, from the deployment's profile
+            .synchronous(pragmas.synchronous.into())
+            // Negative cache_size is in KiB rather than pages
+            .pragma("cache_size", format!("-{}", pragmas.cache_size_kib))
+            .pragma("mmap_size", pragmas.mmap_size_bytes.to_string())
+            .pragma("temp_store", pragmas.temp_store.as_str())
             // Enable foreign key constraints
             // SQLite has them disabled by default for backwards compatibility
             .foreign_keys(true)
@@ -248,28 +518,55 @@ impl Database {
 
         debug!("Connection options configured");
 
+        // An in-memory database is lost when its connection closes, so
+        // that connection must never be recycled
+        let (idle_timeout, max_lifetime) = if config.is_in_memory() {
+            (None, None)
+        } else {
+            (Some(config.idle_timeout), Some(Duration::from_secs(30 * 60)))
+        };
+
         // Build the pool
         let pool = SqlitePoolOptions::new()
             .max_connections(config.max_connections)
             .min_connections(config.min_connections)
             .acquire_timeout(config.connect_timeout)
-            .idle_timeout(Some(config.idle_timeout))
+            .idle_timeout(idle_timeout)
+            .max_lifetime(max_lifetime)
             .connect_with(connect_options)
             .await
             .map_err(|e| DbError::ConnectionFailed(e.to_string()))?;
 
         info!(
             max_connections = config.max_connections,
+            synchronous = ?pragmas.synchronous,
+            cache_size_kib = pragmas.cache_size_kib,
+            mmap_size_bytes = pragmas.mmap_size_bytes,
+            temp_store = pragmas.temp_store.as_str(),
             "Database pool created"
         );
 
-        let db = Database { pool };
+        let db = Database {
+            pool,
+            events: EventPublisher::disabled(),
+            pii: PiiCipher::disabled(),
+            payload: PayloadCipher::disabled(),
+        };
 
         // Run migrations if enabled
         if config.run_migrations {
             db.run_migrations().await?;
         }
 
+        if let Some(fixtures) = &config.fixtures {
+            let seeded = fixtures.seed(&db).await?;
+            info!(
+                products = seeded.products,
+                sales = seeded.sales,
+                "Database seeded with fixtures"
+            );
+        }
+
         Ok(db)
     }
 
@@ -283,13 +580,172 @@ impl Database {
     /// ## When To Call
     /// - Automatically called by `new()` if `run_migrations` is true
     /// - Manually call when migrations are disabled in config
+    ///
+    /// Rows written before customer data had lookup hashes get them here
+    /// (see [`PiiKeyRepository::encrypt_existing`]).
     pub async fn run_migrations(&self) -> DbResult<()> {
         info!("Running database migrations");
         migrations::run_migrations(&self.pool).await?;
+        self.pii_keys().encrypt_existing(&self.pii).await?;
         info!("Migrations complete");
         Ok(())
     }
 
+    /// Publishes entity events from this handle, its clones and the
+    /// repositories they hand out (see [`crate::events`]).
+    pub fn with_events(mut self, events: EventPublisher) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// The entity event publisher, for writes made outside the
+    /// repositories (e.g. inbound sync).
+    pub fn events(&self) -> &EventPublisher {
+        &self.events
+    }
+
+    /// Encrypts customer emails and phone numbers with the tenant's data
+    /// key from here on (see [`crate::pii`]).
+    ///
+    /// The key is created on first use, wrapped with `master`. Values
+    /// written before encryption was enabled are encrypted now.
+    ///
+    /// ## Errors
+    /// `DbError::Internal` if the tenant's key was wrapped with a
+    /// different master key.
+    pub async fn with_pii_key(mut self, tenant_id: &str, master: &MasterKey) -> DbResult<Self> {
+        let keys = self.pii_keys();
+        let cipher = keys.load_or_create(tenant_id, master).await?;
+        keys.encrypt_existing(&cipher).await?;
+        self.pii = cipher;
+        Ok(self)
+    }
+
+    /// The customer data cipher, for writes made outside the repositories.
+    pub fn pii(&self) -> &PiiCipher {
+        &self.pii
+    }
+
+    /// Seals customer data and payment references in outbox payloads
+    /// from here on (see [`crate::payload_crypto`]). Entries already
+    /// queued are left as they are.
+    pub fn with_payload_cipher(mut self, payload: PayloadCipher) -> Self {
+        self.payload = payload;
+        self
+    }
+
+    /// The outbox payload cipher, for opening payloads sent by other
+    /// devices.
+    pub fn payload_cipher(&self) -> &PayloadCipher {
+        &self.payload
+    }
+
+    /// Writes a consistent copy of this database to `path`, which must not
+    /// exist yet. Sales can carry on while the copy is taken.
+    pub async fn backup_to(&self, path: &Path) -> DbResult<()> {
+        info!(path = %path.display(), "Backing up database");
+        sqlx::query("VACUUM INTO ?1")
+            .bind(path.to_string_lossy().into_owned())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Runs SQLite's integrity and foreign key checks. Returns the problems
+    /// found, empty when the file is sound.
+    pub async fn integrity_check(&self) -> DbResult<Vec<String>> {
+        let mut problems: Vec<String> = sqlx::query_scalar("PRAGMA integrity_check")
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .filter(|line: &String| line != "ok")
+            .collect();
+
+        let orphans: Vec<(String, Option<i64>, String)> =
+            sqlx::query_as("SELECT \"table\", rowid, parent FROM pragma_foreign_key_check")
+                .fetch_all(&self.pool)
+                .await?;
+        problems.extend(orphans.into_iter().map(|(table, rowid, parent)| {
+            format!(
+                "{} row {} references a missing {} row",
+                table,
+                rowid.map_or("?".to_string(), |id| id.to_string()),
+                parent
+            )
+        }));
+
+        Ok(problems)
+    }
+
+    /// Copies this database into a training sandbox at `path`.
+    ///
+    /// ```text
+    /// ┌─────────────────────────────────────────────────────────────────────────┐
+    /// │                        Training Sandbox                                 │
+    /// │                                                                         │
+    /// │  titan.db ── VACUUM INTO ──► titan-training.db (any old copy replaced)  │
+    /// │                                   │                                     │
+    /// │                                   ├── sync_outbox emptied               │
+    /// │                                   └── trigger: outbox inserts ignored   │
+    /// │                                                                         │
+    /// │  Practice sales, stock moves and price edits land in the copy and      │
+    /// │  are never queued for sync; the live database is untouched.            │
+    /// └─────────────────────────────────────────────────────────────────────────┘
+    /// ```
+    ///
+    /// The sandbox shares this handle's event publisher and customer data
+    /// key (the copy holds the same wrapped key). Only a file database can
+    /// be copied this way: the copy of an in-memory one stays in memory.
+    pub async fn training_sandbox(&self, path: impl Into<PathBuf>) -> DbResult<Database> {
+        let path = path.into();
+        info!(path = %path.display(), "Copying database into training sandbox");
+
+        for suffix in ["", "-wal", "-shm"] {
+            let file = PathBuf::from(format!("{}{}", path.display(), suffix));
+            match std::fs::remove_file(&file) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    return Err(DbError::Internal(format!(
+                        "Failed to remove {}: {}",
+                        file.display(),
+                        e
+                    )))
+                }
+            }
+        }
+
+        sqlx::query("VACUUM INTO ?1")
+            .bind(path.to_string_lossy().into_owned())
+            .execute(&self.pool)
+            .await?;
+
+        let sandbox = Database::new(DbConfig::new(&path)).await?;
+        let mut tx = sandbox.pool.begin().await?;
+        sqlx::query("DELETE FROM sync_outbox")
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            CREATE TRIGGER IF NOT EXISTS training_sync_outbox_blocked
+            BEFORE INSERT ON sync_outbox
+            BEGIN
+                SELECT RAISE(IGNORE);
+            END
+            "#,
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(Database {
+            pool: sandbox.pool,
+            events: self.events.clone(),
+            pii: self.pii.clone(),
+            payload: self.payload.clone(),
+        })
+    }
+
     /// Returns a reference to the connection pool.
     ///
     /// ## Usage
@@ -306,17 +762,141 @@ impl Database {
     /// let products = db.products().search("coke", 20).await?;
     /// ```
     pub fn products(&self) -> ProductRepository {
-        ProductRepository::new(self.pool.clone())
+        ProductRepository::new(self.pool.clone()).with_events(self.events.clone())
     }
 
     /// Returns the sale repository.
     pub fn sales(&self) -> SaleRepository {
         SaleRepository::new(self.pool.clone())
+            .with_events(self.events.clone())
+            .with_pii(self.pii.clone())
+    }
+
+    /// Returns the layaway repository.
+    pub fn layaways(&self) -> LayawayRepository {
+        LayawayRepository::new(self.pool.clone()).with_pii(self.pii.clone())
+    }
+
+    /// Returns the store credit repository.
+    pub fn store_credits(&self) -> StoreCreditRepository {
+        StoreCreditRepository::new(self.pool.clone()).with_pii(self.pii.clone())
+    }
+
+    /// Returns the coupon repository.
+    pub fn coupons(&self) -> CouponRepository {
+        CouponRepository::new(self.pool.clone())
+    }
+
+    /// Returns the loyalty repository.
+    pub fn loyalty(&self) -> LoyaltyRepository {
+        LoyaltyRepository::new(self.pool.clone())
+    }
+
+    /// Returns the business customer repository.
+    pub fn business_customers(&self) -> BusinessCustomerRepository {
+        BusinessCustomerRepository::new(self.pool.clone()).with_pii(self.pii.clone())
+    }
+
+    /// Returns the customer erasure repository.
+    pub fn erasures(&self) -> ErasureRepository {
+        ErasureRepository::new(self.pool.clone())
+            .with_pii(self.pii.clone())
+            .with_payload_cipher(self.payload.clone())
+    }
+
+    /// Returns the store transfer repository.
+    pub fn transfers(&self) -> TransferRepository {
+        TransferRepository::new(self.pool.clone())
+    }
+
+    /// Returns the quote repository.
+    pub fn quotes(&self) -> QuoteRepository {
+        QuoteRepository::new(self.pool.clone()).with_pii(self.pii.clone())
     }
 
     /// Returns the sync outbox repository.
     pub fn sync_outbox(&self) -> SyncOutboxRepository {
-        SyncOutboxRepository::new(self.pool.clone())
+        SyncOutboxRepository::new(self.pool.clone()).with_payload_cipher(self.payload.clone())
+    }
+
+    /// Returns the till session repository.
+    pub fn tills(&self) -> TillRepository {
+        TillRepository::new(self.pool.clone())
+    }
+
+    /// Returns the PII key repository.
+    pub fn pii_keys(&self) -> PiiKeyRepository {
+        PiiKeyRepository::new(self.pool.clone())
+    }
+
+    /// Returns the scheduled job repository.
+    pub fn jobs(&self) -> JobRepository {
+        JobRepository::new(self.pool.clone())
+    }
+
+    /// Returns the shelf label repository.
+    pub fn labels(&self) -> LabelRepository {
+        LabelRepository::new(self.pool.clone())
+    }
+
+    /// Returns the feature flag cache repository.
+    pub fn feature_flags(&self) -> FeatureFlagRepository {
+        FeatureFlagRepository::new(self.pool.clone())
+    }
+
+    /// Returns the receipt footer campaign cache repository.
+    pub fn receipt_campaigns(&self) -> ReceiptCampaignRepository {
+        ReceiptCampaignRepository::new(self.pool.clone())
+    }
+
+    /// Returns the product style (variant) repository.
+    pub fn styles(&self) -> ProductStyleRepository {
+        ProductStyleRepository::new(self.pool.clone()).with_events(self.events.clone())
+    }
+
+    /// Returns the bundle (bill of materials) repository.
+    pub fn bundles(&self) -> BundleRepository {
+        BundleRepository::new(self.pool.clone()).with_events(self.events.clone())
+    }
+
+    /// Returns the pack size and stock receipt repository.
+    pub fn packs(&self) -> PackRepository {
+        PackRepository::new(self.pool.clone()).with_events(self.events.clone())
+    }
+
+    /// Returns the waste (shrinkage) repository.
+    pub fn waste(&self) -> WasteRepository {
+        WasteRepository::new(self.pool.clone()).with_events(self.events.clone())
+    }
+
+    /// Returns the supplier and product cost repository.
+    pub fn suppliers(&self) -> SupplierRepository {
+        SupplierRepository::new(self.pool.clone())
+    }
+
+    /// Returns the margin override audit repository.
+    pub fn margin_overrides(&self) -> MarginOverrideRepository {
+        MarginOverrideRepository::new(self.pool.clone())
+    }
+
+    /// Returns the manager override token audit repository.
+    pub fn manager_overrides(&self) -> ManagerOverrideRepository {
+        ManagerOverrideRepository::new(self.pool.clone())
+    }
+
+    /// Returns the cash drawer open audit repository.
+    pub fn cash_drawer_opens(&self) -> CashDrawerRepository {
+        CashDrawerRepository::new(self.pool.clone())
+    }
+
+    /// Returns the Z report repository.
+    pub fn z_reports(&self) -> ZReportRepository {
+        ZReportRepository::new(self.pool.clone())
+    }
+
+    /// Returns the hub's upload queue repository.
+    pub fn hub_upload_queue(&self) -> HubUploadQueueRepository {
+        HubUploadQueueRepository::new(self.pool.clone())
     }
 
     /// Closes the database connection pool.
@@ -349,6 +929,8 @@ impl Database {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures::{ProductFixture, SaleFixture};
+    use uuid::Uuid;
 
     #[tokio::test]
     async fn test_in_memory_database() {
@@ -358,6 +940,14 @@ mod tests {
         assert!(db.health_check().await);
     }
 
+    #[tokio::test]
+    async fn test_in_memory_database_is_migrated() {
+        let db = Database::new(DbConfig::in_memory()).await.unwrap();
+
+        assert_eq!(db.products().count().await.unwrap(), 0);
+        assert!(db.jobs().list().await.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn test_config_builder() {
         let config = DbConfig::new("/tmp/test.db")
@@ -366,5 +956,74 @@ mod tests {
 
         assert_eq!(config.max_connections, 10);
         assert_eq!(config.min_connections, 2);
+        assert_eq!(config.pragmas, PerformanceProfile::Balanced.pragmas());
+    }
+
+    #[tokio::test]
+    async fn test_profile_pragmas_are_applied() {
+        let config = DbConfig::in_memory()
+            .profile(PerformanceProfile::Durable)
+            .cache_size_kib(4 * 1024);
+        let db = Database::new(config).await.unwrap();
+
+        let pragma = |name: &'static str| {
+            let pool = db.pool().clone();
+            async move {
+                sqlx::query_scalar::<_, i64>(&format!("PRAGMA {}", name))
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap()
+            }
+        };
+        assert_eq!(pragma("synchronous").await, 2); // FULL
+        assert_eq!(pragma("cache_size").await, -4096);
+        assert_eq!(pragma("temp_store").await, 1); // FILE
+    }
+
+    #[test]
+    fn test_profile_names() {
+        for profile in PerformanceProfile::ALL {
+            assert_eq!(profile.as_str().parse::<PerformanceProfile>(), Ok(profile));
+        }
+        assert_eq!(" FAST ".parse::<PerformanceProfile>(), Ok(PerformanceProfile::Fast));
+        assert!("turbo".parse::<PerformanceProfile>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_training_sandbox_never_queues_sync() {
+        // VACUUM INTO from an in-memory database makes another in-memory
+        // database, so both sides are files here
+        let dir = std::env::temp_dir();
+        let live_path = dir.join(format!("titan-live-{}.db", Uuid::new_v4()));
+        let path = dir.join(format!("titan-training-{}.db", Uuid::new_v4()));
+        let db = Database::new(DbConfig::new(&live_path)).await.unwrap();
+        let cola = ProductFixture::new("COLA-330").stock(10).insert(&db).await.unwrap();
+        SaleFixture::new().line(&cola, 1).queued_for_sync().insert(&db).await.unwrap();
+
+        let sandbox = db.training_sandbox(&path).await.unwrap();
+
+        assert_eq!(sandbox.sync_outbox().count_pending().await.unwrap(), 0);
+        sandbox.products().update_stock(&cola.id, -4).await.unwrap();
+        SaleFixture::new().line(&cola, 4).queued_for_sync().insert(&sandbox).await.unwrap();
+        assert_eq!(sandbox.sync_outbox().count_pending().await.unwrap(), 0);
+
+        let practiced = sandbox.products().get_by_id(&cola.id).await.unwrap().unwrap();
+        let live = db.products().get_by_id(&cola.id).await.unwrap().unwrap();
+        assert_eq!((practiced.current_stock, live.current_stock), (Some(6), Some(10)));
+        assert_eq!(db.sync_outbox().count_pending().await.unwrap(), 1);
+
+        // A fresh copy replaces the earlier one
+        let again = db.training_sandbox(&path).await.unwrap();
+        let fresh = again.products().get_by_id(&cola.id).await.unwrap().unwrap();
+        assert_eq!(fresh.current_stock, Some(10));
+
+        for handle in [&db, &sandbox, &again] {
+            handle.pool().close().await;
+        }
+        for file in [&live_path, &path] {
+            for suffix in ["", "-wal", "-shm"] {
+                let _ = std::fs::remove_file(format!("{}{}", file.display(), suffix));
+            }
+        }
     }
 }
@@ -0,0 +1,469 @@
+//! # Bundle Repository
+//!
+//! Database operations for bundle products' bills of materials (see
+//! `titan_core::bundle`).
+//!
+//! ## Selling a Bundle
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                     Component Stock                                     │
+//! │                                                                         │
+//! │  consume(sale, device, bundle, 3)          (one transaction)           │
+//! │       │                                                                 │
+//! │       ├── WINE-0042  current_stock - 3, inventory_deltas row           │
+//! │       ├── CHOC-0007  current_stock - 6, inventory_deltas row           │
+//! │       └── BASKET-S   current_stock - 3, inventory_deltas row           │
+//! │                      (delta_type bundle_sale, reference the sale)      │
+//! │                                                                         │
+//! │  The bundle product's own stock is never touched. Components that     │
+//! │  don't track inventory are skipped, as for any other stock move.       │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::Utc;
+use sqlx::SqlitePool;
+use tracing::debug;
+
+use crate::error::{DbError, DbResult};
+use crate::events::EventPublisher;
+use crate::repository::product::ProductRepository;
+use crate::repository::stock::{move_stock, REFERENCE_SALE};
+use crate::repository::sync::SyncOutboxRepository;
+use titan_core::bundle::normalize_components;
+use titan_core::{
+    BundleComponent, EntityEvent, Product, ProductBundle, ProductChange, ValidationError,
+};
+
+/// Inventory delta reason when a sold bundle takes its components out of
+/// stock.
+pub const DELTA_BUNDLE_SALE: &str = "bundle_sale";
+
+/// Repository for bundle bills of materials.
+#[derive(Debug, Clone)]
+pub struct BundleRepository {
+    pool: SqlitePool,
+    events: EventPublisher,
+}
+
+impl BundleRepository {
+    /// Creates a new BundleRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        BundleRepository {
+            pool,
+            events: EventPublisher::disabled(),
+        }
+    }
+
+    /// Publishes `ProductChanged` and `StockChanged` events through
+    /// `events`.
+    pub fn with_events(mut self, events: EventPublisher) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Gets a product's bill of materials; empty (version 0) for a product
+    /// that is not a bundle.
+    ///
+    /// ## Errors
+    /// `DbError::NotFound` if the product doesn't exist.
+    pub async fn get(&self, product_id: &str) -> DbResult<ProductBundle> {
+        let header = sqlx::query!(
+            r#"
+            SELECT
+                bundle_version as "version!: i64",
+                bundle_updated_at as "updated_at: chrono::DateTime<Utc>",
+                created_at as "created_at!: chrono::DateTime<Utc>"
+            FROM products
+            WHERE id = ?1
+            "#,
+            product_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| DbError::not_found("Product", product_id))?;
+
+        let components = sqlx::query_as!(
+            BundleComponent,
+            r#"
+            SELECT component_id as "product_id!", quantity
+            FROM bundle_components
+            WHERE bundle_id = ?1
+            ORDER BY component_id
+            "#,
+            product_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ProductBundle {
+            product_id: product_id.to_string(),
+            components,
+            sync_version: header.version,
+            updated_at: header.updated_at.unwrap_or(header.created_at),
+        })
+    }
+
+    /// Loads the component products of a bundle (deleted ones included, so
+    /// a bundle with a missing component can still be reported).
+    pub async fn component_products(&self, bundle: &ProductBundle) -> DbResult<Vec<Product>> {
+        let products = ProductRepository::new(self.pool.clone());
+        let mut found = Vec::with_capacity(bundle.components.len());
+        for component in &bundle.components {
+            if let Some(product) = products.get_by_id(&component.product_id).await? {
+                found.push(product);
+            }
+        }
+        Ok(found)
+    }
+
+    /// Replaces a product's bill of materials and queues it for sync; an
+    /// empty list makes the product an ordinary product again.
+    ///
+    /// ## Errors
+    /// - `DbError::InvalidInput` for an invalid list (see
+    ///   `titan_core::bundle::normalize_components`), a component that is
+    ///   itself a bundle, or a bundle that is a component of another one
+    /// - `DbError::NotFound` if the product or a component doesn't exist or
+    ///   is deleted
+    pub async fn set_components(
+        &self,
+        product_id: &str,
+        components: &[BundleComponent],
+    ) -> DbResult<ProductBundle> {
+        let components = normalize_components(product_id, components)?;
+        let now = Utc::now();
+
+        debug!(product_id = %product_id, count = components.len(), "Setting bundle components");
+
+        if !components.is_empty() {
+            self.check_no_nesting(product_id, &components).await?;
+        }
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let versions = sqlx::query!(
+            r#"
+            UPDATE products
+            SET
+                bundle_version = bundle_version + 1,
+                bundle_updated_at = ?2
+            WHERE id = ?1 AND deleted_at IS NULL
+            RETURNING bundle_version as "bundle_version!: i64", sync_version
+            "#,
+            product_id,
+            now
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| DbError::not_found("Product", product_id))?;
+
+        replace_components(&mut tx, product_id, &components).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let bundle = ProductBundle {
+            product_id: product_id.to_string(),
+            components,
+            sync_version: versions.bundle_version,
+            updated_at: now,
+        };
+        let payload = serde_json::to_string(&bundle)
+            .map_err(|e| DbError::Internal(format!("Failed to serialize bundle: {}", e)))?;
+        SyncOutboxRepository::new(self.pool.clone())
+            .upsert_for_sync("PRODUCT_BUNDLE", product_id, &payload)
+            .await?;
+
+        self.events.publish(EntityEvent::ProductChanged {
+            product_id: product_id.to_string(),
+            change: ProductChange::Updated,
+            version: versions.sync_version,
+        });
+        Ok(bundle)
+    }
+
+    /// Applies a bill of materials received through sync.
+    ///
+    /// ## Returns
+    /// * `Ok(true)` - Applied
+    /// * `Ok(false)` - Product unknown, or its bill of materials is already
+    ///   at or past the document's version
+    pub async fn upsert_from_sync(&self, doc: &ProductBundle) -> DbResult<bool> {
+        doc.validate()?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let version: Option<i64> = sqlx::query_scalar!(
+            r#"
+            UPDATE products
+            SET
+                bundle_version = ?2,
+                bundle_updated_at = ?3
+            WHERE id = ?1 AND bundle_version < ?2
+            RETURNING sync_version
+            "#,
+            doc.product_id,
+            doc.sync_version,
+            doc.updated_at
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(version) = version else {
+            return Ok(false);
+        };
+
+        replace_components(&mut tx, &doc.product_id, &doc.components).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        self.events.publish(EntityEvent::ProductChanged {
+            product_id: doc.product_id.clone(),
+            change: ProductChange::Updated,
+            version,
+        });
+        Ok(true)
+    }
+
+    /// Lists the bills of materials of products with IDs after `after_id`
+    /// that have one (or had one), in ID order, for a full resync; pass an
+    /// empty string for the first page.
+    pub async fn list_after(&self, after_id: &str, limit: u32) -> DbResult<Vec<ProductBundle>> {
+        let ids = sqlx::query_scalar!(
+            r#"
+            SELECT id as "id!" FROM products
+            WHERE id > ?1 AND bundle_version > 0
+            ORDER BY id
+            LIMIT ?2
+            "#,
+            after_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut bundles = Vec::with_capacity(ids.len());
+        for id in ids {
+            bundles.push(self.get(&id).await?);
+        }
+        Ok(bundles)
+    }
+
+    /// Takes the components of `quantity` sold bundles out of stock, with
+    /// one inventory delta per component referencing the sale. Returns the
+    /// stock moved per component.
+    ///
+    /// Availability is the caller's check (see
+    /// `titan_core::bundle::check_components_available`); this only moves
+    /// the stock.
+    pub async fn consume(
+        &self,
+        sale_id: &str,
+        device_id: &str,
+        bundle: &ProductBundle,
+        quantity: i64,
+    ) -> DbResult<Vec<BundleComponent>> {
+        let demand = bundle.component_quantities(quantity);
+
+        debug!(bundle_id = %bundle.product_id, quantity, sale_id = %sale_id, "Consuming bundle components");
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        for component in &demand {
+            move_stock(
+                &mut tx,
+                REFERENCE_SALE,
+                &component.product_id,
+                -component.quantity,
+                DELTA_BUNDLE_SALE,
+                sale_id,
+                device_id,
+            )
+            .await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        for component in &demand {
+            let stock: Option<Option<i64>> = sqlx::query_scalar!(
+                "SELECT current_stock FROM products WHERE id = ?1 AND track_inventory = 1",
+                component.product_id
+            )
+            .fetch_optional(&self.pool)
+            .await?;
+            if let Some(stock) = stock {
+                self.events.publish(EntityEvent::StockChanged {
+                    product_id: component.product_id.clone(),
+                    delta: -component.quantity,
+                    stock,
+                });
+            }
+        }
+
+        Ok(demand)
+    }
+
+    // =========================================================================
+    // Helpers
+    // =========================================================================
+
+    /// Bundles don't nest: components must not be bundles, and the bundle
+    /// must not be a component of another bundle.
+    async fn check_no_nesting(
+        &self,
+        product_id: &str,
+        components: &[BundleComponent],
+    ) -> DbResult<()> {
+        let used_in: Option<String> = sqlx::query_scalar!(
+            r#"SELECT bundle_id as "bundle_id!" FROM bundle_components WHERE component_id = ?1 LIMIT 1"#,
+            product_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        if used_in.is_some() {
+            return Err(not_nested(product_id));
+        }
+
+        for component in components {
+            let row = sqlx::query!(
+                r#"
+                SELECT EXISTS(
+                    SELECT 1 FROM bundle_components WHERE bundle_id = ?1
+                ) as "is_bundle!: bool"
+                FROM products
+                WHERE id = ?1 AND deleted_at IS NULL
+                "#,
+                component.product_id
+            )
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| DbError::not_found("Product", &component.product_id))?;
+
+            if row.is_bundle {
+                return Err(not_nested(&component.product_id));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn not_nested(product_id: &str) -> DbError {
+    DbError::InvalidInput(ValidationError::InvalidFormat {
+        field: "components".to_string(),
+        reason: format!("{} is part of a bundle structure; bundles don't nest", product_id),
+    })
+}
+
+async fn replace_components(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    bundle_id: &str,
+    components: &[BundleComponent],
+) -> DbResult<()> {
+    sqlx::query!("DELETE FROM bundle_components WHERE bundle_id = ?1", bundle_id)
+        .execute(&mut **tx)
+        .await?;
+
+    for component in components {
+        sqlx::query!(
+            "INSERT INTO bundle_components (bundle_id, component_id, quantity) VALUES (?1, ?2, ?3)",
+            bundle_id,
+            component.product_id,
+            component.quantity
+        )
+        .execute(&mut **tx)
+        .await?;
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::Fixtures;
+    use crate::pool::{Database, DbConfig};
+    use titan_core::{BundleComponent, Product};
+
+    #[tokio::test]
+    async fn test_bundle_components_and_stock() {
+        let config = DbConfig::in_memory().with_fixtures(Fixtures::new().products(4));
+        let db = Database::new(config).await.unwrap();
+        let products = db.products().search("", 4).await.unwrap();
+        let (basket, wine, choc) = (&products[0], &products[1], &products[2]);
+
+        let bundle = db
+            .bundles()
+            .set_components(
+                &basket.id,
+                &[
+                    BundleComponent::new(wine.id.as_str(), 1),
+                    BundleComponent::new(choc.id.as_str(), 2),
+                ],
+            )
+            .await
+            .unwrap();
+        assert!(bundle.is_bundle());
+        assert_eq!(bundle.sync_version, 1);
+        assert_eq!(db.bundles().get(&basket.id).await.unwrap(), bundle);
+        assert!(!db.bundles().get(&wine.id).await.unwrap().is_bundle());
+
+        // Bundles don't nest, either way round
+        let nested = [BundleComponent::new(basket.id.as_str(), 1)];
+        assert!(db.bundles().set_components(&products[3].id, &nested).await.is_err());
+        let nested = [BundleComponent::new(products[3].id.as_str(), 1)];
+        assert!(db.bundles().set_components(&wine.id, &nested).await.is_err());
+
+        let moved = db
+            .bundles()
+            .consume("sale-1", "pos-01", &bundle, 3)
+            .await
+            .unwrap();
+        assert_eq!(moved.len(), 2);
+        let stock = |id: String| {
+            let db = db.clone();
+            async move { db.products().get_by_id(&id).await.unwrap().unwrap().current_stock }
+        };
+        let before = |p: &Product| p.current_stock.unwrap();
+        assert_eq!(stock(wine.id.clone()).await, Some(before(wine) - 3));
+        assert_eq!(stock(choc.id.clone()).await, Some(before(choc) - 6));
+        assert_eq!(stock(basket.id.clone()).await, basket.current_stock);
+
+        let deltas: i64 = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "n!: i64" FROM inventory_deltas WHERE reference_id = 'sale-1' AND delta_type = 'bundle_sale'"#
+        )
+        .fetch_one(db.pool())
+        .await
+        .unwrap();
+        assert_eq!(deltas, 2);
+
+        // Sync applies newer bills of materials only
+        let pending = db.sync_outbox().get_pending(10).await.unwrap();
+        assert!(pending.iter().any(|e| e.entity_type == "PRODUCT_BUNDLE"));
+        let mut incoming = bundle.clone();
+        incoming.sync_version = 5;
+        incoming.components.truncate(1);
+        assert!(db.bundles().upsert_from_sync(&incoming).await.unwrap());
+        assert!(!db.bundles().upsert_from_sync(&incoming).await.unwrap());
+        assert_eq!(db.bundles().get(&basket.id).await.unwrap().components.len(), 1);
+        assert_eq!(db.bundles().list_after("", 10).await.unwrap().len(), 1);
+    }
+}
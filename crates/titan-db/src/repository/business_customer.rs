@@ -0,0 +1,431 @@
+//! # Business Customer Repository
+//!
+//! Database operations for business customers: buyers with a tax
+//! registration number that receive e-invoices.
+//!
+//! Deleted customers (see `titan_core::tombstone`) are kept so their past
+//! invoices still resolve, but drop out of search.
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::debug;
+
+use crate::error::{DbError, DbResult};
+use crate::pii::{PiiCipher, PiiField};
+use crate::repository::sync::SyncOutboxRepository;
+use titan_core::{BusinessCustomer, Page, PageRequest, Tombstone, TombstoneAction};
+
+/// Repository for business customer database operations.
+#[derive(Debug, Clone)]
+pub struct BusinessCustomerRepository {
+    pool: SqlitePool,
+    pii: PiiCipher,
+}
+
+impl BusinessCustomerRepository {
+    /// Creates a new BusinessCustomerRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        BusinessCustomerRepository {
+            pool,
+            pii: PiiCipher::disabled(),
+        }
+    }
+
+    /// Encrypts customer emails with `pii` (see [`crate::pii`]).
+    pub fn with_pii(mut self, pii: PiiCipher) -> Self {
+        self.pii = pii;
+        self
+    }
+
+    /// Decrypts a customer's email.
+    fn reveal(&self, mut customer: BusinessCustomer) -> DbResult<BusinessCustomer> {
+        customer.email = self.pii.decrypt_opt(PiiField::Email, customer.email)?;
+        Ok(customer)
+    }
+
+    /// Inserts a customer, or updates its details if the ID exists and is
+    /// still at `customer.sync_version` (see
+    /// [optimistic locking](crate::repository)).
+    ///
+    /// Deletion is left alone: see [`soft_delete`](Self::soft_delete) and
+    /// [`restore`](Self::restore).
+    ///
+    /// ## Returns
+    /// The customer's version after the save.
+    ///
+    /// ## Errors
+    /// - `DbError::UniqueViolation` if another customer of the tenant has
+    ///   the same tax ID
+    /// - `DbError::VersionConflict` if the customer changed since it was read
+    pub async fn save(&self, customer: &BusinessCustomer) -> DbResult<i64> {
+        debug!(customer_id = %customer.id, tax_id = %customer.tax_id, "Saving business customer");
+
+        let email = customer.email.as_deref();
+        let email_enc = self.pii.encrypt_opt(PiiField::Email, email)?;
+        let email_hash = self.pii.blind_index_opt(PiiField::Email, email);
+
+        let version: Option<i64> = sqlx::query_scalar!(
+            r#"
+            INSERT INTO business_customers (
+                id, tenant_id, name, tax_id,
+                address_line, city, postal_code, country_code, email, email_hash,
+                created_at, updated_at, sync_version
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+            ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                tax_id = excluded.tax_id,
+                address_line = excluded.address_line,
+                city = excluded.city,
+                postal_code = excluded.postal_code,
+                country_code = excluded.country_code,
+                email = excluded.email,
+                email_hash = excluded.email_hash,
+                updated_at = excluded.updated_at,
+                sync_version = business_customers.sync_version + 1
+            WHERE business_customers.sync_version = excluded.sync_version
+            RETURNING sync_version
+            "#,
+            customer.id,
+            customer.tenant_id,
+            customer.name,
+            customer.tax_id,
+            customer.address_line,
+            customer.city,
+            customer.postal_code,
+            customer.country_code,
+            email_enc,
+            email_hash,
+            customer.created_at,
+            customer.updated_at,
+            customer.sync_version
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match version {
+            Some(version) => Ok(version),
+            None => {
+                let actual = sqlx::query_scalar!(
+                    "SELECT sync_version FROM business_customers WHERE id = ?1",
+                    customer.id
+                )
+                .fetch_one(&self.pool)
+                .await?;
+                Err(DbError::version_conflict(
+                    "BusinessCustomer",
+                    &customer.id,
+                    customer.sync_version,
+                    actual,
+                ))
+            }
+        }
+    }
+
+    /// Gets a customer by ID.
+    pub async fn get_by_id(&self, id: &str) -> DbResult<Option<BusinessCustomer>> {
+        let customer = sqlx::query_as!(
+            BusinessCustomer,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                name,
+                tax_id,
+                address_line,
+                city,
+                postal_code,
+                country_code,
+                email,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                deleted_at as "deleted_at: DateTime<Utc>",
+                sync_version
+            FROM business_customers
+            WHERE id = ?1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        customer.map(|c| self.reveal(c)).transpose()
+    }
+
+    /// Finds customers whose name or tax ID contains `query`, by name.
+    pub async fn search(&self, query: &str, limit: u32) -> DbResult<Vec<BusinessCustomer>> {
+        let customers = sqlx::query_as!(
+            BusinessCustomer,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                name,
+                tax_id,
+                address_line,
+                city,
+                postal_code,
+                country_code,
+                email,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                deleted_at as "deleted_at: DateTime<Utc>",
+                sync_version
+            FROM business_customers
+            WHERE (name LIKE '%' || ?1 || '%' OR tax_id LIKE '%' || ?1 || '%')
+            AND deleted_at IS NULL
+            ORDER BY name
+            LIMIT ?2
+            "#,
+            query,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        customers.into_iter().map(|c| self.reveal(c)).collect()
+    }
+
+    /// Finds customers like [`search`](Self::search), one page at a time.
+    ///
+    /// ## Errors
+    /// `DbError::InvalidInput` for a cursor not made by this method.
+    pub async fn search_page(
+        &self,
+        query: &str,
+        page: &PageRequest,
+    ) -> DbResult<Page<BusinessCustomer>> {
+        let after = page.cursor_keys(2)?;
+        let (after_name, after_id) = match &after {
+            Some(keys) => (Some(keys[0].as_str()), Some(keys[1].as_str())),
+            None => (None, None),
+        };
+        let limit = page.fetch_limit();
+
+        debug!(query = %query, limit = %limit, "Searching business customers page");
+
+        let rows = sqlx::query_as!(
+            BusinessCustomer,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                name,
+                tax_id,
+                address_line,
+                city,
+                postal_code,
+                country_code,
+                email,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                deleted_at as "deleted_at: DateTime<Utc>",
+                sync_version
+            FROM business_customers
+            WHERE (name LIKE '%' || ?1 || '%' OR tax_id LIKE '%' || ?1 || '%')
+            AND deleted_at IS NULL
+            AND (?2 IS NULL OR name > ?2 OR (name = ?2 AND id > ?3))
+            ORDER BY name, id
+            LIMIT ?4
+            "#,
+            query,
+            after_name,
+            after_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|c| self.reveal(c))
+        .collect::<DbResult<Vec<_>>>()?;
+
+        let total = if page.include_total {
+            let count: i64 = sqlx::query_scalar!(
+                r#"
+                SELECT COUNT(*) as "count!: i64"
+                FROM business_customers
+                WHERE (name LIKE '%' || ?1 || '%' OR tax_id LIKE '%' || ?1 || '%')
+                AND deleted_at IS NULL
+                "#,
+                query
+            )
+            .fetch_one(&self.pool)
+            .await?;
+            Some(count)
+        } else {
+            None
+        };
+
+        Ok(Page::from_rows(rows, page, |c| vec![c.name.clone(), c.id.clone()]).with_total(total))
+    }
+
+    /// Soft-deletes a customer and queues the tombstone for sync.
+    ///
+    /// ## Errors
+    /// `DbError::NotFound` if the customer doesn't exist or is already
+    /// deleted.
+    pub async fn soft_delete(&self, id: &str) -> DbResult<Tombstone> {
+        self.set_deleted(id, TombstoneAction::Delete).await
+    }
+
+    /// Restores a soft-deleted customer and queues the tombstone for sync.
+    ///
+    /// ## Errors
+    /// `DbError::NotFound` if the customer doesn't exist or isn't deleted.
+    pub async fn restore(&self, id: &str) -> DbResult<Tombstone> {
+        self.set_deleted(id, TombstoneAction::Restore).await
+    }
+
+    async fn set_deleted(&self, id: &str, action: TombstoneAction) -> DbResult<Tombstone> {
+        debug!(customer_id = %id, ?action, "Changing business customer deletion");
+
+        let now = Utc::now();
+        let deleted = action == TombstoneAction::Delete;
+
+        let version: Option<i64> = sqlx::query_scalar!(
+            r#"
+            UPDATE business_customers
+            SET
+                deleted_at = CASE WHEN ?2 THEN ?3 ELSE NULL END,
+                updated_at = ?3,
+                sync_version = sync_version + 1
+            WHERE id = ?1 AND (deleted_at IS NULL) = ?2
+            RETURNING sync_version
+            "#,
+            id,
+            deleted,
+            now
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(version) = version else {
+            return Err(DbError::not_found("BusinessCustomer", id));
+        };
+
+        let tombstone = Tombstone::new("business_customer", id, action, version, now);
+        SyncOutboxRepository::new(self.pool.clone())
+            .queue_tombstone(&tombstone)
+            .await?;
+
+        Ok(tombstone)
+    }
+
+    /// Applies a tombstone received through sync.
+    ///
+    /// Returns `false` if the customer is unknown here or already at or
+    /// past the tombstone's version.
+    pub async fn apply_tombstone(&self, tombstone: &Tombstone) -> DbResult<bool> {
+        let deleted_at = tombstone.deleted_at();
+        let result = sqlx::query!(
+            r#"
+            UPDATE business_customers
+            SET
+                deleted_at = ?2,
+                updated_at = ?3,
+                sync_version = ?4
+            WHERE id = ?1 AND sync_version < ?4
+            "#,
+            tombstone.entity_id,
+            deleted_at,
+            tombstone.at,
+            tombstone.version
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pii::{MasterKey, CIPHERTEXT_PREFIX};
+    use crate::pool::{Database, DbConfig};
+    use chrono::Utc;
+    use titan_core::{StoreCreditAccount, StoreCreditDocument, DEFAULT_TENANT_ID};
+
+    #[tokio::test]
+    async fn test_customer_data_is_encrypted_at_rest() {
+        let plain = Database::new(DbConfig::in_memory()).await.unwrap();
+        let customer = BusinessCustomer {
+            id: "c-1".to_string(),
+            tenant_id: DEFAULT_TENANT_ID.to_string(),
+            name: "Acme".to_string(),
+            tax_id: "GB123456789".to_string(),
+            address_line: None,
+            city: None,
+            postal_code: None,
+            country_code: "GB".to_string(),
+            email: Some("ap@acme.test".to_string()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            deleted_at: None,
+            sync_version: 0,
+        };
+        plain.business_customers().save(&customer).await.unwrap();
+
+        // Opening with a key encrypts what was written in plain text
+        let master = MasterKey::generate().unwrap();
+        let db = plain
+            .with_pii_key(DEFAULT_TENANT_ID, &master)
+            .await
+            .unwrap();
+        let stored: String = sqlx::query_scalar("SELECT email FROM business_customers")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert!(stored.starts_with(CIPHERTEXT_PREFIX));
+        let read = db
+            .business_customers()
+            .get_by_id("c-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(read.email.as_deref(), Some("ap@acme.test"));
+
+        let doc = StoreCreditDocument {
+            account: StoreCreditAccount {
+                id: "sc-1".to_string(),
+                tenant_id: DEFAULT_TENANT_ID.to_string(),
+                credit_number: "SC-1".to_string(),
+                customer_name: "Ada".to_string(),
+                customer_phone: Some("0300 1234567".to_string()),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+                sync_version: 1,
+            },
+            entries: Vec::new(),
+        };
+        db.store_credits().upsert_from_sync(&doc).await.unwrap();
+        let found = db
+            .store_credits()
+            .find_by_phone(" 0300 1234567 ")
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].customer_phone.as_deref(), Some("0300 1234567"));
+
+        // The same key opens again; another one does not
+        let again = db
+            .clone()
+            .with_pii_key(DEFAULT_TENANT_ID, &master)
+            .await
+            .unwrap();
+        assert_eq!(
+            again
+                .store_credits()
+                .find_by_phone("0300 1234567")
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        let other = MasterKey::generate().unwrap();
+        assert!(db.with_pii_key(DEFAULT_TENANT_ID, &other).await.is_err());
+    }
+}
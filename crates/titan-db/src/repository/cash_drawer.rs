@@ -0,0 +1,150 @@
+//! # Cash Drawer Repository
+//!
+//! The audit trail of manual cash drawer opens (see
+//! `titan_core::cash_drawer`).
+//!
+//! ## Audit Trail
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                     Cash Drawer Open Audit                              │
+//! │                                                                         │
+//! │  open_cash_drawer (no sale)                                             │
+//! │       │ dyn CashDrawer::open ──► sent, or the device error              │
+//! │       ▼                                                                 │
+//! │  record(DrawerOpen)       insert only, never updated or deleted         │
+//! │                                                                         │
+//! │  list_between(from, to)   manager report, oldest first                  │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::info;
+
+use crate::error::DbResult;
+use titan_core::DrawerOpen;
+
+/// Repository for the cash drawer open audit trail.
+#[derive(Debug, Clone)]
+pub struct CashDrawerRepository {
+    pool: SqlitePool,
+}
+
+impl CashDrawerRepository {
+    /// Creates a new CashDrawerRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        CashDrawerRepository { pool }
+    }
+
+    /// Adds a manual open to the audit trail.
+    pub async fn record(&self, entry: &DrawerOpen) -> DbResult<()> {
+        info!(
+            id = %entry.id,
+            drawer = %entry.drawer,
+            succeeded = entry.succeeded,
+            user_id = %entry.user_id,
+            "Recording cash drawer open"
+        );
+
+        sqlx::query!(
+            r#"
+            INSERT INTO cash_drawer_opens (
+                id, station_number, drawer, reason, succeeded, error,
+                user_id, device_id, created_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            "#,
+            entry.id,
+            entry.station_number,
+            entry.drawer,
+            entry.reason,
+            entry.succeeded,
+            entry.error,
+            entry.user_id,
+            entry.device_id,
+            entry.created_at
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Lists manual opens in `[from, to)`, oldest first.
+    pub async fn list_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> DbResult<Vec<DrawerOpen>> {
+        let entries = sqlx::query_as!(
+            DrawerOpen,
+            r#"
+            SELECT
+                id as "id!",
+                station_number,
+                drawer,
+                reason,
+                succeeded as "succeeded: bool",
+                error,
+                user_id,
+                device_id,
+                created_at as "created_at: DateTime<Utc>"
+            FROM cash_drawer_opens
+            WHERE created_at >= ?1 AND created_at < ?2
+            ORDER BY created_at, id
+            "#,
+            from,
+            to
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::pool::{Database, DbConfig};
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_cash_drawer_open_audit_trail() {
+        use titan_core::DrawerOpen;
+
+        let db = Database::new(DbConfig::in_memory()).await.unwrap();
+        let now = Utc::now();
+        let opened = DrawerOpen {
+            id: Uuid::new_v4().to_string(),
+            station_number: Some(3),
+            drawer: "printer_kick".to_string(),
+            reason: Some("Change for a customer".to_string()),
+            succeeded: true,
+            error: None,
+            user_id: "default".to_string(),
+            device_id: "pos-01".to_string(),
+            created_at: now,
+        };
+        let failed = DrawerOpen {
+            id: Uuid::new_v4().to_string(),
+            reason: None,
+            succeeded: false,
+            error: Some("printer is offline".to_string()),
+            created_at: now + Duration::seconds(5),
+            ..opened.clone()
+        };
+        db.cash_drawer_opens().record(&opened).await.unwrap();
+        db.cash_drawer_opens().record(&failed).await.unwrap();
+
+        let listed = db
+            .cash_drawer_opens()
+            .list_between(now - Duration::minutes(1), now + Duration::minutes(1))
+            .await
+            .unwrap();
+        assert_eq!(listed, vec![opened, failed]);
+    }
+}
@@ -0,0 +1,440 @@
+//! # Erasure Repository
+//!
+//! Anonymizes a customer's personal data for an erasure request (see
+//! `titan_core::erasure`) and keeps the erasure log.
+//!
+//! Every update is guarded so a row already anonymized is not touched
+//! again: a request that arrives a second time counts only rows that
+//! synced in since, and bumps no versions otherwise. Rows that are
+//! anonymized get a new `sync_version`, so an older copy that still holds
+//! the data cannot overwrite them through sync.
+//!
+//! Phone numbers and emails are matched on their lookup hashes (see
+//! [`crate::pii`]), and the log keeps the subject encrypted.
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::{debug, info};
+
+use crate::error::{DbError, DbResult};
+use crate::payload_crypto::PayloadCipher;
+use crate::pii::{PiiCipher, PiiField};
+use crate::repository::sync::SyncOutboxRepository;
+use titan_core::erasure::ERASED_NAME;
+use titan_core::{ErasureCounts, ErasureOrigin, ErasureRecord, ErasureRequest, ErasureSubject};
+
+/// Repository for customer erasure.
+#[derive(Debug, Clone)]
+pub struct ErasureRepository {
+    pool: SqlitePool,
+    pii: PiiCipher,
+    payload: PayloadCipher,
+}
+
+impl ErasureRepository {
+    /// Creates a new ErasureRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        ErasureRepository {
+            pool,
+            pii: PiiCipher::disabled(),
+            payload: PayloadCipher::disabled(),
+        }
+    }
+
+    /// Matches and logs customer data encrypted with `pii`.
+    pub fn with_pii(mut self, pii: PiiCipher) -> Self {
+        self.pii = pii;
+        self
+    }
+
+    /// Seals the subject of queued requests with `payload`.
+    pub fn with_payload_cipher(mut self, payload: PayloadCipher) -> Self {
+        self.payload = payload;
+        self
+    }
+
+    /// Decrypts a log entry's subject.
+    fn reveal(&self, mut row: ErasureLogRow) -> DbResult<ErasureRecord> {
+        row.phone = self.pii.decrypt_opt(PiiField::Phone, row.phone)?;
+        row.email = self.pii.decrypt_opt(PiiField::Email, row.email)?;
+        Ok(row.into())
+    }
+
+    /// Erases a customer at this device's request and queues the request
+    /// for sync (`CUSTOMER_ERASURE`), so the hub, the other terminals and
+    /// the cloud erase their copies too.
+    pub async fn forget(&self, request: &ErasureRequest) -> DbResult<ErasureRecord> {
+        let record = self.apply(request, ErasureOrigin::Local).await?;
+
+        let payload = serde_json::to_string(request).map_err(|e| {
+            DbError::Internal(format!("Failed to serialize erasure request: {}", e))
+        })?;
+        SyncOutboxRepository::new(self.pool.clone())
+            .with_payload_cipher(self.payload.clone())
+            .queue_for_sync("CUSTOMER_ERASURE", &request.id, &payload)
+            .await?;
+
+        Ok(record)
+    }
+
+    /// Anonymizes the request's subject and records it in the erasure log.
+    ///
+    /// Applying a request again is harmless; its log entry adds up the
+    /// rows each application anonymized.
+    ///
+    /// ## Errors
+    /// `DbError::InvalidInput` if the subject has no identifier.
+    pub async fn apply(
+        &self,
+        request: &ErasureRequest,
+        origin: ErasureOrigin,
+    ) -> DbResult<ErasureRecord> {
+        let subject = request.subject.normalized()?;
+        let now = Utc::now();
+
+        debug!(request_id = %request.id, ?origin, "Applying customer erasure");
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let mut counts = ErasureCounts::default();
+
+        if let Some(customer_id) = &subject.business_customer_id {
+            counts.age_verifications = sqlx::query!(
+                r#"
+                UPDATE sale_age_verifications
+                SET birthdate = NULL
+                WHERE birthdate IS NOT NULL
+                AND sale_id IN (
+                    SELECT sale_id FROM sale_invoice_customers WHERE customer_id = ?1
+                )
+                "#,
+                customer_id
+            )
+            .execute(&mut *tx)
+            .await?
+            .rows_affected() as u32;
+
+            // The tax ID is unique per tenant, so it becomes a placeholder
+            // derived from the ID rather than a shared value.
+            counts.business_customers = sqlx::query!(
+                r#"
+                UPDATE business_customers
+                SET
+                    name = ?2,
+                    tax_id = 'ERASED-' || id,
+                    address_line = NULL,
+                    city = NULL,
+                    postal_code = NULL,
+                    email = NULL,
+                    email_hash = NULL,
+                    deleted_at = COALESCE(deleted_at, ?3),
+                    updated_at = ?3,
+                    sync_version = sync_version + 1
+                WHERE id = ?1 AND tax_id != 'ERASED-' || id
+                "#,
+                customer_id,
+                ERASED_NAME,
+                now
+            )
+            .execute(&mut *tx)
+            .await?
+            .rows_affected() as u32;
+        }
+
+        if let Some(phone) = &subject.phone {
+            let phone_hash = self.pii.blind_index(PiiField::Phone, phone);
+            counts.store_credit_accounts = sqlx::query!(
+                r#"
+                UPDATE store_credit_accounts
+                SET
+                    customer_name = ?2,
+                    customer_phone = NULL,
+                    customer_phone_hash = NULL,
+                    updated_at = ?3,
+                    sync_version = sync_version + 1
+                WHERE customer_phone_hash = ?1
+                "#,
+                phone_hash,
+                ERASED_NAME,
+                now
+            )
+            .execute(&mut *tx)
+            .await?
+            .rows_affected() as u32;
+
+            counts.layaways = sqlx::query!(
+                r#"
+                UPDATE layaways
+                SET
+                    customer_name = ?2,
+                    customer_phone = NULL,
+                    customer_phone_hash = NULL,
+                    notes = NULL,
+                    updated_at = ?3,
+                    sync_version = sync_version + 1
+                WHERE customer_phone_hash = ?1
+                "#,
+                phone_hash,
+                ERASED_NAME,
+                now
+            )
+            .execute(&mut *tx)
+            .await?
+            .rows_affected() as u32;
+        }
+
+        if let Some(email) = &subject.email {
+            let email_hash = self.pii.blind_index(PiiField::Email, email);
+            counts.quotes = sqlx::query!(
+                r#"
+                UPDATE quotes
+                SET
+                    customer_name = NULL,
+                    customer_email = NULL,
+                    customer_email_hash = NULL,
+                    notes = NULL,
+                    updated_at = ?2,
+                    sync_version = sync_version + 1
+                WHERE customer_email_hash = ?1
+                "#,
+                email_hash,
+                now
+            )
+            .execute(&mut *tx)
+            .await?
+            .rows_affected() as u32;
+        }
+
+        let phone_enc = self.pii.encrypt_opt(PiiField::Phone, subject.phone.as_deref())?;
+        let email_enc = self.pii.encrypt_opt(PiiField::Email, subject.email.as_deref())?;
+        sqlx::query!(
+            r#"
+            INSERT INTO erasure_log (
+                request_id, tenant_id,
+                business_customer_id, phone, email,
+                reason, requested_by, requested_at, origin,
+                business_customers, age_verifications, store_credit_accounts,
+                layaways, quotes, erased_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+            ON CONFLICT(request_id) DO UPDATE SET
+                business_customers = business_customers + excluded.business_customers,
+                age_verifications = age_verifications + excluded.age_verifications,
+                store_credit_accounts = store_credit_accounts + excluded.store_credit_accounts,
+                layaways = layaways + excluded.layaways,
+                quotes = quotes + excluded.quotes
+            "#,
+            request.id,
+            request.tenant_id,
+            subject.business_customer_id,
+            phone_enc,
+            email_enc,
+            request.reason,
+            request.requested_by,
+            request.requested_at,
+            origin,
+            counts.business_customers,
+            counts.age_verifications,
+            counts.store_credit_accounts,
+            counts.layaways,
+            counts.quotes,
+            now
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        info!(
+            request_id = %request.id,
+            ?origin,
+            rows = counts.total(),
+            "Customer data erased"
+        );
+
+        self.get(&request.id)
+            .await?
+            .ok_or_else(|| DbError::not_found("Erasure", &request.id))
+    }
+
+    /// Gets the erasure log entry for a request.
+    pub async fn get(&self, request_id: &str) -> DbResult<Option<ErasureRecord>> {
+        let row = sqlx::query_as!(
+            ErasureLogRow,
+            r#"
+            SELECT
+                request_id,
+                tenant_id,
+                business_customer_id,
+                phone,
+                email,
+                reason,
+                requested_by,
+                requested_at as "requested_at: DateTime<Utc>",
+                origin as "origin: ErasureOrigin",
+                business_customers as "business_customers: u32",
+                age_verifications as "age_verifications: u32",
+                store_credit_accounts as "store_credit_accounts: u32",
+                layaways as "layaways: u32",
+                quotes as "quotes: u32",
+                erased_at as "erased_at: DateTime<Utc>"
+            FROM erasure_log
+            WHERE request_id = ?1
+            "#,
+            request_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(|r| self.reveal(r)).transpose()
+    }
+
+    /// Lists erasure log entries, most recent first.
+    pub async fn list(&self, limit: u32) -> DbResult<Vec<ErasureRecord>> {
+        let rows = sqlx::query_as!(
+            ErasureLogRow,
+            r#"
+            SELECT
+                request_id,
+                tenant_id,
+                business_customer_id,
+                phone,
+                email,
+                reason,
+                requested_by,
+                requested_at as "requested_at: DateTime<Utc>",
+                origin as "origin: ErasureOrigin",
+                business_customers as "business_customers: u32",
+                age_verifications as "age_verifications: u32",
+                store_credit_accounts as "store_credit_accounts: u32",
+                layaways as "layaways: u32",
+                quotes as "quotes: u32",
+                erased_at as "erased_at: DateTime<Utc>"
+            FROM erasure_log
+            ORDER BY erased_at DESC, request_id
+            LIMIT ?1
+            "#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(|r| self.reveal(r)).collect()
+    }
+}
+
+/// An `erasure_log` row, flattened.
+struct ErasureLogRow {
+    request_id: String,
+    tenant_id: String,
+    business_customer_id: Option<String>,
+    phone: Option<String>,
+    email: Option<String>,
+    reason: Option<String>,
+    requested_by: String,
+    requested_at: DateTime<Utc>,
+    origin: ErasureOrigin,
+    business_customers: u32,
+    age_verifications: u32,
+    store_credit_accounts: u32,
+    layaways: u32,
+    quotes: u32,
+    erased_at: DateTime<Utc>,
+}
+
+impl From<ErasureLogRow> for ErasureRecord {
+    fn from(row: ErasureLogRow) -> Self {
+        ErasureRecord {
+            request: ErasureRequest {
+                id: row.request_id,
+                tenant_id: row.tenant_id,
+                subject: ErasureSubject {
+                    business_customer_id: row.business_customer_id,
+                    phone: row.phone,
+                    email: row.email,
+                },
+                reason: row.reason,
+                requested_by: row.requested_by,
+                requested_at: row.requested_at,
+            },
+            origin: row.origin,
+            counts: ErasureCounts {
+                business_customers: row.business_customers,
+                age_verifications: row.age_verifications,
+                store_credit_accounts: row.store_credit_accounts,
+                layaways: row.layaways,
+                quotes: row.quotes,
+            },
+            erased_at: row.erased_at,
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::pool::{Database, DbConfig};
+    use chrono::Utc;
+    use titan_core::{
+        BusinessCustomer, ErasureOrigin, ErasureRequest, ErasureSubject, DEFAULT_TENANT_ID,
+    };
+
+    #[tokio::test]
+    async fn test_erasure_is_applied_once() {
+        let db = Database::new(DbConfig::in_memory()).await.unwrap();
+        let customer = BusinessCustomer {
+            id: "c-1".to_string(),
+            tenant_id: DEFAULT_TENANT_ID.to_string(),
+            name: "Acme".to_string(),
+            tax_id: "GB123456789".to_string(),
+            address_line: Some("1 High St".to_string()),
+            city: None,
+            postal_code: None,
+            country_code: "GB".to_string(),
+            email: Some("ap@acme.test".to_string()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            deleted_at: None,
+            sync_version: 0,
+        };
+        db.business_customers().save(&customer).await.unwrap();
+
+        let subject = ErasureSubject {
+            business_customer_id: Some("c-1".to_string()),
+            ..Default::default()
+        };
+        let request = ErasureRequest::new(
+            "er-1".to_string(),
+            DEFAULT_TENANT_ID,
+            &subject,
+            None,
+            "u-1",
+            Utc::now(),
+        )
+        .unwrap();
+
+        let record = db.erasures().forget(&request).await.unwrap();
+        assert_eq!(record.counts.business_customers, 1);
+        assert_eq!(record.origin, ErasureOrigin::Local);
+        let erased = db.business_customers().get_by_id("c-1").await.unwrap().unwrap();
+        assert_eq!(erased.name, titan_core::erasure::ERASED_NAME);
+        assert!(erased.email.is_none() && erased.address_line.is_none());
+        assert!(erased.deleted_at.is_some());
+        assert_eq!(erased.sync_version, 1);
+
+        // The same request coming back through sync changes nothing
+        let again = db.erasures().apply(&request, ErasureOrigin::Sync).await.unwrap();
+        assert_eq!(again.counts.total(), 1);
+        let unchanged = db.business_customers().get_by_id("c-1").await.unwrap().unwrap();
+        assert_eq!(unchanged.sync_version, 1);
+        assert_eq!(db.erasures().list(10).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,81 @@
+//! # Feature Flag Repository
+//!
+//! The local cache of the feature flags the cloud reported for this store
+//! (see `titan_core::feature`). Flag checks read the cache only, so they
+//! keep working while the cloud is out of reach.
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::debug;
+
+use crate::error::{DbError, DbResult};
+use titan_core::FeatureFlags;
+
+/// Repository for the feature flag cache.
+#[derive(Debug, Clone)]
+pub struct FeatureFlagRepository {
+    pool: SqlitePool,
+}
+
+impl FeatureFlagRepository {
+    /// Creates a new FeatureFlagRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        FeatureFlagRepository { pool }
+    }
+
+    /// Loads the cached flags; never-fetched (every feature off) if the
+    /// cache is empty.
+    pub async fn load(&self) -> DbResult<FeatureFlags> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                flags,
+                ttl_secs,
+                fetched_at as "fetched_at: DateTime<Utc>"
+            FROM feature_flag_cache
+            WHERE id = 1
+            "#
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(FeatureFlags::default());
+        };
+        let flags = serde_json::from_str(&row.flags)
+            .map_err(|e| DbError::Internal(format!("Corrupt feature flag cache: {}", e)))?;
+
+        Ok(FeatureFlags {
+            flags,
+            fetched_at: Some(row.fetched_at),
+            ttl_secs: u32::try_from(row.ttl_secs).unwrap_or(0),
+        })
+    }
+
+    /// Replaces the cache with freshly fetched flags.
+    pub async fn save(&self, flags: &FeatureFlags) -> DbResult<()> {
+        let json = serde_json::to_string(&flags.flags)
+            .map_err(|e| DbError::Internal(format!("Failed to serialize feature flags: {}", e)))?;
+        let ttl_secs = i64::from(flags.ttl_secs);
+        let fetched_at = flags.fetched_at.unwrap_or_else(Utc::now);
+
+        sqlx::query!(
+            r#"
+            INSERT INTO feature_flag_cache (id, flags, ttl_secs, fetched_at)
+            VALUES (1, ?1, ?2, ?3)
+            ON CONFLICT(id) DO UPDATE SET
+                flags = excluded.flags,
+                ttl_secs = excluded.ttl_secs,
+                fetched_at = excluded.fetched_at
+            "#,
+            json,
+            ttl_secs,
+            fetched_at
+        )
+        .execute(&self.pool)
+        .await?;
+
+        debug!(flags = flags.flags.len(), ttl_secs, "Feature flag cache updated");
+        Ok(())
+    }
+}
@@ -0,0 +1,288 @@
+//! # Hub Upload Queue Repository
+//!
+//! Cloud-bound outbox entries the hub accepted from terminals, kept until
+//! they are uploaded. The terminals keep their own copies until the hub
+//! relays the cloud's ack, so a hub lost with a full queue loses nothing.
+//!
+//! ## Queue
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Hub Upload Queue                                │
+//! │                                                                         │
+//! │  accept(entry)                                                          │
+//! │       ├── new, or payload changed ──► queued           ──► false        │
+//! │       ├── already queued          ──► left as it is    ──► false        │
+//! │       └── already uploaded        ──► left as it is    ──► true         │
+//! │                                                                         │
+//! │  pending(limit) ──► upload ──► mark_uploaded / mark_failed              │
+//! │  prune_uploaded(before) ──► uploaded rows deleted                       │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::debug;
+
+use crate::error::DbResult;
+
+/// An entry in the hub's upload queue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HubUpload {
+    /// Terminal that sent the entry.
+    pub device_id: String,
+    /// The entry's ID in that terminal's outbox.
+    pub entry_id: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub payload: String,
+    pub schema_version: i64,
+    pub correlation_id: Option<String>,
+    /// W3C `traceparent` the terminal sent with the entry.
+    pub trace_context: Option<String>,
+    pub received_at: DateTime<Utc>,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+}
+
+/// Repository for the hub's upload queue.
+#[derive(Debug, Clone)]
+pub struct HubUploadQueueRepository {
+    pool: SqlitePool,
+}
+
+impl HubUploadQueueRepository {
+    /// Creates a new HubUploadQueueRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        HubUploadQueueRepository { pool }
+    }
+
+    /// Queues an entry received from a terminal.
+    ///
+    /// A terminal sends an entry again until the cloud's ack reaches it,
+    /// so the entry may be here already; it is only queued again if its
+    /// payload changed. Returns whether the entry, as sent, is already in
+    /// the cloud.
+    pub async fn accept(&self, upload: &HubUpload) -> DbResult<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO hub_upload_queue (
+                device_id, entry_id, entity_type, entity_id, payload,
+                schema_version, correlation_id, trace_context, received_at, attempts
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, 0)
+            ON CONFLICT(device_id, entry_id) DO UPDATE SET
+                entity_type = excluded.entity_type,
+                entity_id = excluded.entity_id,
+                payload = excluded.payload,
+                schema_version = excluded.schema_version,
+                correlation_id = excluded.correlation_id,
+                trace_context = excluded.trace_context,
+                received_at = excluded.received_at,
+                uploaded_at = NULL,
+                attempts = 0,
+                last_error = NULL
+            WHERE hub_upload_queue.payload != excluded.payload
+            "#,
+            upload.device_id,
+            upload.entry_id,
+            upload.entity_type,
+            upload.entity_id,
+            upload.payload,
+            upload.schema_version,
+            upload.correlation_id,
+            upload.trace_context,
+            upload.received_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let uploaded = sqlx::query_scalar!(
+            r#"
+            SELECT uploaded_at IS NOT NULL as "uploaded!: bool"
+            FROM hub_upload_queue
+            WHERE device_id = ?1 AND entry_id = ?2
+            "#,
+            upload.device_id,
+            upload.entry_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        debug!(
+            device_id = %upload.device_id,
+            entry_id = %upload.entry_id,
+            uploaded,
+            "Accepted entry for upload"
+        );
+        Ok(uploaded)
+    }
+
+    /// Entries waiting for the cloud: fewest failed attempts first, so an
+    /// entry the cloud keeps refusing does not hold up the rest, then
+    /// oldest first.
+    pub async fn pending(&self, limit: u32) -> DbResult<Vec<HubUpload>> {
+        let uploads = sqlx::query_as!(
+            HubUpload,
+            r#"
+            SELECT
+                device_id,
+                entry_id,
+                entity_type,
+                entity_id,
+                payload,
+                schema_version,
+                correlation_id,
+                trace_context,
+                received_at as "received_at: DateTime<Utc>",
+                attempts,
+                last_error
+            FROM hub_upload_queue
+            WHERE uploaded_at IS NULL
+            ORDER BY attempts ASC, received_at ASC
+            LIMIT ?1
+            "#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(uploads)
+    }
+
+    /// Marks an entry as uploaded, unless it was queued again with a new
+    /// payload since it was read. Returns whether it was marked.
+    pub async fn mark_uploaded(&self, upload: &HubUpload) -> DbResult<bool> {
+        let now = Utc::now();
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE hub_upload_queue SET
+                uploaded_at = ?4,
+                last_error = NULL
+            WHERE device_id = ?1 AND entry_id = ?2 AND payload = ?3
+            "#,
+            upload.device_id,
+            upload.entry_id,
+            upload.payload,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Records a failed upload; the entry is tried again on the next run.
+    pub async fn mark_failed(&self, device_id: &str, entry_id: &str, error: &str) -> DbResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE hub_upload_queue SET
+                attempts = attempts + 1,
+                last_error = ?3
+            WHERE device_id = ?1 AND entry_id = ?2
+            "#,
+            device_id,
+            entry_id,
+            error
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Counts entries waiting for the cloud.
+    pub async fn count_pending(&self) -> DbResult<i64> {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64" FROM hub_upload_queue WHERE uploaded_at IS NULL"#
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Deletes entries uploaded before `before`. A terminal that sends one
+    /// of them again afterwards has it uploaded a second time.
+    ///
+    /// ## Returns
+    /// Number of deleted entries.
+    pub async fn prune_uploaded(&self, before: DateTime<Utc>) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM hub_upload_queue
+            WHERE uploaded_at IS NOT NULL AND uploaded_at < ?1
+            "#,
+            before
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pool::{Database, DbConfig};
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_hub_upload_queue_dedups_resends() {
+        let db = Database::new(DbConfig::in_memory()).await.unwrap();
+        let queue = db.hub_upload_queue();
+        let upload = HubUpload {
+            device_id: "pos-02".to_string(),
+            entry_id: Uuid::new_v4().to_string(),
+            entity_type: "SUPPLIER".to_string(),
+            entity_id: "sup-1".to_string(),
+            payload: r#"{"v":1}"#.to_string(),
+            schema_version: 1,
+            correlation_id: None,
+            trace_context: Some(
+                "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_string(),
+            ),
+            received_at: Utc::now(),
+            attempts: 0,
+            last_error: None,
+        };
+
+        assert!(!queue.accept(&upload).await.unwrap());
+        assert!(!queue.accept(&upload).await.unwrap());
+        assert_eq!(queue.pending(10).await.unwrap(), vec![upload.clone()]);
+
+        assert!(queue.mark_uploaded(&upload).await.unwrap());
+        assert!(queue.accept(&upload).await.unwrap());
+        assert_eq!(queue.count_pending().await.unwrap(), 0);
+
+        // A changed payload goes up again; the old one cannot mark it
+        let changed = HubUpload {
+            payload: r#"{"v":2}"#.to_string(),
+            ..upload.clone()
+        };
+        assert!(!queue.accept(&changed).await.unwrap());
+        assert!(!queue.mark_uploaded(&upload).await.unwrap());
+        queue
+            .mark_failed(&changed.device_id, &changed.entry_id, "offline")
+            .await
+            .unwrap();
+        let retry = queue.pending(10).await.unwrap();
+        assert_eq!(
+            (retry[0].attempts, retry[0].last_error.as_deref()),
+            (1, Some("offline"))
+        );
+
+        assert!(queue.mark_uploaded(&changed).await.unwrap());
+        let later = Utc::now() + Duration::minutes(1);
+        assert_eq!(queue.prune_uploaded(later).await.unwrap(), 1);
+    }
+}
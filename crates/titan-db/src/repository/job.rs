@@ -0,0 +1,384 @@
+//! # Job Repository
+//!
+//! Database operations for scheduled background jobs and their run history.
+//!
+//! ## Run Lifecycle
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                          Job Run Lifecycle                              │
+//! │                                                                         │
+//! │  register(name, schedule, enabled)  once per app start; keeps edits    │
+//! │       │                                                                 │
+//! │       ▼                                                                 │
+//! │  due(now) ── enabled AND next_run_at <= now                             │
+//! │       │                                                                 │
+//! │       ▼                                                                 │
+//! │  start_run ── INSERT job_runs ('running'), last_run_at = now           │
+//! │       │                                                                 │
+//! │       ▼                                                                 │
+//! │  finish_run ── job_runs: 'succeeded' | 'failed', error, duration_ms    │
+//! │                scheduled_jobs: next_run_at, consecutive_failures       │
+//! │                (reset on success, +1 on failure)                       │
+//! │                                                                         │
+//! │  Runs still 'running' at startup were cut off by an exit and are       │
+//! │  closed as failed by fail_interrupted_runs.                            │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::debug;
+
+use crate::error::{DbError, DbResult};
+use titan_core::{JobRun, JobRunStatus, ScheduledJob};
+
+/// Error recorded for runs cut off by the app exiting.
+const INTERRUPTED_ERROR: &str = "Interrupted: the app exited during the run";
+
+/// Repository for scheduled job database operations.
+#[derive(Debug, Clone)]
+pub struct JobRepository {
+    pool: SqlitePool,
+}
+
+impl JobRepository {
+    /// Creates a new JobRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        JobRepository { pool }
+    }
+
+    /// Adds a job if it is not registered yet, enabled or not.
+    ///
+    /// An existing job keeps its stored schedule and enabled flag, so
+    /// edits made on the terminal survive restarts.
+    pub async fn register(&self, name: &str, default_schedule: &str, enabled: bool) -> DbResult<()> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO scheduled_jobs (name, schedule, enabled, updated_at)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT(name) DO NOTHING
+            "#,
+            name,
+            default_schedule,
+            enabled,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Lists all registered jobs by name.
+    pub async fn list(&self) -> DbResult<Vec<ScheduledJob>> {
+        let jobs = sqlx::query_as!(
+            ScheduledJob,
+            r#"
+            SELECT
+                name as "name!",
+                schedule,
+                enabled as "enabled: bool",
+                last_run_at as "last_run_at: DateTime<Utc>",
+                last_status as "last_status: JobRunStatus",
+                next_run_at as "next_run_at: DateTime<Utc>",
+                consecutive_failures,
+                updated_at as "updated_at: DateTime<Utc>"
+            FROM scheduled_jobs
+            ORDER BY name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(jobs)
+    }
+
+    /// Gets a job by name.
+    pub async fn get(&self, name: &str) -> DbResult<Option<ScheduledJob>> {
+        let job = sqlx::query_as!(
+            ScheduledJob,
+            r#"
+            SELECT
+                name as "name!",
+                schedule,
+                enabled as "enabled: bool",
+                last_run_at as "last_run_at: DateTime<Utc>",
+                last_status as "last_status: JobRunStatus",
+                next_run_at as "next_run_at: DateTime<Utc>",
+                consecutive_failures,
+                updated_at as "updated_at: DateTime<Utc>"
+            FROM scheduled_jobs
+            WHERE name = ?1
+            "#,
+            name
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    /// Enabled jobs whose next run is at or before `now`.
+    pub async fn due(&self, now: DateTime<Utc>) -> DbResult<Vec<ScheduledJob>> {
+        let jobs = sqlx::query_as!(
+            ScheduledJob,
+            r#"
+            SELECT
+                name as "name!",
+                schedule,
+                enabled as "enabled: bool",
+                last_run_at as "last_run_at: DateTime<Utc>",
+                last_status as "last_status: JobRunStatus",
+                next_run_at as "next_run_at: DateTime<Utc>",
+                consecutive_failures,
+                updated_at as "updated_at: DateTime<Utc>"
+            FROM scheduled_jobs
+            WHERE enabled = 1 AND next_run_at IS NOT NULL AND next_run_at <= ?1
+            ORDER BY next_run_at
+            "#,
+            now
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(jobs)
+    }
+
+    /// Changes a job's schedule and enabled flag.
+    ///
+    /// `next_run_at` is computed by the caller from the new schedule.
+    ///
+    /// ## Errors
+    /// Returns `DbError::NotFound` if no such job is registered.
+    pub async fn update_schedule(
+        &self,
+        name: &str,
+        schedule: &str,
+        enabled: bool,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> DbResult<()> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            r#"
+            UPDATE scheduled_jobs SET
+                schedule = ?2,
+                enabled = ?3,
+                next_run_at = ?4,
+                updated_at = ?5
+            WHERE name = ?1
+            "#,
+            name,
+            schedule,
+            enabled,
+            next_run_at,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DbError::NotFound {
+                entity: "Job".to_string(),
+                id: name.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Sets when a job will next run.
+    pub async fn set_next_run(
+        &self,
+        name: &str,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> DbResult<()> {
+        sqlx::query!(
+            "UPDATE scheduled_jobs SET next_run_at = ?2 WHERE name = ?1",
+            name,
+            next_run_at
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Records the start of a run and returns its ID.
+    pub async fn start_run(&self, name: &str, started_at: DateTime<Utc>) -> DbResult<i64> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let run_id = sqlx::query!(
+            "INSERT INTO job_runs (job_name, status, started_at) VALUES (?1, 'running', ?2)",
+            name,
+            started_at
+        )
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+
+        sqlx::query!(
+            r#"
+            UPDATE scheduled_jobs SET
+                last_run_at = ?2,
+                last_status = 'running'
+            WHERE name = ?1
+            "#,
+            name,
+            started_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        debug!(job = %name, run_id, "Job run started");
+        Ok(run_id)
+    }
+
+    /// Records the end of a run and schedules the next one.
+    ///
+    /// `error` is `None` for a successful run.
+    ///
+    /// ## Returns
+    /// The job's consecutive failures after this run (0 on success).
+    pub async fn finish_run(
+        &self,
+        run_id: i64,
+        name: &str,
+        started_at: DateTime<Utc>,
+        error: Option<&str>,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> DbResult<i64> {
+        let finished_at = Utc::now();
+        let duration_ms = (finished_at - started_at).num_milliseconds().max(0);
+        let status = if error.is_some() {
+            JobRunStatus::Failed
+        } else {
+            JobRunStatus::Succeeded
+        };
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        sqlx::query!(
+            r#"
+            UPDATE job_runs SET
+                status = ?2,
+                error = ?3,
+                finished_at = ?4,
+                duration_ms = ?5
+            WHERE id = ?1
+            "#,
+            run_id,
+            status,
+            error,
+            finished_at,
+            duration_ms
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let failures: i64 = sqlx::query_scalar!(
+            r#"
+            UPDATE scheduled_jobs SET
+                last_status = ?2,
+                next_run_at = ?3,
+                consecutive_failures = CASE
+                    WHEN ?2 = 'failed' THEN consecutive_failures + 1
+                    ELSE 0
+                END
+            WHERE name = ?1
+            RETURNING consecutive_failures
+            "#,
+            name,
+            status,
+            next_run_at
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        debug!(job = %name, run_id, ?status, duration_ms, "Job run finished");
+        Ok(failures)
+    }
+
+    /// Closes runs left 'running' by an earlier exit as failed.
+    ///
+    /// Call before the scheduler starts; returns the number of runs closed.
+    pub async fn fail_interrupted_runs(&self) -> DbResult<u64> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            r#"
+            UPDATE job_runs SET
+                status = 'failed',
+                error = ?1,
+                finished_at = ?2
+            WHERE status = 'running'
+            "#,
+            INTERRUPTED_ERROR,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE scheduled_jobs SET last_status = 'failed' WHERE last_status = 'running'"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Lists recent runs, newest first, optionally for one job.
+    pub async fn recent_runs(&self, name: Option<&str>, limit: u32) -> DbResult<Vec<JobRun>> {
+        let runs = sqlx::query_as!(
+            JobRun,
+            r#"
+            SELECT
+                id as "id!",
+                job_name,
+                status as "status: JobRunStatus",
+                error,
+                started_at as "started_at: DateTime<Utc>",
+                finished_at as "finished_at: DateTime<Utc>",
+                duration_ms
+            FROM job_runs
+            WHERE ?1 IS NULL OR job_name = ?1
+            ORDER BY started_at DESC, id DESC
+            LIMIT ?2
+            "#,
+            name,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(runs)
+    }
+
+    /// Deletes runs that started more than `days_old` days ago.
+    pub async fn prune_runs(&self, days_old: u32) -> DbResult<u64> {
+        let cutoff = Utc::now() - chrono::Duration::days(days_old as i64);
+        let result = sqlx::query!(
+            "DELETE FROM job_runs WHERE status != 'running' AND started_at < ?1",
+            cutoff
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,289 @@
+//! # Label Repository
+//!
+//! Database operations for the shelf label queue and label templates (see
+//! `titan_core::label`).
+//!
+//! ## Queueing
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                          Label Queue Upsert                             │
+//! │                                                                         │
+//! │  enqueue(product, reason, old price, price, copies)                     │
+//! │       │                                                                 │
+//! │       ├── no pending row ──► INSERT 'pending'                           │
+//! │       │                                                                 │
+//! │       └── pending row ──► price_cents = new price                       │
+//! │                           old_price_cents kept (first change wins)      │
+//! │                           manual: copies added; price change: kept      │
+//! │                                                                         │
+//! │  mark_printed(ids) ──► 'printed', printed_at; the next change queues    │
+//! │                        a fresh row                                      │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::error::DbResult;
+use titan_core::label::{validate_template, MAX_LABEL_COPIES};
+use titan_core::{LabelFormat, LabelReason, LabelStatus, LabelTemplate, QueuedLabel};
+
+/// Repository for label queue and template database operations.
+#[derive(Debug, Clone)]
+pub struct LabelRepository {
+    pool: SqlitePool,
+}
+
+impl LabelRepository {
+    /// Creates a new LabelRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        LabelRepository { pool }
+    }
+
+    // =========================================================================
+    // Queue
+    // =========================================================================
+
+    /// Queues labels for a product, merging with its pending row if it has
+    /// one (see the module docs).
+    ///
+    /// `copies` is clamped to `1..=MAX_LABEL_COPIES`.
+    pub async fn enqueue(
+        &self,
+        product_id: &str,
+        reason: LabelReason,
+        old_price_cents: Option<i64>,
+        price_cents: i64,
+        copies: i64,
+    ) -> DbResult<QueuedLabel> {
+        debug!(product_id = %product_id, ?reason, price_cents, "Queueing shelf label");
+
+        let id = Uuid::new_v4().to_string();
+        let copies = copies.clamp(1, MAX_LABEL_COPIES);
+        let now = Utc::now();
+
+        let label = sqlx::query_as!(
+            QueuedLabel,
+            r#"
+            INSERT INTO label_queue (
+                id, product_id, reason, status, old_price_cents, price_cents, copies,
+                created_at, updated_at
+            ) VALUES (?1, ?2, ?3, 'pending', ?4, ?5, ?6, ?7, ?7)
+            ON CONFLICT(product_id) WHERE status = 'pending' DO UPDATE SET
+                price_cents = excluded.price_cents,
+                old_price_cents = COALESCE(label_queue.old_price_cents, excluded.old_price_cents),
+                copies = CASE
+                    WHEN excluded.reason = 'manual'
+                    THEN MIN(label_queue.copies + excluded.copies, ?8)
+                    ELSE label_queue.copies
+                END,
+                updated_at = excluded.updated_at
+            RETURNING
+                id as "id!",
+                product_id,
+                reason as "reason: LabelReason",
+                status as "status: LabelStatus",
+                old_price_cents,
+                price_cents,
+                copies,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                printed_at as "printed_at: DateTime<Utc>"
+            "#,
+            id,
+            product_id,
+            reason,
+            old_price_cents,
+            price_cents,
+            copies,
+            now,
+            MAX_LABEL_COPIES
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(label)
+    }
+
+    /// Lists pending labels, oldest first.
+    pub async fn list_pending(&self, limit: u32) -> DbResult<Vec<QueuedLabel>> {
+        let labels = sqlx::query_as!(
+            QueuedLabel,
+            r#"
+            SELECT
+                id as "id!",
+                product_id,
+                reason as "reason: LabelReason",
+                status as "status: LabelStatus",
+                old_price_cents,
+                price_cents,
+                copies,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                printed_at as "printed_at: DateTime<Utc>"
+            FROM label_queue
+            WHERE status = 'pending'
+            ORDER BY created_at, id
+            LIMIT ?1
+            "#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(labels)
+    }
+
+    /// Marks pending labels as printed.
+    ///
+    /// ## Returns
+    /// The number of labels marked (already printed or unknown IDs are
+    /// skipped).
+    pub async fn mark_printed(&self, ids: &[String]) -> DbResult<u64> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+        let mut marked = 0;
+
+        for id in ids {
+            marked += sqlx::query!(
+                r#"
+                UPDATE label_queue
+                SET status = 'printed', printed_at = ?2, updated_at = ?2
+                WHERE id = ?1 AND status = 'pending'
+                "#,
+                id,
+                now
+            )
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+
+        tx.commit().await?;
+        debug!(marked, "Marked shelf labels printed");
+        Ok(marked)
+    }
+
+    // =========================================================================
+    // Templates
+    // =========================================================================
+
+    /// Lists saved templates, the default first, then by name.
+    pub async fn list_templates(&self) -> DbResult<Vec<LabelTemplate>> {
+        let templates = sqlx::query_as!(
+            LabelTemplate,
+            r#"
+            SELECT
+                id as "id!",
+                name,
+                format as "format: LabelFormat",
+                body,
+                is_default as "is_default: bool",
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>"
+            FROM label_templates
+            ORDER BY is_default DESC, name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(templates)
+    }
+
+    /// Gets a template by ID.
+    pub async fn get_template(&self, id: &str) -> DbResult<Option<LabelTemplate>> {
+        let template = sqlx::query_as!(
+            LabelTemplate,
+            r#"
+            SELECT
+                id as "id!",
+                name,
+                format as "format: LabelFormat",
+                body,
+                is_default as "is_default: bool",
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>"
+            FROM label_templates
+            WHERE id = ?1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(template)
+    }
+
+    /// Gets the default template, if one is saved.
+    pub async fn default_template(&self) -> DbResult<Option<LabelTemplate>> {
+        let template = sqlx::query_as!(
+            LabelTemplate,
+            r#"
+            SELECT
+                id as "id!",
+                name,
+                format as "format: LabelFormat",
+                body,
+                is_default as "is_default: bool",
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>"
+            FROM label_templates
+            WHERE is_default = 1
+            "#
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(template)
+    }
+
+    /// Inserts or updates a template. Making it the default takes the flag
+    /// from the previous default.
+    ///
+    /// ## Errors
+    /// `DbError::InvalidInput` if the template does not validate (see
+    /// `titan_core::label::validate_template`).
+    pub async fn save_template(&self, template: &LabelTemplate) -> DbResult<()> {
+        validate_template(&template.name, template.format, &template.body)?;
+        debug!(template_id = %template.id, name = %template.name, "Saving label template");
+
+        let mut tx = self.pool.begin().await?;
+
+        if template.is_default {
+            sqlx::query!(
+                "UPDATE label_templates SET is_default = 0 WHERE is_default = 1 AND id != ?1",
+                template.id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO label_templates (id, name, format, body, is_default, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                format = excluded.format,
+                body = excluded.body,
+                is_default = excluded.is_default,
+                updated_at = excluded.updated_at
+            "#,
+            template.id,
+            template.name,
+            template.format,
+            template.body,
+            template.is_default,
+            template.created_at,
+            template.updated_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
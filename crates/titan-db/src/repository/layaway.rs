@@ -0,0 +1,751 @@
+//! # Layaway Repository
+//!
+//! Database operations for layaway orders, their payments, and the stock
+//! they hold in reserve.
+//!
+//! ## Stock Reservation
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                     Layaway Stock Movements                             │
+//! │                                                                         │
+//! │  create(doc)            cancel(id, ...)            complete(id, sale)   │
+//! │       │                      │                           │              │
+//! │       ▼                      ▼                           ▼              │
+//! │  ┌──────────────────┐  ┌──────────────────┐  ┌──────────────────────┐  │
+//! │  │ SINGLE TX        │  │ SINGLE TX        │  │ SINGLE TX            │  │
+//! │  │ header + items   │  │ status=cancelled │  │ status=completed     │  │
+//! │  │ + deposit        │  │ + refund row     │  │ + completed sale     │  │
+//! │  │ stock -= qty     │  │ stock += qty     │  │ + sale items         │  │
+//! │  │ 'layaway_reserve'│  │ 'layaway_release'│  │ (no stock change)    │  │
+//! │  └──────────────────┘  └──────────────────┘  └──────────────────────┘  │
+//! │                                                                         │
+//! │  Every stock change also writes an inventory_deltas row                │
+//! │  (reference_type = 'layaway') so the audit trail shows why stock       │
+//! │  moved, distinct from ordinary sales.                                  │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::{debug, info};
+
+use crate::error::{DbError, DbResult};
+use crate::pii::{PiiCipher, PiiField};
+use crate::repository::stock::{move_stock, REFERENCE_LAYAWAY};
+use titan_core::{
+    Layaway, LayawayDocument, LayawayItem, LayawayPayment, LayawaySettlement, LayawayStatus,
+    PaymentMethod, Sale, SaleItem, SaleStatus,
+};
+
+/// Inventory delta reason when goods are put aside for a layaway.
+pub const DELTA_LAYAWAY_RESERVE: &str = "layaway_reserve";
+
+/// Inventory delta reason when a cancelled layaway's goods go back on sale.
+pub const DELTA_LAYAWAY_RELEASE: &str = "layaway_release";
+
+/// Repository for layaway database operations.
+#[derive(Debug, Clone)]
+pub struct LayawayRepository {
+    pool: SqlitePool,
+    pii: PiiCipher,
+}
+
+impl LayawayRepository {
+    /// Creates a new LayawayRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        LayawayRepository {
+            pool,
+            pii: PiiCipher::disabled(),
+        }
+    }
+
+    /// Encrypts customer phone numbers with `pii` (see [`crate::pii`]).
+    pub fn with_pii(mut self, pii: PiiCipher) -> Self {
+        self.pii = pii;
+        self
+    }
+
+    /// Decrypts a layaway's customer phone.
+    fn reveal(&self, mut layaway: Layaway) -> DbResult<Layaway> {
+        layaway.customer_phone = self.pii.decrypt_opt(PiiField::Phone, layaway.customer_phone)?;
+        Ok(layaway)
+    }
+
+    /// Creates a layaway with its items and deposit, and reserves the stock.
+    ///
+    /// `doc.layaway.paid_cents` must equal the sum of `doc.payments`.
+    pub async fn create(&self, doc: &LayawayDocument) -> DbResult<()> {
+        let layaway = &doc.layaway;
+        debug!(id = %layaway.id, layaway_number = %layaway.layaway_number, "Creating layaway");
+        debug_assert_eq!(
+            layaway.paid_cents,
+            doc.payments.iter().map(|p| p.amount_cents).sum::<i64>()
+        );
+        let phone = layaway.customer_phone.as_deref();
+        let phone_enc = self.pii.encrypt_opt(PiiField::Phone, phone)?;
+        let phone_hash = self.pii.blind_index_opt(PiiField::Phone, phone);
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO layaways (
+                id, tenant_id, layaway_number, status,
+                customer_name, customer_phone, customer_phone_hash,
+                subtotal_cents, tax_cents, total_cents,
+                paid_cents, restocking_fee_cents, sale_id,
+                user_id, device_id, notes,
+                created_at, updated_at, closed_at, sync_version
+            ) VALUES (
+                ?1, ?2, ?3, ?4,
+                ?5, ?6, ?20,
+                ?7, ?8, ?9,
+                ?10, ?11, ?12,
+                ?13, ?14, ?15,
+                ?16, ?17, ?18, ?19
+            )
+            "#,
+            layaway.id,
+            layaway.tenant_id,
+            layaway.layaway_number,
+            layaway.status,
+            layaway.customer_name,
+            phone_enc,
+            layaway.subtotal_cents,
+            layaway.tax_cents,
+            layaway.total_cents,
+            layaway.paid_cents,
+            layaway.restocking_fee_cents,
+            layaway.sale_id,
+            layaway.user_id,
+            layaway.device_id,
+            layaway.notes,
+            layaway.created_at,
+            layaway.updated_at,
+            layaway.closed_at,
+            layaway.sync_version,
+            phone_hash
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for item in &doc.items {
+            insert_item(&mut tx, item).await?;
+            move_stock(
+                &mut tx,
+                REFERENCE_LAYAWAY,
+                &item.product_id,
+                -item.quantity,
+                DELTA_LAYAWAY_RESERVE,
+                &layaway.id,
+                &layaway.device_id,
+            )
+            .await?;
+        }
+
+        for payment in &doc.payments {
+            insert_payment(&mut tx, payment).await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        info!(id = %layaway.id, items = doc.items.len(), "Layaway created, stock reserved");
+
+        Ok(())
+    }
+
+    /// Gets a layaway by ID.
+    pub async fn get_by_id(&self, id: &str) -> DbResult<Option<Layaway>> {
+        let layaway = sqlx::query_as!(
+            Layaway,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                layaway_number,
+                status as "status: LayawayStatus",
+                customer_name,
+                customer_phone,
+                subtotal_cents,
+                tax_cents,
+                total_cents,
+                paid_cents,
+                restocking_fee_cents,
+                sale_id,
+                user_id,
+                device_id,
+                notes,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                closed_at as "closed_at: DateTime<Utc>",
+                sync_version
+            FROM layaways
+            WHERE id = ?1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        layaway.map(|l| self.reveal(l)).transpose()
+    }
+
+    /// Gets a layaway by its printed number.
+    pub async fn get_by_number(&self, layaway_number: &str) -> DbResult<Option<Layaway>> {
+        let layaway = sqlx::query_as!(
+            Layaway,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                layaway_number,
+                status as "status: LayawayStatus",
+                customer_name,
+                customer_phone,
+                subtotal_cents,
+                tax_cents,
+                total_cents,
+                paid_cents,
+                restocking_fee_cents,
+                sale_id,
+                user_id,
+                device_id,
+                notes,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                closed_at as "closed_at: DateTime<Utc>",
+                sync_version
+            FROM layaways
+            WHERE layaway_number = ?1
+            "#,
+            layaway_number
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        layaway.map(|l| self.reveal(l)).transpose()
+    }
+
+    /// Gets all items for a layaway.
+    pub async fn get_items(&self, layaway_id: &str) -> DbResult<Vec<LayawayItem>> {
+        let items = sqlx::query_as!(
+            LayawayItem,
+            r#"
+            SELECT
+                id,
+                layaway_id,
+                product_id,
+                sku_snapshot,
+                name_snapshot,
+                unit_price_cents,
+                quantity,
+                line_total_cents,
+                tax_cents,
+                created_at as "created_at: DateTime<Utc>"
+            FROM layaway_items
+            WHERE layaway_id = ?1
+            ORDER BY created_at, id
+            "#,
+            layaway_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(items)
+    }
+
+    /// Gets all payments (and refunds) for a layaway, oldest first.
+    pub async fn get_payments(&self, layaway_id: &str) -> DbResult<Vec<LayawayPayment>> {
+        let payments = sqlx::query_as!(
+            LayawayPayment,
+            r#"
+            SELECT
+                id,
+                layaway_id,
+                method as "method: PaymentMethod",
+                amount_cents,
+                device_id,
+                created_at as "created_at: DateTime<Utc>"
+            FROM layaway_payments
+            WHERE layaway_id = ?1
+            ORDER BY created_at, id
+            "#,
+            layaway_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(payments)
+    }
+
+    /// Gets a layaway together with its items and payments.
+    pub async fn get_document(&self, id: &str) -> DbResult<Option<LayawayDocument>> {
+        let Some(layaway) = self.get_by_id(id).await? else {
+            return Ok(None);
+        };
+        let items = self.get_items(&layaway.id).await?;
+        let payments = self.get_payments(&layaway.id).await?;
+
+        Ok(Some(LayawayDocument {
+            layaway,
+            items,
+            payments,
+        }))
+    }
+
+    /// Lists layaways, newest first, optionally filtered by status.
+    pub async fn list(&self, status: Option<LayawayStatus>, limit: u32) -> DbResult<Vec<Layaway>> {
+        let layaways = sqlx::query_as!(
+            Layaway,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                layaway_number,
+                status as "status: LayawayStatus",
+                customer_name,
+                customer_phone,
+                subtotal_cents,
+                tax_cents,
+                total_cents,
+                paid_cents,
+                restocking_fee_cents,
+                sale_id,
+                user_id,
+                device_id,
+                notes,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                closed_at as "closed_at: DateTime<Utc>",
+                sync_version
+            FROM layaways
+            WHERE ?1 IS NULL OR status = ?1
+            ORDER BY created_at DESC
+            LIMIT ?2
+            "#,
+            status,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        layaways.into_iter().map(|l| self.reveal(l)).collect()
+    }
+
+    /// Records a follow-up payment against an active layaway.
+    ///
+    /// ## Errors
+    /// `DbError::NotFound` if the layaway is not active or the payment would
+    /// take it past its total (e.g. a concurrent payment on another terminal).
+    pub async fn add_payment(&self, payment: &LayawayPayment) -> DbResult<()> {
+        debug!(layaway_id = %payment.layaway_id, amount = payment.amount_cents, "Adding layaway payment");
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE layaways SET
+                paid_cents = paid_cents + ?2,
+                updated_at = ?3,
+                sync_version = sync_version + 1
+            WHERE id = ?1
+              AND status = 'active'
+              AND paid_cents + ?2 <= total_cents
+            "#,
+            payment.layaway_id,
+            payment.amount_cents,
+            payment.created_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DbError::not_found("Layaway (active)", &payment.layaway_id));
+        }
+
+        insert_payment(&mut tx, payment).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Completes a fully paid layaway at pickup, recording it as a completed
+    /// sale. Stock is not touched: it was reserved when the layaway was
+    /// created.
+    ///
+    /// ## Errors
+    /// `DbError::NotFound` if the layaway is not active or not fully paid.
+    pub async fn complete(&self, layaway_id: &str, sale: &Sale, items: &[SaleItem]) -> DbResult<()> {
+        debug_assert_eq!(sale.status, SaleStatus::Completed);
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE layaways SET
+                status = 'completed',
+                sale_id = ?2,
+                updated_at = ?3,
+                closed_at = ?3,
+                sync_version = sync_version + 1
+            WHERE id = ?1
+              AND status = 'active'
+              AND paid_cents >= total_cents
+            "#,
+            layaway_id,
+            sale.id,
+            sale.completed_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DbError::not_found("Layaway (active, paid)", layaway_id));
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO sales (
+                id, tenant_id, receipt_number, status,
+                subtotal_cents, tax_cents, discount_cents, total_cents,
+                user_id, device_id, notes,
+                created_at, updated_at, completed_at, sync_version
+            ) VALUES (
+                ?1, ?2, ?3, ?4,
+                ?5, ?6, ?7, ?8,
+                ?9, ?10, ?11,
+                ?12, ?13, ?14, ?15
+            )
+            "#,
+            sale.id,
+            sale.tenant_id,
+            sale.receipt_number,
+            sale.status,
+            sale.subtotal_cents,
+            sale.tax_cents,
+            sale.discount_cents,
+            sale.total_cents,
+            sale.user_id,
+            sale.device_id,
+            sale.notes,
+            sale.created_at,
+            sale.updated_at,
+            sale.completed_at,
+            sale.sync_version
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for item in items {
+            sqlx::query!(
+                r#"
+                INSERT INTO sale_items (
+                    id, sale_id, product_id,
+                    sku_snapshot, name_snapshot, unit_price_cents,
+                    quantity, line_total_cents, tax_cents, discount_cents,
+                    base_price_cents, created_at, tax_rate_bps, unit_cost_cents
+                ) VALUES (
+                    ?1, ?2, ?3,
+                    ?4, ?5, ?6,
+                    ?7, ?8, ?9, ?10,
+                    ?11, ?12, ?13,
+                    (SELECT cost_cents FROM products WHERE id = ?3)
+                )
+                "#,
+                item.id,
+                item.sale_id,
+                item.product_id,
+                item.sku_snapshot,
+                item.name_snapshot,
+                item.unit_price_cents,
+                item.quantity,
+                item.line_total_cents,
+                item.tax_cents,
+                item.discount_cents,
+                item.base_price_cents,
+                item.created_at,
+                item.tax_rate_bps
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        info!(layaway_id = %layaway_id, sale_id = %sale.id, "Layaway picked up");
+
+        Ok(())
+    }
+
+    /// Cancels an active layaway: records the restocking fee and refund, and
+    /// returns the reserved goods to stock.
+    ///
+    /// `refund` is the negative payment row handed back to the customer;
+    /// `None` when the whole amount paid was retained as the fee.
+    ///
+    /// ## Errors
+    /// `DbError::NotFound` if the layaway is not active.
+    pub async fn cancel(
+        &self,
+        layaway_id: &str,
+        settlement: &LayawaySettlement,
+        refund: Option<&LayawayPayment>,
+        device_id: &str,
+    ) -> DbResult<()> {
+        let now = Utc::now();
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE layaways SET
+                status = 'cancelled',
+                restocking_fee_cents = ?2,
+                paid_cents = paid_cents - ?3,
+                updated_at = ?4,
+                closed_at = ?4,
+                sync_version = sync_version + 1
+            WHERE id = ?1 AND status = 'active'
+            "#,
+            layaway_id,
+            settlement.restocking_fee_cents,
+            settlement.refund_cents,
+            now
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DbError::not_found("Layaway (active)", layaway_id));
+        }
+
+        if let Some(refund) = refund {
+            insert_payment(&mut tx, refund).await?;
+        }
+
+        let reserved = sqlx::query!(
+            r#"
+            SELECT product_id, quantity
+            FROM layaway_items
+            WHERE layaway_id = ?1
+            "#,
+            layaway_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for row in reserved {
+            move_stock(
+                &mut tx,
+                REFERENCE_LAYAWAY,
+                &row.product_id,
+                row.quantity,
+                DELTA_LAYAWAY_RELEASE,
+                layaway_id,
+                device_id,
+            )
+            .await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        info!(
+            layaway_id = %layaway_id,
+            fee = settlement.restocking_fee_cents,
+            refund = settlement.refund_cents,
+            "Layaway cancelled, stock released"
+        );
+
+        Ok(())
+    }
+
+    /// Applies a layaway received from another terminal.
+    ///
+    /// Last-writer-wins on `sync_version`, replacing the header, items and
+    /// payments. Stock is not moved here: the originating terminal's
+    /// inventory deltas carry the reservation.
+    ///
+    /// ## Returns
+    /// `true` if the document was applied, `false` if the local copy was
+    /// already at the same or a newer version.
+    pub async fn upsert_from_sync(&self, doc: &LayawayDocument) -> DbResult<bool> {
+        let layaway = &doc.layaway;
+        let phone = layaway.customer_phone.as_deref();
+        let phone_enc = self.pii.encrypt_opt(PiiField::Phone, phone)?;
+        let phone_hash = self.pii.blind_index_opt(PiiField::Phone, phone);
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let current: Option<i64> = sqlx::query_scalar!(
+            r#"SELECT sync_version as "v!: i64" FROM layaways WHERE id = ?1"#,
+            layaway.id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if current.is_some_and(|v| v >= layaway.sync_version) {
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO layaways (
+                id, tenant_id, layaway_number, status,
+                customer_name, customer_phone, customer_phone_hash,
+                subtotal_cents, tax_cents, total_cents,
+                paid_cents, restocking_fee_cents, sale_id,
+                user_id, device_id, notes,
+                created_at, updated_at, closed_at, sync_version
+            ) VALUES (
+                ?1, ?2, ?3, ?4,
+                ?5, ?6, ?20,
+                ?7, ?8, ?9,
+                ?10, ?11, ?12,
+                ?13, ?14, ?15,
+                ?16, ?17, ?18, ?19
+            )
+            ON CONFLICT(id) DO UPDATE SET
+                status = excluded.status,
+                customer_name = excluded.customer_name,
+                customer_phone = excluded.customer_phone,
+                customer_phone_hash = excluded.customer_phone_hash,
+                paid_cents = excluded.paid_cents,
+                restocking_fee_cents = excluded.restocking_fee_cents,
+                sale_id = excluded.sale_id,
+                notes = excluded.notes,
+                updated_at = excluded.updated_at,
+                closed_at = excluded.closed_at,
+                sync_version = excluded.sync_version
+            "#,
+            layaway.id,
+            layaway.tenant_id,
+            layaway.layaway_number,
+            layaway.status,
+            layaway.customer_name,
+            phone_enc,
+            layaway.subtotal_cents,
+            layaway.tax_cents,
+            layaway.total_cents,
+            layaway.paid_cents,
+            layaway.restocking_fee_cents,
+            layaway.sale_id,
+            layaway.user_id,
+            layaway.device_id,
+            layaway.notes,
+            layaway.created_at,
+            layaway.updated_at,
+            layaway.closed_at,
+            layaway.sync_version,
+            phone_hash
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!("DELETE FROM layaway_items WHERE layaway_id = ?1", layaway.id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query!("DELETE FROM layaway_payments WHERE layaway_id = ?1", layaway.id)
+            .execute(&mut *tx)
+            .await?;
+
+        for item in &doc.items {
+            insert_item(&mut tx, item).await?;
+        }
+        for payment in &doc.payments {
+            insert_payment(&mut tx, payment).await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        Ok(true)
+    }
+}
+
+/// Inserts one layaway line inside an open transaction.
+async fn insert_item(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    item: &LayawayItem,
+) -> DbResult<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO layaway_items (
+            id, layaway_id, product_id,
+            sku_snapshot, name_snapshot, unit_price_cents,
+            quantity, line_total_cents, tax_cents, created_at
+        ) VALUES (
+            ?1, ?2, ?3,
+            ?4, ?5, ?6,
+            ?7, ?8, ?9, ?10
+        )
+        "#,
+        item.id,
+        item.layaway_id,
+        item.product_id,
+        item.sku_snapshot,
+        item.name_snapshot,
+        item.unit_price_cents,
+        item.quantity,
+        item.line_total_cents,
+        item.tax_cents,
+        item.created_at
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Inserts one layaway payment (or refund) inside an open transaction.
+async fn insert_payment(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    payment: &LayawayPayment,
+) -> DbResult<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO layaway_payments (
+            id, layaway_id, method, amount_cents, device_id, created_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        "#,
+        payment.id,
+        payment.layaway_id,
+        payment.method,
+        payment.amount_cents,
+        payment.device_id,
+        payment.created_at
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,456 @@
+//! # Loyalty Repository
+//!
+//! Database operations for loyalty accounts and their points ledger.
+//!
+//! ## Ledger Writes
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                       Loyalty Ledger Writes                             │
+//! │                                                                         │
+//! │  add_entries(account, accrual)            earn / reward                 │
+//! │    insert-or-ignore: IDs are derived from the account, so a replay      │
+//! │    or the same reward issued on another terminal is written once        │
+//! │                                                                         │
+//! │  redeem(entry)                            redeem (-)                    │
+//! │  ┌─────────────────────────────────────────┐                            │
+//! │  │ SINGLE TX                               │                            │
+//! │  │ rewards = SUM(reward_cents)             │                            │
+//! │  │ rewards >= amount ? insert : reject     │                            │
+//! │  └─────────────────────────────────────────┘                            │
+//! │                                                                         │
+//! │  upsert_from_sync(doc)   union of entries by id, never rejects          │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::{debug, info, warn};
+
+use crate::error::{DbError, DbResult};
+use titan_core::{LoyaltyAccount, LoyaltyDocument, LoyaltyEntry, LoyaltyEntryKind};
+
+/// Outcome of a checked reward redemption.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoyaltyRedemption {
+    /// Whether the entry is on the ledger (also `true` for a replay of an
+    /// entry that was already applied).
+    pub applied: bool,
+    /// Rewards balance after the redemption, or the current balance if
+    /// rejected.
+    pub balance_cents: i64,
+}
+
+/// Repository for loyalty database operations.
+#[derive(Debug, Clone)]
+pub struct LoyaltyRepository {
+    pool: SqlitePool,
+}
+
+impl LoyaltyRepository {
+    /// Creates a new LoyaltyRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        LoyaltyRepository { pool }
+    }
+
+    /// Gets an account by ID.
+    pub async fn get_by_id(&self, id: &str) -> DbResult<Option<LoyaltyAccount>> {
+        let account = sqlx::query_as!(
+            LoyaltyAccount,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                member_number,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                sync_version
+            FROM loyalty_accounts
+            WHERE id = ?1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(account)
+    }
+
+    /// Gets an account by the member number on its card.
+    pub async fn get_by_member_number(&self, member_number: &str) -> DbResult<Option<LoyaltyAccount>> {
+        let account = sqlx::query_as!(
+            LoyaltyAccount,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                member_number,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                sync_version
+            FROM loyalty_accounts
+            WHERE member_number = ?1
+            "#,
+            member_number
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(account)
+    }
+
+    /// Enrolls a new member.
+    ///
+    /// ## Errors
+    /// `DbError::UniqueViolation` if the member number is taken.
+    pub async fn create_account(&self, account: &LoyaltyAccount) -> DbResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO loyalty_accounts (
+                id, tenant_id, member_number, created_at, updated_at, sync_version
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            account.id,
+            account.tenant_id,
+            account.member_number,
+            account.created_at,
+            account.updated_at,
+            account.sync_version
+        )
+        .execute(&self.pool)
+        .await?;
+
+        info!(member_number = %account.member_number, "Loyalty member enrolled");
+        Ok(())
+    }
+
+    /// Gets the ledger entries of an account, oldest first.
+    pub async fn get_entries(&self, account_id: &str) -> DbResult<Vec<LoyaltyEntry>> {
+        let entries = sqlx::query_as!(
+            LoyaltyEntry,
+            r#"
+            SELECT
+                id,
+                account_id,
+                kind as "kind: LoyaltyEntryKind",
+                points,
+                reward_cents,
+                sale_id,
+                device_id,
+                created_at as "created_at: DateTime<Utc>"
+            FROM loyalty_entries
+            WHERE account_id = ?1
+            ORDER BY created_at, id
+            "#,
+            account_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// Gets an account with its ledger.
+    pub async fn get_document(&self, id: &str) -> DbResult<Option<LoyaltyDocument>> {
+        let Some(account) = self.get_by_id(id).await? else {
+            return Ok(None);
+        };
+        let entries = self.get_entries(id).await?;
+
+        Ok(Some(LoyaltyDocument { account, entries }))
+    }
+
+    /// Appends earned points and issued rewards to an account.
+    ///
+    /// Entries already on the ledger are skipped.
+    ///
+    /// ## Returns
+    /// The number of entries written.
+    ///
+    /// ## Errors
+    /// `DbError::NotFound` if the account does not exist.
+    pub async fn add_entries(&self, account_id: &str, entries: &[LoyaltyEntry]) -> DbResult<u64> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let now = Utc::now();
+        let touched = sqlx::query!(
+            r#"
+            UPDATE loyalty_accounts
+            SET updated_at = ?2, sync_version = sync_version + 1
+            WHERE id = ?1
+            "#,
+            account_id,
+            now
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if touched.rows_affected() == 0 {
+            return Err(DbError::not_found("Loyalty account", account_id));
+        }
+
+        let mut written = 0;
+        for entry in entries {
+            written += insert_entry(&mut tx, account_id, entry).await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        debug!(account_id, written, "Loyalty entries added");
+        Ok(written)
+    }
+
+    /// Writes a reward redemption if, and only if, the rewards balance
+    /// covers it.
+    ///
+    /// `entry.reward_cents` is negative. Replaying an entry that is already
+    /// on the ledger is reported as applied without spending twice.
+    ///
+    /// ## Errors
+    /// `DbError::NotFound` if the account does not exist.
+    pub async fn redeem(&self, entry: &LoyaltyEntry) -> DbResult<LoyaltyRedemption> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        // Take the write lock before reading the balance so two redemptions
+        // against the same ledger are serialized.
+        let touched = sqlx::query!(
+            r#"
+            UPDATE loyalty_accounts
+            SET updated_at = ?2, sync_version = sync_version + 1
+            WHERE id = ?1
+            "#,
+            entry.account_id,
+            entry.created_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if touched.rows_affected() == 0 {
+            return Err(DbError::not_found("Loyalty account", &entry.account_id));
+        }
+
+        let replayed: Option<String> =
+            sqlx::query_scalar!("SELECT id FROM loyalty_entries WHERE id = ?1", entry.id)
+                .fetch_optional(&mut *tx)
+                .await?;
+
+        let balance: i64 = sqlx::query_scalar!(
+            r#"
+            SELECT COALESCE(SUM(reward_cents), 0) as "balance!: i64"
+            FROM loyalty_entries
+            WHERE account_id = ?1
+            "#,
+            entry.account_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if replayed.is_some() {
+            // Nothing to write; the lock-only update is rolled back on drop.
+            return Ok(LoyaltyRedemption {
+                applied: true,
+                balance_cents: balance,
+            });
+        }
+
+        if balance + entry.reward_cents < 0 {
+            debug!(account_id = %entry.account_id, balance, amount = entry.reward_cents, "Loyalty redemption rejected");
+            return Ok(LoyaltyRedemption {
+                applied: false,
+                balance_cents: balance,
+            });
+        }
+
+        insert_entry(&mut tx, &entry.account_id, entry).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        info!(account_id = %entry.account_id, amount = entry.reward_cents, "Loyalty reward redeemed");
+
+        Ok(LoyaltyRedemption {
+            applied: true,
+            balance_cents: balance + entry.reward_cents,
+        })
+    }
+
+    /// Merges an account received from another terminal or store.
+    ///
+    /// The account row is created if missing and entries are merged by ID.
+    /// Nothing is rejected: redemptions were already checked where they
+    /// were written.
+    ///
+    /// ## Returns
+    /// `true` if anything changed locally.
+    pub async fn upsert_from_sync(&self, doc: &LoyaltyDocument) -> DbResult<bool> {
+        let account = &doc.account;
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let account_changed = sqlx::query!(
+            r#"
+            INSERT INTO loyalty_accounts (
+                id, tenant_id, member_number, created_at, updated_at, sync_version
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT(id) DO UPDATE SET
+                updated_at = excluded.updated_at,
+                sync_version = excluded.sync_version
+            WHERE excluded.sync_version > loyalty_accounts.sync_version
+            "#,
+            account.id,
+            account.tenant_id,
+            account.member_number,
+            account.created_at,
+            account.updated_at,
+            account.sync_version
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            > 0;
+
+        let mut new_entries = 0;
+        for entry in &doc.entries {
+            new_entries += insert_entry(&mut tx, &account.id, entry).await?;
+        }
+
+        let balance: i64 = sqlx::query_scalar!(
+            r#"
+            SELECT COALESCE(SUM(reward_cents), 0) as "balance!: i64"
+            FROM loyalty_entries
+            WHERE account_id = ?1
+            "#,
+            account.id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        if balance < 0 {
+            // Spent on a standalone terminal that could not see the other
+            // redemption (see titan_core::loyalty); surfaced for follow-up.
+            warn!(
+                member_number = %account.member_number,
+                balance,
+                "Loyalty rewards overdrawn after merge"
+            );
+        }
+
+        Ok(account_changed || new_entries > 0)
+    }
+}
+
+/// Inserts one ledger entry inside an open transaction, skipping it if it
+/// is already on the ledger.
+///
+/// ## Returns
+/// 1 if the entry was written, 0 if it was already there.
+async fn insert_entry(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    account_id: &str,
+    entry: &LoyaltyEntry,
+) -> DbResult<u64> {
+    let written = sqlx::query!(
+        r#"
+        INSERT OR IGNORE INTO loyalty_entries (
+            id, account_id, kind, points, reward_cents, sale_id, device_id, created_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+        "#,
+        entry.id,
+        account_id,
+        entry.kind,
+        entry.points,
+        entry.reward_cents,
+        entry.sale_id,
+        entry.device_id,
+        entry.created_at
+    )
+    .execute(&mut **tx)
+    .await?
+    .rows_affected();
+
+    Ok(written)
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::pool::{Database, DbConfig};
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_loyalty_rewards_spent_once() {
+        use titan_core::{LoyaltyAccount, LoyaltyEntry, LoyaltyEntryKind, LoyaltyProgram};
+
+        let db = Database::new(DbConfig::in_memory()).await.unwrap();
+        let now = Utc::now();
+        let account = LoyaltyAccount {
+            id: Uuid::new_v4().to_string(),
+            tenant_id: titan_core::DEFAULT_TENANT_ID.to_string(),
+            member_number: "LM-1001".to_string(),
+            created_at: now,
+            updated_at: now,
+            sync_version: 1,
+        };
+        db.loyalty().create_account(&account).await.unwrap();
+        assert!(db.loyalty().create_account(&account).await.is_err());
+
+        // $1200 of purchases earns 1200 points: two $5 rewards
+        let program = LoyaltyProgram::default();
+        let doc = db.loyalty().get_document(&account.id).await.unwrap().unwrap();
+        let accrual = program.accrue(&doc, "s-1", 120_000, "pos-01", now);
+        assert_eq!(db.loyalty().add_entries(&account.id, &accrual.entries).await.unwrap(), 3);
+        // Accruing the same sale again writes nothing
+        assert_eq!(db.loyalty().add_entries(&account.id, &accrual.entries).await.unwrap(), 0);
+
+        let redeem = |amount: i64| LoyaltyEntry {
+            id: Uuid::new_v4().to_string(),
+            account_id: account.id.clone(),
+            kind: LoyaltyEntryKind::Redeem,
+            points: 0,
+            reward_cents: -amount,
+            sale_id: Some("s-2".to_string()),
+            device_id: "pos-01".to_string(),
+            created_at: now,
+        };
+        let first = redeem(700);
+        let applied = db.loyalty().redeem(&first).await.unwrap();
+        assert!(applied.applied);
+        assert_eq!(applied.balance_cents, 300);
+        // A replay is not spent twice; more than is left is rejected
+        assert_eq!(db.loyalty().redeem(&first).await.unwrap().balance_cents, 300);
+        assert!(!db.loyalty().redeem(&redeem(400)).await.unwrap().applied);
+
+        let mut doc = db.loyalty().get_document(&account.id).await.unwrap().unwrap();
+        assert_eq!(doc.points_balance(), 200);
+        assert_eq!(doc.reward_balance_cents(), 300);
+
+        // A copy from another store merges by id
+        doc.entries.push(redeem(300));
+        assert!(db.loyalty().upsert_from_sync(&doc).await.unwrap());
+        assert!(!db.loyalty().upsert_from_sync(&doc).await.unwrap());
+        let merged = db.loyalty().get_by_member_number("LM-1001").await.unwrap().unwrap();
+        let merged = db.loyalty().get_document(&merged.id).await.unwrap().unwrap();
+        assert_eq!(merged.reward_balance_cents(), 0);
+    }
+}
@@ -0,0 +1,159 @@
+//! # Manager Override Repository
+//!
+//! The audit trail of signed manager override tokens used at this terminal
+//! (see `titan_core::override_token`). The token ID is the key, so the
+//! trail is also what makes a token single-use.
+//!
+//! ## Audit Trail
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                    Manager Override Audit                               │
+//! │                                                                         │
+//! │  refund_sale(.., override_token)                                        │
+//! │       │ OverrideKey::verify ──► claims.authorizes(refund, sale, amount) │
+//! │       ▼                                                                 │
+//! │  record(ManagerOverride)   insert only; a token ID seen before is       │
+//! │                            DbError::UniqueViolation (already used)      │
+//! │                                                                         │
+//! │  list_between(from, to)    manager report, oldest first                 │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::info;
+
+use crate::error::{DbError, DbResult};
+use titan_core::{ManagerOverride, OverrideAction};
+
+/// Repository for the manager override token audit trail.
+#[derive(Debug, Clone)]
+pub struct ManagerOverrideRepository {
+    pool: SqlitePool,
+}
+
+impl ManagerOverrideRepository {
+    /// Creates a new ManagerOverrideRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        ManagerOverrideRepository { pool }
+    }
+
+    /// Adds a used token to the audit trail.
+    ///
+    /// ## Errors
+    /// - `DbError::UniqueViolation` if the token was already used
+    pub async fn record(&self, entry: &ManagerOverride) -> DbResult<()> {
+        info!(
+            id = %entry.id,
+            action = entry.action.as_str(),
+            manager_id = %entry.manager_id,
+            "Recording manager override"
+        );
+
+        sqlx::query!(
+            r#"
+            INSERT INTO manager_overrides (
+                id, action, subject, amount_cents, manager_id, reason,
+                issued_at, expires_at, user_id, device_id, used_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+            "#,
+            entry.id,
+            entry.action,
+            entry.subject,
+            entry.amount_cents,
+            entry.manager_id,
+            entry.reason,
+            entry.issued_at,
+            entry.expires_at,
+            entry.user_id,
+            entry.device_id,
+            entry.used_at
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match DbError::from(e) {
+            DbError::UniqueViolation { .. } => DbError::duplicate("Override token", &entry.id),
+            other => other,
+        })?;
+
+        Ok(())
+    }
+
+    /// Lists tokens used in `[from, to)`, oldest first.
+    pub async fn list_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> DbResult<Vec<ManagerOverride>> {
+        let entries = sqlx::query_as!(
+            ManagerOverride,
+            r#"
+            SELECT
+                id as "id!",
+                action as "action: OverrideAction",
+                subject,
+                amount_cents,
+                manager_id,
+                reason,
+                issued_at as "issued_at: DateTime<Utc>",
+                expires_at as "expires_at: DateTime<Utc>",
+                user_id,
+                device_id,
+                used_at as "used_at: DateTime<Utc>"
+            FROM manager_overrides
+            WHERE used_at >= ?1 AND used_at < ?2
+            ORDER BY used_at, id
+            "#,
+            from,
+            to
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::error::DbError;
+    use crate::pool::{Database, DbConfig};
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_manager_override_token_used_once() {
+        use titan_core::{ManagerOverride, OverrideAction};
+
+        let db = Database::new(DbConfig::in_memory()).await.unwrap();
+        let now = Utc::now();
+        let entry = ManagerOverride {
+            id: Uuid::new_v4().to_string(),
+            action: OverrideAction::Refund,
+            subject: Some("sale-1".to_string()),
+            amount_cents: Some(25_000),
+            manager_id: "mgr-1".to_string(),
+            reason: "Faulty TV".to_string(),
+            issued_at: now - Duration::minutes(2),
+            expires_at: now + Duration::minutes(8),
+            user_id: "default".to_string(),
+            device_id: "pos-01".to_string(),
+            used_at: now,
+        };
+        db.manager_overrides().record(&entry).await.unwrap();
+
+        let again = db.manager_overrides().record(&entry).await.unwrap_err();
+        assert!(matches!(again, DbError::UniqueViolation { .. }));
+
+        let listed = db
+            .manager_overrides()
+            .list_between(now - Duration::minutes(1), now + Duration::minutes(1))
+            .await
+            .unwrap();
+        assert_eq!(listed, vec![entry]);
+    }
+}
@@ -0,0 +1,160 @@
+//! # Margin Override Repository
+//!
+//! The audit trail of manager approvals for manual prices below the
+//! store's blocking margin floor (see `titan_core::margin_guard`).
+//!
+//! ## Audit Trail
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                     Margin Override Audit                               │
+//! │                                                                         │
+//! │  update_product / override_line_price                                  │
+//! │       │ MarginGuardrail::check(.., bypass) ──► Bypassed                │
+//! │       ▼                                                                 │
+//! │  record(MarginOverride)   insert only, never updated or deleted        │
+//! │                                                                         │
+//! │  list_between(from, to)   manager report, oldest first                 │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::info;
+
+use crate::error::DbResult;
+use titan_core::{MarginOverride, PriceChangeKind};
+
+/// Repository for the margin override audit trail.
+#[derive(Debug, Clone)]
+pub struct MarginOverrideRepository {
+    pool: SqlitePool,
+}
+
+impl MarginOverrideRepository {
+    /// Creates a new MarginOverrideRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        MarginOverrideRepository { pool }
+    }
+
+    /// Adds an approved override to the audit trail.
+    pub async fn record(&self, entry: &MarginOverride) -> DbResult<()> {
+        info!(
+            id = %entry.id,
+            kind = entry.kind.as_str(),
+            product_id = %entry.product_id,
+            manager_id = %entry.manager_id,
+            "Recording margin override"
+        );
+
+        sqlx::query!(
+            r#"
+            INSERT INTO margin_overrides (
+                id, kind, product_id, old_price_cents, new_price_cents,
+                cost_cents, margin_bps, floor_bps, manager_id, reason,
+                user_id, device_id, created_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+            "#,
+            entry.id,
+            entry.kind,
+            entry.product_id,
+            entry.old_price_cents,
+            entry.new_price_cents,
+            entry.cost_cents,
+            entry.margin_bps,
+            entry.floor_bps,
+            entry.manager_id,
+            entry.reason,
+            entry.user_id,
+            entry.device_id,
+            entry.created_at
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Lists overrides approved in `[from, to)`, oldest first.
+    pub async fn list_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> DbResult<Vec<MarginOverride>> {
+        let entries = sqlx::query_as!(
+            MarginOverride,
+            r#"
+            SELECT
+                id as "id!",
+                kind as "kind: PriceChangeKind",
+                product_id,
+                old_price_cents,
+                new_price_cents,
+                cost_cents,
+                margin_bps,
+                floor_bps as "floor_bps: u32",
+                manager_id,
+                reason,
+                user_id,
+                device_id,
+                created_at as "created_at: DateTime<Utc>"
+            FROM margin_overrides
+            WHERE created_at >= ?1 AND created_at < ?2
+            ORDER BY created_at, id
+            "#,
+            from,
+            to
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::pool::{Database, DbConfig};
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_margin_override_audit_trail() {
+        use titan_core::{MarginOverride, PriceChangeKind};
+
+        let db = Database::new(DbConfig::in_memory()).await.unwrap();
+        let now = Utc::now();
+        let entry = MarginOverride {
+            id: Uuid::new_v4().to_string(),
+            kind: PriceChangeKind::LinePrice,
+            product_id: "p1".to_string(),
+            old_price_cents: 1000,
+            new_price_cents: 750,
+            cost_cents: Some(700),
+            margin_bps: Some(666),
+            floor_bps: 2000,
+            manager_id: "mgr-1".to_string(),
+            reason: "Price match".to_string(),
+            user_id: "default".to_string(),
+            device_id: "pos-01".to_string(),
+            created_at: now,
+        };
+        db.margin_overrides().record(&entry).await.unwrap();
+
+        let listed = db
+            .margin_overrides()
+            .list_between(now - Duration::minutes(1), now + Duration::minutes(1))
+            .await
+            .unwrap();
+        assert_eq!(listed, vec![entry]);
+        assert!(db
+            .margin_overrides()
+            .list_between(now + Duration::minutes(1), now + Duration::minutes(2))
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -32,12 +32,82 @@
 //! └─────────────────────────────────────────────────────────────────────────┘
 //! ```
 //!
+//! ## Optimistic Locking
+//! Full-row writes of synced entities (product `update`, business customer
+//! `save`) are compare-and-swap on `sync_version`: the row is only written
+//! if it is still at the version the caller read. Otherwise nothing is
+//! written and the caller gets `DbError::VersionConflict` with the version
+//! now stored, so an edit made on a stale copy cannot silently undo a
+//! change that arrived through sync (or vice versa).
+//!
+//! ```text
+//! UI loads product (v4) ── edits price ──► update(product @ v4)
+//!                                             │
+//! inbound sync: upsert v5 ────────────────────┤ (lands first)
+//!                                             ▼
+//!        UPDATE … WHERE id = ? AND sync_version = 4   → 0 rows
+//!        → VersionConflict { expected: 4, actual: 5 }
+//! ```
+//!
+//! Status transitions (finalize a sale, receive a transfer, …) guard on
+//! the current status instead, and stock moves by delta, so neither needs
+//! a version.
+//!
 //! ## Available Repositories
 //!
+//! - [`BundleRepository`] - Bundle bills of materials, component stock
+//! - [`BusinessCustomerRepository`] - Business customers for e-invoicing
+//! - [`CashDrawerRepository`] - Audit trail of manual cash drawer opens
+//! - [`CouponRepository`] - Coupons and their redemptions
+//! - [`ErasureRepository`] - Customer data erasure and the erasure log
+//! - [`FeatureFlagRepository`] - Local cache of the store's feature flags
+//! - [`HubUploadQueueRepository`] - Hub's queue of entries bound for the cloud
+//! - [`JobRepository`] - Scheduled background jobs and run history
+//! - [`LabelRepository`] - Shelf label queue and label templates
+//! - [`LayawayRepository`] - Layaway orders, payments, stock reservation
+//! - [`LoyaltyRepository`] - Loyalty accounts and points ledger
+//! - [`ManagerOverrideRepository`] - Audit trail of used manager override tokens
+//! - [`MarginOverrideRepository`] - Audit trail of margin floor bypasses
+//! - [`PackRepository`] - Case packs, stock receipts
+//! - [`PiiKeyRepository`] - Tenant data keys for customer data encryption
 //! - [`ProductRepository`] - Product CRUD and search
-//! - [`SaleRepository`] - Sale and sale item operations
+//! - [`QuoteRepository`] - Quotes and quote-to-sale conversion
+//! - [`ReceiptCampaignRepository`] - Local cache of the store's receipt footer campaigns
+//! - [`SaleRepository`] - Sale and sale item operations, refunds
+//! - [`ProductStyleRepository`] - Product styles and their variants
+//! - [`StoreCreditRepository`] - Store credit accounts and ledger
+//! - [`SupplierRepository`] - Suppliers, price lists and product costs
 //! - [`SyncOutboxRepository`] - Sync queue management
+//! - [`TillRepository`] - Till sessions, blind close, variance report
+//! - [`TransferRepository`] - Stock transfers between stores
+//! - [`WasteRepository`] - Waste and shrinkage write-offs
+//! - [`ZReportRepository`] - Z closes, numbered per device
 
+pub mod bundle;
+pub mod business_customer;
+pub mod cash_drawer;
+pub mod coupon;
+pub mod erasure;
+pub mod feature_flag;
+pub mod hub_queue;
+pub mod job;
+pub mod label;
+pub mod layaway;
+pub mod loyalty;
+pub mod manager_override;
+pub mod margin_override;
+pub mod pack;
+pub mod pii_key;
 pub mod product;
+pub mod quote;
+pub mod receipt_campaign;
 pub mod sale;
+pub(crate) mod stock;
+pub mod store_credit;
+pub mod style;
+pub mod supplier;
 pub mod sync;
+pub mod till;
+pub mod transfer;
+pub mod waste;
+pub mod z_report;
@@ -0,0 +1,647 @@
+//! # Pack Repository
+//!
+//! Database operations for product pack sizes and stock receipts (see
+//! `titan_core::pack`).
+//!
+//! ## Receiving
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                     Receiving Goods                                     │
+//! │                                                                         │
+//! │  receive("DN-1042", [3 × case COKE-330, 4 × each COKE-330])             │
+//! │       │                                                                 │
+//! │       ├── convert_line with the product's packs: 72 + 4 units          │
+//! │       │   (all lines checked before anything is written)               │
+//! │       ├── stock_receipts + stock_receipt_items (unit, size, quantity)  │
+//! │       ├── per costed line: product_costs last cost, average weighted   │
+//! │       │                    by the stock on hand before the line        │
+//! │       └── per line: current_stock + units, inventory_deltas row        │
+//! │                     (delta_type receive, reference the receipt)        │
+//! │                                                                         │
+//! │  One transaction: a bad line receives nothing. Afterwards a changed    │
+//! │  average becomes products.cost_cents (queued as PRODUCT_PATCH).        │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::error::{DbError, DbResult};
+use crate::events::EventPublisher;
+use crate::repository::product::ProductRepository;
+use crate::repository::stock::{move_stock, REFERENCE_RECEIPT};
+use crate::repository::supplier::SupplierRepository;
+use crate::repository::sync::SyncOutboxRepository;
+use titan_core::pack::{convert_line, normalize_packs};
+use titan_core::supplier::weighted_average_cost;
+use titan_core::{
+    EntityEvent, ProductChange, ProductPack, ProductPacks, ReceivingLine, StockReceipt,
+    StockReceiptLine, Tracked,
+};
+
+/// Inventory delta reason for goods received into stock.
+pub const DELTA_RECEIVE: &str = "receive";
+
+/// Repository for pack sizes and stock receipts.
+#[derive(Debug, Clone)]
+pub struct PackRepository {
+    pool: SqlitePool,
+    events: EventPublisher,
+}
+
+impl PackRepository {
+    /// Creates a new PackRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        PackRepository {
+            pool,
+            events: EventPublisher::disabled(),
+        }
+    }
+
+    /// Publishes `ProductChanged` and `StockChanged` events through
+    /// `events`.
+    pub fn with_events(mut self, events: EventPublisher) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Gets a product's packs; empty (version 0) for a product sold only
+    /// by the each.
+    ///
+    /// ## Errors
+    /// `DbError::NotFound` if the product doesn't exist.
+    pub async fn get(&self, product_id: &str) -> DbResult<ProductPacks> {
+        let header = sqlx::query!(
+            r#"
+            SELECT
+                packs_version as "version!: i64",
+                packs_updated_at as "updated_at: chrono::DateTime<Utc>",
+                created_at as "created_at!: chrono::DateTime<Utc>"
+            FROM products
+            WHERE id = ?1
+            "#,
+            product_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| DbError::not_found("Product", product_id))?;
+
+        let packs = sqlx::query_as!(
+            ProductPack,
+            r#"
+            SELECT name, units, barcode
+            FROM product_packs
+            WHERE product_id = ?1
+            ORDER BY units
+            "#,
+            product_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ProductPacks {
+            product_id: product_id.to_string(),
+            packs,
+            sync_version: header.version,
+            updated_at: header.updated_at.unwrap_or(header.created_at),
+        })
+    }
+
+    /// Finds the product and pack a case barcode belongs to.
+    pub async fn find_by_barcode(&self, barcode: &str) -> DbResult<Option<(String, ProductPack)>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT pp.product_id, pp.name, pp.units, pp.barcode
+            FROM product_packs pp
+            JOIN products p ON p.id = pp.product_id
+            WHERE pp.barcode = ?1 AND p.deleted_at IS NULL
+            LIMIT 1
+            "#,
+            barcode
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| {
+            (
+                r.product_id,
+                ProductPack {
+                    name: r.name,
+                    units: r.units,
+                    barcode: r.barcode,
+                },
+            )
+        }))
+    }
+
+    /// Replaces a product's packs and queues the new set for sync; an
+    /// empty set leaves the product sold and received by the each.
+    ///
+    /// ## Errors
+    /// - `DbError::InvalidInput` for an invalid set (see
+    ///   `titan_core::pack::normalize_packs`)
+    /// - `DbError::NotFound` if the product doesn't exist or is deleted
+    pub async fn set_packs(&self, product_id: &str, packs: &[ProductPack]) -> DbResult<ProductPacks> {
+        let packs = normalize_packs(packs)?;
+        let now = Utc::now();
+
+        debug!(product_id = %product_id, count = packs.len(), "Setting product packs");
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let versions = sqlx::query!(
+            r#"
+            UPDATE products
+            SET
+                packs_version = packs_version + 1,
+                packs_updated_at = ?2
+            WHERE id = ?1 AND deleted_at IS NULL
+            RETURNING packs_version as "packs_version!: i64", sync_version
+            "#,
+            product_id,
+            now
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| DbError::not_found("Product", product_id))?;
+
+        replace_packs(&mut tx, product_id, &packs).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let doc = ProductPacks {
+            product_id: product_id.to_string(),
+            packs,
+            sync_version: versions.packs_version,
+            updated_at: now,
+        };
+        let payload = serde_json::to_string(&doc)
+            .map_err(|e| DbError::Internal(format!("Failed to serialize packs: {}", e)))?;
+        SyncOutboxRepository::new(self.pool.clone())
+            .upsert_for_sync("PRODUCT_PACKS", product_id, &payload)
+            .await?;
+
+        self.events.publish(EntityEvent::ProductChanged {
+            product_id: product_id.to_string(),
+            change: ProductChange::Updated,
+            version: versions.sync_version,
+        });
+        Ok(doc)
+    }
+
+    /// Applies a pack set received through sync.
+    ///
+    /// ## Returns
+    /// * `Ok(true)` - Applied
+    /// * `Ok(false)` - Product unknown, or its set is already at or past
+    ///   the document's version
+    pub async fn upsert_from_sync(&self, doc: &ProductPacks) -> DbResult<bool> {
+        let packs = normalize_packs(&doc.packs)?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let version: Option<i64> = sqlx::query_scalar!(
+            r#"
+            UPDATE products
+            SET
+                packs_version = ?2,
+                packs_updated_at = ?3
+            WHERE id = ?1 AND packs_version < ?2
+            RETURNING sync_version
+            "#,
+            doc.product_id,
+            doc.sync_version,
+            doc.updated_at
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(version) = version else {
+            return Ok(false);
+        };
+
+        replace_packs(&mut tx, &doc.product_id, &packs).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        self.events.publish(EntityEvent::ProductChanged {
+            product_id: doc.product_id.clone(),
+            change: ProductChange::Updated,
+            version,
+        });
+        Ok(true)
+    }
+
+    /// Receives goods into stock: converts every line to eaches with the
+    /// product's packs, records the receipt and adds the stock.
+    ///
+    /// Lines with a cost (their own, or the supplier's listed cost) update
+    /// the product's last and average cost; a changed average becomes the
+    /// product's cost and syncs as a product edit.
+    ///
+    /// ## Errors
+    /// - `DbError::InvalidInput` for an empty receipt, a quantity or cost
+    ///   out of range or a unit the product doesn't define
+    /// - `DbError::NotFound` if the supplier or a product doesn't exist, or
+    ///   the product is deleted
+    pub async fn receive(
+        &self,
+        reference: Option<&str>,
+        supplier_id: Option<&str>,
+        lines: &[ReceivingLine],
+        received_by: &str,
+        device_id: &str,
+    ) -> DbResult<StockReceipt> {
+        if lines.is_empty() {
+            return Err(DbError::InvalidInput(titan_core::ValidationError::Required {
+                field: "lines".to_string(),
+            }));
+        }
+
+        let suppliers = SupplierRepository::new(self.pool.clone());
+        if let Some(id) = supplier_id {
+            if suppliers.get(id).await?.is_none() {
+                return Err(DbError::not_found("Supplier", id));
+            }
+        }
+
+        let mut converted = Vec::with_capacity(lines.len());
+        for line in lines {
+            let packs = self.get(&line.product_id).await?;
+            let mut line = convert_line(line, &packs)?;
+            if let (None, Some(id)) = (line.each_cost_cents, supplier_id) {
+                line.each_cost_cents = suppliers.cost_of(id, &line.product_id).await?;
+            }
+            converted.push(line);
+        }
+
+        let receipt = StockReceipt {
+            id: Uuid::new_v4().to_string(),
+            reference: reference
+                .map(str::trim)
+                .filter(|r| !r.is_empty())
+                .map(str::to_string),
+            supplier_id: supplier_id.map(str::to_string),
+            lines: converted,
+            received_by: received_by.to_string(),
+            device_id: device_id.to_string(),
+            received_at: Utc::now(),
+        };
+
+        debug!(id = %receipt.id, lines = receipt.lines.len(), units = receipt.total_units(), "Receiving stock");
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO stock_receipts (
+                id, reference, supplier_id, received_by, device_id, received_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            receipt.id,
+            receipt.reference,
+            receipt.supplier_id,
+            receipt.received_by,
+            receipt.device_id,
+            receipt.received_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for (line_no, line) in receipt.lines.iter().enumerate() {
+            let line_no = line_no as i64;
+            let live = sqlx::query!(
+                r#"
+                INSERT INTO stock_receipt_items (
+                    receipt_id, line_no, product_id, unit, units_per_pack, quantity, units,
+                    each_cost_cents
+                )
+                SELECT ?1, ?2, id, ?4, ?5, ?6, ?7, ?8
+                FROM products
+                WHERE id = ?3 AND deleted_at IS NULL
+                "#,
+                receipt.id,
+                line_no,
+                line.product_id,
+                line.unit,
+                line.units_per_pack,
+                line.quantity,
+                line.units,
+                line.each_cost_cents
+            )
+            .execute(&mut *tx)
+            .await?;
+            if live.rows_affected() == 0 {
+                return Err(DbError::not_found("Product", &line.product_id));
+            }
+
+            if let Some(cost) = line.each_cost_cents {
+                update_cost(&mut tx, &receipt, line, cost).await?;
+            }
+
+            move_stock(
+                &mut tx,
+                REFERENCE_RECEIPT,
+                &line.product_id,
+                line.units,
+                DELTA_RECEIVE,
+                &receipt.id,
+                device_id,
+            )
+            .await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        self.apply_average_costs(&receipt).await?;
+
+        for line in &receipt.lines {
+            let stock: Option<Option<i64>> = sqlx::query_scalar!(
+                "SELECT current_stock FROM products WHERE id = ?1 AND track_inventory = 1",
+                line.product_id
+            )
+            .fetch_optional(&self.pool)
+            .await?;
+            if let Some(stock) = stock {
+                self.events.publish(EntityEvent::StockChanged {
+                    product_id: line.product_id.clone(),
+                    delta: line.units,
+                    stock,
+                });
+            }
+        }
+
+        Ok(receipt)
+    }
+
+    /// Makes each costed product's average cost its cost, as a tracked
+    /// product edit.
+    async fn apply_average_costs(&self, receipt: &StockReceipt) -> DbResult<()> {
+        let products = ProductRepository::new(self.pool.clone()).with_events(self.events.clone());
+        let mut done: Vec<&str> = Vec::new();
+
+        for line in receipt.lines.iter().filter(|l| l.each_cost_cents.is_some()) {
+            if done.contains(&line.product_id.as_str()) {
+                continue;
+            }
+            done.push(&line.product_id);
+
+            let Some(product) = products.get_by_id(&line.product_id).await? else {
+                continue;
+            };
+            let average = sqlx::query_scalar!(
+                "SELECT average_cost_cents FROM product_costs WHERE product_id = ?1",
+                line.product_id
+            )
+            .fetch_optional(&self.pool)
+            .await?
+            .flatten();
+            if average.is_none() || average == product.cost_cents {
+                continue;
+            }
+
+            let mut product = Tracked::new(product);
+            product.get_mut().cost_cents = average;
+            products.update_tracked(&product).await?;
+        }
+        Ok(())
+    }
+
+    /// Gets a stock receipt with its lines.
+    pub async fn get_receipt(&self, id: &str) -> DbResult<Option<StockReceipt>> {
+        let header = sqlx::query!(
+            r#"
+            SELECT
+                id as "id!",
+                reference,
+                supplier_id,
+                received_by,
+                device_id,
+                received_at as "received_at: DateTime<Utc>"
+            FROM stock_receipts
+            WHERE id = ?1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(header) = header else {
+            return Ok(None);
+        };
+
+        let lines = sqlx::query_as!(
+            StockReceiptLine,
+            r#"
+            SELECT product_id, unit, units_per_pack, quantity, units, each_cost_cents
+            FROM stock_receipt_items
+            WHERE receipt_id = ?1
+            ORDER BY line_no
+            "#,
+            id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(Some(StockReceipt {
+            id: header.id,
+            reference: header.reference,
+            supplier_id: header.supplier_id,
+            lines,
+            received_by: header.received_by,
+            device_id: header.device_id,
+            received_at: header.received_at,
+        }))
+    }
+
+    /// Lists the most recent stock receipts, newest first.
+    pub async fn recent_receipts(&self, limit: u32) -> DbResult<Vec<StockReceipt>> {
+        let ids = sqlx::query_scalar!(
+            r#"
+            SELECT id as "id!" FROM stock_receipts
+            ORDER BY received_at DESC
+            LIMIT ?1
+            "#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut receipts = Vec::with_capacity(ids.len());
+        for id in ids {
+            if let Some(receipt) = self.get_receipt(&id).await? {
+                receipts.push(receipt);
+            }
+        }
+        Ok(receipts)
+    }
+}
+
+/// Records a receipt line's cost as the product's last cost and folds it
+/// into the average, weighted by the stock on hand before the line.
+async fn update_cost(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    receipt: &StockReceipt,
+    line: &StockReceiptLine,
+    each_cost_cents: i64,
+) -> DbResult<()> {
+    let on_hand: i64 = sqlx::query_scalar!(
+        r#"SELECT COALESCE(current_stock, 0) as "stock!: i64" FROM products WHERE id = ?1"#,
+        line.product_id
+    )
+    .fetch_one(&mut **tx)
+    .await?;
+    // Before the first costed receipt, the product's own cost stands in
+    // for the stock on hand
+    let average = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(
+            (SELECT average_cost_cents FROM product_costs WHERE product_id = ?1),
+            cost_cents
+        ) as "cost: i64"
+        FROM products
+        WHERE id = ?1
+        "#,
+        line.product_id
+    )
+    .fetch_one(&mut **tx)
+    .await?;
+
+    let average = weighted_average_cost(on_hand, average, line.units, each_cost_cents);
+    sqlx::query!(
+        r#"
+        INSERT INTO product_costs (
+            product_id, last_cost_cents, average_cost_cents, last_supplier_id, updated_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5)
+        ON CONFLICT(product_id) DO UPDATE SET
+            last_cost_cents = excluded.last_cost_cents,
+            average_cost_cents = excluded.average_cost_cents,
+            last_supplier_id = excluded.last_supplier_id,
+            updated_at = excluded.updated_at
+        "#,
+        line.product_id,
+        each_cost_cents,
+        average,
+        receipt.supplier_id,
+        receipt.received_at
+    )
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+async fn replace_packs(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    product_id: &str,
+    packs: &[ProductPack],
+) -> DbResult<()> {
+    sqlx::query!("DELETE FROM product_packs WHERE product_id = ?1", product_id)
+        .execute(&mut **tx)
+        .await?;
+
+    for pack in packs {
+        sqlx::query!(
+            "INSERT INTO product_packs (product_id, name, units, barcode) VALUES (?1, ?2, ?3, ?4)",
+            product_id,
+            pack.name,
+            pack.units,
+            pack.barcode
+        )
+        .execute(&mut **tx)
+        .await?;
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::Fixtures;
+    use crate::pool::{Database, DbConfig};
+    use titan_core::{ProductPack, ReceivingLine};
+
+    #[tokio::test]
+    async fn test_packs_and_case_receiving() {
+        let config = DbConfig::in_memory().with_fixtures(Fixtures::new().products(2));
+        let db = Database::new(config).await.unwrap();
+        let products = db.products().search("", 2).await.unwrap();
+        let cola = &products[0];
+
+        let mut case = ProductPack::new("Case", 24);
+        case.barcode = Some("10049000042566".to_string());
+        let packs = db
+            .packs()
+            .set_packs(&cola.id, &[case, ProductPack::new("inner", 6)])
+            .await
+            .unwrap();
+        assert_eq!(packs.sync_version, 1);
+        assert_eq!(packs.largest().unwrap().name, "case");
+        assert_eq!(db.packs().get(&cola.id).await.unwrap(), packs);
+        let (product_id, pack) = db.packs().find_by_barcode("10049000042566").await.unwrap().unwrap();
+        assert_eq!((product_id.as_str(), pack.units), (cola.id.as_str(), 24));
+
+        let line = |product_id: &str, unit: &str, quantity: i64| ReceivingLine {
+            product_id: product_id.to_string(),
+            unit: unit.to_string(),
+            quantity,
+            unit_cost_cents: None,
+        };
+        let receipt = db
+            .packs()
+            .receive(
+                Some("DN-1042"),
+                None,
+                &[line(&cola.id, "case", 3), line(&cola.id, "each", 4)],
+                "manager",
+                "pos-01",
+            )
+            .await
+            .unwrap();
+        assert_eq!(receipt.total_units(), 76);
+        assert_eq!(db.packs().get_receipt(&receipt.id).await.unwrap().unwrap(), receipt);
+        let stock = db.products().get_by_id(&cola.id).await.unwrap().unwrap().current_stock;
+        assert_eq!(stock, Some(cola.current_stock.unwrap() + 76));
+
+        // A bad line receives nothing
+        let other = &products[1];
+        assert!(db
+            .packs()
+            .receive(None, None, &[line(&other.id, "each", 2), line(&other.id, "case", 1)], "manager", "pos-01")
+            .await
+            .is_err());
+        let stock = db.products().get_by_id(&other.id).await.unwrap().unwrap().current_stock;
+        assert_eq!(stock, other.current_stock);
+        assert_eq!(db.packs().recent_receipts(10).await.unwrap().len(), 1);
+
+        // Sync applies newer sets only
+        let mut incoming = packs.clone();
+        incoming.product_id = other.id.clone();
+        assert!(db.packs().upsert_from_sync(&incoming).await.unwrap());
+        assert!(!db.packs().upsert_from_sync(&incoming).await.unwrap());
+        let pending = db.sync_outbox().get_pending(10).await.unwrap();
+        assert!(pending.iter().any(|e| e.entity_type == "PRODUCT_PACKS"));
+    }
+}
@@ -0,0 +1,223 @@
+//! # PII Key Repository
+//!
+//! Tenant data keys for PII encryption (see [`crate::pii`]), stored
+//! wrapped with the master key, and the one-time encryption of rows
+//! written in plain text.
+
+use chrono::Utc;
+use sqlx::{Row, SqlitePool};
+use tracing::{debug, info};
+
+use crate::error::{DbError, DbResult};
+use crate::pii::{random_key, MasterKey, PiiCipher, PiiField, CIPHERTEXT_PREFIX};
+
+/// An encrypted column: table, key column, value column, hash column.
+struct PiiColumn {
+    table: &'static str,
+    key: &'static str,
+    column: &'static str,
+    hash: Option<&'static str>,
+    field: PiiField,
+}
+
+/// Every column holding encrypted customer data.
+const PII_COLUMNS: &[PiiColumn] = &[
+    PiiColumn {
+        table: "business_customers",
+        key: "id",
+        column: "email",
+        hash: Some("email_hash"),
+        field: PiiField::Email,
+    },
+    PiiColumn {
+        table: "quotes",
+        key: "id",
+        column: "customer_email",
+        hash: Some("customer_email_hash"),
+        field: PiiField::Email,
+    },
+    PiiColumn {
+        table: "layaways",
+        key: "id",
+        column: "customer_phone",
+        hash: Some("customer_phone_hash"),
+        field: PiiField::Phone,
+    },
+    PiiColumn {
+        table: "store_credit_accounts",
+        key: "id",
+        column: "customer_phone",
+        hash: Some("customer_phone_hash"),
+        field: PiiField::Phone,
+    },
+    PiiColumn {
+        table: "erasure_log",
+        key: "request_id",
+        column: "phone",
+        hash: None,
+        field: PiiField::Phone,
+    },
+    PiiColumn {
+        table: "erasure_log",
+        key: "request_id",
+        column: "email",
+        hash: None,
+        field: PiiField::Email,
+    },
+];
+
+/// Repository for tenant data keys.
+#[derive(Debug, Clone)]
+pub struct PiiKeyRepository {
+    pool: SqlitePool,
+}
+
+impl PiiKeyRepository {
+    /// Creates a new PiiKeyRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        PiiKeyRepository { pool }
+    }
+
+    /// Opens the tenant's data key, creating one on first use.
+    ///
+    /// ## Errors
+    /// `DbError::Internal` if the stored key was wrapped with another
+    /// master key.
+    pub async fn load_or_create(&self, tenant_id: &str, master: &MasterKey) -> DbResult<PiiCipher> {
+        if let Some(wrapped) = self.get_wrapped(tenant_id).await? {
+            let data_key = master.unwrap_data_key(tenant_id, &wrapped)?;
+            return Ok(PiiCipher::new(&data_key));
+        }
+
+        let wrapped = master.wrap_data_key(tenant_id, &random_key()?)?;
+        let now = Utc::now();
+        sqlx::query!(
+            r#"
+            INSERT OR IGNORE INTO pii_keys (tenant_id, wrapped_key, created_at)
+            VALUES (?1, ?2, ?3)
+            "#,
+            tenant_id,
+            wrapped,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+        info!(tenant_id, "PII data key created");
+
+        // Another connection may have created one first
+        let wrapped = self
+            .get_wrapped(tenant_id)
+            .await?
+            .ok_or_else(|| DbError::not_found("PII key", tenant_id))?;
+        Ok(PiiCipher::new(&master.unwrap_data_key(tenant_id, &wrapped)?))
+    }
+
+    /// Re-wraps the tenant's data key with a new master key.
+    ///
+    /// Stored values stay as they are; only the key row changes.
+    ///
+    /// ## Errors
+    /// `DbError::NotFound` if the tenant has no key; `DbError::Internal`
+    /// if `old` is not the key it was wrapped with.
+    pub async fn rewrap(&self, tenant_id: &str, old: &MasterKey, new: &MasterKey) -> DbResult<()> {
+        let wrapped = self
+            .get_wrapped(tenant_id)
+            .await?
+            .ok_or_else(|| DbError::not_found("PII key", tenant_id))?;
+        let rewrapped = new.wrap_data_key(tenant_id, &old.unwrap_data_key(tenant_id, &wrapped)?)?;
+
+        sqlx::query!(
+            "UPDATE pii_keys SET wrapped_key = ?2 WHERE tenant_id = ?1",
+            tenant_id,
+            rewrapped
+        )
+        .execute(&self.pool)
+        .await?;
+        info!(tenant_id, "PII data key re-wrapped");
+
+        Ok(())
+    }
+
+    /// Encrypts and hashes values still stored in plain text.
+    ///
+    /// With a disabled cipher values stay in plain text and only missing
+    /// lookup hashes are filled in.
+    ///
+    /// ## Returns
+    /// The number of values updated.
+    pub async fn encrypt_existing(&self, cipher: &PiiCipher) -> DbResult<u64> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let mut updated = 0;
+        for col in PII_COLUMNS {
+            let pending = match (cipher.is_enabled(), col.hash) {
+                (true, _) => format!("{} NOT LIKE ?1", col.column),
+                (false, Some(hash)) => format!("{} IS NULL", hash),
+                (false, None) => continue,
+            };
+            let select = format!(
+                "SELECT {key}, {column} FROM {table} WHERE {column} IS NOT NULL AND {pending}",
+                key = col.key,
+                column = col.column,
+                table = col.table,
+            );
+            let mut query = sqlx::query(&select);
+            if cipher.is_enabled() {
+                query = query.bind(format!("{}%", CIPHERTEXT_PREFIX));
+            }
+            let rows = query.fetch_all(&mut *tx).await?;
+
+            let update = match col.hash {
+                Some(hash) => format!(
+                    "UPDATE {table} SET {column} = ?2, {hash} = ?3 WHERE {key} = ?1",
+                    table = col.table,
+                    column = col.column,
+                    key = col.key,
+                ),
+                None => format!(
+                    "UPDATE {table} SET {column} = ?2 WHERE {key} = ?1",
+                    table = col.table,
+                    column = col.column,
+                    key = col.key,
+                ),
+            };
+            for row in rows {
+                let key: String = row.try_get(0)?;
+                let value: String = row.try_get(1)?;
+                let mut query = sqlx::query(&update)
+                    .bind(&key)
+                    .bind(cipher.encrypt(col.field, &value)?);
+                if col.hash.is_some() {
+                    query = query.bind(cipher.blind_index(col.field, &value));
+                }
+                query.execute(&mut *tx).await?;
+                updated += 1;
+            }
+            debug!(table = col.table, column = col.column, "PII column checked");
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        if updated > 0 {
+            info!(updated, enabled = cipher.is_enabled(), "Customer data brought up to date");
+        }
+        Ok(updated)
+    }
+
+    async fn get_wrapped(&self, tenant_id: &str) -> DbResult<Option<String>> {
+        let wrapped = sqlx::query_scalar!(
+            "SELECT wrapped_key FROM pii_keys WHERE tenant_id = ?1",
+            tenant_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(wrapped)
+    }
+}
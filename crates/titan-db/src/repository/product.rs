@@ -4,8 +4,13 @@
 //!
 //! ## Key Operations
 //! - Full-text search using FTS5
-//! - CRUD operations
-//! - Inventory updates
+//! - Paged search/browse with keyset cursors
+//! - CRUD operations, soft delete and restore (see `titan_core::tombstone`)
+//! - Inventory updates, and stock recomputed from the `inventory_deltas`
+//!   ledger after a count (for support, see the `titan-db` CLI)
+//! - Tracked edits queued for sync as field patches
+//! - Attributes and tags (see `titan_core::attribute`), searched by
+//!   filter and synced as one `PRODUCT_ATTRIBUTES` document per product
 //!
 //! ## FTS5 Search
 //! ```text
@@ -33,13 +38,32 @@
 //! └─────────────────────────────────────────────────────────────────────────┘
 //! ```
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use sqlx::SqlitePool;
 use tracing::debug;
 use uuid::Uuid;
 
 use crate::error::{DbError, DbResult};
-use titan_core::{Product, DEFAULT_TENANT_ID};
+use crate::events::EventPublisher;
+use crate::repository::sync::SyncOutboxRepository;
+use titan_core::attribute::normalize_attributes;
+use titan_core::department::department_product_id;
+use titan_core::{
+    AttributeFilter, DepartmentLine, EntityEvent, EntityPatch, ItemTracking, Page, PageRequest, Product,
+    ProductAttribute, ProductAttributes, ProductChange, Tombstone, TombstoneAction, Tracked,
+    DEFAULT_TENANT_ID,
+};
+
+/// Stock movements of one kind in a product's `inventory_deltas` ledger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StockLedgerLine {
+    /// `sale`, `sync`, `receive`, a waste reason, ...
+    pub delta_type: String,
+    /// Number of movements.
+    pub movements: i64,
+    /// Sum of their deltas.
+    pub total: i64,
+}
 
 /// Repository for product database operations.
 ///
@@ -56,12 +80,31 @@ use titan_core::{Product, DEFAULT_TENANT_ID};
 #[derive(Debug, Clone)]
 pub struct ProductRepository {
     pool: SqlitePool,
+    events: EventPublisher,
 }
 
 impl ProductRepository {
     /// Creates a new ProductRepository.
     pub fn new(pool: SqlitePool) -> Self {
-        ProductRepository { pool }
+        ProductRepository {
+            pool,
+            events: EventPublisher::disabled(),
+        }
+    }
+
+    /// Publishes `ProductChanged` and `StockChanged` events through
+    /// `events`.
+    pub fn with_events(mut self, events: EventPublisher) -> Self {
+        self.events = events;
+        self
+    }
+
+    fn publish_change(&self, product_id: &str, change: ProductChange, version: i64) {
+        self.events.publish(EntityEvent::ProductChanged {
+            product_id: product_id.to_string(),
+            change,
+            version,
+        });
     }
 
     /// Searches products using full-text search.
@@ -114,19 +157,23 @@ impl ProductRepository {
                 p.name,
                 p.description,
                 p.price_cents,
+                p.base_price_cents,
                 p.cost_cents,
                 p.tax_rate_bps as "tax_rate_bps: u32",
                 p.track_inventory as "track_inventory: bool",
                 p.allow_negative_stock as "allow_negative_stock: bool",
                 p.current_stock,
+                p.item_tracking as "item_tracking: ItemTracking",
+                p.min_purchase_age as "min_purchase_age: u32",
                 p.is_active as "is_active: bool",
+                p.deleted_at as "deleted_at: chrono::DateTime<Utc>",
                 p.created_at as "created_at: chrono::DateTime<Utc>",
                 p.updated_at as "updated_at: chrono::DateTime<Utc>",
                 p.sync_version
             FROM products p
             INNER JOIN products_fts fts ON p.rowid = fts.rowid
             WHERE products_fts MATCH ?1
-            AND p.is_active = 1
+            AND p.is_active = 1 AND p.deleted_at IS NULL
             ORDER BY rank
             LIMIT ?2
             "#,
@@ -157,17 +204,21 @@ impl ProductRepository {
                 name,
                 description,
                 price_cents,
+                base_price_cents,
                 cost_cents,
                 tax_rate_bps as "tax_rate_bps: u32",
                 track_inventory as "track_inventory: bool",
                 allow_negative_stock as "allow_negative_stock: bool",
                 current_stock,
+                item_tracking as "item_tracking: ItemTracking",
+                min_purchase_age as "min_purchase_age: u32",
                 is_active as "is_active: bool",
+                deleted_at as "deleted_at: chrono::DateTime<Utc>",
                 created_at as "created_at: chrono::DateTime<Utc>",
                 updated_at as "updated_at: chrono::DateTime<Utc>",
                 sync_version
             FROM products
-            WHERE is_active = 1
+            WHERE is_active = 1 AND deleted_at IS NULL
             ORDER BY name
             LIMIT ?1
             "#,
@@ -179,6 +230,134 @@ impl ProductRepository {
         Ok(products)
     }
 
+    /// Searches active products one page at a time.
+    ///
+    /// Unlike [`search`](Self::search), results are ordered by name (then
+    /// ID) rather than relevance, so they can be paged with a keyset
+    /// cursor. An empty query pages through the whole active catalog.
+    ///
+    /// ## Errors
+    /// `DbError::InvalidInput` for a cursor not made by this method.
+    pub async fn search_page(&self, query: &str, page: &PageRequest) -> DbResult<Page<Product>> {
+        let query = query.trim();
+        let after = page.cursor_keys(2)?;
+        let (after_name, after_id) = match &after {
+            Some(keys) => (Some(keys[0].as_str()), Some(keys[1].as_str())),
+            None => (None, None),
+        };
+        let limit = page.fetch_limit();
+
+        debug!(query = %query, limit = %limit, paged = after.is_some(), "Searching products page");
+
+        let (rows, total) = if query.is_empty() {
+            let rows: Vec<Product> = sqlx::query_as!(
+                Product,
+                r#"
+                SELECT
+                    p.id,
+                    p.tenant_id,
+                    p.sku,
+                    p.barcode,
+                    p.name,
+                    p.description,
+                    p.price_cents,
+                    p.base_price_cents,
+                    p.cost_cents,
+                    p.tax_rate_bps as "tax_rate_bps: u32",
+                    p.track_inventory as "track_inventory: bool",
+                    p.allow_negative_stock as "allow_negative_stock: bool",
+                    p.current_stock,
+                    p.item_tracking as "item_tracking: ItemTracking",
+                    p.min_purchase_age as "min_purchase_age: u32",
+                    p.is_active as "is_active: bool",
+                    p.deleted_at as "deleted_at: chrono::DateTime<Utc>",
+                    p.created_at as "created_at: chrono::DateTime<Utc>",
+                    p.updated_at as "updated_at: chrono::DateTime<Utc>",
+                    p.sync_version
+                FROM products p
+                WHERE p.is_active = 1 AND p.deleted_at IS NULL
+                AND (?1 IS NULL OR p.name > ?1 OR (p.name = ?1 AND p.id > ?2))
+                ORDER BY p.name, p.id
+                LIMIT ?3
+                "#,
+                after_name,
+                after_id,
+                limit
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+            let total = if page.include_total {
+                Some(self.count().await?)
+            } else {
+                None
+            };
+            (rows, total)
+        } else {
+            let fts_query = format!("{}*", query);
+            let rows: Vec<Product> = sqlx::query_as!(
+                Product,
+                r#"
+                SELECT
+                    p.id,
+                    p.tenant_id,
+                    p.sku,
+                    p.barcode,
+                    p.name,
+                    p.description,
+                    p.price_cents,
+                    p.base_price_cents,
+                    p.cost_cents,
+                    p.tax_rate_bps as "tax_rate_bps: u32",
+                    p.track_inventory as "track_inventory: bool",
+                    p.allow_negative_stock as "allow_negative_stock: bool",
+                    p.current_stock,
+                    p.item_tracking as "item_tracking: ItemTracking",
+                    p.min_purchase_age as "min_purchase_age: u32",
+                    p.is_active as "is_active: bool",
+                    p.deleted_at as "deleted_at: chrono::DateTime<Utc>",
+                    p.created_at as "created_at: chrono::DateTime<Utc>",
+                    p.updated_at as "updated_at: chrono::DateTime<Utc>",
+                    p.sync_version
+                FROM products p
+                INNER JOIN products_fts fts ON p.rowid = fts.rowid
+                WHERE products_fts MATCH ?1
+                AND p.is_active = 1 AND p.deleted_at IS NULL
+                AND (?2 IS NULL OR p.name > ?2 OR (p.name = ?2 AND p.id > ?3))
+                ORDER BY p.name, p.id
+                LIMIT ?4
+                "#,
+                fts_query,
+                after_name,
+                after_id,
+                limit
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+            let total = if page.include_total {
+                let count: i64 = sqlx::query_scalar!(
+                    r#"
+                    SELECT COUNT(*) as "count!: i64"
+                    FROM products p
+                    INNER JOIN products_fts fts ON p.rowid = fts.rowid
+                    WHERE products_fts MATCH ?1
+                    AND p.is_active = 1 AND p.deleted_at IS NULL
+                    "#,
+                    fts_query
+                )
+                .fetch_one(&self.pool)
+                .await?;
+                Some(count)
+            } else {
+                None
+            };
+            (rows, total)
+        };
+
+        Ok(Page::from_rows(rows, page, |p| vec![p.name.clone(), p.id.clone()]).with_total(total))
+    }
+
     /// Gets a product by its ID.
     ///
     /// ## Arguments
@@ -199,12 +378,16 @@ impl ProductRepository {
                 name,
                 description,
                 price_cents,
+                base_price_cents,
                 cost_cents,
                 tax_rate_bps as "tax_rate_bps: u32",
                 track_inventory as "track_inventory: bool",
                 allow_negative_stock as "allow_negative_stock: bool",
                 current_stock,
+                item_tracking as "item_tracking: ItemTracking",
+                min_purchase_age as "min_purchase_age: u32",
                 is_active as "is_active: bool",
+                deleted_at as "deleted_at: chrono::DateTime<Utc>",
                 created_at as "created_at: chrono::DateTime<Utc>",
                 updated_at as "updated_at: chrono::DateTime<Utc>",
                 sync_version
@@ -219,6 +402,49 @@ impl ProductRepository {
         Ok(product)
     }
 
+    /// Lists products (active or not) with IDs after `after_id`, in ID order.
+    ///
+    /// Used to page through the whole catalog, e.g. for a full resync;
+    /// pass an empty string for the first page.
+    pub async fn list_after(&self, after_id: &str, limit: u32) -> DbResult<Vec<Product>> {
+        let products: Vec<Product> = sqlx::query_as!(
+            Product,
+            r#"
+            SELECT 
+                id,
+                tenant_id,
+                sku,
+                barcode,
+                name,
+                description,
+                price_cents,
+                base_price_cents,
+                cost_cents,
+                tax_rate_bps as "tax_rate_bps: u32",
+                track_inventory as "track_inventory: bool",
+                allow_negative_stock as "allow_negative_stock: bool",
+                current_stock,
+                item_tracking as "item_tracking: ItemTracking",
+                min_purchase_age as "min_purchase_age: u32",
+                is_active as "is_active: bool",
+                deleted_at as "deleted_at: chrono::DateTime<Utc>",
+                created_at as "created_at: chrono::DateTime<Utc>",
+                updated_at as "updated_at: chrono::DateTime<Utc>",
+                sync_version
+            FROM products
+            WHERE id > ?1
+            ORDER BY id
+            LIMIT ?2
+            "#,
+            after_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(products)
+    }
+
     /// Gets a product by its SKU.
     ///
     /// ## Arguments
@@ -239,12 +465,16 @@ impl ProductRepository {
                 name,
                 description,
                 price_cents,
+                base_price_cents,
                 cost_cents,
                 tax_rate_bps as "tax_rate_bps: u32",
                 track_inventory as "track_inventory: bool",
                 allow_negative_stock as "allow_negative_stock: bool",
                 current_stock,
+                item_tracking as "item_tracking: ItemTracking",
+                min_purchase_age as "min_purchase_age: u32",
                 is_active as "is_active: bool",
+                deleted_at as "deleted_at: chrono::DateTime<Utc>",
                 created_at as "created_at: chrono::DateTime<Utc>",
                 updated_at as "updated_at: chrono::DateTime<Utc>",
                 sync_version
@@ -284,17 +514,21 @@ impl ProductRepository {
                 name,
                 description,
                 price_cents,
+                base_price_cents,
                 cost_cents,
                 tax_rate_bps as "tax_rate_bps: u32",
                 track_inventory as "track_inventory: bool",
                 allow_negative_stock as "allow_negative_stock: bool",
                 current_stock,
+                item_tracking as "item_tracking: ItemTracking",
+                min_purchase_age as "min_purchase_age: u32",
                 is_active as "is_active: bool",
+                deleted_at as "deleted_at: chrono::DateTime<Utc>",
                 created_at as "created_at: chrono::DateTime<Utc>",
                 updated_at as "updated_at: chrono::DateTime<Utc>",
                 sync_version
             FROM products
-            WHERE barcode = ?1 AND is_active = 1
+            WHERE barcode = ?1 AND is_active = 1 AND deleted_at IS NULL
             "#,
             barcode
         )
@@ -321,12 +555,14 @@ impl ProductRepository {
                 id, tenant_id, sku, barcode, name, description,
                 price_cents, cost_cents, tax_rate_bps,
                 track_inventory, allow_negative_stock, current_stock,
-                is_active, created_at, updated_at, sync_version
+                is_active, created_at, updated_at, sync_version,
+                item_tracking, min_purchase_age, deleted_at, base_price_cents
             ) VALUES (
                 ?1, ?2, ?3, ?4, ?5, ?6,
                 ?7, ?8, ?9,
                 ?10, ?11, ?12,
-                ?13, ?14, ?15, ?16
+                ?13, ?14, ?15, ?16,
+                ?17, ?18, ?19, ?20
             )
             "#,
             product.id,
@@ -344,24 +580,33 @@ impl ProductRepository {
             product.is_active,
             product.created_at,
             product.updated_at,
-            product.sync_version
+            product.sync_version,
+            product.item_tracking,
+            product.min_purchase_age,
+            product.deleted_at,
+            product.base_price_cents
         )
         .execute(&self.pool)
         .await?;
 
+        self.publish_change(&product.id, ProductChange::Created, product.sync_version);
+
         // Return the product as-is (it already has all fields)
         Ok(product.clone())
     }
 
-    /// Updates an existing product.
+    /// Updates an existing product if it is still at the version it was
+    /// read at (see [optimistic locking](crate::repository)).
     ///
     /// ## Arguments
-    /// * `product` - Product with updated fields
+    /// * `product` - Product with updated fields; `sync_version` is the
+    ///   version it was loaded at
     ///
     /// ## Returns
-    /// * `Ok(())` - Update successful
+    /// * `Ok(version)` - Update successful, the product's new version
     /// * `Err(DbError::NotFound)` - Product doesn't exist
-    pub async fn update(&self, product: &Product) -> DbResult<()> {
+    /// * `Err(DbError::VersionConflict)` - Product changed since it was read
+    pub async fn update(&self, product: &Product) -> DbResult<i64> {
         debug!(id = %product.id, "Updating product");
 
         let now = Utc::now();
@@ -381,8 +626,11 @@ impl ProductRepository {
                 current_stock = ?11,
                 is_active = ?12,
                 updated_at = ?13,
+                item_tracking = ?14,
+                min_purchase_age = ?15,
+                base_price_cents = ?17,
                 sync_version = sync_version + 1
-            WHERE id = ?1
+            WHERE id = ?1 AND sync_version = ?16
             "#,
             product.id,
             product.sku,
@@ -396,16 +644,66 @@ impl ProductRepository {
             product.allow_negative_stock,
             product.current_stock,
             product.is_active,
-            now
+            now,
+            product.item_tracking,
+            product.min_purchase_age,
+            product.sync_version,
+            product.base_price_cents
         )
         .execute(&self.pool)
         .await?;
 
         if result.rows_affected() == 0 {
-            return Err(DbError::not_found("Product", &product.id));
+            return Err(self.write_failed(&product.id, product.sync_version).await);
         }
 
-        Ok(())
+        let version = product.sync_version + 1;
+        self.publish_change(&product.id, ProductChange::Updated, version);
+        Ok(version)
+    }
+
+    /// Why a versioned write matched no row: the product is gone, or it
+    /// has moved past `expected`.
+    async fn write_failed(&self, id: &str, expected: i64) -> DbError {
+        let actual = sqlx::query_scalar!("SELECT sync_version FROM products WHERE id = ?1", id)
+            .fetch_optional(&self.pool)
+            .await;
+        match actual {
+            Ok(Some(actual)) => DbError::version_conflict("Product", id, expected, actual),
+            Ok(None) => DbError::not_found("Product", id),
+            Err(e) => e.into(),
+        }
+    }
+
+    /// Saves a tracked edit and queues only the changed fields for sync.
+    ///
+    /// The outbox entry (`PRODUCT_PATCH`) carries an [`EntityPatch`] based
+    /// on the version the product was loaded at; a later edit made before
+    /// it syncs is folded into the same entry.
+    ///
+    /// ## Returns
+    /// * `Ok(Some(patch))` - Product saved, patch queued
+    /// * `Ok(None)` - Nothing changed, nothing written
+    /// * `Err(DbError::NotFound)` - Product doesn't exist
+    /// * `Err(DbError::VersionConflict)` - Product changed since it was
+    ///   loaded; nothing written or queued
+    pub async fn update_tracked(&self, product: &Tracked<Product>) -> DbResult<Option<EntityPatch>> {
+        let original = product.original();
+        let patch = product
+            .patch(original.sync_version, Utc::now())
+            .map_err(|e| DbError::Internal(format!("Failed to diff product: {}", e)))?;
+
+        let Some(patch) = patch else {
+            debug!(id = %original.id, "Product unchanged, skipping update");
+            return Ok(None);
+        };
+
+        self.update(product.current()).await?;
+        SyncOutboxRepository::new(self.pool.clone())
+            .queue_patch("PRODUCT_PATCH", &original.id, &patch)
+            .await?;
+
+        Ok(Some(patch))
     }
 
     /// Updates product stock level.
@@ -436,7 +734,7 @@ impl ProductRepository {
 
         let now = Utc::now();
 
-        let result: sqlx::sqlite::SqliteQueryResult = sqlx::query!(
+        let row = sqlx::query!(
             r#"
             UPDATE products 
             SET 
@@ -444,64 +742,528 @@ impl ProductRepository {
                 updated_at = ?3,
                 sync_version = sync_version + 1
             WHERE id = ?1
+            RETURNING current_stock
             "#,
             id,
             delta,
             now
         )
-        .execute(&self.pool)
+        .fetch_optional(&self.pool)
         .await?;
 
-        if result.rows_affected() == 0 {
+        let Some(row) = row else {
             return Err(DbError::not_found("Product", id));
-        }
+        };
 
+        self.events.publish(EntityEvent::StockChanged {
+            product_id: id.to_string(),
+            delta: delta.into(),
+            stock: row.current_stock,
+        });
         Ok(())
     }
 
-    /// Soft-deletes a product by setting is_active = false.
+    /// A product's stock movements since `since` (all of them when
+    /// `None`), totalled by delta type.
+    pub async fn stock_ledger(
+        &self,
+        id: &str,
+        since: Option<DateTime<Utc>>,
+    ) -> DbResult<Vec<StockLedgerLine>> {
+        // occurred_at is written both by SQLite (datetime('now')) and by
+        // chrono, so both sides are normalized before comparing
+        let lines = sqlx::query_as!(
+            StockLedgerLine,
+            r#"
+            SELECT
+                delta_type as "delta_type!",
+                COUNT(*) as "movements!: i64",
+                COALESCE(SUM(delta), 0) as "total!: i64"
+            FROM inventory_deltas
+            WHERE product_id = ?1
+              AND (?2 IS NULL OR datetime(occurred_at) > datetime(?2))
+            GROUP BY delta_type
+            ORDER BY delta_type
+            "#,
+            id,
+            since
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(lines)
+    }
+
+    /// Sets a tracked product's stock to `opening` plus every movement in
+    /// the ledger since `since`, e.g. the last physical count and when it
+    /// was taken. Returns the new stock.
+    ///
+    /// Only movements are in the ledger: stock set outright (catalog
+    /// edits, a hub resync) after `since` is replaced.
+    pub async fn recompute_stock(
+        &self,
+        id: &str,
+        opening: i64,
+        since: Option<DateTime<Utc>>,
+    ) -> DbResult<i64> {
+        let product = self
+            .get_by_id(id)
+            .await?
+            .ok_or_else(|| DbError::not_found("Product", id))?;
+        if !product.track_inventory {
+            return Err(DbError::Internal(format!(
+                "Product {} does not track inventory",
+                product.sku
+            )));
+        }
+
+        let moved: i64 = self
+            .stock_ledger(id, since)
+            .await?
+            .iter()
+            .map(|line| line.total)
+            .sum();
+        let stock = opening + moved;
+        let now = Utc::now();
+
+        sqlx::query!(
+            r#"
+            UPDATE products
+            SET
+                current_stock = ?2,
+                updated_at = ?3,
+                sync_version = sync_version + 1
+            WHERE id = ?1
+            "#,
+            id,
+            stock,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+
+        let previous = product.current_stock.unwrap_or(0);
+        debug!(id = %id, previous, stock, "Stock recomputed from ledger");
+        self.events.publish(EntityEvent::StockChanged {
+            product_id: id.to_string(),
+            delta: stock - previous,
+            stock: Some(stock),
+        });
+        Ok(stock)
+    }
+
+    /// Soft-deletes a product and queues the tombstone for sync.
     ///
     /// ## Why Soft Delete?
     /// - Historical sales still reference this product
     /// - Can be restored if deleted by mistake
     /// - Sync can propagate the deletion
-    pub async fn soft_delete(&self, id: &str) -> DbResult<()> {
-        debug!(id = %id, "Soft-deleting product");
+    ///
+    /// ## Errors
+    /// `DbError::NotFound` if the product doesn't exist or is already
+    /// deleted.
+    pub async fn soft_delete(&self, id: &str) -> DbResult<Tombstone> {
+        self.set_deleted(id, TombstoneAction::Delete).await
+    }
+
+    /// Restores a soft-deleted product and queues the tombstone for sync.
+    ///
+    /// ## Errors
+    /// `DbError::NotFound` if the product doesn't exist or isn't deleted.
+    pub async fn restore(&self, id: &str) -> DbResult<Tombstone> {
+        self.set_deleted(id, TombstoneAction::Restore).await
+    }
+
+    async fn set_deleted(&self, id: &str, action: TombstoneAction) -> DbResult<Tombstone> {
+        debug!(id = %id, ?action, "Changing product deletion");
 
         let now = Utc::now();
+        let deleted = action == TombstoneAction::Delete;
 
-        let result: sqlx::sqlite::SqliteQueryResult = sqlx::query!(
+        let version: Option<i64> = sqlx::query_scalar!(
             r#"
-            UPDATE products 
-            SET 
-                is_active = 0,
-                updated_at = ?2,
+            UPDATE products
+            SET
+                deleted_at = CASE WHEN ?2 THEN ?3 ELSE NULL END,
+                updated_at = ?3,
                 sync_version = sync_version + 1
-            WHERE id = ?1
+            WHERE id = ?1 AND (deleted_at IS NULL) = ?2
+            RETURNING sync_version
             "#,
             id,
+            deleted,
             now
         )
-        .execute(&self.pool)
+        .fetch_optional(&self.pool)
         .await?;
 
-        if result.rows_affected() == 0 {
+        let Some(version) = version else {
             return Err(DbError::not_found("Product", id));
+        };
+
+        let tombstone = Tombstone::new("product", id, action, version, now);
+        SyncOutboxRepository::new(self.pool.clone())
+            .queue_tombstone(&tombstone)
+            .await?;
+
+        self.publish_change(id, deletion_change(action), version);
+        Ok(tombstone)
+    }
+
+    /// Applies a tombstone received through sync.
+    ///
+    /// ## Returns
+    /// * `Ok(true)` - Applied
+    /// * `Ok(false)` - Product unknown, or already at or past the
+    ///   tombstone's version
+    pub async fn apply_tombstone(&self, tombstone: &Tombstone) -> DbResult<bool> {
+        let deleted_at = tombstone.deleted_at();
+        let result = sqlx::query!(
+            r#"
+            UPDATE products
+            SET
+                deleted_at = ?2,
+                updated_at = ?3,
+                sync_version = ?4
+            WHERE id = ?1 AND sync_version < ?4
+            "#,
+            tombstone.entity_id,
+            deleted_at,
+            tombstone.at,
+            tombstone.version
+        )
+        .execute(&self.pool)
+        .await?;
+
+        let applied = result.rows_affected() > 0;
+        if applied {
+            self.publish_change(&tombstone.entity_id, deletion_change(tombstone.action), tombstone.version);
         }
+        Ok(applied)
+    }
 
-        Ok(())
+    /// Lists deleted products, most recently deleted first.
+    pub async fn list_deleted(&self, limit: u32) -> DbResult<Vec<Product>> {
+        let products: Vec<Product> = sqlx::query_as!(
+            Product,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                sku,
+                barcode,
+                name,
+                description,
+                price_cents,
+                base_price_cents,
+                cost_cents,
+                tax_rate_bps as "tax_rate_bps: u32",
+                track_inventory as "track_inventory: bool",
+                allow_negative_stock as "allow_negative_stock: bool",
+                current_stock,
+                item_tracking as "item_tracking: ItemTracking",
+                min_purchase_age as "min_purchase_age: u32",
+                is_active as "is_active: bool",
+                deleted_at as "deleted_at: chrono::DateTime<Utc>",
+                created_at as "created_at: chrono::DateTime<Utc>",
+                updated_at as "updated_at: chrono::DateTime<Utc>",
+                sync_version
+            FROM products
+            WHERE deleted_at IS NOT NULL
+            ORDER BY deleted_at DESC
+            LIMIT ?1
+            "#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(products)
+    }
+
+    // =========================================================================
+    // Attributes
+    // =========================================================================
+
+    /// Gets a product's attributes and tags.
+    ///
+    /// ## Errors
+    /// `DbError::NotFound` if the product doesn't exist.
+    pub async fn attributes(&self, product_id: &str) -> DbResult<ProductAttributes> {
+        let header = sqlx::query!(
+            r#"
+            SELECT
+                attributes_version as "version!: i64",
+                attributes_updated_at as "updated_at: chrono::DateTime<Utc>",
+                created_at as "created_at!: chrono::DateTime<Utc>"
+            FROM products
+            WHERE id = ?1
+            "#,
+            product_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| DbError::not_found("Product", product_id))?;
+
+        let attributes = sqlx::query_as!(
+            ProductAttribute,
+            r#"
+            SELECT key, value
+            FROM product_attributes
+            WHERE product_id = ?1
+            ORDER BY key
+            "#,
+            product_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ProductAttributes {
+            product_id: product_id.to_string(),
+            attributes,
+            sync_version: header.version,
+            updated_at: header.updated_at.unwrap_or(header.created_at),
+        })
+    }
+
+    /// Replaces a product's attributes and tags, and queues the new set
+    /// for sync.
+    ///
+    /// The set is normalized first (see
+    /// `titan_core::attribute::normalize_attributes`); an empty set clears
+    /// the product's attributes.
+    ///
+    /// ## Errors
+    /// - `DbError::InvalidInput` for an invalid key or value
+    /// - `DbError::NotFound` if the product doesn't exist or is deleted
+    pub async fn set_attributes(
+        &self,
+        product_id: &str,
+        attributes: &[ProductAttribute],
+    ) -> DbResult<ProductAttributes> {
+        let attributes = normalize_attributes(attributes)?;
+        let now = Utc::now();
+
+        debug!(product_id = %product_id, count = attributes.len(), "Setting product attributes");
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let versions = sqlx::query!(
+            r#"
+            UPDATE products
+            SET
+                attributes_version = attributes_version + 1,
+                attributes_updated_at = ?2
+            WHERE id = ?1 AND deleted_at IS NULL
+            RETURNING attributes_version as "attributes_version!: i64", sync_version
+            "#,
+            product_id,
+            now
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| DbError::not_found("Product", product_id))?;
+
+        replace_attributes(&mut tx, product_id, &attributes).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let doc = ProductAttributes {
+            product_id: product_id.to_string(),
+            attributes,
+            sync_version: versions.attributes_version,
+            updated_at: now,
+        };
+        let payload = serde_json::to_string(&doc)
+            .map_err(|e| DbError::Internal(format!("Failed to serialize attributes: {}", e)))?;
+        SyncOutboxRepository::new(self.pool.clone())
+            .upsert_for_sync("PRODUCT_ATTRIBUTES", product_id, &payload)
+            .await?;
+
+        self.publish_change(product_id, ProductChange::Updated, versions.sync_version);
+        Ok(doc)
+    }
+
+    /// Applies an attribute set received through sync.
+    ///
+    /// ## Returns
+    /// * `Ok(true)` - Applied
+    /// * `Ok(false)` - Product unknown, or its set is already at or past
+    ///   the document's version
+    pub async fn upsert_attributes_from_sync(&self, doc: &ProductAttributes) -> DbResult<bool> {
+        let attributes = normalize_attributes(&doc.attributes)?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let version: Option<i64> = sqlx::query_scalar!(
+            r#"
+            UPDATE products
+            SET
+                attributes_version = ?2,
+                attributes_updated_at = ?3
+            WHERE id = ?1 AND attributes_version < ?2
+            RETURNING sync_version
+            "#,
+            doc.product_id,
+            doc.sync_version,
+            doc.updated_at
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(version) = version else {
+            return Ok(false);
+        };
+
+        replace_attributes(&mut tx, &doc.product_id, &attributes).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        self.publish_change(&doc.product_id, ProductChange::Updated, version);
+        Ok(true)
+    }
+
+    /// Searches active products by attribute, e.g. every `color=red`
+    /// product or every product tagged `organic`, ordered by name.
+    pub async fn search_by_attribute(
+        &self,
+        filter: &AttributeFilter,
+        limit: u32,
+    ) -> DbResult<Vec<Product>> {
+        debug!(key = %filter.key, value = ?filter.value, limit = %limit, "Searching products by attribute");
+
+        let products: Vec<Product> = sqlx::query_as!(
+            Product,
+            r#"
+            SELECT
+                p.id,
+                p.tenant_id,
+                p.sku,
+                p.barcode,
+                p.name,
+                p.description,
+                p.price_cents,
+                p.base_price_cents,
+                p.cost_cents,
+                p.tax_rate_bps as "tax_rate_bps: u32",
+                p.track_inventory as "track_inventory: bool",
+                p.allow_negative_stock as "allow_negative_stock: bool",
+                p.current_stock,
+                p.item_tracking as "item_tracking: ItemTracking",
+                p.min_purchase_age as "min_purchase_age: u32",
+                p.is_active as "is_active: bool",
+                p.deleted_at as "deleted_at: chrono::DateTime<Utc>",
+                p.created_at as "created_at: chrono::DateTime<Utc>",
+                p.updated_at as "updated_at: chrono::DateTime<Utc>",
+                p.sync_version
+            FROM product_attributes a
+            JOIN products p ON p.id = a.product_id
+            WHERE a.key = ?1
+              AND (?2 IS NULL OR a.value = ?2 COLLATE NOCASE)
+              AND p.is_active = 1
+              AND p.deleted_at IS NULL
+            ORDER BY p.name, p.id
+            LIMIT ?3
+            "#,
+            filter.key,
+            filter.value,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(products)
+    }
+
+    /// Creates (or renames and re-taxes) the product that carries a
+    /// department's sale lines, and returns its ID.
+    ///
+    /// The product is inactive and untracked, so it never shows up in
+    /// search or moves stock; it exists so department lines can be sale
+    /// items like any other (see `titan_core::department`).
+    pub async fn ensure_department_product(&self, line: &DepartmentLine) -> DbResult<String> {
+        let id = department_product_id(&line.department_code);
+        let sku = format!("DEPT-{}", line.department_code);
+        let now = Utc::now();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO products (
+                id, tenant_id, sku, name, price_cents, tax_rate_bps,
+                track_inventory, allow_negative_stock, is_active,
+                created_at, updated_at, sync_version
+            ) VALUES (?1, ?2, ?3, ?4, 0, ?5, 0, 1, 0, ?6, ?6, 1)
+            ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                tax_rate_bps = excluded.tax_rate_bps,
+                updated_at = excluded.updated_at
+            WHERE products.name <> excluded.name
+               OR products.tax_rate_bps <> excluded.tax_rate_bps
+            "#,
+            id,
+            DEFAULT_TENANT_ID,
+            sku,
+            line.name,
+            line.tax_rate_bps,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(id)
     }
 
     /// Counts total products (for diagnostics).
     pub async fn count(&self) -> DbResult<i64> {
-        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM products WHERE is_active = 1")
-            .fetch_one(&self.pool)
-            .await?;
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM products WHERE is_active = 1 AND deleted_at IS NULL",
+        )
+        .fetch_one(&self.pool)
+        .await?;
 
         Ok(count)
     }
 }
 
+/// Replaces the attribute rows of `product_id` with `attributes` inside
+/// `tx`.
+async fn replace_attributes(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    product_id: &str,
+    attributes: &[ProductAttribute],
+) -> DbResult<()> {
+    sqlx::query!(
+        "DELETE FROM product_attributes WHERE product_id = ?1",
+        product_id
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    for attribute in attributes {
+        sqlx::query!(
+            "INSERT INTO product_attributes (product_id, key, value) VALUES (?1, ?2, ?3)",
+            product_id,
+            attribute.key,
+            attribute.value
+        )
+        .execute(&mut **tx)
+        .await?;
+    }
+    Ok(())
+}
+
 /// Helper to generate a new product ID.
 ///
 /// ## Usage
@@ -512,3 +1274,234 @@ impl ProductRepository {
 pub fn generate_product_id() -> String {
     Uuid::new_v4().to_string()
 }
+
+/// The product change a tombstone makes.
+fn deletion_change(action: TombstoneAction) -> ProductChange {
+    match action {
+        TombstoneAction::Delete => ProductChange::Deleted,
+        TombstoneAction::Restore => ProductChange::Restored,
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::error::DbError;
+    use crate::events::EventPublisher;
+    use crate::fixtures::{Fixtures, ProductFixture, SaleFixture};
+    use crate::pool::{Database, DbConfig};
+    use chrono::{Duration, Utc};
+    use titan_core::{
+        AttributeFilter, BusinessCustomer, EntityEvent, PageRequest, ProductAttribute,
+        ProductChange, Tombstone, TombstoneAction, DEFAULT_TENANT_ID,
+    };
+
+    #[tokio::test]
+    async fn test_soft_delete_and_restore() {
+        let config = DbConfig::in_memory().with_fixtures(Fixtures::new().products(3));
+        let db = Database::new(config).await.unwrap();
+        let product = db.products().search("", 1).await.unwrap().remove(0);
+
+        let deleted = db.products().soft_delete(&product.id).await.unwrap();
+        assert_eq!(deleted.version, product.sync_version + 1);
+        assert_eq!(db.products().count().await.unwrap(), 2);
+        assert!(db
+            .products()
+            .search_page("", &PageRequest::first(10))
+            .await
+            .unwrap()
+            .items
+            .iter()
+            .all(|p| p.id != product.id));
+        assert_eq!(db.products().list_deleted(10).await.unwrap().len(), 1);
+        // Already deleted
+        assert!(db.products().soft_delete(&product.id).await.is_err());
+
+        let restored = db.products().restore(&product.id).await.unwrap();
+        assert_eq!(db.products().count().await.unwrap(), 3);
+        // The restore replaced the delete in the outbox
+        let pending = db.sync_outbox().get_pending(10).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert!(pending[0].payload.contains("restore"));
+
+        // A stale tombstone from another terminal is ignored
+        assert!(!db.products().apply_tombstone(&deleted).await.unwrap());
+        let newer = Tombstone::new(
+            "product",
+            &product.id,
+            TombstoneAction::Delete,
+            restored.version + 1,
+            Utc::now(),
+        );
+        assert!(db.products().apply_tombstone(&newer).await.unwrap());
+        assert_eq!(db.products().count().await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_stale_writes_conflict() {
+        let config = DbConfig::in_memory().with_fixtures(Fixtures::new().products(1));
+        let db = Database::new(config).await.unwrap();
+        let loaded = db.products().search("", 1).await.unwrap().remove(0);
+
+        let mut first = loaded.clone();
+        first.price_cents += 100;
+        assert_eq!(
+            db.products().update(&first).await.unwrap(),
+            loaded.sync_version + 1
+        );
+
+        // Edited from the same stale copy
+        let mut second = loaded.clone();
+        second.name = "Renamed".to_string();
+        assert!(matches!(
+            db.products().update(&second).await,
+            Err(DbError::VersionConflict { expected, actual, .. })
+                if expected == loaded.sync_version && actual == loaded.sync_version + 1
+        ));
+
+        let customer = BusinessCustomer {
+            id: "c-1".to_string(),
+            tenant_id: DEFAULT_TENANT_ID.to_string(),
+            name: "Acme".to_string(),
+            tax_id: "GB123456789".to_string(),
+            address_line: None,
+            city: None,
+            postal_code: None,
+            country_code: "GB".to_string(),
+            email: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            deleted_at: None,
+            sync_version: 0,
+        };
+        let customers = db.business_customers();
+        assert_eq!(customers.save(&customer).await.unwrap(), 0);
+        assert_eq!(customers.save(&customer).await.unwrap(), 1);
+        assert!(matches!(
+            customers.save(&customer).await,
+            Err(DbError::VersionConflict { actual: 1, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_writes_publish_events() {
+        let (tx, mut rx) = tokio::sync::broadcast::channel(16);
+        let config = DbConfig::in_memory().with_fixtures(Fixtures::new().products(1));
+        let db = Database::new(config)
+            .await
+            .unwrap()
+            .with_events(EventPublisher::new(tx));
+        let product = db.products().search("", 1).await.unwrap().remove(0);
+        let stock = product.current_stock;
+
+        db.products().update_stock(&product.id, -2).await.unwrap();
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            EntityEvent::StockChanged {
+                product_id: product.id.clone(),
+                delta: -2,
+                stock: Some(stock.unwrap_or(0) - 2),
+            }
+        );
+
+        let tombstone = db.products().soft_delete(&product.id).await.unwrap();
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            EntityEvent::ProductChanged {
+                product_id: product.id.clone(),
+                change: ProductChange::Deleted,
+                version: tombstone.version,
+            }
+        );
+
+        // A failed write publishes nothing
+        assert!(db.products().soft_delete(&product.id).await.is_err());
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_attributes_replace_search_and_sync() {
+        let config = DbConfig::in_memory().with_fixtures(Fixtures::new().products(3));
+        let db = Database::new(config).await.unwrap();
+        let products = db.products().search("", 3).await.unwrap();
+        let (wine, other) = (&products[0], &products[1]);
+
+        let saved = db
+            .products()
+            .set_attributes(
+                &wine.id,
+                &[
+                    ProductAttribute::new("Color", "Red"),
+                    ProductAttribute::tag("organic"),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(saved.sync_version, 1);
+        assert_eq!(saved.get("color"), Some("Red"));
+        assert_eq!(db.products().attributes(&wine.id).await.unwrap(), saved);
+
+        let red = AttributeFilter::parse("color=red").unwrap();
+        let found = db.products().search_by_attribute(&red, 10).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, wine.id);
+        let organic = AttributeFilter::parse("organic").unwrap();
+        assert_eq!(db.products().search_by_attribute(&organic, 10).await.unwrap().len(), 1);
+
+        // Replacing the set drops what it leaves out
+        db.products()
+            .set_attributes(&wine.id, &[ProductAttribute::new("color", "white")])
+            .await
+            .unwrap();
+        assert!(db.products().search_by_attribute(&red, 10).await.unwrap().is_empty());
+        let pending = db.sync_outbox().get_pending(10).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].entity_type, "PRODUCT_ATTRIBUTES");
+        assert!(pending[0].payload.contains("white"));
+
+        // Sync applies newer sets only
+        let mut incoming = saved.clone();
+        incoming.product_id = other.id.clone();
+        assert!(db.products().upsert_attributes_from_sync(&incoming).await.unwrap());
+        assert!(!db.products().upsert_attributes_from_sync(&incoming).await.unwrap());
+        assert_eq!(db.products().search_by_attribute(&red, 10).await.unwrap()[0].id, other.id);
+
+        assert!(db
+            .products()
+            .set_attributes(&wine.id, &[ProductAttribute::new("shelf life", "1y")])
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stock_recomputed_from_ledger() {
+        let db = Database::new(DbConfig::in_memory()).await.unwrap();
+        let cola = ProductFixture::new("COLA").stock(10).insert(&db).await.unwrap();
+        SaleFixture::new().line(&cola, 2).insert(&db).await.unwrap();
+        SaleFixture::new().line(&cola, 1).insert(&db).await.unwrap();
+
+        let ledger = db.products().stock_ledger(&cola.id, None).await.unwrap();
+        assert_eq!(
+            ledger,
+            vec![crate::StockLedgerLine {
+                delta_type: "sale".to_string(),
+                movements: 2,
+                total: -3,
+            }]
+        );
+
+        // Counted 12 before both sales: 12 - 3
+        let stock = db.products().recompute_stock(&cola.id, 12, None).await.unwrap();
+        assert_eq!(stock, 9);
+        let stored = db.products().get_by_id(&cola.id).await.unwrap().unwrap();
+        assert_eq!(stored.current_stock, Some(9));
+
+        // Counted after both sales: nothing to add
+        let later = Utc::now() + Duration::minutes(1);
+        assert!(db.products().stock_ledger(&cola.id, Some(later)).await.unwrap().is_empty());
+        assert_eq!(db.products().recompute_stock(&cola.id, 7, Some(later)).await.unwrap(), 7);
+    }
+}
@@ -0,0 +1,554 @@
+//! # Quote Repository
+//!
+//! Database operations for quotes (estimates) and their conversion to sales.
+//!
+//! ## Conversion Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                     Quote → Sale Conversion                             │
+//! │                                                                         │
+//! │  convert_to_sale(quote_id, sale, items)                                │
+//! │       │                                                                 │
+//! │       ▼                                                                 │
+//! │  ┌─────────────────────────────────────────────────────────────────┐   │
+//! │  │                   SINGLE TRANSACTION                            │   │
+//! │  │                                                                 │   │
+//! │  │  1. UPDATE quotes SET status = 'converted', converted_sale_id  │   │
+//! │  │     WHERE id = ? AND status = 'open'                           │   │
+//! │  │     (0 rows → someone else converted it first → abort)         │   │
+//! │  │                                                                 │   │
+//! │  │  2. INSERT INTO sales (draft, quoted totals)                   │   │
+//! │  │                                                                 │   │
+//! │  │  3. INSERT INTO sale_items (quoted unit prices)                │   │
+//! │  └─────────────────────────────────────────────────────────────────┘   │
+//! │                                                                         │
+//! │  The resulting draft sale is paid and finalized through the normal     │
+//! │  add_payment / finalize_sale flow.                                     │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::{debug, info};
+
+use crate::error::{DbError, DbResult};
+use crate::pii::{PiiCipher, PiiField};
+use titan_core::{Quote, QuoteDocument, QuoteItem, QuoteStatus, Sale, SaleItem, SaleStatus};
+
+/// Repository for quote database operations.
+#[derive(Debug, Clone)]
+pub struct QuoteRepository {
+    pool: SqlitePool,
+    pii: PiiCipher,
+}
+
+impl QuoteRepository {
+    /// Creates a new QuoteRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        QuoteRepository {
+            pool,
+            pii: PiiCipher::disabled(),
+        }
+    }
+
+    /// Encrypts customer emails with `pii` (see [`crate::pii`]).
+    pub fn with_pii(mut self, pii: PiiCipher) -> Self {
+        self.pii = pii;
+        self
+    }
+
+    /// Decrypts a quote's customer email.
+    fn reveal(&self, mut quote: Quote) -> DbResult<Quote> {
+        quote.customer_email = self.pii.decrypt_opt(PiiField::Email, quote.customer_email)?;
+        Ok(quote)
+    }
+
+    /// Inserts a quote and its items in a single transaction.
+    pub async fn insert(&self, doc: &QuoteDocument) -> DbResult<()> {
+        let quote = &doc.quote;
+        debug!(id = %quote.id, quote_number = %quote.quote_number, "Inserting quote");
+        let email = quote.customer_email.as_deref();
+        let email_enc = self.pii.encrypt_opt(PiiField::Email, email)?;
+        let email_hash = self.pii.blind_index_opt(PiiField::Email, email);
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO quotes (
+                id, tenant_id, quote_number, status,
+                customer_name, customer_email, customer_email_hash,
+                subtotal_cents, tax_cents, discount_cents, total_cents,
+                user_id, device_id, notes,
+                valid_until, converted_sale_id,
+                created_at, updated_at, sync_version
+            ) VALUES (
+                ?1, ?2, ?3, ?4,
+                ?5, ?6, ?19,
+                ?7, ?8, ?9, ?10,
+                ?11, ?12, ?13,
+                ?14, ?15,
+                ?16, ?17, ?18
+            )
+            "#,
+            quote.id,
+            quote.tenant_id,
+            quote.quote_number,
+            quote.status,
+            quote.customer_name,
+            email_enc,
+            quote.subtotal_cents,
+            quote.tax_cents,
+            quote.discount_cents,
+            quote.total_cents,
+            quote.user_id,
+            quote.device_id,
+            quote.notes,
+            quote.valid_until,
+            quote.converted_sale_id,
+            quote.created_at,
+            quote.updated_at,
+            quote.sync_version,
+            email_hash
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for item in &doc.items {
+            insert_item(&mut tx, item).await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Gets a quote by ID.
+    pub async fn get_by_id(&self, id: &str) -> DbResult<Option<Quote>> {
+        let quote = sqlx::query_as!(
+            Quote,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                quote_number,
+                status as "status: QuoteStatus",
+                customer_name,
+                customer_email,
+                subtotal_cents,
+                tax_cents,
+                discount_cents,
+                total_cents,
+                user_id,
+                device_id,
+                notes,
+                valid_until as "valid_until: DateTime<Utc>",
+                converted_sale_id,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                sync_version
+            FROM quotes
+            WHERE id = ?1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        quote.map(|q| self.reveal(q)).transpose()
+    }
+
+    /// Gets a quote by its printed quote number.
+    pub async fn get_by_number(&self, quote_number: &str) -> DbResult<Option<Quote>> {
+        let quote = sqlx::query_as!(
+            Quote,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                quote_number,
+                status as "status: QuoteStatus",
+                customer_name,
+                customer_email,
+                subtotal_cents,
+                tax_cents,
+                discount_cents,
+                total_cents,
+                user_id,
+                device_id,
+                notes,
+                valid_until as "valid_until: DateTime<Utc>",
+                converted_sale_id,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                sync_version
+            FROM quotes
+            WHERE quote_number = ?1
+            "#,
+            quote_number
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        quote.map(|q| self.reveal(q)).transpose()
+    }
+
+    /// Gets all items for a quote.
+    pub async fn get_items(&self, quote_id: &str) -> DbResult<Vec<QuoteItem>> {
+        let items = sqlx::query_as!(
+            QuoteItem,
+            r#"
+            SELECT
+                id,
+                quote_id,
+                product_id,
+                sku_snapshot,
+                name_snapshot,
+                unit_price_cents,
+                tax_rate_bps as "tax_rate_bps: u32",
+                quantity,
+                line_total_cents,
+                tax_cents,
+                created_at as "created_at: DateTime<Utc>"
+            FROM quote_items
+            WHERE quote_id = ?1
+            ORDER BY created_at, id
+            "#,
+            quote_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(items)
+    }
+
+    /// Gets a quote together with its items.
+    pub async fn get_document(&self, id: &str) -> DbResult<Option<QuoteDocument>> {
+        let Some(quote) = self.get_by_id(id).await? else {
+            return Ok(None);
+        };
+        let items = self.get_items(&quote.id).await?;
+
+        Ok(Some(QuoteDocument { quote, items }))
+    }
+
+    /// Lists quotes, newest first, optionally filtered by status.
+    pub async fn list(&self, status: Option<QuoteStatus>, limit: u32) -> DbResult<Vec<Quote>> {
+        let quotes = sqlx::query_as!(
+            Quote,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                quote_number,
+                status as "status: QuoteStatus",
+                customer_name,
+                customer_email,
+                subtotal_cents,
+                tax_cents,
+                discount_cents,
+                total_cents,
+                user_id,
+                device_id,
+                notes,
+                valid_until as "valid_until: DateTime<Utc>",
+                converted_sale_id,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                sync_version
+            FROM quotes
+            WHERE ?1 IS NULL OR status = ?1
+            ORDER BY created_at DESC
+            LIMIT ?2
+            "#,
+            status,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        quotes.into_iter().map(|q| self.reveal(q)).collect()
+    }
+
+    /// Cancels an open quote.
+    pub async fn cancel(&self, quote_id: &str) -> DbResult<()> {
+        let now = Utc::now();
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE quotes SET
+                status = 'cancelled',
+                updated_at = ?2,
+                sync_version = sync_version + 1
+            WHERE id = ?1 AND status = 'open'
+            "#,
+            quote_id,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DbError::not_found("Quote (open)", quote_id));
+        }
+
+        Ok(())
+    }
+
+    /// Converts an open quote into a draft sale (see module docs).
+    ///
+    /// ## Errors
+    /// `DbError::NotFound` if the quote is no longer open, e.g. because it
+    /// was converted on another terminal first.
+    pub async fn convert_to_sale(
+        &self,
+        quote_id: &str,
+        sale: &Sale,
+        items: &[SaleItem],
+    ) -> DbResult<()> {
+        debug_assert_eq!(sale.status, SaleStatus::Draft);
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE quotes SET
+                status = 'converted',
+                converted_sale_id = ?2,
+                updated_at = ?3,
+                sync_version = sync_version + 1
+            WHERE id = ?1 AND status = 'open'
+            "#,
+            quote_id,
+            sale.id,
+            sale.created_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DbError::not_found("Quote (open)", quote_id));
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO sales (
+                id, tenant_id, receipt_number, status,
+                subtotal_cents, tax_cents, discount_cents, total_cents,
+                user_id, device_id, notes,
+                created_at, updated_at, completed_at, sync_version
+            ) VALUES (
+                ?1, ?2, ?3, ?4,
+                ?5, ?6, ?7, ?8,
+                ?9, ?10, ?11,
+                ?12, ?13, ?14, ?15
+            )
+            "#,
+            sale.id,
+            sale.tenant_id,
+            sale.receipt_number,
+            sale.status,
+            sale.subtotal_cents,
+            sale.tax_cents,
+            sale.discount_cents,
+            sale.total_cents,
+            sale.user_id,
+            sale.device_id,
+            sale.notes,
+            sale.created_at,
+            sale.updated_at,
+            sale.completed_at,
+            sale.sync_version
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for item in items {
+            sqlx::query!(
+                r#"
+                INSERT INTO sale_items (
+                    id, sale_id, product_id,
+                    sku_snapshot, name_snapshot, unit_price_cents,
+                    quantity, line_total_cents, tax_cents, discount_cents,
+                    base_price_cents, created_at, tax_rate_bps, unit_cost_cents
+                ) VALUES (
+                    ?1, ?2, ?3,
+                    ?4, ?5, ?6,
+                    ?7, ?8, ?9, ?10,
+                    ?11, ?12, ?13,
+                    (SELECT cost_cents FROM products WHERE id = ?3)
+                )
+                "#,
+                item.id,
+                item.sale_id,
+                item.product_id,
+                item.sku_snapshot,
+                item.name_snapshot,
+                item.unit_price_cents,
+                item.quantity,
+                item.line_total_cents,
+                item.tax_cents,
+                item.discount_cents,
+                item.base_price_cents,
+                item.created_at,
+                item.tax_rate_bps
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        info!(quote_id = %quote_id, sale_id = %sale.id, "Quote converted to sale");
+
+        Ok(())
+    }
+
+    /// Applies a quote received from another terminal.
+    ///
+    /// Last-writer-wins on `sync_version`: the incoming document replaces the
+    /// local copy (header and items) only if it is newer.
+    ///
+    /// ## Returns
+    /// `true` if the document was applied, `false` if the local copy was
+    /// already at the same or a newer version.
+    pub async fn upsert_from_sync(&self, doc: &QuoteDocument) -> DbResult<bool> {
+        let quote = &doc.quote;
+        let email = quote.customer_email.as_deref();
+        let email_enc = self.pii.encrypt_opt(PiiField::Email, email)?;
+        let email_hash = self.pii.blind_index_opt(PiiField::Email, email);
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let current: Option<i64> = sqlx::query_scalar!(
+            r#"SELECT sync_version as "v!: i64" FROM quotes WHERE id = ?1"#,
+            quote.id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if current.is_some_and(|v| v >= quote.sync_version) {
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO quotes (
+                id, tenant_id, quote_number, status,
+                customer_name, customer_email, customer_email_hash,
+                subtotal_cents, tax_cents, discount_cents, total_cents,
+                user_id, device_id, notes,
+                valid_until, converted_sale_id,
+                created_at, updated_at, sync_version
+            ) VALUES (
+                ?1, ?2, ?3, ?4,
+                ?5, ?6, ?19,
+                ?7, ?8, ?9, ?10,
+                ?11, ?12, ?13,
+                ?14, ?15,
+                ?16, ?17, ?18
+            )
+            ON CONFLICT(id) DO UPDATE SET
+                status = excluded.status,
+                customer_name = excluded.customer_name,
+                customer_email = excluded.customer_email,
+                customer_email_hash = excluded.customer_email_hash,
+                subtotal_cents = excluded.subtotal_cents,
+                tax_cents = excluded.tax_cents,
+                discount_cents = excluded.discount_cents,
+                total_cents = excluded.total_cents,
+                notes = excluded.notes,
+                valid_until = excluded.valid_until,
+                converted_sale_id = excluded.converted_sale_id,
+                updated_at = excluded.updated_at,
+                sync_version = excluded.sync_version
+            "#,
+            quote.id,
+            quote.tenant_id,
+            quote.quote_number,
+            quote.status,
+            quote.customer_name,
+            email_enc,
+            quote.subtotal_cents,
+            quote.tax_cents,
+            quote.discount_cents,
+            quote.total_cents,
+            quote.user_id,
+            quote.device_id,
+            quote.notes,
+            quote.valid_until,
+            quote.converted_sale_id,
+            quote.created_at,
+            quote.updated_at,
+            quote.sync_version,
+            email_hash
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!("DELETE FROM quote_items WHERE quote_id = ?1", quote.id)
+            .execute(&mut *tx)
+            .await?;
+
+        for item in &doc.items {
+            insert_item(&mut tx, item).await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        Ok(true)
+    }
+}
+
+/// Inserts one quote line inside an open transaction.
+async fn insert_item(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    item: &QuoteItem,
+) -> DbResult<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO quote_items (
+            id, quote_id, product_id,
+            sku_snapshot, name_snapshot, unit_price_cents, tax_rate_bps,
+            quantity, line_total_cents, tax_cents, created_at
+        ) VALUES (
+            ?1, ?2, ?3,
+            ?4, ?5, ?6, ?7,
+            ?8, ?9, ?10, ?11
+        )
+        "#,
+        item.id,
+        item.quote_id,
+        item.product_id,
+        item.sku_snapshot,
+        item.name_snapshot,
+        item.unit_price_cents,
+        item.tax_rate_bps,
+        item.quantity,
+        item.line_total_cents,
+        item.tax_cents,
+        item.created_at
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+//! # Receipt Campaign Repository
+//!
+//! The local copy of the store's receipt footer campaigns (see
+//! `titan_core::receipt_campaign`), kept in the `config` table under
+//! `receipt_campaigns` so receipts print them while the cloud is out of
+//! reach.
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::debug;
+
+use crate::error::{DbError, DbResult};
+use titan_core::ReceiptCampaign;
+
+/// `config` key holding the campaigns as a JSON array.
+const CONFIG_KEY: &str = "receipt_campaigns";
+
+/// Repository for the receipt campaign cache.
+#[derive(Debug, Clone)]
+pub struct ReceiptCampaignRepository {
+    pool: SqlitePool,
+}
+
+impl ReceiptCampaignRepository {
+    /// Creates a new ReceiptCampaignRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        ReceiptCampaignRepository { pool }
+    }
+
+    /// Loads the cached campaigns and when they were saved; none (and no
+    /// time) if they were never fetched.
+    pub async fn load(&self) -> DbResult<(Vec<ReceiptCampaign>, Option<DateTime<Utc>>)> {
+        let row = sqlx::query!(
+            r#"
+            SELECT value, updated_at as "updated_at: DateTime<Utc>"
+            FROM config
+            WHERE key = ?1
+            "#,
+            CONFIG_KEY
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok((Vec::new(), None));
+        };
+        let campaigns = serde_json::from_str(&row.value)
+            .map_err(|e| DbError::Internal(format!("Corrupt receipt campaign cache: {}", e)))?;
+        Ok((campaigns, Some(row.updated_at)))
+    }
+
+    /// Replaces the cache with freshly fetched campaigns.
+    pub async fn save(&self, campaigns: &[ReceiptCampaign]) -> DbResult<()> {
+        let json = serde_json::to_string(campaigns).map_err(|e| {
+            DbError::Internal(format!("Failed to serialize receipt campaigns: {}", e))
+        })?;
+        let now = Utc::now();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO config (key, value, updated_at)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(key) DO UPDATE SET
+                value = excluded.value,
+                updated_at = excluded.updated_at
+            "#,
+            CONFIG_KEY,
+            json,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+
+        debug!(
+            campaigns = campaigns.len(),
+            "Receipt campaign cache updated"
+        );
+        Ok(())
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::pool::{Database, DbConfig};
+
+    #[tokio::test]
+    async fn test_receipt_campaign_cache_round_trip() {
+        use titan_core::receipt_campaign::parse_campaigns;
+
+        let db = Database::new(DbConfig::in_memory()).await.unwrap();
+        let (campaigns, fetched_at) = db.receipt_campaigns().load().await.unwrap();
+        assert!(campaigns.is_empty());
+        assert!(fetched_at.is_none());
+
+        let parsed = parse_campaigns(
+            r#"[{"id": "next", "type": "coupon", "message": "$5 off next time",
+                 "when": {"min_total_cents": 2500}}]"#,
+        )
+        .unwrap();
+        // Saving again replaces the cache
+        db.receipt_campaigns().save(&[]).await.unwrap();
+        db.receipt_campaigns()
+            .save(&parsed.campaigns)
+            .await
+            .unwrap();
+
+        let (campaigns, fetched_at) = db.receipt_campaigns().load().await.unwrap();
+        assert_eq!(campaigns, parsed.campaigns);
+        assert!(fetched_at.is_some());
+    }
+}
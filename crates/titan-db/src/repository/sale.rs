@@ -22,27 +22,60 @@
 //! │  4. (OPTIONAL) VOID                                                    │
 //! │     └── void_sale() → Sale { status: Voided }                          │
 //! │                                                                         │
+//! │  Sales rung up on other terminals arrive complete through the hub:     │
+//! │     └── upsert_from_sync() → relayed copy, takes no stock out          │
+//! │                                                                         │
+//! │  Past the retention period (see titan_core::retention):                │
+//! │     └── prune_before() → PrunedSale marker, sale deleted               │
+//! │     └── restore_pruned() → fetched back from the hub, marker removed   │
+//! │                                                                         │
 //! └─────────────────────────────────────────────────────────────────────────┘
 //! ```
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use sqlx::SqlitePool;
 use tracing::debug;
 use uuid::Uuid;
 
 use crate::error::{DbError, DbResult};
-use titan_core::{Payment, Sale, SaleItem, SaleStatus, DEFAULT_TENANT_ID};
+use crate::events::EventPublisher;
+use crate::pii::PiiCipher;
+use crate::repository::store_credit::issue_credit;
+use titan_core::department::department_code_of;
+use titan_core::{
+    AgeVerification, AgeVerificationMethod, DepartmentSalesReport, DepartmentSalesRow, EntityEvent, FiscalSignature, ItemTracking, MarginStats, Page, PageRequest, Payment, ProductMargin, PrunedSale, RefundDestination, Sale,
+    SaleDocument, SaleItem, SaleItemTracking, SaleRefund, SaleStatus, StoreCreditDocument, TaxRateSummary, TrackedSaleLine, ValidationError,
+    DEFAULT_TENANT_ID,
+};
 
 /// Repository for sale database operations.
 #[derive(Debug, Clone)]
 pub struct SaleRepository {
     pool: SqlitePool,
+    events: EventPublisher,
+    pii: PiiCipher,
 }
 
 impl SaleRepository {
     /// Creates a new SaleRepository.
     pub fn new(pool: SqlitePool) -> Self {
-        SaleRepository { pool }
+        SaleRepository {
+            pool,
+            events: EventPublisher::disabled(),
+            pii: PiiCipher::disabled(),
+        }
+    }
+
+    /// Publishes `SaleFinalized` events through `events`.
+    pub fn with_events(mut self, events: EventPublisher) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Encrypts the phone of store credit issued by refunds with `pii`.
+    pub fn with_pii(mut self, pii: PiiCipher) -> Self {
+        self.pii = pii;
+        self
     }
 
     /// Gets a sale by ID.
@@ -61,6 +94,7 @@ impl SaleRepository {
                 total_cents,
                 user_id,
                 device_id,
+                station_number,
                 notes,
                 created_at as "created_at: chrono::DateTime<Utc>",
                 updated_at as "updated_at: chrono::DateTime<Utc>",
@@ -77,8 +111,100 @@ impl SaleRepository {
         Ok(sale)
     }
 
+    /// Gets a sale with its lines and payments, as queued for sync.
+    pub async fn get_document(&self, id: &str) -> DbResult<Option<SaleDocument>> {
+        let Some(sale) = self.get_by_id(id).await? else {
+            return Ok(None);
+        };
+        let items = self.get_items(id).await?;
+        let payments = self.get_payments(id).await?;
+
+        Ok(Some(SaleDocument {
+            sale,
+            items,
+            payments,
+        }))
+    }
+
+    /// Pages through the sales history, newest first.
+    ///
+    /// `query` matches the start of the receipt number; an empty query
+    /// lists every sale, drafts included.
+    ///
+    /// ## Errors
+    /// `DbError::InvalidInput` for a cursor not made by this method.
+    pub async fn search_page(&self, query: &str, page: &PageRequest) -> DbResult<Page<Sale>> {
+        let query = query.trim();
+        let after = page.cursor_keys(2)?;
+        let (before_created, before_id) = match &after {
+            Some(keys) => {
+                let created_at = chrono::DateTime::parse_from_rfc3339(&keys[0])
+                    .map_err(|_| ValidationError::InvalidFormat {
+                        field: "cursor".to_string(),
+                        reason: "not a cursor from this list".to_string(),
+                    })?
+                    .with_timezone(&Utc);
+                (Some(created_at), Some(keys[1].as_str()))
+            }
+            None => (None, None),
+        };
+        let limit = page.fetch_limit();
+
+        debug!(query = %query, limit = %limit, paged = after.is_some(), "Searching sales page");
+
+        let rows: Vec<Sale> = sqlx::query_as!(
+            Sale,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                receipt_number,
+                status as "status: SaleStatus",
+                subtotal_cents,
+                tax_cents,
+                discount_cents,
+                total_cents,
+                user_id,
+                device_id,
+                station_number,
+                notes,
+                created_at as "created_at: chrono::DateTime<Utc>",
+                updated_at as "updated_at: chrono::DateTime<Utc>",
+                completed_at as "completed_at: chrono::DateTime<Utc>",
+                sync_version
+            FROM sales
+            WHERE receipt_number LIKE ?1 || '%'
+            AND (?2 IS NULL OR created_at < ?2 OR (created_at = ?2 AND id < ?3))
+            ORDER BY created_at DESC, id DESC
+            LIMIT ?4
+            "#,
+            query,
+            before_created,
+            before_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let total = if page.include_total {
+            let count: i64 = sqlx::query_scalar!(
+                r#"SELECT COUNT(*) as "count!: i64" FROM sales WHERE receipt_number LIKE ?1 || '%'"#,
+                query
+            )
+            .fetch_one(&self.pool)
+            .await?;
+            Some(count)
+        } else {
+            None
+        };
+
+        Ok(Page::from_rows(rows, page, |s| vec![s.created_at.to_rfc3339(), s.id.clone()]).with_total(total))
+    }
+
     /// Inserts a sale directly (used by commands layer).
     ///
+    /// A draft sale publishes `EntityEvent::SaleCreated`.
+    ///
     /// ## Arguments
     /// * `sale` - Complete sale object to insert
     pub async fn insert_sale(&self, sale: &Sale) -> DbResult<()> {
@@ -89,13 +215,13 @@ impl SaleRepository {
             INSERT INTO sales (
                 id, tenant_id, receipt_number, status,
                 subtotal_cents, tax_cents, discount_cents, total_cents,
-                user_id, device_id, notes,
+                user_id, device_id, station_number, notes,
                 created_at, updated_at, completed_at, sync_version
             ) VALUES (
                 ?1, ?2, ?3, ?4,
                 ?5, ?6, ?7, ?8,
-                ?9, ?10, ?11,
-                ?12, ?13, ?14, ?15
+                ?9, ?10, ?11, ?12,
+                ?13, ?14, ?15, ?16
             )
             "#,
             sale.id,
@@ -108,6 +234,7 @@ impl SaleRepository {
             sale.total_cents,
             sale.user_id,
             sale.device_id,
+            sale.station_number,
             sale.notes,
             sale.created_at,
             sale.updated_at,
@@ -117,6 +244,12 @@ impl SaleRepository {
         .execute(&self.pool)
         .await?;
 
+        if sale.status == SaleStatus::Draft {
+            self.events.publish(EntityEvent::SaleCreated {
+                sale_id: sale.id.clone(),
+                total_cents: sale.total_cents,
+            });
+        }
         Ok(())
     }
 
@@ -146,6 +279,7 @@ impl SaleRepository {
             total_cents: 0,
             user_id: user_id.to_string(),
             device_id: device_id.to_string(),
+            station_number: None,
             notes: None,
             created_at: now,
             updated_at: now,
@@ -158,13 +292,13 @@ impl SaleRepository {
             INSERT INTO sales (
                 id, tenant_id, receipt_number, status,
                 subtotal_cents, tax_cents, discount_cents, total_cents,
-                user_id, device_id, notes,
+                user_id, device_id, station_number, notes,
                 created_at, updated_at, completed_at, sync_version
             ) VALUES (
                 ?1, ?2, ?3, ?4,
                 ?5, ?6, ?7, ?8,
-                ?9, ?10, ?11,
-                ?12, ?13, ?14, ?15
+                ?9, ?10, ?11, ?12,
+                ?13, ?14, ?15, ?16
             )
             "#,
             sale.id,
@@ -177,6 +311,7 @@ impl SaleRepository {
             sale.total_cents,
             sale.user_id,
             sale.device_id,
+            sale.station_number,
             sale.notes,
             sale.created_at,
             sale.updated_at,
@@ -203,12 +338,13 @@ impl SaleRepository {
                 id, sale_id, product_id,
                 sku_snapshot, name_snapshot, unit_price_cents,
                 quantity, line_total_cents, tax_cents, discount_cents,
-                created_at
+                base_price_cents, created_at, tax_rate_bps, unit_cost_cents
             ) VALUES (
                 ?1, ?2, ?3,
                 ?4, ?5, ?6,
                 ?7, ?8, ?9, ?10,
-                ?11
+                ?11, ?12, ?13,
+                (SELECT cost_cents FROM products WHERE id = ?3)
             )
             "#,
             item.id,
@@ -221,7 +357,9 @@ impl SaleRepository {
             item.line_total_cents,
             item.tax_cents,
             item.discount_cents,
-            item.created_at
+            item.base_price_cents,
+            item.created_at,
+            item.tax_rate_bps
         )
         .execute(&self.pool)
         .await?;
@@ -245,6 +383,8 @@ impl SaleRepository {
                 line_total_cents,
                 tax_cents,
                 discount_cents,
+                base_price_cents,
+                tax_rate_bps as "tax_rate_bps: u32",
                 created_at as "created_at: chrono::DateTime<Utc>"
             FROM sale_items
             WHERE sale_id = ?1
@@ -258,6 +398,514 @@ impl SaleRepository {
         Ok(items)
     }
 
+    /// Replaces the serial/lot numbers captured for a sale item.
+    ///
+    /// All `codes` must belong to `sale_item_id`; an empty slice clears them.
+    pub async fn set_item_tracking(
+        &self,
+        sale_item_id: &str,
+        codes: &[SaleItemTracking],
+    ) -> DbResult<()> {
+        debug!(sale_item_id = %sale_item_id, count = codes.len(), "Setting sale item tracking");
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        sqlx::query!(
+            "DELETE FROM sale_item_tracking WHERE sale_item_id = ?1",
+            sale_item_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for code in codes {
+            debug_assert_eq!(code.sale_item_id, sale_item_id);
+            sqlx::query!(
+                r#"
+                INSERT INTO sale_item_tracking (
+                    id, sale_id, sale_item_id, product_id, kind, code, created_at
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                "#,
+                code.id,
+                code.sale_id,
+                code.sale_item_id,
+                code.product_id,
+                code.kind,
+                code.code,
+                code.created_at
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Gets all serial/lot numbers captured for a sale.
+    pub async fn get_tracking(&self, sale_id: &str) -> DbResult<Vec<SaleItemTracking>> {
+        let codes = sqlx::query_as!(
+            SaleItemTracking,
+            r#"
+            SELECT
+                id,
+                sale_id,
+                sale_item_id,
+                product_id,
+                kind as "kind: ItemTracking",
+                code,
+                created_at as "created_at: chrono::DateTime<Utc>"
+            FROM sale_item_tracking
+            WHERE sale_id = ?1
+            ORDER BY sale_item_id, code
+            "#,
+            sale_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(codes)
+    }
+
+    /// Finds completed sale lines carrying a serial or lot number (recalls).
+    ///
+    /// ## Arguments
+    /// * `kind` - Serial or Lot
+    /// * `code` - Exact serial/lot number
+    /// * `product_id` - Optionally restrict to one product (lot numbers are
+    ///   only unique per manufacturer)
+    pub async fn find_by_tracking_code(
+        &self,
+        kind: ItemTracking,
+        code: &str,
+        product_id: Option<&str>,
+        limit: u32,
+    ) -> DbResult<Vec<TrackedSaleLine>> {
+        let lines = sqlx::query_as!(
+            TrackedSaleLine,
+            r#"
+            SELECT
+                s.id as "sale_id!",
+                s.receipt_number,
+                s.device_id,
+                s.completed_at as "completed_at: chrono::DateTime<Utc>",
+                si.product_id,
+                si.sku_snapshot,
+                si.name_snapshot,
+                si.quantity,
+                t.kind as "kind: ItemTracking",
+                t.code
+            FROM sale_item_tracking t
+            JOIN sale_items si ON si.id = t.sale_item_id
+            JOIN sales s ON s.id = t.sale_id
+            WHERE t.kind = ?1
+              AND t.code = ?2
+              AND (?3 IS NULL OR t.product_id = ?3)
+              AND s.status = 'completed'
+            ORDER BY s.completed_at DESC
+            LIMIT ?4
+            "#,
+            kind,
+            code,
+            product_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(lines)
+    }
+
+    /// Records the customer age check for a sale.
+    ///
+    /// Replaces an earlier check (e.g. re-verified after a 21+ item was added).
+    pub async fn set_age_verification(&self, verification: &AgeVerification) -> DbResult<()> {
+        debug!(sale_id = %verification.sale_id, method = verification.method.as_str(), "Recording age verification");
+
+        sqlx::query!(
+            r#"
+            INSERT INTO sale_age_verifications (
+                sale_id, required_age, method, birthdate,
+                verified_by, device_id, verified_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            ON CONFLICT(sale_id) DO UPDATE SET
+                required_age = excluded.required_age,
+                method = excluded.method,
+                birthdate = excluded.birthdate,
+                verified_by = excluded.verified_by,
+                device_id = excluded.device_id,
+                verified_at = excluded.verified_at
+            "#,
+            verification.sale_id,
+            verification.required_age,
+            verification.method,
+            verification.birthdate,
+            verification.verified_by,
+            verification.device_id,
+            verification.verified_at
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Gets the customer age check recorded for a sale, if any.
+    pub async fn get_age_verification(&self, sale_id: &str) -> DbResult<Option<AgeVerification>> {
+        let verification = sqlx::query_as!(
+            AgeVerification,
+            r#"
+            SELECT
+                sale_id,
+                required_age as "required_age: u32",
+                method as "method: AgeVerificationMethod",
+                birthdate as "birthdate: chrono::NaiveDate",
+                verified_by,
+                device_id,
+                verified_at as "verified_at: chrono::DateTime<Utc>"
+            FROM sale_age_verifications
+            WHERE sale_id = ?1
+            "#,
+            sale_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(verification)
+    }
+
+    /// Stores the fiscal signature of a sale with the payload it covers.
+    ///
+    /// ## Errors
+    /// `DbError::UniqueViolation` if the sale is already signed or the backend
+    /// sequence number is taken.
+    pub async fn add_fiscal_signature(&self, signature: &FiscalSignature, payload: &str) -> DbResult<()> {
+        debug!(sale_id = %signature.sale_id, backend = %signature.backend, sequence = signature.sequence, "Storing fiscal signature");
+
+        sqlx::query!(
+            r#"
+            INSERT INTO sale_fiscal_signatures (
+                sale_id, backend, sequence, signature,
+                previous_signature, payload, signed_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#,
+            signature.sale_id,
+            signature.backend,
+            signature.sequence,
+            signature.signature,
+            signature.previous_signature,
+            payload,
+            signature.signed_at
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Gets the fiscal signature of a sale, if it was signed.
+    pub async fn get_fiscal_signature(&self, sale_id: &str) -> DbResult<Option<FiscalSignature>> {
+        let signature = sqlx::query_as!(
+            FiscalSignature,
+            r#"
+            SELECT
+                sale_id,
+                backend,
+                sequence,
+                signature,
+                previous_signature,
+                signed_at as "signed_at: chrono::DateTime<Utc>"
+            FROM sale_fiscal_signatures
+            WHERE sale_id = ?1
+            "#,
+            sale_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(signature)
+    }
+
+    /// Gets the most recent signature of a fiscal backend: the link the
+    /// next receipt chains to.
+    pub async fn get_last_fiscal_signature(&self, backend: &str) -> DbResult<Option<FiscalSignature>> {
+        let signature = sqlx::query_as!(
+            FiscalSignature,
+            r#"
+            SELECT
+                sale_id,
+                backend,
+                sequence,
+                signature,
+                previous_signature,
+                signed_at as "signed_at: chrono::DateTime<Utc>"
+            FROM sale_fiscal_signatures
+            WHERE backend = ?1
+            ORDER BY sequence DESC
+            LIMIT 1
+            "#,
+            backend
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(signature)
+    }
+
+    /// Links a sale to the business customer it is invoiced to, or removes
+    /// the link with `None`.
+    pub async fn set_invoice_customer(&self, sale_id: &str, customer_id: Option<&str>) -> DbResult<()> {
+        match customer_id {
+            Some(customer_id) => {
+                debug!(sale_id = %sale_id, customer_id = %customer_id, "Linking sale to business customer");
+                sqlx::query!(
+                    r#"
+                    INSERT INTO sale_invoice_customers (sale_id, customer_id)
+                    VALUES (?1, ?2)
+                    ON CONFLICT(sale_id) DO UPDATE SET customer_id = excluded.customer_id
+                    "#,
+                    sale_id,
+                    customer_id
+                )
+                .execute(&self.pool)
+                .await?;
+            }
+            None => {
+                sqlx::query!("DELETE FROM sale_invoice_customers WHERE sale_id = ?1", sale_id)
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Gets the ID of the business customer a sale is invoiced to, if any.
+    pub async fn get_invoice_customer(&self, sale_id: &str) -> DbResult<Option<String>> {
+        let customer_id = sqlx::query_scalar!(
+            "SELECT customer_id FROM sale_invoice_customers WHERE sale_id = ?1",
+            sale_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(customer_id)
+    }
+
+    /// Lists completed sales invoiced to a business customer and completed
+    /// in `[from, to)`, oldest first.
+    pub async fn list_invoiced(
+        &self,
+        from: chrono::DateTime<Utc>,
+        to: chrono::DateTime<Utc>,
+    ) -> DbResult<Vec<String>> {
+        let sale_ids = sqlx::query_scalar!(
+            r#"
+            SELECT s.id as "id!"
+            FROM sales s
+            JOIN sale_invoice_customers c ON c.sale_id = s.id
+            WHERE s.status = 'completed'
+              AND s.completed_at >= ?1
+              AND s.completed_at < ?2
+            ORDER BY s.completed_at
+            "#,
+            from,
+            to
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(sale_ids)
+    }
+
+    /// Sales and tax per tax rate for sales completed in `[from, to)`
+    /// (rows for `TaxReport::new`; see `titan_core::tax_report`), on
+    /// register `station` only if given.
+    pub async fn tax_summary(
+        &self,
+        from: chrono::DateTime<Utc>,
+        to: chrono::DateTime<Utc>,
+        station: Option<i64>,
+    ) -> DbResult<Vec<TaxRateSummary>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                si.tax_rate_bps as "rate_bps: u32",
+                COALESCE(SUM(CASE WHEN COALESCE(si.tax_rate_bps > 0, si.tax_cents <> 0)
+                    THEN si.line_total_cents - si.discount_cents ELSE 0 END), 0)
+                    as "taxable_cents!: i64",
+                COALESCE(SUM(CASE WHEN COALESCE(si.tax_rate_bps > 0, si.tax_cents <> 0)
+                    THEN 0 ELSE si.line_total_cents - si.discount_cents END), 0)
+                    as "non_taxable_cents!: i64",
+                COALESCE(SUM(si.tax_cents), 0) as "tax_cents!: i64",
+                COUNT(*) as "line_count!: i64"
+            FROM sale_items si
+            JOIN sales s ON s.id = si.sale_id
+            WHERE s.status = 'completed'
+              AND s.completed_at >= ?1
+              AND s.completed_at < ?2
+              AND (?3 IS NULL OR s.station_number = ?3)
+            GROUP BY si.tax_rate_bps
+            "#,
+            from,
+            to,
+            station
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| TaxRateSummary {
+                rate_bps: r.rate_bps,
+                jurisdiction: None,
+                taxable_cents: r.taxable_cents,
+                non_taxable_cents: r.non_taxable_cents,
+                tax_cents: r.tax_cents,
+                line_count: r.line_count,
+            })
+            .collect())
+    }
+
+    /// Price vs cost per product for sales completed in `[from, to)`, at
+    /// the cost frozen on each line (see `titan_core::supplier`), on
+    /// register `station` only if given.
+    pub async fn margin_stats(
+        &self,
+        from: chrono::DateTime<Utc>,
+        to: chrono::DateTime<Utc>,
+        station: Option<i64>,
+    ) -> DbResult<MarginStats> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                si.product_id as "product_id!",
+                MAX(si.sku_snapshot) as "sku!: String",
+                MAX(si.name_snapshot) as "name!: String",
+                SUM(si.quantity) as "units!: i64",
+                SUM(si.line_total_cents - si.discount_cents) as "sales_cents!: i64",
+                SUM(si.unit_cost_cents * si.quantity) as "cost_cents!: i64"
+            FROM sale_items si
+            JOIN sales s ON s.id = si.sale_id
+            WHERE s.status = 'completed'
+              AND s.completed_at >= ?1
+              AND s.completed_at < ?2
+              AND (?3 IS NULL OR s.station_number = ?3)
+              AND si.unit_cost_cents IS NOT NULL
+            GROUP BY si.product_id
+            "#,
+            from,
+            to,
+            station
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let uncosted_sales_cents = sqlx::query_scalar!(
+            r#"
+            SELECT COALESCE(SUM(si.line_total_cents - si.discount_cents), 0) as "cents!: i64"
+            FROM sale_items si
+            JOIN sales s ON s.id = si.sale_id
+            WHERE s.status = 'completed'
+              AND s.completed_at >= ?1
+              AND s.completed_at < ?2
+              AND (?3 IS NULL OR s.station_number = ?3)
+              AND si.unit_cost_cents IS NULL
+            "#,
+            from,
+            to,
+            station
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let products = rows
+            .into_iter()
+            .map(|r| ProductMargin {
+                product_id: r.product_id,
+                sku: r.sku,
+                name: r.name,
+                units: r.units,
+                sales_cents: r.sales_cents,
+                cost_cents: r.cost_cents,
+            })
+            .collect();
+        Ok(MarginStats::new(products, uncosted_sales_cents))
+    }
+
+    /// Department sales completed in `[from, to)`, apart from SKU sales
+    /// (see `titan_core::department`), on register `station` only if given.
+    pub async fn department_sales(
+        &self,
+        from: chrono::DateTime<Utc>,
+        to: chrono::DateTime<Utc>,
+        station: Option<i64>,
+    ) -> DbResult<DepartmentSalesReport> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                si.product_id as "product_id!",
+                MAX(si.name_snapshot) as "name!: String",
+                SUM(si.quantity) as "units!: i64",
+                SUM(si.line_total_cents - si.discount_cents) as "sales_cents!: i64",
+                SUM(si.tax_cents) as "tax_cents!: i64",
+                COUNT(*) as "line_count!: i64"
+            FROM sale_items si
+            JOIN sales s ON s.id = si.sale_id
+            WHERE s.status = 'completed'
+              AND s.completed_at >= ?1
+              AND s.completed_at < ?2
+              AND (?3 IS NULL OR s.station_number = ?3)
+              AND si.product_id LIKE 'department:%'
+            GROUP BY si.product_id
+            "#,
+            from,
+            to,
+            station
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let sku_sales_cents = sqlx::query_scalar!(
+            r#"
+            SELECT COALESCE(SUM(si.line_total_cents - si.discount_cents), 0) as "cents!: i64"
+            FROM sale_items si
+            JOIN sales s ON s.id = si.sale_id
+            WHERE s.status = 'completed'
+              AND s.completed_at >= ?1
+              AND s.completed_at < ?2
+              AND (?3 IS NULL OR s.station_number = ?3)
+              AND si.product_id NOT LIKE 'department:%'
+            "#,
+            from,
+            to,
+            station
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let departments = rows
+            .into_iter()
+            .filter_map(|r| {
+                Some(DepartmentSalesRow {
+                    department_code: department_code_of(&r.product_id)?.to_string(),
+                    name: r.name,
+                    units: r.units,
+                    sales_cents: r.sales_cents,
+                    tax_cents: r.tax_cents,
+                    line_count: r.line_count,
+                })
+            })
+            .collect();
+        Ok(DepartmentSalesReport::new(departments, sku_sales_cents))
+    }
+
     /// Updates sale totals.
     ///
     /// ## When To Call
@@ -305,10 +953,11 @@ impl SaleRepository {
     /// 1. Updates sale status to Completed
     /// 2. Sets completed_at timestamp
     /// 3. Increments sync_version
+    /// 4. Publishes `SaleFinalized`
     pub async fn finalize_sale(&self, sale_id: &str) -> DbResult<()> {
         let now = Utc::now();
 
-        let result: sqlx::sqlite::SqliteQueryResult = sqlx::query!(
+        let total_cents: Option<i64> = sqlx::query_scalar!(
             r#"
             UPDATE sales SET
                 status = 'completed',
@@ -316,17 +965,22 @@ impl SaleRepository {
                 updated_at = ?2,
                 sync_version = sync_version + 1
             WHERE id = ?1 AND status = 'draft'
+            RETURNING total_cents
             "#,
             sale_id,
             now
         )
-        .execute(&self.pool)
+        .fetch_optional(&self.pool)
         .await?;
 
-        if result.rows_affected() == 0 {
+        let Some(total_cents) = total_cents else {
             return Err(DbError::not_found("Sale (draft)", sale_id));
-        }
+        };
 
+        self.events.publish(EntityEvent::SaleFinalized {
+            sale_id: sale_id.to_string(),
+            total_cents,
+        });
         Ok(())
     }
 
@@ -427,6 +1081,380 @@ impl SaleRepository {
 
         Ok(total.unwrap_or(0))
     }
+
+    /// Records a returnless refund against a completed sale.
+    ///
+    /// When the refund goes to store credit, `credit` holds the account
+    /// (created if new) and its issue entry; both are written in the same
+    /// transaction as the refund.
+    pub async fn add_refund(
+        &self,
+        refund: &SaleRefund,
+        credit: Option<&StoreCreditDocument>,
+    ) -> DbResult<()> {
+        debug!(sale_id = %refund.sale_id, amount = %refund.amount_cents, "Recording refund");
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        if let Some(doc) = credit {
+            issue_credit(&mut tx, doc, &self.pii).await?;
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO sale_refunds (
+                id, sale_id, amount_cents, destination, store_credit_id,
+                reason, user_id, device_id, created_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            "#,
+            refund.id,
+            refund.sale_id,
+            refund.amount_cents,
+            refund.destination,
+            refund.store_credit_id,
+            refund.reason,
+            refund.user_id,
+            refund.device_id,
+            refund.created_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Gets all refunds for a sale, oldest first.
+    pub async fn get_refunds(&self, sale_id: &str) -> DbResult<Vec<SaleRefund>> {
+        let refunds = sqlx::query_as!(
+            SaleRefund,
+            r#"
+            SELECT
+                id,
+                sale_id,
+                amount_cents,
+                destination as "destination: RefundDestination",
+                store_credit_id,
+                reason,
+                user_id,
+                device_id,
+                created_at as "created_at: chrono::DateTime<Utc>"
+            FROM sale_refunds
+            WHERE sale_id = ?1
+            ORDER BY created_at
+            "#,
+            sale_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(refunds)
+    }
+
+    /// Gets the total already refunded on a sale.
+    pub async fn get_total_refunded(&self, sale_id: &str) -> DbResult<i64> {
+        let total = sqlx::query_scalar!(
+            r#"
+            SELECT COALESCE(SUM(amount_cents), 0) as "total!: i64"
+            FROM sale_refunds
+            WHERE sale_id = ?1
+            "#,
+            sale_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(total)
+    }
+
+    /// Stores a sale another terminal rang up, so it can be found,
+    /// reprinted and refunded here.
+    ///
+    /// The copy is marked relayed: its lines take no stock out (the
+    /// terminal that rang it up sent those deltas) and no event is
+    /// published. Sales rung up here are never replaced; a relayed copy is
+    /// replaced when the incoming `sync_version` is newer. Lines and
+    /// payments already stored are kept.
+    ///
+    /// ## Returns
+    /// `true` if the document was applied, `false` if it was stale or the
+    /// sale was rung up here.
+    pub async fn upsert_from_sync(&self, doc: &SaleDocument) -> DbResult<bool> {
+        let sale = &doc.sale;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let current = sqlx::query!(
+            r#"SELECT sync_version as "v!: i64", relayed as "relayed!: bool" FROM sales WHERE id = ?1"#,
+            sale.id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if current.is_some_and(|row| !row.relayed || row.v >= sale.sync_version) {
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO sales (
+                id, tenant_id, receipt_number, status,
+                subtotal_cents, tax_cents, discount_cents, total_cents,
+                user_id, device_id, station_number, notes,
+                created_at, updated_at, completed_at, sync_version, relayed
+            ) VALUES (
+                ?1, ?2, ?3, ?4,
+                ?5, ?6, ?7, ?8,
+                ?9, ?10, ?11, ?12,
+                ?13, ?14, ?15, ?16, 1
+            )
+            ON CONFLICT(id) DO UPDATE SET
+                status = excluded.status,
+                subtotal_cents = excluded.subtotal_cents,
+                tax_cents = excluded.tax_cents,
+                discount_cents = excluded.discount_cents,
+                total_cents = excluded.total_cents,
+                notes = excluded.notes,
+                updated_at = excluded.updated_at,
+                completed_at = excluded.completed_at,
+                sync_version = excluded.sync_version
+            "#,
+            sale.id,
+            sale.tenant_id,
+            sale.receipt_number,
+            sale.status,
+            sale.subtotal_cents,
+            sale.tax_cents,
+            sale.discount_cents,
+            sale.total_cents,
+            sale.user_id,
+            sale.device_id,
+            sale.station_number,
+            sale.notes,
+            sale.created_at,
+            sale.updated_at,
+            sale.completed_at,
+            sale.sync_version
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        for item in &doc.items {
+            sqlx::query!(
+                r#"
+                INSERT INTO sale_items (
+                    id, sale_id, product_id,
+                    sku_snapshot, name_snapshot, unit_price_cents,
+                    quantity, line_total_cents, tax_cents, discount_cents,
+                    base_price_cents, created_at, tax_rate_bps, unit_cost_cents
+                ) VALUES (
+                    ?1, ?2, ?3,
+                    ?4, ?5, ?6,
+                    ?7, ?8, ?9, ?10,
+                    ?11, ?12, ?13,
+                    (SELECT cost_cents FROM products WHERE id = ?3)
+                )
+                ON CONFLICT(id) DO NOTHING
+                "#,
+                item.id,
+                item.sale_id,
+                item.product_id,
+                item.sku_snapshot,
+                item.name_snapshot,
+                item.unit_price_cents,
+                item.quantity,
+                item.line_total_cents,
+                item.tax_cents,
+                item.discount_cents,
+                item.base_price_cents,
+                item.created_at,
+                item.tax_rate_bps
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for payment in &doc.payments {
+            sqlx::query!(
+                r#"
+                INSERT INTO payments (
+                    id, sale_id, method,
+                    amount_cents, tendered_cents, change_cents,
+                    reference, created_at
+                ) VALUES (
+                    ?1, ?2, ?3,
+                    ?4, ?5, ?6,
+                    ?7, ?8
+                )
+                ON CONFLICT(id) DO NOTHING
+                "#,
+                payment.id,
+                payment.sale_id,
+                payment.method,
+                payment.amount_cents,
+                payment.tendered_cents,
+                payment.change_cents,
+                payment.reference,
+                payment.created_at
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        debug!(sale_id = %sale.id, device_id = %sale.device_id, "Stored relayed sale");
+        Ok(true)
+    }
+
+    /// Gets a sale with its lines and payments by receipt number.
+    pub async fn get_document_by_receipt(
+        &self,
+        receipt_number: &str,
+    ) -> DbResult<Option<SaleDocument>> {
+        let id: Option<String> = sqlx::query_scalar!(
+            r#"SELECT id as "id!" FROM sales WHERE receipt_number = ?1 ORDER BY created_at DESC LIMIT 1"#,
+            receipt_number
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match id {
+            Some(id) => self.get_document(&id).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Prunes up to `limit` finished sales past the retention `cutoff`,
+    /// leaving a [`PrunedSale`] marker for each.
+    ///
+    /// A sale rung up here goes once the cloud acked it before the cutoff;
+    /// a relayed copy once it was completed before the cutoff. Drafts,
+    /// refunded sales and fiscally signed sales are kept, and so are sales
+    /// with serial/lot numbers or an age check: those rows go with the sale
+    /// (ON DELETE CASCADE) and recalls and audits still need them.
+    ///
+    /// ## Returns
+    /// Number of pruned sales.
+    pub async fn prune_before(&self, cutoff: DateTime<Utc>, limit: u32) -> DbResult<u64> {
+        let now = Utc::now();
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let ids: Vec<String> = sqlx::query_scalar!(
+            r#"
+            SELECT s.id as "id!"
+            FROM sales s
+            WHERE s.status IN ('completed', 'voided')
+            AND CASE
+                WHEN s.relayed = 1 THEN COALESCE(s.completed_at, s.updated_at) < ?1
+                ELSE s.cloud_acked_at IS NOT NULL AND s.cloud_acked_at < ?1
+            END
+            AND NOT EXISTS (SELECT 1 FROM sale_refunds r WHERE r.sale_id = s.id)
+            AND NOT EXISTS (SELECT 1 FROM sale_fiscal_signatures f WHERE f.sale_id = s.id)
+            AND NOT EXISTS (SELECT 1 FROM sale_item_tracking t WHERE t.sale_id = s.id)
+            AND NOT EXISTS (SELECT 1 FROM sale_age_verifications a WHERE a.sale_id = s.id)
+            ORDER BY s.created_at
+            LIMIT ?2
+            "#,
+            cutoff,
+            limit
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for id in &ids {
+            sqlx::query!(
+                r#"
+                INSERT OR REPLACE INTO pruned_sales (
+                    sale_id, tenant_id, receipt_number, device_id,
+                    total_cents, completed_at, pruned_at
+                )
+                SELECT id, tenant_id, receipt_number, device_id,
+                       total_cents, completed_at, ?2
+                FROM sales WHERE id = ?1
+                "#,
+                id,
+                now
+            )
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query!("DELETE FROM payments WHERE sale_id = ?1", id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query!("DELETE FROM sale_items WHERE sale_id = ?1", id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query!("DELETE FROM sales WHERE id = ?1", id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        if !ids.is_empty() {
+            debug!(pruned = ids.len(), cutoff = %cutoff, "Pruned finished sales");
+        }
+        Ok(ids.len() as u64)
+    }
+
+    /// Gets the marker of a sale pruned from this terminal, by receipt
+    /// number.
+    pub async fn find_pruned(&self, receipt_number: &str) -> DbResult<Option<PrunedSale>> {
+        let pruned = sqlx::query_as!(
+            PrunedSale,
+            r#"
+            SELECT
+                sale_id,
+                receipt_number,
+                device_id,
+                total_cents,
+                completed_at as "completed_at: DateTime<Utc>",
+                pruned_at as "pruned_at!: DateTime<Utc>"
+            FROM pruned_sales
+            WHERE receipt_number = ?1
+            "#,
+            receipt_number
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(pruned)
+    }
+
+    /// Stores a pruned sale fetched back from the hub, as a relayed copy
+    /// (so it takes no stock out), and removes its marker.
+    ///
+    /// ## Returns
+    /// `true` if the sale was stored.
+    pub async fn restore_pruned(&self, doc: &SaleDocument) -> DbResult<bool> {
+        let restored = self.upsert_from_sync(doc).await?;
+        sqlx::query!("DELETE FROM pruned_sales WHERE sale_id = ?1", doc.sale.id)
+            .execute(&self.pool)
+            .await?;
+
+        debug!(sale_id = %doc.sale.id, restored, "Restored pruned sale");
+        Ok(restored)
+    }
 }
 
 /// Generates a receipt number in format: YYYYMMDD-DD-NNNN
@@ -473,3 +1501,265 @@ pub fn generate_sale_item_id() -> String {
 pub fn generate_payment_id() -> String {
     Uuid::new_v4().to_string()
 }
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::{ProductFixture, SaleFixture};
+    use crate::pool::{Database, DbConfig};
+    use chrono::{Duration, Utc};
+    use titan_core::{
+        AgeVerification, AgeVerificationMethod, ItemTracking, PageRequest, SaleDocument,
+        SaleItemTracking,
+    };
+
+    #[tokio::test]
+    async fn test_tax_summary_uses_frozen_rates() {
+        let db = Database::new(DbConfig::in_memory()).await.unwrap();
+        let taxed = ProductFixture::new("TAX-1")
+            .price_cents(1000)
+            .tax_rate_bps(825)
+            .insert(&db)
+            .await
+            .unwrap();
+        let food = ProductFixture::new("FOOD-1")
+            .price_cents(500)
+            .insert(&db)
+            .await
+            .unwrap();
+
+        let day = Utc::now() - chrono::Duration::days(1);
+        SaleFixture::new()
+            .line(&taxed, 2)
+            .line(&food, 1)
+            .at(day)
+            .insert(&db)
+            .await
+            .unwrap();
+        SaleFixture::new().line(&taxed, 5).at(day).draft().insert(&db).await.unwrap();
+
+        // A later rate change does not move sales already made
+        let mut changed = taxed.clone();
+        changed.tax_rate_bps = 900;
+        db.products().update(&changed).await.unwrap();
+
+        let rows = db
+            .sales()
+            .tax_summary(day - chrono::Duration::hours(1), Utc::now(), None)
+            .await
+            .unwrap();
+        let report = titan_core::TaxReport::new(day.date_naive(), day.date_naive(), rows);
+        assert_eq!(report.rates.len(), 2);
+        assert_eq!(report.rates[0].rate_bps, Some(0));
+        assert_eq!(report.rates[1].rate_bps, Some(825));
+        assert_eq!(report.taxable_cents(), 2000);
+        assert_eq!(report.non_taxable_cents(), 500);
+        assert_eq!(report.tax_cents(), 165);
+    }
+
+    #[tokio::test]
+    async fn test_tax_summary_for_one_station() {
+        let db = Database::new(DbConfig::in_memory()).await.unwrap();
+        let taxed = ProductFixture::new("TAX-1")
+            .price_cents(1000)
+            .tax_rate_bps(825)
+            .insert(&db)
+            .await
+            .unwrap();
+
+        let day = Utc::now() - chrono::Duration::days(1);
+        SaleFixture::new().line(&taxed, 1).station(1).at(day).insert(&db).await.unwrap();
+        SaleFixture::new().line(&taxed, 3).station(2).at(day).insert(&db).await.unwrap();
+        SaleFixture::new().line(&taxed, 2).at(day).insert(&db).await.unwrap();
+
+        let from = day - chrono::Duration::hours(1);
+        let register_2 = db.sales().tax_summary(from, Utc::now(), Some(2)).await.unwrap();
+        assert_eq!(register_2.len(), 1);
+        assert_eq!(register_2[0].taxable_cents, 3000);
+
+        let store = db.sales().tax_summary(from, Utc::now(), None).await.unwrap();
+        assert_eq!(store[0].taxable_cents, 6000);
+    }
+
+    #[tokio::test]
+    async fn test_department_sales_report_apart_from_skus() {
+        use titan_core::DepartmentLine;
+
+        let db = Database::new(DbConfig::in_memory()).await.unwrap();
+        let cola = ProductFixture::new("COLA-330").price_cents(100).insert(&db).await.unwrap();
+        let mut line = DepartmentLine {
+            department_code: "BAKERY".to_string(),
+            name: "Misc Bakery".to_string(),
+            tax_rate_bps: 0,
+            unit_price_cents: 350,
+        };
+
+        let id = db.products().ensure_department_product(&line).await.unwrap();
+        line.name = "Bakery".to_string();
+        assert_eq!(db.products().ensure_department_product(&line).await.unwrap(), id);
+
+        let mut bakery = db.products().get_by_id(&id).await.unwrap().unwrap();
+        assert_eq!(bakery.name, "Bakery");
+        assert!(!bakery.is_active && !bakery.track_inventory);
+        assert!(db.products().search("Bakery", 10).await.unwrap().is_empty());
+
+        bakery.price_cents = 350;
+        let day = Utc::now() - Duration::hours(1);
+        SaleFixture::new().line(&bakery, 2).line(&cola, 3).at(day).insert(&db).await.unwrap();
+
+        let report = db
+            .sales()
+            .department_sales(day - Duration::hours(1), Utc::now(), None)
+            .await
+            .unwrap();
+        assert_eq!(report.departments.len(), 1);
+        assert_eq!(report.departments[0].department_code, "BAKERY");
+        assert_eq!((report.departments[0].units, report.departments[0].sales_cents), (2, 700));
+        assert_eq!(report.sku_sales_cents, 300);
+        assert_eq!(report.department_sales_cents(), 700);
+    }
+
+    #[tokio::test]
+    async fn test_relayed_sale_is_found_but_takes_no_stock() {
+        let db = Database::new(DbConfig::in_memory()).await.unwrap();
+        let cola = ProductFixture::new("COLA").stock(10).insert(&db).await.unwrap();
+        let (sale, items, payment) = SaleFixture::new()
+            .device("pos-02")
+            .receipt_number("R-POS2-0001")
+            .line(&cola, 3)
+            .build();
+        let doc = SaleDocument {
+            sale,
+            items,
+            payments: payment.into_iter().collect(),
+        };
+
+        let deltas = || async {
+            sqlx::query_scalar!(r#"SELECT COUNT(*) as "n!: i64" FROM inventory_deltas"#)
+                .fetch_one(db.pool())
+                .await
+                .unwrap()
+        };
+        let before = deltas().await;
+
+        assert!(db.sales().upsert_from_sync(&doc).await.unwrap());
+        assert!(!db.sales().upsert_from_sync(&doc).await.unwrap());
+        assert_eq!(deltas().await, before);
+
+        let found = db
+            .sales()
+            .search_page("R-POS2", &PageRequest::first(5))
+            .await
+            .unwrap();
+        assert_eq!(found.items.len(), 1);
+        let copy = db.sales().get_document(&doc.sale.id).await.unwrap().unwrap();
+        assert_eq!((copy.items.len(), copy.payments.len()), (1, 1));
+        assert_eq!(copy.sale.device_id, "pos-02");
+
+        // A sale rung up here is never replaced by a relayed copy
+        let local = SaleFixture::new().line(&cola, 1).insert(&db).await.unwrap();
+        let mut echo = db.sales().get_document(&local.id).await.unwrap().unwrap();
+        echo.sale.sync_version += 1;
+        echo.sale.notes = Some("from the hub".to_string());
+        assert!(!db.sales().upsert_from_sync(&echo).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_sales_are_pruned_after_cloud_ack_and_restored() {
+        let db = Database::new(DbConfig::in_memory()).await.unwrap();
+        let cola = ProductFixture::new("COLA").stock(10).insert(&db).await.unwrap();
+        let long_ago = Utc::now() - Duration::days(120);
+        let old = SaleFixture::new()
+            .receipt_number("R01-OLD-0001")
+            .line(&cola, 2)
+            .at(long_ago)
+            .queued_for_sync()
+            .insert(&db)
+            .await
+            .unwrap();
+        SaleFixture::new().line(&cola, 1).at(long_ago).draft().insert(&db).await.unwrap();
+        let sales = db.sales();
+
+        // Not in the cloud yet: kept however old
+        let cutoff = Utc::now() - Duration::days(90);
+        assert_eq!(sales.prune_before(cutoff, 100).await.unwrap(), 0);
+
+        let outbox = db.sync_outbox();
+        let entry = outbox.get_pending(10).await.unwrap().remove(0);
+        outbox.mark_uploaded(&entry.id).await.unwrap();
+        assert_eq!(sales.prune_before(cutoff, 100).await.unwrap(), 0);
+
+        // Once the ack is older than the cutoff, only the finished sale goes
+        let later = Utc::now() + Duration::seconds(1);
+        let doc = sales.get_document(&old.id).await.unwrap().unwrap();
+        assert_eq!(sales.prune_before(later, 100).await.unwrap(), 1);
+        assert!(sales.get_by_id(&old.id).await.unwrap().is_none());
+        let marker = sales.find_pruned("R01-OLD-0001").await.unwrap().unwrap();
+        assert_eq!((marker.sale_id.as_str(), marker.total_cents), (old.id.as_str(), old.total_cents));
+
+        // Fetched back from the hub: found again, without taking stock
+        let stock = db.products().get_by_id(&cola.id).await.unwrap().unwrap().current_stock;
+        assert!(sales.restore_pruned(&doc).await.unwrap());
+        assert!(sales.find_pruned("R01-OLD-0001").await.unwrap().is_none());
+        let restored = sales.get_document_by_receipt("R01-OLD-0001").await.unwrap().unwrap();
+        assert_eq!(restored.items.len(), 1);
+        let after = db.products().get_by_id(&cola.id).await.unwrap().unwrap().current_stock;
+        assert_eq!(after, stock);
+    }
+
+    #[tokio::test]
+    async fn test_tracked_and_age_checked_sales_are_not_pruned() {
+        let db = Database::new(DbConfig::in_memory()).await.unwrap();
+        let pills = ProductFixture::new("PILLS")
+            .item_tracking(ItemTracking::Lot)
+            .insert(&db)
+            .await
+            .unwrap();
+        let beer = ProductFixture::new("BEER").min_purchase_age(21).insert(&db).await.unwrap();
+        let long_ago = Utc::now() - Duration::days(120);
+        let recalled = SaleFixture::new().line(&pills, 1).at(long_ago).queued_for_sync().insert(&db).await.unwrap();
+        let carded = SaleFixture::new().line(&beer, 6).at(long_ago).queued_for_sync().insert(&db).await.unwrap();
+        let sales = db.sales();
+
+        let line = sales.get_document(&recalled.id).await.unwrap().unwrap().items.remove(0);
+        let lot = SaleItemTracking {
+            id: "trk-1".to_string(),
+            sale_id: recalled.id.clone(),
+            sale_item_id: line.id.clone(),
+            product_id: pills.id.clone(),
+            kind: ItemTracking::Lot,
+            code: "LOT-42".to_string(),
+            created_at: long_ago,
+        };
+        sales.set_item_tracking(&line.id, &[lot]).await.unwrap();
+        sales
+            .set_age_verification(&AgeVerification {
+                sale_id: carded.id.clone(),
+                required_age: 21,
+                method: AgeVerificationMethod::IdScan,
+                birthdate: None,
+                verified_by: "cashier-1".to_string(),
+                device_id: "pos-01".to_string(),
+                verified_at: long_ago,
+            })
+            .await
+            .unwrap();
+
+        let outbox = db.sync_outbox();
+        for entry in outbox.get_pending(10).await.unwrap() {
+            outbox.mark_uploaded(&entry.id).await.unwrap();
+        }
+
+        // Acked long ago, but the lot and the age check keep both sales
+        let later = Utc::now() + Duration::seconds(1);
+        assert_eq!(sales.prune_before(later, 100).await.unwrap(), 0);
+
+        let found = sales.find_by_tracking_code(ItemTracking::Lot, "LOT-42", None, 10).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].sale_id, recalled.id);
+        assert!(sales.get_age_verification(&carded.id).await.unwrap().is_some());
+    }
+}
@@ -0,0 +1,92 @@
+//! # Stock Movements
+//!
+//! Shared helper for documents that move stock outside of the plain
+//! per-line sale decrement (layaways, store transfers, bundle components,
+//! stock receipts, waste). Each movement updates the product's
+//! `current_stock` and writes an `inventory_deltas` row so the audit trail
+//! shows which document moved the goods.
+
+use chrono::Utc;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::error::DbResult;
+
+/// `inventory_deltas.reference_type` for layaway reservations.
+pub(crate) const REFERENCE_LAYAWAY: &str = "layaway";
+
+/// `inventory_deltas.reference_type` for store transfers.
+pub(crate) const REFERENCE_TRANSFER: &str = "transfer";
+
+/// `inventory_deltas.reference_type` for sales (bundle components).
+pub(crate) const REFERENCE_SALE: &str = "sale";
+
+/// `inventory_deltas.reference_type` for stock receipts.
+pub(crate) const REFERENCE_RECEIPT: &str = "receipt";
+
+/// `inventory_deltas.reference_type` for waste write-offs.
+pub(crate) const REFERENCE_WASTE: &str = "waste";
+
+/// Adjusts stock for a tracked product and records the inventory delta.
+///
+/// Products with `track_inventory = 0` are left alone and get no delta row.
+pub(crate) async fn move_stock(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    reference_type: &str,
+    product_id: &str,
+    delta: i64,
+    delta_type: &str,
+    reference_id: &str,
+    device_id: &str,
+) -> DbResult<()> {
+    let now = Utc::now();
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE products
+        SET
+            current_stock = COALESCE(current_stock, 0) + ?2,
+            updated_at = ?3,
+            sync_version = sync_version + 1
+        WHERE id = ?1 AND track_inventory = 1
+        "#,
+        product_id,
+        delta,
+        now
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Ok(());
+    }
+
+    let id = Uuid::new_v4().to_string();
+    sqlx::query!(
+        r#"
+        INSERT INTO inventory_deltas (
+            id, product_id, delta, delta_type, reference_id, reference_type,
+            origin_device_id, occurred_at, sequence_num, created_at
+        )
+        SELECT
+            ?1, ?2, ?3, ?4, ?5, ?6,
+            ?7, ?8, COALESCE(MAX(sequence_num), 0) + 1, ?8
+        FROM inventory_deltas
+        WHERE origin_device_id = ?7
+        "#,
+        id,
+        product_id,
+        delta,
+        delta_type,
+        reference_id,
+        reference_type,
+        device_id,
+        now
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    debug!(product_id = %product_id, delta, delta_type, reference_type, "Stock moved");
+
+    Ok(())
+}
@@ -0,0 +1,450 @@
+//! # Store Credit Repository
+//!
+//! Database operations for store credit accounts and their ledger.
+//!
+//! ## Ledger Writes
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                     Store Credit Ledger Writes                          │
+//! │                                                                         │
+//! │  SaleRepository::add_refund(refund, Some(doc))   issue (+)              │
+//! │                                                                         │
+//! │  redeem(entry)                                   redeem (-)             │
+//! │  ┌─────────────────────────────────────────┐                            │
+//! │  │ SINGLE TX                               │                            │
+//! │  │ balance = SUM(entries)                  │                            │
+//! │  │ balance >= amount ? insert : reject     │                            │
+//! │  └─────────────────────────────────────────┘                            │
+//! │  Runs on the hub for every terminal in the store (or locally on a      │
+//! │  standalone terminal), so the check and the write are serialized.      │
+//! │                                                                         │
+//! │  upsert_from_sync(doc)   union of entries by id, never rejects          │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::{debug, info, warn};
+
+use crate::error::{DbError, DbResult};
+use crate::pii::{PiiCipher, PiiField};
+use titan_core::{StoreCreditAccount, StoreCreditDocument, StoreCreditEntry, StoreCreditEntryKind};
+
+/// Outcome of a checked redemption.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Redemption {
+    /// Whether the entry is on the ledger (also `true` for a replay of an
+    /// entry that was already applied).
+    pub applied: bool,
+    /// Balance after the redemption, or the current balance if rejected.
+    pub balance_cents: i64,
+}
+
+/// Repository for store credit database operations.
+#[derive(Debug, Clone)]
+pub struct StoreCreditRepository {
+    pool: SqlitePool,
+    pii: PiiCipher,
+}
+
+impl StoreCreditRepository {
+    /// Creates a new StoreCreditRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        StoreCreditRepository {
+            pool,
+            pii: PiiCipher::disabled(),
+        }
+    }
+
+    /// Encrypts customer phone numbers with `pii` (see [`crate::pii`]).
+    pub fn with_pii(mut self, pii: PiiCipher) -> Self {
+        self.pii = pii;
+        self
+    }
+
+    /// Decrypts an account's customer phone.
+    fn reveal(&self, mut account: StoreCreditAccount) -> DbResult<StoreCreditAccount> {
+        account.customer_phone = self.pii.decrypt_opt(PiiField::Phone, account.customer_phone)?;
+        Ok(account)
+    }
+
+    /// Gets an account by ID.
+    pub async fn get_by_id(&self, id: &str) -> DbResult<Option<StoreCreditAccount>> {
+        let account = sqlx::query_as!(
+            StoreCreditAccount,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                credit_number,
+                customer_name,
+                customer_phone,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                sync_version
+            FROM store_credit_accounts
+            WHERE id = ?1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        account.map(|a| self.reveal(a)).transpose()
+    }
+
+    /// Gets an account by its printed credit number.
+    pub async fn get_by_number(&self, credit_number: &str) -> DbResult<Option<StoreCreditAccount>> {
+        let account = sqlx::query_as!(
+            StoreCreditAccount,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                credit_number,
+                customer_name,
+                customer_phone,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                sync_version
+            FROM store_credit_accounts
+            WHERE credit_number = ?1
+            "#,
+            credit_number
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        account.map(|a| self.reveal(a)).transpose()
+    }
+
+    /// Finds accounts for a customer phone number, newest first.
+    ///
+    /// Matches on the phone's lookup hash, so surrounding spaces are
+    /// ignored.
+    pub async fn find_by_phone(&self, customer_phone: &str) -> DbResult<Vec<StoreCreditAccount>> {
+        let phone_hash = self.pii.blind_index(PiiField::Phone, customer_phone);
+        let accounts = sqlx::query_as!(
+            StoreCreditAccount,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                credit_number,
+                customer_name,
+                customer_phone,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                sync_version
+            FROM store_credit_accounts
+            WHERE customer_phone_hash = ?1
+            ORDER BY created_at DESC
+            "#,
+            phone_hash
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        accounts.into_iter().map(|a| self.reveal(a)).collect()
+    }
+
+    /// Gets the ledger entries of an account, oldest first.
+    pub async fn get_entries(&self, account_id: &str) -> DbResult<Vec<StoreCreditEntry>> {
+        let entries = sqlx::query_as!(
+            StoreCreditEntry,
+            r#"
+            SELECT
+                id,
+                account_id,
+                kind as "kind: StoreCreditEntryKind",
+                amount_cents,
+                sale_id,
+                device_id,
+                created_at as "created_at: DateTime<Utc>"
+            FROM store_credit_entries
+            WHERE account_id = ?1
+            ORDER BY created_at, id
+            "#,
+            account_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// Gets an account with its ledger.
+    pub async fn get_document(&self, id: &str) -> DbResult<Option<StoreCreditDocument>> {
+        let Some(account) = self.get_by_id(id).await? else {
+            return Ok(None);
+        };
+        let entries = self.get_entries(id).await?;
+
+        Ok(Some(StoreCreditDocument { account, entries }))
+    }
+
+    /// Current balance of an account.
+    pub async fn get_balance(&self, account_id: &str) -> DbResult<i64> {
+        let balance = sqlx::query_scalar!(
+            r#"
+            SELECT COALESCE(SUM(amount_cents), 0) as "balance!: i64"
+            FROM store_credit_entries
+            WHERE account_id = ?1
+            "#,
+            account_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(balance)
+    }
+
+    /// Writes a redemption if, and only if, the balance covers it.
+    ///
+    /// `entry.amount_cents` is negative. Replaying an entry that is already
+    /// on the ledger is reported as applied without spending twice.
+    ///
+    /// ## Errors
+    /// `DbError::NotFound` if the account does not exist.
+    pub async fn redeem(&self, entry: &StoreCreditEntry) -> DbResult<Redemption> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        // Take the write lock before reading the balance so two redemptions
+        // against the same ledger are serialized.
+        let touched = sqlx::query!(
+            r#"
+            UPDATE store_credit_accounts
+            SET updated_at = ?2, sync_version = sync_version + 1
+            WHERE id = ?1
+            "#,
+            entry.account_id,
+            entry.created_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if touched.rows_affected() == 0 {
+            return Err(DbError::not_found("Store credit", &entry.account_id));
+        }
+
+        let replayed: Option<String> = sqlx::query_scalar!(
+            "SELECT id FROM store_credit_entries WHERE id = ?1",
+            entry.id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let balance: i64 = sqlx::query_scalar!(
+            r#"
+            SELECT COALESCE(SUM(amount_cents), 0) as "balance!: i64"
+            FROM store_credit_entries
+            WHERE account_id = ?1
+            "#,
+            entry.account_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if replayed.is_some() {
+            // Nothing to write; the lock-only update is rolled back on drop.
+            return Ok(Redemption {
+                applied: true,
+                balance_cents: balance,
+            });
+        }
+
+        if balance + entry.amount_cents < 0 {
+            debug!(account_id = %entry.account_id, balance, amount = entry.amount_cents, "Store credit redemption rejected");
+            return Ok(Redemption {
+                applied: false,
+                balance_cents: balance,
+            });
+        }
+
+        insert_entry(&mut tx, entry).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        info!(account_id = %entry.account_id, amount = entry.amount_cents, "Store credit redeemed");
+
+        Ok(Redemption {
+            applied: true,
+            balance_cents: balance + entry.amount_cents,
+        })
+    }
+
+    /// Merges an account received from another terminal or store.
+    ///
+    /// The account row is created if missing (customer details follow the
+    /// higher `sync_version`), and entries are merged by ID. Nothing is
+    /// rejected: redemptions were already checked where they were written.
+    ///
+    /// ## Returns
+    /// `true` if anything changed locally.
+    pub async fn upsert_from_sync(&self, doc: &StoreCreditDocument) -> DbResult<bool> {
+        let account = &doc.account;
+        let phone = account.customer_phone.as_deref();
+        let phone_enc = self.pii.encrypt_opt(PiiField::Phone, phone)?;
+        let phone_hash = self.pii.blind_index_opt(PiiField::Phone, phone);
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let account_changed = sqlx::query!(
+            r#"
+            INSERT INTO store_credit_accounts (
+                id, tenant_id, credit_number,
+                customer_name, customer_phone, customer_phone_hash,
+                created_at, updated_at, sync_version
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            ON CONFLICT(id) DO UPDATE SET
+                customer_name = excluded.customer_name,
+                customer_phone = excluded.customer_phone,
+                customer_phone_hash = excluded.customer_phone_hash,
+                updated_at = excluded.updated_at,
+                sync_version = excluded.sync_version
+            WHERE excluded.sync_version > store_credit_accounts.sync_version
+            "#,
+            account.id,
+            account.tenant_id,
+            account.credit_number,
+            account.customer_name,
+            phone_enc,
+            phone_hash,
+            account.created_at,
+            account.updated_at,
+            account.sync_version
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            > 0;
+
+        let mut new_entries = 0;
+        for entry in &doc.entries {
+            new_entries += sqlx::query!(
+                r#"
+                INSERT OR IGNORE INTO store_credit_entries (
+                    id, account_id, kind, amount_cents, sale_id, device_id, created_at
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                "#,
+                entry.id,
+                account.id,
+                entry.kind,
+                entry.amount_cents,
+                entry.sale_id,
+                entry.device_id,
+                entry.created_at
+            )
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+
+        let balance: i64 = sqlx::query_scalar!(
+            r#"
+            SELECT COALESCE(SUM(amount_cents), 0) as "balance!: i64"
+            FROM store_credit_entries
+            WHERE account_id = ?1
+            "#,
+            account.id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        if balance < 0 {
+            // Spent in two places that could not see each other (see
+            // titan_core::store_credit); surfaced for follow-up.
+            warn!(
+                credit_number = %account.credit_number,
+                balance,
+                "Store credit overdrawn after merge"
+            );
+        }
+
+        Ok(account_changed || new_entries > 0)
+    }
+}
+
+/// Appends issued credit inside an open transaction.
+///
+/// The account is created if it is new (its phone encrypted with `pii`),
+/// otherwise its version is bumped; `doc.entries` holds only the entries
+/// to append.
+pub(crate) async fn issue_credit(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    doc: &StoreCreditDocument,
+    pii: &PiiCipher,
+) -> DbResult<()> {
+    let account = &doc.account;
+    let phone = account.customer_phone.as_deref();
+    let phone_enc = pii.encrypt_opt(PiiField::Phone, phone)?;
+    let phone_hash = pii.blind_index_opt(PiiField::Phone, phone);
+    sqlx::query!(
+        r#"
+        INSERT INTO store_credit_accounts (
+            id, tenant_id, credit_number,
+            customer_name, customer_phone, customer_phone_hash,
+            created_at, updated_at, sync_version
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+        ON CONFLICT(id) DO UPDATE SET
+            updated_at = excluded.updated_at,
+            sync_version = store_credit_accounts.sync_version + 1
+        "#,
+        account.id,
+        account.tenant_id,
+        account.credit_number,
+        account.customer_name,
+        phone_enc,
+        phone_hash,
+        account.created_at,
+        account.updated_at,
+        account.sync_version
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    for entry in &doc.entries {
+        insert_entry(tx, entry).await?;
+    }
+
+    Ok(())
+}
+
+/// Inserts one ledger entry inside an open transaction.
+async fn insert_entry(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    entry: &StoreCreditEntry,
+) -> DbResult<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO store_credit_entries (
+            id, account_id, kind, amount_cents, sale_id, device_id, created_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+        "#,
+        entry.id,
+        entry.account_id,
+        entry.kind,
+        entry.amount_cents,
+        entry.sale_id,
+        entry.device_id,
+        entry.created_at
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
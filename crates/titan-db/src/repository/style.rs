@@ -0,0 +1,640 @@
+//! # Product Style Repository
+//!
+//! Database operations for product styles and their variants (see
+//! `titan_core::variant`).
+//!
+//! ## Variants
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                       Adding a Variant                                  │
+//! │                                                                         │
+//! │  add_variant(style, product, ["M", "black"])                            │
+//! │       │                                                                 │
+//! │       ├── group.check_options: values for every key, combination free   │
+//! │       ├── product attributes: size=M, color=black (others kept)         │
+//! │       │     └── queued as PRODUCT_ATTRIBUTES                            │
+//! │       ├── products.style_id = style                                     │
+//! │       └── style version + 1, queued as PRODUCT_STYLE (variant IDs)      │
+//! │                                                                         │
+//! │  search_grouped("tee") ──► FTS hits, variants collapsed into one       │
+//! │                            result per style                            │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::error::{DbError, DbResult};
+use crate::events::EventPublisher;
+use crate::repository::product::ProductRepository;
+use crate::repository::sync::SyncOutboxRepository;
+use titan_core::variant::{
+    group_search_results, normalize_option_keys, validate_style_name, variant_options,
+};
+use titan_core::{
+    ItemTracking, Product, ProductAttribute, ProductStyle, ProductStyleDocument, SearchResult,
+    Variant, VariantGroup, DEFAULT_TENANT_ID,
+};
+
+/// Repository for product styles and variants.
+#[derive(Debug, Clone)]
+pub struct ProductStyleRepository {
+    pool: SqlitePool,
+    events: EventPublisher,
+}
+
+/// A `product_styles` row; option keys are stored comma-separated.
+struct StyleRow {
+    id: String,
+    tenant_id: String,
+    name: String,
+    description: Option<String>,
+    option_keys: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    sync_version: i64,
+}
+
+impl From<StyleRow> for ProductStyle {
+    fn from(row: StyleRow) -> Self {
+        ProductStyle {
+            id: row.id,
+            tenant_id: row.tenant_id,
+            name: row.name,
+            description: row.description,
+            option_keys: row.option_keys.split(',').map(str::to_string).collect(),
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            sync_version: row.sync_version,
+        }
+    }
+}
+
+impl ProductStyleRepository {
+    /// Creates a new ProductStyleRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        ProductStyleRepository {
+            pool,
+            events: EventPublisher::disabled(),
+        }
+    }
+
+    /// Publishes `ProductChanged` events for variant attribute changes
+    /// through `events`.
+    pub fn with_events(mut self, events: EventPublisher) -> Self {
+        self.events = events;
+        self
+    }
+
+    fn products(&self) -> ProductRepository {
+        ProductRepository::new(self.pool.clone()).with_events(self.events.clone())
+    }
+
+    /// Creates a style and queues it for sync.
+    ///
+    /// ## Errors
+    /// `DbError::InvalidInput` for an empty name or invalid option keys.
+    pub async fn create(
+        &self,
+        name: &str,
+        description: Option<&str>,
+        option_keys: &[String],
+    ) -> DbResult<ProductStyle> {
+        validate_style_name(name)?;
+        let option_keys = normalize_option_keys(option_keys)?;
+        let now = Utc::now();
+
+        let style = ProductStyle {
+            id: Uuid::new_v4().to_string(),
+            tenant_id: DEFAULT_TENANT_ID.to_string(),
+            name: name.trim().to_string(),
+            description: description
+                .map(str::trim)
+                .filter(|d| !d.is_empty())
+                .map(str::to_string),
+            option_keys,
+            created_at: now,
+            updated_at: now,
+            sync_version: 1,
+        };
+
+        debug!(id = %style.id, name = %style.name, "Creating product style");
+
+        let option_keys = style.option_keys.join(",");
+        sqlx::query!(
+            r#"
+            INSERT INTO product_styles (
+                id, tenant_id, name, description, option_keys,
+                created_at, updated_at, sync_version
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            "#,
+            style.id,
+            style.tenant_id,
+            style.name,
+            style.description,
+            option_keys,
+            style.created_at,
+            style.updated_at,
+            style.sync_version
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.queue(&ProductStyleDocument {
+            style: style.clone(),
+            variant_ids: Vec::new(),
+        })
+        .await?;
+        Ok(style)
+    }
+
+    /// Gets a style by ID.
+    pub async fn get(&self, id: &str) -> DbResult<Option<ProductStyle>> {
+        let row = sqlx::query_as!(
+            StyleRow,
+            r#"
+            SELECT
+                id as "id!",
+                tenant_id,
+                name,
+                description,
+                option_keys,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                sync_version
+            FROM product_styles
+            WHERE id = ?1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(ProductStyle::from))
+    }
+
+    /// Changes a style's shared name and description.
+    ///
+    /// ## Errors
+    /// - `DbError::InvalidInput` for an empty name
+    /// - `DbError::NotFound` if the style doesn't exist
+    pub async fn update_details(
+        &self,
+        id: &str,
+        name: &str,
+        description: Option<&str>,
+    ) -> DbResult<ProductStyle> {
+        validate_style_name(name)?;
+        let name = name.trim();
+        let description = description.map(str::trim).filter(|d| !d.is_empty());
+        let now = Utc::now();
+
+        let updated = sqlx::query!(
+            r#"
+            UPDATE product_styles
+            SET name = ?2, description = ?3, updated_at = ?4, sync_version = sync_version + 1
+            WHERE id = ?1
+            "#,
+            id,
+            name,
+            description,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Err(DbError::not_found("ProductStyle", id));
+        }
+
+        self.queue_current(id).await?;
+        self.get(id)
+            .await?
+            .ok_or_else(|| DbError::not_found("ProductStyle", id))
+    }
+
+    /// Gets a style with its variants (active or not, deleted ones left
+    /// out), oldest first.
+    pub async fn group(&self, id: &str) -> DbResult<Option<VariantGroup>> {
+        let Some(style) = self.get(id).await? else {
+            return Ok(None);
+        };
+
+        let products = self.variant_products(id).await?;
+        let mut variants = Vec::with_capacity(products.len());
+        for product in products {
+            let attributes = self.products().attributes(&product.id).await?;
+            // A variant whose options were edited away still shows, with
+            // empty values, so it can be fixed or removed
+            let options = variant_options(&style.option_keys, &attributes.attributes)
+                .unwrap_or_else(|_| {
+                    style
+                        .option_keys
+                        .iter()
+                        .map(|key| attributes.get(key).unwrap_or_default().to_string())
+                        .collect()
+                });
+            variants.push(Variant { product, options });
+        }
+
+        Ok(Some(VariantGroup::new(style, variants)))
+    }
+
+    /// Makes `product_id` a variant of `style_id` with `options` (values in
+    /// the style's option key order), or changes its values if it already
+    /// is one. The values are written to the product's attributes.
+    ///
+    /// ## Errors
+    /// - `DbError::InvalidInput` for missing values or values another
+    ///   variant already has
+    /// - `DbError::NotFound` if the style or product doesn't exist
+    pub async fn add_variant(
+        &self,
+        style_id: &str,
+        product_id: &str,
+        options: &[String],
+    ) -> DbResult<VariantGroup> {
+        let group = self
+            .group(style_id)
+            .await?
+            .ok_or_else(|| DbError::not_found("ProductStyle", style_id))?;
+        let options: Vec<String> = options.iter().map(|o| o.trim().to_string()).collect();
+        group.check_options(product_id, &options)?;
+
+        debug!(style_id = %style_id, product_id = %product_id, ?options, "Adding variant");
+
+        // Option values replace any earlier values for the same keys
+        let current = self.products().attributes(product_id).await?;
+        let mut attributes: Vec<ProductAttribute> = current
+            .attributes
+            .into_iter()
+            .filter(|a| !group.style.option_keys.contains(&a.key))
+            .collect();
+        attributes.extend(
+            group
+                .style
+                .option_keys
+                .iter()
+                .zip(&options)
+                .map(|(key, value)| ProductAttribute::new(key.as_str(), value.as_str())),
+        );
+        self.products().set_attributes(product_id, &attributes).await?;
+
+        let linked = sqlx::query!(
+            "UPDATE products SET style_id = ?2 WHERE id = ?1 AND deleted_at IS NULL",
+            product_id,
+            style_id
+        )
+        .execute(&self.pool)
+        .await?;
+        if linked.rows_affected() == 0 {
+            return Err(DbError::not_found("Product", product_id));
+        }
+
+        self.touch(style_id).await?;
+        self.queue_current(style_id).await?;
+        self.group(style_id)
+            .await?
+            .ok_or_else(|| DbError::not_found("ProductStyle", style_id))
+    }
+
+    /// Detaches a product from its style; it stays a product of its own.
+    /// Its attributes are left as they are.
+    ///
+    /// ## Errors
+    /// `DbError::NotFound` if the product is not a variant.
+    pub async fn remove_variant(&self, product_id: &str) -> DbResult<()> {
+        let style_id: Option<String> = sqlx::query_scalar!(
+            r#"SELECT style_id as "style_id!" FROM products WHERE id = ?1 AND style_id IS NOT NULL"#,
+            product_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        let Some(style_id) = style_id else {
+            return Err(DbError::not_found("Variant", product_id));
+        };
+
+        sqlx::query!("UPDATE products SET style_id = NULL WHERE id = ?1", product_id)
+            .execute(&self.pool)
+            .await?;
+
+        self.touch(&style_id).await?;
+        self.queue_current(&style_id).await
+    }
+
+    /// Applies a style received through sync: the style itself and which
+    /// known products are its variants.
+    ///
+    /// ## Returns
+    /// * `Ok(true)` - Applied
+    /// * `Ok(false)` - Already at or past the document's version
+    pub async fn upsert_from_sync(&self, doc: &ProductStyleDocument) -> DbResult<bool> {
+        let style = &doc.style;
+        style.validate()?;
+        let option_keys = style.option_keys.join(",");
+        let variant_ids = serde_json::to_string(&doc.variant_ids)
+            .map_err(|e| DbError::Internal(format!("Failed to serialize variant IDs: {}", e)))?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let current: Option<i64> = sqlx::query_scalar!(
+            r#"SELECT sync_version as "v!: i64" FROM product_styles WHERE id = ?1"#,
+            style.id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        if current.is_some_and(|v| v >= style.sync_version) {
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO product_styles (
+                id, tenant_id, name, description, option_keys,
+                created_at, updated_at, sync_version
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                description = excluded.description,
+                option_keys = excluded.option_keys,
+                updated_at = excluded.updated_at,
+                sync_version = excluded.sync_version
+            "#,
+            style.id,
+            style.tenant_id,
+            style.name,
+            style.description,
+            option_keys,
+            style.created_at,
+            style.updated_at,
+            style.sync_version
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE products SET style_id = NULL
+            WHERE style_id = ?1 AND id NOT IN (SELECT value FROM json_each(?2))
+            "#,
+            style.id,
+            variant_ids
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "UPDATE products SET style_id = ?1 WHERE id IN (SELECT value FROM json_each(?2))",
+            style.id,
+            variant_ids
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+        Ok(true)
+    }
+
+    /// Lists styles with IDs after `after_id`, in ID order, as sync
+    /// documents (for a full resync); pass an empty string for the first
+    /// page.
+    pub async fn list_after(
+        &self,
+        after_id: &str,
+        limit: u32,
+    ) -> DbResult<Vec<ProductStyleDocument>> {
+        let rows = sqlx::query_as!(
+            StyleRow,
+            r#"
+            SELECT
+                id as "id!",
+                tenant_id,
+                name,
+                description,
+                option_keys,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                sync_version
+            FROM product_styles
+            WHERE id > ?1
+            ORDER BY id
+            LIMIT ?2
+            "#,
+            after_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut docs = Vec::with_capacity(rows.len());
+        for row in rows {
+            let style = ProductStyle::from(row);
+            let variant_ids = self.variant_ids(&style.id).await?;
+            docs.push(ProductStyleDocument { style, variant_ids });
+        }
+        Ok(docs)
+    }
+
+    /// Searches products like `ProductRepository::search`, with the
+    /// variants of a style collapsed into one result.
+    pub async fn search_grouped(&self, query: &str, limit: u32) -> DbResult<Vec<SearchResult>> {
+        let products = self.products().search(query, limit).await?;
+        let ids = serde_json::to_string(&products.iter().map(|p| &p.id).collect::<Vec<_>>())
+            .map_err(|e| DbError::Internal(format!("Failed to serialize product IDs: {}", e)))?;
+
+        let links = sqlx::query!(
+            r#"
+            SELECT p.id as "product_id!", s.id as "style_id!"
+            FROM products p
+            JOIN product_styles s ON s.id = p.style_id
+            WHERE p.id IN (SELECT value FROM json_each(?1))
+            "#,
+            ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut styles: Vec<(String, ProductStyle)> = Vec::with_capacity(links.len());
+        for link in links {
+            if let Some(style) = self.get(&link.style_id).await? {
+                styles.push((link.product_id, style));
+            }
+        }
+
+        Ok(group_search_results(products, |product: &Product| {
+            styles
+                .iter()
+                .find(|(id, _)| *id == product.id)
+                .map(|(_, style)| style)
+        }))
+    }
+
+    // =========================================================================
+    // Helpers
+    // =========================================================================
+
+    async fn variant_ids(&self, style_id: &str) -> DbResult<Vec<String>> {
+        let ids = sqlx::query_scalar!(
+            r#"
+            SELECT id as "id!" FROM products
+            WHERE style_id = ?1 AND deleted_at IS NULL
+            ORDER BY created_at, sku
+            "#,
+            style_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids)
+    }
+
+    async fn variant_products(&self, style_id: &str) -> DbResult<Vec<Product>> {
+        let products = sqlx::query_as!(
+            Product,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                sku,
+                barcode,
+                name,
+                description,
+                price_cents,
+                base_price_cents,
+                cost_cents,
+                tax_rate_bps as "tax_rate_bps: u32",
+                track_inventory as "track_inventory: bool",
+                allow_negative_stock as "allow_negative_stock: bool",
+                current_stock,
+                item_tracking as "item_tracking: ItemTracking",
+                min_purchase_age as "min_purchase_age: u32",
+                is_active as "is_active: bool",
+                deleted_at as "deleted_at: chrono::DateTime<Utc>",
+                created_at as "created_at: chrono::DateTime<Utc>",
+                updated_at as "updated_at: chrono::DateTime<Utc>",
+                sync_version
+            FROM products
+            WHERE style_id = ?1 AND deleted_at IS NULL
+            ORDER BY created_at, sku
+            "#,
+            style_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(products)
+    }
+
+    /// Bumps a style's version after its variants changed.
+    async fn touch(&self, style_id: &str) -> DbResult<()> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"
+            UPDATE product_styles
+            SET updated_at = ?2, sync_version = sync_version + 1
+            WHERE id = ?1
+            "#,
+            style_id,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn queue_current(&self, style_id: &str) -> DbResult<()> {
+        let style = self
+            .get(style_id)
+            .await?
+            .ok_or_else(|| DbError::not_found("ProductStyle", style_id))?;
+        let variant_ids = self.variant_ids(style_id).await?;
+        self.queue(&ProductStyleDocument { style, variant_ids }).await
+    }
+
+    async fn queue(&self, doc: &ProductStyleDocument) -> DbResult<()> {
+        let payload = serde_json::to_string(doc)
+            .map_err(|e| DbError::Internal(format!("Failed to serialize style: {}", e)))?;
+        SyncOutboxRepository::new(self.pool.clone())
+            .upsert_for_sync("PRODUCT_STYLE", &doc.style.id, &payload)
+            .await
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::Fixtures;
+    use crate::pool::{Database, DbConfig};
+    use titan_core::{ProductStyleDocument, SearchResult};
+
+    #[tokio::test]
+    async fn test_styles_variants_and_grouped_search() {
+        let config = DbConfig::in_memory().with_fixtures(Fixtures::new().products(3));
+        let db = Database::new(config).await.unwrap();
+        let products = db.products().search("", 3).await.unwrap();
+        let keys = vec!["Size".to_string(), "color".to_string()];
+
+        let style = db
+            .styles()
+            .create("Crew Tee", Some("Heavy cotton"), &keys)
+            .await
+            .unwrap();
+        assert_eq!(style.option_keys, vec!["size", "color"]);
+
+        let options = |size: &str, color: &str| vec![size.to_string(), color.to_string()];
+        db.styles()
+            .add_variant(&style.id, &products[0].id, &options("M", "black"))
+            .await
+            .unwrap();
+        let group = db
+            .styles()
+            .add_variant(&style.id, &products[1].id, &options("L", "black"))
+            .await
+            .unwrap();
+        assert_eq!(group.variants.len(), 2);
+        assert_eq!(group.style.sync_version, 3);
+        assert_eq!(group.find(&options("L", "black")).unwrap().product.id, products[1].id);
+        let attributes = db.products().attributes(&products[0].id).await.unwrap();
+        assert_eq!(attributes.get("size"), Some("M"));
+
+        // The same combination can't be taken twice
+        assert!(db
+            .styles()
+            .add_variant(&style.id, &products[2].id, &options("m", "Black"))
+            .await
+            .is_err());
+
+        // Variants collapse into one search result
+        let results = db.styles().search_grouped("", 10).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().any(|r| matches!(
+            r,
+            SearchResult::Style { matches, .. } if matches.len() == 2
+        )));
+
+        let pending = db.sync_outbox().get_pending(20).await.unwrap();
+        let doc = pending
+            .iter()
+            .find(|e| e.entity_type == "PRODUCT_STYLE")
+            .unwrap();
+        let doc: ProductStyleDocument = serde_json::from_str(&doc.payload).unwrap();
+        assert_eq!(doc.variant_ids.len(), 2);
+
+        // Sync moves the variant set to what the document lists
+        db.styles().remove_variant(&products[0].id).await.unwrap();
+        assert!(db.styles().remove_variant(&products[0].id).await.is_err());
+        let mut incoming = doc.clone();
+        incoming.style.sync_version += 10;
+        assert!(db.styles().upsert_from_sync(&incoming).await.unwrap());
+        assert!(!db.styles().upsert_from_sync(&incoming).await.unwrap());
+        assert_eq!(db.styles().group(&style.id).await.unwrap().unwrap().variants.len(), 2);
+        assert_eq!(db.styles().list_after("", 10).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,559 @@
+//! # Supplier Repository
+//!
+//! Database operations for suppliers, their price lists and product costs
+//! (see `titan_core::supplier`).
+//!
+//! ## Suppliers
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                     Suppliers and Price Lists                           │
+//! │                                                                         │
+//! │  create / update         suppliers row, version + 1                    │
+//! │  set_items(supplier, [COKE-330 "AC-7781" 0.42, ...])                   │
+//! │       ├── normalize_items: one entry per product, costs in range       │
+//! │       ├── supplier_items replaced                                      │
+//! │       └── version + 1                                                  │
+//! │  every change queues the supplier with its items as SUPPLIER           │
+//! │  (relayed to the other terminals, uploaded to the cloud)               │
+//! │                                                                         │
+//! │  product_cost(product)   last / average cost from costed receipts      │
+//! │                          (maintained by PackRepository::receive)       │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::error::{DbError, DbResult};
+use crate::repository::sync::SyncOutboxRepository;
+use titan_core::supplier::{normalize_items, validate_supplier_name};
+use titan_core::{ProductCost, Supplier, SupplierDocument, SupplierItem};
+
+/// Repository for suppliers and product costs.
+#[derive(Debug, Clone)]
+pub struct SupplierRepository {
+    pool: SqlitePool,
+}
+
+impl SupplierRepository {
+    /// Creates a new SupplierRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        SupplierRepository { pool }
+    }
+
+    /// Creates a supplier with an empty price list and queues it for sync.
+    ///
+    /// ## Errors
+    /// `DbError::InvalidInput` for an empty or too long name.
+    pub async fn create(
+        &self,
+        name: &str,
+        contact_name: Option<&str>,
+        phone: Option<&str>,
+        email: Option<&str>,
+    ) -> DbResult<Supplier> {
+        validate_supplier_name(name)?;
+        let now = Utc::now();
+
+        let supplier = Supplier {
+            id: Uuid::new_v4().to_string(),
+            name: name.trim().to_string(),
+            contact_name: trimmed(contact_name),
+            phone: trimmed(phone),
+            email: trimmed(email),
+            is_active: true,
+            created_at: now,
+            updated_at: now,
+            sync_version: 1,
+        };
+
+        debug!(id = %supplier.id, name = %supplier.name, "Creating supplier");
+
+        sqlx::query!(
+            r#"
+            INSERT INTO suppliers (
+                id, name, contact_name, phone, email, is_active,
+                created_at, updated_at, sync_version
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            "#,
+            supplier.id,
+            supplier.name,
+            supplier.contact_name,
+            supplier.phone,
+            supplier.email,
+            supplier.is_active,
+            supplier.created_at,
+            supplier.updated_at,
+            supplier.sync_version
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.queue(&SupplierDocument {
+            supplier: supplier.clone(),
+            items: Vec::new(),
+        })
+        .await?;
+        Ok(supplier)
+    }
+
+    /// Changes a supplier's name, contact details and whether it is
+    /// active.
+    ///
+    /// ## Errors
+    /// - `DbError::InvalidInput` for an empty or too long name
+    /// - `DbError::NotFound` if the supplier doesn't exist
+    pub async fn update(
+        &self,
+        id: &str,
+        name: &str,
+        contact_name: Option<&str>,
+        phone: Option<&str>,
+        email: Option<&str>,
+        is_active: bool,
+    ) -> DbResult<SupplierDocument> {
+        validate_supplier_name(name)?;
+        let name = name.trim();
+        let contact_name = trimmed(contact_name);
+        let phone = trimmed(phone);
+        let email = trimmed(email);
+        let now = Utc::now();
+
+        let updated = sqlx::query!(
+            r#"
+            UPDATE suppliers SET
+                name = ?2, contact_name = ?3, phone = ?4, email = ?5, is_active = ?6,
+                updated_at = ?7, sync_version = sync_version + 1
+            WHERE id = ?1
+            "#,
+            id,
+            name,
+            contact_name,
+            phone,
+            email,
+            is_active,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Err(DbError::not_found("Supplier", id));
+        }
+
+        self.queue_current(id).await
+    }
+
+    /// Replaces a supplier's price list.
+    ///
+    /// ## Errors
+    /// - `DbError::InvalidInput` for a product listed twice, a cost out of
+    ///   range or a supplier SKU that is too long
+    /// - `DbError::NotFound` if the supplier or a product doesn't exist
+    pub async fn set_items(&self, id: &str, items: &[SupplierItem]) -> DbResult<SupplierDocument> {
+        let items = normalize_items(items)?;
+        let now = Utc::now();
+
+        debug!(id = %id, count = items.len(), "Setting supplier items");
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let updated = sqlx::query!(
+            r#"
+            UPDATE suppliers SET updated_at = ?2, sync_version = sync_version + 1
+            WHERE id = ?1
+            "#,
+            id,
+            now
+        )
+        .execute(&mut *tx)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Err(DbError::not_found("Supplier", id));
+        }
+
+        sqlx::query!("DELETE FROM supplier_items WHERE supplier_id = ?1", id)
+            .execute(&mut *tx)
+            .await?;
+        for item in &items {
+            let inserted = sqlx::query!(
+                r#"
+                INSERT INTO supplier_items (supplier_id, product_id, supplier_sku, cost_cents)
+                SELECT ?1, id, ?3, ?4 FROM products WHERE id = ?2 AND deleted_at IS NULL
+                "#,
+                id,
+                item.product_id,
+                item.supplier_sku,
+                item.cost_cents
+            )
+            .execute(&mut *tx)
+            .await?;
+            if inserted.rows_affected() == 0 {
+                return Err(DbError::not_found("Product", &item.product_id));
+            }
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        self.queue_current(id).await
+    }
+
+    /// Gets a supplier with its price list.
+    pub async fn get(&self, id: &str) -> DbResult<Option<SupplierDocument>> {
+        let supplier = sqlx::query_as!(
+            Supplier,
+            r#"
+            SELECT
+                id as "id!",
+                name,
+                contact_name,
+                phone,
+                email,
+                is_active as "is_active: bool",
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                sync_version
+            FROM suppliers
+            WHERE id = ?1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(supplier) = supplier else {
+            return Ok(None);
+        };
+        let items = self.items(id).await?;
+        Ok(Some(SupplierDocument { supplier, items }))
+    }
+
+    /// Lists suppliers by name; inactive ones only if asked for.
+    pub async fn list(&self, include_inactive: bool) -> DbResult<Vec<Supplier>> {
+        let suppliers = sqlx::query_as!(
+            Supplier,
+            r#"
+            SELECT
+                id as "id!",
+                name,
+                contact_name,
+                phone,
+                email,
+                is_active as "is_active: bool",
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                sync_version
+            FROM suppliers
+            WHERE is_active = 1 OR ?1
+            ORDER BY name COLLATE NOCASE, id
+            "#,
+            include_inactive
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(suppliers)
+    }
+
+    /// A supplier's cost per each for a product, if it lists it.
+    pub async fn cost_of(&self, supplier_id: &str, product_id: &str) -> DbResult<Option<i64>> {
+        let cost = sqlx::query_scalar!(
+            "SELECT cost_cents FROM supplier_items WHERE supplier_id = ?1 AND product_id = ?2",
+            supplier_id,
+            product_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(cost)
+    }
+
+    /// A product's last and average cost; both `None` before its first
+    /// costed receipt.
+    pub async fn product_cost(&self, product_id: &str) -> DbResult<ProductCost> {
+        let cost = sqlx::query_as!(
+            ProductCost,
+            r#"
+            SELECT
+                product_id as "product_id!",
+                last_cost_cents,
+                average_cost_cents,
+                last_supplier_id,
+                updated_at as "updated_at: DateTime<Utc>"
+            FROM product_costs
+            WHERE product_id = ?1
+            "#,
+            product_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(cost.unwrap_or_else(|| ProductCost {
+            product_id: product_id.to_string(),
+            last_cost_cents: None,
+            average_cost_cents: None,
+            last_supplier_id: None,
+            updated_at: None,
+        }))
+    }
+
+    /// Applies a supplier received through sync.
+    ///
+    /// Price list entries for products this terminal doesn't know yet are
+    /// skipped; they arrive with the next version of the supplier.
+    ///
+    /// ## Returns
+    /// * `Ok(true)` - Applied
+    /// * `Ok(false)` - Already at or past the document's version
+    pub async fn upsert_from_sync(&self, doc: &SupplierDocument) -> DbResult<bool> {
+        doc.validate()?;
+        let supplier = &doc.supplier;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let current: Option<i64> = sqlx::query_scalar!(
+            r#"SELECT sync_version as "v!: i64" FROM suppliers WHERE id = ?1"#,
+            supplier.id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        if current.is_some_and(|v| v >= supplier.sync_version) {
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO suppliers (
+                id, name, contact_name, phone, email, is_active,
+                created_at, updated_at, sync_version
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                contact_name = excluded.contact_name,
+                phone = excluded.phone,
+                email = excluded.email,
+                is_active = excluded.is_active,
+                updated_at = excluded.updated_at,
+                sync_version = excluded.sync_version
+            "#,
+            supplier.id,
+            supplier.name,
+            supplier.contact_name,
+            supplier.phone,
+            supplier.email,
+            supplier.is_active,
+            supplier.created_at,
+            supplier.updated_at,
+            supplier.sync_version
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!("DELETE FROM supplier_items WHERE supplier_id = ?1", supplier.id)
+            .execute(&mut *tx)
+            .await?;
+        for item in &doc.items {
+            sqlx::query!(
+                r#"
+                INSERT INTO supplier_items (supplier_id, product_id, supplier_sku, cost_cents)
+                SELECT ?1, id, ?3, ?4 FROM products WHERE id = ?2
+                "#,
+                supplier.id,
+                item.product_id,
+                item.supplier_sku,
+                item.cost_cents
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+        Ok(true)
+    }
+
+    /// Lists suppliers with IDs after `after_id`, in ID order, as sync
+    /// documents (for a full resync); pass an empty string for the first
+    /// page.
+    pub async fn list_after(&self, after_id: &str, limit: u32) -> DbResult<Vec<SupplierDocument>> {
+        let suppliers = sqlx::query_as!(
+            Supplier,
+            r#"
+            SELECT
+                id as "id!",
+                name,
+                contact_name,
+                phone,
+                email,
+                is_active as "is_active: bool",
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                sync_version
+            FROM suppliers
+            WHERE id > ?1
+            ORDER BY id
+            LIMIT ?2
+            "#,
+            after_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut docs = Vec::with_capacity(suppliers.len());
+        for supplier in suppliers {
+            let items = self.items(&supplier.id).await?;
+            docs.push(SupplierDocument { supplier, items });
+        }
+        Ok(docs)
+    }
+
+    async fn items(&self, supplier_id: &str) -> DbResult<Vec<SupplierItem>> {
+        let items = sqlx::query_as!(
+            SupplierItem,
+            r#"
+            SELECT product_id, supplier_sku, cost_cents
+            FROM supplier_items
+            WHERE supplier_id = ?1
+            ORDER BY product_id
+            "#,
+            supplier_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(items)
+    }
+
+    async fn queue_current(&self, id: &str) -> DbResult<SupplierDocument> {
+        let doc = self
+            .get(id)
+            .await?
+            .ok_or_else(|| DbError::not_found("Supplier", id))?;
+        self.queue(&doc).await?;
+        Ok(doc)
+    }
+
+    async fn queue(&self, doc: &SupplierDocument) -> DbResult<()> {
+        let payload = serde_json::to_string(doc)
+            .map_err(|e| DbError::Internal(format!("Failed to serialize supplier: {}", e)))?;
+        SyncOutboxRepository::new(self.pool.clone())
+            .upsert_for_sync("SUPPLIER", &doc.supplier.id, &payload)
+            .await
+    }
+}
+
+/// Trims an optional text field; blank becomes `None`.
+fn trimmed(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::{ProductFixture, SaleFixture};
+    use crate::pool::{Database, DbConfig};
+    use chrono::Utc;
+    use titan_core::ReceivingLine;
+
+    #[tokio::test]
+    async fn test_supplier_costs_and_margins() {
+        use titan_core::SupplierItem;
+
+        let db = Database::new(DbConfig::in_memory()).await.unwrap();
+        let cola = ProductFixture::new("COLA-330")
+            .price_cents(100)
+            .cost_cents(40)
+            .stock(100)
+            .insert(&db)
+            .await
+            .unwrap();
+        let gum = ProductFixture::new("GUM-1").price_cents(50).insert(&db).await.unwrap();
+
+        let supplier = db
+            .suppliers()
+            .create(" ACME Beverages ", Some("Dana"), None, Some(" "))
+            .await
+            .unwrap();
+        assert_eq!(supplier.name, "ACME Beverages");
+        assert_eq!(supplier.email, None);
+        let doc = db
+            .suppliers()
+            .set_items(&supplier.id, &[SupplierItem::new(&cola.id, Some("AC-7781"), 42)])
+            .await
+            .unwrap();
+        assert_eq!(doc.supplier.sync_version, 2);
+        assert!(db
+            .suppliers()
+            .set_items(&supplier.id, &[SupplierItem::new("missing", None, 1)])
+            .await
+            .is_err());
+
+        // No line cost: the supplier's cost, averaged with the stock on hand
+        let line = ReceivingLine {
+            product_id: cola.id.clone(),
+            unit: "each".to_string(),
+            quantity: 48,
+            unit_cost_cents: None,
+        };
+        let receipt = db
+            .packs()
+            .receive(Some("INV-77"), Some(&supplier.id), &[line], "manager", "pos-01")
+            .await
+            .unwrap();
+        assert_eq!(receipt.lines[0].each_cost_cents, Some(42));
+        assert_eq!(db.packs().get_receipt(&receipt.id).await.unwrap().unwrap(), receipt);
+
+        let cost = db.suppliers().product_cost(&cola.id).await.unwrap();
+        assert_eq!(cost.last_cost_cents, Some(42));
+        assert_eq!(cost.average_cost_cents, Some(41)); // (100 × 40 + 48 × 42) / 148
+        assert_eq!(cost.last_supplier_id.as_deref(), Some(supplier.id.as_str()));
+        let product = db.products().get_by_id(&cola.id).await.unwrap().unwrap();
+        assert_eq!(product.cost_cents, Some(41));
+
+        // Sale lines keep the cost they were sold at
+        let day = Utc::now() - chrono::Duration::hours(1);
+        SaleFixture::new().line(&product, 2).line(&gum, 1).at(day).insert(&db).await.unwrap();
+        let mut repriced = db.products().get_by_id(&cola.id).await.unwrap().unwrap();
+        repriced.cost_cents = Some(90);
+        db.products().update(&repriced).await.unwrap();
+
+        let stats = db
+            .sales()
+            .margin_stats(day - chrono::Duration::hours(1), Utc::now(), None)
+            .await
+            .unwrap();
+        assert_eq!(stats.products.len(), 1);
+        assert_eq!((stats.sales_cents, stats.cost_cents), (200, 82));
+        assert_eq!(stats.uncosted_sales_cents, 50);
+        assert_eq!(stats.margin_bps(), Some(5900));
+
+        // Sync applies newer versions only
+        let mut incoming = db.suppliers().get(&supplier.id).await.unwrap().unwrap();
+        incoming.supplier.name = "ACME Drinks".to_string();
+        incoming.supplier.sync_version += 1;
+        assert!(db.suppliers().upsert_from_sync(&incoming).await.unwrap());
+        assert!(!db.suppliers().upsert_from_sync(&incoming).await.unwrap());
+        assert_eq!(db.suppliers().list_after("", 10).await.unwrap(), vec![incoming]);
+
+        let pending = db.sync_outbox().get_pending(20).await.unwrap();
+        assert!(pending.iter().any(|e| e.entity_type == "SUPPLIER"));
+        assert!(pending.iter().any(|e| e.entity_type == "PRODUCT_PATCH"));
+    }
+}
@@ -42,28 +42,48 @@
 //! │  • Sync entry is never orphaned (same transaction)                     │
 //! │  • Offline? No problem - entries queue up                              │
 //! │  • Back online? Worker syncs pending entries                           │
+//! │  • Payloads are checked against their schema before they are queued   │
+//! │    (see titan_core::sync_payload); failures land in sync_quarantine    │
 //! │                                                                         │
 //! └─────────────────────────────────────────────────────────────────────────┘
 //! ```
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use sqlx::SqlitePool;
 use tracing::debug;
 use uuid::Uuid;
 
-use crate::error::DbResult;
-use titan_core::{SyncOutboxEntry, DEFAULT_TENANT_ID};
+use crate::correlation;
+use crate::error::{DbError, DbResult};
+use crate::payload_crypto::PayloadCipher;
+use titan_core::sync_history::period_duration;
+use titan_core::{
+    AckLevel, EntityPatch, KnownHub, OutboxAckCounts, OutboxEntryStatus, QuarantinedPayload,
+    SyncOutboxEntry, SyncPayload, SyncStatusPeriod, Tombstone, DEFAULT_TENANT_ID,
+    PAYLOAD_SCHEMA_VERSION,
+};
 
 /// Repository for sync outbox operations.
 #[derive(Debug, Clone)]
 pub struct SyncOutboxRepository {
     pool: SqlitePool,
+    payload: PayloadCipher,
 }
 
 impl SyncOutboxRepository {
     /// Creates a new SyncOutboxRepository.
     pub fn new(pool: SqlitePool) -> Self {
-        SyncOutboxRepository { pool }
+        SyncOutboxRepository {
+            pool,
+            payload: PayloadCipher::disabled(),
+        }
+    }
+
+    /// Seals the sensitive fields of queued payloads with `payload` (see
+    /// [`crate::payload_crypto`]).
+    pub fn with_payload_cipher(mut self, payload: PayloadCipher) -> Self {
+        self.payload = payload;
+        self
     }
 
     /// Queues an entity for synchronization.
@@ -73,6 +93,10 @@ impl SyncOutboxRepository {
     /// * `entity_id` - The entity's UUID
     /// * `payload` - JSON serialization of the full entity
     ///
+    /// Fails with [`DbError::InvalidSyncPayload`] if the payload does not
+    /// match the entity type's schema. Customer data and payment references
+    /// in the payload are stored sealed.
+    ///
     /// ## Example
     /// ```rust,ignore
     /// let payload = serde_json::to_string(&sale)?;
@@ -84,6 +108,9 @@ impl SyncOutboxRepository {
         entity_id: &str,
         payload: &str,
     ) -> DbResult<SyncOutboxEntry> {
+        SyncPayload::parse(entity_type, entity_id, PAYLOAD_SCHEMA_VERSION, payload)?;
+        let payload = self.payload.seal(entity_type, payload)?;
+
         let id = Uuid::new_v4().to_string();
         let now = Utc::now();
 
@@ -98,22 +125,26 @@ impl SyncOutboxRepository {
             tenant_id: DEFAULT_TENANT_ID.to_string(),
             entity_type: entity_type.to_string(),
             entity_id: entity_id.to_string(),
-            payload: payload.to_string(),
+            payload,
             attempts: 0,
             last_error: None,
             created_at: now,
             attempted_at: None,
             synced_at: None,
+            correlation_id: correlation::current(),
+            trace_context: correlation::trace_context(),
         };
 
         sqlx::query!(
             r#"
             INSERT INTO sync_outbox (
                 id, tenant_id, entity_type, entity_id, payload,
-                attempts, last_error, created_at, attempted_at, synced_at
+                attempts, last_error, created_at, attempted_at, synced_at,
+                correlation_id, trace_context
             ) VALUES (
                 ?1, ?2, ?3, ?4, ?5,
-                ?6, ?7, ?8, ?9, ?10
+                ?6, ?7, ?8, ?9, ?10,
+                ?11, ?12
             )
             "#,
             entry.id,
@@ -125,7 +156,9 @@ impl SyncOutboxRepository {
             entry.last_error,
             entry.created_at,
             entry.attempted_at,
-            entry.synced_at
+            entry.synced_at,
+            entry.correlation_id,
+            entry.trace_context
         )
         .execute(&self.pool)
         .await?;
@@ -133,6 +166,130 @@ impl SyncOutboxRepository {
         Ok(entry)
     }
 
+    /// Queues an entity for synchronization, replacing any earlier entry.
+    ///
+    /// Use this for entities that change after they are first queued (e.g.
+    /// a quote that is later converted). The outbox holds one row per
+    /// entity, so the latest payload is reset to pending and re-sent.
+    pub async fn upsert_for_sync(
+        &self,
+        entity_type: &str,
+        entity_id: &str,
+        payload: &str,
+    ) -> DbResult<()> {
+        SyncPayload::parse(entity_type, entity_id, PAYLOAD_SCHEMA_VERSION, payload)?;
+        let payload = self.payload.seal(entity_type, payload)?;
+
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+        let correlation_id = correlation::current();
+        let trace_context = correlation::trace_context();
+
+        debug!(
+            entity_type = %entity_type,
+            entity_id = %entity_id,
+            "Upserting sync entry"
+        );
+
+        sqlx::query!(
+            r#"
+            INSERT INTO sync_outbox (
+                id, tenant_id, entity_type, entity_id, payload,
+                attempts, created_at, correlation_id, trace_context
+            ) VALUES (
+                ?1, ?2, ?3, ?4, ?5,
+                0, ?6, ?7, ?8
+            )
+            ON CONFLICT(entity_type, entity_id) DO UPDATE SET
+                payload = excluded.payload,
+                attempts = 0,
+                last_error = NULL,
+                created_at = excluded.created_at,
+                attempted_at = NULL,
+                synced_at = NULL,
+                held_by_hub = NULL,
+                hub_acked_at = NULL,
+                cloud_acked_at = NULL,
+                correlation_id = excluded.correlation_id,
+                trace_context = excluded.trace_context
+            "#,
+            id,
+            DEFAULT_TENANT_ID,
+            entity_type,
+            entity_id,
+            payload,
+            now,
+            correlation_id,
+            trace_context
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Queues a field patch for synchronization.
+    ///
+    /// The outbox holds one row per entity, so a patch that has not synced
+    /// yet absorbs the new one: its base version is kept and the newer
+    /// field values win. Once synced, the row is replaced.
+    pub async fn queue_patch(
+        &self,
+        entity_type: &str,
+        entity_id: &str,
+        patch: &EntityPatch,
+    ) -> DbResult<()> {
+        let combined = match self.pending_patch(entity_type, entity_id).await? {
+            Some(mut pending) => {
+                pending.absorb(patch.clone());
+                pending
+            }
+            None => patch.clone(),
+        };
+
+        let payload = serde_json::to_string(&combined)
+            .map_err(|e| DbError::Internal(format!("Failed to serialize patch: {}", e)))?;
+        self.upsert_for_sync(entity_type, entity_id, &payload).await
+    }
+
+    /// Queues a soft delete or restore for synchronization.
+    ///
+    /// Keyed by the entity's ID, so a delete and a restore made before
+    /// the next sync collapse into the latest one.
+    pub async fn queue_tombstone(&self, tombstone: &Tombstone) -> DbResult<()> {
+        let payload = serde_json::to_string(tombstone)
+            .map_err(|e| DbError::Internal(format!("Failed to serialize tombstone: {}", e)))?;
+        self.upsert_for_sync("TOMBSTONE", &tombstone.entity_id, &payload)
+            .await
+    }
+
+    /// Gets the patch queued for an entity that has not synced yet.
+    ///
+    /// Used for field-level merges: a local edit still in the outbox is
+    /// weighed against incoming patches for the same fields.
+    pub async fn pending_patch(
+        &self,
+        entity_type: &str,
+        entity_id: &str,
+    ) -> DbResult<Option<EntityPatch>> {
+        let payload: Option<String> = sqlx::query_scalar!(
+            r#"
+            SELECT payload
+            FROM sync_outbox
+            WHERE entity_type = ?1 AND entity_id = ?2 AND synced_at IS NULL
+            "#,
+            entity_type,
+            entity_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        payload
+            .map(|p| serde_json::from_str(&p))
+            .transpose()
+            .map_err(|e| DbError::Internal(format!("Invalid queued patch: {}", e)))
+    }
+
     /// Gets pending entries that need to be synced.
     ///
     /// ## Arguments
@@ -140,6 +297,8 @@ impl SyncOutboxRepository {
     ///
     /// ## Returns
     /// Entries where `synced_at IS NULL`, ordered by created_at (oldest first).
+    /// Entries a hub holds for upload (see [`Self::mark_held`]) are left
+    /// out; the hub already has them.
     pub async fn get_pending(&self, limit: u32) -> DbResult<Vec<SyncOutboxEntry>> {
         let entries: Vec<SyncOutboxEntry> = sqlx::query_as!(
             SyncOutboxEntry,
@@ -154,9 +313,11 @@ impl SyncOutboxRepository {
                 last_error,
                 created_at as "created_at: chrono::DateTime<Utc>",
                 attempted_at as "attempted_at: chrono::DateTime<Utc>",
-                synced_at as "synced_at: chrono::DateTime<Utc>"
+                synced_at as "synced_at: chrono::DateTime<Utc>",
+                correlation_id,
+                trace_context
             FROM sync_outbox
-            WHERE synced_at IS NULL
+            WHERE synced_at IS NULL AND held_by_hub IS NULL
             ORDER BY created_at ASC
             LIMIT ?1
             "#,
@@ -168,7 +329,46 @@ impl SyncOutboxRepository {
         Ok(entries)
     }
 
-    /// Marks an entry as successfully synced.
+    /// Gets pending entries of one entity type, oldest first.
+    ///
+    /// Includes entries a hub holds: the cloud fallback uploads them when
+    /// that hub is gone.
+    pub async fn get_pending_by_type(
+        &self,
+        entity_type: &str,
+        limit: u32,
+    ) -> DbResult<Vec<SyncOutboxEntry>> {
+        let entries: Vec<SyncOutboxEntry> = sqlx::query_as!(
+            SyncOutboxEntry,
+            r#"
+            SELECT 
+                id,
+                tenant_id,
+                entity_type,
+                entity_id,
+                payload,
+                attempts,
+                last_error,
+                created_at as "created_at: chrono::DateTime<Utc>",
+                attempted_at as "attempted_at: chrono::DateTime<Utc>",
+                synced_at as "synced_at: chrono::DateTime<Utc>",
+                correlation_id,
+                trace_context
+            FROM sync_outbox
+            WHERE synced_at IS NULL AND entity_type = ?1
+            ORDER BY created_at ASC
+            LIMIT ?2
+            "#,
+            entity_type,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// Marks an entry as successfully synced on the hub's ack.
     ///
     /// ## Arguments
     /// * `id` - The outbox entry ID
@@ -179,7 +379,8 @@ impl SyncOutboxRepository {
             r#"
             UPDATE sync_outbox SET
                 synced_at = ?2,
-                attempted_at = ?2
+                attempted_at = ?2,
+                hub_acked_at = ?2
             WHERE id = ?1
             "#,
             id,
@@ -191,6 +392,118 @@ impl SyncOutboxRepository {
         Ok(())
     }
 
+    /// Marks an entry as synced on the cloud's ack: uploaded by the cloud
+    /// fallback, or reported uploaded already by the hub.
+    pub async fn mark_uploaded(&self, id: &str) -> DbResult<()> {
+        let now = Utc::now();
+
+        sqlx::query!(
+            r#"
+            UPDATE sync_outbox SET
+                synced_at = ?2,
+                attempted_at = ?2,
+                cloud_acked_at = ?2
+            WHERE id = ?1
+            "#,
+            id,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Marks an entry as accepted by the hub `hub_device_id` for upload to
+    /// the cloud. It stays unsynced, but is not sent again until the hub
+    /// relays the cloud's ack or the entry is released.
+    pub async fn mark_held(&self, id: &str, hub_device_id: &str) -> DbResult<()> {
+        let now = Utc::now();
+
+        sqlx::query!(
+            r#"
+            UPDATE sync_outbox SET
+                held_by_hub = ?2,
+                attempted_at = ?3,
+                hub_acked_at = ?3
+            WHERE id = ?1 AND synced_at IS NULL
+            "#,
+            id,
+            hub_device_id,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Marks a held entry as synced once the cloud has it.
+    ///
+    /// Returns `false` if the entry is not held (any more): an entry
+    /// re-queued with a newer payload since it was sent has to go out
+    /// again.
+    pub async fn mark_cloud_acked(&self, id: &str) -> DbResult<bool> {
+        let now = Utc::now();
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE sync_outbox SET
+                synced_at = ?2,
+                cloud_acked_at = ?2
+            WHERE id = ?1 AND synced_at IS NULL AND held_by_hub IS NOT NULL
+            "#,
+            id,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Releases the entries held by any hub other than `current_hub`, so
+    /// they are sent to it. Called when a new PRIMARY has taken over.
+    ///
+    /// ## Returns
+    /// Number of released entries.
+    pub async fn release_held(&self, current_hub: &str) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE sync_outbox SET held_by_hub = NULL
+            WHERE synced_at IS NULL
+            AND held_by_hub IS NOT NULL
+            AND held_by_hub != ?1
+            "#,
+            current_hub
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Releases the entries held since before `before`, so a cloud ack
+    /// that never arrived is asked for again.
+    ///
+    /// ## Returns
+    /// Number of released entries.
+    pub async fn release_stale_held(&self, before: DateTime<Utc>) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE sync_outbox SET held_by_hub = NULL
+            WHERE synced_at IS NULL
+            AND held_by_hub IS NOT NULL
+            AND attempted_at < ?1
+            "#,
+            before
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
     /// Records a sync failure.
     ///
     /// ## Arguments
@@ -217,7 +530,274 @@ impl SyncOutboxRepository {
         Ok(())
     }
 
-    /// Counts pending sync entries.
+    /// Moves an outbox entry whose payload failed validation into the
+    /// quarantine, so it is neither retried nor lost.
+    pub async fn quarantine_entry(&self, entry: &SyncOutboxEntry, reason: &str) -> DbResult<()> {
+        let mut tx = self.pool.begin().await?;
+        let id = Uuid::new_v4().to_string();
+        let schema_version = i64::from(PAYLOAD_SCHEMA_VERSION);
+        let now = Utc::now();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO sync_quarantine (
+                id, tenant_id, source, entity_type, entity_id,
+                payload, schema_version, reason, quarantined_at
+            ) VALUES (?1, ?2, 'outbox', ?3, ?4, ?5, ?6, ?7, ?8)
+            "#,
+            id,
+            entry.tenant_id,
+            entry.entity_type,
+            entry.entity_id,
+            entry.payload,
+            schema_version,
+            reason,
+            now
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!("DELETE FROM sync_outbox WHERE id = ?1", entry.id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Quarantines a payload another device sent (hub side).
+    pub async fn quarantine_received(
+        &self,
+        source_device: &str,
+        entity_type: &str,
+        entity_id: &str,
+        payload: &str,
+        schema_version: u32,
+        reason: &str,
+    ) -> DbResult<()> {
+        let id = Uuid::new_v4().to_string();
+        let schema_version = i64::from(schema_version);
+        let now = Utc::now();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO sync_quarantine (
+                id, tenant_id, source, entity_type, entity_id,
+                payload, schema_version, reason, quarantined_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            "#,
+            id,
+            DEFAULT_TENANT_ID,
+            source_device,
+            entity_type,
+            entity_id,
+            payload,
+            schema_version,
+            reason,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Lists quarantined payloads, newest first.
+    pub async fn list_quarantined(&self, limit: u32) -> DbResult<Vec<QuarantinedPayload>> {
+        let entries = sqlx::query_as!(
+            QuarantinedPayload,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                source,
+                entity_type,
+                entity_id,
+                payload,
+                schema_version,
+                reason,
+                quarantined_at as "quarantined_at: chrono::DateTime<Utc>"
+            FROM sync_quarantine
+            ORDER BY quarantined_at DESC
+            LIMIT ?1
+            "#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// Returns the hub this device last completed a handshake with.
+    pub async fn last_known_hub(&self, store_id: &str) -> DbResult<Option<KnownHub>> {
+        let hub = sqlx::query_as!(
+            KnownHub,
+            r#"
+            SELECT
+                store_id,
+                hub_url,
+                election_term,
+                connected_at as "connected_at: chrono::DateTime<Utc>"
+            FROM sync_hub_cache
+            WHERE store_id = ?1
+            "#,
+            store_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(hub)
+    }
+
+    /// Records a successful handshake with the store's hub.
+    pub async fn remember_hub(&self, store_id: &str, hub_url: &str, term: u64) -> DbResult<()> {
+        let term = term as i64;
+        let now = Utc::now();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO sync_hub_cache (store_id, hub_url, election_term, connected_at)
+            VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT(store_id) DO UPDATE SET
+                hub_url = excluded.hub_url,
+                election_term = excluded.election_term,
+                connected_at = excluded.connected_at
+            "#,
+            store_id,
+            hub_url,
+            term,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+
+        debug!(store_id = %store_id, hub_url = %hub_url, term, "Remembered hub");
+        Ok(())
+    }
+
+    /// Records a change of connection state at `at`.
+    ///
+    /// Closes the current period (if any) and opens one for `state`.
+    /// `reason` says why the previous state ended, e.g. a lost connection.
+    pub async fn record_sync_status(
+        &self,
+        state: &str,
+        reason: Option<&str>,
+        at: chrono::DateTime<Utc>,
+    ) -> DbResult<()> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let open = sqlx::query!(
+            r#"
+            SELECT id as "id!", started_at as "started_at: chrono::DateTime<Utc>"
+            FROM sync_status_history
+            WHERE ended_at IS NULL
+            "#
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for period in open {
+            let duration_ms = period_duration(period.started_at, at).num_milliseconds();
+            sqlx::query!(
+                "UPDATE sync_status_history SET ended_at = ?2, duration_ms = ?3 WHERE id = ?1",
+                period.id,
+                at,
+                duration_ms
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query!(
+            "INSERT INTO sync_status_history (state, reason, started_at) VALUES (?1, ?2, ?3)",
+            state,
+            reason,
+            at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        debug!(state = %state, "Recorded sync status");
+        Ok(())
+    }
+
+    /// Lists connection periods that were still running at `since`,
+    /// oldest first.
+    pub async fn sync_status_history(
+        &self,
+        since: chrono::DateTime<Utc>,
+    ) -> DbResult<Vec<SyncStatusPeriod>> {
+        let periods = sqlx::query_as!(
+            SyncStatusPeriod,
+            r#"
+            SELECT
+                id as "id!",
+                state,
+                reason,
+                started_at as "started_at: chrono::DateTime<Utc>",
+                ended_at as "ended_at: chrono::DateTime<Utc>",
+                duration_ms
+            FROM sync_status_history
+            WHERE ended_at IS NULL OR ended_at >= ?1
+            ORDER BY started_at, id
+            "#,
+            since
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(periods)
+    }
+
+    /// Deletes connection periods that ended more than `days_old` days ago.
+    pub async fn prune_sync_status_history(&self, days_old: u32) -> DbResult<u64> {
+        let cutoff = Utc::now() - chrono::Duration::days(days_old as i64);
+        let result = sqlx::query!(
+            "DELETE FROM sync_status_history WHERE ended_at IS NOT NULL AND ended_at < ?1",
+            cutoff
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Rewinds the inbound cursors so the next sync starts from scratch.
+    ///
+    /// The `outbox` cursor is left alone: local sales still waiting to go
+    /// up must not be resent or skipped by a resync.
+    pub async fn reset_inbound_cursors(&self) -> DbResult<()> {
+        let now = Utc::now();
+
+        sqlx::query!(
+            r#"
+            UPDATE sync_cursors SET
+                last_sequence = 0,
+                last_timestamp = NULL,
+                metadata = NULL,
+                updated_at = ?1
+            WHERE stream_id IN ('inbound', 'inventory')
+            "#,
+            now
+        )
+        .execute(&self.pool)
+        .await?;
+
+        debug!("Reset inbound sync cursors");
+        Ok(())
+    }
+
+    /// Counts pending sync entries, including those a hub holds.
     pub async fn count_pending(&self) -> DbResult<i64> {
         let count: i64 =
             sqlx::query_scalar("SELECT COUNT(*) FROM sync_outbox WHERE synced_at IS NULL")
@@ -227,6 +807,69 @@ impl SyncOutboxRepository {
         Ok(count)
     }
 
+    /// Counts pending sync entries by how far they have got: not acked
+    /// yet, or held by a hub until the cloud acks them.
+    pub async fn count_by_ack_level(&self) -> DbResult<OutboxAckCounts> {
+        let counts = sqlx::query_as!(
+            OutboxAckCounts,
+            r#"
+            SELECT
+                COALESCE(SUM(held_by_hub IS NULL), 0) as "pending!: i64",
+                COALESCE(SUM(held_by_hub IS NOT NULL), 0) as "hub_acked!: i64"
+            FROM sync_outbox
+            WHERE synced_at IS NULL
+            "#
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(counts)
+    }
+
+    /// Lists outbox entries with their ack level for the outbox inspector:
+    /// pending entries oldest first, then (with `include_synced`) synced
+    /// ones newest first.
+    pub async fn list_entries(
+        &self,
+        limit: u32,
+        include_synced: bool,
+    ) -> DbResult<Vec<OutboxEntryStatus>> {
+        let entries = sqlx::query_as!(
+            OutboxEntryStatus,
+            r#"
+            SELECT
+                id,
+                entity_type,
+                entity_id,
+                CASE
+                    WHEN cloud_acked_at IS NOT NULL THEN 'cloud_acked'
+                    WHEN synced_at IS NOT NULL OR held_by_hub IS NOT NULL THEN 'hub_acked'
+                    ELSE 'pending'
+                END as "ack_level!: AckLevel",
+                attempts,
+                last_error,
+                held_by_hub,
+                created_at as "created_at: chrono::DateTime<Utc>",
+                hub_acked_at as "hub_acked_at: chrono::DateTime<Utc>",
+                cloud_acked_at as "cloud_acked_at: chrono::DateTime<Utc>",
+                synced_at as "synced_at: chrono::DateTime<Utc>"
+            FROM sync_outbox
+            WHERE synced_at IS NULL OR ?1
+            ORDER BY
+                synced_at IS NOT NULL,
+                CASE WHEN synced_at IS NULL THEN created_at END ASC,
+                synced_at DESC
+            LIMIT ?2
+            "#,
+            include_synced,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
     /// Deletes old synced entries (cleanup).
     ///
     /// ## Arguments
@@ -249,3 +892,151 @@ impl SyncOutboxRepository {
         Ok(result.rows_affected())
     }
 }
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::Fixtures;
+    use crate::pool::{Database, DbConfig};
+    use chrono::{Duration, Utc};
+    use titan_core::{AckLevel, ErasureRequest, ErasureSubject, DEFAULT_TENANT_ID};
+
+    #[tokio::test]
+    async fn test_held_entries_wait_for_the_cloud() {
+        let config = DbConfig::in_memory()
+            .with_fixtures(Fixtures::new().products(5).sales(3).queue_sales_for_sync());
+        let db = Database::new(config).await.unwrap();
+        let outbox = db.sync_outbox();
+        let pending = outbox.get_pending(10).await.unwrap();
+        let (first, second) = (&pending[0].id, &pending[1].id);
+
+        // Held entries are not sent again, but are not synced either
+        outbox.mark_held(first, "hub-a").await.unwrap();
+        assert_eq!(outbox.get_pending(10).await.unwrap().len(), 2);
+        assert_eq!(outbox.count_pending().await.unwrap(), 3);
+        assert!(!outbox.mark_cloud_acked(second).await.unwrap());
+
+        // A new hub takes over: what the old one held is sent again
+        assert_eq!(outbox.release_held("hub-a").await.unwrap(), 0);
+        assert_eq!(outbox.release_held("hub-b").await.unwrap(), 1);
+        assert_eq!(outbox.get_pending(10).await.unwrap().len(), 3);
+
+        outbox.mark_held(first, "hub-b").await.unwrap();
+        outbox.mark_held(second, "hub-b").await.unwrap();
+        assert!(outbox.mark_cloud_acked(first).await.unwrap());
+        assert_eq!(outbox.count_pending().await.unwrap(), 2);
+
+        // An ack that never comes is asked for again
+        let later = Utc::now() + Duration::minutes(1);
+        assert_eq!(outbox.release_stale_held(later).await.unwrap(), 1);
+        assert_eq!(outbox.get_pending(10).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_outbox_ack_levels() {
+        let config = DbConfig::in_memory()
+            .with_fixtures(Fixtures::new().products(5).sales(3).queue_sales_for_sync());
+        let db = Database::new(config).await.unwrap();
+        let outbox = db.sync_outbox();
+        let pending = outbox.get_pending(10).await.unwrap();
+        let (first, second, third) = (&pending[0].id, &pending[1].id, &pending[2].id);
+
+        outbox.mark_held(first, "hub-a").await.unwrap();
+        outbox.mark_held(second, "hub-a").await.unwrap();
+        assert!(outbox.mark_cloud_acked(second).await.unwrap());
+        let counts = outbox.count_by_ack_level().await.unwrap();
+        assert_eq!(
+            (counts.pending, counts.hub_acked, counts.total()),
+            (1, 1, 2)
+        );
+
+        let listed = outbox.list_entries(10, false).await.unwrap();
+        let levels: Vec<_> = listed
+            .iter()
+            .map(|e| (e.id.as_str(), e.ack_level))
+            .collect();
+        assert_eq!(
+            levels,
+            vec![
+                (first.as_str(), AckLevel::HubAcked),
+                (third.as_str(), AckLevel::Pending)
+            ]
+        );
+        assert_eq!(listed[0].held_by_hub.as_deref(), Some("hub-a"));
+        assert!(listed[0].hub_acked_at.is_some() && listed[0].cloud_acked_at.is_none());
+
+        // Synced entries come last; a released entry is pending again
+        outbox.mark_uploaded(third).await.unwrap();
+        let later = Utc::now() + Duration::minutes(1);
+        outbox.release_stale_held(later).await.unwrap();
+        let listed = outbox.list_entries(10, true).await.unwrap();
+        assert_eq!(listed.len(), 3);
+        assert_eq!(
+            (listed[0].id.as_str(), listed[0].ack_level),
+            (first.as_str(), AckLevel::Pending)
+        );
+        assert!(listed[0].hub_acked_at.is_some());
+        assert!(listed[1..]
+            .iter()
+            .all(|e| e.ack_level == AckLevel::CloudAcked));
+    }
+
+    #[tokio::test]
+    async fn test_outbox_rows_carry_the_command_trace() {
+        let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let config = DbConfig::in_memory()
+            .with_fixtures(Fixtures::new().products(2).sales(1).queue_sales_for_sync());
+        let db = crate::correlation::scope(
+            "corr-1".to_string(),
+            Some(traceparent.to_string()),
+            Database::new(config),
+        )
+        .await
+        .unwrap();
+
+        let pending = db.sync_outbox().get_pending(10).await.unwrap();
+        assert_eq!(pending[0].correlation_id.as_deref(), Some("corr-1"));
+        assert_eq!(pending[0].trace_context.as_deref(), Some(traceparent));
+    }
+
+    #[tokio::test]
+    async fn test_outbox_payloads_are_sealed_but_queryable() {
+        let cipher = crate::PayloadCipher::new(vec![crate::PayloadKey::generate().unwrap()]);
+        let db = Database::new(DbConfig::in_memory())
+            .await
+            .unwrap()
+            .with_payload_cipher(cipher.clone());
+        let subject = ErasureSubject {
+            phone: Some("555-0100".to_string()),
+            ..Default::default()
+        };
+        let request = ErasureRequest::new(
+            "er-1".to_string(),
+            DEFAULT_TENANT_ID,
+            &subject,
+            None,
+            "u-1",
+            Utc::now(),
+        )
+        .unwrap();
+        db.erasures().forget(&request).await.unwrap();
+
+        let pending = db.sync_outbox().get_pending(10).await.unwrap();
+        assert!(!pending[0].payload.contains("555-0100"));
+        let requested_by: String =
+            sqlx::query_scalar("SELECT json_extract(payload, '$.requested_by') FROM sync_outbox")
+                .fetch_one(db.pool())
+                .await
+                .unwrap();
+        assert_eq!(requested_by, "u-1");
+
+        let opened = cipher
+            .open("CUSTOMER_ERASURE", &pending[0].payload)
+            .unwrap();
+        let opened: ErasureRequest = serde_json::from_str(&opened).unwrap();
+        assert_eq!(opened, request);
+    }
+}
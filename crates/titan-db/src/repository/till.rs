@@ -0,0 +1,683 @@
+//! # Till Repository
+//!
+//! Database operations for till sessions, blind counts, and the cash
+//! variance exceptions report.
+//!
+//! ## Blind Close Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                       Blind Close Flow                                  │
+//! │                                                                         │
+//! │  close_session(session_id, closed_by, counts)                          │
+//! │       │                                                                 │
+//! │       ▼                                                                 │
+//! │  ┌─────────────────────────────────────────────────────────────────┐   │
+//! │  │                   SINGLE TRANSACTION                            │   │
+//! │  │                                                                 │   │
+//! │  │  1. lock the session (UPDATE ... WHERE status = 'open')        │   │
+//! │  │                                                                 │   │
+//! │  │  2. expected = opening_float                                   │   │
+//! │  │              + Σ cash payments on completed sales              │   │
+//! │  │                (same device, completed after opened_at)        │   │
+//! │  │              + Σ cash layaway payments, net of refunds         │   │
+//! │  │                (same device, taken after opened_at)            │   │
+//! │  │              - Σ cash refunds on sales                         │   │
+//! │  │                (same device, paid out after opened_at)         │   │
+//! │  │                                                                 │   │
+//! │  │  3. INSERT INTO till_counts (one row per denomination)         │   │
+//! │  │                                                                 │   │
+//! │  │  4. UPDATE till_sessions SET status = 'closed',                │   │
+//! │  │       expected, counted, variance = counted - expected         │   │
+//! │  └─────────────────────────────────────────────────────────────────┘   │
+//! │                                                                         │
+//! │  The expected amount is computed here, never sent by the client,       │
+//! │  so the cashier cannot see or influence it during the count.           │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{DateTime, Utc};
+use sqlx::{SqliteConnection, SqlitePool};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::error::{DbError, DbResult};
+use titan_core::till::{calculate_variance, counted_total_cents};
+use titan_core::{
+    DenominationCount, TillSession, TillSessionStatus, VarianceException, VariancePolicy,
+    DEFAULT_TENANT_ID,
+};
+
+/// Repository for till session database operations.
+#[derive(Debug, Clone)]
+pub struct TillRepository {
+    pool: SqlitePool,
+}
+
+impl TillRepository {
+    /// Creates a new TillRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        TillRepository { pool }
+    }
+
+    /// Opens a new till session on a device.
+    ///
+    /// ## Errors
+    /// Returns `DbError::UniqueViolation` if the device already has an open
+    /// session (enforced by `idx_till_sessions_open_device`).
+    pub async fn open_session(
+        &self,
+        device_id: &str,
+        user_id: &str,
+        opening_float_cents: i64,
+    ) -> DbResult<TillSession> {
+        let session = TillSession {
+            id: Uuid::new_v4().to_string(),
+            tenant_id: DEFAULT_TENANT_ID.to_string(),
+            device_id: device_id.to_string(),
+            user_id: user_id.to_string(),
+            status: TillSessionStatus::Open,
+            opening_float_cents,
+            expected_cash_cents: None,
+            counted_cash_cents: None,
+            variance_cents: None,
+            blind_close: true,
+            closed_by: None,
+            notes: None,
+            opened_at: Utc::now(),
+            closed_at: None,
+            sync_version: 0,
+        };
+
+        debug!(id = %session.id, device_id = %device_id, "Opening till session");
+
+        sqlx::query!(
+            r#"
+            INSERT INTO till_sessions (
+                id, tenant_id, device_id, user_id, status,
+                opening_float_cents, blind_close, opened_at, sync_version
+            ) VALUES (
+                ?1, ?2, ?3, ?4, ?5,
+                ?6, ?7, ?8, ?9
+            )
+            "#,
+            session.id,
+            session.tenant_id,
+            session.device_id,
+            session.user_id,
+            session.status,
+            session.opening_float_cents,
+            session.blind_close,
+            session.opened_at,
+            session.sync_version
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match DbError::from(e) {
+            DbError::UniqueViolation { .. } => {
+                DbError::duplicate("Open till session for device", device_id)
+            }
+            other => other,
+        })?;
+
+        Ok(session)
+    }
+
+    /// Gets a till session by ID.
+    pub async fn get_by_id(&self, id: &str) -> DbResult<Option<TillSession>> {
+        let session = sqlx::query_as!(
+            TillSession,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                device_id,
+                user_id,
+                status as "status: TillSessionStatus",
+                opening_float_cents,
+                expected_cash_cents,
+                counted_cash_cents,
+                variance_cents,
+                blind_close as "blind_close: bool",
+                closed_by,
+                notes,
+                opened_at as "opened_at: DateTime<Utc>",
+                closed_at as "closed_at: DateTime<Utc>",
+                sync_version
+            FROM till_sessions
+            WHERE id = ?1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(session)
+    }
+
+    /// Gets the open till session for a device, if any.
+    pub async fn get_open_session(&self, device_id: &str) -> DbResult<Option<TillSession>> {
+        let session = sqlx::query_as!(
+            TillSession,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                device_id,
+                user_id,
+                status as "status: TillSessionStatus",
+                opening_float_cents,
+                expected_cash_cents,
+                counted_cash_cents,
+                variance_cents,
+                blind_close as "blind_close: bool",
+                closed_by,
+                notes,
+                opened_at as "opened_at: DateTime<Utc>",
+                closed_at as "closed_at: DateTime<Utc>",
+                sync_version
+            FROM till_sessions
+            WHERE device_id = ?1 AND status = 'open'
+            "#,
+            device_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(session)
+    }
+
+    /// Lists the till sessions still open on any device, oldest first.
+    pub async fn open_sessions(&self) -> DbResult<Vec<TillSession>> {
+        let sessions = sqlx::query_as!(
+            TillSession,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                device_id,
+                user_id,
+                status as "status: TillSessionStatus",
+                opening_float_cents,
+                expected_cash_cents,
+                counted_cash_cents,
+                variance_cents,
+                blind_close as "blind_close: bool",
+                closed_by,
+                notes,
+                opened_at as "opened_at: DateTime<Utc>",
+                closed_at as "closed_at: DateTime<Utc>",
+                sync_version
+            FROM till_sessions
+            WHERE status = 'open'
+            ORDER BY opened_at
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(sessions)
+    }
+
+    /// Calculates the cash that should be in the drawer right now.
+    ///
+    /// ## Formula
+    /// `opening_float + Σ payments.amount_cents` for cash payments on sales
+    /// completed on the session's device since the session opened.
+    /// `amount_cents` is the amount applied to the sale (change already
+    /// excluded), which is exactly what stays in the drawer.
+    ///
+    /// Cash layaway deposits and instalments (and cancellation refunds,
+    /// stored as negative amounts) are added too: that money moves through
+    /// the drawer when it is taken, not when the goods are picked up.
+    ///
+    /// Cash refunds on sales are paid out of the drawer and subtracted.
+    pub async fn calculate_expected_cash(&self, session: &TillSession) -> DbResult<i64> {
+        let mut conn = self.pool.acquire().await?;
+        expected_cash(&mut conn, session).await
+    }
+
+    /// Closes a till session with a blind denomination count.
+    ///
+    /// The session is locked, the expected amount is computed, and the
+    /// counts and variance are recorded in a single transaction, so cash
+    /// taken on the till while it closes is never left out of the expected
+    /// amount.
+    ///
+    /// ## Returns
+    /// The closed session (including expected/variance, for manager views).
+    pub async fn close_session(
+        &self,
+        session_id: &str,
+        closed_by: &str,
+        counts: &[DenominationCount],
+        notes: Option<&str>,
+    ) -> DbResult<TillSession> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        // Take the write lock before summing the drawer so a sale, layaway
+        // payment or refund committed meanwhile is either counted or waits.
+        let touched = sqlx::query!(
+            "UPDATE till_sessions SET closed_by = ?2 WHERE id = ?1 AND status = 'open'",
+            session_id,
+            closed_by
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if touched.rows_affected() == 0 {
+            return Err(DbError::not_found("TillSession (open)", session_id));
+        }
+
+        let session = sqlx::query_as!(
+            TillSession,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                device_id,
+                user_id,
+                status as "status: TillSessionStatus",
+                opening_float_cents,
+                expected_cash_cents,
+                counted_cash_cents,
+                variance_cents,
+                blind_close as "blind_close: bool",
+                closed_by,
+                notes,
+                opened_at as "opened_at: DateTime<Utc>",
+                closed_at as "closed_at: DateTime<Utc>",
+                sync_version
+            FROM till_sessions
+            WHERE id = ?1
+            "#,
+            session_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let expected = expected_cash(&mut tx, &session).await?;
+        let counted = counted_total_cents(counts);
+        let variance = calculate_variance(expected, counted);
+        let now = Utc::now();
+
+        for count in counts {
+            let id = Uuid::new_v4().to_string();
+            let total = count.total_cents();
+            sqlx::query!(
+                r#"
+                INSERT INTO till_counts (
+                    id, session_id, denomination_cents, quantity, total_cents, created_at
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                "#,
+                id,
+                session_id,
+                count.denomination_cents,
+                count.quantity,
+                total,
+                now
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE till_sessions SET
+                status = 'closed',
+                expected_cash_cents = ?2,
+                counted_cash_cents = ?3,
+                variance_cents = ?4,
+                blind_close = 1,
+                closed_by = ?5,
+                notes = ?6,
+                closed_at = ?7,
+                sync_version = sync_version + 1
+            WHERE id = ?1 AND status = 'open'
+            "#,
+            session_id,
+            expected,
+            counted,
+            variance,
+            closed_by,
+            notes,
+            now
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DbError::not_found("TillSession (open)", session_id));
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        info!(
+            session_id = %session_id,
+            expected = expected,
+            counted = counted,
+            variance = variance,
+            "Till session closed"
+        );
+
+        self.get_by_id(session_id)
+            .await?
+            .ok_or_else(|| DbError::not_found("TillSession", session_id))
+    }
+
+    /// Gets the denomination counts recorded for a session.
+    pub async fn get_counts(&self, session_id: &str) -> DbResult<Vec<DenominationCount>> {
+        let counts = sqlx::query_as!(
+            DenominationCount,
+            r#"
+            SELECT denomination_cents, quantity
+            FROM till_counts
+            WHERE session_id = ?1
+            ORDER BY denomination_cents DESC
+            "#,
+            session_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(counts)
+    }
+
+    /// Builds the variance exceptions report.
+    ///
+    /// Returns cashiers whose number of large-variance closes (per `policy`)
+    /// since `since` meets the flag threshold, worst offenders first.
+    pub async fn variance_exceptions(
+        &self,
+        policy: &VariancePolicy,
+        since: DateTime<Utc>,
+    ) -> DbResult<Vec<VarianceException>> {
+        let rows = sqlx::query_as!(
+            VarianceException,
+            r#"
+            SELECT
+                user_id as "user_id!",
+                COUNT(*) as "large_variance_count!: i64",
+                COALESCE(SUM(variance_cents), 0) as "net_variance_cents!: i64",
+                COALESCE(SUM(ABS(variance_cents)), 0) as "absolute_variance_cents!: i64",
+                MAX(closed_at) as "last_closed_at: DateTime<Utc>"
+            FROM till_sessions
+            WHERE status = 'closed'
+              AND closed_at >= ?1
+              AND ABS(variance_cents) >= ?2
+            GROUP BY user_id
+            HAVING COUNT(*) >= ?3
+            ORDER BY COUNT(*) DESC, SUM(ABS(variance_cents)) DESC
+            "#,
+            since,
+            policy.large_variance_cents,
+            policy.flag_after
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+}
+
+/// Sums the cash that should be in a session's drawer (see
+/// [`TillRepository::calculate_expected_cash`]) on `conn`, so a close can
+/// do it inside its own transaction.
+async fn expected_cash(conn: &mut SqliteConnection, session: &TillSession) -> DbResult<i64> {
+    let cash_taken: i64 = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(SUM(p.amount_cents), 0) as "total!: i64"
+        FROM payments p
+        JOIN sales s ON s.id = p.sale_id
+        WHERE p.method = 'cash'
+          AND s.status = 'completed'
+          AND s.device_id = ?1
+          AND s.completed_at >= ?2
+        "#,
+        session.device_id,
+        session.opened_at
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let layaway_cash: i64 = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(SUM(amount_cents), 0) as "total!: i64"
+        FROM layaway_payments
+        WHERE method = 'cash'
+          AND device_id = ?1
+          AND created_at >= ?2
+        "#,
+        session.device_id,
+        session.opened_at
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let cash_refunded: i64 = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(SUM(amount_cents), 0) as "total!: i64"
+        FROM sale_refunds
+        WHERE destination = 'cash'
+          AND device_id = ?1
+          AND created_at >= ?2
+        "#,
+        session.device_id,
+        session.opened_at
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(session.opening_float_cents + cash_taken + layaway_cash - cash_refunded)
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{ProductFixture, SaleFixture};
+    use crate::pool::{Database, DbConfig};
+    use chrono::Duration;
+    use titan_core::{
+        Layaway, LayawayDocument, LayawayPayment, LayawayStatus, PaymentMethod, Product,
+        RefundDestination, SaleRefund,
+    };
+
+    async fn setup() -> (Database, Product) {
+        let db = Database::new(DbConfig::in_memory()).await.unwrap();
+        let product = ProductFixture::realistic(0).insert(&db).await.unwrap();
+        (db, product)
+    }
+
+    fn layaway_payment(
+        layaway_id: &str,
+        method: PaymentMethod,
+        amount_cents: i64,
+    ) -> LayawayPayment {
+        LayawayPayment {
+            id: Uuid::new_v4().to_string(),
+            layaway_id: layaway_id.to_string(),
+            method,
+            amount_cents,
+            device_id: "pos-01".to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn refund(sale_id: &str, destination: RefundDestination, amount_cents: i64) -> SaleRefund {
+        SaleRefund {
+            id: Uuid::new_v4().to_string(),
+            sale_id: sale_id.to_string(),
+            amount_cents,
+            destination,
+            store_credit_id: None,
+            reason: None,
+            user_id: "default".to_string(),
+            device_id: "pos-01".to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_expected_cash_covers_sales_layaways_and_refunds() {
+        let (db, product) = setup().await;
+
+        // Rung up before the till opened: not in this drawer
+        SaleFixture::new()
+            .line(&product, 5)
+            .at(Utc::now() - Duration::hours(1))
+            .insert(&db)
+            .await
+            .unwrap();
+
+        let session = db
+            .tills()
+            .open_session("pos-01", "cashier", 10_000)
+            .await
+            .unwrap();
+        assert_eq!(
+            db.tills().calculate_expected_cash(&session).await.unwrap(),
+            10_000
+        );
+
+        let cash_sale = SaleFixture::new()
+            .line(&product, 2)
+            .insert(&db)
+            .await
+            .unwrap();
+        SaleFixture::new()
+            .line(&product, 3)
+            .paid_with(PaymentMethod::ExternalCard)
+            .insert(&db)
+            .await
+            .unwrap();
+        SaleFixture::new()
+            .line(&product, 4)
+            .device("pos-02")
+            .insert(&db)
+            .await
+            .unwrap();
+
+        let now = Utc::now();
+        let layaway_id = Uuid::new_v4().to_string();
+        db.layaways()
+            .create(&LayawayDocument {
+                layaway: Layaway {
+                    id: layaway_id.clone(),
+                    tenant_id: DEFAULT_TENANT_ID.to_string(),
+                    layaway_number: "LAY-0001".to_string(),
+                    status: LayawayStatus::Active,
+                    customer_name: "Ayesha".to_string(),
+                    customer_phone: None,
+                    subtotal_cents: 2_000,
+                    tax_cents: 0,
+                    total_cents: 2_000,
+                    paid_cents: 500,
+                    restocking_fee_cents: 0,
+                    sale_id: None,
+                    user_id: "cashier".to_string(),
+                    device_id: "pos-01".to_string(),
+                    notes: None,
+                    created_at: now,
+                    updated_at: now,
+                    closed_at: None,
+                    sync_version: 1,
+                },
+                items: Vec::new(),
+                payments: vec![layaway_payment(&layaway_id, PaymentMethod::Cash, 500)],
+            })
+            .await
+            .unwrap();
+        db.layaways()
+            .add_payment(&layaway_payment(&layaway_id, PaymentMethod::Cash, 300))
+            .await
+            .unwrap();
+        db.layaways()
+            .add_payment(&layaway_payment(
+                &layaway_id,
+                PaymentMethod::ExternalCard,
+                200,
+            ))
+            .await
+            .unwrap();
+
+        db.sales()
+            .add_refund(&refund(&cash_sale.id, RefundDestination::Cash, 100), None)
+            .await
+            .unwrap();
+        db.sales()
+            .add_refund(
+                &refund(&cash_sale.id, RefundDestination::ExternalCard, 50),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let expected = db.tills().calculate_expected_cash(&session).await.unwrap();
+        assert_eq!(expected, 10_000 + cash_sale.total_cents + 500 + 300 - 100);
+    }
+
+    #[tokio::test]
+    async fn test_close_records_counts_and_variance() {
+        let (db, product) = setup().await;
+        let tills = db.tills();
+
+        let session = tills
+            .open_session("pos-01", "cashier", 10_000)
+            .await
+            .unwrap();
+        let sale = SaleFixture::new()
+            .line(&product, 1)
+            .insert(&db)
+            .await
+            .unwrap();
+
+        // The cashier counts only the float: the sale's cash is missing
+        let counts = [
+            DenominationCount {
+                denomination_cents: 5_000,
+                quantity: 1,
+            },
+            DenominationCount {
+                denomination_cents: 1_000,
+                quantity: 5,
+            },
+        ];
+        let closed = tills
+            .close_session(&session.id, "manager", &counts, Some("short"))
+            .await
+            .unwrap();
+
+        assert_eq!(closed.status, TillSessionStatus::Closed);
+        assert_eq!(closed.expected_cash_cents, Some(10_000 + sale.total_cents));
+        assert_eq!(closed.counted_cash_cents, Some(10_000));
+        assert_eq!(closed.variance_cents, Some(-sale.total_cents));
+        assert_eq!(closed.closed_by.as_deref(), Some("manager"));
+        assert_eq!(
+            tills.get_counts(&session.id).await.unwrap(),
+            counts.to_vec()
+        );
+
+        // A closed session cannot be closed again, and nothing is added
+        let again = tills
+            .close_session(&session.id, "manager", &counts, None)
+            .await;
+        assert!(matches!(again, Err(DbError::NotFound { .. })));
+        assert_eq!(tills.get_counts(&session.id).await.unwrap().len(), 2);
+        assert!(tills
+            .get_by_id(&session.id)
+            .await
+            .unwrap()
+            .unwrap()
+            .closed_at
+            .is_some());
+    }
+}
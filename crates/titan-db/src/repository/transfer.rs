@@ -0,0 +1,501 @@
+//! # Store Transfer Repository
+//!
+//! Database operations for stock transfers between stores.
+//!
+//! ## Stock Movements
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                     Store Transfer Stock Movements                      │
+//! │                                                                         │
+//! │  create(doc)             receive(id, counts)        cancel(id)          │
+//! │  (sending store)         (receiving store)          (sending store)     │
+//! │       │                        │                          │             │
+//! │       ▼                        ▼                          ▼             │
+//! │  ┌──────────────────┐  ┌────────────────────┐  ┌──────────────────┐    │
+//! │  │ SINGLE TX        │  │ SINGLE TX          │  │ SINGLE TX        │    │
+//! │  │ header + items   │  │ status=received    │  │ status=cancelled │    │
+//! │  │ stock -= sent    │  │ qty_received set   │  │ stock += sent    │    │
+//! │  │ 'transfer_out'   │  │ stock += received  │  │ 'transfer_return'│    │
+//! │  │                  │  │ 'transfer_in'      │  │                  │    │
+//! │  └──────────────────┘  └────────────────────┘  └──────────────────┘    │
+//! │                                                                         │
+//! │  upsert_from_sync(doc) stores the other store's copy and never moves   │
+//! │  stock: each store only moves its own stock.                            │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::{debug, info};
+
+use crate::error::{DbError, DbResult};
+use crate::repository::stock::{move_stock, REFERENCE_TRANSFER};
+use titan_core::{StoreTransfer, StoreTransferDocument, StoreTransferItem, TransferStatus};
+
+/// Inventory delta reason when goods leave the sending store.
+pub const DELTA_TRANSFER_OUT: &str = "transfer_out";
+
+/// Inventory delta reason when goods are counted in at the receiving store.
+pub const DELTA_TRANSFER_IN: &str = "transfer_in";
+
+/// Inventory delta reason when a cancelled transfer's goods go back on sale.
+pub const DELTA_TRANSFER_RETURN: &str = "transfer_return";
+
+/// Repository for store transfer database operations.
+#[derive(Debug, Clone)]
+pub struct TransferRepository {
+    pool: SqlitePool,
+}
+
+impl TransferRepository {
+    /// Creates a new TransferRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        TransferRepository { pool }
+    }
+
+    /// Creates an outbound transfer and takes the goods out of local stock.
+    pub async fn create(&self, doc: &StoreTransferDocument, device_id: &str) -> DbResult<()> {
+        let transfer = &doc.transfer;
+        debug!(id = %transfer.id, transfer_number = %transfer.transfer_number, "Creating store transfer");
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        insert_header(&mut tx, transfer).await?;
+
+        for item in &doc.items {
+            insert_item(&mut tx, item).await?;
+            move_stock(
+                &mut tx,
+                REFERENCE_TRANSFER,
+                &item.product_id,
+                -item.quantity_sent,
+                DELTA_TRANSFER_OUT,
+                &transfer.id,
+                device_id,
+            )
+            .await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        info!(
+            transfer_id = %transfer.id,
+            to_store = %transfer.to_store_id,
+            lines = doc.items.len(),
+            "Store transfer shipped"
+        );
+
+        Ok(())
+    }
+
+    /// Gets a transfer by ID.
+    pub async fn get_by_id(&self, id: &str) -> DbResult<Option<StoreTransfer>> {
+        let transfer = sqlx::query_as!(
+            StoreTransfer,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                transfer_number,
+                from_store_id,
+                to_store_id,
+                status as "status: TransferStatus",
+                notes,
+                created_by,
+                received_by,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                received_at as "received_at: DateTime<Utc>",
+                cancelled_at as "cancelled_at: DateTime<Utc>",
+                sync_version
+            FROM store_transfers
+            WHERE id = ?1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(transfer)
+    }
+
+    /// Gets a transfer by its printed number.
+    pub async fn get_by_number(&self, transfer_number: &str) -> DbResult<Option<StoreTransfer>> {
+        let transfer = sqlx::query_as!(
+            StoreTransfer,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                transfer_number,
+                from_store_id,
+                to_store_id,
+                status as "status: TransferStatus",
+                notes,
+                created_by,
+                received_by,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                received_at as "received_at: DateTime<Utc>",
+                cancelled_at as "cancelled_at: DateTime<Utc>",
+                sync_version
+            FROM store_transfers
+            WHERE transfer_number = ?1
+            "#,
+            transfer_number
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(transfer)
+    }
+
+    /// Gets all lines for a transfer.
+    pub async fn get_items(&self, transfer_id: &str) -> DbResult<Vec<StoreTransferItem>> {
+        let items = sqlx::query_as!(
+            StoreTransferItem,
+            r#"
+            SELECT
+                id,
+                transfer_id,
+                product_id,
+                sku_snapshot,
+                name_snapshot,
+                quantity_sent,
+                quantity_received
+            FROM store_transfer_items
+            WHERE transfer_id = ?1
+            ORDER BY sku_snapshot, id
+            "#,
+            transfer_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(items)
+    }
+
+    /// Gets a transfer together with its lines.
+    pub async fn get_document(&self, id: &str) -> DbResult<Option<StoreTransferDocument>> {
+        let Some(transfer) = self.get_by_id(id).await? else {
+            return Ok(None);
+        };
+        let items = self.get_items(&transfer.id).await?;
+
+        Ok(Some(StoreTransferDocument { transfer, items }))
+    }
+
+    /// Lists transfers involving `store_id`, newest first.
+    ///
+    /// ## Arguments
+    /// * `store_id` - Local store; matches either side of the transfer
+    /// * `status` - Optional status filter (e.g. in transit only)
+    /// * `limit` - Maximum transfers to return
+    pub async fn list(
+        &self,
+        store_id: &str,
+        status: Option<TransferStatus>,
+        limit: u32,
+    ) -> DbResult<Vec<StoreTransfer>> {
+        let transfers = sqlx::query_as!(
+            StoreTransfer,
+            r#"
+            SELECT
+                id,
+                tenant_id,
+                transfer_number,
+                from_store_id,
+                to_store_id,
+                status as "status: TransferStatus",
+                notes,
+                created_by,
+                received_by,
+                created_at as "created_at: DateTime<Utc>",
+                updated_at as "updated_at: DateTime<Utc>",
+                received_at as "received_at: DateTime<Utc>",
+                cancelled_at as "cancelled_at: DateTime<Utc>",
+                sync_version
+            FROM store_transfers
+            WHERE (from_store_id = ?1 OR to_store_id = ?1)
+              AND (?2 IS NULL OR status = ?2)
+            ORDER BY created_at DESC
+            LIMIT ?3
+            "#,
+            store_id,
+            status,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(transfers)
+    }
+
+    /// Counts an inbound transfer in and adds the received goods to stock.
+    ///
+    /// ## Arguments
+    /// * `received` - `(item_id, quantity_received)` for every line
+    ///
+    /// ## Errors
+    /// `DbError::NotFound` if the transfer is no longer in transit.
+    pub async fn receive(
+        &self,
+        transfer_id: &str,
+        received: &[(String, i64)],
+        received_by: &str,
+        device_id: &str,
+    ) -> DbResult<()> {
+        let now = Utc::now();
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE store_transfers SET
+                status = 'received',
+                received_by = ?2,
+                received_at = ?3,
+                updated_at = ?3,
+                sync_version = sync_version + 1
+            WHERE id = ?1 AND status = 'in_transit'
+            "#,
+            transfer_id,
+            received_by,
+            now
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DbError::not_found("Transfer (in transit)", transfer_id));
+        }
+
+        for (item_id, quantity) in received {
+            let product_id = sqlx::query_scalar!(
+                r#"
+                UPDATE store_transfer_items
+                SET quantity_received = ?3
+                WHERE id = ?1 AND transfer_id = ?2
+                RETURNING product_id
+                "#,
+                item_id,
+                transfer_id,
+                quantity
+            )
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| DbError::not_found("Transfer item", item_id))?;
+
+            move_stock(
+                &mut tx,
+                REFERENCE_TRANSFER,
+                &product_id,
+                *quantity,
+                DELTA_TRANSFER_IN,
+                transfer_id,
+                device_id,
+            )
+            .await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        info!(transfer_id = %transfer_id, lines = received.len(), "Store transfer received");
+
+        Ok(())
+    }
+
+    /// Cancels an outbound transfer and puts the goods back in stock.
+    ///
+    /// ## Errors
+    /// `DbError::NotFound` if the transfer is no longer in transit.
+    pub async fn cancel(&self, transfer_id: &str, device_id: &str) -> DbResult<()> {
+        let now = Utc::now();
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE store_transfers SET
+                status = 'cancelled',
+                cancelled_at = ?2,
+                updated_at = ?2,
+                sync_version = sync_version + 1
+            WHERE id = ?1 AND status = 'in_transit'
+            "#,
+            transfer_id,
+            now
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DbError::not_found("Transfer (in transit)", transfer_id));
+        }
+
+        let shipped = sqlx::query!(
+            r#"
+            SELECT product_id, quantity_sent
+            FROM store_transfer_items
+            WHERE transfer_id = ?1
+            "#,
+            transfer_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for row in shipped {
+            move_stock(
+                &mut tx,
+                REFERENCE_TRANSFER,
+                &row.product_id,
+                row.quantity_sent,
+                DELTA_TRANSFER_RETURN,
+                transfer_id,
+                device_id,
+            )
+            .await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        info!(transfer_id = %transfer_id, "Store transfer cancelled, stock returned");
+
+        Ok(())
+    }
+
+    /// Applies a transfer received from another terminal or store.
+    ///
+    /// Last-writer-wins on `sync_version`, replacing the header and lines.
+    /// Stock is not moved here: the store that changed the transfer moved
+    /// its own stock when it did so.
+    ///
+    /// ## Returns
+    /// `true` if the document was applied, `false` if the local copy was
+    /// already at the same or a newer version.
+    pub async fn upsert_from_sync(&self, doc: &StoreTransferDocument) -> DbResult<bool> {
+        let transfer = &doc.transfer;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let current: Option<i64> = sqlx::query_scalar!(
+            r#"SELECT sync_version as "v!: i64" FROM store_transfers WHERE id = ?1"#,
+            transfer.id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if current.is_some_and(|v| v >= transfer.sync_version) {
+            return Ok(false);
+        }
+
+        sqlx::query!("DELETE FROM store_transfer_items WHERE transfer_id = ?1", transfer.id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query!("DELETE FROM store_transfers WHERE id = ?1", transfer.id)
+            .execute(&mut *tx)
+            .await?;
+
+        insert_header(&mut tx, transfer).await?;
+        for item in &doc.items {
+            insert_item(&mut tx, item).await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        Ok(true)
+    }
+}
+
+/// Inserts a transfer header inside an open transaction.
+async fn insert_header(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    transfer: &StoreTransfer,
+) -> DbResult<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO store_transfers (
+            id, tenant_id, transfer_number,
+            from_store_id, to_store_id, status,
+            notes, created_by, received_by,
+            created_at, updated_at, received_at, cancelled_at,
+            sync_version
+        ) VALUES (
+            ?1, ?2, ?3,
+            ?4, ?5, ?6,
+            ?7, ?8, ?9,
+            ?10, ?11, ?12, ?13,
+            ?14
+        )
+        "#,
+        transfer.id,
+        transfer.tenant_id,
+        transfer.transfer_number,
+        transfer.from_store_id,
+        transfer.to_store_id,
+        transfer.status,
+        transfer.notes,
+        transfer.created_by,
+        transfer.received_by,
+        transfer.created_at,
+        transfer.updated_at,
+        transfer.received_at,
+        transfer.cancelled_at,
+        transfer.sync_version
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Inserts one transfer line inside an open transaction.
+async fn insert_item(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    item: &StoreTransferItem,
+) -> DbResult<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO store_transfer_items (
+            id, transfer_id, product_id,
+            sku_snapshot, name_snapshot,
+            quantity_sent, quantity_received
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+        "#,
+        item.id,
+        item.transfer_id,
+        item.product_id,
+        item.sku_snapshot,
+        item.name_snapshot,
+        item.quantity_sent,
+        item.quantity_received
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
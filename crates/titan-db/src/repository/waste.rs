@@ -0,0 +1,277 @@
+//! # Waste Repository
+//!
+//! Database operations for waste and shrinkage write-offs (see
+//! `titan_core::waste`).
+//!
+//! ## Recording Waste
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                     Writing Off Stock                                   │
+//! │                                                                         │
+//! │  record(MILK-1L, 4, expiry, "fridge 2")     (one transaction)          │
+//! │       │                                                                 │
+//! │       ├── waste_records row (price frozen as unit_price_cents)         │
+//! │       └── current_stock - 4, inventory_deltas row                      │
+//! │           (delta_type expiry, reference_type waste, the record id)     │
+//! │                                                                         │
+//! │  then: outbox WASTE (uploaded to the cloud for shrinkage by store),    │
+//! │        StockChanged event                                              │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{DateTime, Utc};
+use sqlx::SqlitePool;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::error::{DbError, DbResult};
+use crate::events::EventPublisher;
+use crate::repository::stock::{move_stock, REFERENCE_WASTE};
+use crate::repository::sync::SyncOutboxRepository;
+use titan_core::waste::{normalize_note, validate_quantity};
+use titan_core::{EntityEvent, WasteReason, WasteRecord};
+
+/// Repository for waste records.
+#[derive(Debug, Clone)]
+pub struct WasteRepository {
+    pool: SqlitePool,
+    events: EventPublisher,
+}
+
+impl WasteRepository {
+    /// Creates a new WasteRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        WasteRepository {
+            pool,
+            events: EventPublisher::disabled(),
+        }
+    }
+
+    /// Publishes `StockChanged` events through `events`.
+    pub fn with_events(mut self, events: EventPublisher) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Writes `quantity` units of a product off stock and queues the record
+    /// for the cloud.
+    ///
+    /// ## Errors
+    /// - `DbError::InvalidInput` for a quantity out of range or a note that
+    ///   is too long
+    /// - `DbError::NotFound` if the product doesn't exist or is deleted
+    pub async fn record(
+        &self,
+        product_id: &str,
+        quantity: i64,
+        reason: WasteReason,
+        note: Option<&str>,
+        recorded_by: &str,
+        device_id: &str,
+    ) -> DbResult<WasteRecord> {
+        validate_quantity(quantity)?;
+        let note = normalize_note(note)?;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let unit_price_cents: i64 = sqlx::query_scalar!(
+            "SELECT price_cents FROM products WHERE id = ?1 AND deleted_at IS NULL",
+            product_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| DbError::not_found("Product", product_id))?;
+
+        let record = WasteRecord {
+            id: Uuid::new_v4().to_string(),
+            product_id: product_id.to_string(),
+            quantity,
+            reason,
+            unit_price_cents,
+            note,
+            recorded_by: recorded_by.to_string(),
+            device_id: device_id.to_string(),
+            recorded_at: Utc::now(),
+        };
+
+        debug!(id = %record.id, product_id = %product_id, quantity, reason = reason.as_str(), "Recording waste");
+
+        sqlx::query!(
+            r#"
+            INSERT INTO waste_records (
+                id, product_id, quantity, reason, unit_price_cents, note,
+                recorded_by, device_id, recorded_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+            "#,
+            record.id,
+            record.product_id,
+            record.quantity,
+            record.reason,
+            record.unit_price_cents,
+            record.note,
+            record.recorded_by,
+            record.device_id,
+            record.recorded_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        move_stock(
+            &mut tx,
+            REFERENCE_WASTE,
+            product_id,
+            -quantity,
+            reason.as_str(),
+            &record.id,
+            device_id,
+        )
+        .await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let payload = serde_json::to_string(&record)
+            .map_err(|e| DbError::Internal(format!("Failed to serialize waste record: {}", e)))?;
+        SyncOutboxRepository::new(self.pool.clone())
+            .queue_for_sync("WASTE", &record.id, &payload)
+            .await?;
+
+        let stock: Option<Option<i64>> = sqlx::query_scalar!(
+            "SELECT current_stock FROM products WHERE id = ?1 AND track_inventory = 1",
+            product_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        if let Some(stock) = stock {
+            self.events.publish(EntityEvent::StockChanged {
+                product_id: product_id.to_string(),
+                delta: -quantity,
+                stock,
+            });
+        }
+
+        Ok(record)
+    }
+
+    /// Lists waste recorded in `[from, to)`, oldest first.
+    pub async fn list_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> DbResult<Vec<WasteRecord>> {
+        let records = sqlx::query_as!(
+            WasteRecord,
+            r#"
+            SELECT
+                id as "id!",
+                product_id,
+                quantity,
+                reason as "reason: WasteReason",
+                unit_price_cents,
+                note,
+                recorded_by,
+                device_id,
+                recorded_at as "recorded_at: DateTime<Utc>"
+            FROM waste_records
+            WHERE recorded_at >= ?1 AND recorded_at < ?2
+            ORDER BY recorded_at, id
+            "#,
+            from,
+            to
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::Fixtures;
+    use crate::pool::{Database, DbConfig};
+
+    #[tokio::test]
+    async fn test_waste_writes_off_stock() {
+        use titan_core::{WasteReason, WasteReport};
+
+        let config = DbConfig::in_memory().with_fixtures(Fixtures::new().products(1));
+        let db = Database::new(config).await.unwrap();
+        let product = db.products().search("", 1).await.unwrap().remove(0);
+
+        let record = db
+            .waste()
+            .record(
+                &product.id,
+                4,
+                WasteReason::Expiry,
+                Some(" fridge 2 "),
+                "manager",
+                "pos-01",
+            )
+            .await
+            .unwrap();
+        assert_eq!(record.note.as_deref(), Some("fridge 2"));
+        assert_eq!(record.unit_price_cents, product.price_cents);
+        let stock = db
+            .products()
+            .get_by_id(&product.id)
+            .await
+            .unwrap()
+            .unwrap()
+            .current_stock;
+        assert_eq!(stock, product.current_stock.map(|s| s - 4));
+
+        let delta: String = sqlx::query_scalar!(
+            r#"SELECT delta_type as "delta_type!" FROM inventory_deltas WHERE reference_type = 'waste' AND reference_id = ?1 AND delta = -4"#,
+            record.id
+        )
+        .fetch_one(db.pool())
+        .await
+        .unwrap();
+        assert_eq!(delta, "expiry");
+
+        assert!(db
+            .waste()
+            .record(
+                &product.id,
+                0,
+                WasteReason::Theft,
+                None,
+                "manager",
+                "pos-01"
+            )
+            .await
+            .is_err());
+        assert!(db
+            .waste()
+            .record("missing", 1, WasteReason::Damage, None, "manager", "pos-01")
+            .await
+            .is_err());
+
+        let now = chrono::Utc::now();
+        let records = db
+            .waste()
+            .list_between(
+                now - chrono::Duration::hours(1),
+                now + chrono::Duration::hours(1),
+            )
+            .await
+            .unwrap();
+        assert_eq!(records, vec![record]);
+        let report = WasteReport::new(now.date_naive(), records);
+        assert_eq!(report.total_units(), 4);
+
+        let pending = db.sync_outbox().get_pending(10).await.unwrap();
+        assert!(pending.iter().any(|e| e.entity_type == "WASTE"));
+    }
+}
@@ -0,0 +1,391 @@
+//! # Z Report Repository
+//!
+//! Database operations for Z closes (see `titan_core::day_end`).
+//!
+//! ## Closing the Day
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Z Close                                         │
+//! │                                                                         │
+//! │  close_day(device, station, business_date, now)     (one transaction)   │
+//! │       │                                                                 │
+//! │       ├── already closed for that date ──► the existing report          │
+//! │       │                                                                 │
+//! │       ├── previous close ──► period_start, sequence + 1                 │
+//! │       ├── sales completed in (period_start, now]: count, totals         │
+//! │       ├── sales voided in the period: count                             │
+//! │       ├── their payments, by method                                     │
+//! │       └── INSERT z_reports                                              │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::SqlitePool;
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::error::{DbError, DbResult};
+use titan_core::{PaymentMethod, PaymentTotal, ZReport};
+
+/// Repository for Z reports.
+#[derive(Debug, Clone)]
+pub struct ZReportRepository {
+    pool: SqlitePool,
+}
+
+/// A `z_reports` row, payments still as JSON.
+struct ZReportRow {
+    id: String,
+    device_id: String,
+    station_number: Option<i64>,
+    sequence: i64,
+    business_date: NaiveDate,
+    period_start: Option<DateTime<Utc>>,
+    closed_at: DateTime<Utc>,
+    sale_count: i64,
+    void_count: i64,
+    subtotal_cents: i64,
+    tax_cents: i64,
+    discount_cents: i64,
+    total_cents: i64,
+    payments: String,
+}
+
+impl TryFrom<ZReportRow> for ZReport {
+    type Error = DbError;
+
+    fn try_from(row: ZReportRow) -> Result<Self, Self::Error> {
+        let payments = serde_json::from_str(&row.payments).map_err(|e| {
+            DbError::Internal(format!("Invalid payments in Z report {}: {}", row.id, e))
+        })?;
+        Ok(ZReport {
+            id: row.id,
+            device_id: row.device_id,
+            station_number: row.station_number,
+            sequence: row.sequence,
+            business_date: row.business_date,
+            period_start: row.period_start,
+            closed_at: row.closed_at,
+            sale_count: row.sale_count,
+            void_count: row.void_count,
+            subtotal_cents: row.subtotal_cents,
+            tax_cents: row.tax_cents,
+            discount_cents: row.discount_cents,
+            total_cents: row.total_cents,
+            payments,
+        })
+    }
+}
+
+impl ZReportRepository {
+    /// Creates a new ZReportRepository.
+    pub fn new(pool: SqlitePool) -> Self {
+        ZReportRepository { pool }
+    }
+
+    /// Closes the sales `device_id` completed since its previous Z close,
+    /// up to `now`, as the Z report for `business_date`.
+    ///
+    /// Closing a date that is already closed returns its report unchanged.
+    pub async fn close_day(
+        &self,
+        device_id: &str,
+        station_number: Option<i64>,
+        business_date: NaiveDate,
+        now: DateTime<Utc>,
+    ) -> DbResult<ZReport> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        let existing = sqlx::query_as!(
+            ZReportRow,
+            r#"
+            SELECT
+                id as "id!", device_id, station_number, sequence,
+                business_date as "business_date: NaiveDate",
+                period_start as "period_start: DateTime<Utc>",
+                closed_at as "closed_at: DateTime<Utc>",
+                sale_count, void_count, subtotal_cents, tax_cents,
+                discount_cents, total_cents, payments
+            FROM z_reports
+            WHERE device_id = ?1 AND business_date = ?2
+            "#,
+            device_id,
+            business_date
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        if let Some(row) = existing {
+            debug!(device_id, %business_date, "Day already closed");
+            return row.try_into();
+        }
+
+        let previous = sqlx::query!(
+            r#"
+            SELECT sequence, closed_at as "closed_at: DateTime<Utc>"
+            FROM z_reports
+            WHERE device_id = ?1
+            ORDER BY sequence DESC
+            LIMIT 1
+            "#,
+            device_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        let (sequence, period_start) = match previous {
+            Some(p) => (p.sequence + 1, Some(p.closed_at)),
+            None => (1, None),
+        };
+
+        let totals = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) as "sale_count!: i64",
+                COALESCE(SUM(subtotal_cents), 0) as "subtotal_cents!: i64",
+                COALESCE(SUM(tax_cents), 0) as "tax_cents!: i64",
+                COALESCE(SUM(discount_cents), 0) as "discount_cents!: i64",
+                COALESCE(SUM(total_cents), 0) as "total_cents!: i64"
+            FROM sales
+            WHERE device_id = ?1
+              AND status = 'completed'
+              AND (?2 IS NULL OR completed_at > ?2)
+              AND completed_at <= ?3
+            "#,
+            device_id,
+            period_start,
+            now
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let void_count: i64 = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) as "count!: i64"
+            FROM sales
+            WHERE device_id = ?1
+              AND status = 'voided'
+              AND (?2 IS NULL OR updated_at > ?2)
+              AND updated_at <= ?3
+            "#,
+            device_id,
+            period_start,
+            now
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let payments: Vec<PaymentTotal> = sqlx::query!(
+            r#"
+            SELECT
+                p.method as "method!: PaymentMethod",
+                COUNT(*) as "count!: i64",
+                COALESCE(SUM(p.amount_cents), 0) as "amount_cents!: i64"
+            FROM payments p
+            JOIN sales s ON s.id = p.sale_id
+            WHERE s.device_id = ?1
+              AND s.status = 'completed'
+              AND (?2 IS NULL OR s.completed_at > ?2)
+              AND s.completed_at <= ?3
+            GROUP BY p.method
+            ORDER BY p.method
+            "#,
+            device_id,
+            period_start,
+            now
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|r| PaymentTotal {
+            method: r.method,
+            count: r.count,
+            amount_cents: r.amount_cents,
+        })
+        .collect();
+
+        let report = ZReport {
+            id: Uuid::new_v4().to_string(),
+            device_id: device_id.to_string(),
+            station_number,
+            sequence,
+            business_date,
+            period_start,
+            closed_at: now,
+            sale_count: totals.sale_count,
+            void_count,
+            subtotal_cents: totals.subtotal_cents,
+            tax_cents: totals.tax_cents,
+            discount_cents: totals.discount_cents,
+            total_cents: totals.total_cents,
+            payments,
+        };
+        let payments_json = serde_json::to_string(&report.payments)
+            .map_err(|e| DbError::Internal(format!("Failed to serialize payments: {}", e)))?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO z_reports (
+                id, device_id, station_number, sequence, business_date,
+                period_start, closed_at, sale_count, void_count,
+                subtotal_cents, tax_cents, discount_cents, total_cents, payments
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+            "#,
+            report.id,
+            report.device_id,
+            report.station_number,
+            report.sequence,
+            report.business_date,
+            report.period_start,
+            report.closed_at,
+            report.sale_count,
+            report.void_count,
+            report.subtotal_cents,
+            report.tax_cents,
+            report.discount_cents,
+            report.total_cents,
+            payments_json
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| DbError::TransactionFailed(e.to_string()))?;
+
+        info!(
+            device_id,
+            sequence,
+            %business_date,
+            sales = report.sale_count,
+            total_cents = report.total_cents,
+            "Z close recorded"
+        );
+        Ok(report)
+    }
+
+    /// A device's Z reports, newest first.
+    pub async fn list(&self, device_id: &str, limit: u32) -> DbResult<Vec<ZReport>> {
+        let rows = sqlx::query_as!(
+            ZReportRow,
+            r#"
+            SELECT
+                id as "id!", device_id, station_number, sequence,
+                business_date as "business_date: NaiveDate",
+                period_start as "period_start: DateTime<Utc>",
+                closed_at as "closed_at: DateTime<Utc>",
+                sale_count, void_count, subtotal_cents, tax_cents,
+                discount_cents, total_cents, payments
+            FROM z_reports
+            WHERE device_id = ?1
+            ORDER BY sequence DESC
+            LIMIT ?2
+            "#,
+            device_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(ZReport::try_from).collect()
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::{ProductFixture, SaleFixture};
+    use crate::pool::{Database, DbConfig};
+    use chrono::Utc;
+    use titan_core::PaymentMethod;
+
+    #[tokio::test]
+    async fn test_z_close_covers_sales_since_the_previous_close() {
+        let db = Database::new(DbConfig::in_memory()).await.unwrap();
+        let tea = ProductFixture::new("TEA-1")
+            .price_cents(500)
+            .tax_rate_bps(0)
+            .insert(&db)
+            .await
+            .unwrap();
+
+        let hour_ago = Utc::now() - chrono::Duration::hours(1);
+        SaleFixture::new()
+            .line(&tea, 2)
+            .at(hour_ago)
+            .insert(&db)
+            .await
+            .unwrap();
+        SaleFixture::new()
+            .line(&tea, 1)
+            .paid_with(PaymentMethod::ExternalCard)
+            .at(hour_ago)
+            .insert(&db)
+            .await
+            .unwrap();
+        SaleFixture::new()
+            .line(&tea, 1)
+            .draft()
+            .at(hour_ago)
+            .insert(&db)
+            .await
+            .unwrap();
+        SaleFixture::new()
+            .line(&tea, 4)
+            .device("pos-02")
+            .at(hour_ago)
+            .insert(&db)
+            .await
+            .unwrap();
+
+        let monday = chrono::NaiveDate::from_ymd_opt(2026, 3, 9).unwrap();
+        let z1 = db
+            .z_reports()
+            .close_day("pos-01", Some(1), monday, Utc::now())
+            .await
+            .unwrap();
+        assert_eq!((z1.sequence, z1.sale_count, z1.total_cents), (1, 2, 1500));
+        assert_eq!(z1.period_start, None);
+        let by_method: Vec<_> = z1
+            .payments
+            .iter()
+            .map(|p| (p.method, p.amount_cents))
+            .collect();
+        assert_eq!(
+            by_method,
+            vec![
+                (PaymentMethod::Cash, 1000),
+                (PaymentMethod::ExternalCard, 500)
+            ]
+        );
+
+        // Running the day end again gives the same report
+        let again = db
+            .z_reports()
+            .close_day("pos-01", Some(1), monday, Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(again, z1);
+
+        SaleFixture::new().line(&tea, 3).insert(&db).await.unwrap();
+        let tuesday = monday.succ_opt().unwrap();
+        let z2 = db
+            .z_reports()
+            .close_day("pos-01", Some(1), tuesday, Utc::now())
+            .await
+            .unwrap();
+        assert_eq!((z2.sequence, z2.sale_count, z2.total_cents), (2, 1, 1500));
+        assert_eq!(z2.period_start, Some(z1.closed_at));
+
+        let listed = db.z_reports().list("pos-01", 10).await.unwrap();
+        assert_eq!(
+            listed.iter().map(|z| z.sequence).collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+    }
+}
@@ -0,0 +1,492 @@
+//! # Schema Description
+//!
+//! The live schema of a database, read from SQLite itself rather than the
+//! migration files: tables, columns, foreign keys, indexes and the newest
+//! migration applied. Support tooling exports it as JSON (to attach to a
+//! ticket) or Graphviz DOT (to draw), and compares it with the schema this
+//! build creates to find store databases that drifted or were edited by
+//! hand.
+//!
+//! ## Drift Check
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Schema Drift Check                               │
+//! │                                                                         │
+//! │  store titan.db ──► describe_schema ─────────┐                          │
+//! │                                              ├─► drift ─► [SchemaDrift] │
+//! │  :memory: + migrations ──► expected_schema ──┘   (empty = matches)      │
+//! │                                                                         │
+//! │  Compared: tables, columns (type, NOT NULL, default, primary key),      │
+//! │  indexes (unique, columns) and foreign keys, by name.                   │
+//! │  Not compared: data, triggers, views, FTS shadow tables.                │
+//! │                                                                         │
+//! │  to_json() ──► ticket attachment   to_dot() ──► `dot -Tsvg` ER diagram  │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! `verify_schema` (see `migrations`) answers "which migrations ran"; this
+//! answers "what the database looks like now", which differs when someone
+//! ran SQL by hand.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+use crate::error::DbResult;
+use crate::migrations::latest_version;
+use crate::pool::{Database, DbConfig};
+
+// =============================================================================
+// Description
+// =============================================================================
+
+/// A database's schema, tables sorted by name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaDescription {
+    /// Newest migration applied (None without `_sqlx_migrations`).
+    pub schema_version: Option<i64>,
+    /// Newest migration embedded in the build that described it.
+    pub latest_version: i64,
+    pub tables: Vec<TableInfo>,
+}
+
+/// One table or virtual table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableInfo {
+    pub name: String,
+    /// Columns in declaration order.
+    pub columns: Vec<ColumnInfo>,
+    pub foreign_keys: Vec<ForeignKeyInfo>,
+    /// Indexes sorted by name, including those SQLite creates for
+    /// UNIQUE and PRIMARY KEY constraints.
+    pub indexes: Vec<IndexInfo>,
+}
+
+/// One column.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnInfo {
+    pub name: String,
+    /// Declared type, as written (may be empty).
+    pub data_type: String,
+    pub not_null: bool,
+    /// Default expression, as written.
+    pub default_value: Option<String>,
+    /// Position in the primary key (1-based), 0 when not part of it.
+    pub primary_key: i64,
+}
+
+/// One foreign key, possibly over several columns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForeignKeyInfo {
+    pub columns: Vec<String>,
+    /// Referenced table.
+    pub table: String,
+    /// Referenced columns (empty: the referenced table's primary key).
+    pub references: Vec<String>,
+    pub on_delete: String,
+}
+
+/// One index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexInfo {
+    pub name: String,
+    pub unique: bool,
+    /// Indexed columns in order; `<expr>` for an expression.
+    pub columns: Vec<String>,
+    /// Whether the index has a WHERE clause.
+    pub partial: bool,
+}
+
+/// Reads the schema of the database behind `pool`.
+///
+/// ## Usage
+/// For support tooling and diagnostics; compare with [`expected_schema`]
+/// to find hand edits.
+pub async fn describe_schema(pool: &SqlitePool) -> DbResult<SchemaDescription> {
+    // Shadow tables belong to their FTS table and follow it
+    let names: Vec<String> = sqlx::query_scalar(
+        "SELECT name FROM pragma_table_list \
+         WHERE schema = 'main' AND type IN ('table', 'virtual') \
+         AND name NOT LIKE 'sqlite_%' AND name <> '_sqlx_migrations' \
+         ORDER BY name",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut tables = Vec::with_capacity(names.len());
+    for name in names {
+        tables.push(describe_table(pool, name).await?);
+    }
+
+    // A database never migrated has no _sqlx_migrations table
+    let schema_version: Option<i64> =
+        sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations WHERE success")
+            .fetch_one(pool)
+            .await
+            .unwrap_or(None);
+
+    Ok(SchemaDescription {
+        schema_version,
+        latest_version: latest_version(),
+        tables,
+    })
+}
+
+async fn describe_table(pool: &SqlitePool, name: String) -> DbResult<TableInfo> {
+    let columns: Vec<(String, String, bool, Option<String>, i64)> = sqlx::query_as(
+        "SELECT name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?) ORDER BY cid",
+    )
+    .bind(&name)
+    .fetch_all(pool)
+    .await?;
+
+    let fk_rows: Vec<(i64, String, String, Option<String>, String)> = sqlx::query_as(
+        "SELECT id, \"table\", \"from\", \"to\", on_delete \
+         FROM pragma_foreign_key_list(?) ORDER BY id, seq",
+    )
+    .bind(&name)
+    .fetch_all(pool)
+    .await?;
+    let mut foreign_keys: Vec<(i64, ForeignKeyInfo)> = Vec::new();
+    for (id, table, from, to, on_delete) in fk_rows {
+        if foreign_keys.last().is_none_or(|(last, _)| *last != id) {
+            foreign_keys.push((
+                id,
+                ForeignKeyInfo {
+                    columns: Vec::new(),
+                    table,
+                    references: Vec::new(),
+                    on_delete,
+                },
+            ));
+        }
+        let (_, fk) = foreign_keys.last_mut().expect("pushed above");
+        fk.columns.push(from);
+        fk.references.extend(to);
+    }
+
+    let index_rows: Vec<(String, bool, bool)> =
+        sqlx::query_as("SELECT name, \"unique\", partial FROM pragma_index_list(?) ORDER BY name")
+            .bind(&name)
+            .fetch_all(pool)
+            .await?;
+    let mut indexes = Vec::with_capacity(index_rows.len());
+    for (index, unique, partial) in index_rows {
+        let columns: Vec<Option<String>> =
+            sqlx::query_scalar("SELECT name FROM pragma_index_info(?) ORDER BY seqno")
+                .bind(&index)
+                .fetch_all(pool)
+                .await?;
+        indexes.push(IndexInfo {
+            name: index,
+            unique,
+            columns: columns
+                .into_iter()
+                .map(|column| column.unwrap_or_else(|| "<expr>".to_string()))
+                .collect(),
+            partial,
+        });
+    }
+
+    Ok(TableInfo {
+        name,
+        columns: columns
+            .into_iter()
+            .map(
+                |(name, data_type, not_null, default_value, primary_key)| ColumnInfo {
+                    name,
+                    data_type,
+                    not_null,
+                    default_value,
+                    primary_key,
+                },
+            )
+            .collect(),
+        foreign_keys: foreign_keys.into_iter().map(|(_, fk)| fk).collect(),
+        indexes,
+    })
+}
+
+/// The schema this build creates: a fresh in-memory database, migrated.
+pub async fn expected_schema() -> DbResult<SchemaDescription> {
+    let db = Database::new(DbConfig::in_memory()).await?;
+    describe_schema(db.pool()).await
+}
+
+// =============================================================================
+// Export
+// =============================================================================
+
+impl SchemaDescription {
+    /// The table called `name`.
+    pub fn table(&self, name: &str) -> Option<&TableInfo> {
+        self.tables.iter().find(|table| table.name == name)
+    }
+
+    /// Pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("schema description serializes")
+    }
+
+    /// A Graphviz entity-relationship diagram: one box per table listing
+    /// its columns, one edge per foreign key.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph schema {\n");
+        dot.push_str("  graph [rankdir=LR];\n");
+        dot.push_str("  node [shape=plaintext, fontname=\"Helvetica\"];\n");
+
+        for table in &self.tables {
+            dot.push_str(&format!(
+                "  \"{}\" [label=<<TABLE BORDER=\"0\" CELLBORDER=\"1\" CELLSPACING=\"0\">",
+                table.name
+            ));
+            dot.push_str(&format!(
+                "<TR><TD BGCOLOR=\"lightgrey\"><B>{}</B></TD></TR>",
+                escape_html(&table.name)
+            ));
+            for column in &table.columns {
+                let key = if column.primary_key > 0 { " PK" } else { "" };
+                dot.push_str(&format!(
+                    "<TR><TD PORT=\"{}\" ALIGN=\"LEFT\">{} {}{}</TD></TR>",
+                    escape_html(&column.name),
+                    escape_html(&column.name),
+                    escape_html(&column.data_type),
+                    key
+                ));
+            }
+            dot.push_str("</TABLE>>];\n");
+        }
+
+        for table in &self.tables {
+            for fk in &table.foreign_keys {
+                // Edges only to tables in the diagram (SQLite allows others)
+                if self.table(&fk.table).is_none() {
+                    continue;
+                }
+                let from = fk.columns.first().map(String::as_str).unwrap_or_default();
+                dot.push_str(&format!(
+                    "  \"{}\":\"{}\" -> \"{}\";\n",
+                    table.name, from, fk.table
+                ));
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// =============================================================================
+// Drift
+// =============================================================================
+
+/// How a schema differs from the expected one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftKind {
+    MissingTable,
+    ExtraTable,
+    MissingColumn,
+    ExtraColumn,
+    /// Same name, different type, NOT NULL, default or key position.
+    ChangedColumn,
+    MissingIndex,
+    ExtraIndex,
+    /// Same name, different columns, uniqueness or WHERE clause.
+    ChangedIndex,
+    MissingForeignKey,
+    ExtraForeignKey,
+}
+
+/// One difference between a database's schema and the expected one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaDrift {
+    pub kind: DriftKind,
+    pub table: String,
+    /// Column, index or foreign key columns; None for whole tables.
+    pub name: Option<String>,
+}
+
+impl fmt::Display for SchemaDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let what = match self.kind {
+            DriftKind::MissingTable => "table missing",
+            DriftKind::ExtraTable => "table not created by any migration",
+            DriftKind::MissingColumn => "column missing",
+            DriftKind::ExtraColumn => "column not created by any migration",
+            DriftKind::ChangedColumn => "column definition changed",
+            DriftKind::MissingIndex => "index missing",
+            DriftKind::ExtraIndex => "index not created by any migration",
+            DriftKind::ChangedIndex => "index definition changed",
+            DriftKind::MissingForeignKey => "foreign key missing",
+            DriftKind::ExtraForeignKey => "foreign key not created by any migration",
+        };
+        match &self.name {
+            Some(name) => write!(f, "{}.{}: {}", self.table, name, what),
+            None => write!(f, "{}: {}", self.table, what),
+        }
+    }
+}
+
+impl SchemaDescription {
+    /// Every difference from `expected`, table by table.
+    pub fn drift(&self, expected: &SchemaDescription) -> Vec<SchemaDrift> {
+        let mut drift = Vec::new();
+        let mut push = |kind, table: &str, name: Option<String>| {
+            drift.push(SchemaDrift {
+                kind,
+                table: table.to_string(),
+                name,
+            })
+        };
+
+        for want in &expected.tables {
+            let Some(have) = self.table(&want.name) else {
+                push(DriftKind::MissingTable, &want.name, None);
+                continue;
+            };
+            let table = want.name.as_str();
+
+            for column in &want.columns {
+                match have.columns.iter().find(|c| c.name == column.name) {
+                    None => push(DriftKind::MissingColumn, table, Some(column.name.clone())),
+                    Some(found) if found != column => {
+                        push(DriftKind::ChangedColumn, table, Some(column.name.clone()))
+                    }
+                    Some(_) => {}
+                }
+            }
+            for column in &have.columns {
+                if !want.columns.iter().any(|c| c.name == column.name) {
+                    push(DriftKind::ExtraColumn, table, Some(column.name.clone()));
+                }
+            }
+
+            for index in &want.indexes {
+                match have.indexes.iter().find(|i| i.name == index.name) {
+                    None => push(DriftKind::MissingIndex, table, Some(index.name.clone())),
+                    Some(found) if found != index => {
+                        push(DriftKind::ChangedIndex, table, Some(index.name.clone()))
+                    }
+                    Some(_) => {}
+                }
+            }
+            for index in &have.indexes {
+                if !want.indexes.iter().any(|i| i.name == index.name) {
+                    push(DriftKind::ExtraIndex, table, Some(index.name.clone()));
+                }
+            }
+
+            for fk in &want.foreign_keys {
+                if !have.foreign_keys.contains(fk) {
+                    push(
+                        DriftKind::MissingForeignKey,
+                        table,
+                        Some(fk.columns.join(",")),
+                    );
+                }
+            }
+            for fk in &have.foreign_keys {
+                if !want.foreign_keys.contains(fk) {
+                    push(
+                        DriftKind::ExtraForeignKey,
+                        table,
+                        Some(fk.columns.join(",")),
+                    );
+                }
+            }
+        }
+
+        for have in &self.tables {
+            if expected.table(&have.name).is_none() {
+                push(DriftKind::ExtraTable, &have.name, None);
+            }
+        }
+        drift
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_describe_schema() {
+        let schema = expected_schema().await.unwrap();
+        assert_eq!(schema.schema_version, Some(latest_version()));
+        assert!(schema.table("_sqlx_migrations").is_none());
+
+        let items = schema.table("sale_items").unwrap();
+        let id = items.columns.iter().find(|c| c.name == "id").unwrap();
+        assert_eq!(id.primary_key, 1);
+        assert!(items
+            .foreign_keys
+            .iter()
+            .any(|fk| fk.columns == ["sale_id"] && fk.table == "sales"));
+        let index = items
+            .indexes
+            .iter()
+            .find(|i| i.name == "idx_sale_items_sale")
+            .unwrap();
+        assert_eq!(index.columns, ["sale_id"]);
+        assert!(!index.unique);
+
+        let barcode = schema
+            .table("products")
+            .unwrap()
+            .indexes
+            .iter()
+            .find(|i| i.name == "idx_products_barcode")
+            .unwrap();
+        assert!(barcode.partial);
+
+        let dot = schema.to_dot();
+        assert!(dot.starts_with("digraph schema {"));
+        assert!(dot.contains("\"sale_items\":\"sale_id\" -> \"sales\";"));
+
+        let json: SchemaDescription = serde_json::from_str(&schema.to_json()).unwrap();
+        assert_eq!(json, schema);
+    }
+
+    #[tokio::test]
+    async fn test_drift_finds_hand_edits() {
+        let expected = expected_schema().await.unwrap();
+        let db = Database::new(DbConfig::in_memory()).await.unwrap();
+        assert!(describe_schema(db.pool())
+            .await
+            .unwrap()
+            .drift(&expected)
+            .is_empty());
+
+        for sql in [
+            "DROP INDEX idx_sale_items_sale",
+            "ALTER TABLE products ADD COLUMN shelf TEXT",
+            "CREATE TABLE scratch (id INTEGER PRIMARY KEY)",
+        ] {
+            sqlx::query(sql).execute(db.pool()).await.unwrap();
+        }
+
+        let drift = describe_schema(db.pool()).await.unwrap().drift(&expected);
+        let found: Vec<String> = drift.iter().map(|d| d.to_string()).collect();
+        assert_eq!(
+            found,
+            [
+                "products.shelf: column not created by any migration",
+                "sale_items.idx_sale_items_sale: index missing",
+                "scratch: table not created by any migration",
+            ]
+        );
+    }
+}
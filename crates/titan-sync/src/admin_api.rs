@@ -0,0 +1,250 @@
+//! # Hub Admin API
+//!
+//! HTTP endpoints on the hub for the store manager's back-office tools.
+//! Terminals send their day-end report to the hub when the scheduled day
+//! end finishes; the hub keeps the latest one per terminal so the manager
+//! can check every register closed without walking the shop floor.
+//!
+//! ## Endpoints
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Hub Admin API                                   │
+//! │                                                                         │
+//! │  POS #2 ── DayEndReport ──► DeltaProcessor ──► DayEndBoard              │
+//! │                                                (latest per device)      │
+//! │                                                                         │
+//! │  GET /api/admin/day-end                                                 │
+//! │      ──► every terminal's latest report, by device ID                   │
+//! │  GET /api/admin/day-end/{device_id}                                     │
+//! │      ──► one terminal's latest report, 404 before its first             │
+//! │                                                                         │
+//! │  Responses are JSON DayEndReports. With hub.admin_token set, every      │
+//! │  request needs "Authorization: Bearer <token>", else 401.               │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! The board lives in memory: after a hub restart it fills up again with
+//! the next day end.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::Serialize;
+use tokio::sync::RwLock;
+use tracing::info;
+
+use titan_core::DayEndReport;
+
+// =============================================================================
+// Day-End Board
+// =============================================================================
+
+/// The latest day-end report of each terminal, shared by the delta
+/// processor (writer) and the admin API (reader).
+#[derive(Debug, Clone, Default)]
+pub struct DayEndBoard {
+    reports: Arc<RwLock<HashMap<String, DayEndReport>>>,
+}
+
+impl DayEndBoard {
+    /// Creates an empty board.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keeps `report` as the latest from `device_id` (the connection's
+    /// device, not the one in the report).
+    pub async fn record(&self, device_id: &str, report: DayEndReport) {
+        info!(
+            device_id,
+            business_date = %report.business_date,
+            outcome = ?report.outcome(),
+            "Day end reported"
+        );
+        self.reports
+            .write()
+            .await
+            .insert(device_id.to_string(), report);
+    }
+
+    /// The latest report from `device_id`.
+    pub async fn get(&self, device_id: &str) -> Option<DayEndReport> {
+        self.reports.read().await.get(device_id).cloned()
+    }
+
+    /// Every terminal's latest report, by device ID.
+    pub async fn all(&self) -> Vec<DayEndReport> {
+        let mut reports: Vec<DayEndReport> = self.reports.read().await.values().cloned().collect();
+        reports.sort_by(|a, b| a.device_id.cmp(&b.device_id));
+        reports
+    }
+}
+
+// =============================================================================
+// Router
+// =============================================================================
+
+/// Error body returned with every non-2xx status.
+#[derive(Debug, Serialize)]
+struct ApiError {
+    error: &'static str,
+}
+
+fn api_error(status: StatusCode, error: &'static str) -> Response {
+    (status, Json(ApiError { error })).into_response()
+}
+
+#[derive(Clone)]
+struct AdminState {
+    board: DayEndBoard,
+    token: Option<Arc<str>>,
+}
+
+/// Builds the admin routes, served by the hub next to `/ws`.
+///
+/// `token`, when set, is the bearer token clients must send.
+pub fn router(board: DayEndBoard, token: Option<String>) -> Router {
+    let state = AdminState {
+        board,
+        token: token.filter(|t| !t.is_empty()).map(Arc::from),
+    };
+
+    Router::new()
+        .route("/api/admin/day-end", get(day_end_list_handler))
+        .route("/api/admin/day-end/{device_id}", get(day_end_handler))
+        .with_state(state)
+}
+
+async fn day_end_list_handler(State(state): State<AdminState>, headers: HeaderMap) -> Response {
+    if !authorized(state.token.as_deref(), &headers) {
+        return api_error(StatusCode::UNAUTHORIZED, "unauthorized");
+    }
+    Json(state.board.all().await).into_response()
+}
+
+async fn day_end_handler(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+    Path(device_id): Path<String>,
+) -> Response {
+    if !authorized(state.token.as_deref(), &headers) {
+        return api_error(StatusCode::UNAUTHORIZED, "unauthorized");
+    }
+    match state.board.get(&device_id).await {
+        Some(report) => Json(report).into_response(),
+        None => api_error(StatusCode::NOT_FOUND, "no day end reported"),
+    }
+}
+
+/// Whether the request carries the expected bearer token (always, when no
+/// token is configured).
+fn authorized(token: Option<&str>, headers: &HeaderMap) -> bool {
+    let Some(token) = token else {
+        return true;
+    };
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|given| constant_time_eq(given.as_bytes(), token.as_bytes()))
+}
+
+/// Compares without stopping at the first difference, so response timing
+/// does not reveal how much of the token was right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use chrono::{NaiveDate, Utc};
+    use titan_core::{DayEndStep, DayEndStepResult, StepOutcome};
+    use tower::ServiceExt;
+
+    fn report(device_id: &str, outcome: StepOutcome) -> DayEndReport {
+        DayEndReport {
+            device_id: device_id.to_string(),
+            station_number: Some(1),
+            business_date: NaiveDate::from_ymd_opt(2026, 3, 10).unwrap(),
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+            z_report: None,
+            steps: vec![DayEndStepResult {
+                step: DayEndStep::Backup,
+                outcome,
+                detail: "titan-20260310-230000.db".to_string(),
+            }],
+        }
+    }
+
+    async fn get(app: Router, uri: &str, token: Option<&str>) -> (StatusCode, serde_json::Value) {
+        let mut request = Request::get(uri);
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let response = app
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_latest_report_per_device() {
+        let board = DayEndBoard::new();
+        board
+            .record("pos-02", report("pos-02", StepOutcome::Ok))
+            .await;
+        board
+            .record("pos-01", report("pos-01", StepOutcome::Ok))
+            .await;
+        board
+            .record("pos-01", report("pos-01", StepOutcome::Failed))
+            .await;
+        let app = router(board, None);
+
+        let (status, body) = get(app.clone(), "/api/admin/day-end", None).await;
+        assert_eq!(status, StatusCode::OK);
+        let reports: Vec<DayEndReport> = serde_json::from_value(body).unwrap();
+        let devices: Vec<_> = reports.iter().map(|r| r.device_id.as_str()).collect();
+        assert_eq!(devices, vec!["pos-01", "pos-02"]);
+        assert_eq!(reports[0].outcome(), StepOutcome::Failed);
+
+        let (status, body) = get(app.clone(), "/api/admin/day-end/pos-02", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["steps"][0]["outcome"], "ok");
+
+        let (status, _) = get(app, "/api/admin/day-end/pos-09", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_token_required_when_configured() {
+        let app = router(DayEndBoard::new(), Some("s3cret".to_string()));
+
+        let (status, _) = get(app.clone(), "/api/admin/day-end", None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = get(app.clone(), "/api/admin/day-end", Some("wrong")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, body) = get(app, "/api/admin/day-end", Some("s3cret")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, serde_json::json!([]));
+    }
+}
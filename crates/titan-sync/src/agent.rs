@@ -27,26 +27,87 @@
 //! │  │ connection     │  │                │  │                        │    │
 //! │  └────────────────┘  └────────────────┘  └────────────────────────┘    │
 //! │                                                                         │
+//! │  In peer mode the agent also serves a PeerHub for the other terminal   │
+//! │  (see `peer`).                                                          │
+//! │                                                                         │
+//! │  Every task runs under a Supervisor: a task that panics is started     │
+//! │  again after a backoff and reported as a "sync://error".               │
+//! │                                                                         │
 //! │  STATUS EVENTS (to Tauri):                                             │
 //! │  ────────────────────────                                              │
-//! │  "sync://status"   - { state: "connected", hub: "..." }                │
+//! │  "sync://status"   - { state: "ready", hub: "..." }                    │
 //! │  "sync://progress" - { pending: 5, synced: 100 }                       │
 //! │  "sync://error"    - { message: "Connection failed", retryable: true } │
 //! └─────────────────────────────────────────────────────────────────────────┘
 //! ```
 
+use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, oneshot, Mutex, RwLock};
 use tracing::{debug, error, info, warn};
 
+use titan_core::ErrorCode;
 use titan_db::Database;
 
+use crate::cloud_fallback::CloudFallback;
+use crate::cloud_uplink::CloudUplinkConfig;
+use crate::compat;
 use crate::config::{SyncConfig, SyncMode};
+use crate::control::SyncControl;
+use crate::hub_cache;
 use crate::error::{SyncError, SyncResult};
 use crate::inbound::{InboundHandler, InboundHandlerHandle};
+use crate::integrity::DeviceKey;
 use crate::outbox::{OutboxProcessor, OutboxProcessorHandle};
-use crate::protocol::SyncMessage;
-use crate::transport::{ConnectionState, Transport, TransportConfig, TransportHandle};
+use crate::peer::{PeerHub, PeerHubHandle};
+use crate::protocol::{
+    CouponRedeemRequest, CouponRedeemResult, HelloPayload, LoyaltyRedeemRequest, LoyaltyRedeemResult, SaleLookupRequest, SaleLookupResult, StoreCreditRedeemRequest,
+    StoreCreditRedeemResult, SyncMessage, UpdateSlotRequest, UpdateSlotResult,
+};
+use crate::supervisor::{RestartPolicy, Supervisor};
+use crate::throttle::BandwidthPolicy;
+use crate::transport::{
+    ConnectionState, Transport, TransportConfig, TransportEvent, TransportHandle, Transition,
+};
+
+/// Days of connection history kept for the uptime report.
+pub const STATUS_HISTORY_RETENTION_DAYS: u32 = 90;
+
+/// How long a terminal waits for the hub to answer a store credit redemption.
+pub const STORE_CREDIT_REDEEM_TIMEOUT_SECS: u64 = 5;
+
+/// How long a device waits for the hub to answer an update slot request.
+pub const UPDATE_SLOT_TIMEOUT_SECS: u64 = 5;
+
+/// How long a terminal waits for the hub to answer a sale lookup (the hub
+/// may have to ask the cloud).
+pub const SALE_LOOKUP_TIMEOUT_SECS: u64 = 15;
+
+/// How long a terminal waits for the hub to answer a loyalty redemption
+/// (the hub checks it with the cloud).
+pub const LOYALTY_REDEEM_TIMEOUT_SECS: u64 = 15;
+
+/// How long a terminal waits for the hub to answer a coupon redemption.
+pub const COUPON_REDEEM_TIMEOUT_SECS: u64 = 5;
+
+/// How often an outbox flush checks whether the outbox has drained.
+const FLUSH_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Redemption requests waiting for the hub's answer, keyed by request ID.
+type PendingRedemptions = Arc<Mutex<HashMap<String, oneshot::Sender<StoreCreditRedeemResult>>>>;
+
+/// Update slot requests waiting for the hub's answer, keyed by request ID.
+type PendingUpdateSlots = Arc<Mutex<HashMap<String, oneshot::Sender<UpdateSlotResult>>>>;
+
+/// Sale lookups waiting for the hub's answer, keyed by request ID.
+type PendingSaleLookups = Arc<Mutex<HashMap<String, oneshot::Sender<SaleLookupResult>>>>;
+
+/// Loyalty redemptions waiting for the hub's answer, keyed by request ID.
+type PendingLoyaltyRedemptions = Arc<Mutex<HashMap<String, oneshot::Sender<LoyaltyRedeemResult>>>>;
+
+/// Coupon redemptions waiting for the hub's answer, keyed by request ID.
+type PendingCouponRedemptions = Arc<Mutex<HashMap<String, oneshot::Sender<CouponRedeemResult>>>>;
 
 // =============================================================================
 // Sync Status
@@ -61,6 +122,9 @@ pub struct SyncStatus {
     /// Whether currently connected to hub.
     pub is_connected: bool,
 
+    /// Last ping round trip to the hub (milliseconds), while connected.
+    pub latency_ms: Option<u64>,
+
     /// URL of the connected hub (if any).
     pub hub_url: Option<String>,
 
@@ -73,24 +137,59 @@ pub struct SyncStatus {
     /// Last error message (if any).
     pub last_error: Option<String>,
 
+    /// Code of the last error, for the UI to react to.
+    pub last_error_code: Option<ErrorCode>,
+
     /// Sync mode.
     pub mode: SyncMode,
+
+    /// Whether uploads are throttled for a metered link.
+    pub metered: bool,
+
+    /// Whether the outbox is going straight to the cloud because no hub
+    /// has been reachable (see `cloud_fallback`).
+    pub cloud_fallback: bool,
+
+    /// Whether sync was paused by an operator (see `control`).
+    pub paused: bool,
+
+    /// Whether a full resync is waiting for the hub's `ResyncComplete`.
+    pub resyncing: bool,
+
+    /// Protocol version negotiated with the hub, once connected.
+    pub protocol_version: Option<u32>,
 }
 
 impl Default for SyncStatus {
     fn default() -> Self {
         SyncStatus {
-            connection_state: ConnectionState::Disconnected,
+            connection_state: ConnectionState::Idle,
             is_connected: false,
+            latency_ms: None,
             hub_url: None,
             pending_count: 0,
             last_sync: None,
             last_error: None,
+            last_error_code: None,
             mode: SyncMode::Auto,
+            metered: false,
+            cloud_fallback: false,
+            paused: false,
+            resyncing: false,
+            protocol_version: None,
         }
     }
 }
 
+/// Fills in the latest round trip; the transport measures it on every pong,
+/// too often to push through the status lock.
+fn with_latency(mut status: SyncStatus, transport: Option<&TransportHandle>) -> SyncStatus {
+    status.latency_ms = transport
+        .and_then(TransportHandle::round_trip)
+        .map(|rtt| rtt.as_millis() as u64);
+    status
+}
+
 // =============================================================================
 // Event Emitter Trait
 // =============================================================================
@@ -103,8 +202,14 @@ pub trait SyncEventEmitter: Send + Sync {
     /// Emits a sync progress event.
     fn emit_progress(&self, pending: i64, synced: i64);
 
-    /// Emits a sync error event.
-    fn emit_error(&self, message: &str, retryable: bool);
+    /// Emits a sync error event; whether it is retryable follows `code`.
+    fn emit_error(&self, code: ErrorCode, message: &str);
+
+    /// Emits an upgrade-required event: the hub and this terminal share no
+    /// protocol version, so sync stays off until one is updated.
+    fn emit_upgrade_required(&self, message: &str) {
+        self.emit_error(ErrorCode::UpgradeRequired, message);
+    }
 }
 
 /// No-op event emitter for testing.
@@ -113,7 +218,7 @@ pub struct NoOpEmitter;
 impl SyncEventEmitter for NoOpEmitter {
     fn emit_status(&self, _status: &SyncStatus) {}
     fn emit_progress(&self, _pending: i64, _synced: i64) {}
-    fn emit_error(&self, _message: &str, _retryable: bool) {}
+    fn emit_error(&self, _code: ErrorCode, _message: &str) {}
 }
 
 // =============================================================================
@@ -145,6 +250,39 @@ pub struct SyncAgent {
 
     /// Inbound handler handle.
     inbound_handle: Option<InboundHandlerHandle>,
+
+    /// Store credit redemptions waiting for the hub.
+    pending_redemptions: PendingRedemptions,
+
+    /// Update slot requests waiting for the hub.
+    pending_update_slots: PendingUpdateSlots,
+
+    /// Sale lookups waiting for the hub.
+    pending_sale_lookups: PendingSaleLookups,
+
+    /// Loyalty redemptions waiting for the hub.
+    pending_loyalty_redemptions: PendingLoyaltyRedemptions,
+
+    /// Coupon redemptions waiting for the hub.
+    pending_coupon_redemptions: PendingCouponRedemptions,
+
+    /// Upload limits, shared with the outbox processor.
+    bandwidth: BandwidthPolicy,
+
+    /// Operator pause switch, shared with the outbox and inbound handlers.
+    control: SyncControl,
+
+    /// Cloud settings for the direct-upload fallback.
+    cloud: Option<CloudUplinkConfig>,
+
+    /// Stops the cloud fallback (set while it runs).
+    fallback_tx: Option<mpsc::Sender<()>>,
+
+    /// Restarts background tasks that die (set after start).
+    supervisor: Option<Supervisor>,
+
+    /// The hub served for the other terminal (peer mode, set after start).
+    peer: Option<PeerHubHandle>,
 }
 
 impl SyncAgent {
@@ -159,8 +297,11 @@ impl SyncAgent {
         db: Arc<Database>,
         emitter: Arc<dyn SyncEventEmitter>,
     ) -> Self {
+        // Invalid windows are reported by validate() when the agent starts
+        let bandwidth = BandwidthPolicy::from_settings(&config.sync).unwrap_or_default();
         let status = SyncStatus {
             mode: config.sync.mode,
+            metered: bandwidth.is_metered(),
             ..Default::default()
         };
 
@@ -173,12 +314,52 @@ impl SyncAgent {
             transport: None,
             outbox_handle: None,
             inbound_handle: None,
+            pending_redemptions: Arc::new(Mutex::new(HashMap::new())),
+            pending_update_slots: Arc::new(Mutex::new(HashMap::new())),
+            pending_sale_lookups: Arc::new(Mutex::new(HashMap::new())),
+            pending_loyalty_redemptions: Arc::new(Mutex::new(HashMap::new())),
+            pending_coupon_redemptions: Arc::new(Mutex::new(HashMap::new())),
+            bandwidth,
+            control: SyncControl::new(),
+            cloud: None,
+            fallback_tx: None,
+            supervisor: None,
+            peer: None,
         }
     }
 
+    /// Sets the cloud settings used when `cloud_fallback_secs` is
+    /// configured and no hub can be reached.
+    pub fn with_cloud_fallback(mut self, cloud: CloudUplinkConfig) -> Self {
+        self.cloud = Some(cloud);
+        self
+    }
+
     /// Returns the current sync status.
     pub async fn status(&self) -> SyncStatus {
-        self.status.read().await.clone()
+        let status = self.status.read().await.clone();
+        with_latency(status, self.transport.as_ref())
+    }
+
+    /// Returns a handle for controlling the agent once it has been started.
+    pub fn handle(&self) -> Option<SyncAgentHandle> {
+        self.shutdown_tx.as_ref().map(|tx| {
+            SyncAgentHandle::new(
+                tx.clone(),
+                self.db.clone(),
+                self.status.clone(),
+                self.transport.clone(),
+                self.config.device_id().to_string(),
+                self.pending_redemptions.clone(),
+                self.pending_update_slots.clone(),
+                self.pending_sale_lookups.clone(),
+                self.pending_loyalty_redemptions.clone(),
+                self.pending_coupon_redemptions.clone(),
+                self.bandwidth.clone(),
+                self.control.clone(),
+                self.outbox_handle.clone(),
+            )
+        })
     }
 
     /// Starts the sync agent.
@@ -195,18 +376,8 @@ impl SyncAgent {
         // Validate configuration
         self.config.validate()?;
 
-        // Get hub URL
-        let hub_url = match self.config.hub_url() {
-            Some(url) => url.to_string(),
-            None => {
-                // In future milestones, we'd start discovery here
-                // For now, require explicit hub URL
-                warn!("No hub URL configured, sync will not start");
-                return Err(SyncError::InvalidConfig(
-                    "Hub URL required for sync".into(),
-                ));
-            }
-        };
+        // Configured hub, else the last known one, else discovery
+        let hub_url = hub_cache::locate_hub(&self.config, &self.db).await?;
 
         info!(
             device_id = %self.config.device_id(),
@@ -222,11 +393,58 @@ impl SyncAgent {
             initial_backoff: std::time::Duration::from_millis(self.config.sync.initial_backoff_ms),
             max_backoff: std::time::Duration::from_secs(self.config.sync.max_backoff_secs),
             max_retries: self.config.sync.max_retries,
+            ping_interval: std::time::Duration::from_secs(self.config.sync.ping_interval_secs),
+            pong_timeout: std::time::Duration::from_secs(self.config.sync.pong_timeout_secs),
+            max_missed_pongs: self.config.sync.max_missed_pongs,
+            hello: Some(SyncMessage::Hello(HelloPayload {
+                priority: self.config.device.priority,
+                subscriptions: self.config.device.subscriptions(),
+                ..HelloPayload::new(
+                    self.config.device_id(),
+                    &self.config.device.name,
+                    self.config.store_id(),
+                )
+            })),
+            signing_key: self
+                .config
+                .store
+                .enrollment_key
+                .as_deref()
+                .map(|key| DeviceKey::derive(key, self.config.device_id())),
             ..Default::default()
         };
 
-        // Spawn transport
-        let (transport_handle, incoming_rx) = Transport::spawn(transport_config);
+        match self
+            .db
+            .sync_outbox()
+            .prune_sync_status_history(STATUS_HISTORY_RETENTION_DAYS)
+            .await
+        {
+            Ok(0) => {}
+            Ok(pruned) => debug!(pruned, "Pruned old sync status history"),
+            Err(e) => warn!(?e, "Failed to prune sync status history"),
+        }
+
+        // Every background task runs under the supervisor, which restarts
+        // it if it panics
+        let supervisor = Supervisor::new(RestartPolicy::default(), self.emitter.clone());
+        self.supervisor = Some(supervisor.clone());
+
+        // Spawn transport, subscribing first so no transition is missed
+        let (transport, transport_handle, incoming_rx) = Transport::new(transport_config);
+        let mut events = Some(transport_handle.subscribe());
+        let (db, status, emitter) = (self.db.clone(), self.status.clone(), self.emitter.clone());
+        let tracked = transport_handle.clone();
+        supervisor.spawn("connection tracker", move || {
+            // A restarted tracker follows transitions from then on
+            let events = events.take().unwrap_or_else(|| tracked.subscribe());
+            Self::track_connection(db.clone(), status.clone(), emitter.clone(), events)
+        });
+        let transport = Arc::new(Mutex::new(transport));
+        supervisor.spawn("transport", move || {
+            let transport = transport.clone();
+            async move { transport.lock().await.run().await }
+        });
         self.transport = Some(transport_handle.clone());
 
         // Create outbox processor
@@ -234,6 +452,8 @@ impl SyncAgent {
             self.db.clone(),
             self.config.clone(),
             transport_handle.clone(),
+            self.bandwidth.clone(),
+            self.control.clone(),
         );
         self.outbox_handle = Some(outbox_handle.clone());
 
@@ -242,32 +462,66 @@ impl SyncAgent {
             self.db.clone(),
             self.config.clone(),
             transport_handle.clone(),
+            self.control.clone(),
         );
         self.inbound_handle = Some(inbound_handle.clone());
 
+        // A paired terminal is also the other one's hub
+        if self.config.mode() == SyncMode::Peer {
+            let mut peer = PeerHub::new(self.config.clone(), self.db.clone());
+            if let Some(cloud) = self.cloud.clone() {
+                peer = peer.with_cloud(cloud);
+            }
+            self.peer = Some(peer.start(inbound_handle.clone()).await?);
+        }
+
         // Create shutdown channel
         let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
         self.shutdown_tx = Some(shutdown_tx);
 
         // Spawn background tasks
-        tokio::spawn(outbox_processor.run());
-        tokio::spawn(inbound_handler.run());
-
-        // Spawn message router
+        let outbox_processor = Arc::new(Mutex::new(outbox_processor));
+        supervisor.spawn("outbox processor", move || {
+            let outbox_processor = outbox_processor.clone();
+            async move { outbox_processor.lock().await.run().await }
+        });
+        let inbound_handler = Arc::new(Mutex::new(inbound_handler));
+        supervisor.spawn("inbound handler", move || {
+            let inbound_handler = inbound_handler.clone();
+            async move { inbound_handler.lock().await.run().await }
+        });
+        self.start_cloud_fallback(&supervisor);
+
+        // Spawn message router; its receivers outlive a restart
         let config = self.config.clone();
+        let db = self.db.clone();
         let status = self.status.clone();
         let emitter = self.emitter.clone();
-
-        tokio::spawn(Self::message_router(
-            config,
-            status,
-            emitter,
-            incoming_rx,
-            transport_handle,
-            outbox_handle,
-            inbound_handle,
-            shutdown_rx,
-        ));
+        let pending_redemptions = self.pending_redemptions.clone();
+        let pending_update_slots = self.pending_update_slots.clone();
+        let pending_sale_lookups = self.pending_sale_lookups.clone();
+        let pending_loyalty_redemptions = self.pending_loyalty_redemptions.clone();
+        let pending_coupon_redemptions = self.pending_coupon_redemptions.clone();
+        let incoming_rx = Arc::new(Mutex::new(incoming_rx));
+        let shutdown_rx = Arc::new(Mutex::new(shutdown_rx));
+
+        supervisor.spawn("message router", move || {
+            Self::message_router(
+                config.clone(),
+                db.clone(),
+                status.clone(),
+                emitter.clone(),
+                incoming_rx.clone(),
+                outbox_handle.clone(),
+                inbound_handle.clone(),
+                pending_redemptions.clone(),
+                pending_update_slots.clone(),
+                pending_sale_lookups.clone(),
+                pending_loyalty_redemptions.clone(),
+                pending_coupon_redemptions.clone(),
+                shutdown_rx.clone(),
+            )
+        });
 
         // Update status
         {
@@ -279,15 +533,59 @@ impl SyncAgent {
         Ok(())
     }
 
+    /// Spawns the cloud fallback if this terminal is configured for it.
+    ///
+    /// A forced PRIMARY is the hub itself and never falls back.
+    fn start_cloud_fallback(&mut self, supervisor: &Supervisor) {
+        let Some(after_secs) = self.config.sync.cloud_fallback_secs else {
+            return;
+        };
+        if self.config.mode() == SyncMode::Primary {
+            return;
+        }
+        let Some(cloud) = self.cloud.clone() else {
+            warn!("cloud_fallback_secs is set but no cloud settings were given");
+            return;
+        };
+
+        let cloud = CloudUplinkConfig {
+            bandwidth: Some(self.bandwidth.clone()),
+            ..cloud
+        };
+        let (fallback, fallback_tx) = CloudFallback::new(
+            self.db.clone(),
+            cloud,
+            Duration::from_secs(after_secs),
+            self.status.clone(),
+            self.emitter.clone(),
+        );
+        let poll_interval = Duration::from_secs(self.config.sync.poll_interval_secs);
+        let fallback = Arc::new(Mutex::new(fallback));
+        supervisor.spawn("cloud fallback", move || {
+            let fallback = fallback.clone();
+            async move { fallback.lock().await.run(poll_interval).await }
+        });
+        self.fallback_tx = Some(fallback_tx);
+    }
+
     /// Stops the sync agent gracefully.
     pub async fn shutdown(&mut self) -> SyncResult<()> {
         info!("Shutting down sync agent");
 
+        // Components stopping now are not failures
+        if let Some(supervisor) = self.supervisor.take() {
+            supervisor.stop();
+        }
+
         // Send shutdown signal
         if let Some(tx) = self.shutdown_tx.take() {
             let _ = tx.send(()).await;
         }
 
+        if let Some(tx) = self.fallback_tx.take() {
+            let _ = tx.send(()).await;
+        }
+
         // Shutdown components
         if let Some(ref handle) = self.outbox_handle {
             let _ = handle.shutdown().await;
@@ -301,10 +599,14 @@ impl SyncAgent {
             let _ = handle.shutdown().await;
         }
 
+        if let Some(peer) = self.peer.take() {
+            peer.shutdown().await;
+        }
+
         // Update status
         {
             let mut s = self.status.write().await;
-            s.connection_state = ConnectionState::Disconnected;
+            s.connection_state = ConnectionState::Idle;
             s.is_connected = false;
         }
 
@@ -313,41 +615,60 @@ impl SyncAgent {
     }
 
     /// Main message router loop.
+    #[allow(clippy::too_many_arguments)]
     async fn message_router(
         config: Arc<SyncConfig>,
+        db: Arc<Database>,
         status: Arc<RwLock<SyncStatus>>,
         emitter: Arc<dyn SyncEventEmitter>,
-        mut incoming_rx: mpsc::Receiver<SyncMessage>,
-        transport: TransportHandle,
+        incoming_rx: Arc<Mutex<mpsc::Receiver<SyncMessage>>>,
         outbox_handle: OutboxProcessorHandle,
         inbound_handle: InboundHandlerHandle,
-        mut shutdown_rx: mpsc::Receiver<()>,
+        pending_redemptions: PendingRedemptions,
+        pending_update_slots: PendingUpdateSlots,
+        pending_sale_lookups: PendingSaleLookups,
+        pending_loyalty_redemptions: PendingLoyaltyRedemptions,
+        pending_coupon_redemptions: PendingCouponRedemptions,
+        shutdown_rx: Arc<Mutex<mpsc::Receiver<()>>>,
     ) {
-        let mut handshake_done = false;
-
+        let mut incoming_rx = incoming_rx.lock().await;
+        let mut shutdown_rx = shutdown_rx.lock().await;
         loop {
             tokio::select! {
                 Some(msg) = incoming_rx.recv() => {
-                    // Update connection status
-                    if transport.is_connected().await {
-                        let mut s = status.write().await;
-                        s.connection_state = ConnectionState::Connected;
-                        s.is_connected = true;
-                    }
-
                     match msg {
                         SyncMessage::Welcome(welcome) => {
-                            // Handshake complete
+                            // Handshake complete (the transport is now Ready)
                             info!(
                                 store_id = %welcome.store_id,
                                 term = welcome.election_term,
+                                protocol_version = welcome.protocol_version,
                                 "Handshake complete"
                             );
-                            handshake_done = true;
-
-                            // Update status
-                            let s = status.read().await.clone();
-                            emitter.emit_status(&s);
+                            let hub_url = {
+                                let mut s = status.write().await;
+                                s.protocol_version = Some(welcome.protocol_version);
+                                s.hub_url.clone()
+                            };
+                            if let Some(hub_url) = hub_url {
+                                // Tried first on the next start (see hub_cache)
+                                let outbox = db.sync_outbox();
+                                let term = welcome.election_term;
+                                if let Err(e) = outbox.remember_hub(config.store_id(), &hub_url, term).await {
+                                    warn!(?e, "Failed to remember hub");
+                                }
+                            }
+                            // Entries an earlier hub held may never have
+                            // reached the cloud: this hub gets them instead
+                            match db.sync_outbox().release_held(&welcome.hub_device_id).await {
+                                Ok(0) => {}
+                                Ok(released) => info!(
+                                    released,
+                                    hub = %welcome.hub_device_id,
+                                    "Resending entries held by the previous hub"
+                                ),
+                                Err(e) => warn!(?e, "Failed to release held outbox entries"),
+                            }
                         }
 
                         SyncMessage::BatchAck(ack) => {
@@ -357,6 +678,12 @@ impl SyncAgent {
                             }
                         }
 
+                        msg @ SyncMessage::CloudAck { .. } => {
+                            if let Err(e) = outbox_handle.handle_ack(msg).await {
+                                error!(?e, "Failed to route cloud ack");
+                            }
+                        }
+
                         SyncMessage::EntityUpdate(update) => {
                             // Route to inbound handler
                             if let Err(e) = inbound_handle.handle_update(SyncMessage::EntityUpdate(update)).await {
@@ -364,6 +691,50 @@ impl SyncAgent {
                             }
                         }
 
+                        SyncMessage::ResyncComplete { products } => {
+                            info!(products, "Full resync complete");
+                            let snapshot = {
+                                let mut s = status.write().await;
+                                s.resyncing = false;
+                                s.last_sync = Some(chrono::Utc::now().to_rfc3339());
+                                s.clone()
+                            };
+                            emitter.emit_status(&snapshot);
+                        }
+
+                        SyncMessage::StoreCreditRedeemResult(result) => {
+                            // Results are broadcast; only ours has a waiter
+                            if result.device_id == config.device_id() {
+                                if let Some(waiter) = pending_redemptions.lock().await.remove(&result.request_id) {
+                                    let _ = waiter.send(result);
+                                }
+                            }
+                        }
+
+                        SyncMessage::UpdateSlotResult(result) => {
+                            if let Some(waiter) = pending_update_slots.lock().await.remove(&result.request_id) {
+                                let _ = waiter.send(result);
+                            }
+                        }
+
+                        SyncMessage::SaleLookupResult(result) => {
+                            if let Some(waiter) = pending_sale_lookups.lock().await.remove(&result.request_id) {
+                                let _ = waiter.send(result);
+                            }
+                        }
+
+                        SyncMessage::LoyaltyRedeemResult(result) => {
+                            if let Some(waiter) = pending_loyalty_redemptions.lock().await.remove(&result.request_id) {
+                                let _ = waiter.send(result);
+                            }
+                        }
+
+                        SyncMessage::CouponRedeemResult(result) => {
+                            if let Some(waiter) = pending_coupon_redemptions.lock().await.remove(&result.request_id) {
+                                let _ = waiter.send(result);
+                            }
+                        }
+
                         SyncMessage::Ping { .. } => {
                             // Send pong (handled by transport layer, but log it)
                             debug!("Received ping");
@@ -373,34 +744,33 @@ impl SyncAgent {
                             debug!("Received pong");
                         }
 
-                        SyncMessage::Error { code, message: msg_text } => {
-                            // Handle error from hub
+                        SyncMessage::Error { ref code, message: ref msg_text } => {
+                            // Handle error from hub; codes this build does not
+                            // know are treated as internal (not retryable)
                             warn!(code = %code, message = %msg_text, "Received error from hub");
+                            let error_code = msg.error_code().unwrap_or(ErrorCode::Internal);
+                            let text = format!("{}: {}", code, msg_text);
                             let mut s = status.write().await;
-                            s.last_error = Some(format!("{}: {}", code, msg_text));
-                            emitter.emit_error(&format!("{}: {}", code, msg_text), true);
+                            s.last_error = Some(text.clone());
+                            s.last_error_code = Some(error_code);
+                            drop(s);
+
+                            match error_code {
+                                ErrorCode::UpgradeRequired => emitter.emit_upgrade_required(msg_text),
+                                _ => emitter.emit_error(error_code, &text),
+                            }
+                            if error_code == ErrorCode::Busy {
+                                // Uploads hold off; the transport handles a full hub
+                                if let Err(e) = outbox_handle.handle_ack(msg).await {
+                                    error!(?e, "Failed to route busy notice");
+                                }
+                            }
                         }
 
                         other => {
                             debug!(?other, "Unhandled message type");
                         }
                     }
-
-                    // Send Hello if connected but not handshaked
-                    if transport.is_connected().await && !handshake_done {
-                        let hello = SyncMessage::hello(
-                            config.device_id(),
-                            &config.device.name,
-                            config.store_id(),
-                            config.device.priority,
-                        );
-
-                        if let Err(e) = transport.send(hello).await {
-                            error!(?e, "Failed to send Hello");
-                        } else {
-                            debug!("Sent Hello message");
-                        }
-                    }
                 }
 
                 _ = shutdown_rx.recv() => {
@@ -412,6 +782,58 @@ impl SyncAgent {
 
         info!("Message router stopped");
     }
+
+    /// Mirrors transport state changes into the sync status, emits them and
+    /// records them in the status history. Ends when the transport stops
+    /// (reaches Idle).
+    async fn track_connection(
+        db: Arc<Database>,
+        status: Arc<RwLock<SyncStatus>>,
+        emitter: Arc<dyn SyncEventEmitter>,
+        mut events: broadcast::Receiver<TransportEvent>,
+    ) {
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(skipped, "Connection tracker lagged behind transport events");
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+
+            let snapshot = {
+                let mut s = status.write().await;
+                s.connection_state = event.to;
+                s.is_connected = event.to == ConnectionState::Ready;
+                if let Transition::Lost { reason, .. } = &event.transition {
+                    s.last_error = Some(reason.clone());
+                    s.last_error_code = Some(ErrorCode::Unavailable);
+                }
+                if !s.is_connected {
+                    // Renegotiated on reconnect; an unfinished resync is abandoned
+                    s.protocol_version = None;
+                    s.resyncing = false;
+                }
+                s.clone()
+            };
+            emitter.emit_status(&snapshot);
+
+            let reason = match &event.transition {
+                Transition::Lost { reason, .. } => Some(reason.as_str()),
+                _ => None,
+            };
+            let state = event.to.to_string();
+            let history = db.sync_outbox();
+            if let Err(e) = history.record_sync_status(&state, reason, chrono::Utc::now()).await {
+                warn!(?e, "Failed to record sync status history");
+            }
+
+            if event.to == ConnectionState::Idle {
+                break;
+            }
+        }
+    }
 }
 
 // =============================================================================
@@ -422,29 +844,487 @@ impl SyncAgent {
 ///
 /// This is used by the Tauri app to control the sync agent without
 /// needing direct access to the agent instance.
+#[derive(Clone)]
 pub struct SyncAgentHandle {
     /// Shutdown sender.
     shutdown_tx: mpsc::Sender<()>,
 
+    /// Database connection (for resetting sync cursors).
+    db: Arc<Database>,
+
     /// Status accessor.
     status: Arc<RwLock<SyncStatus>>,
+
+    /// Transport for request/response messages to the hub.
+    transport: Option<TransportHandle>,
+
+    /// This device's ID (stamped on requests).
+    device_id: String,
+
+    /// Store credit redemptions waiting for the hub.
+    pending_redemptions: PendingRedemptions,
+
+    /// Update slot requests waiting for the hub.
+    pending_update_slots: PendingUpdateSlots,
+
+    /// Sale lookups waiting for the hub.
+    pending_sale_lookups: PendingSaleLookups,
+
+    /// Loyalty redemptions waiting for the hub.
+    pending_loyalty_redemptions: PendingLoyaltyRedemptions,
+
+    /// Coupon redemptions waiting for the hub.
+    pending_coupon_redemptions: PendingCouponRedemptions,
+
+    /// Upload limits (for toggling metered mode).
+    bandwidth: BandwidthPolicy,
+
+    /// Operator pause switch.
+    control: SyncControl,
+
+    /// Outbox processor (for flushing).
+    outbox: Option<OutboxProcessorHandle>,
 }
 
 impl SyncAgentHandle {
     /// Creates a new handle from agent internals.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         shutdown_tx: mpsc::Sender<()>,
+        db: Arc<Database>,
         status: Arc<RwLock<SyncStatus>>,
+        transport: Option<TransportHandle>,
+        device_id: String,
+        pending_redemptions: PendingRedemptions,
+        pending_update_slots: PendingUpdateSlots,
+        pending_sale_lookups: PendingSaleLookups,
+        pending_loyalty_redemptions: PendingLoyaltyRedemptions,
+        pending_coupon_redemptions: PendingCouponRedemptions,
+        bandwidth: BandwidthPolicy,
+        control: SyncControl,
+        outbox: Option<OutboxProcessorHandle>,
     ) -> Self {
         SyncAgentHandle {
             shutdown_tx,
+            db,
             status,
+            transport,
+            device_id,
+            pending_redemptions,
+            pending_update_slots,
+            pending_sale_lookups,
+            pending_loyalty_redemptions,
+            pending_coupon_redemptions,
+            bandwidth,
+            control,
+            outbox,
+        }
+    }
+
+    /// Sends a stock change made on this device (a count correction, a
+    /// receipt) to the hub, which applies it and broadcasts it to the
+    /// other terminals.
+    ///
+    /// The local stock is not touched: callers update it once this returns.
+    ///
+    /// ## Errors
+    /// - `SyncError::Disconnected` if the hub is not connected
+    pub async fn send_inventory_delta(
+        &self,
+        product_id: &str,
+        sku: &str,
+        delta_quantity: i32,
+    ) -> SyncResult<()> {
+        let transport = match &self.transport {
+            Some(transport) if transport.is_connected().await => transport,
+            _ => return Err(SyncError::Disconnected),
+        };
+        transport
+            .send(SyncMessage::inventory_delta(product_id, sku, delta_quantity))
+            .await?;
+
+        debug!(product_id = %product_id, delta_quantity, "Sent inventory delta to hub");
+        Ok(())
+    }
+
+    /// Asks the hub to spend store credit and waits for its answer.
+    ///
+    /// The request's `device_id` is set to this device. A rejected
+    /// redemption is returned as `Ok` with `approved: false`; errors mean
+    /// the hub could not be asked or did not answer in time, in which case
+    /// the same request (same `entry_id`) can safely be retried.
+    ///
+    /// ## Errors
+    /// - `SyncError::Disconnected` if the hub is not connected
+    /// - `SyncError::Timeout` if the hub did not answer in time
+    pub async fn redeem_store_credit(
+        &self,
+        mut request: StoreCreditRedeemRequest,
+    ) -> SyncResult<StoreCreditRedeemResult> {
+        let transport = match &self.transport {
+            Some(transport) if transport.is_connected().await => transport,
+            _ => return Err(SyncError::Disconnected),
+        };
+        request.device_id = self.device_id.clone();
+        let request_id = request.request_id.clone();
+
+        let (tx, rx) = oneshot::channel();
+        self.pending_redemptions.lock().await.insert(request_id.clone(), tx);
+
+        if let Err(e) = transport.send(SyncMessage::StoreCreditRedeem(request)).await {
+            self.pending_redemptions.lock().await.remove(&request_id);
+            return Err(e);
+        }
+
+        let answer = tokio::time::timeout(Duration::from_secs(STORE_CREDIT_REDEEM_TIMEOUT_SECS), rx).await;
+        match answer {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(_)) => Err(SyncError::ShuttingDown),
+            Err(_) => {
+                self.pending_redemptions.lock().await.remove(&request_id);
+                Err(SyncError::Timeout(STORE_CREDIT_REDEEM_TIMEOUT_SECS))
+            }
+        }
+    }
+
+    /// Asks the hub for a slot to install app `version` (see
+    /// `update_rollout`) and waits for its answer.
+    ///
+    /// A hub older than protocol v5 cannot stagger updates, so the slot is
+    /// granted without asking it: holding devices back would strand them
+    /// on a build the hub may need them to move off.
+    ///
+    /// ## Errors
+    /// - `SyncError::Disconnected` if the hub is not connected
+    /// - `SyncError::Timeout` if the hub did not answer in time (ask again)
+    pub async fn request_update_slot(&self, version: &str) -> SyncResult<UpdateSlotResult> {
+        let transport = match &self.transport {
+            Some(transport) if transport.is_connected().await => transport,
+            _ => return Err(SyncError::Disconnected),
+        };
+        let request_id = uuid::Uuid::new_v4().to_string();
+
+        let hub_version = self.status.read().await.protocol_version.unwrap_or(0);
+        if hub_version < compat::STAGED_UPDATE_VERSION {
+            info!(hub_version, version = %version, "Hub predates staged updates, updating now");
+            return Ok(UpdateSlotResult {
+                request_id,
+                device_id: self.device_id.clone(),
+                granted: true,
+                queue_position: 0,
+                retry_after_secs: 0,
+            });
+        }
+
+        let (tx, rx) = oneshot::channel();
+        self.pending_update_slots.lock().await.insert(request_id.clone(), tx);
+
+        let request = SyncMessage::UpdateSlotRequest(UpdateSlotRequest {
+            request_id: request_id.clone(),
+            device_id: self.device_id.clone(),
+            version: version.to_string(),
+        });
+        if let Err(e) = transport.send(request).await {
+            self.pending_update_slots.lock().await.remove(&request_id);
+            return Err(e);
+        }
+
+        let answer = tokio::time::timeout(Duration::from_secs(UPDATE_SLOT_TIMEOUT_SECS), rx).await;
+        match answer {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(_)) => Err(SyncError::ShuttingDown),
+            Err(_) => {
+                self.pending_update_slots.lock().await.remove(&request_id);
+                Err(SyncError::Timeout(UPDATE_SLOT_TIMEOUT_SECS))
+            }
+        }
+    }
+
+    /// Asks the hub for a sale this terminal no longer has (see
+    /// `titan_core::retention`) and waits for its answer.
+    ///
+    /// The hub answers from its own copy, or from the cloud when it has
+    /// none; `sale` is `None` if neither has the receipt.
+    ///
+    /// ## Errors
+    /// - `SyncError::Disconnected` if the hub is not connected
+    /// - `SyncError::UnsupportedVersion` if the hub predates protocol v9
+    /// - `SyncError::Timeout` if the hub did not answer in time
+    pub async fn lookup_sale(&self, receipt_number: &str) -> SyncResult<SaleLookupResult> {
+        let transport = match &self.transport {
+            Some(transport) if transport.is_connected().await => transport,
+            _ => return Err(SyncError::Disconnected),
+        };
+
+        let hub_version = self.status.read().await.protocol_version.unwrap_or(0);
+        if hub_version < compat::SALE_LOOKUP_VERSION {
+            return Err(SyncError::UnsupportedVersion(hub_version));
+        }
+
+        let request_id = uuid::Uuid::new_v4().to_string();
+        let (tx, rx) = oneshot::channel();
+        self.pending_sale_lookups.lock().await.insert(request_id.clone(), tx);
+
+        let request = SyncMessage::SaleLookup(SaleLookupRequest {
+            request_id: request_id.clone(),
+            device_id: self.device_id.clone(),
+            receipt_number: receipt_number.to_string(),
+        });
+        if let Err(e) = transport.send(request).await {
+            self.pending_sale_lookups.lock().await.remove(&request_id);
+            return Err(e);
+        }
+
+        let answer = tokio::time::timeout(Duration::from_secs(SALE_LOOKUP_TIMEOUT_SECS), rx).await;
+        match answer {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(_)) => Err(SyncError::ShuttingDown),
+            Err(_) => {
+                self.pending_sale_lookups.lock().await.remove(&request_id);
+                Err(SyncError::Timeout(SALE_LOOKUP_TIMEOUT_SECS))
+            }
+        }
+    }
+
+    /// Asks the hub to spend loyalty rewards and waits for its answer.
+    ///
+    /// The request's `device_id` is set to this device. As with store
+    /// credit, a rejection is returned as `Ok` with `approved: false` and
+    /// the same request (same `entry_id`) can safely be retried on error.
+    ///
+    /// ## Errors
+    /// - `SyncError::Disconnected` if the hub is not connected
+    /// - `SyncError::UnsupportedVersion` if the hub predates protocol v10
+    /// - `SyncError::Timeout` if the hub did not answer in time
+    pub async fn redeem_loyalty(
+        &self,
+        mut request: LoyaltyRedeemRequest,
+    ) -> SyncResult<LoyaltyRedeemResult> {
+        let transport = match &self.transport {
+            Some(transport) if transport.is_connected().await => transport,
+            _ => return Err(SyncError::Disconnected),
+        };
+
+        let hub_version = self.status.read().await.protocol_version.unwrap_or(0);
+        if hub_version < compat::LOYALTY_REDEEM_VERSION {
+            return Err(SyncError::UnsupportedVersion(hub_version));
+        }
+
+        request.device_id = self.device_id.clone();
+        let request_id = request.request_id.clone();
+
+        let (tx, rx) = oneshot::channel();
+        self.pending_loyalty_redemptions.lock().await.insert(request_id.clone(), tx);
+
+        if let Err(e) = transport.send(SyncMessage::LoyaltyRedeem(request)).await {
+            self.pending_loyalty_redemptions.lock().await.remove(&request_id);
+            return Err(e);
+        }
+
+        let answer = tokio::time::timeout(Duration::from_secs(LOYALTY_REDEEM_TIMEOUT_SECS), rx).await;
+        match answer {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(_)) => Err(SyncError::ShuttingDown),
+            Err(_) => {
+                self.pending_loyalty_redemptions.lock().await.remove(&request_id);
+                Err(SyncError::Timeout(LOYALTY_REDEEM_TIMEOUT_SECS))
+            }
+        }
+    }
+
+    /// Asks the hub to record a coupon use and waits for its answer.
+    ///
+    /// The request's `device_id` is set to this device. A coupon the hub
+    /// refuses is returned as `Ok` with `approved: false`; on error the
+    /// same request (same `redemption_id`) can safely be retried.
+    ///
+    /// ## Errors
+    /// - `SyncError::Disconnected` if the hub is not connected
+    /// - `SyncError::UnsupportedVersion` if the hub predates protocol v11
+    /// - `SyncError::Timeout` if the hub did not answer in time
+    pub async fn redeem_coupon(
+        &self,
+        mut request: CouponRedeemRequest,
+    ) -> SyncResult<CouponRedeemResult> {
+        let transport = match &self.transport {
+            Some(transport) if transport.is_connected().await => transport,
+            _ => return Err(SyncError::Disconnected),
+        };
+
+        let hub_version = self.status.read().await.protocol_version.unwrap_or(0);
+        if hub_version < compat::COUPON_REDEEM_VERSION {
+            return Err(SyncError::UnsupportedVersion(hub_version));
+        }
+
+        request.device_id = self.device_id.clone();
+        let request_id = request.request_id.clone();
+
+        let (tx, rx) = oneshot::channel();
+        self.pending_coupon_redemptions.lock().await.insert(request_id.clone(), tx);
+
+        if let Err(e) = transport.send(SyncMessage::CouponRedeem(request)).await {
+            self.pending_coupon_redemptions.lock().await.remove(&request_id);
+            return Err(e);
+        }
+
+        let answer = tokio::time::timeout(Duration::from_secs(COUPON_REDEEM_TIMEOUT_SECS), rx).await;
+        match answer {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(_)) => Err(SyncError::ShuttingDown),
+            Err(_) => {
+                self.pending_coupon_redemptions.lock().await.remove(&request_id);
+                Err(SyncError::Timeout(COUPON_REDEEM_TIMEOUT_SECS))
+            }
+        }
+    }
+
+    /// Tells the hub this device finished installing `version` (or gave
+    /// up), freeing its update slot.
+    ///
+    /// ## Errors
+    /// - `SyncError::Disconnected` if the hub is not connected (the slot
+    ///   frees itself when its lease runs out)
+    pub async fn report_update_finished(&self, version: &str, success: bool) -> SyncResult<()> {
+        let transport = match &self.transport {
+            Some(transport) if transport.is_connected().await => transport,
+            _ => return Err(SyncError::Disconnected),
+        };
+        if self.status.read().await.protocol_version.unwrap_or(0) < compat::STAGED_UPDATE_VERSION {
+            return Ok(());
+        }
+        transport
+            .send(SyncMessage::UpdateFinished {
+                version: version.to_string(),
+                success,
+            })
+            .await?;
+
+        info!(version = %version, success, "Reported update finished to hub");
+        Ok(())
+    }
+
+    /// Uploads the outbox to the hub now and waits, up to `wait`, for it
+    /// to drain. Returns the entries still pending afterwards.
+    ///
+    /// ## Errors
+    /// - `SyncError::Disconnected` if the hub is not connected
+    pub async fn flush_outbox(&self, wait: Duration) -> SyncResult<i64> {
+        let connected = match &self.transport {
+            Some(transport) => transport.is_connected().await,
+            None => false,
+        };
+        let outbox = match &self.outbox {
+            Some(outbox) if connected => outbox,
+            _ => return Err(SyncError::Disconnected),
+        };
+        outbox.flush()?;
+
+        let deadline = tokio::time::Instant::now() + wait;
+        loop {
+            let pending = self.db.sync_outbox().count_pending().await?;
+            if pending == 0 || tokio::time::Instant::now() >= deadline {
+                info!(pending, "Outbox flush finished");
+                return Ok(pending);
+            }
+            tokio::time::sleep(FLUSH_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Sends the outcome of this terminal's day end to the hub, which
+    /// shows it on the admin API (see `admin_api`).
+    ///
+    /// A hub older than protocol v6 has nowhere to keep it; the report is
+    /// dropped there and only logged.
+    ///
+    /// ## Errors
+    /// - `SyncError::Disconnected` if the hub is not connected
+    pub async fn report_day_end(&self, report: titan_core::DayEndReport) -> SyncResult<()> {
+        let transport = match &self.transport {
+            Some(transport) if transport.is_connected().await => transport,
+            _ => return Err(SyncError::Disconnected),
+        };
+        let hub_version = self.status.read().await.protocol_version.unwrap_or(0);
+        if hub_version < compat::DAY_END_REPORT_VERSION {
+            info!(hub_version, "Hub predates day-end reports, not sending");
+            return Ok(());
         }
+        let business_date = report.business_date;
+        transport.send(SyncMessage::DayEndReport(report)).await?;
+
+        info!(%business_date, "Reported day end to hub");
+        Ok(())
     }
 
     /// Gets the current sync status.
     pub async fn status(&self) -> SyncStatus {
-        self.status.read().await.clone()
+        let status = self.status.read().await.clone();
+        with_latency(status, self.transport.as_ref())
+    }
+
+    /// Turns metered mode on or off. Takes effect on the next upload.
+    pub async fn set_metered(&self, metered: bool) -> SyncStatus {
+        self.bandwidth.set_metered(metered);
+        self.status.write().await.metered = metered;
+        self.status().await
+    }
+
+    /// Pauses sync: no uploads, and hub updates are dropped. The hub
+    /// connection stays up.
+    pub async fn pause(&self) -> SyncStatus {
+        self.set_paused(true).await
+    }
+
+    /// Resumes sync after [`pause`](Self::pause).
+    pub async fn resume(&self) -> SyncStatus {
+        self.set_paused(false).await
+    }
+
+    async fn set_paused(&self, paused: bool) -> SyncStatus {
+        self.control.set_paused(paused);
+        self.status.write().await.paused = paused;
+        info!(paused, "Sync pause toggled");
+        self.status().await
+    }
+
+    /// Drops the inbound sync cursors and asks the hub for the whole
+    /// catalog again, stock included (see `control`).
+    ///
+    /// Returns once the request is sent; `resyncing` in the status clears
+    /// when the hub reports the resync complete.
+    ///
+    /// ## Errors
+    /// - `SyncError::Paused` if sync is paused (snapshots would be dropped)
+    /// - `SyncError::Disconnected` if the hub is not connected
+    /// - `SyncError::UpgradeRequired` if the hub predates full resync
+    pub async fn trigger_full_resync(&self) -> SyncResult<SyncStatus> {
+        if self.control.is_paused() {
+            return Err(SyncError::Paused);
+        }
+        let transport = match &self.transport {
+            Some(transport) if transport.is_connected().await => transport,
+            _ => return Err(SyncError::Disconnected),
+        };
+        match self.status.read().await.protocol_version {
+            Some(version) if version >= compat::FULL_RESYNC_VERSION => {}
+            version => {
+                return Err(SyncError::UpgradeRequired(format!(
+                    "hub speaks protocol v{}, full resync needs v{}",
+                    version.unwrap_or(0),
+                    compat::FULL_RESYNC_VERSION
+                )))
+            }
+        }
+
+        self.db.sync_outbox().reset_inbound_cursors().await?;
+        transport
+            .send(SyncMessage::ResyncRequest {
+                device_id: self.device_id.clone(),
+            })
+            .await?;
+        self.status.write().await.resyncing = true;
+
+        info!("Requested full resync from hub");
+        Ok(self.status().await)
     }
 
     /// Signals the agent to shut down gracefully.
@@ -462,6 +1342,7 @@ pub struct SyncAgentBuilder {
     config: SyncConfig,
     db: Option<Arc<Database>>,
     emitter: Option<Arc<dyn SyncEventEmitter>>,
+    cloud: Option<CloudUplinkConfig>,
 }
 
 impl SyncAgentBuilder {
@@ -471,6 +1352,7 @@ impl SyncAgentBuilder {
             config,
             db: None,
             emitter: None,
+            cloud: None,
         }
     }
 
@@ -486,6 +1368,12 @@ impl SyncAgentBuilder {
         self
     }
 
+    /// Sets the cloud settings for the direct-upload fallback.
+    pub fn with_cloud_fallback(mut self, cloud: CloudUplinkConfig) -> Self {
+        self.cloud = Some(cloud);
+        self
+    }
+
     /// Builds the SyncAgent.
     pub fn build(self) -> SyncResult<SyncAgent> {
         let db = self
@@ -494,7 +1382,11 @@ impl SyncAgentBuilder {
 
         let emitter = self.emitter.unwrap_or_else(|| Arc::new(NoOpEmitter));
 
-        Ok(SyncAgent::with_emitter(self.config, db, emitter))
+        let agent = SyncAgent::with_emitter(self.config, db, emitter);
+        Ok(match self.cloud {
+            Some(cloud) => agent.with_cloud_fallback(cloud),
+            None => agent,
+        })
     }
 }
 
@@ -505,7 +1397,7 @@ mod tests {
     #[test]
     fn test_sync_status_default() {
         let status = SyncStatus::default();
-        assert_eq!(status.connection_state, ConnectionState::Disconnected);
+        assert_eq!(status.connection_state, ConnectionState::Idle);
         assert!(!status.is_connected);
         assert_eq!(status.pending_count, 0);
     }
@@ -55,11 +55,27 @@ use std::time::Duration;
 
 use tokio::sync::{mpsc, RwLock};
 use tokio::time::{interval, Instant};
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, info_span, warn, Instrument};
 
+use titan_core::{
+    CouponDocument, CouponRedemption, EntityPatch, ErasureOrigin, ErasureRequest, LoyaltyDocument, LoyaltyEntry,
+    LoyaltyEntryKind, SaleDocument, StoreCreditDocument, StoreCreditEntry, StoreCreditEntryKind,
+    SyncPayload, Tombstone,
+};
+use titan_db::{Database, HubUpload, PayloadCipher};
+
+use crate::admin_api::DayEndBoard;
+use crate::cloud_fallback::CLOUD_ENTITY_TYPES;
+use crate::cloud_uplink::{CloudUplink, CloudUplinkConfig};
 use crate::error::{SyncError, SyncResult};
 use crate::hub::HubHandle;
-use crate::protocol::{InventoryDelta, InventoryUpdate, SyncMessage};
+use crate::protocol::{
+    BatchAck, CouponRedeemRequest, CouponRedeemResult, EntityUpdate, FailedEntry, InventoryDelta, InventoryUpdate, LoyaltyRedeemRequest,
+    LoyaltyRedeemResult, OutboxBatch, OutboxEntry, SaleLookupRequest, SaleLookupResult, StoreCreditRedeemRequest, StoreCreditRedeemResult, SyncMessage, UpdateSlotRequest,
+    UpdateSlotResult,
+};
+use crate::telemetry;
+use crate::update_rollout::{SlotDecision, UpdateCoordinator, UPDATE_RETRY_SECS};
 
 // =============================================================================
 // Constants
@@ -71,6 +87,36 @@ pub const DEFAULT_COALESCE_WINDOW_MS: u64 = 50;
 /// Maximum pending deltas before force flush.
 const MAX_PENDING_DELTAS: usize = 1000;
 
+/// Products per page when streaming a full resync.
+const RESYNC_PAGE_SIZE: u32 = 200;
+
+/// Outbox entity types that are shared documents rather than deltas.
+///
+/// These are relayed verbatim to every terminal as `EntityUpdate` upserts
+/// so a document created on one POS can be opened on another. Customer
+/// erasures are relayed the same way so every terminal erases its copy,
+/// product attribute sets so every terminal can search and promote by
+/// them, product styles so variants group the same everywhere, bundle
+/// bills of materials so every terminal takes the same components out of
+/// stock, pack sizes so any terminal can receive by the case, suppliers
+/// with their price lists so any terminal can receive from them,
+/// coupons so a single-use code redeemed on one register is spent on all,
+/// and loyalty accounts so points earned on one register count on all.
+const RELAYED_ENTITY_TYPES: &[&str] = &[
+    "QUOTE",
+    "LAYAWAY",
+    "STORE_TRANSFER",
+    "STORE_CREDIT",
+    "CUSTOMER_ERASURE",
+    "PRODUCT_ATTRIBUTES",
+    "PRODUCT_STYLE",
+    "PRODUCT_BUNDLE",
+    "PRODUCT_PACKS",
+    "SUPPLIER",
+    "COUPON",
+    "LOYALTY",
+];
+
 // =============================================================================
 // Broadcast Mode
 // =============================================================================
@@ -245,8 +291,10 @@ impl InventoryAggregator {
     async fn run(self, mut cmd_rx: mpsc::Receiver<AggregatorCommand>) {
         info!(mode = %self.config.mode, "Inventory aggregator started");
 
-        // Coalesce timer (only active in Coalesced mode)
-        let mut coalesce_interval = interval(self.config.coalesce_window);
+        // Coalesce timer (only active in Coalesced mode; `interval` panics
+        // on the zero window of an immediate config)
+        let period = self.config.coalesce_window.max(Duration::from_millis(1));
+        let mut coalesce_interval = interval(period);
 
         loop {
             tokio::select! {
@@ -358,6 +406,12 @@ impl InventoryAggregator {
                 continue;
             }
 
+            debug!(
+                product_id = %pending_delta.product_id,
+                age_ms = pending_delta.first_seen.elapsed().as_millis() as u64,
+                "Broadcasting coalesced delta"
+            );
+
             let delta = InventoryDelta {
                 product_id: pending_delta.product_id,
                 sku: pending_delta.sku,
@@ -390,19 +444,138 @@ impl InventoryAggregator {
 // =============================================================================
 
 /// Processes incoming messages from the hub and routes them to the aggregator.
+///
+/// Shared documents (see [`RELAYED_ENTITY_TYPES`]), completed sales
+/// sent with their lines, product field patches and tombstones (soft
+/// deletes and restores) in outbox batches are relayed to all terminals
+/// instead, so any register can look up, reprint or refund them. Every
+/// batch entry is checked against its payload schema first; entries that
+/// fail are quarantined and go no further. Each batch is answered with a
+/// `BatchAck` to the sending terminal; cloud-bound entries are reported
+/// held until the hub has uploaded them (see `hub_uplink`).
+///
+/// With a database attached, the processor also applies tombstones,
+/// customer erasures and relayed sales to the hub's copy, keeps the hub's store credit ledger and answers `StoreCreditRedeem` requests against it:
+///
+/// ```text
+/// POS #2 ──► StoreCreditRedeem { entry_id, amount }
+///            │
+///            ▼
+///   db.store_credits().redeem(entry)      (single tx: check + write)
+///            │
+///            ├──► StoreCreditRedeemResult { device_id: POS #2, approved }
+///            └──► EntityUpdate store_credit (approved only, all terminals)
+/// ```
+///
+/// It also answers a terminal's `ResyncRequest` by streaming the catalog
+/// to that terminal alone, on its own task so deltas keep flowing:
+///
+/// ```text
+/// POS #2 ──► ResyncRequest
+///            │
+///            ▼
+///   db.products().list_after(last_id, 200)   (repeat until empty)
+///            │
+///            ├──► EntityUpdate product "snapshot" × N   (POS #2 only)
+///            └──► ResyncComplete { products: N }        (POS #2 only)
+/// ```
+///
+/// A terminal's `SaleLookup` is answered from the hub's copy of the sale,
+/// or from the cloud when the hub has none and cloud settings are given:
+///
+/// ```text
+/// POS #2 ──► SaleLookup { receipt_number }
+///            │
+///            ▼
+///   db.sales().get_document_by_receipt()  ──► found: from_cloud = false
+///            │ not found
+///            ▼
+///   CloudUplink::get_sale()               ──► from_cloud = true
+///            │
+///            └──► SaleLookupResult { sale }             (POS #2 only)
+/// ```
+///
+/// A `LoyaltyRedeem` is checked by the cloud, which sees every store's
+/// redemptions, when cloud settings are given; a hub without them checks
+/// it against its own ledger:
+///
+/// ```text
+/// POS #2 ──► LoyaltyRedeem { entry_id, amount }
+///            │
+///            ▼
+///   CloudUplink::redeem_loyalty_reward()  ──► approved: merged into db
+///     (no cloud: db.loyalty().redeem())
+///            │
+///            ├──► LoyaltyRedeemResult { approved }      (POS #2 only)
+///            └──► EntityUpdate loyalty (approved only, all terminals)
+/// ```
+///
+/// A `CouponRedeem` is counted against every redemption the hub has seen,
+/// so the last use of a coupon goes to one register only:
+///
+/// ```text
+/// POS #2 ──► CouponRedeem { coupon, redemption_id }
+///            │
+///            ▼
+///   db.coupons().upsert_from_sync(coupon)  (the hub may not know it yet)
+///   db.coupons().redeem()
+///            │
+///            ├──► CouponRedeemResult { approved }       (POS #2 only)
+///            └──► EntityUpdate coupon (approved only, all terminals)
+/// ```
+///
+/// Update slot requests and reports go to the hub's
+/// [`UpdateCoordinator`] (see `update_rollout`); day-end reports to the
+/// [`DayEndBoard`], when one is attached (see `admin_api`).
 pub struct DeltaProcessor {
     /// Aggregator handle.
     aggregator: AggregatorHandle,
+    /// Hub handle for relaying shared documents.
+    hub: HubHandle,
+    /// Hub's local database (store credit ledger).
+    db: Option<Arc<Database>>,
+    /// Staged app update slots.
+    updates: UpdateCoordinator,
+    /// Latest day-end report per terminal, for the admin API.
+    day_end: Option<DayEndBoard>,
+    /// Cloud settings for looking up sales the hub does not have.
+    cloud: Option<CloudUplinkConfig>,
 }
 
 impl DeltaProcessor {
     /// Creates a new delta processor.
-    pub fn new(aggregator: AggregatorHandle) -> Self {
-        DeltaProcessor { aggregator }
+    pub fn new(aggregator: AggregatorHandle, hub: HubHandle) -> Self {
+        let updates = UpdateCoordinator::new(hub.device_id());
+        DeltaProcessor {
+            aggregator,
+            hub,
+            db: None,
+            updates,
+            day_end: None,
+            cloud: None,
+        }
+    }
+
+    /// Attaches the hub's database so store credit can be verified.
+    pub fn with_database(mut self, db: Arc<Database>) -> Self {
+        self.db = Some(db);
+        self
+    }
+
+    /// Keeps the day-end reports terminals send in `board`.
+    pub fn with_day_end_board(mut self, board: DayEndBoard) -> Self {
+        self.day_end = Some(board);
+        self
+    }
+
+    /// Looks up sales the hub does not have in the cloud with `cloud`.
+    pub fn with_cloud(mut self, cloud: CloudUplinkConfig) -> Self {
+        self.cloud = Some(cloud);
+        self
     }
 
     /// Starts processing messages from the given receiver.
-    pub async fn start(self, mut delta_rx: mpsc::Receiver<(String, SyncMessage)>) {
+    pub async fn start(mut self, mut delta_rx: mpsc::Receiver<(String, SyncMessage)>) {
         info!("Delta processor started");
 
         while let Some((device_id, msg)) = delta_rx.recv().await {
@@ -413,17 +586,63 @@ impl DeltaProcessor {
                     }
                 }
                 SyncMessage::OutboxBatch(batch) => {
-                    // Process each entity in the batch
-                    for entity in batch.entities {
-                        if entity.entity_type == "InventoryDelta" {
-                            if let Ok(delta) = serde_json::from_str::<InventoryDelta>(&entity.payload) {
-                                if let Err(e) = self.aggregator.process_delta(device_id.clone(), delta).await {
-                                    error!(?e, "Failed to process delta from batch");
-                                }
-                            }
-                        }
+                    let ack = self.process_batch(&device_id, batch).await;
+                    if let Err(e) = self.hub.send_to(&device_id, SyncMessage::BatchAck(ack)).await {
+                        warn!(device_id = %device_id, ?e, "Failed to send batch ack");
+                    }
+                }
+                SyncMessage::ResyncRequest { .. } => {
+                    // The connection's device ID, not the one in the message
+                    self.start_resync(device_id);
+                }
+                SyncMessage::StoreCreditRedeem(request) => {
+                    let result = self.redeem_store_credit(&request).await;
+                    if let Err(e) = self.hub.broadcast(SyncMessage::StoreCreditRedeemResult(result)) {
+                        error!(?e, "Failed to send store credit result");
+                    }
+                }
+                SyncMessage::UpdateSlotRequest(request) => {
+                    // The connection's device ID, not the one in the message
+                    let result = self.grant_update_slot(&device_id, request);
+                    let answer = SyncMessage::UpdateSlotResult(result);
+                    if let Err(e) = self.hub.send_to(&device_id, answer).await {
+                        warn!(device_id = %device_id, ?e, "Failed to send update slot result");
                     }
                 }
+                SyncMessage::SaleLookup(request) => {
+                    // The connection's device ID, not the one in the message
+                    self.start_sale_lookup(device_id, request);
+                }
+                SyncMessage::LoyaltyRedeem(request) => {
+                    // The connection's device ID, not the one in the message
+                    self.start_loyalty_redemption(device_id, request);
+                }
+                SyncMessage::CouponRedeem(request) => {
+                    // The connection's device ID, not the one in the message
+                    let result = self.redeem_coupon(&device_id, &request).await;
+                    if let Err(e) = self.hub.send_to(&device_id, SyncMessage::CouponRedeemResult(result)).await {
+                        warn!(device_id = %device_id, ?e, "Failed to send coupon redemption result");
+                    }
+                }
+                SyncMessage::UpdateFinished { version, success } => {
+                    let released = self.updates.finish(&device_id);
+                    info!(
+                        device_id = %device_id,
+                        version = %version,
+                        success,
+                        released,
+                        "Device finished updating"
+                    );
+                }
+                SyncMessage::DayEndReport(report) => match &self.day_end {
+                    // The connection's device ID, not the one in the report
+                    Some(board) => board.record(&device_id, report).await,
+                    None => info!(
+                        device_id = %device_id,
+                        outcome = ?report.outcome(),
+                        "Day end reported (no admin API)"
+                    ),
+                },
                 other => {
                     debug!(?other, "Ignoring non-delta message");
                 }
@@ -432,6 +651,909 @@ impl DeltaProcessor {
 
         info!("Delta processor stopped");
     }
+
+    /// Validates, applies and relays a terminal's outbox batch, and says
+    /// which entries the terminal can let go of.
+    ///
+    /// Cloud-bound entries are only acked once uploaded: until then they
+    /// sit in the hub's upload queue and are reported held (see
+    /// `hub_uplink`), and once uploaded they are reported as such. Without
+    /// a database they are not acked at all, so the terminal keeps sending
+    /// them.
+    async fn process_batch(&self, device_id: &str, batch: OutboxBatch) -> BatchAck {
+        let mut ack = BatchAck {
+            acked_ids: Vec::new(),
+            failed_ids: Vec::new(),
+            held_ids: Vec::new(),
+            held_by: String::new(),
+            uploaded_ids: Vec::new(),
+            new_cursor: 0,
+        };
+
+        for entity in batch.entities {
+            let span = info_span!(
+                "hub_entry",
+                device_id = %device_id,
+                entity_type = %entity.entity_type,
+                entity_id = %entity.entity_id
+            );
+            telemetry::set_parent(&span, entity.trace_context.as_deref());
+            self.process_entry(device_id, entity, &mut ack)
+                .instrument(span)
+                .await;
+        }
+
+        if !ack.held_ids.is_empty() {
+            ack.held_by = self.hub.device_id().to_string();
+        }
+        ack
+    }
+
+    /// Handles one entry of a batch, recording the outcome in `ack`.
+    async fn process_entry(&self, device_id: &str, entity: OutboxEntry, ack: &mut BatchAck) {
+        debug!(
+            device_id = %device_id,
+            entity_type = %entity.entity_type,
+            entity_id = %entity.entity_id,
+            correlation_id = entity.correlation_id.as_deref(),
+            "Received outbox entry"
+        );
+        if entity.entity_type != "InventoryDelta"
+            && !self.validate_entry(device_id, &entity).await
+        {
+            ack.failed_ids.push(FailedEntry {
+                id: entity.id.clone(),
+                error: "Invalid payload, quarantined on the hub".to_string(),
+                retryable: false,
+            });
+            return;
+        }
+        // Applied and relayed opened; queued for the cloud as it came
+        let opened = match self.open_payload(&entity) {
+            Ok(opened) => opened,
+            Err(e) => {
+                warn!(entity_id = %entity.entity_id, error = %e, "Cannot open outbox payload");
+                ack.failed_ids.push(FailedEntry {
+                    id: entity.id.clone(),
+                    error: e.to_string(),
+                    retryable: true,
+                });
+                return;
+            }
+        };
+        if opened.entity_type == "STORE_CREDIT" {
+            self.apply_store_credit(&opened).await;
+        }
+        if opened.entity_type == "COUPON" {
+            self.apply_coupon(&opened).await;
+        }
+        if opened.entity_type == "LOYALTY" {
+            self.apply_loyalty(&opened).await;
+        }
+        if opened.entity_type == "TOMBSTONE" {
+            self.apply_tombstone(&opened).await;
+        }
+        if opened.entity_type == "CUSTOMER_ERASURE" {
+            self.apply_erasure(&opened).await;
+        }
+        if opened.entity_type == "SALE" {
+            self.apply_sale(&opened).await;
+        }
+        if let Some(update) = relay_update(&opened) {
+            debug!(
+                entity_type = %opened.entity_type,
+                entity_id = %opened.entity_id,
+                "Relaying shared document"
+            );
+            if let Err(e) = self.hub.broadcast(SyncMessage::EntityUpdate(update)) {
+                error!(?e, "Failed to relay entity update");
+            }
+        } else if opened.entity_type == "InventoryDelta" {
+            if let Ok(delta) = serde_json::from_str::<InventoryDelta>(&opened.payload) {
+                if let Err(e) = self.aggregator.process_delta(device_id.to_string(), delta).await {
+                    error!(?e, "Failed to process delta from batch");
+                }
+            }
+        }
+
+        if !CLOUD_ENTITY_TYPES.contains(&entity.entity_type.as_str()) {
+            ack.acked_ids.push(entity.id);
+            return;
+        }
+        let Some(db) = &self.db else {
+            return;
+        };
+        match db.hub_upload_queue().accept(&queued_upload(device_id, &entity)).await {
+            Ok(true) => ack.uploaded_ids.push(entity.id),
+            Ok(false) => ack.held_ids.push(entity.id),
+            Err(e) => {
+                error!(entity_id = %entity.entity_id, ?e, "Failed to queue entry for upload");
+                ack.failed_ids.push(FailedEntry {
+                    id: entity.id,
+                    error: e.to_string(),
+                    retryable: true,
+                });
+            }
+        }
+    }
+
+    /// The entry with the sealed fields of its payload opened (see
+    /// `titan_db::payload_crypto`). Fails if they were sealed with a key
+    /// this hub was not given.
+    fn open_payload(&self, entity: &OutboxEntry) -> Result<OutboxEntry, titan_db::DbError> {
+        let payload = match &self.db {
+            Some(db) => db.payload_cipher().open(&entity.entity_type, &entity.payload)?,
+            None => PayloadCipher::disabled().open(&entity.entity_type, &entity.payload)?,
+        };
+        Ok(OutboxEntry {
+            payload,
+            ..entity.clone()
+        })
+    }
+
+    /// Streams the catalog to one terminal in the background.
+    fn start_resync(&self, device_id: String) {
+        let hub = self.hub.clone();
+        let Some(db) = self.db.clone() else {
+            warn!(device_id = %device_id, "Resync requested but the hub has no database");
+            tokio::spawn(async move {
+                let done = SyncMessage::ResyncComplete { products: 0 };
+                if let Err(e) = hub.send_to(&device_id, done).await {
+                    warn!(?e, "Failed to answer resync request");
+                }
+            });
+            return;
+        };
+
+        tokio::spawn(async move {
+            info!(device_id = %device_id, "Starting full resync");
+            match stream_catalog(&hub, &db, &device_id).await {
+                Ok(products) => info!(device_id = %device_id, products, "Full resync sent"),
+                Err(e) => error!(device_id = %device_id, ?e, "Full resync failed"),
+            }
+        });
+    }
+
+    /// Answers a sale lookup on its own task, since it may wait on the
+    /// cloud.
+    fn start_sale_lookup(&self, device_id: String, request: SaleLookupRequest) {
+        let hub = self.hub.clone();
+        let db = self.db.clone();
+        let cloud = self.cloud.clone();
+
+        tokio::spawn(async move {
+            let mut result = SaleLookupResult {
+                request_id: request.request_id,
+                device_id: device_id.clone(),
+                sale: None,
+                from_cloud: false,
+                error: None,
+            };
+            match lookup_sale(db.as_deref(), cloud, &request.receipt_number).await {
+                Ok((sale, from_cloud)) => {
+                    result.sale = sale;
+                    result.from_cloud = from_cloud;
+                }
+                Err(e) => {
+                    warn!(device_id = %device_id, receipt_number = %request.receipt_number, ?e, "Sale lookup failed");
+                    result.error = Some(e.to_string());
+                }
+            }
+            info!(
+                device_id = %device_id,
+                receipt_number = %request.receipt_number,
+                found = result.sale.is_some(),
+                from_cloud = result.from_cloud,
+                "Sale lookup answered"
+            );
+            if let Err(e) = hub.send_to(&device_id, SyncMessage::SaleLookupResult(result)).await {
+                warn!(device_id = %device_id, ?e, "Failed to send sale lookup result");
+            }
+        });
+    }
+
+    /// Answers a loyalty redemption on its own task, since it may wait on
+    /// the cloud.
+    ///
+    /// On approval the updated account is broadcast so every terminal sees
+    /// the new balance.
+    fn start_loyalty_redemption(&self, device_id: String, request: LoyaltyRedeemRequest) {
+        let hub = self.hub.clone();
+        let db = self.db.clone();
+        let cloud = self.cloud.clone();
+
+        tokio::spawn(async move {
+            let mut result = LoyaltyRedeemResult {
+                request_id: request.request_id.clone(),
+                device_id: device_id.clone(),
+                approved: false,
+                balance_cents: 0,
+                reason: None,
+            };
+            let entry = LoyaltyEntry {
+                id: request.entry_id.clone(),
+                account_id: request.account_id.clone(),
+                kind: LoyaltyEntryKind::Redeem,
+                points: 0,
+                reward_cents: -request.amount_cents,
+                sale_id: request.sale_id.clone(),
+                device_id: device_id.clone(),
+                created_at: chrono::Utc::now(),
+            };
+
+            match db.as_deref() {
+                None => result.reason = Some("hub cannot verify loyalty rewards".to_string()),
+                Some(_) if request.amount_cents <= 0 => {
+                    result.reason = Some("amount must be positive".to_string());
+                }
+                Some(db) => match redeem_loyalty(db, cloud, &entry).await {
+                    Ok(Some((approved, balance_cents))) => {
+                        result.approved = approved;
+                        result.balance_cents = balance_cents;
+                        if !approved {
+                            result.reason = Some("insufficient rewards".to_string());
+                        }
+                    }
+                    Ok(None) => result.reason = Some("unknown loyalty account".to_string()),
+                    Err(e) => {
+                        warn!(device_id = %device_id, account_id = %request.account_id, ?e, "Loyalty redemption failed");
+                        result.reason = Some(format!("rewards could not be verified: {}", e));
+                    }
+                },
+            }
+            info!(
+                device_id = %device_id,
+                account_id = %request.account_id,
+                amount = request.amount_cents,
+                approved = result.approved,
+                "Loyalty redemption answered"
+            );
+
+            if result.approved {
+                if let Some(db) = db.as_deref() {
+                    broadcast_loyalty(&hub, db, &request.account_id).await;
+                }
+            }
+            if let Err(e) = hub.send_to(&device_id, SyncMessage::LoyaltyRedeemResult(result)).await {
+                warn!(device_id = %device_id, ?e, "Failed to send loyalty redemption result");
+            }
+        });
+    }
+
+    /// Checks an outbox entry against its payload schema.
+    ///
+    /// Invalid entries are neither applied nor relayed; with a database
+    /// attached they are quarantined along with the sending device.
+    async fn validate_entry(&self, device_id: &str, entity: &OutboxEntry) -> bool {
+        let Err(e) = SyncPayload::parse(
+            &entity.entity_type,
+            &entity.entity_id,
+            entity.schema_version,
+            &entity.payload,
+        ) else {
+            return true;
+        };
+
+        warn!(
+            device_id,
+            entity_type = %entity.entity_type,
+            entity_id = %entity.entity_id,
+            error = %e,
+            "Quarantining invalid payload"
+        );
+        if let Some(db) = &self.db {
+            if let Err(e) = db
+                .sync_outbox()
+                .quarantine_received(
+                    device_id,
+                    &entity.entity_type,
+                    &entity.entity_id,
+                    &entity.payload,
+                    entity.schema_version,
+                    &e.to_string(),
+                )
+                .await
+            {
+                error!(?e, "Failed to quarantine payload");
+            }
+        }
+        false
+    }
+
+    /// Merges a store credit document from a terminal into the hub's ledger.
+    async fn apply_store_credit(&self, entity: &OutboxEntry) {
+        let Some(db) = &self.db else {
+            return;
+        };
+        match serde_json::from_str::<StoreCreditDocument>(&entity.payload) {
+            Ok(doc) => {
+                if let Err(e) = db.store_credits().upsert_from_sync(&doc).await {
+                    error!(entity_id = %entity.entity_id, ?e, "Failed to merge store credit");
+                }
+            }
+            Err(e) => warn!(entity_id = %entity.entity_id, ?e, "Invalid store credit payload"),
+        }
+    }
+
+    /// Merges a coupon's redemptions from a terminal into the hub's copy,
+    /// so the hub counts the uses of every register in the store.
+    async fn apply_coupon(&self, entity: &OutboxEntry) {
+        let Some(db) = &self.db else {
+            return;
+        };
+        match serde_json::from_str::<CouponDocument>(&entity.payload) {
+            Ok(doc) => {
+                if let Err(e) = db.coupons().upsert_from_sync(&doc).await {
+                    error!(entity_id = %entity.entity_id, ?e, "Failed to merge coupon");
+                }
+            }
+            Err(e) => warn!(entity_id = %entity.entity_id, ?e, "Invalid coupon payload"),
+        }
+    }
+
+    /// Merges a loyalty account from a terminal into the hub's ledger.
+    async fn apply_loyalty(&self, entity: &OutboxEntry) {
+        let Some(db) = &self.db else {
+            return;
+        };
+        match serde_json::from_str::<LoyaltyDocument>(&entity.payload) {
+            Ok(doc) => {
+                if let Err(e) = db.loyalty().upsert_from_sync(&doc).await {
+                    error!(entity_id = %entity.entity_id, ?e, "Failed to merge loyalty account");
+                }
+            }
+            Err(e) => warn!(entity_id = %entity.entity_id, ?e, "Invalid loyalty payload"),
+        }
+    }
+
+    /// Stores a sale rung up on a terminal in the hub's database, so the
+    /// hub can look it up and upload it with its lines. Sales sent
+    /// without their lines (older terminals) are left to the terminal.
+    async fn apply_sale(&self, entity: &OutboxEntry) {
+        let Some(db) = &self.db else {
+            return;
+        };
+        match serde_json::from_str::<SaleDocument>(&entity.payload) {
+            Ok(doc) if doc.items.is_empty() => {}
+            Ok(doc) => {
+                if let Err(e) = db.sales().upsert_from_sync(&doc).await {
+                    error!(entity_id = %entity.entity_id, ?e, "Failed to store relayed sale");
+                }
+            }
+            Err(e) => warn!(entity_id = %entity.entity_id, ?e, "Invalid sale payload"),
+        }
+    }
+
+    /// Applies a soft delete or restore to the hub's copy, so a later
+    /// resync does not bring a deleted entity back.
+    async fn apply_tombstone(&self, entity: &OutboxEntry) {
+        let Some(db) = &self.db else {
+            return;
+        };
+        let tombstone = match serde_json::from_str::<Tombstone>(&entity.payload) {
+            Ok(tombstone) => tombstone,
+            Err(e) => {
+                warn!(entity_id = %entity.entity_id, ?e, "Invalid tombstone payload");
+                return;
+            }
+        };
+        let result = match tombstone.entity_type.as_str() {
+            "product" => db.products().apply_tombstone(&tombstone).await,
+            "business_customer" => db.business_customers().apply_tombstone(&tombstone).await,
+            _ => Ok(false),
+        };
+        if let Err(e) = result {
+            error!(entity_id = %entity.entity_id, ?e, "Failed to apply tombstone");
+        }
+    }
+
+    /// Erases the customer from the hub's copy before the request is
+    /// relayed to the terminals.
+    async fn apply_erasure(&self, entity: &OutboxEntry) {
+        let Some(db) = &self.db else {
+            return;
+        };
+        match serde_json::from_str::<ErasureRequest>(&entity.payload) {
+            Ok(request) => {
+                if let Err(e) = db.erasures().apply(&request, ErasureOrigin::Sync).await {
+                    error!(entity_id = %entity.entity_id, ?e, "Failed to apply customer erasure");
+                }
+            }
+            Err(e) => warn!(entity_id = %entity.entity_id, ?e, "Invalid customer erasure payload"),
+        }
+    }
+
+    /// Answers a device's update slot request.
+    fn grant_update_slot(
+        &mut self,
+        device_id: &str,
+        request: UpdateSlotRequest,
+    ) -> UpdateSlotResult {
+        let decision = self.updates.request(device_id, Instant::now().into_std());
+        info!(device_id = %device_id, version = %request.version, ?decision, "Update slot requested");
+
+        let (granted, queue_position) = match decision {
+            SlotDecision::Granted => (true, 0),
+            SlotDecision::Wait { position } => (false, position),
+        };
+        UpdateSlotResult {
+            request_id: request.request_id,
+            device_id: device_id.to_string(),
+            granted,
+            queue_position,
+            retry_after_secs: if granted { 0 } else { UPDATE_RETRY_SECS },
+        }
+    }
+
+    /// Checks and writes a redemption against the hub's ledger.
+    ///
+    /// On approval the updated account is broadcast so every terminal sees
+    /// the new balance.
+    async fn redeem_store_credit(&self, request: &StoreCreditRedeemRequest) -> StoreCreditRedeemResult {
+        let reject = |balance_cents: i64, reason: &str| StoreCreditRedeemResult {
+            request_id: request.request_id.clone(),
+            device_id: request.device_id.clone(),
+            approved: false,
+            balance_cents,
+            reason: Some(reason.to_string()),
+        };
+
+        let Some(db) = &self.db else {
+            return reject(0, "hub cannot verify store credit");
+        };
+        if request.amount_cents <= 0 {
+            return reject(0, "amount must be positive");
+        }
+
+        let entry = StoreCreditEntry {
+            id: request.entry_id.clone(),
+            account_id: request.account_id.clone(),
+            kind: StoreCreditEntryKind::Redeem,
+            amount_cents: -request.amount_cents,
+            sale_id: request.sale_id.clone(),
+            device_id: request.device_id.clone(),
+            created_at: chrono::Utc::now(),
+        };
+
+        let redemption = match db.store_credits().redeem(&entry).await {
+            Ok(redemption) => redemption,
+            Err(titan_db::DbError::NotFound { .. }) => return reject(0, "unknown store credit"),
+            Err(e) => {
+                error!(account_id = %request.account_id, ?e, "Store credit redemption failed");
+                return reject(0, "hub database error");
+            }
+        };
+
+        if !redemption.applied {
+            return reject(redemption.balance_cents, "insufficient balance");
+        }
+
+        match db.store_credits().get_document(&request.account_id).await {
+            Ok(Some(doc)) => match serde_json::to_value(&doc) {
+                Ok(data) => {
+                    let update = EntityUpdate {
+                        entity_type: "store_credit".to_string(),
+                        entity_id: doc.account.id.clone(),
+                        operation: "upsert".to_string(),
+                        version: doc.account.sync_version,
+                        updated_at: doc.account.updated_at.to_rfc3339(),
+                        data,
+                    };
+                    if let Err(e) = self.hub.broadcast(SyncMessage::EntityUpdate(update)) {
+                        error!(?e, "Failed to broadcast store credit");
+                    }
+                }
+                Err(e) => error!(?e, "Failed to serialize store credit"),
+            },
+            Ok(None) => {}
+            Err(e) => error!(?e, "Failed to load store credit after redemption"),
+        }
+
+        StoreCreditRedeemResult {
+            request_id: request.request_id.clone(),
+            device_id: request.device_id.clone(),
+            approved: true,
+            balance_cents: redemption.balance_cents,
+            reason: None,
+        }
+    }
+
+    /// Checks and records a coupon use against the hub's redemptions.
+    ///
+    /// On approval the updated coupon is broadcast so every terminal counts
+    /// the new use.
+    async fn redeem_coupon(&self, device_id: &str, request: &CouponRedeemRequest) -> CouponRedeemResult {
+        let reject = |uses: i64, reason: &str| CouponRedeemResult {
+            request_id: request.request_id.clone(),
+            device_id: device_id.to_string(),
+            approved: false,
+            uses,
+            reason: Some(reason.to_string()),
+        };
+
+        let Some(db) = &self.db else {
+            return reject(0, "hub cannot verify coupons");
+        };
+        let Ok(redeemed_at) = chrono::DateTime::parse_from_rfc3339(&request.timestamp) else {
+            return reject(0, "invalid timestamp");
+        };
+
+        // The definition only replaces the hub's copy if it is newer
+        let doc = CouponDocument {
+            coupon: request.coupon.clone(),
+            redemptions: Vec::new(),
+        };
+        if let Err(e) = db.coupons().upsert_from_sync(&doc).await {
+            error!(coupon_id = %request.coupon.id, ?e, "Failed to store coupon before redemption");
+            return reject(0, "hub database error");
+        }
+
+        let redemption = CouponRedemption {
+            id: request.redemption_id.clone(),
+            coupon_id: request.coupon.id.clone(),
+            sale_id: request.sale_id.clone(),
+            store_id: String::new(),
+            device_id: device_id.to_string(),
+            discount_cents: request.discount_cents,
+            redeemed_at: redeemed_at.with_timezone(&chrono::Utc),
+        };
+        let used = match db.coupons().redeem(&redemption).await {
+            Ok(used) => used,
+            Err(e) => {
+                error!(coupon_id = %request.coupon.id, ?e, "Coupon redemption failed");
+                return reject(0, "hub database error");
+            }
+        };
+        info!(
+            device_id = %device_id,
+            code = %request.coupon.code,
+            approved = used.applied,
+            uses = used.uses,
+            "Coupon redemption answered"
+        );
+
+        if let Some(reason) = used.rejection {
+            return reject(used.uses, &reason);
+        }
+
+        match db.coupons().get_document(&request.coupon.id).await {
+            Ok(Some(doc)) => match serde_json::to_value(&doc) {
+                Ok(data) => {
+                    let update = EntityUpdate {
+                        entity_type: "coupon".to_string(),
+                        entity_id: doc.coupon.id.clone(),
+                        operation: "upsert".to_string(),
+                        version: doc.coupon.sync_version,
+                        updated_at: doc.coupon.updated_at.to_rfc3339(),
+                        data,
+                    };
+                    if let Err(e) = self.hub.broadcast(SyncMessage::EntityUpdate(update)) {
+                        error!(?e, "Failed to broadcast coupon");
+                    }
+                }
+                Err(e) => error!(?e, "Failed to serialize coupon"),
+            },
+            Ok(None) => {}
+            Err(e) => error!(?e, "Failed to load coupon after redemption"),
+        }
+
+        CouponRedeemResult {
+            request_id: request.request_id.clone(),
+            device_id: device_id.to_string(),
+            approved: true,
+            uses: used.uses,
+            reason: None,
+        }
+    }
+}
+
+/// Finds a sale by receipt number in the hub's database, then in the
+/// cloud.
+///
+/// Returns the sale, if found, and whether it came from the cloud.
+async fn lookup_sale(
+    db: Option<&Database>,
+    cloud: Option<CloudUplinkConfig>,
+    receipt_number: &str,
+) -> SyncResult<(Option<SaleDocument>, bool)> {
+    if let Some(db) = db {
+        if let Some(sale) = db.sales().get_document_by_receipt(receipt_number).await? {
+            return Ok((Some(sale), false));
+        }
+    }
+
+    let Some(cloud) = cloud else {
+        return Ok((None, false));
+    };
+    let mut uplink = CloudUplink::new(cloud)?;
+    uplink.connect().await?;
+    let sale = uplink.get_sale(receipt_number).await?;
+    Ok((sale, true))
+}
+
+/// Checks and writes a loyalty redemption: with the cloud when cloud
+/// settings are given, against the hub's ledger otherwise. A redemption
+/// the cloud approves is merged into the hub's ledger.
+///
+/// Returns whether it was approved and the rewards balance, or `None` if
+/// the account is unknown.
+async fn redeem_loyalty(
+    db: &Database,
+    cloud: Option<CloudUplinkConfig>,
+    entry: &LoyaltyEntry,
+) -> SyncResult<Option<(bool, i64)>> {
+    let Some(cloud) = cloud else {
+        return match db.loyalty().redeem(entry).await {
+            Ok(redemption) => Ok(Some((redemption.applied, redemption.balance_cents))),
+            Err(titan_db::DbError::NotFound { .. }) => Ok(None),
+            Err(e) => Err(e.into()),
+        };
+    };
+
+    let Some(mut doc) = db.loyalty().get_document(&entry.account_id).await? else {
+        return Ok(None);
+    };
+    let mut uplink = CloudUplink::new(cloud)?;
+    uplink.connect().await?;
+    let (approved, balance_cents) = uplink.redeem_loyalty_reward(&doc, entry).await?;
+
+    if approved {
+        doc.entries.push(entry.clone());
+        db.loyalty().upsert_from_sync(&doc).await?;
+    }
+    Ok(Some((approved, balance_cents)))
+}
+
+/// Broadcasts a loyalty account to every terminal.
+async fn broadcast_loyalty(hub: &HubHandle, db: &Database, account_id: &str) {
+    match db.loyalty().get_document(account_id).await {
+        Ok(Some(doc)) => match serde_json::to_value(&doc) {
+            Ok(data) => {
+                let update = EntityUpdate {
+                    entity_type: "loyalty".to_string(),
+                    entity_id: doc.account.id.clone(),
+                    operation: "upsert".to_string(),
+                    version: doc.account.sync_version,
+                    updated_at: doc.account.updated_at.to_rfc3339(),
+                    data,
+                };
+                if let Err(e) = hub.broadcast(SyncMessage::EntityUpdate(update)) {
+                    error!(?e, "Failed to broadcast loyalty account");
+                }
+            }
+            Err(e) => error!(?e, "Failed to serialize loyalty account"),
+        },
+        Ok(None) => {}
+        Err(e) => error!(?e, "Failed to load loyalty account after redemption"),
+    }
+}
+
+/// Sends every product to `device_id` as a snapshot, each followed by its
+/// attribute set, bill of materials and packs when it has them, then
+/// every product style and supplier, then `ResyncComplete`.
+///
+/// Returns the number of products sent.
+async fn stream_catalog(hub: &HubHandle, db: &Database, device_id: &str) -> SyncResult<u64> {
+    let mut sent = 0u64;
+    let mut after = String::new();
+
+    loop {
+        let page = db.products().list_after(&after, RESYNC_PAGE_SIZE).await?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        after = last.id.clone();
+
+        for product in &page {
+            let update = EntityUpdate {
+                entity_type: "product".to_string(),
+                entity_id: product.id.clone(),
+                operation: "snapshot".to_string(),
+                version: product.sync_version,
+                updated_at: product.updated_at.to_rfc3339(),
+                data: serde_json::to_value(product)?,
+            };
+            hub.send_to(device_id, SyncMessage::EntityUpdate(update)).await?;
+            sent += 1;
+
+            let attributes = db.products().attributes(&product.id).await?;
+            if attributes.sync_version > 0 {
+                let update = EntityUpdate {
+                    entity_type: "product_attributes".to_string(),
+                    entity_id: product.id.clone(),
+                    operation: "upsert".to_string(),
+                    version: attributes.sync_version,
+                    updated_at: attributes.updated_at.to_rfc3339(),
+                    data: serde_json::to_value(&attributes)?,
+                };
+                hub.send_to(device_id, SyncMessage::EntityUpdate(update)).await?;
+            }
+
+            let bundle = db.bundles().get(&product.id).await?;
+            if bundle.sync_version > 0 {
+                let update = EntityUpdate {
+                    entity_type: "product_bundle".to_string(),
+                    entity_id: product.id.clone(),
+                    operation: "upsert".to_string(),
+                    version: bundle.sync_version,
+                    updated_at: bundle.updated_at.to_rfc3339(),
+                    data: serde_json::to_value(&bundle)?,
+                };
+                hub.send_to(device_id, SyncMessage::EntityUpdate(update)).await?;
+            }
+
+            let packs = db.packs().get(&product.id).await?;
+            if packs.sync_version > 0 {
+                let update = EntityUpdate {
+                    entity_type: "product_packs".to_string(),
+                    entity_id: product.id.clone(),
+                    operation: "upsert".to_string(),
+                    version: packs.sync_version,
+                    updated_at: packs.updated_at.to_rfc3339(),
+                    data: serde_json::to_value(&packs)?,
+                };
+                hub.send_to(device_id, SyncMessage::EntityUpdate(update)).await?;
+            }
+        }
+    }
+
+    // Styles go last so the variants they list are already there
+    let mut after = String::new();
+    loop {
+        let page = db.styles().list_after(&after, RESYNC_PAGE_SIZE).await?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        after = last.style.id.clone();
+
+        for doc in &page {
+            let update = EntityUpdate {
+                entity_type: "product_style".to_string(),
+                entity_id: doc.style.id.clone(),
+                operation: "upsert".to_string(),
+                version: doc.style.sync_version,
+                updated_at: doc.style.updated_at.to_rfc3339(),
+                data: serde_json::to_value(doc)?,
+            };
+            hub.send_to(device_id, SyncMessage::EntityUpdate(update)).await?;
+        }
+    }
+
+    // Suppliers after products, so their price lists resolve
+    let mut after = String::new();
+    loop {
+        let page = db.suppliers().list_after(&after, RESYNC_PAGE_SIZE).await?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        after = last.supplier.id.clone();
+
+        for doc in &page {
+            let update = EntityUpdate {
+                entity_type: "supplier".to_string(),
+                entity_id: doc.supplier.id.clone(),
+                operation: "upsert".to_string(),
+                version: doc.supplier.sync_version,
+                updated_at: doc.supplier.updated_at.to_rfc3339(),
+                data: serde_json::to_value(doc)?,
+            };
+            hub.send_to(device_id, SyncMessage::EntityUpdate(update)).await?;
+        }
+    }
+
+    hub.send_to(device_id, SyncMessage::ResyncComplete { products: sent })
+        .await?;
+    Ok(sent)
+}
+
+/// The upload queue row for an entry from `device_id`.
+fn queued_upload(device_id: &str, entry: &OutboxEntry) -> HubUpload {
+    HubUpload {
+        device_id: device_id.to_string(),
+        entry_id: entry.id.clone(),
+        entity_type: entry.entity_type.clone(),
+        entity_id: entry.entity_id.clone(),
+        payload: entry.payload.clone(),
+        schema_version: i64::from(entry.schema_version),
+        correlation_id: entry.correlation_id.clone(),
+        trace_context: entry.trace_context.clone(),
+        received_at: chrono::Utc::now(),
+        attempts: 0,
+        last_error: None,
+    }
+}
+
+/// Converts an outbox entry for a shared document into an upsert, a
+/// product field patch into a patch, or a tombstone into a delete or
+/// restore.
+///
+/// Completed sales are relayed only when they carry their lines; a sale
+/// from an older terminal is just the header, which is no use for a
+/// reprint or refund.
+///
+/// Returns `None` for entity types that are not relayed or payloads that
+/// are not valid JSON. The version comes from the payload's `sync_version`
+/// (for patches, the version after the patch's base).
+fn relay_update(entry: &OutboxEntry) -> Option<EntityUpdate> {
+    if entry.entity_type == "PRODUCT_PATCH" {
+        return relay_patch(entry, "product");
+    }
+    if entry.entity_type == "TOMBSTONE" {
+        return relay_tombstone(entry);
+    }
+
+    let is_sale = entry.entity_type == "SALE";
+    if !is_sale && !RELAYED_ENTITY_TYPES.contains(&entry.entity_type.as_str()) {
+        return None;
+    }
+
+    let data: serde_json::Value = match serde_json::from_str(&entry.payload) {
+        Ok(data) => data,
+        Err(e) => {
+            warn!(entity_id = %entry.entity_id, ?e, "Invalid relayed payload");
+            return None;
+        }
+    };
+    let has_lines = data
+        .get("items")
+        .and_then(|items| items.as_array())
+        .is_some_and(|items| !items.is_empty());
+    if is_sale && !has_lines {
+        return None;
+    }
+
+    Some(EntityUpdate {
+        entity_type: entry.entity_type.to_lowercase(),
+        entity_id: entry.entity_id.clone(),
+        operation: "upsert".to_string(),
+        version: data.get("sync_version").and_then(|v| v.as_i64()).unwrap_or(0),
+        updated_at: data
+            .get("updated_at")
+            .and_then(|v| v.as_str())
+            .unwrap_or(&entry.created_at)
+            .to_string(),
+        data,
+    })
+}
+
+/// Converts a queued field patch into a patch update for `entity_type`.
+fn relay_patch(entry: &OutboxEntry, entity_type: &str) -> Option<EntityUpdate> {
+    let patch: EntityPatch = match serde_json::from_str(&entry.payload) {
+        Ok(patch) => patch,
+        Err(e) => {
+            warn!(entity_id = %entry.entity_id, ?e, "Invalid relayed patch");
+            return None;
+        }
+    };
+
+    Some(EntityUpdate {
+        entity_type: entity_type.to_string(),
+        entity_id: entry.entity_id.clone(),
+        operation: "patch".to_string(),
+        version: patch.base_version + 1,
+        updated_at: patch.updated_at.to_rfc3339(),
+        data: serde_json::to_value(&patch).ok()?,
+    })
+}
+
+/// Converts a queued tombstone into a delete or restore of its entity.
+fn relay_tombstone(entry: &OutboxEntry) -> Option<EntityUpdate> {
+    let tombstone: Tombstone = match serde_json::from_str(&entry.payload) {
+        Ok(tombstone) => tombstone,
+        Err(e) => {
+            warn!(entity_id = %entry.entity_id, ?e, "Invalid relayed tombstone");
+            return None;
+        }
+    };
+
+    Some(EntityUpdate {
+        entity_type: tombstone.entity_type.clone(),
+        entity_id: tombstone.entity_id.clone(),
+        operation: tombstone.action.operation().to_string(),
+        version: tombstone.version,
+        updated_at: tombstone.at.to_rfc3339(),
+        data: serde_json::to_value(&tombstone).ok()?,
+    })
 }
 
 #[cfg(test)]
@@ -463,4 +1585,156 @@ mod tests {
         assert_eq!(config.mode, BroadcastMode::Coalesced);
         assert_eq!(config.coalesce_window, Duration::from_millis(100));
     }
+
+    fn outbox_entry(entity_type: &str, payload: &str) -> OutboxEntry {
+        OutboxEntry {
+            id: "e-1".to_string(),
+            entity_type: entity_type.to_string(),
+            entity_id: "q-1".to_string(),
+            payload: payload.to_string(),
+            schema_version: 1,
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            correlation_id: None,
+            trace_context: None,
+        }
+    }
+
+    #[test]
+    fn test_relay_update_for_shared_documents() {
+        let entry = outbox_entry(
+            "QUOTE",
+            r#"{"id":"q-1","sync_version":3,"updated_at":"2024-01-02T00:00:00Z","items":[]}"#,
+        );
+        let update = relay_update(&entry).unwrap();
+        assert_eq!(update.entity_type, "quote");
+        assert_eq!(update.operation, "upsert");
+        assert_eq!(update.version, 3);
+        assert_eq!(update.updated_at, "2024-01-02T00:00:00Z");
+
+        let entry = outbox_entry(
+            "PRODUCT_ATTRIBUTES",
+            r#"{"product_id":"q-1","attributes":[{"key":"color","value":"red"}],"sync_version":2,"updated_at":"2024-01-02T00:00:00Z"}"#,
+        );
+        let update = relay_update(&entry).unwrap();
+        assert_eq!(update.entity_type, "product_attributes");
+        assert_eq!(update.version, 2);
+
+        let entry = outbox_entry(
+            "PRODUCT_STYLE",
+            r#"{"id":"s-1","name":"Tee","option_keys":["size"],"sync_version":4,"updated_at":"2024-01-02T00:00:00Z","variant_ids":["p-1"]}"#,
+        );
+        let update = relay_update(&entry).unwrap();
+        assert_eq!(update.entity_type, "product_style");
+        assert_eq!(update.version, 4);
+
+        let entry = outbox_entry(
+            "PRODUCT_BUNDLE",
+            r#"{"product_id":"b-1","components":[{"product_id":"p-1","quantity":2}],"sync_version":3,"updated_at":"2024-01-02T00:00:00Z"}"#,
+        );
+        let update = relay_update(&entry).unwrap();
+        assert_eq!(update.entity_type, "product_bundle");
+        assert_eq!(update.version, 3);
+
+        let entry = outbox_entry(
+            "PRODUCT_PACKS",
+            r#"{"product_id":"p-1","packs":[{"name":"case","units":24}],"sync_version":2,"updated_at":"2024-01-02T00:00:00Z"}"#,
+        );
+        let update = relay_update(&entry).unwrap();
+        assert_eq!(update.entity_type, "product_packs");
+        assert_eq!(update.version, 2);
+
+        let entry = outbox_entry(
+            "SUPPLIER",
+            r#"{"id":"sup-1","name":"ACME","is_active":true,"sync_version":5,"updated_at":"2024-01-02T00:00:00Z","items":[]}"#,
+        );
+        let update = relay_update(&entry).unwrap();
+        assert_eq!(update.entity_type, "supplier");
+        assert_eq!(update.version, 5);
+
+        let entry = outbox_entry(
+            "COUPON",
+            r#"{"id":"cp-1","code":"SAVE10","kind":"percent","value":1000,"sync_version":4,"updated_at":"2024-01-02T00:00:00Z","redemptions":[]}"#,
+        );
+        let update = relay_update(&entry).unwrap();
+        assert_eq!(update.entity_type, "coupon");
+        assert_eq!(update.version, 4);
+
+        let entry = outbox_entry(
+            "LOYALTY",
+            r#"{"id":"la-1","tenant_id":"t-1","member_number":"M100","sync_version":6,"created_at":"2024-01-01T00:00:00Z","updated_at":"2024-01-02T00:00:00Z","entries":[]}"#,
+        );
+        let update = relay_update(&entry).unwrap();
+        assert_eq!(update.entity_type, "loyalty");
+        assert_eq!(update.version, 6);
+
+        assert!(relay_update(&outbox_entry("SALE", "{}")).is_none());
+        assert!(relay_update(&outbox_entry("QUOTE", "not json")).is_none());
+    }
+
+    #[test]
+    fn test_relay_update_for_completed_sale() {
+        let entry = outbox_entry(
+            "SALE",
+            r#"{"id":"s-1","receipt_number":"R-0001","sync_version":2,"updated_at":"2024-01-02T00:00:00Z","items":[{"id":"si-1","sale_id":"s-1"}],"payments":[]}"#,
+        );
+        let update = relay_update(&entry).unwrap();
+        assert_eq!(update.entity_type, "sale");
+        assert_eq!(update.operation, "upsert");
+        assert_eq!(update.version, 2);
+        assert_eq!(update.data["receipt_number"], "R-0001");
+
+        // Header-only sales from older terminals stay where they were rung up
+        let entry = outbox_entry(
+            "SALE",
+            r#"{"id":"s-2","sync_version":1,"updated_at":"2024-01-02T00:00:00Z"}"#,
+        );
+        assert!(relay_update(&entry).is_none());
+        let entry = outbox_entry("SALE", r#"{"id":"s-3","items":[]}"#);
+        assert!(relay_update(&entry).is_none());
+    }
+
+    #[test]
+    fn test_relay_update_for_product_patch() {
+        let entry = outbox_entry(
+            "PRODUCT_PATCH",
+            r#"{"base_version":7,"updated_at":"2024-01-02T00:00:00Z","fields":{"price_cents":299}}"#,
+        );
+        let update = relay_update(&entry).unwrap();
+        assert_eq!(update.entity_type, "product");
+        assert_eq!(update.operation, "patch");
+        assert_eq!(update.version, 8);
+        assert_eq!(update.data["fields"]["price_cents"], 299);
+
+        assert!(relay_update(&outbox_entry("PRODUCT_PATCH", "{}")).is_none());
+    }
+
+    #[test]
+    fn test_relay_update_for_tombstone() {
+        let entry = outbox_entry(
+            "TOMBSTONE",
+            r#"{"entity_type":"product","entity_id":"p-1","action":"restore","version":6,"at":"2024-01-02T00:00:00Z"}"#,
+        );
+        let update = relay_update(&entry).unwrap();
+        assert_eq!(update.entity_type, "product");
+        assert_eq!(update.entity_id, "p-1");
+        assert_eq!(update.operation, "restore");
+        assert_eq!(update.version, 6);
+
+        assert!(relay_update(&outbox_entry("TOMBSTONE", "{}")).is_none());
+    }
+
+    #[test]
+    fn test_queued_upload_keeps_the_sending_device() {
+        let mut entry = outbox_entry("SUPPLIER", r#"{"id":"sup-1"}"#);
+        entry.schema_version = 2;
+        entry.correlation_id = Some("corr-1".to_string());
+        entry.trace_context = Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".into());
+
+        let upload = queued_upload("pos-02", &entry);
+        assert_eq!((upload.device_id.as_str(), upload.entry_id.as_str()), ("pos-02", "e-1"));
+        assert_eq!(upload.schema_version, 2);
+        assert_eq!(upload.correlation_id.as_deref(), Some("corr-1"));
+        assert_eq!(upload.trace_context, entry.trace_context);
+        assert_eq!((upload.attempts, upload.last_error), (0, None));
+    }
 }
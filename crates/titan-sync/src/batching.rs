@@ -0,0 +1,266 @@
+//! # Adaptive Outbox Batching
+//!
+//! Sizes outbox batches by bytes instead of entry count. A hundred small
+//! till sessions fit in a few kilobytes, while a hundred sales with long
+//! line lists can run past the hub's message limit. Batches are filled up
+//! to a byte target, and the target follows how the hub is coping.
+//!
+//! ## Tuning
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Batch Size Tuning                               │
+//! │                                                                         │
+//! │  send batch (≤ target bytes, ≤ batch_size entries) ──► wait for ack    │
+//! │                                                                         │
+//! │  ack in < 1s, no failures        ──► target × 1.5 (up to max)          │
+//! │  ack in > 5s, or > 20% failed    ──► target ÷ 2   (down to 16 KiB)     │
+//! │  no ack within 30s               ──► target ÷ 2, batch sent again       │
+//! │  hub answers BUSY                ──► target ÷ 2, nothing sent for 10s  │
+//! │                                      (see `shedding`), then resent     │
+//! │  anything else                   ──► target unchanged                   │
+//! │                                                                         │
+//! │  The first entry always goes, so an entry larger than the target is   │
+//! │  sent on its own rather than never.                                     │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use titan_core::SyncOutboxEntry;
+
+use crate::shedding::BUSY_COOLDOWN;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Smallest byte target the tuner shrinks to.
+pub const MIN_BATCH_BYTES: usize = 16 * 1024;
+
+/// Envelope bytes per entry on top of its payload (IDs, type, timestamps).
+pub const ENTRY_OVERHEAD_BYTES: usize = 256;
+
+/// Acks faster than this grow the target.
+const FAST_ACK: Duration = Duration::from_secs(1);
+
+/// Acks slower than this shrink the target.
+const SLOW_ACK: Duration = Duration::from_secs(5);
+
+/// A batch unacknowledged for this long is treated as lost.
+pub const ACK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Share of failed entries in an ack that shrinks the target.
+const MAX_FAILURE_RATE: f64 = 0.2;
+
+// =============================================================================
+// Batch Tuner
+// =============================================================================
+
+/// A batch waiting for its ack.
+#[derive(Debug, Clone, Copy)]
+struct InFlight {
+    sent_at: Instant,
+}
+
+/// Adjusts the batch byte target from ack latency and failure rate.
+#[derive(Debug)]
+pub struct BatchTuner {
+    target: usize,
+    max: usize,
+    in_flight: Option<InFlight>,
+    /// Nothing is sent before this (after the hub said it is busy).
+    hold_until: Option<Instant>,
+}
+
+impl BatchTuner {
+    /// Creates a tuner capped at `max_bytes`, starting at a quarter of it.
+    pub fn new(max_bytes: usize) -> Self {
+        let max = max_bytes.max(MIN_BATCH_BYTES);
+        BatchTuner {
+            target: (max / 4).max(MIN_BATCH_BYTES),
+            max,
+            in_flight: None,
+            hold_until: None,
+        }
+    }
+
+    /// Current byte target.
+    pub fn target(&self) -> usize {
+        self.target
+    }
+
+    /// Whether a new batch may be sent at `now`: nothing is waiting for an
+    /// ack, or the last batch timed out (which shrinks the target).
+    pub fn ready(&mut self, now: Instant) -> bool {
+        if self.hold_until.is_some_and(|until| now < until) {
+            return false;
+        }
+        self.hold_until = None;
+
+        match self.in_flight {
+            None => true,
+            Some(batch) if now.duration_since(batch.sent_at) >= ACK_TIMEOUT => {
+                self.in_flight = None;
+                self.shrink();
+                true
+            }
+            Some(_) => false,
+        }
+    }
+
+    /// Records that a batch went out at `now`.
+    pub fn sent(&mut self, now: Instant) {
+        self.in_flight = Some(InFlight { sent_at: now });
+    }
+
+    /// Records an ack for the batch in flight.
+    pub fn acked(&mut self, acked: usize, failed: usize, now: Instant) {
+        let Some(batch) = self.in_flight.take() else {
+            return;
+        };
+
+        let latency = now.duration_since(batch.sent_at);
+        let total = acked + failed;
+        let failure_rate = if total == 0 {
+            0.0
+        } else {
+            failed as f64 / total as f64
+        };
+
+        if latency > SLOW_ACK || failure_rate > MAX_FAILURE_RATE {
+            self.shrink();
+        } else if latency < FAST_ACK && failed == 0 {
+            self.target = (self.target + self.target / 2).min(self.max);
+        }
+    }
+
+    /// Records that the hub shed load at `now`: the batch in flight was
+    /// dropped and will be resent once the cooldown is over.
+    pub fn busy(&mut self, now: Instant) {
+        self.in_flight = None;
+        self.hold_until = Some(now + BUSY_COOLDOWN);
+        self.shrink();
+    }
+
+    /// Forgets the batch in flight (e.g. after a reconnect).
+    pub fn reset(&mut self) {
+        self.in_flight = None;
+    }
+
+    fn shrink(&mut self) {
+        self.target = (self.target / 2).max(MIN_BATCH_BYTES);
+    }
+}
+
+// =============================================================================
+// Batch Selection
+// =============================================================================
+
+/// Bytes an entry adds to a batch.
+pub fn entry_bytes(entry: &SyncOutboxEntry) -> usize {
+    entry.payload.len() + ENTRY_OVERHEAD_BYTES
+}
+
+/// How many leading entries fit in `budget` bytes (at least one).
+pub fn fit(entries: &[SyncOutboxEntry], budget: usize) -> usize {
+    let mut used = 0;
+    for (i, entry) in entries.iter().enumerate() {
+        used += entry_bytes(entry);
+        if used > budget {
+            return i.max(1);
+        }
+    }
+    entries.len()
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn entry(payload_len: usize) -> SyncOutboxEntry {
+        SyncOutboxEntry {
+            id: "e".into(),
+            tenant_id: "t".into(),
+            entity_type: "SALE".into(),
+            entity_id: "s".into(),
+            payload: "x".repeat(payload_len),
+            attempts: 0,
+            last_error: None,
+            created_at: Utc::now(),
+            attempted_at: None,
+            synced_at: None,
+            correlation_id: None,
+            trace_context: None,
+        }
+    }
+
+    #[test]
+    fn test_fit_by_bytes() {
+        let small = vec![entry(744); 10]; // 1 KiB each with overhead
+        assert_eq!(fit(&small, 4 * 1024), 4);
+        assert_eq!(fit(&small, 64 * 1024), 10);
+
+        // An oversized entry still goes, alone
+        let big = vec![entry(100_000), entry(10)];
+        assert_eq!(fit(&big, MIN_BATCH_BYTES), 1);
+        assert_eq!(fit(&[], MIN_BATCH_BYTES), 0);
+    }
+
+    #[test]
+    fn test_tuner_grows_on_fast_acks_and_shrinks_on_trouble() {
+        let start = Instant::now();
+        let mut tuner = BatchTuner::new(256 * 1024);
+        assert_eq!(tuner.target(), 64 * 1024);
+
+        assert!(tuner.ready(start));
+        tuner.sent(start);
+        assert!(!tuner.ready(start + Duration::from_millis(100)));
+        tuner.acked(50, 0, start + Duration::from_millis(200));
+        assert_eq!(tuner.target(), 96 * 1024);
+
+        // Slow ack
+        tuner.sent(start);
+        tuner.acked(50, 0, start + Duration::from_secs(6));
+        assert_eq!(tuner.target(), 48 * 1024);
+
+        // Too many failures
+        tuner.sent(start);
+        tuner.acked(7, 3, start + Duration::from_millis(200));
+        assert_eq!(tuner.target(), 24 * 1024);
+
+        // Lost batch, and the floor
+        tuner.sent(start);
+        assert!(tuner.ready(start + ACK_TIMEOUT));
+        assert_eq!(tuner.target(), MIN_BATCH_BYTES);
+    }
+
+    #[test]
+    fn test_tuner_holds_off_when_hub_is_busy() {
+        let start = Instant::now();
+        let mut tuner = BatchTuner::new(256 * 1024);
+        tuner.sent(start);
+        tuner.busy(start + Duration::from_millis(50));
+
+        assert_eq!(tuner.target(), 32 * 1024);
+        assert!(!tuner.ready(start + Duration::from_secs(5)));
+        assert!(tuner.ready(start + BUSY_COOLDOWN + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_tuner_caps_at_max() {
+        let start = Instant::now();
+        let mut tuner = BatchTuner::new(64 * 1024);
+        for _ in 0..10 {
+            tuner.sent(start);
+            tuner.acked(1, 0, start);
+        }
+        assert_eq!(tuner.target(), 64 * 1024);
+    }
+}
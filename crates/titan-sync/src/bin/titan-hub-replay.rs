@@ -0,0 +1,155 @@
+//! # Hub Replay Tool
+//!
+//! Plays a hub recording (see `titan_sync::recorder`) back into a test hub
+//! and compares what that hub broadcasts with what the store's hub sent,
+//! to reproduce a sync problem away from the store.
+//!
+//! ## Replay
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        titan-hub-replay                                 │
+//! │                                                                         │
+//! │  <RECORDING> (+ <RECORDING>.1.jsonl) ──► inbound messages, in order     │
+//! │        │                                                                │
+//! │        ▼                                                                │
+//! │  ReplayHub on 127.0.0.1 over a copy of --db (or an empty database)      │
+//! │        │                                                                │
+//! │        ▼                                                                │
+//! │  message type   recorded out   replayed out                             │
+//! │  (broadcasts the store's hub sent vs. the ones the test hub sent)       │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Usage
+//! ```bash
+//! titan-hub-replay ./data/hub.jsonl --db ./backup/titan.db --speed 10
+//! titan-hub-replay ./data/hub.jsonl --speed 0
+//! ```
+//!
+//! The database given is copied first; the replay never writes to it.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use titan_db::{Database, DbConfig};
+use titan_sync::recorder::{load_recording, Direction, ReplayHub};
+
+/// Recorded gaps are replayed this many times faster unless `--speed`.
+const DEFAULT_SPEED: f64 = 1.0;
+
+/// How long to collect broadcasts after the last message.
+const SETTLE: Duration = Duration::from_millis(500);
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = env::args().collect();
+
+    let mut recording: Option<PathBuf> = None;
+    let mut db_path: Option<PathBuf> = None;
+    let mut speed = DEFAULT_SPEED;
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--db" | "-d" if i + 1 < args.len() => {
+                db_path = Some(PathBuf::from(&args[i + 1]));
+                i += 1;
+            }
+            "--speed" | "-s" if i + 1 < args.len() => {
+                speed = args[i + 1]
+                    .parse()
+                    .ok()
+                    .filter(|s: &f64| *s >= 0.0)
+                    .ok_or_else(|| format!("Invalid --speed: {}", args[i + 1]))?;
+                i += 1;
+            }
+            "--help" | "-h" => {
+                print_help();
+                return Ok(());
+            }
+            other => recording = Some(PathBuf::from(other)),
+        }
+        i += 1;
+    }
+
+    let Some(recording) = recording else {
+        print_help();
+        return Ok(());
+    };
+    let messages = load_recording(&recording)?;
+    println!(
+        "Loaded {} messages from {}",
+        messages.len(),
+        recording.display()
+    );
+
+    let db = match &db_path {
+        Some(path) => open_copy(path).await?,
+        None => Database::new(DbConfig::in_memory()).await?,
+    };
+    let hub = ReplayHub::start(Arc::new(db)).await?;
+    let mut broadcasts = hub.subscribe();
+
+    let report = hub.replay(&messages, speed).await?;
+    tokio::time::sleep(SETTLE).await;
+    hub.shutdown().await;
+
+    // Recorded outbound counts every terminal that got a broadcast, so
+    // compare distinct messages: the replay hub has no terminals
+    let mut counts: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    let mut seen = std::collections::HashSet::new();
+    for record in messages
+        .iter()
+        .filter(|r| r.direction == Direction::Outbound)
+    {
+        if seen.insert((record.elapsed_ms, record.message.to_string())) {
+            counts.entry(record.message_type.clone()).or_default().0 += 1;
+        }
+    }
+    while let Ok(msg) = broadcasts.try_recv() {
+        counts.entry(msg.type_name().to_string()).or_default().1 += 1;
+    }
+
+    println!(
+        "Replayed {} inbound messages ({} skipped)",
+        report.replayed, report.skipped
+    );
+    println!();
+    println!("{:<24} {:>12} {:>12}", "MESSAGE", "RECORDED", "REPLAYED");
+    for (message_type, (recorded, replayed)) in &counts {
+        let marker = if recorded == replayed { "" } else { "  *" };
+        println!(
+            "{:<24} {:>12} {:>12}{}",
+            message_type, recorded, replayed, marker
+        );
+    }
+    Ok(())
+}
+
+fn print_help() {
+    println!("Titan POS Hub Replay");
+    println!();
+    println!("Usage: titan-hub-replay <RECORDING> [--db <PATH>] [--speed <N>]");
+    println!();
+    println!("Options:");
+    println!("  -d, --db <PATH>    Database to replay over (copied first; default: empty)");
+    println!("  -s, --speed <N>    Replay N times faster than recorded; 0 = no gaps (default: 1)");
+    println!("  -h, --help         Show this help message");
+    println!();
+    println!("Counts marked * differ between the recording and the replay.");
+}
+
+/// Opens a copy of the database at `path`, brought up to this build's
+/// schema.
+async fn open_copy(path: &Path) -> Result<Database, Box<dyn std::error::Error>> {
+    if !path.exists() {
+        return Err(format!("No database at {}", path.display()).into());
+    }
+    let copy = env::temp_dir().join(format!("titan-hub-replay-{}.db", std::process::id()));
+    std::fs::copy(path, &copy)?;
+    println!("Replaying over a copy at {}", copy.display());
+    Ok(Database::new(DbConfig::new(&copy).run_migrations(true)).await?)
+}
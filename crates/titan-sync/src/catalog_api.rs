@@ -0,0 +1,422 @@
+//! # Hub Catalog API
+//!
+//! Read-only HTTP endpoints on the hub for thin clients (handheld stock
+//! scanners, web tablets) that need to look up products and stock but do
+//! not run the desktop app or hold a copy of the database.
+//!
+//! ## Endpoints
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Hub Catalog API                                 │
+//! │                                                                         │
+//! │  GET /api/catalog/search?q=coke&limit=20                                │
+//! │      ──► products_fts (prefix match), active products only              │
+//! │      ──► empty q lists active products by name                          │
+//! │                                                                         │
+//! │  GET /api/catalog/products/{id}                                         │
+//! │  GET /api/catalog/barcode/{code}                                        │
+//! │      ──► one product, 404 when unknown or deleted                       │
+//! │                                                                         │
+//! │  GET /api/sales/{receipt_number}                                        │
+//! │      ──► SaleDocument, for terminals that pruned their own copy         │
+//! │                                                                         │
+//! │  Responses are JSON CatalogItems. With hub.catalog_token set, every     │
+//! │  request needs "Authorization: Bearer <token>", else 401.               │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! The API serves the hub's own SQLite copy, so a scanner sees the stock
+//! the hub knows about: deltas from other terminals arrive within the
+//! aggregator's broadcast window.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error};
+
+use titan_core::Product;
+use titan_db::Database;
+
+/// Results returned by a search when the client gives no limit.
+pub const DEFAULT_SEARCH_LIMIT: u32 = 20;
+
+/// Most results one search returns.
+pub const MAX_SEARCH_LIMIT: u32 = 100;
+
+// =============================================================================
+// Response Types
+// =============================================================================
+
+/// A product as the catalog API returns it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CatalogItem {
+    pub id: String,
+    pub sku: String,
+    pub barcode: Option<String>,
+    pub name: String,
+    /// Price charged at this store.
+    pub price_cents: i64,
+    pub tax_rate_bps: u32,
+    pub track_inventory: bool,
+    /// Stock on hand (None when inventory is not tracked).
+    pub current_stock: Option<i64>,
+    /// Whether one unit can be sold now.
+    pub in_stock: bool,
+    pub is_active: bool,
+}
+
+impl From<&Product> for CatalogItem {
+    fn from(product: &Product) -> Self {
+        CatalogItem {
+            id: product.id.clone(),
+            sku: product.sku.clone(),
+            barcode: product.barcode.clone(),
+            name: product.name.clone(),
+            price_cents: product.price_cents,
+            tax_rate_bps: product.tax_rate_bps,
+            track_inventory: product.track_inventory,
+            current_stock: product
+                .track_inventory
+                .then(|| product.current_stock.unwrap_or(0)),
+            in_stock: product.can_sell(1),
+            is_active: product.is_active,
+        }
+    }
+}
+
+/// Error body returned with every non-2xx status.
+#[derive(Debug, Serialize)]
+struct ApiError {
+    error: &'static str,
+}
+
+fn api_error(status: StatusCode, error: &'static str) -> Response {
+    (status, Json(ApiError { error })).into_response()
+}
+
+// =============================================================================
+// Router
+// =============================================================================
+
+#[derive(Clone)]
+struct CatalogState {
+    db: Arc<Database>,
+    token: Option<Arc<str>>,
+}
+
+/// Builds the catalog routes, served by the hub next to `/ws`.
+///
+/// `token`, when set, is the bearer token clients must send.
+pub fn router(db: Arc<Database>, token: Option<String>) -> Router {
+    let state = CatalogState {
+        db,
+        token: token.filter(|t| !t.is_empty()).map(Arc::from),
+    };
+
+    Router::new()
+        .route("/api/catalog/search", get(search_handler))
+        .route("/api/catalog/products/{id}", get(product_handler))
+        .route("/api/catalog/barcode/{code}", get(barcode_handler))
+        .route("/api/sales/{receipt_number}", get(sale_handler))
+        .with_state(state)
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchParams {
+    #[serde(default)]
+    q: String,
+    limit: Option<u32>,
+}
+
+async fn search_handler(
+    State(state): State<CatalogState>,
+    headers: HeaderMap,
+    Query(params): Query<SearchParams>,
+) -> Response {
+    if !authorized(state.token.as_deref(), &headers) {
+        return api_error(StatusCode::UNAUTHORIZED, "unauthorized");
+    }
+
+    let query = fts_safe(&params.q);
+    let limit = search_limit(params.limit);
+    match state.db.products().search(&query, limit).await {
+        Ok(products) => {
+            debug!(query = %query, count = products.len(), "Catalog API search");
+            let items: Vec<CatalogItem> = products.iter().map(CatalogItem::from).collect();
+            Json(items).into_response()
+        }
+        Err(e) => {
+            error!(error = %e, "Catalog API search failed");
+            api_error(StatusCode::INTERNAL_SERVER_ERROR, "search failed")
+        }
+    }
+}
+
+async fn product_handler(
+    State(state): State<CatalogState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    if !authorized(state.token.as_deref(), &headers) {
+        return api_error(StatusCode::UNAUTHORIZED, "unauthorized");
+    }
+    single(state.db.products().get_by_id(&id).await)
+}
+
+async fn barcode_handler(
+    State(state): State<CatalogState>,
+    headers: HeaderMap,
+    Path(code): Path<String>,
+) -> Response {
+    if !authorized(state.token.as_deref(), &headers) {
+        return api_error(StatusCode::UNAUTHORIZED, "unauthorized");
+    }
+    single(state.db.products().get_by_barcode(&code).await)
+}
+
+/// Serves a sale document to a terminal that pruned it (see
+/// `titan_core::retention`).
+async fn sale_handler(
+    State(state): State<CatalogState>,
+    headers: HeaderMap,
+    Path(receipt_number): Path<String>,
+) -> Response {
+    if !authorized(state.token.as_deref(), &headers) {
+        return api_error(StatusCode::UNAUTHORIZED, "unauthorized");
+    }
+    match state
+        .db
+        .sales()
+        .get_document_by_receipt(&receipt_number)
+        .await
+    {
+        Ok(Some(doc)) => Json(doc).into_response(),
+        Ok(None) => api_error(StatusCode::NOT_FOUND, "sale not found"),
+        Err(e) => {
+            error!(error = %e, "Catalog API sale lookup failed");
+            api_error(StatusCode::INTERNAL_SERVER_ERROR, "lookup failed")
+        }
+    }
+}
+
+/// Response for a single-product lookup; deleted products are not found.
+fn single(result: titan_db::error::DbResult<Option<Product>>) -> Response {
+    match result {
+        Ok(Some(product)) if product.deleted_at.is_none() => {
+            Json(CatalogItem::from(&product)).into_response()
+        }
+        Ok(_) => api_error(StatusCode::NOT_FOUND, "product not found"),
+        Err(e) => {
+            error!(error = %e, "Catalog API lookup failed");
+            api_error(StatusCode::INTERNAL_SERVER_ERROR, "lookup failed")
+        }
+    }
+}
+
+// =============================================================================
+// Client Helpers
+// =============================================================================
+
+/// URL of a sale on the hub serving `hub_url` (its WebSocket URL, as
+/// discovered or configured), or `None` when that is not a ws:// or wss://
+/// URL.
+pub fn sale_url(hub_url: &str, receipt_number: &str) -> Option<String> {
+    let (scheme, rest) = if let Some(rest) = hub_url.strip_prefix("ws://") {
+        ("http", rest)
+    } else if let Some(rest) = hub_url.strip_prefix("wss://") {
+        ("https", rest)
+    } else {
+        return None;
+    };
+    let authority = rest.split('/').next().filter(|a| !a.is_empty())?;
+    let receipt: String = receipt_number
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+    Some(format!("{}://{}/api/sales/{}", scheme, authority, receipt))
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Whether the request carries the expected bearer token (always, when no
+/// token is configured).
+fn authorized(token: Option<&str>, headers: &HeaderMap) -> bool {
+    let Some(token) = token else {
+        return true;
+    };
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|given| constant_time_eq(given.as_bytes(), token.as_bytes()))
+}
+
+/// Compares without stopping at the first difference, so response timing
+/// does not reveal how much of the token was right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Clamps the client's limit to `1..=MAX_SEARCH_LIMIT`.
+fn search_limit(limit: Option<u32>) -> u32 {
+    limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT)
+}
+
+/// Quotes each word of the client's query, so FTS5 operators and stray
+/// quotes in it cannot break the MATCH expression.
+fn fts_safe(query: &str) -> String {
+    query
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .map(|word| format!("\"{}\"", word))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use titan_db::{DbConfig, ProductFixture, SaleFixture};
+    use tower::ServiceExt;
+
+    async fn db_with_products() -> Arc<Database> {
+        let db = Database::new(DbConfig::in_memory()).await.unwrap();
+        ProductFixture::new("COKE-330")
+            .name("Coca-Cola 330ml")
+            .barcode("5000112637922")
+            .price_cents(150)
+            .stock(12)
+            .insert(&db)
+            .await
+            .unwrap();
+        ProductFixture::new("PEPSI-330")
+            .name("Pepsi 330ml")
+            .price_cents(140)
+            .stock(0)
+            .insert(&db)
+            .await
+            .unwrap();
+        Arc::new(db)
+    }
+
+    async fn get(app: Router, uri: &str, token: Option<&str>) -> (StatusCode, serde_json::Value) {
+        let mut request = Request::get(uri);
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let response = app
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_search_and_lookup() {
+        let app = router(db_with_products().await, None);
+
+        let (status, body) = get(app.clone(), "/api/catalog/search?q=coca", None).await;
+        assert_eq!(status, StatusCode::OK);
+        let items: Vec<CatalogItem> = serde_json::from_value(body).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].sku, "COKE-330");
+        assert_eq!(items[0].current_stock, Some(12));
+        assert!(items[0].in_stock);
+
+        let (status, body) = get(app.clone(), "/api/catalog/barcode/5000112637922", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["sku"], "COKE-330");
+
+        let (status, _) = get(app, "/api/catalog/products/missing", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_token_required_when_configured() {
+        let app = router(db_with_products().await, Some("s3cret".to_string()));
+
+        let (status, _) = get(app.clone(), "/api/catalog/search?q=pepsi", None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = get(app.clone(), "/api/catalog/search?q=pepsi", Some("wrong")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, body) = get(app, "/api/catalog/search?q=pepsi", Some("s3cret")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body[0]["in_stock"], false);
+    }
+
+    #[tokio::test]
+    async fn test_sale_lookup_by_receipt() {
+        let db = db_with_products().await;
+        let coke = db.products().get_by_sku("COKE-330").await.unwrap().unwrap();
+        SaleFixture::new()
+            .receipt_number("R01-0001")
+            .line(&coke, 2)
+            .insert(&db)
+            .await
+            .unwrap();
+        let app = router(db, Some("s3cret".to_string()));
+
+        let (status, _) = get(app.clone(), "/api/sales/R01-0001", None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, body) = get(app.clone(), "/api/sales/R01-0001", Some("s3cret")).await;
+        assert_eq!(status, StatusCode::OK);
+        let doc: titan_core::SaleDocument = serde_json::from_value(body).unwrap();
+        assert_eq!(doc.sale.receipt_number, "R01-0001");
+        assert_eq!(doc.items.len(), 1);
+
+        let (status, body) = get(app, "/api/sales/R01-9999", Some("s3cret")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "sale not found");
+    }
+
+    #[test]
+    fn test_sale_url_from_hub_url() {
+        assert_eq!(
+            sale_url("ws://192.168.1.10:8765/ws", "R01-0001").as_deref(),
+            Some("http://192.168.1.10:8765/api/sales/R01-0001")
+        );
+        assert_eq!(
+            sale_url("wss://hub.local:8765", "R 1/2").as_deref(),
+            Some("https://hub.local:8765/api/sales/R%201%2F2")
+        );
+        assert_eq!(sale_url("http://hub.local", "R01-0001"), None);
+    }
+
+    #[test]
+    fn test_search_limit_is_clamped() {
+        assert_eq!(search_limit(None), DEFAULT_SEARCH_LIMIT);
+        assert_eq!(search_limit(Some(0)), 1);
+        assert_eq!(search_limit(Some(5000)), MAX_SEARCH_LIMIT);
+    }
+
+    #[test]
+    fn test_fts_safe_strips_operators() {
+        assert_eq!(fts_safe("coke \"330\" OR*"), r#""coke" "330" "OR""#);
+        assert_eq!(fts_safe("  -^ "), "");
+    }
+}
@@ -39,15 +39,25 @@
 //! ## Token Storage
 //! Tokens are stored in memory with automatic refresh scheduling.
 //! The refresh happens 5 minutes before expiration to ensure seamless operation.
+//!
+//! ## Refresh Rotation
+//! Each refresh token works once: the cloud answers with the next one, and
+//! treats a token presented twice as stolen, revoking the whole session.
+//! So a refresh token is taken out of [`TokenInfo`] before it is sent and
+//! never retried: if the refresh fails for any reason (including a lost
+//! response, after which the cloud has already rotated), the manager
+//! falls back to the API key exchange, which starts a new session.
 
+use crate::cloud_net::{self, ProxyConfig};
 use crate::error::{SyncError, SyncResult};
 use crate::proto::{auth_service_client::AuthServiceClient, ExchangeTokenRequest, RefreshTokenRequest, RevokeTokenRequest};
+use crate::telemetry::{self, TRACEPARENT_HEADER};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tonic::transport::{Channel, Endpoint};
 use tonic::metadata::MetadataValue;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, info, warn};
 
 /// Margin before token expiration to trigger refresh (5 minutes)
 const REFRESH_MARGIN_SECS: u64 = 300;
@@ -80,6 +90,11 @@ impl TokenInfo {
         Instant::now() >= self.expires_at
     }
     
+    /// Take the refresh token for its one use, leaving none behind.
+    pub fn take_refresh_token(&mut self) -> Option<String> {
+        Some(std::mem::take(&mut self.refresh_token)).filter(|t| !t.is_empty())
+    }
+
     /// Get remaining valid time
     pub fn remaining_secs(&self) -> u64 {
         let now = Instant::now();
@@ -108,6 +123,8 @@ pub struct CloudAuthConfig {
     pub device_name: Option<String>,
     /// Enable TLS verification (should be true in production)
     pub verify_tls: bool,
+    /// HTTP proxy to tunnel through (see `cloud_net`)
+    pub proxy: Option<ProxyConfig>,
 }
 
 impl CloudAuthConfig {
@@ -134,6 +151,7 @@ impl CloudAuthConfig {
             verify_tls: std::env::var("TITAN_VERIFY_TLS")
                 .map(|v| v != "false" && v != "0")
                 .unwrap_or(true),
+            proxy: ProxyConfig::from_env(),
         }
     }
 }
@@ -196,14 +214,21 @@ impl CloudAuth {
         let mut token_guard = self.token.write().await;
         
         // Double-check after acquiring write lock
-        if let Some(token) = token_guard.as_ref() {
+        if let Some(token) = token_guard.as_mut() {
             if !token.needs_refresh() {
                 return Ok(token.access_token.clone());
             }
             
-            // Try to refresh if we have a refresh token and token isn't fully expired
-            if !token.is_expired() {
-                match self.do_refresh(&token.refresh_token).await {
+            // Try to refresh if we have an unused refresh token and the
+            // token isn't fully expired
+            let refresh_token = if token.is_expired() {
+                None
+            } else {
+                token.take_refresh_token()
+            };
+            if let Some(refresh_token) = refresh_token {
+                let (store_id, tenant_id) = (token.store_id.clone(), token.tenant_id.clone());
+                match self.do_refresh(&refresh_token, store_id, tenant_id).await {
                     Ok(new_token) => {
                         info!(
                             store_id = %new_token.store_id,
@@ -307,10 +332,7 @@ impl CloudAuth {
         
         // TODO: Add TLS configuration based on verify_tls
         
-        let channel = endpoint
-            .connect()
-            .await
-            .map_err(|e| SyncError::Connection(format!("Failed to connect to cloud: {}", e)))?;
+        let channel = cloud_net::connect(endpoint, self.config.proxy.as_ref()).await?;
         
         *channel_guard = Some(channel.clone());
         
@@ -350,7 +372,15 @@ impl CloudAuth {
     }
     
     /// Refresh an existing token
-    async fn do_refresh(&self, refresh_token: &str) -> SyncResult<TokenInfo> {
+    ///
+    /// Called with the token lock held, so the IDs are passed in (refresh
+    /// doesn't return them).
+    async fn do_refresh(
+        &self,
+        refresh_token: &str,
+        store_id: String,
+        tenant_id: String,
+    ) -> SyncResult<TokenInfo> {
         let channel = self.get_channel().await?;
         let mut client = AuthServiceClient::new(channel);
         
@@ -361,19 +391,18 @@ impl CloudAuth {
         let response = client
             .refresh_token(request)
             .await
-            .map_err(|e| SyncError::AuthFailed(format!("Token refresh failed: {}", e)))?;
+            .map_err(|e| {
+                if e.code() == tonic::Code::Unauthenticated {
+                    // Reused, revoked or expired session; only the API key
+                    // can start a new one
+                    warn!(message = %e.message(), "Cloud refused the refresh token");
+                }
+                SyncError::AuthFailed(format!("Token refresh failed: {}", e))
+            })?;
         
         let resp = response.into_inner();
         let expires_at = Instant::now() + Duration::from_secs(resp.expires_in as u64);
         
-        // Get current store/tenant IDs (refresh doesn't return them)
-        let (store_id, tenant_id) = {
-            let guard = self.token.read().await;
-            guard.as_ref()
-                .map(|t| (t.store_id.clone(), t.tenant_id.clone()))
-                .unwrap_or_default()
-        };
-        
         Ok(TokenInfo {
             access_token: resp.access_token,
             expires_at,
@@ -406,7 +435,8 @@ impl CloudAuth {
     }
 }
 
-/// Interceptor for adding authorization headers to gRPC requests
+/// Interceptor for adding authorization headers to gRPC requests, and the
+/// current span's trace context (see `telemetry`)
 ///
 /// Use this to create authenticated clients for other services:
 /// ```rust,ignore
@@ -432,6 +462,11 @@ impl tonic::service::Interceptor for AuthInterceptor {
             .parse::<MetadataValue<_>>()
             .map_err(|_| tonic::Status::invalid_argument("Invalid token"))?;
         request.metadata_mut().insert("authorization", token_value);
+
+        // Lets the cloud's spans for the call join the caller's trace
+        if let Some(value) = telemetry::current_trace_context().and_then(|t| t.parse().ok()) {
+            request.metadata_mut().insert(TRACEPARENT_HEADER, value);
+        }
         Ok(request)
     }
 }
@@ -488,4 +523,18 @@ mod tests {
         assert_eq!(config.device_id, "device-001");
         assert_eq!(config.device_name, Some("Register 1".to_string()));
     }
+
+    #[test]
+    fn test_refresh_token_is_taken_once() {
+        let mut token = TokenInfo {
+            access_token: "test".to_string(),
+            expires_at: Instant::now() + Duration::from_secs(60),
+            refresh_token: "refresh".to_string(),
+            store_id: "store1".to_string(),
+            tenant_id: "tenant1".to_string(),
+        };
+
+        assert_eq!(token.take_refresh_token().as_deref(), Some("refresh"));
+        assert_eq!(token.take_refresh_token(), None);
+    }
 }
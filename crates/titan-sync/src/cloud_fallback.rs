@@ -0,0 +1,452 @@
+//! # Cloud Fallback
+//!
+//! Store-and-forward relay for terminals stranded without a hub. If the
+//! PRIMARY dies and no other device can take over (every terminal forced
+//! SECONDARY), sales would sit in the local outbox until someone fixes the
+//! hub. With a fallback delay configured, a terminal that has been unable
+//! to reach any hub for that long uploads its own outbox straight to the
+//! cloud, authenticated as itself.
+//!
+//! ## Fallback Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Cloud Fallback Flow                             │
+//! │                                                                         │
+//! │  hub connected ─────────────────────────────► idle (hub path)          │
+//! │       │                                                                 │
+//! │       ▼ connection lost                                                 │
+//! │  offline < cloud_fallback_secs ─────────────► wait                      │
+//! │       │                                                                 │
+//! │       ▼ offline >= cloud_fallback_secs                                  │
+//! │  CloudUplink::connect()   (ExchangeToken with this device's ID, so     │
+//! │       │                    the token is scoped to this terminal)        │
+//! │       ▼ every poll interval                                             │
+//! │  sync_outbox (SALE, STORE_TRANSFER, STORE_CREDIT, CUSTOMER_ERASURE,     │
+//! │               WASTE as an inventory delta, SUPPLIER, COUPON,            │
+//! │               LOYALTY)                                                  │
+//! │       ──► UploadBatch ──► mark_uploaded / mark_failed                   │
+//! │                                                                         │
+//! │  hub back ──► disconnect from the cloud, the hub path takes over        │
+//! │  sync paused ──► no uploads until resumed (see `control`)               │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Only entities the cloud accepts are uploaded. Quotes, layaways, till
+//! sessions and product patches are shared between terminals through the
+//! hub, so they stay queued until a hub is reachable again.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::{mpsc, RwLock};
+use tokio::time::Instant;
+use tracing::{debug, info, info_span, warn, Instrument};
+
+use titan_core::{SaleDocument, SyncPayload, PAYLOAD_SCHEMA_VERSION};
+use titan_db::Database;
+
+use crate::agent::{SyncEventEmitter, SyncStatus};
+use crate::cloud_uplink::{
+    coupon_to_entity, erasure_to_entity, payment_to_entity, sale_item_to_entity, sale_to_entity,
+    loyalty_to_entity, store_credit_to_entity, supplier_to_entity, transfer_to_entity, waste_to_entity, CloudUplink,
+    CloudUplinkConfig,
+};
+use crate::error::{SyncError, SyncResult};
+use crate::proto::{SyncEntity, UploadBatchResponse};
+use crate::telemetry;
+
+/// Outbox entity types the cloud accepts from a terminal directly.
+pub const CLOUD_ENTITY_TYPES: &[&str] = &[
+    "SALE",
+    "STORE_TRANSFER",
+    "STORE_CREDIT",
+    "CUSTOMER_ERASURE",
+    "WASTE",
+    "SUPPLIER",
+    "COUPON",
+    "LOYALTY",
+];
+
+/// Matches the outbox processor: entries past this are left alone.
+const MAX_RETRY_ATTEMPTS: i64 = 10;
+
+// =============================================================================
+// Outage Clock
+// =============================================================================
+
+/// Tracks how long the hub has been unreachable.
+#[derive(Debug)]
+struct OutageClock {
+    after: Duration,
+    offline_since: Option<Instant>,
+}
+
+impl OutageClock {
+    fn new(after: Duration) -> Self {
+        OutageClock {
+            after,
+            offline_since: None,
+        }
+    }
+
+    /// Records the hub state at `now` and returns whether the outage has
+    /// lasted long enough to fall back.
+    fn observe(&mut self, connected: bool, now: Instant) -> bool {
+        if connected {
+            self.offline_since = None;
+            return false;
+        }
+        let since = *self.offline_since.get_or_insert(now);
+        now.duration_since(since) >= self.after
+    }
+}
+
+// =============================================================================
+// Cloud Fallback
+// =============================================================================
+
+/// Uploads the local outbox to the cloud while no hub is reachable.
+pub struct CloudFallback {
+    db: Arc<Database>,
+    cloud: CloudUplinkConfig,
+    status: Arc<RwLock<SyncStatus>>,
+    emitter: Arc<dyn SyncEventEmitter>,
+    clock: OutageClock,
+    uplink: Option<CloudUplink>,
+    shutdown_rx: mpsc::Receiver<()>,
+}
+
+impl CloudFallback {
+    /// Creates the fallback task and the sender that stops it.
+    ///
+    /// `after` is how long the hub must be unreachable before uploads go
+    /// to the cloud.
+    pub fn new(
+        db: Arc<Database>,
+        cloud: CloudUplinkConfig,
+        after: Duration,
+        status: Arc<RwLock<SyncStatus>>,
+        emitter: Arc<dyn SyncEventEmitter>,
+    ) -> (Self, mpsc::Sender<()>) {
+        let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
+        let fallback = CloudFallback {
+            db,
+            cloud,
+            status,
+            emitter,
+            // The agent starts unconnected, so the clock starts running now
+            clock: OutageClock::new(after),
+            uplink: None,
+            shutdown_rx,
+        };
+        (fallback, shutdown_tx)
+    }
+
+    /// Runs until shutdown, checking the hub state every `poll_interval`.
+    pub async fn run(&mut self, poll_interval: Duration) {
+        info!(after = ?self.clock.after, "Cloud fallback armed");
+
+        let mut interval = tokio::time::interval(poll_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if let Err(e) = self.tick().await {
+                        warn!(?e, "Cloud fallback upload failed");
+                    }
+                }
+                _ = self.shutdown_rx.recv() => break,
+            }
+        }
+
+        self.deactivate().await;
+        info!("Cloud fallback stopped");
+    }
+
+    async fn tick(&mut self) -> SyncResult<()> {
+        let (connected, paused) = {
+            let s = self.status.read().await;
+            (s.is_connected, s.paused)
+        };
+        if !self.clock.observe(connected, Instant::now()) {
+            self.deactivate().await;
+            return Ok(());
+        }
+
+        // Paused by an operator: the outage clock keeps running, uploads wait
+        if paused {
+            debug!("Sync paused, skipping cloud upload");
+            return Ok(());
+        }
+
+        if self.uplink.is_none() {
+            let mut uplink = CloudUplink::new(self.cloud.clone())?;
+            uplink.connect().await?;
+            warn!("No hub reachable, uploading the outbox directly to the cloud");
+            self.uplink = Some(uplink);
+            self.set_active(true).await;
+        }
+
+        match self.upload_pending().await {
+            // Metered links wait for their window like the hub path does
+            Err(SyncError::OutsideSyncWindow) => {
+                debug!("Outside metered sync window, deferring cloud upload");
+                Ok(())
+            }
+            Err(e @ (SyncError::Connection(_) | SyncError::Upload(_))) => {
+                // Reconnect on the next tick
+                self.deactivate().await;
+                Err(e)
+            }
+            other => other,
+        }
+    }
+
+    /// Uploads one batch of cloud-bound outbox entries.
+    async fn upload_pending(&self) -> SyncResult<()> {
+        let Some(uplink) = &self.uplink else {
+            return Ok(());
+        };
+
+        let limit = self.cloud.batch_size as u32;
+        let mut entries = Vec::new();
+        for entity_type in CLOUD_ENTITY_TYPES {
+            let pending = self
+                .db
+                .sync_outbox()
+                .get_pending_by_type(entity_type, limit)
+                .await?;
+            entries.extend(
+                pending
+                    .into_iter()
+                    .filter(|e| e.attempts < MAX_RETRY_ATTEMPTS),
+            );
+        }
+        entries.sort_by_key(|e| e.created_at);
+        entries.truncate(self.cloud.batch_size);
+
+        let mut batch = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let payload = match self
+                .db
+                .payload_cipher()
+                .open(&entry.entity_type, &entry.payload)
+            {
+                Ok(payload) => payload,
+                Err(e) => {
+                    self.db
+                        .sync_outbox()
+                        .mark_failed(&entry.id, &e.to_string())
+                        .await?;
+                    continue;
+                }
+            };
+            // Invalid payloads are quarantined by the outbox processor once
+            // a hub is back; they are not ours to upload
+            let entities = cloud_entities(
+                &self.db,
+                &entry.entity_type,
+                &entry.entity_id,
+                PAYLOAD_SCHEMA_VERSION,
+                &payload,
+                entry.correlation_id.as_deref(),
+                entry.trace_context.as_deref(),
+            )
+            .await?;
+            if let Some(entities) = entities {
+                batch.push((entry, entities));
+            }
+        }
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let entities = batch
+            .iter()
+            .flat_map(|(_, entities)| entities.iter().cloned())
+            .collect();
+        let span = info_span!("fallback_upload", entries = batch.len());
+        for (entry, _) in &batch {
+            telemetry::add_link(&span, entry.trace_context.as_deref());
+        }
+        let ack = uplink.upload_batch(entities).instrument(span).await?;
+
+        let outbox = self.db.sync_outbox();
+        let mut synced = 0;
+        for (entry, entities) in &batch {
+            match outcome(entities, &ack) {
+                Ok(()) => {
+                    outbox.mark_uploaded(&entry.id).await?;
+                    synced += 1;
+                }
+                Err(reason) => outbox.mark_failed(&entry.id, &reason).await?,
+            }
+        }
+
+        info!(
+            synced,
+            total = batch.len(),
+            "Uploaded outbox batch to the cloud"
+        );
+        let pending = outbox.count_pending().await?;
+        self.emitter.emit_progress(pending, synced);
+        Ok(())
+    }
+
+    /// Drops the cloud connection and reports that the hub path is back in
+    /// charge.
+    async fn deactivate(&mut self) {
+        if let Some(mut uplink) = self.uplink.take() {
+            uplink.disconnect().await;
+            self.set_active(false).await;
+        }
+    }
+
+    async fn set_active(&self, active: bool) {
+        let snapshot = {
+            let mut s = self.status.write().await;
+            s.cloud_fallback = active;
+            s.clone()
+        };
+        self.emitter.emit_status(&snapshot);
+    }
+}
+
+/// Cloud entities for an outbox entry, or `None` if the cloud does not
+/// take it from terminals (or the payload does not parse).
+///
+/// A sale's lines and payments come with the payload; a sale from an
+/// older terminal is just the header, so they are read from `db` instead
+/// and only the device that rang the sale up has them all. Serial and lot
+/// tracking is always read from `db`.
+///
+/// Every entity carries the entry's correlation ID and trace context, so
+/// the cloud's spans join the trace of the command that queued it.
+pub(crate) async fn cloud_entities(
+    db: &Database,
+    entity_type: &str,
+    entity_id: &str,
+    schema_version: u32,
+    payload: &str,
+    correlation_id: Option<&str>,
+    trace_context: Option<&str>,
+) -> SyncResult<Option<Vec<SyncEntity>>> {
+    let Ok(payload) = SyncPayload::parse(entity_type, entity_id, schema_version, payload) else {
+        return Ok(None);
+    };
+
+    let entities = match payload {
+        SyncPayload::Sale(doc) => {
+            let sales = db.sales();
+            let SaleDocument {
+                sale,
+                mut items,
+                mut payments,
+            } = doc;
+            if items.is_empty() {
+                items = sales.get_items(&sale.id).await?;
+                payments = sales.get_payments(&sale.id).await?;
+            }
+            let tracking = sales.get_tracking(&sale.id).await?;
+
+            let mut entities = vec![sale_to_entity(&sale)];
+            entities.extend(items.iter().map(|i| sale_item_to_entity(i, &tracking)));
+            entities.extend(payments.iter().map(payment_to_entity));
+            entities
+        }
+        SyncPayload::StoreTransfer(doc) => vec![transfer_to_entity(&doc)],
+        SyncPayload::StoreCredit(doc) => vec![store_credit_to_entity(&doc)],
+        SyncPayload::CustomerErasure(request) => vec![erasure_to_entity(&request)],
+        SyncPayload::Waste(record) => vec![waste_to_entity(&record)],
+        SyncPayload::Supplier(doc) => vec![supplier_to_entity(&doc)],
+        SyncPayload::Coupon(doc) => vec![coupon_to_entity(&doc)],
+        SyncPayload::Loyalty(doc) => vec![loyalty_to_entity(&doc)],
+        _ => return Ok(None),
+    };
+    let correlation_id = correlation_id.unwrap_or_default();
+    let trace_context = trace_context.unwrap_or_default();
+    let entities = entities
+        .into_iter()
+        .map(|entity| SyncEntity {
+            correlation_id: correlation_id.to_string(),
+            trace_context: trace_context.to_string(),
+            ..entity
+        })
+        .collect();
+    Ok(Some(entities))
+}
+
+/// Whether every entity of an outbox entry was accepted, or the cloud's
+/// reason for refusing one.
+pub(crate) fn outcome(entities: &[SyncEntity], ack: &UploadBatchResponse) -> Result<(), String> {
+    let ids: HashSet<&str> = entities.iter().map(|e| e.entity_id.as_str()).collect();
+    if let Some(error) = ack
+        .errors
+        .iter()
+        .find(|e| ids.contains(e.entity_id.as_str()))
+    {
+        return Err(error.error_message.clone());
+    }
+
+    let synced: HashSet<&str> = ack.synced_ids.iter().map(String::as_str).collect();
+    if ids.iter().all(|id| synced.contains(id)) {
+        Ok(())
+    } else {
+        Err("not acknowledged by the cloud".to_string())
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::SyncError as ProtoSyncError;
+
+    fn entity(id: &str) -> SyncEntity {
+        SyncEntity {
+            entity_id: id.to_string(),
+            entity_type: "SALE".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_outage_clock_waits_for_delay() {
+        let start = Instant::now();
+        let mut clock = OutageClock::new(Duration::from_secs(60));
+
+        assert!(!clock.observe(false, start));
+        assert!(!clock.observe(false, start + Duration::from_secs(59)));
+        assert!(clock.observe(false, start + Duration::from_secs(60)));
+
+        // A reconnect resets the outage
+        assert!(!clock.observe(true, start + Duration::from_secs(61)));
+        assert!(!clock.observe(false, start + Duration::from_secs(62)));
+        assert!(clock.observe(false, start + Duration::from_secs(122)));
+    }
+
+    #[test]
+    fn test_outcome_needs_every_entity() {
+        let entities = [entity("sale-1"), entity("item-1")];
+        let mut ack = UploadBatchResponse {
+            synced_ids: vec!["sale-1".into(), "item-1".into(), "sale-2".into()],
+            ..Default::default()
+        };
+        assert_eq!(outcome(&entities, &ack), Ok(()));
+
+        ack.synced_ids = vec!["sale-1".into()];
+        assert!(outcome(&entities, &ack).is_err());
+
+        ack.errors = vec![ProtoSyncError {
+            entity_id: "item-1".into(),
+            error_message: "unknown product".into(),
+            ..Default::default()
+        }];
+        assert_eq!(outcome(&entities, &ack), Err("unknown product".to_string()));
+    }
+}
@@ -30,22 +30,38 @@
 //! │  └────────────────────────────────────────────────────────────────────┘│
 //! └─────────────────────────────────────────────────────────────────────────┘
 //! ```
+//!
+//! ## Queued Uploads
+//! The cloud may queue a large batch instead of processing it in the call
+//! (`ACCEPTED`). [`CloudUplink::upload_batch`] then polls `GetBatchStatus`
+//! until the batch is processed, so callers always get the final result.
 
-use crate::cloud_auth::{CloudAuth, CloudAuthConfig};
+use crate::cloud_auth::{AuthInterceptor, CloudAuth, CloudAuthConfig};
+use crate::cloud_net::{self, ProxyConfig};
 use crate::error::{SyncError, SyncResult};
+use crate::throttle::{BandwidthPolicy, UploadThrottle};
 use crate::proto::{
     sync_service_client::SyncServiceClient,
     config_service_client::ConfigServiceClient,
     health_service_client::HealthServiceClient,
     health_check_response::ServingStatus,
-    sync_entity, SyncEntity, GetPendingUpdatesRequest, UploadBatchRequest,
+    storage_service_client::StorageServiceClient,
+    create_signed_url_request::{Access, Kind},
+    sync_entity, entity_update, SyncEntity, GetBatchStatusRequest, GetPendingUpdatesRequest, UploadBatchRequest,
+    AcknowledgeUpdatesRequest, SyncCursor,
+    GetSaleRequest, GetSaleResponse,
     UploadBatchResponse, GetStoreConfigRequest, GetStoreConfigResponse,
+    GetLatestReleaseRequest, GetLatestReleaseResponse, GetFeatureFlagsRequest,
+    CreateSignedUrlRequest, CreateSignedUrlResponse,
     HealthCheckRequest, Money, Timestamp, Sale, SaleItem, Payment,
-    EntityUpdate,
+    EntityUpdate, StoreTransfer, StoreTransferItem, StoreCredit, StoreCreditEntry,
+    CustomerErasure, InventoryDelta, Product, Supplier, SupplierItem, Coupon, CouponRedemption,
+    LoyaltyAccount, LoyaltyEntry, RedeemLoyaltyRewardRequest,
 };
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::RwLock;
+use prost::Message;
+use tokio::sync::{Mutex, RwLock};
 use tokio_stream::StreamExt;
 use tonic::transport::{Channel, Endpoint};
 use tracing::{debug, info, warn};
@@ -67,6 +83,10 @@ pub struct CloudUplinkConfig {
     pub device_name: Option<String>,
     /// Enable TLS verification
     pub verify_tls: bool,
+    /// HTTP proxy to tunnel through
+    pub proxy: Option<ProxyConfig>,
+    /// Upload bandwidth limits (unthrottled when unset)
+    pub bandwidth: Option<BandwidthPolicy>,
     /// Upload batch size
     pub batch_size: usize,
     /// Upload interval
@@ -77,6 +97,8 @@ pub struct CloudUplinkConfig {
     pub connect_timeout: Duration,
     /// Request timeout
     pub request_timeout: Duration,
+    /// Longest wait for the cloud to process a queued batch
+    pub queued_batch_timeout: Duration,
 }
 
 impl Default for CloudUplinkConfig {
@@ -89,11 +111,14 @@ impl Default for CloudUplinkConfig {
             device_id: String::new(),
             device_name: None,
             verify_tls: true,
+            proxy: None,
+            bandwidth: None,
             batch_size: 100,
             upload_interval: Duration::from_secs(30),
             download_interval: Duration::from_secs(60),
             connect_timeout: Duration::from_secs(10),
             request_timeout: Duration::from_secs(30),
+            queued_batch_timeout: Duration::from_secs(300),
         }
     }
 }
@@ -106,6 +131,7 @@ pub struct CloudUplink {
     auth: Arc<CloudAuth>,
     channel: Option<Channel>,
     connected: Arc<RwLock<bool>>,
+    throttle: Option<Mutex<UploadThrottle>>,
 }
 
 impl CloudUplink {
@@ -119,12 +145,18 @@ impl CloudUplink {
             device_id: config.device_id.clone(),
             device_name: config.device_name.clone(),
             verify_tls: config.verify_tls,
+            proxy: config.proxy.clone(),
         };
 
         let auth = Arc::new(CloudAuth::new(auth_config)?);
+        let throttle = config
+            .bandwidth
+            .clone()
+            .map(|policy| Mutex::new(UploadThrottle::new(policy)));
 
         Ok(Self {
             config,
+            throttle,
             auth,
             channel: None,
             connected: Arc::new(RwLock::new(false)),
@@ -139,10 +171,7 @@ impl CloudUplink {
             .map_err(|e| SyncError::Connection(format!("Invalid endpoint: {}", e)))?
             .connect_timeout(self.config.connect_timeout);
 
-        let channel = endpoint
-            .connect()
-            .await
-            .map_err(|e| SyncError::Connection(format!("Failed to connect: {}", e)))?;
+        let channel = cloud_net::connect(endpoint, self.config.proxy.as_ref()).await?;
 
         // Authenticate
         self.auth.authenticate().await?;
@@ -179,22 +208,6 @@ impl CloudUplink {
     /// * `entities` - Vec of sync entities (sales, payments, inventory deltas)
     pub async fn upload_batch(&self, entities: Vec<SyncEntity>) -> SyncResult<UploadBatchResponse> {
         let channel = self.channel()?;
-        let token = self.auth.get_access_token().await?;
-
-        let mut client = SyncServiceClient::with_interceptor(
-            channel,
-            move |mut req: tonic::Request<()>| {
-                let token = token.clone();
-                req.metadata_mut().insert(
-                    "authorization",
-                    format!("Bearer {}", token)
-                        .parse()
-                        .expect("valid header value"),
-                );
-                Ok(req)
-            },
-        );
-
         let batch_id = uuid::Uuid::new_v4().to_string();
         let entity_count = entities.len();
 
@@ -206,14 +219,32 @@ impl CloudUplink {
             device_id: self.config.device_id.clone(),
             entities,
             cursors: vec![], // No cursors to report in this batch
+            allow_queued: true,
         };
 
+        // Uploads queue behind the throttle, so concurrent batches share
+        // the bandwidth budget
+        if let Some(throttle) = &self.throttle {
+            let mut throttle = throttle.lock().await;
+            if !throttle.policy().allows_upload_now() {
+                return Err(SyncError::OutsideSyncWindow);
+            }
+            throttle.acquire(request.encoded_len()).await;
+        }
+
+        // Fetched after any throttle wait so the token is fresh
+        let token = self.auth.get_access_token().await?;
+        let mut client = SyncServiceClient::with_interceptor(channel, AuthInterceptor::new(token));
+
         let response = client
             .upload_batch(request)
             .await
             .map_err(|e| SyncError::Upload(format!("Upload failed: {}", e)))?;
 
-        let ack = response.into_inner();
+        let mut ack = response.into_inner();
+        if ack.status == BATCH_ACCEPTED {
+            ack = self.wait_for_batch(&ack.batch_status_id).await?;
+        }
 
         info!(
             batch_id = %batch_id,
@@ -226,24 +257,61 @@ impl CloudUplink {
         Ok(ack)
     }
 
+    /// Polls a queued batch until the cloud has processed it.
+    ///
+    /// ## Errors
+    /// `SyncError::Upload` if the cloud gave up on the batch or it is still
+    /// not processed after `queued_batch_timeout`; uploading it again is safe.
+    async fn wait_for_batch(&self, batch_status_id: &str) -> SyncResult<UploadBatchResponse> {
+        let deadline = tokio::time::Instant::now() + self.config.queued_batch_timeout;
+        debug!(batch_status_id, "Batch queued by the cloud, waiting for it");
+
+        for attempt in 0.. {
+            let delay = batch_poll_delay(attempt);
+            if tokio::time::Instant::now() + delay > deadline {
+                break;
+            }
+            tokio::time::sleep(delay).await;
+
+            let token = self.auth.get_access_token().await?;
+            let mut client =
+                SyncServiceClient::with_interceptor(self.channel()?, AuthInterceptor::new(token));
+            let status = client
+                .get_batch_status(GetBatchStatusRequest {
+                    batch_status_id: batch_status_id.to_string(),
+                })
+                .await
+                .map_err(|e| SyncError::Upload(format!("Batch status failed: {}", e)))?
+                .into_inner();
+
+            match status.status.as_str() {
+                "COMPLETED" => {
+                    return status.result.ok_or_else(|| {
+                        SyncError::Upload("Completed batch has no result".to_string())
+                    })
+                }
+                "FAILED" => {
+                    return Err(SyncError::Upload(format!(
+                        "Cloud could not process batch: {}",
+                        status.error_message
+                    )))
+                }
+                _ => {}
+            }
+        }
+
+        Err(SyncError::Upload(format!(
+            "Cloud still processing batch {} after {:?}",
+            batch_status_id, self.config.queued_batch_timeout
+        )))
+    }
+
     /// Download pending updates from the cloud.
     pub async fn download_updates(&self) -> SyncResult<Vec<EntityUpdate>> {
         let channel = self.channel()?;
         let token = self.auth.get_access_token().await?;
 
-        let mut client = SyncServiceClient::with_interceptor(
-            channel,
-            move |mut req: tonic::Request<()>| {
-                let token = token.clone();
-                req.metadata_mut().insert(
-                    "authorization",
-                    format!("Bearer {}", token)
-                        .parse()
-                        .expect("valid header value"),
-                );
-                Ok(req)
-            },
-        );
+        let mut client = SyncServiceClient::with_interceptor(channel, AuthInterceptor::new(token));
 
         info!("Downloading pending updates from cloud");
 
@@ -279,24 +347,137 @@ impl CloudUplink {
         Ok(updates)
     }
 
+    /// Download the tenant's coupons that changed since this store last
+    /// acknowledged the `coupons` stream.
+    ///
+    /// Returns the coupons with the stream position to pass to
+    /// [`Self::acknowledge_coupons`] once they are stored. Coupons that
+    /// cannot be read are skipped (and acknowledged with the rest).
+    pub async fn download_coupons(&self) -> SyncResult<(Vec<titan_core::CouponDocument>, i64)> {
+        let channel = self.channel()?;
+        let token = self.auth.get_access_token().await?;
+
+        let mut client = SyncServiceClient::with_interceptor(channel, AuthInterceptor::new(token));
+
+        let request = GetPendingUpdatesRequest {
+            store_id: self.config.store_id.clone(),
+            cursor: None,
+            limit: self.config.batch_size as i32,
+            entity_types: vec!["COUPON".to_string()],
+        };
+
+        let mut stream = client
+            .get_pending_updates(request)
+            .await
+            .map_err(|e| SyncError::Download(format!("Coupon download failed: {}", e)))?
+            .into_inner();
+
+        let mut coupons = Vec::new();
+        let mut position = 0;
+        while let Some(result) = stream.next().await {
+            let update = result
+                .map_err(|e| SyncError::Download(format!("Coupon download failed: {}", e)))?;
+            position = position.max(update.version);
+            let Some(entity_update::Data::Coupon(coupon)) = &update.data else {
+                continue;
+            };
+            match coupon_from_proto(coupon, &self.config.tenant_id) {
+                Some(doc) => coupons.push(doc),
+                None => warn!(coupon_id = %coupon.id, "Skipping unreadable coupon"),
+            }
+        }
+
+        info!(count = coupons.len(), "Downloaded coupons from cloud");
+        Ok((coupons, position))
+    }
+
+    /// Move this store's `coupons` stream past `position` (see
+    /// [`Self::download_coupons`]).
+    pub async fn acknowledge_coupons(&self, position: i64) -> SyncResult<()> {
+        let channel = self.channel()?;
+        let token = self.auth.get_access_token().await?;
+
+        let mut client = SyncServiceClient::with_interceptor(channel, AuthInterceptor::new(token));
+
+        client
+            .acknowledge_updates(AcknowledgeUpdatesRequest {
+                store_id: self.config.store_id.clone(),
+                update_ids: Vec::new(),
+                new_cursor: Some(SyncCursor {
+                    stream: "coupons".to_string(),
+                    position,
+                    updated_at: None,
+                }),
+            })
+            .await
+            .map_err(|e| SyncError::Download(format!("Coupon acknowledgement failed: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Look up an uploaded sale of this store by receipt number, for a
+    /// terminal that no longer has it (see `titan_core::retention`).
+    ///
+    /// Returns `None` when the cloud has no such sale.
+    pub async fn get_sale(&self, receipt_number: &str) -> SyncResult<Option<titan_core::SaleDocument>> {
+        let channel = self.channel()?;
+        let token = self.auth.get_access_token().await?;
+
+        let mut client = SyncServiceClient::with_interceptor(channel, AuthInterceptor::new(token));
+
+        let request = GetSaleRequest {
+            receipt_number: receipt_number.to_string(),
+        };
+
+        let response = match client.get_sale(request).await {
+            Ok(response) => response.into_inner(),
+            Err(status) if status.code() == tonic::Code::NotFound => return Ok(None),
+            Err(e) => return Err(SyncError::Cloud(format!("Failed to look up sale: {}", e))),
+        };
+
+        sale_from_proto(&response, &self.config.tenant_id)
+            .map(Some)
+            .ok_or_else(|| SyncError::Cloud(format!("Cloud sent an invalid sale for {}", receipt_number)))
+    }
+
+    /// Spend loyalty rewards against the tenant's ledger in the cloud.
+    ///
+    /// `doc` is this store's copy of the account, merged in first. Returns
+    /// whether the redemption was approved and the rewards balance left.
+    pub async fn redeem_loyalty_reward(
+        &self,
+        doc: &titan_core::LoyaltyDocument,
+        redemption: &titan_core::LoyaltyEntry,
+    ) -> SyncResult<(bool, i64)> {
+        let channel = self.channel()?;
+        let token = self.auth.get_access_token().await?;
+
+        let mut client = SyncServiceClient::with_interceptor(channel, AuthInterceptor::new(token));
+
+        let request = RedeemLoyaltyRewardRequest {
+            store_id: self.config.store_id.clone(),
+            account: Some(loyalty_proto(doc)),
+            redemption: Some(loyalty_entry_proto(redemption)),
+        };
+
+        let response = client
+            .redeem_loyalty_reward(request)
+            .await
+            .map_err(|e| SyncError::Cloud(format!("Loyalty redemption failed: {}", e)))?
+            .into_inner();
+
+        Ok((
+            response.approved,
+            response.balance.map(|m| m.cents).unwrap_or(0),
+        ))
+    }
+
     /// Get store configuration from the cloud.
     pub async fn get_store_config(&self) -> SyncResult<GetStoreConfigResponse> {
         let channel = self.channel()?;
         let token = self.auth.get_access_token().await?;
 
-        let mut client = ConfigServiceClient::with_interceptor(
-            channel,
-            move |mut req: tonic::Request<()>| {
-                let token = token.clone();
-                req.metadata_mut().insert(
-                    "authorization",
-                    format!("Bearer {}", token)
-                        .parse()
-                        .expect("valid header value"),
-                );
-                Ok(req)
-            },
-        );
+        let mut client = ConfigServiceClient::with_interceptor(channel, AuthInterceptor::new(token));
 
         let request = GetStoreConfigRequest {
             store_id: self.config.store_id.clone(),
@@ -310,6 +491,92 @@ impl CloudUplink {
         Ok(response.into_inner())
     }
 
+    /// Ask the cloud for the newest app release offered to this store.
+    ///
+    /// `channel` is "stable" or "beta"; `current_version` is the semantic
+    /// version this device runs.
+    pub async fn get_latest_release(
+        &self,
+        channel: &str,
+        platform: &str,
+        current_version: &str,
+    ) -> SyncResult<GetLatestReleaseResponse> {
+        let channel_conn = self.channel()?;
+        let token = self.auth.get_access_token().await?;
+
+        let mut client =
+            ConfigServiceClient::with_interceptor(channel_conn, AuthInterceptor::new(token));
+
+        let request = GetLatestReleaseRequest {
+            store_id: self.config.store_id.clone(),
+            channel: channel.to_string(),
+            platform: platform.to_string(),
+            current_version: current_version.to_string(),
+        };
+
+        let response = client
+            .get_latest_release(request)
+            .await
+            .map_err(|e| SyncError::Cloud(format!("Failed to check for updates: {}", e)))?;
+
+        Ok(response.into_inner())
+    }
+
+    /// Fetch the feature flags in effect for this store, ready to cache.
+    pub async fn get_feature_flags(&self) -> SyncResult<titan_core::FeatureFlags> {
+        let channel = self.channel()?;
+        let token = self.auth.get_access_token().await?;
+
+        let mut client = ConfigServiceClient::with_interceptor(channel, AuthInterceptor::new(token));
+
+        let request = GetFeatureFlagsRequest {
+            store_id: self.config.store_id.clone(),
+        };
+
+        let response = client
+            .get_feature_flags(request)
+            .await
+            .map_err(|e| SyncError::Cloud(format!("Failed to get feature flags: {}", e)))?
+            .into_inner();
+
+        Ok(titan_core::FeatureFlags::new(
+            response.flags.into_iter().map(|f| (f.key, f.enabled)),
+            chrono::Utc::now(),
+            response.ttl_secs,
+        ))
+    }
+
+    /// Ask the cloud for a signed URL to one object in this store's storage
+    /// area; the file itself then goes straight to object storage.
+    ///
+    /// `expires_in_secs` of 0 uses the cloud's default lifetime.
+    pub async fn create_signed_url(
+        &self,
+        kind: Kind,
+        access: Access,
+        name: &str,
+        expires_in_secs: i32,
+    ) -> SyncResult<CreateSignedUrlResponse> {
+        let channel = self.channel()?;
+        let token = self.auth.get_access_token().await?;
+
+        let mut client = StorageServiceClient::with_interceptor(channel, AuthInterceptor::new(token));
+
+        let request = CreateSignedUrlRequest {
+            kind: kind as i32,
+            access: access as i32,
+            name: name.to_string(),
+            expires_in_secs,
+        };
+
+        let response = client
+            .create_signed_url(request)
+            .await
+            .map_err(|e| SyncError::Cloud(format!("Failed to get signed URL: {}", e)))?;
+
+        Ok(response.into_inner())
+    }
+
     /// Check cloud health.
     pub async fn health_check(&self) -> SyncResult<bool> {
         let channel = self.channel()?;
@@ -333,6 +600,14 @@ impl CloudUplink {
     }
 }
 
+/// `UploadBatchResponse.status` of a batch the cloud queued.
+const BATCH_ACCEPTED: &str = "ACCEPTED";
+
+/// Wait before the `attempt`th batch status poll: 1s doubling up to 10s.
+fn batch_poll_delay(attempt: u32) -> Duration {
+    Duration::from_secs(1u64 << attempt.min(4)).min(Duration::from_secs(10))
+}
+
 // =============================================================================
 // Entity Conversion Helpers
 // =============================================================================
@@ -347,6 +622,7 @@ impl CloudUplink {
 /// tenant_id                 →  store_id
 /// device_id                 →  device_id
 /// receipt_number            →  receipt_number
+/// station_number            →  station_number (None: 0)
 /// subtotal_cents            →  subtotal.cents
 /// tax_cents                 →  tax_amount.cents
 /// discount_cents            →  discount_amount.cents
@@ -367,6 +643,8 @@ pub fn sale_to_entity(sale: &titan_core::Sale) -> SyncEntity {
         entity_id: sale.id.clone(),
         entity_type: "SALE".to_string(),
         device_sequence: sale.sync_version,
+        correlation_id: String::new(),
+        trace_context: String::new(),
         created_at: Some(Timestamp {
             value: sale.created_at.to_rfc3339(),
         }),
@@ -375,6 +653,7 @@ pub fn sale_to_entity(sale: &titan_core::Sale) -> SyncEntity {
             store_id: sale.tenant_id.clone(),
             device_id: sale.device_id.clone(),
             receipt_number: sale.receipt_number.clone(),
+            station_number: sale.station_number.unwrap_or(0) as i32,
             subtotal: Some(Money {
                 cents: sale.subtotal_cents,
                 currency: "USD".to_string(),
@@ -418,13 +697,29 @@ pub fn sale_to_entity(sale: &titan_core::Sale) -> SyncEntity {
 /// unit_price_cents          →  unit_price.cents
 /// line_total_cents          →  line_total.cents
 /// tax_cents                 →  tax_amount.cents
-/// (no tax_rate_bps)         →  tax_rate_bps = 0
+/// tax_rate_bps (None → 0)    →  tax_rate_bps
+/// base_price_cents (None)   →  base_price (unset)
+/// tracking[].kind           →  tracking_kind ("serial" / "lot" / "")
+/// tracking[].code           →  tracking_codes
 /// ```
-pub fn sale_item_to_entity(item: &titan_core::SaleItem) -> SyncEntity {
+///
+/// `tracking` holds the serial/lot rows captured for the sale; rows for
+/// other items are ignored.
+pub fn sale_item_to_entity(
+    item: &titan_core::SaleItem,
+    tracking: &[titan_core::SaleItemTracking],
+) -> SyncEntity {
+    let tracking: Vec<&titan_core::SaleItemTracking> = tracking
+        .iter()
+        .filter(|t| t.sale_item_id == item.id)
+        .collect();
+
     SyncEntity {
         entity_id: item.id.clone(),
         entity_type: "SALE_ITEM".to_string(),
         device_sequence: 0,
+        correlation_id: String::new(),
+        trace_context: String::new(),
         created_at: Some(Timestamp {
             value: item.created_at.to_rfc3339(),
         }),
@@ -447,7 +742,16 @@ pub fn sale_item_to_entity(item: &titan_core::SaleItem) -> SyncEntity {
                 cents: item.tax_cents,
                 currency: "USD".to_string(),
             }),
-            tax_rate_bps: 0, // Not stored in SaleItem, would need to look up from Product
+            tax_rate_bps: item.tax_rate_bps.unwrap_or(0) as i32,
+            tracking_kind: tracking
+                .first()
+                .map(|t| t.kind.as_str().to_string())
+                .unwrap_or_default(),
+            tracking_codes: tracking.iter().map(|t| t.code.clone()).collect(),
+            base_price: item.base_price_cents.map(|cents| Money {
+                cents,
+                currency: "USD".to_string(),
+            }),
         })),
     }
 }
@@ -473,12 +777,16 @@ pub fn payment_to_entity(payment: &titan_core::Payment) -> SyncEntity {
     let method_str = match payment.method {
         titan_core::PaymentMethod::Cash => "CASH",
         titan_core::PaymentMethod::ExternalCard => "EXTERNAL_CARD",
+        titan_core::PaymentMethod::StoreCredit => "STORE_CREDIT",
+        titan_core::PaymentMethod::LoyaltyReward => "LOYALTY_REWARD",
     };
 
     SyncEntity {
         entity_id: payment.id.clone(),
         entity_type: "PAYMENT".to_string(),
         device_sequence: 0,
+        correlation_id: String::new(),
+        trace_context: String::new(),
         created_at: Some(Timestamp {
             value: payment.created_at.to_rfc3339(),
         }),
@@ -504,6 +812,794 @@ pub fn payment_to_entity(payment: &titan_core::Payment) -> SyncEntity {
     }
 }
 
+/// Convert a sale looked up in the cloud back into a document, stored on
+/// the terminal as a relayed copy.
+///
+/// # Field Mapping
+/// ```text
+/// proto::Sale / SaleItem / Payment  →  titan_core::SaleDocument
+/// ──────────────────────────────────────────────────────────────
+/// status COMPLETED, REFUNDED        →  Completed (refunds are separate)
+/// status VOIDED                     →  Voided
+/// station_number 0                  →  None
+/// (none)                            →  user_id "", notes None, line discount 0
+/// completed_at (else created_at)    →  updated_at
+/// method CASH, STORE_CREDIT         →  Cash, StoreCredit (else ExternalCard)
+/// change_given 0 / reference ""     →  None
+/// ```
+///
+/// Returns `None` if the response has no sale or a timestamp cannot be
+/// parsed.
+pub fn sale_from_proto(
+    response: &GetSaleResponse,
+    tenant_id: &str,
+) -> Option<titan_core::SaleDocument> {
+    use titan_core::{PaymentMethod, SaleStatus};
+
+    let parse = |ts: &Option<Timestamp>| -> Option<chrono::DateTime<chrono::Utc>> {
+        let ts = ts.as_ref()?;
+        chrono::DateTime::parse_from_rfc3339(&ts.value)
+            .ok()
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+    };
+    let cents = |money: &Option<Money>| money.as_ref().map(|m| m.cents).unwrap_or(0);
+    let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+
+    let sale = response.sale.as_ref()?;
+    let created_at = parse(&sale.created_at)?;
+    let completed_at = parse(&sale.completed_at);
+    let status = match sale.status.as_str() {
+        "COMPLETED" | "REFUNDED" => SaleStatus::Completed,
+        "VOIDED" => SaleStatus::Voided,
+        _ => SaleStatus::Draft,
+    };
+
+    let items = sale
+        .items
+        .iter()
+        .map(|item| titan_core::SaleItem {
+            id: item.id.clone(),
+            sale_id: sale.id.clone(),
+            product_id: item.product_id.clone(),
+            sku_snapshot: item.sku.clone(),
+            name_snapshot: item.name.clone(),
+            unit_price_cents: cents(&item.unit_price),
+            quantity: i64::from(item.quantity),
+            line_total_cents: cents(&item.line_total),
+            tax_cents: cents(&item.tax_amount),
+            discount_cents: 0,
+            base_price_cents: item.base_price.as_ref().map(|m| m.cents),
+            tax_rate_bps: Some(item.tax_rate_bps.max(0) as u32),
+            created_at,
+        })
+        .collect();
+
+    let payments = response
+        .payments
+        .iter()
+        .map(|payment| {
+            let method = match payment.method.as_str() {
+                "CASH" => PaymentMethod::Cash,
+                "STORE_CREDIT" => PaymentMethod::StoreCredit,
+                "LOYALTY_REWARD" => PaymentMethod::LoyaltyReward,
+                _ => PaymentMethod::ExternalCard,
+            };
+            let change = cents(&payment.change_given);
+            Some(titan_core::Payment {
+                id: payment.id.clone(),
+                sale_id: sale.id.clone(),
+                method,
+                amount_cents: cents(&payment.amount),
+                tendered_cents: None,
+                change_cents: (change != 0).then_some(change),
+                reference: non_empty(&payment.reference),
+                created_at: parse(&payment.created_at)?,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(titan_core::SaleDocument {
+        sale: titan_core::Sale {
+            id: sale.id.clone(),
+            tenant_id: tenant_id.to_string(),
+            receipt_number: sale.receipt_number.clone(),
+            status,
+            subtotal_cents: cents(&sale.subtotal),
+            tax_cents: cents(&sale.tax_amount),
+            discount_cents: cents(&sale.discount_amount),
+            total_cents: cents(&sale.total),
+            user_id: String::new(),
+            device_id: sale.device_id.clone(),
+            station_number: (sale.station_number > 0).then(|| i64::from(sale.station_number)),
+            notes: None,
+            created_at,
+            updated_at: completed_at.unwrap_or(created_at),
+            completed_at,
+            sync_version: 0,
+        },
+        items,
+        payments,
+    })
+}
+
+/// Convert a store transfer document to a proto::SyncEntity.
+///
+/// # Field Mapping
+/// ```text
+/// titan_core::StoreTransferDocument  →  proto::StoreTransfer
+/// ──────────────────────────────────────────────────────────
+/// status (enum)                      →  status (IN_TRANSIT, RECEIVED, CANCELLED)
+/// notes / received_by (None)         →  "" (empty)
+/// items[].sku_snapshot               →  items[].sku
+/// items[].quantity_received (None)   →  items[].quantity_received unset
+/// sync_version                       →  version
+/// ```
+pub fn transfer_to_entity(doc: &titan_core::StoreTransferDocument) -> SyncEntity {
+    let transfer = &doc.transfer;
+    let ts = |dt: &chrono::DateTime<chrono::Utc>| Timestamp {
+        value: dt.to_rfc3339(),
+    };
+
+    SyncEntity {
+        entity_id: transfer.id.clone(),
+        entity_type: "STORE_TRANSFER".to_string(),
+        device_sequence: transfer.sync_version,
+        correlation_id: String::new(),
+        trace_context: String::new(),
+        created_at: Some(ts(&transfer.updated_at)),
+        data: Some(sync_entity::Data::StoreTransfer(StoreTransfer {
+            id: transfer.id.clone(),
+            transfer_number: transfer.transfer_number.clone(),
+            from_store_id: transfer.from_store_id.clone(),
+            to_store_id: transfer.to_store_id.clone(),
+            status: transfer.status.as_str().to_uppercase(),
+            notes: transfer.notes.clone().unwrap_or_default(),
+            created_by: transfer.created_by.clone(),
+            received_by: transfer.received_by.clone().unwrap_or_default(),
+            items: doc
+                .items
+                .iter()
+                .map(|item| StoreTransferItem {
+                    id: item.id.clone(),
+                    product_id: item.product_id.clone(),
+                    sku: item.sku_snapshot.clone(),
+                    name: item.name_snapshot.clone(),
+                    quantity_sent: item.quantity_sent as i32,
+                    quantity_received: item.quantity_received.map(|q| q as i32),
+                })
+                .collect(),
+            created_at: Some(ts(&transfer.created_at)),
+            updated_at: Some(ts(&transfer.updated_at)),
+            received_at: transfer.received_at.as_ref().map(ts),
+            cancelled_at: transfer.cancelled_at.as_ref().map(ts),
+            version: transfer.sync_version,
+        })),
+    }
+}
+
+/// Convert a product downloaded from the cloud into a product for
+/// [`crate::inbound`].
+///
+/// `price` is the store's price; `base_price` is set when that is a store
+/// override and is kept so local reports can tell the two apart. Fields the
+/// cloud does not carry (description, negative stock, tracking, purchase
+/// age) are left at their defaults.
+///
+/// Returns `None` if a timestamp cannot be parsed.
+pub fn product_from_proto(product: &Product, tenant_id: &str) -> Option<titan_core::Product> {
+    let parse = |ts: &Option<Timestamp>| -> Option<chrono::DateTime<chrono::Utc>> {
+        let ts = ts.as_ref()?;
+        chrono::DateTime::parse_from_rfc3339(&ts.value)
+            .ok()
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+    };
+    let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+
+    Some(titan_core::Product {
+        id: product.id.clone(),
+        tenant_id: tenant_id.to_string(),
+        sku: product.sku.clone(),
+        barcode: non_empty(&product.barcode),
+        name: product.name.clone(),
+        description: None,
+        price_cents: product.price.as_ref().map(|m| m.cents).unwrap_or(0),
+        base_price_cents: product.base_price.as_ref().map(|m| m.cents),
+        cost_cents: product.cost.as_ref().map(|m| m.cents),
+        tax_rate_bps: product.tax_rate_bps.max(0) as u32,
+        track_inventory: product.track_inventory,
+        allow_negative_stock: false,
+        current_stock: product.track_inventory.then_some(product.current_stock),
+        item_tracking: titan_core::ItemTracking::None,
+        min_purchase_age: None,
+        is_active: product.is_active,
+        deleted_at: None,
+        created_at: parse(&product.created_at)?,
+        updated_at: parse(&product.updated_at)?,
+        sync_version: product.version,
+    })
+}
+
+/// The attribute set sent with a product downloaded from the cloud, as a
+/// document for [`crate::inbound`].
+///
+/// The set takes the product's cloud version, which only grows, so a newer
+/// download always replaces an older one. Returns `None` if the cloud sent
+/// an attribute terminals would not accept or a timestamp cannot be parsed.
+pub fn attributes_from_proto(product: &Product) -> Option<titan_core::ProductAttributes> {
+    let updated_at = product.updated_at.as_ref()?;
+    let updated_at = chrono::DateTime::parse_from_rfc3339(&updated_at.value)
+        .ok()?
+        .with_timezone(&chrono::Utc);
+    let attributes: Vec<titan_core::ProductAttribute> = product
+        .attributes
+        .iter()
+        .map(|(key, value)| titan_core::ProductAttribute::new(key.as_str(), value.as_str()))
+        .collect();
+
+    Some(titan_core::ProductAttributes {
+        product_id: product.id.clone(),
+        attributes: titan_core::attribute::normalize_attributes(&attributes).ok()?,
+        sync_version: product.version,
+        updated_at,
+    })
+}
+
+/// Convert a store transfer downloaded from the cloud back into a document
+/// for [`crate::inbound`].
+///
+/// Returns `None` if the status or a timestamp cannot be parsed.
+pub fn transfer_from_proto(
+    transfer: &StoreTransfer,
+    tenant_id: &str,
+) -> Option<titan_core::StoreTransferDocument> {
+    use titan_core::TransferStatus;
+
+    let parse = |ts: &Option<Timestamp>| -> Option<chrono::DateTime<chrono::Utc>> {
+        let ts = ts.as_ref()?;
+        chrono::DateTime::parse_from_rfc3339(&ts.value)
+            .ok()
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+    };
+    let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+
+    let status = match transfer.status.as_str() {
+        "IN_TRANSIT" => TransferStatus::InTransit,
+        "RECEIVED" => TransferStatus::Received,
+        "CANCELLED" => TransferStatus::Cancelled,
+        other => {
+            warn!(transfer_id = %transfer.id, status = %other, "Unknown transfer status");
+            return None;
+        }
+    };
+
+    Some(titan_core::StoreTransferDocument {
+        transfer: titan_core::StoreTransfer {
+            id: transfer.id.clone(),
+            tenant_id: tenant_id.to_string(),
+            transfer_number: transfer.transfer_number.clone(),
+            from_store_id: transfer.from_store_id.clone(),
+            to_store_id: transfer.to_store_id.clone(),
+            status,
+            notes: non_empty(&transfer.notes),
+            created_by: transfer.created_by.clone(),
+            received_by: non_empty(&transfer.received_by),
+            created_at: parse(&transfer.created_at)?,
+            updated_at: parse(&transfer.updated_at)?,
+            received_at: parse(&transfer.received_at),
+            cancelled_at: parse(&transfer.cancelled_at),
+            sync_version: transfer.version,
+        },
+        items: transfer
+            .items
+            .iter()
+            .map(|item| titan_core::StoreTransferItem {
+                id: item.id.clone(),
+                transfer_id: transfer.id.clone(),
+                product_id: item.product_id.clone(),
+                sku_snapshot: item.sku.clone(),
+                name_snapshot: item.name.clone(),
+                quantity_sent: item.quantity_sent as i64,
+                quantity_received: item.quantity_received.map(|q| q as i64),
+            })
+            .collect(),
+    })
+}
+
+/// Convert a store credit account to a proto::SyncEntity.
+///
+/// # Field Mapping
+/// ```text
+/// titan_core::StoreCreditDocument  →  proto::StoreCredit
+/// ──────────────────────────────────────────────────────
+/// customer_phone (None)            →  "" (empty)
+/// entries[].kind (enum)            →  entries[].kind (ISSUE, REDEEM)
+/// entries[].amount_cents (signed)  →  entries[].amount.cents (signed)
+/// entries[].sale_id (None)         →  "" (empty)
+/// sync_version                     →  version
+/// ```
+pub fn store_credit_to_entity(doc: &titan_core::StoreCreditDocument) -> SyncEntity {
+    let account = &doc.account;
+    let ts = |dt: &chrono::DateTime<chrono::Utc>| Timestamp {
+        value: dt.to_rfc3339(),
+    };
+
+    SyncEntity {
+        entity_id: account.id.clone(),
+        entity_type: "STORE_CREDIT".to_string(),
+        device_sequence: account.sync_version,
+        correlation_id: String::new(),
+        trace_context: String::new(),
+        created_at: Some(ts(&account.updated_at)),
+        data: Some(sync_entity::Data::StoreCredit(StoreCredit {
+            id: account.id.clone(),
+            credit_number: account.credit_number.clone(),
+            customer_name: account.customer_name.clone(),
+            customer_phone: account.customer_phone.clone().unwrap_or_default(),
+            entries: doc
+                .entries
+                .iter()
+                .map(|entry| StoreCreditEntry {
+                    id: entry.id.clone(),
+                    kind: entry.kind.as_str().to_uppercase(),
+                    amount: Some(Money {
+                        cents: entry.amount_cents,
+                        currency: "USD".to_string(),
+                    }),
+                    sale_id: entry.sale_id.clone().unwrap_or_default(),
+                    device_id: entry.device_id.clone(),
+                    created_at: Some(ts(&entry.created_at)),
+                })
+                .collect(),
+            created_at: Some(ts(&account.created_at)),
+            updated_at: Some(ts(&account.updated_at)),
+            version: account.sync_version,
+        })),
+    }
+}
+
+/// Convert a store credit account downloaded from the cloud back into a
+/// document for [`crate::inbound`].
+///
+/// Returns `None` if an entry kind or a timestamp cannot be parsed.
+pub fn store_credit_from_proto(
+    credit: &StoreCredit,
+    tenant_id: &str,
+) -> Option<titan_core::StoreCreditDocument> {
+    use titan_core::StoreCreditEntryKind;
+
+    let parse = |ts: &Option<Timestamp>| -> Option<chrono::DateTime<chrono::Utc>> {
+        let ts = ts.as_ref()?;
+        chrono::DateTime::parse_from_rfc3339(&ts.value)
+            .ok()
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+    };
+    let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+
+    let entries = credit
+        .entries
+        .iter()
+        .map(|entry| {
+            let kind = match entry.kind.as_str() {
+                "ISSUE" => StoreCreditEntryKind::Issue,
+                "REDEEM" => StoreCreditEntryKind::Redeem,
+                other => {
+                    warn!(credit_id = %credit.id, kind = %other, "Unknown store credit entry kind");
+                    return None;
+                }
+            };
+            Some(titan_core::StoreCreditEntry {
+                id: entry.id.clone(),
+                account_id: credit.id.clone(),
+                kind,
+                amount_cents: entry.amount.as_ref().map(|m| m.cents).unwrap_or(0),
+                sale_id: non_empty(&entry.sale_id),
+                device_id: entry.device_id.clone(),
+                created_at: parse(&entry.created_at)?,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(titan_core::StoreCreditDocument {
+        account: titan_core::StoreCreditAccount {
+            id: credit.id.clone(),
+            tenant_id: tenant_id.to_string(),
+            credit_number: credit.credit_number.clone(),
+            customer_name: credit.customer_name.clone(),
+            customer_phone: non_empty(&credit.customer_phone),
+            created_at: parse(&credit.created_at)?,
+            updated_at: parse(&credit.updated_at)?,
+            sync_version: credit.version,
+        },
+        entries,
+    })
+}
+
+/// Convert a coupon with its redemptions to a proto::SyncEntity.
+///
+/// The cloud only takes the redemptions from it; the definition is
+/// written in the back office.
+///
+/// # Field Mapping
+/// ```text
+/// titan_core::CouponDocument        →  proto::Coupon
+/// ──────────────────────────────────────────────────────
+/// description (None)                →  "" (empty)
+/// kind (enum)                       →  kind (PERCENT, AMOUNT)
+/// starts_at / expires_at (None)     →  unset
+/// redemptions[].discount_cents      →  redemptions[].discount.cents
+/// sync_version                      →  version
+/// ```
+pub fn coupon_to_entity(doc: &titan_core::CouponDocument) -> SyncEntity {
+    let coupon = &doc.coupon;
+    let ts = |dt: &chrono::DateTime<chrono::Utc>| Timestamp {
+        value: dt.to_rfc3339(),
+    };
+
+    SyncEntity {
+        entity_id: coupon.id.clone(),
+        entity_type: "COUPON".to_string(),
+        device_sequence: coupon.sync_version,
+        correlation_id: String::new(),
+        trace_context: String::new(),
+        created_at: Some(ts(&coupon.updated_at)),
+        data: Some(sync_entity::Data::Coupon(Coupon {
+            id: coupon.id.clone(),
+            code: coupon.code.clone(),
+            description: coupon.description.clone().unwrap_or_default(),
+            kind: coupon.kind.as_str().to_uppercase(),
+            value: coupon.value,
+            min_subtotal_cents: coupon.min_subtotal_cents,
+            product_ids: coupon.product_ids.clone(),
+            starts_at: coupon.starts_at.as_ref().map(ts),
+            expires_at: coupon.expires_at.as_ref().map(ts),
+            max_uses: coupon.max_uses,
+            active: coupon.active,
+            redemptions: doc
+                .redemptions
+                .iter()
+                .map(|redemption| CouponRedemption {
+                    id: redemption.id.clone(),
+                    sale_id: redemption.sale_id.clone(),
+                    store_id: redemption.store_id.clone(),
+                    device_id: redemption.device_id.clone(),
+                    discount: Some(Money {
+                        cents: redemption.discount_cents,
+                        currency: "USD".to_string(),
+                    }),
+                    redeemed_at: Some(ts(&redemption.redeemed_at)),
+                })
+                .collect(),
+            created_at: Some(ts(&coupon.created_at)),
+            updated_at: Some(ts(&coupon.updated_at)),
+            version: coupon.sync_version,
+        })),
+    }
+}
+
+/// Convert a coupon downloaded from the cloud into a document for
+/// [`titan_db::CouponRepository::upsert_from_sync`].
+///
+/// Returns `None` if the kind or a timestamp cannot be parsed.
+pub fn coupon_from_proto(coupon: &Coupon, tenant_id: &str) -> Option<titan_core::CouponDocument> {
+    let parse = |ts: &Timestamp| -> Option<chrono::DateTime<chrono::Utc>> {
+        chrono::DateTime::parse_from_rfc3339(&ts.value)
+            .ok()
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+    };
+    let required = |ts: &Option<Timestamp>| ts.as_ref().and_then(parse);
+    let optional = |ts: &Option<Timestamp>| match ts {
+        Some(ts) => parse(ts).map(Some),
+        None => Some(None),
+    };
+
+    let Some(kind) = titan_core::CouponKind::parse(&coupon.kind.to_lowercase()) else {
+        warn!(coupon_id = %coupon.id, kind = %coupon.kind, "Unknown coupon kind");
+        return None;
+    };
+
+    let redemptions = coupon
+        .redemptions
+        .iter()
+        .map(|redemption| {
+            Some(titan_core::CouponRedemption {
+                id: redemption.id.clone(),
+                coupon_id: coupon.id.clone(),
+                sale_id: redemption.sale_id.clone(),
+                store_id: redemption.store_id.clone(),
+                device_id: redemption.device_id.clone(),
+                discount_cents: redemption.discount.as_ref().map(|m| m.cents).unwrap_or(0),
+                redeemed_at: required(&redemption.redeemed_at)?,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(titan_core::CouponDocument {
+        coupon: titan_core::Coupon {
+            id: coupon.id.clone(),
+            tenant_id: tenant_id.to_string(),
+            code: coupon.code.clone(),
+            description: (!coupon.description.is_empty()).then(|| coupon.description.clone()),
+            kind,
+            value: coupon.value,
+            min_subtotal_cents: coupon.min_subtotal_cents,
+            product_ids: coupon.product_ids.clone(),
+            starts_at: optional(&coupon.starts_at)?,
+            expires_at: optional(&coupon.expires_at)?,
+            max_uses: coupon.max_uses,
+            active: coupon.active,
+            created_at: required(&coupon.created_at)?,
+            updated_at: required(&coupon.updated_at)?,
+            sync_version: coupon.version,
+        },
+        redemptions,
+    })
+}
+
+/// Convert a loyalty account with its ledger to a proto::SyncEntity.
+///
+/// # Field Mapping
+/// ```text
+/// titan_core::LoyaltyDocument   →  proto::LoyaltyAccount
+/// ─────────────────────────────────────────────────────
+/// entries[].kind (enum)         →  entries[].kind (EARN, REWARD, REDEEM)
+/// entries[].reward_cents        →  entries[].reward.cents
+/// entries[].sale_id (None)      →  "" (empty)
+/// sync_version                  →  version
+/// ```
+pub fn loyalty_to_entity(doc: &titan_core::LoyaltyDocument) -> SyncEntity {
+    let account = &doc.account;
+
+    SyncEntity {
+        entity_id: account.id.clone(),
+        entity_type: "LOYALTY".to_string(),
+        device_sequence: account.sync_version,
+        correlation_id: String::new(),
+        trace_context: String::new(),
+        created_at: Some(Timestamp {
+            value: account.updated_at.to_rfc3339(),
+        }),
+        data: Some(sync_entity::Data::Loyalty(loyalty_proto(doc))),
+    }
+}
+
+fn loyalty_proto(doc: &titan_core::LoyaltyDocument) -> LoyaltyAccount {
+    let account = &doc.account;
+    let ts = |dt: &chrono::DateTime<chrono::Utc>| Timestamp {
+        value: dt.to_rfc3339(),
+    };
+
+    LoyaltyAccount {
+        id: account.id.clone(),
+        member_number: account.member_number.clone(),
+        entries: doc.entries.iter().map(loyalty_entry_proto).collect(),
+        created_at: Some(ts(&account.created_at)),
+        updated_at: Some(ts(&account.updated_at)),
+        version: account.sync_version,
+    }
+}
+
+fn loyalty_entry_proto(entry: &titan_core::LoyaltyEntry) -> LoyaltyEntry {
+    LoyaltyEntry {
+        id: entry.id.clone(),
+        kind: entry.kind.as_str().to_uppercase(),
+        points: entry.points,
+        reward: Some(Money {
+            cents: entry.reward_cents,
+            currency: "USD".to_string(),
+        }),
+        sale_id: entry.sale_id.clone().unwrap_or_default(),
+        device_id: entry.device_id.clone(),
+        created_at: Some(Timestamp {
+            value: entry.created_at.to_rfc3339(),
+        }),
+    }
+}
+
+/// Convert a loyalty account downloaded from the cloud back into a
+/// document for [`crate::inbound`].
+///
+/// Returns `None` if an entry kind or a timestamp cannot be parsed.
+pub fn loyalty_from_proto(
+    account: &LoyaltyAccount,
+    tenant_id: &str,
+) -> Option<titan_core::LoyaltyDocument> {
+    let parse = |ts: &Option<Timestamp>| -> Option<chrono::DateTime<chrono::Utc>> {
+        let ts = ts.as_ref()?;
+        chrono::DateTime::parse_from_rfc3339(&ts.value)
+            .ok()
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+    };
+
+    let entries = account
+        .entries
+        .iter()
+        .map(|entry| {
+            let Some(kind) = titan_core::LoyaltyEntryKind::parse(&entry.kind) else {
+                warn!(account_id = %account.id, kind = %entry.kind, "Unknown loyalty entry kind");
+                return None;
+            };
+            Some(titan_core::LoyaltyEntry {
+                id: entry.id.clone(),
+                account_id: account.id.clone(),
+                kind,
+                points: entry.points,
+                reward_cents: entry.reward.as_ref().map(|m| m.cents).unwrap_or(0),
+                sale_id: (!entry.sale_id.is_empty()).then(|| entry.sale_id.clone()),
+                device_id: entry.device_id.clone(),
+                created_at: parse(&entry.created_at)?,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(titan_core::LoyaltyDocument {
+        account: titan_core::LoyaltyAccount {
+            id: account.id.clone(),
+            tenant_id: tenant_id.to_string(),
+            member_number: account.member_number.clone(),
+            created_at: parse(&account.created_at)?,
+            updated_at: parse(&account.updated_at)?,
+            sync_version: account.version,
+        },
+        entries,
+    })
+}
+
+/// Convert a customer erasure request to a proto::SyncEntity.
+///
+/// # Field Mapping
+/// ```text
+/// titan_core::ErasureRequest  →  proto::CustomerErasure
+/// ─────────────────────────────────────────────────────
+/// subject.* (None)            →  "" (empty)
+/// reason (None)               →  "" (empty)
+/// tenant_id                   →  (from the token)
+/// ```
+pub fn erasure_to_entity(request: &titan_core::ErasureRequest) -> SyncEntity {
+    let requested_at = Timestamp {
+        value: request.requested_at.to_rfc3339(),
+    };
+    let subject = &request.subject;
+
+    SyncEntity {
+        entity_id: request.id.clone(),
+        entity_type: "CUSTOMER_ERASURE".to_string(),
+        device_sequence: 0,
+        correlation_id: String::new(),
+        trace_context: String::new(),
+        created_at: Some(requested_at.clone()),
+        data: Some(sync_entity::Data::CustomerErasure(CustomerErasure {
+            id: request.id.clone(),
+            business_customer_id: subject.business_customer_id.clone().unwrap_or_default(),
+            phone: subject.phone.clone().unwrap_or_default(),
+            email: subject.email.clone().unwrap_or_default(),
+            reason: request.reason.clone().unwrap_or_default(),
+            requested_by: request.requested_by.clone(),
+            requested_at: Some(requested_at),
+        })),
+    }
+}
+
+/// Convert a waste record to a proto::SyncEntity: a negative inventory
+/// delta tagged with the waste reason.
+///
+/// # Field Mapping
+/// ```text
+/// titan_core::WasteRecord  →  proto::InventoryDelta
+/// ───────────────────────────────────────────────────
+/// quantity                 →  delta (negated)
+/// reason (expiry)          →  reason (EXPIRY)
+/// id                       →  id, reference_id
+/// quantity × unit price    →  value_cents
+/// store_id                 →  (from the token)
+/// ```
+pub fn waste_to_entity(record: &titan_core::WasteRecord) -> SyncEntity {
+    let recorded_at = Timestamp {
+        value: record.recorded_at.to_rfc3339(),
+    };
+
+    SyncEntity {
+        entity_id: record.id.clone(),
+        entity_type: "INVENTORY_DELTA".to_string(),
+        device_sequence: 0,
+        correlation_id: String::new(),
+        trace_context: String::new(),
+        created_at: Some(recorded_at.clone()),
+        data: Some(sync_entity::Data::InventoryDelta(InventoryDelta {
+            id: record.id.clone(),
+            store_id: String::new(),
+            device_id: record.device_id.clone(),
+            product_id: record.product_id.clone(),
+            delta: -(record.quantity as i32),
+            reason: record.reason.cloud_reason().to_string(),
+            reference_id: record.id.clone(),
+            value_cents: record.value_cents(),
+            created_at: Some(recorded_at),
+        })),
+    }
+}
+
+/// Convert a supplier and its price list to a proto::SyncEntity.
+///
+/// # Field Mapping
+/// ```text
+/// titan_core::SupplierDocument  →  proto::Supplier
+/// ───────────────────────────────────────────────────
+/// contact_name / phone / email  →  same, "" when None
+/// items[].supplier_sku (None)   →  items[].supplier_sku ""
+/// items[].cost_cents            →  items[].cost.cents
+/// sync_version                  →  version
+/// ```
+pub fn supplier_to_entity(doc: &titan_core::SupplierDocument) -> SyncEntity {
+    let supplier = &doc.supplier;
+    let ts = |dt: &chrono::DateTime<chrono::Utc>| Timestamp {
+        value: dt.to_rfc3339(),
+    };
+
+    SyncEntity {
+        entity_id: supplier.id.clone(),
+        entity_type: "SUPPLIER".to_string(),
+        device_sequence: supplier.sync_version,
+        correlation_id: String::new(),
+        trace_context: String::new(),
+        created_at: Some(ts(&supplier.updated_at)),
+        data: Some(sync_entity::Data::Supplier(Supplier {
+            id: supplier.id.clone(),
+            name: supplier.name.clone(),
+            contact_name: supplier.contact_name.clone().unwrap_or_default(),
+            phone: supplier.phone.clone().unwrap_or_default(),
+            email: supplier.email.clone().unwrap_or_default(),
+            is_active: supplier.is_active,
+            items: doc
+                .items
+                .iter()
+                .map(|item| SupplierItem {
+                    product_id: item.product_id.clone(),
+                    supplier_sku: item.supplier_sku.clone().unwrap_or_default(),
+                    cost: Some(Money {
+                        cents: item.cost_cents,
+                        currency: "USD".to_string(),
+                    }),
+                })
+                .collect(),
+            created_at: Some(ts(&supplier.created_at)),
+            updated_at: Some(ts(&supplier.updated_at)),
+            version: supplier.sync_version,
+        })),
+    }
+}
+
+/// Convert a customer erasure downloaded from the cloud back into a
+/// request for [`crate::inbound`].
+///
+/// Returns `None` if the timestamp cannot be parsed or no identifier is
+/// set.
+pub fn erasure_from_proto(
+    erasure: &CustomerErasure,
+    tenant_id: &str,
+) -> Option<titan_core::ErasureRequest> {
+    let requested_at = erasure.requested_at.as_ref()?;
+    let requested_at = chrono::DateTime::parse_from_rfc3339(&requested_at.value)
+        .ok()?
+        .with_timezone(&chrono::Utc);
+    let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+
+    let subject = titan_core::ErasureSubject {
+        business_customer_id: non_empty(&erasure.business_customer_id),
+        phone: non_empty(&erasure.phone),
+        email: non_empty(&erasure.email),
+    };
+    titan_core::ErasureRequest::new(
+        erasure.id.clone(),
+        tenant_id,
+        &subject,
+        non_empty(&erasure.reason),
+        &erasure.requested_by,
+        requested_at,
+    )
+    .map_err(|e| warn!(erasure_id = %erasure.id, error = %e, "Invalid customer erasure"))
+    .ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -514,4 +1610,459 @@ mod tests {
         assert_eq!(config.cloud_url, "http://localhost:50051");
         assert_eq!(config.batch_size, 100);
     }
+
+    #[test]
+    fn test_batch_poll_delay_backs_off_to_ten_seconds() {
+        assert_eq!(batch_poll_delay(0), Duration::from_secs(1));
+        assert_eq!(batch_poll_delay(1), Duration::from_secs(2));
+        assert_eq!(batch_poll_delay(3), Duration::from_secs(8));
+        assert_eq!(batch_poll_delay(4), Duration::from_secs(10));
+        assert_eq!(batch_poll_delay(40), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_sale_item_entity_carries_lot() {
+        let now = chrono::Utc::now();
+        let item = titan_core::SaleItem {
+            id: "si-1".to_string(),
+            sale_id: "s-1".to_string(),
+            product_id: "p-1".to_string(),
+            sku_snapshot: "MED-1".to_string(),
+            name_snapshot: "Medicine".to_string(),
+            unit_price_cents: 500,
+            quantity: 2,
+            line_total_cents: 1000,
+            tax_cents: 0,
+            discount_cents: 0,
+            base_price_cents: None,
+            tax_rate_bps: None,
+            created_at: now,
+        };
+        let lot = |item_id: &str, code: &str| titan_core::SaleItemTracking {
+            id: format!("t-{}", code),
+            sale_id: "s-1".to_string(),
+            sale_item_id: item_id.to_string(),
+            product_id: "p-1".to_string(),
+            kind: titan_core::ItemTracking::Lot,
+            code: code.to_string(),
+            created_at: now,
+        };
+
+        let entity = sale_item_to_entity(&item, &[lot("si-1", "LOT-7"), lot("si-2", "LOT-9")]);
+        let Some(sync_entity::Data::SaleItem(proto)) = entity.data else {
+            panic!("expected sale item");
+        };
+        assert_eq!(proto.tracking_kind, "lot");
+        assert_eq!(proto.tracking_codes, vec!["LOT-7".to_string()]);
+
+        let entity = sale_item_to_entity(&item, &[]);
+        let Some(sync_entity::Data::SaleItem(proto)) = entity.data else {
+            panic!("expected sale item");
+        };
+        assert!(proto.tracking_kind.is_empty());
+    }
+
+    #[test]
+    fn test_sale_round_trip() {
+        let now = chrono::Utc::now();
+        let sale = titan_core::Sale {
+            id: "s-1".to_string(),
+            tenant_id: "t-1".to_string(),
+            receipt_number: "R01-0042".to_string(),
+            status: titan_core::SaleStatus::Completed,
+            subtotal_cents: 1000,
+            tax_cents: 83,
+            discount_cents: 0,
+            total_cents: 1083,
+            user_id: "u-1".to_string(),
+            device_id: "pos-1".to_string(),
+            station_number: Some(1),
+            notes: None,
+            created_at: now,
+            updated_at: now,
+            completed_at: Some(now),
+            sync_version: 3,
+        };
+        let item = titan_core::SaleItem {
+            id: "si-1".to_string(),
+            sale_id: "s-1".to_string(),
+            product_id: "p-1".to_string(),
+            sku_snapshot: "COLA".to_string(),
+            name_snapshot: "Cola".to_string(),
+            unit_price_cents: 500,
+            quantity: 2,
+            line_total_cents: 1000,
+            tax_cents: 83,
+            discount_cents: 0,
+            base_price_cents: None,
+            tax_rate_bps: Some(825),
+            created_at: now,
+        };
+        let payment = titan_core::Payment {
+            id: "pay-1".to_string(),
+            sale_id: "s-1".to_string(),
+            method: titan_core::PaymentMethod::Cash,
+            amount_cents: 1083,
+            tendered_cents: Some(2000),
+            change_cents: Some(917),
+            reference: None,
+            created_at: now,
+        };
+
+        // What the cloud sends back: the sale with its lines, and payments
+        let Some(sync_entity::Data::Sale(mut proto_sale)) = sale_to_entity(&sale).data else {
+            panic!("expected a sale");
+        };
+        let Some(sync_entity::Data::SaleItem(proto_item)) = sale_item_to_entity(&item, &[]).data else {
+            panic!("expected a sale item");
+        };
+        let Some(sync_entity::Data::Payment(proto_payment)) = payment_to_entity(&payment).data else {
+            panic!("expected a payment");
+        };
+        proto_sale.items.push(proto_item);
+        let response = GetSaleResponse {
+            sale: Some(proto_sale),
+            payments: vec![proto_payment],
+        };
+
+        let doc = sale_from_proto(&response, "t-1").unwrap();
+        assert_eq!(doc.sale.receipt_number, "R01-0042");
+        assert_eq!(doc.sale.status, titan_core::SaleStatus::Completed);
+        assert_eq!(doc.sale.total_cents, 1083);
+        assert_eq!(doc.sale.station_number, Some(1));
+        assert_eq!(doc.items.len(), 1);
+        assert_eq!(doc.items[0].tax_rate_bps, Some(825));
+        assert_eq!(doc.payments[0].method, titan_core::PaymentMethod::Cash);
+        assert_eq!(doc.payments[0].change_cents, Some(917));
+
+        assert!(sale_from_proto(&GetSaleResponse::default(), "t-1").is_none());
+    }
+
+    #[test]
+    fn test_transfer_round_trip() {
+        let now = chrono::Utc::now();
+        let doc = titan_core::StoreTransferDocument {
+            transfer: titan_core::StoreTransfer {
+                id: "t-1".to_string(),
+                tenant_id: "tenant".to_string(),
+                transfer_number: "T-0001".to_string(),
+                from_store_id: "store-a".to_string(),
+                to_store_id: "store-b".to_string(),
+                status: titan_core::TransferStatus::InTransit,
+                notes: None,
+                created_by: "u-1".to_string(),
+                received_by: None,
+                created_at: now,
+                updated_at: now,
+                received_at: None,
+                cancelled_at: None,
+                sync_version: 1,
+            },
+            items: vec![titan_core::StoreTransferItem {
+                id: "ti-1".to_string(),
+                transfer_id: "t-1".to_string(),
+                product_id: "p-1".to_string(),
+                sku_snapshot: "COKE-330".to_string(),
+                name_snapshot: "Coke".to_string(),
+                quantity_sent: 24,
+                quantity_received: None,
+            }],
+        };
+
+        let entity = transfer_to_entity(&doc);
+        assert_eq!(entity.entity_type, "STORE_TRANSFER");
+        let Some(sync_entity::Data::StoreTransfer(proto)) = entity.data else {
+            panic!("expected store transfer");
+        };
+        assert_eq!(proto.status, "IN_TRANSIT");
+
+        let back = transfer_from_proto(&proto, "tenant").unwrap();
+        assert_eq!(back.transfer.to_store_id, "store-b");
+        assert_eq!(back.transfer.status, titan_core::TransferStatus::InTransit);
+        assert_eq!(back.items[0].quantity_sent, 24);
+        assert_eq!(back.items[0].quantity_received, None);
+        assert!(back.transfer.notes.is_none());
+    }
+
+    #[test]
+    fn test_product_from_proto_keeps_price_provenance() {
+        let now = Timestamp {
+            value: chrono::Utc::now().to_rfc3339(),
+        };
+        let usd = |cents| Money {
+            cents,
+            currency: "USD".to_string(),
+        };
+        let mut proto = Product {
+            id: "p-1".to_string(),
+            sku: "COKE-330".to_string(),
+            name: "Coke".to_string(),
+            price: Some(usd(229)),
+            base_price: Some(usd(250)),
+            tax_rate_bps: 825,
+            is_active: true,
+            created_at: Some(now.clone()),
+            updated_at: Some(now),
+            version: 3,
+            ..Default::default()
+        };
+
+        let product = product_from_proto(&proto, "tenant").unwrap();
+        assert_eq!(product.price_cents, 229);
+        assert_eq!(product.base_price_cents, Some(250));
+        assert!(product.has_price_override());
+        assert!(product.barcode.is_none());
+        assert_eq!(product.sync_version, 3);
+
+        proto.base_price = None;
+        let product = product_from_proto(&proto, "tenant").unwrap();
+        assert!(!product.has_price_override());
+
+        proto.created_at = None;
+        assert!(product_from_proto(&proto, "tenant").is_none());
+    }
+
+    #[test]
+    fn test_attributes_from_proto() {
+        let mut proto = Product {
+            id: "p-1".to_string(),
+            updated_at: Some(Timestamp {
+                value: "2024-01-15T10:30:00Z".to_string(),
+            }),
+            version: 4,
+            ..Default::default()
+        };
+        proto.attributes.insert("vintage".to_string(), "2019".to_string());
+        proto.attributes.insert("color".to_string(), "red".to_string());
+        proto.attributes.insert("organic".to_string(), String::new());
+
+        let doc = attributes_from_proto(&proto).unwrap();
+        let keys: Vec<&str> = doc.attributes.iter().map(|a| a.key.as_str()).collect();
+        assert_eq!(keys, vec!["color", "organic", "vintage"]);
+        assert_eq!(doc.sync_version, 4);
+
+        proto.attributes.insert("shelf life".to_string(), "1y".to_string());
+        assert!(attributes_from_proto(&proto).is_none());
+    }
+
+    #[test]
+    fn test_store_credit_round_trip() {
+        let now = chrono::Utc::now();
+        let entry = |id: &str, kind, amount_cents| titan_core::StoreCreditEntry {
+            id: id.to_string(),
+            account_id: "sc-1".to_string(),
+            kind,
+            amount_cents,
+            sale_id: None,
+            device_id: "pos-01".to_string(),
+            created_at: now,
+        };
+        let doc = titan_core::StoreCreditDocument {
+            account: titan_core::StoreCreditAccount {
+                id: "sc-1".to_string(),
+                tenant_id: "tenant".to_string(),
+                credit_number: "SC-0001".to_string(),
+                customer_name: "Ada".to_string(),
+                customer_phone: None,
+                created_at: now,
+                updated_at: now,
+                sync_version: 2,
+            },
+            entries: vec![
+                entry("e-1", titan_core::StoreCreditEntryKind::Issue, 2500),
+                entry("e-2", titan_core::StoreCreditEntryKind::Redeem, -1000),
+            ],
+        };
+
+        let entity = store_credit_to_entity(&doc);
+        assert_eq!(entity.entity_type, "STORE_CREDIT");
+        let Some(sync_entity::Data::StoreCredit(proto)) = entity.data else {
+            panic!("expected store credit");
+        };
+        assert_eq!(proto.entries[1].kind, "REDEEM");
+
+        let back = store_credit_from_proto(&proto, "tenant").unwrap();
+        assert_eq!(back.balance_cents(), 1500);
+        assert_eq!(back.account.sync_version, 2);
+        assert!(back.account.customer_phone.is_none());
+        assert!(back.entries[0].sale_id.is_none());
+    }
+
+    #[test]
+    fn test_coupon_round_trip() {
+        let now = chrono::Utc::now();
+        let doc = titan_core::CouponDocument {
+            coupon: titan_core::Coupon {
+                id: "cp-1".to_string(),
+                tenant_id: "tenant".to_string(),
+                code: "SAVE10".to_string(),
+                description: None,
+                kind: titan_core::CouponKind::Percent,
+                value: 1000,
+                min_subtotal_cents: Some(2000),
+                product_ids: Vec::new(),
+                starts_at: None,
+                expires_at: Some(now),
+                max_uses: Some(1),
+                active: true,
+                created_at: now,
+                updated_at: now,
+                sync_version: 3,
+            },
+            redemptions: vec![titan_core::CouponRedemption {
+                id: "r-1".to_string(),
+                coupon_id: "cp-1".to_string(),
+                sale_id: "s-1".to_string(),
+                store_id: String::new(),
+                device_id: "pos-01".to_string(),
+                discount_cents: 250,
+                redeemed_at: now,
+            }],
+        };
+
+        let entity = coupon_to_entity(&doc);
+        assert_eq!(entity.entity_type, "COUPON");
+        let Some(sync_entity::Data::Coupon(proto)) = entity.data else {
+            panic!("expected coupon");
+        };
+        assert_eq!(proto.kind, "PERCENT");
+        assert!(proto.starts_at.is_none());
+
+        let back = coupon_from_proto(&proto, "tenant").unwrap();
+        assert_eq!(back.coupon.kind, titan_core::CouponKind::Percent);
+        assert_eq!(back.coupon.max_uses, Some(1));
+        assert!(back.coupon.starts_at.is_none());
+        assert!(back.coupon.expires_at.is_some());
+        assert!(back.coupon.description.is_none());
+        assert_eq!(back.uses(), 1);
+        assert_eq!(back.redemptions[0].discount_cents, 250);
+    }
+
+    #[test]
+    fn test_loyalty_round_trip() {
+        let now = chrono::Utc::now();
+        let entry = |id: &str, kind, points, reward_cents, sale_id: Option<&str>| {
+            titan_core::LoyaltyEntry {
+                id: id.to_string(),
+                account_id: "la-1".to_string(),
+                kind,
+                points,
+                reward_cents,
+                sale_id: sale_id.map(str::to_string),
+                device_id: "pos-01".to_string(),
+                created_at: now,
+            }
+        };
+        let doc = titan_core::LoyaltyDocument {
+            account: titan_core::LoyaltyAccount {
+                id: "la-1".to_string(),
+                tenant_id: "tenant".to_string(),
+                member_number: "LM-1".to_string(),
+                created_at: now,
+                updated_at: now,
+                sync_version: 3,
+            },
+            entries: vec![
+                entry("la-1:earn:s-1", titan_core::LoyaltyEntryKind::Earn, 520, 0, Some("s-1")),
+                entry("la-1:reward:1", titan_core::LoyaltyEntryKind::Reward, -500, 500, None),
+                entry("r-1", titan_core::LoyaltyEntryKind::Redeem, 0, -200, Some("s-2")),
+            ],
+        };
+
+        let entity = loyalty_to_entity(&doc);
+        assert_eq!(entity.entity_type, "LOYALTY");
+        let Some(sync_entity::Data::Loyalty(proto)) = entity.data else {
+            panic!("expected loyalty");
+        };
+        assert_eq!(proto.entries[1].kind, "REWARD");
+        assert_eq!(proto.entries[1].sale_id, "");
+
+        let back = loyalty_from_proto(&proto, "tenant").unwrap();
+        assert_eq!(back.points_balance(), 20);
+        assert_eq!(back.reward_balance_cents(), 300);
+        assert_eq!(back.entries[2].kind, titan_core::LoyaltyEntryKind::Redeem);
+        assert!(back.entries[1].sale_id.is_none());
+        assert_eq!(back.account.sync_version, 3);
+    }
+
+    #[test]
+    fn test_erasure_round_trip() {
+        let subject = titan_core::ErasureSubject {
+            phone: Some("555-0100".to_string()),
+            ..Default::default()
+        };
+        let request = titan_core::ErasureRequest::new(
+            "er-1".to_string(),
+            "tenant",
+            &subject,
+            None,
+            "u-1",
+            chrono::Utc::now(),
+        )
+        .unwrap();
+
+        let entity = erasure_to_entity(&request);
+        assert_eq!(entity.entity_type, "CUSTOMER_ERASURE");
+        let Some(sync_entity::Data::CustomerErasure(proto)) = entity.data else {
+            panic!("expected customer erasure");
+        };
+        assert_eq!(proto.email, "");
+
+        let back = erasure_from_proto(&proto, "tenant").unwrap();
+        assert_eq!(back, request);
+    }
+
+    #[test]
+    fn test_waste_entity_is_negative_delta() {
+        let record = titan_core::WasteRecord {
+            id: "w-1".to_string(),
+            product_id: "p-1".to_string(),
+            quantity: 4,
+            reason: titan_core::WasteReason::Expiry,
+            unit_price_cents: 129,
+            note: None,
+            recorded_by: "default".to_string(),
+            device_id: "pos-01".to_string(),
+            recorded_at: chrono::Utc::now(),
+        };
+
+        let entity = waste_to_entity(&record);
+        assert_eq!(entity.entity_type, "INVENTORY_DELTA");
+        let Some(sync_entity::Data::InventoryDelta(proto)) = entity.data else {
+            panic!("expected inventory delta");
+        };
+        assert_eq!(proto.delta, -4);
+        assert_eq!(proto.reason, "EXPIRY");
+        assert_eq!(proto.value_cents, 516);
+        assert_eq!(proto.reference_id, "w-1");
+    }
+
+    #[test]
+    fn test_supplier_entity() {
+        let now = chrono::Utc::now();
+        let doc = titan_core::SupplierDocument {
+            supplier: titan_core::Supplier {
+                id: "sup-1".to_string(),
+                name: "ACME Beverages".to_string(),
+                contact_name: None,
+                phone: Some("555-0100".to_string()),
+                email: None,
+                is_active: true,
+                created_at: now,
+                updated_at: now,
+                sync_version: 3,
+            },
+            items: vec![titan_core::SupplierItem::new("p-1", None, 42)],
+        };
+
+        let entity = supplier_to_entity(&doc);
+        assert_eq!(entity.entity_type, "SUPPLIER");
+        let Some(sync_entity::Data::Supplier(proto)) = entity.data else {
+            panic!("expected supplier");
+        };
+        assert_eq!(proto.version, 3);
+        assert_eq!(proto.contact_name, "");
+        assert_eq!(proto.items[0].supplier_sku, "");
+        assert_eq!(proto.items[0].cost.as_ref().unwrap().cents, 42);
+    }
 }
@@ -0,0 +1,288 @@
+//! # Protocol Version Negotiation
+//!
+//! Terminals and the hub are upgraded one at a time, so a store runs mixed
+//! versions for a while. Each side supports a range of protocol versions;
+//! the handshake settles on the highest version both speak.
+//!
+//! ## Negotiation
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                      Version Negotiation                                │
+//! │                                                                         │
+//! │  SECONDARY ───► Hello { protocol_version: 3, min_protocol_version: 2 } │
+//! │                                                                         │
+//! │  Hub (supports 2..=3):                                                  │
+//! │    negotiated = min(3, 3) = 3                                           │
+//! │    negotiated >= max(2, 2)?                                             │
+//! │      yes ──► Welcome { protocol_version: 3 }                            │
+//! │      no  ──► Error { code: UPGRADE_REQUIRED, message } and close        │
+//! │                                                                         │
+//! │  Broadcasts to the client are down-converted to the negotiated          │
+//! │  version (see `downgrade`).                                             │
+//! │                                                                         │
+//! │  SECONDARY on UPGRADE_REQUIRED, or a Welcome outside its range:         │
+//! │    stops reconnecting and raises an upgrade-required event              │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Version History
+//! - v2: store credit redemption, device priority in Hello
+//! - v3: version negotiation, product field patches (`operation: "patch"`)
+//! - v4: full resync (`ResyncRequest`, `operation: "snapshot"`)
+//! - v5: staged app updates (`UpdateSlotRequest`, see `update_rollout`)
+//! - v6: day-end reports (`DayEndReport`, served by `admin_api`)
+//! - v7: cloud-bound entries held until uploaded (`BatchAck.held_ids`,
+//!   `CloudAck`, see `hub_uplink`)
+//! - v8: hub and cloud ack levels (`BatchAck.uploaded_ids`)
+//! - v9: sale lookup by receipt number (`SaleLookup`, hub copy then cloud)
+//! - v10: loyalty reward redemption (`LoyaltyRedeem`, cloud ledger then hub)
+//! - v11: coupon redemption counted at the hub (`CouponRedeem`)
+
+use crate::protocol::{SyncMessage, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
+
+/// Version assumed for peers that predate negotiation (no
+/// `min_protocol_version` in Hello, no `protocol_version` in Welcome).
+pub const LEGACY_PROTOCOL_VERSION: u32 = 2;
+
+/// First version whose hubs answer `ResyncRequest`.
+pub const FULL_RESYNC_VERSION: u32 = 4;
+
+/// First version whose hubs hand out update slots.
+pub const STAGED_UPDATE_VERSION: u32 = 5;
+
+/// First version whose hubs keep day-end reports.
+pub const DAY_END_REPORT_VERSION: u32 = 6;
+
+/// First version whose terminals hold entries until the hub relays the
+/// cloud's ack.
+pub const HELD_UPLOAD_VERSION: u32 = 7;
+
+/// First version whose terminals tell a hub ack from a cloud ack.
+pub const ACK_LEVEL_VERSION: u32 = 8;
+
+/// First version whose hubs look up sales terminals no longer have.
+pub const SALE_LOOKUP_VERSION: u32 = 9;
+
+/// First version whose hubs verify loyalty reward redemptions.
+pub const LOYALTY_REDEEM_VERSION: u32 = 10;
+
+/// First version whose hubs count coupon uses for the whole store.
+pub const COUPON_REDEEM_VERSION: u32 = 11;
+
+// =============================================================================
+// Negotiation
+// =============================================================================
+
+/// Two protocol version ranges with no version in common.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VersionMismatch {
+    /// Range this build supports.
+    pub ours: (u32, u32),
+    /// Range the peer supports.
+    pub theirs: (u32, u32),
+}
+
+impl VersionMismatch {
+    /// Whether the peer is the side that needs upgrading.
+    pub fn peer_is_older(&self) -> bool {
+        self.theirs.1 < self.ours.0
+    }
+
+    /// User-facing message naming the device that has to be upgraded
+    /// (`older` is "terminal" or "hub").
+    pub fn message(&self, older: &str) -> String {
+        let (older_max, newer_min) = if self.peer_is_older() {
+            (self.theirs.1, self.ours.0)
+        } else {
+            (self.ours.1, self.theirs.0)
+        };
+        format!(
+            "Titan POS update required: the {} speaks sync protocol v{} but v{} or newer is needed",
+            older, older_max, newer_min
+        )
+    }
+}
+
+impl std::fmt::Display for VersionMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "no common protocol version (ours v{}..=v{}, peer v{}..=v{})",
+            self.ours.0, self.ours.1, self.theirs.0, self.theirs.1
+        )
+    }
+}
+
+/// Picks the highest version both sides support.
+///
+/// `peer_min` is `None` for peers that predate negotiation; they speak
+/// only `peer_max`.
+pub fn negotiate(peer_max: u32, peer_min: Option<u32>) -> Result<u32, VersionMismatch> {
+    let ours = (MIN_PROTOCOL_VERSION, PROTOCOL_VERSION);
+    let theirs = (peer_min.unwrap_or(peer_max).min(peer_max), peer_max);
+
+    let negotiated = ours.1.min(theirs.1);
+    if negotiated < ours.0.max(theirs.0) {
+        return Err(VersionMismatch { ours, theirs });
+    }
+    Ok(negotiated)
+}
+
+// =============================================================================
+// Down-Conversion
+// =============================================================================
+
+/// Converts a message for a peer on an older `version`, or returns `None`
+/// if the peer cannot use it at all.
+pub fn downgrade(message: SyncMessage, version: u32) -> Option<SyncMessage> {
+    match message {
+        // Field patches arrived in v3; older terminals pick the product up
+        // from its next full upsert
+        SyncMessage::EntityUpdate(update) if version < 3 && update.operation == "patch" => None,
+        // Snapshots only go to terminals that asked for a resync, which
+        // takes v4; anything older has no use for them
+        SyncMessage::EntityUpdate(update)
+            if version < FULL_RESYNC_VERSION && update.operation == "snapshot" =>
+        {
+            None
+        }
+        SyncMessage::ResyncComplete { .. } if version < FULL_RESYNC_VERSION => None,
+        // Older terminals know one ack level, and before v7 nothing of
+        // holding: they keep held entries pending and send them again
+        // until the hub acks them as uploaded
+        SyncMessage::BatchAck(mut ack) if version < ACK_LEVEL_VERSION => {
+            ack.acked_ids.append(&mut ack.uploaded_ids);
+            if version < HELD_UPLOAD_VERSION {
+                ack.held_ids.clear();
+                ack.held_by.clear();
+            }
+            Some(SyncMessage::BatchAck(ack))
+        }
+        SyncMessage::CloudAck { .. } if version < HELD_UPLOAD_VERSION => None,
+        other => Some(other),
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{BatchAck, EntityUpdate};
+
+    fn update(operation: &str) -> SyncMessage {
+        SyncMessage::EntityUpdate(EntityUpdate {
+            entity_type: "product".into(),
+            entity_id: "p-1".into(),
+            operation: operation.into(),
+            data: serde_json::json!({}),
+            version: 2,
+            updated_at: "2024-01-01T00:00:00Z".into(),
+        })
+    }
+
+    #[test]
+    fn test_negotiate_picks_highest_common_version() {
+        assert_eq!(
+            negotiate(PROTOCOL_VERSION, Some(MIN_PROTOCOL_VERSION)),
+            Ok(PROTOCOL_VERSION)
+        );
+        // Newer peer that still speaks our version
+        assert_eq!(
+            negotiate(PROTOCOL_VERSION + 1, Some(PROTOCOL_VERSION)),
+            Ok(PROTOCOL_VERSION)
+        );
+        // Legacy peer without a minimum
+        assert_eq!(
+            negotiate(LEGACY_PROTOCOL_VERSION, None),
+            Ok(LEGACY_PROTOCOL_VERSION)
+        );
+    }
+
+    #[test]
+    fn test_negotiate_rejects_disjoint_ranges() {
+        let too_old = negotiate(MIN_PROTOCOL_VERSION - 1, None).unwrap_err();
+        assert!(too_old.peer_is_older());
+        assert!(too_old
+            .message("terminal")
+            .contains(&format!("v{} or newer", MIN_PROTOCOL_VERSION)));
+
+        let too_new = negotiate(PROTOCOL_VERSION + 3, Some(PROTOCOL_VERSION + 2)).unwrap_err();
+        assert!(!too_new.peer_is_older());
+        assert!(too_new.message("hub").contains(&format!(
+            "the hub speaks sync protocol v{}",
+            PROTOCOL_VERSION
+        )));
+    }
+
+    #[test]
+    fn test_downgrade_drops_patches_for_v2() {
+        assert!(downgrade(update("patch"), 2).is_none());
+        assert!(downgrade(update("upsert"), 2).is_some());
+        assert!(downgrade(update("patch"), 3).is_some());
+    }
+
+    #[test]
+    fn test_downgrade_drops_snapshots_before_v4() {
+        assert!(downgrade(update("snapshot"), 3).is_none());
+        assert!(downgrade(update("snapshot"), 4).is_some());
+        assert!(downgrade(SyncMessage::ResyncComplete { products: 3 }, 3).is_none());
+    }
+
+    #[test]
+    fn test_downgrade_strips_holding_before_v7() {
+        let ack = SyncMessage::BatchAck(BatchAck {
+            acked_ids: vec!["e-1".into()],
+            failed_ids: vec![],
+            held_ids: vec!["e-2".into()],
+            held_by: "hub-01".into(),
+            uploaded_ids: vec![],
+            new_cursor: 0,
+        });
+
+        match downgrade(ack.clone(), 6) {
+            Some(SyncMessage::BatchAck(old)) => {
+                assert_eq!(old.acked_ids, vec!["e-1".to_string()]);
+                assert!(old.held_ids.is_empty() && old.held_by.is_empty());
+            }
+            other => panic!("expected a BatchAck, got {:?}", other),
+        }
+        match downgrade(ack, HELD_UPLOAD_VERSION) {
+            Some(SyncMessage::BatchAck(current)) => assert_eq!(current.held_ids.len(), 1),
+            other => panic!("expected a BatchAck, got {:?}", other),
+        }
+
+        let cloud_ack = SyncMessage::CloudAck { ids: vec!["e-2".into()] };
+        assert!(downgrade(cloud_ack.clone(), 6).is_none());
+        assert!(downgrade(cloud_ack, HELD_UPLOAD_VERSION).is_some());
+    }
+
+    #[test]
+    fn test_downgrade_folds_uploaded_into_acked_before_v8() {
+        let ack = SyncMessage::BatchAck(BatchAck {
+            acked_ids: vec!["e-1".into()],
+            failed_ids: vec![],
+            held_ids: vec![],
+            held_by: String::new(),
+            uploaded_ids: vec!["e-2".into()],
+            new_cursor: 0,
+        });
+
+        match downgrade(ack.clone(), HELD_UPLOAD_VERSION) {
+            Some(SyncMessage::BatchAck(old)) => {
+                assert_eq!(old.acked_ids, vec!["e-1".to_string(), "e-2".to_string()]);
+                assert!(old.uploaded_ids.is_empty());
+            }
+            other => panic!("expected a BatchAck, got {:?}", other),
+        }
+        match downgrade(ack, ACK_LEVEL_VERSION) {
+            Some(SyncMessage::BatchAck(current)) => {
+                assert_eq!(current.acked_ids.len(), 1);
+                assert_eq!(current.uploaded_ids.len(), 1);
+            }
+            other => panic!("expected a BatchAck, got {:?}", other),
+        }
+    }
+}
@@ -30,24 +30,35 @@
 //! id = "550e8400-e29b-41d4-a716-446655440000"
 //! name = "Register 1"
 //! priority = 50  # For leader election (higher = more likely to be PRIMARY)
+//! entities = ["product", "category", "tax_rate"]  # Pushed to this device (default: all)
+//! override_signing_key = "..."  # Manager's device only: issues override tokens
 //!
 //! [sync]
-//! mode = "auto"  # auto | primary | secondary
+//! mode = "auto"  # auto | primary | secondary | peer
 //! hub_url = "ws://192.168.1.100:8080/sync"
+//! peer_url = "ws://192.168.1.101:8765/ws"  # The other terminal (peer mode)
 //! batch_size = 100
 //! poll_interval_secs = 5
 //!
 //! [store]
 //! id = "store-001"
 //! name = "Downtown Branch"
+//! enrollment_key = "..."  # Signs LAN sync messages (optional)
+//! override_public_key = "..."  # Checks manager override tokens (optional)
 //! ```
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use titan_core::{OverrideKey, OverrideSigner};
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+use crate::batching::MIN_BATCH_BYTES;
 use crate::error::{SyncError, SyncResult};
+use crate::integrity::MIN_ENROLLMENT_KEY_LEN;
+use crate::protocol::ENTITY_UPDATE_TYPES;
+use crate::recorder::DEFAULT_RECORD_MAX_MESSAGES;
+use crate::throttle::BandwidthPolicy;
 
 // =============================================================================
 // Sync Mode
@@ -80,6 +91,13 @@ use crate::error::{SyncError, SyncResult};
 /// │  • Always connects to discovered/configured PRIMARY                    │
 /// │  • Use for devices that should never be hub                            │
 /// │                                                                         │
+/// │  PEER (Paired)                                                         │
+/// │  ─────────────                                                         │
+/// │  • Exactly two terminals, no hub and no election                       │
+/// │  • Each one serves a hub for the other and connects to `peer_url`      │
+/// │  • Outboxes and inventory deltas merge both ways (see `peer`)          │
+/// │  • Use for stores that only ever run two registers                     │
+/// │                                                                         │
 /// │  OFFLINE                                                               │
 /// │  ───────                                                               │
 /// │  • Sync disabled completely                                            │
@@ -102,6 +120,9 @@ pub enum SyncMode {
 
     /// Sync disabled - offline mode only.
     Offline,
+
+    /// Paired with exactly one other terminal, both acting as hub.
+    Peer,
 }
 
 impl SyncMode {
@@ -123,6 +144,7 @@ impl std::fmt::Display for SyncMode {
             SyncMode::Primary => write!(f, "primary"),
             SyncMode::Secondary => write!(f, "secondary"),
             SyncMode::Offline => write!(f, "offline"),
+            SyncMode::Peer => write!(f, "peer"),
         }
     }
 }
@@ -136,8 +158,9 @@ impl std::str::FromStr for SyncMode {
             "primary" | "hub" | "server" => Ok(SyncMode::Primary),
             "secondary" | "client" => Ok(SyncMode::Secondary),
             "offline" | "disabled" => Ok(SyncMode::Offline),
+            "peer" | "paired" => Ok(SyncMode::Peer),
             other => Err(SyncError::InvalidConfig(format!(
-                "Unknown sync mode: '{}'. Valid options: auto, primary, secondary, offline",
+                "Unknown sync mode: '{}'. Valid options: auto, primary, secondary, offline, peer",
                 other
             ))),
         }
@@ -164,6 +187,29 @@ pub struct DeviceConfig {
     /// Default: 50
     #[serde(default = "default_priority")]
     pub priority: u8,
+
+    /// Entity types the hub pushes to this device (see
+    /// `protocol::ENTITY_UPDATE_TYPES`). Empty means all of them; a
+    /// kitchen display or handheld can leave out customers and sales.
+    #[serde(default)]
+    pub entities: Vec<String>,
+
+    /// The store's override signing key (see
+    /// `titan_core::OverrideSigner`). Set on the manager's device only:
+    /// whoever holds it can issue manager override tokens, so tills get
+    /// the public key instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub override_signing_key: Option<String>,
+}
+
+impl DeviceConfig {
+    /// The subscriptions to declare in Hello (None for all entities).
+    pub fn subscriptions(&self) -> Option<Vec<String>> {
+        if self.entities.is_empty() {
+            return None;
+        }
+        Some(self.entities.iter().map(|t| t.to_lowercase()).collect())
+    }
 }
 
 fn default_device_name() -> String {
@@ -180,6 +226,8 @@ impl Default for DeviceConfig {
             id: Uuid::new_v4().to_string(),
             name: default_device_name(),
             priority: default_priority(),
+            entities: Vec::new(),
+            override_signing_key: None,
         }
     }
 }
@@ -197,6 +245,18 @@ pub struct StoreConfig {
     /// Human-readable store name.
     #[serde(default)]
     pub name: String,
+
+    /// Secret issued when the store's devices were enrolled. When set, LAN
+    /// sync messages are signed with a per-device key derived from it (see
+    /// `integrity`) and unsigned peers are refused.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enrollment_key: Option<String>,
+
+    /// Public half of the store's override key, on every device: manager
+    /// override tokens are checked against it (see
+    /// `titan_core::override_token`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub override_public_key: Option<String>,
 }
 
 impl Default for StoreConfig {
@@ -204,6 +264,8 @@ impl Default for StoreConfig {
         StoreConfig {
             id: "default-store".to_string(),
             name: "Default Store".to_string(),
+            enrollment_key: None,
+            override_public_key: None,
         }
     }
 }
@@ -220,14 +282,24 @@ pub struct SyncSettings {
     pub mode: SyncMode,
 
     /// WebSocket URL of the Store Hub (if known).
-    /// Can be discovered via mDNS/UDP in Auto mode.
+    /// When unset, the last known hub is probed and then discovery runs
+    /// (see `hub_cache`).
     #[serde(default)]
     pub hub_url: Option<String>,
 
-    /// Number of outbox entries to send per batch.
+    /// WebSocket URL of the other terminal's hub in peer mode.
+    #[serde(default)]
+    pub peer_url: Option<String>,
+
+    /// Maximum number of outbox entries per batch.
     #[serde(default = "default_batch_size")]
     pub batch_size: usize,
 
+    /// Ceiling for the byte size of a batch. The actual target adapts to
+    /// ack latency and failures below this (see `batching`).
+    #[serde(default = "default_batch_max_bytes")]
+    pub batch_max_bytes: usize,
+
     /// Interval between outbox poll cycles (seconds).
     #[serde(default = "default_poll_interval")]
     pub poll_interval_secs: u64,
@@ -248,6 +320,48 @@ pub struct SyncSettings {
     /// Maximum backoff duration (seconds) for reconnection.
     #[serde(default = "default_max_backoff")]
     pub max_backoff_secs: u64,
+
+    /// Interval between keepalive pings to the hub (seconds).
+    #[serde(default = "default_ping_interval")]
+    pub ping_interval_secs: u64,
+
+    /// How long to wait for each pong (seconds).
+    #[serde(default = "default_pong_timeout")]
+    pub pong_timeout_secs: u64,
+
+    /// Consecutive missed pongs before the connection is treated as dead
+    /// and re-established.
+    #[serde(default = "default_max_missed_pongs")]
+    pub max_missed_pongs: u32,
+
+    /// Upload bandwidth cap (kilobits per second). Unlimited when unset.
+    #[serde(default)]
+    pub upload_limit_kbps: Option<u32>,
+
+    /// Metered mode (e.g. on an LTE backup link): uploads are capped at
+    /// `metered_limit_kbps` and confined to `metered_windows`.
+    #[serde(default)]
+    pub metered: bool,
+
+    /// Upload cap while metered (kilobits per second).
+    #[serde(default = "default_metered_limit")]
+    pub metered_limit_kbps: u32,
+
+    /// Local time windows (`HH:MM-HH:MM`, may wrap past midnight) in which
+    /// uploads run while metered. Empty means any time.
+    #[serde(default)]
+    pub metered_windows: Vec<String>,
+
+    /// Seconds without a reachable hub after which this terminal uploads
+    /// its own outbox straight to the cloud (see `cloud_fallback`).
+    /// Disabled when unset.
+    #[serde(default)]
+    pub cloud_fallback_secs: Option<u64>,
+
+    /// Seconds an entry the hub holds for the cloud waits for the cloud's
+    /// ack before it is uploaded again (see `hub_uplink`).
+    #[serde(default = "default_cloud_ack_timeout")]
+    pub cloud_ack_timeout_secs: u64,
 }
 
 // =============================================================================
@@ -272,7 +386,10 @@ pub struct SyncSettings {
 /// │  Bind Address:                                                         │
 /// │  ───────────────                                                       │
 /// │  • Default: 0.0.0.0 (all interfaces)                                   │
+/// │  • "::" listens on IPv4 and IPv6 on dual-stack hosts                   │
 /// │  • Can be restricted to specific interface for security                │
+/// │  • `interfaces` binds one listener per address of the named            │
+/// │    interfaces (e.g. one VLAN) and announces only those                 │
 /// │                                                                         │
 /// └─────────────────────────────────────────────────────────────────────────┘
 /// ```
@@ -286,6 +403,11 @@ pub struct HubSettings {
     #[serde(default = "default_bind_addr")]
     pub bind_addr: String,
 
+    /// Network interfaces to listen and announce on, by name (`eth0`,
+    /// `vlan20`). Empty means `bind_addr` and every usable address.
+    #[serde(default)]
+    pub interfaces: Vec<String>,
+
     /// Heartbeat interval for announcing PRIMARY status (seconds).
     #[serde(default = "default_heartbeat_interval")]
     pub heartbeat_interval_secs: u64,
@@ -302,6 +424,37 @@ pub struct HubSettings {
     /// Only used when broadcast_mode is Coalesced.
     #[serde(default = "default_coalesce_window")]
     pub coalesce_window_ms: u64,
+
+    /// Most terminals connected at once; more are turned away as busy.
+    #[serde(default = "default_max_clients")]
+    pub max_clients: usize,
+
+    /// Sustained messages per second accepted from one terminal.
+    #[serde(default = "default_client_rate_limit")]
+    pub client_rate_limit: u32,
+
+    /// Messages one terminal may send in a burst above the rate limit.
+    #[serde(default = "default_client_burst")]
+    pub client_burst: u32,
+
+    /// Bearer token thin clients must send to the catalog API (see
+    /// `catalog_api`). Open to the store network when unset.
+    #[serde(default)]
+    pub catalog_token: Option<String>,
+
+    /// Bearer token for the admin API (see `admin_api`). Open to the
+    /// store network when unset.
+    #[serde(default)]
+    pub admin_token: Option<String>,
+
+    /// Records every message to and from terminals to this file (see
+    /// `recorder`). Off when unset.
+    #[serde(default)]
+    pub record_path: Option<PathBuf>,
+
+    /// Most messages kept in the recording; older ones are discarded.
+    #[serde(default = "default_record_max_messages")]
+    pub record_max_messages: usize,
 }
 
 fn default_hub_port() -> u16 {
@@ -324,15 +477,39 @@ fn default_coalesce_window() -> u64 {
     50
 }
 
+fn default_max_clients() -> usize {
+    32
+}
+
+fn default_client_rate_limit() -> u32 {
+    20
+}
+
+fn default_client_burst() -> u32 {
+    100
+}
+
+fn default_record_max_messages() -> usize {
+    DEFAULT_RECORD_MAX_MESSAGES
+}
+
 impl Default for HubSettings {
     fn default() -> Self {
         HubSettings {
             port: default_hub_port(),
             bind_addr: default_bind_addr(),
+            interfaces: Vec::new(),
             heartbeat_interval_secs: default_heartbeat_interval(),
             heartbeat_timeout_secs: default_heartbeat_timeout(),
             broadcast_mode: BroadcastMode::default(),
             coalesce_window_ms: default_coalesce_window(),
+            max_clients: default_max_clients(),
+            client_rate_limit: default_client_rate_limit(),
+            client_burst: default_client_burst(),
+            catalog_token: None,
+            admin_token: None,
+            record_path: None,
+            record_max_messages: default_record_max_messages(),
         }
     }
 }
@@ -340,7 +517,15 @@ impl Default for HubSettings {
 impl HubSettings {
     /// Returns the full bind address.
     pub fn bind_address(&self) -> String {
-        format!("{}:{}", self.bind_addr, self.port)
+        join_host_port(&self.bind_addr, self.port)
+    }
+}
+
+/// Joins a bind host and port, bracketing IPv6 addresses.
+pub(crate) fn join_host_port(host: &str, port: u16) -> String {
+    match host.parse::<std::net::IpAddr>() {
+        Ok(ip) => std::net::SocketAddr::new(ip, port).to_string(),
+        Err(_) => format!("{}:{}", host, port),
     }
 }
 
@@ -410,6 +595,11 @@ pub struct DiscoverySettings {
     /// Discovery timeout (seconds).
     #[serde(default = "default_discovery_timeout")]
     pub timeout_secs: u64,
+
+    /// Device IDs allowed to act as this store's hub. Empty means any hub
+    /// announcing this store's ID.
+    #[serde(default)]
+    pub allowed_hubs: Vec<String>,
 }
 
 fn default_true() -> bool {
@@ -431,6 +621,7 @@ impl Default for DiscoverySettings {
             udp_enabled: true,
             udp_port: default_discovery_port(),
             timeout_secs: default_discovery_timeout(),
+            allowed_hubs: Vec::new(),
         }
     }
 }
@@ -438,6 +629,9 @@ impl Default for DiscoverySettings {
 fn default_batch_size() -> usize {
     100
 }
+fn default_batch_max_bytes() -> usize {
+    256 * 1024
+}
 fn default_poll_interval() -> u64 {
     5
 }
@@ -453,18 +647,45 @@ fn default_initial_backoff() -> u64 {
 fn default_max_backoff() -> u64 {
     60
 }
+fn default_ping_interval() -> u64 {
+    15
+}
+fn default_pong_timeout() -> u64 {
+    5
+}
+fn default_max_missed_pongs() -> u32 {
+    2
+}
+
+fn default_metered_limit() -> u32 {
+    256
+}
+fn default_cloud_ack_timeout() -> u64 {
+    600
+}
 
 impl Default for SyncSettings {
     fn default() -> Self {
         SyncSettings {
             mode: SyncMode::default(),
             hub_url: None,
+            peer_url: None,
             batch_size: default_batch_size(),
+            batch_max_bytes: default_batch_max_bytes(),
             poll_interval_secs: default_poll_interval(),
             connect_timeout_secs: default_connect_timeout(),
             max_retries: default_max_retries(),
             initial_backoff_ms: default_initial_backoff(),
             max_backoff_secs: default_max_backoff(),
+            ping_interval_secs: default_ping_interval(),
+            pong_timeout_secs: default_pong_timeout(),
+            max_missed_pongs: default_max_missed_pongs(),
+            upload_limit_kbps: None,
+            metered: false,
+            metered_limit_kbps: default_metered_limit(),
+            metered_windows: Vec::new(),
+            cloud_fallback_secs: None,
+            cloud_ack_timeout_secs: default_cloud_ack_timeout(),
         }
     }
 }
@@ -489,17 +710,25 @@ impl Default for SyncSettings {
 /// [sync]
 /// mode = "auto"
 /// batch_size = 100
+/// batch_max_bytes = 262144
 /// poll_interval_secs = 5
+/// metered_limit_kbps = 256
+/// metered_windows = ["22:00-06:00"]
+/// cloud_fallback_secs = 900
+/// cloud_ack_timeout_secs = 600
 ///
 /// [hub]
 /// port = 8765
 /// broadcast_mode = "coalesced"
 /// coalesce_window_ms = 50
+/// max_clients = 32
+/// client_rate_limit = 20
 ///
 /// [discovery]
 /// mdns_enabled = true
 /// udp_enabled = true
 /// udp_port = 5555
+/// allowed_hubs = ["pos-01", "pos-02"]
 /// ```
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SyncConfig {
@@ -602,12 +831,109 @@ impl SyncConfig {
             }
         }
 
+        // Peer mode needs to know where the other terminal is
+        if self.sync.mode == SyncMode::Peer {
+            match &self.sync.peer_url {
+                None => {
+                    return Err(SyncError::InvalidConfig(
+                        "peer_url is required in peer mode".into(),
+                    ))
+                }
+                Some(url) if !url.starts_with("ws://") && !url.starts_with("wss://") => {
+                    return Err(SyncError::InvalidUrl(format!(
+                        "Peer URL must start with ws:// or wss://, got: {}",
+                        url
+                    )));
+                }
+                Some(_) => {}
+            }
+        }
+
         // Validate batch size
         if self.sync.batch_size == 0 {
             return Err(SyncError::InvalidConfig(
                 "batch_size must be greater than 0".into(),
             ));
         }
+        if self.sync.batch_max_bytes < MIN_BATCH_BYTES {
+            return Err(SyncError::InvalidConfig(format!(
+                "batch_max_bytes must be at least {}",
+                MIN_BATCH_BYTES
+            )));
+        }
+
+        // Bandwidth limits and metered windows
+        if self.sync.upload_limit_kbps == Some(0) || self.sync.metered_limit_kbps == 0 {
+            return Err(SyncError::InvalidConfig(
+                "bandwidth limits must be greater than 0".into(),
+            ));
+        }
+        BandwidthPolicy::from_settings(&self.sync)?;
+
+        // Hub load shedding
+        if self.hub.max_clients == 0 || self.hub.client_rate_limit == 0 || self.hub.client_burst == 0
+        {
+            return Err(SyncError::InvalidConfig(
+                "hub client limits must be greater than 0".into(),
+            ));
+        }
+
+        if self.sync.cloud_fallback_secs == Some(0) {
+            return Err(SyncError::InvalidConfig(
+                "cloud_fallback_secs must be greater than 0".into(),
+            ));
+        }
+        if self.sync.cloud_ack_timeout_secs == 0 {
+            return Err(SyncError::InvalidConfig(
+                "cloud_ack_timeout_secs must be greater than 0".into(),
+            ));
+        }
+
+        // Subscriptions must name entities the hub pushes
+        if let Some(unknown) = self
+            .device
+            .entities
+            .iter()
+            .find(|t| !ENTITY_UPDATE_TYPES.contains(&t.to_lowercase().as_str()))
+        {
+            return Err(SyncError::InvalidConfig(format!(
+                "Unknown entity type in device.entities: {} (expected one of {})",
+                unknown,
+                ENTITY_UPDATE_TYPES.join(", ")
+            )));
+        }
+
+        // Enrollment key must carry enough entropy to sign with
+        if let Some(key) = &self.store.enrollment_key {
+            if key.len() < MIN_ENROLLMENT_KEY_LEN {
+                return Err(SyncError::InvalidConfig(format!(
+                    "enrollment_key must be at least {} characters",
+                    MIN_ENROLLMENT_KEY_LEN
+                )));
+            }
+        }
+
+        // Override keys must parse, and a signing key must match the
+        // store's public key or its tokens would be refused everywhere
+        let invalid = |e: titan_core::CoreError| SyncError::InvalidConfig(e.to_string());
+        if let Some(key) = &self.store.override_public_key {
+            OverrideKey::from_public_key(key).map_err(invalid)?;
+        }
+        if let Some(key) = &self.device.override_signing_key {
+            let public_key = OverrideSigner::from_signing_key(key)
+                .map_err(invalid)?
+                .public_key();
+            if self
+                .store
+                .override_public_key
+                .as_ref()
+                .is_some_and(|store_key| *store_key != public_key)
+            {
+                return Err(SyncError::InvalidConfig(
+                    "override_signing_key does not match the store's override_public_key".into(),
+                ));
+            }
+        }
 
         Ok(())
     }
@@ -632,6 +958,17 @@ impl SyncConfig {
             }
         }
 
+        // Entity subscriptions (comma-separated entity types)
+        if let Ok(entities) = std::env::var("TITAN_SYNC_ENTITIES") {
+            debug!(entities = %entities, "Overriding entity subscriptions from environment");
+            self.device.entities = entities
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(String::from)
+                .collect();
+        }
+
         // Sync mode
         if let Ok(mode) = std::env::var("TITAN_SYNC_MODE") {
             if let Ok(parsed) = mode.parse() {
@@ -646,11 +983,56 @@ impl SyncConfig {
             self.sync.hub_url = Some(url);
         }
 
+        // Peer URL
+        if let Ok(url) = std::env::var("TITAN_PEER_URL") {
+            debug!(url = %url, "Overriding peer URL from environment");
+            self.sync.peer_url = Some(url);
+        }
+
+        // Metered mode
+        if let Ok(metered) = std::env::var("TITAN_SYNC_METERED") {
+            if let Ok(m) = metered.parse::<bool>() {
+                debug!(metered = m, "Overriding metered mode from environment");
+                self.sync.metered = m;
+            }
+        }
+
+        // Cloud fallback delay
+        if let Ok(secs) = std::env::var("TITAN_CLOUD_FALLBACK_SECS") {
+            if let Ok(s) = secs.parse::<u64>() {
+                debug!(secs = s, "Overriding cloud fallback delay from environment");
+                self.sync.cloud_fallback_secs = Some(s);
+            }
+        }
+
         // Store ID
         if let Ok(id) = std::env::var("TITAN_STORE_ID") {
             self.store.id = id;
         }
 
+        // Enrollment key
+        if let Ok(key) = std::env::var("TITAN_ENROLLMENT_KEY") {
+            self.store.enrollment_key = Some(key);
+        }
+
+        // Override keys
+        if let Ok(key) = std::env::var("TITAN_OVERRIDE_PUBLIC_KEY") {
+            self.store.override_public_key = Some(key);
+        }
+        if let Ok(key) = std::env::var("TITAN_OVERRIDE_SIGNING_KEY") {
+            self.device.override_signing_key = Some(key);
+        }
+
+        // Hub allow-list (comma-separated device IDs)
+        if let Ok(hubs) = std::env::var("TITAN_ALLOWED_HUBS") {
+            self.discovery.allowed_hubs = hubs
+                .split(',')
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(String::from)
+                .collect();
+        }
+
         // Hub port
         if let Ok(port) = std::env::var("TITAN_HUB_PORT") {
             if let Ok(p) = port.parse::<u16>() {
@@ -659,6 +1041,16 @@ impl SyncConfig {
             }
         }
 
+        // Hub traffic recording
+        if let Ok(path) = std::env::var("TITAN_HUB_RECORD") {
+            if path.is_empty() {
+                self.hub.record_path = None;
+            } else {
+                debug!(path = %path, "Recording hub traffic from environment");
+                self.hub.record_path = Some(PathBuf::from(path));
+            }
+        }
+
         // Broadcast mode
         if let Ok(mode) = std::env::var("TITAN_BROADCAST_MODE") {
             match mode.to_lowercase().as_str() {
@@ -705,6 +1097,11 @@ impl SyncConfig {
     pub fn hub_url(&self) -> Option<&str> {
         self.sync.hub_url.as_deref()
     }
+
+    /// Returns the other terminal's URL in peer mode.
+    pub fn peer_url(&self) -> Option<&str> {
+        self.sync.peer_url.as_deref()
+    }
 }
 
 #[cfg(test)]
@@ -718,6 +1115,7 @@ mod tests {
         assert_eq!("hub".parse::<SyncMode>().unwrap(), SyncMode::Primary);
         assert_eq!("secondary".parse::<SyncMode>().unwrap(), SyncMode::Secondary);
         assert_eq!("offline".parse::<SyncMode>().unwrap(), SyncMode::Offline);
+        assert_eq!("peer".parse::<SyncMode>().unwrap(), SyncMode::Peer);
         assert!("invalid".parse::<SyncMode>().is_err());
     }
 
@@ -727,6 +1125,29 @@ mod tests {
         assert!(!config.device.id.is_empty()); // Auto-generated
         assert_eq!(config.sync.mode, SyncMode::Auto);
         assert_eq!(config.sync.batch_size, 100);
+        assert!(config.device.override_signing_key.is_none());
+    }
+
+    #[test]
+    fn test_override_keys() {
+        let signing_key = OverrideSigner::generate().unwrap();
+        let public_key = OverrideSigner::from_signing_key(&signing_key)
+            .unwrap()
+            .public_key();
+
+        // A till has the public key only; the manager's device both
+        let mut config = SyncConfig::default();
+        config.store.override_public_key = Some(public_key);
+        assert!(config.validate().is_ok());
+        config.device.override_signing_key = Some(signing_key);
+        assert!(config.validate().is_ok());
+
+        // Another store's signing key, or a garbled key, is refused
+        config.device.override_signing_key = Some(OverrideSigner::generate().unwrap());
+        assert!(config.validate().is_err());
+        config.device.override_signing_key = None;
+        config.store.override_public_key = Some("not-a-key".to_string());
+        assert!(config.validate().is_err());
     }
 
     #[test]
@@ -746,6 +1167,35 @@ mod tests {
         // Valid WebSocket URL should pass
         config.sync.hub_url = Some("ws://localhost:8080".to_string());
         assert!(config.validate().is_ok());
+
+        // Malformed metered window should fail
+        config.sync.metered_windows = vec!["nightly".to_string()];
+        assert!(config.validate().is_err());
+        config.sync.metered_windows = vec!["22:00-06:00".to_string()];
+        assert!(config.validate().is_ok());
+
+        // Byte ceiling below the tuner's floor should fail
+        config.sync.batch_max_bytes = 1024;
+        assert!(config.validate().is_err());
+        config.sync.batch_max_bytes = 256 * 1024;
+
+        // A hub that admits nobody should fail
+        config.hub.max_clients = 0;
+        assert!(config.validate().is_err());
+        config.hub.max_clients = SyncConfig::default().hub.max_clients;
+
+        // Held entries must be re-uploaded at some point
+        config.sync.cloud_ack_timeout_secs = 0;
+        assert!(config.validate().is_err());
+        config.sync.cloud_ack_timeout_secs = SyncConfig::default().sync.cloud_ack_timeout_secs;
+
+        // Subscriptions must name pushed entity types
+        config.device.entities = vec!["Product".to_string(), "customers".to_string()];
+        assert!(config.validate().is_err());
+        config.device.entities.pop();
+        assert!(config.validate().is_ok());
+        let subscriptions = config.device.subscriptions().unwrap();
+        assert_eq!(subscriptions, ["product"]);
     }
 
     #[test]
@@ -754,6 +1204,21 @@ mod tests {
         assert!(SyncMode::Primary.can_be_primary());
         assert!(!SyncMode::Secondary.can_be_primary());
         assert!(!SyncMode::Offline.can_be_primary());
+        assert!(!SyncMode::Peer.can_be_primary());
+    }
+
+    #[test]
+    fn test_peer_mode_requires_peer_url() {
+        let mut config = SyncConfig::default();
+        config.sync.mode = SyncMode::Peer;
+        assert!(config.validate().is_err());
+
+        config.sync.peer_url = Some("http://192.168.1.20:8765/ws".to_string());
+        assert!(config.validate().is_err());
+
+        config.sync.peer_url = Some("ws://192.168.1.20:8765/ws".to_string());
+        assert!(config.validate().is_ok());
+        assert_eq!(config.mode().to_string(), "peer");
     }
 
     #[test]
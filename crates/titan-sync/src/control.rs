@@ -0,0 +1,76 @@
+//! # Operator Controls
+//!
+//! Runtime switches support staff use to recover a terminal without
+//! reinstalling it: pausing sync while the local database is inspected,
+//! and asking the hub for a full resync afterwards.
+//!
+//! ## Pause and Resync
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Pause / Full Resync                              │
+//! │                                                                         │
+//! │  pause_sync ──► SyncControl.paused = true                               │
+//! │                   • OutboxProcessor: no uploads (sales stay queued)     │
+//! │                   • InboundHandler:  hub updates dropped               │
+//! │                   • CloudFallback:   no direct uploads                  │
+//! │                 The connection stays up, so resuming is instant.        │
+//! │                                                                         │
+//! │  resume_sync ──► paused = false (a full resync catches up anything     │
+//! │                  dropped meanwhile)                                     │
+//! │                                                                         │
+//! │  trigger_full_resync  (connected, hub on protocol v4+, not paused)      │
+//! │    1. reset inbound sync_cursors                                        │
+//! │    2. ResyncRequest ──────────────────────► hub                         │
+//! │    3. EntityUpdate "snapshot" × catalog ◄── (product + current stock)  │
+//! │       applied regardless of version; stock differences are logged      │
+//! │    4. ResyncComplete { products } ◄─────── status.resyncing = false    │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Operator switches shared by the agent's components. Cloning shares them.
+#[derive(Debug, Clone, Default)]
+pub struct SyncControl {
+    paused: Arc<AtomicBool>,
+}
+
+impl SyncControl {
+    /// Creates controls with sync running.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether sync is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Pauses or resumes sync for every holder of these controls.
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pause_is_shared_between_clones() {
+        let control = SyncControl::new();
+        let outbox = control.clone();
+        assert!(!outbox.is_paused());
+
+        control.set_paused(true);
+        assert!(outbox.is_paused());
+
+        outbox.set_paused(false);
+        assert!(!control.is_paused());
+    }
+}
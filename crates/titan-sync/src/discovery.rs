@@ -34,13 +34,29 @@
 //! └─────────────────────────────────────────────────────────────────────────┘
 //! ```
 //!
+//! ## Store Scoping
+//! Stores sharing a network (a mall back corridor) hear each other's
+//! broadcasts, so every message carries the store ID:
+//! - a hub answers a request only if the requester names its store
+//! - announcements from other stores are ignored
+//! - with `discovery.allowed_hubs` set, only those device IDs are accepted
+//!   as hubs, even from this store
+//!
+//! ## Multi-Homed Hosts
+//! Requests go out on every usable interface: to the subnet broadcast
+//! address for IPv4 and to the all-nodes multicast group for IPv6 (see
+//! `netif`). Replies list every address the hub listens on, and terminals
+//! fall back to one of those when the reply came from an address they
+//! cannot dial (an IPv6 link-local source).
+//!
 //! ## Configuration
 //! - mDNS service type: `_titan-pos._tcp.local`
 //! - UDP discovery port: 5555 (configurable)
 //! - Discovery timeout: 5 seconds
 
 use std::collections::HashMap;
-use std::net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::io::ErrorKind;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -51,6 +67,7 @@ use tracing::{debug, info, warn};
 
 use crate::config::SyncConfig;
 use crate::error::{SyncError, SyncResult};
+use crate::netif;
 
 // =============================================================================
 // Constants
@@ -111,6 +128,9 @@ pub struct DiscoveredHub {
     pub store_id: String,
     /// IP address of the hub.
     pub ip_address: IpAddr,
+    /// Addresses the hub listens on, as it announced them (empty for hubs
+    /// that predate per-interface announcements).
+    pub addresses: Vec<IpAddr>,
     /// WebSocket port of the hub.
     pub ws_port: u16,
     /// Election term of the hub.
@@ -124,7 +144,30 @@ pub struct DiscoveredHub {
 impl DiscoveredHub {
     /// Returns the WebSocket URL for connecting to this hub.
     pub fn ws_url(&self) -> String {
-        format!("ws://{}:{}/sync", self.ip_address, self.ws_port)
+        format!("ws://{}/ws", SocketAddr::new(self.ip_address, self.ws_port))
+    }
+
+    /// Picks the address to dial: where the reply came from, unless that
+    /// cannot be dialled as-is and the hub announced one that can.
+    fn reachable_address(from: IpAddr, announced: &[IpAddr]) -> IpAddr {
+        if netif::is_announceable(&from) {
+            return from;
+        }
+        announced
+            .iter()
+            .find(|ip| ip.is_ipv6() == from.is_ipv6())
+            .or_else(|| announced.first())
+            .copied()
+            .unwrap_or(from)
+    }
+
+    /// Whether this device may use the hub: it is another device of the
+    /// same store and, if an allow-list is configured, on it.
+    pub fn is_eligible(&self, sync_config: &SyncConfig) -> bool {
+        let allowed = &sync_config.discovery.allowed_hubs;
+        self.device_id != sync_config.device_id()
+            && self.store_id == sync_config.store_id()
+            && (allowed.is_empty() || allowed.contains(&self.device_id))
     }
 }
 
@@ -162,6 +205,115 @@ impl Default for DiscoveryConfig {
     }
 }
 
+impl From<&SyncConfig> for DiscoveryConfig {
+    fn from(config: &SyncConfig) -> Self {
+        DiscoveryConfig {
+            discovery_port: config.discovery.udp_port,
+            ws_port: config.hub.port,
+            discovery_timeout: Duration::from_secs(config.discovery.timeout_secs),
+            mdns_enabled: config.discovery.mdns_enabled,
+            udp_enabled: config.discovery.udp_enabled,
+            ..Default::default()
+        }
+    }
+}
+
+// =============================================================================
+// Discovery Sockets
+// =============================================================================
+
+/// UDP sockets for discovery: IPv4 always, IPv6 where the host has it.
+struct DiscoverySockets {
+    v4: UdpSocket,
+    v6: Option<UdpSocket>,
+}
+
+impl DiscoverySockets {
+    /// Binds both families on `port` (0 for ephemeral ports).
+    async fn bind(port: u16) -> SyncResult<Self> {
+        let bind_addr = SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, port);
+        let v4 = UdpSocket::bind(bind_addr).await.map_err(|e| {
+            SyncError::ConnectionFailed(format!(
+                "Failed to bind discovery socket on port {}: {}",
+                port, e
+            ))
+        })?;
+
+        // Enable broadcast
+        v4.set_broadcast(true).map_err(|e| {
+            SyncError::ConnectionFailed(format!("Failed to enable broadcast: {}", e))
+        })?;
+
+        let v6 = match netif::bind_udp_v6(port) {
+            Ok(socket) => Some(socket),
+            Err(e) => {
+                debug!(?e, "IPv6 discovery unavailable");
+                None
+            }
+        };
+
+        Ok(DiscoverySockets { v4, v6 })
+    }
+
+    async fn send_to(&self, msg: &[u8], target: SocketAddr) -> std::io::Result<usize> {
+        match (target, &self.v6) {
+            (SocketAddr::V4(_), _) => self.v4.send_to(msg, target).await,
+            (SocketAddr::V6(_), Some(v6)) => v6.send_to(msg, target).await,
+            (SocketAddr::V6(_), None) => Err(std::io::Error::new(
+                ErrorKind::Unsupported,
+                "no IPv6 discovery socket",
+            )),
+        }
+    }
+
+    async fn recv_from(&self, buf: &mut [u8]) -> std::io::Result<(usize, SocketAddr)> {
+        let Some(v6) = &self.v6 else {
+            return self.v4.recv_from(buf).await;
+        };
+        loop {
+            let socket = tokio::select! {
+                ready = self.v4.readable() => { ready?; &self.v4 }
+                ready = v6.readable() => { ready?; v6 }
+            };
+            match socket.try_recv_from(buf) {
+                Err(e) if e.kind() == ErrorKind::WouldBlock => continue,
+                other => return other,
+            }
+        }
+    }
+
+    /// Sends a request to every interface's broadcast or multicast target.
+    async fn send_request(&self, msg: &[u8], port: u16) {
+        for target in request_targets(port) {
+            if let Err(e) = self.send_to(msg, target).await {
+                warn!(?e, %target, "Failed to send discovery request");
+            }
+        }
+    }
+}
+
+/// Broadcast and multicast targets for a discovery request, one per
+/// interface address. Falls back to the limited broadcast address when the
+/// interfaces cannot be listed.
+fn request_targets(port: u16) -> Vec<SocketAddr> {
+    let mut targets: Vec<SocketAddr> = match netif::local_addresses(&[]) {
+        Ok(addresses) => addresses
+            .iter()
+            .filter_map(|a| a.discovery_target(port))
+            .collect(),
+        Err(e) => {
+            debug!(?e, "Could not list interfaces, broadcasting on the default route");
+            Vec::new()
+        }
+    };
+    targets.sort_by_key(|t| t.to_string());
+    targets.dedup();
+    if !targets.iter().any(SocketAddr::is_ipv4) {
+        targets.push(SocketAddr::new(IpAddr::V4(Ipv4Addr::BROADCAST), port));
+    }
+    targets
+}
+
 // =============================================================================
 // Discovery Service
 // =============================================================================
@@ -174,8 +326,10 @@ pub struct DiscoveryService {
     sync_config: Arc<SyncConfig>,
     /// Known hubs (device_id -> DiscoveredHub).
     known_hubs: Arc<RwLock<HashMap<String, DiscoveredHub>>>,
-    /// UDP socket for discovery.
-    socket: Option<Arc<UdpSocket>>,
+    /// Election term while this device is the hub and answers requests.
+    hub_term: Arc<RwLock<Option<u64>>>,
+    /// UDP sockets for discovery.
+    socket: Option<Arc<DiscoverySockets>>,
     /// Shutdown sender.
     shutdown_tx: Option<mpsc::Sender<()>>,
 }
@@ -185,6 +339,8 @@ pub struct DiscoveryService {
 pub struct DiscoveryHandle {
     /// Known hubs.
     known_hubs: Arc<RwLock<HashMap<String, DiscoveredHub>>>,
+    /// Election term while answering requests as the hub.
+    hub_term: Arc<RwLock<Option<u64>>>,
     /// Channel to trigger discovery.
     discover_tx: mpsc::Sender<()>,
     /// Shutdown sender.
@@ -200,6 +356,12 @@ impl DiscoveryHandle {
             .map_err(|_| SyncError::ChannelError("Discovery channel closed".into()))
     }
 
+    /// Starts (`Some(term)`) or stops (`None`) answering discovery requests
+    /// from this store as its hub.
+    pub async fn set_hub_term(&self, term: Option<u64>) {
+        *self.hub_term.write().await = term;
+    }
+
     /// Returns all known hubs.
     pub async fn known_hubs(&self) -> Vec<DiscoveredHub> {
         self.known_hubs.read().await.values().cloned().collect()
@@ -235,6 +397,7 @@ impl DiscoveryService {
             config,
             sync_config,
             known_hubs: Arc::new(RwLock::new(HashMap::new())),
+            hub_term: Arc::new(RwLock::new(None)),
             socket: None,
             shutdown_tx: None,
         }
@@ -242,21 +405,14 @@ impl DiscoveryService {
 
     /// Starts the discovery service and returns a handle.
     pub async fn start(mut self) -> SyncResult<DiscoveryHandle> {
-        // Bind UDP socket for discovery
-        let bind_addr = SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, self.config.discovery_port);
-        let socket = UdpSocket::bind(bind_addr).await.map_err(|e| {
-            SyncError::ConnectionFailed(format!(
-                "Failed to bind discovery socket on port {}: {}",
-                self.config.discovery_port, e
-            ))
-        })?;
+        // Bind UDP sockets for discovery
+        let socket = DiscoverySockets::bind(self.config.discovery_port).await?;
 
-        // Enable broadcast
-        socket.set_broadcast(true).map_err(|e| {
-            SyncError::ConnectionFailed(format!("Failed to enable broadcast: {}", e))
-        })?;
-
-        info!(port = self.config.discovery_port, "Discovery service started");
+        info!(
+            port = self.config.discovery_port,
+            ipv6 = socket.v6.is_some(),
+            "Discovery service started"
+        );
 
         let socket = Arc::new(socket);
         self.socket = Some(socket.clone());
@@ -268,6 +424,7 @@ impl DiscoveryService {
 
         let handle = DiscoveryHandle {
             known_hubs: self.known_hubs.clone(),
+            hub_term: self.hub_term.clone(),
             discover_tx,
             shutdown_tx,
         };
@@ -276,8 +433,18 @@ impl DiscoveryService {
         let listener_socket = socket.clone();
         let listener_hubs = self.known_hubs.clone();
         let listener_config = self.sync_config.clone();
+        let listener_term = self.hub_term.clone();
+        let ws_port = self.config.ws_port;
         tokio::spawn(async move {
-            Self::run_listener(listener_socket, listener_hubs, listener_config, shutdown_rx).await;
+            Self::run_listener(
+                listener_socket,
+                listener_hubs,
+                listener_config,
+                listener_term,
+                ws_port,
+                shutdown_rx,
+            )
+            .await;
         });
 
         // Spawn the discovery requester task
@@ -301,9 +468,11 @@ impl DiscoveryService {
 
     /// Runs the UDP listener for discovery messages.
     async fn run_listener(
-        socket: Arc<UdpSocket>,
+        socket: Arc<DiscoverySockets>,
         known_hubs: Arc<RwLock<HashMap<String, DiscoveredHub>>>,
         sync_config: Arc<SyncConfig>,
+        hub_term: Arc<RwLock<Option<u64>>>,
+        ws_port: u16,
         mut shutdown_rx: mpsc::Receiver<()>,
     ) {
         let mut buf = [0u8; 1024];
@@ -323,6 +492,8 @@ impl DiscoveryService {
                                 &socket,
                                 &known_hubs,
                                 &sync_config,
+                                *hub_term.read().await,
+                                ws_port,
                             ).await {
                                 debug!(?e, "Failed to handle discovery message");
                             }
@@ -340,9 +511,11 @@ impl DiscoveryService {
     async fn handle_message(
         data: &[u8],
         from: SocketAddr,
-        _socket: &UdpSocket,
+        socket: &DiscoverySockets,
         known_hubs: &RwLock<HashMap<String, DiscoveredHub>>,
         sync_config: &SyncConfig,
+        hub_term: Option<u64>,
+        ws_port: u16,
     ) -> SyncResult<()> {
         // Validate magic bytes
         if data.len() < 6 || &data[0..4] != DISCOVERY_MAGIC {
@@ -363,14 +536,29 @@ impl DiscoveryService {
         match msg_type {
             DiscoveryMessageType::HubRequest => {
                 debug!(?from, "Received hub request");
-                // Only respond if we're the hub (handled by hub server)
-                // This is a no-op here; the hub server will respond
+                // Only the hub answers, and only for its own store
+                let Some(term) = hub_term else {
+                    return Ok(());
+                };
+                let store_id = Self::parse_hub_request(payload)?;
+                if store_id != sync_config.store_id() {
+                    debug!(?from, store_id = %store_id, "Ignoring hub request from another store");
+                    return Ok(());
+                }
+                let addresses: Vec<IpAddr> = netif::local_addresses(&sync_config.hub.interfaces)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|a| a.ip)
+                    .collect();
+                let reply = Self::build_hub_announce(sync_config, ws_port, term, &addresses);
+                socket.send_to(&reply, from).await.map_err(|e| {
+                    SyncError::ConnectionFailed(format!("Failed to answer hub request: {}", e))
+                })?;
             }
             DiscoveryMessageType::HubAnnounce | DiscoveryMessageType::HubHeartbeat => {
                 // Parse hub announcement
                 if let Some(hub) = Self::parse_hub_announce(payload, from.ip())? {
-                    // Don't add ourselves
-                    if hub.device_id != sync_config.device_id() {
+                    if hub.is_eligible(sync_config) {
                         debug!(
                             device_id = %hub.device_id,
                             ip = %hub.ip_address,
@@ -378,6 +566,12 @@ impl DiscoveryService {
                             "Discovered hub"
                         );
                         known_hubs.write().await.insert(hub.device_id.clone(), hub);
+                    } else {
+                        debug!(
+                            device_id = %hub.device_id,
+                            store_id = %hub.store_id,
+                            "Ignoring hub of another store or not on the allow-list"
+                        );
                     }
                 }
             }
@@ -386,6 +580,21 @@ impl DiscoveryService {
         Ok(())
     }
 
+    /// Parses the store ID out of a hub request payload.
+    fn parse_hub_request(payload: &[u8]) -> SyncResult<String> {
+        // Payload format:
+        // - 1 byte: store_id_len
+        // - N bytes: store_id (UTF-8)
+        let Some((&len, rest)) = payload.split_first() else {
+            return Err(SyncError::InvalidMessage("Hub request too short".into()));
+        };
+        let store_id = rest
+            .get(..len as usize)
+            .ok_or_else(|| SyncError::InvalidMessage("Store ID truncated".into()))?;
+        String::from_utf8(store_id.to_vec())
+            .map_err(|_| SyncError::InvalidMessage("Invalid store_id UTF-8".into()))
+    }
+
     /// Parses a hub announcement payload.
     fn parse_hub_announce(payload: &[u8], from_ip: IpAddr) -> SyncResult<Option<DiscoveredHub>> {
         // Payload format:
@@ -398,6 +607,9 @@ impl DiscoveryService {
         // - N bytes: device_name (UTF-8)
         // - 1 byte: store_id_len
         // - N bytes: store_id (UTF-8)
+        // - optional, from hubs that announce per-interface addresses:
+        //   - 1 byte: address count
+        //   - per address: 1 byte family (4 or 6), then 4 or 16 bytes
 
         if payload.len() < 13 {
             return Err(SyncError::InvalidMessage("Hub announce too short".into()));
@@ -445,12 +657,16 @@ impl DiscoveryService {
         }
         let store_id = String::from_utf8(payload[offset..offset + store_id_len].to_vec())
             .map_err(|_| SyncError::InvalidMessage("Invalid store_id UTF-8".into()))?;
+        offset += store_id_len;
+
+        let addresses = Self::parse_addresses(&payload[offset..])?;
 
         Ok(Some(DiscoveredHub {
             device_id,
             device_name,
             store_id,
-            ip_address: from_ip,
+            ip_address: DiscoveredHub::reachable_address(from_ip, &addresses),
+            addresses,
             ws_port,
             election_term,
             priority,
@@ -458,9 +674,43 @@ impl DiscoveryService {
         }))
     }
 
+    /// Parses the optional address list at the end of an announcement.
+    fn parse_addresses(mut payload: &[u8]) -> SyncResult<Vec<IpAddr>> {
+        let Some((&count, rest)) = payload.split_first() else {
+            return Ok(Vec::new());
+        };
+        payload = rest;
+
+        let truncated = || SyncError::InvalidMessage("Hub address truncated".into());
+        let mut addresses = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let (&family, rest) = payload.split_first().ok_or_else(truncated)?;
+            let (ip, rest) = match family {
+                4 => {
+                    let octets: [u8; 4] = rest.get(..4).ok_or_else(truncated)?.try_into().unwrap();
+                    (IpAddr::V4(Ipv4Addr::from(octets)), &rest[4..])
+                }
+                6 => {
+                    let octets: [u8; 16] =
+                        rest.get(..16).ok_or_else(truncated)?.try_into().unwrap();
+                    (IpAddr::V6(Ipv6Addr::from(octets)), &rest[16..])
+                }
+                other => {
+                    return Err(SyncError::InvalidMessage(format!(
+                        "Unknown address family: {}",
+                        other
+                    )))
+                }
+            };
+            addresses.push(ip);
+            payload = rest;
+        }
+        Ok(addresses)
+    }
+
     /// Runs the discovery requester (sends broadcast requests).
     async fn run_requester(
-        socket: Arc<UdpSocket>,
+        socket: Arc<DiscoverySockets>,
         config: DiscoveryConfig,
         sync_config: Arc<SyncConfig>,
         _known_hubs: Arc<RwLock<HashMap<String, DiscoveredHub>>>,
@@ -478,15 +728,8 @@ impl DiscoveryService {
             // Build discovery request message
             let msg = Self::build_discovery_request(&sync_config);
 
-            // Send broadcast to 255.255.255.255
-            let broadcast_addr = SocketAddr::new(
-                IpAddr::V4(Ipv4Addr::BROADCAST),
-                config.discovery_port,
-            );
-
-            if let Err(e) = socket.send_to(&msg, broadcast_addr).await {
-                warn!(?e, "Failed to send discovery broadcast");
-            }
+            // Broadcast on every interface
+            socket.send_request(&msg, config.discovery_port).await;
         }
     }
 
@@ -509,11 +752,13 @@ impl DiscoveryService {
         msg
     }
 
-    /// Builds a hub announcement message.
+    /// Builds a hub announcement message listing `addresses` (the hub's
+    /// listening addresses; at most 255 are sent).
     pub fn build_hub_announce(
         sync_config: &SyncConfig,
         ws_port: u16,
         election_term: u64,
+        addresses: &[IpAddr],
     ) -> Vec<u8> {
         let mut msg = Vec::with_capacity(256);
 
@@ -541,6 +786,21 @@ impl DiscoveryService {
         msg.push(store_id.len() as u8);
         msg.extend_from_slice(store_id);
 
+        let addresses = &addresses[..addresses.len().min(u8::MAX as usize)];
+        msg.push(addresses.len() as u8);
+        for ip in addresses {
+            match ip {
+                IpAddr::V4(v4) => {
+                    msg.push(4);
+                    msg.extend_from_slice(&v4.octets());
+                }
+                IpAddr::V6(v6) => {
+                    msg.push(6);
+                    msg.extend_from_slice(&v6.octets());
+                }
+            }
+        }
+
         msg
     }
 }
@@ -572,24 +832,11 @@ pub async fn discover_hubs(
     info!("Starting hub discovery scan");
 
     // Bind to any available port for sending
-    let socket = UdpSocket::bind("0.0.0.0:0").await.map_err(|e| {
-        SyncError::ConnectionFailed(format!("Failed to bind discovery socket: {}", e))
-    })?;
-
-    socket.set_broadcast(true).map_err(|e| {
-        SyncError::ConnectionFailed(format!("Failed to enable broadcast: {}", e))
-    })?;
+    let socket = DiscoverySockets::bind(0).await?;
 
-    // Build and send discovery request
+    // Build and send discovery request on every interface
     let request = DiscoveryService::build_discovery_request(sync_config);
-    let broadcast_addr = SocketAddr::new(
-        IpAddr::V4(Ipv4Addr::BROADCAST),
-        config.discovery_port,
-    );
-
-    socket.send_to(&request, broadcast_addr).await.map_err(|e| {
-        SyncError::ConnectionFailed(format!("Failed to send discovery broadcast: {}", e))
-    })?;
+    socket.send_request(&request, config.discovery_port).await;
 
     debug!("Sent discovery broadcast, waiting for responses");
 
@@ -621,8 +868,8 @@ pub async fn discover_hubs(
                         if let Ok(Some(hub)) =
                             DiscoveryService::parse_hub_announce(&data[6..], addr.ip())
                         {
-                            // Skip ourselves
-                            if hub.device_id != sync_config.device_id() {
+                            // Skip ourselves and other stores' hubs
+                            if hub.is_eligible(sync_config) {
                                 info!(
                                     device_id = %hub.device_id,
                                     ip = %hub.ip_address,
@@ -688,7 +935,7 @@ mod tests {
     #[test]
     fn test_build_hub_announce() {
         let sync_config = SyncConfig::default();
-        let msg = DiscoveryService::build_hub_announce(&sync_config, 8765, 1);
+        let msg = DiscoveryService::build_hub_announce(&sync_config, 8765, 1, &[]);
 
         // Check magic
         assert_eq!(&msg[0..4], DISCOVERY_MAGIC);
@@ -707,12 +954,95 @@ mod tests {
             device_name: "Test".into(),
             store_id: "store-1".into(),
             ip_address: IpAddr::V4(Ipv4Addr::new(192, 168, 1, 100)),
+            addresses: vec![],
             ws_port: 8765,
             election_term: 1,
             priority: 50,
             discovered_at: Instant::now(),
         };
 
-        assert_eq!(hub.ws_url(), "ws://192.168.1.100:8765/sync");
+        assert_eq!(hub.ws_url(), "ws://192.168.1.100:8765/ws");
+
+        let hub = DiscoveredHub {
+            ip_address: "fd00:1::10".parse().unwrap(),
+            ..hub
+        };
+        assert_eq!(hub.ws_url(), "ws://[fd00:1::10]:8765/ws");
+    }
+
+    #[test]
+    fn test_hub_announce_carries_addresses() {
+        let sync_config = SyncConfig::default();
+        let addresses: Vec<IpAddr> = vec![
+            "192.168.1.10".parse().unwrap(),
+            "10.20.0.5".parse().unwrap(),
+            "fd00:1::10".parse().unwrap(),
+        ];
+        let msg = DiscoveryService::build_hub_announce(&sync_config, 8765, 2, &addresses);
+
+        // Replies from a dialable address use it
+        let from: IpAddr = "10.20.0.5".parse().unwrap();
+        let hub = DiscoveryService::parse_hub_announce(&msg[6..], from)
+            .unwrap()
+            .unwrap();
+        assert_eq!(hub.addresses, addresses);
+        assert_eq!(hub.ip_address, from);
+
+        // A link-local IPv6 source falls back to an announced IPv6 address
+        let hub = DiscoveryService::parse_hub_announce(&msg[6..], "fe80::1".parse().unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(hub.ip_address, addresses[2]);
+
+        // Announcements from older hubs end at the store ID
+        let legacy = &msg[..msg.len() - (1 + 5 + 5 + 17)];
+        let hub = DiscoveryService::parse_hub_announce(&legacy[6..], from)
+            .unwrap()
+            .unwrap();
+        assert!(hub.addresses.is_empty());
+        assert_eq!(hub.ip_address, from);
+    }
+
+    #[test]
+    fn test_hub_request_carries_store_id() {
+        let mut sync_config = SyncConfig::default();
+        sync_config.store.id = "store-downtown".into();
+        let msg = DiscoveryService::build_discovery_request(&sync_config);
+
+        let store_id = DiscoveryService::parse_hub_request(&msg[6..]).unwrap();
+        assert_eq!(store_id, "store-downtown");
+        assert!(DiscoveryService::parse_hub_request(&[9, b'x']).is_err());
+    }
+
+    #[test]
+    fn test_hub_eligibility_is_store_scoped() {
+        let mut hub_config = SyncConfig::default();
+        hub_config.device.id = "pos-01".into();
+        hub_config.store.id = "store-downtown".into();
+        let announce = DiscoveryService::build_hub_announce(&hub_config, 8765, 4, &[]);
+        let ip = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 10));
+        let hub = DiscoveryService::parse_hub_announce(&announce[6..], ip)
+            .unwrap()
+            .unwrap();
+
+        let mut sync_config = SyncConfig::default();
+        sync_config.device.id = "pos-02".into();
+        sync_config.store.id = "store-downtown".into();
+        assert!(hub.is_eligible(&sync_config));
+
+        // Neighbouring store on the same LAN
+        sync_config.store.id = "store-uptown".into();
+        assert!(!hub.is_eligible(&sync_config));
+        sync_config.store.id = "store-downtown".into();
+
+        // Allow-list in force
+        sync_config.discovery.allowed_hubs = vec!["pos-03".into()];
+        assert!(!hub.is_eligible(&sync_config));
+        sync_config.discovery.allowed_hubs.push("pos-01".into());
+        assert!(hub.is_eligible(&sync_config));
+
+        // Never ourselves
+        sync_config.device.id = "pos-01".into();
+        assert!(!hub.is_eligible(&sync_config));
     }
 }
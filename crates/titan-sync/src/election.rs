@@ -340,6 +340,11 @@ impl ElectionService {
                 info!("Offline mode - election disabled");
                 NodeRole::Offline
             }
+            SyncMode::Peer => {
+                // Both terminals serve a hub; neither leads
+                info!("Peer mode - paired terminal, election disabled");
+                NodeRole::Secondary
+            }
             SyncMode::Auto => {
                 // Start discovery and election
                 info!("Auto mode - starting discovery");
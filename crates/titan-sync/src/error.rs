@@ -13,6 +13,8 @@
 //! │  │  InvalidConfig  │  │  Connection     │  │  InvalidMessage         │ │
 //! │  │  MissingDeviceId│  │  Disconnected   │  │  UnsupportedVersion     │ │
 //! │  │  InvalidUrl     │  │  Timeout        │  │  DeserializationFailed  │ │
+//! │  │                 │  │  Heartbeat      │  │  UpgradeRequired        │ │
+//! │  │                 │  │                 │  │  IntegrityFailed        │ │
 //! │  └─────────────────┘  └─────────────────┘  └─────────────────────────┘ │
 //! │                                                                         │
 //! │  ┌─────────────────┐  ┌─────────────────┐  ┌─────────────────────────┐ │
@@ -20,11 +22,14 @@
 //! │  │                 │  │                 │  │                         │ │
 //! │  │  QueryFailed    │  │  BatchFailed    │  │  ApplyFailed            │ │
 //! │  │  MigrationError │  │  EmptyPayload   │  │  ConflictDetected       │ │
+//! │  │                 │  │  OutsideWindow  │  │                         │ │
+//! │  │                 │  │  Paused         │  │                         │ │
 //! │  └─────────────────┘  └─────────────────┘  └─────────────────────────┘ │
 //! └─────────────────────────────────────────────────────────────────────────┘
 //! ```
 
 use thiserror::Error;
+use titan_core::ErrorCode;
 
 /// Result type alias for sync operations.
 pub type SyncResult<T> = Result<T, SyncError>;
@@ -83,6 +88,10 @@ pub enum SyncError {
     #[error("WebSocket error: {0}")]
     WebSocketError(String),
 
+    /// The hub stopped answering pings (half-open connection).
+    #[error("No pong for {0} consecutive pings")]
+    HeartbeatTimeout(u32),
+
     // =========================================================================
     // Protocol Errors
     // =========================================================================
@@ -94,6 +103,14 @@ pub enum SyncError {
     #[error("Unsupported protocol version: {0}")]
     UnsupportedVersion(u32),
 
+    /// Hub and terminal share no protocol version; one must be upgraded.
+    #[error("Upgrade required: {0}")]
+    UpgradeRequired(String),
+
+    /// A LAN message failed signature or replay checks.
+    #[error("Message integrity check failed: {0}")]
+    IntegrityFailed(String),
+
     /// Failed to serialize message.
     #[error("Serialization failed: {0}")]
     SerializationFailed(String),
@@ -132,6 +149,14 @@ pub enum SyncError {
     #[error("Max retries exceeded for outbox entry {id}: {last_error}")]
     MaxRetriesExceeded { id: String, last_error: String },
 
+    /// Metered mode only allows uploads inside the configured time windows.
+    #[error("Upload deferred: outside the metered sync window")]
+    OutsideSyncWindow,
+
+    /// Sync was paused by an operator; nothing moves until it is resumed.
+    #[error("Sync is paused")]
+    Paused,
+
     // =========================================================================
     // Inbound Errors
     // =========================================================================
@@ -174,6 +199,10 @@ pub enum SyncError {
     #[error("Protocol error: {0}")]
     ProtocolError(String),
 
+    /// The hub is shedding load (full, or this terminal is sending too fast).
+    #[error("Hub busy: {0}")]
+    HubBusy(String),
+
     /// Discovery failed.
     #[error("Discovery failed: {0}")]
     DiscoveryFailed(String),
@@ -216,7 +245,20 @@ pub enum SyncError {
 
 impl From<titan_db::DbError> for SyncError {
     fn from(err: titan_db::DbError) -> Self {
-        SyncError::DatabaseError(err.to_string())
+        match err {
+            titan_db::DbError::VersionConflict {
+                entity,
+                id,
+                expected,
+                actual,
+            } => SyncError::ConflictDetected {
+                entity_type: entity,
+                entity_id: id,
+                local_version: actual,
+                remote_version: expected,
+            },
+            other => SyncError::DatabaseError(other.to_string()),
+        }
     }
 }
 
@@ -275,26 +317,66 @@ impl From<sqlx::Error> for SyncError {
 // =============================================================================
 
 impl SyncError {
+    /// Stable code for this error, as sent to the frontend and the hub.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            SyncError::InvalidConfig(_)
+            | SyncError::MissingDeviceId
+            | SyncError::InvalidUrl(_)
+            | SyncError::ConfigLoadFailed(_)
+            | SyncError::ConfigSaveFailed(_) => ErrorCode::ConfigError,
+
+            SyncError::ConnectionFailed(_)
+            | SyncError::Disconnected
+            | SyncError::WebSocketError(_)
+            | SyncError::HeartbeatTimeout(_)
+            | SyncError::OutboxBatchFailed(_) => ErrorCode::Unavailable,
+            SyncError::Timeout(_) => ErrorCode::Timeout,
+            SyncError::TlsError(_)
+            | SyncError::TransportError(_)
+            | SyncError::DiscoveryFailed(_)
+            | SyncError::ElectionError(_) => ErrorCode::Internal,
+
+            SyncError::UpgradeRequired(_) | SyncError::UnsupportedVersion(_) => {
+                ErrorCode::UpgradeRequired
+            }
+            SyncError::IntegrityFailed(_) => ErrorCode::IntegrityFailed,
+            SyncError::InvalidMessage(_)
+            | SyncError::SerializationFailed(_)
+            | SyncError::DeserializationFailed(_)
+            | SyncError::UnexpectedMessageType { .. }
+            | SyncError::ProtocolError(_) => ErrorCode::ProtocolError,
+
+            SyncError::DatabaseError(_) | SyncError::MigrationFailed(_) => ErrorCode::DatabaseError,
+
+            SyncError::EmptyPayload { .. } | SyncError::ApplyFailed(_) => {
+                ErrorCode::ValidationError
+            }
+            SyncError::MaxRetriesExceeded { .. } => ErrorCode::Internal,
+            SyncError::OutsideSyncWindow => ErrorCode::SyncDeferred,
+            SyncError::Paused => ErrorCode::SyncPaused,
+            SyncError::HubBusy(_) => ErrorCode::Busy,
+            SyncError::ConflictDetected { .. } => ErrorCode::Conflict,
+
+            SyncError::Internal(_) | SyncError::ShuttingDown | SyncError::ChannelError(_) => {
+                ErrorCode::Internal
+            }
+
+            SyncError::AuthFailed(_) | SyncError::TokenExpired(_) => ErrorCode::AuthFailed,
+            SyncError::Connection(_)
+            | SyncError::Cloud(_)
+            | SyncError::Upload(_)
+            | SyncError::Download(_) => ErrorCode::CloudError,
+        }
+    }
+
     /// Returns true if this error is recoverable and the operation can be retried.
     ///
-    /// ## Retryable Errors
-    /// - Connection failures (network issues)
-    /// - Timeouts
-    /// - Temporary disconnections
-    ///
-    /// ## Non-Retryable Errors
-    /// - Configuration errors
-    /// - Protocol/version mismatches
-    /// - Permanent conflicts
+    /// Follows the error's [`ErrorCode`]: network drops, timeouts, a busy
+    /// hub and a closed metered window are retryable; configuration,
+    /// protocol mismatches and conflicts are not.
     pub fn is_retryable(&self) -> bool {
-        matches!(
-            self,
-            SyncError::ConnectionFailed(_)
-                | SyncError::Disconnected
-                | SyncError::Timeout(_)
-                | SyncError::WebSocketError(_)
-                | SyncError::OutboxBatchFailed(_)
-        )
+        self.code().is_retryable()
     }
 
     /// Returns true if this error indicates a configuration problem.
@@ -315,6 +397,8 @@ impl SyncError {
             self,
             SyncError::InvalidMessage(_)
                 | SyncError::UnsupportedVersion(_)
+                | SyncError::UpgradeRequired(_)
+                | SyncError::IntegrityFailed(_)
                 | SyncError::SerializationFailed(_)
                 | SyncError::DeserializationFailed(_)
                 | SyncError::UnexpectedMessageType { .. }
@@ -331,10 +415,38 @@ mod tests {
         assert!(SyncError::ConnectionFailed("network error".into()).is_retryable());
         assert!(SyncError::Disconnected.is_retryable());
         assert!(SyncError::Timeout(30).is_retryable());
+        assert!(SyncError::HeartbeatTimeout(2).is_retryable());
+        assert!(SyncError::OutsideSyncWindow.is_retryable());
+        assert!(SyncError::HubBusy("at capacity".into()).is_retryable());
 
         assert!(!SyncError::InvalidConfig("bad config".into()).is_retryable());
         assert!(!SyncError::MissingDeviceId.is_retryable());
         assert!(!SyncError::UnsupportedVersion(99).is_retryable());
+        assert!(!SyncError::UpgradeRequired("hub too old".into()).is_retryable());
+        assert!(SyncError::UpgradeRequired("hub too old".into()).is_protocol_error());
+        assert!(!SyncError::Paused.is_retryable());
+    }
+
+    #[test]
+    fn test_error_codes() {
+        assert_eq!(SyncError::HubBusy("full".into()).code(), ErrorCode::Busy);
+        assert_eq!(SyncError::Disconnected.code(), ErrorCode::Unavailable);
+        assert_eq!(SyncError::MissingDeviceId.code(), ErrorCode::ConfigError);
+        assert_eq!(
+            SyncError::IntegrityFailed("bad signature".into()).code(),
+            ErrorCode::IntegrityFailed
+        );
+        assert_eq!(SyncError::Paused.code(), ErrorCode::SyncPaused);
+    }
+
+    #[test]
+    fn test_version_conflict_converts() {
+        let err = SyncError::from(titan_db::DbError::version_conflict("Product", "p-1", 4, 5));
+        assert!(matches!(
+            err,
+            SyncError::ConflictDetected { local_version: 5, remote_version: 4, .. }
+        ));
+        assert!(!err.is_retryable());
     }
 
     #[test]
@@ -31,11 +31,27 @@
 //! │  Message Flow:                                                          │
 //! │  ─────────────                                                          │
 //! │  1. SECONDARY connects with Hello message                              │
-//! │  2. Hub responds with Welcome (includes current term)                  │
+//! │  2. Hub negotiates the protocol version and responds with Welcome      │
+//! │     (current term, negotiated version) or UPGRADE_REQUIRED             │
 //! │  3. SECONDARY sends InventoryDelta messages                            │
 //! │  4. Hub broadcasts InventoryUpdate to all connected devices            │
+//! │     (EntityUpdates only for the entity types a device subscribed to    │
+//! │     in its Hello; all of them when it named none)                      │
 //! │  5. Hub sends periodic Heartbeat to maintain connection                │
 //! │                                                                         │
+//! │  With a store enrollment key every message is signed and replay-       │
+//! │  checked (see `integrity`); unsigned clients get AUTH_FAILED.          │
+//! │  Past max_clients, or a client's message rate, the hub answers BUSY    │
+//! │  (see `shedding`).                                                      │
+//! │                                                                         │
+//! │  Given the database (`with_catalog`), the hub also serves the          │
+//! │  read-only /api/catalog/* routes for thin clients (see `catalog_api`). │
+//! │  Given the day-end board (`with_admin`), it serves /api/admin/* with   │
+//! │  the terminals' day-end reports (see `admin_api`).                     │
+//! │                                                                         │
+//! │  With `recording` set, every message in and out is also written to a   │
+//! │  ring file, customer fields redacted (see `recorder`).                 │
+//! │                                                                         │
 //! └─────────────────────────────────────────────────────────────────────────┘
 //! ```
 
@@ -53,15 +69,24 @@ use axum::{
     Router,
 };
 use futures_util::{SinkExt, StreamExt};
+use titan_core::ErrorCode;
+use titan_db::Database;
 use tokio::net::TcpListener;
 use tokio::sync::{broadcast, mpsc, RwLock};
 use tokio::time::{interval, Duration};
 use tracing::{debug, error, info, warn};
 
-use crate::config::SyncConfig;
+use crate::admin_api::{self, DayEndBoard};
+use crate::catalog_api;
+use crate::compat;
+use crate::config::{join_host_port, HubSettings, SyncConfig};
 use crate::election::ElectionHandle;
 use crate::error::{SyncError, SyncResult};
+use crate::integrity::Session;
+use crate::netif;
 use crate::protocol::{HelloPayload, SyncMessage, WelcomePayload};
+use crate::recorder::{Direction, HubRecorder, RecordingConfig};
+use crate::shedding::{self, Admission, ClientLimits, MessageLimiter};
 
 // =============================================================================
 // Constants
@@ -76,6 +101,9 @@ const PING_INTERVAL: Duration = Duration::from_secs(30);
 /// Maximum message size (1MB).
 const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
 
+/// Signing session shared by a connection's send and receive paths.
+type SharedSession = Arc<std::sync::Mutex<Session>>;
+
 // =============================================================================
 // Hub Configuration
 // =============================================================================
@@ -87,6 +115,16 @@ pub struct HubConfig {
     pub port: u16,
     /// Bind address (default: 0.0.0.0).
     pub bind_addr: String,
+    /// Interfaces to listen on; empty listens on `bind_addr` alone.
+    pub interfaces: Vec<String>,
+    /// Connection and per-client message limits.
+    pub limits: ClientLimits,
+    /// Bearer token for the catalog API (None leaves it open).
+    pub catalog_token: Option<String>,
+    /// Bearer token for the admin API (None leaves it open).
+    pub admin_token: Option<String>,
+    /// Where to record hub traffic (None records nothing).
+    pub recording: Option<RecordingConfig>,
 }
 
 impl Default for HubConfig {
@@ -94,6 +132,28 @@ impl Default for HubConfig {
         HubConfig {
             port: DEFAULT_HUB_PORT,
             bind_addr: "0.0.0.0".to_string(),
+            interfaces: Vec::new(),
+            limits: ClientLimits::default(),
+            catalog_token: None,
+            admin_token: None,
+            recording: None,
+        }
+    }
+}
+
+impl From<&HubSettings> for HubConfig {
+    fn from(settings: &HubSettings) -> Self {
+        HubConfig {
+            port: settings.port,
+            bind_addr: settings.bind_addr.clone(),
+            interfaces: settings.interfaces.clone(),
+            limits: ClientLimits::from(settings),
+            catalog_token: settings.catalog_token.clone(),
+            admin_token: settings.admin_token.clone(),
+            recording: settings.record_path.clone().map(|path| RecordingConfig {
+                path,
+                max_messages: settings.record_max_messages,
+            }),
         }
     }
 }
@@ -101,7 +161,27 @@ impl Default for HubConfig {
 impl HubConfig {
     /// Returns the full bind address.
     pub fn bind_address(&self) -> String {
-        format!("{}:{}", self.bind_addr, self.port)
+        join_host_port(&self.bind_addr, self.port)
+    }
+
+    /// Addresses to listen on: one per address of the configured
+    /// interfaces, or just the bind address.
+    fn listen_addresses(&self) -> SyncResult<Vec<String>> {
+        if self.interfaces.is_empty() {
+            return Ok(vec![self.bind_address()]);
+        }
+
+        let addresses: Vec<String> = netif::local_addresses(&self.interfaces)?
+            .iter()
+            .map(|a| SocketAddr::new(a.ip, self.port).to_string())
+            .collect();
+        if addresses.is_empty() {
+            return Err(SyncError::TransportError(format!(
+                "No usable address on interfaces {:?}",
+                self.interfaces
+            )));
+        }
+        Ok(addresses)
     }
 }
 
@@ -120,6 +200,10 @@ pub struct ConnectedClient {
     pub addr: SocketAddr,
     /// Connection time.
     pub connected_at: std::time::Instant,
+    /// Protocol version negotiated in the handshake.
+    pub protocol_version: u32,
+    /// Messages addressed to this client alone.
+    direct_tx: mpsc::Sender<SyncMessage>,
 }
 
 // =============================================================================
@@ -134,10 +218,14 @@ pub struct HubState {
     election: ElectionHandle,
     /// Connected clients.
     clients: RwLock<HashMap<String, ConnectedClient>>,
+    /// Connection and per-client message limits.
+    limits: ClientLimits,
     /// Broadcast channel for sending messages to all clients.
     broadcast_tx: broadcast::Sender<SyncMessage>,
     /// Channel for receiving inventory deltas from clients.
     delta_tx: mpsc::Sender<(String, SyncMessage)>,
+    /// Traffic recorder, when recording is on.
+    recorder: Option<HubRecorder>,
 }
 
 impl HubState {
@@ -146,14 +234,26 @@ impl HubState {
         sync_config: Arc<SyncConfig>,
         election: ElectionHandle,
         delta_tx: mpsc::Sender<(String, SyncMessage)>,
+        limits: ClientLimits,
+        recorder: Option<HubRecorder>,
     ) -> Self {
         let (broadcast_tx, _) = broadcast::channel(256);
         HubState {
             sync_config,
             election,
             clients: RwLock::new(HashMap::new()),
+            limits,
             broadcast_tx,
             delta_tx,
+            recorder,
+        }
+    }
+
+    /// Records a message to or from `device_id`, `size` bytes on the
+    /// wire, when recording is on.
+    fn record(&self, direction: Direction, device_id: &str, msg: &SyncMessage, size: usize) {
+        if let Some(recorder) = &self.recorder {
+            recorder.record(direction, device_id, msg, size);
         }
     }
 
@@ -163,6 +263,24 @@ impl HubState {
         Ok(())
     }
 
+    /// Sends a message to one connected client.
+    ///
+    /// Unlike [`broadcast`](Self::broadcast) this waits for room in the
+    /// client's queue, so long streams (a full resync) are not dropped.
+    pub async fn send_to(&self, device_id: &str, msg: SyncMessage) -> SyncResult<()> {
+        let direct_tx = self
+            .clients
+            .read()
+            .await
+            .get(device_id)
+            .map(|client| client.direct_tx.clone())
+            .ok_or_else(|| SyncError::ChannelError(format!("Device {} not connected", device_id)))?;
+        direct_tx
+            .send(msg)
+            .await
+            .map_err(|_| SyncError::ChannelError(format!("Device {} disconnected", device_id)))
+    }
+
     /// Returns the number of connected clients.
     pub async fn client_count(&self) -> usize {
         self.clients.read().await.len()
@@ -184,6 +302,10 @@ pub struct HubServer {
     config: HubConfig,
     /// Shared hub state.
     state: Arc<HubState>,
+    /// Database served by the catalog API, when enabled.
+    catalog_db: Option<Arc<Database>>,
+    /// Day-end reports served by the admin API, when enabled.
+    day_end_board: Option<DayEndBoard>,
 }
 
 /// Handle for controlling the hub server.
@@ -201,6 +323,16 @@ impl HubHandle {
         self.state.broadcast(msg)
     }
 
+    /// Subscribes to everything broadcast to the connected clients.
+    pub fn subscribe(&self) -> broadcast::Receiver<SyncMessage> {
+        self.state.broadcast_tx.subscribe()
+    }
+
+    /// Sends a message to one connected client.
+    pub async fn send_to(&self, device_id: &str, msg: SyncMessage) -> SyncResult<()> {
+        self.state.send_to(device_id, msg).await
+    }
+
     /// Returns the number of connected clients.
     pub async fn client_count(&self) -> usize {
         self.state.client_count().await
@@ -211,6 +343,11 @@ impl HubHandle {
         self.state.client_ids().await
     }
 
+    /// The hub's own device ID.
+    pub fn device_id(&self) -> &str {
+        self.state.sync_config.device_id()
+    }
+
     /// Shuts down the hub server.
     pub async fn shutdown(&self) -> SyncResult<()> {
         self.shutdown_tx
@@ -228,8 +365,33 @@ impl HubServer {
         election: ElectionHandle,
         delta_tx: mpsc::Sender<(String, SyncMessage)>,
     ) -> Self {
-        let state = Arc::new(HubState::new(sync_config, election, delta_tx));
-        HubServer { config, state }
+        let recorder = config.recording.clone().map(HubRecorder::start);
+        let state = Arc::new(HubState::new(
+            sync_config,
+            election,
+            delta_tx,
+            config.limits,
+            recorder,
+        ));
+        HubServer {
+            config,
+            state,
+            catalog_db: None,
+            day_end_board: None,
+        }
+    }
+
+    /// Also serves the catalog API for thin clients from `db`.
+    pub fn with_catalog(mut self, db: Arc<Database>) -> Self {
+        self.catalog_db = Some(db);
+        self
+    }
+
+    /// Also serves the admin API, showing the day-end reports kept in
+    /// `board` (share it with `DeltaProcessor::with_day_end_board`).
+    pub fn with_admin(mut self, board: DayEndBoard) -> Self {
+        self.day_end_board = Some(board);
+        self
     }
 
     /// Starts the hub server and returns a handle.
@@ -242,28 +404,45 @@ impl HubServer {
         };
 
         // Build the router
-        let app = Router::new()
+        let mut app = Router::new()
             .route("/ws", get(ws_handler))
             .route("/health", get(health_handler))
             .with_state(self.state.clone());
+        if let Some(db) = self.catalog_db {
+            app = app.merge(catalog_api::router(db, self.config.catalog_token.clone()));
+        }
+        if let Some(board) = self.day_end_board {
+            app = app.merge(admin_api::router(board, self.config.admin_token.clone()));
+        }
 
-        // Bind the listener
-        let bind_addr = self.config.bind_address();
-        let listener = TcpListener::bind(&bind_addr)
-            .await
-            .map_err(|e| SyncError::TransportError(format!("Failed to bind to {}: {}", bind_addr, e)))?;
-
-        info!(addr = %bind_addr, "Hub server started");
+        // Bind the listeners
+        let mut listeners = Vec::new();
+        for bind_addr in self.config.listen_addresses()? {
+            let listener = TcpListener::bind(&bind_addr).await.map_err(|e| {
+                SyncError::TransportError(format!("Failed to bind to {}: {}", bind_addr, e))
+            })?;
+            info!(addr = %bind_addr, "Hub server started");
+            listeners.push(listener);
+        }
 
-        // Spawn the server
+        // Spawn one server per listener, all stopped by the same signal
+        let (stop_tx, _) = broadcast::channel::<()>(1);
+        for listener in listeners {
+            let mut stop_rx = stop_tx.subscribe();
+            let app = app.clone();
+            tokio::spawn(async move {
+                axum::serve(listener, app)
+                    .with_graceful_shutdown(async move {
+                        let _ = stop_rx.recv().await;
+                    })
+                    .await
+                    .ok();
+            });
+        }
         tokio::spawn(async move {
-            axum::serve(listener, app)
-                .with_graceful_shutdown(async move {
-                    shutdown_rx.recv().await;
-                    info!("Hub server shutting down");
-                })
-                .await
-                .ok();
+            shutdown_rx.recv().await;
+            info!("Hub server shutting down");
+            let _ = stop_tx.send(());
         });
 
         Ok(handle)
@@ -294,17 +473,31 @@ async fn ws_handler(
 async fn handle_socket(socket: WebSocket, state: Arc<HubState>, addr: SocketAddr) {
     let (mut sender, mut receiver) = socket.split();
 
-    // Wait for Hello message
-    let hello = match receive_hello(&mut receiver).await {
+    // Wait for Hello message, verifying its signature when the store is
+    // enrolled
+    let enrollment_key = state.sync_config.store.enrollment_key.as_deref();
+    let (hello, session, hello_size) = match receive_hello(&mut receiver, enrollment_key).await {
         Ok(hello) => hello,
         Err(e) => {
             warn!(addr = %addr, ?e, "Failed to receive Hello - closing connection");
+            if matches!(e, SyncError::IntegrityFailed(_)) {
+                let reject_msg =
+                    SyncMessage::error(ErrorCode::AuthFailed, "Device is not enrolled in this store");
+                let unknown = addr.to_string();
+                let _ = send_message(&state, &unknown, &mut sender, &reject_msg, None).await;
+            }
             return;
         }
     };
 
     let device_id = hello.device_id.clone();
     let store_id = hello.store_id.clone();
+    state.record(
+        Direction::Inbound,
+        &device_id,
+        &SyncMessage::Hello(hello.clone()),
+        hello_size,
+    );
 
     // Verify store_id matches
     if store_id != state.sync_config.store_id() {
@@ -314,26 +507,64 @@ async fn handle_socket(socket: WebSocket, state: Arc<HubState>, addr: SocketAddr
             our_store = %state.sync_config.store_id(),
             "Store ID mismatch - rejecting connection"
         );
-        let reject_msg = SyncMessage::Error {
-            code: "STORE_MISMATCH".to_string(),
-            message: "Store ID does not match".to_string(),
-        };
-        if let Ok(json) = serde_json::to_string(&reject_msg) {
-            let _ = sender.send(Message::Text(json.into())).await;
-        }
+        let reject_msg = SyncMessage::error(ErrorCode::StoreMismatch, "Store ID does not match");
+        let _ = send_message(
+            &state,
+            &device_id,
+            &mut sender,
+            &reject_msg,
+            session.as_ref(),
+        )
+        .await;
         return;
     }
 
+    // Settle on a protocol version both sides speak
+    let protocol_version =
+        match compat::negotiate(hello.protocol_version, hello.min_protocol_version) {
+            Ok(version) => version,
+            Err(mismatch) => {
+                let older = if mismatch.peer_is_older() { "terminal" } else { "hub" };
+                warn!(
+                    device_id = %device_id,
+                    %mismatch,
+                    "Protocol version mismatch - rejecting connection"
+                );
+                let reject_msg =
+                    SyncMessage::error(ErrorCode::UpgradeRequired, &mismatch.message(older));
+                let _ = send_message(
+                    &state,
+                    &device_id,
+                    &mut sender,
+                    &reject_msg,
+                    session.as_ref(),
+                )
+                .await;
+                return;
+            }
+        };
+
     info!(
         device_id = %device_id,
         store_id = %store_id,
         addr = %addr,
+        protocol_version,
         "Client authenticated"
     );
 
-    // Register client
+    // Register client, unless the hub is full. A device reconnecting
+    // replaces its own entry, so it is always let back in.
+    let (direct_tx, mut direct_rx) = mpsc::channel::<SyncMessage>(64);
     {
         let mut clients = state.clients.write().await;
+        let max_clients = state.limits.max_clients;
+        if clients.len() >= max_clients && !clients.contains_key(&device_id) {
+            drop(clients);
+            warn!(device_id = %device_id, max_clients, "Hub at capacity - turning client away");
+            let busy = shedding::at_capacity(max_clients);
+            let _ = send_message(&state, &device_id, &mut sender, &busy, session.as_ref()).await;
+            return;
+        }
         clients.insert(
             device_id.clone(),
             ConnectedClient {
@@ -341,6 +572,8 @@ async fn handle_socket(socket: WebSocket, state: Arc<HubState>, addr: SocketAddr
                 store_id: store_id.clone(),
                 addr,
                 connected_at: std::time::Instant::now(),
+                protocol_version,
+                direct_tx,
             },
         );
     }
@@ -352,9 +585,11 @@ async fn handle_socket(socket: WebSocket, state: Arc<HubState>, addr: SocketAddr
         store_id: state.sync_config.store_id().to_string(),
         election_term: term,
         server_time: chrono::Utc::now().to_rfc3339(),
+        protocol_version,
     });
 
-    if let Err(e) = send_message(&mut sender, &welcome).await {
+    let sent = send_message(&state, &device_id, &mut sender, &welcome, session.as_ref()).await;
+    if let Err(e) = sent {
         warn!(device_id = %device_id, ?e, "Failed to send Welcome");
         remove_client(&state, &device_id).await;
         return;
@@ -365,28 +600,34 @@ async fn handle_socket(socket: WebSocket, state: Arc<HubState>, addr: SocketAddr
 
     // Spawn task for sending broadcasts
     let sender_device_id = device_id.clone();
-    let (outgoing_tx, mut outgoing_rx) = mpsc::channel::<Message>(64);
-
-    // Outgoing message task
-    let outgoing_handle = tokio::spawn(async move {
-        while let Some(msg) = outgoing_rx.recv().await {
-            if sender.send(msg).await.is_err() {
-                break;
-            }
-        }
-    });
+    let (outgoing_tx, outgoing_rx) = mpsc::channel::<Outgoing>(64);
+
+    // Outgoing message task, the only one that seals frames
+    let writer_state = state.clone();
+    let writer_device_id = device_id.clone();
+    let outgoing_handle = tokio::spawn(write_outgoing(
+        outgoing_rx,
+        sender,
+        session.clone(),
+        move |msg, size| writer_state.record(Direction::Outbound, &writer_device_id, msg, size),
+    ));
 
     // Broadcast forwarding task
     let outgoing_tx_clone = outgoing_tx.clone();
+    let broadcast_hello = hello.clone();
     let broadcast_handle = tokio::spawn(async move {
         loop {
             match broadcast_rx.recv().await {
                 Ok(msg) => {
-                    // Don't send message back to originator
-                    if let Ok(json) = serde_json::to_string(&msg) {
-                        if outgoing_tx_clone.send(Message::Text(json.into())).await.is_err() {
-                            break;
-                        }
+                    if !broadcast_hello.wants(&msg) {
+                        continue;
+                    }
+                    // Down-convert for older terminals
+                    let Some(msg) = compat::downgrade(msg, protocol_version) else {
+                        continue;
+                    };
+                    if outgoing_tx_clone.send(Outgoing::sync(msg)).await.is_err() {
+                        break;
                     }
                 }
                 Err(broadcast::error::RecvError::Lagged(_)) => {
@@ -398,27 +639,71 @@ async fn handle_socket(socket: WebSocket, state: Arc<HubState>, addr: SocketAddr
         }
     });
 
+    // Direct message forwarding task
+    let outgoing_tx_direct = outgoing_tx.clone();
+    let direct_hello = hello.clone();
+    let direct_handle = tokio::spawn(async move {
+        while let Some(msg) = direct_rx.recv().await {
+            if !direct_hello.wants(&msg) {
+                continue;
+            }
+            let Some(msg) = compat::downgrade(msg, protocol_version) else {
+                continue;
+            };
+            if outgoing_tx_direct.send(Outgoing::sync(msg)).await.is_err() {
+                break;
+            }
+        }
+    });
+
     // Ping task
     let outgoing_tx_ping = outgoing_tx.clone();
     let ping_handle = tokio::spawn(async move {
         let mut ping_interval = interval(PING_INTERVAL);
         loop {
             ping_interval.tick().await;
-            if outgoing_tx_ping.send(Message::Ping(axum::body::Bytes::new())).await.is_err() {
+            let ping = Outgoing::Control(Message::Ping(axum::body::Bytes::new()));
+            if outgoing_tx_ping.send(ping).await.is_err() {
                 break;
             }
         }
     });
 
     // Main receive loop
+    let mut limiter = MessageLimiter::new(&state.limits);
     loop {
         match receiver.next().await {
             Some(Ok(msg)) => {
+                // Only data messages count against the rate limit
+                if matches!(msg, Message::Text(_) | Message::Binary(_)) {
+                    if let Admission::Drop { notify } = limiter.admit(tokio::time::Instant::now()) {
+                        if notify {
+                            warn!(
+                                device_id = %device_id,
+                                dropped = limiter.dropped(),
+                                "Client over its message rate - dropping messages"
+                            );
+                            let notice = Outgoing::sync(shedding::rate_limited());
+                            let _ = outgoing_tx.send(notice).await;
+                        }
+                        continue;
+                    }
+                }
+
                 match msg {
                     Message::Text(text) => {
-                        match serde_json::from_str::<SyncMessage>(&text) {
+                        match decode(session.as_ref(), &text) {
                             Ok(sync_msg) => {
-                                handle_client_message(&state, &device_id, sync_msg).await;
+                                handle_client_message(&state, &device_id, sync_msg, text.len())
+                                    .await;
+                            }
+                            Err(e @ SyncError::IntegrityFailed(_)) => {
+                                warn!(
+                                    device_id = %device_id,
+                                    ?e,
+                                    "Rejected unauthenticated message - closing connection"
+                                );
+                                break;
                             }
                             Err(e) => {
                                 debug!(device_id = %device_id, ?e, "Invalid message format");
@@ -426,9 +711,18 @@ async fn handle_socket(socket: WebSocket, state: Arc<HubState>, addr: SocketAddr
                         }
                     }
                     Message::Binary(data) => {
-                        match serde_json::from_slice::<SyncMessage>(&data) {
+                        match decode(session.as_ref(), &String::from_utf8_lossy(&data)) {
                             Ok(sync_msg) => {
-                                handle_client_message(&state, &device_id, sync_msg).await;
+                                handle_client_message(&state, &device_id, sync_msg, data.len())
+                                    .await;
+                            }
+                            Err(e @ SyncError::IntegrityFailed(_)) => {
+                                warn!(
+                                    device_id = %device_id,
+                                    ?e,
+                                    "Rejected unauthenticated message - closing connection"
+                                );
+                                break;
                             }
                             Err(e) => {
                                 debug!(device_id = %device_id, ?e, "Invalid binary message");
@@ -440,7 +734,9 @@ async fn handle_socket(socket: WebSocket, state: Arc<HubState>, addr: SocketAddr
                     }
                     Message::Ping(data) => {
                         // Respond with pong
-                        let _ = outgoing_tx.send(Message::Pong(data)).await;
+                        let _ = outgoing_tx
+                            .send(Outgoing::Control(Message::Pong(data)))
+                            .await;
                     }
                     Message::Close(_) => {
                         info!(device_id = %device_id, "Client requested close");
@@ -462,14 +758,20 @@ async fn handle_socket(socket: WebSocket, state: Arc<HubState>, addr: SocketAddr
     // Cleanup
     ping_handle.abort();
     broadcast_handle.abort();
+    direct_handle.abort();
     outgoing_handle.abort();
     remove_client(&state, &device_id).await;
 }
 
 /// Receives and parses the Hello message.
+///
+/// With an enrollment key the Hello must be signed by the device it names;
+/// the returned session signs and verifies the rest of the connection.
+/// Also returns the Hello's size in bytes.
 async fn receive_hello(
     receiver: &mut futures_util::stream::SplitStream<WebSocket>,
-) -> SyncResult<HelloPayload> {
+    enrollment_key: Option<&str>,
+) -> SyncResult<(HelloPayload, Option<SharedSession>, usize)> {
     // Wait up to 10 seconds for Hello
     let timeout = tokio::time::timeout(Duration::from_secs(10), receiver.next()).await;
 
@@ -481,11 +783,17 @@ async fn receive_hello(
                 _ => return Err(SyncError::ProtocolError("Expected text message".into())),
             };
 
+            if let Some(enrollment_key) = enrollment_key {
+                let (session, payload) = Session::accept(enrollment_key, &text)?;
+                let session = Arc::new(std::sync::Mutex::new(session));
+                return Ok((payload, Some(session), text.len()));
+            }
+
             let sync_msg: SyncMessage = serde_json::from_str(&text)
                 .map_err(|e| SyncError::ProtocolError(format!("Invalid JSON: {}", e)))?;
 
             match sync_msg {
-                SyncMessage::Hello(payload) => Ok(payload),
+                SyncMessage::Hello(payload) => Ok((payload, None, text.len())),
                 _ => Err(SyncError::ProtocolError("Expected Hello message".into())),
             }
         }
@@ -495,13 +803,16 @@ async fn receive_hello(
     }
 }
 
-/// Sends a SyncMessage.
+/// Sends a SyncMessage to `device_id`.
 async fn send_message(
+    state: &HubState,
+    device_id: &str,
     sender: &mut futures_util::stream::SplitSink<WebSocket, Message>,
     msg: &SyncMessage,
+    session: Option<&SharedSession>,
 ) -> SyncResult<()> {
-    let json = serde_json::to_string(msg)
-        .map_err(|e| SyncError::ProtocolError(format!("Serialization error: {}", e)))?;
+    let json = encode(session, msg)?;
+    state.record(Direction::Outbound, device_id, msg, json.len());
     sender
         .send(Message::Text(json.into()))
         .await
@@ -509,9 +820,82 @@ async fn send_message(
     Ok(())
 }
 
-/// Handles a message from a client.
-async fn handle_client_message(state: &HubState, device_id: &str, msg: SyncMessage) {
+/// What a connection's writer task sends.
+///
+/// Sync messages are sealed by the writer itself, in the order they were
+/// queued. A frame sealed by another task could be overtaken on its way to
+/// the queue and reach the terminal after a higher sequence number, which
+/// the terminal rejects as a replay before dropping the connection.
+enum Outgoing {
+    /// A message to encode, and sign on an enrolled connection.
+    Sync(Box<SyncMessage>),
+    /// A WebSocket frame sent as is (pings and pongs).
+    Control(Message),
+}
+
+impl Outgoing {
+    fn sync(msg: SyncMessage) -> Self {
+        Outgoing::Sync(Box::new(msg))
+    }
+}
+
+/// Writes queued messages to `sink` until the queue closes or the socket
+/// fails, calling `sent` with each sync message and its size on the wire.
+async fn write_outgoing<S>(
+    mut outgoing_rx: mpsc::Receiver<Outgoing>,
+    mut sink: S,
+    session: Option<SharedSession>,
+    mut sent: impl FnMut(&SyncMessage, usize),
+) where
+    S: futures_util::Sink<Message> + Unpin,
+{
+    while let Some(outgoing) = outgoing_rx.recv().await {
+        let frame = match outgoing {
+            Outgoing::Sync(msg) => match encode(session.as_ref(), &msg) {
+                Ok(json) => {
+                    sent(&msg, json.len());
+                    Message::Text(json.into())
+                }
+                Err(e) => {
+                    debug!(?e, "Dropping message that could not be encoded");
+                    continue;
+                }
+            },
+            Outgoing::Control(frame) => frame,
+        };
+        if sink.send(frame).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Serializes a message for a client, signing it on an enrolled connection.
+fn encode(session: Option<&SharedSession>, msg: &SyncMessage) -> SyncResult<String> {
+    match session {
+        Some(session) => lock_session(session).seal(msg),
+        None => serde_json::to_string(msg)
+            .map_err(|e| SyncError::ProtocolError(format!("Serialization error: {}", e))),
+    }
+}
+
+/// Parses a client message, verifying it on an enrolled connection.
+fn decode(session: Option<&SharedSession>, text: &str) -> SyncResult<SyncMessage> {
+    match session {
+        Some(session) => lock_session(session).open(text),
+        None => Ok(serde_json::from_str(text)?),
+    }
+}
+
+fn lock_session(session: &SharedSession) -> std::sync::MutexGuard<'_, Session> {
+    // Sealing and opening cannot panic midway, so a poisoned lock still
+    // holds a consistent session
+    session.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Handles a message from a client, `size` bytes on the wire.
+async fn handle_client_message(state: &HubState, device_id: &str, msg: SyncMessage, size: usize) {
     debug!(device_id = %device_id, ?msg, "Received client message");
+    state.record(Direction::Inbound, device_id, &msg, size);
 
     // Forward to delta processor
     if let Err(e) = state.delta_tx.send((device_id.to_string(), msg)).await {
@@ -543,7 +927,58 @@ mod tests {
         let config = HubConfig {
             port: 9000,
             bind_addr: "127.0.0.1".to_string(),
+            ..Default::default()
         };
         assert_eq!(config.bind_address(), "127.0.0.1:9000");
+
+        let v6 = HubConfig {
+            bind_addr: "::".to_string(),
+            ..config
+        };
+        assert_eq!(v6.bind_address(), "[::]:9000");
+        assert_eq!(v6.listen_addresses().unwrap(), ["[::]:9000"]);
+    }
+
+    #[tokio::test]
+    async fn test_writer_seals_frames_in_send_order() {
+        use crate::integrity::{DeviceKey, Role};
+
+        const KEY: &str = "store-001-enrollment-key";
+        let mut terminal = Session::new(DeviceKey::derive(KEY, "pos-02"), Role::Terminal);
+        let hello = terminal
+            .seal(&SyncMessage::hello("pos-02", "Register 2", "store-001", 50))
+            .unwrap();
+        let (hub, _) = Session::accept(KEY, &hello).unwrap();
+        let session = Arc::new(std::sync::Mutex::new(hub));
+
+        // Broadcasts and direct replies race onto the same queue
+        let (outgoing_tx, outgoing_rx) = mpsc::channel(4);
+        let producers: Vec<_> = (0..2)
+            .map(|_| {
+                let tx = outgoing_tx.clone();
+                tokio::spawn(async move {
+                    for _ in 0..50 {
+                        tx.send(Outgoing::sync(SyncMessage::ping())).await.unwrap();
+                        tokio::task::yield_now().await;
+                    }
+                })
+            })
+            .collect();
+        drop(outgoing_tx);
+
+        let mut frames = Vec::new();
+        let mut sent = 0;
+        write_outgoing(outgoing_rx, &mut frames, Some(session), |_, _| sent += 1).await;
+        for producer in producers {
+            producer.await.unwrap();
+        }
+
+        assert_eq!((frames.len(), sent), (100, 100));
+        for frame in frames {
+            let Message::Text(text) = frame else {
+                panic!("expected a text frame");
+            };
+            terminal.open(&text).unwrap();
+        }
     }
 }
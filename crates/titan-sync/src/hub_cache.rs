@@ -0,0 +1,206 @@
+//! # Last-Known-Good Hub
+//!
+//! Finds the hub a SECONDARY should connect to. The hub rarely moves, so
+//! the URL of the last successful handshake is kept in SQLite and tried
+//! first; a discovery scan (several seconds of waiting for announcements)
+//! only runs when that hub no longer answers.
+//!
+//! ## Lookup Order
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                          Hub Lookup Order                               │
+//! │                                                                         │
+//! │  0. peer mode: sync.peer_url ────────────────────────► use as-is       │
+//! │                                                                         │
+//! │  1. sync.hub_url in config ──────────────────────────► use as-is       │
+//! │                                                                         │
+//! │  2. sync_hub_cache (this store)                                         │
+//! │       GET http://<host>:<port>/health  (2s)                             │
+//! │         200 ──────────────────────────────────────────► use cached     │
+//! │         anything else ──► fall through                                  │
+//! │                                                                         │
+//! │  3. discover_hubs() ──► eligible hub with the highest term             │
+//! │                                                                         │
+//! │  Welcome received ──► remember_hub(url, term)                           │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+use tracing::{debug, info, warn};
+use url::Url;
+
+use titan_db::Database;
+
+use crate::config::{SyncConfig, SyncMode};
+use crate::discovery::{discover_hubs, DiscoveredHub, DiscoveryConfig};
+use crate::error::{SyncError, SyncResult};
+
+/// How long the cached hub gets to answer its health check.
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+// =============================================================================
+// Lookup
+// =============================================================================
+
+/// Returns the WebSocket URL of this store's hub.
+pub async fn locate_hub(config: &SyncConfig, db: &Database) -> SyncResult<String> {
+    if config.mode() == SyncMode::Peer {
+        // validate() makes sure it is set
+        return config.peer_url().map(String::from).ok_or_else(|| {
+            SyncError::InvalidConfig("peer_url is required in peer mode".into())
+        });
+    }
+    if let Some(url) = config.hub_url() {
+        return Ok(url.to_string());
+    }
+
+    let store_id = config.store_id();
+    if let Some(cached) = db.sync_outbox().last_known_hub(store_id).await? {
+        if probe(&cached.hub_url, PROBE_TIMEOUT).await {
+            info!(
+                hub_url = %cached.hub_url,
+                term = cached.election_term,
+                "Using last known hub"
+            );
+            return Ok(cached.hub_url);
+        }
+        warn!(hub_url = %cached.hub_url, "Last known hub did not answer, running discovery");
+    }
+
+    let hubs = discover_hubs(&DiscoveryConfig::from(config), config).await?;
+    let hub = newest(&hubs).ok_or_else(|| {
+        SyncError::DiscoveryFailed(format!("No hub found for store {}", store_id))
+    })?;
+    info!(
+        hub = %hub.device_id,
+        term = hub.election_term,
+        "Discovered hub"
+    );
+    Ok(hub.ws_url())
+}
+
+/// The hub with the highest election term; older terms are stale leaders.
+fn newest(hubs: &[DiscoveredHub]) -> Option<&DiscoveredHub> {
+    hubs.iter().max_by_key(|h| h.election_term)
+}
+
+// =============================================================================
+// Health Probe
+// =============================================================================
+
+/// Whether the hub behind `hub_url` answers its health endpoint in time.
+pub async fn probe(hub_url: &str, limit: Duration) -> bool {
+    let Some((addr, request)) = health_request(hub_url) else {
+        return false;
+    };
+
+    let check = async {
+        let mut stream = TcpStream::connect(&addr).await?;
+        stream.write_all(request.as_bytes()).await?;
+        let mut head = [0u8; 12];
+        stream.read_exact(&mut head).await?;
+        Ok::<_, std::io::Error>(is_ok_status(&head))
+    };
+
+    match timeout(limit, check).await {
+        Ok(Ok(healthy)) => healthy,
+        Ok(Err(e)) => {
+            debug!(addr = %addr, ?e, "Hub health probe failed");
+            false
+        }
+        Err(_) => {
+            debug!(addr = %addr, "Hub health probe timed out");
+            false
+        }
+    }
+}
+
+/// The `host:port` to dial and the HTTP request for the hub's health
+/// endpoint.
+fn health_request(hub_url: &str) -> Option<(String, String)> {
+    let url = Url::parse(hub_url).ok()?;
+    let host = match url.host()? {
+        url::Host::Ipv6(ip) => format!("[{}]", ip),
+        other => other.to_string(),
+    };
+    let port = url.port_or_known_default()?;
+    let authority = format!("{}:{}", host, port);
+    let request = format!(
+        "GET /health HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        authority
+    );
+    Some((authority, request))
+}
+
+fn is_ok_status(head: &[u8]) -> bool {
+    head.starts_with(b"HTTP/1.") && head.get(8..12) == Some(b" 200".as_slice())
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{IpAddr, Ipv4Addr};
+    use tokio::time::Instant;
+
+    fn hub(device_id: &str, term: u64) -> DiscoveredHub {
+        DiscoveredHub {
+            device_id: device_id.to_string(),
+            device_name: device_id.to_string(),
+            store_id: "store-001".to_string(),
+            ip_address: IpAddr::V4(Ipv4Addr::new(192, 168, 1, 10)),
+            addresses: Vec::new(),
+            ws_port: 8765,
+            election_term: term,
+            priority: 50,
+            discovered_at: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn test_health_request() {
+        let (addr, request) = health_request("ws://192.168.1.10:8765/ws").unwrap();
+        assert_eq!(addr, "192.168.1.10:8765");
+        assert!(request.starts_with("GET /health HTTP/1.1\r\nHost: 192.168.1.10:8765\r\n"));
+
+        let (addr, _) = health_request("ws://[fd00:1::10]:8765/ws").unwrap();
+        assert_eq!(addr, "[fd00:1::10]:8765");
+
+        assert!(health_request("not a url").is_none());
+    }
+
+    #[test]
+    fn test_status_line_and_newest_hub() {
+        assert!(is_ok_status(b"HTTP/1.1 200"));
+        assert!(!is_ok_status(b"HTTP/1.1 503"));
+        assert!(!is_ok_status(b"SSH-2.0-Open"));
+
+        let hubs = [hub("pos-01", 3), hub("pos-02", 5), hub("pos-03", 4)];
+        assert_eq!(newest(&hubs).unwrap().device_id, "pos-02");
+        assert!(newest(&[]).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_probe() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/ws", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 256];
+            let _ = socket.read(&mut buf).await;
+            let _ = socket.write_all(b"HTTP/1.1 200 OK\r\n\r\nOK").await;
+        });
+        assert!(probe(&url, Duration::from_secs(2)).await);
+
+        // The listener is gone after its one connection
+        server.await.unwrap();
+        assert!(!probe(&url, Duration::from_millis(500)).await);
+    }
+}
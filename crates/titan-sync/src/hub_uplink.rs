@@ -0,0 +1,301 @@
+//! # Hub Uplink
+//!
+//! Uploads the cloud-bound entries terminals sent to the hub, and tells
+//! each terminal when its entries are in the cloud. Until then the
+//! terminal holds its own copy (see `outbox`), so if this hub dies with
+//! entries still queued, the terminals send them to the next PRIMARY.
+//!
+//! ## Upload Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Hub Uplink Flow                                 │
+//! │                                                                         │
+//! │  POS #2 ── OutboxBatch ──► DeltaProcessor                               │
+//! │                               ├── cloud-bound ──► hub_upload_queue      │
+//! │                               │   ◄── BatchAck { held_ids }             │
+//! │                               ├── already uploaded                      │
+//! │                               │   ◄── BatchAck { uploaded_ids }         │
+//! │                               └── hub only                              │
+//! │                                   ◄── BatchAck { acked_ids }            │
+//! │                                                                         │
+//! │  HubUplink (every upload interval):                                     │
+//! │    hub_upload_queue.pending ──► UploadBatch ──► mark_uploaded           │
+//! │    ──► CloudAck { ids } to each terminal (POS #2 marks them synced)     │
+//! │    uploaded for KEEP_UPLOADED ──► pruned                                │
+//! │                                                                         │
+//! │  Terminal offline when the CloudAck goes out: it sends the entry        │
+//! │  again after cloud_ack_timeout_secs and the hub acks it as uploaded.    │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Terminals send a sale with its lines and payments. Older terminals send
+//! only the sale itself, so it goes up with the lines in the hub's own
+//! database; a sale rung up on such a terminal stays queued here (and
+//! held on that terminal, so it is not lost with the hub) until the
+//! terminal uploads it through the cloud fallback.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::sync::mpsc;
+use tracing::{debug, info, info_span, warn, Instrument};
+
+use titan_db::{Database, HubUpload};
+
+use crate::cloud_fallback::{cloud_entities, outcome};
+use crate::cloud_uplink::{CloudUplink, CloudUplinkConfig};
+use crate::error::{SyncError, SyncResult};
+use crate::hub::HubHandle;
+use crate::proto::SyncEntity;
+use crate::protocol::SyncMessage;
+use crate::telemetry;
+
+/// How long uploaded entries are remembered, so a terminal that missed its
+/// `CloudAck` is acked instead of uploading the entry again.
+const KEEP_UPLOADED: Duration = Duration::from_secs(7 * 24 * 3600);
+
+// =============================================================================
+// Hub Uplink
+// =============================================================================
+
+/// Uploads the hub's upload queue to the cloud.
+pub struct HubUplink {
+    db: Arc<Database>,
+    hub: HubHandle,
+    cloud: CloudUplinkConfig,
+    uplink: Option<CloudUplink>,
+    shutdown_rx: mpsc::Receiver<()>,
+}
+
+impl HubUplink {
+    /// Creates the uploader and the sender that stops it.
+    pub fn new(
+        db: Arc<Database>,
+        hub: HubHandle,
+        cloud: CloudUplinkConfig,
+    ) -> (Self, mpsc::Sender<()>) {
+        let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
+        let uplink = HubUplink {
+            db,
+            hub,
+            cloud,
+            uplink: None,
+            shutdown_rx,
+        };
+        (uplink, shutdown_tx)
+    }
+
+    /// Runs until shutdown, uploading every `interval`.
+    pub async fn run(&mut self, interval: Duration) {
+        info!("Hub uplink started");
+
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    if let Err(e) = self.tick().await {
+                        warn!(?e, "Hub upload failed");
+                    }
+                }
+                _ = self.shutdown_rx.recv() => break,
+            }
+        }
+
+        if let Some(mut uplink) = self.uplink.take() {
+            uplink.disconnect().await;
+        }
+        info!("Hub uplink stopped");
+    }
+
+    async fn tick(&mut self) -> SyncResult<()> {
+        if self.uplink.is_none() {
+            let mut uplink = CloudUplink::new(self.cloud.clone())?;
+            uplink.connect().await?;
+            self.uplink = Some(uplink);
+        }
+
+        match self.upload_pending().await {
+            Err(SyncError::OutsideSyncWindow) => {
+                debug!("Outside metered sync window, deferring hub upload");
+                Ok(())
+            }
+            Err(e @ (SyncError::Connection(_) | SyncError::Upload(_))) => {
+                // Reconnect on the next tick
+                if let Some(mut uplink) = self.uplink.take() {
+                    uplink.disconnect().await;
+                }
+                Err(e)
+            }
+            other => other.map(|_| ()),
+        }
+    }
+
+    /// Uploads one batch from the queue and sends the terminals their
+    /// `CloudAck`s. Returns how many entries were uploaded.
+    async fn upload_pending(&self) -> SyncResult<usize> {
+        let Some(uplink) = &self.uplink else {
+            return Ok(0);
+        };
+        let queue = self.db.hub_upload_queue();
+
+        let mut batch: Vec<(HubUpload, Vec<SyncEntity>)> = Vec::new();
+        for upload in queue.pending(self.cloud.batch_size as u32).await? {
+            // Queued as the terminal sealed it; the cloud gets it opened
+            let payload = match self
+                .db
+                .payload_cipher()
+                .open(&upload.entity_type, &upload.payload)
+            {
+                Ok(payload) => payload,
+                Err(e) => {
+                    queue
+                        .mark_failed(&upload.device_id, &upload.entry_id, &e.to_string())
+                        .await?;
+                    continue;
+                }
+            };
+            let entities = cloud_entities(
+                &self.db,
+                &upload.entity_type,
+                &upload.entity_id,
+                upload.schema_version as u32,
+                &payload,
+                upload.correlation_id.as_deref(),
+                upload.trace_context.as_deref(),
+            )
+            .await?;
+            match entities {
+                Some(entities) if !lines_missing(&upload.entity_type, &entities) => {
+                    batch.push((upload, entities));
+                }
+                Some(_) => {
+                    queue
+                        .mark_failed(
+                            &upload.device_id,
+                            &upload.entry_id,
+                            "sale lines are not on the hub",
+                        )
+                        .await?;
+                }
+                None => {
+                    queue
+                        .mark_failed(
+                            &upload.device_id,
+                            &upload.entry_id,
+                            "not accepted by the cloud",
+                        )
+                        .await?;
+                }
+            }
+        }
+        if batch.is_empty() {
+            return Ok(0);
+        }
+
+        let entities = batch
+            .iter()
+            .flat_map(|(_, entities)| entities.iter().cloned())
+            .collect();
+        // One upload carries entries from many sales, so it links to their
+        // traces rather than joining one
+        let span = info_span!("hub_upload", entries = batch.len());
+        for (upload, _) in &batch {
+            telemetry::add_link(&span, upload.trace_context.as_deref());
+        }
+        let ack = uplink.upload_batch(entities).instrument(span).await?;
+
+        let mut uploaded = Vec::new();
+        for (upload, entities) in &batch {
+            match outcome(entities, &ack) {
+                Ok(()) => {
+                    if queue.mark_uploaded(upload).await? {
+                        uploaded.push((upload.device_id.clone(), upload.entry_id.clone()));
+                    }
+                }
+                Err(reason) => {
+                    queue
+                        .mark_failed(&upload.device_id, &upload.entry_id, &reason)
+                        .await?;
+                }
+            }
+        }
+
+        info!(
+            uploaded = uploaded.len(),
+            total = batch.len(),
+            "Uploaded hub queue batch to the cloud"
+        );
+
+        for (device_id, ids) in cloud_acks(&uploaded) {
+            // A terminal that misses this sends the entries again later
+            if let Err(e) = self.hub.send_to(&device_id, SyncMessage::CloudAck { ids }).await {
+                debug!(device_id = %device_id, ?e, "Could not send cloud ack");
+            }
+        }
+
+        let keep = chrono::Duration::from_std(KEEP_UPLOADED).unwrap_or_default();
+        queue.prune_uploaded(Utc::now() - keep).await?;
+
+        Ok(uploaded.len())
+    }
+}
+
+/// Whether a sale came out without its lines: the hub does not have them.
+fn lines_missing(entity_type: &str, entities: &[SyncEntity]) -> bool {
+    entity_type == "SALE" && !entities.iter().any(|e| e.entity_type == "SALE_ITEM")
+}
+
+/// Uploaded `(device_id, entry_id)` pairs, grouped by device.
+fn cloud_acks(uploaded: &[(String, String)]) -> BTreeMap<String, Vec<String>> {
+    let mut acks: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (device_id, entry_id) in uploaded {
+        acks.entry(device_id.clone())
+            .or_default()
+            .push(entry_id.clone());
+    }
+    acks
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(entity_type: &str) -> SyncEntity {
+        SyncEntity {
+            entity_type: entity_type.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sale_without_lines_is_held_back() {
+        assert!(lines_missing("SALE", &[entity("SALE"), entity("PAYMENT")]));
+        assert!(!lines_missing(
+            "SALE",
+            &[entity("SALE"), entity("SALE_ITEM"), entity("PAYMENT")]
+        ));
+        assert!(!lines_missing("SUPPLIER", &[entity("SUPPLIER")]));
+    }
+
+    #[test]
+    fn test_cloud_acks_grouped_by_device() {
+        let uploaded = vec![
+            ("pos-02".to_string(), "e-1".to_string()),
+            ("pos-01".to_string(), "e-2".to_string()),
+            ("pos-02".to_string(), "e-3".to_string()),
+        ];
+        let acks = cloud_acks(&uploaded);
+
+        assert_eq!(acks.len(), 2);
+        assert_eq!(acks["pos-01"], vec!["e-2".to_string()]);
+        assert_eq!(acks["pos-02"], vec!["e-1".to_string(), "e-3".to_string()]);
+    }
+}
@@ -10,8 +10,16 @@
 //! │  PRODUCT UPDATES                                                       │
 //! │  ───────────────                                                       │
 //! │  • Upsert: Full product data (new or updated)                          │
-//! │  • Patch: Partial field updates (price change)                         │
-//! │  • Delete: Soft delete (set is_active = false)                         │
+//! │  • Patch: Changed fields only (price change), merged per field         │
+//! │  • Delete / Restore: Tombstone setting or clearing deleted_at          │
+//! │  • Snapshot: Full product from a resync, stock included (see below)    │
+//! │  • A price change on an active product queues a shelf label            │
+//! │  • Attributes: the product's whole attribute/tag set, replaced when    │
+//! │    the incoming set's version is newer                                 │
+//! │  • Styles: shared name/description plus which products are its         │
+//! │    variants; products it no longer lists are detached                  │
+//! │  • Bundles: the bill of materials, replaced when newer                 │
+//! │  • Packs: the product's case/inner sizes, replaced when newer          │
 //! │                                                                         │
 //! │  INVENTORY DELTAS (CRDT-style)                                         │
 //! │  ────────────────────────────                                          │
@@ -28,6 +36,30 @@
 //! │  ─────────────────────                                                 │
 //! │  • User permission changes                                             │
 //! │  • Category hierarchy updates                                          │
+//! │                                                                         │
+//! │  SHARED DOCUMENTS                                                      │
+//! │  ────────────────                                                      │
+//! │  • Quotes created on another terminal (header + items)                 │
+//! │  • Layaways (header + items + payments), so any terminal can take      │
+//! │    a payment or hand over the goods                                    │
+//! │  • Store transfers (header + items), from this store's terminals or,   │
+//! │    via the cloud, from the other store; never moves stock              │
+//! │  • Replaced wholesale when the incoming version is newer               │
+//! │  • Store credit accounts: ledger entries merged by id, never replaced  │
+//! │  • Coupons: redemptions merged by id, definition replaced when newer   │
+//! │  • Loyalty accounts: points and rewards ledger merged by id            │
+//! │  • Suppliers with their price lists, replaced when newer               │
+//! │  • Completed sales from other registers (header + items + payments),   │
+//! │    for lookups, reprints and refunds; never take stock out             │
+//! │                                                                         │
+//! │  BUSINESS CUSTOMERS                                                    │
+//! │  ──────────────────                                                    │
+//! │  • Delete / Restore tombstones only (customers are not synced yet)     │
+//! │                                                                         │
+//! │  CUSTOMER ERASURES                                                     │
+//! │  ─────────────────                                                     │
+//! │  • Anonymize the customer's local copies; never version-skipped,      │
+//! │    applying one again changes nothing                                  │
 //! └─────────────────────────────────────────────────────────────────────────┘
 //! ```
 //!
@@ -43,10 +75,30 @@
 //! │  else:                                                                  │
 //! │      skip (already have newer data)                                    │
 //! │                                                                         │
+//! │  PRODUCT PATCHES (field-level merge):                                  │
+//! │  • No version skip: each patched field is applied unless this          │
+//! │    terminal has a newer unsynced edit of the same field                │
+//! │  • Fields the patch does not name are left untouched                   │
+//! │                                                                         │
 //! │  INVENTORY SPECIAL CASE (CRDT):                                        │
 //! │  • Deltas always applied, never skipped                                │
 //! │  • current_stock += delta (atomic operation)                           │
 //! │  • No version conflicts possible                                       │
+//! │                                                                         │
+//! │  TOMBSTONES (delete / restore):                                        │
+//! │  • Applied only over an older sync_version, like upserts, so a delete  │
+//! │    and a later restore converge whatever order terminals see them in   │
+//! │                                                                         │
+//! │  FULL RESYNC SNAPSHOTS:                                                │
+//! │  • No version skip: the hub's copy replaces the local one              │
+//! │  • current_stock is set to the hub's count (logged when it differed)   │
+//! │                                                                         │
+//! │  LOCAL WRITE RACES:                                                    │
+//! │  • Product writes only land if the row is still at the version read   │
+//! │    (WHERE sync_version = ?); a local edit in between restarts the      │
+//! │    update against the newer row                                        │
+//! │                                                                         │
+//! │  While sync is paused (see `control`) updates are dropped unapplied.   │
 //! └─────────────────────────────────────────────────────────────────────────┘
 //! ```
 
@@ -54,13 +106,21 @@ use std::sync::Arc;
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
+use titan_core::{
+    EntityEvent, EntityPatch, LabelReason, ProductChange, Tombstone, TombstoneAction,
+};
 use titan_db::Database;
 
 use crate::config::SyncConfig;
+use crate::control::SyncControl;
 use crate::error::{SyncError, SyncResult};
 use crate::protocol::{EntityUpdate, SyncMessage, UpdateAck};
 use crate::transport::TransportHandle;
 
+/// Tries at applying a product update when local writes keep landing
+/// between reading the product and writing it back.
+const LOCAL_WRITE_ATTEMPTS: u32 = 3;
+
 // =============================================================================
 // Inbound Handler
 // =============================================================================
@@ -76,6 +136,9 @@ pub struct InboundHandler {
     /// Transport for sending acknowledgements.
     transport: TransportHandle,
 
+    /// Operator pause switch.
+    control: SyncControl,
+
     /// Receiver for incoming update messages.
     update_rx: mpsc::Receiver<SyncMessage>,
 
@@ -117,6 +180,7 @@ impl InboundHandler {
         db: Arc<Database>,
         config: Arc<SyncConfig>,
         transport: TransportHandle,
+        control: SyncControl,
     ) -> (Self, InboundHandlerHandle) {
         let (update_tx, update_rx) = mpsc::channel(100);
         let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
@@ -125,6 +189,7 @@ impl InboundHandler {
             db,
             config,
             transport,
+            control,
             update_rx,
             shutdown_rx,
         };
@@ -138,13 +203,17 @@ impl InboundHandler {
     }
 
     /// Runs the inbound handler loop.
-    pub async fn run(mut self) {
+    pub async fn run(&mut self) {
         info!("Inbound handler starting");
 
         loop {
             tokio::select! {
                 Some(msg) = self.update_rx.recv() => {
                     if let SyncMessage::EntityUpdate(update) = msg {
+                        if self.control.is_paused() {
+                            debug!(entity_id = %update.entity_id, "Sync paused, dropping update");
+                            continue;
+                        }
                         if let Err(e) = self.process_update(update).await {
                             error!(?e, "Failed to process entity update");
                         }
@@ -177,6 +246,20 @@ impl InboundHandler {
             "tax_rate" => self.apply_tax_rate_update(&update).await,
             "category" => self.apply_category_update(&update).await,
             "user" => self.apply_user_update(&update).await,
+            "sale" => self.apply_sale_update(&update).await,
+            "quote" => self.apply_quote_update(&update).await,
+            "layaway" => self.apply_layaway_update(&update).await,
+            "store_transfer" => self.apply_transfer_update(&update).await,
+            "store_credit" => self.apply_store_credit_update(&update).await,
+            "business_customer" => self.apply_business_customer_update(&update).await,
+            "customer_erasure" => self.apply_erasure_update(&update).await,
+            "product_attributes" => self.apply_product_attributes(&update).await,
+            "product_style" => self.apply_product_style(&update).await,
+            "product_bundle" => self.apply_product_bundle(&update).await,
+            "product_packs" => self.apply_product_packs(&update).await,
+            "supplier" => self.apply_supplier(&update).await,
+            "coupon" => self.apply_coupon_update(&update).await,
+            "loyalty" => self.apply_loyalty_update(&update).await,
             _ => {
                 warn!(entity_type = %update.entity_type, "Unknown entity type");
                 Ok(0)
@@ -205,7 +288,31 @@ impl InboundHandler {
     }
 
     /// Applies a product update.
+    ///
+    /// Writes are compare-and-swap on the version read at the start, so a
+    /// local edit saved in between is never overwritten blindly: the
+    /// update is worked out again against the newer row.
     async fn apply_product_update(&self, update: &EntityUpdate) -> SyncResult<i64> {
+        let mut attempt = 1;
+        loop {
+            match self.try_apply_product_update(update).await {
+                Err(SyncError::ConflictDetected { local_version, .. })
+                    if attempt < LOCAL_WRITE_ATTEMPTS =>
+                {
+                    debug!(
+                        entity_id = %update.entity_id,
+                        local_version,
+                        attempt,
+                        "Product changed locally while applying update, retrying"
+                    );
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn try_apply_product_update(&self, update: &EntityUpdate) -> SyncResult<i64> {
         // Check version to avoid applying stale updates
         let current = self
             .db
@@ -213,6 +320,16 @@ impl InboundHandler {
             .get_by_id(&update.entity_id)
             .await?;
 
+        // Patches are merged per field rather than skipped by version
+        if update.operation == "patch" {
+            return self.apply_product_patch(update, current).await;
+        }
+
+        // Resync snapshots replace whatever is here, whatever its version
+        if update.operation == "snapshot" {
+            return self.apply_product_snapshot(update, current).await;
+        }
+
         if let Some(ref product) = current {
             if product.sync_version >= update.version {
                 debug!(
@@ -236,9 +353,11 @@ impl InboundHandler {
 
                 // Use existing upsert method or implement sync-specific one
                 // For now, we'll use the existing product methods
-                if current.is_some() {
+                if let Some(ref local) = current {
                     // Update existing
-                    self.update_product_from_sync(&product).await?;
+                    self.update_product_from_sync(&product, local.sync_version)
+                        .await?;
+                    self.queue_price_label(local, &product).await;
                 } else {
                     // Insert new
                     self.insert_product_from_sync(&product).await?;
@@ -252,32 +371,195 @@ impl InboundHandler {
 
                 Ok(update.version)
             }
-            "patch" => {
-                // Partial update - only update specified fields
-                // This requires a dedicated method that handles partial JSON
-                warn!(
-                    entity_id = %update.entity_id,
-                    "Product patch not implemented yet"
-                );
+            "delete" | "restore" => self.apply_tombstone(update).await,
+            _ => {
+                warn!(operation = %update.operation, "Unknown operation for Product");
                 Ok(current.map(|p| p.sync_version).unwrap_or(0))
             }
-            "delete" => {
-                // Soft delete
-                self.soft_delete_product(&update.entity_id, update.version)
-                    .await?;
+        }
+    }
 
-                info!(
-                    entity_id = %update.entity_id,
-                    version = update.version,
-                    "Soft deleted product"
-                );
+    /// Applies a business customer update (tombstones only).
+    async fn apply_business_customer_update(&self, update: &EntityUpdate) -> SyncResult<i64> {
+        match update.operation.as_str() {
+            "delete" | "restore" => self.apply_tombstone(update).await,
+            _ => {
+                warn!(operation = %update.operation, "Unknown operation for BusinessCustomer");
+                Ok(0)
+            }
+        }
+    }
 
-                Ok(update.version)
+    /// Applies a soft delete or restore.
+    ///
+    /// The tombstone travels in `data`; a bare delete from an older hub
+    /// (no data) is read as a tombstone at the update's version.
+    async fn apply_tombstone(&self, update: &EntityUpdate) -> SyncResult<i64> {
+        let tombstone = serde_json::from_value::<Tombstone>(update.data.clone())
+            .unwrap_or_else(|_| {
+                let action = if update.operation == "restore" {
+                    TombstoneAction::Restore
+                } else {
+                    TombstoneAction::Delete
+                };
+                Tombstone::new(
+                    &update.entity_type,
+                    &update.entity_id,
+                    action,
+                    update.version,
+                    chrono::Utc::now(),
+                )
+            });
+
+        let applied = match tombstone.entity_type.as_str() {
+            "product" => self.db.products().apply_tombstone(&tombstone).await?,
+            "business_customer" => {
+                self.db
+                    .business_customers()
+                    .apply_tombstone(&tombstone)
+                    .await?
             }
-            _ => {
-                warn!(operation = %update.operation, "Unknown operation for Product");
-                Ok(current.map(|p| p.sync_version).unwrap_or(0))
+            other => {
+                warn!(entity_type = %other, "Tombstone for an entity that cannot be deleted");
+                false
             }
+        };
+
+        if applied {
+            info!(
+                entity_type = %tombstone.entity_type,
+                entity_id = %tombstone.entity_id,
+                action = ?tombstone.action,
+                version = tombstone.version,
+                "Applied tombstone"
+            );
+        } else {
+            debug!(
+                entity_type = %tombstone.entity_type,
+                entity_id = %tombstone.entity_id,
+                version = tombstone.version,
+                "Skipping stale or unknown tombstone"
+            );
+        }
+        Ok(tombstone.version)
+    }
+
+    /// Applies a product from a full resync, stock included.
+    ///
+    /// A stock count that differs from the hub's is corrected and logged;
+    /// that drift is what a resync is usually run to find.
+    async fn apply_product_snapshot(
+        &self,
+        update: &EntityUpdate,
+        current: Option<titan_core::Product>,
+    ) -> SyncResult<i64> {
+        let mut product: titan_core::Product = serde_json::from_value(update.data.clone())?;
+        product.sync_version = update.version;
+
+        match current {
+            Some(local) => {
+                self.update_product_from_sync(&product, local.sync_version)
+                    .await?;
+                self.queue_price_label(&local, &product).await;
+                if local.current_stock != product.current_stock {
+                    self.set_product_stock(&product.id, product.current_stock)
+                        .await?;
+                    let delta = product.current_stock.unwrap_or(0) - local.current_stock.unwrap_or(0);
+                    self.db.events().publish(EntityEvent::StockChanged {
+                        product_id: product.id.clone(),
+                        delta,
+                        stock: product.current_stock,
+                    });
+                    warn!(
+                        entity_id = %update.entity_id,
+                        local_stock = ?local.current_stock,
+                        hub_stock = ?product.current_stock,
+                        "Corrected stock from resync"
+                    );
+                }
+            }
+            None => self.insert_product_from_sync(&product).await?,
+        }
+
+        debug!(entity_id = %update.entity_id, version = update.version, "Applied product snapshot");
+        Ok(update.version)
+    }
+
+    /// Applies a product field patch with a field-level merge.
+    ///
+    /// Incoming fields win unless this terminal has a newer edit of the
+    /// same field still waiting in the outbox; that edit reaches the other
+    /// terminals when it syncs.
+    async fn apply_product_patch(
+        &self,
+        update: &EntityUpdate,
+        current: Option<titan_core::Product>,
+    ) -> SyncResult<i64> {
+        let Some(product) = current else {
+            // Nothing to patch; the product arrives with its next upsert
+            warn!(entity_id = %update.entity_id, "Patch for unknown product, skipping");
+            return Ok(0);
+        };
+
+        let incoming: EntityPatch = serde_json::from_value(update.data.clone())?;
+        let local = self
+            .db
+            .sync_outbox()
+            .pending_patch("PRODUCT_PATCH", &update.entity_id)
+            .await?;
+        let outcome = incoming.merge(local.as_ref());
+
+        if !outcome.kept_local.is_empty() {
+            info!(
+                entity_id = %update.entity_id,
+                fields = ?outcome.kept_local,
+                "Kept newer local product edits"
+            );
+        }
+
+        let mut patched = outcome.patch.apply(&product)?;
+        patched.sync_version = product.sync_version.max(update.version);
+        self.update_product_from_sync(&patched, product.sync_version)
+            .await?;
+        self.queue_price_label(&product, &patched).await;
+
+        info!(
+            entity_id = %update.entity_id,
+            version = patched.sync_version,
+            fields = outcome.patch.fields.len(),
+            "Applied product patch"
+        );
+
+        Ok(patched.sync_version)
+    }
+
+    /// Queues a shelf label when an applied update changed the price of a
+    /// product on sale. A failure is only logged: the price is applied.
+    async fn queue_price_label(&self, before: &titan_core::Product, after: &titan_core::Product) {
+        let unchanged = before.price_cents == after.price_cents;
+        if unchanged || !after.is_active || after.deleted_at.is_some() {
+            return;
+        }
+
+        let queued = self
+            .db
+            .labels()
+            .enqueue(
+                &after.id,
+                LabelReason::PriceChange,
+                Some(before.price_cents),
+                after.price_cents,
+                1,
+            )
+            .await;
+        match queued {
+            Ok(_) => debug!(
+                product_id = %after.id,
+                old_price_cents = before.price_cents,
+                price_cents = after.price_cents,
+                "Queued shelf label for price change"
+            ),
+            Err(e) => warn!(product_id = %after.id, error = %e, "Could not queue shelf label"),
         }
     }
 
@@ -298,32 +580,37 @@ impl InboundHandler {
         let delta_data: InventoryDeltaData = serde_json::from_value(update.data.clone())?;
 
         // Apply delta atomically using SQL
-        let rows_affected = sqlx::query!(
+        let row = sqlx::query!(
             r#"
             UPDATE products
             SET current_stock = COALESCE(current_stock, 0) + ?1,
                 updated_at = datetime('now')
             WHERE id = ?2
+            RETURNING current_stock
             "#,
             delta_data.delta,
             delta_data.product_id
         )
-        .execute(self.db.pool())
-        .await?
-        .rows_affected();
+        .fetch_optional(self.db.pool())
+        .await?;
 
-        if rows_affected == 0 {
-            warn!(
-                product_id = %delta_data.product_id,
-                "Product not found for inventory delta"
-            );
-        } else {
+        if let Some(row) = row {
+            self.db.events().publish(EntityEvent::StockChanged {
+                product_id: delta_data.product_id.clone(),
+                delta: delta_data.delta,
+                stock: row.current_stock,
+            });
             info!(
                 product_id = %delta_data.product_id,
                 delta = delta_data.delta,
                 reason = ?delta_data.reason,
                 "Applied inventory delta"
             );
+        } else {
+            warn!(
+                product_id = %delta_data.product_id,
+                "Product not found for inventory delta"
+            );
         }
 
         // Record delta in local history (for auditing)
@@ -364,13 +651,272 @@ impl InboundHandler {
         Ok(update.version)
     }
 
+    /// Applies a product attribute set edited on another terminal.
+    async fn apply_product_attributes(&self, update: &EntityUpdate) -> SyncResult<i64> {
+        let doc: titan_core::ProductAttributes = serde_json::from_value(update.data.clone())?;
+
+        if self.db.products().upsert_attributes_from_sync(&doc).await? {
+            info!(
+                entity_id = %update.entity_id,
+                version = doc.sync_version,
+                count = doc.attributes.len(),
+                "Applied product attributes"
+            );
+        } else {
+            debug!(entity_id = %update.entity_id, "Skipping stale product attributes");
+        }
+
+        Ok(doc.sync_version)
+    }
+
+    /// Applies a product style (and its variant list) edited on another
+    /// terminal.
+    async fn apply_product_style(&self, update: &EntityUpdate) -> SyncResult<i64> {
+        let doc: titan_core::ProductStyleDocument = serde_json::from_value(update.data.clone())?;
+
+        if self.db.styles().upsert_from_sync(&doc).await? {
+            info!(
+                entity_id = %update.entity_id,
+                version = doc.style.sync_version,
+                variants = doc.variant_ids.len(),
+                "Applied product style"
+            );
+        } else {
+            debug!(entity_id = %update.entity_id, "Skipping stale product style");
+        }
+
+        Ok(doc.style.sync_version)
+    }
+
+    /// Applies a bundle's bill of materials edited on another terminal.
+    async fn apply_product_bundle(&self, update: &EntityUpdate) -> SyncResult<i64> {
+        let doc: titan_core::ProductBundle = serde_json::from_value(update.data.clone())?;
+
+        if self.db.bundles().upsert_from_sync(&doc).await? {
+            info!(
+                entity_id = %update.entity_id,
+                version = doc.sync_version,
+                components = doc.components.len(),
+                "Applied bundle components"
+            );
+        } else {
+            debug!(entity_id = %update.entity_id, "Skipping stale bundle components");
+        }
+
+        Ok(doc.sync_version)
+    }
+
+    /// Applies a product's pack sizes edited on another terminal.
+    async fn apply_product_packs(&self, update: &EntityUpdate) -> SyncResult<i64> {
+        let doc: titan_core::ProductPacks = serde_json::from_value(update.data.clone())?;
+
+        if self.db.packs().upsert_from_sync(&doc).await? {
+            info!(
+                entity_id = %update.entity_id,
+                version = doc.sync_version,
+                count = doc.packs.len(),
+                "Applied product packs"
+            );
+        } else {
+            debug!(entity_id = %update.entity_id, "Skipping stale product packs");
+        }
+
+        Ok(doc.sync_version)
+    }
+
+    /// Applies a supplier and price list edited on another terminal.
+    async fn apply_supplier(&self, update: &EntityUpdate) -> SyncResult<i64> {
+        let doc: titan_core::SupplierDocument = serde_json::from_value(update.data.clone())?;
+
+        if self.db.suppliers().upsert_from_sync(&doc).await? {
+            info!(
+                entity_id = %update.entity_id,
+                version = doc.supplier.sync_version,
+                items = doc.items.len(),
+                "Applied supplier"
+            );
+        } else {
+            debug!(entity_id = %update.entity_id, "Skipping stale supplier");
+        }
+
+        Ok(doc.supplier.sync_version)
+    }
+
+    /// Applies a sale completed on another register, so it can be found,
+    /// reprinted and refunded here.
+    ///
+    /// The sale is stored as relayed: its stock already came out through
+    /// the other register's inventory deltas.
+    async fn apply_sale_update(&self, update: &EntityUpdate) -> SyncResult<i64> {
+        let doc: titan_core::SaleDocument = serde_json::from_value(update.data.clone())?;
+
+        if self.db.sales().upsert_from_sync(&doc).await? {
+            info!(
+                entity_id = %update.entity_id,
+                receipt_number = %doc.sale.receipt_number,
+                version = doc.sale.sync_version,
+                "Applied sale from another register"
+            );
+        } else {
+            debug!(entity_id = %update.entity_id, "Skipping local or stale sale update");
+        }
+
+        Ok(doc.sale.sync_version)
+    }
+
+    /// Applies a quote created or changed on another terminal.
+    async fn apply_quote_update(&self, update: &EntityUpdate) -> SyncResult<i64> {
+        let doc: titan_core::QuoteDocument = serde_json::from_value(update.data.clone())?;
+
+        if self.db.quotes().upsert_from_sync(&doc).await? {
+            info!(
+                entity_id = %update.entity_id,
+                version = doc.quote.sync_version,
+                "Applied quote upsert"
+            );
+        } else {
+            debug!(entity_id = %update.entity_id, "Skipping stale quote update");
+        }
+
+        Ok(doc.quote.sync_version)
+    }
+
+    /// Applies a layaway document shared by another terminal.
+    ///
+    /// Only the document is replaced; the stock it reserves arrives as
+    /// inventory deltas from the terminal that created it.
+    async fn apply_layaway_update(&self, update: &EntityUpdate) -> SyncResult<i64> {
+        let doc: titan_core::LayawayDocument = serde_json::from_value(update.data.clone())?;
+
+        if self.db.layaways().upsert_from_sync(&doc).await? {
+            info!(
+                entity_id = %update.entity_id,
+                version = doc.layaway.sync_version,
+                "Applied layaway upsert"
+            );
+        } else {
+            debug!(entity_id = %update.entity_id, "Skipping stale layaway update");
+        }
+
+        Ok(doc.layaway.sync_version)
+    }
+
+    /// Applies a store transfer from another terminal or, via the cloud,
+    /// from the other store on the transfer.
+    async fn apply_transfer_update(&self, update: &EntityUpdate) -> SyncResult<i64> {
+        let doc: titan_core::StoreTransferDocument = serde_json::from_value(update.data.clone())?;
+
+        if self.db.transfers().upsert_from_sync(&doc).await? {
+            info!(
+                entity_id = %update.entity_id,
+                status = doc.transfer.status.as_str(),
+                version = doc.transfer.sync_version,
+                "Applied store transfer upsert"
+            );
+        } else {
+            debug!(entity_id = %update.entity_id, "Skipping stale store transfer update");
+        }
+
+        Ok(doc.transfer.sync_version)
+    }
+
+    /// Applies a store credit account from the hub or another store.
+    ///
+    /// Entries are merged rather than replaced, so a stale copy can only
+    /// add entries this terminal has not seen yet.
+    async fn apply_store_credit_update(&self, update: &EntityUpdate) -> SyncResult<i64> {
+        let doc: titan_core::StoreCreditDocument = serde_json::from_value(update.data.clone())?;
+
+        if self.db.store_credits().upsert_from_sync(&doc).await? {
+            info!(
+                entity_id = %update.entity_id,
+                balance = doc.balance_cents(),
+                "Applied store credit update"
+            );
+        } else {
+            debug!(entity_id = %update.entity_id, "Store credit already up to date");
+        }
+
+        Ok(doc.account.sync_version)
+    }
+
+    /// Applies a coupon from the hub or the cloud.
+    ///
+    /// Like store credit, redemptions are merged rather than replaced, so
+    /// a use recorded on another register counts here whatever the version.
+    async fn apply_coupon_update(&self, update: &EntityUpdate) -> SyncResult<i64> {
+        let doc: titan_core::CouponDocument = serde_json::from_value(update.data.clone())?;
+
+        if self.db.coupons().upsert_from_sync(&doc).await? {
+            info!(
+                entity_id = %update.entity_id,
+                code = %doc.coupon.code,
+                uses = doc.uses(),
+                "Applied coupon update"
+            );
+        } else {
+            debug!(entity_id = %update.entity_id, "Coupon already up to date");
+        }
+
+        Ok(doc.coupon.sync_version)
+    }
+
+    /// Applies a loyalty account from the hub.
+    ///
+    /// Entries are merged by ID like store credit, so points earned and
+    /// rewards spent on other registers add up here.
+    async fn apply_loyalty_update(&self, update: &EntityUpdate) -> SyncResult<i64> {
+        let doc: titan_core::LoyaltyDocument = serde_json::from_value(update.data.clone())?;
+
+        if self.db.loyalty().upsert_from_sync(&doc).await? {
+            info!(
+                entity_id = %update.entity_id,
+                points = doc.points_balance(),
+                rewards = doc.reward_balance_cents(),
+                "Applied loyalty update"
+            );
+        } else {
+            debug!(entity_id = %update.entity_id, "Loyalty account already up to date");
+        }
+
+        Ok(doc.account.sync_version)
+    }
+
+    /// Applies a customer erasure requested on another terminal or in the
+    /// cloud.
+    async fn apply_erasure_update(&self, update: &EntityUpdate) -> SyncResult<i64> {
+        let request: titan_core::ErasureRequest = serde_json::from_value(update.data.clone())?;
+
+        let record = self
+            .db
+            .erasures()
+            .apply(&request, titan_core::ErasureOrigin::Sync)
+            .await?;
+        info!(
+            entity_id = %update.entity_id,
+            rows = record.counts.total(),
+            "Applied customer erasure"
+        );
+
+        Ok(update.version)
+    }
+
     // =========================================================================
     // Database Operations (would ideally be in titan-db SyncInboundRepository)
     // =========================================================================
 
-    /// Updates an existing product from sync data.
-    async fn update_product_from_sync(&self, product: &titan_core::Product) -> SyncResult<()> {
-        sqlx::query!(
+    /// Updates an existing product from sync data, if it is still at
+    /// `expected_version`.
+    ///
+    /// ## Errors
+    /// `SyncError::ConflictDetected` if a local write changed the product
+    /// since it was read.
+    async fn update_product_from_sync(
+        &self,
+        product: &titan_core::Product,
+        expected_version: i64,
+    ) -> SyncResult<()> {
+        let result = sqlx::query!(
             r#"
             UPDATE products SET
                 sku = ?2,
@@ -384,8 +930,12 @@ impl InboundHandler {
                 allow_negative_stock = ?10,
                 is_active = ?11,
                 updated_at = ?12,
-                sync_version = ?13
-            WHERE id = ?1
+                sync_version = ?13,
+                item_tracking = ?14,
+                min_purchase_age = ?15,
+                deleted_at = ?16,
+                base_price_cents = ?18
+            WHERE id = ?1 AND sync_version = ?17
             "#,
             product.id,
             product.sku,
@@ -399,11 +949,37 @@ impl InboundHandler {
             product.allow_negative_stock,
             product.is_active,
             product.updated_at,
-            product.sync_version
+            product.sync_version,
+            product.item_tracking,
+            product.min_purchase_age,
+            product.deleted_at,
+            expected_version,
+            product.base_price_cents
         )
         .execute(self.db.pool())
         .await?;
 
+        if result.rows_affected() == 0 {
+            let local_version = self
+                .db
+                .products()
+                .get_by_id(&product.id)
+                .await?
+                .map(|p| p.sync_version)
+                .unwrap_or(0);
+            return Err(SyncError::ConflictDetected {
+                entity_type: "product".to_string(),
+                entity_id: product.id.clone(),
+                local_version,
+                remote_version: product.sync_version,
+            });
+        }
+
+        self.db.events().publish(EntityEvent::ProductChanged {
+            product_id: product.id.clone(),
+            change: ProductChange::Updated,
+            version: product.sync_version,
+        });
         Ok(())
     }
 
@@ -415,12 +991,14 @@ impl InboundHandler {
                 id, tenant_id, sku, barcode, name, description,
                 price_cents, cost_cents, tax_rate_bps,
                 track_inventory, allow_negative_stock, current_stock,
-                is_active, created_at, updated_at, sync_version
+                is_active, created_at, updated_at, sync_version,
+                item_tracking, min_purchase_age, deleted_at, base_price_cents
             ) VALUES (
                 ?1, ?2, ?3, ?4, ?5, ?6,
                 ?7, ?8, ?9,
                 ?10, ?11, ?12,
-                ?13, ?14, ?15, ?16
+                ?13, ?14, ?15, ?16,
+                ?17, ?18, ?19, ?20
             )
             "#,
             product.id,
@@ -438,26 +1016,30 @@ impl InboundHandler {
             product.is_active,
             product.created_at,
             product.updated_at,
-            product.sync_version
+            product.sync_version,
+            product.item_tracking,
+            product.min_purchase_age,
+            product.deleted_at,
+            product.base_price_cents
         )
         .execute(self.db.pool())
         .await?;
 
+        self.db.events().publish(EntityEvent::ProductChanged {
+            product_id: product.id.clone(),
+            change: ProductChange::Created,
+            version: product.sync_version,
+        });
         Ok(())
     }
 
-    /// Soft deletes a product.
-    async fn soft_delete_product(&self, product_id: &str, version: i64) -> SyncResult<()> {
+    /// Overwrites a product's stock count (resync only; everything else
+    /// moves stock by delta).
+    async fn set_product_stock(&self, product_id: &str, stock: Option<i64>) -> SyncResult<()> {
         sqlx::query!(
-            r#"
-            UPDATE products SET
-                is_active = false,
-                updated_at = datetime('now'),
-                sync_version = ?2
-            WHERE id = ?1
-            "#,
+            "UPDATE products SET current_stock = ?2 WHERE id = ?1",
             product_id,
-            version
+            stock
         )
         .execute(self.db.pool())
         .await?;
@@ -475,8 +1057,8 @@ impl InboundHandler {
         let id = uuid::Uuid::new_v4().to_string();
         let now = chrono::Utc::now().to_rfc3339();
         
-        // Get local device ID from config or use a default
-        let origin_device_id = "sync".to_string();
+        // Attribute the delta to this device
+        let origin_device_id = self.config.device_id().to_string();
         
         // Get next sequence number (simplified - in production would use atomic counter)
         let sequence_num: i64 = 1;
@@ -512,7 +1094,5 @@ impl InboundHandler {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     // Tests would go here with mock database
 }
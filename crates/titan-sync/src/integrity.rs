@@ -0,0 +1,368 @@
+//! # Message Integrity
+//!
+//! HMAC signing of hub WebSocket messages, so a rogue device on store Wi-Fi
+//! cannot inject InventoryUpdate or EntityUpdate messages, or replay ones
+//! it captured.
+//!
+//! ## Signed Session
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Signed LAN Session                              │
+//! │                                                                         │
+//! │  device key = HMAC-SHA256(enrollment_key, "titan-sync/device/" + id)   │
+//! │  Both the terminal and the hub derive it; it never goes on the wire.   │
+//! │                                                                         │
+//! │  SECONDARY ───► Frame { seq: 0, nonce: Nt, body: Hello, mac }          │
+//! │  PRIMARY   ◄─── Frame { seq: 0, nonce: Nh, body: Welcome, mac }        │
+//! │  both      ◄──► Frame { seq: 1, 2, 3 ..., mac }                        │
+//! │                                                                         │
+//! │  mac = HMAC(device key, sender role | sender nonce | receiver nonce    │
+//! │                         | seq | body)                                   │
+//! │                                                                         │
+//! │  • Forged or altered frame   → MAC mismatch, rejected                  │
+//! │  • Frame replayed in session → seq not increasing, rejected            │
+//! │  • Frame from an old session → nonces differ, MAC mismatch             │
+//! │  • Frame reflected back      → sender role differs, MAC mismatch       │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! The Hello is signed before the terminal has seen the hub's nonce, so a
+//! captured Hello can be replayed. That gets an attacker a Welcome and
+//! nothing else: every later frame needs the device key.
+//!
+//! WebSocket Ping/Pong control frames are not signed; they carry no data.
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::error::{SyncError, SyncResult};
+use crate::protocol::{HelloPayload, SyncMessage};
+
+type HmacSha256 = Hmac<Sha256>;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Shortest enrollment key accepted by config validation.
+pub const MIN_ENROLLMENT_KEY_LEN: usize = 16;
+
+/// Domain separator for device key derivation.
+const DEVICE_KEY_LABEL: &[u8] = b"titan-sync/device/";
+
+// =============================================================================
+// Device Key
+// =============================================================================
+
+/// Per-device signing key derived from the store enrollment key.
+#[derive(Clone)]
+pub struct DeviceKey([u8; 32]);
+
+impl DeviceKey {
+    /// Derives the key for `device_id`.
+    pub fn derive(enrollment_key: &str, device_id: &str) -> Self {
+        let mut mac = new_mac(enrollment_key.as_bytes());
+        mac.update(DEVICE_KEY_LABEL);
+        mac.update(device_id.as_bytes());
+        DeviceKey(mac.finalize().into_bytes().into())
+    }
+}
+
+impl std::fmt::Debug for DeviceKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DeviceKey(..)")
+    }
+}
+
+fn new_mac(key: &[u8]) -> HmacSha256 {
+    HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length")
+}
+
+// =============================================================================
+// Wire Frame
+// =============================================================================
+
+/// A signed message as sent on the wire.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SignedFrame {
+    /// Per-direction sequence number, starting at 0.
+    seq: u64,
+    /// Sender's session nonce.
+    nonce: String,
+    /// The message JSON, signed as sent.
+    body: String,
+    /// Hex HMAC-SHA256.
+    mac: String,
+}
+
+impl SignedFrame {
+    fn parse(text: &str) -> SyncResult<Self> {
+        serde_json::from_str(text)
+            .map_err(|_| SyncError::IntegrityFailed("message is not signed".into()))
+    }
+}
+
+/// Which side of the connection signed a frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// SECONDARY connecting to the hub.
+    Terminal,
+    /// PRIMARY serving the connection.
+    Hub,
+}
+
+impl Role {
+    fn label(self) -> &'static [u8] {
+        match self {
+            Role::Terminal => b"terminal",
+            Role::Hub => b"hub",
+        }
+    }
+
+    fn peer(self) -> Role {
+        match self {
+            Role::Terminal => Role::Hub,
+            Role::Hub => Role::Terminal,
+        }
+    }
+}
+
+// =============================================================================
+// Session
+// =============================================================================
+
+/// Signing state for one connection.
+#[derive(Debug)]
+pub struct Session {
+    key: DeviceKey,
+    role: Role,
+    local_nonce: String,
+    peer_nonce: Option<String>,
+    send_seq: u64,
+    next_recv_seq: u64,
+}
+
+impl Session {
+    /// Starts a session with a fresh nonce.
+    pub fn new(key: DeviceKey, role: Role) -> Self {
+        Session {
+            key,
+            role,
+            local_nonce: uuid::Uuid::new_v4().simple().to_string(),
+            peer_nonce: None,
+            send_seq: 0,
+            next_recv_seq: 0,
+        }
+    }
+
+    /// Hub side: verifies a terminal's signed Hello, deriving the key from
+    /// the device ID it claims.
+    pub fn accept(enrollment_key: &str, text: &str) -> SyncResult<(Session, HelloPayload)> {
+        let frame = SignedFrame::parse(text)?;
+        let SyncMessage::Hello(hello) = SyncMessage::from_json(&frame.body)? else {
+            return Err(SyncError::ProtocolError("Expected Hello message".into()));
+        };
+
+        let key = DeviceKey::derive(enrollment_key, &hello.device_id);
+        let mut session = Session::new(key, Role::Hub);
+        session.verify(frame)?;
+        Ok((session, hello))
+    }
+
+    /// Signs `msg` and returns the frame text to send.
+    pub fn seal(&mut self, msg: &SyncMessage) -> SyncResult<String> {
+        let body = msg.to_json()?;
+        // Before the peer has spoken, its nonce is unknown
+        let peer_nonce = self.peer_nonce.as_deref().unwrap_or("");
+        let mac = self.mac(
+            self.role,
+            &self.local_nonce,
+            peer_nonce,
+            self.send_seq,
+            &body,
+        );
+
+        let frame = SignedFrame {
+            seq: self.send_seq,
+            nonce: self.local_nonce.clone(),
+            body,
+            mac: hex::encode(mac.finalize().into_bytes()),
+        };
+        self.send_seq += 1;
+        serde_json::to_string(&frame).map_err(SyncError::from)
+    }
+
+    /// Verifies a received frame and returns its message.
+    pub fn open(&mut self, text: &str) -> SyncResult<SyncMessage> {
+        let frame = SignedFrame::parse(text)?;
+        let body = self.verify(frame)?;
+        Ok(SyncMessage::from_json(&body)?)
+    }
+
+    fn verify(&mut self, frame: SignedFrame) -> SyncResult<String> {
+        if frame.seq < self.next_recv_seq {
+            return Err(SyncError::IntegrityFailed(format!(
+                "replayed frame (seq {} < {})",
+                frame.seq, self.next_recv_seq
+            )));
+        }
+        if let Some(peer_nonce) = &self.peer_nonce {
+            if *peer_nonce != frame.nonce {
+                return Err(SyncError::IntegrityFailed(
+                    "frame from another session".into(),
+                ));
+            }
+        }
+
+        // The peer could only bind our nonce once we had sent something
+        let local_nonce = if self.send_seq == 0 {
+            ""
+        } else {
+            &self.local_nonce
+        };
+        let expected = hex::decode(&frame.mac)
+            .map_err(|_| SyncError::IntegrityFailed("malformed MAC".into()))?;
+        self.mac(
+            self.role.peer(),
+            &frame.nonce,
+            local_nonce,
+            frame.seq,
+            &frame.body,
+        )
+        .verify_slice(&expected)
+        .map_err(|_| SyncError::IntegrityFailed("MAC mismatch".into()))?;
+
+        self.peer_nonce = Some(frame.nonce);
+        self.next_recv_seq = frame.seq + 1;
+        Ok(frame.body)
+    }
+
+    fn mac(
+        &self,
+        sender: Role,
+        sender_nonce: &str,
+        receiver_nonce: &str,
+        seq: u64,
+        body: &str,
+    ) -> HmacSha256 {
+        let mut mac = new_mac(&self.key.0);
+        for part in [
+            sender.label(),
+            sender_nonce.as_bytes(),
+            receiver_nonce.as_bytes(),
+            &seq.to_be_bytes(),
+            body.as_bytes(),
+        ] {
+            // Length-prefixed so parts cannot bleed into each other
+            mac.update(&(part.len() as u64).to_be_bytes());
+            mac.update(part);
+        }
+        mac
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENROLLMENT_KEY: &str = "store-001-enrollment-key";
+
+    /// Terminal and hub sessions after a completed handshake.
+    fn handshake() -> (Session, Session) {
+        let mut terminal =
+            Session::new(DeviceKey::derive(ENROLLMENT_KEY, "pos-02"), Role::Terminal);
+        let hello = terminal
+            .seal(&SyncMessage::hello("pos-02", "Register 2", "store-001", 50))
+            .unwrap();
+
+        let (mut hub, payload) = Session::accept(ENROLLMENT_KEY, &hello).unwrap();
+        assert_eq!(payload.device_id, "pos-02");
+
+        let welcome = hub.seal(&SyncMessage::ping()).unwrap();
+        terminal.open(&welcome).unwrap();
+        (terminal, hub)
+    }
+
+    #[test]
+    fn test_round_trip_both_directions() {
+        let (mut terminal, mut hub) = handshake();
+
+        let frame = terminal
+            .seal(&SyncMessage::inventory_delta("p-1", "SKU-1", -7))
+            .unwrap();
+        assert!(matches!(
+            hub.open(&frame).unwrap(),
+            SyncMessage::InventoryDelta(_)
+        ));
+
+        let frame = hub.seal(&SyncMessage::ping()).unwrap();
+        assert!(matches!(
+            terminal.open(&frame).unwrap(),
+            SyncMessage::Ping { .. }
+        ));
+    }
+
+    #[test]
+    fn test_rejects_replay_and_tampering() {
+        let (mut terminal, mut hub) = handshake();
+
+        let frame = terminal
+            .seal(&SyncMessage::inventory_delta("p-1", "SKU-1", -7))
+            .unwrap();
+        hub.open(&frame).unwrap();
+        assert!(matches!(
+            hub.open(&frame),
+            Err(SyncError::IntegrityFailed(_))
+        ));
+
+        let frame = terminal
+            .seal(&SyncMessage::inventory_delta("p-1", "SKU-1", -7))
+            .unwrap();
+        let tampered = frame.replace("-7", "-700");
+        assert!(matches!(
+            hub.open(&tampered),
+            Err(SyncError::IntegrityFailed(_))
+        ));
+
+        // Unsigned messages are refused outright
+        let plain = SyncMessage::ping().to_json().unwrap();
+        assert!(matches!(
+            hub.open(&plain),
+            Err(SyncError::IntegrityFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_wrong_key_and_reflection() {
+        let mut rogue = Session::new(
+            DeviceKey::derive("some-other-store-key", "pos-02"),
+            Role::Terminal,
+        );
+        let hello = rogue
+            .seal(&SyncMessage::hello("pos-02", "Register 2", "store-001", 50))
+            .unwrap();
+        assert!(Session::accept(ENROLLMENT_KEY, &hello).is_err());
+
+        // A hub frame echoed back at the hub does not verify as a terminal frame
+        let (_, mut hub) = handshake();
+        let echoed = hub.seal(&SyncMessage::ping()).unwrap();
+        assert!(hub.open(&echoed).is_err());
+    }
+
+    #[test]
+    fn test_rejects_frames_from_another_session() {
+        let (mut old_terminal, _) = handshake();
+        let captured = old_terminal.seal(&SyncMessage::ping()).unwrap();
+
+        let (_, mut hub) = handshake();
+        assert!(matches!(
+            hub.open(&captured),
+            Err(SyncError::IntegrityFailed(_))
+        ));
+    }
+}
@@ -69,23 +69,40 @@
 //!
 //! ### Core Modules (Milestone 1)
 //! - [`agent`] - Main `SyncAgent` orchestrator
+//! - [`batching`] - Byte-sized outbox batches with adaptive tuning
+//! - [`compat`] - Protocol version negotiation and down-conversion
 //! - [`config`] - Sync configuration (mode, device ID, hub URL)
+//! - [`control`] - Operator pause switch and full resync flow
 //! - [`error`] - Sync error types
 //! - [`inbound`] - Handler for incoming updates
+//! - [`integrity`] - HMAC signing and replay protection for LAN messages
 //! - [`outbox`] - Outbox processor for uploads
 //! - [`protocol`] - Message types for sync communication
+//! - [`supervisor`] - Restarts background tasks that die, with backoff
+//! - [`telemetry`] - OTLP span export and trace context across devices
+//! - [`throttle`] - Upload bandwidth caps and metered mode
 //! - [`transport`] - WebSocket client with reconnection
 //!
 //! ### Store Hub Modules (Milestone 2)
 //! - [`discovery`] - mDNS + UDP broadcast hub discovery
 //! - [`election`] - Leader election with fencing tokens
 //! - [`hub`] - WebSocket server for PRIMARY mode
+//! - [`hub_cache`] - Last-known-good hub, probed before discovery
+//! - [`netif`] - Interface enumeration for multi-homed and IPv6 hosts
+//! - [`peer`] - Hub-less pairing of two terminals (peer mode)
+//! - [`recorder`] - Opt-in recording of hub traffic, and replay into a test hub
+//! - [`shedding`] - Hub connection limits and per-client message rates
 //! - [`aggregator`] - Inventory delta aggregation and broadcasting
+//! - [`catalog_api`] - HTTP product search and stock lookup for thin clients
+//! - [`admin_api`] - HTTP day-end reports for the store manager
 //!
 //! ### Cloud Uplink Modules (Milestone 3)
 //! - [`proto`] - Generated gRPC client stubs from proto/titan_sync.proto
 //! - [`cloud_auth`] - JWT token management and API key exchange
 //! - [`cloud_uplink`] - gRPC client for cloud sync (PRIMARY → Cloud)
+//! - [`cloud_net`] - HTTP proxy tunnel and cloud connectivity diagnostic
+//! - [`cloud_fallback`] - Direct outbox upload when no hub is reachable
+//! - [`hub_uplink`] - Hub upload queue to the cloud, cloud acks to terminals
 //!
 //! ## Usage
 //!
@@ -112,23 +129,41 @@
 
 // Core sync modules (Milestone 1)
 pub mod agent;
+pub mod batching;
+pub mod compat;
 pub mod config;
+pub mod control;
 pub mod error;
 pub mod inbound;
+pub mod integrity;
 pub mod outbox;
 pub mod protocol;
+pub mod supervisor;
+pub mod telemetry;
+pub mod throttle;
 pub mod transport;
 
 // Store Hub modules (Milestone 2)
+pub mod admin_api;
 pub mod aggregator;
+pub mod catalog_api;
 pub mod discovery;
 pub mod election;
 pub mod hub;
+pub mod hub_cache;
+pub mod netif;
+pub mod peer;
+pub mod recorder;
+pub mod shedding;
+pub mod update_rollout;
 
 // Cloud Uplink modules (Milestone 3)
 pub mod proto;
 pub mod cloud_auth;
 pub mod cloud_uplink;
+pub mod cloud_net;
+pub mod cloud_fallback;
+pub mod hub_uplink;
 
 // =============================================================================
 // Re-exports
@@ -136,17 +171,30 @@ pub mod cloud_uplink;
 
 // Core types
 pub use agent::{SyncAgent, SyncAgentHandle, SyncEventEmitter, SyncStatus};
+pub use compat::VersionMismatch;
 pub use config::{BroadcastMode, HubSettings, SyncConfig, SyncMode};
+pub use control::SyncControl;
 pub use error::{SyncError, SyncResult};
+pub use integrity::{DeviceKey, Role, Session};
 pub use protocol::SyncMessage;
-pub use transport::ConnectionState;
+pub use supervisor::{RestartPolicy, Supervisor};
+pub use throttle::{BandwidthPolicy, SyncWindow, TokenBucket};
+pub use transport::{ConnectionState, Transition, TransportEvent};
 
 // Milestone 2 types
+pub use admin_api::DayEndBoard;
 pub use aggregator::{AggregatorConfig, AggregatorHandle, InventoryAggregator};
 pub use discovery::{DiscoveredHub, DiscoveryConfig, DiscoveryHandle, DiscoveryService};
 pub use election::{ElectionConfig, ElectionHandle, ElectionService, ElectionState, NodeRole};
 pub use hub::{HubConfig, HubHandle, HubServer};
+pub use peer::{PeerHub, PeerHubHandle};
+pub use recorder::{HubRecorder, RecordedMessage, RecordingConfig, ReplayHub};
+pub use shedding::ClientLimits;
+pub use update_rollout::{SlotDecision, UpdateCoordinator};
 
 // Milestone 3 types
 pub use cloud_auth::{CloudAuth, CloudAuthConfig, TokenInfo};
 pub use cloud_uplink::{CloudUplink, CloudUplinkConfig};
+pub use cloud_net::{ConnectivityReport, Hop, HopResult, ProxyConfig};
+pub use cloud_fallback::CloudFallback;
+pub use hub_uplink::HubUplink;
@@ -0,0 +1,180 @@
+//! # Network Interfaces
+//!
+//! Interface enumeration for discovery and the hub listener. A store PC can
+//! sit on several VLANs, or on IPv4 and IPv6 at once; a single broadcast to
+//! 255.255.255.255 leaves through one interface only, and the address the
+//! kernel picks for it is not always the one terminals can reach.
+//!
+//! ## Per-Interface Discovery
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                     Per-Interface Discovery                             │
+//! │                                                                         │
+//! │  eth0   192.168.1.10/24    ──► HubRequest to 192.168.1.255:5555         │
+//! │  vlan20 10.20.0.5/16       ──► HubRequest to 10.20.255.255:5555         │
+//! │  eth0   fd00:1::10         ──► HubRequest to [ff02::1%eth0]:5555        │
+//! │  lo     127.0.0.1          ✗  skipped (loopback)                        │
+//! │  eth0   fe80::1            ✗  not announced (needs a zone to dial)      │
+//! │                                                                         │
+//! │  The hub answers each request by unicast, so the reply leaves through  │
+//! │  the interface facing the terminal, and lists the addresses it listens │
+//! │  on (`hub.interfaces`, or every usable address).                        │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV6};
+
+use socket2::{Domain, Protocol, Socket, Type};
+use tokio::net::UdpSocket;
+
+use crate::error::{SyncError, SyncResult};
+
+/// IPv6 link-local all-nodes multicast group, the IPv6 stand-in for a
+/// subnet broadcast.
+pub const ALL_NODES_V6: Ipv6Addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 1);
+
+// =============================================================================
+// Local Addresses
+// =============================================================================
+
+/// One address of a local network interface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalAddress {
+    /// Interface name (`eth0`, `vlan20`, ...).
+    pub interface: String,
+    /// Interface index, used as the IPv6 multicast scope.
+    pub index: Option<u32>,
+    /// The address itself.
+    pub ip: IpAddr,
+    /// Subnet broadcast address (IPv4 only).
+    pub broadcast: Option<Ipv4Addr>,
+}
+
+impl LocalAddress {
+    /// Where a discovery request for `port` is sent on this interface.
+    pub fn discovery_target(&self, port: u16) -> Option<SocketAddr> {
+        match self.ip {
+            IpAddr::V4(_) => Some(SocketAddr::new(
+                IpAddr::V4(self.broadcast.unwrap_or(Ipv4Addr::BROADCAST)),
+                port,
+            )),
+            IpAddr::V6(_) => self
+                .index
+                .map(|index| SocketAddr::V6(SocketAddrV6::new(ALL_NODES_V6, port, 0, index))),
+        }
+    }
+}
+
+/// Whether peers can dial `ip` as-is: not loopback, and not link-local
+/// (IPv6 link-local addresses need a zone that differs per peer).
+pub fn is_announceable(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => !v4.is_loopback() && !v4.is_link_local() && !v4.is_unspecified(),
+        IpAddr::V6(v6) => {
+            !v6.is_loopback() && !v6.is_unspecified() && (v6.segments()[0] & 0xffc0) != 0xfe80
+        }
+    }
+}
+
+/// Usable addresses of this machine, limited to `interfaces` by name
+/// unless that is empty.
+pub fn local_addresses(interfaces: &[String]) -> SyncResult<Vec<LocalAddress>> {
+    let all = if_addrs::get_if_addrs()
+        .map_err(|e| SyncError::ConnectionFailed(format!("Failed to list interfaces: {}", e)))?
+        .into_iter()
+        .map(|iface| LocalAddress {
+            ip: iface.ip(),
+            broadcast: match &iface.addr {
+                if_addrs::IfAddr::V4(v4) => v4.broadcast,
+                if_addrs::IfAddr::V6(_) => None,
+            },
+            index: iface.index,
+            interface: iface.name,
+        })
+        .collect();
+    Ok(select(all, interfaces))
+}
+
+fn select(all: Vec<LocalAddress>, interfaces: &[String]) -> Vec<LocalAddress> {
+    all.into_iter()
+        .filter(|a| is_announceable(&a.ip))
+        .filter(|a| interfaces.is_empty() || interfaces.contains(&a.interface))
+        .collect()
+}
+
+// =============================================================================
+// Sockets
+// =============================================================================
+
+/// Binds an IPv6-only UDP socket, so it can share `port` with an IPv4
+/// socket on dual-stack hosts.
+pub fn bind_udp_v6(port: u16) -> SyncResult<UdpSocket> {
+    let bind = |e: std::io::Error| {
+        SyncError::ConnectionFailed(format!("Failed to bind IPv6 discovery socket: {}", e))
+    };
+
+    let socket = Socket::new(Domain::IPV6, Type::DGRAM, Some(Protocol::UDP)).map_err(bind)?;
+    socket.set_only_v6(true).map_err(bind)?;
+    socket.set_reuse_address(true).map_err(bind)?;
+    socket.set_nonblocking(true).map_err(bind)?;
+    let addr = SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), port);
+    socket.bind(&addr.into()).map_err(bind)?;
+    UdpSocket::from_std(socket.into()).map_err(bind)
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(interface: &str, ip: &str, broadcast: Option<&str>) -> LocalAddress {
+        LocalAddress {
+            interface: interface.into(),
+            index: Some(2),
+            ip: ip.parse().unwrap(),
+            broadcast: broadcast.map(|b| b.parse().unwrap()),
+        }
+    }
+
+    #[test]
+    fn test_select_skips_unreachable_and_filters_interfaces() {
+        let all = vec![
+            addr("lo", "127.0.0.1", None),
+            addr("eth0", "192.168.1.10", Some("192.168.1.255")),
+            addr("eth0", "fe80::1", None),
+            addr("eth0", "fd00:1::10", None),
+            addr("vlan20", "10.20.0.5", Some("10.20.255.255")),
+            addr("eth1", "169.254.3.4", None),
+        ];
+
+        let ips: Vec<String> = select(all.clone(), &[])
+            .iter()
+            .map(|a| a.ip.to_string())
+            .collect();
+        assert_eq!(ips, ["192.168.1.10", "fd00:1::10", "10.20.0.5"]);
+
+        let only_vlan = select(all, &["vlan20".to_string()]);
+        assert_eq!(only_vlan.len(), 1);
+        assert_eq!(only_vlan[0].interface, "vlan20");
+    }
+
+    #[test]
+    fn test_discovery_targets() {
+        let v4 = addr("eth0", "192.168.1.10", Some("192.168.1.255"));
+        assert_eq!(
+            v4.discovery_target(5555).unwrap().to_string(),
+            "192.168.1.255:5555"
+        );
+
+        let v6 = addr("eth0", "fd00:1::10", None);
+        let target = v6.discovery_target(5555).unwrap();
+        assert_eq!(target.ip(), IpAddr::V6(ALL_NODES_V6));
+        assert!(matches!(target, SocketAddr::V6(t) if t.scope_id() == 2));
+
+        let no_index = LocalAddress { index: None, ..v6 };
+        assert!(no_index.discovery_target(5555).is_none());
+    }
+}
@@ -22,26 +22,40 @@
 //! │  │                    OutboxProcessor                              │   │
 //! │  │                                                                 │   │
 //! │  │  1. Poll: SELECT * FROM sync_outbox                            │   │
-//! │  │           WHERE synced_at IS NULL                              │   │
+//! │  │           WHERE synced_at IS NULL AND held_by_hub IS NULL      │   │
 //! │  │           ORDER BY created_at LIMIT 100                        │   │
 //! │  │                                                                 │   │
-//! │  │  2. Batch: Group entries into OutboxBatch message              │   │
+//! │  │  2. Batch: Group entries into OutboxBatch message, up to the   │   │
+//! │  │     adaptive byte target (see `batching`)                      │   │
 //! │  │                                                                 │   │
 //! │  │  3. Send: Transport.send(OutboxBatch)                          │   │
 //! │  │                                                                 │   │
 //! │  │  4. Wait: Await BatchAck response                              │   │
 //! │  │                                                                 │   │
 //! │  │  5. Mark: UPDATE sync_outbox SET synced_at = NOW()             │   │
-//! │  │           WHERE id IN (acked_ids)                              │   │
+//! │  │           WHERE id IN (acked_ids)          -- hub_acked_at     │   │
+//! │  │           WHERE id IN (uploaded_ids)       -- cloud_acked_at   │   │
 //! │  │                                                                 │   │
 //! │  │  6. Retry: UPDATE sync_outbox SET attempts += 1                │   │
 //! │  │            WHERE id IN (failed_ids)                            │   │
+//! │  │                                                                 │   │
+//! │  │  7. Hold: UPDATE sync_outbox SET held_by_hub = <hub>           │   │
+//! │  │           WHERE id IN (held_ids); synced on CloudAck, sent     │   │
+//! │  │           again after cloud_ack_timeout_secs or when a new     │   │
+//! │  │           hub is elected (see `hub_uplink`)                    │   │
 //! │  └─────────────────────────────────────────────────────────────────┘   │
 //! │                                                                         │
 //! │  TIMING:                                                               │
 //! │  • Poll interval: 5 seconds (configurable)                             │
-//! │  • Batch size: 100 entries (configurable)                              │
+//! │  • Batch size: 100 entries and 256 KiB at most (configurable); one    │
+//! │    batch in flight at a time, the byte target tuned from its ack      │
 //! │  • Max retries: 10 (then logged and skipped)                           │
+//! │  • Invalid payloads: moved to sync_quarantine, never sent              │
+//! │  • Bandwidth: batches wait for the upload throttle; in metered mode    │
+//! │    polls outside the sync windows are skipped (see `throttle`)         │
+//! │  • Paused (see `control`): polls are skipped, entries stay queued      │
+//! │  • Flush (day end): polls now, and again after each ack until the      │
+//! │    outbox is empty or the link goes down                               │
 //! └─────────────────────────────────────────────────────────────────────────┘
 //! ```
 
@@ -50,12 +64,16 @@ use std::time::Duration;
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
-use titan_core::SyncOutboxEntry;
+use titan_core::{SyncOutboxEntry, SyncPayload, PAYLOAD_SCHEMA_VERSION};
 use titan_db::Database;
 
+use crate::batching::{self, BatchTuner};
 use crate::config::SyncConfig;
+use crate::control::SyncControl;
 use crate::error::{SyncError, SyncResult};
 use crate::protocol::{BatchAck, OutboxBatch, OutboxEntry, SyncMessage};
+use crate::shedding;
+use crate::throttle::{BandwidthPolicy, UploadThrottle};
 use crate::transport::TransportHandle;
 
 // =============================================================================
@@ -86,6 +104,21 @@ pub struct OutboxProcessor {
     /// Current batch sequence number.
     batch_seq: u64,
 
+    /// Upload bandwidth limiter.
+    throttle: UploadThrottle,
+
+    /// Adaptive batch byte target.
+    tuner: BatchTuner,
+
+    /// Operator pause switch.
+    control: SyncControl,
+
+    /// Receiver for flush requests.
+    flush_rx: mpsc::Receiver<()>,
+
+    /// Whether a flush is draining the outbox (next batch sent on ack).
+    flushing: bool,
+
     /// Shutdown receiver.
     shutdown_rx: mpsc::Receiver<()>,
 }
@@ -98,10 +131,13 @@ pub struct OutboxProcessorHandle {
 
     /// Sender for routing ack messages to the processor.
     ack_tx: mpsc::Sender<SyncMessage>,
+
+    /// Flush request sender.
+    flush_tx: mpsc::Sender<()>,
 }
 
 impl OutboxProcessorHandle {
-    /// Sends an acknowledgement message to the processor.
+    /// Sends an acknowledgement message (or a BUSY answer) to the processor.
     pub async fn handle_ack(&self, message: SyncMessage) -> SyncResult<()> {
         self.ack_tx
             .send(message)
@@ -109,6 +145,18 @@ impl OutboxProcessorHandle {
             .map_err(|_| SyncError::ChannelError("Ack channel closed".into()))
     }
 
+    /// Uploads the outbox now instead of on the next poll, batch after
+    /// batch until it is empty. Returns once the request is queued.
+    pub fn flush(&self) -> SyncResult<()> {
+        match self.flush_tx.try_send(()) {
+            // A flush already waiting covers this one
+            Ok(()) | Err(mpsc::error::TrySendError::Full(_)) => Ok(()),
+            Err(mpsc::error::TrySendError::Closed(_)) => {
+                Err(SyncError::ChannelError("Flush channel closed".into()))
+            }
+        }
+    }
+
     /// Triggers graceful shutdown.
     pub async fn shutdown(&self) -> SyncResult<()> {
         self.shutdown_tx
@@ -124,28 +172,42 @@ impl OutboxProcessor {
         db: Arc<Database>,
         config: Arc<SyncConfig>,
         transport: TransportHandle,
+        bandwidth: BandwidthPolicy,
+        control: SyncControl,
     ) -> (Self, OutboxProcessorHandle) {
         let (ack_tx, ack_rx) = mpsc::channel(100);
         let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
+        let (flush_tx, flush_rx) = mpsc::channel(1);
 
+        let tuner = BatchTuner::new(config.sync.batch_max_bytes);
         let processor = OutboxProcessor {
             db,
             config,
             transport,
             ack_rx,
             batch_seq: 0,
+            throttle: UploadThrottle::new(bandwidth),
+            tuner,
+            control,
+            flush_rx,
+            flushing: false,
             shutdown_rx,
         };
 
-        let handle = OutboxProcessorHandle { shutdown_tx, ack_tx };
+        let handle = OutboxProcessorHandle {
+            shutdown_tx,
+            ack_tx,
+            flush_tx,
+        };
 
         (processor, handle)
     }
 
     /// Runs the outbox processor loop.
     ///
-    /// This should be spawned as a background task.
-    pub async fn run(mut self) {
+    /// This should be spawned as a background task. It borrows the
+    /// processor, so a supervisor can run it again after a panic.
+    pub async fn run(&mut self) {
         info!("Outbox processor starting");
 
         let poll_interval = Duration::from_secs(self.config.sync.poll_interval_secs);
@@ -163,10 +225,34 @@ impl OutboxProcessor {
 
                 // Handle acknowledgements
                 Some(msg) = self.ack_rx.recv() => {
-                    if let SyncMessage::BatchAck(ack) = msg {
-                        if let Err(e) = self.handle_batch_ack(ack).await {
-                            error!(?e, "Failed to handle batch ack");
+                    match msg {
+                        SyncMessage::BatchAck(ack) => {
+                            if let Err(e) = self.handle_batch_ack(ack).await {
+                                error!(?e, "Failed to handle batch ack");
+                            }
+                            if self.flushing {
+                                if let Err(e) = self.process_batch().await {
+                                    error!(?e, "Failed to process outbox batch");
+                                }
+                            }
+                        }
+                        SyncMessage::CloudAck { ids } => {
+                            self.handle_cloud_ack(&ids).await;
                         }
+                        msg if shedding::is_busy(&msg) => {
+                            warn!("Hub is busy, holding uploads");
+                            self.tuner.busy(tokio::time::Instant::now());
+                        }
+                        _ => {}
+                    }
+                }
+
+                // Flush requested
+                Some(()) = self.flush_rx.recv() => {
+                    info!("Flushing outbox");
+                    self.flushing = true;
+                    if let Err(e) = self.process_batch().await {
+                        error!(?e, "Failed to process outbox batch");
                     }
                 }
 
@@ -186,15 +272,48 @@ impl OutboxProcessor {
         // Only process if connected
         if !self.transport.is_connected().await {
             debug!("Not connected, skipping outbox processing");
+            // Whatever was in flight is resent after the reconnect
+            self.tuner.reset();
+            self.flushing = false;
+            return Ok(());
+        }
+
+        if self.control.is_paused() {
+            debug!("Sync paused, skipping outbox processing");
+            self.flushing = false;
+            return Ok(());
+        }
+
+        // One batch in flight at a time
+        if !self.tuner.ready(tokio::time::Instant::now()) {
+            debug!("Waiting for the previous batch to be acknowledged");
             return Ok(());
         }
 
+        // Metered links only upload inside their sync windows
+        if !self.throttle.policy().allows_upload_now() {
+            debug!("Outside metered sync window, skipping outbox processing");
+            return Ok(());
+        }
+
+        // Ask again about entries whose cloud ack never came
+        let hold_timeout = chrono::Duration::seconds(self.config.sync.cloud_ack_timeout_secs as i64);
+        let released = self
+            .db
+            .sync_outbox()
+            .release_stale_held(chrono::Utc::now() - hold_timeout)
+            .await?;
+        if released > 0 {
+            info!(released, "Resending entries held too long by the hub");
+        }
+
         // Get pending entries
         let batch_size = self.config.sync.batch_size as u32;
         let entries = self.db.sync_outbox().get_pending(batch_size).await?;
 
         if entries.is_empty() {
             debug!("No pending outbox entries");
+            self.flushing = false;
             return Ok(());
         }
 
@@ -215,19 +334,60 @@ impl OutboxProcessor {
             );
         }
 
+        // Rows queued before payloads were validated may not fit their
+        // schema; quarantine those instead of sending them
+        let mut valid = Vec::with_capacity(processable.len());
+        for entry in processable {
+            match SyncPayload::parse(
+                &entry.entity_type,
+                &entry.entity_id,
+                PAYLOAD_SCHEMA_VERSION,
+                &entry.payload,
+            ) {
+                Ok(_) => valid.push(entry),
+                Err(e) => {
+                    warn!(
+                        id = %entry.id,
+                        entity_type = %entry.entity_type,
+                        entity_id = %entry.entity_id,
+                        error = %e,
+                        "Quarantining invalid outbox payload"
+                    );
+                    self.db.sync_outbox().quarantine_entry(&entry, &e.to_string()).await?;
+                }
+            }
+        }
+        let mut processable = valid;
+
         if processable.is_empty() {
             return Ok(());
         }
 
+        // Fill the batch up to the current byte target
+        let target = self.tuner.target();
+        processable.truncate(batching::fit(&processable, target));
+
         // Build batch message
         let batch = self.build_batch(&processable)?;
 
-        // Send batch
+        // Send batch once the bandwidth budget allows; a throttled batch is
+        // picked up again on a later tick
         let message = SyncMessage::OutboxBatch(batch);
+        let size = message
+            .to_json()
+            .map_err(|e| SyncError::OutboxBatchFailed(e.to_string()))?
+            .len();
+        if let Err(wait) = self.throttle.try_acquire(size, tokio::time::Instant::now()) {
+            debug!(bytes = size, wait_ms = wait.as_millis() as u64, "Upload throttled");
+            return Ok(());
+        }
         self.transport.send(message).await?;
+        self.tuner.sent(tokio::time::Instant::now());
 
         debug!(
             count = processable.len(),
+            bytes = size,
+            target,
             batch_seq = self.batch_seq,
             "Sent outbox batch"
         );
@@ -246,7 +406,10 @@ impl OutboxProcessor {
                 entity_type: e.entity_type.clone(),
                 entity_id: e.entity_id.clone(),
                 payload: e.payload.clone(),
+                schema_version: PAYLOAD_SCHEMA_VERSION,
                 created_at: e.created_at.to_rfc3339(),
+                correlation_id: e.correlation_id.clone(),
+                trace_context: e.trace_context.clone(),
             })
             .collect();
 
@@ -258,13 +421,20 @@ impl OutboxProcessor {
     }
 
     /// Handles a batch acknowledgement.
-    async fn handle_batch_ack(&self, ack: BatchAck) -> SyncResult<()> {
+    async fn handle_batch_ack(&mut self, ack: BatchAck) -> SyncResult<()> {
         info!(
             acked = ack.acked_ids.len(),
+            held = ack.held_ids.len(),
+            uploaded = ack.uploaded_ids.len(),
             failed = ack.failed_ids.len(),
             new_cursor = ack.new_cursor,
             "Received batch acknowledgement"
         );
+        self.tuner.acked(
+            ack.acked_ids.len() + ack.held_ids.len() + ack.uploaded_ids.len(),
+            ack.failed_ids.len(),
+            tokio::time::Instant::now(),
+        );
 
         // Mark acked entries as synced
         for id in &ack.acked_ids {
@@ -273,6 +443,20 @@ impl OutboxProcessor {
             }
         }
 
+        // The hub uploaded these before
+        for id in &ack.uploaded_ids {
+            if let Err(e) = self.db.sync_outbox().mark_uploaded(id).await {
+                error!(?e, id = %id, "Failed to mark entry as synced");
+            }
+        }
+
+        // The hub has these, but the cloud does not yet
+        for id in &ack.held_ids {
+            if let Err(e) = self.db.sync_outbox().mark_held(id, &ack.held_by).await {
+                error!(?e, id = %id, "Failed to mark entry as held");
+            }
+        }
+
         // Mark failed entries with error
         for failed in &ack.failed_ids {
             let error_msg = format!(
@@ -295,6 +479,20 @@ impl OutboxProcessor {
 
         Ok(())
     }
+
+    /// Marks held entries synced once the hub reports them in the cloud.
+    async fn handle_cloud_ack(&self, ids: &[String]) {
+        let mut synced = 0;
+        for id in ids {
+            match self.db.sync_outbox().mark_cloud_acked(id).await {
+                Ok(true) => synced += 1,
+                // Re-queued with a newer payload since, which still has to go
+                Ok(false) => debug!(id = %id, "Cloud ack for an entry no longer held"),
+                Err(e) => error!(?e, id = %id, "Failed to mark entry as synced"),
+            }
+        }
+        info!(synced, total = ids.len(), "Received cloud acknowledgement");
+    }
 }
 
 // =============================================================================
@@ -0,0 +1,254 @@
+//! # Peer Pairing
+//!
+//! Hub-less sync for stores that only ever run two registers. Electing a
+//! hub between two machines buys nothing but failure modes, so in peer
+//! mode each terminal serves a small hub for the other one and connects
+//! to the other one's hub as an ordinary terminal. Both outboxes flow
+//! both ways and neither side is in charge.
+//!
+//! ## Peer Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                            Peer Pairing                                 │
+//! │                                                                         │
+//! │   POS A                                  POS B                          │
+//! │   ┌──────────────────────┐               ┌──────────────────────┐       │
+//! │   │ SyncAgent ───────────┼── OutboxBatch ┼─► PeerHub            │       │
+//! │   │                      │               │     DeltaProcessor   │       │
+//! │   │                      │               │     (B's database)   │       │
+//! │   │ PeerHub ◄────────────┼ OutboxBatch ──┼── SyncAgent          │       │
+//! │   │   DeltaProcessor     │               │                      │       │
+//! │   │   (A's database)     │               │                      │       │
+//! │   └──────────────────────┘               └──────────────────────┘       │
+//! │                                                                         │
+//! │  On each side, what the hub would broadcast to other terminals is      │
+//! │  applied to the local database instead:                                │
+//! │    EntityUpdate (relayed documents) ──► InboundHandler as-is           │
+//! │    InventoryUpdate ──► "inventory_delta" EntityUpdate ──► stock        │
+//! │                                                                         │
+//! │  Cloud-bound entries sent by the peer are uploaded by this side's      │
+//! │  HubUplink (when cloud settings are given), so each terminal's sales   │
+//! │  reach the cloud through the other one.                                │
+//! │                                                                         │
+//! │  No election, no discovery: sync.peer_url names the other terminal     │
+//! │  and the hub admits one client.                                        │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use std::sync::Arc;
+
+use tokio::sync::{broadcast, mpsc};
+use tracing::{debug, error, info, warn};
+
+use titan_db::Database;
+
+use crate::aggregator::{AggregatorConfig, AggregatorHandle, DeltaProcessor, InventoryAggregator};
+use crate::cloud_uplink::CloudUplinkConfig;
+use crate::config::SyncConfig;
+use crate::election::{ElectionConfig, ElectionHandle, ElectionService};
+use crate::error::SyncResult;
+use crate::hub::{HubConfig, HubHandle, HubServer};
+use crate::hub_uplink::HubUplink;
+use crate::inbound::InboundHandlerHandle;
+use crate::protocol::{EntityUpdate, SyncMessage};
+
+/// Clients a peer hub admits: the other terminal.
+pub const PEER_MAX_CLIENTS: usize = 1;
+
+// =============================================================================
+// Peer Hub
+// =============================================================================
+
+/// The hub one terminal of a pair serves for the other.
+pub struct PeerHub {
+    /// Sync configuration (peer mode).
+    config: Arc<SyncConfig>,
+    /// This terminal's database, which the peer's entries are applied to.
+    db: Arc<Database>,
+    /// Cloud settings for uploading the peer's cloud-bound entries.
+    cloud: Option<CloudUplinkConfig>,
+}
+
+/// Handle for stopping a running peer hub.
+pub struct PeerHubHandle {
+    hub: HubHandle,
+    election: ElectionHandle,
+    aggregator: AggregatorHandle,
+    uplink_tx: Option<mpsc::Sender<()>>,
+}
+
+impl PeerHub {
+    /// Creates a peer hub applying to `db`.
+    pub fn new(config: Arc<SyncConfig>, db: Arc<Database>) -> Self {
+        PeerHub {
+            config,
+            db,
+            cloud: None,
+        }
+    }
+
+    /// Uploads the cloud-bound entries the peer sends with `cloud`.
+    pub fn with_cloud(mut self, cloud: CloudUplinkConfig) -> Self {
+        self.cloud = Some(cloud);
+        self
+    }
+
+    /// Starts the hub, and applies what it broadcasts through `inbound`.
+    pub async fn start(self, inbound: InboundHandlerHandle) -> SyncResult<PeerHubHandle> {
+        // Peer mode never campaigns; the hub only needs a term to report
+        let election = ElectionService::new(self.config.clone(), ElectionConfig::default()).start();
+
+        let mut hub_config = HubConfig::from(&self.config.hub);
+        hub_config.limits.max_clients = PEER_MAX_CLIENTS;
+        let (delta_tx, delta_rx) = mpsc::channel(256);
+        let hub = HubServer::new(hub_config, self.config.clone(), election.clone(), delta_tx)
+            .with_catalog(self.db.clone())
+            .start()
+            .await?;
+
+        // Subscribed before anything can be broadcast
+        let updates = hub.subscribe();
+
+        // One peer: nothing to gain from coalescing
+        let aggregator =
+            InventoryAggregator::new(AggregatorConfig::immediate(), hub.clone()).start();
+        let mut processor =
+            DeltaProcessor::new(aggregator.clone(), hub.clone()).with_database(self.db.clone());
+        if let Some(cloud) = &self.cloud {
+            processor = processor.with_cloud(cloud.clone());
+        }
+        tokio::spawn(processor.start(delta_rx));
+
+        tokio::spawn(apply_locally(updates, inbound));
+
+        let uplink_tx = self.cloud.map(|cloud| {
+            let interval = cloud.upload_interval;
+            let (mut uplink, uplink_tx) = HubUplink::new(self.db.clone(), hub.clone(), cloud);
+            tokio::spawn(async move { uplink.run(interval).await });
+            uplink_tx
+        });
+
+        info!(
+            device_id = %self.config.device_id(),
+            peer_url = self.config.peer_url().unwrap_or_default(),
+            "Peer hub started"
+        );
+        Ok(PeerHubHandle {
+            hub,
+            election,
+            aggregator,
+            uplink_tx,
+        })
+    }
+}
+
+impl PeerHubHandle {
+    /// The hub the peer connects to.
+    pub fn hub(&self) -> &HubHandle {
+        &self.hub
+    }
+
+    /// Stops the hub and everything started with it.
+    pub async fn shutdown(&self) {
+        if let Some(tx) = &self.uplink_tx {
+            let _ = tx.send(()).await;
+        }
+        let _ = self.aggregator.shutdown().await;
+        let _ = self.hub.shutdown().await;
+        let _ = self.election.shutdown().await;
+    }
+}
+
+// =============================================================================
+// Local Apply
+// =============================================================================
+
+/// Feeds the hub's broadcasts to this terminal's inbound handler, until
+/// either side closes.
+async fn apply_locally(
+    mut updates: broadcast::Receiver<SyncMessage>,
+    inbound: InboundHandlerHandle,
+) {
+    loop {
+        let msg = match updates.recv().await {
+            Ok(msg) => msg,
+            Err(broadcast::error::RecvError::Lagged(missed)) => {
+                warn!(missed, "Peer hub broadcasts lagged");
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+        let Some(update) = local_update(msg) else {
+            continue;
+        };
+        if let Err(e) = inbound.handle_update(update).await {
+            error!(?e, "Failed to apply peer update locally");
+            break;
+        }
+    }
+    debug!("Peer hub local apply stopped");
+}
+
+/// The update this terminal applies for a message its peer hub
+/// broadcast, if any.
+///
+/// Relayed documents are applied as they are; aggregated stock changes
+/// become the `inventory_delta` updates the inbound handler adds to
+/// stock. Everything else (redemption results, heartbeats) only concerns
+/// the connected peer.
+pub fn local_update(msg: SyncMessage) -> Option<SyncMessage> {
+    match msg {
+        SyncMessage::EntityUpdate(update) => Some(SyncMessage::EntityUpdate(update)),
+        SyncMessage::InventoryUpdate(update) => Some(SyncMessage::EntityUpdate(EntityUpdate {
+            entity_type: "inventory_delta".to_string(),
+            entity_id: format!("{}:{}", update.source_device_id, update.timestamp),
+            operation: "upsert".to_string(),
+            data: serde_json::json!({
+                "product_id": update.product_id,
+                "delta": update.delta_quantity,
+                "reason": format!("peer {}", update.source_device_id),
+            }),
+            version: 0,
+            updated_at: update.timestamp,
+        })),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::InventoryUpdate;
+
+    #[test]
+    fn test_inventory_update_becomes_local_delta() {
+        let msg = SyncMessage::InventoryUpdate(InventoryUpdate {
+            product_id: "p-1".to_string(),
+            sku: "SKU-1".to_string(),
+            delta_quantity: -3,
+            source_device_id: "pos-b".to_string(),
+            timestamp: "2026-10-17T10:00:00Z".to_string(),
+        });
+
+        let Some(SyncMessage::EntityUpdate(update)) = local_update(msg) else {
+            panic!("expected an entity update");
+        };
+        assert_eq!(update.entity_type, "inventory_delta");
+        assert_eq!(update.data["product_id"], "p-1");
+        assert_eq!(update.data["delta"], -3);
+    }
+
+    #[test]
+    fn test_peer_only_messages_are_not_applied() {
+        let relayed = SyncMessage::EntityUpdate(EntityUpdate {
+            entity_type: "quote".to_string(),
+            entity_id: "q-1".to_string(),
+            operation: "upsert".to_string(),
+            data: serde_json::json!({}),
+            version: 1,
+            updated_at: "2026-10-17T10:00:00Z".to_string(),
+        });
+        assert!(local_update(relayed).is_some());
+        assert!(local_update(SyncMessage::ResyncComplete { products: 0 }).is_none());
+    }
+}
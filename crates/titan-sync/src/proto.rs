@@ -11,4 +11,7 @@
 //! - `HealthServiceClient` - Health checks
 
 // Include the generated code from build.rs
+// Generated oneof enums carry full entity messages by value.
+#![allow(clippy::large_enum_variant)]
+
 tonic::include_proto!("titan.sync.v1");
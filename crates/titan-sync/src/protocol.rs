@@ -9,13 +9,17 @@
 //! │                                                                         │
 //! │  HANDSHAKE FLOW                                                        │
 //! │  ──────────────                                                        │
-//! │  SECONDARY ───► Hello { device_id, protocol_version }                  │
-//! │  PRIMARY   ◄─── Welcome { store_id, sync_cursor }                      │
+//! │  SECONDARY ───► Hello { device_id, protocol_version, min_version }     │
+//! │  PRIMARY   ◄─── Welcome { store_id, protocol_version (negotiated) }    │
+//! │  PRIMARY   ◄─── Error { UPGRADE_REQUIRED }   (no common version)       │
 //! │                                                                         │
 //! │  OUTBOX UPLOAD (SECONDARY → PRIMARY)                                   │
 //! │  ───────────────────────────────────                                   │
 //! │  SECONDARY ───► OutboxBatch { entries: [...] }                         │
 //! │  PRIMARY   ◄─── BatchAck { acked_ids: [...], failed_ids: [...] }       │
+//! │  PRIMARY   ◄─── BatchAck { held_ids: [...] }   (v7, cloud-bound)       │
+//! │  PRIMARY   ◄─── CloudAck { ids: [...] }        (v7, once uploaded)     │
+//! │  PRIMARY   ◄─── BatchAck { uploaded_ids: [...] } (v8, in the cloud)    │
 //! │                                                                         │
 //! │  INVENTORY SYNC (Milestone 2)                                          │
 //! │  ────────────────────────────                                          │
@@ -27,6 +31,42 @@
 //! │  PRIMARY   ───► Heartbeat { device_id, term }                          │
 //! │  ANY       ───► ElectionStart { candidate_id, priority }               │
 //! │                                                                         │
+//! │  STORE CREDIT REDEMPTION                                               │
+//! │  ───────────────────────                                               │
+//! │  SECONDARY ───► StoreCreditRedeem { request_id, amount_cents }         │
+//! │  PRIMARY   ───► StoreCreditRedeemResult { approved }       (broadcast) │
+//! │                                                                         │
+//! │  FULL RESYNC (v4)                                                      │
+//! │  ────────────────                                                      │
+//! │  SECONDARY ───► ResyncRequest { device_id }                            │
+//! │  PRIMARY   ───► EntityUpdate { operation: "snapshot" } ... (to device) │
+//! │  PRIMARY   ───► ResyncComplete { products }                (to device) │
+//! │                                                                         │
+//! │  STAGED UPDATES (v5)                                                   │
+//! │  ───────────────────                                                   │
+//! │  SECONDARY ───► UpdateSlotRequest { request_id, version }              │
+//! │  PRIMARY   ───► UpdateSlotResult { granted, queue_position } (to device)│
+//! │  SECONDARY ───► UpdateFinished { version, success }                    │
+//! │                                                                         │
+//! │  DAY END (v6)                                                          │
+//! │  ────────────                                                          │
+//! │  SECONDARY ───► DayEndReport { device_id, business_date, steps, ... }  │
+//! │                                                                         │
+//! │  SALE LOOKUP (v9)                                                      │
+//! │  ────────────────                                                      │
+//! │  SECONDARY ───► SaleLookup { request_id, receipt_number }              │
+//! │  PRIMARY   ───► SaleLookupResult { sale, from_cloud }      (to device) │
+//! │                                                                         │
+//! │  LOYALTY REWARD REDEMPTION (v10)                                       │
+//! │  ───────────────────────────────                                       │
+//! │  SECONDARY ───► LoyaltyRedeem { request_id, amount_cents }             │
+//! │  PRIMARY   ───► LoyaltyRedeemResult { approved }           (to device) │
+//! │                                                                         │
+//! │  COUPON REDEMPTION (v11)                                               │
+//! │  ───────────────────────                                               │
+//! │  SECONDARY ───► CouponRedeem { request_id, coupon, redemption_id }     │
+//! │  PRIMARY   ───► CouponRedeemResult { approved, uses }      (to device) │
+//! │                                                                         │
 //! │  KEEPALIVE                                                             │
 //! │  ─────────                                                             │
 //! │  Both      ◄──► Ping { timestamp }                                     │
@@ -47,9 +87,41 @@
 //! Future versions may use Protobuf or MessagePack for efficiency.
 
 use serde::{Deserialize, Serialize};
+use titan_core::ErrorCode;
 
 /// Current protocol version.
-pub const PROTOCOL_VERSION: u32 = 2;
+pub const PROTOCOL_VERSION: u32 = 11;
+
+/// Oldest protocol version this build can still talk to (see `compat`).
+pub const MIN_PROTOCOL_VERSION: u32 = 2;
+
+/// Entity types the hub pushes to terminals as EntityUpdates, i.e. what a
+/// terminal can subscribe to in its Hello.
+pub const ENTITY_UPDATE_TYPES: &[&str] = &[
+    "product",
+    "inventory_delta",
+    "tax_rate",
+    "category",
+    "user",
+    "sale",
+    "quote",
+    "layaway",
+    "store_transfer",
+    "store_credit",
+    "business_customer",
+    "customer_erasure",
+    "product_attributes",
+    "product_style",
+    "product_bundle",
+    "product_packs",
+    "supplier",
+    "coupon",
+    "loyalty",
+];
+
+/// Entity types every terminal gets whatever it subscribed to: an erasure
+/// must reach devices holding sales for the customer.
+const ALWAYS_PUSHED: &[&str] = &["customer_erasure"];
 
 // =============================================================================
 // Main Message Enum (Tagged Union)
@@ -82,6 +154,10 @@ pub enum SyncMessage {
     /// Acknowledgement for a batch upload.
     BatchAck(BatchAck),
 
+    /// Sent by the hub to the terminal an entry came from once the entry
+    /// is in the cloud (v7); the terminal stops holding it.
+    CloudAck { ids: Vec<String> },
+
     // =========================================================================
     // Inventory Sync Messages (Milestone 2)
     // =========================================================================
@@ -118,6 +194,80 @@ pub enum SyncMessage {
     /// Acknowledgement for an entity update.
     UpdateAck(UpdateAck),
 
+    // =========================================================================
+    // Store Credit Messages
+    // =========================================================================
+
+    /// Request to spend store credit, checked against the hub's ledger.
+    StoreCreditRedeem(StoreCreditRedeemRequest),
+
+    /// Hub's answer to a redemption, addressed to the requesting device.
+    StoreCreditRedeemResult(StoreCreditRedeemResult),
+
+    // =========================================================================
+    // Full Resync Messages (v4)
+    // =========================================================================
+
+    /// Request for a full catalog snapshot, sent by a terminal recovering
+    /// from a corrupted local database.
+    ResyncRequest { device_id: String },
+
+    /// Sent to the requesting terminal after the last snapshot update.
+    ResyncComplete { products: u64 },
+
+    // =========================================================================
+    // Staged Update Messages (v5)
+    // =========================================================================
+
+    /// Request for permission to install an app update (see
+    /// `update_rollout`).
+    UpdateSlotRequest(UpdateSlotRequest),
+
+    /// Hub's answer to a slot request, sent to the requesting device.
+    UpdateSlotResult(UpdateSlotResult),
+
+    /// Sent after installing an update, or giving up on it; frees the slot.
+    UpdateFinished { version: String, success: bool },
+
+    // =========================================================================
+    // Day End Messages (v6)
+    // =========================================================================
+
+    /// Outcome of a terminal's scheduled day end, kept by the hub for the
+    /// admin API.
+    DayEndReport(titan_core::DayEndReport),
+
+    // =========================================================================
+    // Sale Lookup Messages (v9)
+    // =========================================================================
+
+    /// Request for a sale this terminal no longer has, by receipt number.
+    SaleLookup(SaleLookupRequest),
+
+    /// Hub's answer to a sale lookup, sent to the requesting device.
+    SaleLookupResult(SaleLookupResult),
+
+    // =========================================================================
+    // Loyalty Messages (v10)
+    // =========================================================================
+
+    /// Request to spend loyalty rewards, checked against the tenant's
+    /// ledger.
+    LoyaltyRedeem(LoyaltyRedeemRequest),
+
+    /// Hub's answer to a reward redemption, sent to the requesting device.
+    LoyaltyRedeemResult(LoyaltyRedeemResult),
+
+    // =========================================================================
+    // Coupon Messages (v11)
+    // =========================================================================
+
+    /// Request to use a coupon, counted against the hub's redemptions.
+    CouponRedeem(CouponRedeemRequest),
+
+    /// Hub's answer to a coupon redemption, sent to the requesting device.
+    CouponRedeemResult(CouponRedeemResult),
+
     // =========================================================================
     // Keepalive Messages
     // =========================================================================
@@ -169,9 +319,20 @@ pub struct HelloPayload {
     /// Protocol version supported by this device.
     pub protocol_version: u32,
 
+    /// Oldest protocol version this device can still speak. Absent from
+    /// terminals that predate negotiation.
+    #[serde(default)]
+    pub min_protocol_version: Option<u32>,
+
     /// Device priority for election.
     #[serde(default)]
     pub priority: u8,
+
+    /// Entity types this device wants EntityUpdates for (see
+    /// [`ENTITY_UPDATE_TYPES`]). Absent means all of them, as for
+    /// terminals that predate subscriptions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subscriptions: Option<Vec<String>>,
 }
 
 impl HelloPayload {
@@ -181,9 +342,22 @@ impl HelloPayload {
             device_name: device_name.to_string(),
             store_id: store_id.to_string(),
             protocol_version: PROTOCOL_VERSION,
+            min_protocol_version: Some(MIN_PROTOCOL_VERSION),
             priority: 50,
+            subscriptions: None,
         }
     }
+
+    /// Whether the hub should send `msg` to this device. Only
+    /// EntityUpdates are filtered; everything else always goes out.
+    pub fn wants(&self, msg: &SyncMessage) -> bool {
+        let (Some(subscriptions), SyncMessage::EntityUpdate(update)) = (&self.subscriptions, msg)
+        else {
+            return true;
+        };
+        let named = |t: &str| t.eq_ignore_ascii_case(&update.entity_type);
+        ALWAYS_PUSHED.iter().any(|t| named(t)) || subscriptions.iter().any(|t| named(t))
+    }
 }
 
 /// Welcome message sent by PRIMARY after successful handshake.
@@ -201,6 +375,15 @@ pub struct WelcomePayload {
 
     /// Server time for clock sync reference.
     pub server_time: String,
+
+    /// Version negotiated for this connection. Hubs that predate
+    /// negotiation omit it and speak v2.
+    #[serde(default = "legacy_protocol_version")]
+    pub protocol_version: u32,
+}
+
+fn legacy_protocol_version() -> u32 {
+    crate::compat::LEGACY_PROTOCOL_VERSION
 }
 
 // =============================================================================
@@ -223,8 +406,26 @@ pub struct OutboxEntry {
     /// Full entity payload as JSON string.
     pub payload: String,
 
+    /// Schema version the payload was written against (see
+    /// `titan_core::sync_payload`). Entries from older builds omit it.
+    #[serde(default = "legacy_payload_schema")]
+    pub schema_version: u32,
+
     /// When this entry was created.
     pub created_at: String,
+
+    /// Correlation ID of the terminal command that queued the entry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+
+    /// W3C `traceparent` of that command, so the hub's spans for the
+    /// entry join its trace (see `telemetry`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_context: Option<String>,
+}
+
+fn legacy_payload_schema() -> u32 {
+    1
 }
 
 /// Batch of outbox entries for upload.
@@ -253,6 +454,21 @@ pub struct BatchAck {
     #[serde(default)]
     pub failed_ids: Vec<FailedEntry>,
 
+    /// IDs the hub has queued for the cloud but not uploaded yet (v7).
+    /// The terminal keeps them until a `CloudAck`, so they survive the
+    /// hub being lost before the upload.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub held_ids: Vec<String>,
+
+    /// Hub holding `held_ids`.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub held_by: String,
+
+    /// IDs the hub had already uploaded to the cloud (v8), so the
+    /// terminal records a cloud ack rather than a hub ack.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub uploaded_ids: Vec<String>,
+
     /// Updated sync cursor.
     #[serde(default)]
     pub new_cursor: i64,
@@ -407,7 +623,8 @@ pub struct EntityUpdate {
     /// Entity ID.
     pub entity_id: String,
 
-    /// Update operation: "upsert", "patch", "delete".
+    /// Update operation: "upsert", "patch", "delete", or "snapshot" (full
+    /// resync: applied regardless of version, stock included).
     pub operation: String,
 
     /// Entity data as JSON.
@@ -439,6 +656,265 @@ pub struct UpdateAck {
     pub error: Option<String>,
 }
 
+// =============================================================================
+// Store Credit Payloads
+// =============================================================================
+
+/// Request to spend store credit.
+///
+/// The hub writes the redemption only if the balance on its ledger covers
+/// it, so terminals of the same store cannot spend the same credit twice.
+/// `entry_id` makes the request idempotent: a retry after a lost result is
+/// reported as approved without spending again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreCreditRedeemRequest {
+    /// Correlates the result with the waiting request.
+    pub request_id: String,
+
+    /// Device asking for the redemption.
+    pub device_id: String,
+
+    /// Store credit account ID.
+    pub account_id: String,
+
+    /// ID of the ledger entry to write.
+    pub entry_id: String,
+
+    /// Amount to spend (positive).
+    pub amount_cents: i64,
+
+    /// Sale the credit is tendered on.
+    #[serde(default)]
+    pub sale_id: Option<String>,
+
+    /// When the redemption was requested (ISO8601).
+    pub timestamp: String,
+}
+
+/// Hub's answer to a [`StoreCreditRedeemRequest`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreCreditRedeemResult {
+    /// Request this answers.
+    pub request_id: String,
+
+    /// Device that sent the request; other devices ignore the result.
+    pub device_id: String,
+
+    /// Whether the redemption was written.
+    pub approved: bool,
+
+    /// Balance after the redemption, or the current balance if rejected.
+    pub balance_cents: i64,
+
+    /// Why the redemption was rejected.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+// =============================================================================
+// Staged Update Payloads
+// =============================================================================
+
+/// Request for an update slot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSlotRequest {
+    /// Correlates the result with the waiting request.
+    pub request_id: String,
+
+    /// Device asking for the slot.
+    pub device_id: String,
+
+    /// Version the device wants to install.
+    pub version: String,
+}
+
+/// Hub's answer to an [`UpdateSlotRequest`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSlotResult {
+    /// Request this answers.
+    pub request_id: String,
+
+    /// Device that sent the request.
+    pub device_id: String,
+
+    /// Whether the device may install now.
+    pub granted: bool,
+
+    /// Place in line when not granted (1 = next).
+    #[serde(default)]
+    pub queue_position: u32,
+
+    /// When to ask again if not granted.
+    #[serde(default)]
+    pub retry_after_secs: u64,
+}
+
+// =============================================================================
+// Sale Lookup Payloads
+// =============================================================================
+
+/// Request for a sale by receipt number.
+///
+/// The hub answers from its own copy when it has one, and asks the cloud
+/// otherwise, so refunds against receipts older than the terminals'
+/// retention period still work.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaleLookupRequest {
+    /// Correlates the result with the waiting request.
+    pub request_id: String,
+
+    /// Device asking for the sale.
+    pub device_id: String,
+
+    /// Receipt number printed on the sale.
+    pub receipt_number: String,
+}
+
+/// Hub's answer to a [`SaleLookupRequest`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaleLookupResult {
+    /// Request this answers.
+    pub request_id: String,
+
+    /// Device that sent the request.
+    pub device_id: String,
+
+    /// The sale, or `None` if neither the hub nor the cloud has it.
+    #[serde(default)]
+    pub sale: Option<titan_core::SaleDocument>,
+
+    /// Whether the sale came from the cloud rather than the hub's copy.
+    #[serde(default)]
+    pub from_cloud: bool,
+
+    /// Why the lookup could not be answered (cloud unreachable, ...).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+// =============================================================================
+// Loyalty Payloads
+// =============================================================================
+
+/// Request to spend loyalty rewards.
+///
+/// Rewards can be spent in any store of the tenant, so the hub checks the
+/// balance with the cloud when it has cloud settings and against its own
+/// ledger otherwise. As with store credit, `entry_id` makes the request
+/// idempotent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoyaltyRedeemRequest {
+    /// Correlates the result with the waiting request.
+    pub request_id: String,
+
+    /// Device asking for the redemption.
+    pub device_id: String,
+
+    /// Loyalty account ID.
+    pub account_id: String,
+
+    /// ID of the ledger entry to write.
+    pub entry_id: String,
+
+    /// Rewards to spend, in cents (positive).
+    pub amount_cents: i64,
+
+    /// Sale the rewards are tendered on.
+    #[serde(default)]
+    pub sale_id: Option<String>,
+
+    /// When the redemption was requested (ISO8601).
+    pub timestamp: String,
+}
+
+/// Hub's answer to a [`LoyaltyRedeemRequest`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoyaltyRedeemResult {
+    /// Request this answers.
+    pub request_id: String,
+
+    /// Device that sent the request.
+    pub device_id: String,
+
+    /// Whether the redemption was written.
+    pub approved: bool,
+
+    /// Rewards left after the redemption, or the current balance if
+    /// rejected.
+    pub balance_cents: i64,
+
+    /// Why the redemption was rejected (balance too low, cloud
+    /// unreachable, ...).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+// =============================================================================
+// Coupon Payloads
+// =============================================================================
+
+/// Request to use a coupon.
+///
+/// The hub records the redemption only if the coupon still has uses left
+/// once every register's redemptions are counted, so two terminals cannot
+/// both take the last use. The coupon's definition travels with the
+/// request because the hub only learns coupons from the terminals.
+/// `redemption_id` makes the request idempotent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CouponRedeemRequest {
+    /// Correlates the result with the waiting request.
+    pub request_id: String,
+
+    /// Device asking for the redemption.
+    pub device_id: String,
+
+    /// The coupon, as this terminal knows it.
+    pub coupon: titan_core::Coupon,
+
+    /// ID of the redemption to record.
+    pub redemption_id: String,
+
+    /// Sale the coupon is used on.
+    pub sale_id: String,
+
+    /// Discount the coupon gives on the sale.
+    pub discount_cents: i64,
+
+    /// When the coupon was used (ISO8601); the coupon's window is checked
+    /// at this time.
+    pub timestamp: String,
+}
+
+/// Hub's answer to a [`CouponRedeemRequest`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CouponRedeemResult {
+    /// Request this answers.
+    pub request_id: String,
+
+    /// Device that sent the request.
+    pub device_id: String,
+
+    /// Whether the redemption was recorded.
+    pub approved: bool,
+
+    /// Uses after the redemption, or the current uses if rejected.
+    pub uses: i64,
+
+    /// Why the coupon could not be used (used up, expired, ...).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
 // =============================================================================
 // Helper Functions
 // =============================================================================
@@ -451,6 +927,7 @@ impl SyncMessage {
             SyncMessage::Welcome(_) => "Welcome",
             SyncMessage::OutboxBatch(_) => "OutboxBatch",
             SyncMessage::BatchAck(_) => "BatchAck",
+            SyncMessage::CloudAck { .. } => "CloudAck",
             SyncMessage::InventoryDelta(_) => "InventoryDelta",
             SyncMessage::InventoryUpdate(_) => "InventoryUpdate",
             SyncMessage::Heartbeat(_) => "Heartbeat",
@@ -459,6 +936,20 @@ impl SyncMessage {
             SyncMessage::ElectionResult(_) => "ElectionResult",
             SyncMessage::EntityUpdate(_) => "EntityUpdate",
             SyncMessage::UpdateAck(_) => "UpdateAck",
+            SyncMessage::StoreCreditRedeem(_) => "StoreCreditRedeem",
+            SyncMessage::StoreCreditRedeemResult(_) => "StoreCreditRedeemResult",
+            SyncMessage::ResyncRequest { .. } => "ResyncRequest",
+            SyncMessage::ResyncComplete { .. } => "ResyncComplete",
+            SyncMessage::UpdateSlotRequest(_) => "UpdateSlotRequest",
+            SyncMessage::UpdateSlotResult(_) => "UpdateSlotResult",
+            SyncMessage::UpdateFinished { .. } => "UpdateFinished",
+            SyncMessage::DayEndReport(_) => "DayEndReport",
+            SyncMessage::SaleLookup(_) => "SaleLookup",
+            SyncMessage::SaleLookupResult(_) => "SaleLookupResult",
+            SyncMessage::LoyaltyRedeem(_) => "LoyaltyRedeem",
+            SyncMessage::LoyaltyRedeemResult(_) => "LoyaltyRedeemResult",
+            SyncMessage::CouponRedeem(_) => "CouponRedeem",
+            SyncMessage::CouponRedeemResult(_) => "CouponRedeemResult",
             SyncMessage::Ping { .. } => "Ping",
             SyncMessage::Pong { .. } => "Pong",
             SyncMessage::Error { .. } => "Error",
@@ -474,7 +965,9 @@ impl SyncMessage {
             device_name: device_name.to_string(),
             store_id: store_id.to_string(),
             protocol_version: PROTOCOL_VERSION,
+            min_protocol_version: Some(MIN_PROTOCOL_VERSION),
             priority,
+            subscriptions: None,
         })
     }
 
@@ -494,13 +987,21 @@ impl SyncMessage {
     }
 
     /// Creates an Error message.
-    pub fn error(code: &str, message: &str) -> Self {
+    pub fn error(code: ErrorCode, message: &str) -> Self {
         SyncMessage::Error {
-            code: code.to_string(),
+            code: code.as_str().to_string(),
             message: message.to_string(),
         }
     }
 
+    /// The code of an Error message, if it is one this build knows.
+    pub fn error_code(&self) -> Option<ErrorCode> {
+        match self {
+            SyncMessage::Error { code, .. } => code.parse().ok(),
+            _ => None,
+        }
+    }
+
     /// Creates a Heartbeat message.
     pub fn heartbeat(device_id: &str, term: u64, hub_url: &str, priority: u8, connected_count: usize) -> Self {
         SyncMessage::Heartbeat(HeartbeatPayload {
@@ -552,6 +1053,50 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_legacy_handshake_defaults() {
+        // Payloads from builds that predate version negotiation
+        let hello = r#"{"type":"Hello","payload":{"deviceId":"d","deviceName":"n","storeId":"s","protocolVersion":2}}"#;
+        let SyncMessage::Hello(hello) = SyncMessage::from_json(hello).unwrap() else {
+            panic!("Expected Hello message");
+        };
+        assert_eq!(hello.min_protocol_version, None);
+        assert_eq!(hello.subscriptions, None);
+
+        let welcome = r#"{"type":"Welcome","payload":{"hubDeviceId":"h","storeId":"s","electionTerm":1,"serverTime":"t"}}"#;
+        let SyncMessage::Welcome(welcome) = SyncMessage::from_json(welcome).unwrap() else {
+            panic!("Expected Welcome message");
+        };
+        assert_eq!(welcome.protocol_version, 2);
+    }
+
+    #[test]
+    fn test_hello_subscriptions() {
+        let update = |entity_type: &str| {
+            SyncMessage::EntityUpdate(EntityUpdate {
+                entity_type: entity_type.to_string(),
+                entity_id: "e-1".to_string(),
+                operation: "upsert".to_string(),
+                data: serde_json::Value::Null,
+                version: 1,
+                updated_at: "2026-10-17T09:00:00Z".to_string(),
+            })
+        };
+        let mut hello = HelloPayload::new("kds-1", "Kitchen", "store-001");
+        assert!(hello.wants(&update("sale")));
+
+        hello.subscriptions = Some(vec!["product".to_string(), "category".to_string()]);
+        assert!(hello.wants(&update("product")));
+        assert!(hello.wants(&update("PRODUCT")));
+        assert!(!hello.wants(&update("sale")));
+        assert!(!hello.wants(&update("business_customer")));
+        assert!(hello.wants(&update("customer_erasure")));
+        assert!(hello.wants(&SyncMessage::inventory_delta("p-1", "SKU", -1)));
+
+        let json = SyncMessage::Hello(hello).to_json().unwrap();
+        assert!(json.contains(r#""subscriptions":["product","category"]"#));
+    }
+
     #[test]
     fn test_inventory_delta() {
         let delta = SyncMessage::inventory_delta("prod-123", "SKU-001", -5);
@@ -568,10 +1113,113 @@ mod tests {
         assert!(json.contains("42")); // term
     }
 
+    #[test]
+    fn test_store_credit_redeem_round_trip() {
+        let msg = SyncMessage::StoreCreditRedeem(StoreCreditRedeemRequest {
+            request_id: "req-1".to_string(),
+            device_id: "pos-02".to_string(),
+            account_id: "sc-1".to_string(),
+            entry_id: "e-1".to_string(),
+            amount_cents: 1500,
+            sale_id: Some("s-1".to_string()),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        });
+        let json = msg.to_json().unwrap();
+        assert!(json.contains("\"type\":\"StoreCreditRedeem\""));
+        assert!(json.contains("amountCents"));
+
+        let SyncMessage::StoreCreditRedeem(parsed) = SyncMessage::from_json(&json).unwrap() else {
+            panic!("Expected StoreCreditRedeem message");
+        };
+        assert_eq!(parsed.amount_cents, 1500);
+        assert_eq!(parsed.sale_id.as_deref(), Some("s-1"));
+    }
+
+    #[test]
+    fn test_loyalty_redeem_result_round_trip() {
+        let msg = SyncMessage::LoyaltyRedeemResult(LoyaltyRedeemResult {
+            request_id: "req-1".to_string(),
+            device_id: "pos-02".to_string(),
+            approved: false,
+            balance_cents: 300,
+            reason: Some("insufficient rewards".to_string()),
+        });
+        let json = msg.to_json().unwrap();
+        assert!(json.contains("\"type\":\"LoyaltyRedeemResult\""));
+        assert!(json.contains("balanceCents"));
+
+        let SyncMessage::LoyaltyRedeemResult(parsed) = SyncMessage::from_json(&json).unwrap()
+        else {
+            panic!("Expected LoyaltyRedeemResult message");
+        };
+        assert!(!parsed.approved);
+        assert_eq!(parsed.balance_cents, 300);
+    }
+
+    #[test]
+    fn test_coupon_redeem_round_trip() {
+        let now = chrono::Utc::now();
+        let msg = SyncMessage::CouponRedeem(CouponRedeemRequest {
+            request_id: "req-1".to_string(),
+            device_id: "pos-02".to_string(),
+            coupon: titan_core::Coupon {
+                id: "cp-1".to_string(),
+                tenant_id: "tenant".to_string(),
+                code: "SAVE10".to_string(),
+                description: None,
+                kind: titan_core::CouponKind::Percent,
+                value: 1000,
+                min_subtotal_cents: None,
+                product_ids: Vec::new(),
+                starts_at: None,
+                expires_at: None,
+                max_uses: Some(1),
+                active: true,
+                created_at: now,
+                updated_at: now,
+                sync_version: 1,
+            },
+            redemption_id: "r-1".to_string(),
+            sale_id: "s-1".to_string(),
+            discount_cents: 250,
+            timestamp: now.to_rfc3339(),
+        });
+        let json = msg.to_json().unwrap();
+        assert!(json.contains("\"type\":\"CouponRedeem\""));
+        assert!(json.contains("redemptionId"));
+
+        let SyncMessage::CouponRedeem(parsed) = SyncMessage::from_json(&json).unwrap() else {
+            panic!("Expected CouponRedeem message");
+        };
+        assert_eq!(parsed.coupon.max_uses, Some(1));
+        assert_eq!(parsed.discount_cents, 250);
+    }
+
+    #[test]
+    fn test_sale_lookup_result_without_sale() {
+        let json = r#"{"type":"SaleLookupResult","payload":{"requestId":"req-1","deviceId":"pos-02"}}"#;
+        let SyncMessage::SaleLookupResult(parsed) = SyncMessage::from_json(json).unwrap() else {
+            panic!("Expected SaleLookupResult message");
+        };
+        assert!(parsed.sale.is_none());
+        assert!(!parsed.from_cloud);
+        assert!(parsed.error.is_none());
+    }
+
     #[test]
     fn test_error_message() {
-        let error = SyncMessage::error("STORE_MISMATCH", "Store ID does not match");
+        let error = SyncMessage::error(ErrorCode::StoreMismatch, "Store ID does not match");
         let json = error.to_json().unwrap();
         assert!(json.contains("STORE_MISMATCH"));
+
+        let parsed = SyncMessage::from_json(&json).unwrap();
+        assert_eq!(parsed.error_code(), Some(ErrorCode::StoreMismatch));
+
+        // Codes from newer peers are kept as text but not recognised
+        let unknown = SyncMessage::Error {
+            code: "SOMETHING_NEW".to_string(),
+            message: String::new(),
+        };
+        assert_eq!(unknown.error_code(), None);
     }
 }
\ No newline at end of file
@@ -0,0 +1,616 @@
+//! # Hub Traffic Recording
+//!
+//! An opt-in flight recorder for the hub: every SyncMessage a terminal
+//! sends it and every message it sends a terminal is written to a
+//! recording file, with its size and timing, so "inventory went crazy
+//! yesterday" can be answered by looking at what actually went over the
+//! wire, and by playing it back into a test hub.
+//!
+//! ## Recording Flow
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                          Hub Recording                                  │
+//! │                                                                         │
+//! │  hub.record_path (TITAN_HUB_RECORD) set ──► HubRecorder on HubServer    │
+//! │                                                                         │
+//! │  POS ──► Hello / OutboxBatch / ... ──► record(Inbound)  ─┐              │
+//! │  POS ◄── Welcome / broadcasts / ... ◄── record(Outbound) ┤              │
+//! │                                                          ▼              │
+//! │      redact customer fields ──► queue ──► writer thread (JSON lines)    │
+//! │      (full queue: dropped and counted, the hub never waits)             │
+//! │                                                                         │
+//! │  Ring file: <path> fills to max_messages / 2, then becomes             │
+//! │  <path>.1.jsonl (replacing the older half) and <path> starts again     │
+//! │                                                                         │
+//! │  Replay: load_recording(path) ──► ReplayHub::replay(speed)              │
+//! │     inbound messages, original gaps / speed ──► DeltaProcessor          │
+//! │     (test database)      what it broadcasts ──► ReplayHub::subscribe    │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Redaction
+//! Customer data never reaches the file: the fields listed by
+//! `titan_core::sensitive_fields` are replaced with `[redacted]` in
+//! outbox payloads, relayed entity updates and sale lookup results.
+//! Redacted values are still strings, so a recording replays.
+//!
+//! The `titan-hub-replay` tool replays a recording into a hub over a copy
+//! of a store's database.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::Duration;
+use tracing::{debug, error, info, warn};
+
+use titan_core::sensitive_fields;
+use titan_db::Database;
+
+use crate::aggregator::{AggregatorConfig, AggregatorHandle, DeltaProcessor, InventoryAggregator};
+use crate::config::SyncConfig;
+use crate::election::{ElectionConfig, ElectionHandle, ElectionService};
+use crate::error::{SyncError, SyncResult};
+use crate::hub::{HubConfig, HubHandle, HubServer};
+use crate::protocol::SyncMessage;
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Messages kept in a recording unless configured otherwise.
+pub const DEFAULT_RECORD_MAX_MESSAGES: usize = 20_000;
+
+/// What a redacted customer field reads.
+pub const REDACTED: &str = "[redacted]";
+
+/// Messages waiting for the writer before new ones are dropped.
+const RECORD_QUEUE: usize = 1024;
+
+// =============================================================================
+// Recorded Messages
+// =============================================================================
+
+/// Which way a message went, from the hub's side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    /// Sent by a terminal to the hub.
+    Inbound,
+    /// Sent by the hub to a terminal.
+    Outbound,
+}
+
+/// One line of a recording.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedMessage {
+    /// When the hub sent or received the message.
+    pub at: DateTime<Utc>,
+    /// Milliseconds since the recorder started, for replay timing.
+    pub elapsed_ms: u64,
+    pub direction: Direction,
+    /// Terminal the message came from or went to.
+    pub device_id: String,
+    /// `SyncMessage::type_name`.
+    pub message_type: String,
+    /// Bytes on the wire, signature envelope included.
+    pub size: usize,
+    /// The message, customer fields redacted.
+    pub message: Value,
+}
+
+/// The message as recorded: serialized, with customer fields redacted.
+pub fn redact(msg: &SyncMessage) -> Value {
+    let mut value = serde_json::to_value(msg).unwrap_or(Value::Null);
+    let Some(payload) = value.get_mut("payload") else {
+        return value;
+    };
+
+    match msg {
+        SyncMessage::OutboxBatch(batch) => {
+            let Some(Value::Array(entries)) = payload.get_mut("entities") else {
+                return value;
+            };
+            for (entry, recorded) in batch.entities.iter().zip(entries.iter_mut()) {
+                if let Some(Value::String(text)) = recorded.get_mut("payload") {
+                    *text = redact_payload(&entry.entity_type, text);
+                }
+            }
+        }
+        SyncMessage::EntityUpdate(update) => {
+            if let Some(data) = payload.get_mut("data") {
+                redact_fields(&update.entity_type, data);
+            }
+        }
+        SyncMessage::SaleLookupResult(_) => {
+            if let Some(sale) = payload.get_mut("sale") {
+                redact_fields("SALE", sale);
+            }
+        }
+        _ => {}
+    }
+    value
+}
+
+/// An outbox payload (a JSON string) with its customer fields redacted.
+/// A payload that is not JSON is redacted whole when its type has any.
+fn redact_payload(entity_type: &str, payload: &str) -> String {
+    if sensitive_fields(entity_type).is_empty() {
+        return payload.to_string();
+    }
+    match serde_json::from_str::<Value>(payload) {
+        Ok(mut doc) => {
+            redact_fields(entity_type, &mut doc);
+            doc.to_string()
+        }
+        Err(_) => REDACTED.to_string(),
+    }
+}
+
+fn redact_fields(entity_type: &str, doc: &mut Value) {
+    for path in sensitive_fields(entity_type) {
+        redact_path(doc, path);
+    }
+}
+
+/// Replaces the strings at `path` (dotted, `name[]` for each element of
+/// an array); missing fields and nulls are left alone.
+fn redact_path(value: &mut Value, path: &str) {
+    let (segment, rest) = match path.split_once('.') {
+        Some((segment, rest)) => (segment, Some(rest)),
+        None => (path, None),
+    };
+    let (name, each) = match segment.strip_suffix("[]") {
+        Some(name) => (name, true),
+        None => (segment, false),
+    };
+    let Some(child) = value.get_mut(name) else {
+        return;
+    };
+
+    let visit = |target: &mut Value| match rest {
+        Some(rest) => redact_path(target, rest),
+        None => {
+            if target.is_string() {
+                *target = Value::String(REDACTED.to_string());
+            }
+        }
+    };
+    match child {
+        Value::Array(items) if each => items.iter_mut().for_each(visit),
+        _ if each => {}
+        target => visit(target),
+    }
+}
+
+// =============================================================================
+// Recorder
+// =============================================================================
+
+/// Where and how much the hub records.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordingConfig {
+    /// Recording file (JSON lines).
+    pub path: PathBuf,
+    /// Most messages kept across both halves of the ring.
+    pub max_messages: usize,
+}
+
+/// The file holding the older half of the recording at `path`.
+pub fn previous_segment(path: &Path) -> PathBuf {
+    path.with_extension("1.jsonl")
+}
+
+/// Hands messages to the writer thread. Cheap to clone; every connection
+/// records through the same one.
+#[derive(Clone)]
+pub struct HubRecorder {
+    tx: mpsc::Sender<RecordedMessage>,
+    started: Instant,
+    dropped: Arc<AtomicU64>,
+}
+
+impl HubRecorder {
+    /// Starts the writer thread for `config`. A file that cannot be
+    /// opened is logged and nothing is recorded.
+    pub fn start(config: RecordingConfig) -> Self {
+        let (tx, mut rx) = mpsc::channel::<RecordedMessage>(RECORD_QUEUE);
+        std::thread::spawn(move || {
+            let mut ring = match RingFile::open(&config) {
+                Ok(ring) => ring,
+                Err(e) => {
+                    error!(path = ?config.path, error = %e, "Hub recording could not start");
+                    return;
+                }
+            };
+            info!(path = ?config.path, max_messages = config.max_messages, "Hub recording started");
+
+            while let Some(record) = rx.blocking_recv() {
+                let line = match serde_json::to_string(&record) {
+                    Ok(line) => line,
+                    Err(e) => {
+                        warn!(error = %e, "Failed to serialize recorded message");
+                        continue;
+                    }
+                };
+                if let Err(e) = ring.append(&line) {
+                    error!(path = ?config.path, error = %e, "Hub recording stopped");
+                    return;
+                }
+            }
+            debug!("Hub recording writer stopped");
+        });
+
+        HubRecorder {
+            tx,
+            started: Instant::now(),
+            dropped: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Records `msg`, `size` bytes on the wire. Never waits: with the
+    /// writer behind, the message is dropped and counted.
+    pub fn record(&self, direction: Direction, device_id: &str, msg: &SyncMessage, size: usize) {
+        let record = RecordedMessage {
+            at: Utc::now(),
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+            direction,
+            device_id: device_id.to_string(),
+            message_type: msg.type_name().to_string(),
+            size,
+            message: redact(msg),
+        };
+        if self.tx.try_send(record).is_err() {
+            let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+            if dropped.is_power_of_two() {
+                warn!(dropped, "Hub recording is behind - messages dropped");
+            }
+        }
+    }
+
+    /// Messages dropped because the writer was behind or had stopped.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// The recording file: two halves, the current one appended to.
+struct RingFile {
+    path: PathBuf,
+    half: usize,
+    written: usize,
+    file: File,
+}
+
+impl RingFile {
+    fn open(config: &RecordingConfig) -> std::io::Result<Self> {
+        if let Some(parent) = config.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        // A restarted hub carries on filling the current half
+        let written = match File::open(&config.path) {
+            Ok(file) => BufReader::new(file).lines().count(),
+            Err(_) => 0,
+        };
+        Ok(RingFile {
+            path: config.path.clone(),
+            half: (config.max_messages / 2).max(1),
+            written,
+            file: OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&config.path)?,
+        })
+    }
+
+    fn append(&mut self, line: &str) -> std::io::Result<()> {
+        if self.written >= self.half {
+            std::fs::rename(&self.path, previous_segment(&self.path))?;
+            self.file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+            self.written = 0;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.written += 1;
+        Ok(())
+    }
+}
+
+/// Reads a recording, older half first. Lines that do not parse (the
+/// last one, cut short by a crash) are skipped.
+///
+/// ## Errors
+/// `SyncError::InvalidConfig` when there is no recording at `path`, or
+/// the I/O error if a half cannot be read.
+pub fn load_recording(path: &Path) -> SyncResult<Vec<RecordedMessage>> {
+    let previous = previous_segment(path);
+    if !path.exists() && !previous.exists() {
+        return Err(SyncError::InvalidConfig(format!(
+            "No recording at {}",
+            path.display()
+        )));
+    }
+
+    let mut records = Vec::new();
+    let mut skipped = 0;
+    for segment in [previous.as_path(), path] {
+        let Ok(file) = File::open(segment) else {
+            continue;
+        };
+        for line in BufReader::new(file).lines() {
+            match serde_json::from_str::<RecordedMessage>(&line?) {
+                Ok(record) => records.push(record),
+                Err(_) => skipped += 1,
+            }
+        }
+    }
+    if skipped > 0 {
+        warn!(skipped, "Unreadable lines skipped in recording");
+    }
+    Ok(records)
+}
+
+// =============================================================================
+// Replay
+// =============================================================================
+
+/// What a replay sent.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplayReport {
+    /// Inbound messages fed to the hub.
+    pub replayed: usize,
+    /// Inbound messages not fed: handshakes, and messages this build no
+    /// longer reads.
+    pub skipped: usize,
+}
+
+/// A throwaway hub for replaying recordings: the hub server on a loopback
+/// port, an immediate aggregator and a delta processor over `db`.
+pub struct ReplayHub {
+    hub: HubHandle,
+    election: ElectionHandle,
+    aggregator: AggregatorHandle,
+    delta_tx: mpsc::Sender<(String, SyncMessage)>,
+}
+
+impl ReplayHub {
+    /// Starts the hub. `db` should be a copy, never a store's live
+    /// database: replayed sales and stock changes are written to it.
+    pub async fn start(db: Arc<Database>) -> SyncResult<Self> {
+        let config = Arc::new(SyncConfig::default());
+        let election = ElectionService::new(config.clone(), ElectionConfig::default()).start();
+
+        let hub_config = HubConfig {
+            port: 0,
+            bind_addr: "127.0.0.1".to_string(),
+            ..HubConfig::default()
+        };
+        let (delta_tx, delta_rx) = mpsc::channel(256);
+        let hub = HubServer::new(hub_config, config, election.clone(), delta_tx.clone())
+            .start()
+            .await?;
+
+        let aggregator =
+            InventoryAggregator::new(AggregatorConfig::immediate(), hub.clone()).start();
+        let processor = DeltaProcessor::new(aggregator.clone(), hub.clone()).with_database(db);
+        tokio::spawn(processor.start(delta_rx));
+
+        Ok(ReplayHub {
+            hub,
+            election,
+            aggregator,
+            delta_tx,
+        })
+    }
+
+    /// Everything the hub broadcasts while replaying.
+    pub fn subscribe(&self) -> broadcast::Receiver<SyncMessage> {
+        self.hub.subscribe()
+    }
+
+    /// Feeds the inbound messages of `recording` to the hub in order, as
+    /// if each came from its recorded terminal. `speed` scales the
+    /// recorded gaps (2.0 = twice as fast); 0 sends them back to back.
+    pub async fn replay(
+        &self,
+        recording: &[RecordedMessage],
+        speed: f64,
+    ) -> SyncResult<ReplayReport> {
+        let mut report = ReplayReport::default();
+        let mut last_ms: Option<u64> = None;
+
+        for record in recording
+            .iter()
+            .filter(|r| r.direction == Direction::Inbound)
+        {
+            // The hub answers Hello itself; it never reaches the processor
+            let msg = match serde_json::from_value::<SyncMessage>(record.message.clone()) {
+                Ok(SyncMessage::Hello(_)) => {
+                    report.skipped += 1;
+                    continue;
+                }
+                Ok(msg) => msg,
+                Err(e) => {
+                    debug!(message_type = %record.message_type, error = %e, "Skipping unreadable message");
+                    report.skipped += 1;
+                    continue;
+                }
+            };
+
+            if let (Some(last), true) = (last_ms, speed > 0.0) {
+                let gap = record.elapsed_ms.saturating_sub(last) as f64 / speed;
+                tokio::time::sleep(Duration::from_millis(gap as u64)).await;
+            }
+            last_ms = Some(record.elapsed_ms);
+
+            self.delta_tx
+                .send((record.device_id.clone(), msg))
+                .await
+                .map_err(|_| SyncError::ChannelError("Replay hub stopped".into()))?;
+            report.replayed += 1;
+        }
+
+        info!(
+            replayed = report.replayed,
+            skipped = report.skipped,
+            "Replay finished"
+        );
+        Ok(report)
+    }
+
+    /// Stops the hub and everything started with it.
+    pub async fn shutdown(&self) {
+        let _ = self.aggregator.shutdown().await;
+        let _ = self.hub.shutdown().await;
+        let _ = self.election.shutdown().await;
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{InventoryDelta, OutboxBatch, OutboxEntry};
+    use titan_db::DbConfig;
+
+    fn temp_path() -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("titan-recording-{}", uuid::Uuid::new_v4()))
+            .join("hub.jsonl")
+    }
+
+    fn delta(quantity: i32) -> SyncMessage {
+        SyncMessage::InventoryDelta(InventoryDelta {
+            product_id: "p-1".to_string(),
+            sku: "COKE-330".to_string(),
+            delta_quantity: quantity,
+            timestamp: "2026-10-16T09:00:00Z".to_string(),
+        })
+    }
+
+    #[test]
+    fn test_customer_fields_are_redacted() {
+        let payload = serde_json::json!({
+            "id": "q-1",
+            "customer_name": "Jane Doe",
+            "customer_email": null,
+            "total_cents": 1200
+        });
+        let batch = SyncMessage::OutboxBatch(OutboxBatch {
+            device_id: "pos-2".to_string(),
+            entities: vec![OutboxEntry {
+                id: "o-1".to_string(),
+                entity_type: "QUOTE".to_string(),
+                entity_id: "q-1".to_string(),
+                payload: payload.to_string(),
+                schema_version: 1,
+                created_at: "2026-10-16T09:00:00Z".to_string(),
+                correlation_id: None,
+                trace_context: None,
+            }],
+            batch_seq: 7,
+        });
+
+        let recorded = redact(&batch);
+        let text = recorded["payload"]["entities"][0]["payload"]
+            .as_str()
+            .unwrap();
+        let doc: Value = serde_json::from_str(text).unwrap();
+        assert_eq!(doc["customer_name"], REDACTED);
+        assert_eq!(doc["customer_email"], Value::Null);
+        assert_eq!(doc["total_cents"], 1200);
+        assert!(!recorded.to_string().contains("Jane"));
+
+        // Still a message this build reads
+        assert!(serde_json::from_value::<SyncMessage>(recorded).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_recording_rotates_and_loads_in_order() {
+        let path = temp_path();
+        let recorder = HubRecorder::start(RecordingConfig {
+            path: path.clone(),
+            max_messages: 4,
+        });
+        for quantity in 1..=5 {
+            recorder.record(Direction::Inbound, "pos-2", &delta(-quantity), 120);
+        }
+        // Closing the queue lets the writer finish
+        drop(recorder);
+
+        let mut records = Vec::new();
+        for _ in 0..50 {
+            records = load_recording(&path).unwrap_or_default();
+            if records.len() == 3 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        // Halves of two: 3 and 4 rotated out behind 5
+        let quantities: Vec<i64> = records
+            .iter()
+            .map(|r| r.message["payload"]["deltaQuantity"].as_i64().unwrap())
+            .collect();
+        assert_eq!(quantities, [-3, -4, -5]);
+        assert_eq!(records[0].message_type, "InventoryDelta");
+        assert_eq!(records[0].size, 120);
+
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+    }
+
+    #[tokio::test]
+    async fn test_replay_feeds_a_test_hub() {
+        let db = Arc::new(Database::new(DbConfig::in_memory()).await.unwrap());
+        let hub = ReplayHub::start(db).await.unwrap();
+        let mut broadcasts = hub.subscribe();
+
+        let record = |direction, msg: &SyncMessage| RecordedMessage {
+            at: Utc::now(),
+            elapsed_ms: 0,
+            direction,
+            device_id: "pos-2".to_string(),
+            message_type: msg.type_name().to_string(),
+            size: 0,
+            message: redact(msg),
+        };
+        let recording = vec![
+            record(Direction::Inbound, &delta(-2)),
+            record(Direction::Outbound, &delta(-2)),
+            RecordedMessage {
+                message: serde_json::json!({ "type": "Retired", "payload": {} }),
+                ..record(Direction::Inbound, &delta(0))
+            },
+        ];
+
+        let report = hub.replay(&recording, 0.0).await.unwrap();
+        assert_eq!(
+            report,
+            ReplayReport {
+                replayed: 1,
+                skipped: 1
+            }
+        );
+
+        let update = tokio::time::timeout(Duration::from_secs(2), broadcasts.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        match update {
+            SyncMessage::InventoryUpdate(update) => assert_eq!(update.delta_quantity, -2),
+            other => panic!("expected InventoryUpdate, got {}", other.type_name()),
+        }
+        hub.shutdown().await;
+    }
+}
@@ -0,0 +1,206 @@
+//! # Hub Load Shedding
+//!
+//! Keeps one misbehaving terminal from taking the hub down. The hub often
+//! runs on the same low-end machine as a till, so a terminal stuck in a
+//! send loop, or a store that has outgrown its hub, must be turned away
+//! politely rather than allowed to starve everyone else.
+//!
+//! ## Limits
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Hub Load Shedding                                │
+//! │                                                                         │
+//! │  CONNECTIONS (hub.max_clients, default 32)                              │
+//! │  Hello from a new device at capacity                                    │
+//! │    ──► Error { BUSY, "Hub is at capacity ..." } and close               │
+//! │  Terminal: waits max_backoff before reconnecting, not the short        │
+//! │  exponential steps, so a full hub is not hammered with retries         │
+//! │  (a device reconnecting to replace its own session is let in)          │
+//! │                                                                         │
+//! │  MESSAGES (hub.client_rate_limit/s, burst hub.client_burst)             │
+//! │  Per-connection token bucket; a message over the limit is dropped      │
+//! │    ──► Error { BUSY, "Too many messages ..." } (at most once a second) │
+//! │  Terminal: outbox holds uploads for BUSY_COOLDOWN and shrinks its      │
+//! │  batch target; the dropped batch is resent after the cooldown          │
+//! │  Pings and pongs are never limited.                                     │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use std::time::Duration;
+
+use titan_core::ErrorCode;
+use tokio::time::Instant;
+
+use crate::config::HubSettings;
+use crate::protocol::SyncMessage;
+use crate::throttle::TokenBucket;
+
+/// How long a terminal holds its uploads after a BUSY answer.
+pub const BUSY_COOLDOWN: Duration = Duration::from_secs(10);
+
+/// Least time between two BUSY answers on one connection.
+const BUSY_NOTICE_INTERVAL: Duration = Duration::from_secs(1);
+
+// =============================================================================
+// Limits
+// =============================================================================
+
+/// Connection and message limits for a hub.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientLimits {
+    /// Most terminals connected at once.
+    pub max_clients: usize,
+    /// Sustained messages per second per terminal.
+    pub rate_per_sec: u32,
+    /// Messages per terminal allowed in a burst.
+    pub burst: u32,
+}
+
+impl Default for ClientLimits {
+    fn default() -> Self {
+        Self::from(&HubSettings::default())
+    }
+}
+
+impl From<&HubSettings> for ClientLimits {
+    fn from(settings: &HubSettings) -> Self {
+        ClientLimits {
+            max_clients: settings.max_clients,
+            rate_per_sec: settings.client_rate_limit,
+            burst: settings.client_burst,
+        }
+    }
+}
+
+// =============================================================================
+// Message Limiter
+// =============================================================================
+
+/// What to do with a message from a client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admission {
+    /// Within the limit: process it.
+    Accept,
+    /// Over the limit: drop it, and tell the client if `notify`.
+    Drop { notify: bool },
+}
+
+/// Per-connection message rate limit.
+#[derive(Debug)]
+pub struct MessageLimiter {
+    bucket: TokenBucket,
+    dropped: u64,
+    last_notice: Option<Instant>,
+}
+
+impl MessageLimiter {
+    /// Creates a limiter with a full burst allowance.
+    pub fn new(limits: &ClientLimits) -> Self {
+        MessageLimiter {
+            bucket: TokenBucket::new(u64::from(limits.rate_per_sec), u64::from(limits.burst)),
+            dropped: 0,
+            last_notice: None,
+        }
+    }
+
+    /// Decides whether a message received at `now` is processed.
+    pub fn admit(&mut self, now: Instant) -> Admission {
+        if self.bucket.try_take(1, now).is_ok() {
+            return Admission::Accept;
+        }
+
+        self.dropped += 1;
+        let notify = self
+            .last_notice
+            .is_none_or(|last| now.duration_since(last) >= BUSY_NOTICE_INTERVAL);
+        if notify {
+            self.last_notice = Some(now);
+        }
+        Admission::Drop { notify }
+    }
+
+    /// Messages dropped on this connection so far.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+}
+
+// =============================================================================
+// Busy Messages
+// =============================================================================
+
+/// Rejection sent to a device when the hub is full.
+pub fn at_capacity(max_clients: usize) -> SyncMessage {
+    SyncMessage::error(
+        ErrorCode::Busy,
+        &format!(
+            "Hub is at capacity ({} terminals connected); try again later",
+            max_clients
+        ),
+    )
+}
+
+/// Notice sent when a client's messages are being dropped.
+pub fn rate_limited() -> SyncMessage {
+    SyncMessage::error(ErrorCode::Busy, "Too many messages; slow down")
+}
+
+/// Whether `msg` is the hub shedding load.
+pub fn is_busy(msg: &SyncMessage) -> bool {
+    msg.error_code() == Some(ErrorCode::Busy)
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limiter_drops_floods_and_recovers() {
+        let limits = ClientLimits {
+            max_clients: 4,
+            rate_per_sec: 10,
+            burst: 20,
+        };
+        let mut limiter = MessageLimiter::new(&limits);
+        let start = Instant::now();
+
+        let accepted = (0..100)
+            .filter(|_| limiter.admit(start) == Admission::Accept)
+            .count();
+        assert!((20..=21).contains(&accepted), "accepted {}", accepted);
+        assert_eq!(limiter.dropped(), 100 - accepted as u64);
+
+        // Refilled after a while
+        assert_eq!(
+            limiter.admit(start + Duration::from_secs(2)),
+            Admission::Accept
+        );
+    }
+
+    #[test]
+    fn test_limiter_notifies_once_per_interval() {
+        let limits = ClientLimits {
+            max_clients: 4,
+            rate_per_sec: 1,
+            burst: 1,
+        };
+        let mut limiter = MessageLimiter::new(&limits);
+        let start = Instant::now();
+        while limiter.admit(start) == Admission::Accept {}
+
+        let later = start + Duration::from_millis(10);
+        assert_eq!(limiter.admit(later), Admission::Drop { notify: false });
+    }
+
+    #[test]
+    fn test_busy_messages() {
+        assert!(is_busy(&at_capacity(32)));
+        assert!(is_busy(&rate_limited()));
+        assert!(!is_busy(&SyncMessage::error(ErrorCode::AuthFailed, "no")));
+        assert_eq!(ClientLimits::default().max_clients, 32);
+    }
+}
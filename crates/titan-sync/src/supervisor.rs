@@ -0,0 +1,261 @@
+//! # Task Supervisor
+//!
+//! Watchdog for the sync agent's background tasks. Each component (the
+//! transport, outbox processor, inbound handler, message router, connection
+//! tracker and cloud fallback) runs in its own task, and a panic in one of
+//! them used to leave the agent half-working: sales queued but never
+//! uploaded, or hub updates never applied, with nothing on screen. The
+//! supervisor watches each task's join handle and starts the component
+//! again after a backoff, telling the frontend what was restarted.
+//!
+//! ## Restarts
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Supervised Task                                 │
+//! │                                                                         │
+//! │  spawn(component, start) ──► tokio::spawn(start()) ──► join handle     │
+//! │                                                                         │
+//! │  finished (shutdown, transport gave up) ──► done, not restarted        │
+//! │  panicked ──► wait backoff (1s, 2s, 4s … max 60s)                      │
+//! │               ──► start() again                                         │
+//! │               ──► "sync://error" INTERNAL                               │
+//! │                   "outbox processor stopped unexpectedly (…);           │
+//! │                    restarted (attempt 2)"                               │
+//! │                                                                         │
+//! │  stop() before shutdown ──► no further restarts                        │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! ## Rules
+//! - Only a panic counts as a failure; a component that returns has stopped
+//!   on purpose and stays stopped
+//! - Components keep their channels across restarts (they are shared behind
+//!   a mutex), so handles held elsewhere keep working
+//! - The backoff starts over once a component has stayed up for
+//!   `healthy_after`
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tokio::task::{JoinError, JoinHandle};
+use tokio::time::Instant;
+use tracing::{error, info};
+
+use titan_core::ErrorCode;
+
+use crate::agent::SyncEventEmitter;
+
+/// How quickly failed components are restarted.
+#[derive(Debug, Clone)]
+pub struct RestartPolicy {
+    /// Wait before the first restart.
+    pub initial_backoff: Duration,
+
+    /// Longest wait between restarts.
+    pub max_backoff: Duration,
+
+    /// Uptime after which a component counts as healthy again and the
+    /// backoff starts over.
+    pub healthy_after: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        RestartPolicy {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            healthy_after: Duration::from_secs(300),
+        }
+    }
+}
+
+/// Restarts the agent's background tasks when they die. Cloning shares it.
+#[derive(Clone)]
+pub struct Supervisor {
+    policy: RestartPolicy,
+    emitter: Arc<dyn SyncEventEmitter>,
+    stopped: Arc<watch::Sender<bool>>,
+    restarts: Arc<AtomicU64>,
+}
+
+impl Supervisor {
+    /// Creates a supervisor reporting restarts to `emitter`.
+    pub fn new(policy: RestartPolicy, emitter: Arc<dyn SyncEventEmitter>) -> Self {
+        let (stopped, _) = watch::channel(false);
+        Supervisor {
+            policy,
+            emitter,
+            stopped: Arc::new(stopped),
+            restarts: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Runs `start()` in a task, and again after a backoff whenever that
+    /// task panics.
+    ///
+    /// The returned handle finishes once the component stops for good.
+    pub fn spawn<F, Fut>(&self, component: &'static str, mut start: F) -> JoinHandle<()>
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let policy = self.policy.clone();
+        let emitter = self.emitter.clone();
+        let restarts = self.restarts.clone();
+        let mut stopped = self.stopped.subscribe();
+
+        tokio::spawn(async move {
+            let mut backoff = policy.initial_backoff;
+            let mut attempt = 0u32;
+
+            loop {
+                let started = Instant::now();
+                let reason = match tokio::spawn(start()).await {
+                    Ok(()) => break,
+                    Err(e) if e.is_cancelled() => break,
+                    Err(e) => panic_message(e),
+                };
+                if *stopped.borrow() {
+                    break;
+                }
+
+                if started.elapsed() >= policy.healthy_after {
+                    backoff = policy.initial_backoff;
+                    attempt = 0;
+                }
+                attempt += 1;
+                error!(component, %reason, attempt, ?backoff, "Sync task died, restarting");
+
+                tokio::select! {
+                    _ = tokio::time::sleep(backoff) => {}
+                    _ = stopped.wait_for(|stopped| *stopped) => break,
+                }
+                backoff = (backoff * 2).min(policy.max_backoff);
+
+                restarts.fetch_add(1, Ordering::Relaxed);
+                emitter.emit_error(
+                    ErrorCode::Internal,
+                    &format!(
+                        "{} stopped unexpectedly ({}); restarted (attempt {})",
+                        component, reason, attempt
+                    ),
+                );
+            }
+
+            info!(component, "Supervised task finished");
+        })
+    }
+
+    /// Stops restarting components. Called before the agent shuts them down.
+    pub fn stop(&self) {
+        self.stopped.send_replace(true);
+    }
+
+    /// Restarts so far, across all components.
+    pub fn restarts(&self) -> u64 {
+        self.restarts.load(Ordering::Relaxed)
+    }
+}
+
+/// The panic payload of a failed task, when it is a message.
+fn panic_message(error: JoinError) -> String {
+    let payload = match error.try_into_panic() {
+        Ok(payload) => payload,
+        Err(error) => return error.to_string(),
+    };
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "panicked".to_string()
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::SyncStatus;
+    use std::sync::atomic::AtomicU32;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingEmitter {
+        errors: Mutex<Vec<(ErrorCode, String)>>,
+    }
+
+    impl SyncEventEmitter for RecordingEmitter {
+        fn emit_status(&self, _status: &SyncStatus) {}
+        fn emit_progress(&self, _pending: i64, _synced: i64) {}
+        fn emit_error(&self, code: ErrorCode, message: &str) {
+            self.errors
+                .lock()
+                .unwrap()
+                .push((code, message.to_string()));
+        }
+    }
+
+    fn supervisor(emitter: Arc<RecordingEmitter>) -> Supervisor {
+        let policy = RestartPolicy {
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(40),
+            healthy_after: Duration::from_secs(60),
+        };
+        Supervisor::new(policy, emitter)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_panicked_task_is_restarted_and_reported() {
+        let emitter = Arc::new(RecordingEmitter::default());
+        let supervisor = supervisor(emitter.clone());
+        let runs = Arc::new(AtomicU32::new(0));
+
+        let counter = runs.clone();
+        let task = supervisor.spawn("outbox processor", move || {
+            let counter = counter.clone();
+            async move {
+                // Dies twice, then finishes cleanly
+                if counter.fetch_add(1, Ordering::SeqCst) < 2 {
+                    panic!("ack channel closed");
+                }
+            }
+        });
+        task.await.unwrap();
+
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        assert_eq!(supervisor.restarts(), 2);
+        let errors = emitter.errors.lock().unwrap();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].0, ErrorCode::Internal);
+        assert_eq!(
+            errors[1].1,
+            "outbox processor stopped unexpectedly (ack channel closed); restarted (attempt 2)"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_clean_exit_and_stop_are_not_restarted() {
+        let emitter = Arc::new(RecordingEmitter::default());
+        let supervisor = supervisor(emitter.clone());
+
+        // Returning is a deliberate stop
+        supervisor.spawn("transport", || async {}).await.unwrap();
+
+        // A panic after stop() is left alone
+        supervisor.stop();
+        supervisor
+            .spawn("inbound handler", || async { panic!("late failure") })
+            .await
+            .unwrap();
+
+        assert_eq!(supervisor.restarts(), 0);
+        assert!(emitter.errors.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,173 @@
+//! # Distributed Tracing
+//!
+//! Exports spans over OTLP and carries their W3C trace context
+//! (`traceparent`) from device to device, so one sale's journey from the
+//! terminal through the hub to the cloud shows up as a single trace.
+//!
+//! ## Propagation
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                     Trace Context Propagation                           │
+//! │                                                                         │
+//! │  terminal: span "command" (finalize_sale, desktop middleware)           │
+//! │    sync_outbox.trace_context = traceparent                              │
+//! │       │                                                                 │
+//! │       ▼  OutboxEntry.traceContext                                       │
+//! │  hub: span "hub_entry" (child) ──► hub_upload_queue.trace_context       │
+//! │       │                                                                 │
+//! │       ▼  span "hub_upload" (linked to each entry's trace)               │
+//! │  cloud: "traceparent" gRPC header ──► span "upload_batch" (child)       │
+//! │         SyncEntity.trace_context  ──► span "process_entity" (child)     │
+//! │                                                                         │
+//! │  A terminal uploading through the cloud fallback sends the same         │
+//! │  SyncEntity.trace_context, so the trace reads terminal ──► cloud.       │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! Export is off unless `OTEL_EXPORTER_OTLP_ENDPOINT` names an OTLP/gRPC
+//! collector (e.g. `http://localhost:4317`). When it is off spans have no
+//! trace context, so nothing is stamped on outbox rows or sent on.
+
+use std::collections::HashMap;
+
+use opentelemetry::propagation::TextMapPropagator;
+use opentelemetry::trace::{TraceContextExt, TraceError, TracerProvider as _};
+use opentelemetry::{Context, KeyValue};
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{Tracer, TracerProvider};
+use opentelemetry_sdk::{runtime, Resource};
+use tracing::{warn, Span, Subscriber};
+use tracing_opentelemetry::{OpenTelemetryLayer, OpenTelemetrySpanExt};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Environment variable naming the OTLP/gRPC collector.
+pub const OTLP_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+/// gRPC metadata key carrying the trace context to the cloud.
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+// =============================================================================
+// Export
+// =============================================================================
+
+/// Layer exporting a subscriber's spans over OTLP.
+pub type OtlpLayer<S> = OpenTelemetryLayer<S, Tracer>;
+
+/// Keeps span export running. Dropping it flushes the spans still
+/// buffered, so hold it until the process exits.
+pub struct Telemetry {
+    provider: TracerProvider,
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            warn!(error = %e, "Could not flush spans on shutdown");
+        }
+    }
+}
+
+/// The layer exporting spans to the OTLP collector as `service_name`, or
+/// `None` when `OTEL_EXPORTER_OTLP_ENDPOINT` is not set.
+///
+/// Must be called inside a tokio runtime: the exporter runs on it.
+pub fn otlp_layer<S>(
+    service_name: &'static str,
+) -> Result<Option<(OtlpLayer<S>, Telemetry)>, TraceError>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let endpoint = match std::env::var(OTLP_ENDPOINT_ENV) {
+        Ok(endpoint) if !endpoint.is_empty() => endpoint,
+        _ => return Ok(None),
+    };
+
+    let exporter = SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint)
+        .build()?;
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_resource(Resource::new([KeyValue::new("service.name", service_name)]))
+        .build();
+    let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer(service_name));
+
+    Ok(Some((layer, Telemetry { provider })))
+}
+
+// =============================================================================
+// Trace Context
+// =============================================================================
+
+/// The trace context of `span` as a W3C `traceparent`, or `None` when the
+/// span is not exported.
+pub fn trace_context(span: &Span) -> Option<String> {
+    to_traceparent(&span.context())
+}
+
+/// The trace context of the current span (see [`trace_context`]).
+pub fn current_trace_context() -> Option<String> {
+    trace_context(&Span::current())
+}
+
+/// Makes `span` a child of the span `traceparent` came from. A missing or
+/// malformed `traceparent` leaves `span` as it is.
+///
+/// Call it before the span is first entered.
+pub fn set_parent(span: &Span, traceparent: Option<&str>) {
+    if let Some(cx) = traceparent.and_then(from_traceparent) {
+        span.set_parent(cx);
+    }
+}
+
+/// Links `span` to the span `traceparent` came from, for work done for
+/// several traces at once (a batch upload).
+pub fn add_link(span: &Span, traceparent: Option<&str>) {
+    if let Some(cx) = traceparent.and_then(from_traceparent) {
+        span.add_link(cx.span().span_context().clone());
+    }
+}
+
+fn to_traceparent(cx: &Context) -> Option<String> {
+    let mut carrier = HashMap::new();
+    TraceContextPropagator::new().inject_context(cx, &mut carrier);
+    carrier.remove(TRACEPARENT_HEADER)
+}
+
+fn from_traceparent(traceparent: &str) -> Option<Context> {
+    let carrier = HashMap::from([(TRACEPARENT_HEADER.to_string(), traceparent.to_string())]);
+    let cx = TraceContextPropagator::new().extract(&carrier);
+    cx.span().span_context().is_valid().then_some(cx)
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn test_traceparent_round_trip() {
+        let cx = from_traceparent(TRACEPARENT).unwrap();
+        assert_eq!(to_traceparent(&cx).as_deref(), Some(TRACEPARENT));
+
+        assert!(from_traceparent("not a traceparent").is_none());
+        // All-zero trace IDs are invalid
+        assert!(
+            from_traceparent("00-00000000000000000000000000000000-00f067aa0ba902b7-01").is_none()
+        );
+    }
+
+    #[test]
+    fn test_no_trace_context_without_export() {
+        let span = tracing::info_span!("command");
+        set_parent(&span, Some(TRACEPARENT));
+        assert_eq!(trace_context(&span), None);
+        assert_eq!(current_trace_context(), None);
+    }
+}
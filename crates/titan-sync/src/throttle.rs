@@ -0,0 +1,400 @@
+//! # Upload Throttling
+//!
+//! Bandwidth caps and time windows for uploads, so stores on metered
+//! links (LTE backup) do not burn through their data plan.
+//!
+//! ## Throttling Model
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                        Upload Throttling                                │
+//! │                                                                         │
+//! │  SyncSettings ──► BandwidthPolicy (shared, metered flag toggles live)  │
+//! │                        │                                                │
+//! │                        ├─► limit_kbps()                                 │
+//! │                        │     unmetered: upload_limit_kbps (or none)     │
+//! │                        │     metered:   min(upload, metered limit)      │
+//! │                        │                                                │
+//! │                        └─► allows_upload_at(local time)                 │
+//! │                              unmetered or no windows: always            │
+//! │                              metered: only inside metered_windows       │
+//! │                                                                         │
+//! │  UploadThrottle (one per uploader)                                     │
+//! │  ─────────────────────────────────                                      │
+//! │  TokenBucket refilled at the current limit, ~2s of burst.              │
+//! │  A batch may overdraw the bucket; the next one waits until it is       │
+//! │  back in credit, so the average rate holds for any batch size.         │
+//! │                                                                         │
+//! │  OutboxProcessor: try_acquire() and skip the poll tick if throttled    │
+//! │  CloudUplink:     acquire() and sleep until the batch may go           │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::NaiveTime;
+use tokio::time::Instant;
+
+use crate::config::SyncSettings;
+use crate::error::{SyncError, SyncResult};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// Seconds of traffic a full bucket allows in one burst.
+const BURST_SECS: f64 = 2.0;
+
+// =============================================================================
+// Token Bucket
+// =============================================================================
+
+/// Byte-rate token bucket.
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    /// Refill rate (bytes per second).
+    rate: f64,
+
+    /// Bucket capacity (bytes).
+    burst: f64,
+
+    /// Available bytes; negative while overdrawn.
+    tokens: f64,
+
+    /// When `tokens` was last refilled.
+    updated: Instant,
+}
+
+impl TokenBucket {
+    /// Creates a full bucket.
+    pub fn new(rate_bytes_per_sec: u64, burst_bytes: u64) -> Self {
+        TokenBucket {
+            rate: rate_bytes_per_sec.max(1) as f64,
+            burst: burst_bytes.max(1) as f64,
+            tokens: burst_bytes.max(1) as f64,
+            updated: Instant::now(),
+        }
+    }
+
+    /// Creates a bucket for a limit in kilobits per second.
+    pub fn from_kbps(kbps: u32) -> Self {
+        let rate = u64::from(kbps) * 1000 / 8;
+        Self::new(rate, (rate as f64 * BURST_SECS) as u64)
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+        self.updated = now;
+    }
+
+    /// Takes `bytes` if the bucket is in credit, otherwise returns how long
+    /// until it will be.
+    pub fn try_take(&mut self, bytes: usize, now: Instant) -> Result<(), Duration> {
+        self.refill(now);
+        if self.tokens < 0.0 {
+            return Err(Duration::from_secs_f64(-self.tokens / self.rate));
+        }
+        self.tokens -= bytes as f64;
+        Ok(())
+    }
+
+    /// Takes `bytes`, sleeping first if the bucket is overdrawn.
+    pub async fn take(&mut self, bytes: usize) {
+        while let Err(wait) = self.try_take(bytes, Instant::now()) {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+// =============================================================================
+// Sync Windows
+// =============================================================================
+
+/// Daily window of local time, e.g. `22:00-06:00` (may wrap past midnight).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncWindow {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl SyncWindow {
+    /// Whether `time` falls inside the window (start inclusive, end
+    /// exclusive). A window whose start equals its end covers the whole day.
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start == self.end || (self.start <= time && time < self.end)
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+impl std::str::FromStr for SyncWindow {
+    type Err = SyncError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            SyncError::InvalidConfig(format!("Invalid sync window '{}', expected HH:MM-HH:MM", s))
+        };
+        let (start, end) = s.split_once('-').ok_or_else(invalid)?;
+        let parse = |t: &str| NaiveTime::parse_from_str(t.trim(), "%H:%M").map_err(|_| invalid());
+
+        Ok(SyncWindow {
+            start: parse(start)?,
+            end: parse(end)?,
+        })
+    }
+}
+
+// =============================================================================
+// Bandwidth Policy
+// =============================================================================
+
+/// Upload limits from [`SyncSettings`], with a metered flag that can be
+/// flipped at runtime. Cloning shares the flag.
+#[derive(Debug, Clone)]
+pub struct BandwidthPolicy {
+    metered: Arc<AtomicBool>,
+    upload_limit_kbps: Option<u32>,
+    metered_limit_kbps: u32,
+    metered_windows: Arc<[SyncWindow]>,
+}
+
+impl BandwidthPolicy {
+    /// Builds the policy from sync settings.
+    ///
+    /// ## Errors
+    /// - `SyncError::InvalidConfig` if a metered window does not parse
+    pub fn from_settings(settings: &SyncSettings) -> SyncResult<Self> {
+        let metered_windows = settings
+            .metered_windows
+            .iter()
+            .map(|w| w.parse())
+            .collect::<SyncResult<Vec<SyncWindow>>>()?;
+
+        Ok(BandwidthPolicy {
+            metered: Arc::new(AtomicBool::new(settings.metered)),
+            upload_limit_kbps: settings.upload_limit_kbps,
+            metered_limit_kbps: settings.metered_limit_kbps,
+            metered_windows: metered_windows.into(),
+        })
+    }
+
+    /// Whether metered mode is on.
+    pub fn is_metered(&self) -> bool {
+        self.metered.load(Ordering::Relaxed)
+    }
+
+    /// Turns metered mode on or off for every holder of this policy.
+    pub fn set_metered(&self, metered: bool) {
+        self.metered.store(metered, Ordering::Relaxed);
+    }
+
+    /// Current upload limit in kilobits per second (`None` = unlimited).
+    pub fn limit_kbps(&self) -> Option<u32> {
+        if self.is_metered() {
+            Some(
+                self.upload_limit_kbps
+                    .map_or(self.metered_limit_kbps, |limit| {
+                        limit.min(self.metered_limit_kbps)
+                    }),
+            )
+        } else {
+            self.upload_limit_kbps
+        }
+    }
+
+    /// Whether uploads may run at local time `time`.
+    pub fn allows_upload_at(&self, time: NaiveTime) -> bool {
+        !self.is_metered()
+            || self.metered_windows.is_empty()
+            || self.metered_windows.iter().any(|w| w.contains(time))
+    }
+
+    /// Whether uploads may run now (local time).
+    pub fn allows_upload_now(&self) -> bool {
+        self.allows_upload_at(chrono::Local::now().time())
+    }
+}
+
+impl Default for BandwidthPolicy {
+    fn default() -> Self {
+        Self::from_settings(&SyncSettings::default()).expect("default settings have no windows")
+    }
+}
+
+// =============================================================================
+// Upload Throttle
+// =============================================================================
+
+/// Token bucket that follows the policy's current limit.
+#[derive(Debug)]
+pub struct UploadThrottle {
+    policy: BandwidthPolicy,
+    bucket: Option<(u32, TokenBucket)>,
+}
+
+impl UploadThrottle {
+    /// Creates a throttle for one uploader.
+    pub fn new(policy: BandwidthPolicy) -> Self {
+        UploadThrottle {
+            policy,
+            bucket: None,
+        }
+    }
+
+    /// The policy this throttle follows.
+    pub fn policy(&self) -> &BandwidthPolicy {
+        &self.policy
+    }
+
+    /// Bucket for the current limit, rebuilt when the limit changes.
+    fn bucket(&mut self) -> Option<&mut TokenBucket> {
+        let Some(kbps) = self.policy.limit_kbps() else {
+            self.bucket = None;
+            return None;
+        };
+        if !matches!(self.bucket, Some((current, _)) if current == kbps) {
+            self.bucket = Some((kbps, TokenBucket::from_kbps(kbps)));
+        }
+        self.bucket.as_mut().map(|(_, bucket)| bucket)
+    }
+
+    /// Admits a `bytes`-sized upload, or returns how long until one can go.
+    pub fn try_acquire(&mut self, bytes: usize, now: Instant) -> Result<(), Duration> {
+        match self.bucket() {
+            Some(bucket) => bucket.try_take(bytes, now),
+            None => Ok(()),
+        }
+    }
+
+    /// Waits until a `bytes`-sized upload may go.
+    pub async fn acquire(&mut self, bytes: usize) {
+        if let Some(bucket) = self.bucket() {
+            bucket.take(bytes).await;
+        }
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    fn metered_settings(windows: &[&str]) -> SyncSettings {
+        SyncSettings {
+            metered: true,
+            upload_limit_kbps: Some(1_000),
+            metered_limit_kbps: 80,
+            metered_windows: windows.iter().map(|w| w.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_bucket_overdraws_then_waits() {
+        let start = Instant::now();
+        // 1000 bytes/s, 2000 byte burst
+        let mut bucket = TokenBucket::from_kbps(8);
+
+        assert!(bucket.try_take(5_000, start).is_ok());
+        let wait = bucket.try_take(100, start).unwrap_err();
+        assert_eq!(wait, Duration::from_secs(3));
+
+        assert!(bucket
+            .try_take(100, start + Duration::from_secs(2))
+            .is_err());
+        assert!(bucket.try_take(100, start + Duration::from_secs(3)).is_ok());
+    }
+
+    #[test]
+    fn test_bucket_refill_is_capped() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(1_000, 2_000);
+
+        bucket.try_take(2_000, start).unwrap();
+        // An hour idle still only refills to the burst size
+        let later = start + Duration::from_secs(3_600);
+        bucket.try_take(2_500, later).unwrap();
+        assert!(bucket.try_take(1, later).is_err());
+    }
+
+    #[test]
+    fn test_window_parsing_and_wrap() {
+        let night: SyncWindow = "22:00-06:00".parse().unwrap();
+        assert!(night.contains(time(23, 30)));
+        assert!(night.contains(time(5, 59)));
+        assert!(!night.contains(time(6, 0)));
+        assert!(!night.contains(time(12, 0)));
+
+        let lunch: SyncWindow = "12:00 - 13:30".parse().unwrap();
+        assert!(lunch.contains(time(12, 0)));
+        assert!(!lunch.contains(time(13, 30)));
+
+        let all_day: SyncWindow = "00:00-00:00".parse().unwrap();
+        assert!(all_day.contains(time(17, 0)));
+
+        assert!("22:00".parse::<SyncWindow>().is_err());
+        assert!("25:00-06:00".parse::<SyncWindow>().is_err());
+    }
+
+    #[test]
+    fn test_policy_limits() {
+        let policy = BandwidthPolicy::from_settings(&metered_settings(&["22:00-06:00"])).unwrap();
+        assert_eq!(policy.limit_kbps(), Some(80));
+        assert!(!policy.allows_upload_at(time(12, 0)));
+        assert!(policy.allows_upload_at(time(23, 0)));
+
+        // Toggling is seen by every clone
+        let shared = policy.clone();
+        shared.set_metered(false);
+        assert_eq!(policy.limit_kbps(), Some(1_000));
+        assert!(policy.allows_upload_at(time(12, 0)));
+
+        let unlimited = BandwidthPolicy::default();
+        assert_eq!(unlimited.limit_kbps(), None);
+        unlimited.set_metered(true);
+        assert_eq!(
+            unlimited.limit_kbps(),
+            Some(SyncSettings::default().metered_limit_kbps)
+        );
+    }
+
+    #[test]
+    fn test_throttle_follows_metered_toggle() {
+        let policy = BandwidthPolicy::default();
+        let mut throttle = UploadThrottle::new(policy.clone());
+        let now = Instant::now();
+
+        // Unlimited: anything goes
+        assert!(throttle.try_acquire(10_000_000, now).is_ok());
+        assert!(throttle.try_acquire(10_000_000, now).is_ok());
+
+        policy.set_metered(true);
+        assert!(throttle.try_acquire(10_000_000, now).is_ok());
+        assert!(throttle.try_acquire(1, now).is_err());
+
+        policy.set_metered(false);
+        assert!(throttle.try_acquire(1, now).is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_acquire_sleeps_until_in_credit() {
+        let mut throttle =
+            UploadThrottle::new(BandwidthPolicy::from_settings(&metered_settings(&[])).unwrap());
+        // 80 kbps = 10_000 bytes/s
+        let start = Instant::now();
+        throttle.acquire(30_000).await;
+        throttle.acquire(1).await;
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+    }
+}
@@ -2,28 +2,32 @@
 //!
 //! WebSocket client with automatic reconnection and backoff.
 //!
-//! ## Connection Lifecycle
+//! ## Connection State Machine
 //! ```text
 //! ┌─────────────────────────────────────────────────────────────────────────┐
 //! │                    WebSocket Connection States                          │
 //! │                                                                         │
-//! │  ┌────────────┐    connect()    ┌────────────┐                         │
-//! │  │Disconnected│ ──────────────► │ Connecting │                         │
-//! │  └────────────┘                 └─────┬──────┘                         │
-//! │        ▲                              │                                 │
-//! │        │                    success   │   failure                       │
-//! │        │                        ┌─────┴─────┐                          │
-//! │        │                        ▼           ▼                           │
-//! │        │              ┌────────────┐  ┌────────────┐                   │
-//! │        │              │ Connected  │  │ Backoff    │                   │
-//! │        │              └─────┬──────┘  └─────┬──────┘                   │
-//! │        │                    │               │                           │
-//! │        │              disconnect/error      │  timer expired            │
-//! │        │                    │               │                           │
-//! │        │                    ▼               │                           │
-//! │        │              ┌────────────┐        │                           │
-//! │        └───────────── │Reconnecting│ ◄──────┘                          │
-//! │                       └────────────┘                                    │
+//! │  ┌────────┐ Connect ┌────────────┐ Opened ┌─────────────┐               │
+//! │  │  Idle  │ ──────► │ Connecting │ ─────► │ Handshaking │               │
+//! │  └────────┘         └─────┬──────┘        └──────┬──────┘               │
+//! │      ▲                    │                      │ HandshakeComplete    │
+//! │      │                    │ Lost                 ▼    (Welcome)         │
+//! │      │                    │               ┌────────────┐                │
+//! │      │                    │     ┌──────── │   Ready    │                │
+//! │      │                    ▼     ▼  Lost   └─────┬──────┘                │
+//! │      │              ┌────────────┐              │ Shutdown              │
+//! │      │              │  Backoff   │              ▼                       │
+//! │      │              └─────┬──────┘        ┌────────────┐                │
+//! │      │                    │ Connect       │  Draining  │  flush queue,  │
+//! │      │                    └──► Connecting └─────┬──────┘  send Close    │
+//! │      │                                          │ Drained               │
+//! │      └──────────────────────────────────────────┘                       │
+//! │                                                                         │
+//! │  Shutdown while Connecting/Backoff and Lost with retries exhausted     │
+//! │  go straight to Idle. Idle is terminal for a spawned transport.        │
+//! │  So is UPGRADE_REQUIRED during the handshake: no retry can fix a       │
+//! │  protocol version gap. BUSY during the handshake (hub full, see        │
+//! │  `shedding`) waits the full max_backoff before the next attempt.       │
 //! │                                                                         │
 //! │  BACKOFF STRATEGY (Exponential with Jitter)                            │
 //! │  ───────────────────────────────────────────                           │
@@ -32,9 +36,26 @@
 //! │  Attempt 3: 2s                                                          │
 //! │  ...                                                                    │
 //! │  Max: 60s                                                               │
+//! │  Reset once a connection reaches Ready.                                 │
 //! │                                                                         │
 //! └─────────────────────────────────────────────────────────────────────────┘
 //! ```
+//!
+//! Every transition is published as a [`TransportEvent`] on a broadcast
+//! channel (see [`TransportHandle::subscribe`]), so consumers can drive UI
+//! state and tests can assert the exact reconnect sequence.
+//!
+//! ## Heartbeat
+//! ```text
+//!  every ping_interval:  Ping(seq) ──────────────► hub
+//!                        Pong(seq) ◄────────────── hub   → round trip
+//!  no Pong within pong_timeout            → missed += 1
+//!  missed == max_missed_pongs             → Lost (reconnect)
+//! ```
+//!
+//! A connection that dies silently (e.g. an access point roam) is otherwise
+//! only noticed on the next failed send. Only one ping is outstanding at a
+//! time, and a late pong for an earlier ping is ignored.
 
 use backoff::backoff::Backoff;
 use backoff::ExponentialBackoff;
@@ -42,15 +63,22 @@ use futures_util::stream::SplitSink;
 use futures_util::{SinkExt, StreamExt};
 use std::sync::Arc;
 use std::time::Duration;
+use titan_core::ErrorCode;
 use tokio::net::TcpStream;
-use tokio::sync::{mpsc, Mutex, RwLock};
-use tokio::time::timeout;
+use tokio::sync::{broadcast, mpsc, watch, Mutex};
+use tokio::time::{timeout, Instant};
 use tokio_tungstenite::tungstenite::Message as WsMessage;
 use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
 use tracing::{debug, error, info, warn};
 
+use crate::compat;
 use crate::error::{SyncError, SyncResult};
+use crate::integrity::{DeviceKey, Role, Session};
 use crate::protocol::SyncMessage;
+use crate::shedding;
+
+/// Transition events kept for slow subscribers before they lag.
+const EVENT_CAPACITY: usize = 64;
 
 // =============================================================================
 // Transport State
@@ -59,30 +87,121 @@ use crate::protocol::SyncMessage;
 /// Connection state for the WebSocket transport.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConnectionState {
-    /// Not connected.
-    Disconnected,
-    /// Attempting to connect.
+    /// Not connected and not trying to.
+    Idle,
+    /// Opening the WebSocket.
     Connecting,
-    /// Connected and ready.
-    Connected,
-    /// Waiting before reconnection attempt.
+    /// WebSocket open; Hello sent, waiting for the hub's Welcome.
+    Handshaking,
+    /// Handshake complete; messages flow.
+    Ready,
+    /// Shutting down; flushing queued messages before closing.
+    Draining,
+    /// Waiting before the next connection attempt.
     Backoff,
-    /// Reconnection in progress.
-    Reconnecting,
 }
 
 impl std::fmt::Display for ConnectionState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            ConnectionState::Disconnected => write!(f, "disconnected"),
+            ConnectionState::Idle => write!(f, "idle"),
             ConnectionState::Connecting => write!(f, "connecting"),
-            ConnectionState::Connected => write!(f, "connected"),
+            ConnectionState::Handshaking => write!(f, "handshaking"),
+            ConnectionState::Ready => write!(f, "ready"),
+            ConnectionState::Draining => write!(f, "draining"),
             ConnectionState::Backoff => write!(f, "backoff"),
-            ConnectionState::Reconnecting => write!(f, "reconnecting"),
         }
     }
 }
 
+/// What moves the connection from one state to another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transition {
+    /// A connection attempt started (1 = first attempt since last Ready).
+    Connect { attempt: u32 },
+    /// The WebSocket opened.
+    Opened,
+    /// The hub answered with Welcome (or no handshake is configured).
+    HandshakeComplete,
+    /// The attempt or connection failed. `retry_in` is `None` once retries
+    /// are exhausted.
+    Lost {
+        reason: String,
+        retry_in: Option<Duration>,
+    },
+    /// Shutdown was requested.
+    Shutdown,
+    /// Queued messages were flushed and the socket closed.
+    Drained,
+}
+
+impl ConnectionState {
+    /// The state `transition` leads to, or `None` if it is not valid here.
+    pub fn next(self, transition: &Transition) -> Option<ConnectionState> {
+        use ConnectionState::*;
+
+        match (self, transition) {
+            (Idle | Backoff, Transition::Connect { .. }) => Some(Connecting),
+            (Connecting, Transition::Opened) => Some(Handshaking),
+            (Handshaking, Transition::HandshakeComplete) => Some(Ready),
+            (
+                Connecting | Handshaking | Ready,
+                Transition::Lost {
+                    retry_in: Some(_), ..
+                },
+            ) => Some(Backoff),
+            (Connecting | Handshaking | Ready, Transition::Lost { retry_in: None, .. }) => {
+                Some(Idle)
+            }
+            (Handshaking | Ready, Transition::Shutdown) => Some(Draining),
+            (Connecting | Backoff, Transition::Shutdown) => Some(Idle),
+            // The socket may die before the flush finishes
+            (Draining, Transition::Drained | Transition::Lost { .. }) => Some(Idle),
+            _ => None,
+        }
+    }
+}
+
+/// A state change published to [`TransportHandle::subscribe`] receivers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransportEvent {
+    pub from: ConnectionState,
+    pub to: ConnectionState,
+    pub transition: Transition,
+}
+
+/// Owns the current state and publishes every change.
+struct StateMachine {
+    state: watch::Sender<ConnectionState>,
+    events: broadcast::Sender<TransportEvent>,
+}
+
+impl StateMachine {
+    fn current(&self) -> ConnectionState {
+        *self.state.borrow()
+    }
+
+    /// Applies a transition and returns the resulting state. Invalid
+    /// transitions are logged and leave the state unchanged.
+    fn apply(&self, transition: Transition) -> ConnectionState {
+        let from = self.current();
+        let Some(to) = from.next(&transition) else {
+            warn!(state = %from, ?transition, "Ignoring invalid transport transition");
+            return from;
+        };
+
+        debug!(%from, %to, ?transition, "Transport state changed");
+        self.state.send_replace(to);
+        // No subscribers is fine
+        let _ = self.events.send(TransportEvent {
+            from,
+            to,
+            transition,
+        });
+        to
+    }
+}
+
 // =============================================================================
 // Transport Configuration
 // =============================================================================
@@ -102,14 +221,28 @@ pub struct TransportConfig {
     /// Maximum backoff duration.
     pub max_backoff: Duration,
 
-    /// Maximum reconnection attempts (0 = infinite).
+    /// Maximum consecutive connection attempts (0 = infinite).
     pub max_retries: u32,
 
     /// Ping interval for keepalive.
     pub ping_interval: Duration,
 
-    /// Pong timeout (disconnect if no pong received).
+    /// How long to wait for each pong.
     pub pong_timeout: Duration,
+
+    /// Consecutive missed pongs before the connection is dropped.
+    pub max_missed_pongs: u32,
+
+    /// Message sent as soon as the socket opens. The connection is Ready
+    /// once a Welcome arrives; without a hello it is Ready immediately.
+    pub hello: Option<SyncMessage>,
+
+    /// How long to wait for Welcome before dropping the connection.
+    pub handshake_timeout: Duration,
+
+    /// Key for signing messages to an enrolled hub (see `integrity`).
+    /// Messages are sent unsigned without one.
+    pub signing_key: Option<DeviceKey>,
 }
 
 impl Default for TransportConfig {
@@ -120,8 +253,12 @@ impl Default for TransportConfig {
             initial_backoff: Duration::from_millis(500),
             max_backoff: Duration::from_secs(60),
             max_retries: 0, // Infinite
-            ping_interval: Duration::from_secs(30),
-            pong_timeout: Duration::from_secs(10),
+            ping_interval: Duration::from_secs(15),
+            pong_timeout: Duration::from_secs(5),
+            max_missed_pongs: 2,
+            hello: None,
+            handshake_timeout: Duration::from_secs(10),
+            signing_key: None,
         }
     }
 }
@@ -137,7 +274,13 @@ pub struct TransportHandle {
     outgoing_tx: mpsc::Sender<SyncMessage>,
 
     /// Current connection state.
-    state: Arc<RwLock<ConnectionState>>,
+    state: watch::Receiver<ConnectionState>,
+
+    /// Last measured ping round trip on the current connection.
+    round_trip: watch::Receiver<Option<Duration>>,
+
+    /// State change events (kept to hand out subscriptions).
+    events: broadcast::Sender<TransportEvent>,
 
     /// Shutdown signal.
     shutdown_tx: mpsc::Sender<()>,
@@ -154,12 +297,25 @@ impl TransportHandle {
 
     /// Returns the current connection state.
     pub async fn state(&self) -> ConnectionState {
-        *self.state.read().await
+        *self.state.borrow()
     }
 
-    /// Returns true if currently connected.
+    /// Returns true once the handshake is complete.
     pub async fn is_connected(&self) -> bool {
-        *self.state.read().await == ConnectionState::Connected
+        *self.state.borrow() == ConnectionState::Ready
+    }
+
+    /// Last ping round trip on the current connection, if one was measured.
+    pub fn round_trip(&self) -> Option<Duration> {
+        *self.round_trip.borrow()
+    }
+
+    /// Subscribes to state change events from now on.
+    ///
+    /// A subscriber that falls more than a few dozen events behind gets
+    /// `RecvError::Lagged` and should re-read [`state`](Self::state).
+    pub fn subscribe(&self) -> broadcast::Receiver<TransportEvent> {
+        self.events.subscribe()
     }
 
     /// Triggers graceful shutdown.
@@ -171,23 +327,86 @@ impl TransportHandle {
     }
 }
 
+// =============================================================================
+// Heartbeat
+// =============================================================================
+
+/// Keepalive bookkeeping for one connection.
+#[derive(Debug, Default)]
+struct Heartbeat {
+    /// Sequence number of the last ping sent.
+    seq: u64,
+    /// Unanswered ping: sequence number and when it was sent.
+    outstanding: Option<(u64, Instant)>,
+    /// Consecutive pings that went unanswered.
+    missed: u32,
+}
+
+impl Heartbeat {
+    /// Payload for the next ping, or `None` while one is still outstanding.
+    fn ping(&mut self, now: Instant) -> Option<Vec<u8>> {
+        if self.outstanding.is_some() {
+            return None;
+        }
+        self.seq += 1;
+        self.outstanding = Some((self.seq, now));
+        Some(self.seq.to_be_bytes().to_vec())
+    }
+
+    /// When the outstanding ping times out.
+    fn deadline(&self, pong_timeout: Duration) -> Option<Instant> {
+        self.outstanding.map(|(_, sent)| sent + pong_timeout)
+    }
+
+    /// Records a pong; returns the round trip if it answers the
+    /// outstanding ping.
+    fn pong(&mut self, payload: &[u8], now: Instant) -> Option<Duration> {
+        let (seq, sent) = self.outstanding?;
+        if payload != seq.to_be_bytes() {
+            return None;
+        }
+        self.outstanding = None;
+        self.missed = 0;
+        Some(now - sent)
+    }
+
+    /// Gives up on the outstanding ping; returns the consecutive misses.
+    fn timed_out(&mut self) -> u32 {
+        self.outstanding = None;
+        self.missed += 1;
+        self.missed
+    }
+}
+
 // =============================================================================
 // WebSocket Transport
 // =============================================================================
 
+/// How a connection loop ended without an error.
+enum Closed {
+    /// Shutdown requested; the connection was drained.
+    Shutdown,
+    /// The hub closed the connection.
+    ByPeer,
+}
+
 /// WebSocket transport with automatic reconnection.
 ///
 /// ## Usage
 /// ```rust,ignore
 /// let config = TransportConfig {
 ///     url: "ws://localhost:8080/sync".into(),
+///     hello: Some(SyncMessage::hello(...)),
 ///     ..Default::default()
 /// };
 ///
-/// let (handle, incoming_rx) = Transport::spawn(config)?;
+/// // Subscribe before starting to see every transition
+/// let (transport, handle, incoming_rx) = Transport::new(config);
+/// let mut events = handle.subscribe();
+/// transport.start();
 ///
 /// // Send messages
-/// handle.send(make_hello(...)?).await?;
+/// handle.send(msg).await?;
 ///
 /// // Receive messages
 /// while let Some(msg) = incoming_rx.recv().await {
@@ -196,149 +415,217 @@ impl TransportHandle {
 /// ```
 pub struct Transport {
     config: TransportConfig,
-    state: Arc<RwLock<ConnectionState>>,
+    machine: StateMachine,
+    round_trip: watch::Sender<Option<Duration>>,
     outgoing_rx: mpsc::Receiver<SyncMessage>,
     incoming_tx: mpsc::Sender<SyncMessage>,
     shutdown_rx: mpsc::Receiver<()>,
 }
 
 impl Transport {
-    /// Creates a new transport and spawns its background task.
+    /// Creates a transport without starting it.
     ///
-    /// Returns a handle for sending messages and a receiver for incoming messages.
-    pub fn spawn(config: TransportConfig) -> (TransportHandle, mpsc::Receiver<SyncMessage>) {
+    /// Returns the transport, a handle for sending messages and subscribing
+    /// to state changes, and a receiver for incoming messages.
+    pub fn new(
+        config: TransportConfig,
+    ) -> (Transport, TransportHandle, mpsc::Receiver<SyncMessage>) {
         let (outgoing_tx, outgoing_rx) = mpsc::channel::<SyncMessage>(100);
         let (incoming_tx, incoming_rx) = mpsc::channel::<SyncMessage>(100);
         let (shutdown_tx, shutdown_rx) = mpsc::channel::<()>(1);
-        let state = Arc::new(RwLock::new(ConnectionState::Disconnected));
+        let (state_tx, state_rx) = watch::channel(ConnectionState::Idle);
+        let (round_trip_tx, round_trip_rx) = watch::channel(None);
+        let (events_tx, _) = broadcast::channel(EVENT_CAPACITY);
 
         let transport = Transport {
             config,
-            state: state.clone(),
+            machine: StateMachine {
+                state: state_tx,
+                events: events_tx.clone(),
+            },
+            round_trip: round_trip_tx,
             outgoing_rx,
             incoming_tx,
             shutdown_rx,
         };
 
-        // Spawn background task
-        tokio::spawn(transport.run());
-
         let handle = TransportHandle {
             outgoing_tx,
-            state,
+            state: state_rx,
+            round_trip: round_trip_rx,
+            events: events_tx,
             shutdown_tx,
         };
 
+        (transport, handle, incoming_rx)
+    }
+
+    /// Spawns the transport's background task.
+    pub fn start(mut self) {
+        tokio::spawn(async move { self.run().await });
+    }
+
+    /// Creates a transport and spawns its background task.
+    ///
+    /// Returns a handle for sending messages and a receiver for incoming messages.
+    pub fn spawn(config: TransportConfig) -> (TransportHandle, mpsc::Receiver<SyncMessage>) {
+        let (transport, handle, incoming_rx) = Transport::new(config);
+        transport.start();
         (handle, incoming_rx)
     }
 
-    /// Main transport loop.
-    async fn run(mut self) {
+    /// Main transport loop. Returns once the machine reaches Idle.
+    ///
+    /// Borrows the transport, so a supervisor can run it again (with the
+    /// same handle and channels) after a panic.
+    pub async fn run(&mut self) {
         info!(url = %self.config.url, "Transport starting");
 
         let mut backoff = self.create_backoff();
-        let mut retry_count = 0u32;
+        let mut attempt = 0u32;
 
         loop {
-            // Check for shutdown
-            if self.shutdown_rx.try_recv().is_ok() {
-                info!("Transport received shutdown signal");
-                break;
-            }
+            attempt += 1;
+            self.machine.apply(Transition::Connect { attempt });
 
-            // Try to connect
-            *self.state.write().await = ConnectionState::Connecting;
+            let connected = tokio::select! {
+                result = Self::connect_with_timeout(&self.config) => result,
+                _ = self.shutdown_rx.recv() => {
+                    info!("Shutdown while connecting");
+                    self.machine.apply(Transition::Shutdown);
+                    break;
+                }
+            };
 
-            match self.connect_with_timeout().await {
+            let mut fatal = false;
+            let mut busy = false;
+            let reason = match connected {
                 Ok(ws_stream) => {
                     info!("WebSocket connected");
-                    *self.state.write().await = ConnectionState::Connected;
+                    self.machine.apply(Transition::Opened);
 
-                    // Reset backoff on successful connection
-                    backoff.reset();
-                    retry_count = 0;
+                    let result = self.connection_loop(ws_stream).await;
+                    self.round_trip.send_replace(None);
 
-                    // Run the connection loop
-                    if let Err(e) = self.connection_loop(ws_stream).await {
-                        warn!(?e, "Connection loop ended");
+                    // A connection that got through the handshake starts
+                    // the backoff over
+                    if self.machine.current() == ConnectionState::Ready {
+                        backoff.reset();
+                        attempt = 0;
+                    }
+
+                    match result {
+                        Ok(Closed::Shutdown) => break,
+                        Ok(Closed::ByPeer) => "closed by hub".to_string(),
+                        Err(e) => {
+                            warn!(?e, "Connection loop ended");
+                            // Reconnecting cannot close a version gap
+                            fatal = matches!(e, SyncError::UpgradeRequired(_));
+                            busy = matches!(e, SyncError::HubBusy(_));
+                            e.to_string()
+                        }
                     }
                 }
                 Err(e) => {
                     error!(?e, "Failed to connect");
+                    e.to_string()
                 }
-            }
+            };
+
+            // Retry limit applies to consecutive failed attempts
+            let retry_in = if fatal {
+                None
+            } else if self.config.max_retries > 0 && attempt >= self.config.max_retries {
+                error!(
+                    max_retries = self.config.max_retries,
+                    "Max reconnection attempts reached"
+                );
+                None
+            } else if busy {
+                // A full hub is not helped by quick retries
+                Some(self.config.max_backoff)
+            } else {
+                backoff.next_backoff()
+            };
 
-            // Connection lost or failed - enter backoff
-            *self.state.write().await = ConnectionState::Backoff;
-
-            // Check retry limit
-            if self.config.max_retries > 0 {
-                retry_count += 1;
-                if retry_count >= self.config.max_retries {
-                    error!(
-                        max_retries = self.config.max_retries,
-                        "Max reconnection attempts reached"
-                    );
-                    break;
-                }
-            }
+            self.machine.apply(Transition::Lost { reason, retry_in });
 
             // Wait for backoff duration
-            if let Some(duration) = backoff.next_backoff() {
-                debug!(?duration, attempt = retry_count, "Waiting before reconnect");
+            let Some(duration) = retry_in else {
+                break;
+            };
+            debug!(?duration, attempt, "Waiting before reconnect");
 
-                tokio::select! {
-                    _ = tokio::time::sleep(duration) => {
-                        *self.state.write().await = ConnectionState::Reconnecting;
-                    }
-                    _ = self.shutdown_rx.recv() => {
-                        info!("Shutdown during backoff");
-                        break;
-                    }
+            tokio::select! {
+                _ = tokio::time::sleep(duration) => {}
+                _ = self.shutdown_rx.recv() => {
+                    info!("Shutdown during backoff");
+                    self.machine.apply(Transition::Shutdown);
+                    break;
                 }
-            } else {
-                // Backoff exhausted (shouldn't happen with infinite backoff)
-                error!("Backoff exhausted");
-                break;
             }
         }
 
-        *self.state.write().await = ConnectionState::Disconnected;
-        info!("Transport stopped");
+        info!(state = %self.machine.current(), "Transport stopped");
     }
 
     /// Connects with timeout.
     async fn connect_with_timeout(
-        &self,
+        config: &TransportConfig,
     ) -> SyncResult<WebSocketStream<MaybeTlsStream<TcpStream>>> {
-        let connect_future = connect_async(&self.config.url);
+        let connect_future = connect_async(&config.url);
 
-        match timeout(self.config.connect_timeout, connect_future).await {
+        match timeout(config.connect_timeout, connect_future).await {
             Ok(Ok((ws_stream, response))) => {
                 debug!(status = ?response.status(), "WebSocket handshake complete");
                 Ok(ws_stream)
             }
             Ok(Err(e)) => Err(SyncError::from(e)),
-            Err(_) => Err(SyncError::Timeout(self.config.connect_timeout.as_secs())),
+            Err(_) => Err(SyncError::Timeout(config.connect_timeout.as_secs())),
         }
     }
 
-    /// Main connection loop - handles sending and receiving.
+    /// Main connection loop - handshake, then sending and receiving.
     async fn connection_loop(
         &mut self,
         ws_stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
-    ) -> SyncResult<()> {
+    ) -> SyncResult<Closed> {
         let (write, mut read) = ws_stream.split();
         let write = Arc::new(Mutex::new(write));
+        let mut session = self
+            .config
+            .signing_key
+            .clone()
+            .map(|key| Session::new(key, Role::Terminal));
+
+        match &self.config.hello {
+            Some(hello) => {
+                let json = encode(session.as_mut(), hello)?;
+                write
+                    .lock()
+                    .await
+                    .send(WsMessage::Text(json.into()))
+                    .await?;
+                debug!("Sent Hello message");
+            }
+            None => {
+                self.machine.apply(Transition::HandshakeComplete);
+            }
+        }
+
+        let handshake_deadline = tokio::time::sleep(self.config.handshake_timeout);
+        tokio::pin!(handshake_deadline);
 
         let mut ping_interval = tokio::time::interval(self.config.ping_interval);
         ping_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut heartbeat = Heartbeat::default();
+        self.round_trip.send_replace(None);
 
         loop {
             tokio::select! {
                 // Handle outgoing messages
                 Some(msg) = self.outgoing_rx.recv() => {
-                    let json = msg.to_json()?;
+                    let json = encode(session.as_mut(), &msg)?;
                     debug!(msg_type = %msg.type_name(), "Sending message");
                     let mut writer = write.lock().await;
                     writer.send(WsMessage::Text(json.into())).await?;
@@ -348,13 +635,43 @@ impl Transport {
                 Some(result) = read.next() => {
                     match result {
                         Ok(WsMessage::Text(text)) => {
-                            match SyncMessage::from_json(&text) {
+                            let handshaking = self.machine.current() == ConnectionState::Handshaking;
+                            match decode(session.as_mut(), &text, handshaking) {
                                 Ok(msg) => {
                                     debug!(msg_type = %msg.type_name(), "Received message");
+                                    let upgrade = if handshaking { upgrade_required(&msg) } else { None };
+                                    let turned_away = match &msg {
+                                        SyncMessage::Error { message, .. } if handshaking && shedding::is_busy(&msg) => {
+                                            Some(message.clone())
+                                        }
+                                        _ => None,
+                                    };
+                                    if matches!(msg, SyncMessage::Welcome(_)) && handshaking && upgrade.is_none() {
+                                        self.machine.apply(Transition::HandshakeComplete);
+                                    }
+
+                                    // A version gap is surfaced as an UPGRADE_REQUIRED error
+                                    // in place of the message
+                                    let msg = match &upgrade {
+                                        Some(reason) => SyncMessage::error(ErrorCode::UpgradeRequired, reason),
+                                        None => msg,
+                                    };
                                     if self.incoming_tx.send(msg).await.is_err() {
                                         warn!("Incoming message receiver dropped");
                                         return Err(SyncError::ChannelError("Receiver dropped".into()));
                                     }
+                                    if let Some(reason) = upgrade {
+                                        error!(%reason, "Hub and terminal share no protocol version");
+                                        return Err(SyncError::UpgradeRequired(reason));
+                                    }
+                                    if let Some(reason) = turned_away {
+                                        warn!(%reason, "Hub is at capacity");
+                                        return Err(SyncError::HubBusy(reason));
+                                    }
+                                }
+                                Err(e @ SyncError::IntegrityFailed(_)) => {
+                                    error!(?e, "Rejected unauthenticated message from hub");
+                                    return Err(e);
                                 }
                                 Err(e) => {
                                     warn!(?e, "Failed to parse message");
@@ -365,12 +682,15 @@ impl Transport {
                             let mut writer = write.lock().await;
                             writer.send(WsMessage::Pong(data)).await?;
                         }
-                        Ok(WsMessage::Pong(_)) => {
-                            debug!("Received pong");
+                        Ok(WsMessage::Pong(data)) => {
+                            if let Some(rtt) = heartbeat.pong(&data, Instant::now()) {
+                                debug!(rtt_ms = rtt.as_millis() as u64, "Received pong");
+                                self.round_trip.send_replace(Some(rtt));
+                            }
                         }
                         Ok(WsMessage::Close(frame)) => {
                             info!(?frame, "Received close frame");
-                            return Ok(());
+                            return Ok(Closed::ByPeer);
                         }
                         Ok(WsMessage::Binary(_)) => {
                             warn!("Received unexpected binary message");
@@ -385,19 +705,52 @@ impl Transport {
                     }
                 }
 
+                // Give up on a hub that never answers Hello
+                _ = &mut handshake_deadline,
+                    if self.machine.current() == ConnectionState::Handshaking =>
+                {
+                    warn!("Handshake timed out");
+                    return Err(SyncError::Timeout(self.config.handshake_timeout.as_secs()));
+                }
+
                 // Send periodic pings
                 _ = ping_interval.tick() => {
-                    let mut writer = write.lock().await;
-                    writer.send(WsMessage::Ping(vec![].into())).await?;
-                    debug!("Sent ping");
+                    if let Some(payload) = heartbeat.ping(Instant::now()) {
+                        let mut writer = write.lock().await;
+                        writer.send(WsMessage::Ping(payload.into())).await?;
+                        debug!("Sent ping");
+                    }
+                }
+
+                // Count a ping the hub never answered
+                _ = sleep_until_or_never(heartbeat.deadline(self.config.pong_timeout)) => {
+                    let missed = heartbeat.timed_out();
+                    warn!(missed, "Pong not received in time");
+                    if missed >= self.config.max_missed_pongs {
+                        return Err(SyncError::HeartbeatTimeout(missed));
+                    }
                 }
 
                 // Check for shutdown
                 _ = self.shutdown_rx.recv() => {
-                    info!("Shutdown signal received, closing connection");
+                    info!("Shutdown signal received, draining connection");
+                    self.machine.apply(Transition::Shutdown);
+
                     let mut writer = write.lock().await;
+                    while let Ok(msg) = self.outgoing_rx.try_recv() {
+                        let json = encode(session.as_mut(), &msg)?;
+                        if let Err(e) = writer.send(WsMessage::Text(json.into())).await {
+                            self.machine.apply(Transition::Lost {
+                                reason: e.to_string(),
+                                retry_in: None,
+                            });
+                            return Ok(Closed::Shutdown);
+                        }
+                    }
                     let _ = writer.send(WsMessage::Close(None)).await;
-                    return Ok(());
+
+                    self.machine.apply(Transition::Drained);
+                    return Ok(Closed::Shutdown);
                 }
             }
         }
@@ -415,6 +768,58 @@ impl Transport {
     }
 }
 
+/// Serializes an outgoing message, signing it when the session is signed.
+fn encode(session: Option<&mut Session>, msg: &SyncMessage) -> SyncResult<String> {
+    match session {
+        Some(session) => session.seal(msg),
+        None => Ok(msg.to_json()?),
+    }
+}
+
+/// Parses an incoming message, verifying it when the session is signed.
+///
+/// The hub cannot sign its rejection of a Hello it failed to verify, so
+/// unsigned `Error` messages are let through until the handshake completes.
+fn decode(session: Option<&mut Session>, text: &str, handshaking: bool) -> SyncResult<SyncMessage> {
+    let Some(session) = session else {
+        return Ok(SyncMessage::from_json(text)?);
+    };
+
+    let result = session.open(text);
+    if result.is_err() && handshaking {
+        if let Ok(msg @ SyncMessage::Error { .. }) = SyncMessage::from_json(text) {
+            return Ok(msg);
+        }
+    }
+    result
+}
+
+/// The reason the handshake cannot succeed, if `msg` shows the hub and this
+/// terminal share no protocol version.
+fn upgrade_required(msg: &SyncMessage) -> Option<String> {
+    match msg {
+        SyncMessage::Error { message, .. }
+            if msg.error_code() == Some(ErrorCode::UpgradeRequired) =>
+        {
+            Some(message.clone())
+        }
+        SyncMessage::Welcome(welcome) => compat::negotiate(welcome.protocol_version, None)
+            .err()
+            .map(|mismatch| {
+                mismatch.message(if mismatch.peer_is_older() { "hub" } else { "terminal" })
+            }),
+        _ => None,
+    }
+}
+
+/// Sleeps until `deadline`, or forever if there is none.
+async fn sleep_until_or_never(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
 // =============================================================================
 // Sender Wrapper (for use in other components)
 // =============================================================================
@@ -426,9 +831,34 @@ pub type WsSink = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, WsMessag
 mod tests {
     use super::*;
 
+    fn lost(retry_in: Option<Duration>) -> Transition {
+        Transition::Lost {
+            reason: "refused".into(),
+            retry_in,
+        }
+    }
+
+    /// Config pointing at a port nothing listens on.
+    fn unreachable_config() -> TransportConfig {
+        TransportConfig {
+            url: "ws://127.0.0.1:1/sync".into(),
+            connect_timeout: Duration::from_secs(2),
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(10),
+            ..Default::default()
+        }
+    }
+
+    async fn next_event(events: &mut broadcast::Receiver<TransportEvent>) -> TransportEvent {
+        timeout(Duration::from_secs(5), events.recv())
+            .await
+            .expect("timed out waiting for transport event")
+            .expect("event channel closed")
+    }
+
     #[test]
     fn test_connection_state_display() {
-        assert_eq!(ConnectionState::Connected.to_string(), "connected");
+        assert_eq!(ConnectionState::Ready.to_string(), "ready");
         assert_eq!(ConnectionState::Backoff.to_string(), "backoff");
     }
 
@@ -437,5 +867,135 @@ mod tests {
         let config = TransportConfig::default();
         assert_eq!(config.connect_timeout, Duration::from_secs(10));
         assert_eq!(config.max_retries, 0); // Infinite
+        assert!(config.hello.is_none());
+    }
+
+    #[test]
+    fn test_happy_path_transitions() {
+        use ConnectionState::*;
+
+        let state = Idle.next(&Transition::Connect { attempt: 1 }).unwrap();
+        assert_eq!(state, Connecting);
+        let state = state.next(&Transition::Opened).unwrap();
+        assert_eq!(state, Handshaking);
+        let state = state.next(&Transition::HandshakeComplete).unwrap();
+        assert_eq!(state, Ready);
+        let state = state.next(&Transition::Shutdown).unwrap();
+        assert_eq!(state, Draining);
+        assert_eq!(state.next(&Transition::Drained), Some(Idle));
+    }
+
+    #[test]
+    fn test_lost_transitions() {
+        use ConnectionState::*;
+
+        let retry = Some(Duration::from_secs(1));
+        assert_eq!(Ready.next(&lost(retry)), Some(Backoff));
+        assert_eq!(Handshaking.next(&lost(retry)), Some(Backoff));
+        assert_eq!(Connecting.next(&lost(None)), Some(Idle));
+        assert_eq!(Draining.next(&lost(retry)), Some(Idle));
+        assert_eq!(Backoff.next(&Transition::Shutdown), Some(Idle));
+    }
+
+    #[test]
+    fn test_invalid_transitions() {
+        use ConnectionState::*;
+
+        assert_eq!(Idle.next(&Transition::Opened), None);
+        assert_eq!(Connecting.next(&Transition::HandshakeComplete), None);
+        assert_eq!(Ready.next(&Transition::Connect { attempt: 1 }), None);
+        assert_eq!(Idle.next(&Transition::Shutdown), None);
+        assert_eq!(Backoff.next(&lost(None)), None);
+    }
+
+    #[test]
+    fn test_heartbeat_round_trip() {
+        let mut heartbeat = Heartbeat::default();
+        let sent = Instant::now();
+
+        let payload = heartbeat.ping(sent).unwrap();
+        // Still waiting for the first pong
+        assert!(heartbeat.ping(sent).is_none());
+        assert_eq!(
+            heartbeat.deadline(Duration::from_secs(5)),
+            Some(sent + Duration::from_secs(5))
+        );
+
+        let rtt = heartbeat.pong(&payload, sent + Duration::from_millis(40));
+        assert_eq!(rtt, Some(Duration::from_millis(40)));
+        assert_eq!(heartbeat.deadline(Duration::from_secs(5)), None);
+    }
+
+    #[test]
+    fn test_heartbeat_misses_and_late_pong() {
+        let mut heartbeat = Heartbeat::default();
+        let now = Instant::now();
+
+        let stale = heartbeat.ping(now).unwrap();
+        assert_eq!(heartbeat.timed_out(), 1);
+
+        heartbeat.ping(now).unwrap();
+        // The pong for the timed-out ping does not count
+        assert_eq!(heartbeat.pong(&stale, now), None);
+        assert_eq!(heartbeat.timed_out(), 2);
+
+        // An answered ping resets the count
+        let payload = heartbeat.ping(now).unwrap();
+        assert!(heartbeat.pong(&payload, now).is_some());
+        heartbeat.ping(now).unwrap();
+        assert_eq!(heartbeat.timed_out(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_until_retries_exhausted() {
+        let config = TransportConfig {
+            max_retries: 2,
+            ..unreachable_config()
+        };
+        let (transport, handle, _incoming_rx) = Transport::new(config);
+        let mut events = handle.subscribe();
+        transport.start();
+
+        let steps = [
+            (ConnectionState::Idle, ConnectionState::Connecting),
+            (ConnectionState::Connecting, ConnectionState::Backoff),
+            (ConnectionState::Backoff, ConnectionState::Connecting),
+            (ConnectionState::Connecting, ConnectionState::Idle),
+        ];
+        let mut seen = Vec::new();
+        for (from, to) in steps {
+            let event = next_event(&mut events).await;
+            assert_eq!((event.from, event.to), (from, to), "{:?}", event);
+            seen.push(event.transition);
+        }
+
+        assert_eq!(seen[2], Transition::Connect { attempt: 2 });
+        assert!(matches!(seen[3], Transition::Lost { retry_in: None, .. }));
+        assert_eq!(handle.state().await, ConnectionState::Idle);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_during_backoff() {
+        let config = TransportConfig {
+            initial_backoff: Duration::from_secs(60),
+            max_backoff: Duration::from_secs(60),
+            ..unreachable_config()
+        };
+        let (transport, handle, _incoming_rx) = Transport::new(config);
+        let mut events = handle.subscribe();
+        transport.start();
+
+        loop {
+            if next_event(&mut events).await.to == ConnectionState::Backoff {
+                break;
+            }
+        }
+        handle.shutdown().await.unwrap();
+
+        let event = next_event(&mut events).await;
+        assert_eq!(event.from, ConnectionState::Backoff);
+        assert_eq!(event.to, ConnectionState::Idle);
+        assert_eq!(event.transition, Transition::Shutdown);
+        assert!(!handle.is_connected().await);
     }
 }
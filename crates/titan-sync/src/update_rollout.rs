@@ -0,0 +1,241 @@
+//! # Staged Update Rollout
+//!
+//! Once a store is offered a new app release (see the cloud's
+//! `GetLatestRelease`), its devices must not all restart into it at once:
+//! a bad build would take down every till, and a restarting hub drops
+//! every terminal's connection. Devices ask the hub for an update slot
+//! first, and the hub hands slots out one at a time.
+//!
+//! ## Slot Protocol (v5)
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────────┐
+//! │                         Update Slot Protocol                            │
+//! │                                                                         │
+//! │  POS #2 ──► UpdateSlotRequest { version }                               │
+//! │                   │                                                     │
+//! │                   ▼                                                     │
+//! │  hub: UpdateCoordinator::request(POS #2)                                │
+//! │       ├── slot free, POS #2 next in line ──► granted                    │
+//! │       │     POS #2 installs, restarts ──► UpdateFinished ──► slot free  │
+//! │       └── otherwise ──► queue_position, retry_after_secs                │
+//! │                          POS #2 asks again after retry_after_secs       │
+//! │                                                                         │
+//! │  Order: terminals first come first served; the hub's own device last,  │
+//! │  once no terminal is updating or waiting.                               │
+//! └─────────────────────────────────────────────────────────────────────────┘
+//! ```
+//!
+//! A device that never reports back (crashed mid-install) loses its slot
+//! after [`UPDATE_SLOT_LEASE`]; a waiting device that stops asking loses
+//! its place after three retry intervals.
+
+use std::time::{Duration, Instant};
+
+use tracing::warn;
+
+/// How long a device waits before asking for a slot again.
+pub const UPDATE_RETRY_SECS: u64 = 60;
+
+/// Longest a device holds the slot without reporting `UpdateFinished`.
+pub const UPDATE_SLOT_LEASE: Duration = Duration::from_secs(15 * 60);
+
+/// A waiting device that has not asked for this long loses its place.
+const WAIT_EXPIRY: Duration = Duration::from_secs(3 * UPDATE_RETRY_SECS);
+
+/// The hub's answer to a slot request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotDecision {
+    /// The device may install now.
+    Granted,
+    /// Ask again later; `position` is 1 for the next device in line.
+    Wait { position: u32 },
+}
+
+/// Hands out update slots on the hub (see the module docs).
+#[derive(Debug)]
+pub struct UpdateCoordinator {
+    /// The hub's own device, which updates last.
+    hub_device_id: String,
+    /// Slot lease.
+    lease: Duration,
+    /// Device holding the slot, and when it was granted.
+    updating: Option<(String, Instant)>,
+    /// Waiting devices in the order they first asked, with when they last
+    /// asked.
+    waiting: Vec<(String, Instant)>,
+}
+
+impl UpdateCoordinator {
+    /// Creates a coordinator for the hub running as `hub_device_id`.
+    pub fn new(hub_device_id: impl Into<String>) -> Self {
+        UpdateCoordinator {
+            hub_device_id: hub_device_id.into(),
+            lease: UPDATE_SLOT_LEASE,
+            updating: None,
+            waiting: Vec::new(),
+        }
+    }
+
+    /// Sets the slot lease (default [`UPDATE_SLOT_LEASE`]).
+    pub fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+
+    /// Device currently holding the slot.
+    pub fn updating(&self) -> Option<&str> {
+        self.updating
+            .as_ref()
+            .map(|(device_id, _)| device_id.as_str())
+    }
+
+    /// Handles a slot request from `device_id`.
+    ///
+    /// The holder asking again (its answer was lost) is granted again.
+    pub fn request(&mut self, device_id: &str, now: Instant) -> SlotDecision {
+        self.expire(now);
+
+        if self.updating() == Some(device_id) {
+            return SlotDecision::Granted;
+        }
+        match self.waiting.iter_mut().find(|(id, _)| id == device_id) {
+            Some(entry) => entry.1 = now,
+            None => self.waiting.push((device_id.to_string(), now)),
+        }
+
+        let queue = self.queue();
+        let position = queue.iter().position(|id| id == device_id).unwrap_or(0);
+        if self.updating.is_none() && position == 0 {
+            self.waiting.retain(|(id, _)| id != device_id);
+            self.updating = Some((device_id.to_string(), now));
+            return SlotDecision::Granted;
+        }
+
+        SlotDecision::Wait {
+            position: position as u32 + 1,
+        }
+    }
+
+    /// Frees the slot once `device_id` reports its update finished.
+    ///
+    /// Returns false if it did not hold the slot (it is dropped from the
+    /// queue instead).
+    pub fn finish(&mut self, device_id: &str) -> bool {
+        if self.updating() == Some(device_id) {
+            self.updating = None;
+            return true;
+        }
+        self.waiting.retain(|(id, _)| id != device_id);
+        false
+    }
+
+    /// Waiting devices in grant order: terminals as they asked, then the hub.
+    fn queue(&self) -> Vec<String> {
+        let (hub, terminals): (Vec<_>, Vec<_>) = self
+            .waiting
+            .iter()
+            .map(|(id, _)| id.clone())
+            .partition(|id| *id == self.hub_device_id);
+        terminals.into_iter().chain(hub).collect()
+    }
+
+    /// Drops an expired slot and waiting devices that stopped asking.
+    fn expire(&mut self, now: Instant) {
+        if let Some((device_id, granted_at)) = &self.updating {
+            if now.duration_since(*granted_at) >= self.lease {
+                warn!(device_id = %device_id, "Update slot lease expired");
+                self.updating = None;
+            }
+        }
+        self.waiting
+            .retain(|(_, asked_at)| now.duration_since(*asked_at) < WAIT_EXPIRY);
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEC: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn test_one_device_at_a_time_in_order() {
+        let t0 = Instant::now();
+        let mut c = UpdateCoordinator::new("hub");
+
+        assert_eq!(c.request("pos-1", t0), SlotDecision::Granted);
+        assert_eq!(c.request("pos-2", t0), SlotDecision::Wait { position: 1 });
+        assert_eq!(c.request("pos-3", t0), SlotDecision::Wait { position: 2 });
+        // A lost answer: the holder is granted again
+        assert_eq!(c.request("pos-1", t0 + SEC), SlotDecision::Granted);
+
+        assert!(c.finish("pos-1"));
+        // pos-3 asked after pos-2, so it keeps waiting
+        assert_eq!(
+            c.request("pos-3", t0 + 60 * SEC),
+            SlotDecision::Wait { position: 2 }
+        );
+        assert_eq!(c.request("pos-2", t0 + 60 * SEC), SlotDecision::Granted);
+        assert_eq!(c.updating(), Some("pos-2"));
+    }
+
+    #[test]
+    fn test_hub_goes_last() {
+        let t0 = Instant::now();
+        let mut c = UpdateCoordinator::new("hub");
+
+        assert_eq!(c.request("pos-1", t0), SlotDecision::Granted);
+        assert_eq!(c.request("hub", t0), SlotDecision::Wait { position: 1 });
+        // A terminal asking later still goes before the hub
+        assert_eq!(c.request("pos-2", t0), SlotDecision::Wait { position: 1 });
+        assert_eq!(
+            c.request("hub", t0 + SEC),
+            SlotDecision::Wait { position: 2 }
+        );
+
+        c.finish("pos-1");
+        assert_eq!(
+            c.request("hub", t0 + 2 * SEC),
+            SlotDecision::Wait { position: 2 }
+        );
+        assert_eq!(c.request("pos-2", t0 + 2 * SEC), SlotDecision::Granted);
+        c.finish("pos-2");
+        assert_eq!(c.request("hub", t0 + 3 * SEC), SlotDecision::Granted);
+    }
+
+    #[test]
+    fn test_lease_expires() {
+        let t0 = Instant::now();
+        let mut c = UpdateCoordinator::new("hub").with_lease(600 * SEC);
+
+        assert_eq!(c.request("pos-1", t0), SlotDecision::Granted);
+        assert_eq!(c.request("pos-2", t0), SlotDecision::Wait { position: 1 });
+        assert_eq!(
+            c.request("pos-2", t0 + 120 * SEC),
+            SlotDecision::Wait { position: 1 }
+        );
+        assert_eq!(c.request("pos-2", t0 + 600 * SEC), SlotDecision::Granted);
+        // The late report does not free pos-2's slot
+        assert!(!c.finish("pos-1"));
+        assert_eq!(c.updating(), Some("pos-2"));
+    }
+
+    #[test]
+    fn test_waiting_device_that_stops_asking_loses_its_place() {
+        let t0 = Instant::now();
+        let mut c = UpdateCoordinator::new("hub");
+
+        assert_eq!(c.request("pos-1", t0), SlotDecision::Granted);
+        assert_eq!(c.request("pos-2", t0), SlotDecision::Wait { position: 1 });
+        assert_eq!(c.request("pos-3", t0), SlotDecision::Wait { position: 2 });
+        c.finish("pos-1");
+
+        // pos-2 went offline; once its place expires pos-3 moves up
+        let later = t0 + WAIT_EXPIRY;
+        assert_eq!(c.request("pos-3", later), SlotDecision::Granted);
+    }
+}
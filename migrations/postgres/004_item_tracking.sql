@@ -0,0 +1,24 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Serial & Lot Tracking
+-- =============================================================================
+--
+-- Regulated products (electronics, pharmacy, food) can require a serial or
+-- lot number to be captured at sale time. Stores upload the captured codes
+-- with each sale item so a recall can be traced across every store.
+
+-- -----------------------------------------------------------------------------
+-- Products - capture requirement
+-- -----------------------------------------------------------------------------
+ALTER TABLE products
+    ADD COLUMN IF NOT EXISTS item_tracking TEXT NOT NULL DEFAULT 'none'; -- none, serial, lot
+
+-- -----------------------------------------------------------------------------
+-- Sale Items - captured codes
+-- -----------------------------------------------------------------------------
+ALTER TABLE sale_items
+    ADD COLUMN IF NOT EXISTS tracking_kind TEXT, -- serial, lot (NULL when untracked)
+    ADD COLUMN IF NOT EXISTS tracking_codes TEXT[] NOT NULL DEFAULT '{}';
+
+-- Recall lookups: WHERE tracking_codes @> ARRAY['LOT-2024-07']
+CREATE INDEX IF NOT EXISTS idx_sale_items_tracking_codes
+    ON sale_items USING GIN (tracking_codes);
@@ -0,0 +1,69 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Store Transfers
+-- =============================================================================
+--
+-- Stock transfers between stores of the same tenant. The cloud keeps the
+-- authoritative copy and routes it between the two stores:
+--
+-- - Sending store uploads the transfer (IN_TRANSIT) → receiving store downloads
+-- - Receiving store uploads the receipt (RECEIVED) → sending store downloads
+-- - Sending store may cancel while IN_TRANSIT (CANCELLED)
+--
+-- Once a transfer leaves IN_TRANSIT it is closed; later uploads for it are
+-- ignored and the store that sent them gets the closed copy on its next sync.
+
+-- Download cursor for transfers: bumped every time a transfer changes.
+CREATE SEQUENCE IF NOT EXISTS store_transfer_route_seq;
+
+-- -----------------------------------------------------------------------------
+-- Store Transfers
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS store_transfers (
+    id TEXT PRIMARY KEY NOT NULL,
+    tenant_id TEXT NOT NULL REFERENCES tenants(id),
+    transfer_number TEXT NOT NULL,
+
+    from_store_id TEXT NOT NULL REFERENCES stores(id),
+    to_store_id TEXT NOT NULL REFERENCES stores(id),
+
+    -- Status
+    status TEXT NOT NULL DEFAULT 'IN_TRANSIT', -- IN_TRANSIT, RECEIVED, CANCELLED
+
+    notes TEXT,
+    created_by TEXT NOT NULL,
+    received_by TEXT,
+
+    -- Timestamps
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    received_at TIMESTAMPTZ,
+    cancelled_at TIMESTAMPTZ,
+
+    -- Versioning
+    version BIGINT NOT NULL DEFAULT 1,
+    route_seq BIGINT NOT NULL DEFAULT nextval('store_transfer_route_seq'),
+
+    CHECK (from_store_id <> to_store_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_store_transfers_from ON store_transfers(from_store_id, route_seq);
+CREATE INDEX IF NOT EXISTS idx_store_transfers_to ON store_transfers(to_store_id, route_seq);
+CREATE INDEX IF NOT EXISTS idx_store_transfers_in_transit ON store_transfers(tenant_id)
+    WHERE status = 'IN_TRANSIT';
+
+-- -----------------------------------------------------------------------------
+-- Store Transfer Items
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS store_transfer_items (
+    id TEXT PRIMARY KEY NOT NULL,
+    transfer_id TEXT NOT NULL REFERENCES store_transfers(id) ON DELETE CASCADE,
+    product_id TEXT NOT NULL,
+
+    sku TEXT NOT NULL,
+    name TEXT NOT NULL,
+
+    quantity_sent INTEGER NOT NULL,
+    quantity_received INTEGER -- NULL until received
+);
+
+CREATE INDEX IF NOT EXISTS idx_store_transfer_items_transfer ON store_transfer_items(transfer_id);
@@ -0,0 +1,59 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Store Credit
+-- =============================================================================
+--
+-- Customer store credit shared by every store of a tenant. Each store hub
+-- uploads the accounts it touched; the cloud merges them and sends every
+-- change to all stores of the tenant:
+--
+-- - Accounts are keyed by id; customer details follow the higher version
+-- - Ledger entries are append-only and merged by id (never updated)
+-- - balance = SUM(store_credit_entries.amount_cents)
+--
+-- Redemptions are checked against the balance at the store hub. Two stores
+-- spending the same credit while offline from the cloud show up here as a
+-- negative balance.
+
+-- Download cursor for store credit: bumped every time an account changes.
+CREATE SEQUENCE IF NOT EXISTS store_credit_route_seq;
+
+-- -----------------------------------------------------------------------------
+-- Store Credit Accounts
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS store_credit_accounts (
+    id TEXT PRIMARY KEY NOT NULL,
+    tenant_id TEXT NOT NULL REFERENCES tenants(id),
+    credit_number TEXT NOT NULL,
+
+    customer_name TEXT NOT NULL,
+    customer_phone TEXT,
+
+    -- Timestamps
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+
+    -- Versioning
+    version BIGINT NOT NULL DEFAULT 1,
+    route_seq BIGINT NOT NULL DEFAULT nextval('store_credit_route_seq'),
+
+    UNIQUE (tenant_id, credit_number)
+);
+
+CREATE INDEX IF NOT EXISTS idx_store_credit_accounts_route ON store_credit_accounts(tenant_id, route_seq);
+
+-- -----------------------------------------------------------------------------
+-- Store Credit Entries
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS store_credit_entries (
+    id TEXT PRIMARY KEY NOT NULL,
+    account_id TEXT NOT NULL REFERENCES store_credit_accounts(id) ON DELETE CASCADE,
+
+    kind TEXT NOT NULL, -- ISSUE, REDEEM
+    amount_cents BIGINT NOT NULL, -- positive for ISSUE, negative for REDEEM
+
+    sale_id TEXT,
+    device_id TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_store_credit_entries_account ON store_credit_entries(account_id);
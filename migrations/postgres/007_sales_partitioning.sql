@@ -0,0 +1,182 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Sales Partitioning & Retention
+-- =============================================================================
+--
+-- sales, sale_items and payments grow with every transaction of every
+-- store. They are range-partitioned by month on created_at so that old
+-- data can be archived and dropped a month at a time instead of deleted
+-- row by row:
+--
+--   sales                      (partitioned by RANGE (created_at))
+--   ├── sales_p202401          [2024-01-01, 2024-02-01)
+--   ├── sales_p202402          [2024-02-01, 2024-03-01)
+--   ├── ...
+--   └── sales_default          anything without a monthly partition yet
+--
+-- Partitions are created ahead of time by titan_ensure_monthly_partitions
+-- (called here for existing data and by the cloud API's retention job).
+-- A sale item is stored in the month of its sale, so a whole sale always
+-- lives in the same month across the three tables.
+--
+-- Partitioned tables can only enforce uniqueness on keys that include the
+-- partition column, so the primary keys become (id, created_at) and the
+-- sale_items/payments foreign keys to sales are dropped. Uploads stay
+-- idempotent because a record's created_at never changes.
+
+-- -----------------------------------------------------------------------------
+-- Partition maintenance
+-- -----------------------------------------------------------------------------
+-- Creates the monthly partitions of parent_table from the month of
+-- from_month up to months_ahead months after the current month (UTC).
+-- Rows already sitting in <parent_table>_default for a new month are moved
+-- into the new partition before it is attached. Returns the number of
+-- partitions created.
+CREATE OR REPLACE FUNCTION titan_ensure_monthly_partitions(
+    parent_table TEXT,
+    from_month DATE,
+    months_ahead INTEGER
+) RETURNS INTEGER AS $$
+DECLARE
+    month_start DATE := date_trunc('month', from_month)::date;
+    last_month DATE := (date_trunc('month', NOW() AT TIME ZONE 'UTC')
+                        + make_interval(months => months_ahead))::date;
+    lower_bound TIMESTAMPTZ;
+    upper_bound TIMESTAMPTZ;
+    partition_name TEXT;
+    created INTEGER := 0;
+BEGIN
+    WHILE month_start <= last_month LOOP
+        partition_name := format('%s_p%s', parent_table, to_char(month_start, 'YYYYMM'));
+        lower_bound := month_start::timestamp AT TIME ZONE 'UTC';
+        upper_bound := (month_start + INTERVAL '1 month')::timestamp AT TIME ZONE 'UTC';
+
+        IF to_regclass(partition_name) IS NULL THEN
+            EXECUTE format('CREATE TABLE %I (LIKE %I INCLUDING DEFAULTS)',
+                partition_name, parent_table);
+            EXECUTE format(
+                'WITH moved AS (DELETE FROM %I WHERE created_at >= %L AND created_at < %L RETURNING *) '
+                'INSERT INTO %I SELECT * FROM moved',
+                parent_table || '_default', lower_bound, upper_bound, partition_name);
+            EXECUTE format('ALTER TABLE %I ATTACH PARTITION %I FOR VALUES FROM (%L) TO (%L)',
+                parent_table, partition_name, lower_bound, upper_bound);
+            created := created + 1;
+        END IF;
+
+        month_start := (month_start + INTERVAL '1 month')::date;
+    END LOOP;
+
+    RETURN created;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Foreign keys into sales cannot survive the new composite primary key
+ALTER TABLE sale_items DROP CONSTRAINT IF EXISTS sale_items_sale_id_fkey;
+ALTER TABLE payments DROP CONSTRAINT IF EXISTS payments_sale_id_fkey;
+
+-- -----------------------------------------------------------------------------
+-- Sales
+-- -----------------------------------------------------------------------------
+ALTER TABLE sales RENAME TO sales_unpartitioned;
+
+CREATE TABLE sales (LIKE sales_unpartitioned INCLUDING DEFAULTS)
+    PARTITION BY RANGE (created_at);
+CREATE TABLE sales_default PARTITION OF sales DEFAULT;
+
+SELECT titan_ensure_monthly_partitions(
+    'sales',
+    COALESCE((SELECT MIN(created_at) FROM sales_unpartitioned), NOW())::date,
+    3
+);
+
+INSERT INTO sales SELECT * FROM sales_unpartitioned;
+DROP TABLE sales_unpartitioned;
+
+ALTER TABLE sales ADD PRIMARY KEY (id, created_at);
+ALTER TABLE sales ADD FOREIGN KEY (store_id) REFERENCES stores(id);
+ALTER TABLE sales ADD FOREIGN KEY (tenant_id) REFERENCES tenants(id);
+
+CREATE INDEX IF NOT EXISTS idx_sales_store ON sales(store_id);
+CREATE INDEX IF NOT EXISTS idx_sales_device ON sales(device_id);
+CREATE INDEX IF NOT EXISTS idx_sales_tenant ON sales(tenant_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_sales_receipt ON sales(store_id, receipt_number);
+CREATE INDEX IF NOT EXISTS idx_sales_created ON sales(created_at);
+CREATE INDEX IF NOT EXISTS idx_sales_status ON sales(status);
+
+CREATE TRIGGER update_sales_updated_at BEFORE UPDATE ON sales
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- -----------------------------------------------------------------------------
+-- Sale Items (created_at = created_at of the sale)
+-- -----------------------------------------------------------------------------
+UPDATE sale_items si
+SET created_at = s.created_at
+FROM sales s
+WHERE s.id = si.sale_id AND si.created_at <> s.created_at;
+
+ALTER TABLE sale_items RENAME TO sale_items_unpartitioned;
+
+CREATE TABLE sale_items (LIKE sale_items_unpartitioned INCLUDING DEFAULTS)
+    PARTITION BY RANGE (created_at);
+CREATE TABLE sale_items_default PARTITION OF sale_items DEFAULT;
+
+SELECT titan_ensure_monthly_partitions(
+    'sale_items',
+    COALESCE((SELECT MIN(created_at) FROM sale_items_unpartitioned), NOW())::date,
+    3
+);
+
+INSERT INTO sale_items SELECT * FROM sale_items_unpartitioned;
+DROP TABLE sale_items_unpartitioned;
+
+ALTER TABLE sale_items ADD PRIMARY KEY (id, created_at);
+ALTER TABLE sale_items ADD FOREIGN KEY (product_id) REFERENCES products(id);
+
+CREATE INDEX IF NOT EXISTS idx_sale_items_id ON sale_items(id);
+CREATE INDEX IF NOT EXISTS idx_sale_items_sale ON sale_items(sale_id);
+CREATE INDEX IF NOT EXISTS idx_sale_items_product ON sale_items(product_id);
+CREATE INDEX IF NOT EXISTS idx_sale_items_tracking_codes
+    ON sale_items USING GIN (tracking_codes);
+
+-- -----------------------------------------------------------------------------
+-- Payments
+-- -----------------------------------------------------------------------------
+ALTER TABLE payments RENAME TO payments_unpartitioned;
+
+CREATE TABLE payments (LIKE payments_unpartitioned INCLUDING DEFAULTS)
+    PARTITION BY RANGE (created_at);
+CREATE TABLE payments_default PARTITION OF payments DEFAULT;
+
+SELECT titan_ensure_monthly_partitions(
+    'payments',
+    COALESCE((SELECT MIN(created_at) FROM payments_unpartitioned), NOW())::date,
+    3
+);
+
+INSERT INTO payments SELECT * FROM payments_unpartitioned;
+DROP TABLE payments_unpartitioned;
+
+ALTER TABLE payments ADD PRIMARY KEY (id, created_at);
+ALTER TABLE payments ADD FOREIGN KEY (store_id) REFERENCES stores(id);
+ALTER TABLE payments ADD FOREIGN KEY (tenant_id) REFERENCES tenants(id);
+
+CREATE INDEX IF NOT EXISTS idx_payments_sale ON payments(sale_id);
+CREATE INDEX IF NOT EXISTS idx_payments_store ON payments(store_id);
+CREATE INDEX IF NOT EXISTS idx_payments_tenant ON payments(tenant_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_payments_created ON payments(created_at);
+
+-- -----------------------------------------------------------------------------
+-- Retention Policies
+-- -----------------------------------------------------------------------------
+-- How long each tenant's sales data is kept before it is archived and
+-- removed. Tenants without a row use the server default
+-- (SALES_RETENTION_MONTHS). Whole monthly partitions are dropped once they
+-- are older than the longest retention of any tenant; tenants with a
+-- shorter retention have their older rows archived and deleted first.
+CREATE TABLE IF NOT EXISTS tenant_retention_policies (
+    tenant_id TEXT PRIMARY KEY NOT NULL REFERENCES tenants(id),
+    sales_retention_months INTEGER NOT NULL CHECK (sales_retention_months >= 1),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TRIGGER update_tenant_retention_policies_updated_at BEFORE UPDATE ON tenant_retention_policies
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
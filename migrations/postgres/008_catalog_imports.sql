@@ -0,0 +1,73 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Bulk Catalog Imports
+-- =============================================================================
+--
+-- A catalog file is uploaded, validated row by row and staged here before
+-- anything touches the products table:
+--
+--   UploadCatalog ──► catalog_imports (STAGED)
+--                     ├── catalog_import_rows    accepted rows
+--                     └── catalog_import_errors  rejected rows + reason
+--
+--   PublishCatalogImport ──► upsert products by (tenant_id, sku)
+--                            (auto_queue_product_downloads fans the changes
+--                             out to every store) ──► PUBLISHED
+
+-- -----------------------------------------------------------------------------
+-- Catalog Imports
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS catalog_imports (
+    id TEXT PRIMARY KEY NOT NULL,
+    tenant_id TEXT NOT NULL REFERENCES tenants(id),
+    uploaded_by TEXT NOT NULL, -- Store (or user) that uploaded the file
+
+    file_name TEXT NOT NULL,
+    format TEXT NOT NULL, -- csv, json
+    status TEXT NOT NULL DEFAULT 'STAGED', -- STAGED, PUBLISHED
+
+    total_rows INTEGER NOT NULL,
+    accepted_rows INTEGER NOT NULL,
+    error_rows INTEGER NOT NULL,
+
+    -- Publish results
+    inserted_count INTEGER,
+    updated_count INTEGER,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    published_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_catalog_imports_tenant ON catalog_imports(tenant_id, created_at);
+
+-- -----------------------------------------------------------------------------
+-- Staged Rows
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS catalog_import_rows (
+    import_id TEXT NOT NULL REFERENCES catalog_imports(id) ON DELETE CASCADE,
+    row_number INTEGER NOT NULL,
+
+    sku TEXT NOT NULL,
+    name TEXT NOT NULL,
+    barcode TEXT,
+    price_cents BIGINT NOT NULL,
+    cost_cents BIGINT,
+    tax_rate_bps INTEGER NOT NULL,
+    track_inventory BOOLEAN NOT NULL,
+    is_active BOOLEAN NOT NULL,
+    category TEXT,
+    department TEXT,
+
+    PRIMARY KEY (import_id, row_number)
+);
+
+-- -----------------------------------------------------------------------------
+-- Row Errors
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS catalog_import_errors (
+    import_id TEXT NOT NULL REFERENCES catalog_imports(id) ON DELETE CASCADE,
+    row_number INTEGER NOT NULL,
+    field TEXT NOT NULL,
+    message TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_catalog_import_errors_import ON catalog_import_errors(import_id, row_number);
@@ -0,0 +1,48 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Tenant Users & Roles
+-- =============================================================================
+--
+-- Back-office accounts that sign in to the cloud (as opposed to `users`, the
+-- store staff that log in at a till). Each account has one tenant-wide role:
+--
+--   OWNER    everything, including inviting and disabling users
+--   MANAGER  store configuration, catalog imports, reports
+--   ANALYST  reports only (read-only)
+--
+-- Accounts are created by invitation: the invite token (stored hashed) is
+-- exchanged for a password once, after which the account can log in.
+
+-- -----------------------------------------------------------------------------
+-- Tenant Users
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS tenant_users (
+    id TEXT PRIMARY KEY NOT NULL,
+    tenant_id TEXT NOT NULL REFERENCES tenants(id),
+
+    email TEXT NOT NULL, -- Stored lowercase
+    display_name TEXT NOT NULL,
+    role TEXT NOT NULL CHECK (role IN ('OWNER', 'MANAGER', 'ANALYST')),
+
+    -- Authentication
+    password_hash TEXT, -- NULL until the invite is accepted
+    invite_token_hash TEXT, -- SHA-256 of the invite token, cleared on accept
+    invite_expires_at TIMESTAMPTZ,
+    invited_by TEXT REFERENCES tenant_users(id),
+
+    -- Status
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    last_login_at TIMESTAMPTZ,
+
+    -- Timestamps
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    -- Email is the login name, so it is unique across tenants
+    CONSTRAINT unique_tenant_user_email UNIQUE (email)
+);
+
+CREATE INDEX IF NOT EXISTS idx_tenant_users_tenant ON tenant_users(tenant_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_tenant_users_invite
+    ON tenant_users(invite_token_hash) WHERE invite_token_hash IS NOT NULL;
+
+CREATE TRIGGER update_tenant_users_updated_at BEFORE UPDATE ON tenant_users FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,53 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Audit Log
+-- =============================================================================
+--
+-- One row per mutating RPC, written by the cloud API's audit middleware:
+-- who called (store, back-office user or anonymous), which method, which
+-- entities it touched and how it ended. Rows are never changed or removed;
+-- the triggers below reject UPDATE, DELETE and TRUNCATE.
+
+-- -----------------------------------------------------------------------------
+-- Audit Log
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS audit_log (
+    id BIGSERIAL PRIMARY KEY,
+
+    -- Who
+    tenant_id TEXT, -- NULL when the caller could not be identified
+    actor_type TEXT NOT NULL, -- STORE, USER, ANONYMOUS
+    actor_id TEXT, -- store_id or tenant_users.id
+    store_id TEXT, -- Store acted on (the caller itself for store tokens)
+
+    -- What
+    method TEXT NOT NULL, -- Full gRPC path, e.g. /titan.sync.v1.ConfigService/UpdateConfigValue
+    entity_ids TEXT[] NOT NULL DEFAULT '{}', -- kind:id, e.g. user:..., config:currency
+
+    -- Result
+    status_code INTEGER NOT NULL, -- gRPC status code, 0 = OK
+    status_message TEXT,
+    duration_ms INTEGER NOT NULL,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_tenant ON audit_log(tenant_id, id);
+CREATE INDEX IF NOT EXISTS idx_audit_log_store ON audit_log(tenant_id, store_id, id);
+CREATE INDEX IF NOT EXISTS idx_audit_log_actor ON audit_log(tenant_id, actor_id, id);
+CREATE INDEX IF NOT EXISTS idx_audit_log_entities ON audit_log USING GIN (entity_ids);
+
+-- -----------------------------------------------------------------------------
+-- Append-only enforcement
+-- -----------------------------------------------------------------------------
+CREATE OR REPLACE FUNCTION reject_audit_log_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_log_no_update_delete BEFORE UPDATE OR DELETE ON audit_log
+    FOR EACH ROW EXECUTE FUNCTION reject_audit_log_change();
+
+CREATE TRIGGER audit_log_no_truncate BEFORE TRUNCATE ON audit_log
+    FOR EACH STATEMENT EXECUTE FUNCTION reject_audit_log_change();
@@ -0,0 +1,9 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Sale Correlation IDs
+-- =============================================================================
+--
+-- The correlation ID of the terminal command that produced the sale, as
+-- sent in SyncEntity.correlation_id. Lets support find the terminal logs
+-- for a cloud sale row. NULL for sales from older terminals.
+
+ALTER TABLE sales ADD COLUMN IF NOT EXISTS correlation_id TEXT;
@@ -0,0 +1,38 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Customer Erasure
+-- =============================================================================
+--
+-- "Right to erasure" requests, from the PrivacyService.ForgetCustomer RPC
+-- or uploaded by a store (CUSTOMER_ERASURE). The cloud anonymizes the
+-- store credit accounts it holds for the customer and sends the request to
+-- every store of the tenant, which anonymize their own copies.
+--
+-- Sales, payments and credit ledger entries are kept; only names and
+-- contact details are overwritten. The row itself is the erasure log: it
+-- keeps the identifiers the request named, not the erased data.
+
+-- Download cursor for erasures: one per request.
+CREATE SEQUENCE IF NOT EXISTS customer_erasure_route_seq;
+
+CREATE TABLE IF NOT EXISTS customer_erasures (
+    request_id TEXT PRIMARY KEY NOT NULL,
+    tenant_id TEXT NOT NULL REFERENCES tenants(id),
+
+    -- Subject (at least one is set)
+    business_customer_id TEXT,
+    phone TEXT,
+    email TEXT, -- Stored lowercase
+
+    reason TEXT,
+    requested_by TEXT NOT NULL, -- Cashier or back-office user
+    requested_at TIMESTAMPTZ NOT NULL,
+    origin_store_id TEXT, -- NULL when requested through the RPC
+
+    -- What the cloud anonymized
+    store_credit_accounts INTEGER NOT NULL DEFAULT 0,
+    erased_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    route_seq BIGINT NOT NULL DEFAULT nextval('customer_erasure_route_seq')
+);
+
+CREATE INDEX IF NOT EXISTS idx_customer_erasures_route ON customer_erasures(tenant_id, route_seq);
@@ -0,0 +1,22 @@
+-- =============================================================================
+-- Titan POS Cloud Database - PII Encryption
+-- =============================================================================
+--
+-- Customer phone numbers and emails are stored encrypted ("pii1:..."),
+-- with the same scheme as the terminals. Each tenant has its own data key,
+-- stored here wrapped with the master key (PII_MASTER_KEY), which is never
+-- in the database.
+--
+-- Lookups match a keyed hash of the normalized value instead of the value.
+-- Rows written before this migration are encrypted and hashed at startup.
+
+CREATE TABLE IF NOT EXISTS tenant_data_keys (
+    tenant_id TEXT PRIMARY KEY NOT NULL REFERENCES tenants(id),
+    wrapped_key TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE store_credit_accounts ADD COLUMN IF NOT EXISTS customer_phone_hash TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_store_credit_accounts_phone_hash
+    ON store_credit_accounts(tenant_id, customer_phone_hash);
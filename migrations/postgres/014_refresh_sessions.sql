@@ -0,0 +1,44 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Refresh Token Rotation
+-- =============================================================================
+--
+-- Every sign-in (store API key exchange or back-office login) starts a
+-- session. Each refresh token belongs to one session and can be used once:
+-- using it returns the next token of the chain.
+--
+--   exchange ──► T1 ──refresh──► T2 ──refresh──► T3 ...
+--                 │
+--                 └── presented again ──► session revoked (T2, T3 refused)
+--
+-- A refresh token presented a second time has leaked or been replayed, so
+-- the whole session is revoked and its owner must sign in again.
+
+-- -----------------------------------------------------------------------------
+-- Sessions
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS auth_sessions (
+    id TEXT PRIMARY KEY NOT NULL,
+    tenant_id TEXT NOT NULL REFERENCES tenants(id),
+    subject TEXT NOT NULL, -- Store ID or tenant user ID
+    device_id TEXT NOT NULL DEFAULT '', -- Empty for back-office users
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    revoked_at TIMESTAMPTZ,
+    revoked_reason TEXT -- 'REUSE' or 'LOGOUT'
+);
+
+CREATE INDEX IF NOT EXISTS idx_auth_sessions_subject ON auth_sessions(tenant_id, subject);
+
+-- -----------------------------------------------------------------------------
+-- Refresh Tokens
+-- -----------------------------------------------------------------------------
+-- Expired tokens are removed as their session rotates.
+CREATE TABLE IF NOT EXISTS refresh_tokens (
+    jti TEXT PRIMARY KEY NOT NULL, -- JWT ID of the token
+    session_id TEXT NOT NULL REFERENCES auth_sessions(id) ON DELETE CASCADE,
+    issued_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ -- Set when exchanged for the next token
+);
+
+CREATE INDEX IF NOT EXISTS idx_refresh_tokens_session ON refresh_tokens(session_id);
@@ -0,0 +1,45 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Queued Upload Batches
+-- =============================================================================
+--
+-- Large uploads are not processed inside the UploadBatch call. The batch is
+-- stored here, its ID is pushed onto a Redis list, and a worker processes it:
+--
+--   UploadBatch ──► upload_batches (QUEUED) ──LPUSH id──► Redis list
+--                                                          │ BRPOP
+--                                                          ▼
+--                   PROCESSING ──► COMPLETED (result)   worker
+--                        │
+--                        └── worker lost, MAX_ATTEMPTS reached ──► FAILED
+--
+-- The store polls GetBatchStatus for the outcome. The payload is dropped
+-- once the batch completes; finished batches are removed after a week.
+
+-- -----------------------------------------------------------------------------
+-- Upload Batches
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS upload_batches (
+    id TEXT PRIMARY KEY NOT NULL, -- Batch status ID given to the store
+    batch_id TEXT NOT NULL, -- The store's own batch ID
+    tenant_id TEXT NOT NULL REFERENCES tenants(id),
+    store_id TEXT NOT NULL,
+    device_id TEXT NOT NULL,
+
+    status TEXT NOT NULL DEFAULT 'QUEUED', -- QUEUED, PROCESSING, COMPLETED, FAILED
+    entity_count INTEGER NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+
+    payload BYTEA, -- Encoded UploadBatchRequest, until processed
+    result BYTEA, -- Encoded UploadBatchResponse, once COMPLETED
+    error_message TEXT, -- Set when FAILED
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ,
+
+    -- An upload retried after a lost response gets the same batch back
+    UNIQUE (store_id, batch_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_upload_batches_pending ON upload_batches(status, created_at)
+    WHERE status IN ('QUEUED', 'PROCESSING');
@@ -0,0 +1,32 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Entity Quarantine
+-- =============================================================================
+--
+-- Uploaded entities are applied by a processor for their entity type. A
+-- terminal newer than the cloud may upload a type the cloud has no
+-- processor for yet; such entities are kept here instead of being dropped:
+--
+--   SyncEntity (unknown type) ──► quarantined_entities
+--                                      │
+--   cloud upgraded with a processor ───┘──► replayed at startup, removed
+
+-- -----------------------------------------------------------------------------
+-- Quarantined Entities
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS quarantined_entities (
+    tenant_id TEXT NOT NULL REFERENCES tenants(id),
+    store_id TEXT NOT NULL,
+    device_id TEXT NOT NULL,
+
+    entity_type TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    payload BYTEA NOT NULL, -- Encoded SyncEntity, as uploaded
+
+    seen_count INTEGER NOT NULL DEFAULT 1, -- Uploads of this entity
+    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (store_id, entity_type, entity_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_quarantined_entities_type ON quarantined_entities(entity_type, first_seen_at);
@@ -0,0 +1,61 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Store Assortments
+-- =============================================================================
+--
+-- By default every store downloads the whole tenant catalog. A store with
+-- an assortment only gets the products on its list:
+--
+--   stores.assortment_scoped = FALSE ──► every tenant product
+--   stores.assortment_scoped = TRUE  ──► products in store_assortments
+--
+-- Products removed from an assortment are not deleted at the store; they
+-- just stop receiving updates.
+
+ALTER TABLE stores ADD COLUMN IF NOT EXISTS assortment_scoped BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- -----------------------------------------------------------------------------
+-- Store Assortments
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS store_assortments (
+    store_id TEXT NOT NULL REFERENCES stores(id) ON DELETE CASCADE,
+    product_id TEXT NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    added_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (store_id, product_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_store_assortments_product ON store_assortments(product_id);
+
+-- -----------------------------------------------------------------------------
+-- Function: Queue a download for all stores in a tenant
+-- -----------------------------------------------------------------------------
+-- Replaces the version from 002: product changes skip scoped stores that do
+-- not carry the product. Deletes still go to every store.
+CREATE OR REPLACE FUNCTION queue_download_for_tenant(
+    p_tenant_id TEXT,
+    p_entity_type TEXT,
+    p_entity_id TEXT,
+    p_operation TEXT,
+    p_payload JSONB
+) RETURNS VOID AS $$
+DECLARE
+    v_store_id TEXT;
+BEGIN
+    FOR v_store_id IN
+        SELECT s.id FROM stores s
+        WHERE s.tenant_id = p_tenant_id
+          AND s.is_active = TRUE
+          AND (p_entity_type <> 'PRODUCT'
+               OR p_operation = 'DELETE' -- the assortment row is already gone
+               OR NOT s.assortment_scoped
+               OR EXISTS (
+                   SELECT 1 FROM store_assortments a
+                   WHERE a.store_id = s.id AND a.product_id = p_entity_id
+               ))
+    LOOP
+        PERFORM queue_download_for_store(
+            p_tenant_id, v_store_id, p_entity_type, p_entity_id, p_operation, p_payload
+        );
+    END LOOP;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,35 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Store Price Overrides
+-- =============================================================================
+--
+-- products.price_cents is the tenant's base price. A store can sell a
+-- product at its own price:
+--
+--   store_price_overrides row ──► the store downloads the override as the
+--                                 price, with the base price alongside
+--   no row                    ──► the store downloads the base price
+--
+-- Stores record the base price on sale items sold at an override, so
+-- reports can tell local pricing from tenant pricing.
+
+-- -----------------------------------------------------------------------------
+-- Store Price Overrides
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS store_price_overrides (
+    store_id TEXT NOT NULL REFERENCES stores(id) ON DELETE CASCADE,
+    product_id TEXT NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    price_cents BIGINT NOT NULL CHECK (price_cents >= 0),
+    set_by TEXT NOT NULL, -- User who set the override
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (store_id, product_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_store_price_overrides_product
+    ON store_price_overrides(product_id);
+
+-- -----------------------------------------------------------------------------
+-- Sale Items - price provenance
+-- -----------------------------------------------------------------------------
+ALTER TABLE sale_items
+    ADD COLUMN IF NOT EXISTS base_price_cents BIGINT; -- NULL when sold at the base price
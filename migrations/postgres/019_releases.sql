@@ -0,0 +1,40 @@
+-- =============================================================================
+-- Titan POS Cloud Database - App Releases
+-- =============================================================================
+--
+-- Desktop app builds published by the release pipeline. Terminals ask
+-- ConfigService.GetLatestRelease for the newest build on their channel:
+--
+--   beta channel   ──► newest of the beta and stable releases
+--   stable channel ──► newest stable release
+--
+-- rollout_percent stages a release across stores: each store falls in a
+-- fixed bucket (0-99) per version, and is offered the release once the
+-- bucket is below rollout_percent. Raising the percentage widens the
+-- rollout without moving stores already in it. Mandatory releases go to
+-- every store. A withdrawn release is no longer offered.
+
+-- -----------------------------------------------------------------------------
+-- Releases
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS releases (
+    id TEXT PRIMARY KEY,
+    version TEXT NOT NULL,      -- Semantic version, e.g. "1.4.0" or "1.5.0-beta.2"
+    channel TEXT NOT NULL CHECK (channel IN ('stable', 'beta')),
+    platform TEXT NOT NULL,     -- e.g. "windows-x86_64"
+    download_url TEXT NOT NULL,
+    sha256 TEXT NOT NULL,       -- Hex digest of the bundle
+    signature TEXT NOT NULL,    -- Hex Ed25519 signature of the bundle
+    notes TEXT,
+    rollout_percent INTEGER NOT NULL DEFAULT 100
+        CHECK (rollout_percent BETWEEN 0 AND 100),
+    mandatory BOOLEAN NOT NULL DEFAULT FALSE,
+    published_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    withdrawn_at TIMESTAMPTZ,
+
+    UNIQUE (channel, platform, version)
+);
+
+CREATE INDEX IF NOT EXISTS idx_releases_platform
+    ON releases(platform, channel)
+    WHERE withdrawn_at IS NULL;
@@ -0,0 +1,34 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Feature Flags
+-- =============================================================================
+--
+-- Switches for rolling new subsystems (loyalty, gift cards, ...) out one
+-- tenant or store at a time. Terminals fetch the flags in effect for their
+-- store through ConfigService.GetFeatureFlags and cache them locally:
+--
+--   store row (store_id set)  ──► wins for that store
+--   tenant row (store_id NULL) ──► default for every store of the tenant
+--   no row                    ──► off
+--
+-- A flag switched on for the tenant can be held back in one store with a
+-- store row that is off, and the other way round.
+
+-- -----------------------------------------------------------------------------
+-- Feature Flags
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS feature_flags (
+    tenant_id TEXT NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    store_id TEXT REFERENCES stores(id) ON DELETE CASCADE, -- NULL = tenant-wide
+    key TEXT NOT NULL,
+    enabled BOOLEAN NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- One tenant-wide row and one row per store for each key
+CREATE UNIQUE INDEX IF NOT EXISTS idx_feature_flags_tenant
+    ON feature_flags(tenant_id, key)
+    WHERE store_id IS NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_feature_flags_store
+    ON feature_flags(store_id, key)
+    WHERE store_id IS NOT NULL;
@@ -0,0 +1,16 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Sales Tax Report
+-- =============================================================================
+--
+-- ReportService.GetTaxReport sums a tenant's completed sales by completion
+-- time. sale_items already carry the rate each line was taxed at
+-- (tax_rate_bps); jurisdictions are named from tax_rates.
+
+-- Tax report: completed sales of a tenant by completion time
+CREATE INDEX IF NOT EXISTS idx_sales_tenant_completed
+    ON sales(tenant_id, completed_at)
+    WHERE status = 'COMPLETED';
+
+-- Tax report: naming the jurisdiction of a rate
+CREATE INDEX IF NOT EXISTS idx_tax_rates_tenant_rate
+    ON tax_rates(tenant_id, rate_bps);
@@ -0,0 +1,25 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Product Attributes
+-- =============================================================================
+--
+-- Key-value attributes and tags on products (size, color, vintage, ...),
+-- one row per (product, key). A tag is a key with an empty value. Keys
+-- are stored normalized (lowercase; letters, digits, '_', '-', '.'), the
+-- way terminals normalize them.
+--
+-- Attributes travel down with their product (Product.attributes), so a
+-- change to a product's attributes must bump products.version.
+
+-- -----------------------------------------------------------------------------
+-- Product Attributes
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS product_attributes (
+    product_id TEXT NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL DEFAULT '',
+    PRIMARY KEY (product_id, key)
+);
+
+-- Attribute search across the catalog
+CREATE INDEX IF NOT EXISTS idx_product_attributes_key_value
+    ON product_attributes(key, lower(value));
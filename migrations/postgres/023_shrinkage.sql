@@ -0,0 +1,15 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Shrinkage
+-- =============================================================================
+--
+-- Terminals upload waste write-offs as inventory deltas with reason
+-- DAMAGE, EXPIRY or THEFT and the retail value of the goods, which
+-- ReportService.GetShrinkageReport sums per store and reason.
+
+ALTER TABLE inventory_deltas
+    ADD COLUMN IF NOT EXISTS value_cents BIGINT NOT NULL DEFAULT 0;
+
+-- Shrinkage report: a tenant's waste deltas by time
+CREATE INDEX IF NOT EXISTS idx_inventory_deltas_tenant_waste
+    ON inventory_deltas(tenant_id, created_at)
+    WHERE reason IN ('DAMAGE', 'EXPIRY', 'THEFT');
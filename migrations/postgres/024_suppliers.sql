@@ -0,0 +1,45 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Suppliers
+-- =============================================================================
+--
+-- Suppliers and their price lists, uploaded by the tenant's terminals. A
+-- supplier is replaced with its whole price list when a higher version
+-- arrives; costs are per each.
+
+-- -----------------------------------------------------------------------------
+-- Suppliers
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS suppliers (
+    id TEXT PRIMARY KEY NOT NULL,
+    tenant_id TEXT NOT NULL REFERENCES tenants(id),
+
+    name TEXT NOT NULL,
+    contact_name TEXT,
+    phone TEXT,
+    email TEXT,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+
+    -- Timestamps
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+
+    -- Versioning
+    version BIGINT NOT NULL DEFAULT 1
+);
+
+CREATE INDEX IF NOT EXISTS idx_suppliers_tenant ON suppliers(tenant_id);
+
+-- -----------------------------------------------------------------------------
+-- Supplier Items
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS supplier_items (
+    supplier_id TEXT NOT NULL REFERENCES suppliers(id) ON DELETE CASCADE,
+    product_id TEXT NOT NULL,
+
+    supplier_sku TEXT,
+    cost_cents BIGINT NOT NULL,
+
+    PRIMARY KEY (supplier_id, product_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_supplier_items_product ON supplier_items(product_id);
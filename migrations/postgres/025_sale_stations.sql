@@ -0,0 +1,11 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Sale Stations
+-- =============================================================================
+--
+-- The register (station) a sale was rung up on, as uploaded by the
+-- terminal. NULL for sales uploaded before terminals recorded it.
+
+ALTER TABLE sales ADD COLUMN IF NOT EXISTS station_number INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_sales_store_station
+    ON sales(store_id, station_number, created_at);
@@ -0,0 +1,66 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Store Presence and Webhooks
+-- =============================================================================
+--
+-- Whether each store hub is reachable, from the messages it sends on its
+-- notification stream, and the tenant webhooks told when that changes:
+--
+--   message on the stream          ──► last_seen_at = now
+--                                      offline ──► online   (store.online)
+--   silent STORE_OFFLINE_AFTER_SECS ──► online ──► offline  (store.offline)
+--
+-- Each event is queued in webhook_deliveries once per subscribed webhook
+-- and POSTed by the dispatcher, with retries, until the endpoint answers
+-- 2xx or the attempts run out.
+
+-- -----------------------------------------------------------------------------
+-- Store Presence
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS store_presence (
+    store_id TEXT PRIMARY KEY NOT NULL REFERENCES stores(id) ON DELETE CASCADE,
+    last_seen_at TIMESTAMPTZ NOT NULL,
+    online BOOLEAN NOT NULL,
+    status_since TIMESTAMPTZ NOT NULL  -- last online/offline change
+);
+
+-- The presence monitor looks for online stores gone quiet
+CREATE INDEX IF NOT EXISTS idx_store_presence_online
+    ON store_presence(last_seen_at)
+    WHERE online;
+
+-- -----------------------------------------------------------------------------
+-- Tenant Webhooks
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS tenant_webhooks (
+    id TEXT PRIMARY KEY NOT NULL,
+    tenant_id TEXT NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,              -- HMAC-SHA256 key for X-Titan-Signature
+    events TEXT[] NOT NULL DEFAULT '{}', -- e.g. {store.offline}; empty = all
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_tenant_webhooks_tenant
+    ON tenant_webhooks(tenant_id)
+    WHERE enabled;
+
+-- -----------------------------------------------------------------------------
+-- Webhook Deliveries
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id BIGSERIAL PRIMARY KEY,
+    webhook_id TEXT NOT NULL REFERENCES tenant_webhooks(id) ON DELETE CASCADE,
+    event TEXT NOT NULL,
+    payload TEXT NOT NULL,             -- JSON body, signed as sent
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_error TEXT,
+    delivered_at TIMESTAMPTZ,
+    failed_at TIMESTAMPTZ,             -- gave up after the last attempt
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due
+    ON webhook_deliveries(next_attempt_at)
+    WHERE delivered_at IS NULL AND failed_at IS NULL;
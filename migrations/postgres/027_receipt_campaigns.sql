@@ -0,0 +1,11 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Receipt Footer Campaigns
+-- =============================================================================
+--
+-- Promotions printed under the customer's receipt, set per store as the
+-- `receipt_campaigns` config value: a JSON array of campaigns (survey
+-- invitation, next-purchase coupon, or a message for baskets that match a
+-- condition). Terminals fetch the array with the store configuration and
+-- evaluate it when they print the original receipt of a sale.
+
+ALTER TABLE store_configs ADD COLUMN IF NOT EXISTS receipt_campaigns TEXT;
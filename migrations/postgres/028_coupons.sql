@@ -0,0 +1,66 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Coupons
+-- =============================================================================
+--
+-- Coupons shared by every store of a tenant. The back office writes the
+-- definitions (UpsertCoupon); stores upload the redemptions they recorded,
+-- and every change is sent to all stores of the tenant:
+--
+-- - Definitions are only changed here; a newer version replaces the old one
+-- - Redemptions are append-only and merged by id (never updated)
+-- - uses = COUNT(coupon_redemptions)
+--
+-- Uses are checked at the terminal that rings up the sale. A single-use code
+-- spent in two stores while both were offline from the cloud shows up here
+-- as more redemptions than max_uses.
+
+-- Download cursor for coupons: bumped every time a coupon changes.
+CREATE SEQUENCE IF NOT EXISTS coupon_route_seq;
+
+-- -----------------------------------------------------------------------------
+-- Coupons
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS coupons (
+    id TEXT PRIMARY KEY NOT NULL,
+    tenant_id TEXT NOT NULL REFERENCES tenants(id),
+    code TEXT NOT NULL,
+    description TEXT,
+
+    kind TEXT NOT NULL, -- PERCENT, AMOUNT
+    value BIGINT NOT NULL CHECK (value > 0), -- bps for PERCENT, cents for AMOUNT
+
+    min_subtotal_cents BIGINT,
+    product_ids TEXT[] NOT NULL DEFAULT '{}', -- empty for the whole cart
+    starts_at TIMESTAMPTZ,
+    expires_at TIMESTAMPTZ,
+    max_uses BIGINT, -- 1 = single-use, NULL = unlimited
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+
+    -- Timestamps
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+
+    -- Versioning
+    version BIGINT NOT NULL DEFAULT 1,
+    route_seq BIGINT NOT NULL DEFAULT nextval('coupon_route_seq'),
+
+    UNIQUE (tenant_id, code)
+);
+
+CREATE INDEX IF NOT EXISTS idx_coupons_route ON coupons(tenant_id, route_seq);
+
+-- -----------------------------------------------------------------------------
+-- Coupon Redemptions
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS coupon_redemptions (
+    id TEXT PRIMARY KEY NOT NULL,
+    coupon_id TEXT NOT NULL REFERENCES coupons(id) ON DELETE CASCADE,
+
+    sale_id TEXT NOT NULL,
+    store_id TEXT NOT NULL,
+    device_id TEXT NOT NULL,
+    discount_cents BIGINT NOT NULL,
+    redeemed_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_coupon_redemptions_coupon ON coupon_redemptions(coupon_id);
@@ -0,0 +1,56 @@
+-- =============================================================================
+-- Titan POS Cloud Database - Loyalty
+-- =============================================================================
+--
+-- Loyalty members shared by every store of a tenant. Store hubs upload the
+-- accounts they touched; the cloud merges them and sends every change to
+-- all stores of the tenant:
+--
+-- - Ledger entries are append-only and merged by id (never updated)
+-- - points = SUM(points), rewards = SUM(reward_cents)
+--
+-- Rewards are spent through RedeemLoyaltyReward, which checks the balance
+-- here under a row lock, so the same rewards cannot be spent in two stores.
+-- Only a terminal running without a hub spends against its own copy.
+
+-- Download cursor for loyalty: bumped every time an account changes.
+CREATE SEQUENCE IF NOT EXISTS loyalty_route_seq;
+
+-- -----------------------------------------------------------------------------
+-- Loyalty Accounts
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS loyalty_accounts (
+    id TEXT PRIMARY KEY NOT NULL,
+    tenant_id TEXT NOT NULL REFERENCES tenants(id),
+    member_number TEXT NOT NULL,
+
+    -- Timestamps
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+
+    -- Versioning
+    version BIGINT NOT NULL DEFAULT 1,
+    route_seq BIGINT NOT NULL DEFAULT nextval('loyalty_route_seq'),
+
+    UNIQUE (tenant_id, member_number)
+);
+
+CREATE INDEX IF NOT EXISTS idx_loyalty_accounts_route ON loyalty_accounts(tenant_id, route_seq);
+
+-- -----------------------------------------------------------------------------
+-- Loyalty Entries
+-- -----------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS loyalty_entries (
+    id TEXT PRIMARY KEY NOT NULL,
+    account_id TEXT NOT NULL REFERENCES loyalty_accounts(id) ON DELETE CASCADE,
+
+    kind TEXT NOT NULL, -- EARN, REWARD, REDEEM
+    points BIGINT NOT NULL DEFAULT 0,
+    reward_cents BIGINT NOT NULL DEFAULT 0,
+
+    sale_id TEXT,
+    device_id TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_loyalty_entries_account ON loyalty_entries(account_id);
@@ -0,0 +1,97 @@
+-- =============================================================================
+-- Titan POS: Till Sessions and Blind Close
+-- Migration: 004_till_sessions.sql
+-- =============================================================================
+--
+-- This migration adds till (cash drawer) sessions with blind-close support.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                         Till Tables                                     │
+-- │                                                                         │
+-- │  till_sessions ◄─────── till_counts (per-denomination blind count)      │
+-- │  (one open session per device)                                          │
+-- │                                                                         │
+-- │  expected = opening_float + Σ cash payments on completed sales          │
+-- │             (same device, completed while the session was open)         │
+-- │  variance = counted - expected                                          │
+-- │             > 0 → OVER     < 0 → SHORT                                  │
+-- │                                                                         │
+-- │  The exceptions report groups closed sessions by user_id and flags      │
+-- │  cashiers with repeated large |variance|.                               │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+-- =============================================================================
+-- Till Sessions Table
+-- =============================================================================
+-- Status flow: open → closed
+--
+-- expected_cash_cents / counted_cash_cents / variance_cents stay NULL until
+-- the session is closed. During a blind close the expected amount is computed
+-- server-side and never returned to the cashier.
+
+CREATE TABLE IF NOT EXISTS till_sessions (
+    id TEXT PRIMARY KEY NOT NULL,
+    tenant_id TEXT NOT NULL DEFAULT '00000000-0000-0000-0000-000000000001',
+
+    -- Who/where
+    device_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+
+    -- Status: open, closed
+    status TEXT NOT NULL DEFAULT 'open',
+
+    -- Cash amounts (all in cents)
+    opening_float_cents INTEGER NOT NULL DEFAULT 0,
+    expected_cash_cents INTEGER,
+    counted_cash_cents INTEGER,
+    variance_cents INTEGER,
+
+    -- Close details
+    blind_close INTEGER NOT NULL DEFAULT 1,
+    closed_by TEXT,
+    notes TEXT,
+
+    -- Timestamps
+    opened_at TEXT NOT NULL DEFAULT (datetime('now')),
+    closed_at TEXT,
+
+    -- CRDT support
+    sync_version INTEGER NOT NULL DEFAULT 0
+);
+
+-- Only one open drawer per device
+CREATE UNIQUE INDEX IF NOT EXISTS idx_till_sessions_open_device
+    ON till_sessions(device_id) WHERE status = 'open';
+
+-- Exceptions report: closed sessions per cashier over time
+CREATE INDEX IF NOT EXISTS idx_till_sessions_user_closed
+    ON till_sessions(user_id, closed_at) WHERE status = 'closed';
+
+-- =============================================================================
+-- Till Counts Table
+-- =============================================================================
+-- Per-denomination quantities entered during the blind count.
+--
+-- Example for a single close:
+--   denomination_cents: 2000  quantity: 4   total_cents: 8000
+--   denomination_cents: 25    quantity: 12  total_cents: 300
+
+CREATE TABLE IF NOT EXISTS till_counts (
+    id TEXT PRIMARY KEY NOT NULL,
+    session_id TEXT NOT NULL,
+
+    denomination_cents INTEGER NOT NULL,
+    quantity INTEGER NOT NULL,
+    total_cents INTEGER NOT NULL,
+
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    FOREIGN KEY (session_id) REFERENCES till_sessions(id),
+    UNIQUE(session_id, denomination_cents)
+);
+
+CREATE INDEX IF NOT EXISTS idx_till_counts_session ON till_counts(session_id);
@@ -0,0 +1,97 @@
+-- =============================================================================
+-- Titan POS: Quotes (Estimates)
+-- Migration: 005_quotes.sql
+-- =============================================================================
+--
+-- This migration adds customer-facing quotes that can be converted to sales.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                         Quote Tables                                    │
+-- │                                                                         │
+-- │  quotes ◄─────── quote_items (frozen prices, snapshot pattern)          │
+-- │    │                                                                    │
+-- │    └── converted_sale_id ──► sales.id (set when converted)              │
+-- │                                                                         │
+-- │  Quotes are synced (entity type QUOTE) so a quote created on one        │
+-- │  terminal can be looked up and converted on another.                    │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+-- =============================================================================
+-- Quotes Table
+-- =============================================================================
+-- Status flow: open → converted | cancelled
+--
+-- Expiry is NOT a status: a quote is expired when valid_until < now. This
+-- keeps terminals consistent without a background job rewriting rows.
+
+CREATE TABLE IF NOT EXISTS quotes (
+    id TEXT PRIMARY KEY NOT NULL,
+    tenant_id TEXT NOT NULL DEFAULT '00000000-0000-0000-0000-000000000001',
+
+    -- Human-readable number printed on the document
+    quote_number TEXT NOT NULL UNIQUE,
+
+    -- Status: open, converted, cancelled
+    status TEXT NOT NULL DEFAULT 'open',
+
+    -- Customer (optional - walk-in quotes are allowed)
+    customer_name TEXT,
+    customer_email TEXT,
+
+    -- Totals (all in cents)
+    subtotal_cents INTEGER NOT NULL DEFAULT 0,
+    tax_cents INTEGER NOT NULL DEFAULT 0,
+    discount_cents INTEGER NOT NULL DEFAULT 0,
+    total_cents INTEGER NOT NULL DEFAULT 0,
+
+    -- Who/where
+    user_id TEXT NOT NULL,
+    device_id TEXT NOT NULL,
+    notes TEXT,
+
+    -- Validity and conversion
+    valid_until TEXT NOT NULL,
+    converted_sale_id TEXT,
+
+    -- Timestamps
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    -- CRDT support
+    sync_version INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_quotes_status ON quotes(status, valid_until);
+CREATE INDEX IF NOT EXISTS idx_quotes_customer ON quotes(customer_name);
+
+-- =============================================================================
+-- Quote Items Table
+-- =============================================================================
+-- Prices and tax rates are frozen at quote time so conversion preserves
+-- exactly what the customer was quoted.
+
+CREATE TABLE IF NOT EXISTS quote_items (
+    id TEXT PRIMARY KEY NOT NULL,
+    quote_id TEXT NOT NULL,
+    product_id TEXT NOT NULL,
+
+    -- Snapshot of product at quote time
+    sku_snapshot TEXT NOT NULL,
+    name_snapshot TEXT NOT NULL,
+    unit_price_cents INTEGER NOT NULL,
+    tax_rate_bps INTEGER NOT NULL DEFAULT 0,
+
+    quantity INTEGER NOT NULL,
+    line_total_cents INTEGER NOT NULL,
+    tax_cents INTEGER NOT NULL DEFAULT 0,
+
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    FOREIGN KEY (quote_id) REFERENCES quotes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_quote_items_quote ON quote_items(quote_id);
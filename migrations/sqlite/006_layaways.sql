@@ -0,0 +1,125 @@
+-- =============================================================================
+-- Titan POS: Layaway Orders
+-- Migration: 006_layaways.sql
+-- =============================================================================
+--
+-- This migration adds layaway orders: goods reserved against a deposit and
+-- paid off in instalments until pickup.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                        Layaway Tables                                   │
+-- │                                                                         │
+-- │  layaways ◄───┬─── layaway_items    (frozen prices, snapshot pattern)   │
+-- │    │          └─── layaway_payments (deposit, instalments, refund < 0)  │
+-- │    │                                                                    │
+-- │    └── sale_id ──► sales.id (set at pickup)                             │
+-- │                                                                         │
+-- │  Stock movements are recorded in inventory_deltas:                      │
+-- │    create  → delta = -qty, delta_type = 'layaway_reserve'               │
+-- │    cancel  → delta = +qty, delta_type = 'layaway_release'               │
+-- │    pickup  → no stock change (already reserved)                         │
+-- │  reference_type = 'layaway', reference_id = layaways.id                 │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+-- =============================================================================
+-- Layaways Table
+-- =============================================================================
+-- Status flow: active → completed | cancelled
+--
+-- paid_cents is the running net of layaway_payments, kept on the header so
+-- list views don't need to aggregate.
+
+CREATE TABLE IF NOT EXISTS layaways (
+    id TEXT PRIMARY KEY NOT NULL,
+    tenant_id TEXT NOT NULL DEFAULT '00000000-0000-0000-0000-000000000001',
+
+    -- Human-readable number printed on the customer's slip
+    layaway_number TEXT NOT NULL UNIQUE,
+
+    -- Status: active, completed, cancelled
+    status TEXT NOT NULL DEFAULT 'active',
+
+    -- Customer (required - goods are held for a named person)
+    customer_name TEXT NOT NULL,
+    customer_phone TEXT,
+
+    -- Totals (all in cents)
+    subtotal_cents INTEGER NOT NULL DEFAULT 0,
+    tax_cents INTEGER NOT NULL DEFAULT 0,
+    total_cents INTEGER NOT NULL DEFAULT 0,
+    paid_cents INTEGER NOT NULL DEFAULT 0,
+    restocking_fee_cents INTEGER NOT NULL DEFAULT 0,
+
+    -- Sale recorded at pickup
+    sale_id TEXT,
+
+    -- Who/where
+    user_id TEXT NOT NULL,
+    device_id TEXT NOT NULL,
+    notes TEXT,
+
+    -- Timestamps
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    closed_at TEXT,
+
+    -- CRDT support
+    sync_version INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_layaways_status ON layaways(status, created_at);
+CREATE INDEX IF NOT EXISTS idx_layaways_customer ON layaways(customer_name);
+
+-- =============================================================================
+-- Layaway Items Table
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS layaway_items (
+    id TEXT PRIMARY KEY NOT NULL,
+    layaway_id TEXT NOT NULL,
+    product_id TEXT NOT NULL,
+
+    -- Snapshot of product at layaway time
+    sku_snapshot TEXT NOT NULL,
+    name_snapshot TEXT NOT NULL,
+    unit_price_cents INTEGER NOT NULL,
+
+    quantity INTEGER NOT NULL,
+    line_total_cents INTEGER NOT NULL,
+    tax_cents INTEGER NOT NULL DEFAULT 0,
+
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    FOREIGN KEY (layaway_id) REFERENCES layaways(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_layaway_items_layaway ON layaway_items(layaway_id);
+
+-- =============================================================================
+-- Layaway Payments Table
+-- =============================================================================
+-- Refunds on cancellation are stored as negative amounts. Cash rows are
+-- included in the till's expected cash for the device that took them.
+
+CREATE TABLE IF NOT EXISTS layaway_payments (
+    id TEXT PRIMARY KEY NOT NULL,
+    layaway_id TEXT NOT NULL,
+
+    -- Method: cash, external_card
+    method TEXT NOT NULL,
+    amount_cents INTEGER NOT NULL,
+
+    -- Terminal that took (or refunded) the money
+    device_id TEXT NOT NULL,
+
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    FOREIGN KEY (layaway_id) REFERENCES layaways(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_layaway_payments_layaway ON layaway_payments(layaway_id);
+CREATE INDEX IF NOT EXISTS idx_layaway_payments_device ON layaway_payments(device_id, created_at);
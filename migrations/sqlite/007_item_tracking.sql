@@ -0,0 +1,48 @@
+-- =============================================================================
+-- Titan POS: Serial & Lot Tracking
+-- Migration: 007_item_tracking.sql
+-- =============================================================================
+--
+-- This migration adds serial/lot number capture for regulated products.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                      Item Tracking                                      │
+-- │                                                                         │
+-- │  products.item_tracking: none | serial | lot                            │
+-- │                                                                         │
+-- │  sale_items ◄─────── sale_item_tracking (one row per captured code)     │
+-- │                      serial: one row per unit sold                      │
+-- │                      lot:    one row per sale line                      │
+-- │                                                                         │
+-- │  Recall lookup: sale_item_tracking(kind, code) → sale_items → sales     │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+ALTER TABLE products ADD COLUMN item_tracking TEXT NOT NULL DEFAULT 'none';
+
+-- =============================================================================
+-- Sale Item Tracking Table
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS sale_item_tracking (
+    id TEXT PRIMARY KEY NOT NULL,
+    sale_id TEXT NOT NULL,
+    sale_item_id TEXT NOT NULL,
+    product_id TEXT NOT NULL,
+
+    -- Kind: serial, lot
+    kind TEXT NOT NULL,
+    code TEXT NOT NULL,
+
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    FOREIGN KEY (sale_id) REFERENCES sales(id) ON DELETE CASCADE,
+    FOREIGN KEY (sale_item_id) REFERENCES sale_items(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_sale_item_tracking_code ON sale_item_tracking(kind, code);
+CREATE INDEX IF NOT EXISTS idx_sale_item_tracking_sale ON sale_item_tracking(sale_id);
+CREATE INDEX IF NOT EXISTS idx_sale_item_tracking_item ON sale_item_tracking(sale_item_id);
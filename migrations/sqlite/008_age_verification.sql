@@ -0,0 +1,46 @@
+-- =============================================================================
+-- Titan POS: Age Verification
+-- Migration: 008_age_verification.sql
+-- =============================================================================
+--
+-- This migration adds minimum purchase ages for restricted products and the
+-- age check recorded on each sale that contains them.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                      Age Verification                                   │
+-- │                                                                         │
+-- │  products.min_purchase_age: NULL (unrestricted) | 1..99                 │
+-- │                                                                         │
+-- │  sales ◄─────── sale_age_verifications (one row per sale)               │
+-- │                 method: birthdate | id_scan                             │
+-- │                 required_age at time of check                           │
+-- │                 who checked, on which device, when                      │
+-- │                                                                         │
+-- │  Kept for compliance audits; never deleted with the cart.               │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+ALTER TABLE products ADD COLUMN min_purchase_age INTEGER;
+
+-- =============================================================================
+-- Sale Age Verifications Table
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS sale_age_verifications (
+    sale_id TEXT PRIMARY KEY NOT NULL,
+
+    required_age INTEGER NOT NULL,
+
+    -- Method: birthdate, id_scan
+    method TEXT NOT NULL,
+    birthdate TEXT,
+
+    verified_by TEXT NOT NULL,
+    device_id TEXT NOT NULL,
+    verified_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    FOREIGN KEY (sale_id) REFERENCES sales(id) ON DELETE CASCADE
+);
@@ -0,0 +1,75 @@
+-- =============================================================================
+-- Titan POS: Store Transfers
+-- Migration: 009_store_transfers.sql
+-- =============================================================================
+--
+-- This migration adds inventory transfer documents between stores of the
+-- same tenant. Both the sending and the receiving store keep a copy of the
+-- document; the cloud routes it between them.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                      Store Transfers                                    │
+-- │                                                                         │
+-- │  store_transfers ◄─────── store_transfer_items                          │
+-- │  status: in_transit │ received │ cancelled                              │
+-- │                                                                         │
+-- │  Stock movements (inventory_deltas, reference_type = 'transfer'):       │
+-- │    sender   create   → -quantity_sent      ('transfer_out')             │
+-- │    sender   cancel   → +quantity_sent      ('transfer_return')          │
+-- │    receiver receive  → +quantity_received  ('transfer_in')              │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+-- =============================================================================
+-- Store Transfers Table
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS store_transfers (
+    id TEXT PRIMARY KEY NOT NULL,
+    tenant_id TEXT NOT NULL,
+    transfer_number TEXT NOT NULL UNIQUE,
+
+    from_store_id TEXT NOT NULL,
+    to_store_id TEXT NOT NULL,
+
+    -- Status: in_transit, received, cancelled
+    status TEXT NOT NULL DEFAULT 'in_transit',
+
+    notes TEXT,
+    created_by TEXT NOT NULL,
+    received_by TEXT,
+
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    received_at TEXT,
+    cancelled_at TEXT,
+
+    sync_version INTEGER NOT NULL DEFAULT 1
+);
+
+CREATE INDEX IF NOT EXISTS idx_store_transfers_status ON store_transfers(status, created_at);
+CREATE INDEX IF NOT EXISTS idx_store_transfers_from ON store_transfers(from_store_id);
+CREATE INDEX IF NOT EXISTS idx_store_transfers_to ON store_transfers(to_store_id);
+
+-- =============================================================================
+-- Store Transfer Items Table
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS store_transfer_items (
+    id TEXT PRIMARY KEY NOT NULL,
+    transfer_id TEXT NOT NULL,
+    product_id TEXT NOT NULL,
+
+    sku_snapshot TEXT NOT NULL,
+    name_snapshot TEXT NOT NULL,
+
+    quantity_sent INTEGER NOT NULL,
+    quantity_received INTEGER,
+
+    FOREIGN KEY (transfer_id) REFERENCES store_transfers(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_store_transfer_items_transfer ON store_transfer_items(transfer_id);
@@ -0,0 +1,94 @@
+-- =============================================================================
+-- Titan POS: Store Credit & Returnless Refunds
+-- Migration: 010_store_credit.sql
+-- =============================================================================
+--
+-- This migration adds customer store credit and refunds against completed
+-- sales. A refund can be paid out in cash, back to the card, or issued as
+-- store credit; store credit can then be tendered on later sales.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                  Store Credit & Refunds                                 │
+-- │                                                                         │
+-- │  sales ◄──── sale_refunds ────► store_credit_accounts                   │
+-- │                                        ▲                                │
+-- │                                        │                                │
+-- │                               store_credit_entries                      │
+-- │                               issue  (+) / redeem (-)                   │
+-- │                                                                         │
+-- │  balance = SUM(store_credit_entries.amount_cents)                       │
+-- │  Entries are append-only; synced copies merge by entry id.              │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+-- =============================================================================
+-- Store Credit Accounts Table
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS store_credit_accounts (
+    id TEXT PRIMARY KEY NOT NULL,
+    tenant_id TEXT NOT NULL,
+    credit_number TEXT NOT NULL UNIQUE,
+
+    customer_name TEXT NOT NULL,
+    customer_phone TEXT,
+
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    sync_version INTEGER NOT NULL DEFAULT 1
+);
+
+CREATE INDEX IF NOT EXISTS idx_store_credit_accounts_phone ON store_credit_accounts(customer_phone);
+
+-- =============================================================================
+-- Store Credit Entries Table
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS store_credit_entries (
+    id TEXT PRIMARY KEY NOT NULL,
+    account_id TEXT NOT NULL,
+
+    -- Kind: issue, redeem
+    kind TEXT NOT NULL,
+    -- Signed amount in cents: positive for issue, negative for redeem
+    amount_cents INTEGER NOT NULL,
+
+    sale_id TEXT,
+    device_id TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    FOREIGN KEY (account_id) REFERENCES store_credit_accounts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_store_credit_entries_account ON store_credit_entries(account_id);
+
+-- =============================================================================
+-- Sale Refunds Table
+-- =============================================================================
+-- Returnless refunds: the customer keeps the goods, stock is not touched.
+
+CREATE TABLE IF NOT EXISTS sale_refunds (
+    id TEXT PRIMARY KEY NOT NULL,
+    sale_id TEXT NOT NULL,
+
+    amount_cents INTEGER NOT NULL CHECK (amount_cents > 0),
+
+    -- Destination: cash, external_card, store_credit
+    destination TEXT NOT NULL,
+    store_credit_id TEXT,
+
+    reason TEXT,
+    user_id TEXT NOT NULL,
+    device_id TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    FOREIGN KEY (sale_id) REFERENCES sales(id),
+    FOREIGN KEY (store_credit_id) REFERENCES store_credit_accounts(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_sale_refunds_sale ON sale_refunds(sale_id);
+CREATE INDEX IF NOT EXISTS idx_sale_refunds_device ON sale_refunds(device_id, created_at);
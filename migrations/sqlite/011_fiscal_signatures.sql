@@ -0,0 +1,43 @@
+-- =============================================================================
+-- Titan POS: Fiscal Signatures
+-- Migration: 011_fiscal_signatures.sql
+-- =============================================================================
+--
+-- This migration stores the fiscal signature produced for each finalized
+-- sale in markets that require signed, sequential receipts.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                      Fiscal Signatures                                  │
+-- │                                                                         │
+-- │  sales ◄─────── sale_fiscal_signatures (one row per sale)               │
+-- │                 backend + sequence: gapless per backend                 │
+-- │                 signature chains to previous_signature                  │
+-- │                 payload: exact text that was signed                     │
+-- │                                                                         │
+-- │  Kept for tax authority audits; never deleted.                          │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+-- =============================================================================
+-- Sale Fiscal Signatures Table
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS sale_fiscal_signatures (
+    sale_id TEXT PRIMARY KEY NOT NULL,
+
+    -- Backend: file_export, ...
+    backend TEXT NOT NULL,
+    sequence INTEGER NOT NULL CHECK (sequence > 0),
+
+    signature TEXT NOT NULL,
+    previous_signature TEXT,
+    payload TEXT NOT NULL,
+
+    signed_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    FOREIGN KEY (sale_id) REFERENCES sales(id),
+    UNIQUE (backend, sequence)
+);
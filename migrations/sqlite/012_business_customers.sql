@@ -0,0 +1,65 @@
+-- =============================================================================
+-- Titan POS: Business Customers & E-Invoicing
+-- Migration: 012_business_customers.sql
+-- =============================================================================
+--
+-- This migration adds business customers (buyers with a tax registration
+-- number) and the link from a sale to the customer it is invoiced to.
+-- Linked, completed sales can be exported as UBL e-invoices.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                  Business Customers & E-Invoicing                       │
+-- │                                                                         │
+-- │  business_customers (name, tax_id, address, country)                    │
+-- │          ▲                                                              │
+-- │          │                                                              │
+-- │  sale_invoice_customers (one row per invoiced sale) ───► sales          │
+-- │                                                                         │
+-- │  Sales without a row are receipt-only and never exported.               │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+-- =============================================================================
+-- Business Customers Table
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS business_customers (
+    id TEXT PRIMARY KEY NOT NULL,
+    tenant_id TEXT NOT NULL,
+
+    name TEXT NOT NULL,
+    -- Normalized: uppercase letters and digits only
+    tax_id TEXT NOT NULL,
+
+    address_line TEXT,
+    city TEXT,
+    postal_code TEXT,
+    -- ISO 3166-1 alpha-2
+    country_code TEXT NOT NULL,
+    email TEXT,
+
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    UNIQUE (tenant_id, tax_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_business_customers_name ON business_customers(name);
+
+-- =============================================================================
+-- Sale Invoice Customers Table
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS sale_invoice_customers (
+    sale_id TEXT PRIMARY KEY NOT NULL,
+    customer_id TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    FOREIGN KEY (sale_id) REFERENCES sales(id) ON DELETE CASCADE,
+    FOREIGN KEY (customer_id) REFERENCES business_customers(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_sale_invoice_customers_customer ON sale_invoice_customers(customer_id);
@@ -0,0 +1,44 @@
+-- =============================================================================
+-- Titan POS: Sync Payload Quarantine
+-- Migration: 013_sync_quarantine.sql
+-- =============================================================================
+--
+-- Outbox payloads are validated against typed schemas (titan-core
+-- sync_payload). A payload that fails is not sent or relayed; it is kept
+-- here with the reason so it can be inspected and repaired.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                       Sync Payload Quarantine                           │
+-- │                                                                         │
+-- │  sync_outbox ──(invalid, queued by an older build)──► sync_quarantine   │
+-- │                                        source = 'outbox'                │
+-- │                                                                         │
+-- │  hub: OutboxBatch entry ──(invalid)──────────────────► sync_quarantine  │
+-- │                                        source = <sending device id>     │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS sync_quarantine (
+    id TEXT PRIMARY KEY NOT NULL,
+    tenant_id TEXT NOT NULL DEFAULT '00000000-0000-0000-0000-000000000001',
+
+    -- 'outbox' or the device that sent the payload to the hub
+    source TEXT NOT NULL,
+
+    entity_type TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+
+    -- Payload exactly as received, and the schema version it claimed
+    payload TEXT NOT NULL,
+    schema_version INTEGER NOT NULL,
+
+    -- Why validation failed
+    reason TEXT NOT NULL,
+
+    quarantined_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_sync_quarantine_at ON sync_quarantine(quarantined_at);
@@ -0,0 +1,35 @@
+-- =============================================================================
+-- Titan POS: Last-Known-Good Hub
+-- Migration: 014_hub_cache.sql
+-- =============================================================================
+--
+-- A SECONDARY remembers the hub it last completed a handshake with. On the
+-- next start it probes that hub's /health endpoint first and only runs a
+-- full discovery scan when the probe fails; the hub rarely moves.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                       Last-Known-Good Hub                               │
+-- │                                                                         │
+-- │  Welcome received ──► upsert sync_hub_cache (store_id)                  │
+-- │                                                                         │
+-- │  agent start ──► cached hub? ──► GET /health ──► OK: connect            │
+-- │                       │                  └──────► fail: discovery      │
+-- │                       └── none ─────────────────► discovery            │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS sync_hub_cache (
+    -- One hub per store
+    store_id TEXT PRIMARY KEY NOT NULL,
+
+    -- WebSocket URL the handshake succeeded on
+    hub_url TEXT NOT NULL,
+
+    -- Election term the hub reported in its Welcome
+    election_term INTEGER NOT NULL DEFAULT 0,
+
+    connected_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
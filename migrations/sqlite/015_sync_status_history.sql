@@ -0,0 +1,45 @@
+-- =============================================================================
+-- Titan POS: Sync Status History
+-- Migration: 015_sync_status_history.sql
+-- =============================================================================
+--
+-- Every change of the terminal's hub connection state, kept as periods so
+-- outages can be listed with their cause and daily uptime worked out
+-- (titan-core sync_history). Used to show a flaky store network to the ISP.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                        Sync Status History                              │
+-- │                                                                         │
+-- │  transport state change ──► close the open row (ended_at, duration_ms)  │
+-- │                          └► insert a row for the new state             │
+-- │                                                                         │
+-- │  state   reason          started_at   ended_at   duration_ms            │
+-- │  ready   NULL            08:00        11:00      10800000               │
+-- │  backoff pong timeout    11:00        11:02      120000                 │
+-- │  ready   NULL            11:02        NULL       NULL   (current)       │
+-- │                                                                         │
+-- │  Rows older than the retention window are pruned when the agent starts. │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS sync_status_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+
+    -- Connection state: 'connecting', 'handshaking', 'ready', 'draining',
+    -- 'backoff' or 'idle'
+    state TEXT NOT NULL,
+
+    -- Why the previous state ended (set when a connection was lost)
+    reason TEXT,
+
+    started_at TEXT NOT NULL,
+
+    -- NULL for the current state
+    ended_at TEXT,
+    duration_ms INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_sync_status_history_started ON sync_status_history(started_at);
@@ -0,0 +1,12 @@
+-- =============================================================================
+-- Titan POS: Outbox Correlation IDs
+-- Migration: 016_outbox_correlation.sql
+-- =============================================================================
+--
+-- The correlation ID of the command that queued each outbox entry (see
+-- titan-db correlation), sent on to the hub and the cloud so one sale can
+-- be traced from the button press to its cloud row. NULL for entries
+-- queued outside a command (sync agent, older builds).
+-- =============================================================================
+
+ALTER TABLE sync_outbox ADD COLUMN correlation_id TEXT;
@@ -0,0 +1,57 @@
+-- =============================================================================
+-- Titan POS: Scheduled Jobs
+-- Migration: 017_scheduled_jobs.sql
+-- =============================================================================
+--
+-- Background jobs run by the desktop scheduler (maintenance, price
+-- activation, reconciliation, backups) and the history of their runs.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                        Scheduled Jobs                                   │
+-- │                                                                         │
+-- │  scheduled_jobs: one row per job the app registers                      │
+-- │    name (PK), schedule (cron), enabled, next_run_at, last run state     │
+-- │    The schedule and enabled flag are kept across restarts, so an        │
+-- │    edited schedule survives upgrades.                                   │
+-- │                                                                         │
+-- │  job_runs: one row per run                                              │
+-- │    'running' ──► 'succeeded' | 'failed' (error, duration_ms)            │
+-- │    Rows older than the retention window are pruned by a job.            │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS scheduled_jobs (
+    name TEXT PRIMARY KEY NOT NULL,
+
+    -- Cron expression in the store's local time
+    schedule TEXT NOT NULL,
+    enabled INTEGER NOT NULL DEFAULT 1,
+
+    last_run_at TEXT,
+    -- 'running', 'succeeded' or 'failed'
+    last_status TEXT,
+    next_run_at TEXT,
+
+    -- Failed runs since the last success (alerts at the threshold)
+    consecutive_failures INTEGER NOT NULL DEFAULT 0,
+
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS job_runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    job_name TEXT NOT NULL REFERENCES scheduled_jobs(name),
+
+    -- 'running', 'succeeded' or 'failed'
+    status TEXT NOT NULL DEFAULT 'running',
+    error TEXT,
+
+    started_at TEXT NOT NULL,
+    finished_at TEXT,
+    duration_ms INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_job_runs_job_started ON job_runs(job_name, started_at);
@@ -0,0 +1,18 @@
+-- =============================================================================
+-- Titan POS: Pagination Indexes
+-- Migration: 018_pagination_indexes.sql
+-- =============================================================================
+--
+-- Keyset pagination (titan-core page) walks results in a fixed sort order
+-- and resumes after the last row's keys. These indexes match those orders
+-- so every page is an index range scan, however deep:
+--   products:  active catalog by (name, id)
+--   sales:     history newest first by (created_at, id)
+--   business_customers: by (name, id)
+-- =============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_products_active_name ON products(is_active, name, id);
+
+CREATE INDEX IF NOT EXISTS idx_sales_created_id ON sales(created_at, id);
+
+CREATE INDEX IF NOT EXISTS idx_business_customers_name_id ON business_customers(name, id);
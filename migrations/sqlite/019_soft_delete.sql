@@ -0,0 +1,25 @@
+-- =============================================================================
+-- Titan POS: Soft Delete
+-- Migration: 019_soft_delete.sql
+-- =============================================================================
+--
+-- Products and business customers are deleted by setting deleted_at and
+-- restored by clearing it (titan-core tombstone). Deleted rows drop out of
+-- search, lookups and pages but stay for old sales and invoices that point
+-- at them. Each delete/restore bumps sync_version and is synced as a
+-- TOMBSTONE outbox entry.
+--
+-- is_active stays as "offered for sale": an inactive product is hidden
+-- from the POS but still listed in back-office screens; a deleted one is
+-- gone from both.
+-- =============================================================================
+
+ALTER TABLE products ADD COLUMN deleted_at TEXT;
+
+ALTER TABLE business_customers ADD COLUMN deleted_at TEXT;
+
+-- Customers had no sync version; tombstones need one to order changes.
+ALTER TABLE business_customers ADD COLUMN sync_version INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_products_deleted ON products(deleted_at)
+    WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,42 @@
+-- =============================================================================
+-- Titan POS: Customer Erasure Log
+-- Migration: 020_customer_erasure.sql
+-- =============================================================================
+--
+-- One row per customer erasure request applied on this device, whether it
+-- was made here or arrived through sync (titan-core erasure). The rows it
+-- anonymized are updated in place; this table records that it happened,
+-- who asked, and how many rows of each kind were touched.
+--
+-- Rows are keyed by request id: a request that arrives again (relayed by
+-- the hub and then routed by the cloud) is applied again, which changes
+-- nothing more, and is not logged twice.
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS erasure_log (
+    request_id TEXT PRIMARY KEY NOT NULL,
+    tenant_id TEXT NOT NULL,
+
+    -- Subject identifiers, kept as evidence of what was erased
+    business_customer_id TEXT,
+    phone TEXT,
+    email TEXT,
+
+    reason TEXT,
+    requested_by TEXT NOT NULL,
+    requested_at TEXT NOT NULL,
+
+    -- Origin: local, sync
+    origin TEXT NOT NULL,
+
+    -- Rows anonymized, per kind
+    business_customers INTEGER NOT NULL DEFAULT 0,
+    age_verifications INTEGER NOT NULL DEFAULT 0,
+    store_credit_accounts INTEGER NOT NULL DEFAULT 0,
+    layaways INTEGER NOT NULL DEFAULT 0,
+    quotes INTEGER NOT NULL DEFAULT 0,
+
+    erased_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_erasure_log_erased ON erasure_log(erased_at);
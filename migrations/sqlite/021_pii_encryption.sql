@@ -0,0 +1,37 @@
+-- =============================================================================
+-- Titan POS - PII Encryption
+-- =============================================================================
+--
+-- Customer emails and phone numbers are stored encrypted ("pii1:..."), see
+-- titan_db::pii. Each gets a *_hash column holding a keyed hash of the
+-- normalized value, which is what lookups match on.
+--
+-- Rows written before this migration hold plain text; they are encrypted
+-- and hashed when the database is first opened with the tenant's key.
+
+-- -----------------------------------------------------------------------------
+-- Tenant Data Keys
+-- -----------------------------------------------------------------------------
+-- One random data key per tenant, encrypted with the master key held
+-- outside the database.
+CREATE TABLE IF NOT EXISTS pii_keys (
+    tenant_id TEXT PRIMARY KEY NOT NULL,
+    wrapped_key TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+-- -----------------------------------------------------------------------------
+-- Lookup Hashes
+-- -----------------------------------------------------------------------------
+ALTER TABLE business_customers ADD COLUMN email_hash TEXT;
+ALTER TABLE quotes ADD COLUMN customer_email_hash TEXT;
+ALTER TABLE layaways ADD COLUMN customer_phone_hash TEXT;
+ALTER TABLE store_credit_accounts ADD COLUMN customer_phone_hash TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_business_customers_email_hash ON business_customers(email_hash);
+CREATE INDEX IF NOT EXISTS idx_quotes_customer_email_hash ON quotes(customer_email_hash);
+CREATE INDEX IF NOT EXISTS idx_layaways_customer_phone_hash ON layaways(customer_phone_hash);
+CREATE INDEX IF NOT EXISTS idx_store_credit_accounts_phone_hash ON store_credit_accounts(customer_phone_hash);
+
+-- Ciphertext is never compared
+DROP INDEX IF EXISTS idx_store_credit_accounts_phone;
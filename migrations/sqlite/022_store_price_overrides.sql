@@ -0,0 +1,27 @@
+-- =============================================================================
+-- Titan POS: Store Price Overrides
+-- Migration: 022_store_price_overrides.sql
+-- =============================================================================
+--
+-- The cloud sends each store its own price for a product: the tenant base
+-- price, or the store's override of it. This migration keeps where the
+-- price came from, on the product and on the sale items sold at it.
+--
+-- ## Column Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                      Price Provenance                                   │
+-- │                                                                         │
+-- │  products.price_cents        price charged at this store                │
+-- │  products.base_price_cents   NULL: price_cents is the tenant price      │
+-- │                              set:  tenant price; price_cents is a       │
+-- │                                    store override                       │
+-- │                                                                         │
+-- │  sale_items.base_price_cents frozen at sale time, like unit_price_cents │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+ALTER TABLE products ADD COLUMN base_price_cents INTEGER;
+
+ALTER TABLE sale_items ADD COLUMN base_price_cents INTEGER;
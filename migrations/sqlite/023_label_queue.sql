@@ -0,0 +1,61 @@
+-- =============================================================================
+-- Titan POS: Shelf Label Queue
+-- Migration: 023_label_queue.sql
+-- =============================================================================
+--
+-- Shelf labels waiting to be printed, and the printer templates they are
+-- rendered with (see titan_core::label).
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                        Shelf Labels                                     │
+-- │                                                                         │
+-- │  label_queue: labels to print for one product                           │
+-- │    'pending' ──► 'printed' (printed_at)                                 │
+-- │    At most one pending row per product: another price change before    │
+-- │    printing updates price_cents and keeps old_price_cents.              │
+-- │                                                                         │
+-- │  label_templates: ZPL or EPL bodies with {placeholders}                 │
+-- │    At most one is_default template.                                     │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS label_queue (
+    id TEXT PRIMARY KEY NOT NULL,
+    product_id TEXT NOT NULL REFERENCES products(id),
+
+    -- 'price_change' or 'manual'
+    reason TEXT NOT NULL DEFAULT 'price_change',
+    -- 'pending' or 'printed'
+    status TEXT NOT NULL DEFAULT 'pending',
+
+    -- Price before the first change since the last print (NULL: manual)
+    old_price_cents INTEGER,
+    price_cents INTEGER NOT NULL,
+    copies INTEGER NOT NULL DEFAULT 1 CHECK (copies > 0),
+
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    printed_at TEXT
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_label_queue_pending_product
+    ON label_queue(product_id) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_label_queue_status_created ON label_queue(status, created_at);
+
+CREATE TABLE IF NOT EXISTS label_templates (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    -- 'zpl' or 'epl'
+    format TEXT NOT NULL DEFAULT 'zpl',
+    body TEXT NOT NULL,
+    is_default INTEGER NOT NULL DEFAULT 0,
+
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_label_templates_default
+    ON label_templates(is_default) WHERE is_default = 1;
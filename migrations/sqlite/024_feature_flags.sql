@@ -0,0 +1,34 @@
+-- =============================================================================
+-- Titan POS: Feature Flag Cache
+-- Migration: 024_feature_flags.sql
+-- =============================================================================
+--
+-- The feature flags the cloud last reported for this store (see
+-- titan_core::feature). One row, replaced on every successful fetch.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                       Feature Flag Cache                                │
+-- │                                                                         │
+-- │  cloud GetFeatureFlags ──► replace row (flags, ttl_secs, fetched_at)   │
+-- │                                                                         │
+-- │  flag check ──► read row ──► no row: every feature off                 │
+-- │                                                                         │
+-- │  fetched_at + ttl_secs passed ──► refresh; the row stays in effect     │
+-- │  until a fetch succeeds                                                 │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS feature_flag_cache (
+    -- Always 1: the cache holds a single snapshot
+    id INTEGER PRIMARY KEY NOT NULL CHECK (id = 1),
+
+    -- JSON object of flag key to true/false
+    flags TEXT NOT NULL DEFAULT '{}',
+
+    ttl_secs INTEGER NOT NULL,
+
+    fetched_at TEXT NOT NULL
+);
@@ -0,0 +1,42 @@
+-- =============================================================================
+-- Titan POS: Sale Item Tax Rate
+-- Migration: 025_sale_item_tax_rate.sql
+-- =============================================================================
+--
+-- Freezes the tax rate on each sale item, like its price, so the sales tax
+-- report (see titan_core::tax_report) can split sales by rate even after
+-- a product's rate changes.
+--
+-- ## Column Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                      Sale Item Tax Rate                                 │
+-- │                                                                         │
+-- │  sale_items.tax_rate_bps   rate the line was taxed at (825 = 8.25%)     │
+-- │                            NULL: unknown (layaway pickups, and older    │
+-- │                            lines with nothing to work it out from)      │
+-- │                                                                         │
+-- │  Existing lines get the rate their stored tax works out to,             │
+-- │  tax_cents × 10000 / line_total_cents to the nearest bps, or the        │
+-- │  product's current rate when it gives exactly that tax (the tax         │
+-- │  was rounded to the cent). Lines with no total stay NULL.               │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+ALTER TABLE sale_items ADD COLUMN tax_rate_bps INTEGER;
+
+UPDATE sale_items
+SET tax_rate_bps = COALESCE(
+    (SELECT p.tax_rate_bps FROM products p
+     WHERE p.id = sale_items.product_id
+       AND (sale_items.line_total_cents * p.tax_rate_bps + 5000) / 10000 = sale_items.tax_cents),
+    CAST(ROUND(sale_items.tax_cents * 10000.0 / sale_items.line_total_cents) AS INTEGER)
+)
+WHERE tax_rate_bps IS NULL
+  AND line_total_cents > 0;
+
+-- Tax report: completed sales by completion time
+CREATE INDEX IF NOT EXISTS idx_sales_completed
+    ON sales(completed_at)
+    WHERE status = 'completed';
@@ -0,0 +1,39 @@
+-- =============================================================================
+-- Titan POS: Product Attributes
+-- Migration: 026_product_attributes.sql
+-- =============================================================================
+--
+-- Key-value attributes and tags on products (see titan_core::attribute),
+-- kept in a child table so stores can add their own without a schema
+-- change.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                      Product Attributes                                 │
+-- │                                                                         │
+-- │  product_attributes: one row per (product, key)                         │
+-- │    key    normalized: lowercase, letters/digits/_/-/.                   │
+-- │    value  '' for a tag                                                  │
+-- │                                                                         │
+-- │  products.attributes_version / attributes_updated_at                    │
+-- │    version of the product's whole attribute set, which syncs as one    │
+-- │    PRODUCT_ATTRIBUTES document (newer version wins)                     │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS product_attributes (
+    product_id TEXT NOT NULL REFERENCES products(id),
+    key TEXT NOT NULL,
+    value TEXT NOT NULL DEFAULT '',
+
+    PRIMARY KEY (product_id, key)
+);
+
+-- Attribute search: products with a key, or a key and value
+CREATE INDEX IF NOT EXISTS idx_product_attributes_key_value
+    ON product_attributes(key, value COLLATE NOCASE);
+
+ALTER TABLE products ADD COLUMN attributes_version INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE products ADD COLUMN attributes_updated_at TEXT;
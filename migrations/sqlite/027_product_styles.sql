@@ -0,0 +1,40 @@
+-- =============================================================================
+-- Titan POS: Product Styles (Variants)
+-- Migration: 027_product_styles.sql
+-- =============================================================================
+--
+-- Styles group products that are variants of one item (one tee in several
+-- sizes and colors; see titan_core::variant). Each variant stays a full
+-- product row with its own SKU, barcode, price and stock.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                        Product Styles                                   │
+-- │                                                                         │
+-- │  product_styles: shared name and description                            │
+-- │    option_keys  attribute keys the variants differ by, comma-separated │
+-- │                 in display order ("size,color"); keys are normalized    │
+-- │                 and can hold no commas                                  │
+-- │                                                                         │
+-- │  products.style_id: the style a product is a variant of (NULL: none)   │
+-- │    The variant's option values are its product_attributes rows.        │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS product_styles (
+    id TEXT PRIMARY KEY NOT NULL,
+    tenant_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT,
+    option_keys TEXT NOT NULL,
+
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    sync_version INTEGER NOT NULL DEFAULT 1
+);
+
+ALTER TABLE products ADD COLUMN style_id TEXT REFERENCES product_styles(id);
+
+CREATE INDEX IF NOT EXISTS idx_products_style ON products(style_id) WHERE style_id IS NOT NULL;
@@ -0,0 +1,37 @@
+-- =============================================================================
+-- Titan POS: Bundle Products
+-- Migration: 028_product_bundles.sql
+-- =============================================================================
+--
+-- Bills of materials for bundle products (see titan_core::bundle): selling
+-- a bundle takes its components out of stock.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                      Bundle Components                                  │
+-- │                                                                         │
+-- │  bundle_components: one row per (bundle, component)                     │
+-- │    quantity   units of the component in one bundle                      │
+-- │                                                                         │
+-- │  products.bundle_version / bundle_updated_at                            │
+-- │    version of the product's bill of materials, which syncs as one      │
+-- │    PRODUCT_BUNDLE document (newer version wins)                         │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS bundle_components (
+    bundle_id TEXT NOT NULL REFERENCES products(id),
+    component_id TEXT NOT NULL REFERENCES products(id),
+    quantity INTEGER NOT NULL CHECK (quantity > 0),
+
+    PRIMARY KEY (bundle_id, component_id)
+);
+
+-- Which bundles a product is part of
+CREATE INDEX IF NOT EXISTS idx_bundle_components_component
+    ON bundle_components(component_id);
+
+ALTER TABLE products ADD COLUMN bundle_version INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE products ADD COLUMN bundle_updated_at TEXT;
@@ -0,0 +1,65 @@
+-- =============================================================================
+-- Titan POS: Pack Sizes and Stock Receipts
+-- Migration: 029_product_packs.sql
+-- =============================================================================
+--
+-- Case packs per product and goods received in them (see titan_core::pack).
+-- Stock stays in eaches; packs are converted when goods are received.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                      Packs and Receiving                                │
+-- │                                                                         │
+-- │  product_packs: one row per (product, pack name)                        │
+-- │    units    eaches in one pack                                          │
+-- │    barcode  case GTIN, scanned at receiving                             │
+-- │                                                                         │
+-- │  products.packs_version / packs_updated_at                              │
+-- │    version of the product's pack set, which syncs as one                │
+-- │    PRODUCT_PACKS document (newer version wins)                          │
+-- │                                                                         │
+-- │  stock_receipts ──< stock_receipt_items                                 │
+-- │    each line keeps its unit and pack size as received                  │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS product_packs (
+    product_id TEXT NOT NULL REFERENCES products(id),
+    name TEXT NOT NULL,
+    units INTEGER NOT NULL CHECK (units > 1),
+    barcode TEXT,
+
+    PRIMARY KEY (product_id, name)
+);
+
+-- Case barcode lookup at receiving
+CREATE INDEX IF NOT EXISTS idx_product_packs_barcode
+    ON product_packs(barcode) WHERE barcode IS NOT NULL;
+
+ALTER TABLE products ADD COLUMN packs_version INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE products ADD COLUMN packs_updated_at TEXT;
+
+CREATE TABLE IF NOT EXISTS stock_receipts (
+    id TEXT PRIMARY KEY NOT NULL,
+    reference TEXT,
+    received_by TEXT NOT NULL,
+    device_id TEXT NOT NULL,
+    received_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_stock_receipts_received_at
+    ON stock_receipts(received_at);
+
+CREATE TABLE IF NOT EXISTS stock_receipt_items (
+    receipt_id TEXT NOT NULL REFERENCES stock_receipts(id),
+    line_no INTEGER NOT NULL,
+    product_id TEXT NOT NULL REFERENCES products(id),
+    unit TEXT NOT NULL,
+    units_per_pack INTEGER NOT NULL,
+    quantity INTEGER NOT NULL,
+    units INTEGER NOT NULL,
+
+    PRIMARY KEY (receipt_id, line_no)
+);
@@ -0,0 +1,37 @@
+-- =============================================================================
+-- Titan POS: Waste and Shrinkage
+-- Migration: 030_waste_records.sql
+-- =============================================================================
+--
+-- Goods written off without being sold (see titan_core::waste).
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                      Waste Records                                      │
+-- │                                                                         │
+-- │  waste_records: one row per write-off                                   │
+-- │    reason            damage | expiry | theft                            │
+-- │    unit_price_cents  product price when recorded (report value)         │
+-- │                                                                         │
+-- │  Stock movements (inventory_deltas, reference_type = 'waste'):          │
+-- │    delta = -quantity, delta_type = reason, reference_id = record id     │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS waste_records (
+    id TEXT PRIMARY KEY NOT NULL,
+    product_id TEXT NOT NULL REFERENCES products(id),
+    quantity INTEGER NOT NULL CHECK (quantity > 0),
+    reason TEXT NOT NULL CHECK (reason IN ('damage', 'expiry', 'theft')),
+    unit_price_cents INTEGER NOT NULL,
+    note TEXT,
+    recorded_by TEXT NOT NULL,
+    device_id TEXT NOT NULL,
+    recorded_at TEXT NOT NULL
+);
+
+-- Daily waste report
+CREATE INDEX IF NOT EXISTS idx_waste_records_recorded_at
+    ON waste_records(recorded_at);
@@ -0,0 +1,72 @@
+-- =============================================================================
+-- Titan POS: Suppliers and Product Costs
+-- Migration: 031_suppliers.sql
+-- =============================================================================
+--
+-- Suppliers with their price lists, product costs maintained at receiving,
+-- and the cost frozen on each sale line for margin reporting (see
+-- titan_core::supplier).
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                      Suppliers and Costs                                │
+-- │                                                                         │
+-- │  suppliers ──< supplier_items (product, supplier SKU, cost per each)   │
+-- │    sync_version: supplier and price list sync as one SUPPLIER          │
+-- │    document (newer version wins)                                       │
+-- │                                                                         │
+-- │  product_costs: last and weighted average cost per each, updated by    │
+-- │    costed receipts; products.cost_cents follows the average            │
+-- │                                                                         │
+-- │  stock_receipts.supplier_id, stock_receipt_items.each_cost_cents       │
+-- │                                                                         │
+-- │  sale_items.unit_cost_cents  product cost when the line was sold       │
+-- │    (NULL: product had no cost); margins are price vs this cost         │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS suppliers (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    contact_name TEXT,
+    phone TEXT,
+    email TEXT,
+    is_active INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    sync_version INTEGER NOT NULL DEFAULT 1
+);
+
+CREATE TABLE IF NOT EXISTS supplier_items (
+    supplier_id TEXT NOT NULL REFERENCES suppliers(id),
+    product_id TEXT NOT NULL,
+    supplier_sku TEXT,
+    cost_cents INTEGER NOT NULL CHECK (cost_cents >= 0),
+
+    PRIMARY KEY (supplier_id, product_id)
+);
+
+-- Suppliers of a product
+CREATE INDEX IF NOT EXISTS idx_supplier_items_product
+    ON supplier_items(product_id);
+
+CREATE TABLE IF NOT EXISTS product_costs (
+    product_id TEXT PRIMARY KEY NOT NULL REFERENCES products(id),
+    last_cost_cents INTEGER,
+    average_cost_cents INTEGER,
+    last_supplier_id TEXT,
+    updated_at TEXT
+);
+
+ALTER TABLE stock_receipts ADD COLUMN supplier_id TEXT;
+ALTER TABLE stock_receipt_items ADD COLUMN each_cost_cents INTEGER;
+
+ALTER TABLE sale_items ADD COLUMN unit_cost_cents INTEGER;
+
+-- Existing lines take their product's current cost, like 025 did for
+-- the tax rate
+UPDATE sale_items
+SET unit_cost_cents = (SELECT p.cost_cents FROM products p WHERE p.id = sale_items.product_id)
+WHERE unit_cost_cents IS NULL;
@@ -0,0 +1,41 @@
+-- =============================================================================
+-- Titan POS: Margin Override Audit Trail
+-- Migration: 032_margin_overrides.sql
+-- =============================================================================
+--
+-- Manager approvals of manual prices below the store's blocking margin
+-- floor (see titan_core::margin_guard). Rows are only ever inserted.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                     Margin Override Audit                               │
+-- │                                                                         │
+-- │  margin_overrides: one row per approved price                          │
+-- │    kind: product_price (catalog edit) | line_price (cart line)         │
+-- │    old / new price, cost and margin when approved, the floor           │
+-- │    manager_id + reason: who approved it and why                        │
+-- │    user_id + device_id: who entered the price, where                   │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS margin_overrides (
+    id TEXT PRIMARY KEY NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('product_price', 'line_price')),
+    product_id TEXT NOT NULL,
+    old_price_cents INTEGER NOT NULL,
+    new_price_cents INTEGER NOT NULL,
+    cost_cents INTEGER,
+    margin_bps INTEGER,
+    floor_bps INTEGER NOT NULL,
+    manager_id TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    device_id TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+-- Audit report by date
+CREATE INDEX IF NOT EXISTS idx_margin_overrides_created
+    ON margin_overrides(created_at);
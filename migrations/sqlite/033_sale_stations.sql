@@ -0,0 +1,27 @@
+-- =============================================================================
+-- Titan POS: Sale Stations
+-- Migration: 033_sale_stations.sql
+-- =============================================================================
+--
+-- Stamps each sale with the register (station) it was rung up on, apart
+-- from device_id, so reports can be run per register (see
+-- titan_core::station).
+--
+-- ## Column Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                         Sale Stations                                   │
+-- │                                                                         │
+-- │  sales.station_number   register number (1-999) configured on the       │
+-- │                         terminal when the sale was made                 │
+-- │                         NULL: sales from before stations existed        │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+ALTER TABLE sales ADD COLUMN station_number INTEGER;
+
+-- Reports for one register: completed sales by completion time
+CREATE INDEX IF NOT EXISTS idx_sales_station_completed
+    ON sales(station_number, completed_at)
+    WHERE status = 'completed';
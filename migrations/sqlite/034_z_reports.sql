@@ -0,0 +1,43 @@
+-- =============================================================================
+-- Titan POS: Z Reports
+-- Migration: 034_z_reports.sql
+-- =============================================================================
+--
+-- Z closes run by the day-end job (see titan_core::day_end). A Z report
+-- freezes the totals of the sales a device completed since its previous
+-- close; rows are only ever inserted.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                         Z Reports                                       │
+-- │                                                                         │
+-- │  z_reports: one row per device and business date                        │
+-- │    sequence: Z number on the device (1, 2, 3, ...)                      │
+-- │    period_start: previous close (NULL for the first)                    │
+-- │    closed_at: end of the period, sales completed up to it are in        │
+-- │    counts and totals; payments by method as JSON                        │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS z_reports (
+    id TEXT PRIMARY KEY NOT NULL,
+    device_id TEXT NOT NULL,
+    station_number INTEGER,
+    sequence INTEGER NOT NULL,
+    business_date TEXT NOT NULL,
+    period_start TEXT,
+    closed_at TEXT NOT NULL,
+    sale_count INTEGER NOT NULL,
+    void_count INTEGER NOT NULL,
+    subtotal_cents INTEGER NOT NULL,
+    tax_cents INTEGER NOT NULL,
+    discount_cents INTEGER NOT NULL,
+    total_cents INTEGER NOT NULL,
+    -- [{"method": "cash", "count": 3, "amount_cents": 4500}, ...]
+    payments TEXT NOT NULL,
+
+    UNIQUE (device_id, business_date),
+    UNIQUE (device_id, sequence)
+);
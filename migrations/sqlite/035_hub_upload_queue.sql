@@ -0,0 +1,53 @@
+-- =============================================================================
+-- Titan POS: Hub Upload Queue
+-- Migration: 035_hub_upload_queue.sql
+-- =============================================================================
+--
+-- Cloud-bound outbox entries survive a hub failover. The hub keeps the
+-- entries it accepted in hub_upload_queue until they reach the cloud, and
+-- the terminal that sent them keeps its own copy (held, not synced) until
+-- the hub relays the cloud's ack. If the hub dies first, the terminal
+-- releases its held entries and sends them to the new PRIMARY.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                       Hub Upload Queue                                  │
+-- │                                                                         │
+-- │  terminal: sync_outbox ──OutboxBatch──► hub: hub_upload_queue           │
+-- │            held_by_hub = <hub> ◄──BatchAck { held_ids }──┘              │
+-- │                                                                         │
+-- │  hub: uploaded to the cloud ──► uploaded_at = now                       │
+-- │            synced_at = now ◄──CloudAck { ids }──┘                       │
+-- │                                                                         │
+-- │  terminal, new hub elected ──► held_by_hub = NULL, sent again           │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+-- Hub that accepted the entry for upload and has not relayed the cloud's
+-- ack yet; NULL when no hub holds it
+ALTER TABLE sync_outbox ADD COLUMN held_by_hub TEXT;
+
+CREATE TABLE IF NOT EXISTS hub_upload_queue (
+    -- Terminal that sent the entry and the entry's ID in its outbox
+    device_id TEXT NOT NULL,
+    entry_id TEXT NOT NULL,
+
+    entity_type TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    schema_version INTEGER NOT NULL,
+    correlation_id TEXT,
+
+    received_at TEXT NOT NULL,
+    uploaded_at TEXT,           -- NULL = waiting for the cloud
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+
+    PRIMARY KEY (device_id, entry_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_hub_upload_queue_pending
+    ON hub_upload_queue(received_at)
+    WHERE uploaded_at IS NULL;
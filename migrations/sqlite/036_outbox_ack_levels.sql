@@ -0,0 +1,35 @@
+-- =============================================================================
+-- Titan POS: Outbox Ack Levels
+-- Migration: 036_outbox_ack_levels.sql
+-- =============================================================================
+--
+-- Records how far each outbox entry has got: a hub ack only means the
+-- store hub has the entry, which may still be lost with the hub. The
+-- cloud ack is the end of the line for cloud-bound entries.
+--
+-- ## Ack Levels
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                       Outbox Ack Levels                                 │
+-- │                                                                         │
+-- │  pending      hub_acked_at IS NULL    sent (again) on the next poll     │
+-- │  hub_acked    hub_acked_at = <time>   hub has it; cloud-bound entries   │
+-- │               held_by_hub = <hub>     are held until the cloud ack      │
+-- │  cloud_acked  cloud_acked_at = <time> in the cloud, synced              │
+-- │                                                                         │
+-- │  held with no cloud ack for sync.cloud_ack_timeout_secs                 │
+-- │       ──► held_by_hub = NULL, uploaded again                            │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+-- When the hub last acked the entry
+ALTER TABLE sync_outbox ADD COLUMN hub_acked_at TEXT;
+
+-- When the cloud acked the entry, through the hub or the cloud fallback
+ALTER TABLE sync_outbox ADD COLUMN cloud_acked_at TEXT;
+
+-- Entries synced or held before this migration were acked by a hub
+UPDATE sync_outbox
+SET hub_acked_at = COALESCE(synced_at, attempted_at)
+WHERE synced_at IS NOT NULL OR held_by_hub IS NOT NULL;
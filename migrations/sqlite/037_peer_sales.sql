@@ -0,0 +1,63 @@
+-- =============================================================================
+-- Titan POS: Peer Sales
+-- Migration: 037_peer_sales.sql
+-- =============================================================================
+--
+-- Completed sales are relayed through the store hub to every terminal, so a
+-- sale rung up on one register can be found, reprinted and refunded on any
+-- other. A relayed sale is stored like a local one but marked, so its lines
+-- do not take stock out a second time: the terminal that rang it up already
+-- sent those inventory deltas.
+--
+-- ## Relay
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                          Peer Sales                                     │
+-- │                                                                         │
+-- │  POS #1: finalize_sale ──► sync_outbox SALE { sale, items, payments }   │
+-- │  hub: EntityUpdate "sale" ──► POS #2, POS #3 (and the hub's own copy)   │
+-- │  POS #2: sales (relayed = 1), sale_items, payments                      │
+-- │          trg_sale_item_inventory_delta skips relayed sales              │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+-- 1 = received from another terminal through the hub
+ALTER TABLE sales ADD COLUMN relayed INTEGER NOT NULL DEFAULT 0;
+
+DROP TRIGGER IF EXISTS trg_sale_item_inventory_delta;
+
+CREATE TRIGGER IF NOT EXISTS trg_sale_item_inventory_delta
+AFTER INSERT ON sale_items
+WHEN (SELECT track_inventory FROM products WHERE id = NEW.product_id) = 1
+ AND (SELECT relayed FROM sales WHERE id = NEW.sale_id) IS NOT 1
+BEGIN
+    INSERT INTO inventory_deltas (
+        id,
+        product_id,
+        delta,
+        delta_type,
+        reference_id,
+        reference_type,
+        origin_device_id,
+        occurred_at,
+        sequence_num,
+        synced
+    )
+    VALUES (
+        lower(hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' ||
+              substr(hex(randomblob(2)), 2) || '-' ||
+              substr('89ab', abs(random()) % 4 + 1, 1) ||
+              substr(hex(randomblob(2)), 2) || '-' || hex(randomblob(6))),
+        NEW.product_id,
+        -NEW.quantity,  -- Negative because sales reduce inventory
+        'sale',
+        NEW.sale_id,
+        'sale',
+        COALESCE((SELECT device_id FROM node_state WHERE is_local = 1), 'local'),
+        datetime('now'),
+        COALESCE((SELECT MAX(sequence_num) + 1 FROM inventory_deltas
+                  WHERE origin_device_id = (SELECT device_id FROM node_state WHERE is_local = 1)), 1),
+        0  -- Not yet synced
+    );
+END;
@@ -0,0 +1,15 @@
+-- =============================================================================
+-- Titan POS: Outbox Trace Context
+-- Migration: 038_outbox_trace_context.sql
+-- =============================================================================
+--
+-- The W3C trace context (`traceparent`) of the command that queued each
+-- outbox entry, kept with the entry on the terminal and in the hub's upload
+-- queue so the spans the hub and the cloud record for it join the
+-- command's trace (see titan-sync telemetry). NULL when span export is off
+-- or the entry was queued outside a command.
+-- =============================================================================
+
+ALTER TABLE sync_outbox ADD COLUMN trace_context TEXT;
+
+ALTER TABLE hub_upload_queue ADD COLUMN trace_context TEXT;
@@ -0,0 +1,65 @@
+-- =============================================================================
+-- Titan POS: Sale Retention
+-- Migration: 039_sale_retention.sql
+-- =============================================================================
+--
+-- Terminals prune finished sales the cloud has had for longer than the
+-- retention period, so SQLite does not grow without bound on small
+-- devices. A pruned sale leaves a marker behind: a search for its receipt
+-- knows it existed and fetches it back from the hub.
+--
+-- ## Retention
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                         Sale Retention                                  │
+-- │                                                                         │
+-- │  sync_outbox SALE cloud acked ──► sales.cloud_acked_at (trigger)        │
+-- │                                                                         │
+-- │  prune_before(cutoff):                                                  │
+-- │    completed/voided, no refunds, no fiscal signature, and               │
+-- │      relayed = 0: cloud_acked_at < cutoff                               │
+-- │      relayed = 1: completed_at < cutoff (its own terminal uploads it)   │
+-- │    ──► pruned_sales marker; payments, sale_items, sales deleted         │
+-- │                                                                         │
+-- │  restore_pruned(doc) ──► relayed copy, marker removed                   │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+--
+-- Sales whose outbox entries were already cleaned up have no cloud ack on
+-- record and are kept.
+-- =============================================================================
+
+-- When the cloud acked the sale's outbox entry (NULL: not yet, or unknown)
+ALTER TABLE sales ADD COLUMN cloud_acked_at TEXT;
+
+UPDATE sales
+SET cloud_acked_at = (
+    SELECT MAX(o.cloud_acked_at)
+    FROM sync_outbox o
+    WHERE o.entity_type = 'SALE' AND o.entity_id = sales.id
+)
+WHERE relayed = 0;
+
+CREATE TRIGGER IF NOT EXISTS trg_outbox_sale_cloud_acked
+AFTER UPDATE OF cloud_acked_at ON sync_outbox
+WHEN NEW.entity_type = 'SALE' AND NEW.cloud_acked_at IS NOT NULL
+BEGIN
+    UPDATE sales SET cloud_acked_at = NEW.cloud_acked_at WHERE id = NEW.entity_id;
+END;
+
+-- =============================================================================
+-- Pruned Sale Markers
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS pruned_sales (
+    sale_id TEXT PRIMARY KEY NOT NULL,
+    tenant_id TEXT NOT NULL,
+    receipt_number TEXT NOT NULL,
+    device_id TEXT NOT NULL,
+    total_cents INTEGER NOT NULL,
+    completed_at TEXT,
+    pruned_at TEXT NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_pruned_sales_receipt
+    ON pruned_sales(tenant_id, receipt_number);
@@ -0,0 +1,37 @@
+-- =============================================================================
+-- Titan POS: Cash Drawer Open Audit Trail
+-- Migration: 040_cash_drawer_opens.sql
+-- =============================================================================
+--
+-- Manual ("no sale") opens of a register's cash drawer (see
+-- titan_core::cash_drawer). Rows are only ever inserted.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                     Cash Drawer Open Audit                              │
+-- │                                                                         │
+-- │  cash_drawer_opens: one row per open_cash_drawer without a sale         │
+-- │    station_number + drawer: which register, printer_kick | direct       │
+-- │    reason: what staff gave, if anything                                 │
+-- │    succeeded + error: whether the command reached the drawer            │
+-- │    user_id + device_id: who opened it, where                            │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS cash_drawer_opens (
+    id TEXT PRIMARY KEY NOT NULL,
+    station_number INTEGER,
+    drawer TEXT NOT NULL,
+    reason TEXT,
+    succeeded INTEGER NOT NULL CHECK (succeeded IN (0, 1)),
+    error TEXT,
+    user_id TEXT NOT NULL,
+    device_id TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+-- Audit report by date
+CREATE INDEX IF NOT EXISTS idx_cash_drawer_opens_created
+    ON cash_drawer_opens(created_at);
@@ -0,0 +1,42 @@
+-- =============================================================================
+-- Titan POS: Manager Override Token Audit Trail
+-- Migration: 041_manager_overrides.sql
+-- =============================================================================
+--
+-- Signed manager override tokens used at this terminal (see
+-- titan_core::override_token). The token ID is the primary key, so a
+-- token cannot be used twice. Rows are only ever inserted.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                     Manager Override Audit                              │
+-- │                                                                         │
+-- │  manager_overrides: one row per used token                              │
+-- │    id: the token ID (single use)                                        │
+-- │    action: refund | price_override | void_sale                          │
+-- │    subject + amount_cents: the sale and amount it was used for          │
+-- │    manager_id + reason: who approved it and why                         │
+-- │    issued_at / expires_at: the token's lifetime                         │
+-- │    user_id + device_id + used_at: who used it, where, when              │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS manager_overrides (
+    id TEXT PRIMARY KEY NOT NULL,
+    action TEXT NOT NULL CHECK (action IN ('refund', 'price_override', 'void_sale')),
+    subject TEXT,
+    amount_cents INTEGER,
+    manager_id TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    issued_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    device_id TEXT NOT NULL,
+    used_at TEXT NOT NULL
+);
+
+-- Audit report by date
+CREATE INDEX IF NOT EXISTS idx_manager_overrides_used
+    ON manager_overrides(used_at);
@@ -0,0 +1,71 @@
+-- =============================================================================
+-- Titan POS: Coupons
+-- Migration: 042_coupons.sql
+-- =============================================================================
+--
+-- Coupons issued in the cloud back office and the redemptions counted
+-- against them (see titan_core::coupon). Definitions arrive from the cloud;
+-- redemptions are written by the terminal that rang up the sale and merged
+-- by id from every other terminal and store.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                            Coupons                                      │
+-- │                                                                         │
+-- │  coupons ◄──── coupon_redemptions ────► sales                           │
+-- │    code: unique, normalized (upper case, no spaces or dashes)           │
+-- │    kind + value: percent (bps) or amount (cents)                        │
+-- │    product_ids: JSON array, empty for the whole cart                    │
+-- │    max_uses: 1 for single-use, NULL for unlimited                       │
+-- │                                                                         │
+-- │  uses = COUNT(coupon_redemptions); rows are append-only                 │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+-- =============================================================================
+-- Coupons Table
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS coupons (
+    id TEXT PRIMARY KEY NOT NULL,
+    tenant_id TEXT NOT NULL,
+    code TEXT NOT NULL UNIQUE,
+    description TEXT,
+
+    -- Kind: percent, amount
+    kind TEXT NOT NULL CHECK (kind IN ('percent', 'amount')),
+    value INTEGER NOT NULL CHECK (value > 0),
+
+    min_subtotal_cents INTEGER,
+    product_ids TEXT NOT NULL DEFAULT '[]',
+    starts_at TEXT,
+    expires_at TEXT,
+    max_uses INTEGER,
+    active INTEGER NOT NULL DEFAULT 1,
+
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    sync_version INTEGER NOT NULL DEFAULT 1
+);
+
+-- =============================================================================
+-- Coupon Redemptions Table
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS coupon_redemptions (
+    id TEXT PRIMARY KEY NOT NULL,
+    coupon_id TEXT NOT NULL,
+    sale_id TEXT NOT NULL,
+    store_id TEXT NOT NULL DEFAULT '',
+    device_id TEXT NOT NULL,
+    discount_cents INTEGER NOT NULL,
+    redeemed_at TEXT NOT NULL,
+
+    FOREIGN KEY (coupon_id) REFERENCES coupons(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_coupon_redemptions_coupon ON coupon_redemptions(coupon_id);
+CREATE INDEX IF NOT EXISTS idx_coupon_redemptions_sale ON coupon_redemptions(sale_id);
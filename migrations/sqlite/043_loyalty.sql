@@ -0,0 +1,62 @@
+-- =============================================================================
+-- Titan POS: Loyalty
+-- Migration: 043_loyalty.sql
+-- =============================================================================
+--
+-- Loyalty members and their points ledger (see titan_core::loyalty).
+-- Points are earned on sales, converted into rewards at the program's
+-- threshold, and rewards are spent as a payment tender.
+--
+-- ## Table Overview
+-- ```
+-- ┌─────────────────────────────────────────────────────────────────────────┐
+-- │                            Loyalty                                      │
+-- │                                                                         │
+-- │  loyalty_accounts ◄──── loyalty_entries ────► sales                     │
+-- │    member_number: unique      earn   (+points)                          │
+-- │                               reward (-points, +reward_cents)           │
+-- │                               redeem (-reward_cents)                    │
+-- │                                                                         │
+-- │  points  = SUM(points)        rewards = SUM(reward_cents)               │
+-- │  Entries are append-only; synced copies merge by entry id.              │
+-- └─────────────────────────────────────────────────────────────────────────┘
+-- ```
+-- =============================================================================
+
+-- =============================================================================
+-- Loyalty Accounts Table
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS loyalty_accounts (
+    id TEXT PRIMARY KEY NOT NULL,
+    tenant_id TEXT NOT NULL,
+    member_number TEXT NOT NULL UNIQUE,
+
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    sync_version INTEGER NOT NULL DEFAULT 1
+);
+
+-- =============================================================================
+-- Loyalty Entries Table
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS loyalty_entries (
+    id TEXT PRIMARY KEY NOT NULL,
+    account_id TEXT NOT NULL,
+
+    -- Kind: earn, reward, redeem
+    kind TEXT NOT NULL CHECK (kind IN ('earn', 'reward', 'redeem')),
+    -- Signed: see the table overview
+    points INTEGER NOT NULL DEFAULT 0,
+    reward_cents INTEGER NOT NULL DEFAULT 0,
+
+    sale_id TEXT,
+    device_id TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+
+    FOREIGN KEY (account_id) REFERENCES loyalty_accounts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_loyalty_entries_account ON loyalty_entries(account_id);